@@ -6,6 +6,20 @@ import { HistoryPage } from './pages/HistoryPage';
 import { BookmarksPage } from './pages/BookmarksPage';
 import { Component, ErrorInfo, ReactNode } from 'react';
 import { ResearchProvider } from './contexts/ResearchContext';
+import { useDeepLinks } from './hooks/useDeepLinks';
+import { useUiState } from './hooks/useUiState';
+
+// Handles claudius:// links; must render inside the router
+function DeepLinkHandler() {
+  useDeepLinks();
+  return null;
+}
+
+// Reopens and remembers the last view; must render inside the router
+function UiStateHandler() {
+  useUiState();
+  return null;
+}
 
 // Error boundary to catch React errors
 class ErrorBoundary extends Component<{ children: ReactNode }, { hasError: boolean; error: Error | null }> {
@@ -42,6 +56,8 @@ export function App() {
     <ErrorBoundary>
       <ResearchProvider>
         <HashRouter>
+          <DeepLinkHandler />
+          <UiStateHandler />
           <Layout>
             <Routes>
               <Route path="/" element={<HomePage />} />
@@ -1,7 +1,10 @@
 import { useState, useEffect } from 'react';
 import { invoke } from '@tauri-apps/api/core';
+import { listen, UnlistenFn } from '@tauri-apps/api/event';
 import { LoadingBorderAura } from './components/LoadingBorderAura';
+import { PopoverResearchTimeline } from './components/PopoverResearchTimeline';
 import { useResearch } from './contexts/ResearchContext';
+import type { QuickResearchResult } from './types';
 
 interface Briefing {
   id: number;
@@ -17,6 +20,13 @@ interface BriefingCard {
   sources?: string[];
 }
 
+// State of a run started by the quick research shortcut
+interface QuickResearch {
+  query: string;
+  status: 'running' | 'done' | 'failed';
+  text: string | null;  // The archive answer, the run summary or the error
+}
+
 interface Topic {
   id: string;
   name: string;
@@ -32,11 +42,46 @@ export function PopoverApp() {
   const [isRunning, setIsRunning] = useState(false);
   const [loading, setLoading] = useState(true);
   const [researchError, setResearchError] = useState<string | null>(null);
+  const [quickResearch, setQuickResearch] = useState<QuickResearch | null>(null);
 
   useEffect(() => {
     loadData();
   }, []);
 
+  // The quick research shortcut researches the clipboard and reports here
+  useEffect(() => {
+    let mounted = true;
+    const unlistenFns: UnlistenFn[] = [];
+
+    (async () => {
+      unlistenFns.push(await listen<{ query: string }>('quick_research:started', (event) => {
+        if (!mounted) return;
+        setQuickResearch({ query: event.payload.query, status: 'running', text: null });
+        setIsRunning(true);
+        setIsResearchRunning(true);
+      }));
+      unlistenFns.push(await listen<QuickResearchResult>('quick_research:completed', (event) => {
+        if (!mounted) return;
+        const { query, answer, message } = event.payload;
+        setQuickResearch({ query, status: 'done', text: answer?.answer ?? message });
+        setIsRunning(false);
+        setIsResearchRunning(false);
+        if (event.payload.mode === 'research') loadData();
+      }));
+      unlistenFns.push(await listen<{ query: string; error: string }>('quick_research:failed', (event) => {
+        if (!mounted) return;
+        setQuickResearch({ query: event.payload.query, status: 'failed', text: event.payload.error });
+        setIsRunning(false);
+        setIsResearchRunning(false);
+      }));
+    })();
+
+    return () => {
+      mounted = false;
+      unlistenFns.forEach((unlisten) => unlisten());
+    };
+  }, [setIsResearchRunning]);
+
   async function loadData() {
     try {
       const [briefingsResult, topicsResult] = await Promise.all([
@@ -184,6 +229,44 @@ export function PopoverApp() {
         </div>
       )}
 
+      {/* Live progress of a run started here, in the main window or on schedule */}
+      <PopoverResearchTimeline />
+
+      {/* Quick research from the keyboard shortcut */}
+      {quickResearch && (
+        <div className="px-3 py-2 bg-purple-50 dark:bg-purple-900/20 border-y border-purple-200 dark:border-purple-800">
+          <div className="flex items-start gap-2">
+            <div className="flex-1 min-w-0">
+              <p className="text-xs font-medium text-purple-900 dark:text-purple-300">
+                {quickResearch.status === 'running'
+                  ? 'Researching...'
+                  : quickResearch.status === 'failed' ? 'Quick Research Failed' : 'Quick Research'}
+              </p>
+              {quickResearch.query && (
+                <p className="text-xs text-purple-700 dark:text-purple-400 mt-0.5 truncate" title={quickResearch.query}>
+                  {quickResearch.query}
+                </p>
+              )}
+              {quickResearch.text && (
+                <p className="text-xs text-gray-700 dark:text-gray-300 mt-1 max-h-32 overflow-y-auto whitespace-pre-wrap">
+                  {quickResearch.text}
+                </p>
+              )}
+            </div>
+            {quickResearch.status !== 'running' && (
+              <button
+                onClick={() => setQuickResearch(null)}
+                className="text-purple-600 dark:text-purple-400 hover:text-purple-800 dark:hover:text-purple-200 p-0.5"
+              >
+                <svg className="w-3.5 h-3.5" fill="none" viewBox="0 0 24 24" stroke="currentColor">
+                  <path strokeLinecap="round" strokeLinejoin="round" strokeWidth={2} d="M6 18L18 6M6 6l12 12" />
+                </svg>
+              </button>
+            )}
+          </div>
+        </div>
+      )}
+
       {/* Research Error */}
       {researchError && (
         <div className="px-3 py-2 bg-red-50 dark:bg-red-900/20 border-y border-red-200 dark:border-red-800">
@@ -1,10 +1,10 @@
 import { useState } from 'react';
 import { motion, AnimatePresence } from 'framer-motion';
-import { /* ThumbsUp, ThumbsDown, */ ExternalLink, ChevronDown, ChevronUp, Sparkles, MessageCircle, Bookmark, X, AlertTriangle, Printer, Copy, Check, Download, Share2 } from 'lucide-react';
+import { /* ThumbsUp, ThumbsDown, */ ExternalLink, ChevronDown, ChevronUp, Sparkles, MessageCircle, Bookmark, X, AlertTriangle, Printer, Copy, Check, Download, Share2, Bell, Github, CalendarPlus } from 'lucide-react';
 import { formatDistanceToNow, parseISO } from 'date-fns';
 import { convertFileSrc } from '@tauri-apps/api/core';
 import ReactMarkdown from 'react-markdown';
-import type { Briefing } from '../types';
+import type { Briefing, CardAction, ActionOutcome } from '../types';
 
 // Delete Confirmation Dialog
 function DeleteConfirmDialog({
@@ -77,6 +77,33 @@ function DeleteConfirmDialog({
   );
 }
 
+// Button label and icon for a card action
+function describeAction(action: CardAction): { label: string; Icon: typeof Bell } {
+  switch (action.type) {
+    case 'remind_me':
+      return { label: `Remind me in ${action.in_hours ?? 24}h`, Icon: Bell };
+    case 'open_url':
+      return { label: action.label || 'Open link', Icon: ExternalLink };
+    case 'create_github_issue':
+      return { label: `Create issue in ${action.repo}`, Icon: Github };
+    case 'add_calendar_event':
+      return { label: `Add to calendar (${action.date})`, Icon: CalendarPlus };
+  }
+}
+
+function describeOutcome(outcome: ActionOutcome): string {
+  switch (outcome.type) {
+    case 'reminder_scheduled':
+      return 'Reminder scheduled';
+    case 'url_opened':
+      return 'Opened';
+    case 'issue_created':
+      return 'Issue created';
+    case 'calendar_event_created':
+      return 'Added to calendar';
+  }
+}
+
 // Parse date string as local time (not UTC)
 // "2025-12-08" should be today in local time, not yesterday
 function parseLocalDate(dateStr: string): Date {
@@ -157,6 +184,7 @@ export function BriefingCard({ briefing, /* onThumbsUp, onThumbsDown, */ onOpenC
   const [expanded, setExpanded] = useState(false);
   // const [feedbackGiven, setFeedbackGiven] = useState<'up' | 'down' | null>(null);
   const [showDeleteConfirm, setShowDeleteConfirm] = useState(false);
+  const [actionStatus, setActionStatus] = useState<Record<number, string>>({});
 
   // Default values for optional fields (must be before handlers that use them)
   const relevance = (briefing.relevance || 'medium') as 'high' | 'medium' | 'low';
@@ -391,6 +419,9 @@ ${suggestedNextHtml}
       markdown += '\n';
     }
     
+    if (briefing.action_items && briefing.action_items.length > 0) {
+      markdown += `## Action Items\n\n${briefing.action_items.map(item => `- ${item}`).join('\n')}\n\n`;
+    }
     if (briefing.suggested_next) {
       markdown += `## Suggested Next Step\n\n${briefing.suggested_next}\n\n`;
     }
@@ -426,6 +457,24 @@ ${suggestedNextHtml}
     }
   };
 
+  // Run a structured card action (ids are "<briefingId>-<cardIndex>")
+  const handleRunAction = async (actionIndex: number) => {
+    const [briefingId, cardIndex] = briefing.id.split('-').map(Number);
+    setActionStatus(prev => ({ ...prev, [actionIndex]: 'Running...' }));
+    try {
+      const { invoke } = await import('@tauri-apps/api/core');
+      const outcome = await invoke<ActionOutcome>('run_card_action', {
+        briefingId,
+        cardIndex,
+        actionIndex,
+      });
+      setActionStatus(prev => ({ ...prev, [actionIndex]: describeOutcome(outcome) }));
+    } catch (error) {
+      console.error('Failed to run card action:', error);
+      setActionStatus(prev => ({ ...prev, [actionIndex]: `Failed: ${error}` }));
+    }
+  };
+
   // Export to markdown file
   const handleExport = async (format: 'markdown') => {
     const markdown = generateMarkdown();
@@ -631,6 +680,44 @@ ${suggestedNextHtml}
         </div>
       )}
 
+      {briefing.action_items && briefing.action_items.length > 0 && (
+        <div className="mb-4">
+          <h5 className="text-sm font-medium text-gray-900 dark:text-white mb-2">
+            Action Items
+          </h5>
+          <ul className="list-disc list-inside space-y-1 text-sm text-gray-700 dark:text-gray-300">
+            {briefing.action_items.map((item, i) => (
+              <li key={i}>{item}</li>
+            ))}
+          </ul>
+        </div>
+      )}
+
+      {briefing.actions && briefing.actions.length > 0 && (
+        <div className="mb-4">
+          <h5 className="text-sm font-medium text-gray-900 dark:text-white mb-2">
+            Actions
+          </h5>
+          <div className="flex flex-wrap gap-2">
+            {briefing.actions.map((action, i) => {
+              const { label, Icon } = describeAction(action);
+              return (
+                <button
+                  key={i}
+                  onClick={() => handleRunAction(i)}
+                  disabled={actionStatus[i] === 'Running...'}
+                  className="inline-flex items-center gap-1.5 px-3 py-1.5 text-sm text-gray-700 dark:text-gray-300 bg-gray-100 dark:bg-gray-700 hover:bg-gray-200 dark:hover:bg-gray-600 rounded-lg transition-colors disabled:opacity-50"
+                  title={actionStatus[i] || label}
+                >
+                  <Icon className="w-4 h-4" />
+                  {actionStatus[i] || label}
+                </button>
+              );
+            })}
+          </div>
+        </div>
+      )}
+
       {briefing.suggested_next && (
         <div className="mb-4 p-3 bg-primary-50 dark:bg-primary-900/20 rounded-lg border border-primary-200 dark:border-primary-800">
           <div className="flex items-start gap-2">
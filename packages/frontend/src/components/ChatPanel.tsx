@@ -1,6 +1,6 @@
 import { useState, useRef, useEffect } from 'react';
 import { motion, AnimatePresence } from 'framer-motion';
-import { X, Send, Loader2, Trash2, Sparkles, Wrench } from 'lucide-react';
+import { X, Send, Loader2, Trash2, Sparkles, Wrench, Download } from 'lucide-react';
 import { useChat } from '../hooks/useChat';
 import { ChatMessage } from './ChatMessage';
 
@@ -13,17 +13,17 @@ interface ChatPanelProps {
 }
 
 export function ChatPanel({ briefingId, cardIndex, briefingTitle, isOpen, onClose }: ChatPanelProps) {
-  const { messages, loading, sending, error, toolActivity, sendMessage, clearHistory } = useChat(briefingId, cardIndex);
+  const { messages, loading, sending, error, toolActivity, streamingText, sendMessage, clearHistory, exportChat } = useChat(briefingId, cardIndex);
   const [input, setInput] = useState('');
   const messagesEndRef = useRef<HTMLDivElement>(null);
   const inputRef = useRef<HTMLTextAreaElement>(null);
 
-  // Scroll to bottom when messages change
+  // Scroll to bottom when messages change or the reply grows
   useEffect(() => {
     if (messagesEndRef.current) {
       messagesEndRef.current.scrollIntoView({ behavior: 'smooth' });
     }
-  }, [messages]);
+  }, [messages, streamingText]);
 
   // Focus input when panel opens
   useEffect(() => {
@@ -94,6 +94,15 @@ export function ChatPanel({ briefingId, cardIndex, briefingTitle, isOpen, onClos
                 </div>
               </div>
               <div className="flex items-center gap-2">
+                {messages.length > 0 && (
+                  <button
+                    onClick={() => exportChat('markdown')}
+                    className="p-2 rounded-lg hover:bg-white/10 text-gray-400 hover:text-white transition-colors"
+                    title="Export chat as markdown"
+                  >
+                    <Download className="w-4 h-4" />
+                  </button>
+                )}
                 {messages.length > 0 && (
                   <button
                     onClick={handleClear}
@@ -133,7 +142,19 @@ export function ChatPanel({ briefingId, cardIndex, briefingTitle, isOpen, onClos
                   {messages.map((message) => (
                     <ChatMessage key={message.id} message={message} />
                   ))}
-                  {sending && (
+                  {sending && streamingText && (
+                    <ChatMessage
+                      message={{
+                        id: -1,
+                        briefing_id: briefingId ? parseInt(briefingId, 10) : 0,
+                        card_index: cardIndex,
+                        role: 'assistant',
+                        content: streamingText,
+                        created_at: new Date().toISOString(),
+                      }}
+                    />
+                  )}
+                  {sending && (!streamingText || toolActivity) && (
                     <motion.div
                       initial={{ opacity: 0 }}
                       animate={{ opacity: 1 }}
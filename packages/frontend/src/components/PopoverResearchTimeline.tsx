@@ -0,0 +1,165 @@
+import { useEffect, useState } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import { listen, UnlistenFn } from '@tauri-apps/api/event';
+import type { RecordedResearchEvent, ResearchProgressSnapshot } from '../types/research-events';
+
+// Events shown in the popover timeline
+const TIMELINE_EVENTS = [
+  'research:started',
+  'research:topic_started',
+  'research:tool_started',
+  'research:tool_executed',
+  'research:topic_completed',
+  'research:topic_timeout',
+  'research:synthesis_started',
+  'research:saving',
+  'research:generating_images',
+  'research:completed',
+  'research:cancelled',
+  'research:reset',
+];
+
+const FINISHED_EVENTS = ['research:completed', 'research:cancelled', 'research:reset'];
+
+function describe(entry: RecordedResearchEvent): string | null {
+  const p = entry.payload;
+  switch (entry.event) {
+    case 'research:started':
+      return `Started ${p.total_topics} topic${p.total_topics === 1 ? '' : 's'}`;
+    case 'research:topic_started':
+      return `Researching ${p.topic_name}`;
+    case 'research:tool_executed':
+      return `${p.tool_name}${p.status === 'error' ? ' failed' : ''}`;
+    case 'research:topic_completed':
+      return `${p.topic_name}: ${p.cards_generated} card${p.cards_generated === 1 ? '' : 's'}`;
+    case 'research:topic_timeout':
+      return `${p.topic_name} timed out`;
+    case 'research:synthesis_started':
+      return 'Synthesizing briefing';
+    case 'research:saving':
+      return 'Saving briefing';
+    case 'research:generating_images':
+      return 'Generating images';
+    default:
+      return null;
+  }
+}
+
+function formatElapsed(ms: number): string {
+  const secs = Math.floor(ms / 1000);
+  return `${Math.floor(secs / 60)}:${String(secs % 60).padStart(2, '0')}`;
+}
+
+/**
+ * Compact live view of a research run for the menu bar popover.
+ * Catches up from get_research_progress_snapshot, then follows research events.
+ */
+export function PopoverResearchTimeline() {
+  const [events, setEvents] = useState<RecordedResearchEvent[]>([]);
+  const [isRunning, setIsRunning] = useState(false);
+  const [startedAt, setStartedAt] = useState<number | null>(null);
+  const [now, setNow] = useState(Date.now());
+  const [isCancelling, setIsCancelling] = useState(false);
+
+  useEffect(() => {
+    let mounted = true;
+    const unlistenFns: UnlistenFn[] = [];
+
+    (async () => {
+      for (const name of TIMELINE_EVENTS) {
+        const unlisten = await listen<Record<string, unknown>>(name, (event) => {
+          if (!mounted) return;
+          const entry = { event: name, payload: event.payload ?? {} };
+          if (name === 'research:started') {
+            setEvents([entry]);
+            setIsRunning(true);
+            setStartedAt(Date.now());
+          } else {
+            setEvents((prev) => [...prev, entry]);
+          }
+          if (FINISHED_EVENTS.includes(name)) {
+            setIsRunning(false);
+            setIsCancelling(false);
+          }
+        });
+        unlistenFns.push(unlisten);
+      }
+
+      try {
+        const snapshot = await invoke<ResearchProgressSnapshot>('get_research_progress_snapshot');
+        if (!mounted) return;
+        setEvents(snapshot.events);
+        setIsRunning(snapshot.is_running);
+        setStartedAt(snapshot.elapsed_ms != null ? Date.now() - snapshot.elapsed_ms : null);
+      } catch (err) {
+        console.error('Failed to load research progress:', err);
+      }
+    })();
+
+    return () => {
+      mounted = false;
+      unlistenFns.forEach((unlisten) => unlisten());
+    };
+  }, []);
+
+  useEffect(() => {
+    if (!isRunning) return;
+    const timer = setInterval(() => setNow(Date.now()), 1000);
+    return () => clearInterval(timer);
+  }, [isRunning]);
+
+  async function handleCancel() {
+    if (isCancelling) return;
+    setIsCancelling(true);
+    try {
+      await invoke('cancel_research');
+    } catch (err) {
+      console.error('Failed to cancel research:', err);
+      setIsCancelling(false);
+    }
+  }
+
+  if (!isRunning) return null;
+
+  const currentTopic = [...events]
+    .reverse()
+    .find((e) => e.event === 'research:topic_started')?.payload.topic_name as string | undefined;
+  const last = events[events.length - 1];
+  const currentTool = last?.event === 'research:tool_started' ? (last.payload.tool_name as string) : null;
+  const timeline = events
+    .map(describe)
+    .filter((line): line is string => line !== null)
+    .slice(-5);
+
+  return (
+    <div className="px-3 py-2 bg-blue-50 dark:bg-blue-900/20 border-b border-blue-200 dark:border-blue-800">
+      <div className="flex items-center justify-between gap-2">
+        <div className="min-w-0">
+          <p className="text-xs font-medium text-blue-900 dark:text-blue-200 truncate">
+            {currentTopic ? `Researching ${currentTopic}` : 'Starting research...'}
+          </p>
+          <p className="text-[11px] text-blue-700 dark:text-blue-400 truncate">
+            {currentTool ? `Running ${currentTool}` : 'Thinking...'}
+            {startedAt !== null && ` • ${formatElapsed(now - startedAt)}`}
+          </p>
+        </div>
+        <button
+          onClick={handleCancel}
+          disabled={isCancelling}
+          className="px-2 py-1 text-[11px] font-medium text-red-700 dark:text-red-300 bg-white dark:bg-gray-800 border border-red-200 dark:border-red-800 rounded hover:bg-red-50 dark:hover:bg-red-900/30 disabled:opacity-50 transition-colors flex-shrink-0"
+        >
+          {isCancelling ? 'Cancelling...' : 'Cancel'}
+        </button>
+      </div>
+      {timeline.length > 0 && (
+        <ul className="mt-1.5 space-y-0.5">
+          {timeline.map((line, idx) => (
+            <li key={idx} className="text-[11px] text-gray-600 dark:text-gray-400 truncate">
+              {line}
+            </li>
+          ))}
+        </ul>
+      )}
+    </div>
+  );
+}
@@ -1,6 +1,6 @@
 import { motion } from 'framer-motion';
 import { useEffect, useState } from 'react';
-import { Loader2, CheckCircle2, XCircle, Database, Brain, StopCircle, X } from 'lucide-react';
+import { Loader2, CheckCircle2, XCircle, Database, Brain, StopCircle, X, Clock, AlertTriangle } from 'lucide-react';
 import type { ResearchProgressState } from '../hooks/useResearchProgress';
 import { useResearchReset } from '../hooks/useResearchReset';
 
@@ -150,16 +150,58 @@ export function ResearchProgressCard({ progress }: ResearchProgressCardProps) {
                 key={`${topic.topicName}-${idx}`}
                 className="flex items-center gap-2 text-sm"
               >
-                <CheckCircle2 className="w-4 h-4 text-green-500" />
+                {progress.topicsTimedOut.includes(topic.topicName) ? (
+                  <Clock className="w-4 h-4 text-amber-500" />
+                ) : (
+                  <CheckCircle2 className="w-4 h-4 text-green-500" />
+                )}
                 <span className="text-gray-700 dark:text-gray-300">
                   {topic.topicName}
                 </span>
+                {progress.topicsTimedOut.includes(topic.topicName) && (
+                  <span className="text-xs text-amber-600 dark:text-amber-400">
+                    timed out
+                  </span>
+                )}
               </div>
             ))}
           </div>
         </div>
       )}
 
+      {/* Firecrawl budget warnings */}
+      {progress.budgetWarnings.length > 0 && (
+        <div className="mt-4 space-y-1">
+          {progress.budgetWarnings.map((message, idx) => (
+            <div key={idx} className="flex items-start gap-2 text-sm text-amber-700 dark:text-amber-400">
+              <AlertTriangle className="w-4 h-4 mt-0.5 flex-shrink-0" />
+              <span>{message}</span>
+            </div>
+          ))}
+        </div>
+      )}
+
+      {/* Reasoning (extended thinking) */}
+      {progress.reasoning.length > 0 && (
+        <details className="mt-4">
+          <summary className="text-sm font-medium text-gray-700 dark:text-gray-300 cursor-pointer">
+            Reasoning ({progress.reasoning.length})
+          </summary>
+          <div className="mt-2 space-y-3 max-h-64 overflow-y-auto">
+            {progress.reasoning.map((step, idx) => (
+              <div key={`${step.topicName}-${idx}`} className="text-sm">
+                <p className="text-xs font-medium text-gray-500 dark:text-gray-400 mb-1">
+                  {step.topicName}
+                </p>
+                <p className="text-gray-700 dark:text-gray-300 whitespace-pre-wrap">
+                  {step.text}
+                </p>
+              </div>
+            ))}
+          </div>
+        </details>
+      )}
+
       {/* Error Display */}
       {progress.error && (
         <div className="mt-4 p-3 bg-red-50 dark:bg-red-900/20 border border-red-200 dark:border-red-800 rounded-lg">
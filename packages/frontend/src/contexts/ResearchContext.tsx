@@ -1,9 +1,13 @@
 import { createContext, useContext, useState, useEffect, useRef, ReactNode } from 'react';
+import { invoke } from '@tauri-apps/api/core';
 import { listen, UnlistenFn } from '@tauri-apps/api/event';
 import type {
+  RecordedResearchEvent,
   ResearchStartedEvent,
   TopicStartedEvent,
   TopicCompletedEvent,
+  TopicTimeoutEvent,
+  ThinkingEvent,
   SynthesisStartedEvent,
   SynthesisCompletedEvent,
   SavingEvent,
@@ -11,6 +15,7 @@ import type {
   CompletedEvent,
   CancelledEvent,
   ResetEvent,
+  FirecrawlBudgetEvent,
 } from '../types/research-events';
 
 export interface ResearchProgressState {
@@ -23,6 +28,12 @@ export interface ResearchProgressState {
     topicName: string;
     cardsGenerated: number;
   }[];
+  topicsTimedOut: string[];
+  reasoning: {
+    topicName: string;
+    text: string;
+  }[]; // Thinking summaries, when extended thinking is enabled
+  budgetWarnings: string[]; // Firecrawl budget warnings raised during the run
   totalCards: number;
   error?: string;
 }
@@ -34,6 +45,9 @@ const initialProgressState: ResearchProgressState = {
   currentTopicName: '',
   currentPhase: '',
   topicsCompleted: [],
+  topicsTimedOut: [],
+  reasoning: [],
+  budgetWarnings: [],
   totalCards: 0,
 };
 
@@ -44,6 +58,13 @@ interface ResearchContextType {
   setProgress: React.Dispatch<React.SetStateAction<ResearchProgressState>>;
 }
 
+// Events that end a run; a backlog ending in one of these isn't replayed
+const FINISHED_EVENTS = ['research:completed', 'research:cancelled', 'research:reset'];
+
+// Identifies an event seen both live and in the backlog
+const eventKey = (name: string, payload: unknown) =>
+  `${name}@${(payload as { timestamp?: string } | null)?.timestamp ?? ''}`;
+
 const ResearchContext = createContext<ResearchContextType | undefined>(undefined);
 
 export function ResearchProvider({ children }: { children: ReactNode }) {
@@ -57,17 +78,53 @@ export function ResearchProvider({ children }: { children: ReactNode }) {
   useEffect(() => {
     let mounted = true;
 
+    // Handlers by event name, so backlog events go through the same code as live ones
+    const handlers = new Map<string, (event: { payload: unknown }) => void>();
+    // Live events that arrive before the backlog has been replayed
+    let replayed = false;
+    const pending: { name: string; payload: unknown }[] = [];
+
     // Helper to register listener and store unlisten function
     const registerListener = async <T,>(
       eventName: string,
       handler: (event: { payload: T }) => void
     ) => {
+      handlers.set(eventName, handler as (event: { payload: unknown }) => void);
       const unlisten = await listen<T>(eventName, (event) => {
-        if (mounted) handler(event);
+        if (!mounted) return;
+        if (!replayed) {
+          pending.push({ name: eventName, payload: event.payload });
+          return;
+        }
+        handler(event);
       });
       unlistenFns.current.push(unlisten);
     };
 
+    // Rebuild a run that started before this window was listening
+    const replayBacklog = async () => {
+      const seen = new Set<string>();
+      try {
+        const backlog = await invoke<RecordedResearchEvent[]>('get_event_backlog');
+        const start = backlog.map((e) => e.event).lastIndexOf('research:started');
+        const run = start >= 0 ? backlog.slice(start) : [];
+        if (mounted && !run.some((e) => FINISHED_EVENTS.includes(e.event))) {
+          for (const e of run) {
+            seen.add(eventKey(e.event, e.payload));
+            handlers.get(e.event)?.({ payload: e.payload });
+          }
+        }
+      } catch (err) {
+        console.error('[ResearchContext] Failed to load event backlog:', err);
+      }
+      replayed = true;
+      for (const e of pending.splice(0)) {
+        if (mounted && !seen.has(eventKey(e.name, e.payload))) {
+          handlers.get(e.name)?.({ payload: e.payload });
+        }
+      }
+    };
+
     // Set up all listeners
     (async () => {
       // Research started
@@ -80,6 +137,9 @@ export function ResearchProvider({ children }: { children: ReactNode }) {
           currentTopicName: '',
           currentPhase: 'starting',
           topicsCompleted: [],
+          topicsTimedOut: [],
+          reasoning: [],
+          budgetWarnings: [],
           totalCards: 0,
         });
       });
@@ -110,6 +170,33 @@ export function ResearchProvider({ children }: { children: ReactNode }) {
         }));
       });
 
+      // Topic timed out (skipped, research continues)
+      await registerListener<TopicTimeoutEvent>('research:topic_timeout', (event) => {
+        console.log('[ResearchContext] topic_timeout event:', event.payload);
+        setProgress((prev) => ({
+          ...prev,
+          topicsTimedOut: [...prev.topicsTimedOut, event.payload.topic_name],
+        }));
+      });
+
+      // Claude's reasoning (extended thinking)
+      await registerListener<ThinkingEvent>('research:thinking', (event) => {
+        const text = event.payload.reasoning;
+        if (!text) return;
+        setProgress((prev) => ({
+          ...prev,
+          reasoning: [...prev.reasoning, { topicName: event.payload.topic_name, text }],
+        }));
+      });
+
+      // Firecrawl budget nearly or fully used
+      await registerListener<FirecrawlBudgetEvent>('research:firecrawl_budget', (event) => {
+        setProgress((prev) => ({
+          ...prev,
+          budgetWarnings: [...prev.budgetWarnings, event.payload.message],
+        }));
+      });
+
       // Synthesis started
       await registerListener<SynthesisStartedEvent>('research:synthesis_started', (event) => {
         console.log('[ResearchContext] synthesis_started event:', event.payload);
@@ -176,6 +263,8 @@ export function ResearchProvider({ children }: { children: ReactNode }) {
         console.log('[ResearchContext] reset event');
         setProgress(initialProgressState);
       });
+
+      await replayBacklog();
     })();
 
     // Cleanup listeners on unmount - synchronously call stored unlisten functions
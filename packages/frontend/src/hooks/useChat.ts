@@ -8,6 +8,14 @@ interface ChatToolStartEvent {
   card_index: number;
 }
 
+// A piece of the streamed reply; a new iteration (after tool calls) starts it over
+interface ChatDeltaEvent {
+  briefing_id: number;
+  card_index: number;
+  iteration: number;
+  text: string;
+}
+
 interface ChatToolCompleteEvent {
   tool_name: string;
   success: boolean;
@@ -51,6 +59,8 @@ export function useChat(briefingId: string | null, cardIndex: number) {
   const [sending, setSending] = useState(false);
   const [error, setError] = useState<string | null>(null);
   const [toolActivity, setToolActivity] = useState<string | null>(null);
+  const [streamingText, setStreamingText] = useState('');
+  const streamIterationRef = useRef(0);
   const unlistenersRef = useRef<(() => void)[]>([]);
 
   // Set up event listeners for tool activity
@@ -89,6 +99,18 @@ export function useChat(briefingId: string | null, cardIndex: number) {
           }
         });
         unlistenersRef.current.push(unlistenComplete);
+
+        // Listen for streamed reply text
+        const unlistenDelta = await listen<ChatDeltaEvent>('chat:delta', (event) => {
+          if (briefingId && event.payload.briefing_id === parseInt(briefingId, 10) &&
+              event.payload.card_index === cardIndex) {
+            const { iteration, text } = event.payload;
+            const sameIteration = iteration === streamIterationRef.current;
+            streamIterationRef.current = iteration;
+            setStreamingText(prev => (sameIteration ? prev + text : text));
+          }
+        });
+        unlistenersRef.current.push(unlistenDelta);
       } catch (err) {
         console.log('Failed to set up event listeners:', err);
       }
@@ -134,6 +156,8 @@ export function useChat(briefingId: string | null, cardIndex: number) {
 
     setSending(true);
     setError(null);
+    setStreamingText('');
+    streamIterationRef.current = 0;
 
     // Optimistically add user message to UI
     const tempUserMessage: ChatMessage = {
@@ -170,6 +194,7 @@ export function useChat(briefingId: string | null, cardIndex: number) {
       return null;
     } finally {
       setSending(false);
+      setStreamingText('');
     }
   }, [briefingId, cardIndex]);
 
@@ -189,6 +214,29 @@ export function useChat(briefingId: string | null, cardIndex: number) {
     }
   }, [briefingId, cardIndex]);
 
+  // Export the conversation to a file (markdown or JSON)
+  const exportChat = useCallback(async (format: 'markdown' | 'json' = 'markdown'): Promise<boolean> => {
+    if (!briefingId) return false;
+
+    try {
+      const content = await safeInvoke<string>('export_chat', {
+        briefingId: parseInt(briefingId, 10),
+        cardIndex,
+        format,
+      });
+      const extension = format === 'json' ? 'json' : 'md';
+      return await safeInvoke<boolean>('export_card', {
+        content,
+        defaultFilename: `chat-briefing-${briefingId}-card-${cardIndex + 1}.${extension}`,
+        fileType: format,
+      });
+    } catch (err) {
+      const errorMessage = err instanceof Error ? err.message : 'Failed to export chat';
+      setError(errorMessage);
+      return false;
+    }
+  }, [briefingId, cardIndex]);
+
   // Load history when briefingId or cardIndex changes
   useEffect(() => {
     loadHistory();
@@ -200,8 +248,10 @@ export function useChat(briefingId: string | null, cardIndex: number) {
     sending,
     error,
     toolActivity,
+    streamingText,
     sendMessage,
     clearHistory,
+    exportChat,
     reloadHistory: loadHistory,
   };
 }
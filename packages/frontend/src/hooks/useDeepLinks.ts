@@ -0,0 +1,47 @@
+import { useEffect } from 'react';
+import { useNavigate } from 'react-router-dom';
+import { listen } from '@tauri-apps/api/event';
+import { invoke } from '@tauri-apps/api/core';
+import type { DeepLink } from '../types';
+
+function historyPath(link: DeepLink): string {
+  const params = new URLSearchParams({ briefing: String(link.briefing_id) });
+  if (link.card_index !== null) {
+    params.set('card', String(link.card_index));
+  }
+  return `/history?${params.toString()}`;
+}
+
+/**
+ * Navigate to the briefing named by a claudius:// link, whether it arrives
+ * while the app is open or launched the app before this window loaded.
+ */
+export function useDeepLinks() {
+  const navigate = useNavigate();
+
+  useEffect(() => {
+    let mounted = true;
+
+    const open = (link: DeepLink) => {
+      if (!mounted) return;
+      console.log('Opening deep link:', link);
+      navigate(historyPath(link));
+    };
+
+    // A link that launched the app was emitted before we were listening
+    invoke<DeepLink | null>('take_pending_deep_link')
+      .then((link) => link && open(link))
+      .catch((err) => console.error('Failed to check pending deep link:', err));
+
+    const unlisten = listen<DeepLink>('deeplink:open', (event) => {
+      // Clear the copy kept for late listeners so a reload doesn't reopen it
+      invoke('take_pending_deep_link').catch(() => {});
+      open(event.payload);
+    });
+
+    return () => {
+      mounted = false;
+      unlisten.then((fn) => fn());
+    };
+  }, [navigate]);
+}
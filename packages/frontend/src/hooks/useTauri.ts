@@ -3,10 +3,38 @@ import type {
   Briefing,
   Topic,
   MCPServer,
+  McpCatalog,
+  McpRequiredField,
+  BuiltinTool,
+  ResearchModeInfo,
+  ImageStyle,
   ResearchSettings,
   UserFeedback,
   BriefingFilters,
+  BriefingListFilter,
   Bookmark,
+  ReadLaterItem,
+  DiscardedCard,
+  ToolAnalytics,
+  Statistics,
+  RunReport,
+  TopicResult,
+  FailedTopic,
+  UiState,
+  ProfileInfo,
+  ProfileUsage,
+  Workspace,
+  ModelInfo,
+  TopicSuggestion,
+  TopicPreview,
+  TopicActivity,
+  TopicTimeline,
+  ArchiveAnswer,
+  ArchiveTurn,
+  QuickResearchResult,
+  MetaBriefing,
+  ConfigFile,
+  ConfigChangedEvent,
 } from '../types';
 
 // Check if running inside Tauri - more robust check for Tauri 2.0
@@ -45,16 +73,39 @@ async function safeInvoke<T>(cmd: string, args?: Record<string, unknown>): Promi
   }
 }
 
+// Subscribe to `config:changed` for one config file (emitted when it is edited
+// outside the app, e.g. by the CLI). Returns an unsubscribe function.
+function onConfigChanged(file: ConfigFile, callback: () => void): () => void {
+  if (!isTauri) return () => {};
+  let unlisten: (() => void) | null = null;
+  let cancelled = false;
+  import('@tauri-apps/api/event')
+    .then(({ listen }) =>
+      listen<ConfigChangedEvent>('config:changed', (event) => {
+        if (event.payload.files.includes(file)) callback();
+      })
+    )
+    .then((fn) => {
+      if (cancelled) fn();
+      else unlisten = fn;
+    })
+    .catch(() => {});
+  return () => {
+    cancelled = true;
+    unlisten?.();
+  };
+}
+
 export function useBriefings() {
   const [briefings, setBriefings] = useState<Briefing[]>([]);
   const [loading, setLoading] = useState(false);
   const [error, setError] = useState<string | null>(null);
 
-  const getBriefings = useCallback(async (limit?: number) => {
+  const getBriefings = useCallback(async (limit?: number, filter?: BriefingListFilter) => {
     setLoading(true);
     setError(null);
     try {
-      const result = await safeInvoke<Briefing[]>('get_briefings', { limit });
+      const result = await safeInvoke<Briefing[]>('get_briefings', { limit, filter });
       setBriefings(result);
       return result;
     } catch (err) {
@@ -164,11 +215,11 @@ export function useTopics() {
     }
   }, []);
 
-  const addTopic = useCallback(async (name: string, description?: string) => {
+  const addTopic = useCallback(async (name: string, description?: string, preferredSources?: string[]) => {
     setLoading(true);
     setError(null);
     try {
-      const result = await safeInvoke<Topic>('add_topic', { name, description });
+      const result = await safeInvoke<Topic>('add_topic', { name, description, preferredSources });
       setTopics(prev => [...prev, result]);
       return result;
     } catch (err) {
@@ -180,11 +231,11 @@ export function useTopics() {
     }
   }, []);
 
-  const updateTopic = useCallback(async (id: string, name?: string, description?: string, enabled?: boolean) => {
+  const updateTopic = useCallback(async (id: string, name?: string, description?: string, enabled?: boolean, preferredSources?: string[], mcpServers?: string[]) => {
     setLoading(true);
     setError(null);
     try {
-      const result = await safeInvoke<Topic>('update_topic', { id, name, description, enabled });
+      const result = await safeInvoke<Topic>('update_topic', { id, name, description, enabled, preferredSources, mcpServers });
       setTopics(prev => prev.map(t => t.id === id ? result : t));
     } catch (err) {
       const errorMessage = err instanceof Error ? err.message : 'Failed to update topic';
@@ -194,6 +245,30 @@ export function useTopics() {
     }
   }, []);
 
+  // days: snooze for that many days (0 clears); skipNextRun: skip only the next run
+  const snoozeTopic = useCallback(async (id: string, days?: number, skipNextRun?: boolean) => {
+    setError(null);
+    try {
+      const result = await safeInvoke<Topic>('snooze_topic', { id, days, skipNextRun });
+      setTopics(prev => prev.map(t => t.id === id ? result : t));
+    } catch (err) {
+      const errorMessage = err instanceof Error ? err.message : 'Failed to snooze topic';
+      setError(errorMessage);
+    }
+  }, []);
+
+  // Per-topic dedup overrides; leave either undefined to use the global setting
+  const setTopicDedup = useCallback(async (id: string, days?: number, threshold?: number) => {
+    setError(null);
+    try {
+      const result = await safeInvoke<Topic>('set_topic_dedup', { id, days, threshold });
+      setTopics(prev => prev.map(t => t.id === id ? result : t));
+    } catch (err) {
+      const errorMessage = err instanceof Error ? err.message : 'Failed to update topic dedup settings';
+      setError(errorMessage);
+    }
+  }, []);
+
   const deleteTopic = useCallback(async (id: string) => {
     setLoading(true);
     setError(null);
@@ -219,10 +294,105 @@ export function useTopics() {
     getTopics,
     addTopic,
     updateTopic,
+    snoozeTopic,
+    setTopicDedup,
     deleteTopic,
   };
 }
 
+// Estimate a topic's tools and cost per run before adding it
+export async function previewTopic(name: string, description?: string, preferredSources?: string[]): Promise<TopicPreview> {
+  return safeInvoke<TopicPreview>('preview_topic', { name, description, preferredSources });
+}
+
+// How much each topic's findings change from run to run
+export async function getTopicActivity(runs?: number): Promise<TopicActivity[]> {
+  return safeInvoke<TopicActivity[]>('get_topic_activity', { runs });
+}
+
+// A topic's cards in chronological order, optionally with a narrative from Claude
+export async function getTopicTimeline(topic: string, narrate = false, days?: number): Promise<TopicTimeline> {
+  return safeInvoke<TopicTimeline>('get_topic_timeline', { topic, narrate, days });
+}
+
+// Ask a question about the whole briefing archive; pass earlier turns to follow up
+export async function askArchive(message: string, history: ArchiveTurn[] = []): Promise<ArchiveAnswer> {
+  return safeInvoke<ArchiveAnswer>('send_archive_chat_message', { message, history });
+}
+
+// Summarize the past week's or month's briefings into a new briefing
+export async function generateMetaBriefing(period: 'weekly' | 'monthly'): Promise<MetaBriefing> {
+  return safeInvoke<MetaBriefing>('generate_meta_briefing', { period });
+}
+
+// Topic Suggestions Hook - topics discovered from bookmarks, ratings and chats
+export function useTopicSuggestions() {
+  const [suggestions, setSuggestions] = useState<TopicSuggestion[]>([]);
+  const [refreshing, setRefreshing] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  const getSuggestions = useCallback(async () => {
+    setError(null);
+    try {
+      setSuggestions(await safeInvoke<TopicSuggestion[]>('get_topic_suggestions'));
+    } catch (err) {
+      const errorMessage = err instanceof Error ? err.message : 'Failed to load topic suggestions';
+      setError(errorMessage);
+    }
+  }, []);
+
+  const refreshSuggestions = useCallback(async () => {
+    setRefreshing(true);
+    setError(null);
+    try {
+      await safeInvoke<TopicSuggestion[]>('suggest_topics');
+      await getSuggestions();
+    } catch (err) {
+      const errorMessage = err instanceof Error ? err.message : 'Failed to find topic suggestions';
+      setError(errorMessage);
+    } finally {
+      setRefreshing(false);
+    }
+  }, [getSuggestions]);
+
+  const acceptSuggestion = useCallback(async (id: number) => {
+    setError(null);
+    try {
+      const topic = await safeInvoke<Topic>('accept_topic_suggestion', { id });
+      setSuggestions(prev => prev.filter(s => s.id !== id));
+      return topic;
+    } catch (err) {
+      const errorMessage = err instanceof Error ? err.message : 'Failed to accept suggestion';
+      setError(errorMessage);
+      return null;
+    }
+  }, []);
+
+  const dismissSuggestion = useCallback(async (id: number) => {
+    setError(null);
+    try {
+      await safeInvoke<boolean>('dismiss_topic_suggestion', { id });
+      setSuggestions(prev => prev.filter(s => s.id !== id));
+    } catch (err) {
+      const errorMessage = err instanceof Error ? err.message : 'Failed to dismiss suggestion';
+      setError(errorMessage);
+    }
+  }, []);
+
+  useEffect(() => {
+    getSuggestions();
+  }, [getSuggestions]);
+
+  return {
+    suggestions,
+    refreshing,
+    error,
+    refreshSuggestions,
+    acceptSuggestion,
+    dismissSuggestion,
+  };
+}
+
 export function useMCPServers() {
   const [servers, setServers] = useState<MCPServer[]>([]);
   const [loading, setLoading] = useState(false);
@@ -265,6 +435,25 @@ export function useMCPServers() {
     }
   }, []);
 
+  const installPreset = useCallback(async (id: string, key?: string) => {
+    setLoading(true);
+    setError(null);
+    try {
+      const result = await safeInvoke<MCPServer>('install_mcp_preset', { id, key: key || null });
+      // Installing a preset that is already configured updates it in place
+      setServers(prev => prev.some(s => s.id === result.id)
+        ? prev.map(s => s.id === result.id ? result : s)
+        : [...prev, result]);
+      return result;
+    } catch (err) {
+      const errorMessage = typeof err === 'string' ? err : (err instanceof Error ? err.message : 'Failed to install MCP server');
+      setError(errorMessage);
+      return null;
+    } finally {
+      setLoading(false);
+    }
+  }, []);
+
   const toggleServer = useCallback(async (id: string, enabled: boolean) => {
     setLoading(true);
     setError(null);
@@ -317,18 +506,46 @@ export function useMCPServers() {
     getServers();
   }, [getServers]);
 
+  useEffect(() => onConfigChanged('mcp_servers', getServers), [getServers]);
+
   return {
     servers,
     loading,
     error,
     getServers,
     addServer,
+    installPreset,
     updateServer,
     toggleServer,
     removeServer,
   };
 }
 
+// The MCP server catalog for the install picker
+export async function getMcpCatalog(refresh = false): Promise<McpCatalog> {
+  return safeInvoke<McpCatalog>('get_mcp_catalog', { refresh });
+}
+
+// Values a catalog preset needs before it can be installed
+export async function getMcpPresetFields(id: string): Promise<McpRequiredField[]> {
+  return safeInvoke<McpRequiredField[]>('get_mcp_preset_fields', { id });
+}
+
+// Built-in tools with their enabled state, for the tool toggles in Settings
+export async function getBuiltinTools(): Promise<BuiltinTool[]> {
+  return safeInvoke<BuiltinTool[]>('get_builtin_tools');
+}
+
+// Every research mode with its description
+export async function getResearchModes(): Promise<ResearchModeInfo[]> {
+  return safeInvoke<ResearchModeInfo[]>('get_research_modes');
+}
+
+// Header image style presets for the image_style setting
+export async function getImageStyles(): Promise<ImageStyle[]> {
+  return safeInvoke<ImageStyle[]>('get_image_styles');
+}
+
 export function useSettings() {
   const [settings, setSettings] = useState<ResearchSettings | null>(null);
   const [loading, setLoading] = useState(false);
@@ -369,6 +586,12 @@ export function useSettings() {
     }
   }, [settings]);
 
+  // Register and save the show/hide shortcut ("off" turns it off); throws if it's invalid or taken
+  const updateGlobalShortcut = useCallback(async (shortcut: string) => {
+    const result = await safeInvoke<ResearchSettings>('update_global_shortcut', { shortcut });
+    setSettings(result);
+  }, []);
+
   const runResearch = useCallback(async () => {
     setLoading(true);
     setError(null);
@@ -383,17 +606,52 @@ export function useSettings() {
     }
   }, []);
 
+  // One-off question researched without a topic; saved as an "ad-hoc" briefing
+  const researchAdhoc = useCallback(async (query: string) => {
+    setLoading(true);
+    setError(null);
+    try {
+      return await safeInvoke<string>('research_adhoc', { query });
+    } catch (err) {
+      const errorMessage = typeof err === 'string' ? err : (err instanceof Error ? err.message : 'Failed to research question');
+      setError(errorMessage);
+      return null;
+    } finally {
+      setLoading(false);
+    }
+  }, []);
+
+  // Research copied text the way the quick research shortcut does
+  const quickResearch = useCallback(async (text: string) => {
+    setLoading(true);
+    setError(null);
+    try {
+      return await safeInvoke<QuickResearchResult>('quick_research', { text });
+    } catch (err) {
+      const errorMessage = typeof err === 'string' ? err : (err instanceof Error ? err.message : 'Quick research failed');
+      setError(errorMessage);
+      return null;
+    } finally {
+      setLoading(false);
+    }
+  }, []);
+
   useEffect(() => {
     getSettings();
   }, [getSettings]);
 
+  useEffect(() => onConfigChanged('settings', getSettings), [getSettings]);
+
   return {
     settings,
     loading,
     error,
     getSettings,
     updateSettings,
+    updateGlobalShortcut,
     runResearch,
+    researchAdhoc,
+    quickResearch,
   };
 }
 
@@ -450,6 +708,137 @@ export function useApiKey() {
   };
 }
 
+// Credential Profiles Hook (named Anthropic API keys)
+export function useProfiles() {
+  const [profiles, setProfiles] = useState<ProfileInfo[]>([]);
+  const [error, setError] = useState<string | null>(null);
+
+  const getProfiles = useCallback(async () => {
+    setError(null);
+    try {
+      setProfiles(await safeInvoke<ProfileInfo[]>('list_profiles'));
+    } catch (err) {
+      const errorMessage = err instanceof Error ? err.message : 'Failed to load profiles';
+      setError(errorMessage);
+    }
+  }, []);
+
+  const setActiveProfile = useCallback(async (name: string) => {
+    setError(null);
+    try {
+      await safeInvoke<void>('set_active_profile', { name });
+      await getProfiles();
+      return true;
+    } catch (err) {
+      const errorMessage = err instanceof Error ? err.message : 'Failed to switch profile';
+      setError(errorMessage);
+      return false;
+    }
+  }, [getProfiles]);
+
+  useEffect(() => {
+    getProfiles();
+  }, [getProfiles]);
+
+  return {
+    profiles,
+    error,
+    getProfiles,
+    setActiveProfile,
+  };
+}
+
+export async function getProfileUsage(days?: number): Promise<ProfileUsage[]> {
+  return safeInvoke<ProfileUsage[]>('get_profile_usage', { days });
+}
+
+// Workspaces Hook (separate topics, briefings and settings)
+export function useWorkspaces() {
+  const [workspaces, setWorkspaces] = useState<Workspace[]>([]);
+  const [error, setError] = useState<string | null>(null);
+
+  const getWorkspaces = useCallback(async () => {
+    setError(null);
+    try {
+      setWorkspaces(await safeInvoke<Workspace[]>('list_workspaces'));
+    } catch (err) {
+      const errorMessage = err instanceof Error ? err.message : 'Failed to load workspaces';
+      setError(errorMessage);
+    }
+  }, []);
+
+  const createWorkspace = useCallback(async (name: string) => {
+    setError(null);
+    try {
+      await safeInvoke<void>('create_workspace', { name });
+      await getWorkspaces();
+      return true;
+    } catch (err) {
+      const errorMessage = err instanceof Error ? err.message : 'Failed to create workspace';
+      setError(errorMessage);
+      return false;
+    }
+  }, [getWorkspaces]);
+
+  // Restarts the app on the new workspace; only returns on failure
+  const switchWorkspace = useCallback(async (name: string) => {
+    setError(null);
+    try {
+      await safeInvoke<void>('switch_workspace', { name });
+    } catch (err) {
+      const errorMessage = err instanceof Error ? err.message : 'Failed to switch workspace';
+      setError(errorMessage);
+    }
+  }, []);
+
+  useEffect(() => {
+    getWorkspaces();
+  }, [getWorkspaces]);
+
+  return {
+    workspaces,
+    error,
+    getWorkspaces,
+    createWorkspace,
+    switchWorkspace,
+  };
+}
+
+// Models Hook - available Anthropic models for the model dropdown
+export function useModels() {
+  const [models, setModels] = useState<ModelInfo[]>([]);
+  const [loading, setLoading] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  const getModels = useCallback(async (refresh?: boolean) => {
+    setLoading(true);
+    setError(null);
+    try {
+      setModels(await safeInvoke<ModelInfo[]>('list_models', { refresh }));
+    } catch (err) {
+      const errorMessage = err instanceof Error ? err.message : 'Failed to load models';
+      setError(errorMessage);
+    } finally {
+      setLoading(false);
+    }
+  }, []);
+
+  useEffect(() => {
+    getModels();
+  }, [getModels]);
+
+  return {
+    models,
+    loading,
+    error,
+    getModels,
+  };
+}
+
+export async function checkModel(model: string): Promise<string | null> {
+  return safeInvoke<string | null>('check_model', { model });
+}
+
 // OpenAI API Key Hook (for DALL-E image generation)
 export function useOpenAIApiKey() {
   const [maskedKey, setMaskedKey] = useState<string | null>(null);
@@ -521,6 +910,160 @@ export function useOpenAIApiKey() {
   };
 }
 
+// Read-Later Queue Hook
+export function useReadLater() {
+  const [queue, setQueue] = useState<ReadLaterItem[]>([]);
+  const [loading, setLoading] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  const getQueue = useCallback(async (includeCompleted = false) => {
+    setLoading(true);
+    setError(null);
+    try {
+      const result = await safeInvoke<ReadLaterItem[]>('get_read_later_queue', { includeCompleted });
+      setQueue(result);
+      return result;
+    } catch (err) {
+      const errorMessage = err instanceof Error ? err.message : 'Failed to fetch read-later queue';
+      setError(errorMessage);
+      setQueue([]);
+      return [];
+    } finally {
+      setLoading(false);
+    }
+  }, []);
+
+  const saveForLater = useCallback(async (briefingId: number, cardIndex: number) => {
+    try {
+      const item = await safeInvoke<ReadLaterItem>('save_card_for_later', { briefingId, cardIndex });
+      await getQueue();
+      return item;
+    } catch (err) {
+      const errorMessage = err instanceof Error ? err.message : 'Failed to save card for later';
+      setError(errorMessage);
+      return null;
+    }
+  }, [getQueue]);
+
+  const completeItem = useCallback(async (id: number) => {
+    try {
+      await safeInvoke<boolean>('complete_read_later_item', { id });
+      setQueue(prev => prev.filter(item => item.id !== id));
+    } catch (err) {
+      const errorMessage = err instanceof Error ? err.message : 'Failed to complete item';
+      setError(errorMessage);
+    }
+  }, []);
+
+  const removeItem = useCallback(async (id: number) => {
+    try {
+      await safeInvoke<boolean>('remove_read_later_item', { id });
+      setQueue(prev => prev.filter(item => item.id !== id));
+    } catch (err) {
+      const errorMessage = err instanceof Error ? err.message : 'Failed to remove item';
+      setError(errorMessage);
+    }
+  }, []);
+
+  const reorderQueue = useCallback(async (ids: number[]) => {
+    try {
+      await safeInvoke<void>('reorder_read_later', { ids });
+      setQueue(prev => ids
+        .map(id => prev.find(item => item.id === id))
+        .filter((item): item is ReadLaterItem => item !== undefined));
+    } catch (err) {
+      const errorMessage = err instanceof Error ? err.message : 'Failed to reorder queue';
+      setError(errorMessage);
+    }
+  }, []);
+
+  useEffect(() => {
+    getQueue();
+  }, [getQueue]);
+
+  return {
+    queue,
+    loading,
+    error,
+    getQueue,
+    saveForLater,
+    completeItem,
+    removeItem,
+    reorderQueue,
+  };
+}
+
+// Notion Token Hook (for Notion export)
+export function useNotionToken() {
+  const [maskedToken, setMaskedToken] = useState<string | null>(null);
+  const [hasToken, setHasToken] = useState(false);
+  const [loading, setLoading] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  const checkToken = useCallback(async () => {
+    setLoading(true);
+    setError(null);
+    try {
+      const masked = await safeInvoke<string | null>('get_notion_token');
+      const exists = await safeInvoke<boolean>('has_notion_token');
+      setMaskedToken(masked);
+      setHasToken(exists);
+    } catch (err) {
+      const errorMessage = err instanceof Error ? err.message : 'Failed to check Notion token';
+      setError(errorMessage);
+    } finally {
+      setLoading(false);
+    }
+  }, []);
+
+  const setToken = useCallback(async (token: string) => {
+    setLoading(true);
+    setError(null);
+    try {
+      await safeInvoke<void>('set_notion_token', { token });
+      await checkToken();
+      return true;
+    } catch (err) {
+      const errorMessage = err instanceof Error ? err.message : 'Failed to set Notion token';
+      setError(errorMessage);
+      return false;
+    } finally {
+      setLoading(false);
+    }
+  }, [checkToken]);
+
+  const clearToken = useCallback(async () => {
+    setLoading(true);
+    setError(null);
+    try {
+      await safeInvoke<void>('clear_notion_token');
+      setMaskedToken(null);
+      setHasToken(false);
+      return true;
+    } catch (err) {
+      const errorMessage = err instanceof Error ? err.message : 'Failed to clear Notion token';
+      setError(errorMessage);
+      return false;
+    } finally {
+      setLoading(false);
+    }
+  }, []);
+
+  useEffect(() => {
+    checkToken();
+  }, [checkToken]);
+
+  return {
+    maskedToken,
+    hasToken,
+    loading,
+    error,
+    checkToken,
+    setToken,
+    clearToken,
+  };
+}
+
 // Bookmark Hook
 export function useBookmarks() {
   const [bookmarks, setBookmarks] = useState<Bookmark[]>([]);
@@ -585,3 +1128,116 @@ export function useBookmarks() {
     isBookmarked,
   };
 }
+
+// Notion Sync
+export async function syncBriefingToNotion(briefingId: number): Promise<string> {
+  return safeInvoke<string>('sync_briefing_to_notion', { briefingId });
+}
+
+// Discarded cards (below min_relevance)
+export async function getDiscardedCards(limit?: number): Promise<DiscardedCard[]> {
+  return safeInvoke<DiscardedCard[]>('get_discarded_cards', { limit });
+}
+
+export async function restoreDiscardedCard(id: number): Promise<number> {
+  return safeInvoke<number>('restore_discarded_card', { id });
+}
+
+export async function deleteDiscardedCard(id: number): Promise<boolean> {
+  return safeInvoke<boolean>('delete_discarded_card', { id });
+}
+
+// Card ordering (card indices, first card first)
+export async function reorderCards(briefingId: number, order: number[]): Promise<void> {
+  return safeInvoke<void>('reorder_cards', { briefingId, order });
+}
+
+// Tool usage analytics
+export async function getToolAnalytics(days?: number): Promise<ToolAnalytics> {
+  return safeInvoke<ToolAnalytics>('get_tool_analytics', { days });
+}
+
+// Dashboard statistics: streaks, briefings per week, cards per topic, spend and tool usage
+export async function getStatistics(days?: number): Promise<Statistics> {
+  return safeInvoke<Statistics>('get_statistics', { days });
+}
+
+// Per-run research report
+export async function getResearchReport(briefingId: number): Promise<RunReport> {
+  return safeInvoke<RunReport>('get_research_report', { briefingId });
+}
+
+// Per-topic breakdown of the run that produced a briefing (empty for older briefings)
+export async function getBriefingTopics(briefingId: number): Promise<TopicResult[]> {
+  return safeInvoke<TopicResult[]>('get_briefing_topics', { briefingId });
+}
+
+// Topics left out of a briefing because their research failed (empty if none did)
+export async function getBriefingFailedTopics(briefingId: number): Promise<FailedTopic[]> {
+  return safeInvoke<FailedTopic[]>('get_briefing_failed_topics', { briefingId });
+}
+
+// Research a briefing's failed topics again; saved as a new briefing
+export async function retryFailedTopics(briefingId: number): Promise<string> {
+  return safeInvoke<string>('retry_failed_topics', { briefingId });
+}
+
+// Saved window geometry and the view the app was last on
+export async function getUiState(): Promise<UiState> {
+  return safeInvoke<UiState>('get_ui_state');
+}
+
+// Remember the current view so the app reopens there
+export async function saveUiState(
+  route: string,
+  briefingId: number | null,
+  cardIndex: number | null
+): Promise<void> {
+  return safeInvoke<void>('save_ui_state', { route, briefingId, cardIndex });
+}
+
+// Regenerate one card's header image, optionally with an edited prompt or style
+// (defaults to the card's own); returns the image path
+export async function regenerateCardImage(
+  briefingId: number,
+  cardIndex: number,
+  prompt?: string,
+  style?: string
+): Promise<string> {
+  return safeInvoke<string>('regenerate_card_image', { briefingId, cardIndex, prompt, style });
+}
+
+// Render a briefing, or one card, as a PNG share image; returns the image path
+export async function renderCardShareImage(
+  briefingId: number,
+  cardIndex?: number,
+  path?: string
+): Promise<string> {
+  return safeInvoke<string>('render_card_share_image', { briefingId, cardIndex, path });
+}
+
+// Narration Hook
+export function useBriefingAudio() {
+  const [generating, setGenerating] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  const generateAudio = useCallback(async (briefingId: number, cardIndex?: number) => {
+    setGenerating(true);
+    setError(null);
+    try {
+      return await safeInvoke<string>('generate_briefing_audio', { briefingId, cardIndex });
+    } catch (err) {
+      const errorMessage = err instanceof Error ? err.message : 'Failed to generate audio';
+      setError(errorMessage);
+      return null;
+    } finally {
+      setGenerating(false);
+    }
+  }, []);
+
+  return {
+    generating,
+    error,
+    generateAudio,
+  };
+}
@@ -0,0 +1,46 @@
+import { useEffect, useState } from 'react';
+import { useLocation, useNavigate } from 'react-router-dom';
+import { getUiState, saveUiState } from './useTauri';
+
+// Current route of the HashRouter, read directly so it's never stale
+function currentRoute(): string {
+  return window.location.hash.replace(/^#/, '') || '/';
+}
+
+function numberParam(params: URLSearchParams, name: string): number | null {
+  const value = params.get(name);
+  return value !== null && !Number.isNaN(Number(value)) ? Number(value) : null;
+}
+
+/**
+ * Reopen the view (and briefing/card) the app was last on, and remember the
+ * view as the user moves around. A deep link that opened the app wins over
+ * the saved view.
+ */
+export function useUiState() {
+  const navigate = useNavigate();
+  const location = useLocation();
+  const [restored, setRestored] = useState(false);
+
+  useEffect(() => {
+    getUiState()
+      .then((state) => {
+        if (state.route && state.route !== '/' && currentRoute() === '/') {
+          navigate(state.route, { replace: true });
+        }
+      })
+      .catch((err) => console.error('Failed to load UI state:', err))
+      .finally(() => setRestored(true));
+  }, [navigate]);
+
+  useEffect(() => {
+    // Don't overwrite the saved view with the start page before it's restored
+    if (!restored) return;
+    const params = new URLSearchParams(location.search);
+    saveUiState(
+      location.pathname + location.search,
+      numberParam(params, 'briefing'),
+      numberParam(params, 'card')
+    ).catch((err) => console.error('Failed to save UI state:', err));
+  }, [restored, location.pathname, location.search]);
+}
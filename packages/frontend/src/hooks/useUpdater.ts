@@ -6,7 +6,7 @@ import type {
   UpdateDownloadedEvent,
   UpdateProgressEvent,
   UpdateState,
-  UpdateInfo,
+  UpdateCheck,
 } from '../types/update-events';
 
 const initialState: UpdateState = {
@@ -17,6 +17,7 @@ const initialState: UpdateState = {
   notes: null,
   downloadProgress: 0,
   error: null,
+  changelog: [],
 };
 
 export function useUpdater() {
@@ -74,24 +75,25 @@ export function useUpdater() {
     };
   }, []);
 
-  // Manual check for updates
+  // Manual check for updates on the configured release channel
   const checkForUpdate = useCallback(async () => {
     setState((prev) => ({ ...prev, isChecking: true, error: null }));
     try {
-      const result = await invoke<UpdateInfo | null>('check_for_update');
-      if (result) {
+      const result = await invoke<UpdateCheck>('check_for_updates');
+      const { update, changelog } = result;
+      if (update) {
         setState((prev) => ({
           ...prev,
           isChecking: false,
           updateAvailable: true,
-          version: result.version,
-          notes: result.notes,
+          version: update.version,
+          notes: update.notes,
+          changelog,
         }));
-        return result;
       } else {
-        setState((prev) => ({ ...prev, isChecking: false }));
-        return null;
+        setState((prev) => ({ ...prev, isChecking: false, changelog }));
       }
+      return result;
     } catch (err) {
       const errorMessage = err instanceof Error ? err.message : String(err);
       setState((prev) => ({
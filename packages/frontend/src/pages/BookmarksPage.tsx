@@ -92,6 +92,8 @@ export function BookmarksPage() {
           detailed_content: card.detailed_content,
           sources: card.sources || [],
           suggested_next: card.suggested_next,
+          action_items: card.action_items,
+          actions: card.actions,
           relevance: (card.relevance as 'high' | 'medium' | 'low') || 'medium',
           created_at: briefing.date,
           topic_id: '',
@@ -140,7 +142,7 @@ export function BookmarksPage() {
     const parts = briefingId.split('-');
     const numericBriefingId = parseInt(parts[0], 10);
     try {
-      await invoke('delete_briefing', { id: numericBriefingId });
+      await invoke('delete_briefing', { id: numericBriefingId, confirm: true });
       // Refresh the bookmarks list after deletion
       await getBookmarks();
     } catch (err) {
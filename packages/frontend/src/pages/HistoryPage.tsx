@@ -1,6 +1,7 @@
 import { useEffect, useState, useMemo, useRef } from 'react';
-import { Search, Filter, Calendar, Loader2, AlertCircle } from 'lucide-react';
+import { Search, Filter, Calendar, Loader2, AlertCircle, X } from 'lucide-react';
 import { motion } from 'framer-motion';
+import { useSearchParams } from 'react-router-dom';
 import { invoke } from '@tauri-apps/api/core';
 // date-fns available for future use
 import { BriefingCard } from '../components/BriefingCard';
@@ -22,6 +23,12 @@ export function HistoryPage() {
   const [activeChatCardIndex, setActiveChatCardIndex] = useState<number>(0);
   const [cardsWithChats, setCardsWithChats] = useState<Set<string>>(new Set());
   
+  // Deep links (claudius://briefing/123) open the history focused on one briefing
+  const [searchParams, setSearchParams] = useSearchParams();
+  const focusBriefingId = searchParams.get('briefing');
+  const focusCardIndex = searchParams.get('card');
+  const [focusedBriefing, setFocusedBriefing] = useState<BackendBriefing | null>(null);
+
   // Load more state
   const [visibleCount, setVisibleCount] = useState(20);
   const loadMoreIncrement = 20;
@@ -41,16 +48,40 @@ export function HistoryPage() {
     fetchCardsWithChats();
   }, [rawBriefings, chatOpen]); // Refetch when briefings change or chat closes
 
+  // Fetch the linked briefing directly, it may be older than the loaded list
+  useEffect(() => {
+    if (!focusBriefingId) {
+      setFocusedBriefing(null);
+      return;
+    }
+    invoke<BackendBriefing>('get_briefing_by_id', { id: focusBriefingId })
+      .then((briefing) => {
+        setFocusedBriefing(briefing);
+        invoke('mark_briefing_read', { id: briefing.id }).catch(() => {});
+      })
+      .catch((err) => {
+        console.error('Failed to load linked briefing:', err);
+        setFocusedBriefing(null);
+      });
+  }, [focusBriefingId]);
+
   // Parse the cards JSON and flatten into individual briefing cards
   const briefings = useMemo(() => {
     const result: Briefing[] = [];
-    for (const raw of rawBriefings as unknown as BackendBriefing[]) {
+    const source = focusedBriefing
+      ? [focusedBriefing]
+      : (rawBriefings as unknown as BackendBriefing[]);
+    for (const raw of source) {
       try {
         const cards: BriefingCardData[] = typeof raw.cards === 'string'
           ? JSON.parse(raw.cards)
           : raw.cards || [];
 
-        for (let i = 0; i < cards.length; i++) {
+        // Show cards in their ranked order; ids keep the stored index
+        const order = cards
+          .map((_, i) => i)
+          .sort((a, b) => (cards[a].rank ?? a) - (cards[b].rank ?? b));
+        for (const i of order) {
           const card = cards[i];
           result.push({
             id: `${raw.id}-${i}`,
@@ -59,6 +90,8 @@ export function HistoryPage() {
             detailed_content: card.detailed_content,
             sources: card.sources || [],
             suggested_next: card.suggested_next,
+            action_items: card.action_items,
+            actions: card.actions,
             relevance: (card.relevance as 'high' | 'medium' | 'low') || 'medium',
             created_at: raw.date,
             topic_id: '',
@@ -82,7 +115,18 @@ export function HistoryPage() {
       }
     }
     return result;
-  }, [rawBriefings]);
+  }, [rawBriefings, focusedBriefing]);
+
+  // Scroll to the linked card once it has rendered
+  useEffect(() => {
+    if (!focusedBriefing || focusCardIndex === null) return;
+    const timer = setTimeout(() => {
+      document
+        .getElementById(`card-${focusedBriefing.id}-${focusCardIndex}`)
+        ?.scrollIntoView({ behavior: 'smooth', block: 'start' });
+    }, 300);
+    return () => clearTimeout(timer);
+  }, [focusedBriefing, focusCardIndex]);
 
   // Apply client-side filters (relevance, topic, date, AND search query at card level)
   const filteredBriefings = useMemo(() => {
@@ -212,7 +256,7 @@ export function HistoryPage() {
     const parts = briefingId.split('-');
     const numericBriefingId = parseInt(parts[0], 10);
     try {
-      await invoke('delete_briefing', { id: numericBriefingId });
+      await invoke('delete_briefing', { id: numericBriefingId, confirm: true });
       // Refresh the list after deletion
       await searchBriefings(filters);
     } catch (err) {
@@ -240,6 +284,21 @@ export function HistoryPage() {
         </p>
       </div>
 
+      {focusedBriefing && (
+        <div className="glass-card p-4 mb-6 flex items-center justify-between">
+          <span className="text-sm text-gray-700 dark:text-gray-300">
+            Showing briefing #{focusedBriefing.id}: {focusedBriefing.title}
+          </span>
+          <button
+            onClick={() => setSearchParams({})}
+            className="flex items-center gap-1 text-sm text-primary-600 dark:text-primary-400 hover:underline"
+          >
+            <X className="w-4 h-4" />
+            Show all
+          </button>
+        </div>
+      )}
+
       <div className="glass-card p-6 mb-6">
         <div className="flex gap-3 mb-4">
           <div className="flex-1 relative">
@@ -400,6 +459,7 @@ export function HistoryPage() {
         {visibleBriefings.map((briefing) => (
           <motion.div
             key={briefing.id}
+            id={`card-${briefing.id}`}
             variants={{
               hidden: { opacity: 0, y: 20 },
               show: { opacity: 1, y: 0 }
@@ -1,8 +1,8 @@
 import { useEffect, useState, useMemo } from 'react';
 import { Link } from 'react-router-dom';
 import { format } from 'date-fns';
-import { RefreshCw, Loader2, AlertCircle, Calendar, Play } from 'lucide-react';
-import { invoke } from '@tauri-apps/api/core';
+import { RefreshCw, Loader2, AlertCircle, AlertTriangle, Calendar, Play, RotateCcw } from 'lucide-react';
+import { invoke, convertFileSrc } from '@tauri-apps/api/core';
 import { listen } from '@tauri-apps/api/event';
 import { motion } from 'framer-motion';
 import { BriefingCard } from '../components/BriefingCard';
@@ -10,10 +10,10 @@ import { ChatPanel } from '../components/ChatPanel';
 import { MagneticButton } from '../components/MagneticButton';
 import { ActionableErrorsAlert } from '../components/ActionableErrorsAlert';
 import { ResearchProgressCard } from '../components/ResearchProgressCard';
-import { useBriefings, useBookmarks } from '../hooks/useTauri';
+import { useBriefings, useBookmarks, getBriefingFailedTopics, retryFailedTopics } from '../hooks/useTauri';
 // Note: useResearch context manages isResearchRunning state internally
 import { useResearchProgress } from '../hooks/useResearchProgress';
-import type { Briefing, CardWithChat, BackendBriefing, BriefingCardData } from '../types';
+import type { Briefing, CardWithChat, BackendBriefing, BriefingCardData, FailedTopic } from '../types';
 
 export function HomePage() {
   const { briefings: rawBriefings, loading, error, getTodaysBriefings, /* submitFeedback */ } = useBriefings();
@@ -29,7 +29,11 @@ export function HomePage() {
           ? JSON.parse(raw.cards)
           : raw.cards || [];
 
-        for (let i = 0; i < cards.length; i++) {
+        // Show cards in their ranked order; ids keep the stored index
+        const order = cards
+          .map((_, i) => i)
+          .sort((a, b) => (cards[a].rank ?? a) - (cards[b].rank ?? b));
+        for (const i of order) {
           const card = cards[i];
           result.push({
             id: `${raw.id}-${i}`,
@@ -38,6 +42,8 @@ export function HomePage() {
             detailed_content: card.detailed_content,
             sources: card.sources || [],
             suggested_next: card.suggested_next,
+            action_items: card.action_items,
+            actions: card.actions,
             relevance: (card.relevance as 'high' | 'medium' | 'low') || 'medium',
             created_at: raw.date,
             topic_id: '',
@@ -62,8 +68,16 @@ export function HomePage() {
     }
     return result;
   }, [rawBriefings]);
+
+  // Hero image of the newest briefing that has one (enable_cover_image)
+  const cover = useMemo(
+    () => (rawBriefings as unknown as BackendBriefing[]).find((raw) => raw.cover_image_path),
+    [rawBriefings]
+  );
   const [refreshing, setRefreshing] = useState(false);
   const [researchError, setResearchError] = useState<string | null>(null);
+  // Topics left out of the newest briefing because their research failed
+  const [failedTopics, setFailedTopics] = useState<{ briefingId: number; topics: FailedTopic[] } | null>(null);
 
   // Derive runningResearch from context progress - persists across navigation
   const runningResearch = progress.isRunning;
@@ -129,6 +143,22 @@ export function HomePage() {
     fetchCardsWithChats();
   }, [rawBriefings, chatOpen]); // Refetch when briefings change or chat closes
 
+  // Look up failed topics of the newest briefing
+  useEffect(() => {
+    const ids = (rawBriefings as unknown as BackendBriefing[]).map((raw) => raw.id);
+    if (ids.length === 0) {
+      setFailedTopics(null);
+      return;
+    }
+    const briefingId = Math.max(...ids);
+    getBriefingFailedTopics(briefingId)
+      .then((topics) => setFailedTopics(topics.length > 0 ? { briefingId, topics } : null))
+      .catch((err) => {
+        console.error('Failed to fetch failed topics:', err);
+        setFailedTopics(null);
+      });
+  }, [rawBriefings]);
+
   // Note: isResearchRunning is synced with progress.isRunning in ResearchContext
 
   const handleRefresh = async () => {
@@ -166,6 +196,20 @@ export function HomePage() {
     // Note: runningResearch state is managed by context via research events
   };
 
+  const handleRetryFailed = async () => {
+    if (runningResearch || !failedTopics) return;
+
+    setResearchError(null);
+    try {
+      await retryFailedTopics(failedTopics.briefingId);
+    } catch (err) {
+      const errorMessage = err instanceof Error ? err.message : String(err);
+      console.error('Retry failed:', errorMessage);
+      setResearchError(`Retry failed: ${errorMessage}`);
+      await getTodaysBriefings('retry-error-handler');
+    }
+  };
+
   /* Thumbs handlers commented out - not currently used
   const handleThumbsUp = (briefingId: string) => {
     submitFeedback({
@@ -208,7 +252,7 @@ export function HomePage() {
     const parts = briefingId.split('-');
     const numericBriefingId = parseInt(parts[0], 10);
     try {
-      await invoke('delete_briefing', { id: numericBriefingId });
+      await invoke('delete_briefing', { id: numericBriefingId, confirm: true });
       // Refresh the list after deletion
       await getTodaysBriefings('after-delete');
     } catch (err) {
@@ -292,6 +336,35 @@ export function HomePage() {
         </div>
       )}
 
+      {failedTopics && !runningResearch && (
+        <div className="card p-4 bg-amber-50 dark:bg-amber-900/20 border-amber-200 dark:border-amber-800 mb-6">
+          <div className="flex items-start gap-3">
+            <AlertTriangle className="w-5 h-5 text-amber-600 dark:text-amber-400 flex-shrink-0 mt-0.5" />
+            <div className="flex-1">
+              <h3 className="font-semibold text-amber-900 dark:text-amber-300 mb-1">
+                {failedTopics.topics.length === 1
+                  ? '1 topic failed and was left out'
+                  : `${failedTopics.topics.length} topics failed and were left out`}
+              </h3>
+              <ul className="text-sm text-amber-700 dark:text-amber-400 space-y-0.5">
+                {failedTopics.topics.map((failed) => (
+                  <li key={failed.topic}>
+                    <span className="font-medium">{failed.topic}:</span> {failed.error}
+                  </li>
+                ))}
+              </ul>
+            </div>
+            <button
+              onClick={handleRetryFailed}
+              className="btn btn-secondary flex items-center gap-2 text-sm"
+            >
+              <RotateCcw className="w-4 h-4" />
+              Retry
+            </button>
+          </div>
+        </div>
+      )}
+
       {loading && !refreshing && (
         <div className="flex items-center justify-center py-12">
           <div className="text-center">
@@ -334,6 +407,19 @@ export function HomePage() {
         </div>
       )}
 
+      {cover?.cover_image_path && (
+        <div className="relative mb-6 overflow-hidden rounded-2xl">
+          <img
+            src={convertFileSrc(cover.cover_image_path)}
+            alt=""
+            className="w-full h-56 object-cover"
+          />
+          <div className="absolute inset-0 bg-gradient-to-t from-black/70 to-transparent flex items-end p-5">
+            <h2 className="text-2xl font-bold text-white">{cover.title}</h2>
+          </div>
+        </div>
+      )}
+
       <motion.div
         className="space-y-6"
         variants={{
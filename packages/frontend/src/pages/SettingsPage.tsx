@@ -1,10 +1,20 @@
 import { useState, useEffect } from 'react';
-import { Plus, X, Trash2, CheckCircle2, Loader2, Play, Key, Eye, EyeOff, Edit2, AlertTriangle, Globe, Save, Terminal, Search, Bot, Github, ExternalLink, Sparkles, HardDrive, Layers, Filter, Image, Flame, Zap, Info } from 'lucide-react';
+import type { BuiltinTool, EventPreference, ImageStyle, McpCatalogEntry, NotificationEvent, ResearchModeInfo, TopicPreview } from '../types';
+import { Plus, X, Trash2, CheckCircle2, Loader2, Play, Key, Eye, EyeOff, Edit2, AlertTriangle, Globe, Save, Terminal, Search, Bot, Github, ExternalLink, Sparkles, HardDrive, Layers, Filter, Image, Flame, Zap, Info, RefreshCw, FolderOpen } from 'lucide-react';
 import { invoke } from '@tauri-apps/api/core';
-import { useTopics, useMCPServers, useSettings, useApiKey, useOpenAIApiKey } from '../hooks/useTauri';
+import { useTopics, useMCPServers, useSettings, useApiKey, useOpenAIApiKey, useProfiles, useWorkspaces, useModels, checkModel, useTopicSuggestions, previewTopic, getBuiltinTools, getResearchModes, getImageStyles, getMcpCatalog } from '../hooks/useTauri';
+import { useUpdater } from '../hooks/useUpdater';
 import { MagneticButton } from '../components/MagneticButton';
 import { motion, AnimatePresence } from 'framer-motion';
 
+const NOTIFICATION_EVENTS: { event: NotificationEvent; label: string }[] = [
+  { event: 'research_completed', label: 'Research completed' },
+  { event: 'research_failed', label: 'Research failed' },
+  { event: 'research_cancelled', label: 'Research cancelled' },
+  { event: 'actionable_error', label: 'Problems to fix' },
+  { event: 'weekly_digest_ready', label: 'Weekly digest ready' },
+];
+
 type Tab = 'interests' | 'mcp' | 'research';
 
 // Confirmation Dialog Component
@@ -133,11 +143,22 @@ export function SettingsPage() {
 }
 
 function InterestsTab() {
-  const { topics, loading, addTopic, updateTopic, deleteTopic } = useTopics();
+  const { topics, loading, getTopics, addTopic, updateTopic, deleteTopic } = useTopics();
+  const {
+    suggestions,
+    refreshing: refreshingSuggestions,
+    error: suggestionsError,
+    refreshSuggestions,
+    acceptSuggestion,
+    dismissSuggestion,
+  } = useTopicSuggestions();
   const [newTopicName, setNewTopicName] = useState('');
   const [newTopicDescription, setNewTopicDescription] = useState('');
   const [showAddForm, setShowAddForm] = useState(false);
   const [saving, setSaving] = useState(false);
+  const [preview, setPreview] = useState<TopicPreview | null>(null);
+  const [previewing, setPreviewing] = useState(false);
+  const [previewError, setPreviewError] = useState<string | null>(null);
   const [deleteConfirm, setDeleteConfirm] = useState<{ id: string; name: string } | null>(null);
 
   const handleAddTopic = async () => {
@@ -148,12 +169,33 @@ function InterestsTab() {
       await addTopic(newTopicName, newTopicDescription || undefined);
       setNewTopicName('');
       setNewTopicDescription('');
+      setPreview(null);
       setShowAddForm(false);
     } finally {
       setSaving(false);
     }
   };
 
+  const handlePreviewTopic = async () => {
+    if (!newTopicName.trim()) return;
+
+    setPreviewing(true);
+    setPreviewError(null);
+    try {
+      setPreview(await previewTopic(newTopicName, newTopicDescription || undefined));
+    } catch (err) {
+      setPreviewError(err instanceof Error ? err.message : 'Failed to preview topic');
+    } finally {
+      setPreviewing(false);
+    }
+  };
+
+  const handleAcceptSuggestion = async (id: number) => {
+    if (await acceptSuggestion(id)) {
+      await getTopics();
+    }
+  };
+
   const handleToggleTopic = async (topicId: string, enabled: boolean) => {
     await updateTopic(topicId, undefined, undefined, enabled);
   };
@@ -240,7 +282,40 @@ function InterestsTab() {
                 className="input w-full resize-none"
               />
             </div>
+            {previewError && (
+              <p className="text-xs text-red-600 dark:text-red-400">{previewError}</p>
+            )}
+            {preview && (
+              <div className="p-3 text-sm bg-white dark:bg-gray-900/50 rounded-lg border border-gray-200 dark:border-gray-700">
+                {preview.plan && (
+                  <p className="text-gray-700 dark:text-gray-300 mb-2">{preview.plan}</p>
+                )}
+                <p className="text-gray-600 dark:text-gray-400">
+                  Tools: {preview.tools.length > 0 ? preview.tools.join(', ') : 'none'}
+                </p>
+                <p className="text-gray-600 dark:text-gray-400">
+                  ~{preview.tool_calls} calls per run ({preview.searches} searches, {preview.page_fetches} page fetches)
+                </p>
+                <p className="font-medium text-gray-900 dark:text-white">
+                  ~${preview.estimated_cost_usd.toFixed(3)} per run, ~${preview.estimated_monthly_cost_usd.toFixed(2)} per month
+                </p>
+                {!preview.from_history && (
+                  <p className="text-xs text-gray-500 dark:text-gray-400 mt-1">
+                    Rough estimate; it gets more accurate after a few research runs.
+                  </p>
+                )}
+              </div>
+            )}
             <div className="flex gap-2">
+              <MagneticButton
+                onClick={handlePreviewTopic}
+                disabled={!newTopicName.trim() || previewing}
+                variant="secondary"
+                className="flex items-center gap-2"
+              >
+                {previewing && <Loader2 className="w-4 h-4 animate-spin" />}
+                Preview Cost
+              </MagneticButton>
               <MagneticButton
                 onClick={handleAddTopic}
                 disabled={!newTopicName.trim() || saving}
@@ -265,6 +340,59 @@ function InterestsTab() {
         </div>
       )}
 
+      {/* Topic suggestions from bookmarks, ratings and chats */}
+      <div className="mb-6 p-4 bg-gray-50 dark:bg-gray-800/50 rounded-lg border border-gray-200 dark:border-gray-700">
+        <div className="flex items-center justify-between mb-2">
+          <div className="flex items-center gap-2">
+            <Sparkles className="w-4 h-4 text-primary-600 dark:text-primary-400" />
+            <h3 className="font-medium text-gray-900 dark:text-white">Suggested Topics</h3>
+          </div>
+          <button
+            onClick={refreshSuggestions}
+            disabled={refreshingSuggestions}
+            className="text-sm text-primary-600 dark:text-primary-400 hover:underline disabled:opacity-50 flex items-center gap-1"
+          >
+            {refreshingSuggestions && <Loader2 className="w-3 h-3 animate-spin" />}
+            Find suggestions
+          </button>
+        </div>
+        {suggestionsError && (
+          <p className="text-xs text-red-600 dark:text-red-400 mb-2">{suggestionsError}</p>
+        )}
+        {suggestions.length === 0 ? (
+          <p className="text-sm text-gray-600 dark:text-gray-400">
+            Suggestions appear here based on the cards you bookmark, rate and chat about.
+          </p>
+        ) : (
+          <div className="space-y-2">
+            {suggestions.map((suggestion) => (
+              <div key={suggestion.id} className="flex items-center justify-between gap-3">
+                <div className="flex-1 min-w-0">
+                  <p className="text-sm font-medium text-gray-900 dark:text-white">{suggestion.name}</p>
+                  {suggestion.reason && (
+                    <p className="text-xs text-gray-500 dark:text-gray-400">{suggestion.reason}</p>
+                  )}
+                </div>
+                <button
+                  onClick={() => handleAcceptSuggestion(suggestion.id)}
+                  className="p-1.5 text-gray-400 hover:text-green-600 dark:hover:text-green-400 transition-colors"
+                  aria-label="Add suggested topic"
+                >
+                  <Plus className="w-4 h-4" />
+                </button>
+                <button
+                  onClick={() => dismissSuggestion(suggestion.id)}
+                  className="p-1.5 text-gray-400 hover:text-red-600 dark:hover:text-red-400 transition-colors"
+                  aria-label="Dismiss suggestion"
+                >
+                  <X className="w-4 h-4" />
+                </button>
+              </div>
+            ))}
+          </div>
+        )}
+      </div>
+
       {loading && topics.length === 0 ? (
         <div className="text-center py-8">
           <Loader2 className="w-6 h-6 animate-spin text-gray-400 mx-auto mb-2" />
@@ -359,7 +487,7 @@ const MCP_PRESETS = [
   { name: 'Fetch', command: 'npx -y @anthropic/server-fetch', description: 'Fetch web content' },
 ];
 
-// Quick Setup servers for research - these are the recommended MCP servers
+// Quick Setup servers for research - the catalog presets that take an API key
 interface QuickSetupServer {
   id: string;
   name: string;
@@ -369,65 +497,31 @@ interface QuickSetupServer {
   apiKeyLabel: string;
   apiKeyUrl: string;
   apiKeyEnvVar: string;
-  command: string;
-  args: string[];
   recommended?: boolean;
 }
 
-const QUICK_SETUP_SERVERS: QuickSetupServer[] = [
-  {
-    id: 'brave-search',
-    name: 'Brave Search',
-    description: 'Real-time web search for current news and articles',
-    icon: 'Search',
-    pricing: 'Free tier: 2,000/month',
-    apiKeyLabel: 'API Key',
-    apiKeyUrl: 'https://brave.com/search/api/',
-    apiKeyEnvVar: 'BRAVE_API_KEY',
-    command: 'npx',
-    args: ['-y', '@anthropic/server-brave-search'],
-    recommended: true,
-  },
-  {
-    id: 'perplexity',
-    name: 'Perplexity',
-    description: 'AI-powered search that validates and enriches research',
-    icon: 'Bot',
-    pricing: 'Pay-as-you-go',
-    apiKeyLabel: 'API Key',
-    apiKeyUrl: 'https://www.perplexity.ai/settings/api',
-    apiKeyEnvVar: 'PERPLEXITY_API_KEY',
-    command: 'npx',
-    args: ['-y', 'server-perplexity-ask'],
-    recommended: true,
-  },
-  {
-    id: 'github',
-    name: 'GitHub',
-    description: 'Search repositories, issues, and pull requests',
-    icon: 'Github',
-    pricing: 'Free',
-    apiKeyLabel: 'Personal Access Token',
-    apiKeyUrl: 'https://github.com/settings/tokens',
-    apiKeyEnvVar: 'GITHUB_TOKEN',
-    command: 'npx',
-    args: ['-y', '@modelcontextprotocol/server-github'],
-    recommended: false,
-  },
-  {
-    id: 'firecrawl',
-    name: 'Firecrawl',
-    description: 'Deep web scraping and extraction for comprehensive research',
-    icon: 'Flame',
-    pricing: 'Pay-as-you-go',
-    apiKeyLabel: 'API Key',
-    apiKeyUrl: 'https://firecrawl.dev/app/api-keys',
-    apiKeyEnvVar: 'FIRECRAWL_API_KEY',
-    command: 'npx',
-    args: ['-y', 'firecrawl-mcp'],
-    recommended: false,
-  },
-];
+const QUICK_SETUP_ICONS: Record<string, QuickSetupServer['icon']> = {
+  'brave-search': 'Search',
+  perplexity: 'Bot',
+  github: 'Github',
+  firecrawl: 'Flame',
+};
+
+function toQuickSetupServer(entry: McpCatalogEntry): QuickSetupServer | null {
+  const apiKeyEnvVar = Object.keys(entry.env ?? {}).find(name => entry.env[name].includes('{key}'));
+  if (!apiKeyEnvVar) return null;
+  return {
+    id: entry.id,
+    name: entry.name,
+    description: entry.description,
+    icon: QUICK_SETUP_ICONS[entry.id] ?? 'Search',
+    pricing: entry.pricing,
+    apiKeyLabel: entry.key_label ?? 'API Key',
+    apiKeyUrl: entry.key_url ?? '',
+    apiKeyEnvVar,
+    recommended: entry.recommended,
+  };
+}
 
 // Icon component mapping for Quick Setup
 function QuickSetupIcon({ icon, className }: { icon: QuickSetupServer['icon']; className?: string }) {
@@ -622,8 +716,8 @@ function QuickSetupModal({
             </div>
 
             {error && (
-              <div className="p-3 bg-red-50 dark:bg-red-900/20 border border-red-200 dark:border-red-800 rounded-lg">
-                <p className="text-sm text-red-600 dark:text-red-400">{error}</p>
+              <div className="p-3 bg-red-50 dark:bg-red-900/20 border border-red-200 dark:border-red-800 rounded-lg max-h-48 overflow-y-auto">
+                <p className="text-sm text-red-600 dark:text-red-400 whitespace-pre-line">{error}</p>
               </div>
             )}
 
@@ -645,7 +739,7 @@ function QuickSetupModal({
                 ) : (
                   <Plus className="w-4 h-4" />
                 )}
-                Add Server
+                {saving ? 'Checking connection...' : 'Add Server'}
               </button>
             </div>
           </form>
@@ -656,7 +750,7 @@ function QuickSetupModal({
 }
 
 function MCPServersTab() {
-  const { servers, loading, error: mcpError, addServer, updateServer, toggleServer, removeServer } = useMCPServers();
+  const { servers, loading, error: mcpError, addServer, installPreset, updateServer, toggleServer, removeServer } = useMCPServers();
   const [showAddForm, setShowAddForm] = useState(false);
   const [newServerName, setNewServerName] = useState('');
   const [newServerCommand, setNewServerCommand] = useState('');
@@ -674,12 +768,21 @@ function MCPServersTab() {
   const [deleteConfirm, setDeleteConfirm] = useState<{ id: string; name: string } | null>(null);
 
   // Quick Setup state
+  const [quickSetupServers, setQuickSetupServers] = useState<QuickSetupServer[]>([]);
   const [quickSetupServer, setQuickSetupServer] = useState<QuickSetupServer | null>(null);
   const [quickSetupSaving, setQuickSetupSaving] = useState(false);
 
+  useEffect(() => {
+    getMcpCatalog()
+      .then(catalog => setQuickSetupServers(
+        catalog.servers.map(toQuickSetupServer).filter((s): s is QuickSetupServer => s !== null)
+      ))
+      .catch(err => console.error('Failed to load MCP catalog:', err));
+  }, []);
+
   // Check if a Quick Setup server is already added
   const isQuickSetupServerAdded = (serverId: string): boolean => {
-    const server = QUICK_SETUP_SERVERS.find(s => s.id === serverId);
+    const server = quickSetupServers.find(s => s.id === serverId);
     if (!server) return false;
     // Check by name or by matching the env var key
     return servers.some(s =>
@@ -694,13 +797,7 @@ function MCPServersTab() {
 
     setQuickSetupSaving(true);
     try {
-      const config: Record<string, unknown> = {
-        command: quickSetupServer.command,
-        args: quickSetupServer.args,
-        env: { [quickSetupServer.apiKeyEnvVar]: apiKey },
-      };
-
-      const result = await addServer(quickSetupServer.name, config);
+      const result = await installPreset(quickSetupServer.id, apiKey);
       // Only close modal if server was added successfully
       if (result) {
         setQuickSetupServer(null);
@@ -887,7 +984,7 @@ function MCPServersTab() {
           <span className="text-xs text-gray-500 dark:text-gray-400">Recommended for research</span>
         </div>
         <div className="grid grid-cols-1 sm:grid-cols-3 gap-3">
-          {QUICK_SETUP_SERVERS.map((server) => (
+          {quickSetupServers.map((server) => (
             <QuickSetupCard
               key={server.id}
               server={server}
@@ -1354,8 +1451,18 @@ function MCPServersTab() {
 }
 
 function ResearchSettingsTab({ onMcpServersChanged }: { onMcpServersChanged?: () => void }) {
-  const { settings, loading, updateSettings, runResearch } = useSettings();
-  const { maskedKey, hasKey, loading: apiKeyLoading, setApiKey } = useApiKey();
+  const { settings, loading, getSettings, updateSettings, updateGlobalShortcut, runResearch } = useSettings();
+  const { maskedKey, hasKey, loading: apiKeyLoading, setApiKey, checkApiKey } = useApiKey();
+  const { profiles, setActiveProfile } = useProfiles();
+  const { workspaces, error: workspaceError, createWorkspace, switchWorkspace } = useWorkspaces();
+  const [newWorkspace, setNewWorkspace] = useState('');
+  const { models } = useModels();
+  const [modelWarning, setModelWarning] = useState<string | null>(null);
+  const [aboutMe, setAboutMe] = useState('');
+
+  useEffect(() => {
+    setAboutMe(settings?.about_me ?? '');
+  }, [settings?.about_me]);
   const {
     maskedKey: openaiMaskedKey,
     hasKey: hasOpenaiKey,
@@ -1365,6 +1472,25 @@ function ResearchSettingsTab({ onMcpServersChanged }: { onMcpServersChanged?: ()
   const { servers: mcpServers, toggleServer, getServers: refreshMcpServers } = useMCPServers();
   const [running, setRunning] = useState(false);
   const [savedIndicator, setSavedIndicator] = useState<string | null>(null);
+  const [shortcutError, setShortcutError] = useState<string | null>(null);
+  const updater = useUpdater();
+  const [updateChecked, setUpdateChecked] = useState(false);
+  const [builtinTools, setBuiltinTools] = useState<BuiltinTool[]>([]);
+  const [researchModes, setResearchModes] = useState<ResearchModeInfo[]>([]);
+  const [imageStyles, setImageStyles] = useState<ImageStyle[]>([]);
+
+  useEffect(() => {
+    getBuiltinTools().then(setBuiltinTools).catch(console.error);
+    getResearchModes().then(setResearchModes).catch(console.error);
+    getImageStyles().then(setImageStyles).catch(console.error);
+  }, []);
+
+  const disabledTools = settings?.disabled_tools ?? [];
+  const isToolEnabled = (name: string) => !disabledTools.some(t => t.toLowerCase() === name.toLowerCase());
+  const toggleTool = (name: string, enabled: boolean) => {
+    const rest = disabledTools.filter(t => t.toLowerCase() !== name.toLowerCase());
+    autoSave('disabled_tools', enabled ? rest : [...rest, name]);
+  };
   
   // Check if specific MCP servers are configured
   const firecrawlServer = mcpServers.find(s => 
@@ -1445,6 +1571,14 @@ function ResearchSettingsTab({ onMcpServersChanged }: { onMcpServersChanged?: ()
     }
   };
 
+  // Warn about deprecated or unknown model IDs once the model list is loaded
+  useEffect(() => {
+    if (!settings?.model) return;
+    checkModel(settings.model)
+      .then(setModelWarning)
+      .catch(() => setModelWarning(null));
+  }, [settings?.model, models]);
+
   // Anthropic API Key state
   const [newApiKey, setNewApiKey] = useState('');
   const [showApiKey, setShowApiKey] = useState(false);
@@ -1536,6 +1670,50 @@ function ResearchSettingsTab({ onMcpServersChanged }: { onMcpServersChanged?: ()
       </div>
 
       <div className="space-y-6">
+        {/* Workspace Section */}
+        <div className="p-4 bg-gray-50 dark:bg-gray-800/50 rounded-lg border border-gray-200 dark:border-gray-700">
+          <div className="flex items-center gap-2 mb-3">
+            <FolderOpen className="w-5 h-5 text-gray-600 dark:text-gray-400" />
+            <h3 className="font-medium text-gray-900 dark:text-white">Workspace</h3>
+          </div>
+          <p className="text-sm text-gray-500 dark:text-gray-400 mb-3">
+            Each workspace has its own topics, briefings and settings. API keys are shared. Switching restarts the app.
+          </p>
+          <div className="flex gap-2">
+            <select
+              value={workspaces.find((w) => w.active)?.name ?? 'default'}
+              onChange={(e) => switchWorkspace(e.target.value)}
+              className="input flex-1"
+            >
+              {workspaces.map((w) => (
+                <option key={w.name} value={w.name}>{w.name}</option>
+              ))}
+            </select>
+            <input
+              type="text"
+              value={newWorkspace}
+              onChange={(e) => setNewWorkspace(e.target.value)}
+              placeholder="New workspace"
+              className="input flex-1"
+            />
+            <button
+              onClick={async () => {
+                if (await createWorkspace(newWorkspace.trim())) {
+                  setNewWorkspace('');
+                }
+              }}
+              disabled={!newWorkspace.trim()}
+              className="btn btn-secondary flex items-center gap-1"
+            >
+              <Plus className="w-4 h-4" />
+              Create
+            </button>
+          </div>
+          {workspaceError && (
+            <p className="text-sm text-red-600 dark:text-red-400 mt-2">{workspaceError}</p>
+          )}
+        </div>
+
         {/* API Key Section */}
         <div className="p-4 bg-gray-50 dark:bg-gray-800/50 rounded-lg border border-gray-200 dark:border-gray-700">
           <div className="flex items-center gap-2 mb-3">
@@ -1543,6 +1721,28 @@ function ResearchSettingsTab({ onMcpServersChanged }: { onMcpServersChanged?: ()
             <h3 className="font-medium text-gray-900 dark:text-white">Anthropic API Key</h3>
           </div>
 
+          {profiles.length > 1 && (
+            <div className="mb-3">
+              <label className="block text-xs text-gray-500 dark:text-gray-400 mb-1">Profile</label>
+              <select
+                value={profiles.find((p) => p.active)?.name ?? 'default'}
+                onChange={async (e) => {
+                  if (await setActiveProfile(e.target.value)) {
+                    // Reload settings so later saves keep the new active_profile
+                    await Promise.all([checkApiKey(), getSettings()]);
+                  }
+                }}
+                className="input w-full"
+              >
+                {profiles.map((p) => (
+                  <option key={p.name} value={p.name}>
+                    {p.name}{p.has_key ? '' : ' (no key)'}
+                  </option>
+                ))}
+              </select>
+            </div>
+          )}
+
           {apiKeyLoading ? (
             <div className="flex items-center gap-2 text-sm text-gray-500">
               <Loader2 className="w-4 h-4 animate-spin" />
@@ -1669,6 +1869,48 @@ function ResearchSettingsTab({ onMcpServersChanged }: { onMcpServersChanged?: ()
             </div>
           </div>
 
+          {/* Style preset - combined with each card's image prompt */}
+          {settings.enable_image_generation && (
+            <div className="mb-4">
+              <div className="flex items-center gap-2 mb-2">
+                <label className="text-sm font-medium text-gray-700 dark:text-gray-300">
+                  Style
+                </label>
+                {savedIndicator === 'image_style' && (
+                  <span className="text-xs text-green-600 dark:text-green-400 flex items-center gap-1">
+                    <CheckCircle2 className="w-3 h-3" /> Saved
+                  </span>
+                )}
+              </div>
+              <select
+                value={settings.image_style ?? 'editorial'}
+                onChange={(e) => autoSave('image_style', e.target.value)}
+                className="input w-full"
+              >
+                {imageStyles.map((style) => (
+                  <option key={style.name} value={style.name}>{style.label}</option>
+                ))}
+              </select>
+              <p className="text-xs text-gray-500 dark:text-gray-400 mt-1">
+                Each card keeps the style its image was made with when regenerated.
+              </p>
+              <label className="flex items-center gap-2 mt-3 text-sm text-gray-600 dark:text-gray-400 cursor-pointer">
+                <input
+                  type="checkbox"
+                  checked={settings.enable_cover_image ?? false}
+                  onChange={(e) => autoSave('enable_cover_image', e.target.checked)}
+                  className="rounded border-gray-300 dark:border-gray-600"
+                />
+                Also generate one cover image per briefing from its title and themes (one more image per run)
+                {savedIndicator === 'enable_cover_image' && (
+                  <span className="text-xs text-green-600 dark:text-green-400 flex items-center gap-1">
+                    <CheckCircle2 className="w-3 h-3" /> Saved
+                  </span>
+                )}
+              </label>
+            </div>
+          )}
+
           {/* OpenAI API Key - only show when image generation is enabled */}
           {settings.enable_image_generation && (
             <div className="border-t border-gray-200 dark:border-gray-700 pt-4">
@@ -1843,6 +2085,29 @@ function ResearchSettingsTab({ onMcpServersChanged }: { onMcpServersChanged?: ()
                 </p>
               </div>
             </label>
+
+            {/* Other modes from the research mode registry */}
+            {researchModes
+              .filter(mode => mode.name !== 'standard' && mode.name !== 'firecrawl')
+              .map(mode => (
+                <label key={mode.name} className="flex items-start gap-3 p-3 rounded-lg border cursor-pointer transition-colors
+                  hover:bg-gray-100 dark:hover:bg-gray-700/50
+                  border-gray-200 dark:border-gray-600
+                  has-[:checked]:border-primary-500 has-[:checked]:bg-primary-50 dark:has-[:checked]:bg-primary-900/20">
+                  <input
+                    type="radio"
+                    name="research_mode"
+                    value={mode.name}
+                    checked={settings.research_mode === mode.name}
+                    onChange={() => autoSave('research_mode', mode.name)}
+                    className="mt-1 h-4 w-4 text-primary-600 focus:ring-primary-500"
+                  />
+                  <div className="flex-1">
+                    <span className="text-sm font-medium text-gray-900 dark:text-white capitalize">{mode.name.replace('-', ' ')}</span>
+                    <p className="text-xs text-gray-500 dark:text-gray-400 mt-0.5">{mode.description}</p>
+                  </div>
+                </label>
+              ))}
           </div>
           
           {(settings.research_mode ?? 'standard') === 'firecrawl' && !hasFirecrawlConfigured && (
@@ -1909,6 +2174,40 @@ function ResearchSettingsTab({ onMcpServersChanged }: { onMcpServersChanged?: ()
                   </p>
                 </div>
               </div>
+              <div className="grid grid-cols-2 gap-3 mt-4">
+                {([
+                  ['firecrawl_run_budget', 'Calls per run'],
+                  ['firecrawl_monthly_budget', 'Calls per month'],
+                ] as const).map(([key, label]) => (
+                  <div key={key}>
+                    <label className="text-sm font-medium text-gray-900 dark:text-white flex items-center gap-2 mb-1">
+                      {label}
+                      {savedIndicator === key && (
+                        <span className="text-xs text-green-600 dark:text-green-400">Saved ✓</span>
+                      )}
+                    </label>
+                    <input
+                      type="number"
+                      min="0"
+                      defaultValue={settings[key] ?? 0}
+                      onBlur={(e) => {
+                        const value = parseInt(e.target.value);
+                        if (!isNaN(value) && value >= 0) {
+                          if (value !== (settings[key] ?? 0)) {
+                            autoSave(key, value);
+                          }
+                        } else {
+                          e.target.value = (settings[key] ?? 0).toString();
+                        }
+                      }}
+                      className="input w-full"
+                    />
+                  </div>
+                ))}
+              </div>
+              <p className="text-xs text-gray-500 dark:text-gray-400 mt-1">
+                Firecrawl budget (0 = no limit). You're warned at 80% and Firecrawl tools are switched off once a budget is used up.
+              </p>
             </motion.div>
           )}
         </div>
@@ -1957,12 +2256,110 @@ function ResearchSettingsTab({ onMcpServersChanged }: { onMcpServersChanged?: ()
         </div>
         )}
 
+        {/* Built-in Tools Section */}
+        {builtinTools.length > 0 && (
+        <div className="p-4 bg-gray-50 dark:bg-gray-800/50 rounded-lg border border-gray-200 dark:border-gray-700">
+          <div className="flex items-center gap-2 mb-3">
+            <Zap className="w-5 h-5 text-gray-600 dark:text-gray-400" />
+            <h3 className="font-medium text-gray-900 dark:text-white">Built-in Tools</h3>
+            {savedIndicator === 'disabled_tools' && (
+              <motion.span
+                initial={{ opacity: 0, scale: 0.8 }}
+                animate={{ opacity: 1, scale: 1 }}
+                exit={{ opacity: 0 }}
+                className="text-xs text-green-600 dark:text-green-400 flex items-center gap-1"
+              >
+                <CheckCircle2 className="w-3 h-3" /> Saved
+              </motion.span>
+            )}
+          </div>
+          <p className="text-xs text-gray-500 dark:text-gray-400 mb-3">
+            Turned-off tools are not offered to Claude during research or chat. MCP tools can be turned off with <span className="font-mono">claudius config set disable_tool server/tool</span>.
+          </p>
+          <div className="space-y-2">
+            {builtinTools.map(tool => (
+              <label key={tool.name} className="flex items-start gap-3 cursor-pointer">
+                <input
+                  type="checkbox"
+                  checked={isToolEnabled(tool.name)}
+                  onChange={(e) => toggleTool(tool.name, e.target.checked)}
+                  className="mt-0.5 rounded border-gray-300 text-primary-600 focus:ring-primary-500"
+                />
+                <div className="flex-1 min-w-0">
+                  <span className="text-sm font-mono text-gray-700 dark:text-gray-300">{tool.name}</span>
+                  <p className="text-xs text-gray-500 dark:text-gray-400 truncate">{tool.description}</p>
+                </div>
+              </label>
+            ))}
+          </div>
+        </div>
+        )}
+
         {/* Condensed Briefing Section */}
         <div className="p-4 bg-gray-50 dark:bg-gray-800/50 rounded-lg border border-gray-200 dark:border-gray-700">
           <div className="flex items-center gap-2 mb-3">
             <Layers className="w-5 h-5 text-gray-600 dark:text-gray-400" />
             <h3 className="font-medium text-gray-900 dark:text-white">Briefing Format</h3>
           </div>
+          <div className="mb-4">
+            <div className="flex items-center gap-2 mb-2">
+              <label className="text-sm font-medium text-gray-700 dark:text-gray-300">
+                Layout
+              </label>
+              {savedIndicator === 'briefing_format' && (
+                <motion.span
+                  initial={{ opacity: 0, scale: 0.8 }}
+                  animate={{ opacity: 1, scale: 1 }}
+                  exit={{ opacity: 0 }}
+                  className="text-xs text-green-600 dark:text-green-400 flex items-center gap-1"
+                >
+                  <CheckCircle2 className="w-3 h-3" /> Saved
+                </motion.span>
+              )}
+            </div>
+            <select
+              value={settings.briefing_format ?? 'standard'}
+              onChange={(e) => autoSave('briefing_format', e.target.value)}
+              className="input w-full"
+            >
+              <option value="standard">Standard (detailed cards)</option>
+              <option value="newsletter">Newsletter (narrative sections)</option>
+              <option value="digest">Digest (terse bullets)</option>
+              <option value="executive">Executive summary (with action items)</option>
+            </select>
+            <p className="text-xs text-gray-500 dark:text-gray-400 mt-1">
+              Topics can override this with <code>claudius config set topic_format "Topic=digest"</code>; each format is synthesized separately.
+            </p>
+          </div>
+          <div className="mb-4">
+            <div className="flex items-center gap-2 mb-2">
+              <label className="text-sm font-medium text-gray-700 dark:text-gray-300">
+                Research Window
+              </label>
+              {savedIndicator === 'recency_window' && (
+                <motion.span
+                  initial={{ opacity: 0, scale: 0.8 }}
+                  animate={{ opacity: 1, scale: 1 }}
+                  exit={{ opacity: 0 }}
+                  className="text-xs text-green-600 dark:text-green-400 flex items-center gap-1"
+                >
+                  <CheckCircle2 className="w-3 h-3" /> Saved
+                </motion.span>
+              )}
+            </div>
+            <select
+              value={settings.recency_window ?? '24h'}
+              onChange={(e) => autoSave('recency_window', e.target.value)}
+              className="input w-full"
+            >
+              <option value="24h">Last 24 hours (daily research)</option>
+              <option value="72h">Last 72 hours</option>
+              <option value="1w">Past week (weekly research)</option>
+            </select>
+            <p className="text-xs text-gray-500 dark:text-gray-400 mt-1">
+              How far back research looks for news. Topics can override this with <code>claudius config set topic_recency_window "Topic=1w"</code>.
+            </p>
+          </div>
           <div className="flex items-start gap-3">
             <label className="relative inline-flex items-center cursor-pointer mt-0.5">
               <input
@@ -1990,13 +2387,44 @@ function ResearchSettingsTab({ onMcpServersChanged }: { onMcpServersChanged?: ()
                 )}
               </div>
               <p className="text-xs text-gray-500 dark:text-gray-400 mt-1">
-                Combine all topics into one comprehensive daily briefing instead of separate cards.
+                Combine all topics into one comprehensive daily briefing instead of separate cards (Standard layout only).
               </p>
               <p className="text-xs text-gray-500 dark:text-gray-400 mt-1">
                 Creates a single in-depth story covering all your research topics with deeper analysis.
               </p>
             </div>
           </div>
+          <div className="flex items-start gap-3">
+            <label className="relative inline-flex items-center cursor-pointer mt-0.5">
+              <input
+                type="checkbox"
+                checked={settings.merge_same_day_runs ?? false}
+                onChange={(e) => autoSave('merge_same_day_runs', e.target.checked)}
+                className="sr-only peer"
+              />
+              <div className="w-11 h-6 bg-gray-200 peer-focus:outline-none peer-focus:ring-4 peer-focus:ring-primary-300 dark:peer-focus:ring-primary-800 rounded-full peer dark:bg-gray-700 peer-checked:after:translate-x-full peer-checked:after:border-white after:content-[''] after:absolute after:top-[2px] after:left-[2px] after:bg-white after:border-gray-300 after:border after:rounded-full after:h-5 after:w-5 after:transition-all dark:border-gray-600 peer-checked:bg-primary-600"></div>
+            </label>
+            <div className="flex-1">
+              <div className="flex items-center gap-2">
+                <span className="text-sm font-medium text-gray-700 dark:text-gray-300">
+                  One Briefing per Day
+                </span>
+                {savedIndicator === 'merge_same_day_runs' && (
+                  <motion.span
+                    initial={{ opacity: 0, scale: 0.8 }}
+                    animate={{ opacity: 1, scale: 1 }}
+                    exit={{ opacity: 0 }}
+                    className="text-xs text-green-600 dark:text-green-400 flex items-center gap-1"
+                  >
+                    <CheckCircle2 className="w-3 h-3" /> Saved
+                  </motion.span>
+                )}
+              </div>
+              <p className="text-xs text-gray-500 dark:text-gray-400 mt-1">
+                Append later runs of the day to the day's briefing instead of saving a new one. Cards it already has are left out.
+              </p>
+            </div>
+          </div>
         </div>
 
         {/* Deduplication Section */}
@@ -2080,7 +2508,78 @@ function ResearchSettingsTab({ onMcpServersChanged }: { onMcpServersChanged?: ()
                 How similar titles must be to be considered duplicates. Higher = stricter matching.
               </p>
             </div>
+
+            {/* Past cards context budget */}
+            <div>
+              <div className="flex items-center justify-between mb-2">
+                <label className="text-sm font-medium text-gray-700 dark:text-gray-300">
+                  Recent Cards in Prompt
+                </label>
+                {savedIndicator === 'dedup_context_tokens' && (
+                  <motion.span
+                    initial={{ opacity: 0, scale: 0.8 }}
+                    animate={{ opacity: 1, scale: 1 }}
+                    exit={{ opacity: 0 }}
+                    className="text-xs text-green-600 dark:text-green-400 flex items-center gap-1"
+                  >
+                    <CheckCircle2 className="w-3 h-3" /> Saved
+                  </motion.span>
+                )}
+              </div>
+              <div className="flex items-center gap-3">
+                <input
+                  type="range"
+                  min="0"
+                  max="5000"
+                  step="250"
+                  value={settings.dedup_context_tokens ?? 1000}
+                  onChange={(e) => autoSave('dedup_context_tokens', parseInt(e.target.value))}
+                  disabled={(settings.dedup_days ?? 14) === 0}
+                  className={`flex-1 h-2 bg-gray-200 dark:bg-gray-700 rounded-lg appearance-none cursor-pointer accent-primary-600 ${(settings.dedup_days ?? 14) === 0 ? 'opacity-50' : ''}`}
+                />
+                <span className={`text-sm font-medium text-gray-700 dark:text-gray-300 w-24 text-right ${(settings.dedup_days ?? 14) === 0 ? 'opacity-50' : ''}`}>
+                  {(settings.dedup_context_tokens ?? 1000) === 0 ? 'None' : `${settings.dedup_context_tokens ?? 1000} tokens`}
+                </span>
+              </div>
+              <p className={`text-xs text-gray-500 dark:text-gray-400 mt-1 ${(settings.dedup_days ?? 14) === 0 ? 'opacity-50' : ''}`}>
+                How much of the lookback period Claude sees while writing cards, recent cards of the same topics first. Duplicates are still filtered either way.
+              </p>
+            </div>
+          </div>
+        </div>
+
+        <div>
+          <div className="flex items-center gap-2 mb-2">
+            <label className="block text-sm font-medium text-gray-700 dark:text-gray-300">
+              About You
+            </label>
+            {savedIndicator === 'about_me' && (
+              <motion.span
+                initial={{ opacity: 0, scale: 0.8 }}
+                animate={{ opacity: 1, scale: 1 }}
+                exit={{ opacity: 0 }}
+                className="text-xs text-green-600 dark:text-green-400 flex items-center gap-1"
+              >
+                <CheckCircle2 className="w-3 h-3" /> Saved
+              </motion.span>
+            )}
           </div>
+          <textarea
+            value={aboutMe}
+            onChange={(e) => setAboutMe(e.target.value)}
+            onBlur={() => {
+              if (aboutMe.trim() !== (settings.about_me ?? '')) {
+                autoSave('about_me', aboutMe.trim());
+              }
+            }}
+            placeholder="e.g., Senior Rust engineer at a fintech; follow payments infrastructure; skip beginner explanations"
+            rows={3}
+            maxLength={2000}
+            className="input w-full resize-none"
+          />
+          <p className="text-xs text-gray-500 dark:text-gray-400 mt-1">
+            Your role, industry, expertise and preferences. Used to judge relevance and pitch briefings at your level.
+          </p>
         </div>
 
         <div>
@@ -2104,10 +2603,30 @@ function ResearchSettingsTab({ onMcpServersChanged }: { onMcpServersChanged?: ()
             onChange={(e) => autoSave('model', e.target.value)}
             className="input w-full"
           >
-            <option value="claude-haiku-4-5-20251001">Claude Haiku 4.5 (fastest, cheapest)</option>
-            <option value="claude-sonnet-4-5-20250929">Claude Sonnet 4.5 (balanced)</option>
-            <option value="claude-opus-4-5-20251101">Claude Opus 4.5 (most capable)</option>
+            {models.length > 0 ? (
+              <>
+                {!models.some(m => m.id === settings.model) && (
+                  <option value={settings.model}>{settings.model}</option>
+                )}
+                {models.map(m => (
+                  <option key={m.id} value={m.id}>
+                    {m.display_name}{m.deprecated ? ' (deprecated)' : ''}
+                  </option>
+                ))}
+              </>
+            ) : (
+              <>
+                <option value="claude-haiku-4-5-20251001">Claude Haiku 4.5 (fastest, cheapest)</option>
+                <option value="claude-sonnet-4-5-20250929">Claude Sonnet 4.5 (balanced)</option>
+                <option value="claude-opus-4-5-20251101">Claude Opus 4.5 (most capable)</option>
+              </>
+            )}
           </select>
+          {modelWarning && (
+            <p className="text-xs text-amber-600 dark:text-amber-400 mt-1 flex items-center gap-1">
+              <AlertTriangle className="w-3 h-3" /> {modelWarning}
+            </p>
+          )}
         </div>
 
         <div>
@@ -2135,6 +2654,33 @@ function ResearchSettingsTab({ onMcpServersChanged }: { onMcpServersChanged?: ()
             <option value="medium">Medium (balanced)</option>
             <option value="deep">Deep (thorough, slower)</option>
           </select>
+          <div className="flex items-center gap-3 mt-3">
+            <label className="relative inline-flex items-center cursor-pointer">
+              <input
+                type="checkbox"
+                checked={settings.enable_thinking ?? false}
+                onChange={(e) => autoSave('enable_thinking', e.target.checked)}
+                className="sr-only peer"
+              />
+              <div className="w-11 h-6 bg-gray-200 peer-focus:outline-none peer-focus:ring-4 peer-focus:ring-primary-300 dark:peer-focus:ring-primary-800 rounded-full peer dark:bg-gray-700 peer-checked:after:translate-x-full peer-checked:after:border-white after:content-[''] after:absolute after:top-[2px] after:left-[2px] after:bg-white after:border-gray-300 after:border after:rounded-full after:h-5 after:w-5 after:transition-all dark:border-gray-600 peer-checked:bg-primary-600"></div>
+            </label>
+            <span className="text-sm font-medium text-gray-700 dark:text-gray-300">
+              Extended thinking
+            </span>
+            {savedIndicator === 'enable_thinking' && (
+              <motion.span
+                initial={{ opacity: 0, scale: 0.8 }}
+                animate={{ opacity: 1, scale: 1 }}
+                exit={{ opacity: 0 }}
+                className="text-xs text-green-600 dark:text-green-400 flex items-center gap-1"
+              >
+                <CheckCircle2 className="w-3 h-3" /> Saved
+              </motion.span>
+            )}
+          </div>
+          <p className="text-xs text-gray-500 dark:text-gray-400 mt-1">
+            Claude reasons before each research step (more tokens; deeper research gets a larger budget). Its reasoning is shown during research and kept in the research log.
+          </p>
         </div>
 
         <div>
@@ -2235,6 +2781,324 @@ function ResearchSettingsTab({ onMcpServersChanged }: { onMcpServersChanged?: ()
           )}
         </div>
 
+        {/* Per-event notification preferences */}
+        <div className="space-y-2">
+          <div className="flex items-center gap-2">
+            <label className="text-sm font-medium text-gray-700 dark:text-gray-300">
+              Notify Me About
+            </label>
+            {savedIndicator === 'notification_events' && (
+              <motion.span
+                initial={{ opacity: 0, scale: 0.8 }}
+                animate={{ opacity: 1, scale: 1 }}
+                exit={{ opacity: 0 }}
+                className="text-xs text-green-600 dark:text-green-400 flex items-center gap-1"
+              >
+                <CheckCircle2 className="w-3 h-3" /> Saved
+              </motion.span>
+            )}
+          </div>
+          {NOTIFICATION_EVENTS.map(({ event, label }) => {
+            const preference = settings.notification_events?.[event];
+            const mode = preference?.mode ?? (event === 'research_cancelled' ? 'off' : 'notify');
+            const update = (next: EventPreference) =>
+              autoSave('notification_events', { ...settings.notification_events, [event]: next });
+            return (
+              <div key={event} className="flex items-center gap-2">
+                <span className={`w-48 text-sm text-gray-700 dark:text-gray-300 ${!settings.enable_notifications ? 'opacity-50' : ''}`}>
+                  {label}
+                </span>
+                <select
+                  value={mode}
+                  onChange={(e) => update({ mode: e.target.value as EventPreference['mode'], sound: e.target.value === 'notify' ? preference?.sound : undefined })}
+                  disabled={!settings.enable_notifications}
+                  className="input"
+                >
+                  <option value="notify">Notify</option>
+                  <option value="silent">Silent</option>
+                  <option value="off">Off</option>
+                </select>
+                <input
+                  type="text"
+                  defaultValue={preference?.sound ?? ''}
+                  placeholder="Sound (default)"
+                  onBlur={(e) => {
+                    const sound = e.target.value.trim() || undefined;
+                    if (sound !== preference?.sound) update({ mode, sound });
+                  }}
+                  disabled={!settings.enable_notifications || mode !== 'notify'}
+                  className="input w-40"
+                />
+              </div>
+            );
+          })}
+          <p className="text-xs text-gray-500 dark:text-gray-400">
+            Sound names are system sounds, e.g. Glass or Basso on macOS. Leave empty to follow Notification Sound.
+          </p>
+        </div>
+
+        {/* Quiet hours: notifications are held and delivered when they end */}
+        <div className="space-y-3">
+          <div className="flex items-center gap-2">
+            <label className="text-sm font-medium text-gray-700 dark:text-gray-300">
+              Quiet Hours
+            </label>
+            {(savedIndicator === 'quiet_hours_start' || savedIndicator === 'quiet_hours_end') && (
+              <motion.span
+                initial={{ opacity: 0, scale: 0.8 }}
+                animate={{ opacity: 1, scale: 1 }}
+                exit={{ opacity: 0 }}
+                className="text-xs text-green-600 dark:text-green-400 flex items-center gap-1"
+              >
+                <CheckCircle2 className="w-3 h-3" /> Saved
+              </motion.span>
+            )}
+          </div>
+          <div className="flex items-center gap-2">
+            <input
+              type="time"
+              value={settings.quiet_hours_start ?? ''}
+              onChange={(e) => autoSave('quiet_hours_start', e.target.value || null)}
+              disabled={!settings.enable_notifications}
+              className="input"
+            />
+            <span className="text-sm text-gray-500 dark:text-gray-400">to</span>
+            <input
+              type="time"
+              value={settings.quiet_hours_end ?? ''}
+              onChange={(e) => autoSave('quiet_hours_end', e.target.value || null)}
+              disabled={!settings.enable_notifications}
+              className="input"
+            />
+          </div>
+          <p className="text-xs text-gray-500 dark:text-gray-400">
+            Notifications during quiet hours are delivered when they end. Leave empty to turn off.
+          </p>
+          <div className="flex items-center gap-3">
+            <label className="relative inline-flex items-center cursor-pointer">
+              <input
+                type="checkbox"
+                checked={settings.quiet_weekends ?? false}
+                onChange={(e) => autoSave('quiet_weekends', e.target.checked)}
+                disabled={!settings.enable_notifications}
+                className="sr-only peer"
+              />
+              <div className={`w-11 h-6 bg-gray-200 peer-focus:outline-none peer-focus:ring-4 peer-focus:ring-primary-300 dark:peer-focus:ring-primary-800 rounded-full peer dark:bg-gray-700 peer-checked:after:translate-x-full peer-checked:after:border-white after:content-[''] after:absolute after:top-[2px] after:left-[2px] after:bg-white after:border-gray-300 after:border after:rounded-full after:h-5 after:w-5 after:transition-all dark:border-gray-600 peer-checked:bg-primary-600 ${!settings.enable_notifications ? 'opacity-50' : ''}`}></div>
+            </label>
+            <span className={`text-sm font-medium text-gray-700 dark:text-gray-300 ${!settings.enable_notifications ? 'opacity-50' : ''}`}>
+              Quiet on Weekends
+            </span>
+            {savedIndicator === 'quiet_weekends' && (
+              <motion.span
+                initial={{ opacity: 0, scale: 0.8 }}
+                animate={{ opacity: 1, scale: 1 }}
+                exit={{ opacity: 0 }}
+                className="text-xs text-green-600 dark:text-green-400 flex items-center gap-1"
+              >
+                <CheckCircle2 className="w-3 h-3" /> Saved
+              </motion.span>
+            )}
+          </div>
+          <div className="flex items-center gap-3">
+            <label className="relative inline-flex items-center cursor-pointer">
+              <input
+                type="checkbox"
+                checked={settings.respect_system_dnd ?? false}
+                onChange={(e) => autoSave('respect_system_dnd', e.target.checked)}
+                disabled={!settings.enable_notifications}
+                className="sr-only peer"
+              />
+              <div className={`w-11 h-6 bg-gray-200 peer-focus:outline-none peer-focus:ring-4 peer-focus:ring-primary-300 dark:peer-focus:ring-primary-800 rounded-full peer dark:bg-gray-700 peer-checked:after:translate-x-full peer-checked:after:border-white after:content-[''] after:absolute after:top-[2px] after:left-[2px] after:bg-white after:border-gray-300 after:border after:rounded-full after:h-5 after:w-5 after:transition-all dark:border-gray-600 peer-checked:bg-primary-600 ${!settings.enable_notifications ? 'opacity-50' : ''}`}></div>
+            </label>
+            <span className={`text-sm font-medium text-gray-700 dark:text-gray-300 ${!settings.enable_notifications ? 'opacity-50' : ''}`}>
+              Respect Do Not Disturb / Focus
+            </span>
+            {savedIndicator === 'respect_system_dnd' && (
+              <motion.span
+                initial={{ opacity: 0, scale: 0.8 }}
+                animate={{ opacity: 1, scale: 1 }}
+                exit={{ opacity: 0 }}
+                className="text-xs text-green-600 dark:text-green-400 flex items-center gap-1"
+              >
+                <CheckCircle2 className="w-3 h-3" /> Saved
+              </motion.span>
+            )}
+          </div>
+        </div>
+
+        {/* Show/hide shortcut, registered system-wide */}
+        <div className="space-y-2">
+          <div className="flex items-center gap-2">
+            <label className="text-sm font-medium text-gray-700 dark:text-gray-300">
+              Global Shortcut
+            </label>
+            {savedIndicator === 'global_shortcut' && (
+              <motion.span
+                initial={{ opacity: 0, scale: 0.8 }}
+                animate={{ opacity: 1, scale: 1 }}
+                exit={{ opacity: 0 }}
+                className="text-xs text-green-600 dark:text-green-400 flex items-center gap-1"
+              >
+                <CheckCircle2 className="w-3 h-3" /> Saved
+              </motion.span>
+            )}
+          </div>
+          <input
+            type="text"
+            defaultValue={settings.global_shortcut ?? 'CmdOrCtrl+Shift+B'}
+            placeholder="CmdOrCtrl+Shift+B"
+            onBlur={async (e) => {
+              const shortcut = e.target.value.trim() || 'off';
+              if (shortcut === settings.global_shortcut) return;
+              try {
+                await updateGlobalShortcut(shortcut);
+                setShortcutError(null);
+                setSavedIndicator('global_shortcut');
+                setTimeout(() => setSavedIndicator(null), 1500);
+              } catch (err) {
+                setShortcutError(typeof err === 'string' ? err : 'Failed to change the shortcut');
+              }
+            }}
+            className="input w-64"
+          />
+          {shortcutError && (
+            <p className="text-xs text-red-600 dark:text-red-400">{shortcutError}</p>
+          )}
+          <p className="text-xs text-gray-500 dark:text-gray-400">
+            Shows or hides Claudius from any app, e.g. Ctrl+Alt+B. Leave empty or enter "off" to turn it off.
+          </p>
+        </div>
+
+        {/* Quick research on the clipboard, registered system-wide */}
+        <div className="space-y-2">
+          <div className="flex items-center gap-2">
+            <label className="text-sm font-medium text-gray-700 dark:text-gray-300">
+              Quick Research Shortcut
+            </label>
+            {(savedIndicator === 'quick_research_shortcut' || savedIndicator === 'quick_research_mode') && (
+              <motion.span
+                initial={{ opacity: 0, scale: 0.8 }}
+                animate={{ opacity: 1, scale: 1 }}
+                exit={{ opacity: 0 }}
+                className="text-xs text-green-600 dark:text-green-400 flex items-center gap-1"
+              >
+                <CheckCircle2 className="w-3 h-3" /> Saved
+              </motion.span>
+            )}
+          </div>
+          <div className="flex gap-2">
+            <input
+              type="text"
+              defaultValue={settings.quick_research_shortcut ?? 'CmdOrCtrl+Alt+Shift+R'}
+              placeholder="CmdOrCtrl+Alt+Shift+R"
+              onBlur={(e) => {
+                const shortcut = e.target.value.trim() || 'off';
+                if (shortcut === settings.quick_research_shortcut) return;
+                autoSave('quick_research_shortcut', shortcut);
+              }}
+              className="input w-64"
+            />
+            <select
+              value={settings.quick_research_mode ?? 'research'}
+              onChange={(e) => autoSave('quick_research_mode', e.target.value)}
+              className="input w-48"
+            >
+              <option value="research">Research it</option>
+              <option value="ask">Answer from archive</option>
+            </select>
+          </div>
+          <p className="text-xs text-gray-500 dark:text-gray-400">
+            Copy text anywhere, then press the shortcut to research it as an ad-hoc question (or answer it from past briefings) in the menu bar popover.
+          </p>
+        </div>
+
+        {/* Release channel and manual update check */}
+        <div className="space-y-2">
+          <div className="flex items-center gap-2">
+            <label className="text-sm font-medium text-gray-700 dark:text-gray-300">
+              Updates
+            </label>
+            {savedIndicator === 'release_channel' && (
+              <motion.span
+                initial={{ opacity: 0, scale: 0.8 }}
+                animate={{ opacity: 1, scale: 1 }}
+                exit={{ opacity: 0 }}
+                className="text-xs text-green-600 dark:text-green-400 flex items-center gap-1"
+              >
+                <CheckCircle2 className="w-3 h-3" /> Saved
+              </motion.span>
+            )}
+          </div>
+          <div className="flex items-center gap-3">
+            <select
+              value={settings.release_channel ?? 'stable'}
+              onChange={(e) => {
+                setUpdateChecked(false);
+                autoSave('release_channel', e.target.value);
+              }}
+              className="input w-48"
+            >
+              <option value="stable">Stable</option>
+              <option value="beta">Beta (pre-releases)</option>
+            </select>
+            <button
+              onClick={async () => {
+                try {
+                  await updater.checkForUpdate();
+                } catch {
+                  // Shown from updater.error
+                }
+                setUpdateChecked(true);
+              }}
+              disabled={updater.isChecking}
+              className="btn btn-secondary flex items-center gap-2"
+            >
+              {updater.isChecking ? (
+                <Loader2 className="w-4 h-4 animate-spin" />
+              ) : (
+                <RefreshCw className="w-4 h-4" />
+              )}
+              Check for Updates
+            </button>
+          </div>
+          {updateChecked && updater.error && (
+            <p className="text-xs text-red-600 dark:text-red-400">{updater.error}</p>
+          )}
+          {updateChecked && !updater.error && !updater.updateAvailable && (
+            <p className="text-xs text-green-600 dark:text-green-400">Claudius is up to date.</p>
+          )}
+          {updateChecked && updater.changelog.length > 0 && (
+            <div className="max-h-64 overflow-y-auto space-y-3 rounded-lg border border-gray-200 dark:border-gray-700 p-3">
+              {updater.changelog.map((release) => (
+                <div key={release.tag}>
+                  <a
+                    href={release.url}
+                    target="_blank"
+                    rel="noopener noreferrer"
+                    className="text-sm font-medium text-gray-900 dark:text-white hover:underline"
+                  >
+                    v{release.version}
+                    {release.prerelease && (
+                      <span className="ml-2 text-xs text-amber-600 dark:text-amber-400">pre-release</span>
+                    )}
+                  </a>
+                  {release.published_at && (
+                    <span className="ml-2 text-xs text-gray-500 dark:text-gray-400">
+                      {new Date(release.published_at).toLocaleDateString()}
+                    </span>
+                  )}
+                  <p className="text-xs text-gray-600 dark:text-gray-400 whitespace-pre-line mt-1">
+                    {release.notes ?? 'No release notes.'}
+                  </p>
+                </div>
+              ))}
+            </div>
+          )}
+          <p className="text-xs text-gray-500 dark:text-gray-400">
+            Updates download in the background. Beta also installs pre-releases.
+          </p>
+        </div>
+
         {/* Storage Section */}
         <StorageSection
           retentionDays={settings.retention_days}
@@ -13,15 +13,55 @@ export interface Briefing {
   metadata?: Record<string, unknown>;
   // Image generation fields (DALL-E)
   image_prompt?: string;
-  image_style?: string;  // Legacy field (not used with DALL-E)
+  image_style?: string;  // Style preset the header image was generated with
   image_path?: string;
+  source_checks?: SourceCheck[];  // Source URL validation results
+  source_warning?: string;  // Why the card failed source checks (no or stale sources)
+  action_items?: string[];  // Follow-up actions (executive summary format)
+  actions?: CardAction[];  // Structured actions the user can run
+}
+
+// Structured follow-up action attached to a card (see card_actions.rs)
+export type CardAction =
+  | { type: 'remind_me'; message: string; in_hours?: number }
+  | { type: 'open_url'; url: string; label?: string }
+  | { type: 'create_github_issue'; repo: string; title: string; body?: string }
+  | {
+      type: 'add_calendar_event';
+      title: string;
+      date: string;  // YYYY-MM-DD
+      time?: string;  // HH:MM, all-day when absent
+      duration_minutes?: number;
+      description?: string;
+    };
+
+// Result of run_card_action
+export type ActionOutcome =
+  | { type: 'reminder_scheduled'; id: number; due_at: string }
+  | { type: 'url_opened'; url: string }
+  | { type: 'issue_created'; url: string }
+  | { type: 'calendar_event_created'; path: string };
+
+export interface SourceCheck {
+  url: string;
+  valid: boolean;
+  status_code?: number;
+  final_url?: string;  // Set when the URL redirected elsewhere
+  error?: string;
+  published?: string;  // Publication date from the page metadata, when known
 }
 
 export interface Topic {
   id: string;
   name: string;
   description?: string;
+  preferred_sources?: string[];
   enabled: boolean;
+  snoozed_until?: string | null;  // RFC 3339; skipped by research until then
+  skip_next_run?: boolean;
+  dedup_days?: number | null;       // Overrides the global dedup window
+  dedup_threshold?: number | null;  // Overrides the global dedup threshold
+  mcp_servers?: string[];           // "server" or "server/tool"; bound servers are only offered for their topics
   created_at: string;
   updated_at: string;
 }
@@ -34,20 +74,152 @@ export interface MCPServer {
   last_used?: string;
 }
 
+// A server preset from the MCP catalog; `{key}` in env values is the API key
+export interface McpCatalogEntry {
+  id: string;
+  name: string;
+  description: string;
+  pricing: string;
+  recommended: boolean;
+  command: string;
+  args: string[];
+  env: Record<string, string>;
+  key_label?: string;
+  key_url?: string;
+}
+
+// A value the user must provide to install a catalog preset
+export interface McpRequiredField {
+  env_var: string;
+  label: string;
+  url?: string;
+  secret: boolean;
+}
+
+export interface McpCatalog {
+  version: number;
+  servers: McpCatalogEntry[];
+}
+
+// A research mode that can be picked in settings
+export interface ResearchModeInfo {
+  name: string;
+  description: string;
+}
+
+// A header image style preset (from get_image_styles)
+export interface ImageStyle {
+  name: string;
+  label: string;
+  prompt: string;  // Style description added to each card's image prompt
+}
+
+// A built-in tool and whether the disabled_tools setting leaves it on
+export interface BuiltinTool {
+  name: string;
+  description: string;
+  enabled: boolean;
+}
+
+// Config files watched by the desktop app
+export type ConfigFile = 'settings' | 'mcp_servers';
+
+// Event: config files edited outside the app (`config:changed`)
+export interface ConfigChangedEvent {
+  files: ConfigFile[];
+}
+
+export type NotificationEvent =
+  | 'research_completed'
+  | 'research_failed'
+  | 'research_cancelled'
+  | 'actionable_error'
+  | 'watchlist_hit'
+  | 'weekly_digest_ready';
+
+// How an event is shown: with a sound, silently or not at all
+export interface EventPreference {
+  mode: 'notify' | 'silent' | 'off';
+  sound?: string;  // System sound name or "default"; unset follows notification_sound
+}
+
 export interface ResearchSettings {
   model: string;
   research_depth: 'shallow' | 'medium' | 'deep';
   max_sources_per_topic: number;
   enable_notifications: boolean;
   notification_sound: boolean;
+  notification_events?: Partial<Record<NotificationEvent, EventPreference>>;  // Per-event overrides; missing events use their defaults
   enable_web_search?: boolean;
+  disabled_tools?: string[];  // Tools left out of research and chat ("fetch_webpage", "web_search" or "server/tool" for MCP)
   retention_days: number | null;  // null = never delete
   condense_briefings?: boolean;  // Combine all topics into one comprehensive card
+  merge_same_day_runs?: boolean;  // Append later runs of the day to the day's briefing
   dedup_days?: number;  // Days to look back for duplicates (default: 14)
   dedup_threshold?: number;  // Similarity threshold 0-1 (default: 0.75)
+  dedup_context_tokens?: number;  // Token budget for recent cards in the synthesis prompt (default: 1000, 0 = none)
   enable_image_generation?: boolean;  // Generate header images using DALL-E
-  research_mode?: 'standard' | 'firecrawl';  // Research mode - standard uses Brave/Perplexity, firecrawl uses Firecrawl for deep extraction
+  image_style?: string;  // Image style preset name (from get_image_styles)
+  enable_cover_image?: boolean;  // Also generate one hero image per briefing
+  research_mode?: string;  // standard (Brave/Perplexity), firecrawl (deep extraction), academic, github-only or local-only
   rate_limit_firecrawl_agent?: boolean;  // Limit firecrawl_agent to 5 calls/day (free tier)
+  firecrawl_run_budget?: number;  // Max Firecrawl tool calls per research run (0 = no limit)
+  firecrawl_monthly_budget?: number;  // Max Firecrawl tool calls per calendar month (0 = no limit)
+  archive_sources?: boolean;  // Save a text snapshot of each source in the sources table
+  source_recency_days?: number;  // A card's newest dated source must be this recent (0 = any age, default 30)
+  repair_weak_cards?: boolean;  // Send cards with no or stale sources back to Claude once before saving (default true)
+  respect_robots_txt?: boolean;  // Skip pages a site's robots.txt disallows (default true)
+  crawl_delay_ms?: number;  // Minimum gap between page fetches to one domain (default 1000)
+  fetch_contact?: string | null;  // Email or URL added to the User-Agent so site owners can reach you
+  tts_engine?: 'openai' | 'local';  // Narration engine for briefing audio
+  tts_voice?: string;  // OpenAI TTS voice for narration
+  obsidian_vault_path?: string | null;  // Obsidian vault to export briefings into after each run
+  obsidian_folder?: string;  // Folder inside the vault for briefing notes
+  enable_notion_sync?: boolean;  // Sync each new briefing to Notion after research
+  notion_database_id?: string | null;  // Notion database that receives one page per card
+  enable_atom_feed?: boolean;  // Rewrite an Atom feed of recent briefings after each run
+  atom_feed_path?: string | null;  // Where the feed is written (default ~/.claudius/atom.xml)
+  enable_schedule_calendar?: boolean;  // Rewrite an .ics of upcoming runs and follow-up events
+  schedule_calendar_path?: string | null;  // Where the calendar is written (default ~/.claudius/claudius.ics)
+  schedule_mode?: 'cron' | 'adaptive';  // Daemon runs on schedule_cron, or finish before your usual start of day (learned from activity)
+  catch_up_missed_runs?: boolean;  // Daemon makes up a run missed while the machine was asleep or off (default: true)
+  read_later_service?: 'pocket' | 'readwise' | 'instapaper' | null;  // Forward saved cards to this service
+  log_retention_days?: number;  // Days to keep research JSONL log files
+  debug_logging?: boolean;  // Capture debug-level detail in the research log
+  topic_timeout_minutes?: number;  // Wall-clock limit per topic before it is skipped (default 10)
+  mcp_result_max_chars?: number;  // Cap on MCP tool result size passed to Claude (default 20000)
+  mcp_tool_result_limits?: Record<string, number>;  // Per-tool overrides of mcp_result_max_chars
+  min_relevance?: 'low' | 'medium' | 'high';  // Lowest card relevance saved to a briefing (default: low)
+  keep_discarded_cards?: boolean;  // Keep filtered cards for review (default: true)
+  active_profile?: string;  // Credential profile whose Anthropic key is used (default: "default")
+  api_profiles?: string[];  // Named credential profiles besides "default"
+  briefing_format?: 'standard' | 'newsletter' | 'digest' | 'executive';  // Synthesis layout (default: standard)
+  topic_formats?: Record<string, string>;  // Per-topic briefing format overrides (keyed by topic name)
+  recency_window?: '24h' | '72h' | '1w';  // How far back research looks for news (default: 24h)
+  topic_recency_windows?: Record<string, string>;  // Per-topic research window overrides (keyed by topic name)
+  about_me?: string;  // Freeform description of the user (role, expertise, preferences) for prompts
+  topic_discovery_days?: number;  // Days between automatic topic suggestion runs (0 = off, default 7)
+  notification_snooze_hours?: number;  // Delay before a snoozed notification is shown again (default 2)
+  quiet_hours_start?: string | null;  // "HH:MM" local; notifications are held until quiet_hours_end
+  quiet_hours_end?: string | null;  // "HH:MM" local; may be earlier than the start (overnight)
+  quiet_weekends?: boolean;  // Hold notifications on Saturday and Sunday
+  respect_system_dnd?: boolean;  // Hold notifications while the OS is in Do Not Disturb / Focus
+  enable_thinking?: boolean;  // Let Claude reason (extended thinking) before each research step
+  thinking_budgets?: Record<string, number>;  // Per-depth thinking token budgets (keyed by research_depth)
+  meta_briefings?: 'off' | 'weekly' | 'monthly' | 'both';  // Meta-briefings generated by `claudius daemon` (default off)
+  batch_scheduled_research?: boolean;  // Run `claudius daemon` research through the Batch API (half price, slower)
+  batch_deadline_minutes?: number;  // Research topics interactively if the batch hasn't finished them by then (default 120)
+  proxy_url?: string;  // HTTP(S) or SOCKS5 proxy for all requests; the password is the PROXY_PASSWORD secret
+  proxy_username?: string;
+  ca_cert_path?: string;  // PEM file with extra root certificates
+  api_base_url?: string;  // Anthropic API base URL override (LiteLLM, Cloudflare AI Gateway, compatible providers)
+  api_headers?: Record<string, string>;  // Extra headers sent with every Anthropic API request
+  api_user_id?: string;  // Opaque ID sent as metadata.user_id with every Messages request
+  global_shortcut?: string;  // Shows/hides the main window, e.g. "CmdOrCtrl+Shift+B" (default), or "off"
+  quick_research_shortcut?: string;  // Researches the clipboard, e.g. "CmdOrCtrl+Alt+Shift+R" (default), or "off"
+  quick_research_mode?: 'research' | 'ask';  // Ad-hoc research run or an answer from the briefing archive
+  release_channel?: string;  // Updates to follow: "stable" (default) or "beta" (includes pre-releases)
+  config_version?: number;  // Layout version of the config files (set by the backend)
 }
 
 export interface UserFeedback {
@@ -71,6 +243,15 @@ export interface BriefingFilters {
   search_query?: string;
 }
 
+// Server-side filters for get_briefings (db::BriefingFilter)
+export interface BriefingListFilter {
+  from?: string;  // First day included (YYYY-MM-DD)
+  to?: string;  // Last day included (YYYY-MM-DD)
+  topic?: string;  // Only briefings with a card on this topic
+  min_relevance?: 'high' | 'medium' | 'low';  // Only briefings with a card at least this relevant
+  model?: string;  // Only briefings written by this model
+}
+
 export interface ChatMessage {
   id: number;
   briefing_id: number;
@@ -79,6 +260,36 @@ export interface ChatMessage {
   content: string;
   tokens_used?: number;
   created_at: string;
+  tools_used?: string[];  // Tools Claude called while writing this reply
+}
+
+// "Ask my archive": a past card cited in an answer
+export interface ArchiveCitation {
+  briefing_id: number;
+  card_index: number;
+  title: string;
+  date: string;
+}
+
+export interface ArchiveTurn {
+  role: 'user' | 'assistant';
+  content: string;
+}
+
+export interface ArchiveAnswer {
+  answer: string;  // Cites cards as [briefing 42, card 3]
+  citations: ArchiveCitation[];
+  tokens_used: number;
+}
+
+// Weekly or monthly meta-briefing (from generate_meta_briefing)
+export interface MetaBriefing {
+  briefing_id: number;
+  title: string;
+  period: 'weekly' | 'monthly';
+  source_briefings: number;  // Daily briefings it was built from
+  cards: BriefingCardData[];  // Sources link back to daily cards (claudius://briefing/...)
+  tokens_used: number;
 }
 
 export interface CardWithChat {
@@ -93,6 +304,28 @@ export interface Bookmark {
   created_at: string;
 }
 
+export interface ReadLaterItem {
+  id: number;
+  briefing_id: number;
+  card_index: number;
+  position: number;
+  completed: boolean;
+  completed_at?: string;
+  forwarded_to?: string;  // External service the card was sent to
+  created_at: string;
+}
+
+// Card dropped at save time for falling below min_relevance
+export interface DiscardedCard {
+  id: number;
+  briefing_id: number;
+  title: string;
+  topic?: string;
+  relevance: string;
+  card: string; // JSON string of BriefingCardData
+  created_at: string;
+}
+
 // Backend returns briefings with cards as JSON string
 export interface BackendBriefing {
   id: number;
@@ -102,6 +335,22 @@ export interface BackendBriefing {
   research_time_ms?: number;
   model_used?: string;
   total_tokens?: number;
+  cover_image_path?: string; // Hero image for the whole briefing (enable_cover_image)
+}
+
+// What delete_briefing removes (or would remove, without confirm)
+export interface BriefingDeletion {
+  id: number;
+  date: string;
+  title: string;
+  cards: number;
+  bookmarks: number;
+  chat_messages: number;
+}
+
+export interface DeleteBriefingResult {
+  deleted: boolean;
+  briefing: BriefingDeletion;
 }
 
 // Card data structure within the cards JSON
@@ -115,4 +364,272 @@ export interface BriefingCardData {
   topic?: string;
   image_prompt?: string;
   image_path?: string;
+  source_checks?: SourceCheck[];
+  source_warning?: string;
+  action_items?: string[];
+  actions?: CardAction[];
+  rank?: number;  // Display position (0 first); unranked cards keep stored order
+}
+
+// Tool usage analytics (from get_tool_analytics)
+export interface ToolStats {
+  tool_name: string;
+  server?: string;  // MCP server, for mcp_call entries
+  log_type: string;
+  calls: number;
+  failures: number;
+  failure_rate: number;
+  avg_duration_ms?: number;
+  est_tokens: number;  // Estimated tokens added to context by tool output
+}
+
+export interface ServerStats {
+  server: string;
+  tools: number;
+  calls: number;
+  failures: number;
+  failure_rate: number;
+  avg_duration_ms?: number;
+  est_tokens: number;
+}
+
+export interface DailyToolStats {
+  date: string;
+  tool_calls: number;
+  failures: number;
+  avg_duration_ms?: number;
+  est_tool_tokens: number;
+  api_tokens: number;
+}
+
+export interface BriefingToolStats {
+  briefing_id: number;
+  date: string;
+  tool_calls: number;
+  failures: number;
+  est_tool_tokens: number;
+  api_tokens: number;
+}
+
+export interface ToolAnalytics {
+  days: number;
+  tools: ToolStats[];
+  servers: ServerStats[];
+  daily: DailyToolStats[];
+  briefings: BriefingToolStats[];
+}
+
+// Result of quick_research (the quick research shortcut)
+export interface QuickResearchResult {
+  query: string;  // The copied text, shortened if needed
+  mode: 'research' | 'ask';
+  message: string | null;  // Summary of the ad-hoc research run
+  answer: ArchiveAnswer | null;  // Answer from the briefing archive
+}
+
+// Dashboard statistics (from get_statistics)
+export interface WeeklyBriefings {
+  week: string;  // Monday of the week (YYYY-MM-DD)
+  briefings: number;
+  cards: number;
+  tokens: number;
+}
+
+export interface TopicCards {
+  topic: string;
+  cards: number;
+  briefings: number;  // Briefings with at least one card on the topic
+}
+
+export interface RunSpend {
+  runs: number;
+  total_tokens: number;
+  avg_tokens: number;
+  total_cost_usd: number;  // Estimated per model
+  avg_cost_usd: number;
+  avg_duration_ms: number | null;
+}
+
+export interface ToolShare {
+  tool_name: string;
+  calls: number;
+  share: number;  // 0-1 of all tool calls
+}
+
+export interface Statistics {
+  days: number;
+  current_streak: number;  // Consecutive days with research up to today (or yesterday)
+  longest_streak: number;
+  last_research_day: string | null;
+  briefings: number;
+  cards: number;
+  weekly: WeeklyBriefings[];  // Oldest week first
+  topics: TopicCards[];  // Most cards first
+  spend: RunSpend;
+  tools: ToolShare[];  // Most calls first
+}
+
+// Per-run research report (from get_research_report)
+export interface ResearchLogRecord {
+  id: number;
+  briefing_id: number | null;
+  log_type: string;  // 'tool_call', 'api_request', 'mcp_call', 'thinking' or 'error'
+  topic: string | null;
+  tool_name: string | null;
+  input_summary: string | null;
+  output_summary: string | null;
+  duration_ms: number | null;
+  tokens_used: number | null;
+  success: boolean;
+  error_code: string | null;
+  error_message: string | null;
+  user_action_required: boolean;
+  created_at: string;
+  request_id?: string | null;  // Anthropic request-id header, for matching failures in the console
+}
+
+export interface TopicReport {
+  topic: string;
+  api_requests: number;
+  tool_calls: number;
+  failures: number;
+  tokens: number;
+  duration_ms: number;  // Time spent in API requests and tool calls
+}
+
+export interface RunReport {
+  briefing_id: number;
+  date: string;
+  title: string;
+  model?: string;
+  research_time_ms?: number;
+  total_tokens: number;
+  batch_tokens: number;
+  estimated_cost_usd: number;
+  topics: TopicReport[];
+  timeline: ResearchLogRecord[];  // Every logged step, oldest first
+  failures: number;
+}
+
+// What one topic of a research run took and produced (from get_briefing_topics)
+export interface TopicResult {
+  topic: string;
+  tokens: number;  // Research only; synthesis is shared by all topics
+  tool_calls: number;
+  duration_ms: number;
+  card_indices: number[];  // Cards of the briefing about this topic
+}
+
+// A topic whose research failed and was left out of the briefing (from get_briefing_failed_topics)
+export interface FailedTopic {
+  topic: string;
+  error: string;
+}
+
+// Named Anthropic API key profiles
+export interface ProfileInfo {
+  name: string;
+  active: boolean;
+  has_key: boolean;
+}
+
+// Workspace with its own topics, briefings and settings (from list_workspaces)
+export interface Workspace {
+  name: string;
+  path: string;
+  active: boolean;
+}
+
+// Topic suggested by discovery (from get_topic_suggestions)
+export interface TopicSuggestion {
+  id: number;
+  name: string;
+  reason: string | null;
+  status: 'pending' | 'accepted' | 'dismissed';
+  created_at: string;
+}
+
+// Expected tools and cost of researching a topic (from preview_topic)
+export interface TopicPreview {
+  topic: string;
+  tools: string[];
+  searches: number;
+  page_fetches: number;
+  tool_calls: number;
+  plan: string;
+  estimated_tokens: number;
+  estimated_cost_usd: number;
+  estimated_monthly_cost_usd: number;
+  from_history: boolean;  // Step cost learned from recent runs rather than a default
+  model: string;
+  preview_tokens: number;
+}
+
+// Run-over-run novelty of a topic's findings
+export interface TopicActivity {
+  topic: string;
+  runs: number;
+  avg_novelty: number;   // 0.0 (repeats) - 1.0 (all new)
+  last_novelty: number;
+  avg_cards: number;
+  status: 'new' | 'stagnant' | 'active' | 'steady';
+  suggestion: string | null;
+  last_run: string;
+}
+
+// Every card saved for a topic, oldest first (from get_topic_timeline)
+export interface TimelineEntry {
+  briefing_id: number;
+  card_index: number;
+  date: string;
+  title: string;
+  summary: string;
+  relevance: string;
+  sources: string[];
+}
+
+export interface TopicTimeline {
+  topic: string;
+  entries: TimelineEntry[];
+  narrative?: string;  // Markdown, citing cards as [briefing 42, card 3]
+  tokens_used: number;
+}
+
+export interface ModelInfo {
+  id: string;
+  display_name: string;
+  created_at: string | null;
+  deprecated: boolean;
+}
+
+export interface ProfileUsage {
+  profile: string;
+  research_runs: number;
+  research_tokens: number;
+  batch_tokens: number;  // Research tokens billed at the Batch API discount
+  chat_messages: number;
+  chat_tokens: number;
+  total_tokens: number;
+}
+
+// claudius:// link opened from a notification, export or the CLI
+export interface DeepLink {
+  briefing_id: number;
+  card_index: number | null;
+}
+
+// Saved main window geometry and last view (from get_ui_state)
+export interface WindowGeometry {
+  x: number;
+  y: number;
+  width: number;
+  height: number;
+  maximized: boolean;
+}
+
+export interface UiState {
+  window: WindowGeometry | null;
+  route: string | null;  // e.g. "/history?briefing=12&card=2"
+  briefing_id: number | null;
+  card_index: number | null;
 }
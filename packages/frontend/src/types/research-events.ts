@@ -33,6 +33,14 @@ export interface TopicStartedEvent extends ResearchEvent {
 export interface ThinkingEvent extends ResearchEvent {
   topic_name: string;
   phase: 'initial_research' | 'tool_calling' | 'synthesis';
+  reasoning?: string | null; // Thinking summary when extended thinking is enabled
+}
+
+// Event: Tool started executing
+export interface ToolStartedEvent extends ResearchEvent {
+  topic_name: string;
+  tool_name: string;
+  tool_type: 'mcp' | 'builtin';
 }
 
 // Event: Tool execution
@@ -51,6 +59,14 @@ export interface TopicCompletedEvent extends ResearchEvent {
   cards_generated: number;
 }
 
+// Event: Topic aborted by the per-topic timeout (research continues with the next topic)
+export interface TopicTimeoutEvent extends ResearchEvent {
+  topic_name: string;
+  topic_index: number;
+  timeout_secs: number;
+  has_partial_findings: boolean;
+}
+
 // Event: Saving results to database
 export interface SavingEvent extends ResearchEvent {
   total_cards: number;
@@ -122,6 +138,15 @@ export interface ResearchModeErrorEvent extends ResearchEvent {
   error: string;
 }
 
+// Event: A Firecrawl budget is 80% used or used up
+export interface FirecrawlBudgetEvent extends ResearchEvent {
+  scope: 'run' | 'month';
+  used: number;
+  limit: number;
+  exhausted: boolean;
+  message: string;
+}
+
 // Union type for all events
 export type ResearchProgressEvent =
   | { type: 'research:started'; data: ResearchStartedEvent }
@@ -129,8 +154,10 @@ export type ResearchProgressEvent =
   | { type: 'research:mcp_connection_failed'; data: McpConnectionFailedEvent }
   | { type: 'research:topic_started'; data: TopicStartedEvent }
   | { type: 'research:thinking'; data: ThinkingEvent }
+  | { type: 'research:tool_started'; data: ToolStartedEvent }
   | { type: 'research:tool_executed'; data: ToolExecutedEvent }
   | { type: 'research:topic_completed'; data: TopicCompletedEvent }
+  | { type: 'research:topic_timeout'; data: TopicTimeoutEvent }
   | { type: 'research:synthesis_started'; data: SynthesisStartedEvent }
   | { type: 'research:synthesis_completed'; data: SynthesisCompletedEvent }
   | { type: 'research:saving'; data: SavingEvent }
@@ -141,4 +168,20 @@ export type ResearchProgressEvent =
   | { type: 'research:heartbeat'; data: HeartbeatEvent }
   | { type: 'research:web_search'; data: WebSearchEvent }
   | { type: 'research:deep_extraction'; data: DeepExtractionEvent }
-  | { type: 'research:mode_error'; data: ResearchModeErrorEvent };
+  | { type: 'research:mode_error'; data: ResearchModeErrorEvent }
+  | { type: 'research:firecrawl_budget'; data: FirecrawlBudgetEvent };
+
+// An event as recorded by the backend for windows that open mid-run
+export interface RecordedResearchEvent {
+  event: string;  // e.g. "research:topic_started"
+  payload: Record<string, unknown>;
+}
+
+// Returned by get_research_progress_snapshot
+export interface ResearchProgressSnapshot {
+  is_running: boolean;
+  current_phase: string;
+  started_at: string | null;
+  elapsed_ms: number | null;
+  events: RecordedResearchEvent[];
+}
@@ -25,11 +25,38 @@ export interface UpdateState {
   notes: string | null;
   downloadProgress: number; // 0-100
   error: string | null;
+  changelog: Release[]; // Releases newer than the running version, newest first
 }
 
-// Response from check_for_update command
+// An available update, as reported by the updater
 export interface UpdateInfo {
   version: string;
   notes: string | null;
   date: string | null;
 }
+
+// A file attached to a GitHub release
+export interface ReleaseAsset {
+  name: string;
+  url: string;
+  size: number;
+}
+
+// A GitHub release of the configured channel
+export interface Release {
+  version: string;
+  tag: string;
+  notes: string | null;
+  published_at: string | null;
+  url: string;
+  prerelease: boolean;
+  assets: ReleaseAsset[];
+}
+
+// Response from check_for_updates command
+export interface UpdateCheck {
+  current_version: string;
+  channel: string;  // "stable" or "beta"
+  update: UpdateInfo | null;
+  changelog: Release[];
+}
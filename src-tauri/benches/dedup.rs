@@ -0,0 +1,145 @@
+//! Benchmarks for filtering duplicate cards against past briefings.
+//!
+//! Run with `cargo bench --bench dedup`.
+
+use claudius::dedup::{
+    filter_duplicates, filter_duplicates_by_topic, CardFingerprint, DedupPolicy,
+};
+use claudius::research::BriefingCard;
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+use std::hint::black_box;
+
+const TOPICS: &[&str] = &[
+    "AI News",
+    "Rust",
+    "Web Standards",
+    "Climate Tech",
+    "Space",
+    "Security",
+    "Open Source",
+    "Databases",
+];
+
+const WORDS: &[&str] = &[
+    "announces",
+    "releases",
+    "new",
+    "model",
+    "version",
+    "funding",
+    "breach",
+    "launch",
+    "open",
+    "source",
+    "benchmark",
+    "record",
+    "update",
+    "standard",
+    "draft",
+    "approved",
+    "mission",
+    "satellite",
+    "carbon",
+    "capture",
+    "vulnerability",
+    "patch",
+    "compiler",
+    "database",
+];
+
+/// Deterministic pseudo-random sequence (64-bit LCG), so every run
+/// benchmarks the same corpus.
+struct Lcg(u64);
+
+impl Lcg {
+    fn pick(&mut self, below: usize) -> usize {
+        self.0 = self
+            .0
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        ((self.0 >> 33) as usize) % below
+    }
+
+    fn words(&mut self, count: usize) -> String {
+        (0..count)
+            .map(|_| WORDS[self.pick(WORDS.len())])
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+/// `count` past cards spread over the topics, like a month of briefings.
+fn past_cards(rng: &mut Lcg, count: usize) -> Vec<CardFingerprint> {
+    (0..count)
+        .map(|_| CardFingerprint {
+            title: rng.words(7),
+            topic: TOPICS[rng.pick(TOPICS.len())].to_string(),
+            summary: rng.words(30),
+        })
+        .collect()
+}
+
+/// `count` new cards, every fourth a lightly reworded past card.
+fn new_cards(rng: &mut Lcg, past: &[CardFingerprint], count: usize) -> Vec<BriefingCard> {
+    (0..count)
+        .map(|i| {
+            let (title, topic, summary) = if i % 4 == 0 {
+                let repeat = &past[rng.pick(past.len())];
+                (
+                    format!("{} {}", repeat.title, WORDS[rng.pick(WORDS.len())]),
+                    repeat.topic.clone(),
+                    repeat.summary.clone(),
+                )
+            } else {
+                (
+                    rng.words(7),
+                    TOPICS[rng.pick(TOPICS.len())].to_string(),
+                    rng.words(30),
+                )
+            };
+            serde_json::from_value(serde_json::json!({
+                "title": title,
+                "summary": summary,
+                "detailed_content": rng.words(200),
+                "sources": ["https://example.com/story"],
+                "suggested_next": null,
+                "relevance": "high",
+                "topic": topic,
+            }))
+            .unwrap()
+        })
+        .collect()
+}
+
+fn bench_filter_duplicates(c: &mut Criterion) {
+    let mut group = c.benchmark_group("filter_duplicates");
+    for past_count in [100, 1_000, 5_000] {
+        let mut rng = Lcg(past_count as u64);
+        let past = past_cards(&mut rng, past_count);
+        let cards = new_cards(&mut rng, &past, 40);
+        group.bench_with_input(BenchmarkId::new("global", past_count), &past, |b, past| {
+            b.iter_batched(
+                || cards.clone(),
+                |cards| filter_duplicates(cards, black_box(past), 0.75),
+                BatchSize::SmallInput,
+            )
+        });
+
+        let policy = DedupPolicy::new(30, 0.75);
+        group.bench_with_input(
+            BenchmarkId::new("by_topic", past_count),
+            &past,
+            |b, past| {
+                b.iter_batched(
+                    || cards.clone(),
+                    |cards| filter_duplicates_by_topic(cards, black_box(past), &policy),
+                    BatchSize::SmallInput,
+                )
+            },
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_filter_duplicates);
+criterion_main!(benches);
@@ -0,0 +1,118 @@
+//! Benchmarks for article extraction from fetched webpages.
+//!
+//! Run with `cargo bench --bench extraction`.
+
+use claudius::article::extract_article;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use std::hint::black_box;
+
+const WORDS: &[&str] = &[
+    "model",
+    "release",
+    "benchmark",
+    "compiler",
+    "research",
+    "latency",
+    "open",
+    "source",
+    "framework",
+    "launch",
+    "regulation",
+    "dataset",
+    "inference",
+    "security",
+    "update",
+    "team",
+];
+
+/// A news-style page: navigation, header, an article of `paragraphs`
+/// paragraphs with links and a list, a sidebar, comments, footer and scripts.
+fn news_page(paragraphs: usize) -> String {
+    let sentence = |seed: usize| {
+        (0..18)
+            .map(|i| WORDS[(seed * 7 + i * 3) % WORDS.len()])
+            .collect::<Vec<_>>()
+            .join(" ")
+    };
+
+    let mut html = String::from(
+        r#"<!DOCTYPE html><html><head>
+<title>Example News | Technology</title>
+<meta property="og:title" content="A long look at this week's releases">
+<meta name="author" content="Jane Doe">
+<meta property="article:published_time" content="2025-03-01T08:00:00Z">
+<style>body { font-family: sans-serif; } .ad { display: none; }</style>
+<script>window.analytics = { track: function () {} };</script>
+</head><body>
+<nav class="navbar"><ul><li><a href="/">Home</a></li><li><a href="/tech">Tech</a></li><li><a href="/science">Science</a></li></ul></nav>
+<header><h1>Example News</h1></header>
+<main><article>
+<h1>A long look at this week's releases</h1>
+<span class="byline">By Jane Doe</span>
+"#,
+    );
+    for i in 0..paragraphs {
+        if i % 8 == 0 {
+            html.push_str(&format!("<h2>Section {}</h2>\n", i / 8 + 1));
+        }
+        html.push_str(&format!(
+            "<p>{}. <a href=\"https://example.com/{}\">{}</a> {}.</p>\n",
+            sentence(i),
+            i,
+            WORDS[i % WORDS.len()],
+            sentence(i + 1)
+        ));
+        if i % 12 == 5 {
+            html.push_str(
+                "<ul><li>First point</li><li>Second point</li><li>Third point</li></ul>\n",
+            );
+        }
+    }
+    html.push_str(
+        r#"</article>
+<aside class="sidebar"><h3>Related</h3><ul><li><a href="/a">Another story</a></li><li><a href="/b">One more</a></li></ul></aside>
+<div class="comments"><p>Great article!</p><p>I disagree with the second section.</p></div>
+</main>
+<div class="newsletter"><form><input type="email"><button>Subscribe</button></form></div>
+<footer><p>Copyright Example News</p></footer>
+<script>document.querySelectorAll('a').forEach(function (a) { a.rel = 'noopener'; });</script>
+</body></html>"#,
+    );
+    html
+}
+
+/// A page without semantic containers, so candidates are scored by their
+/// paragraphs instead.
+fn div_soup(paragraphs: usize) -> String {
+    let mut html = String::from("<html><body><div id=\"menu\"><a href=\"/\">Home</a></div><div class=\"wrapper\"><div class=\"content\">");
+    for i in 0..paragraphs {
+        html.push_str(&format!(
+            "<div class=\"row\"><p>{} {} {}</p></div>",
+            WORDS[i % WORDS.len()],
+            WORDS[(i * 5) % WORDS.len()],
+            "lorem ipsum dolor sit amet ".repeat(6)
+        ));
+    }
+    html.push_str("</div></div><div class=\"footer\">Footer</div></body></html>");
+    html
+}
+
+fn bench_extract_article(c: &mut Criterion) {
+    let mut group = c.benchmark_group("extract_article");
+    for paragraphs in [20, 200] {
+        let page = news_page(paragraphs);
+        group.throughput(Throughput::Bytes(page.len() as u64));
+        group.bench_with_input(
+            BenchmarkId::new("news_page", paragraphs),
+            &page,
+            |b, page| b.iter(|| extract_article(black_box(page))),
+        );
+    }
+    let soup = div_soup(300);
+    group.throughput(Throughput::Bytes(soup.len() as u64));
+    group.bench_function("div_soup", |b| b.iter(|| extract_article(black_box(&soup))));
+    group.finish();
+}
+
+criterion_group!(benches, bench_extract_article);
+criterion_main!(benches);
@@ -0,0 +1,882 @@
+//! Shared building blocks for the Anthropic tool-use loop.
+//!
+//! The research agent and chat each drive their own loop (research adds
+//! timeouts, progress events and MCP result truncation), but every turn
+//! does the same things: send the conversation, collect the tool calls from
+//! the response, echo the assistant turn back and answer each call with a
+//! tool result. The request/response types and those steps live here.
+
+use crate::config::ResearchSettings;
+use reqwest::header::{HeaderName, HeaderValue};
+use reqwest::{Client, RequestBuilder};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::HashMap;
+
+/// Anthropic API base URL, used unless `api_base_url` is set.
+pub const DEFAULT_BASE_URL: &str = "https://api.anthropic.com";
+
+/// Anthropic API version header value.
+pub const API_VERSION: &str = "2023-06-01";
+
+/// Response header carrying the ID Anthropic's console knows a request by.
+pub const REQUEST_ID_HEADER: &str = "request-id";
+
+/// Longest `metadata.user_id` the API accepts.
+pub const MAX_USER_ID_LENGTH: usize = 256;
+
+/// Where Anthropic API requests are sent: the official API, or a gateway
+/// (LiteLLM, Cloudflare AI Gateway) or compatible provider configured with
+/// `api_base_url` and `api_headers`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ApiEndpoint {
+    /// Base URL without a trailing slash or `/v1`
+    pub base_url: String,
+    /// Sent with every request, e.g. a gateway's own auth header
+    pub headers: HashMap<String, String>,
+    /// Sent as `metadata.user_id` with every Messages request
+    pub user_id: Option<String>,
+}
+
+impl Default for ApiEndpoint {
+    fn default() -> Self {
+        Self {
+            base_url: DEFAULT_BASE_URL.to_string(),
+            headers: HashMap::new(),
+            user_id: None,
+        }
+    }
+}
+
+impl ApiEndpoint {
+    /// An endpoint at `base_url`, which may be given with or without `/v1`.
+    pub fn new(base_url: &str, headers: HashMap<String, String>) -> Self {
+        let base_url = base_url.trim().trim_end_matches('/');
+        let base_url = base_url.strip_suffix("/v1").unwrap_or(base_url);
+        Self {
+            base_url: base_url.to_string(),
+            headers,
+            user_id: None,
+        }
+    }
+
+    pub fn from_settings(settings: &ResearchSettings) -> Self {
+        let endpoint = match settings
+            .api_base_url
+            .as_deref()
+            .filter(|u| !u.trim().is_empty())
+        {
+            Some(base_url) => Self::new(base_url, settings.api_headers.clone()),
+            None => Self {
+                headers: settings.api_headers.clone(),
+                ..Self::default()
+            },
+        };
+        Self {
+            user_id: settings
+                .api_user_id
+                .as_deref()
+                .map(str::trim)
+                .filter(|id| !id.is_empty())
+                .map(str::to_string),
+            ..endpoint
+        }
+    }
+
+    /// The endpoint configured in settings.
+    pub fn current() -> Self {
+        Self::from_settings(&crate::config::read_settings().unwrap_or_default())
+    }
+
+    /// Full URL of an API path such as `/v1/messages`.
+    pub fn url(&self, path: &str) -> String {
+        format!("{}{}", self.base_url, path)
+    }
+
+    pub fn messages_url(&self) -> String {
+        self.url("/v1/messages")
+    }
+
+    /// Metadata to attach to a Messages request, if a user ID is configured.
+    pub fn metadata(&self) -> Option<RequestMetadata> {
+        self.user_id
+            .clone()
+            .map(|user_id| RequestMetadata { user_id })
+    }
+
+    /// Add the API key, version and extra headers to a request.
+    pub fn authorize(&self, request: RequestBuilder, api_key: &str) -> RequestBuilder {
+        self.headers.iter().fold(
+            request
+                .header("x-api-key", api_key)
+                .header("anthropic-version", API_VERSION),
+            |request, (name, value)| request.header(name.as_str(), value.as_str()),
+        )
+    }
+
+    /// Check that the endpoint speaks the Messages API by sending a one-token
+    /// request. An Anthropic-style error (e.g. a rejected key) is reported
+    /// as such; anything else that isn't a message is rejected.
+    #[allow(dead_code)]
+    pub async fn verify(&self, client: &Client, api_key: &str, model: &str) -> Result<(), String> {
+        let response = self
+            .authorize(client.post(self.messages_url()), api_key)
+            .json(&json!({
+                "model": model,
+                "max_tokens": 1,
+                "messages": [{ "role": "user", "content": "ping" }],
+            }))
+            .send()
+            .await
+            .map_err(|e| format!("Could not reach {}: {}", self.messages_url(), e))?;
+
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        check_messages_response(status.as_u16(), &body)
+            .map_err(|e| format!("{} ({})", e, self.messages_url()))
+    }
+}
+
+/// Check an endpoint's reply to a Messages API request.
+fn check_messages_response(status: u16, body: &str) -> Result<(), String> {
+    let json: Option<serde_json::Value> = serde_json::from_str(body).ok();
+    if (200..300).contains(&status) {
+        return match json.map(serde_json::from_value::<MessagesResponse>) {
+            Some(Ok(_)) => Ok(()),
+            _ => Err("Endpoint replied, but not with a Messages API response".to_string()),
+        };
+    }
+    match json
+        .as_ref()
+        .filter(|json| json["type"] == "error")
+        .and_then(|json| json["error"]["message"].as_str())
+    {
+        Some(message) => Err(format!("Endpoint rejected the request: {}", message)),
+        None => Err(format!(
+            "Endpoint returned HTTP {} without a Messages API error",
+            status
+        )),
+    }
+}
+
+/// Check that an extra header name and value can be sent.
+#[allow(dead_code)]
+pub fn validate_header(name: &str, value: &str) -> Result<(), String> {
+    HeaderName::from_bytes(name.as_bytes())
+        .map_err(|_| format!("Invalid header name '{}'", name))?;
+    HeaderValue::from_str(value).map_err(|_| format!("Invalid value for header '{}'", name))?;
+    Ok(())
+}
+
+/// Check a `metadata.user_id`. Anthropic asks for an opaque identifier such
+/// as a UUID or hash, never a name, email address or phone number.
+#[allow(dead_code)]
+pub fn validate_user_id(user_id: &str) -> Result<(), String> {
+    if user_id.chars().count() > MAX_USER_ID_LENGTH {
+        return Err(format!("must be at most {} characters", MAX_USER_ID_LENGTH));
+    }
+    if user_id.contains('@') {
+        return Err("must be an opaque ID, not an email address".to_string());
+    }
+    Ok(())
+}
+
+/// The `request-id` header of an API response, for correlating a request
+/// with Anthropic's console.
+pub fn request_id(response: &reqwest::Response) -> Option<String> {
+    response
+        .headers()
+        .get(REQUEST_ID_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string)
+}
+
+/// Anthropic API message request with tools.
+/// Note: `tools` uses serde_json::Value to support both regular tools and server tools (like web_search)
+#[derive(Debug, Serialize)]
+pub struct MessagesRequest {
+    pub model: String,
+    pub max_tokens: u32,
+    pub messages: Vec<Message>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tools: Option<Vec<serde_json::Value>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub system: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thinking: Option<ThinkingConfig>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<RequestMetadata>,
+}
+
+/// Request metadata; `user_id` lets Anthropic attribute abuse reports to
+/// one user of a shared key.
+#[derive(Debug, Clone, Serialize)]
+pub struct RequestMetadata {
+    pub user_id: String,
+}
+
+/// Extended thinking: Claude reasons for up to `budget_tokens` before answering.
+/// `max_tokens` must be larger than the budget.
+#[derive(Debug, Clone, Serialize)]
+pub struct ThinkingConfig {
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub budget_tokens: u32,
+}
+
+impl ThinkingConfig {
+    /// Smallest budget the API accepts.
+    pub const MIN_BUDGET: u32 = 1024;
+
+    pub fn enabled(budget_tokens: u32) -> Self {
+        Self {
+            kind: "enabled".to_string(),
+            budget_tokens: budget_tokens.max(Self::MIN_BUDGET),
+        }
+    }
+}
+
+/// A message in the conversation.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Message {
+    pub role: String,
+    pub content: MessageContent,
+}
+
+impl Message {
+    pub fn user(text: impl Into<String>) -> Self {
+        Self {
+            role: "user".to_string(),
+            content: MessageContent::Text(text.into()),
+        }
+    }
+
+    /// Tool results, sent back as a user turn.
+    pub fn tool_results(results: Vec<ContentBlock>) -> Self {
+        Self {
+            role: "user".to_string(),
+            content: MessageContent::Blocks(results),
+        }
+    }
+}
+
+/// Message content can be a string or array of content blocks.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum MessageContent {
+    Text(String),
+    Blocks(Vec<ContentBlock>),
+}
+
+/// A content block in a message.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum ContentBlock {
+    #[serde(rename = "text")]
+    Text { text: String },
+    #[serde(rename = "thinking")]
+    Thinking { thinking: String, signature: String },
+    #[serde(rename = "redacted_thinking")]
+    RedactedThinking { data: String },
+    #[serde(rename = "tool_use")]
+    ToolUse {
+        id: String,
+        name: String,
+        input: serde_json::Value,
+    },
+    #[serde(rename = "tool_result")]
+    ToolResult {
+        tool_use_id: String,
+        content: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        is_error: Option<bool>,
+    },
+}
+
+impl ContentBlock {
+    /// Answer a tool call. Errors are passed to Claude as `Error: ...`.
+    pub fn tool_result(tool_use_id: &str, result: Result<String, String>) -> Self {
+        let (content, is_error) = match result {
+            Ok(output) => (output, None),
+            Err(e) => (format!("Error: {}", e), Some(true)),
+        };
+        ContentBlock::ToolResult {
+            tool_use_id: tool_use_id.to_string(),
+            content,
+            is_error,
+        }
+    }
+}
+
+/// Anthropic API response.
+#[derive(Debug, Deserialize)]
+pub struct MessagesResponse {
+    pub content: Vec<ResponseContentBlock>,
+    pub usage: Usage,
+    pub stop_reason: Option<String>,
+    /// The response's `request-id` header, filled in after parsing
+    #[serde(skip)]
+    pub request_id: Option<String>,
+}
+
+/// Content block in API response (slightly different structure for deserialization).
+#[derive(Debug, Deserialize)]
+pub struct ResponseContentBlock {
+    #[serde(rename = "type")]
+    pub content_type: String,
+    #[serde(default)]
+    pub text: Option<String>,
+    #[serde(default)]
+    pub id: Option<String>,
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub input: Option<serde_json::Value>,
+    /// Thinking summary (`thinking` blocks)
+    #[serde(default)]
+    pub thinking: Option<String>,
+    #[serde(default)]
+    pub signature: Option<String>,
+    /// Encrypted reasoning (`redacted_thinking` blocks)
+    #[serde(default)]
+    pub data: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Usage {
+    pub input_tokens: u32,
+    pub output_tokens: u32,
+}
+
+/// A tool call requested by Claude.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ToolCall {
+    pub id: String,
+    pub name: String,
+    pub input: serde_json::Value,
+}
+
+impl MessagesResponse {
+    pub fn total_tokens(&self) -> u32 {
+        self.usage.input_tokens + self.usage.output_tokens
+    }
+
+    /// Client-side tool calls in this response (server tools like web_search
+    /// are run by the API and not included).
+    pub fn tool_calls(&self) -> Vec<ToolCall> {
+        self.content
+            .iter()
+            .filter(|c| c.content_type == "tool_use")
+            .map(|c| ToolCall {
+                id: c.id.clone().unwrap_or_default(),
+                name: c.name.clone().unwrap_or_default(),
+                input: c.input.clone().unwrap_or(json!({})),
+            })
+            .collect()
+    }
+
+    /// Whether Claude is done: no tool calls left, or the turn ended.
+    pub fn is_final(&self) -> bool {
+        self.stop_reason.as_deref() == Some("end_turn")
+            || !self.content.iter().any(|c| c.content_type == "tool_use")
+    }
+
+    /// The response's text blocks joined by newlines.
+    pub fn text(&self) -> String {
+        self.content
+            .iter()
+            .filter(|c| c.content_type == "text")
+            .filter_map(|c| c.text.clone())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Claude's thinking summaries joined by blank lines, if extended
+    /// thinking was enabled and Claude used it.
+    pub fn thinking(&self) -> Option<String> {
+        let summaries: Vec<&str> = self
+            .content
+            .iter()
+            .filter(|c| c.content_type == "thinking")
+            .filter_map(|c| c.thinking.as_deref())
+            .filter(|thinking| !thinking.trim().is_empty())
+            .collect();
+        (!summaries.is_empty()).then(|| summaries.join("\n\n"))
+    }
+
+    /// The assistant turn to add to the conversation before the tool results.
+    ///
+    /// Empty text blocks are dropped (the API rejects "text content blocks
+    /// must be non-empty") and so are server tool blocks. Thinking blocks are
+    /// kept unchanged: with extended thinking the API requires them to be
+    /// passed back alongside the tool calls.
+    pub fn assistant_message(&self) -> Message {
+        let blocks = self
+            .content
+            .iter()
+            .filter_map(|c| match c.content_type.as_str() {
+                "text" => c
+                    .text
+                    .clone()
+                    .filter(|text| !text.is_empty())
+                    .map(|text| ContentBlock::Text { text }),
+                "thinking" => Some(ContentBlock::Thinking {
+                    thinking: c.thinking.clone().unwrap_or_default(),
+                    signature: c.signature.clone().unwrap_or_default(),
+                }),
+                "redacted_thinking" => Some(ContentBlock::RedactedThinking {
+                    data: c.data.clone().unwrap_or_default(),
+                }),
+                "tool_use" => Some(ContentBlock::ToolUse {
+                    id: c.id.clone().unwrap_or_default(),
+                    name: c.name.clone().unwrap_or_default(),
+                    input: c.input.clone().unwrap_or(json!({})),
+                }),
+                _ => None,
+            })
+            .collect();
+        Message {
+            role: "assistant".to_string(),
+            content: MessageContent::Blocks(blocks),
+        }
+    }
+}
+
+/// Builds a [`MessagesResponse`] from the server-sent events of a streamed
+/// (`"stream": true`) request.
+#[derive(Debug, Default)]
+#[allow(dead_code)]
+pub struct StreamAccumulator {
+    content: Vec<ResponseContentBlock>,
+    /// Partial JSON of each block's tool input, parsed when the block ends
+    partial_json: Vec<String>,
+    input_tokens: u32,
+    output_tokens: u32,
+    stop_reason: Option<String>,
+}
+
+#[allow(dead_code)]
+impl StreamAccumulator {
+    /// Handle the `data` of one event. Returns new text to show the reader;
+    /// text blocks are separated by a newline, as in [`MessagesResponse::text`].
+    pub fn handle_event(&mut self, data: &str) -> Result<Option<String>, String> {
+        let event: serde_json::Value = serde_json::from_str(data)
+            .map_err(|e| format!("Failed to parse stream event: {}", e))?;
+        match event["type"].as_str().unwrap_or_default() {
+            "message_start" => {
+                let usage = &event["message"]["usage"];
+                self.input_tokens = usage["input_tokens"].as_u64().unwrap_or(0) as u32;
+                self.output_tokens = usage["output_tokens"].as_u64().unwrap_or(0) as u32;
+            }
+            "content_block_start" => {
+                let block: ResponseContentBlock =
+                    serde_json::from_value(event["content_block"].clone())
+                        .map_err(|e| format!("Failed to parse stream content block: {}", e))?;
+                let text = (block.content_type == "text").then(|| {
+                    let separator = if self.content.iter().any(|c| c.content_type == "text") {
+                        "\n"
+                    } else {
+                        ""
+                    };
+                    format!("{}{}", separator, block.text.as_deref().unwrap_or_default())
+                });
+                self.content.push(block);
+                self.partial_json.push(String::new());
+                return Ok(text.filter(|t| !t.is_empty()));
+            }
+            "content_block_delta" => {
+                let index = event["index"].as_u64().unwrap_or(0) as usize;
+                let Some(block) = self.content.get_mut(index) else {
+                    return Ok(None);
+                };
+                let delta = &event["delta"];
+                match delta["type"].as_str().unwrap_or_default() {
+                    "text_delta" => {
+                        let text = delta["text"].as_str().unwrap_or_default();
+                        block.text.get_or_insert_with(String::new).push_str(text);
+                        return Ok(Some(text.to_string()).filter(|t| !t.is_empty()));
+                    }
+                    "input_json_delta" => self.partial_json[index]
+                        .push_str(delta["partial_json"].as_str().unwrap_or_default()),
+                    "thinking_delta" => block
+                        .thinking
+                        .get_or_insert_with(String::new)
+                        .push_str(delta["thinking"].as_str().unwrap_or_default()),
+                    "signature_delta" => block
+                        .signature
+                        .get_or_insert_with(String::new)
+                        .push_str(delta["signature"].as_str().unwrap_or_default()),
+                    _ => {}
+                }
+            }
+            "content_block_stop" => {
+                let index = event["index"].as_u64().unwrap_or(0) as usize;
+                if let (Some(block), Some(json)) =
+                    (self.content.get_mut(index), self.partial_json.get(index))
+                {
+                    if block.content_type == "tool_use" && !json.is_empty() {
+                        block.input = Some(
+                            serde_json::from_str(json)
+                                .map_err(|e| format!("Failed to parse tool input: {}", e))?,
+                        );
+                    }
+                }
+            }
+            "message_delta" => {
+                if let Some(reason) = event["delta"]["stop_reason"].as_str() {
+                    self.stop_reason = Some(reason.to_string());
+                }
+                // Cumulative for the whole response
+                if let Some(tokens) = event["usage"]["output_tokens"].as_u64() {
+                    self.output_tokens = tokens as u32;
+                }
+            }
+            "error" => {
+                return Err(format!(
+                    "API error: {}",
+                    event["error"]["message"]
+                        .as_str()
+                        .unwrap_or("stream failed")
+                ))
+            }
+            // ping, message_stop
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    pub fn finish(self) -> MessagesResponse {
+        MessagesResponse {
+            content: self.content,
+            usage: Usage {
+                input_tokens: self.input_tokens,
+                output_tokens: self.output_tokens,
+            },
+            stop_reason: self.stop_reason,
+            request_id: None,
+        }
+    }
+}
+
+/// Send `request` with `"stream": true`, passing each piece of text to
+/// `on_text` as it arrives, and return the complete response.
+#[allow(dead_code)]
+pub async fn stream_message(
+    client: &Client,
+    endpoint: &ApiEndpoint,
+    api_key: &str,
+    request: &MessagesRequest,
+    mut on_text: impl FnMut(&str),
+) -> Result<MessagesResponse, String> {
+    let mut body =
+        serde_json::to_value(request).map_err(|e| format!("Failed to serialize request: {}", e))?;
+    body["stream"] = json!(true);
+
+    let mut response = endpoint
+        .authorize(client.post(endpoint.messages_url()), api_key)
+        .header("content-type", "application/json")
+        .json(&body)
+        .send()
+        .await
+        .map_err(|e| format!("HTTP request failed: {}", e))?;
+    let request_id = request_id(&response);
+
+    if !response.status().is_success() {
+        let status = response.status().as_u16();
+        let body = response.text().await.unwrap_or_default();
+        return Err(match request_id {
+            Some(id) => format!("API error {} (request {}): {}", status, id, body),
+            None => format!("API error {}: {}", status, body),
+        });
+    }
+
+    // Events are `event:` / `data:` line pairs; the data names its own type
+    let mut accumulator = StreamAccumulator::default();
+    let mut buffer: Vec<u8> = Vec::new();
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .map_err(|e| format!("Failed to read response stream: {}", e))?
+    {
+        buffer.extend_from_slice(&chunk);
+        while let Some(newline) = buffer.iter().position(|b| *b == b'\n') {
+            let line: Vec<u8> = buffer.drain(..=newline).collect();
+            let line = String::from_utf8_lossy(&line);
+            if let Some(data) = line.trim_end().strip_prefix("data:") {
+                if let Some(text) = accumulator.handle_event(data.trim_start())? {
+                    on_text(&text);
+                }
+            }
+        }
+    }
+
+    Ok(MessagesResponse {
+        request_id,
+        ..accumulator.finish()
+    })
+}
+
+/// Text of an MCP tool result: strings as-is, anything else pretty-printed.
+pub fn mcp_result_text(result: &serde_json::Value) -> String {
+    match result.as_str() {
+        Some(text) => text.to_string(),
+        None => serde_json::to_string_pretty(result).unwrap_or_else(|_| result.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn response(content: serde_json::Value, stop_reason: &str) -> MessagesResponse {
+        serde_json::from_value(json!({
+            "content": content,
+            "usage": { "input_tokens": 100, "output_tokens": 20 },
+            "stop_reason": stop_reason
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_message_content_serialization() {
+        let text_content = MessageContent::Text("Hello".to_string());
+        let json = serde_json::to_string(&text_content).unwrap();
+        assert_eq!(json, "\"Hello\"");
+
+        let blocks_content = MessageContent::Blocks(vec![ContentBlock::Text {
+            text: "Test".to_string(),
+        }]);
+        let json = serde_json::to_string(&blocks_content).unwrap();
+        assert!(json.contains("\"type\":\"text\""));
+    }
+
+    #[test]
+    fn test_stream_accumulator_builds_response() {
+        let events = [
+            json!({"type": "message_start", "message": {"usage": {"input_tokens": 50, "output_tokens": 1}}}),
+            json!({"type": "content_block_start", "index": 0, "content_block": {"type": "text", "text": ""}}),
+            json!({"type": "content_block_delta", "index": 0, "delta": {"type": "text_delta", "text": "Let me "}}),
+            json!({"type": "content_block_delta", "index": 0, "delta": {"type": "text_delta", "text": "check."}}),
+            json!({"type": "content_block_stop", "index": 0}),
+            json!({"type": "ping"}),
+            json!({"type": "content_block_start", "index": 1, "content_block": {"type": "tool_use", "id": "tool_1", "name": "fetch_webpage", "input": {}}}),
+            json!({"type": "content_block_delta", "index": 1, "delta": {"type": "input_json_delta", "partial_json": "{\"url\": \"https://"}}),
+            json!({"type": "content_block_delta", "index": 1, "delta": {"type": "input_json_delta", "partial_json": "example.com\"}"}}),
+            json!({"type": "content_block_stop", "index": 1}),
+            json!({"type": "content_block_start", "index": 2, "content_block": {"type": "text", "text": ""}}),
+            json!({"type": "content_block_delta", "index": 2, "delta": {"type": "text_delta", "text": "Done"}}),
+            json!({"type": "message_delta", "delta": {"stop_reason": "tool_use"}, "usage": {"output_tokens": 30}}),
+            json!({"type": "message_stop"}),
+        ];
+
+        let mut accumulator = StreamAccumulator::default();
+        let mut streamed = String::new();
+        for event in &events {
+            if let Some(text) = accumulator.handle_event(&event.to_string()).unwrap() {
+                streamed.push_str(&text);
+            }
+        }
+        let response = accumulator.finish();
+
+        assert_eq!(streamed, "Let me check.\nDone");
+        assert_eq!(response.text(), streamed);
+        assert_eq!(response.total_tokens(), 80);
+        assert!(!response.is_final());
+        assert_eq!(
+            response.tool_calls(),
+            vec![ToolCall {
+                id: "tool_1".to_string(),
+                name: "fetch_webpage".to_string(),
+                input: json!({"url": "https://example.com"}),
+            }]
+        );
+
+        let error = json!({"type": "error", "error": {"type": "overloaded_error", "message": "Overloaded"}});
+        assert_eq!(
+            StreamAccumulator::default().handle_event(&error.to_string()),
+            Err("API error: Overloaded".to_string())
+        );
+    }
+
+    #[test]
+    fn test_content_block_tool_result() {
+        let block = ContentBlock::tool_result("tool_123", Ok("Success".to_string()));
+        let json = serde_json::to_string(&block).unwrap();
+        assert!(json.contains("tool_result"));
+        assert!(json.contains("tool_123"));
+        assert!(!json.contains("is_error")); // Should be skipped when None
+    }
+
+    #[test]
+    fn test_content_block_tool_result_with_error() {
+        let block = ContentBlock::tool_result("tool_456", Err("Failed".to_string()));
+        let json = serde_json::to_string(&block).unwrap();
+        assert!(json.contains("is_error"));
+        assert!(json.contains("Error: Failed"));
+    }
+
+    #[test]
+    fn test_tool_use_response() {
+        let response = response(
+            json!([
+                { "type": "text", "text": "" },
+                { "type": "server_tool_use", "name": "web_search" },
+                { "type": "tool_use", "id": "t1", "name": "fetch_webpage", "input": { "url": "https://example.com" } }
+            ]),
+            "tool_use",
+        );
+        assert!(!response.is_final());
+        assert_eq!(response.total_tokens(), 120);
+        assert_eq!(
+            response.tool_calls(),
+            vec![ToolCall {
+                id: "t1".to_string(),
+                name: "fetch_webpage".to_string(),
+                input: json!({ "url": "https://example.com" }),
+            }]
+        );
+
+        // Only the tool_use block survives: empty text and server tools are dropped
+        let message = response.assistant_message();
+        assert_eq!(message.role, "assistant");
+        match message.content {
+            MessageContent::Blocks(blocks) => {
+                assert_eq!(blocks.len(), 1);
+                assert!(matches!(&blocks[0], ContentBlock::ToolUse { id, .. } if id == "t1"));
+            }
+            MessageContent::Text(_) => panic!("expected blocks"),
+        }
+    }
+
+    #[test]
+    fn test_final_response_text() {
+        let response = response(
+            json!([
+                { "type": "text", "text": "First" },
+                { "type": "text", "text": "Second" }
+            ]),
+            "end_turn",
+        );
+        assert!(response.is_final());
+        assert!(response.tool_calls().is_empty());
+        assert_eq!(response.text(), "First\nSecond");
+    }
+
+    #[test]
+    fn test_thinking_blocks() {
+        let response = response(
+            json!([
+                { "type": "thinking", "thinking": "Check the release notes first.", "signature": "sig1" },
+                { "type": "redacted_thinking", "data": "opaque" },
+                { "type": "tool_use", "id": "t1", "name": "fetch_webpage", "input": {} }
+            ]),
+            "tool_use",
+        );
+        assert_eq!(
+            response.thinking().as_deref(),
+            Some("Check the release notes first.")
+        );
+
+        // Thinking blocks go back to the API in order, ahead of the tool call
+        let json = serde_json::to_value(response.assistant_message()).unwrap();
+        assert_eq!(json["content"][0]["type"], "thinking");
+        assert_eq!(json["content"][0]["signature"], "sig1");
+        assert_eq!(json["content"][1]["type"], "redacted_thinking");
+        assert_eq!(json["content"][2]["type"], "tool_use");
+    }
+
+    #[test]
+    fn test_thinking_config() {
+        let request = MessagesRequest {
+            model: "claude".to_string(),
+            max_tokens: 6144,
+            messages: vec![Message::user("Hi")],
+            tools: None,
+            system: None,
+            thinking: Some(ThinkingConfig::enabled(4096)),
+            metadata: None,
+        };
+        let json = serde_json::to_value(&request).unwrap();
+        assert!(json.get("metadata").is_none());
+        assert_eq!(json["thinking"]["type"], "enabled");
+        assert_eq!(json["thinking"]["budget_tokens"], 4096);
+
+        assert_eq!(ThinkingConfig::enabled(100).budget_tokens, 1024);
+        assert!(response(json!([]), "end_turn").thinking().is_none());
+    }
+
+    #[test]
+    fn test_api_endpoint() {
+        let endpoint =
+            ApiEndpoint::new("https://gateway.example.com/anthropic/v1/", HashMap::new());
+        assert_eq!(
+            endpoint.messages_url(),
+            "https://gateway.example.com/anthropic/v1/messages"
+        );
+        assert_eq!(
+            ApiEndpoint::from_settings(&ResearchSettings::default()),
+            ApiEndpoint::default()
+        );
+        assert_eq!(
+            ApiEndpoint::default().url("/v1/models"),
+            "https://api.anthropic.com/v1/models"
+        );
+
+        assert!(validate_header("cf-aig-authorization", "Bearer abc").is_ok());
+        assert!(validate_header("bad header", "x").is_err());
+        assert!(validate_header("x-ok", "line\nbreak").is_err());
+    }
+
+    #[test]
+    fn test_request_metadata() {
+        assert!(ApiEndpoint::default().metadata().is_none());
+
+        let settings = ResearchSettings {
+            api_user_id: Some(" 3f2b9c1e ".to_string()),
+            ..ResearchSettings::default()
+        };
+        let request = MessagesRequest {
+            model: "claude".to_string(),
+            max_tokens: 16,
+            messages: vec![Message::user("Hi")],
+            tools: None,
+            system: None,
+            thinking: None,
+            metadata: ApiEndpoint::from_settings(&settings).metadata(),
+        };
+        let json = serde_json::to_value(&request).unwrap();
+        assert_eq!(json["metadata"]["user_id"], "3f2b9c1e");
+
+        let blank = ResearchSettings {
+            api_user_id: Some("  ".to_string()),
+            ..ResearchSettings::default()
+        };
+        assert!(ApiEndpoint::from_settings(&blank).user_id.is_none());
+
+        assert!(validate_user_id("3f2b9c1e").is_ok());
+        assert!(validate_user_id("me@example.com").is_err());
+        assert!(validate_user_id(&"a".repeat(MAX_USER_ID_LENGTH + 1)).is_err());
+    }
+
+    #[test]
+    fn test_check_messages_response() {
+        let message = r#"{"content":[{"type":"text","text":"p"}],"usage":{"input_tokens":8,"output_tokens":1},"stop_reason":"max_tokens"}"#;
+        assert!(check_messages_response(200, message).is_ok());
+        assert!(check_messages_response(200, "<html>Welcome</html>").is_err());
+
+        let error = r#"{"type":"error","error":{"type":"authentication_error","message":"invalid x-api-key"}}"#;
+        assert_eq!(
+            check_messages_response(401, error).unwrap_err(),
+            "Endpoint rejected the request: invalid x-api-key"
+        );
+        assert!(check_messages_response(404, "Not Found")
+            .unwrap_err()
+            .contains("HTTP 404"));
+    }
+
+    #[test]
+    fn test_mcp_result_text() {
+        assert_eq!(mcp_result_text(&json!("plain")), "plain");
+        assert!(mcp_result_text(&json!({ "a": 1 })).contains("\"a\": 1"));
+    }
+}
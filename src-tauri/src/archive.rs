@@ -0,0 +1,456 @@
+//! "Ask my archive": chat about the whole briefing history.
+//!
+//! Cards are stored as JSON inside each briefing, so there is no card index
+//! to query. Retrieval loads the cards of recent briefings and ranks them
+//! against the question with BM25. The best matches are given to Claude as
+//! context labelled with citation tags like `[briefing 42, card 3]`, and the
+//! tags Claude cites in its reply are returned with the answer so the app
+//! can link back to the cards.
+
+use chrono::Local;
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
+use tracing::{info, warn};
+
+use crate::agent_loop::{ApiEndpoint, Message, MessageContent, MessagesRequest, MessagesResponse};
+use crate::db;
+use crate::research::BriefingCard;
+
+/// Briefings searched, newest first.
+const MAX_BRIEFINGS: i64 = 500;
+
+/// Cards given to Claude as context by default.
+pub const DEFAULT_TOP_K: usize = 8;
+
+/// Characters of each card's detailed content included in the context.
+const CONTEXT_CHARS_PER_CARD: usize = 1500;
+
+/// Previous turns of the conversation sent with a question.
+const MAX_HISTORY: usize = 10;
+
+const MAX_TOKENS: u32 = 2048;
+
+/// BM25 term frequency saturation and length normalization.
+const BM25_K1: f64 = 1.2;
+const BM25_B: f64 = 0.75;
+
+/// Words too common to say anything about relevance.
+const STOPWORDS: [&str; 40] = [
+    "a", "about", "an", "and", "any", "are", "as", "at", "be", "by", "can", "did", "do", "does",
+    "for", "from", "has", "have", "how", "in", "is", "it", "its", "me", "my", "of", "on", "or",
+    "that", "the", "there", "this", "to", "was", "what", "when", "where", "which", "who", "with",
+];
+
+lazy_static! {
+    static ref CITATION_RE: Regex = Regex::new(r"\[briefing (\d+), card (\d+)\]").unwrap();
+}
+
+/// A card from a past briefing.
+#[derive(Debug, Clone)]
+pub struct ArchiveCard {
+    pub briefing_id: i64,
+    pub briefing_date: String,
+    pub card_index: i32,
+    pub card: BriefingCard,
+}
+
+impl ArchiveCard {
+    /// Tag Claude uses to cite this card (card numbers are 1-based).
+    pub fn citation_tag(&self) -> String {
+        format!(
+            "[briefing {}, card {}]",
+            self.briefing_id,
+            self.card_index + 1
+        )
+    }
+
+    /// Text searched by retrieval. The title counts twice.
+    fn search_text(&self) -> String {
+        let card = &self.card;
+        format!(
+            "{} {} {} {} {}",
+            card.title, card.title, card.topic, card.summary, card.detailed_content
+        )
+    }
+}
+
+/// A card cited in an archive answer.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ArchiveCitation {
+    pub briefing_id: i64,
+    pub card_index: i32,
+    pub title: String,
+    pub date: String,
+}
+
+/// A previous turn of an archive conversation (kept by the caller).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchiveTurn {
+    pub role: String,
+    pub content: String,
+}
+
+/// Claude's answer and the cards it cited.
+#[derive(Debug, Clone, Serialize)]
+pub struct ArchiveAnswer {
+    pub answer: String,
+    pub citations: Vec<ArchiveCitation>,
+    pub tokens_used: u32,
+}
+
+/// Cards of the most recent briefings, newest first.
+pub fn load_cards(conn: &rusqlite::Connection) -> Result<Vec<ArchiveCard>, String> {
+    let mut stmt = conn
+        .prepare("SELECT id, date, cards FROM briefings ORDER BY date DESC LIMIT ?1")
+        .map_err(|e| format!("Failed to prepare statement: {}", e))?;
+    let rows = stmt
+        .query_map([MAX_BRIEFINGS], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+            ))
+        })
+        .map_err(|e| format!("Query failed: {}", e))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("Failed to collect results: {}", e))?;
+
+    let mut cards = Vec::new();
+    for (briefing_id, date, cards_json) in rows {
+        let Ok(parsed) = serde_json::from_str::<Vec<BriefingCard>>(&cards_json) else {
+            continue;
+        };
+        cards.extend(
+            parsed
+                .into_iter()
+                .enumerate()
+                .map(|(index, card)| ArchiveCard {
+                    briefing_id,
+                    briefing_date: date.clone(),
+                    card_index: index as i32,
+                    card,
+                }),
+        );
+    }
+    Ok(cards)
+}
+
+/// Lowercase words of two or more characters, without stopwords.
+fn tokenize(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| word.chars().count() >= 2)
+        .map(|word| word.to_lowercase())
+        .filter(|word| !STOPWORDS.contains(&word.as_str()))
+        .collect()
+}
+
+/// The `k` cards most relevant to `query`, best first (BM25). Cards sharing
+/// no terms with the query are never returned.
+pub fn rank<'a>(cards: &'a [ArchiveCard], query: &str, k: usize) -> Vec<&'a ArchiveCard> {
+    let query_terms: HashSet<String> = tokenize(query).into_iter().collect();
+    if query_terms.is_empty() || cards.is_empty() {
+        return Vec::new();
+    }
+
+    let docs: Vec<Vec<String>> = cards.iter().map(|c| tokenize(&c.search_text())).collect();
+    let avg_len = docs.iter().map(Vec::len).sum::<usize>() as f64 / docs.len() as f64;
+    let mut doc_freq: HashMap<&str, usize> = HashMap::new();
+    for doc in &docs {
+        let unique: HashSet<&str> = doc.iter().map(String::as_str).collect();
+        for term in unique {
+            if query_terms.contains(term) {
+                *doc_freq.entry(term).or_default() += 1;
+            }
+        }
+    }
+
+    let n = docs.len() as f64;
+    let mut scored: Vec<(f64, &ArchiveCard)> = docs
+        .iter()
+        .zip(cards)
+        .filter_map(|(doc, card)| {
+            let mut term_freq: HashMap<&str, usize> = HashMap::new();
+            for term in doc {
+                if query_terms.contains(term.as_str()) {
+                    *term_freq.entry(term.as_str()).or_default() += 1;
+                }
+            }
+            let len_norm = 1.0 - BM25_B + BM25_B * doc.len() as f64 / avg_len.max(1.0);
+            let score: f64 = term_freq
+                .iter()
+                .map(|(term, &freq)| {
+                    let df = doc_freq[term] as f64;
+                    let idf = ((n - df + 0.5) / (df + 0.5) + 1.0).ln();
+                    let freq = freq as f64;
+                    idf * freq * (BM25_K1 + 1.0) / (freq + BM25_K1 * len_norm)
+                })
+                .sum();
+            (score > 0.0).then_some((score, card))
+        })
+        .collect();
+
+    // Stable sort: ties keep the newer card first
+    scored.sort_by(|a, b| b.0.total_cmp(&a.0));
+    scored.into_iter().take(k).map(|(_, card)| card).collect()
+}
+
+/// Context block listing the retrieved cards with their citation tags.
+fn build_context(hits: &[&ArchiveCard]) -> String {
+    if hits.is_empty() {
+        return "No cards in the archive matched the question.".to_string();
+    }
+    hits.iter()
+        .map(|hit| {
+            let card = &hit.card;
+            let details: String = card
+                .detailed_content
+                .chars()
+                .take(CONTEXT_CHARS_PER_CARD)
+                .collect();
+            let mut entry = format!(
+                "{} {} · Topic: {}\nTitle: {}\nSummary: {}",
+                hit.citation_tag(),
+                hit.briefing_date
+                    .get(..10)
+                    .unwrap_or(hit.briefing_date.as_str()),
+                card.topic,
+                card.title,
+                card.summary
+            );
+            if !details.is_empty() {
+                entry.push_str(&format!("\nDetails: {}", details));
+            }
+            if !card.sources.is_empty() {
+                entry.push_str(&format!("\nSources: {}", card.sources.join(", ")));
+            }
+            entry
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+fn build_system_prompt(context: &str) -> String {
+    format!(
+        r#"You answer questions using the user's archive of past research briefings. Today's date is {date}.
+
+Below are the briefing cards most relevant to the question, each starting with a citation tag. Base your answer on them and cite every card you use with its tag exactly as written, one tag per card (for example [briefing 42, card 3]). If the cards don't cover the question, say so. You may add general knowledge, but make clear when you do. Be concise.
+
+{context}"#,
+        date = Local::now().format("%B %d, %Y"),
+        context = context
+    )
+}
+
+/// Cards cited in `answer`, in order of first citation. Tags that don't
+/// match a retrieved card are ignored.
+pub(crate) fn cited_cards(answer: &str, hits: &[&ArchiveCard]) -> Vec<ArchiveCitation> {
+    let mut citations: Vec<ArchiveCitation> = Vec::new();
+    for captures in CITATION_RE.captures_iter(answer) {
+        let (Ok(briefing_id), Ok(card_number)) =
+            (captures[1].parse::<i64>(), captures[2].parse::<i32>())
+        else {
+            continue;
+        };
+        let Some(hit) = hits
+            .iter()
+            .find(|h| h.briefing_id == briefing_id && h.card_index + 1 == card_number)
+        else {
+            continue;
+        };
+        if !citations
+            .iter()
+            .any(|c| c.briefing_id == hit.briefing_id && c.card_index == hit.card_index)
+        {
+            citations.push(ArchiveCitation {
+                briefing_id: hit.briefing_id,
+                card_index: hit.card_index,
+                title: hit.card.title.clone(),
+                date: hit.briefing_date.clone(),
+            });
+        }
+    }
+    citations
+}
+
+/// Conversation to send: the last turns of `history` (starting with a user
+/// turn, as the API requires) followed by the question.
+fn build_messages(history: &[ArchiveTurn], question: &str) -> Vec<Message> {
+    let recent = &history[history.len().saturating_sub(MAX_HISTORY)..];
+    let mut messages: Vec<Message> = recent
+        .iter()
+        .filter(|turn| turn.role == "user" || turn.role == "assistant")
+        .skip_while(|turn| turn.role != "user")
+        .map(|turn| Message {
+            role: turn.role.clone(),
+            content: MessageContent::Text(turn.content.clone()),
+        })
+        .collect();
+    messages.push(Message::user(question));
+    messages
+}
+
+/// Answer `question` from the briefing archive, continuing the conversation
+/// in `history`. Follow-up questions are matched together with the previous
+/// question so "what about last month?" still finds the right cards.
+pub async fn ask_archive(
+    api_key: &str,
+    model: &str,
+    question: &str,
+    history: &[ArchiveTurn],
+    top_k: usize,
+) -> Result<ArchiveAnswer, String> {
+    let question = question.trim();
+    if question.is_empty() {
+        return Err("Question cannot be empty".to_string());
+    }
+
+    let cards = {
+        let conn = db::get_connection().map_err(|e| format!("Database error: {}", e))?;
+        load_cards(&conn)?
+    };
+    let retrieval_query = match history.iter().rev().find(|turn| turn.role == "user") {
+        Some(previous) => format!("{} {}", previous.content, question),
+        None => question.to_string(),
+    };
+    let hits = rank(&cards, &retrieval_query, top_k);
+    info!(
+        "Archive chat: {} of {} cards retrieved for context",
+        hits.len(),
+        cards.len()
+    );
+
+    let endpoint = ApiEndpoint::current();
+    let request = MessagesRequest {
+        model: model.to_string(),
+        max_tokens: MAX_TOKENS,
+        messages: build_messages(history, question),
+        tools: None,
+        system: Some(build_system_prompt(&build_context(&hits))),
+        thinking: None,
+        metadata: endpoint.metadata(),
+    };
+
+    let client = crate::http_client::client_builder()
+        .timeout(Duration::from_secs(120))
+        .connect_timeout(Duration::from_secs(10))
+        .build()
+        .map_err(|e| format!("Failed to build HTTP client: {}", e))?;
+    let response = endpoint
+        .authorize(client.post(endpoint.messages_url()), api_key)
+        .header("content-type", "application/json")
+        .json(&request)
+        .send()
+        .await
+        .map_err(|e| format!("HTTP request failed: {}", e))?;
+
+    if !response.status().is_success() {
+        let status = response.status().as_u16();
+        let body = response.text().await.unwrap_or_default();
+        return Err(format!("API error {}: {}", status, body));
+    }
+
+    let response: MessagesResponse = response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse response: {}", e))?;
+    let answer = response.text();
+    let tokens_used = response.total_tokens();
+
+    let conn = db::get_connection().map_err(|e| format!("Database error: {}", e))?;
+    if let Err(e) = db::record_api_usage(
+        &conn,
+        &crate::config::active_profile(),
+        "chat",
+        None,
+        tokens_used as i64,
+    ) {
+        warn!("Failed to record archive chat usage: {}", e);
+    }
+
+    Ok(ArchiveAnswer {
+        citations: cited_cards(&answer, &hits),
+        answer,
+        tokens_used,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn card(briefing_id: i64, card_index: i32, title: &str, summary: &str) -> ArchiveCard {
+        ArchiveCard {
+            briefing_id,
+            briefing_date: "2025-01-01T08:00:00".to_string(),
+            card_index,
+            card: serde_json::from_value(serde_json::json!({
+                "title": title,
+                "summary": summary,
+                "detailed_content": "",
+                "sources": [],
+                "suggested_next": null,
+                "relevance": "medium",
+                "topic": "Tech",
+            }))
+            .unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_rank_prefers_matching_cards() {
+        let cards = vec![
+            card(3, 0, "Rust 1.85 released", "The 2024 edition is stable."),
+            card(2, 0, "Python packaging news", "uv keeps getting faster."),
+            card(1, 1, "Async Rust roadmap", "Rust async closures land."),
+        ];
+
+        let hits = rank(&cards, "What happened with Rust async?", 5);
+        let ids: Vec<(i64, i32)> = hits.iter().map(|h| (h.briefing_id, h.card_index)).collect();
+        assert_eq!(ids, vec![(1, 1), (3, 0)]);
+
+        assert!(rank(&cards, "what is the", 5).is_empty());
+        assert_eq!(rank(&cards, "rust", 1).len(), 1);
+    }
+
+    #[test]
+    fn test_cited_cards_only_returns_retrieved_cards() {
+        let cards = [card(42, 2, "Rust 1.85", ""), card(7, 0, "uv 0.5", "")];
+        let hits: Vec<&ArchiveCard> = cards.iter().collect();
+        assert_eq!(hits[0].citation_tag(), "[briefing 42, card 3]");
+
+        let answer = "Rust shipped [briefing 42, card 3], and uv too [briefing 7, card 1]. \
+                      Again [briefing 42, card 3]; unknown [briefing 9, card 1].";
+        let citations = cited_cards(answer, &hits);
+        assert_eq!(
+            citations
+                .iter()
+                .map(|c| (c.briefing_id, c.card_index))
+                .collect::<Vec<_>>(),
+            vec![(42, 2), (7, 0)]
+        );
+    }
+
+    #[test]
+    fn test_build_messages_starts_with_user_turn() {
+        let history = vec![
+            ArchiveTurn {
+                role: "assistant".to_string(),
+                content: "Hi".to_string(),
+            },
+            ArchiveTurn {
+                role: "user".to_string(),
+                content: "Any Rust news?".to_string(),
+            },
+            ArchiveTurn {
+                role: "assistant".to_string(),
+                content: "Yes".to_string(),
+            },
+        ];
+        let messages = build_messages(&history, "And Python?");
+        let roles: Vec<&str> = messages.iter().map(|m| m.role.as_str()).collect();
+        assert_eq!(roles, vec!["user", "assistant", "user"]);
+    }
+}
@@ -0,0 +1,446 @@
+//! Readability-style article extraction for fetched webpages.
+//!
+//! Picks the main content block of a page (skipping navigation, footers,
+//! sidebars and other boilerplate) and pulls out the title, byline and
+//! published date, so `fetch_webpage` spends its character budget on the
+//! article itself.
+
+use lazy_static::lazy_static;
+use regex::Regex;
+use scraper::{ElementRef, Html, Node, Selector};
+
+/// Elements whose content is never part of the article text.
+const SKIP_TAGS: &[&str] = &[
+    "script", "style", "noscript", "nav", "footer", "aside", "form", "button", "iframe", "svg",
+    "header", "select", "template",
+];
+
+/// Elements that each produce their own block of text.
+const BLOCK_TAGS: &[&str] = &[
+    "p",
+    "pre",
+    "blockquote",
+    "figcaption",
+    "li",
+    "td",
+    "th",
+    "dd",
+];
+
+/// Minimum characters of paragraph text for a semantic container to be trusted.
+const MIN_ARTICLE_CHARS: usize = 200;
+
+lazy_static! {
+    static ref BOILERPLATE_RE: Regex = Regex::new(
+        r"(?i)\b(nav|navbar|menu|footer|sidebar|comments?|share|social|cookie|banner|advert|ads?|promo|related|newsletter|subscribe|breadcrumbs?|popup|modal)\b"
+    )
+    .unwrap();
+    static ref WHITESPACE_RE: Regex = Regex::new(r"\s+").unwrap();
+    static ref ARTICLE_SEL: Selector = Selector::parse("article").unwrap();
+    static ref MAIN_SEL: Selector = Selector::parse(r#"main, [role="main"]"#).unwrap();
+    static ref CANDIDATE_SEL: Selector = Selector::parse("div, section, td").unwrap();
+    static ref PARAGRAPH_SEL: Selector = Selector::parse("p").unwrap();
+    static ref BODY_SEL: Selector = Selector::parse("body").unwrap();
+    static ref TITLE_SEL: Selector = Selector::parse(
+        r#"meta[property="og:title"], meta[name="twitter:title"]"#
+    )
+    .unwrap();
+    static ref TITLE_TAG_SEL: Selector = Selector::parse("title").unwrap();
+    static ref H1_SEL: Selector = Selector::parse("h1").unwrap();
+    static ref BYLINE_META_SEL: Selector = Selector::parse(
+        r#"meta[name="author"], meta[property="article:author"], meta[name="byl"]"#
+    )
+    .unwrap();
+    static ref BYLINE_SEL: Selector = Selector::parse(
+        r#"[rel="author"], [itemprop="author"], .byline, .author"#
+    )
+    .unwrap();
+    static ref PUBLISHED_META_SEL: Selector = Selector::parse(
+        r#"meta[property="article:published_time"], meta[itemprop="datePublished"], meta[name="date"], meta[name="pubdate"]"#
+    )
+    .unwrap();
+    static ref TIME_SEL: Selector = Selector::parse("time[datetime]").unwrap();
+}
+
+/// Extracted article content and metadata.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Article {
+    pub title: Option<String>,
+    pub byline: Option<String>,
+    pub published: Option<String>,
+    pub text: String,
+}
+
+impl Article {
+    /// Format the article for a tool result, truncating the body to `max_chars`.
+    pub fn to_tool_output(&self, max_chars: usize) -> String {
+        let mut header = String::new();
+        if let Some(ref title) = self.title {
+            header.push_str(&format!("Title: {}\n", title));
+        }
+        if let Some(ref byline) = self.byline {
+            header.push_str(&format!("By: {}\n", byline));
+        }
+        if let Some(ref published) = self.published {
+            header.push_str(&format!("Published: {}\n", published));
+        }
+        if !header.is_empty() {
+            header.push('\n');
+        }
+
+        // Use character count, not byte index, to avoid UTF-8 panics
+        let char_count = self.text.chars().count();
+        if char_count > max_chars {
+            let truncated: String = self.text.chars().take(max_chars).collect();
+            format!(
+                "{}{}...\n\n[Content truncated, {} total characters]",
+                header, truncated, char_count
+            )
+        } else {
+            format!("{}{}", header, self.text)
+        }
+    }
+}
+
+/// Extract the main article from an HTML document.
+pub fn extract_article(html: &str) -> Article {
+    let document = Html::parse_document(html);
+
+    let mut text = find_content_root(&document)
+        .map(|root| {
+            let mut blocks = Vec::new();
+            let mut inline = String::new();
+            collect_blocks(root, &mut blocks, &mut inline);
+            flush_inline(&mut blocks, &mut inline);
+            blocks.join("\n\n")
+        })
+        .unwrap_or_default();
+
+    // Fall back to all visible body text for pages without a recognizable structure
+    if text.is_empty() {
+        if let Some(body) = document.select(&BODY_SEL).next() {
+            let mut blocks = Vec::new();
+            let mut inline = String::new();
+            collect_blocks(body, &mut blocks, &mut inline);
+            flush_inline(&mut blocks, &mut inline);
+            text = blocks.join("\n\n");
+        }
+    }
+
+    Article {
+        title: extract_title(&document),
+        byline: extract_byline(&document),
+        published: extract_published(&document),
+        text,
+    }
+}
+
+/// Collapse runs of whitespace into single spaces.
+fn normalize(s: &str) -> String {
+    WHITESPACE_RE.replace_all(s, " ").trim().to_string()
+}
+
+fn element_text(el: ElementRef) -> String {
+    normalize(&el.text().collect::<String>())
+}
+
+fn is_boilerplate(el: ElementRef) -> bool {
+    let value = el.value();
+    if value.attr("aria-hidden") == Some("true") || value.attr("hidden").is_some() {
+        return true;
+    }
+    if matches!(
+        value.attr("role"),
+        Some("navigation") | Some("banner") | Some("contentinfo") | Some("complementary")
+    ) {
+        return true;
+    }
+    let class_and_id = format!(
+        "{} {}",
+        value.attr("class").unwrap_or(""),
+        value.attr("id").unwrap_or("")
+    );
+    BOILERPLATE_RE.is_match(&class_and_id)
+}
+
+/// Total length of paragraph text inside an element.
+fn paragraph_chars(el: ElementRef) -> usize {
+    el.select(&PARAGRAPH_SEL)
+        .map(|p| element_text(p).len())
+        .sum()
+}
+
+/// Pick the element most likely to hold the main content.
+fn find_content_root(document: &Html) -> Option<ElementRef<'_>> {
+    // Prefer semantic containers when they hold real content
+    for selector in [&*ARTICLE_SEL, &*MAIN_SEL] {
+        let best = document
+            .select(selector)
+            .map(|el| (paragraph_chars(el), el))
+            .max_by_key(|(chars, _)| *chars);
+        if let Some((chars, el)) = best {
+            if chars >= MIN_ARTICLE_CHARS {
+                return Some(el);
+            }
+        }
+    }
+
+    // Otherwise score containers by the paragraph text directly inside them
+    document
+        .select(&CANDIDATE_SEL)
+        .filter(|el| !is_boilerplate(*el))
+        .map(|el| {
+            let score: usize = el
+                .children()
+                .filter_map(ElementRef::wrap)
+                .filter(|child| child.value().name() == "p")
+                .map(|p| element_text(p).len())
+                .sum();
+            (score, el)
+        })
+        .filter(|(score, _)| *score > 0)
+        .max_by_key(|(score, _)| *score)
+        .map(|(_, el)| el)
+}
+
+fn flush_inline(blocks: &mut Vec<String>, inline: &mut String) {
+    let text = normalize(inline);
+    if !text.is_empty() {
+        blocks.push(text);
+    }
+    inline.clear();
+}
+
+/// Walk an element, emitting headings and block elements as separate blocks.
+fn collect_blocks(el: ElementRef, blocks: &mut Vec<String>, inline: &mut String) {
+    for child in el.children() {
+        match child.value() {
+            Node::Text(text) => {
+                inline.push_str(text);
+            }
+            Node::Element(element) => {
+                let Some(child_el) = ElementRef::wrap(child) else {
+                    continue;
+                };
+                let name = element.name();
+                if SKIP_TAGS.contains(&name) || is_boilerplate(child_el) {
+                    continue;
+                }
+
+                if name == "br" {
+                    inline.push(' ');
+                } else if matches!(name, "h1" | "h2" | "h3" | "h4" | "h5" | "h6") {
+                    flush_inline(blocks, inline);
+                    let heading = element_text(child_el);
+                    if !heading.is_empty() {
+                        blocks.push(format!("## {}", heading));
+                    }
+                } else if BLOCK_TAGS.contains(&name) {
+                    flush_inline(blocks, inline);
+                    let text = element_text(child_el);
+                    if !text.is_empty() {
+                        if name == "li" {
+                            blocks.push(format!("- {}", text));
+                        } else {
+                            blocks.push(text);
+                        }
+                    }
+                } else {
+                    collect_blocks(child_el, blocks, inline);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// First non-empty `content` attribute among matching meta tags.
+fn meta_content(document: &Html, selector: &Selector) -> Option<String> {
+    document
+        .select(selector)
+        .filter_map(|el| el.value().attr("content"))
+        .map(normalize)
+        .find(|s| !s.is_empty())
+}
+
+fn extract_title(document: &Html) -> Option<String> {
+    meta_content(document, &TITLE_SEL)
+        .or_else(|| {
+            document
+                .select(&TITLE_TAG_SEL)
+                .map(element_text)
+                .find(|s| !s.is_empty())
+        })
+        .or_else(|| {
+            document
+                .select(&H1_SEL)
+                .map(element_text)
+                .find(|s| !s.is_empty())
+        })
+}
+
+fn extract_byline(document: &Html) -> Option<String> {
+    meta_content(document, &BYLINE_META_SEL)
+        // article:author is often a profile URL rather than a name
+        .filter(|s| !s.starts_with("http"))
+        .or_else(|| {
+            document
+                .select(&BYLINE_SEL)
+                .map(element_text)
+                .find(|s| !s.is_empty() && s.len() < 100)
+        })
+}
+
+fn extract_published(document: &Html) -> Option<String> {
+    meta_content(document, &PUBLISHED_META_SEL).or_else(|| {
+        document
+            .select(&TIME_SEL)
+            .filter_map(|el| el.value().attr("datetime"))
+            .map(normalize)
+            .find(|s| !s.is_empty())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_simple_page() {
+        let html =
+            r#"<html><head><title>Test</title></head><body><p>Hello World</p></body></html>"#;
+        let article = extract_article(html);
+        assert_eq!(article.title.as_deref(), Some("Test"));
+        assert!(article.text.contains("Hello World"));
+        assert!(!article.text.contains("<p>"));
+    }
+
+    #[test]
+    fn test_extract_strips_scripts_styles_and_decodes_entities() {
+        let html = r#"
+            <html>
+            <head><style>body { color: red; }</style></head>
+            <body>
+                <script>alert('test');</script>
+                <h1>Hello World</h1>
+                <p>This is a &lt;test&gt; with &amp; entities.</p>
+            </body>
+            </html>
+        "#;
+
+        let article = extract_article(html);
+        assert!(article.text.contains("Hello World"));
+        assert!(article.text.contains("This is a <test> with & entities"));
+        assert!(!article.text.contains("alert"));
+        assert!(!article.text.contains("color: red"));
+    }
+
+    #[test]
+    fn test_extract_skips_navigation_and_footer() {
+        let body = "Rust 2.0 was announced today with a focus on ergonomics. ".repeat(5);
+        let html = format!(
+            r#"<html><body>
+                <nav><a href="/">Home</a><a href="/about">About us</a></nav>
+                <div class="sidebar"><p>Trending stories you might like</p></div>
+                <article><h2>Big news</h2><p>{}</p><p>Second paragraph.</p></article>
+                <footer><p>Copyright 2025 Example Corp</p></footer>
+            </body></html>"#,
+            body
+        );
+
+        let article = extract_article(&html);
+        assert!(article.text.contains("## Big news"));
+        assert!(article.text.contains("Rust 2.0 was announced"));
+        assert!(article.text.contains("Second paragraph."));
+        assert!(!article.text.contains("About us"));
+        assert!(!article.text.contains("Trending stories"));
+        assert!(!article.text.contains("Copyright"));
+    }
+
+    #[test]
+    fn test_extract_scores_content_div_without_semantic_tags() {
+        let html = r#"<html><body>
+            <div id="menu"><p>Menu item</p></div>
+            <div class="post-body"><p>First paragraph of the post.</p><p>Second paragraph of the post.</p></div>
+        </body></html>"#;
+
+        let article = extract_article(html);
+        assert!(article.text.contains("First paragraph of the post."));
+        assert!(!article.text.contains("Menu item"));
+    }
+
+    #[test]
+    fn test_extract_metadata() {
+        let html = r#"<html><head>
+            <title>Fallback title</title>
+            <meta property="og:title" content="OG Title">
+            <meta name="author" content="Jane Doe">
+            <meta property="article:published_time" content="2025-01-15T10:00:00Z">
+        </head><body><p>Body</p></body></html>"#;
+
+        let article = extract_article(html);
+        assert_eq!(article.title.as_deref(), Some("OG Title"));
+        assert_eq!(article.byline.as_deref(), Some("Jane Doe"));
+        assert_eq!(article.published.as_deref(), Some("2025-01-15T10:00:00Z"));
+    }
+
+    #[test]
+    fn test_extract_published_from_time_element() {
+        let html = r#"<html><body><article>
+            <span class="byline">By John Smith</span>
+            <time datetime="2025-02-01">Feb 1</time>
+            <p>Text</p>
+        </article></body></html>"#;
+
+        let article = extract_article(html);
+        assert_eq!(article.byline.as_deref(), Some("By John Smith"));
+        assert_eq!(article.published.as_deref(), Some("2025-02-01"));
+    }
+
+    #[test]
+    fn test_extract_list_items() {
+        let html = r#"<html><body><ul><li>One</li><li>Two</li></ul></body></html>"#;
+        let article = extract_article(html);
+        assert!(article.text.contains("- One"));
+        assert!(article.text.contains("- Two"));
+    }
+
+    #[test]
+    fn test_to_tool_output_header_and_truncation() {
+        let article = Article {
+            title: Some("Title".to_string()),
+            byline: None,
+            published: Some("2025-01-01".to_string()),
+            text: "é".repeat(20),
+        };
+
+        let output = article.to_tool_output(10);
+        assert!(output.starts_with("Title: Title\nPublished: 2025-01-01\n\n"));
+        assert!(output.contains("[Content truncated, 20 total characters]"));
+
+        let full = article.to_tool_output(100);
+        assert!(full.ends_with(&"é".repeat(20)));
+        assert!(!full.contains("truncated"));
+    }
+
+    proptest::proptest! {
+        #[test]
+        fn prop_extract_article_never_panics(html in "\\PC*") {
+            let article = extract_article(&html);
+            let _ = article.to_tool_output(100);
+        }
+
+        #[test]
+        fn prop_extract_article_keeps_paragraph_text(
+            words in proptest::collection::vec("[a-zA-Z0-9éü]{1,12}", 1..40),
+            noise in "[^<>&]*",
+        ) {
+            let text = words.join(" ");
+            let html = format!(
+                "<html><body><nav>{}</nav><article><p>{}</p></article></body></html>",
+                noise, text
+            );
+            proptest::prop_assert!(extract_article(&html).text.contains(&text));
+        }
+    }
+}
@@ -0,0 +1,240 @@
+//! Anthropic Message Batches API client.
+//!
+//! Batched requests cost half as much as interactive ones but may take up
+//! to a day to process, so they are only used for scheduled research where
+//! latency doesn't matter. A batch is created with one request per
+//! `custom_id`, polled until it has ended, and its results are read from a
+//! JSONL file. See `ResearchAgent::set_batch_deadline` for how research
+//! rounds are batched.
+
+use crate::agent_loop::{ApiEndpoint, MessagesRequest, MessagesResponse};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Message Batches API path, relative to the API base URL.
+pub const BATCHES_PATH: &str = "/v1/messages/batches";
+
+/// One request in a batch.
+#[derive(Debug, Serialize)]
+struct BatchRequest<'a> {
+    custom_id: &'a str,
+    params: &'a MessagesRequest,
+}
+
+/// Requests of a batch that have finished processing.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct RequestCounts {
+    #[serde(default)]
+    pub succeeded: u32,
+    #[serde(default)]
+    pub errored: u32,
+}
+
+/// A message batch as returned by the API.
+#[derive(Debug, Clone, Deserialize)]
+pub struct MessageBatch {
+    pub id: String,
+    /// "in_progress", "canceling" or "ended"
+    pub processing_status: String,
+    #[serde(default)]
+    pub request_counts: RequestCounts,
+    /// Set once the batch has ended
+    #[serde(default)]
+    pub results_url: Option<String>,
+}
+
+impl MessageBatch {
+    pub fn is_ended(&self) -> bool {
+        self.processing_status == "ended"
+    }
+}
+
+/// Outcome of one request in an ended batch.
+#[derive(Debug)]
+pub enum BatchResult {
+    Succeeded(MessagesResponse),
+    /// Errored, canceled or expired, with a reason
+    Failed(String),
+}
+
+/// Client for the Message Batches API.
+pub struct BatchClient<'a> {
+    client: &'a Client,
+    api_key: &'a str,
+    endpoint: &'a ApiEndpoint,
+}
+
+impl<'a> BatchClient<'a> {
+    pub fn new(client: &'a Client, api_key: &'a str, endpoint: &'a ApiEndpoint) -> Self {
+        Self {
+            client,
+            api_key,
+            endpoint,
+        }
+    }
+
+    fn batches_url(&self) -> String {
+        self.endpoint.url(BATCHES_PATH)
+    }
+
+    async fn send(&self, request: reqwest::RequestBuilder) -> Result<String, String> {
+        let response = self
+            .endpoint
+            .authorize(request, self.api_key)
+            .send()
+            .await
+            .map_err(|e| format!("Batch API request failed: {}", e))?;
+
+        let status = response.status();
+        let body = response
+            .text()
+            .await
+            .map_err(|e| format!("Failed to read Batch API response: {}", e))?;
+        if !status.is_success() {
+            return Err(format!("Batch API error {}: {}", status.as_u16(), body));
+        }
+        Ok(body)
+    }
+
+    async fn send_json(&self, request: reqwest::RequestBuilder) -> Result<MessageBatch, String> {
+        let body = self.send(request).await?;
+        serde_json::from_str(&body).map_err(|e| format!("Failed to parse batch: {}", e))
+    }
+
+    /// Submit a batch of requests, keyed by custom ID.
+    pub async fn create(
+        &self,
+        requests: &[(String, MessagesRequest)],
+    ) -> Result<MessageBatch, String> {
+        let requests: Vec<BatchRequest> = requests
+            .iter()
+            .map(|(custom_id, params)| BatchRequest { custom_id, params })
+            .collect();
+        self.send_json(
+            self.client
+                .post(self.batches_url())
+                .json(&serde_json::json!({ "requests": requests })),
+        )
+        .await
+    }
+
+    /// Current state of a batch.
+    pub async fn get(&self, batch_id: &str) -> Result<MessageBatch, String> {
+        self.send_json(
+            self.client
+                .get(format!("{}/{}", self.batches_url(), batch_id)),
+        )
+        .await
+    }
+
+    /// Ask the API to stop processing a batch. Requests already finished
+    /// still appear in its results.
+    pub async fn cancel(&self, batch_id: &str) -> Result<MessageBatch, String> {
+        self.send_json(
+            self.client
+                .post(format!("{}/{}/cancel", self.batches_url(), batch_id)),
+        )
+        .await
+    }
+
+    /// Results of an ended batch, keyed by custom ID.
+    pub async fn results(
+        &self,
+        batch: &MessageBatch,
+    ) -> Result<HashMap<String, BatchResult>, String> {
+        let url = batch
+            .results_url
+            .as_deref()
+            .ok_or_else(|| format!("Batch {} has no results yet", batch.id))?;
+        let body = self.send(self.client.get(url)).await?;
+        Ok(parse_results(&body))
+    }
+}
+
+/// Parse a batch results file (one JSON object per line). Lines that can't
+/// be parsed are skipped; their requests are treated as missing.
+pub fn parse_results(jsonl: &str) -> HashMap<String, BatchResult> {
+    jsonl
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| serde_json::from_str::<serde_json::Value>(line).ok())
+        .filter_map(|entry| {
+            let custom_id = entry["custom_id"].as_str()?.to_string();
+            let result = &entry["result"];
+            let outcome = match result["type"].as_str() {
+                Some("succeeded") => {
+                    match serde_json::from_value::<MessagesResponse>(result["message"].clone()) {
+                        Ok(message) => BatchResult::Succeeded(message),
+                        Err(e) => BatchResult::Failed(format!("Unreadable message: {}", e)),
+                    }
+                }
+                Some("errored") => BatchResult::Failed(
+                    result["error"]["error"]["message"]
+                        .as_str()
+                        .or(result["error"]["message"].as_str())
+                        .unwrap_or("Request errored")
+                        .to_string(),
+                ),
+                Some(other) => BatchResult::Failed(format!("Request {}", other)),
+                None => return None,
+            };
+            Some((custom_id, outcome))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_results() {
+        let jsonl = r#"{"custom_id":"topic-0","result":{"type":"succeeded","message":{"id":"msg_1","type":"message","role":"assistant","content":[{"type":"text","text":"Findings"}],"stop_reason":"end_turn","usage":{"input_tokens":10,"output_tokens":5}}}}
+{"custom_id":"topic-1","result":{"type":"errored","error":{"type":"error","error":{"type":"invalid_request_error","message":"max_tokens too large"}}}}
+
+{"custom_id":"topic-2","result":{"type":"expired"}}
+not json"#;
+
+        let results = parse_results(jsonl);
+        assert_eq!(results.len(), 3);
+        match &results["topic-0"] {
+            BatchResult::Succeeded(message) => {
+                assert_eq!(message.text(), "Findings");
+                assert_eq!(message.total_tokens(), 15);
+            }
+            BatchResult::Failed(e) => panic!("expected success, got {}", e),
+        }
+        assert!(
+            matches!(&results["topic-1"], BatchResult::Failed(e) if e == "max_tokens too large")
+        );
+        assert!(matches!(&results["topic-2"], BatchResult::Failed(e) if e == "Request expired"));
+    }
+
+    #[test]
+    fn test_batch_request_serialization() {
+        let params = MessagesRequest {
+            model: "claude-haiku-4-5-20251001".to_string(),
+            max_tokens: 2048,
+            messages: vec![crate::agent_loop::Message::user("Research Rust")],
+            tools: None,
+            system: None,
+            thinking: None,
+            metadata: None,
+        };
+        let json = serde_json::to_value(BatchRequest {
+            custom_id: "topic-0",
+            params: &params,
+        })
+        .unwrap();
+        assert_eq!(json["custom_id"], "topic-0");
+        assert_eq!(json["params"]["messages"][0]["content"], "Research Rust");
+
+        let batch: MessageBatch = serde_json::from_str(
+            r#"{"id":"msgbatch_1","processing_status":"ended","request_counts":{"succeeded":2},"results_url":"https://example.com/results"}"#,
+        )
+        .unwrap();
+        assert!(batch.is_ended());
+        assert_eq!(batch.request_counts.succeeded, 2);
+    }
+}
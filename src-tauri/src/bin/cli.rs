@@ -7,13 +7,22 @@ use clap::{Parser, Subcommand};
 use colored::Colorize;
 use comfy_table::{presets::UTF8_FULL, ContentArrangement, Table};
 use scopeguard::defer;
+use std::io::IsTerminal;
+use tracing_subscriber::filter::LevelFilter;
+use tracing_subscriber::prelude::*;
 use uuid::Uuid;
 
+use claudius::card_actions::{self, ActionOutcome};
+use claudius::exit_code::ExitCode;
+use claudius::mcp_client::namespaced_tool_name;
+use claudius::meta_briefing::{self, MetaPeriod};
 use claudius::{
-    db, delete_api_key, get_config_dir, has_api_key, image_gen, read_api_key, read_mcp_servers,
-    read_openai_api_key, read_settings, research_state, validate_api_key, write_api_key,
-    write_mcp_servers, write_settings, Briefing, BriefingCard, MCPServer, MCPServersConfig,
-    ResearchAgent, Topic,
+    archive, chat, config, daemon, db, delete_api_key, get_config_dir, has_api_key, http_client,
+    image_gen, integrations, log_sink, mcp_catalog, models, read_api_key, read_later,
+    read_mcp_servers, read_notion_token, read_openai_api_key, read_settings, releases,
+    research_state, secret_store, server, settings_schema, stats, term_markdown, tts,
+    validate_api_key, write_api_key, write_mcp_servers, write_settings, Briefing, BriefingCard,
+    MCPServer, MCPServersConfig, ResearchAgent, Topic,
 };
 
 const VERSION: &str = env!("CARGO_PKG_VERSION");
@@ -36,6 +45,19 @@ struct Cli {
     #[arg(long, global = true)]
     json: bool,
 
+    /// Credential profile to use instead of the active_profile setting
+    #[arg(long, global = true)]
+    profile: Option<String>,
+
+    /// Directory for settings, data and caches instead of the defaults
+    /// (also set by CLAUDIUS_HOME)
+    #[arg(long, global = true, value_name = "DIR")]
+    config_dir: Option<std::path::PathBuf>,
+
+    /// Workspace to use instead of the active one
+    #[arg(long, global = true)]
+    workspace: Option<String>,
+
     #[command(subcommand)]
     command: Commands,
 }
@@ -77,6 +99,75 @@ enum Commands {
         #[command(subcommand)]
         action: HousekeepingAction,
     },
+
+    /// Work with saved cards
+    Cards {
+        #[command(subcommand)]
+        action: CardAction,
+    },
+
+    /// Ask a question about your whole briefing archive
+    Ask {
+        /// The question
+        question: String,
+        /// Number of past cards given to Claude as context
+        #[arg(short = 'k', long, default_value_t = archive::DEFAULT_TOP_K)]
+        top: usize,
+    },
+
+    /// Work with card chats
+    Chat {
+        #[command(subcommand)]
+        action: ChatAction,
+    },
+
+    /// Run scheduled research without the desktop app (see schedule_cron)
+    Daemon {
+        #[command(subcommand)]
+        action: Option<DaemonAction>,
+    },
+
+    /// Manage workspaces: separate topics, briefings and settings
+    Workspace {
+        #[command(subcommand)]
+        action: WorkspaceAction,
+    },
+
+    /// Research streaks, briefings per week, cards per topic, token spend and tool usage
+    Stats {
+        /// Days to cover (streaks always cover the whole history)
+        #[arg(short, long, default_value_t = stats::DEFAULT_DAYS)]
+        days: i64,
+    },
+
+    /// Check the config, database, API keys, MCP servers and CLI install
+    Doctor,
+
+    /// Update the CLI to the latest release of the release channel
+    Update {
+        /// Only show the available update and its changelog
+        #[arg(long)]
+        check: bool,
+        /// Channel to update from instead of the release_channel setting (stable, beta)
+        #[arg(long)]
+        channel: Option<String>,
+    },
+
+    /// Serve the HTTP API for home automation and other apps
+    Serve {
+        /// Port to listen on
+        #[arg(short, long, default_value_t = server::DEFAULT_PORT)]
+        port: u16,
+        /// Address to bind (use 0.0.0.0 to accept connections from other machines)
+        #[arg(long, default_value = "127.0.0.1")]
+        host: String,
+        /// API token, defaults to the stored token (generated on first run)
+        #[arg(long)]
+        token: Option<String>,
+        /// Print the API token on startup
+        #[arg(long)]
+        show_token: bool,
+    },
 }
 
 // ============================================================================
@@ -94,6 +185,48 @@ enum TopicAction {
         /// Optional description
         #[arg(short, long)]
         description: Option<String>,
+        /// Preferred source to check first (repeatable, e.g. --source lobste.rs)
+        #[arg(short, long = "source")]
+        sources: Vec<String>,
+    },
+    /// Estimate the tools and cost of researching a topic before adding it
+    Preview {
+        /// Topic name
+        name: String,
+        /// Optional description
+        #[arg(short, long)]
+        description: Option<String>,
+        /// Preferred source to check first (repeatable)
+        #[arg(short, long = "source")]
+        sources: Vec<String>,
+    },
+    /// Show or edit a topic's preferred sources
+    Sources {
+        /// Topic ID or name
+        id: String,
+        /// Add a preferred source (repeatable)
+        #[arg(long)]
+        add: Vec<String>,
+        /// Remove a preferred source (repeatable)
+        #[arg(long)]
+        remove: Vec<String>,
+        /// Remove all preferred sources
+        #[arg(long)]
+        clear: bool,
+    },
+    /// Show or edit the MCP servers bound to a topic
+    Mcp {
+        /// Topic ID or name
+        id: String,
+        /// Bind an MCP server, or one tool as "server/tool" (repeatable)
+        #[arg(long)]
+        add: Vec<String>,
+        /// Unbind an MCP server or tool (repeatable)
+        #[arg(long)]
+        remove: Vec<String>,
+        /// Unbind all MCP servers
+        #[arg(long)]
+        clear: bool,
     },
     /// Remove a topic
     Remove {
@@ -110,6 +243,63 @@ enum TopicAction {
         /// Topic ID or name
         id: String,
     },
+    /// Pause a topic for a few days or for the next run without disabling it
+    Snooze {
+        /// Topic ID or name
+        id: String,
+        /// Days to snooze the topic for
+        #[arg(short, long, default_value_t = 7, conflicts_with_all = ["next", "off"])]
+        days: u32,
+        /// Skip only the next research run
+        #[arg(long, conflicts_with = "off")]
+        next: bool,
+        /// Clear the snooze and skip so the topic is researched again
+        #[arg(long)]
+        off: bool,
+    },
+    /// Show or override a topic's dedup window and threshold
+    Dedup {
+        /// Topic ID or name
+        id: String,
+        /// Days of past briefings to check for duplicates (0 turns dedup off)
+        #[arg(long, conflicts_with = "reset")]
+        days: Option<i32>,
+        /// Similarity threshold from 0.0 to 1.0 (0 turns dedup off)
+        #[arg(long, conflicts_with = "reset")]
+        threshold: Option<f64>,
+        /// Go back to the global dedup settings
+        #[arg(long)]
+        reset: bool,
+    },
+    /// Show how much each topic's findings change from run to run
+    Stats {
+        /// Number of recent runs to average over
+        #[arg(short, long, default_value_t = 5)]
+        runs: usize,
+    },
+    /// Show every card saved for a topic in chronological order
+    Timeline {
+        /// Topic ID or name (any card topic works, even for deleted topics)
+        name: String,
+        /// Also have Claude write a narrative timeline of the developments
+        #[arg(long)]
+        narrate: bool,
+        /// Only include briefings from the last N days
+        #[arg(short, long)]
+        days: Option<u32>,
+    },
+    /// Show topics suggested from your bookmarks, ratings and chats
+    Suggest {
+        /// Analyze recent activity for new suggestions now
+        #[arg(long)]
+        refresh: bool,
+        /// Accept a suggestion by ID, adding it as a topic
+        #[arg(long, conflicts_with = "dismiss")]
+        accept: Option<i64>,
+        /// Dismiss a suggestion by ID so it isn't suggested again
+        #[arg(long)]
+        dismiss: Option<i64>,
+    },
 }
 
 // ============================================================================
@@ -123,17 +313,72 @@ enum BriefingAction {
         /// Maximum number of briefings to show
         #[arg(short, long, default_value = "10")]
         limit: i32,
+        /// Only briefings from this day on (YYYY-MM-DD)
+        #[arg(long)]
+        from: Option<String>,
+        /// Only briefings up to and including this day (YYYY-MM-DD)
+        #[arg(long)]
+        to: Option<String>,
+        /// Only briefings with a card on this topic
+        #[arg(short, long)]
+        topic: Option<String>,
+        /// Only briefings with a card at least this relevant (low, medium, high)
+        #[arg(long, value_parser = ["low", "medium", "high"])]
+        min_relevance: Option<String>,
+        /// Only briefings written by this model
+        #[arg(short, long)]
+        model: Option<String>,
     },
     /// Show a specific briefing
     Show {
         /// Briefing ID
         id: i64,
+        /// Print card text as raw Markdown instead of rendering it
+        #[arg(long)]
+        plain: bool,
+        /// Only this card (1-based, as numbered in the output)
+        #[arg(short, long)]
+        card: Option<usize>,
+        /// Step through the cards one at a time
+        #[arg(short, long, conflicts_with = "card")]
+        interactive: bool,
+    },
+    /// Open a briefing in the desktop app
+    Open {
+        /// Briefing ID
+        id: i64,
+        /// Scroll to this card (1-based, as shown by `briefings show`)
+        #[arg(short, long)]
+        card: Option<usize>,
     },
     /// Search briefings
     Search {
         /// Search query
         query: String,
     },
+    /// Delete a briefing with its images, audio, chat history and bookmarks
+    Delete {
+        /// Briefing ID
+        id: i64,
+        /// Don't ask for confirmation
+        #[arg(short, long)]
+        yes: bool,
+    },
+    /// Delete every briefing dated before a day
+    Prune {
+        /// Delete briefings before this date (YYYY-MM-DD)
+        #[arg(long)]
+        before: String,
+        /// Also delete briefings with bookmarked cards
+        #[arg(long)]
+        include_bookmarked: bool,
+        /// Show what would be deleted without deleting
+        #[arg(short = 'n', long)]
+        dry_run: bool,
+        /// Don't ask for confirmation
+        #[arg(short, long)]
+        yes: bool,
+    },
     /// Export a briefing
     Export {
         /// Briefing ID
@@ -142,6 +387,112 @@ enum BriefingAction {
         #[arg(short, long, default_value = "markdown")]
         format: String,
     },
+    /// Generate spoken audio for a briefing
+    Narrate {
+        /// Briefing ID
+        id: i64,
+        /// Only narrate this card (1-based, as shown by `briefings show`)
+        #[arg(short, long)]
+        card: Option<usize>,
+        /// TTS engine (openai or local), defaults to the tts_engine setting
+        #[arg(short, long)]
+        engine: Option<String>,
+        /// OpenAI voice, defaults to the tts_voice setting
+        #[arg(short, long)]
+        voice: Option<String>,
+    },
+    /// Regenerate a card's header image, optionally with a new prompt
+    Image {
+        /// Briefing ID
+        id: i64,
+        /// Card number (1-based, as shown by `briefings show`)
+        #[arg(short, long)]
+        card: usize,
+        /// New image prompt, defaults to the card's current one
+        #[arg(short, long)]
+        prompt: Option<String>,
+        /// Style preset (editorial, isometric, watercolor, photo or none),
+        /// defaults to the one the card's image was made with
+        #[arg(short, long)]
+        style: Option<String>,
+    },
+    /// Write the Atom feed of recent briefings now (it is rewritten after each
+    /// run when enable_atom_feed is set)
+    Feed {
+        /// Output file, defaults to atom_feed_path or ~/.claudius/atom.xml
+        #[arg(short, long)]
+        out: Option<String>,
+    },
+    /// Render a briefing or one of its cards as a PNG image for sharing
+    Share {
+        /// Briefing ID
+        id: i64,
+        /// Only render this card (1-based, as shown by `briefings show`)
+        #[arg(short, long)]
+        card: Option<usize>,
+        /// Output file, defaults to briefing-<id>[-card-<n>].png here
+        #[arg(short, long)]
+        out: Option<String>,
+    },
+    /// Export a briefing to an integration (vault)
+    #[command(name = "export-to")]
+    ExportTo {
+        /// Export target: vault (Obsidian) or notion
+        target: String,
+        /// Briefing ID
+        id: i64,
+        /// Vault path, defaults to the obsidian_vault_path setting
+        #[arg(short, long)]
+        path: Option<String>,
+        /// Notion database ID or URL, defaults to the notion_database_id setting
+        #[arg(short, long)]
+        database: Option<String>,
+    },
+    /// Summarize the past week's briefings: trends, what resolved, what's still developing
+    Weekly,
+    /// Summarize the past month's briefings
+    Monthly,
+    /// List cards dropped for falling below the min_relevance setting
+    Discarded {
+        /// Maximum number of cards to show
+        #[arg(short, long, default_value = "20")]
+        limit: i64,
+    },
+    /// Add a discarded card back to its briefing
+    #[command(name = "restore-card")]
+    RestoreCard {
+        /// Discarded card ID (from `briefings discarded`)
+        id: i64,
+    },
+    /// Set the order a briefing's cards are shown in
+    Reorder {
+        /// Briefing ID
+        id: i64,
+        /// Card numbers (1-based, as in `briefings show`), first card first
+        #[arg(required = true)]
+        cards: Vec<usize>,
+    },
+    /// List the follow-up actions suggested on a briefing's cards
+    Actions {
+        /// Briefing ID
+        id: i64,
+    },
+    /// Run a card action (reminder, link, GitHub issue or calendar event)
+    #[command(name = "run-action")]
+    RunAction {
+        /// Briefing ID
+        id: i64,
+        /// Card number (1-based, as shown by `briefings actions`)
+        card: usize,
+        /// Action number on that card (1-based)
+        action: usize,
+    },
+    /// List pending reminders
+    Reminders {
+        /// Cancel the reminder with this ID
+        #[arg(long)]
+        cancel: Option<i64>,
+    },
 }
 
 // ============================================================================
@@ -152,15 +503,54 @@ enum BriefingAction {
 enum ResearchAction {
     /// Run research now
     Now {
-        /// Only research a specific topic
+        /// Only research specific topics (repeat for several)
         #[arg(short, long)]
-        topic: Option<String>,
+        topic: Vec<String>,
         /// Show verbose output
         #[arg(short, long)]
         verbose: bool,
+        /// Synthesize from cached web, GitHub and source content only (no live tool calls)
+        #[arg(long)]
+        offline: bool,
+        /// Briefing format for this run: standard, newsletter, digest or executive
+        #[arg(long)]
+        format: Option<String>,
+        /// Research mode for this run: standard, firecrawl, academic, github-only or local-only
+        #[arg(long)]
+        mode: Option<String>,
+        /// Submit research through the Batch API (half price, may take hours; topics
+        /// not finished within batch_deadline_minutes are researched interactively)
+        #[arg(long)]
+        batch: bool,
+        /// With --json, print progress events as newline-delimited JSON while research runs
+        #[arg(long)]
+        stream: bool,
+        /// Print the briefing to stdout instead of saving it to the database
+        #[arg(long, conflicts_with = "stream")]
+        no_save: bool,
+        /// Output format with --no-save: md or json (default md)
+        #[arg(long, requires = "no_save")]
+        output: Option<String>,
+    },
+    /// Research a one-off question and save it as an "ad-hoc" briefing (no topic needed)
+    Query {
+        /// The question, e.g. "EU AI Act enforcement this week"
+        question: String,
+        /// Show verbose output
+        #[arg(short, long)]
+        verbose: bool,
+        /// Briefing format: standard, newsletter, digest or executive
+        #[arg(long)]
+        format: Option<String>,
     },
     /// Show research status
     Status,
+    /// Remove a stale research lock left by a crashed app, CLI or daemon
+    Unlock {
+        /// Remove the lock even if its holder still looks alive
+        #[arg(short, long)]
+        force: bool,
+    },
     /// View research logs
     Logs {
         /// Maximum number of logs to show
@@ -169,6 +559,20 @@ enum ResearchAction {
         /// Show only errors
         #[arg(short, long)]
         errors: bool,
+        /// Tail the active JSONL log file instead of querying the database
+        #[arg(short, long)]
+        follow: bool,
+    },
+    /// Show the report of a run: topics, tool call timeline, errors, tokens and cost
+    Report {
+        /// Run ID (the ID of the briefing it produced)
+        run_id: i64,
+    },
+    /// Show per-tool call counts, failure rates, latency and token impact
+    ToolsReport {
+        /// Number of days to include
+        #[arg(short, long, default_value = "30")]
+        days: i64,
     },
 }
 
@@ -214,6 +618,26 @@ enum McpAction {
         /// Server ID or name
         name: String,
     },
+    /// List the servers in the MCP catalog
+    Catalog {
+        /// Fetch the latest catalog instead of using the cached copy
+        #[arg(long)]
+        refresh: bool,
+    },
+    /// Install a server from the MCP catalog (e.g. brave-search)
+    Install {
+        /// Catalog ID or name
+        preset: String,
+        /// API key for the server
+        #[arg(short, long)]
+        key: Option<String>,
+        /// Extra environment variables (KEY=VALUE format)
+        #[arg(short, long)]
+        env: Option<Vec<String>>,
+        /// Save without starting the server to check the setup
+        #[arg(long)]
+        no_verify: bool,
+    },
 }
 
 // ============================================================================
@@ -237,6 +661,96 @@ enum ConfigAction {
         #[command(subcommand)]
         action: ApiKeyAction,
     },
+    /// Manage named API key profiles
+    Profile {
+        #[command(subcommand)]
+        action: ProfileAction,
+    },
+    /// List the Anthropic models available to your API key
+    Models {
+        /// Bypass the cached list and query the API
+        #[arg(long)]
+        refresh: bool,
+    },
+    /// Configure the HTTP(S)/SOCKS5 proxy used for all requests
+    Proxy {
+        #[command(subcommand)]
+        action: ProxyAction,
+    },
+}
+
+#[derive(Subcommand)]
+enum ProxyAction {
+    /// Show the proxy and CA certificate settings
+    Show,
+    /// Use a proxy, e.g. http://proxy.corp:8080 or socks5://127.0.0.1:1080
+    Set {
+        /// Proxy URL (http, https, socks5 or socks5h)
+        url: String,
+        /// Proxy username
+        #[arg(short, long)]
+        username: Option<String>,
+        /// Proxy password (stored in the OS credential store)
+        #[arg(short, long)]
+        password: Option<String>,
+    },
+    /// Stop using a proxy and forget its credentials
+    Clear,
+    /// Check that the Anthropic API is reachable through the proxy and CA settings
+    Test,
+}
+
+#[derive(Subcommand)]
+enum ProfileAction {
+    /// List profiles
+    List,
+    /// Add a profile, or replace its API key
+    Add {
+        /// Profile name (letters, digits, '-' and '_')
+        name: String,
+        /// Anthropic API key for this profile
+        key: String,
+    },
+    /// Remove a profile and its API key
+    Remove {
+        /// Profile name
+        name: String,
+    },
+    /// Make a profile the default for the app and CLI
+    Use {
+        /// Profile name
+        name: String,
+    },
+    /// Show token usage per profile
+    Usage {
+        /// Number of days to include
+        #[arg(short, long, default_value = "30")]
+        days: i64,
+    },
+}
+
+#[derive(Subcommand)]
+enum WorkspaceAction {
+    /// List workspaces
+    List,
+    /// Create an empty workspace
+    Create {
+        /// Workspace name (letters, digits, '-' and '_')
+        name: String,
+    },
+    /// Delete a workspace with its briefings, topics and settings
+    Delete {
+        /// Workspace name
+        name: String,
+        /// Don't ask for confirmation
+        #[arg(short, long)]
+        yes: bool,
+    },
+    /// Make a workspace the one the app and CLI use
+    Use {
+        /// Workspace name
+        name: String,
+    },
 }
 
 #[derive(Subcommand)]
@@ -250,6 +764,8 @@ enum ApiKeyAction {
     },
     /// Clear the API key
     Clear,
+    /// Move API keys and tokens from ~/.claudius/.env into the OS credential store
+    Migrate,
 }
 
 // ============================================================================
@@ -268,6 +784,99 @@ enum HousekeepingAction {
     Status,
     /// Optimize database (run VACUUM)
     Optimize,
+    /// Merge exact duplicate briefings (same title, cards and day) into the oldest copy
+    Dedupe,
+}
+
+// ============================================================================
+// Daemon Commands
+// ============================================================================
+
+#[derive(Subcommand)]
+enum DaemonAction {
+    /// Run the scheduler in the foreground (the default)
+    Run,
+    /// Install and start the daemon as a launchd agent (macOS) or systemd user unit (Linux)
+    Install,
+    /// Stop and remove the installed daemon
+    Uninstall,
+    /// Show whether the daemon is installed and running, and its next run
+    Status,
+    /// Write the .ics calendar of upcoming runs and follow-up events now (the
+    /// daemon refreshes it when enable_schedule_calendar is set)
+    Calendar {
+        /// Output file, defaults to schedule_calendar_path or ~/.claudius/claudius.ics
+        #[arg(short, long)]
+        out: Option<String>,
+    },
+}
+
+// ============================================================================
+// Cards Commands
+// ============================================================================
+
+#[derive(Subcommand)]
+enum CardAction {
+    /// Show and manage the read-later queue
+    Queue {
+        #[command(subcommand)]
+        action: Option<QueueAction>,
+    },
+}
+
+#[derive(Subcommand)]
+enum QueueAction {
+    /// List queued cards
+    List {
+        /// Include completed cards
+        #[arg(short, long)]
+        all: bool,
+    },
+    /// Save a card for later
+    Add {
+        /// Briefing ID
+        briefing_id: i64,
+        /// Card number (1-based, as shown by `briefings show`)
+        card: usize,
+    },
+    /// Mark a queued card as read
+    Done {
+        /// Queue item ID
+        id: i64,
+    },
+    /// Remove a card from the queue
+    Remove {
+        /// Queue item ID
+        id: i64,
+    },
+    /// Move a queued card to a new position (1-based)
+    Move {
+        /// Queue item ID
+        id: i64,
+        /// New position in the queue
+        position: usize,
+    },
+}
+
+// ============================================================================
+// Chat Commands
+// ============================================================================
+
+#[derive(Subcommand)]
+enum ChatAction {
+    /// Export the chat about a card, with timestamps and tools used
+    Export {
+        /// Briefing ID
+        briefing_id: i64,
+        /// Card number (1-based, as shown by `briefings show`)
+        card: usize,
+        /// Output format (markdown or json)
+        #[arg(short, long, default_value = "markdown")]
+        format: String,
+        /// Write to this file instead of printing
+        #[arg(short, long)]
+        output: Option<String>,
+    },
 }
 
 // ============================================================================
@@ -277,10 +886,63 @@ enum HousekeepingAction {
 #[tokio::main]
 async fn main() {
     let cli = Cli::parse();
+    if cli.config_dir.is_some() {
+        config::set_home_override(cli.config_dir.clone());
+    }
+    config::set_workspace_override(cli.workspace.clone());
+
+    // Move ~/.claudius to the XDG directories on Linux (reported once logging is up)
+    let dir_migration = config::migrate_legacy_dir();
+
+    // Keep JSON output (including errors) free of color codes
+    if cli.json {
+        colored::control::set_override(false);
+    }
+
+    // Initialize tracing for verbose output, plus the research log file
+    log_sink::set_debug_logging(read_settings().map(|s| s.debug_logging).unwrap_or(false));
+    tracing_subscriber::registry()
+        .with(
+            // On stderr, so it never mixes into --json output
+            tracing_subscriber::fmt::layer()
+                .with_writer(std::io::stderr)
+                .with_target(false)
+                .with_filter(LevelFilter::INFO),
+        )
+        .with(log_sink::research_layer())
+        .init();
+    log_dir_migration(dir_migration);
+
+    if let Some(workspace) = &cli.workspace {
+        if let Err(e) = config::check_workspace(workspace) {
+            exit_with_error(&e, false, cli.json);
+        }
+    }
 
-    // Initialize tracing for verbose output
-    tracing_subscriber::fmt().with_target(false).init();
+    // Bring legacy config files (config.json) up to the current layout
+    if let Err(e) = config::migrate_config(|interests| {
+        let conn =
+            db::get_connection().map_err(|e| format!("Database connection failed: {}", e))?;
+        db::import_legacy_interests(&conn, interests)
+    }) {
+        tracing::warn!("Config migration failed: {}", e);
+    }
+
+    if let Some(profile) = cli.profile {
+        if !config::list_profiles().contains(&profile) {
+            exit_with_error(
+                &format!(
+                    "Unknown profile: {} (see: claudius config profile list)",
+                    profile
+                ),
+                false,
+                cli.json,
+            );
+        }
+        config::set_profile_override(Some(profile));
+    }
 
+    let mcp_command = matches!(cli.command, Commands::Mcp { .. });
     let result = match cli.command {
         Commands::Topics { action } => handle_topics(action, cli.json).await,
         Commands::Briefings { action } => handle_briefings(action, cli.json).await,
@@ -288,14 +950,61 @@ async fn main() {
         Commands::Mcp { action } => handle_mcp(action, cli.json).await,
         Commands::Config { action } => handle_config(action, cli.json).await,
         Commands::Housekeeping { action } => handle_housekeeping(action, cli.json).await,
+        Commands::Cards { action } => handle_cards(action, cli.json).await,
+        Commands::Ask { question, top } => handle_ask(&question, top, cli.json).await,
+        Commands::Chat { action } => handle_chat(action).await,
+        Commands::Daemon { action } => handle_daemon(action, cli.json).await,
+        Commands::Workspace { action } => handle_workspace(action, cli.json),
+        Commands::Stats { days } => handle_stats(days, cli.json),
+        Commands::Doctor => handle_doctor(cli.json).await,
+        Commands::Update { check, channel } => handle_update(check, channel, cli.json).await,
+        Commands::Serve {
+            port,
+            host,
+            token,
+            show_token,
+        } => handle_serve(host, port, token, show_token).await,
     };
 
     if let Err(e) = result {
-        eprintln!("{} {}", "Error:".red().bold(), e);
-        std::process::exit(1);
+        exit_with_error(&e, mcp_command, cli.json);
+    }
+}
+
+/// Log the outcome of moving `~/.claudius` to the XDG directories.
+fn log_dir_migration(migration: Result<Option<config::XdgDirs>, String>) {
+    match migration {
+        Ok(Some(dirs)) => tracing::info!(
+            "Moved ~/.claudius to {}, {} and {}",
+            dirs.config.display(),
+            dirs.data.display(),
+            dirs.cache.display()
+        ),
+        Ok(None) => {}
+        Err(e) => tracing::warn!("Moving ~/.claudius failed, still using it: {}", e),
     }
 }
 
+/// Report a failed command and exit with the status for its kind of failure
+/// (see `ExitCode`). With --json the error is printed to stdout as JSON.
+fn exit_with_error(message: &str, mcp_command: bool, json: bool) -> ! {
+    let (kind, code) = ExitCode::classify(message, mcp_command);
+    if json {
+        println!(
+            "{}",
+            to_json(&serde_json::json!({
+                "error": message,
+                "kind": kind,
+                "code": code,
+                "exit_code": kind.code(),
+            }))
+        );
+    } else {
+        eprintln!("{} {}", "Error:".red().bold(), message);
+    }
+    std::process::exit(kind.code());
+}
+
 // ============================================================================
 // Topics Handlers
 // ============================================================================
@@ -321,13 +1030,24 @@ async fn handle_topics(action: TopicAction, json: bool) -> Result<(), String> {
                 let mut table = Table::new();
                 table.load_preset(UTF8_FULL);
                 table.set_content_arrangement(ContentArrangement::Dynamic);
-                table.set_header(vec!["Name", "Status", "Description", "ID"]);
+                table.set_header(vec!["Name", "Status", "Description", "Sources", "ID"]);
 
+                let now = Utc::now();
                 for topic in &topics {
-                    let status = if topic.enabled {
-                        "✓ enabled".green().to_string()
-                    } else {
+                    let status = if !topic.enabled {
                         "○ disabled".dimmed().to_string()
+                    } else if topic.is_snoozed(now) {
+                        let until = topic
+                            .snoozed_until
+                            .as_deref()
+                            .and_then(|u| chrono::DateTime::parse_from_rfc3339(u).ok())
+                            .map(|u| u.format("%b %d").to_string())
+                            .unwrap_or_default();
+                        format!("◔ snoozed until {}", until).yellow().to_string()
+                    } else if topic.skip_next_run {
+                        "» skips next run".yellow().to_string()
+                    } else {
+                        "✓ enabled".green().to_string()
                     };
                     let desc = topic.description.as_deref().unwrap_or("-");
                     let short_id = if topic.id.len() >= 8 {
@@ -335,7 +1055,12 @@ async fn handle_topics(action: TopicAction, json: bool) -> Result<(), String> {
                     } else {
                         &topic.id
                     };
-                    table.add_row(vec![&topic.name, &status, desc, short_id]);
+                    let sources = if topic.preferred_sources.is_empty() {
+                        "-".to_string()
+                    } else {
+                        topic.preferred_sources.join(", ")
+                    };
+                    table.add_row(vec![&topic.name, &status, desc, &sources, short_id]);
                 }
 
                 println!("{table}");
@@ -343,7 +1068,11 @@ async fn handle_topics(action: TopicAction, json: bool) -> Result<(), String> {
             }
         }
 
-        TopicAction::Add { name, description } => {
+        TopicAction::Add {
+            name,
+            description,
+            sources,
+        } => {
             // Check if topic already exists
             if db::topic_name_exists(&conn, &name)? {
                 return Err(format!("Topic '{}' already exists", name));
@@ -354,7 +1083,13 @@ async fn handle_topics(action: TopicAction, json: bool) -> Result<(), String> {
                 id: Uuid::new_v4().to_string(),
                 name: name.clone(),
                 description,
+                preferred_sources: sources,
                 enabled: true,
+                snoozed_until: None,
+                skip_next_run: false,
+                dedup_days: None,
+                dedup_threshold: None,
+                mcp_servers: Vec::new(),
                 created_at: now.clone(),
                 updated_at: now,
             };
@@ -369,18 +1104,131 @@ async fn handle_topics(action: TopicAction, json: bool) -> Result<(), String> {
             }
         }
 
-        TopicAction::Remove { id } => {
-            let topic = find_topic(&conn, &id)?;
-            db::delete_topic(&conn, &topic.id)?;
+        TopicAction::Sources {
+            id,
+            add,
+            remove,
+            clear,
+        } => {
+            let mut topic = find_topic(&conn, &id)?;
+            let modified = clear || !add.is_empty() || !remove.is_empty();
+
+            if modified {
+                if clear {
+                    topic.preferred_sources.clear();
+                }
+                topic
+                    .preferred_sources
+                    .retain(|s| !remove.iter().any(|r| r.eq_ignore_ascii_case(s)));
+                for source in add {
+                    if !topic
+                        .preferred_sources
+                        .iter()
+                        .any(|s| s.eq_ignore_ascii_case(&source))
+                    {
+                        topic.preferred_sources.push(source);
+                    }
+                }
+                topic.updated_at = Utc::now().to_rfc3339();
+                db::update_topic(&conn, &topic)?;
+            }
 
             if json {
-                println!("{}", serde_json::json!({ "deleted": topic.id }));
+                println!(
+                    "{}",
+                    to_json(&serde_json::json!({
+                        "topic": topic.name,
+                        "preferred_sources": topic.preferred_sources,
+                    }))
+                );
+            } else if topic.preferred_sources.is_empty() {
+                println!("No preferred sources for '{}'", topic.name);
+                println!(
+                    "Add one with: claudius topics sources \"{}\" --add <site>",
+                    topic.name
+                );
             } else {
-                println!("{} Removed topic '{}'", "✓".green(), topic.name);
+                println!(
+                    "{}",
+                    format!("Preferred sources for '{}'", topic.name).bold()
+                );
+                for source in &topic.preferred_sources {
+                    println!("  - {}", source);
+                }
             }
         }
 
-        TopicAction::Enable { id } => {
+        TopicAction::Mcp {
+            id,
+            add,
+            remove,
+            clear,
+        } => {
+            let mut topic = find_topic(&conn, &id)?;
+            let modified = clear || !add.is_empty() || !remove.is_empty();
+
+            if modified {
+                if clear {
+                    topic.mcp_servers.clear();
+                }
+                topic
+                    .mcp_servers
+                    .retain(|s| !remove.iter().any(|r| r.eq_ignore_ascii_case(s)));
+                for server in add {
+                    if !topic
+                        .mcp_servers
+                        .iter()
+                        .any(|s| s.eq_ignore_ascii_case(&server))
+                    {
+                        topic.mcp_servers.push(server);
+                    }
+                }
+                topic.updated_at = Utc::now().to_rfc3339();
+                db::update_topic(&conn, &topic)?;
+            }
+
+            if json {
+                println!(
+                    "{}",
+                    to_json(&serde_json::json!({
+                        "topic": topic.name,
+                        "mcp_servers": topic.mcp_servers,
+                    }))
+                );
+            } else if topic.mcp_servers.is_empty() {
+                println!("No MCP servers bound to '{}'", topic.name);
+                println!(
+                    "Bind one with: claudius topics mcp \"{}\" --add <server>",
+                    topic.name
+                );
+            } else {
+                println!(
+                    "{}",
+                    format!("MCP servers bound to '{}'", topic.name).bold()
+                );
+                for server in &topic.mcp_servers {
+                    println!("  - {}", server);
+                }
+                println!(
+                    "{}",
+                    "Bound servers are only offered while researching the topics they are bound to."
+                        .dimmed()
+                );
+            }
+        }
+
+        TopicAction::Remove { id } => {
+            let topic = find_topic(&conn, &id)?;
+            db::delete_topic(&conn, &topic.id)?;
+
+            if json {
+                println!("{}", serde_json::json!({ "deleted": topic.id }));
+            } else {
+                println!("{} Removed topic '{}'", "✓".green(), topic.name);
+            }
+        }
+
+        TopicAction::Enable { id } => {
             let mut topic = find_topic(&conn, &id)?;
             topic.enabled = true;
             topic.updated_at = Utc::now().to_rfc3339();
@@ -405,6 +1253,326 @@ async fn handle_topics(action: TopicAction, json: bool) -> Result<(), String> {
                 println!("{} Disabled topic '{}'", "✓".green(), topic.name);
             }
         }
+
+        TopicAction::Snooze {
+            id,
+            days,
+            next,
+            off,
+        } => {
+            let mut topic = find_topic(&conn, &id)?;
+            let now = Utc::now();
+            if off {
+                topic.snooze(0, now);
+                topic.skip_next_run = false;
+            } else if next {
+                topic.skip_next_run = true;
+            } else {
+                topic.snooze(days, now);
+            }
+            topic.updated_at = now.to_rfc3339();
+            db::update_topic(&conn, &topic)?;
+
+            if json {
+                println!("{}", to_json(&topic));
+            } else if off {
+                println!("{} '{}' will be researched again", "✓".green(), topic.name);
+            } else if next {
+                println!(
+                    "{} '{}' will skip the next research run",
+                    "✓".green(),
+                    topic.name
+                );
+            } else {
+                println!(
+                    "{} Snoozed '{}' for {} day(s)",
+                    "✓".green(),
+                    topic.name,
+                    days
+                );
+            }
+        }
+
+        TopicAction::Dedup {
+            id,
+            days,
+            threshold,
+            reset,
+        } => {
+            let mut topic = find_topic(&conn, &id)?;
+            if reset || days.is_some() || threshold.is_some() {
+                settings_schema::validate_topic_dedup(days, threshold)?;
+                if reset {
+                    topic.dedup_days = None;
+                    topic.dedup_threshold = None;
+                }
+                if days.is_some() {
+                    topic.dedup_days = days;
+                }
+                if threshold.is_some() {
+                    topic.dedup_threshold = threshold;
+                }
+                topic.updated_at = Utc::now().to_rfc3339();
+                db::update_topic(&conn, &topic)?;
+            }
+
+            let settings = read_settings().unwrap_or_default();
+            let effective_days = topic.dedup_days.unwrap_or(settings.dedup_days);
+            let effective_threshold = topic.dedup_threshold.unwrap_or(settings.dedup_threshold);
+            if json {
+                println!(
+                    "{}",
+                    to_json(&serde_json::json!({
+                        "topic": topic,
+                        "effective_dedup_days": effective_days,
+                        "effective_dedup_threshold": effective_threshold,
+                    }))
+                );
+            } else {
+                let source = |overridden: bool| if overridden { "topic" } else { "global" };
+                println!("{} dedup for '{}':", "→".cyan(), topic.name);
+                println!(
+                    "  Window:    {} day(s) ({})",
+                    effective_days,
+                    source(topic.dedup_days.is_some())
+                );
+                println!(
+                    "  Threshold: {:.2} ({})",
+                    effective_threshold,
+                    source(topic.dedup_threshold.is_some())
+                );
+            }
+        }
+
+        TopicAction::Stats { runs } => {
+            let activity = claudius::topic_activity::get_topic_activity(&conn, runs.max(1))?;
+            if json {
+                println!("{}", to_json(&serde_json::json!({ "topics": activity })));
+            } else if activity.is_empty() {
+                println!(
+                    "{}",
+                    "No topic activity yet. It is recorded after each research run.".yellow()
+                );
+            } else {
+                let mut table = Table::new();
+                table.load_preset(UTF8_FULL);
+                table.set_content_arrangement(ContentArrangement::Dynamic);
+                table.set_header(vec![
+                    "Topic",
+                    "Runs",
+                    "Novelty",
+                    "Last",
+                    "Cards/run",
+                    "Status",
+                ]);
+                for a in &activity {
+                    let status = match a.status.as_str() {
+                        "stagnant" => a.status.yellow().to_string(),
+                        "active" => a.status.green().to_string(),
+                        _ => a.status.clone(),
+                    };
+                    table.add_row(vec![
+                        a.topic.clone(),
+                        a.runs.to_string(),
+                        format!("{:.0}%", a.avg_novelty * 100.0),
+                        format!("{:.0}%", a.last_novelty * 100.0),
+                        format!("{:.1}", a.avg_cards),
+                        status,
+                    ]);
+                }
+                println!("{table}");
+                for a in activity.iter().filter(|a| a.suggestion.is_some()) {
+                    println!(
+                        "{} {}: {}",
+                        "→".cyan(),
+                        a.topic.bold(),
+                        a.suggestion.as_deref().unwrap_or_default()
+                    );
+                }
+            }
+        }
+        TopicAction::Timeline {
+            name,
+            narrate,
+            days,
+        } => {
+            let topic = find_topic(&conn, &name).map_or(name, |t| t.name);
+            let mut timeline = claudius::topic_timeline::load_timeline(&conn, &topic, days)?;
+            if timeline.entries.is_empty() {
+                return Err(format!("No cards found for topic '{}'", topic));
+            }
+            if narrate {
+                let api_key = require_api_key()?;
+                let settings = read_settings()?;
+                if !json {
+                    println!("{}", "Writing the timeline...".dimmed());
+                }
+                claudius::topic_timeline::narrate_timeline(
+                    &api_key,
+                    &settings.model,
+                    &mut timeline,
+                )
+                .await?;
+            }
+
+            if json {
+                println!("{}", to_json(&timeline));
+                return Ok(());
+            }
+            println!(
+                "{} ({} cards)\n",
+                timeline.topic.bold(),
+                timeline.entries.len()
+            );
+            for entry in &timeline.entries {
+                println!(
+                    "{} {} {}",
+                    entry.date.get(..10).unwrap_or(entry.date.as_str()).dimmed(),
+                    entry.title.cyan(),
+                    format!(
+                        "[briefing {}, card {}]",
+                        entry.briefing_id,
+                        entry.card_index + 1
+                    )
+                    .dimmed()
+                );
+                if !entry.summary.is_empty() {
+                    println!("           {}", entry.summary);
+                }
+            }
+            if let Some(narrative) = &timeline.narrative {
+                println!("\n{}\n", "Timeline".bold());
+                println!("{}", narrative);
+            }
+        }
+        TopicAction::Suggest {
+            refresh,
+            accept,
+            dismiss,
+        } => {
+            if let Some(id) = accept {
+                let topic = db::accept_topic_suggestion(&conn, id)?
+                    .ok_or_else(|| format!("Suggestion {} not found", id))?;
+                if json {
+                    println!("{}", to_json(&topic));
+                } else {
+                    println!("{} Added topic '{}'", "✓".green(), topic.name);
+                }
+                return Ok(());
+            }
+            if let Some(id) = dismiss {
+                if !db::dismiss_topic_suggestion(&conn, id)? {
+                    return Err(format!("Suggestion {} not found", id));
+                }
+                if json {
+                    println!("{}", serde_json::json!({ "dismissed": id }));
+                } else {
+                    println!("{} Dismissed suggestion {}", "✓".green(), id);
+                }
+                return Ok(());
+            }
+
+            if refresh {
+                let api_key = require_api_key()?;
+                let settings = read_settings().unwrap_or_default();
+                if !json {
+                    println!("Analyzing recent bookmarks, ratings and chats...");
+                }
+                claudius::topic_discovery::discover_topics(
+                    &api_key,
+                    &settings.model,
+                    &config::active_profile(),
+                )
+                .await?;
+            }
+
+            let suggestions = db::get_topic_suggestions(&conn, "pending")?;
+            if json {
+                println!(
+                    "{}",
+                    to_json(&serde_json::json!({ "suggestions": suggestions }))
+                );
+            } else if suggestions.is_empty() {
+                println!("{}", "No topic suggestions.".yellow());
+                if !refresh {
+                    println!("Look for new ones with: claudius topics suggest --refresh");
+                }
+            } else {
+                let mut table = Table::new();
+                table.load_preset(UTF8_FULL);
+                table.set_content_arrangement(ContentArrangement::Dynamic);
+                table.set_header(vec!["ID", "Topic", "Why"]);
+                for s in &suggestions {
+                    table.add_row(vec![
+                        s.id.to_string(),
+                        s.name.clone(),
+                        s.reason.clone().unwrap_or_else(|| "-".to_string()),
+                    ]);
+                }
+                println!("{table}");
+                println!(
+                    "
+Accept with: claudius topics suggest --accept <id>"
+                );
+            }
+        }
+        TopicAction::Preview {
+            name,
+            description,
+            sources,
+        } => {
+            let api_key = require_api_key()?;
+            let settings = read_settings().unwrap_or_default();
+            if !json {
+                println!("Estimating research for '{}'...", name);
+            }
+            let preview = claudius::topic_preview::preview_topic(
+                &api_key,
+                &settings.model,
+                &name,
+                description.as_deref(),
+                &sources,
+            )
+            .await?;
+
+            if json {
+                println!("{}", to_json(&preview));
+            } else {
+                println!();
+                println!("{}", preview.topic.bold());
+                if !preview.plan.is_empty() {
+                    println!("  {}", preview.plan);
+                }
+                println!(
+                    "  Tools: {}",
+                    if preview.tools.is_empty() {
+                        "-".to_string()
+                    } else {
+                        preview.tools.join(", ")
+                    }
+                );
+                println!(
+                    "  Expected calls: {} ({} searches, {} page fetches)",
+                    preview.tool_calls, preview.searches, preview.page_fetches
+                );
+                println!(
+                    "  Per run: ~{} tokens, ~${:.3} with {}",
+                    preview.estimated_tokens, preview.estimated_cost_usd, preview.model
+                );
+                println!(
+                    "  Per month of daily runs: ~${:.2}",
+                    preview.estimated_monthly_cost_usd
+                );
+                if !preview.from_history {
+                    println!(
+                        "{}",
+                        "  No recent runs to learn step costs from; using a default.".dimmed()
+                    );
+                }
+                println!();
+                println!("Add it with: claudius topics add \"{}\"", preview.topic);
+            }
+        }
     }
 
     Ok(())
@@ -439,8 +1607,23 @@ async fn handle_briefings(action: BriefingAction, json: bool) -> Result<(), Stri
     let conn = db::get_connection().map_err(|e| format!("Database connection failed: {}", e))?;
 
     match action {
-        BriefingAction::List { limit } => {
-            let briefings = get_briefings(&conn, limit)?;
+        BriefingAction::List {
+            limit,
+            from,
+            to,
+            topic,
+            min_relevance,
+            model,
+        } => {
+            let filter = db::BriefingFilter {
+                from,
+                to,
+                topic,
+                min_relevance,
+                model,
+            };
+            let filtered = filter != db::BriefingFilter::default();
+            let briefings = db::get_briefings_filtered(&conn, &filter, limit)?;
 
             if json {
                 let output: Vec<serde_json::Value> = briefings
@@ -464,6 +1647,8 @@ async fn handle_briefings(action: BriefingAction, json: bool) -> Result<(), Stri
                         "briefings": output
                     }))
                 );
+            } else if briefings.is_empty() && filtered {
+                println!("{}", "No briefings match the filters.".yellow());
             } else if briefings.is_empty() {
                 println!("{}", "No briefings found.".yellow());
                 println!("Run research with: claudius research now");
@@ -493,18 +1678,42 @@ async fn handle_briefings(action: BriefingAction, json: bool) -> Result<(), Stri
             }
         }
 
-        BriefingAction::Show { id } => {
-            let briefing = get_briefing(&conn, id)?;
-            let cards: Vec<BriefingCard> = serde_json::from_str(&briefing.cards)
+        BriefingAction::Show {
+            id,
+            plain,
+            card,
+            interactive,
+        } => {
+            let briefing = db::get_briefing(&conn, id)?;
+            let mut cards: Vec<BriefingCard> = serde_json::from_str(&briefing.cards)
                 .map_err(|e| format!("Failed to parse cards: {}", e))?;
 
+            // Ranked order; card numbers stay the stored ones used by --card
+            let mut order: Vec<usize> = (0..cards.len()).collect();
+            order.sort_by_key(|&i| cards[i].rank.map_or(i, |r| r as usize));
+            if let Some(number) = card {
+                if number == 0 || number > cards.len() {
+                    return Err(format!(
+                        "Briefing {} has no card {} ({} cards)",
+                        id,
+                        number,
+                        cards.len()
+                    ));
+                }
+                order = vec![number - 1];
+            }
+
             if json {
+                if let Some(number) = card {
+                    cards = vec![cards.swap_remove(number - 1)];
+                }
                 println!(
                     "{}",
                     to_json(&serde_json::json!({
                         "id": briefing.id,
                         "date": briefing.date,
                         "title": briefing.title,
+                        "card": card,
                         "cards": cards,
                         "model_used": briefing.model_used,
                         "research_time_ms": briefing.research_time_ms,
@@ -512,36 +1721,128 @@ async fn handle_briefings(action: BriefingAction, json: bool) -> Result<(), Stri
                     }))
                 );
             } else {
-                println!("{}", briefing.title.bold());
-                println!("{}", briefing.date.dimmed());
-                println!();
+                let header = format!("{}\n{}\n", briefing.title.bold(), briefing.date.dimmed());
+                let options = term_markdown::RenderOptions::stdout(3);
+                let pages: Vec<String> = order
+                    .iter()
+                    .map(|&i| format_card(i + 1, &cards[i], plain, &options))
+                    .collect();
 
-                for (i, card) in cards.iter().enumerate() {
-                    println!("{}. {}", i + 1, card.title.cyan().bold());
-                    if !card.topic.is_empty() {
-                        println!("   Topic: {}", card.topic.dimmed());
+                if interactive {
+                    step_through_cards(&header, &pages)?;
+                } else {
+                    let mut out = header;
+                    for page in &pages {
+                        out.push('\n');
+                        out.push_str(page);
+                        out.push_str(&format!("\n{}\n", "─".repeat(60).dimmed()));
                     }
-                    println!();
-                    println!("   {}", card.summary);
-                    println!();
-                    if !card.detailed_content.is_empty() {
-                        println!("   {}", "Details:".yellow());
-                        println!("   {}", card.detailed_content);
-                        println!();
+                    if let Some(ms) = briefing.research_time_ms.filter(|_| card.is_none()) {
+                        out.push_str(&format!("\nResearch completed in {}s", ms / 1000));
                     }
-                    if !card.sources.is_empty() {
-                        println!("   {}", "Sources:".dimmed());
-                        for source in &card.sources {
-                            println!("   - {}", source);
-                        }
-                        println!();
+                    page_output(&out);
+                }
+            }
+        }
+
+        BriefingAction::Delete { id, yes } => {
+            let preview = db::briefing_deletion(&conn, id)?
+                .ok_or_else(|| format!("Briefing {} not found", id))?;
+            if !confirm(&format!("Delete {}?", describe_deletion(&preview)), yes)? {
+                if json {
+                    println!("{}", serde_json::json!({ "cancelled": true }));
+                } else {
+                    println!("Cancelled");
+                }
+                return Ok(());
+            }
+            db::delete_briefing(&conn, id)?;
+
+            if json {
+                println!("{}", to_json(&serde_json::json!({ "deleted": preview })));
+            } else {
+                println!("{} Deleted briefing {}", "✓".green(), id);
+            }
+        }
+
+        BriefingAction::Prune {
+            before,
+            include_bookmarked,
+            dry_run,
+            yes,
+        } => {
+            chrono::NaiveDate::parse_from_str(&before, "%Y-%m-%d")
+                .map_err(|_| format!("Invalid date '{}' (expected YYYY-MM-DD)", before))?;
+            let briefings = db::briefings_before(&conn, &before, include_bookmarked)?;
+
+            if dry_run || briefings.is_empty() {
+                if json {
+                    println!(
+                        "{}",
+                        to_json(&serde_json::json!({
+                            "dry_run": dry_run,
+                            "would_delete": briefings,
+                        }))
+                    );
+                } else if briefings.is_empty() {
+                    println!("{} No briefings before {}", "✓".green(), before);
+                } else {
+                    println!(
+                        "{} {} briefing(s) would be deleted:",
+                        "Preview:".yellow(),
+                        briefings.len()
+                    );
+                    for briefing in &briefings {
+                        println!("  {}", describe_deletion(briefing));
                     }
-                    println!("{}", "─".repeat(60).dimmed());
-                    println!();
+                    println!("\nRun without --dry-run to delete");
+                }
+                return Ok(());
+            }
+
+            let bookmarked = briefings.iter().filter(|b| b.bookmarks > 0).count();
+            let question = if bookmarked > 0 {
+                format!(
+                    "Delete {} briefing(s) before {}, {} with bookmarks?",
+                    briefings.len(),
+                    before,
+                    bookmarked
+                )
+            } else {
+                format!("Delete {} briefing(s) before {}?", briefings.len(), before)
+            };
+            if !confirm(&question, yes)? {
+                if json {
+                    println!("{}", serde_json::json!({ "cancelled": true }));
+                } else {
+                    println!("Cancelled");
+                }
+                return Ok(());
+            }
+
+            let mut deleted = 0;
+            for briefing in &briefings {
+                if db::delete_briefing(&conn, briefing.id)? {
+                    deleted += 1;
                 }
+            }
 
-                if let Some(ms) = briefing.research_time_ms {
-                    println!("Research completed in {}s", ms / 1000);
+            if json {
+                println!(
+                    "{}",
+                    to_json(&serde_json::json!({
+                        "deleted_count": deleted,
+                        "remaining_count": db::count_briefings(&conn)?,
+                    }))
+                );
+            } else {
+                println!("{} Deleted {} briefing(s)", "✓".green(), deleted);
+                if !include_bookmarked {
+                    println!(
+                        "{}",
+                        "Briefings with bookmarks were kept (--include-bookmarked deletes them too)"
+                            .dimmed()
+                    );
                 }
             }
         }
@@ -579,8 +1880,26 @@ async fn handle_briefings(action: BriefingAction, json: bool) -> Result<(), Stri
             }
         }
 
+        BriefingAction::Open { id, card } => {
+            // Fail here rather than letting the app open a missing briefing
+            db::get_briefing(&conn, id)?;
+            let link = match card {
+                Some(0) => return Err("Card numbers start at 1".to_string()),
+                Some(card) => claudius::deep_link::DeepLink::card(id, card - 1),
+                None => claudius::deep_link::DeepLink::briefing(id),
+            };
+            let url = link.url();
+            claudius::deep_link::open_url(&url)?;
+
+            if json {
+                println!("{}", to_json(&serde_json::json!({ "opened": url })));
+            } else {
+                println!("{} Opened {}", "✓".green(), url);
+            }
+        }
+
         BriefingAction::Export { id, format } => {
-            let briefing = get_briefing(&conn, id)?;
+            let briefing = db::get_briefing(&conn, id)?;
             let cards: Vec<BriefingCard> = serde_json::from_str(&briefing.cards)
                 .map_err(|e| format!("Failed to parse cards: {}", e))?;
 
@@ -597,28 +1916,12 @@ async fn handle_briefings(action: BriefingAction, json: bool) -> Result<(), Stri
                     );
                 }
                 "markdown" | "md" => {
-                    println!("# {}", briefing.title);
-                    println!("\n*{}*\n", briefing.date);
-
-                    for card in &cards {
-                        println!("## {}", card.title);
-                        if !card.topic.is_empty() {
-                            println!("\n**Topic:** {}\n", card.topic);
-                        }
-                        println!("{}\n", card.summary);
-                        if !card.detailed_content.is_empty() {
-                            println!("### Details\n");
-                            println!("{}\n", card.detailed_content);
-                        }
-                        if !card.sources.is_empty() {
-                            println!("### Sources\n");
-                            for source in &card.sources {
-                                println!("- {}", source);
-                            }
-                            println!();
-                        }
-                        println!("---\n");
-                    }
+                    print_briefing_markdown(
+                        &briefing.title,
+                        &briefing.date,
+                        Some(briefing.id),
+                        &cards,
+                    );
                 }
                 _ => {
                     return Err(format!(
@@ -628,447 +1931,820 @@ async fn handle_briefings(action: BriefingAction, json: bool) -> Result<(), Stri
                 }
             }
         }
-    }
 
-    Ok(())
-}
+        BriefingAction::Narrate {
+            id,
+            card,
+            engine,
+            voice,
+        } => {
+            let briefing = db::get_briefing(&conn, id)?;
+            let cards: Vec<BriefingCard> = serde_json::from_str(&briefing.cards)
+                .map_err(|e| format!("Failed to parse cards: {}", e))?;
+            let settings = read_settings().unwrap_or_default();
 
-fn get_briefings(conn: &rusqlite::Connection, limit: i32) -> Result<Vec<Briefing>, String> {
-    let mut stmt = conn
-        .prepare(
-            "SELECT id, date, title, cards, research_time_ms, model_used, total_tokens
-         FROM briefings
-         ORDER BY date DESC
-         LIMIT ?1",
-        )
-        .map_err(|e| format!("Failed to prepare statement: {}", e))?;
-
-    let briefings = stmt
-        .query_map([limit], |row| {
-            Ok(Briefing {
-                id: row.get(0)?,
-                date: row.get(1)?,
-                title: row.get(2)?,
-                cards: row.get(3)?,
-                research_time_ms: row.get(4)?,
-                model_used: row.get(5)?,
-                total_tokens: row.get(6)?,
-            })
-        })
-        .map_err(|e| format!("Query failed: {}", e))?
-        .collect::<Result<Vec<_>, _>>()
-        .map_err(|e| format!("Failed to collect results: {}", e))?;
-
-    Ok(briefings)
-}
-
-fn get_briefing(conn: &rusqlite::Connection, id: i64) -> Result<Briefing, String> {
-    let mut stmt = conn
-        .prepare(
-            "SELECT id, date, title, cards, research_time_ms, model_used, total_tokens
-         FROM briefings
-         WHERE id = ?1",
-        )
-        .map_err(|e| format!("Failed to prepare statement: {}", e))?;
-
-    stmt.query_row([id], |row| {
-        Ok(Briefing {
-            id: row.get(0)?,
-            date: row.get(1)?,
-            title: row.get(2)?,
-            cards: row.get(3)?,
-            research_time_ms: row.get(4)?,
-            model_used: row.get(5)?,
-            total_tokens: row.get(6)?,
-        })
-    })
-    .map_err(|e| format!("Briefing not found: {}", e))
-}
-
-fn search_briefings(conn: &rusqlite::Connection, query: &str) -> Result<Vec<Briefing>, String> {
-    let search_pattern = format!("%{}%", query);
-
-    let mut stmt = conn
-        .prepare(
-            "SELECT id, date, title, cards, research_time_ms, model_used, total_tokens
-         FROM briefings
-         WHERE title LIKE ?1 OR cards LIKE ?1
-         ORDER BY date DESC
-         LIMIT 50",
-        )
-        .map_err(|e| format!("Failed to prepare statement: {}", e))?;
-
-    let briefings = stmt
-        .query_map([&search_pattern], |row| {
-            Ok(Briefing {
-                id: row.get(0)?,
-                date: row.get(1)?,
-                title: row.get(2)?,
-                cards: row.get(3)?,
-                research_time_ms: row.get(4)?,
-                model_used: row.get(5)?,
-                total_tokens: row.get(6)?,
-            })
-        })
-        .map_err(|e| format!("Query failed: {}", e))?
-        .collect::<Result<Vec<_>, _>>()
-        .map_err(|e| format!("Failed to collect results: {}", e))?;
+            let engine =
+                tts::TtsEngine::from_setting(engine.as_deref().unwrap_or(&settings.tts_engine));
+            let voice = voice.unwrap_or(settings.tts_voice);
+            let card_index = match card {
+                Some(0) => return Err("Card numbers start at 1".to_string()),
+                Some(n) => Some(n - 1),
+                None => None,
+            };
+            let openai_key = read_openai_api_key();
 
-    Ok(briefings)
-}
+            if !json {
+                println!("{} Generating narration...", "→".cyan());
+            }
 
-// ============================================================================
-// Research Handlers
-// ============================================================================
+            let path = tts::narrate_briefing(
+                id,
+                &briefing.title,
+                &cards,
+                card_index,
+                engine,
+                &voice,
+                openai_key.as_deref(),
+            )
+            .await?;
 
-async fn handle_research(action: ResearchAction, json: bool) -> Result<(), String> {
-    match action {
-        ResearchAction::Now { topic, verbose } => {
-            // Check for API key
-            let api_key = require_api_key()?;
+            if json {
+                println!(
+                    "{}",
+                    to_json(&serde_json::json!({
+                        "briefing_id": id,
+                        "card": card,
+                        "audio_path": path.display().to_string(),
+                    }))
+                );
+            } else {
+                println!("{} Saved audio to {}", "✓".green(), path.display());
+            }
+        }
 
-            // Get settings
-            let settings = read_settings().unwrap_or_default();
+        BriefingAction::Image {
+            id,
+            card,
+            prompt,
+            style,
+        } => {
+            let card_index = card
+                .checked_sub(1)
+                .ok_or("Card numbers start at 1".to_string())?;
+            let openai_key = read_openai_api_key()
+                .ok_or("No OpenAI API key configured (needed for DALL-E image generation)")?;
 
-            // Get topics
-            let conn =
-                db::get_connection().map_err(|e| format!("Database connection failed: {}", e))?;
-            let all_topics = db::get_all_topics(&conn)?;
+            if !json {
+                println!("{} Generating image...", "→".cyan());
+            }
 
-            let topics: Vec<String> = if let Some(ref specific_topic) = topic {
-                // Find the specific topic
-                let found = all_topics
-                    .iter()
-                    .find(|t| t.name.to_lowercase() == specific_topic.to_lowercase());
-                match found {
-                    Some(t) => vec![t.name.clone()],
-                    None => return Err(format!("Topic '{}' not found", specific_topic)),
-                }
-            } else {
-                // Get all enabled topics
-                all_topics
-                    .iter()
-                    .filter(|t| t.enabled)
-                    .map(|t| t.name.clone())
-                    .collect()
-            };
+            let path = image_gen::regenerate_card_image(
+                id,
+                card_index,
+                prompt.as_deref(),
+                style.as_deref(),
+                &openai_key,
+            )
+            .await?;
 
-            if topics.is_empty() {
-                return Err(
-                    "No topics to research. Add topics with: claudius topics add <name>"
-                        .to_string(),
+            if json {
+                println!(
+                    "{}",
+                    to_json(&serde_json::json!({
+                        "briefing_id": id,
+                        "card": card,
+                        "image_path": path.display().to_string(),
+                    }))
                 );
+            } else {
+                println!("{} Saved image to {}", "✓".green(), path.display());
             }
+        }
 
-            if !json {
+        BriefingAction::Feed { out } => {
+            let settings = read_settings().unwrap_or_default();
+            let path =
+                claudius::feed::feed_path(out.as_deref().or(settings.atom_feed_path.as_deref()));
+            claudius::feed::write_feed(&conn, &path)?;
+
+            if json {
                 println!(
-                    "{} Starting research on {} topic(s)...",
-                    "→".cyan(),
-                    topics.len()
+                    "{}",
+                    to_json(&serde_json::json!({ "feed_path": path.display().to_string() }))
                 );
-                if verbose {
-                    for t in &topics {
-                        println!("  • {}", t);
-                    }
-                }
-                println!();
-            }
-
-            // Load past card fingerprints for deduplication
-            let (past_cards_context, past_fingerprints) = if settings.dedup_days > 0 {
-                match db::get_recent_card_fingerprints(&conn, settings.dedup_days) {
-                    Ok(fingerprints) => {
-                        let context = claudius::dedup::format_past_cards_for_prompt(&fingerprints);
-                        if verbose && !json && !fingerprints.is_empty() {
-                            println!(
-                                "{} Loaded {} past cards for dedup",
-                                "→".cyan(),
-                                fingerprints.len()
-                            );
-                        }
-                        (Some(context), fingerprints)
-                    }
-                    Err(e) => {
-                        if verbose && !json {
-                            eprintln!("{} Dedup unavailable: {}", "Warning:".yellow(), e);
-                        }
-                        (None, Vec::new())
-                    }
-                }
             } else {
-                (None, Vec::new())
-            };
-
-            // Set running state BEFORE spawning to prevent race conditions
-            let _cancellation_token = research_state::set_running("starting")
-                .map_err(|e| format!("Cannot start research: {}", e))?;
-
-            // RAII guard: ensure cleanup even if we panic or return early
-            defer! {
-                if let Err(e) = research_state::set_stopped() {
-                    eprintln!("{} Failed to reset research state: {}", "Warning:".yellow(), e);
+                println!("{} Wrote feed to {}", "✓".green(), path.display());
+                if !settings.enable_atom_feed {
+                    println!(
+                        "{}",
+                        "Keep it updated after each run with: claudius config set enable_atom_feed true"
+                            .dimmed()
+                    );
                 }
             }
+        }
 
-            // Create research agent and run in background for progress tracking
-            let mut agent = ResearchAgent::new(
-                api_key,
-                Some(settings.model.clone()),
-                settings.enable_web_search,
-                settings.research_mode.clone(),
-                settings.rate_limit_firecrawl_agent,
-            );
-
-            let start = std::time::Instant::now();
-            let condense = settings.condense_briefings;
-            let dedup_threshold = settings.dedup_threshold;
-
-            // Spawn research on a background task
-            let research_handle = tokio::spawn(async move {
-                agent
-                    .run_research(topics, None, condense, past_cards_context)
-                    .await
+        BriefingAction::Share { id, card, out } => {
+            let card_index = card
+                .map(|n| {
+                    n.checked_sub(1)
+                        .ok_or_else(|| "Card numbers start at 1".to_string())
+                })
+                .transpose()?;
+            let out = out.map(std::path::PathBuf::from).unwrap_or_else(|| {
+                let default = claudius::share_image::default_share_path(id, card_index);
+                default.file_name().unwrap_or_default().into()
             });
+            let path = claudius::share_image::render_share_image(id, card_index, Some(&out))?;
 
-            // Poll for progress updates (only in non-JSON mode)
-            let mut last_phase = String::new();
-            if !json {
-                loop {
-                    tokio::time::sleep(std::time::Duration::from_millis(500)).await;
-
-                    let state = research_state::get_state();
-
-                    // Print phase changes
-                    if state.current_phase != last_phase && !state.current_phase.is_empty() {
-                        // Clear the line and print new phase
-                        print!(
-                            "\r{} {}                    ",
-                            "→".cyan(),
-                            state.current_phase
-                        );
-                        use std::io::Write;
-                        std::io::stdout().flush().ok();
-                        last_phase = state.current_phase.clone();
-                    }
-
-                    // Check if research is done
-                    if research_handle.is_finished() {
-                        println!(); // New line after progress
-                        break;
-                    }
-                }
+            if json {
+                println!(
+                    "{}",
+                    to_json(&serde_json::json!({
+                        "briefing_id": id,
+                        "card": card,
+                        "image_path": path.display().to_string(),
+                    }))
+                );
+            } else {
+                println!("{} Saved share image to {}", "✓".green(), path.display());
             }
+        }
 
-            // Get the result - ensure cleanup happens regardless of success/failure
-            let research_result = research_handle
-                .await
-                .map_err(|e| format!("Research task failed: {}", e))
-                .and_then(|r| r);
-
-            let duration = start.elapsed();
+        BriefingAction::ExportTo {
+            target,
+            id,
+            path,
+            database,
+        } => {
+            let briefing = db::get_briefing(&conn, id)?;
+            let cards: Vec<BriefingCard> = serde_json::from_str(&briefing.cards)
+                .map_err(|e| format!("Failed to parse cards: {}", e))?;
+            let settings = read_settings().unwrap_or_default();
 
-            // Note: cleanup is handled by defer! guard above (panic-safe)
+            let export_target: Box<dyn integrations::ExportTarget> = match target.as_str() {
+                "vault" | "obsidian" => {
+                    let vault = path.or(settings.obsidian_vault_path).ok_or(
+                        "No vault configured. Pass --path or run: claudius config set obsidian_vault_path <dir>",
+                    )?;
+                    Box::new(integrations::ObsidianVault::new(
+                        &vault,
+                        &settings.obsidian_folder,
+                    ))
+                }
+                "notion" => {
+                    let database_id = database.or(settings.notion_database_id).ok_or(
+                        "No Notion database configured. Pass --database or run: claudius config set notion_database_id <id>",
+                    )?;
+                    let token = read_notion_token().ok_or(
+                        "No Notion token configured. Set NOTION_API_KEY in ~/.claudius/.env",
+                    )?;
+                    Box::new(integrations::NotionDatabase::new(&token, &database_id))
+                }
+                _ => {
+                    return Err(format!(
+                        "Unknown export target: {}. Use 'vault' or 'notion'",
+                        target
+                    ))
+                }
+            };
 
-            // Now handle the result
-            let mut result = research_result?;
+            let written = export_target
+                .export(&integrations::ExportBriefing {
+                    id,
+                    date: &briefing.date,
+                    title: &briefing.title,
+                    model_used: briefing.model_used.as_deref(),
+                    cards: &cards,
+                    first_new_card: 0,
+                })
+                .await?;
 
-            // Apply post-synthesis deduplication filter (safety net)
-            if !past_fingerprints.is_empty() && dedup_threshold > 0.0 {
-                let original_count = result.cards.len();
-                result.cards = claudius::dedup::filter_duplicates(
-                    result.cards,
-                    &past_fingerprints,
-                    dedup_threshold,
+            if json {
+                println!(
+                    "{}",
+                    to_json(&serde_json::json!({
+                        "briefing_id": id,
+                        "target": export_target.name(),
+                        "location": written,
+                    }))
+                );
+            } else {
+                println!(
+                    "{} Exported to {}: {}",
+                    "✓".green(),
+                    export_target.name(),
+                    written
                 );
-                let filtered_count = original_count - result.cards.len();
-                if filtered_count > 0 && verbose && !json {
-                    println!("{} Filtered {} duplicate cards", "→".cyan(), filtered_count);
-                }
             }
+        }
 
-            // Save to database
-            let cards_json = serde_json::to_string(&result.cards)
-                .map_err(|e| format!("Failed to serialize cards: {}", e))?;
-
-            conn.execute(
-                "INSERT INTO briefings (date, title, cards, research_time_ms, model_used, total_tokens)
-                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
-                rusqlite::params![
-                    result.date,
-                    result.title,
-                    cards_json,
-                    result.research_time_ms as i64,
-                    result.model_used,
-                    result.total_tokens as i64,
-                ],
-            ).map_err(|e| format!("Failed to save briefing: {}", e))?;
-
-            let briefing_id = conn.last_insert_rowid();
-
-            // Generate images for cards that have image_prompt (if enabled and API key configured)
-            if settings.enable_image_generation {
-                if let Some(openai_key) = read_openai_api_key() {
-                    if !json {
-                        println!("{} Generating header images...", "→".cyan());
-                    }
-
-                    let mut images_generated = 0;
-                    for (idx, card) in result.cards.iter_mut().enumerate() {
-                        if let Some(ref prompt) = card.image_prompt {
-                            if verbose && !json {
-                                println!("  {} Generating image for card {}...", "→".dimmed(), idx);
-                            }
+        BriefingAction::Weekly => handle_meta_briefing(MetaPeriod::Weekly, json).await?,
 
-                            match image_gen::generate_image(prompt, briefing_id, idx, &openai_key)
-                                .await
-                            {
-                                image_gen::ImageGenResult::Success(path) => {
-                                    card.image_path = Some(path.to_string_lossy().to_string());
-                                    images_generated += 1;
-                                    if verbose && !json {
-                                        println!("    {} Image saved", "✓".green());
-                                    }
-                                }
-                                image_gen::ImageGenResult::Disabled => {
-                                    if verbose && !json {
-                                        println!("    {} Image generation disabled", "○".dimmed());
-                                    }
-                                    break;
-                                }
-                                image_gen::ImageGenResult::NoApiKey => {
-                                    if !json {
-                                        println!(
-                                            "    {} No OpenAI API key configured",
-                                            "!".yellow()
-                                        );
-                                    }
-                                    break;
-                                }
-                                image_gen::ImageGenResult::Failed(err) => {
-                                    if verbose && !json {
-                                        println!("    {} Failed: {}", "✗".red(), err);
-                                    }
-                                    // Continue with other cards
-                                }
-                            }
-                        }
-                    }
+        BriefingAction::Monthly => handle_meta_briefing(MetaPeriod::Monthly, json).await?,
 
-                    // Update briefing with image paths if any were generated
-                    if images_generated > 0 {
-                        let updated_cards_json = serde_json::to_string(&result.cards)
-                            .map_err(|e| format!("Failed to serialize updated cards: {}", e))?;
+        BriefingAction::Discarded { limit } => {
+            let cards = db::get_discarded_cards(&conn, limit)?;
 
-                        conn.execute(
-                            "UPDATE briefings SET cards = ?1 WHERE id = ?2",
-                            rusqlite::params![updated_cards_json, briefing_id],
-                        )
-                        .map_err(|e| {
-                            format!("Failed to update briefing with image paths: {}", e)
-                        })?;
+            if json {
+                println!("{}", to_json(&serde_json::json!({ "discarded": cards })));
+            } else if cards.is_empty() {
+                println!("{}", "No discarded cards.".dimmed());
+            } else {
+                let mut table = Table::new();
+                table.load_preset(UTF8_FULL);
+                table.set_content_arrangement(ContentArrangement::Dynamic);
+                table.set_header(vec!["ID", "Briefing", "Relevance", "Topic", "Title"]);
 
-                        if !json {
-                            println!("{} Generated {} images", "✓".green(), images_generated);
-                        }
-                    }
-                } else if verbose && !json {
-                    println!(
-                        "{} Image generation enabled but no OpenAI API key configured",
-                        "!".yellow()
-                    );
+                for c in &cards {
+                    table.add_row(vec![
+                        &c.id.to_string(),
+                        &c.briefing_id.to_string(),
+                        &c.relevance,
+                        c.topic.as_deref().unwrap_or("-"),
+                        &c.title,
+                    ]);
                 }
+
+                println!("{table}");
             }
+        }
+
+        BriefingAction::RestoreCard { id } => {
+            let briefing_id = db::restore_discarded_card(&conn, id)?
+                .ok_or_else(|| format!("Discarded card {} not found", id))?;
 
             if json {
                 println!(
                     "{}",
-                    to_json(&serde_json::json!({
-                        "status": "completed",
-                        "title": result.title,
-                        "cards": result.cards.len(),
-                        "duration_ms": duration.as_millis(),
-                        "model": result.model_used,
-                        "tokens": result.total_tokens,
-                    }))
+                    serde_json::json!({ "restored": id, "briefing_id": briefing_id })
                 );
             } else {
-                println!("{} Research completed!", "✓".green().bold());
-                println!();
                 println!(
-                    "  {} briefing cards generated",
-                    result.cards.len().to_string().cyan()
+                    "{} Restored card {} to briefing {}",
+                    "✓".green(),
+                    id,
+                    briefing_id
                 );
-                println!("  Duration: {}s", duration.as_secs());
-                println!("  Model: {}", result.model_used.dimmed());
-                println!();
-                println!("View with: claudius briefings list");
-            }
-
-            // Try to refresh the desktop app if it's running
-            // This uses the single-instance plugin to send a refresh signal
-            if let Err(e) = std::process::Command::new("open")
-                .args(["-a", "Claudius", "--args", "--refresh"])
-                .stdout(std::process::Stdio::null())
-                .stderr(std::process::Stdio::null())
-                .spawn()
-            {
-                // Silently ignore if app isn't installed or can't be opened
-                if verbose && !json {
-                    println!("{} Could not refresh desktop app: {}", "!".dimmed(), e);
-                }
             }
         }
 
-        ResearchAction::Status => {
-            let state = research_state::get_state();
+        BriefingAction::Reorder { id, cards } => {
+            let order = cards
+                .iter()
+                .map(|&n| {
+                    n.checked_sub(1)
+                        .ok_or_else(|| "Card numbers start at 1".to_string())
+                })
+                .collect::<Result<Vec<usize>, String>>()?;
+            db::reorder_cards(&conn, id, &order)?;
 
             if json {
-                let started_at = state
-                    .started_at
-                    .map(|t| chrono::DateTime::<chrono::Utc>::from(t).to_rfc3339());
                 println!(
                     "{}",
-                    to_json(&serde_json::json!({
-                        "is_running": state.is_running,
-                        "current_phase": state.current_phase,
-                        "started_at": started_at,
-                        "is_cancelled": research_state::is_cancelled(),
-                    }))
+                    serde_json::json!({ "briefing_id": id, "order": cards })
                 );
-            } else if state.is_running {
-                println!("{} Research is running", "●".yellow());
-                println!("  Phase: {}", state.current_phase.cyan());
-                if let Some(started) = state.started_at {
-                    let elapsed = std::time::SystemTime::now()
-                        .duration_since(started)
-                        .unwrap_or_default();
-                    println!("  Running for: {}s", elapsed.as_secs());
-                }
             } else {
-                println!("{} No research currently running", "○".dimmed());
+                println!("{} Reordered the cards of briefing {}", "✓".green(), id);
             }
         }
 
-        ResearchAction::Logs { limit, errors } => {
-            use claudius::research_log::ResearchLogger;
+        BriefingAction::Actions { id } => {
+            let cards_json: String = conn
+                .query_row("SELECT cards FROM briefings WHERE id = ?1", [id], |row| {
+                    row.get(0)
+                })
+                .map_err(|_| format!("Briefing {} not found", id))?;
+            let cards: Vec<BriefingCard> =
+                serde_json::from_str(&cards_json).map_err(|e| format!("Invalid cards: {}", e))?;
 
-            let logs = if errors {
-                ResearchLogger::get_actionable_errors(limit)?
+            if json {
+                let output: Vec<serde_json::Value> = cards
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, c)| !c.actions.is_empty())
+                    .map(|(i, c)| {
+                        serde_json::json!({
+                            "card": i + 1,
+                            "title": c.title,
+                            "actions": c.actions,
+                        })
+                    })
+                    .collect();
+                println!("{}", to_json(&output));
+            } else if cards.iter().all(|c| c.actions.is_empty()) {
+                println!("{}", "No actions suggested for this briefing.".dimmed());
             } else {
-                ResearchLogger::get_logs(None, limit)?
-            };
+                let mut table = Table::new();
+                table.load_preset(UTF8_FULL);
+                table.set_content_arrangement(ContentArrangement::Dynamic);
+                table.set_header(vec!["Card", "Action", "Card Title", "Description"]);
 
-            if json {
+                for (i, card) in cards.iter().enumerate() {
+                    for (j, action) in card.actions.iter().enumerate() {
+                        table.add_row(vec![
+                            (i + 1).to_string(),
+                            (j + 1).to_string(),
+                            card.title.clone(),
+                            action.label(),
+                        ]);
+                    }
+                }
+
+                println!("{table}");
                 println!(
-                    "{}",
-                    to_json(&serde_json::json!({
-                        "logs": logs
-                    }))
+                    "\nRun one with: claudius briefings run-action {} <card> <action>",
+                    id
                 );
-            } else if logs.is_empty() {
-                println!("{}", "No research logs found.".dimmed());
-            } else {
-                for log in &logs {
+            }
+        }
+
+        BriefingAction::RunAction { id, card, action } => {
+            if card == 0 || action == 0 {
+                return Err("Card and action numbers start at 1".to_string());
+            }
+            let (card_title, card_action) =
+                card_actions::load_card_action(&conn, id, card - 1, action - 1)?;
+            let outcome =
+                card_actions::run_action(id, card - 1, &card_title, &card_action, !json).await?;
+
+            if json {
+                println!("{}", to_json(&outcome));
+            } else {
+                match outcome {
+                    ActionOutcome::ReminderScheduled { id, due_at } => println!(
+                        "{} Scheduled reminder {} for {} UTC",
+                        "✓".green(),
+                        id,
+                        due_at
+                    ),
+                    ActionOutcome::UrlOpened { url } => {
+                        println!("{} Opened {}", "✓".green(), url)
+                    }
+                    ActionOutcome::IssueCreated { url } => {
+                        println!("{} Created issue {}", "✓".green(), url)
+                    }
+                    ActionOutcome::CalendarEventCreated { path } => {
+                        println!("{} Saved calendar event to {}", "✓".green(), path)
+                    }
+                }
+            }
+        }
+
+        BriefingAction::Reminders { cancel } => {
+            if let Some(reminder_id) = cancel {
+                if !db::delete_reminder(&conn, reminder_id)? {
+                    return Err(format!("Reminder {} not found", reminder_id));
+                }
+                if json {
+                    println!("{}", serde_json::json!({ "cancelled": reminder_id }));
+                } else {
+                    println!("{} Cancelled reminder {}", "✓".green(), reminder_id);
+                }
+                return Ok(());
+            }
+
+            let reminders = db::get_pending_reminders(&conn)?;
+            if json {
+                println!("{}", to_json(&reminders));
+            } else if reminders.is_empty() {
+                println!("{}", "No pending reminders.".dimmed());
+            } else {
+                let mut table = Table::new();
+                table.load_preset(UTF8_FULL);
+                table.set_content_arrangement(ContentArrangement::Dynamic);
+                table.set_header(vec!["ID", "Due (UTC)", "Briefing", "Card", "Message"]);
+
+                for r in &reminders {
+                    table.add_row(vec![
+                        r.id.to_string(),
+                        r.due_at.clone(),
+                        r.briefing_id.to_string(),
+                        r.card_title.clone(),
+                        r.message.clone(),
+                    ]);
+                }
+
+                println!("{table}");
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Generate and save a weekly or monthly meta-briefing.
+async fn handle_meta_briefing(period: MetaPeriod, json: bool) -> Result<(), String> {
+    let api_key = require_api_key()?;
+    let settings = read_settings()?;
+
+    if !json {
+        println!(
+            "{} Generating {} meta-briefing...",
+            "→".cyan(),
+            period.tag()
+        );
+    }
+    let meta = meta_briefing::generate_meta_briefing(&api_key, &settings.model, period).await?;
+
+    if json {
+        println!("{}", to_json(&meta));
+    } else {
+        println!(
+            "{} Saved briefing {}: {}",
+            "✓".green(),
+            meta.briefing_id,
+            meta.title.bold()
+        );
+        println!(
+            "  {} cards from {} briefing(s), {} tokens",
+            meta.cards.len(),
+            meta.source_briefings,
+            meta.tokens_used
+        );
+        println!();
+        for card in &meta.cards {
+            println!(
+                "  {} {}",
+                format!("[{}]", card.topic).dimmed(),
+                card.title.cyan()
+            );
+        }
+        println!(
+            "\n{}",
+            format!("Read it with: claudius briefings show {}", meta.briefing_id).dimmed()
+        );
+    }
+    Ok(())
+}
+
+/// One card as printed by `briefings show`, numbered `number` (1-based, as
+/// stored). Card text is rendered Markdown unless `plain`.
+fn format_card(
+    number: usize,
+    card: &BriefingCard,
+    plain: bool,
+    options: &term_markdown::RenderOptions,
+) -> String {
+    let text = |markdown: &str| {
+        if plain {
+            format!("   {}", markdown)
+        } else {
+            term_markdown::render(markdown, options)
+        }
+    };
+
+    let mut lines = vec![format!("{}. {}", number, card.title.cyan().bold())];
+    if !card.topic.is_empty() {
+        lines.push(format!("   Topic: {}", card.topic.dimmed()));
+    }
+    lines.push(String::new());
+    lines.push(text(&card.summary));
+    lines.push(String::new());
+    if !card.detailed_content.is_empty() {
+        lines.push(format!("   {}", "Details:".yellow()));
+        lines.push(text(&card.detailed_content));
+        lines.push(String::new());
+    }
+    if !card.sources.is_empty() {
+        lines.push(format!("   {}", "Sources:".dimmed()));
+        for source in &card.sources {
+            let url = claudius::sources::extract_url(source);
+            let check = url
+                .as_ref()
+                .and_then(|url| card.source_checks.iter().find(|c| c.url == *url));
+            let linked;
+            let source = match &url {
+                Some(url) if !plain && options.hyperlinks => {
+                    linked = term_markdown::hyperlink(source, url);
+                    &linked
+                }
+                _ => source,
+            };
+            match check {
+                Some(c) if !c.valid => lines.push(format!(
+                    "   {} {} {}",
+                    "✗".red(),
+                    source,
+                    format!("({})", c.error.as_deref().unwrap_or("broken")).red()
+                )),
+                Some(c) => {
+                    lines.push(format!("   {} {}", "✓".green(), source));
+                    if let Some(ref final_url) = c.final_url {
+                        lines.push(format!("     {} {}", "→".dimmed(), final_url.dimmed()));
+                    }
+                }
+                None => lines.push(format!("   - {}", source)),
+            }
+        }
+        lines.push(String::new());
+    }
+    if let Some(ref warning) = card.source_warning {
+        lines.push(format!("   {} {}", "⚠".yellow(), warning.yellow()));
+        lines.push(String::new());
+    }
+    lines.join("\n")
+}
+
+/// Print `text`, through `$PAGER` (default `less`) when stdout is a
+/// terminal and the text is taller than it. An empty `PAGER`, or a pager
+/// that fails to start, prints it directly.
+fn page_output(text: &str) {
+    use std::io::Write;
+
+    let tall = std::io::stdout().is_terminal()
+        && terminal_size::terminal_size()
+            .is_some_and(|(_, terminal_size::Height(h))| text.lines().count() >= h as usize);
+    let pager = std::env::var("PAGER").unwrap_or_else(|_| "less".to_string());
+    let mut args = pager.split_whitespace();
+    if let Some(program) = args.next().filter(|_| tall) {
+        // Colors on, and quit at once if it fits after all
+        let less = std::env::var("LESS").unwrap_or_else(|_| "FRX".to_string());
+        let child = std::process::Command::new(program)
+            .args(args)
+            .env("LESS", less)
+            .stdin(std::process::Stdio::piped())
+            .spawn();
+        if let Ok(mut child) = child {
+            if let Some(mut stdin) = child.stdin.take() {
+                // Quitting the pager early closes the pipe; that's fine
+                let _ = stdin.write_all(text.as_bytes());
+            }
+            let _ = child.wait();
+            return;
+        }
+    }
+    println!("{}", text);
+}
+
+/// `briefings show --interactive`: show one card at a time, reading
+/// navigation keys from stdin (Enter or n for next, p for previous, q to
+/// quit).
+fn step_through_cards(header: &str, pages: &[String]) -> Result<(), String> {
+    use std::io::Write;
+
+    if pages.is_empty() {
+        println!("{}", header);
+        return Ok(());
+    }
+    if !std::io::stdin().is_terminal() || !std::io::stdout().is_terminal() {
+        return Err("--interactive needs a terminal".to_string());
+    }
+
+    let mut current = 0;
+    loop {
+        // Clear the screen and move to the top
+        print!("\x1b[2J\x1b[H");
+        println!("{}", header);
+        println!("{}", pages[current]);
+        print!(
+            "{} ",
+            format!(
+                "Card {}/{}  [Enter/n] next  [p] previous  [q] quit",
+                current + 1,
+                pages.len()
+            )
+            .dimmed()
+        );
+        std::io::stdout()
+            .flush()
+            .map_err(|e| format!("Failed to write output: {}", e))?;
+
+        let mut input = String::new();
+        let read = std::io::stdin()
+            .read_line(&mut input)
+            .map_err(|e| format!("Failed to read input: {}", e))?;
+        match input.trim().to_lowercase().as_str() {
+            _ if read == 0 => break,
+            "" | "n" | "j" => {
+                if current + 1 == pages.len() {
+                    break;
+                }
+                current += 1;
+            }
+            "p" | "k" => current = current.saturating_sub(1),
+            "q" => break,
+            _ => {}
+        }
+    }
+    println!();
+    Ok(())
+}
+
+/// Print a briefing as Markdown, with a link to open it in the app when it
+/// has been saved (`id`).
+fn print_briefing_markdown(title: &str, date: &str, id: Option<i64>, cards: &[BriefingCard]) {
+    println!("# {}", title);
+    println!("\n*{}*\n", date);
+    if let Some(id) = id {
+        println!(
+            "[Open in Claudius]({})\n",
+            claudius::deep_link::briefing_url(id)
+        );
+    }
+
+    for card in cards {
+        println!("## {}", card.title);
+        if !card.topic.is_empty() {
+            println!("\n**Topic:** {}\n", card.topic);
+        }
+        println!("{}\n", card.summary);
+        if !card.detailed_content.is_empty() {
+            println!("### Details\n");
+            println!("{}\n", card.detailed_content);
+        }
+        if !card.sources.is_empty() {
+            println!("### Sources\n");
+            for source in &card.sources {
+                println!("- {}", source);
+            }
+            println!();
+        }
+        println!("---\n");
+    }
+}
+
+fn search_briefings(conn: &rusqlite::Connection, query: &str) -> Result<Vec<Briefing>, String> {
+    let search_pattern = format!("%{}%", query);
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, date, title, cards, research_time_ms, model_used, total_tokens, tag,
+         cover_image_path
+         FROM briefings
+         WHERE title LIKE ?1 OR cards LIKE ?1
+         ORDER BY date DESC
+         LIMIT 50",
+        )
+        .map_err(|e| format!("Failed to prepare statement: {}", e))?;
+
+    let briefings = stmt
+        .query_map([&search_pattern], |row| {
+            Ok(Briefing {
+                id: row.get(0)?,
+                date: row.get(1)?,
+                title: row.get(2)?,
+                cards: row.get(3)?,
+                research_time_ms: row.get(4)?,
+                model_used: row.get(5)?,
+                total_tokens: row.get(6)?,
+                tag: row.get(7)?,
+                cover_image_path: row.get(8)?,
+            })
+        })
+        .map_err(|e| format!("Query failed: {}", e))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("Failed to collect results: {}", e))?;
+
+    Ok(briefings)
+}
+
+// ============================================================================
+// Research Handlers
+// ============================================================================
+
+async fn handle_research(action: ResearchAction, json: bool) -> Result<(), String> {
+    match action {
+        ResearchAction::Now {
+            topic,
+            verbose,
+            offline,
+            format,
+            mode,
+            batch,
+            stream,
+            no_save,
+            output,
+        } => {
+            let print_only = no_save.then(|| output.unwrap_or_else(|| "md".to_string()));
+            research_now(
+                topic, None, verbose, offline, format, mode, batch, json, stream, print_only,
+            )
+            .await?
+        }
+
+        ResearchAction::Query {
+            question,
+            verbose,
+            format,
+        } => {
+            let question = claudius::research::validate_adhoc_query(&question)?;
+            research_now(
+                Vec::new(),
+                Some(question),
+                verbose,
+                false,
+                format,
+                None,
+                false,
+                json,
+                false,
+                None,
+            )
+            .await?
+        }
+
+        ResearchAction::Status => {
+            let state = research_state::get_state();
+            // Research started by the app or daemon only shows up in the lock file
+            let lock = research_state::lock_info();
+            let locked = lock.as_ref().is_some_and(|l| !l.is_stale());
+
+            if json {
+                let started_at = state
+                    .started_at
+                    .map(|t| chrono::DateTime::<chrono::Utc>::from(t).to_rfc3339());
+                println!(
+                    "{}",
+                    to_json(&serde_json::json!({
+                        "is_running": state.is_running || locked,
+                        "current_phase": state.current_phase,
+                        "started_at": started_at,
+                        "is_cancelled": research_state::is_cancelled(),
+                        "lock": lock.as_ref().map(|l| serde_json::json!({
+                            "pid": l.pid,
+                            "process": l.process,
+                            "started_at": l.started_at,
+                            "stale": l.is_stale(),
+                        })),
+                    }))
+                );
+            } else if let Some(lock) = lock.filter(|_| !state.is_running) {
+                if lock.is_stale() {
+                    println!(
+                        "{} Stale research lock from {} (pid {})",
+                        "!".yellow(),
+                        lock.process,
+                        lock.pid
+                    );
+                    println!("  Remove it with: claudius research unlock");
+                } else {
+                    println!("{} Research is running", "●".yellow());
+                    println!("  Process: {} (pid {})", lock.process.cyan(), lock.pid);
+                    println!("  Started: {}", lock.started_at);
+                }
+            } else if state.is_running {
+                println!("{} Research is running", "●".yellow());
+                println!("  Phase: {}", state.current_phase.cyan());
+                if let Some(started) = state.started_at {
+                    let elapsed = std::time::SystemTime::now()
+                        .duration_since(started)
+                        .unwrap_or_default();
+                    println!("  Running for: {}s", elapsed.as_secs());
+                }
+            } else {
+                println!("{} No research currently running", "○".dimmed());
+            }
+        }
+
+        ResearchAction::Unlock { force } => {
+            let removed = research_state::unlock(force)?;
+            if json {
+                println!(
+                    "{}",
+                    to_json(&serde_json::json!({
+                        "removed": removed.is_some(),
+                        "pid": removed.as_ref().map(|l| l.pid),
+                    }))
+                );
+            } else if let Some(lock) = removed {
+                println!(
+                    "{} Removed research lock held by {} (pid {})",
+                    "✓".green(),
+                    lock.process,
+                    lock.pid
+                );
+            } else {
+                println!("{} No research lock found", "○".dimmed());
+            }
+        }
+
+        ResearchAction::Logs {
+            limit,
+            errors,
+            follow: true,
+        } => follow_research_log(limit, errors, json).await?,
+
+        ResearchAction::Logs { limit, errors, .. } => {
+            use claudius::research_log::ResearchLogger;
+
+            let logs = if errors {
+                ResearchLogger::get_actionable_errors(limit)?
+            } else {
+                ResearchLogger::get_logs(None, limit)?
+            };
+
+            if json {
+                println!(
+                    "{}",
+                    to_json(&serde_json::json!({
+                        "logs": logs
+                    }))
+                );
+            } else if logs.is_empty() {
+                println!("{}", "No research logs found.".dimmed());
+            } else {
+                for log in &logs {
                     let type_color = if !log.success {
                         log.log_type.red()
                     } else {
@@ -1084,395 +2760,2623 @@ async fn handle_research(action: ResearchAction, json: bool) -> Result<(), Strin
                         .as_ref()
                         .map(|t| format!(" [{}]", t))
                         .unwrap_or_default();
+                    let request_info = log
+                        .request_id
+                        .as_ref()
+                        .filter(|_| !log.success)
+                        .map(|id| format!(" (request {})", id))
+                        .unwrap_or_default();
                     println!(
-                        "[{}] {}{} {}",
+                        "[{}] {}{} {}{}",
                         log.created_at[..19].dimmed(),
                         type_color,
                         topic_info.cyan(),
-                        message
+                        message,
+                        request_info.dimmed()
                     );
                 }
             }
         }
-    }
-
-    Ok(())
-}
-
-fn require_api_key() -> Result<String, String> {
-    read_api_key().ok_or_else(|| {
-        format!(
-            "{}\n\n{}\n  {}\n\n{}\n  {}",
-            "Error: No API key configured.".red().bold(),
-            "Set your Anthropic API key with:",
-            "claudius config api-key set <YOUR_KEY>".cyan(),
-            "Or create ~/.claudius/.env with:",
-            "ANTHROPIC_API_KEY=sk-ant-...".dimmed()
-        )
-    })
-}
-
-// ============================================================================
-// MCP Handlers
-// ============================================================================
 
-async fn handle_mcp(action: McpAction, json: bool) -> Result<(), String> {
-    match action {
-        McpAction::List => {
-            let config = read_mcp_servers()?;
+        ResearchAction::Report { run_id } => {
+            let conn =
+                db::get_connection().map_err(|e| format!("Database connection failed: {}", e))?;
+            let report = claudius::research_log::run_report(&conn, run_id)?;
 
             if json {
+                println!("{}", to_json(&report));
+                return Ok(());
+            }
+
+            println!(
+                "{}",
+                format!("Run {}: {}", report.briefing_id, report.title).bold()
+            );
+            println!("  Date: {}", report.date);
+            if let Some(model) = &report.model {
+                println!("  Model: {}", model.dimmed());
+            }
+            if let Some(ms) = report.research_time_ms {
+                println!("  Duration: {}s", ms / 1000);
+            }
+            if report.batch_tokens > 0 {
                 println!(
-                    "{}",
-                    to_json(&serde_json::json!({
-                        "servers": config.servers
-                    }))
+                    "  Tokens: {} ({} via Batch API)",
+                    report.total_tokens, report.batch_tokens
                 );
-            } else if config.servers.is_empty() {
-                println!("{}", "No MCP servers configured.".yellow());
-                println!("Add a server with: claudius mcp add <name> --command <cmd>");
             } else {
+                println!("  Tokens: {}", report.total_tokens);
+            }
+            println!("  Estimated cost: ${:.3}", report.estimated_cost_usd);
+
+            if !report.topics.is_empty() {
+                println!("\n{}", "Topics".bold());
                 let mut table = Table::new();
                 table.load_preset(UTF8_FULL);
                 table.set_content_arrangement(ContentArrangement::Dynamic);
-                table.set_header(vec!["Name", "Status", "Command", "ID"]);
+                table.set_header(vec![
+                    "Topic",
+                    "API requests",
+                    "Tool calls",
+                    "Failures",
+                    "Tokens",
+                    "Time",
+                ]);
+                for t in &report.topics {
+                    table.add_row(vec![
+                        &t.topic,
+                        &t.api_requests.to_string(),
+                        &t.tool_calls.to_string(),
+                        &t.failures.to_string(),
+                        &t.tokens.to_string(),
+                        &format!("{:.1}s", t.duration_ms as f64 / 1000.0),
+                    ]);
+                }
+                println!("{table}");
+            }
 
-                for server in &config.servers {
-                    let status = if server.enabled {
-                        "✓ enabled".green().to_string()
-                    } else {
-                        "○ disabled".dimmed().to_string()
-                    };
-                    let command = server
-                        .config
-                        .get("command")
-                        .and_then(|v| v.as_str())
-                        .unwrap_or("-");
-                    let short_id = if server.id.len() >= 8 {
-                        &server.id[..8]
+            if report.timeline.is_empty() {
+                println!(
+                    "\n{}",
+                    "No research logs were recorded for this run.".dimmed()
+                );
+                return Ok(());
+            }
+
+            println!("\n{}", "Timeline".bold());
+            let mut table = Table::new();
+            table.load_preset(UTF8_FULL);
+            table.set_content_arrangement(ContentArrangement::Dynamic);
+            table.set_header(vec![
+                "Time", "Topic", "Step", "Duration", "Tokens", "Result",
+            ]);
+            for entry in &report.timeline {
+                let step = match &entry.tool_name {
+                    Some(tool) => format!("{} {}", entry.log_type, tool),
+                    None => entry.log_type.clone(),
+                };
+                let result = if entry.success {
+                    "ok".green().to_string()
+                } else {
+                    "failed".red().to_string()
+                };
+                table.add_row(vec![
+                    entry.created_at.get(11..).unwrap_or(&entry.created_at),
+                    entry.topic.as_deref().unwrap_or("-"),
+                    &step,
+                    &entry
+                        .duration_ms
+                        .map(|ms| format!("{}ms", ms))
+                        .unwrap_or("-".to_string()),
+                    &entry
+                        .tokens_used
+                        .map(|t| t.to_string())
+                        .unwrap_or("-".to_string()),
+                    &result,
+                ]);
+            }
+            println!("{table}");
+
+            if report.failures > 0 {
+                println!("\n{}", format!("Errors ({})", report.failures).red().bold());
+                for entry in report.errors() {
+                    println!(
+                        "  {} {} {}: {}{}",
+                        "✗".red(),
+                        entry.topic.as_deref().unwrap_or("-"),
+                        entry
+                            .tool_name
+                            .as_deref()
+                            .unwrap_or(&entry.log_type)
+                            .dimmed(),
+                        entry.error_message.as_deref().unwrap_or("Unknown error"),
+                        entry
+                            .request_id
+                            .as_ref()
+                            .map(|id| format!(" (request {})", id))
+                            .unwrap_or_default()
+                            .dimmed()
+                    );
+                }
+            }
+        }
+
+        ResearchAction::ToolsReport { days } => {
+            use claudius::research_log::ResearchLogger;
+
+            let report = ResearchLogger::get_tool_analytics(days)?;
+
+            if json {
+                println!("{}", to_json(&report));
+            } else if report.tools.is_empty() {
+                println!(
+                    "{}",
+                    format!("No tool calls logged in the last {} days.", days).dimmed()
+                );
+            } else {
+                let avg = |ms: Option<f64>| {
+                    ms.map(|ms| format!("{:.0}ms", ms))
+                        .unwrap_or("-".to_string())
+                };
+
+                println!("{}", format!("Tool usage (last {} days)", days).bold());
+                let mut table = Table::new();
+                table.load_preset(UTF8_FULL);
+                table.set_content_arrangement(ContentArrangement::Dynamic);
+                table.set_header(vec![
+                    "Tool",
+                    "Calls",
+                    "Failures",
+                    "Avg latency",
+                    "Est. tokens",
+                ]);
+                for t in &report.tools {
+                    table.add_row(vec![
+                        &t.tool_name,
+                        &t.calls.to_string(),
+                        &format!("{} ({:.0}%)", t.failures, t.failure_rate * 100.0),
+                        &avg(t.avg_duration_ms),
+                        &t.est_tokens.to_string(),
+                    ]);
+                }
+                println!("{table}");
+
+                if !report.servers.is_empty() {
+                    println!("\n{}", "MCP servers".bold());
+                    let mut table = Table::new();
+                    table.load_preset(UTF8_FULL);
+                    table.set_content_arrangement(ContentArrangement::Dynamic);
+                    table.set_header(vec![
+                        "Server",
+                        "Tools used",
+                        "Calls",
+                        "Failures",
+                        "Avg latency",
+                        "Est. tokens",
+                    ]);
+                    for s in &report.servers {
+                        table.add_row(vec![
+                            &s.server,
+                            &s.tools.to_string(),
+                            &s.calls.to_string(),
+                            &format!("{} ({:.0}%)", s.failures, s.failure_rate * 100.0),
+                            &avg(s.avg_duration_ms),
+                            &s.est_tokens.to_string(),
+                        ]);
+                    }
+                    println!("{table}");
+                }
+
+                if !report.briefings.is_empty() {
+                    println!("\n{}", "Per briefing".bold());
+                    let mut table = Table::new();
+                    table.load_preset(UTF8_FULL);
+                    table.set_content_arrangement(ContentArrangement::Dynamic);
+                    table.set_header(vec![
+                        "Briefing",
+                        "Date",
+                        "Tool calls",
+                        "Failures",
+                        "Est. tool tokens",
+                        "API tokens",
+                    ]);
+                    for b in &report.briefings {
+                        table.add_row(vec![
+                            &b.briefing_id.to_string(),
+                            b.date.get(..10).unwrap_or(&b.date),
+                            &b.tool_calls.to_string(),
+                            &b.failures.to_string(),
+                            &b.est_tool_tokens.to_string(),
+                            &b.api_tokens.to_string(),
+                        ]);
+                    }
+                    println!("{table}");
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Run research on the due topics (or `topic` alone), or on a one-off
+/// `query` saved as an ad-hoc briefing. With `print_only` ("md" or "json")
+/// the briefing is printed in that format instead of being saved, and
+/// nothing else is written to the database.
+#[allow(clippy::too_many_arguments)]
+async fn research_now(
+    only_topics: Vec<String>,
+    query: Option<String>,
+    verbose: bool,
+    offline: bool,
+    format: Option<String>,
+    mode: Option<String>,
+    batch: bool,
+    json: bool,
+    stream: bool,
+    print_only: Option<String>,
+) -> Result<(), String> {
+    if stream && !json {
+        return Err("--stream prints JSON events; use it with --json".to_string());
+    }
+    if let Some(output) = &print_only {
+        if !matches!(output.as_str(), "md" | "markdown" | "json") {
+            return Err(format!(
+                "Unknown output format: {}. Use 'md' or 'json'",
+                output
+            ));
+        }
+    }
+    // Stdout only carries the briefing when it isn't saved
+    let json = json || print_only.is_some();
+
+    // Check for API key
+    let api_key = require_api_key()?;
+    let profile = config::active_profile();
+
+    // Get settings
+    let settings = read_settings().unwrap_or_default();
+
+    // Get topics
+    let conn = db::get_connection().map_err(|e| format!("Database connection failed: {}", e))?;
+    let all_topics = db::get_all_topics(&conn)?;
+
+    let topics: Vec<String> = if let Some(ref query) = query {
+        vec![query.clone()]
+    } else if !only_topics.is_empty() {
+        // Find the specific topics
+        let mut found: Vec<String> = Vec::new();
+        for specific_topic in &only_topics {
+            match all_topics
+                .iter()
+                .find(|t| t.name.to_lowercase() == specific_topic.to_lowercase())
+            {
+                Some(t) if !found.contains(&t.name) => found.push(t.name.clone()),
+                Some(_) => {}
+                None => return Err(format!("Topic '{}' not found", specific_topic)),
+            }
+        }
+        found
+    } else {
+        // Get all enabled topics that aren't snoozed or skipping this run
+        let now = Utc::now();
+        let due: Vec<String> = all_topics
+            .iter()
+            .filter(|t| t.is_due(now))
+            .map(|t| t.name.clone())
+            .collect();
+        if print_only.is_none() {
+            db::clear_skip_next_run(&conn)?;
+        }
+        due
+    };
+
+    if topics.is_empty() {
+        if all_topics.iter().any(|t| t.enabled) {
+            return Err(
+                "All enabled topics are snoozed or skipping this run. Wake one with: claudius topics snooze <name> --off"
+                    .to_string(),
+            );
+        }
+        return Err(
+            "No topics to research. Add topics with: claudius topics add <name>".to_string(),
+        );
+    }
+
+    let preferred_sources: std::collections::HashMap<String, Vec<String>> = all_topics
+        .iter()
+        .filter(|t| topics.contains(&t.name) && !t.preferred_sources.is_empty())
+        .map(|t| (t.name.clone(), t.preferred_sources.clone()))
+        .collect();
+
+    if !json {
+        match &query {
+            Some(query) => println!("{} Researching: {}", "→".cyan(), query),
+            None => println!(
+                "{} Starting research on {} topic(s)...",
+                "→".cyan(),
+                topics.len()
+            ),
+        }
+        if offline {
+            println!("{} Offline mode: using cached content only", "→".cyan());
+        } else if batch {
+            println!(
+                "{} Batch mode: topics not finished within {} minutes are researched interactively",
+                "→".cyan(),
+                settings.batch_deadline_minutes
+            );
+        }
+        if verbose {
+            for t in &topics {
+                println!("  • {}", t);
+            }
+        }
+        println!();
+    }
+
+    // Load past card fingerprints for deduplication (an ad-hoc question wants
+    // its answer even if earlier briefings covered it)
+    let dedup_policy = claudius::dedup::DedupPolicy::for_topics(
+        settings.dedup_days,
+        settings.dedup_threshold,
+        &all_topics,
+    );
+    let (past_cards_context, past_fingerprints) = if dedup_policy.max_days() > 0 && query.is_none()
+    {
+        match db::get_recent_card_fingerprints_for(&conn, &dedup_policy) {
+            Ok(fingerprints) => {
+                let context = claudius::dedup::format_past_cards_for_prompt(
+                    &fingerprints,
+                    &topics,
+                    settings.dedup_context_tokens as usize,
+                );
+                if verbose && !json && !fingerprints.is_empty() {
+                    println!(
+                        "{} Loaded {} past cards for dedup",
+                        "→".cyan(),
+                        fingerprints.len()
+                    );
+                }
+                (Some(context), fingerprints)
+            }
+            Err(e) => {
+                if verbose && !json {
+                    eprintln!("{} Dedup unavailable: {}", "Warning:".yellow(), e);
+                }
+                (None, Vec::new())
+            }
+        }
+    } else {
+        (None, Vec::new())
+    };
+
+    // Set running state BEFORE spawning to prevent race conditions
+    let _cancellation_token = research_state::set_running("starting")
+        .map_err(|e| format!("Cannot start research: {}", e))?;
+
+    // RAII guard: ensure cleanup even if we panic or return early
+    defer! {
+        if let Err(e) = research_state::set_stopped() {
+            eprintln!("{} Failed to reset research state: {}", "Warning:".yellow(), e);
+        }
+    }
+
+    // Create research agent and run in background for progress tracking
+    let mut agent = ResearchAgent::new(
+        api_key.clone(),
+        Some(settings.model.clone()),
+        settings.enable_web_search,
+        match mode {
+            Some(mode) => claudius::research_mode::parse_research_mode(&mode)?,
+            None => settings.research_mode.clone(),
+        },
+        settings.rate_limit_firecrawl_agent,
+    );
+    agent.set_archive_sources(settings.archive_sources);
+    agent.set_preferred_sources(preferred_sources);
+    agent.set_mcp_routing(claudius::mcp_routing::McpRouting::from_topics(&all_topics));
+    agent.set_topic_timeout(std::time::Duration::from_secs(
+        u64::from(settings.topic_timeout_minutes) * 60,
+    ));
+    agent.set_tool_result_limits(
+        settings.mcp_result_max_chars,
+        settings.mcp_tool_result_limits.clone(),
+    );
+    // A --format flag applies to every topic in this run
+    match format {
+        Some(format) => agent.set_briefing_format(
+            claudius::research::parse_briefing_format(&format)?,
+            std::collections::HashMap::new(),
+        ),
+        None => agent.set_briefing_format(
+            settings.briefing_format.clone(),
+            settings.topic_formats.clone(),
+        ),
+    }
+    agent.set_recency_window(
+        settings.recency_window.clone(),
+        settings.topic_recency_windows.clone(),
+    );
+    agent.set_about_me(settings.about_me.clone());
+    agent.set_source_requirements(settings.source_recency_days, settings.repair_weak_cards);
+    agent.set_tool_filter(claudius::tools::ToolFilter::new(&settings.disabled_tools));
+    agent.set_firecrawl_budget(claudius::firecrawl_budget::FirecrawlBudget::load(
+        settings.firecrawl_run_budget,
+        settings.firecrawl_monthly_budget,
+    ));
+    agent.set_thinking_budget(settings.enable_thinking.then(|| {
+        claudius::research::thinking_budget(&settings.research_depth, &settings.thinking_budgets)
+    }));
+    agent.set_offline_mode(offline);
+    if batch {
+        agent.set_batch_deadline(Some(std::time::Duration::from_secs(
+            u64::from(settings.batch_deadline_minutes) * 60,
+        )));
+    }
+
+    let log_since = claudius::research_log::log_timestamp();
+    let start = std::time::Instant::now();
+    let condense = settings.condense_briefings;
+
+    // Spawn research on a background task
+    let research_topics = topics.clone();
+    let research_handle = tokio::spawn(async move {
+        let result = agent
+            .run_research(research_topics, None, condense, past_cards_context)
+            .await;
+        (result, agent.budget_warnings().to_vec())
+    });
+
+    // Poll for progress updates (events in stream mode, phases in non-JSON mode)
+    let mut last_phase = String::new();
+    let mut last_event: Option<u64> = None;
+    if stream {
+        loop {
+            tokio::time::sleep(std::time::Duration::from_millis(250)).await;
+            print_new_events(&mut last_event);
+            if research_handle.is_finished() {
+                break;
+            }
+        }
+    } else if !json {
+        loop {
+            tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+
+            let state = research_state::get_state();
+
+            // Print phase changes
+            if state.current_phase != last_phase && !state.current_phase.is_empty() {
+                // Clear the line and print new phase
+                print!(
+                    "\r{} {}                    ",
+                    "→".cyan(),
+                    state.current_phase
+                );
+                use std::io::Write;
+                std::io::stdout().flush().ok();
+                last_phase = state.current_phase.clone();
+            }
+
+            // Check if research is done
+            if research_handle.is_finished() {
+                println!(); // New line after progress
+                break;
+            }
+        }
+    }
+
+    // Get the result - ensure cleanup happens regardless of success/failure
+    let (research_result, budget_warnings) = match research_handle.await {
+        Ok((result, warnings)) => (result, warnings),
+        Err(e) => (Err(format!("Research task failed: {}", e)), Vec::new()),
+    };
+    for warning in &budget_warnings {
+        eprintln!("{} {}", "Warning:".yellow(), warning.message());
+    }
+    if stream {
+        // Events recorded between the last poll and the task finishing
+        print_new_events(&mut last_event);
+    }
+
+    let duration = start.elapsed();
+
+    // Note: cleanup is handled by defer! guard above (panic-safe)
+
+    // Now handle the result
+    let mut result = research_result?;
+
+    // Apply post-synthesis deduplication filter (safety net)
+    if !past_fingerprints.is_empty() {
+        let original_count = result.cards.len();
+        result.cards = claudius::dedup::filter_duplicates_by_topic(
+            result.cards,
+            &past_fingerprints,
+            &dedup_policy,
+        );
+        let filtered_count = original_count - result.cards.len();
+        if filtered_count > 0 && verbose && !json {
+            println!("{} Filtered {} duplicate cards", "→".cyan(), filtered_count);
+        }
+    }
+
+    // Drop cards below the minimum relevance setting
+    let min_relevance = if query.is_some() {
+        "low"
+    } else {
+        settings.min_relevance.as_str()
+    };
+    let (kept, discarded) = claudius::research::filter_by_relevance(result.cards, min_relevance);
+    result.cards = kept;
+    if !discarded.is_empty() && verbose && !json {
+        println!(
+            "{} Filtered {} cards below {} relevance",
+            "→".cyan(),
+            discarded.len(),
+            min_relevance
+        );
+    }
+
+    // Most relevant first, adjusted by feedback on each topic
+    claudius::research::rank_cards_by_feedback(&mut result.cards);
+    result.index_topic_cards();
+
+    if let Some(query) = &query {
+        result.title = claudius::research::adhoc_title(query);
+    }
+
+    if let Some(output) = &print_only {
+        if output == "json" {
+            println!("{}", to_json(&result));
+        } else {
+            print_briefing_markdown(&result.title, &result.date, None, &result.cards);
+        }
+        return Ok(());
+    }
+
+    publish_cli_event(
+        stream,
+        &mut last_event,
+        "research:saving",
+        serde_json::json!({
+            "timestamp": Utc::now().to_rfc3339(),
+            "total_cards": result.cards.len(),
+        }),
+    );
+
+    // Save to database
+    let cards_json = serde_json::to_string(&result.cards)
+        .map_err(|e| format!("Failed to serialize cards: {}", e))?;
+    let topics_json = serde_json::to_string(&result.topics)
+        .map_err(|e| format!("Failed to serialize topic results: {}", e))?;
+    let failed_topics_json = if result.failed_topics.is_empty() {
+        None
+    } else {
+        Some(
+            serde_json::to_string(&result.failed_topics)
+                .map_err(|e| format!("Failed to serialize failed topics: {}", e))?,
+        )
+    };
+
+    let saved = db::persist_briefing(
+        &conn,
+        &db::BriefingRecord {
+            briefing: db::NewBriefing {
+                run_id: Some(&result.run_id),
+                date: &result.date,
+                title: &result.title,
+                cards: &cards_json,
+                research_time_ms: result.research_time_ms as i64,
+                model_used: &result.model_used,
+                total_tokens: result.total_tokens as i64,
+                tag: query.as_ref().map(|_| claudius::research::ADHOC_TAG),
+                topics: Some(&topics_json),
+                failed_topics: failed_topics_json.as_deref(),
+            },
+            cards: &result.cards,
+            discarded: if settings.keep_discarded_cards {
+                &discarded
+            } else {
+                &[]
+            },
+            profile: &profile,
+            batch_tokens: result.batch_tokens as i64,
+            log_since: &log_since,
+            merge_same_day: settings
+                .merge_same_day_runs
+                .then_some(settings.dedup_threshold),
+        },
+    )?;
+    let (briefing_id, created) = (saved.id, saved.created);
+    // A run merged into the day's briefing continues with all of its cards
+    let merged = saved.merged_cards.is_some();
+    if let Some(cards) = saved.merged_cards {
+        result.cards = cards;
+    }
+
+    // Ad-hoc questions aren't topics, so they don't count toward topic
+    // activity or trigger topic discovery. Merged runs were already recorded
+    // with the day's first run.
+    if query.is_none() && created && !merged {
+        if let Err(e) = claudius::topic_activity::record_run(&conn, briefing_id, &topics) {
+            if verbose && !json {
+                eprintln!(
+                    "{} Failed to record topic activity: {}",
+                    "Warning:".yellow(),
+                    e
+                );
+            }
+        }
+    }
+
+    // Periodically look for new topics the user seems interested in
+    let last_discovery = db::last_topic_discovery(&conn).unwrap_or(None);
+    if query.is_none()
+        && claudius::topic_discovery::discovery_due(
+            last_discovery.as_deref(),
+            settings.topic_discovery_days,
+        )
+    {
+        match claudius::topic_discovery::discover_topics(&api_key, &settings.model, &profile).await
+        {
+            Ok(added) if !added.is_empty() && !json => {
+                println!(
+                    "  {} new topic suggestions (see: claudius topics suggest)",
+                    added.len().to_string().cyan()
+                );
+            }
+            Ok(_) => {}
+            Err(e) => {
+                if verbose && !json {
+                    eprintln!("{} Topic discovery failed: {}", "Warning:".yellow(), e);
+                }
+            }
+        }
+    }
+
+    // Generate images for cards that have image_prompt (if enabled and API key configured)
+    if settings.enable_image_generation && !offline {
+        if let Some(openai_key) = read_openai_api_key() {
+            if !json {
+                println!("{} Generating header images...", "→".cyan());
+            }
+            publish_cli_event(
+                stream,
+                &mut last_event,
+                "research:generating_images",
+                serde_json::json!({
+                    "timestamp": Utc::now().to_rfc3339(),
+                    "total_cards": result.cards.len(),
+                }),
+            );
+
+            let mut generated = Vec::new();
+            for (idx, card) in result.cards.iter_mut().enumerate() {
+                // Cards merged from an earlier run already have their image
+                if card.image_path.is_some() {
+                    continue;
+                }
+                if let Some(ref prompt) = card.image_prompt {
+                    if verbose && !json {
+                        println!("  {} Generating image for card {}...", "→".dimmed(), idx);
+                    }
+
+                    match image_gen::generate_image(
+                        prompt,
+                        &settings.image_style,
+                        briefing_id,
+                        idx,
+                        &openai_key,
+                    )
+                    .await
+                    {
+                        image_gen::ImageGenResult::Success(path) => {
+                            card.image_path = Some(path.to_string_lossy().to_string());
+                            card.image_style = Some(settings.image_style.clone());
+                            generated.push(path);
+                            if verbose && !json {
+                                println!("    {} Image saved", "✓".green());
+                            }
+                        }
+                        image_gen::ImageGenResult::Disabled => {
+                            if verbose && !json {
+                                println!("    {} Image generation disabled", "○".dimmed());
+                            }
+                            break;
+                        }
+                        image_gen::ImageGenResult::NoApiKey => {
+                            if !json {
+                                println!("    {} No OpenAI API key configured", "!".yellow());
+                            }
+                            break;
+                        }
+                        image_gen::ImageGenResult::Failed(err) => {
+                            if verbose && !json {
+                                println!("    {} Failed: {}", "✗".red(), err);
+                            }
+                            // Continue with other cards
+                        }
+                    }
+                }
+            }
+
+            // Update briefing with image paths if any were generated
+            if !generated.is_empty() {
+                db::save_briefing_images(&conn, briefing_id, &result.cards, &generated)?;
+
+                if !json {
+                    println!("{} Generated {} images", "✓".green(), generated.len());
+                }
+            }
+
+            // One hero image for the whole briefing, shown in briefing lists
+            if settings.enable_cover_image && !merged {
+                if let Some(prompt) = image_gen::cover_prompt(&result.title, &result.cards) {
+                    if verbose && !json {
+                        println!("  {} Generating cover image...", "→".dimmed());
+                    }
+                    match image_gen::generate_cover_image(
+                        &prompt,
+                        &settings.image_style,
+                        briefing_id,
+                        &openai_key,
+                    )
+                    .await
+                    {
+                        image_gen::ImageGenResult::Success(path) => {
+                            let saved = db::set_briefing_cover(&conn, briefing_id, &prompt, &path);
+                            if !json {
+                                match saved {
+                                    Ok(()) => println!("{} Generated cover image", "✓".green()),
+                                    Err(e) => {
+                                        println!("    {} Cover image not saved: {}", "✗".red(), e)
+                                    }
+                                }
+                            }
+                        }
+                        image_gen::ImageGenResult::Failed(err) if verbose && !json => {
+                            println!("    {} Cover image failed: {}", "✗".red(), err);
+                        }
+                        _ => {}
+                    }
+                }
+            }
+        } else if verbose && !json {
+            println!(
+                "{} Image generation enabled but no OpenAI API key configured",
+                "!".yellow()
+            );
+        }
+    }
+
+    // Export to configured integrations (e.g. Obsidian vault, Notion)
+    let schedule = claudius::smart_schedule::effective_schedule(&settings);
+    let export_targets = integrations::configured_targets(integrations::ExportConfig {
+        obsidian_vault_path: settings.obsidian_vault_path.as_deref(),
+        obsidian_folder: &settings.obsidian_folder,
+        enable_notion_sync: settings.enable_notion_sync,
+        notion_database_id: settings.notion_database_id.as_deref(),
+        notion_token: read_notion_token(),
+        enable_atom_feed: settings.enable_atom_feed,
+        atom_feed_path: settings.atom_feed_path.as_deref(),
+        enable_schedule_calendar: settings.enable_schedule_calendar,
+        schedule_calendar_path: settings.schedule_calendar_path.as_deref(),
+        schedule_cron: &schedule,
+    });
+    if !export_targets.is_empty() {
+        let written = integrations::export_to_all(
+            &export_targets,
+            &integrations::ExportBriefing {
+                id: briefing_id,
+                date: &result.date,
+                title: &result.title,
+                model_used: Some(&result.model_used),
+                cards: &result.cards,
+                first_new_card: saved.first_new_card,
+            },
+        )
+        .await;
+        if verbose && !json {
+            for location in &written {
+                println!("{} Exported to {}", "✓".green(), location);
+            }
+        }
+    }
+
+    if stream {
+        // The last line of the stream, with the same details as --json alone
+        publish_cli_event(
+            stream,
+            &mut last_event,
+            "research:completed",
+            serde_json::json!({
+                "timestamp": Utc::now().to_rfc3339(),
+                "total_cards": result.cards.len(),
+                "duration_ms": duration.as_millis(),
+                "briefing_id": briefing_id,
+                "url": claudius::deep_link::briefing_url(briefing_id),
+                "title": result.title,
+                "tag": query.as_ref().map(|_| claudius::research::ADHOC_TAG),
+                "model": result.model_used,
+                "tokens": result.total_tokens,
+                "failed_topics": result.failed_topics,
+                "merged": merged,
+            }),
+        );
+    } else if json {
+        println!(
+            "{}",
+            to_json(&serde_json::json!({
+                "status": "completed",
+                "briefing_id": briefing_id,
+                "url": claudius::deep_link::briefing_url(briefing_id),
+                "title": result.title,
+                "tag": query.as_ref().map(|_| claudius::research::ADHOC_TAG),
+                "cards": result.cards.len(),
+                "duration_ms": duration.as_millis(),
+                "model": result.model_used,
+                "tokens": result.total_tokens,
+                "failed_topics": result.failed_topics,
+                "merged": merged,
+            }))
+        );
+    } else {
+        println!("{} Research completed!", "✓".green().bold());
+        println!();
+        println!(
+            "  {} briefing cards generated",
+            result.cards.len().to_string().cyan()
+        );
+        if merged {
+            println!("  Added to today's briefing #{}", briefing_id);
+        }
+        println!("  Duration: {}s", duration.as_secs());
+        println!("  Model: {}", result.model_used.dimmed());
+        println!();
+        if !result.failed_topics.is_empty() {
+            println!(
+                "{} {} topic(s) failed and were left out:",
+                "Warning:".yellow(),
+                result.failed_topics.len()
+            );
+            for failed in &result.failed_topics {
+                println!(
+                    "  {} {}: {}",
+                    "✗".red(),
+                    failed.topic,
+                    failed.error.dimmed()
+                );
+            }
+            if let Some(command) = claudius::research::retry_command(&result.failed_topics) {
+                println!("Retry with: {}", command);
+            }
+            println!();
+        }
+        println!("View with: claudius briefings list");
+        println!("Run report: claudius research report {}", briefing_id);
+    }
+
+    // Try to refresh the desktop app if it's running
+    // This uses the single-instance plugin to send a refresh signal
+    if let Err(e) = std::process::Command::new("open")
+        .args(["-a", "Claudius", "--args", "--refresh"])
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .spawn()
+    {
+        // Silently ignore if app isn't installed or can't be opened
+        if verbose && !json {
+            println!("{} Could not refresh desktop app: {}", "!".dimmed(), e);
+        }
+    }
+
+    Ok(())
+}
+
+/// Print progress events recorded after `last_event` as newline-delimited
+/// JSON, one `{"seq", "event", "payload"}` object per line.
+fn print_new_events(last_event: &mut Option<u64>) {
+    use std::io::Write;
+    let mut stdout = std::io::stdout().lock();
+    for event in research_state::event_backlog(*last_event) {
+        if let Ok(line) = serde_json::to_string(&event) {
+            let _ = writeln!(stdout, "{}", line);
+        }
+        *last_event = Some(event.seq);
+    }
+    let _ = stdout.flush();
+}
+
+/// Record a progress event the CLI raises itself (the app emits the same
+/// ones), printing it right away in stream mode.
+fn publish_cli_event(
+    stream: bool,
+    last_event: &mut Option<u64>,
+    event: &str,
+    payload: serde_json::Value,
+) {
+    research_state::publish(None, event, payload);
+    if stream {
+        print_new_events(last_event);
+    }
+}
+
+/// Print the last `limit` records of the active research log, then keep
+/// printing new records as they are written. Handles size rotation and the
+/// switch to a new file at midnight.
+async fn follow_research_log(limit: i64, errors: bool, json: bool) -> Result<(), String> {
+    let path = log_sink::active_log_path();
+    let mut offset = 0u64;
+
+    if let Ok(content) = std::fs::read_to_string(&path) {
+        let lines: Vec<&str> = content.lines().collect();
+        let skip = lines.len().saturating_sub(limit.max(0) as usize);
+        for line in &lines[skip..] {
+            print_log_record(line, errors, json);
+        }
+        offset = content.len() as u64;
+    }
+
+    if !json {
+        eprintln!(
+            "{}",
+            format!("Following {} (Ctrl+C to stop)", path.display()).dimmed()
+        );
+    }
+
+    let mut tail = log_sink::LogTail::research(offset);
+    loop {
+        tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+        for line in tail.poll()? {
+            print_log_record(&line, errors, json);
+        }
+    }
+}
+
+fn print_log_record(line: &str, errors: bool, json: bool) {
+    let record: log_sink::LogRecord = match serde_json::from_str(line) {
+        Ok(record) => record,
+        Err(_) => return,
+    };
+    if errors && record.level == "info" {
+        return;
+    }
+    if json {
+        println!("{}", line);
+        return;
+    }
+
+    let level = match record.level.as_str() {
+        "error" => record.level.red(),
+        "warn" => record.level.yellow(),
+        _ => record.level.normal(),
+    };
+    println!(
+        "[{}] {} {} {}",
+        record
+            .timestamp
+            .get(..19)
+            .unwrap_or(&record.timestamp)
+            .dimmed(),
+        level,
+        record.event.cyan(),
+        record.message
+    );
+}
+
+fn require_api_key() -> Result<String, String> {
+    read_api_key().ok_or_else(|| {
+        format!(
+            "{}\n\n{}\n  {}\n\n{}\n  {}",
+            "Error: No API key configured.".red().bold(),
+            "Set your Anthropic API key with:",
+            "claudius config api-key set <YOUR_KEY>".cyan(),
+            "Or create ~/.claudius/.env with:",
+            "ANTHROPIC_API_KEY=sk-ant-...".dimmed()
+        )
+    })
+}
+
+// ============================================================================
+// MCP Handlers
+// ============================================================================
+
+async fn handle_mcp(action: McpAction, json: bool) -> Result<(), String> {
+    match action {
+        McpAction::List => {
+            let config = read_mcp_servers()?;
+
+            if json {
+                println!(
+                    "{}",
+                    to_json(&serde_json::json!({
+                        "servers": config.servers
+                    }))
+                );
+            } else if config.servers.is_empty() {
+                println!("{}", "No MCP servers configured.".yellow());
+                println!("Add a server with: claudius mcp add <name> --command <cmd>");
+            } else {
+                let mut table = Table::new();
+                table.load_preset(UTF8_FULL);
+                table.set_content_arrangement(ContentArrangement::Dynamic);
+                table.set_header(vec!["Name", "Status", "Command", "ID"]);
+
+                for server in &config.servers {
+                    let status = if server.enabled {
+                        "✓ enabled".green().to_string()
+                    } else {
+                        "○ disabled".dimmed().to_string()
+                    };
+                    let command = server
+                        .config
+                        .get("command")
+                        .and_then(|v| v.as_str())
+                        .unwrap_or("-");
+                    let short_id = if server.id.len() >= 8 {
+                        &server.id[..8]
+                    } else {
+                        &server.id
+                    };
+                    table.add_row(vec![&server.name, &status, command, short_id]);
+                }
+
+                println!("{table}");
+                println!("\n{} servers total", config.servers.len());
+            }
+        }
+
+        McpAction::Add {
+            name,
+            command,
+            args,
+            env,
+        } => {
+            let mut config = read_mcp_servers()?;
+
+            // Build config object
+            let mut server_config = serde_json::json!({
+                "command": command,
+            });
+
+            if let Some(args_str) = args {
+                let args_vec: Vec<&str> = args_str.split_whitespace().collect();
+                server_config["args"] = serde_json::json!(args_vec);
+            }
+
+            if let Some(env_vars) = env {
+                let mut env_map = serde_json::Map::new();
+                for var in env_vars {
+                    if let Some((key, value)) = var.split_once('=') {
+                        env_map.insert(key.to_string(), serde_json::json!(value));
+                    }
+                }
+                if !env_map.is_empty() {
+                    server_config["env"] = serde_json::Value::Object(env_map);
+                }
+            }
+
+            let server = MCPServer {
+                id: Uuid::new_v4().to_string(),
+                name: name.clone(),
+                enabled: true,
+                config: server_config,
+                last_used: None,
+            };
+
+            config.servers.push(server.clone());
+            write_mcp_servers(&config)?;
+
+            if json {
+                println!("{}", to_json(&server));
+            } else {
+                println!("{} Added MCP server '{}'", "✓".green(), name);
+            }
+        }
+
+        McpAction::Remove { id } => {
+            let mut config = read_mcp_servers()?;
+            let server = find_mcp_server(&config, &id)?;
+            let name = server.name.clone();
+
+            config.servers.retain(|s| s.id != server.id);
+            write_mcp_servers(&config)?;
+
+            if json {
+                println!("{}", serde_json::json!({ "deleted": server.id }));
+            } else {
+                println!("{} Removed MCP server '{}'", "✓".green(), name);
+            }
+        }
+
+        McpAction::Enable { id } => {
+            let mut config = read_mcp_servers()?;
+            let server = find_mcp_server_mut(&mut config, &id)?;
+            server.enabled = true;
+            let name = server.name.clone();
+            let server_clone = server.clone();
+            write_mcp_servers(&config)?;
+
+            if json {
+                println!("{}", to_json(&server_clone));
+            } else {
+                println!("{} Enabled MCP server '{}'", "✓".green(), name);
+            }
+        }
+
+        McpAction::Disable { id } => {
+            let mut config = read_mcp_servers()?;
+            let server = find_mcp_server_mut(&mut config, &id)?;
+            server.enabled = false;
+            let name = server.name.clone();
+            let server_clone = server.clone();
+            write_mcp_servers(&config)?;
+
+            if json {
+                println!("{}", to_json(&server_clone));
+            } else {
+                println!("{} Disabled MCP server '{}'", "✓".green(), name);
+            }
+        }
+
+        McpAction::Catalog { refresh } => {
+            let catalog = mcp_catalog::load_catalog(refresh).await;
+            let installed = read_mcp_servers()?;
+            let is_installed = |name: &str| {
+                installed
+                    .servers
+                    .iter()
+                    .any(|s| s.name.eq_ignore_ascii_case(name))
+            };
+
+            if json {
+                println!("{}", to_json(&catalog));
+            } else {
+                let mut table = Table::new();
+                table.load_preset(UTF8_FULL);
+                table.set_content_arrangement(ContentArrangement::Dynamic);
+                table.set_header(vec!["ID", "Name", "Description", "Pricing", "Key"]);
+
+                for entry in &catalog.servers {
+                    let name = if is_installed(&entry.name) {
+                        format!("{} {}", entry.name, "(installed)".green())
+                    } else {
+                        entry.name.clone()
+                    };
+                    table.add_row(vec![
+                        entry.id.clone(),
+                        name,
+                        entry.description.clone(),
+                        entry.pricing.clone(),
+                        entry.key_env().unwrap_or("-").to_string(),
+                    ]);
+                }
+
+                println!("{table}");
+                println!("\nInstall with: claudius mcp install <id> --key <key>");
+            }
+        }
+
+        McpAction::Install {
+            preset,
+            key,
+            env,
+            no_verify,
+        } => {
+            let catalog = mcp_catalog::load_catalog(false).await;
+            let entry = catalog.find(&preset).ok_or_else(|| {
+                format!(
+                    "'{}' is not in the MCP catalog. Run 'claudius mcp catalog' to see available servers",
+                    preset
+                )
+            })?;
+
+            let mut extra_env: Vec<(String, String)> = env
+                .unwrap_or_default()
+                .iter()
+                .filter_map(|var| var.split_once('='))
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect();
+
+            // Ask for missing values when run interactively
+            for field in entry.missing_fields(key.as_deref(), &extra_env) {
+                if json || !std::io::stdin().is_terminal() {
+                    let hint = field
+                        .url
+                        .as_deref()
+                        .map(|url| format!(" (get one at {})", url))
+                        .unwrap_or_default();
+                    return Err(format!(
+                        "{} needs {}. Pass it with --key{}",
+                        entry.name, field.label, hint
+                    ));
+                }
+                if let Some(url) = &field.url {
+                    eprintln!("Get your {} at {}", field.label, url);
+                }
+                let value = prompt(&format!("{} ({})", field.label, field.env_var))?;
+                extra_env.push((field.env_var, value));
+            }
+
+            if !json && !no_verify {
+                println!(
+                    "{} Starting {} to check the setup...",
+                    "→".cyan(),
+                    entry.name
+                );
+            }
+            let server =
+                mcp_catalog::install(entry, key.as_deref(), &extra_env, !no_verify).await?;
+
+            if json {
+                println!("{}", to_json(&server));
+            } else {
+                println!("{} Installed MCP server '{}'", "✓".green(), server.name);
+                if no_verify {
+                    println!("Check it with: claudius mcp test \"{}\"", server.name);
+                }
+            }
+        }
+
+        McpAction::Test { name } => {
+            let config = read_mcp_servers()?;
+            let server = find_mcp_server(&config, &name)?;
+
+            if !json {
+                println!("{} Testing MCP server '{}'...", "→".cyan(), server.name);
+                let unset = mcp_catalog::unset_env_vars(&server.config);
+                if !unset.is_empty() {
+                    println!("{} No value set for {}", "!".yellow(), unset.join(", "));
+                }
+            }
+
+            match mcp_catalog::verify(&server.name, &server.config, None).await {
+                Ok(tools) => {
+                    if json {
+                        println!(
+                            "{}",
+                            to_json(&serde_json::json!({
+                                "status": "success",
+                                "server": server.name,
+                                "tools": tools.len(),
+                            }))
+                        );
+                    } else {
+                        println!("{} Connection successful!", "✓".green());
+                        println!("  Available tools: {}", tools.len());
+                        for tool in &tools {
+                            println!(
+                                "    • {} (as {})",
+                                tool,
+                                namespaced_tool_name(&server.name, tool)
+                            );
+                        }
+                    }
+                }
+                Err(e) => {
+                    if json {
+                        println!(
+                            "{}",
+                            to_json(&serde_json::json!({
+                                "status": "error",
+                                "server": server.name,
+                                "error": e,
+                            }))
+                        );
+                    } else {
+                        println!("{} Connection failed: {}", "✗".red(), e);
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// One line about a briefing that is about to be deleted.
+fn describe_deletion(briefing: &db::BriefingDeletion) -> String {
+    let mut details = vec![
+        briefing.date.chars().take(10).collect::<String>(),
+        format!("{} cards", briefing.cards),
+    ];
+    if briefing.bookmarks > 0 {
+        details.push(format!("{} bookmarked", briefing.bookmarks));
+    }
+    if briefing.chat_messages > 0 {
+        details.push(format!("{} chat messages", briefing.chat_messages));
+    }
+    format!(
+        "briefing {} \"{}\" ({})",
+        briefing.id,
+        briefing.title,
+        details.join(", ")
+    )
+}
+
+/// Ask a yes/no question on the terminal (default no). `yes` answers it
+/// up front; without a terminal to ask on it must be set.
+fn confirm(question: &str, yes: bool) -> Result<bool, String> {
+    use std::io::Write;
+    if yes {
+        return Ok(true);
+    }
+    if !std::io::stdin().is_terminal() {
+        return Err(format!("{} Pass --yes to confirm.", question));
+    }
+    eprint!("{} [y/N] ", question);
+    std::io::stderr()
+        .flush()
+        .map_err(|e| format!("Failed to write prompt: {}", e))?;
+    let mut answer = String::new();
+    std::io::stdin()
+        .read_line(&mut answer)
+        .map_err(|e| format!("Failed to read input: {}", e))?;
+    Ok(matches!(answer.trim().to_lowercase().as_str(), "y" | "yes"))
+}
+
+/// Ask for a value on the terminal.
+fn prompt(label: &str) -> Result<String, String> {
+    use std::io::Write;
+    eprint!("{}: ", label);
+    std::io::stderr()
+        .flush()
+        .map_err(|e| format!("Failed to write prompt: {}", e))?;
+    let mut value = String::new();
+    std::io::stdin()
+        .read_line(&mut value)
+        .map_err(|e| format!("Failed to read input: {}", e))?;
+    let value = value.trim().to_string();
+    if value.is_empty() {
+        return Err(format!("No {} entered", label));
+    }
+    Ok(value)
+}
+
+fn find_mcp_server(config: &MCPServersConfig, id_or_name: &str) -> Result<MCPServer, String> {
+    for server in &config.servers {
+        if server.id == id_or_name
+            || server.name.to_lowercase() == id_or_name.to_lowercase()
+            || server.id.starts_with(id_or_name)
+        {
+            return Ok(server.clone());
+        }
+    }
+    Err(format!("MCP server '{}' not found", id_or_name))
+}
+
+fn find_mcp_server_mut<'a>(
+    config: &'a mut MCPServersConfig,
+    id_or_name: &str,
+) -> Result<&'a mut MCPServer, String> {
+    for server in &mut config.servers {
+        if server.id == id_or_name
+            || server.name.to_lowercase() == id_or_name.to_lowercase()
+            || server.id.starts_with(id_or_name)
+        {
+            return Ok(server);
+        }
+    }
+    Err(format!("MCP server '{}' not found", id_or_name))
+}
+
+// ============================================================================
+// Config Handlers
+// ============================================================================
+
+async fn handle_config(action: ConfigAction, json: bool) -> Result<(), String> {
+    match action {
+        ConfigAction::Show => {
+            let settings = read_settings().unwrap_or_default();
+            let has_key = has_api_key();
+            let config_dir = get_config_dir();
+
+            if json {
+                println!(
+                    "{}",
+                    to_json(&serde_json::json!({
+                        "workspace": config::active_workspace(),
+                        "config_dir": config_dir.display().to_string(),
+                        "data_dir": config::get_data_dir().display().to_string(),
+                        "cache_dir": config::get_cache_dir().display().to_string(),
+                        "api_key_set": has_key,
+                        "settings": settings,
+                    }))
+                );
+            } else {
+                println!("{}", "Configuration".bold());
+                println!();
+                println!(
+                    "  Config directory: {}",
+                    config_dir.display().to_string().dimmed()
+                );
+                let workspace = config::active_workspace();
+                if workspace != config::DEFAULT_WORKSPACE {
+                    println!("  Workspace: {}", workspace.cyan());
+                }
+                let data_dir = config::get_data_dir();
+                if data_dir != config_dir {
+                    println!(
+                        "  Data directory: {}",
+                        data_dir.display().to_string().dimmed()
+                    );
+                }
+                let cache_dir = config::get_cache_dir();
+                if cache_dir != config_dir.join("cache") {
+                    println!(
+                        "  Cache directory: {}",
+                        cache_dir.display().to_string().dimmed()
+                    );
+                }
+                println!(
+                    "  API key: {}",
+                    if has_key {
+                        "✓ set".green().to_string()
+                    } else {
+                        "✗ not set".red().to_string()
+                    }
+                );
+                println!();
+                println!("{}", "Research Settings".bold());
+                println!();
+                println!("  Model: {}", settings.model.cyan());
+                if !settings.about_me.is_empty() {
+                    println!("  About me: {}", settings.about_me);
+                }
+                println!("  Research depth: {}", settings.research_depth);
+                println!("  Research mode: {}", settings.research_mode);
+                if settings.firecrawl_run_budget > 0 || settings.firecrawl_monthly_budget > 0 {
+                    let limit = |n: u32| {
+                        if n == 0 {
+                            "no limit".to_string()
+                        } else {
+                            format!("{} calls", n)
+                        }
+                    };
+                    println!(
+                        "  Firecrawl budget: {} per run, {} per month",
+                        limit(settings.firecrawl_run_budget),
+                        limit(settings.firecrawl_monthly_budget)
+                    );
+                }
+                if settings.enable_thinking {
+                    println!(
+                        "  Extended thinking: {} tokens per step",
+                        claudius::research::thinking_budget(
+                            &settings.research_depth,
+                            &settings.thinking_budgets
+                        )
+                    );
+                }
+                if let Some(base_url) = &settings.api_base_url {
+                    println!("  API base URL: {}", base_url);
+                }
+                if let Some(user_id) = &settings.api_user_id {
+                    println!("  API user ID: {}", user_id);
+                }
+                if !settings.api_headers.is_empty() {
+                    let mut names: Vec<&String> = settings.api_headers.keys().collect();
+                    names.sort();
+                    println!(
+                        "  Extra API headers: {}",
+                        names
+                            .iter()
+                            .map(|n| n.as_str())
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    );
+                }
+                if let Some(proxy) = &settings.proxy_url {
+                    println!("  Proxy: {}", proxy);
+                }
+                if let Some(path) = &settings.ca_cert_path {
+                    println!("  Extra CA certificates: {}", path);
+                }
+                if settings.schedule_mode == "adaptive" {
+                    println!(
+                        "  Schedule (daemon): adaptive, until learned {}",
+                        settings.schedule_cron
+                    );
+                } else {
+                    println!("  Schedule (daemon): {}", settings.schedule_cron);
+                }
+                println!("  Global shortcut: {}", settings.global_shortcut);
+                println!(
+                    "  Quick research shortcut: {} ({})",
+                    settings.quick_research_shortcut, settings.quick_research_mode
+                );
+                println!("  Release channel: {}", settings.release_channel);
+                if settings.meta_briefings != "off" {
+                    println!("  Meta-briefings (daemon): {}", settings.meta_briefings);
+                }
+                if !settings.catch_up_missed_runs {
+                    println!("  Missed scheduled runs: skipped");
+                }
+                if settings.batch_scheduled_research {
+                    println!(
+                        "  Scheduled runs: Batch API ({} minute deadline)",
+                        settings.batch_deadline_minutes
+                    );
+                }
+                println!(
+                    "  Max sources per topic: {}",
+                    settings.max_sources_per_topic
+                );
+                println!(
+                    "  Notifications: {}",
+                    if settings.enable_notifications {
+                        "enabled"
+                    } else {
+                        "disabled"
+                    }
+                );
+                if settings.enable_notifications {
+                    for event in claudius::notification_prefs::NotificationEvent::ALL {
+                        let preference = claudius::notification_prefs::preference(&settings, event);
+                        if preference != event.default_preference() {
+                            println!("    {}: {}", event.as_str(), preference);
+                        }
+                    }
+                }
+                if let (Some(start), Some(end)) =
+                    (&settings.quiet_hours_start, &settings.quiet_hours_end)
+                {
+                    println!("  Quiet hours: {}-{}", start, end);
+                }
+                if settings.quiet_weekends {
+                    println!("  Quiet weekends: enabled");
+                }
+                if settings.respect_system_dnd {
+                    println!("  Respect Do Not Disturb: enabled");
+                }
+                println!(
+                    "  Web search: {}",
+                    if settings.enable_web_search {
+                        "enabled"
+                    } else {
+                        "disabled"
+                    }
+                );
+                if !settings.disabled_tools.is_empty() {
+                    println!("  Disabled tools: {}", settings.disabled_tools.join(", "));
+                }
+            }
+        }
+
+        ConfigAction::Set { key, value } => {
+            // A corrupt preferences file must not be silently replaced by defaults
+            let mut settings = read_settings()?;
+
+            match key.as_str() {
+                "model" => {
+                    // Validate against the live list when possible, else the cache
+                    let known = match read_api_key() {
+                        Some(api_key) => models::list_models(&api_key, false)
+                            .await
+                            .unwrap_or_default(),
+                        None => models::cached_models().unwrap_or_default(),
+                    };
+                    let check = models::check_model(&value, &known);
+                    if let Some(msg) = check.message(&value) {
+                        if matches!(check, models::ModelCheck::Unknown(_)) {
+                            return Err(msg);
+                        }
+                        eprintln!("{} {}", "Warning:".yellow(), msg);
+                    }
+                    settings.model = value.clone();
+                }
+                "research_depth" | "depth" => settings.research_depth = value.clone(),
+                "research_mode" | "mode" => {
+                    settings.research_mode = claudius::research_mode::parse_research_mode(&value)?;
+                }
+                "max_sources" | "max_sources_per_topic" => {
+                    settings.max_sources_per_topic = value
+                        .parse()
+                        .map_err(|_| "Invalid number for max_sources")?;
+                }
+                "dedup_context_tokens" => {
+                    // Token budget for recent cards in the synthesis prompt
+                    settings.dedup_context_tokens = value
+                        .parse()
+                        .map_err(|_| "Invalid number for dedup_context_tokens")?;
+                }
+                "notifications" | "enable_notifications" => {
+                    settings.enable_notifications = value
+                        .parse()
+                        .map_err(|_| "Invalid boolean for notifications")?;
+                }
+                "web_search" | "enable_web_search" => {
+                    settings.enable_web_search = value
+                        .parse()
+                        .map_err(|_| "Invalid boolean for web_search")?;
+                }
+                "archive_sources" => {
+                    settings.archive_sources = value
+                        .parse()
+                        .map_err(|_| "Invalid boolean for archive_sources")?;
+                }
+                "tts_engine" => match value.as_str() {
+                    "openai" | "local" => settings.tts_engine = value.clone(),
+                    _ => return Err("Invalid tts_engine. Use 'openai' or 'local'".to_string()),
+                },
+                "obsidian_vault_path" | "vault" => {
+                    settings.obsidian_vault_path = if value.is_empty() || value == "none" {
+                        None
                     } else {
-                        &server.id
+                        Some(value.clone())
                     };
-                    table.add_row(vec![&server.name, &status, command, short_id]);
                 }
+                "obsidian_folder" => settings.obsidian_folder = value.clone(),
+                "read_later_service" => {
+                    settings.read_later_service = if value.is_empty() || value == "none" {
+                        None
+                    } else if read_later::ReadLaterService::from_setting(&value).is_some() {
+                        Some(value.to_lowercase())
+                    } else {
+                        return Err(
+                            "Invalid read_later_service. Use pocket, readwise, instapaper or none"
+                                .to_string(),
+                        );
+                    };
+                }
+                "notion_database_id" => {
+                    settings.notion_database_id = if value.is_empty() || value == "none" {
+                        None
+                    } else {
+                        Some(value.clone())
+                    };
+                }
+                "enable_notion_sync" | "notion_sync" => {
+                    settings.enable_notion_sync = value
+                        .parse()
+                        .map_err(|_| "Invalid boolean for enable_notion_sync")?;
+                }
+                "enable_atom_feed" | "atom_feed" => {
+                    settings.enable_atom_feed = value
+                        .parse()
+                        .map_err(|_| "Invalid boolean for enable_atom_feed")?;
+                }
+                "atom_feed_path" => {
+                    settings.atom_feed_path = if value.is_empty() || value == "none" {
+                        None
+                    } else {
+                        Some(value.clone())
+                    };
+                }
+                "enable_schedule_calendar" | "schedule_calendar" => {
+                    settings.enable_schedule_calendar = value
+                        .parse()
+                        .map_err(|_| "Invalid boolean for enable_schedule_calendar")?;
+                }
+                "schedule_calendar_path" => {
+                    settings.schedule_calendar_path = if value.is_empty() || value == "none" {
+                        None
+                    } else {
+                        Some(value.clone())
+                    };
+                }
+                "respect_robots_txt" => {
+                    settings.respect_robots_txt = value
+                        .parse()
+                        .map_err(|_| "Invalid boolean for respect_robots_txt")?;
+                }
+                "crawl_delay_ms" => {
+                    settings.crawl_delay_ms = value
+                        .parse()
+                        .map_err(|_| "Invalid number for crawl_delay_ms")?;
+                }
+                "fetch_contact" => {
+                    settings.fetch_contact = if value.is_empty() || value == "none" {
+                        None
+                    } else {
+                        Some(value.clone())
+                    };
+                }
+                "source_recency_days" => {
+                    settings.source_recency_days = value
+                        .parse()
+                        .map_err(|_| "Invalid number for source_recency_days")?;
+                }
+                "repair_weak_cards" => {
+                    settings.repair_weak_cards = value
+                        .parse()
+                        .map_err(|_| "Invalid boolean for repair_weak_cards")?;
+                }
+                "debug_logging" => {
+                    settings.debug_logging = value
+                        .parse()
+                        .map_err(|_| "Invalid boolean for debug_logging")?;
+                }
+                "topic_timeout_minutes" | "topic_timeout" => {
+                    let minutes: u32 = value
+                        .parse()
+                        .map_err(|_| "Invalid number for topic_timeout_minutes")?;
+                    if minutes == 0 {
+                        return Err("topic_timeout_minutes must be at least 1".to_string());
+                    }
+                    settings.topic_timeout_minutes = minutes;
+                }
+                "firecrawl_run_budget" => {
+                    settings.firecrawl_run_budget = value
+                        .parse()
+                        .map_err(|_| "Invalid number for firecrawl_run_budget")?;
+                }
+                "firecrawl_monthly_budget" => {
+                    settings.firecrawl_monthly_budget = value
+                        .parse()
+                        .map_err(|_| "Invalid number for firecrawl_monthly_budget")?;
+                }
+                "mcp_result_max_chars" => {
+                    settings.mcp_result_max_chars = value
+                        .parse()
+                        .map_err(|_| "Invalid number for mcp_result_max_chars")?;
+                }
+                "briefing_format" | "format" => {
+                    settings.briefing_format = claudius::research::parse_briefing_format(&value)?;
+                }
+                "topic_format" => {
+                    // Format: <topic>=<format>, where an empty format removes the override
+                    let (topic, format) = value
+                        .split_once('=')
+                        .ok_or("Use topic_format <topic>=<format>")?;
+                    if format.trim().is_empty() {
+                        settings.topic_formats.remove(topic.trim());
+                    } else {
+                        settings.topic_formats.insert(
+                            topic.trim().to_string(),
+                            claudius::research::parse_briefing_format(format)?,
+                        );
+                    }
+                }
+                "recency_window" => {
+                    settings.recency_window = claudius::research::parse_recency_window(&value)?;
+                }
+                "topic_recency_window" => {
+                    // Format: <topic>=<window>, where an empty window removes the override
+                    let (topic, window) = value
+                        .split_once('=')
+                        .ok_or("Use topic_recency_window <topic>=<window>")?;
+                    if window.trim().is_empty() {
+                        settings.topic_recency_windows.remove(topic.trim());
+                    } else {
+                        settings.topic_recency_windows.insert(
+                            topic.trim().to_string(),
+                            claudius::research::parse_recency_window(window)?,
+                        );
+                    }
+                }
+                "enable_thinking" | "thinking" => {
+                    settings.enable_thinking = value
+                        .parse()
+                        .map_err(|_| "Invalid boolean for enable_thinking")?;
+                }
+                "thinking_budget" => {
+                    // Format: <depth>=<tokens>, where 0 restores the default budget
+                    let (depth, tokens) = value
+                        .split_once('=')
+                        .ok_or("Use thinking_budget <depth>=<tokens>")?;
+                    let depth = depth.trim().to_lowercase();
+                    if !claudius::research::RESEARCH_DEPTHS.contains(&depth.as_str()) {
+                        return Err(format!(
+                            "Invalid depth '{}'. Use one of: {}",
+                            depth,
+                            claudius::research::RESEARCH_DEPTHS.join(", ")
+                        ));
+                    }
+                    let tokens: u32 = tokens
+                        .trim()
+                        .parse()
+                        .map_err(|_| "Invalid number for thinking_budget")?;
+                    if tokens == 0 {
+                        settings.thinking_budgets.remove(&depth);
+                    } else {
+                        settings.thinking_budgets.insert(depth, tokens);
+                    }
+                }
+                "disable_tool" => {
+                    // Format: <tool> or <server>/<tool> for one MCP server's tool
+                    let tool = value.trim().to_lowercase();
+                    if tool.is_empty() {
+                        return Err("Use disable_tool <tool> or <server>/<tool>".to_string());
+                    }
+                    if !settings.disabled_tools.contains(&tool) {
+                        settings.disabled_tools.push(tool);
+                    }
+                }
+                "enable_tool" => {
+                    let tool = value.trim().to_lowercase();
+                    settings.disabled_tools.retain(|t| t.to_lowercase() != tool);
+                }
+                "mcp_tool_result_limit" => {
+                    // Format: <tool>=<chars>, where 0 removes the override
+                    let (tool, chars) = value
+                        .split_once('=')
+                        .ok_or("Use mcp_tool_result_limit <tool>=<chars>")?;
+                    let chars: usize = chars
+                        .trim()
+                        .parse()
+                        .map_err(|_| "Invalid number for mcp_tool_result_limit")?;
+                    if chars == 0 {
+                        settings.mcp_tool_result_limits.remove(tool.trim());
+                    } else {
+                        settings
+                            .mcp_tool_result_limits
+                            .insert(tool.trim().to_string(), chars);
+                    }
+                }
+                "min_relevance" => {
+                    let level = value.trim().to_lowercase();
+                    if !claudius::research::RELEVANCE_LEVELS.contains(&level.as_str()) {
+                        return Err("Invalid min_relevance. Use low, medium or high".to_string());
+                    }
+                    settings.min_relevance = level;
+                }
+                "enable_cover_image" => {
+                    settings.enable_cover_image = value
+                        .parse()
+                        .map_err(|_| "Invalid boolean for enable_cover_image")?;
+                }
+                "keep_discarded_cards" => {
+                    settings.keep_discarded_cards = value
+                        .parse()
+                        .map_err(|_| "Invalid boolean for keep_discarded_cards")?;
+                }
+                "merge_same_day_runs" => {
+                    settings.merge_same_day_runs = value
+                        .parse()
+                        .map_err(|_| "Invalid boolean for merge_same_day_runs")?;
+                }
+                "about_me" => settings.about_me = value.trim().to_string(),
+                "schedule_cron" => {
+                    daemon::parse_schedule(&value)?;
+                    settings.schedule_cron = value.trim().to_string();
+                }
+                "schedule_mode" => {
+                    settings.schedule_mode = value.trim().to_lowercase();
+                }
+                "meta_briefings" => {
+                    settings.meta_briefings = value.trim().to_lowercase();
+                }
+                "global_shortcut" => {
+                    // Accelerator such as CmdOrCtrl+Shift+B, or "off"
+                    settings.global_shortcut = value.trim().to_string();
+                }
+                "quick_research_shortcut" => {
+                    settings.quick_research_shortcut = value.trim().to_string();
+                }
+                "quick_research_mode" => {
+                    settings.quick_research_mode = value.trim().to_lowercase();
+                }
+                "release_channel" => {
+                    settings.release_channel = value.trim().to_lowercase();
+                }
+                "topic_discovery_days" => {
+                    settings.topic_discovery_days = value
+                        .parse()
+                        .map_err(|_| "Invalid number for topic_discovery_days")?;
+                }
+                "quiet_hours" => {
+                    // Format: HH:MM-HH:MM, or "off"
+                    if matches!(value.trim(), "off" | "none" | "") {
+                        settings.quiet_hours_start = None;
+                        settings.quiet_hours_end = None;
+                    } else {
+                        let (start, end) = claudius::quiet_hours::parse_range(&value)?;
+                        settings.quiet_hours_start = Some(start.format("%H:%M").to_string());
+                        settings.quiet_hours_end = Some(end.format("%H:%M").to_string());
+                    }
+                }
+                "api_base_url" => {
+                    if value.is_empty() || value == "none" {
+                        settings.api_base_url = None;
+                    } else {
+                        let endpoint = claudius::agent_loop::ApiEndpoint::new(
+                            &value,
+                            settings.api_headers.clone(),
+                        );
+                        match read_api_key() {
+                            Some(api_key) => {
+                                let client = http_client::client_builder()
+                                    .timeout(std::time::Duration::from_secs(30))
+                                    .build()
+                                    .map_err(|e| format!("Failed to build HTTP client: {}", e))?;
+                                endpoint.verify(&client, &api_key, &settings.model).await?;
+                            }
+                            None => eprintln!(
+                                "{} No API key configured, so {} wasn't checked",
+                                "Warning:".yellow(),
+                                endpoint.messages_url()
+                            ),
+                        }
+                        settings.api_base_url = Some(endpoint.base_url);
+                    }
+                }
+                "api_user_id" => {
+                    settings.api_user_id = if value.is_empty() || value == "none" {
+                        None
+                    } else {
+                        claudius::agent_loop::validate_user_id(&value)?;
+                        Some(value.clone())
+                    };
+                }
+                "api_header" => {
+                    // Format: Name=Value; an empty value removes the header
+                    let (name, header_value) = value
+                        .split_once('=')
+                        .ok_or("Invalid api_header, use Name=Value")?;
+                    let name = name.trim().to_string();
+                    if header_value.is_empty() {
+                        settings.api_headers.remove(&name);
+                    } else {
+                        claudius::agent_loop::validate_header(&name, header_value)?;
+                        settings.api_headers.insert(name, header_value.to_string());
+                    }
+                }
+                "ca_cert_path" | "ca_cert" => {
+                    settings.ca_cert_path = if value.is_empty() || value == "none" {
+                        None
+                    } else {
+                        http_client::load_ca_certificates(&value)?;
+                        Some(value.clone())
+                    };
+                }
+                "quiet_weekends" => {
+                    settings.quiet_weekends = value
+                        .parse()
+                        .map_err(|_| "Invalid boolean for quiet_weekends")?;
+                }
+                "respect_system_dnd" => {
+                    settings.respect_system_dnd = value
+                        .parse()
+                        .map_err(|_| "Invalid boolean for respect_system_dnd")?;
+                }
+                "catch_up_missed_runs" => {
+                    settings.catch_up_missed_runs = value
+                        .parse()
+                        .map_err(|_| "Invalid boolean for catch_up_missed_runs")?;
+                }
+                "batch_scheduled_research" => {
+                    settings.batch_scheduled_research = value
+                        .parse()
+                        .map_err(|_| "Invalid boolean for batch_scheduled_research")?;
+                }
+                "batch_deadline_minutes" => {
+                    settings.batch_deadline_minutes = value
+                        .parse()
+                        .ok()
+                        .filter(|m| *m > 0)
+                        .ok_or("Invalid number for batch_deadline_minutes")?;
+                }
+                "notification_sound" => {
+                    settings.notification_sound = value
+                        .parse()
+                        .map_err(|_| "Invalid boolean for notification_sound")?;
+                }
+                event if event.starts_with("notify.") => {
+                    use claudius::notification_prefs::{EventPreference, NotificationEvent};
+                    let event = NotificationEvent::parse(&event["notify.".len()..])?;
+                    let preference = EventPreference::parse(&value)?;
+                    if preference == event.default_preference() {
+                        settings.notification_events.remove(&event);
+                    } else {
+                        settings.notification_events.insert(event, preference);
+                    }
+                }
+                "notification_snooze_hours" => {
+                    settings.notification_snooze_hours = value
+                        .parse()
+                        .ok()
+                        .filter(|h| *h > 0)
+                        .ok_or("Invalid number for notification_snooze_hours")?;
+                }
+                "log_retention_days" => {
+                    settings.log_retention_days = value
+                        .parse()
+                        .map_err(|_| "Invalid number for log_retention_days")?;
+                }
+                "tts_voice" => {
+                    if !tts::OPENAI_VOICES.contains(&value.as_str()) {
+                        return Err(format!(
+                            "Invalid tts_voice. Use one of: {}",
+                            tts::OPENAI_VOICES.join(", ")
+                        ));
+                    }
+                    settings.tts_voice = value.clone();
+                }
+                "image_style" => {
+                    let style = value.trim().to_lowercase();
+                    if image_gen::find_image_style(&style).is_none() {
+                        return Err(format!(
+                            "Invalid image_style. Use one of: {}",
+                            image_gen::image_style_names().join(", ")
+                        ));
+                    }
+                    settings.image_style = style;
+                }
+                _ => return Err(format!("Unknown config key: {}", key)),
+            }
 
-                println!("{table}");
-                println!("\n{} servers total", config.servers.len());
+            settings_schema::validate_settings(&settings)?;
+            write_settings(&settings)?;
+
+            if json {
+                println!("{}", serde_json::json!({ "updated": key, "value": value }));
+            } else {
+                println!("{} Set {} = {}", "✓".green(), key, value);
             }
         }
 
-        McpAction::Add {
-            name,
-            command,
-            args,
-            env,
-        } => {
-            let mut config = read_mcp_servers()?;
+        ConfigAction::ApiKey { action } => match action {
+            ApiKeyAction::Show => {
+                if has_api_key() {
+                    if json {
+                        println!("{}", serde_json::json!({ "api_key_set": true }));
+                    } else {
+                        println!("{} API key is configured", "✓".green());
+                    }
+                } else if json {
+                    println!("{}", serde_json::json!({ "api_key_set": false }));
+                } else {
+                    println!("{} No API key configured", "✗".red());
+                    println!("\nSet with: claudius config api-key set <YOUR_KEY>");
+                }
+            }
 
-            // Build config object
-            let mut server_config = serde_json::json!({
-                "command": command,
-            });
+            ApiKeyAction::Set { key } => {
+                validate_api_key(&key)?;
+                write_api_key(&key)?;
 
-            if let Some(args_str) = args {
-                let args_vec: Vec<&str> = args_str.split_whitespace().collect();
-                server_config["args"] = serde_json::json!(args_vec);
+                if json {
+                    println!("{}", serde_json::json!({ "status": "success" }));
+                } else {
+                    println!("{} API key saved", "✓".green());
+                }
             }
 
-            if let Some(env_vars) = env {
-                let mut env_map = serde_json::Map::new();
-                for var in env_vars {
-                    if let Some((key, value)) = var.split_once('=') {
-                        env_map.insert(key.to_string(), serde_json::json!(value));
+            ApiKeyAction::Clear => {
+                delete_api_key()?;
+
+                if json {
+                    println!("{}", serde_json::json!({ "status": "cleared" }));
+                } else {
+                    println!("{} API key cleared", "✓".green());
+                }
+            }
+
+            ApiKeyAction::Migrate => {
+                let secrets = secret_store::secrets();
+                let moved = secrets.migrate_env_file()?;
+
+                if json {
+                    println!(
+                        "{}",
+                        serde_json::json!({ "store": secrets.backend_name(), "migrated": moved })
+                    );
+                } else if moved.is_empty() {
+                    println!("{}", "No credentials left in ~/.claudius/.env".dimmed());
+                } else {
+                    for name in &moved {
+                        println!(
+                            "{} Moved {} to {}",
+                            "✓".green(),
+                            name,
+                            secrets.backend_name()
+                        );
                     }
                 }
-                if !env_map.is_empty() {
-                    server_config["env"] = serde_json::Value::Object(env_map);
+            }
+        },
+
+        ConfigAction::Profile { action } => handle_profile(action, json)?,
+
+        ConfigAction::Proxy { action } => handle_proxy(action, json).await?,
+
+        ConfigAction::Models { refresh } => {
+            let api_key = read_api_key().ok_or("No API key configured")?;
+            let available = models::list_models(&api_key, refresh).await?;
+            let current = read_settings().unwrap_or_default().model;
+
+            if json {
+                println!(
+                    "{}",
+                    to_json(&serde_json::json!({
+                        "current": current,
+                        "models": available,
+                    }))
+                );
+            } else {
+                let mut table = Table::new();
+                table
+                    .load_preset(UTF8_FULL)
+                    .set_content_arrangement(ContentArrangement::Dynamic)
+                    .set_header(vec!["", "ID", "Name", "Created"]);
+                for m in &available {
+                    let marker = if m.id == current { "*" } else { "" };
+                    let name = if m.deprecated {
+                        format!("{} (deprecated)", m.display_name)
+                    } else {
+                        m.display_name.clone()
+                    };
+                    let created = m
+                        .created_at
+                        .as_deref()
+                        .map(|c| c.chars().take(10).collect::<String>())
+                        .unwrap_or_default();
+                    table.add_row(vec![marker.to_string(), m.id.clone(), name, created]);
+                }
+                println!("{table}");
+                if let Some(msg) = models::check_model(&current, &available).message(&current) {
+                    println!("{} {}", "Warning:".yellow(), msg);
                 }
             }
+        }
+    }
 
-            let server = MCPServer {
-                id: Uuid::new_v4().to_string(),
-                name: name.clone(),
-                enabled: true,
-                config: server_config,
-                last_used: None,
-            };
+    Ok(())
+}
+
+async fn handle_proxy(action: ProxyAction, json: bool) -> Result<(), String> {
+    let mut settings = read_settings().unwrap_or_default();
+
+    match action {
+        ProxyAction::Show => {
+            let has_password = http_client::proxy_password().is_some();
+            if json {
+                println!(
+                    "{}",
+                    to_json(&serde_json::json!({
+                        "proxy_url": settings.proxy_url,
+                        "proxy_username": settings.proxy_username,
+                        "proxy_password_set": has_password,
+                        "ca_cert_path": settings.ca_cert_path,
+                    }))
+                );
+            } else {
+                match &settings.proxy_url {
+                    Some(url) => println!("Proxy: {}", url),
+                    None => println!("Proxy: {}", "none".dimmed()),
+                }
+                if let Some(username) = &settings.proxy_username {
+                    let password = if has_password {
+                        "password set"
+                    } else {
+                        "no password"
+                    };
+                    println!("Proxy user: {} ({})", username, password.dimmed());
+                }
+                match &settings.ca_cert_path {
+                    Some(path) => println!("Extra CA certificates: {}", path),
+                    None => println!("Extra CA certificates: {}", "none".dimmed()),
+                }
+            }
+        }
 
-            config.servers.push(server.clone());
-            write_mcp_servers(&config)?;
+        ProxyAction::Set {
+            url,
+            username,
+            password,
+        } => {
+            http_client::parse_proxy_url(&url)?;
+            settings.proxy_url = Some(url.trim().to_string());
+            if let Some(username) = username {
+                settings.proxy_username = (!username.is_empty()).then_some(username);
+            }
+            if let Some(password) = password {
+                secret_store::secrets().set(http_client::PROXY_PASSWORD, &password)?;
+            }
+            write_settings(&settings)?;
 
             if json {
-                println!("{}", to_json(&server));
+                println!("{}", serde_json::json!({ "success": true }));
             } else {
-                println!("{} Added MCP server '{}'", "✓".green(), name);
+                println!("{} Proxy set to {}", "✓".green(), url.trim());
+                println!("Check it with: claudius config proxy test");
             }
         }
 
-        McpAction::Remove { id } => {
-            let mut config = read_mcp_servers()?;
-            let server = find_mcp_server(&config, &id)?;
-            let name = server.name.clone();
+        ProxyAction::Clear => {
+            settings.proxy_url = None;
+            settings.proxy_username = None;
+            secret_store::secrets().delete(http_client::PROXY_PASSWORD)?;
+            write_settings(&settings)?;
 
-            config.servers.retain(|s| s.id != server.id);
-            write_mcp_servers(&config)?;
+            if json {
+                println!("{}", serde_json::json!({ "success": true }));
+            } else {
+                println!("{} Proxy cleared", "✓".green());
+            }
+        }
+
+        ProxyAction::Test => {
+            if !json {
+                let url = http_client::test_url(&settings);
+                match &settings.proxy_url {
+                    Some(proxy) => println!("Connecting to {} via {}...", url, proxy),
+                    None => println!("Connecting to {} (no proxy)...", url),
+                }
+            }
+            let (status, elapsed) =
+                http_client::test_connection(&settings, http_client::proxy_password().as_deref())
+                    .await?;
 
             if json {
-                println!("{}", serde_json::json!({ "deleted": server.id }));
+                println!(
+                    "{}",
+                    to_json(&serde_json::json!({
+                        "success": true,
+                        "status": status,
+                        "elapsed_ms": elapsed.as_millis(),
+                    }))
+                );
             } else {
-                println!("{} Removed MCP server '{}'", "✓".green(), name);
+                println!(
+                    "{} Reached the Anthropic API (HTTP {}, {}ms)",
+                    "✓".green(),
+                    status,
+                    elapsed.as_millis()
+                );
             }
         }
+    }
 
-        McpAction::Enable { id } => {
-            let mut config = read_mcp_servers()?;
-            let server = find_mcp_server_mut(&mut config, &id)?;
-            server.enabled = true;
-            let name = server.name.clone();
-            let server_clone = server.clone();
-            write_mcp_servers(&config)?;
+    Ok(())
+}
 
+fn handle_workspace(action: WorkspaceAction, json: bool) -> Result<(), String> {
+    match action {
+        WorkspaceAction::List => {
+            let workspaces = config::list_workspaces();
             if json {
-                println!("{}", to_json(&server_clone));
+                println!(
+                    "{}",
+                    to_json(&serde_json::json!({ "workspaces": workspaces }))
+                );
             } else {
-                println!("{} Enabled MCP server '{}'", "✓".green(), name);
+                for w in &workspaces {
+                    let marker = if w.active {
+                        "*".green().to_string()
+                    } else {
+                        " ".to_string()
+                    };
+                    println!(
+                        "{} {} {}",
+                        marker,
+                        w.name,
+                        w.path.display().to_string().dimmed()
+                    );
+                }
             }
         }
 
-        McpAction::Disable { id } => {
-            let mut config = read_mcp_servers()?;
-            let server = find_mcp_server_mut(&mut config, &id)?;
-            server.enabled = false;
-            let name = server.name.clone();
-            let server_clone = server.clone();
-            write_mcp_servers(&config)?;
+        WorkspaceAction::Create { name } => {
+            let path = config::create_workspace(&name)?;
 
             if json {
-                println!("{}", to_json(&server_clone));
+                println!(
+                    "{}",
+                    serde_json::json!({ "created": name, "path": path.display().to_string() })
+                );
             } else {
-                println!("{} Disabled MCP server '{}'", "✓".green(), name);
+                println!(
+                    "{} Created workspace {} in {}",
+                    "✓".green(),
+                    name,
+                    path.display()
+                );
+                println!(
+                    "  {}",
+                    format!(
+                        "Switch to it with: claudius workspace use {}, or add --workspace {} to a command",
+                        name, name
+                    )
+                    .dimmed()
+                );
             }
         }
 
-        McpAction::Test { name } => {
-            let config = read_mcp_servers()?;
-            let server = find_mcp_server(&config, &name)?;
+        WorkspaceAction::Delete { name, yes } => {
+            config::check_workspace(&name)?;
+            let question = format!(
+                "Delete workspace {} with all its briefings, topics and settings?",
+                name
+            );
+            if !confirm(&question, yes)? {
+                if json {
+                    println!("{}", serde_json::json!({ "cancelled": true }));
+                } else {
+                    println!("Cancelled");
+                }
+                return Ok(());
+            }
+            config::delete_workspace(&name)?;
 
-            if !json {
-                println!("{} Testing MCP server '{}'...", "→".cyan(), server.name);
+            if json {
+                println!("{}", serde_json::json!({ "deleted": name }));
+            } else {
+                println!("{} Deleted workspace {}", "✓".green(), name);
             }
+        }
 
-            // Convert MCPServer to McpServerConfig for the client
-            let server_config = claudius::mcp_client::McpServerConfig {
-                id: server.id.clone(),
-                name: server.name.clone(),
-                enabled: true, // Force enabled for testing
-                config: server.config.clone(),
-                last_used: None,
-            };
+        WorkspaceAction::Use { name } => {
+            config::save_workspace(&name)?;
 
-            match claudius::mcp_client::McpClient::connect(vec![server_config]).await {
-                Ok(client) => {
-                    let tools = client.get_all_tools();
+            if json {
+                println!("{}", serde_json::json!({ "active_workspace": name }));
+            } else {
+                println!(
+                    "{} Now using workspace {} (restart the app to switch it too)",
+                    "✓".green(),
+                    name
+                );
+            }
+        }
+    }
+    Ok(())
+}
 
-                    if json {
-                        println!(
-                            "{}",
-                            to_json(&serde_json::json!({
-                                "status": "success",
-                                "server": server.name,
-                                "tools": tools.len(),
-                            }))
-                        );
+fn handle_profile(action: ProfileAction, json: bool) -> Result<(), String> {
+    match action {
+        ProfileAction::List => {
+            let active = config::active_profile();
+            let profiles: Vec<serde_json::Value> = config::list_profiles()
+                .into_iter()
+                .map(|name| {
+                    let has_key = secret_store::secrets()
+                        .get(&config::profile_secret_name(&name))
+                        .is_some();
+                    serde_json::json!({
+                        "name": name,
+                        "active": name == active,
+                        "has_key": has_key,
+                    })
+                })
+                .collect();
+
+            if json {
+                println!("{}", to_json(&serde_json::json!({ "profiles": profiles })));
+            } else {
+                for p in &profiles {
+                    let name = p["name"].as_str().unwrap_or_default();
+                    let marker = if p["active"].as_bool() == Some(true) {
+                        "*".green().to_string()
                     } else {
-                        println!("{} Connection successful!", "✓".green());
-                        println!("  Available tools: {}", tools.len());
-                        for tool in &tools {
-                            println!("    • {}", tool.tool.name);
-                        }
-                    }
-                }
-                Err(e) => {
-                    if json {
-                        println!(
-                            "{}",
-                            to_json(&serde_json::json!({
-                                "status": "error",
-                                "server": server.name,
-                                "error": e,
-                            }))
-                        );
+                        " ".to_string()
+                    };
+                    let key = if p["has_key"].as_bool() == Some(true) {
+                        "key set".dimmed().to_string()
                     } else {
-                        println!("{} Connection failed: {}", "✗".red(), e);
-                    }
+                        "no key".red().to_string()
+                    };
+                    println!("{} {} ({})", marker, name, key);
                 }
             }
         }
-    }
 
-    Ok(())
-}
+        ProfileAction::Add { name, key } => {
+            config::add_profile(&name, &key)?;
 
-fn find_mcp_server(config: &MCPServersConfig, id_or_name: &str) -> Result<MCPServer, String> {
-    for server in &config.servers {
-        if server.id == id_or_name
-            || server.name.to_lowercase() == id_or_name.to_lowercase()
-            || server.id.starts_with(id_or_name)
-        {
-            return Ok(server.clone());
+            if json {
+                println!("{}", serde_json::json!({ "added": name }));
+            } else {
+                println!("{} Saved API key for profile {}", "✓".green(), name);
+            }
         }
-    }
-    Err(format!("MCP server '{}' not found", id_or_name))
-}
 
-fn find_mcp_server_mut<'a>(
-    config: &'a mut MCPServersConfig,
-    id_or_name: &str,
-) -> Result<&'a mut MCPServer, String> {
-    for server in &mut config.servers {
-        if server.id == id_or_name
-            || server.name.to_lowercase() == id_or_name.to_lowercase()
-            || server.id.starts_with(id_or_name)
-        {
-            return Ok(server);
+        ProfileAction::Remove { name } => {
+            config::remove_profile(&name)?;
+
+            if json {
+                println!("{}", serde_json::json!({ "removed": name }));
+            } else {
+                println!("{} Removed profile {}", "✓".green(), name);
+            }
         }
-    }
-    Err(format!("MCP server '{}' not found", id_or_name))
-}
 
-// ============================================================================
-// Config Handlers
-// ============================================================================
+        ProfileAction::Use { name } => {
+            config::set_active_profile(&name)?;
 
-async fn handle_config(action: ConfigAction, json: bool) -> Result<(), String> {
-    match action {
-        ConfigAction::Show => {
-            let settings = read_settings().unwrap_or_default();
-            let has_key = has_api_key();
-            let config_dir = get_config_dir();
+            if json {
+                println!("{}", serde_json::json!({ "active_profile": name }));
+            } else {
+                println!("{} Now using profile {}", "✓".green(), name);
+            }
+        }
+
+        ProfileAction::Usage { days } => {
+            let conn =
+                db::get_connection().map_err(|e| format!("Database connection failed: {}", e))?;
+            let usage = db::get_profile_usage(&conn, days)?;
 
             if json {
                 println!(
                     "{}",
-                    to_json(&serde_json::json!({
-                        "config_dir": config_dir.display().to_string(),
-                        "api_key_set": has_key,
-                        "settings": settings,
-                    }))
-                );
-            } else {
-                println!("{}", "Configuration".bold());
-                println!();
-                println!(
-                    "  Config directory: {}",
-                    config_dir.display().to_string().dimmed()
-                );
-                println!(
-                    "  API key: {}",
-                    if has_key {
-                        "✓ set".green().to_string()
-                    } else {
-                        "✗ not set".red().to_string()
-                    }
-                );
-                println!();
-                println!("{}", "Research Settings".bold());
-                println!();
-                println!("  Model: {}", settings.model.cyan());
-                println!("  Research depth: {}", settings.research_depth);
-                println!(
-                    "  Max sources per topic: {}",
-                    settings.max_sources_per_topic
+                    to_json(&serde_json::json!({ "days": days, "usage": usage }))
                 );
+            } else if usage.is_empty() {
                 println!(
-                    "  Notifications: {}",
-                    if settings.enable_notifications {
-                        "enabled"
-                    } else {
-                        "disabled"
-                    }
-                );
-                println!(
-                    "  Web search: {}",
-                    if settings.enable_web_search {
-                        "enabled"
-                    } else {
-                        "disabled"
-                    }
+                    "{}",
+                    format!("No usage recorded in the last {} days.", days).dimmed()
                 );
+            } else {
+                let mut table = Table::new();
+                table.load_preset(UTF8_FULL);
+                table.set_content_arrangement(ContentArrangement::Dynamic);
+                table.set_header(vec![
+                    "Profile",
+                    "Research runs",
+                    "Research tokens",
+                    "Batch tokens",
+                    "Chat messages",
+                    "Chat tokens",
+                    "Total tokens",
+                ]);
+
+                for u in &usage {
+                    table.add_row(vec![
+                        &u.profile,
+                        &u.research_runs.to_string(),
+                        &u.research_tokens.to_string(),
+                        &u.batch_tokens.to_string(),
+                        &u.chat_messages.to_string(),
+                        &u.chat_tokens.to_string(),
+                        &u.total_tokens.to_string(),
+                    ]);
+                }
+
+                println!("{table}");
             }
         }
+    }
 
-        ConfigAction::Set { key, value } => {
-            let mut settings = read_settings().unwrap_or_default();
+    Ok(())
+}
 
-            match key.as_str() {
-                "model" => settings.model = value.clone(),
-                "research_depth" | "depth" => settings.research_depth = value.clone(),
-                "max_sources" | "max_sources_per_topic" => {
-                    settings.max_sources_per_topic = value
-                        .parse()
-                        .map_err(|_| "Invalid number for max_sources")?;
-                }
-                "notifications" | "enable_notifications" => {
-                    settings.enable_notifications = value
-                        .parse()
-                        .map_err(|_| "Invalid boolean for notifications")?;
-                }
-                "web_search" | "enable_web_search" => {
-                    settings.enable_web_search = value
-                        .parse()
-                        .map_err(|_| "Invalid boolean for web_search")?;
-                }
-                _ => return Err(format!("Unknown config key: {}", key)),
-            }
+// ============================================================================
+// Cards Handlers
+// ============================================================================
 
-            write_settings(&settings)?;
+async fn handle_cards(action: CardAction, json: bool) -> Result<(), String> {
+    let conn = db::get_connection().map_err(|e| format!("Database connection failed: {}", e))?;
+
+    let CardAction::Queue { action } = action;
+    match action.unwrap_or(QueueAction::List { all: false }) {
+        QueueAction::List { all } => {
+            let items = db::get_read_later_queue(&conn, all)?;
 
             if json {
-                println!("{}", serde_json::json!({ "updated": key, "value": value }));
+                println!("{}", to_json(&serde_json::json!({ "queue": items })));
+            } else if items.is_empty() {
+                println!("{}", "Read-later queue is empty.".yellow());
+                println!("Save a card with: claudius cards queue add <briefing-id> <card>");
             } else {
-                println!("{} Set {} = {}", "✓".green(), key, value);
+                let mut table = Table::new();
+                table.load_preset(UTF8_FULL);
+                table.set_content_arrangement(ContentArrangement::Dynamic);
+                table.set_header(vec!["ID", "#", "Card", "Briefing", "Status"]);
+
+                for (i, item) in items.iter().enumerate() {
+                    let title = db::get_briefing(&conn, item.briefing_id)
+                        .ok()
+                        .and_then(|b| serde_json::from_str::<Vec<BriefingCard>>(&b.cards).ok())
+                        .and_then(|cards| cards.into_iter().nth(item.card_index as usize))
+                        .map(|c| c.title)
+                        .unwrap_or_else(|| "(card not found)".to_string());
+                    let status = if item.completed {
+                        "done".to_string()
+                    } else if let Some(ref service) = item.forwarded_to {
+                        format!("queued → {}", service)
+                    } else {
+                        "queued".to_string()
+                    };
+                    table.add_row(vec![
+                        item.id.to_string(),
+                        (i + 1).to_string(),
+                        title,
+                        format!("{} (card {})", item.briefing_id, item.card_index + 1),
+                        status,
+                    ]);
+                }
+
+                println!("{table}");
             }
         }
 
-        ConfigAction::ApiKey { action } => match action {
-            ApiKeyAction::Show => {
-                if has_api_key() {
-                    if json {
-                        println!("{}", serde_json::json!({ "api_key_set": true }));
-                    } else {
-                        println!("{} API key is configured", "✓".green());
+        QueueAction::Add { briefing_id, card } => {
+            let briefing = db::get_briefing(&conn, briefing_id)?;
+            let cards: Vec<BriefingCard> = serde_json::from_str(&briefing.cards)
+                .map_err(|e| format!("Failed to parse cards: {}", e))?;
+            let card_data = card
+                .checked_sub(1)
+                .and_then(|idx| cards.get(idx))
+                .ok_or_else(|| format!("Card {} not found in briefing {}", card, briefing_id))?;
+
+            let mut item = db::save_for_later(&conn, briefing_id, (card - 1) as i32)?;
+
+            let service = read_settings()
+                .unwrap_or_default()
+                .read_later_service
+                .as_deref()
+                .and_then(read_later::ReadLaterService::from_setting);
+            let mut forward_error = None;
+            if let Some(service) = service {
+                match read_later::forward_card(service, card_data).await {
+                    Ok(()) => {
+                        db::set_read_later_forwarded(&conn, item.id, service.as_str())?;
+                        item.forwarded_to = Some(service.as_str().to_string());
                     }
-                } else if json {
-                    println!("{}", serde_json::json!({ "api_key_set": false }));
-                } else {
-                    println!("{} No API key configured", "✗".red());
-                    println!("\nSet with: claudius config api-key set <YOUR_KEY>");
+                    Err(e) => forward_error = Some(e),
                 }
             }
 
-            ApiKeyAction::Set { key } => {
-                validate_api_key(&key)?;
-                write_api_key(&key)?;
-
-                if json {
-                    println!("{}", serde_json::json!({ "status": "success" }));
-                } else {
-                    println!("{} API key saved", "✓".green());
+            if json {
+                println!(
+                    "{}",
+                    to_json(&serde_json::json!({
+                        "item": item,
+                        "forward_error": forward_error,
+                    }))
+                );
+            } else {
+                println!("{} Saved '{}' for later", "✓".green(), card_data.title);
+                if let Some(ref service) = item.forwarded_to {
+                    println!("  Sent to {}", service.cyan());
+                }
+                if let Some(e) = forward_error {
+                    println!("  {} Could not forward: {}", "!".yellow(), e);
                 }
             }
+        }
 
-            ApiKeyAction::Clear => {
-                delete_api_key()?;
+        QueueAction::Done { id } => {
+            if !db::complete_read_later(&conn, id)? {
+                return Err(format!("Queue item {} not found", id));
+            }
+            if json {
+                println!("{}", serde_json::json!({ "completed": id }));
+            } else {
+                println!("{} Marked queue item {} as read", "✓".green(), id);
+            }
+        }
 
-                if json {
-                    println!("{}", serde_json::json!({ "status": "cleared" }));
-                } else {
-                    println!("{} API key cleared", "✓".green());
-                }
+        QueueAction::Remove { id } => {
+            if !db::remove_read_later(&conn, id)? {
+                return Err(format!("Queue item {} not found", id));
             }
-        },
+            if json {
+                println!("{}", serde_json::json!({ "removed": id }));
+            } else {
+                println!("{} Removed queue item {}", "✓".green(), id);
+            }
+        }
+
+        QueueAction::Move { id, position } => {
+            let mut ids: Vec<i64> = db::get_read_later_queue(&conn, false)?
+                .iter()
+                .map(|item| item.id)
+                .collect();
+            let current = ids
+                .iter()
+                .position(|&i| i == id)
+                .ok_or_else(|| format!("Queue item {} not found (or already done)", id))?;
+            ids.remove(current);
+            let target = position.saturating_sub(1).min(ids.len());
+            ids.insert(target, id);
+            db::reorder_read_later(&conn, &ids)?;
+
+            if json {
+                println!(
+                    "{}",
+                    serde_json::json!({ "moved": id, "position": target + 1 })
+                );
+            } else {
+                println!(
+                    "{} Moved queue item {} to position {}",
+                    "✓".green(),
+                    id,
+                    target + 1
+                );
+            }
+        }
     }
 
     Ok(())
@@ -1572,9 +5476,11 @@ async fn handle_housekeeping(action: HousekeepingAction, json: bool) -> Result<(
             let total_count = db::count_briefings(&conn)?;
 
             // Get database file size
-            let db_path = get_config_dir().join("claudius.db");
+            let db_path = config::get_data_dir().join("claudius.db");
             let db_size = std::fs::metadata(&db_path).map(|m| m.len()).unwrap_or(0);
 
+            let (web_cache_pages, web_cache_bytes) = db::web_cache_stats(&conn)?;
+
             // Count candidates if retention is set
             let cleanup_candidates = match settings.retention_days {
                 Some(days) => Some(db::count_cleanup_candidates(&conn, days)?),
@@ -1588,7 +5494,9 @@ async fn handle_housekeeping(action: HousekeepingAction, json: bool) -> Result<(
                         "total_briefings": total_count,
                         "retention_days": settings.retention_days,
                         "cleanup_candidates": cleanup_candidates,
-                        "database_size_bytes": db_size
+                        "database_size_bytes": db_size,
+                        "web_cache_pages": web_cache_pages,
+                        "web_cache_bytes": web_cache_bytes
                     })
                 );
             } else {
@@ -1624,6 +5532,11 @@ async fn handle_housekeeping(action: HousekeepingAction, json: bool) -> Result<(
                     format!("{} bytes", db_size)
                 };
                 println!("Database size: {}", size_str.cyan());
+                println!(
+                    "Web cache: {} page(s), {:.1} MB",
+                    web_cache_pages.to_string().cyan(),
+                    web_cache_bytes as f64 / 1_000_000.0
+                );
             }
         }
 
@@ -1632,7 +5545,7 @@ async fn handle_housekeeping(action: HousekeepingAction, json: bool) -> Result<(
                 db::get_connection().map_err(|e| format!("Database connection failed: {}", e))?;
 
             // Get size before
-            let db_path = get_config_dir().join("claudius.db");
+            let db_path = config::get_data_dir().join("claudius.db");
             let size_before = std::fs::metadata(&db_path).map(|m| m.len()).unwrap_or(0);
 
             // Run VACUUM
@@ -1667,7 +5580,586 @@ async fn handle_housekeeping(action: HousekeepingAction, json: bool) -> Result<(
                 println!("{} Database already optimized", "✓".green());
             }
         }
+        HousekeepingAction::Dedupe => {
+            let conn =
+                db::get_connection().map_err(|e| format!("Database connection failed: {}", e))?;
+            let merged = db::merge_duplicate_briefings(&conn)?;
+
+            if json {
+                println!("{}", serde_json::json!({ "merged": merged }));
+            } else if merged > 0 {
+                println!(
+                    "{} Merged {} duplicate briefing{}",
+                    "✓".green(),
+                    merged,
+                    if merged == 1 { "" } else { "s" }
+                );
+            } else {
+                println!("{} No duplicate briefings", "✓".green());
+            }
+        }
+    }
+
+    Ok(())
+}
+
+// ============================================================================
+// Chat Handlers
+// ============================================================================
+
+async fn handle_ask(question: &str, top: usize, json: bool) -> Result<(), String> {
+    let api_key = require_api_key()?;
+    let settings = read_settings()?;
+
+    if !json {
+        println!("{}", "Searching your briefing archive...".dimmed());
+    }
+    let answer = archive::ask_archive(&api_key, &settings.model, question, &[], top).await?;
+
+    if json {
+        println!("{}", to_json(&answer));
+        return Ok(());
+    }
+
+    println!("\n{}\n", answer.answer.trim());
+    if !answer.citations.is_empty() {
+        println!("{}", "Sources:".bold());
+        for citation in &answer.citations {
+            println!(
+                "  {} {} {}",
+                format!(
+                    "[briefing {}, card {}]",
+                    citation.briefing_id,
+                    citation.card_index + 1
+                )
+                .cyan(),
+                citation
+                    .date
+                    .get(..10)
+                    .unwrap_or(citation.date.as_str())
+                    .dimmed(),
+                citation.title
+            );
+        }
+        println!(
+            "\n{}",
+            "Open one with: claudius briefings show <id>".dimmed()
+        );
+    }
+    Ok(())
+}
+
+async fn handle_chat(action: ChatAction) -> Result<(), String> {
+    let ChatAction::Export {
+        briefing_id,
+        card,
+        format,
+        output,
+    } = action;
+    if card == 0 {
+        return Err("Card numbers start at 1".to_string());
+    }
+
+    let exported = chat::export_chat(briefing_id, card as i32 - 1, &format)?;
+    match output {
+        Some(path) => {
+            std::fs::write(&path, exported)
+                .map_err(|e| format!("Failed to write {}: {}", path, e))?;
+            println!("{} Saved chat to {}", "✓".green(), path);
+        }
+        None => println!("{}", exported),
+    }
+    Ok(())
+}
+
+// ============================================================================
+// Update Handler
+// ============================================================================
+
+async fn handle_update(check: bool, channel: Option<String>, json: bool) -> Result<(), String> {
+    let channel = match channel {
+        Some(channel) => channel.trim().to_lowercase(),
+        None => read_settings().unwrap_or_default().release_channel,
+    };
+    let all = releases::fetch_releases(&channel).await?;
+    let changelog = releases::changelog_since(&all, VERSION);
+
+    let release = match changelog.first() {
+        Some(release) if !check => release,
+        latest => {
+            if json {
+                println!(
+                    "{}",
+                    to_json(&serde_json::json!({
+                        "current_version": VERSION,
+                        "channel": channel,
+                        "latest_version": latest.map(|r| &r.version),
+                        "changelog": changelog,
+                    }))
+                );
+            } else if let Some(latest) = latest {
+                println!(
+                    "{} v{} → v{} ({} channel)\n",
+                    "Update available:".bold(),
+                    VERSION,
+                    latest.version,
+                    channel
+                );
+                print_changelog(&changelog);
+                println!("Install with: claudius update");
+            } else {
+                println!(
+                    "{} Claudius v{} is the latest {} release",
+                    "✓".green(),
+                    VERSION,
+                    channel
+                );
+            }
+            return Ok(());
+        }
+    };
+
+    let asset_name = releases::cli_asset_name(std::env::consts::OS, std::env::consts::ARCH);
+    let asset = release.asset(&asset_name).ok_or_else(|| {
+        format!(
+            "Release v{} has no CLI binary for this platform ({}); update the desktop app instead",
+            release.version, asset_name
+        )
+    })?;
+    // The installed `claudius` is usually a symlink into the app; the file
+    // it points at is the one replaced
+    let target = std::env::current_exe()
+        .and_then(std::fs::canonicalize)
+        .map_err(|e| format!("Failed to locate the CLI binary: {}", e))?;
+
+    if !json {
+        print_changelog(&changelog);
+        println!("Downloading v{} ({})...", release.version, asset.name);
+    }
+    let response = http_client::client_builder()
+        .timeout(std::time::Duration::from_secs(300))
+        .build()
+        .map_err(|e| format!("Failed to build HTTP client: {}", e))?
+        .get(&asset.url)
+        .header(
+            "User-Agent",
+            concat!("claudius/", env!("CARGO_PKG_VERSION")),
+        )
+        .send()
+        .await
+        .map_err(|e| format!("Failed to download {}: {}", asset.name, e))?;
+    if !response.status().is_success() {
+        return Err(format!(
+            "Failed to download {}: HTTP {}",
+            asset.name,
+            response.status().as_u16()
+        ));
+    }
+    let bytes = response
+        .bytes()
+        .await
+        .map_err(|e| format!("Failed to download {}: {}", asset.name, e))?;
+    if asset.size > 0 && bytes.len() as u64 != asset.size {
+        return Err(format!(
+            "Download of {} is incomplete ({} of {} bytes)",
+            asset.name,
+            bytes.len(),
+            asset.size
+        ));
+    }
+
+    // Make sure the new binary runs and is the expected version before it
+    // replaces this one
+    let staged = releases::stage_binary(&target, &bytes)?;
+    let reported = std::process::Command::new(&staged)
+        .arg("--version")
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string());
+    if !reported
+        .as_deref()
+        .is_some_and(|v| v.ends_with(&release.version))
+    {
+        let _ = std::fs::remove_file(&staged);
+        return Err(format!(
+            "Downloaded binary failed its check (reported {}); nothing was changed",
+            reported.as_deref().unwrap_or("no version")
+        ));
+    }
+    let backup = releases::swap_binary(&target, &staged)?;
+
+    if json {
+        println!(
+            "{}",
+            to_json(&serde_json::json!({
+                "previous_version": VERSION,
+                "version": release.version,
+                "channel": channel,
+                "path": target,
+                "backup": backup,
+            }))
+        );
+    } else {
+        println!(
+            "{} Updated the CLI to v{} ({})",
+            "✓".green(),
+            release.version,
+            target.display()
+        );
+        println!("  Previous version kept at {}", backup.display());
+    }
+    Ok(())
+}
+
+/// Print each release's version, date and notes, newest first.
+fn print_changelog(changelog: &[releases::Release]) {
+    for release in changelog {
+        let date = release
+            .published_at
+            .as_deref()
+            .and_then(|d| d.get(..10))
+            .unwrap_or("");
+        let pre = if release.prerelease {
+            " (pre-release)"
+        } else {
+            ""
+        };
+        println!(
+            "{} {}{}",
+            format!("v{}", release.version).bold(),
+            date.dimmed(),
+            pre
+        );
+        for line in release
+            .notes
+            .as_deref()
+            .unwrap_or("No release notes.")
+            .lines()
+        {
+            println!("  {}", line);
+        }
+        println!();
+    }
+}
+
+// ============================================================================
+// Stats Handler
+// ============================================================================
+
+fn handle_stats(days: i64, json: bool) -> Result<(), String> {
+    let conn = db::get_connection().map_err(|e| format!("Database connection failed: {}", e))?;
+    let stats = stats::statistics(&conn, days, chrono::Local::now().date_naive())?;
+
+    if json {
+        println!("{}", to_json(&stats));
+        return Ok(());
+    }
+
+    println!(
+        "{}",
+        format!("Statistics (last {} days)", stats.days).bold()
+    );
+    println!();
+    println!(
+        "  Streak: {} days (longest {}){}",
+        stats.current_streak.to_string().cyan(),
+        stats.longest_streak,
+        stats
+            .last_research_day
+            .as_deref()
+            .map(|day| format!(", last research {}", day))
+            .unwrap_or_default()
+    );
+    println!(
+        "  Briefings: {}, cards: {}",
+        stats.briefings.to_string().cyan(),
+        stats.cards.to_string().cyan()
+    );
+    let spend = &stats.spend;
+    println!(
+        "  Research runs: {}, avg {:.0} tokens (~${:.3}), total {} tokens (~${:.2})",
+        spend.runs, spend.avg_tokens, spend.avg_cost_usd, spend.total_tokens, spend.total_cost_usd
+    );
+    if let Some(ms) = spend.avg_duration_ms {
+        println!("  Avg run duration: {:.0}s", ms / 1000.0);
+    }
+
+    if !stats.weekly.is_empty() {
+        println!("\n{}", "Per week".bold());
+        let mut table = Table::new();
+        table.load_preset(UTF8_FULL);
+        table.set_content_arrangement(ContentArrangement::Dynamic);
+        table.set_header(vec!["Week of", "Briefings", "Cards", "Tokens"]);
+        for w in &stats.weekly {
+            table.add_row(vec![
+                &w.week,
+                &w.briefings.to_string(),
+                &w.cards.to_string(),
+                &w.tokens.to_string(),
+            ]);
+        }
+        println!("{table}");
+    }
+
+    if !stats.topics.is_empty() {
+        println!("\n{}", "Cards per topic".bold());
+        let mut table = Table::new();
+        table.load_preset(UTF8_FULL);
+        table.set_content_arrangement(ContentArrangement::Dynamic);
+        table.set_header(vec!["Topic", "Cards", "Briefings"]);
+        for t in &stats.topics {
+            table.add_row(vec![
+                &t.topic,
+                &t.cards.to_string(),
+                &t.briefings.to_string(),
+            ]);
+        }
+        println!("{table}");
+    }
+
+    if !stats.tools.is_empty() {
+        println!("\n{}", "Tool usage".bold());
+        let mut table = Table::new();
+        table.load_preset(UTF8_FULL);
+        table.set_content_arrangement(ContentArrangement::Dynamic);
+        table.set_header(vec!["Tool", "Calls", "Share"]);
+        for t in &stats.tools {
+            table.add_row(vec![
+                &t.tool_name,
+                &t.calls.to_string(),
+                &format!("{:.0}%", t.share * 100.0),
+            ]);
+        }
+        println!("{table}");
+    }
+
+    Ok(())
+}
+
+// ============================================================================
+// Doctor Handler
+// ============================================================================
+
+async fn handle_doctor(json: bool) -> Result<(), String> {
+    if !json {
+        println!("{} Running checks...\n", "→".cyan());
+    }
+    let report = claudius::doctor::run().await;
+
+    if json {
+        println!("{}", to_json(&report));
+    } else {
+        for check in &report.checks {
+            let mark = match check.status {
+                claudius::doctor::CheckStatus::Pass => "✓".green(),
+                claudius::doctor::CheckStatus::Warn => "!".yellow(),
+                claudius::doctor::CheckStatus::Fail => "✗".red(),
+            };
+            println!("{} {}: {}", mark, check.name.bold(), check.detail);
+        }
+        println!(
+            "\n{} passed, {} warning(s), {} failed",
+            report.passed, report.warnings, report.failures
+        );
+    }
+
+    if report.failures > 0 {
+        return Err(format!("{} check(s) failed", report.failures));
+    }
+    Ok(())
+}
+
+// ============================================================================
+// Daemon Handlers
+// ============================================================================
+
+async fn handle_daemon(action: Option<DaemonAction>, json: bool) -> Result<(), String> {
+    match action.unwrap_or(DaemonAction::Run) {
+        DaemonAction::Run => {
+            let cli_path = std::env::current_exe()
+                .map_err(|e| format!("Failed to locate claudius binary: {}", e))?;
+            daemon::run(cli_path).await?;
+        }
+
+        DaemonAction::Install => {
+            let exe = std::env::current_exe()
+                .map_err(|e| format!("Failed to locate claudius binary: {}", e))?;
+            let path = daemon::install(&exe)?;
+            let schedule =
+                claudius::smart_schedule::effective_schedule(&read_settings().unwrap_or_default());
+
+            if json {
+                println!(
+                    "{}",
+                    to_json(&serde_json::json!({
+                        "installed": path.display().to_string(),
+                        "schedule": schedule,
+                    }))
+                );
+            } else {
+                println!("{} Installed daemon at {}", "✓".green(), path.display());
+                println!(
+                    "  Schedule: {} (change with: claudius config set schedule_cron \"...\")",
+                    schedule
+                );
+            }
+        }
+
+        DaemonAction::Uninstall => {
+            let removed = daemon::uninstall()?;
+
+            if json {
+                println!("{}", serde_json::json!({ "uninstalled": removed }));
+            } else if removed {
+                println!("{} Uninstalled daemon", "✓".green());
+            } else {
+                println!("{}", "Daemon is not installed.".dimmed());
+            }
+        }
+
+        DaemonAction::Status => {
+            let status = daemon::status();
+
+            if json {
+                println!("{}", to_json(&status));
+            } else {
+                println!("{}", "Daemon".bold());
+                println!();
+                println!(
+                    "  Installed: {}",
+                    if status.installed {
+                        "yes".green().to_string()
+                    } else {
+                        "no".dimmed().to_string()
+                    }
+                );
+                println!(
+                    "  Running: {}",
+                    if status.running {
+                        "yes".green().to_string()
+                    } else {
+                        "no".dimmed().to_string()
+                    }
+                );
+                if let Some(path) = &status.unit_path {
+                    println!("  Service file: {}", path.dimmed());
+                }
+                if status.adaptive {
+                    match &status.learned {
+                        Some(learned) => println!(
+                            "  Adaptive schedule: usually active from {} (last {} days), \
+                             runs take ~{} min",
+                            learned.first_activity, learned.days, learned.run_minutes
+                        ),
+                        None => println!(
+                            "  Adaptive schedule: {}",
+                            "still learning, following schedule_cron".dimmed()
+                        ),
+                    }
+                }
+                if let Some(state) = &status.state {
+                    println!("  Schedule: {}", state.schedule);
+                    if let Some(next) = &state.next_run {
+                        println!("  Next run: {}", next.cyan());
+                    }
+                    if let Some(run) = &state.last_run {
+                        let outcome = if run.success {
+                            "succeeded".green().to_string()
+                        } else {
+                            format!(
+                                "failed: {}",
+                                run.error.as_deref().unwrap_or("unknown error")
+                            )
+                            .red()
+                            .to_string()
+                        };
+                        println!("  Last run: {} ({})", run.started_at, outcome);
+                        if let Some(missed) = &run.catch_up_for {
+                            println!(
+                                "  {}",
+                                format!("Made up the run missed at {}", missed).dimmed()
+                            );
+                        }
+                    }
+                }
+            }
+        }
+
+        DaemonAction::Calendar { out } => {
+            let settings = read_settings().unwrap_or_default();
+            let path = claudius::schedule_calendar::calendar_path(
+                out.as_deref()
+                    .or(settings.schedule_calendar_path.as_deref()),
+            );
+            let conn =
+                db::get_connection().map_err(|e| format!("Database connection failed: {}", e))?;
+            let schedule = claudius::smart_schedule::effective_schedule(&settings);
+            claudius::schedule_calendar::write_calendar(&conn, &schedule, &path)?;
+
+            if json {
+                println!(
+                    "{}",
+                    to_json(&serde_json::json!({ "calendar_path": path.display().to_string() }))
+                );
+            } else {
+                println!("{} Wrote calendar to {}", "✓".green(), path.display());
+                if !settings.enable_schedule_calendar {
+                    println!(
+                        "{}",
+                        "Keep it updated with: claudius config set enable_schedule_calendar true"
+                            .dimmed()
+                    );
+                }
+            }
+        }
     }
 
     Ok(())
 }
+
+// ============================================================================
+// Serve Handler
+// ============================================================================
+
+async fn handle_serve(
+    host: String,
+    port: u16,
+    token: Option<String>,
+    show_token: bool,
+) -> Result<(), String> {
+    let (token, generated) = server::resolve_token(token)?;
+    let cli_path =
+        std::env::current_exe().map_err(|e| format!("Failed to locate claudius binary: {}", e))?;
+
+    println!(
+        "{} Claudius API listening on http://{}:{}",
+        "→".cyan(),
+        host,
+        port
+    );
+    if generated || show_token {
+        if generated {
+            println!("{} Generated a new API token", "✓".green());
+        }
+        println!("  Token: {}", token.bold());
+    } else {
+        println!(
+            "  {}",
+            "Authenticate with the stored API token (print it with --show-token)".dimmed()
+        );
+    }
+    println!(
+        "  {}",
+        "Send it as: Authorization: Bearer <token>. Press Ctrl+C to stop.".dimmed()
+    );
+
+    server::serve(server::ServeOptions {
+        host,
+        port,
+        token,
+        cli_path,
+        profile: config::active_profile(),
+    })
+    .await
+}
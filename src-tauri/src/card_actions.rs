@@ -0,0 +1,425 @@
+//! Structured card actions for Claudius.
+//!
+//! Synthesis can attach typed follow-up actions to a card (set a reminder,
+//! open a link, file a GitHub issue, add a calendar event). They are stored
+//! with the card in the briefing JSON and run on demand from the app or CLI.
+
+use chrono::{Duration, NaiveDate, NaiveTime, Utc};
+use rusqlite::Connection;
+use serde::{Deserialize, Deserializer, Serialize};
+use std::path::PathBuf;
+use tracing::{info, warn};
+
+use crate::db;
+
+/// Default delay for a `remind_me` action without `in_hours`.
+const DEFAULT_REMINDER_HOURS: u32 = 24;
+
+/// Default length of a calendar event with a start time.
+pub(crate) const DEFAULT_EVENT_MINUTES: u32 = 60;
+
+/// A follow-up action attached to a briefing card.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum CardAction {
+    /// Show a notification after `in_hours` hours
+    RemindMe {
+        message: String,
+        #[serde(default)]
+        in_hours: Option<u32>,
+    },
+    /// Open a link in the default browser
+    OpenUrl {
+        url: String,
+        #[serde(default)]
+        label: Option<String>,
+    },
+    /// Create an issue in a GitHub repository ("owner/name")
+    CreateGithubIssue {
+        repo: String,
+        title: String,
+        #[serde(default)]
+        body: Option<String>,
+    },
+    /// Add an event to the user's calendar via an .ics file
+    AddCalendarEvent {
+        title: String,
+        date: String, // YYYY-MM-DD
+        #[serde(default)]
+        time: Option<String>, // HH:MM, local time; all-day when absent
+        #[serde(default)]
+        duration_minutes: Option<u32>,
+        #[serde(default)]
+        description: Option<String>,
+    },
+}
+
+impl CardAction {
+    /// Short human-readable label for buttons and listings.
+    #[allow(dead_code)]
+    pub fn label(&self) -> String {
+        match self {
+            CardAction::RemindMe { message, in_hours } => format!(
+                "Remind me in {}h: {}",
+                in_hours.unwrap_or(DEFAULT_REMINDER_HOURS),
+                message
+            ),
+            CardAction::OpenUrl { url, label } => {
+                format!("Open {}", label.as_deref().unwrap_or(url))
+            }
+            CardAction::CreateGithubIssue { repo, title, .. } => {
+                format!("Create issue in {}: {}", repo, title)
+            }
+            CardAction::AddCalendarEvent { title, date, .. } => {
+                format!("Add to calendar ({}): {}", date, title)
+            }
+        }
+    }
+}
+
+/// Deserialize a card's actions, skipping entries with unknown types or bad
+/// fields so one malformed action doesn't fail the whole briefing.
+pub fn deserialize_actions<'de, D>(deserializer: D) -> Result<Vec<CardAction>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let values: Option<Vec<serde_json::Value>> = Option::deserialize(deserializer)?;
+    Ok(values
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|v| match serde_json::from_value::<CardAction>(v) {
+            Ok(action) => Some(action),
+            Err(e) => {
+                warn!("Skipping invalid card action: {}", e);
+                None
+            }
+        })
+        .collect())
+}
+
+/// Result of running a card action.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ActionOutcome {
+    ReminderScheduled { id: i64, due_at: String },
+    UrlOpened { url: String },
+    IssueCreated { url: String },
+    CalendarEventCreated { path: String },
+}
+
+/// Load a card's action by position from a stored briefing.
+/// Returns the card title along with the action.
+pub fn load_card_action(
+    conn: &Connection,
+    briefing_id: i64,
+    card_index: usize,
+    action_index: usize,
+) -> Result<(String, CardAction), String> {
+    let cards_json: String = conn
+        .query_row(
+            "SELECT cards FROM briefings WHERE id = ?1",
+            [briefing_id],
+            |row| row.get(0),
+        )
+        .map_err(|e| format!("Briefing {} not found: {}", briefing_id, e))?;
+    let cards: Vec<crate::research::BriefingCard> =
+        serde_json::from_str(&cards_json).map_err(|e| format!("Failed to parse cards: {}", e))?;
+    let card = cards
+        .get(card_index)
+        .ok_or_else(|| format!("Card {} not found in briefing {}", card_index, briefing_id))?;
+    let action = card
+        .actions
+        .get(action_index)
+        .cloned()
+        .ok_or_else(|| format!("Action {} not found on card {}", action_index, card_index))?;
+    Ok((card.title.clone(), action))
+}
+
+/// Open a URL or file with the platform's default handler.
+pub fn open_in_default_app(target: &str) -> Result<(), String> {
+    #[cfg(target_os = "macos")]
+    let result = std::process::Command::new("open").arg(target).spawn();
+    #[cfg(target_os = "linux")]
+    let result = std::process::Command::new("xdg-open").arg(target).spawn();
+    #[cfg(target_os = "windows")]
+    let result = std::process::Command::new("cmd")
+        .args(["/C", "start", "", target])
+        .spawn();
+
+    result
+        .map(|_| ())
+        .map_err(|e| format!("Failed to open {}: {}", target, e))
+}
+
+/// Run a card action.
+///
+/// `open` controls whether URLs and calendar files are opened in the default
+/// app; the CLI passes false when printing JSON.
+pub async fn run_action(
+    briefing_id: i64,
+    card_index: usize,
+    card_title: &str,
+    action: &CardAction,
+    open: bool,
+) -> Result<ActionOutcome, String> {
+    match action {
+        CardAction::RemindMe { message, in_hours } => {
+            let hours = in_hours.unwrap_or(DEFAULT_REMINDER_HOURS);
+            let due_at = (Utc::now() + Duration::hours(i64::from(hours)))
+                .format("%Y-%m-%d %H:%M:%S")
+                .to_string();
+            let conn = db::get_connection().map_err(|e| format!("Database error: {}", e))?;
+            let id = db::insert_reminder(
+                &conn,
+                briefing_id,
+                card_index as i64,
+                card_title,
+                message,
+                &due_at,
+            )?;
+            info!("Scheduled reminder {} for {}", id, due_at);
+            Ok(ActionOutcome::ReminderScheduled { id, due_at })
+        }
+        CardAction::OpenUrl { url, .. } => {
+            if !url.starts_with("http://") && !url.starts_with("https://") {
+                return Err(format!("Refusing to open non-web URL: {}", url));
+            }
+            if open {
+                open_in_default_app(url)?;
+            }
+            Ok(ActionOutcome::UrlOpened { url: url.clone() })
+        }
+        CardAction::CreateGithubIssue { repo, title, body } => {
+            let url = create_github_issue(repo, title, body.as_deref()).await?;
+            Ok(ActionOutcome::IssueCreated { url })
+        }
+        CardAction::AddCalendarEvent {
+            title,
+            date,
+            time,
+            duration_minutes,
+            description,
+        } => {
+            let ics = build_ics_event(
+                title,
+                date,
+                time.as_deref(),
+                duration_minutes.unwrap_or(DEFAULT_EVENT_MINUTES),
+                description.as_deref(),
+            )?;
+            let path = write_ics_file(title, date, &ics)?;
+            if open {
+                open_in_default_app(&path.to_string_lossy())?;
+            }
+            Ok(ActionOutcome::CalendarEventCreated {
+                path: path.to_string_lossy().to_string(),
+            })
+        }
+    }
+}
+
+async fn create_github_issue(
+    repo: &str,
+    title: &str,
+    body: Option<&str>,
+) -> Result<String, String> {
+    let valid_repo = repo.split('/').count() == 2
+        && repo
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | '/'));
+    if !valid_repo {
+        return Err(format!("Invalid GitHub repository '{}'", repo));
+    }
+
+    let token = std::env::var("GITHUB_TOKEN")
+        .ok()
+        .or_else(|| crate::config::read_env_value("GITHUB_TOKEN"))
+        .ok_or("Creating GitHub issues requires a GITHUB_TOKEN")?;
+
+    let response = crate::http_client::client()
+        .post(format!("https://api.github.com/repos/{}/issues", repo))
+        .header("Authorization", format!("Bearer {}", token))
+        .header("Accept", "application/vnd.github+json")
+        .header("User-Agent", "Claudius")
+        .json(&serde_json::json!({
+            "title": title,
+            "body": body.unwrap_or_default(),
+        }))
+        .send()
+        .await
+        .map_err(|e| format!("HTTP request failed: {}", e))?;
+
+    if !response.status().is_success() {
+        let status = response.status().as_u16();
+        let text = response.text().await.unwrap_or_default();
+        return Err(format!("GitHub API error {}: {}", status, text));
+    }
+
+    let issue: serde_json::Value = response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse GitHub response: {}", e))?;
+    let url = issue["html_url"].as_str().unwrap_or_default().to_string();
+    info!("Created GitHub issue {}", url);
+    Ok(url)
+}
+
+/// Escape text for an iCalendar property value.
+pub(crate) fn escape_ics(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+        .replace('\r', "")
+}
+
+/// The `VEVENT` lines of a calendar event. Times are floating local time.
+pub(crate) fn ics_event_lines(
+    uid: &str,
+    title: &str,
+    date: &str,
+    time: Option<&str>,
+    duration_minutes: u32,
+    description: Option<&str>,
+) -> Result<Vec<String>, String> {
+    let day = NaiveDate::parse_from_str(date, "%Y-%m-%d")
+        .map_err(|_| format!("Invalid event date '{}' (expected YYYY-MM-DD)", date))?;
+
+    let (start, end) = match time {
+        Some(time) => {
+            let time = NaiveTime::parse_from_str(time, "%H:%M")
+                .map_err(|_| format!("Invalid event time '{}' (expected HH:MM)", time))?;
+            let start = day.and_time(time);
+            let end = start + Duration::minutes(i64::from(duration_minutes.max(1)));
+            (
+                format!("DTSTART:{}", start.format("%Y%m%dT%H%M%S")),
+                format!("DTEND:{}", end.format("%Y%m%dT%H%M%S")),
+            )
+        }
+        None => (
+            format!("DTSTART;VALUE=DATE:{}", day.format("%Y%m%d")),
+            format!(
+                "DTEND;VALUE=DATE:{}",
+                (day + Duration::days(1)).format("%Y%m%d")
+            ),
+        ),
+    };
+
+    let mut lines = vec![
+        "BEGIN:VEVENT".to_string(),
+        format!("UID:{}", uid),
+        format!("DTSTAMP:{}", Utc::now().format("%Y%m%dT%H%M%SZ")),
+        start,
+        end,
+        format!("SUMMARY:{}", escape_ics(title)),
+    ];
+    if let Some(description) = description {
+        lines.push(format!("DESCRIPTION:{}", escape_ics(description)));
+    }
+    lines.push("END:VEVENT".to_string());
+    Ok(lines)
+}
+
+/// Build a single-event iCalendar document.
+fn build_ics_event(
+    title: &str,
+    date: &str,
+    time: Option<&str>,
+    duration_minutes: u32,
+    description: Option<&str>,
+) -> Result<String, String> {
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//Claudius//Briefing Actions//EN".to_string(),
+    ];
+    lines.extend(ics_event_lines(
+        &format!("{}@claudius", uuid::Uuid::new_v4()),
+        title,
+        date,
+        time,
+        duration_minutes,
+        description,
+    )?);
+    lines.push("END:VCALENDAR".to_string());
+    Ok(lines.join("\r\n") + "\r\n")
+}
+
+fn write_ics_file(title: &str, date: &str, ics: &str) -> Result<PathBuf, String> {
+    let dir = crate::config::get_data_dir().join("calendar");
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| format!("Failed to create calendar directory: {}", e))?;
+    let slug: String = title
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else {
+                '-'
+            }
+        })
+        .take(40)
+        .collect();
+    let path = dir.join(format!("{}-{}.ics", date, slug.trim_matches('-')));
+    std::fs::write(&path, ics).map_err(|e| format!("Failed to write calendar file: {}", e))?;
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_actions_deserialize_skips_invalid() {
+        #[derive(Deserialize)]
+        struct Card {
+            #[serde(default, deserialize_with = "deserialize_actions")]
+            actions: Vec<CardAction>,
+        }
+
+        let card: Card = serde_json::from_str(
+            r#"{"actions": [
+                {"type": "remind_me", "message": "Check the RC", "in_hours": 48},
+                {"type": "send_fax", "to": "nobody"},
+                {"type": "open_url"},
+                {"type": "create_github_issue", "repo": "acme/app", "title": "Upgrade tokio"}
+            ]}"#,
+        )
+        .unwrap();
+        assert_eq!(card.actions.len(), 2);
+        assert_eq!(
+            card.actions[0],
+            CardAction::RemindMe {
+                message: "Check the RC".to_string(),
+                in_hours: Some(48)
+            }
+        );
+        assert!(card.actions[1]
+            .label()
+            .starts_with("Create issue in acme/app"));
+
+        let card: Card = serde_json::from_str(r#"{"actions": null}"#).unwrap();
+        assert!(card.actions.is_empty());
+    }
+
+    #[test]
+    fn test_build_ics_event() {
+        let ics = build_ics_event(
+            "Rust 2.0, launch",
+            "2026-03-05",
+            Some("14:30"),
+            90,
+            Some("Watch the stream"),
+        )
+        .unwrap();
+        assert!(ics.contains("DTSTART:20260305T143000\r\n"));
+        assert!(ics.contains("DTEND:20260305T160000\r\n"));
+        assert!(ics.contains("SUMMARY:Rust 2.0\\, launch\r\n"));
+
+        let all_day = build_ics_event("Conf", "2026-12-31", None, 60, None).unwrap();
+        assert!(all_day.contains("DTSTART;VALUE=DATE:20261231"));
+        assert!(all_day.contains("DTEND;VALUE=DATE:20270101"));
+
+        assert!(build_ics_event("Bad", "next tuesday", None, 60, None).is_err());
+    }
+}
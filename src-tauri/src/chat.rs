@@ -4,15 +4,18 @@
 //! Users can chat about briefings with Claude, using the briefing content as context.
 
 use chrono::{Datelike, Local};
-use regex::Regex;
 use reqwest::Client;
-use serde::{Deserialize, Serialize};
-use std::collections::HashSet;
+use serde::Deserialize;
 use std::time::Duration;
-use tracing::{error, info};
+use tracing::{error, info, warn};
 
+use crate::agent_loop::{
+    mcp_result_text, stream_message, ApiEndpoint, ContentBlock, Message, MessageContent,
+    MessagesRequest,
+};
 use crate::db::{self, ChatMessage};
 use crate::mcp_client::{load_mcp_servers, McpClient};
+use crate::tools::{BuiltinTools, ToolDefinition, ToolFilter, ToolRegistry};
 use serde_json::json;
 use tauri::Emitter;
 
@@ -28,6 +31,16 @@ pub struct ChatToolStartEvent {
     pub card_index: i32,
 }
 
+/// Event emitted as the reply streams in. `text` is the newest piece; a new
+/// `iteration` (after tool calls) starts the reply over.
+#[derive(Clone, serde::Serialize)]
+pub struct ChatDeltaEvent {
+    pub briefing_id: i64,
+    pub card_index: i32,
+    pub iteration: u32,
+    pub text: String,
+}
+
 /// Event emitted when chat tool execution completes.
 #[derive(Clone, serde::Serialize)]
 pub struct ChatToolCompleteEvent {
@@ -37,94 +50,6 @@ pub struct ChatToolCompleteEvent {
     pub card_index: i32,
 }
 
-// ============================================================================
-// API Structures
-// ============================================================================
-
-/// Tool definition for chat.
-#[derive(Debug, Clone, Serialize)]
-struct Tool {
-    name: String,
-    description: String,
-    input_schema: serde_json::Value,
-}
-
-/// Anthropic API message request with tool support.
-#[derive(Debug, Serialize)]
-struct ChatRequest {
-    model: String,
-    max_tokens: u32,
-    messages: Vec<Message>,
-    system: String,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    tools: Option<Vec<serde_json::Value>>,
-}
-
-/// A message in the conversation.
-#[derive(Debug, Clone, Serialize, Deserialize)]
-struct Message {
-    role: String,
-    content: MessageContent,
-}
-
-/// Message content can be a string or array of content blocks.
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(untagged)]
-enum MessageContent {
-    Text(String),
-    Blocks(Vec<ContentBlock>),
-}
-
-/// A content block in a message (for serialization).
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(tag = "type")]
-enum ContentBlock {
-    #[serde(rename = "text")]
-    Text { text: String },
-    #[serde(rename = "tool_use")]
-    ToolUse {
-        id: String,
-        name: String,
-        input: serde_json::Value,
-    },
-    #[serde(rename = "tool_result")]
-    ToolResult {
-        tool_use_id: String,
-        content: String,
-        #[serde(skip_serializing_if = "Option::is_none")]
-        is_error: Option<bool>,
-    },
-}
-
-/// Anthropic API response.
-#[derive(Debug, Deserialize)]
-struct ChatResponse {
-    content: Vec<ResponseContentBlock>,
-    usage: Usage,
-    stop_reason: Option<String>,
-}
-
-/// Content block in API response (for deserialization).
-#[derive(Debug, Deserialize)]
-struct ResponseContentBlock {
-    #[serde(rename = "type")]
-    content_type: String,
-    #[serde(default)]
-    text: Option<String>,
-    #[serde(default)]
-    id: Option<String>,
-    #[serde(default)]
-    name: Option<String>,
-    #[serde(default)]
-    input: Option<serde_json::Value>,
-}
-
-#[derive(Debug, Deserialize)]
-struct Usage {
-    input_tokens: u32,
-    output_tokens: u32,
-}
-
 // ============================================================================
 // Tool Constants
 // ============================================================================
@@ -139,73 +64,30 @@ const WEB_SEARCH_MAX_USES: u32 = 3;
 // Tool Definitions
 // ============================================================================
 
-/// Get built-in tools available for chat.
-fn get_chat_tools() -> Vec<Tool> {
-    vec![
-        Tool {
-            name: "fetch_webpage".to_string(),
-            description: "Fetch and extract text content from a webpage URL. Use this to get current information from news sites, documentation, or other web sources.".to_string(),
-            input_schema: json!({
-                "type": "object",
-                "properties": {
-                    "url": {
-                        "type": "string",
-                        "description": "The URL of the webpage to fetch"
-                    }
-                },
-                "required": ["url"]
-            }),
-        },
-        Tool {
-            name: "get_github_activity".to_string(),
-            description: "Get recent activity from a GitHub repository including recent commits, PRs, and issues. Use this when researching topics related to open source projects or specific GitHub repositories.".to_string(),
-            input_schema: json!({
-                "type": "object",
-                "properties": {
-                    "owner": {
-                        "type": "string",
-                        "description": "The GitHub repository owner (username or organization)"
-                    },
-                    "repo": {
-                        "type": "string",
-                        "description": "The GitHub repository name"
-                    },
-                    "activity_type": {
-                        "type": "string",
-                        "enum": ["commits", "pulls", "issues", "releases"],
-                        "description": "Type of activity to fetch"
-                    }
-                },
-                "required": ["owner", "repo", "activity_type"]
-            }),
-        },
-    ]
-}
-
 /// Get all tools as JSON values for API request.
 ///
-/// Combines built-in tools with MCP tools and optionally Claude's web_search.
+/// Combines built-in tools with MCP tools and optionally Claude's web_search,
+/// leaving out the tools `filter` disables.
 fn get_tools_json(
+    builtin_tools: &BuiltinTools,
     mcp_client: &Option<McpClient>,
     enable_web_search: bool,
+    filter: &ToolFilter,
 ) -> Vec<serde_json::Value> {
-    let tools = get_chat_tools();
-    let mut tools_json: Vec<serde_json::Value> = tools
+    let mut tools_json: Vec<serde_json::Value> = builtin_tools
+        .definitions()
         .iter()
-        .map(|t| {
-            json!({
-                "name": t.name,
-                "description": t.description,
-                "input_schema": t.input_schema
-            })
-        })
+        .map(ToolDefinition::to_json)
         .collect();
 
     // Add MCP tools
     if let Some(ref client) = mcp_client {
         for mcp_tool in client.get_all_tools() {
+            if !filter.allows(Some(&mcp_tool.server_name), &mcp_tool.tool.name) {
+                continue;
+            }
             tools_json.push(json!({
-                "name": mcp_tool.tool.name,
+                "name": mcp_tool.namespaced_name(),
                 "description": mcp_tool.tool.description.clone().unwrap_or_else(||
                     format!("Tool from {} MCP server", mcp_tool.server_name)
                 ),
@@ -215,7 +97,7 @@ fn get_tools_json(
     }
 
     // Add Claude's built-in web search tool if enabled
-    if enable_web_search {
+    if enable_web_search && filter.allows(None, "web_search") {
         tools_json.push(json!({
             "type": WEB_SEARCH_TOOL_TYPE,
             "name": "web_search",
@@ -227,11 +109,6 @@ fn get_tools_json(
     tools_json
 }
 
-/// Names of built-in tools (to differentiate from MCP tools).
-fn get_builtin_tool_names() -> std::collections::HashSet<String> {
-    get_chat_tools().into_iter().map(|t| t.name).collect()
-}
-
 // ============================================================================
 // Chat Functions
 // ============================================================================
@@ -339,10 +216,7 @@ fn build_messages(history: &[ChatMessage], new_message: &str) -> Vec<Message> {
     }
 
     // Add the new user message
-    messages.push(Message {
-        role: "user".to_string(),
-        content: MessageContent::Text(new_message.to_string()),
-    });
+    messages.push(Message::user(new_message));
 
     messages
 }
@@ -356,7 +230,8 @@ const MAX_TOOL_ITERATIONS: u32 = 5;
 /// 1. Loads the briefing for context
 /// 2. Loads existing chat history for this specific card
 /// 3. Initializes MCP client for tool support
-/// 4. Calls the Anthropic API with tools in an agentic loop
+/// 4. Calls the Anthropic API with tools in an agentic loop, streaming the
+///    reply as `chat:delta` events
 /// 5. Saves both user message and assistant response to the database
 /// 6. Returns the assistant's message
 pub async fn send_chat_message(
@@ -384,7 +259,7 @@ pub async fn send_chat_message(
     );
 
     // Initialize MCP client for tools
-    let mut mcp_client: Option<McpClient> = match load_mcp_servers() {
+    let mcp_client: Option<McpClient> = match load_mcp_servers() {
         Ok(servers) => {
             let enabled_servers: Vec<_> = servers.into_iter().filter(|s| s.enabled).collect();
             if enabled_servers.is_empty() {
@@ -421,13 +296,18 @@ pub async fn send_chat_message(
     };
 
     // Build tools JSON
-    let tools_json = get_tools_json(&mcp_client, enable_web_search);
+    let filter = ToolFilter::current();
+    let enable_web_search = enable_web_search && filter.allows(None, "web_search");
+    let builtin_tools = BuiltinTools::from_env().with_filter(filter.clone());
+    let builtin_count = builtin_tools.definitions().len();
+    let tools_json = get_tools_json(&builtin_tools, &mcp_client, enable_web_search, &filter);
     let has_tools = !tools_json.is_empty();
 
     info!(
-        "Chat tools configured: {} total (built-in: 2, MCP: {}, web_search: {})",
+        "Chat tools configured: {} total (built-in: {}, MCP: {}, web_search: {})",
         tools_json.len(),
-        tools_json.len() - 2 - if enable_web_search { 1 } else { 0 },
+        builtin_count,
+        tools_json.len() - builtin_count - if enable_web_search { 1 } else { 0 },
         enable_web_search
     );
 
@@ -439,11 +319,12 @@ pub async fn send_chat_message(
     let mut messages = build_messages(&history, user_message);
 
     // Create HTTP client
-    let http_client = Client::builder()
+    let http_client = crate::http_client::client_builder()
         .timeout(Duration::from_secs(120)) // Longer timeout for tool calls
         .connect_timeout(Duration::from_secs(10))
         .build()
         .map_err(|e| format!("Failed to build HTTP client: {}", e))?;
+    let endpoint = ApiEndpoint::current();
 
     info!(
         "Sending chat message for briefing {} card {} (tools: {}, web_search: {})",
@@ -452,7 +333,7 @@ pub async fn send_chat_message(
 
     let mut total_tokens: u32 = 0;
     let mut iterations: u32 = 0;
-    let builtin_tools = get_builtin_tool_names();
+    let mut tools_used: Vec<String> = Vec::new();
     let final_text: String;
 
     // Agentic loop - continue until Claude finishes or max iterations
@@ -467,44 +348,38 @@ pub async fn send_chat_message(
         }
 
         // Create API request
-        let request = ChatRequest {
+        let request = MessagesRequest {
             model: model.to_string(),
             max_tokens: 2048,
             messages: messages.clone(),
-            system: system_prompt.clone(),
+            system: Some(system_prompt.clone()),
             tools: if has_tools {
                 Some(tools_json.clone())
             } else {
                 None
             },
+            thinking: None,
+            metadata: endpoint.metadata(),
         };
 
-        // Send request to Anthropic API
-        let response = http_client
-            .post("https://api.anthropic.com/v1/messages")
-            .header("x-api-key", api_key)
-            .header("anthropic-version", "2023-06-01")
-            .header("content-type", "application/json")
-            .json(&request)
-            .send()
-            .await
-            .map_err(|e| format!("HTTP request failed: {}", e))?;
-
-        // Check for errors
-        if !response.status().is_success() {
-            let status = response.status().as_u16();
-            let body = response.text().await.unwrap_or_default();
-            error!("Chat API error {}: {}", status, body);
-            return Err(format!("API error {}: {}", status, body));
-        }
-
-        // Parse response
-        let chat_response: ChatResponse = response
-            .json()
-            .await
-            .map_err(|e| format!("Failed to parse response: {}", e))?;
+        // Stream the reply, forwarding text to the UI as it arrives
+        let chat_response = stream_message(&http_client, &endpoint, api_key, &request, |text| {
+            if let Some(app) = app_handle {
+                let _ = app.emit(
+                    "chat:delta",
+                    ChatDeltaEvent {
+                        briefing_id,
+                        card_index,
+                        iteration: iterations,
+                        text: text.to_string(),
+                    },
+                );
+            }
+        })
+        .await
+        .inspect_err(|e| error!("Chat API error: {}", e))?;
 
-        let tokens = chat_response.usage.input_tokens + chat_response.usage.output_tokens;
+        let tokens = chat_response.total_tokens();
         total_tokens += tokens;
 
         info!(
@@ -512,28 +387,19 @@ pub async fn send_chat_message(
             iterations, tokens, chat_response.stop_reason
         );
 
-        // Check for tool use requests
-        let tool_uses: Vec<_> = chat_response
-            .content
-            .iter()
-            .filter(|c| c.content_type == "tool_use")
-            .collect();
+        // Server tools (web_search) run inside the API call
+        for block in &chat_response.content {
+            if block.content_type == "server_tool_use" {
+                record_tool(
+                    &mut tools_used,
+                    block.name.as_deref().unwrap_or("web_search"),
+                );
+            }
+        }
 
         // If no tool calls or stop_reason is end_turn, we're done
-        if tool_uses.is_empty() || chat_response.stop_reason.as_deref() == Some("end_turn") {
-            // Extract final text response
-            final_text = chat_response
-                .content
-                .iter()
-                .filter_map(|block| {
-                    if block.content_type == "text" {
-                        block.text.clone()
-                    } else {
-                        None
-                    }
-                })
-                .collect::<Vec<_>>()
-                .join("\n");
+        if chat_response.is_final() {
+            final_text = chat_response.text();
 
             info!(
                 "Chat complete after {} iterations, {} total tokens",
@@ -543,15 +409,17 @@ pub async fn send_chat_message(
         }
 
         // Execute tool calls
-        info!("Executing {} tool calls", tool_uses.len());
+        let tool_calls = chat_response.tool_calls();
+        info!("Executing {} tool calls", tool_calls.len());
         let mut tool_results: Vec<ContentBlock> = Vec::new();
 
-        for tool_use in &tool_uses {
-            let tool_id = tool_use.id.as_deref().unwrap_or("unknown");
-            let tool_name = tool_use.name.as_deref().unwrap_or("unknown");
-            let tool_input = tool_use.input.clone().unwrap_or(json!({}));
+        for tool_call in &tool_calls {
+            let tool_id = tool_call.id.as_str();
+            let tool_name = tool_call.name.as_str();
+            let tool_input = &tool_call.input;
 
             info!("Executing tool: {} ({})", tool_name, tool_id);
+            record_tool(&mut tools_used, tool_name);
 
             // Emit tool start event
             if let Some(app) = app_handle {
@@ -567,23 +435,17 @@ pub async fn send_chat_message(
 
             let result = execute_chat_tool(
                 &http_client,
-                &mut mcp_client,
+                &mcp_client,
                 &builtin_tools,
                 tool_name,
-                &tool_input,
+                tool_input,
             )
             .await;
 
-            let (content, is_error) = match &result {
-                Ok(output) => {
-                    info!("Tool {} succeeded: {} chars", tool_name, output.len());
-                    (output.clone(), None)
-                }
-                Err(e) => {
-                    error!("Tool {} failed: {}", tool_name, e);
-                    (format!("Error: {}", e), Some(true))
-                }
-            };
+            match &result {
+                Ok(output) => info!("Tool {} succeeded: {} chars", tool_name, output.len()),
+                Err(e) => error!("Tool {} failed: {}", tool_name, e),
+            }
 
             // Emit tool complete event
             if let Some(app) = app_handle {
@@ -598,46 +460,14 @@ pub async fn send_chat_message(
                 );
             }
 
-            tool_results.push(ContentBlock::ToolResult {
-                tool_use_id: tool_id.to_string(),
-                content,
-                is_error,
-            });
+            tool_results.push(ContentBlock::tool_result(tool_id, result));
         }
 
         // Add assistant's response (with tool_use blocks) to messages
-        let assistant_blocks: Vec<ContentBlock> = chat_response
-            .content
-            .iter()
-            .map(|c| {
-                if c.content_type == "text" {
-                    ContentBlock::Text {
-                        text: c.text.clone().unwrap_or_default(),
-                    }
-                } else if c.content_type == "tool_use" {
-                    ContentBlock::ToolUse {
-                        id: c.id.clone().unwrap_or_default(),
-                        name: c.name.clone().unwrap_or_default(),
-                        input: c.input.clone().unwrap_or(json!({})),
-                    }
-                } else {
-                    ContentBlock::Text {
-                        text: String::new(),
-                    }
-                }
-            })
-            .collect();
-
-        messages.push(Message {
-            role: "assistant".to_string(),
-            content: MessageContent::Blocks(assistant_blocks),
-        });
+        messages.push(chat_response.assistant_message());
 
         // Add tool results as user message
-        messages.push(Message {
-            role: "user".to_string(),
-            content: MessageContent::Blocks(tool_results),
-        });
+        messages.push(Message::tool_results(tool_results));
     }
 
     // Save user message to database
@@ -645,15 +475,26 @@ pub async fn send_chat_message(
         db::insert_chat_message(&conn, briefing_id, card_index, "user", user_message, None)?;
 
     // Save assistant response to database
-    let assistant_id = db::insert_chat_message(
+    let assistant_id = db::insert_chat_message_with_tools(
         &conn,
         briefing_id,
         card_index,
         "assistant",
         &final_text,
         Some(total_tokens as i32),
+        &tools_used,
     )?;
 
+    if let Err(e) = db::record_api_usage(
+        &conn,
+        &crate::config::active_profile(),
+        "chat",
+        Some(briefing_id),
+        total_tokens as i64,
+    ) {
+        warn!("Failed to record chat usage: {}", e);
+    }
+
     // Get the saved assistant message
     let assistant_message = db::get_chat_message_by_id(&conn, assistant_id)?
         .ok_or("Failed to retrieve saved message")?;
@@ -661,6 +502,13 @@ pub async fn send_chat_message(
     Ok((assistant_message, total_tokens as i32))
 }
 
+/// Add a tool to the reply's list of tools used, once.
+fn record_tool(tools_used: &mut Vec<String>, name: &str) {
+    if !tools_used.iter().any(|t| t == name) {
+        tools_used.push(name.to_string());
+    }
+}
+
 /// Load a briefing from the database.
 fn load_briefing(conn: &rusqlite::Connection, briefing_id: i64) -> Result<BriefingData, String> {
     let mut stmt = conn
@@ -679,7 +527,6 @@ fn load_briefing(conn: &rusqlite::Connection, briefing_id: i64) -> Result<Briefi
 
 /// Minimal briefing data for chat context.
 struct BriefingData {
-    #[allow(dead_code)]
     id: i64,
     title: String,
     cards: String,
@@ -700,306 +547,145 @@ pub fn clear_chat_history(briefing_id: i64, card_index: i32) -> Result<usize, St
 }
 
 // ============================================================================
-// Tool Execution
+// Export
 // ============================================================================
 
-/// Execute a chat tool and return the result.
-///
-/// Routes to built-in tools or MCP client based on tool name.
-async fn execute_chat_tool(
-    http_client: &Client,
-    mcp_client: &mut Option<McpClient>,
-    builtin_tools: &HashSet<String>,
-    tool_name: &str,
-    tool_input: &serde_json::Value,
-) -> Result<String, String> {
-    // Check if it's a built-in tool
-    if builtin_tools.contains(tool_name) {
-        return execute_builtin_tool(http_client, tool_name, tool_input).await;
-    }
+/// Formats accepted by [`export_chat`].
+pub const EXPORT_FORMATS: [&str; 2] = ["markdown", "json"];
 
-    // Try MCP client
-    if let Some(ref mut client) = mcp_client {
-        // Check if the tool exists in MCP
-        let has_tool = client
-            .get_all_tools()
-            .into_iter()
-            .any(|t| t.tool.name == tool_name);
-
-        if has_tool {
-            info!("Calling MCP tool '{}'", tool_name);
-            // call_tool returns serde_json::Value, convert to String
-            let result = client.call_tool(tool_name, tool_input.clone())?;
-            // Convert Value to readable string
-            if let Some(s) = result.as_str() {
-                return Ok(s.to_string());
-            } else {
-                return Ok(
-                    serde_json::to_string_pretty(&result).unwrap_or_else(|_| result.to_string())
-                );
-            }
-        }
+/// Export the conversation about a card as markdown or JSON, with each
+/// message's timestamp and the tools used for each reply.
+pub fn export_chat(briefing_id: i64, card_index: i32, format: &str) -> Result<String, String> {
+    if !EXPORT_FORMATS.contains(&format) && format != "md" {
+        return Err(format!(
+            "Unknown export format '{}' (expected {})",
+            format,
+            EXPORT_FORMATS.join(" or ")
+        ));
     }
 
-    Err(format!("Unknown tool: {}", tool_name))
-}
-
-/// Execute a built-in tool.
-async fn execute_builtin_tool(
-    client: &Client,
-    tool_name: &str,
-    input: &serde_json::Value,
-) -> Result<String, String> {
-    match tool_name {
-        "fetch_webpage" => {
-            let url = input
-                .get("url")
-                .and_then(|v| v.as_str())
-                .ok_or("Missing url parameter")?;
-            execute_fetch_webpage(client, url).await
-        }
-        "get_github_activity" => {
-            let owner = input
-                .get("owner")
-                .and_then(|v| v.as_str())
-                .ok_or("Missing owner parameter")?;
-            let repo = input
-                .get("repo")
-                .and_then(|v| v.as_str())
-                .ok_or("Missing repo parameter")?;
-            let activity_type = input
-                .get("activity_type")
-                .and_then(|v| v.as_str())
-                .ok_or("Missing activity_type parameter")?;
-
-            // Try to get GitHub token from environment
-            let github_token = std::env::var("GITHUB_TOKEN").ok();
-            execute_github_activity(client, owner, repo, activity_type, github_token.as_deref())
-                .await
-        }
-        _ => Err(format!("Unknown built-in tool: {}", tool_name)),
-    }
-}
-
-/// Fetch and extract text content from a webpage.
-async fn execute_fetch_webpage(client: &Client, url: &str) -> Result<String, String> {
-    // Validate URL
-    if !url.starts_with("http://") && !url.starts_with("https://") {
-        return Err("URL must start with http:// or https://".to_string());
-    }
-
-    info!("Fetching webpage: {}", url);
-
-    let response = client
-        .get(url)
-        .header("User-Agent", "Claudius-Chat-Agent")
-        .send()
-        .await
-        .map_err(|e| format!("Failed to fetch URL: {}", e))?;
-
-    if !response.status().is_success() {
-        return Err(format!("HTTP error: {}", response.status()));
-    }
-
-    let html = response
-        .text()
-        .await
-        .map_err(|e| format!("Failed to read response: {}", e))?;
-
-    // Extract text content from HTML
-    let text = extract_text_from_html(&html);
-
-    // Truncate if too long
-    let max_chars = 8000;
-    let char_count = text.chars().count();
-    if char_count > max_chars {
-        let truncated: String = text.chars().take(max_chars).collect();
-        Ok(format!(
-            "{}...\n\n[Content truncated, {} total characters]",
-            truncated, char_count
-        ))
+    let conn = db::get_connection().map_err(|e| format!("Database connection failed: {}", e))?;
+    let briefing = load_briefing(&conn, briefing_id)?;
+    let messages = db::get_chat_messages(&conn, briefing_id, card_index)?;
+    if messages.is_empty() {
+        return Err(format!(
+            "No chat messages for card {} of briefing {}",
+            card_index + 1,
+            briefing_id
+        ));
+    }
+    let card_title = card_title(&briefing.cards, card_index);
+
+    if format == "json" {
+        serde_json::to_string_pretty(&json!({
+            "briefing_id": briefing.id,
+            "briefing_title": briefing.title,
+            "card_index": card_index,
+            "card_title": card_title,
+            "exported_at": Local::now().to_rfc3339(),
+            "messages": messages,
+        }))
+        .map_err(|e| format!("Failed to serialize chat: {}", e))
     } else {
-        Ok(text)
+        Ok(render_chat_markdown(
+            &briefing,
+            card_index,
+            &card_title,
+            &messages,
+        ))
     }
 }
 
-/// Simple HTML text extraction (removes tags, scripts, styles).
-fn extract_text_from_html(html: &str) -> String {
-    // Remove script and style tags with content
-    let without_scripts = Regex::new(r"(?is)<script[^>]*>.*?</script>")
-        .ok()
-        .map(|re| re.replace_all(html, "").to_string())
-        .unwrap_or_else(|| html.to_string());
-
-    let without_styles = Regex::new(r"(?is)<style[^>]*>.*?</style>")
+/// Title of a card in the briefing cards JSON.
+fn card_title(cards_json: &str, card_index: i32) -> String {
+    serde_json::from_str::<Vec<serde_json::Value>>(cards_json)
         .ok()
-        .map(|re| re.replace_all(&without_scripts, "").to_string())
-        .unwrap_or_else(|| without_scripts.clone());
-
-    // Remove HTML tags
-    let without_tags = Regex::new(r"<[^>]+>")
-        .ok()
-        .map(|re| re.replace_all(&without_styles, " ").to_string())
-        .unwrap_or_else(|| without_styles.clone());
-
-    // Decode common HTML entities
-    let decoded = without_tags
-        .replace("&nbsp;", " ")
-        .replace("&amp;", "&")
-        .replace("&lt;", "<")
-        .replace("&gt;", ">")
-        .replace("&quot;", "\"")
-        .replace("&#39;", "'");
-
-    // Clean up whitespace
-    Regex::new(r"\s+")
-        .ok()
-        .map(|re| re.replace_all(&decoded, " ").trim().to_string())
-        .unwrap_or_else(|| decoded.trim().to_string())
+        .and_then(|cards| {
+            cards
+                .get(card_index as usize)
+                .and_then(|card| card["title"].as_str())
+                .map(|title| title.to_string())
+        })
+        .unwrap_or_else(|| format!("Card {}", card_index + 1))
 }
 
-/// Fetch GitHub activity (commits, PRs, issues, or releases).
-async fn execute_github_activity(
-    client: &Client,
-    owner: &str,
-    repo: &str,
-    activity_type: &str,
-    github_token: Option<&str>,
-) -> Result<String, String> {
-    let endpoint = match activity_type {
-        "commits" => format!(
-            "https://api.github.com/repos/{}/{}/commits?per_page=10",
-            owner, repo
-        ),
-        "pulls" => format!(
-            "https://api.github.com/repos/{}/{}/pulls?state=all&per_page=10",
-            owner, repo
-        ),
-        "issues" => format!(
-            "https://api.github.com/repos/{}/{}/issues?state=all&per_page=10",
-            owner, repo
-        ),
-        "releases" => format!(
-            "https://api.github.com/repos/{}/{}/releases?per_page=5",
-            owner, repo
-        ),
-        _ => return Err(format!("Unknown activity type: {}", activity_type)),
-    };
-
-    info!("Fetching GitHub {}: {}/{}", activity_type, owner, repo);
-
-    let mut request = client
-        .get(&endpoint)
-        .header("User-Agent", "Claudius-Chat-Agent")
-        .header("Accept", "application/vnd.github.v3+json");
-
-    if let Some(token) = github_token {
-        request = request.header("Authorization", format!("Bearer {}", token));
-    }
-
-    let response = request
-        .send()
-        .await
-        .map_err(|e| format!("GitHub API request failed: {}", e))?;
-
-    if !response.status().is_success() {
-        let status = response.status();
-        let body = response.text().await.unwrap_or_default();
-        return Err(format!("GitHub API error {}: {}", status, body));
+/// Render a conversation as markdown. Timestamps are stored in UTC.
+fn render_chat_markdown(
+    briefing: &BriefingData,
+    card_index: i32,
+    card_title: &str,
+    messages: &[ChatMessage],
+) -> String {
+    let mut out = format!("# Chat: {}\n\n", card_title);
+    out.push_str(&format!(
+        "*From \"{}\" (briefing {}, card {})*\n\n",
+        briefing.title,
+        briefing.id,
+        card_index + 1
+    ));
+    out.push_str(&format!(
+        "[Open in Claudius]({})\n",
+        crate::deep_link::briefing_url(briefing.id)
+    ));
+
+    for message in messages {
+        let speaker = if message.role == "user" {
+            "You"
+        } else {
+            "Claude"
+        };
+        out.push_str(&format!(
+            "\n---\n\n**{}** · {} UTC\n",
+            speaker, message.created_at
+        ));
+        if !message.tools_used.is_empty() {
+            out.push_str(&format!(
+                "\n*Tools used: {}*\n",
+                message.tools_used.join(", ")
+            ));
+        }
+        out.push_str(&format!("\n{}\n", message.content.trim()));
     }
 
-    let data: serde_json::Value = response
-        .json()
-        .await
-        .map_err(|e| format!("Failed to parse GitHub response: {}", e))?;
-
-    // Format the response based on activity type
-    format_github_activity(&data, activity_type)
+    out
 }
 
-/// Format GitHub API response into readable text.
-fn format_github_activity(data: &serde_json::Value, activity_type: &str) -> Result<String, String> {
-    let items = data.as_array().ok_or("Expected array response")?;
+// ============================================================================
+// Tool Execution
+// ============================================================================
 
-    if items.is_empty() {
-        return Ok(format!("No {} found.", activity_type));
+/// Execute a chat tool and return the result.
+///
+/// Routes to built-in tools or MCP client based on tool name.
+async fn execute_chat_tool(
+    http_client: &Client,
+    mcp_client: &Option<McpClient>,
+    builtin_tools: &BuiltinTools,
+    tool_name: &str,
+    tool_input: &serde_json::Value,
+) -> Result<String, String> {
+    // Check if it's a built-in tool
+    if builtin_tools.has_tool(tool_name) {
+        let output = builtin_tools
+            .execute(http_client, tool_name, tool_input)
+            .await?;
+        if output.from_cache {
+            info!("Tool {} served from cache", tool_name);
+        }
+        return Ok(output.text);
     }
 
-    let mut result = format!("Recent {} ({} items):\n\n", activity_type, items.len());
-
-    for item in items {
-        match activity_type {
-            "commits" => {
-                let sha = item
-                    .get("sha")
-                    .and_then(|v| v.as_str())
-                    .unwrap_or("unknown");
-                let message = item
-                    .get("commit")
-                    .and_then(|c| c.get("message"))
-                    .and_then(|m| m.as_str())
-                    .unwrap_or("No message");
-                let author = item
-                    .get("commit")
-                    .and_then(|c| c.get("author"))
-                    .and_then(|a| a.get("name"))
-                    .and_then(|n| n.as_str())
-                    .unwrap_or("Unknown");
-                let date = item
-                    .get("commit")
-                    .and_then(|c| c.get("author"))
-                    .and_then(|a| a.get("date"))
-                    .and_then(|d| d.as_str())
-                    .unwrap_or("");
-
-                result.push_str(&format!(
-                    "- {} ({}) by {} on {}\n",
-                    message.lines().next().unwrap_or(message),
-                    &sha[..7.min(sha.len())],
-                    author,
-                    date
-                ));
-            }
-            "pulls" | "issues" => {
-                let number = item.get("number").and_then(|v| v.as_u64()).unwrap_or(0);
-                let title = item
-                    .get("title")
-                    .and_then(|v| v.as_str())
-                    .unwrap_or("No title");
-                let state = item
-                    .get("state")
-                    .and_then(|v| v.as_str())
-                    .unwrap_or("unknown");
-                let user = item
-                    .get("user")
-                    .and_then(|u| u.get("login"))
-                    .and_then(|l| l.as_str())
-                    .unwrap_or("unknown");
-
-                result.push_str(&format!(
-                    "- #{} [{}] {} (by {})\n",
-                    number, state, title, user
-                ));
-            }
-            "releases" => {
-                let name = item
-                    .get("name")
-                    .and_then(|v| v.as_str())
-                    .or_else(|| item.get("tag_name").and_then(|v| v.as_str()))
-                    .unwrap_or("Unnamed");
-                let tag = item.get("tag_name").and_then(|v| v.as_str()).unwrap_or("");
-                let date = item
-                    .get("published_at")
-                    .and_then(|v| v.as_str())
-                    .unwrap_or("");
-
-                result.push_str(&format!("- {} ({}) - {}\n", name, tag, date));
-            }
-            _ => {}
+    // Try MCP client
+    if let Some(ref client) = mcp_client {
+        // MCP tools are offered to Claude under namespaced names
+        if let Some((server, name)) = client.resolve_tool(tool_name) {
+            info!("Calling MCP tool '{}' on server '{}'", name, server);
+            let result = client.call_tool(tool_name, tool_input.clone()).await?;
+            return Ok(mcp_result_text(&result));
         }
     }
 
-    Ok(result)
+    Err(format!("Unknown tool: {}", tool_name))
 }
 
 // ============================================================================
@@ -1011,28 +697,52 @@ mod tests {
     use super::*;
 
     #[test]
-    fn test_get_chat_tools() {
-        let tools = get_chat_tools();
-        assert_eq!(tools.len(), 2);
-
-        let tool_names: Vec<_> = tools.iter().map(|t| t.name.as_str()).collect();
-        assert!(tool_names.contains(&"fetch_webpage"));
-        assert!(tool_names.contains(&"get_github_activity"));
+    fn test_chat_uses_builtin_tools() {
+        let tools = BuiltinTools::default();
+        assert!(tools.has_tool("fetch_webpage"));
+        assert!(tools.has_tool("get_github_activity"));
+        assert!(tools.has_tool("search_arxiv"));
     }
 
     #[test]
-    fn test_get_builtin_tool_names() {
-        let names = get_builtin_tool_names();
-        assert!(names.contains("fetch_webpage"));
-        assert!(names.contains("get_github_activity"));
-        assert_eq!(names.len(), 2);
+    fn test_render_chat_markdown() {
+        let briefing = BriefingData {
+            id: 7,
+            title: "Morning Briefing".to_string(),
+            cards: r#"[{"title": "Rust 2.0"}]"#.to_string(),
+        };
+        let message = |role: &str, content: &str, tools: &[&str]| ChatMessage {
+            id: 1,
+            briefing_id: 7,
+            card_index: 0,
+            role: role.to_string(),
+            content: content.to_string(),
+            tokens_used: None,
+            created_at: "2025-01-01 09:30:00".to_string(),
+            tools_used: tools.iter().map(|t| t.to_string()).collect(),
+        };
+        let messages = vec![
+            message("user", "What changed?", &[]),
+            message("assistant", "Editions got simpler.", &["fetch_webpage"]),
+        ];
+
+        let title = card_title(&briefing.cards, 0);
+        assert_eq!(title, "Rust 2.0");
+        assert_eq!(card_title(&briefing.cards, 3), "Card 4");
+
+        let markdown = render_chat_markdown(&briefing, 0, &title, &messages);
+        assert!(markdown.starts_with("# Chat: Rust 2.0\n"));
+        assert!(markdown.contains("(briefing 7, card 1)"));
+        assert!(markdown.contains("**You** · 2025-01-01 09:30:00 UTC\n\nWhat changed?"));
+        assert!(markdown.contains("*Tools used: fetch_webpage*\n\nEditions got simpler."));
     }
 
     #[test]
     fn test_get_tools_json_without_mcp() {
-        let tools = get_tools_json(&None, false);
-        // Should have 2 built-in tools
-        assert_eq!(tools.len(), 2);
+        let builtin = BuiltinTools::default();
+        let tools = get_tools_json(&builtin, &None, false, &ToolFilter::default());
+        // Should have only the built-in tools
+        assert_eq!(tools.len(), builtin.definitions().len());
 
         // Check tool structure
         let fetch_tool = tools
@@ -1043,9 +753,10 @@ mod tests {
 
     #[test]
     fn test_get_tools_json_with_web_search() {
-        let tools = get_tools_json(&None, true);
-        // Should have 2 built-in tools + web_search
-        assert_eq!(tools.len(), 3);
+        let builtin = BuiltinTools::default();
+        let tools = get_tools_json(&builtin, &None, true, &ToolFilter::default());
+        // Should have the built-in tools + web_search
+        assert_eq!(tools.len(), builtin.definitions().len() + 1);
 
         // Check web_search is included
         let web_search = tools
@@ -1061,26 +772,6 @@ mod tests {
         );
     }
 
-    #[test]
-    fn test_extract_text_from_html() {
-        let html = r#"
-            <html>
-            <head><style>body { color: red; }</style></head>
-            <body>
-                <script>alert('test');</script>
-                <h1>Hello World</h1>
-                <p>This is a &lt;test&gt; with &amp; entities.</p>
-            </body>
-            </html>
-        "#;
-
-        let text = extract_text_from_html(html);
-        assert!(text.contains("Hello World"));
-        assert!(text.contains("This is a <test> with & entities"));
-        assert!(!text.contains("alert"));
-        assert!(!text.contains("color: red"));
-    }
-
     #[test]
     fn test_extract_card_content() {
         let cards_json = r#"[
@@ -1125,88 +816,4 @@ mod tests {
         assert!(prompt.contains("Today's date is"));
         assert!(!prompt.contains("tools to fetch real-time information"));
     }
-
-    #[test]
-    fn test_format_github_activity_commits() {
-        let data = serde_json::json!([
-            {
-                "sha": "abc1234567890",
-                "commit": {
-                    "message": "Fix bug in parser",
-                    "author": {
-                        "name": "John Doe",
-                        "date": "2025-01-01T12:00:00Z"
-                    }
-                }
-            }
-        ]);
-
-        let result = format_github_activity(&data, "commits").unwrap();
-        assert!(result.contains("Fix bug in parser"));
-        assert!(result.contains("abc1234"));
-        assert!(result.contains("John Doe"));
-    }
-
-    #[test]
-    fn test_format_github_activity_issues() {
-        let data = serde_json::json!([
-            {
-                "number": 42,
-                "title": "Bug report",
-                "state": "open",
-                "user": { "login": "reporter" }
-            }
-        ]);
-
-        let result = format_github_activity(&data, "issues").unwrap();
-        assert!(result.contains("#42"));
-        assert!(result.contains("Bug report"));
-        assert!(result.contains("[open]"));
-        assert!(result.contains("reporter"));
-    }
-
-    #[test]
-    fn test_format_github_activity_empty() {
-        let data = serde_json::json!([]);
-        let result = format_github_activity(&data, "commits").unwrap();
-        assert!(result.contains("No commits found"));
-    }
-
-    #[test]
-    fn test_message_content_serialization() {
-        let text_content = MessageContent::Text("Hello".to_string());
-        let json = serde_json::to_string(&text_content).unwrap();
-        assert_eq!(json, "\"Hello\"");
-
-        let blocks_content = MessageContent::Blocks(vec![ContentBlock::Text {
-            text: "Test".to_string(),
-        }]);
-        let json = serde_json::to_string(&blocks_content).unwrap();
-        assert!(json.contains("\"type\":\"text\""));
-    }
-
-    #[test]
-    fn test_content_block_tool_result() {
-        let block = ContentBlock::ToolResult {
-            tool_use_id: "tool_123".to_string(),
-            content: "Success".to_string(),
-            is_error: None,
-        };
-        let json = serde_json::to_string(&block).unwrap();
-        assert!(json.contains("tool_result"));
-        assert!(json.contains("tool_123"));
-        assert!(!json.contains("is_error")); // Should be skipped when None
-    }
-
-    #[test]
-    fn test_content_block_tool_result_with_error() {
-        let block = ContentBlock::ToolResult {
-            tool_use_id: "tool_456".to_string(),
-            content: "Failed".to_string(),
-            is_error: Some(true),
-        };
-        let json = serde_json::to_string(&block).unwrap();
-        assert!(json.contains("is_error"));
-        assert!(json.contains("true"));
-    }
 }
@@ -1,109 +1,17 @@
+use crate::config::{
+    get_logs_dir, read_mcp_servers, read_settings, write_mcp_servers, write_settings, Briefing,
+    MCPServer, ResearchSettings,
+};
 use crate::db::{self, Topic};
 use crate::research::CancelledEvent;
 use crate::research_state;
 use chrono::{Local, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::path::PathBuf;
 use tauri::Emitter;
 use uuid::Uuid;
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct Briefing {
-    pub id: i64,
-    pub date: String,
-    pub title: String,
-    pub cards: String, // JSON string
-    pub research_time_ms: Option<i64>,
-    pub model_used: Option<String>,
-    pub total_tokens: Option<i64>,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct MCPServer {
-    pub id: String,
-    pub name: String,
-    pub enabled: bool,
-    pub config: serde_json::Value,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub last_used: Option<String>,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct MCPServersConfig {
-    pub servers: Vec<MCPServer>,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ResearchSettings {
-    pub schedule_cron: String,
-    pub model: String,
-    pub research_depth: String,
-    pub max_sources_per_topic: i32,
-    pub enable_notifications: bool,
-    #[serde(default = "default_notification_sound")]
-    pub notification_sound: bool,
-    #[serde(default)]
-    pub enable_web_search: bool,
-    #[serde(default)]
-    pub retention_days: Option<i32>, // None = never delete, Some(7/14/30/90)
-    #[serde(default)]
-    pub condense_briefings: bool, // Combine all topics into one comprehensive card
-    #[serde(default = "default_dedup_days")]
-    pub dedup_days: i32, // Days to look back for duplicates
-    #[serde(default = "default_dedup_threshold")]
-    pub dedup_threshold: f64, // Similarity threshold (0.0-1.0)
-    #[serde(default)]
-    pub enable_image_generation: bool, // Generate header images using DALL-E
-    #[serde(default = "default_research_mode")]
-    pub research_mode: String, // "standard" | "firecrawl" - determines which tools are used
-    #[serde(default = "default_rate_limit_firecrawl_agent")]
-    pub rate_limit_firecrawl_agent: bool, // Limit firecrawl_agent to 5 calls/day (free tier)
-}
-
-fn default_rate_limit_firecrawl_agent() -> bool {
-    true
-}
-
-fn default_notification_sound() -> bool {
-    true
-}
-
-fn default_dedup_days() -> i32 {
-    14
-}
-
-fn default_dedup_threshold() -> f64 {
-    0.75
-}
-
-fn default_research_mode() -> String {
-    "standard".to_string()
-}
-
-fn get_config_dir() -> PathBuf {
-    let home = dirs::home_dir().expect("Could not find home directory");
-    home.join(".claudius")
-}
-
-fn ensure_config_dir() -> Result<PathBuf, String> {
-    let config_dir = get_config_dir();
-    std::fs::create_dir_all(&config_dir)
-        .map_err(|e| format!("Failed to create config directory: {}", e))?;
-    Ok(config_dir)
-}
-
-fn get_mcp_servers_path() -> PathBuf {
-    get_config_dir().join("mcp-servers.json")
-}
-
-fn get_preferences_path() -> PathBuf {
-    get_config_dir().join("preferences.json")
-}
-
-fn get_logs_dir() -> PathBuf {
-    get_config_dir().join("logs")
-}
-
 /// Write an error to the agent log file
 fn log_agent_error(context: &str, error: &str) {
     let logs_dir = get_logs_dir();
@@ -127,125 +35,14 @@ fn log_agent_error(context: &str, error: &str) {
     }
 }
 
-fn read_mcp_servers() -> Result<MCPServersConfig, String> {
-    let path = get_mcp_servers_path();
-    tracing::debug!("Reading MCP servers from: {:?}", path);
-    if !path.exists() {
-        tracing::debug!("MCP servers file doesn't exist, returning empty config");
-        return Ok(MCPServersConfig { servers: vec![] });
-    }
-    let content =
-        std::fs::read_to_string(&path).map_err(|e| format!("Failed to read MCP servers: {}", e))?;
-    tracing::debug!("MCP servers file content: {}", content);
-    serde_json::from_str(&content).map_err(|e| format!("Failed to parse MCP servers: {}", e))
-}
-
-fn write_mcp_servers(config: &MCPServersConfig) -> Result<(), String> {
-    ensure_config_dir()?;
-    let path = get_mcp_servers_path();
-    let content = serde_json::to_string_pretty(&config)
-        .map_err(|e| format!("Failed to serialize MCP servers: {}", e))?;
-    std::fs::write(&path, content).map_err(|e| format!("Failed to write MCP servers: {}", e))
-}
-
-fn read_settings() -> Result<ResearchSettings, String> {
-    let path = get_preferences_path();
-    if !path.exists() {
-        return Ok(ResearchSettings {
-            schedule_cron: "0 6 * * *".to_string(),
-            model: "claude-haiku-4-5-20251001".to_string(),
-            research_depth: "medium".to_string(),
-            max_sources_per_topic: 10,
-            enable_notifications: true,
-            notification_sound: true,
-            enable_web_search: false,
-            retention_days: None, // Never delete by default
-            condense_briefings: false,
-            dedup_days: default_dedup_days(),
-            dedup_threshold: default_dedup_threshold(),
-            enable_image_generation: true,
-            research_mode: default_research_mode(),
-            rate_limit_firecrawl_agent: default_rate_limit_firecrawl_agent(),
-        });
-    }
-    let content =
-        std::fs::read_to_string(&path).map_err(|e| format!("Failed to read settings: {}", e))?;
-    serde_json::from_str(&content).map_err(|e| format!("Failed to parse settings: {}", e))
-}
-
-fn write_settings(settings: &ResearchSettings) -> Result<(), String> {
-    ensure_config_dir()?;
-    let path = get_preferences_path();
-    let content = serde_json::to_string_pretty(&settings)
-        .map_err(|e| format!("Failed to serialize settings: {}", e))?;
-    std::fs::write(&path, content).map_err(|e| format!("Failed to write settings: {}", e))
-}
-
-// Legacy config helpers for backwards compatibility
-fn get_config_path() -> PathBuf {
-    get_config_dir().join("config.json")
-}
-
-fn read_config() -> Result<serde_json::Value, String> {
-    let config_path = get_config_path();
-
-    if !config_path.exists() {
-        return Ok(serde_json::json!({
-            "interests": [],
-            "preferences": {
-                "schedule": "0 6 * * *",
-                "briefingLength": "medium",
-                "notificationsEnabled": true
-            }
-        }));
-    }
-
-    let content = std::fs::read_to_string(&config_path)
-        .map_err(|e| format!("Failed to read config: {}", e))?;
-
-    serde_json::from_str(&content).map_err(|e| format!("Failed to parse config: {}", e))
-}
-
-fn write_config(config: &serde_json::Value) -> Result<(), String> {
-    ensure_config_dir()?;
-    let config_path = get_config_path();
-    let content = serde_json::to_string_pretty(&config)
-        .map_err(|e| format!("Failed to serialize config: {}", e))?;
-    std::fs::write(&config_path, content).map_err(|e| format!("Failed to write config: {}", e))
-}
-
 #[tauri::command]
-pub fn get_briefings(limit: Option<i32>) -> Result<Vec<Briefing>, String> {
+pub fn get_briefings(
+    limit: Option<i32>,
+    filter: Option<db::BriefingFilter>,
+) -> Result<Vec<Briefing>, String> {
     let conn = db::get_connection().map_err(|e| format!("Database connection failed: {}", e))?;
 
-    let limit_value = limit.unwrap_or(30);
-
-    let mut stmt = conn
-        .prepare(
-            "SELECT id, date, title, cards, research_time_ms, model_used, total_tokens
-         FROM briefings
-         ORDER BY date DESC
-         LIMIT ?1",
-        )
-        .map_err(|e| format!("Failed to prepare statement: {}", e))?;
-
-    let briefings = stmt
-        .query_map([limit_value], |row| {
-            Ok(Briefing {
-                id: row.get(0)?,
-                date: row.get(1)?,
-                title: row.get(2)?,
-                cards: row.get(3)?,
-                research_time_ms: row.get(4)?,
-                model_used: row.get(5)?,
-                total_tokens: row.get(6)?,
-            })
-        })
-        .map_err(|e| format!("Query failed: {}", e))?
-        .collect::<Result<Vec<_>, _>>()
-        .map_err(|e| format!("Failed to collect results: {}", e))?;
-
-    Ok(briefings)
+    db::get_briefings_filtered(&conn, &filter.unwrap_or_default(), limit.unwrap_or(30))
 }
 
 #[tauri::command]
@@ -254,7 +51,8 @@ pub fn get_briefing(id: i64) -> Result<Briefing, String> {
 
     let mut stmt = conn
         .prepare(
-            "SELECT id, date, title, cards, research_time_ms, model_used, total_tokens
+            "SELECT id, date, title, cards, research_time_ms, model_used, total_tokens, tag,
+         cover_image_path
          FROM briefings
          WHERE id = ?1",
         )
@@ -270,6 +68,8 @@ pub fn get_briefing(id: i64) -> Result<Briefing, String> {
                 research_time_ms: row.get(4)?,
                 model_used: row.get(5)?,
                 total_tokens: row.get(6)?,
+                tag: row.get(7)?,
+                cover_image_path: row.get(8)?,
             })
         })
         .map_err(|e| format!("Failed to get briefing: {}", e))?;
@@ -285,7 +85,8 @@ pub fn search_briefings(query: String) -> Result<Vec<Briefing>, String> {
 
     let mut stmt = conn
         .prepare(
-            "SELECT id, date, title, cards, research_time_ms, model_used, total_tokens
+            "SELECT id, date, title, cards, research_time_ms, model_used, total_tokens, tag,
+         cover_image_path
          FROM briefings
          WHERE title LIKE ?1 OR cards LIKE ?1
          ORDER BY date DESC
@@ -303,6 +104,8 @@ pub fn search_briefings(query: String) -> Result<Vec<Briefing>, String> {
                 research_time_ms: row.get(4)?,
                 model_used: row.get(5)?,
                 total_tokens: row.get(6)?,
+                tag: row.get(7)?,
+                cover_image_path: row.get(8)?,
             })
         })
         .map_err(|e| format!("Query failed: {}", e))?
@@ -335,68 +138,214 @@ pub fn add_feedback(
     Ok(())
 }
 
+/// Set the display order of a briefing's cards (card indices, first card first).
+#[tauri::command]
+pub fn reorder_cards(briefing_id: i64, order: Vec<usize>) -> Result<(), String> {
+    let conn = db::get_connection().map_err(|e| format!("Database connection failed: {}", e))?;
+    db::reorder_cards(&conn, briefing_id, &order)
+}
+
+// Legacy interest commands. config.json is migrated into topics and settings
+// on startup, so these read and write those instead.
+
 #[tauri::command]
 pub fn get_interests() -> Result<serde_json::Value, String> {
-    let config = read_config()?;
-    Ok(config
-        .get("interests")
-        .cloned()
-        .unwrap_or(serde_json::json!([])))
+    let conn = db::get_connection().map_err(|e| format!("Database connection failed: {}", e))?;
+    let names: Vec<String> = db::get_all_topics(&conn)?
+        .into_iter()
+        .map(|t| t.name)
+        .collect();
+    Ok(serde_json::json!(names))
 }
 
 #[tauri::command]
 pub fn add_interest(topic: String) -> Result<(), String> {
-    let mut config = read_config()?;
-
-    let interests = config
-        .get_mut("interests")
-        .and_then(|v| v.as_array_mut())
-        .ok_or_else(|| "Invalid config format".to_string())?;
-
-    // Check if interest already exists
-    if !interests.iter().any(|i| i.as_str() == Some(&topic)) {
-        interests.push(serde_json::json!(topic));
-        write_config(&config)?;
-    }
-
+    let conn = db::get_connection().map_err(|e| format!("Database connection failed: {}", e))?;
+    db::import_legacy_interests(&conn, &[topic])?;
     Ok(())
 }
 
 #[tauri::command]
 pub fn remove_interest(topic: String) -> Result<(), String> {
-    let mut config = read_config()?;
-
-    let interests = config
-        .get_mut("interests")
-        .and_then(|v| v.as_array_mut())
-        .ok_or_else(|| "Invalid config format".to_string())?;
-
-    interests.retain(|i| i.as_str() != Some(&topic));
-    write_config(&config)?;
-
+    let conn = db::get_connection().map_err(|e| format!("Database connection failed: {}", e))?;
+    if let Some(existing) = db::get_all_topics(&conn)?
+        .into_iter()
+        .find(|t| t.name.eq_ignore_ascii_case(&topic))
+    {
+        db::delete_topic(&conn, &existing.id)?;
+    }
     Ok(())
 }
 
 #[tauri::command]
 pub fn get_preferences() -> Result<serde_json::Value, String> {
-    let config = read_config()?;
-    Ok(config
-        .get("preferences")
-        .cloned()
-        .unwrap_or(serde_json::json!({})))
+    let settings = read_settings()?;
+    Ok(serde_json::json!({
+        "schedule": settings.schedule_cron,
+        "briefingLength": settings.research_depth,
+        "notificationsEnabled": settings.enable_notifications,
+    }))
 }
 
 #[tauri::command]
-pub fn update_preferences(preferences: serde_json::Value) -> Result<(), String> {
-    let mut config = read_config()?;
-    config["preferences"] = preferences;
-    write_config(&config)?;
+pub fn update_preferences(
+    app: tauri::AppHandle,
+    preferences: serde_json::Value,
+) -> Result<(), String> {
+    let mut settings = read_settings()?;
+    if let Some(schedule) = preferences.get("schedule").and_then(|v| v.as_str()) {
+        settings.schedule_cron = schedule.to_string();
+    }
+    if let Some(length) = preferences.get("briefingLength").and_then(|v| v.as_str()) {
+        settings.research_depth = crate::config::depth_from_briefing_length(length)
+            .unwrap_or(length)
+            .to_string();
+    }
+    if let Some(enabled) = preferences
+        .get("notificationsEnabled")
+        .and_then(|v| v.as_bool())
+    {
+        settings.enable_notifications = enabled;
+    }
+    update_settings(app, settings)?;
     Ok(())
 }
 
 #[tauri::command]
 pub async fn trigger_research(app: tauri::AppHandle) -> Result<String, String> {
-    use crate::notifications::{notify_research_complete, notify_research_error};
+    run_research_pipeline(app, None, None).await
+}
+
+/// Research a one-off question without creating a topic. The result is saved
+/// as a briefing tagged "ad-hoc".
+#[tauri::command]
+pub async fn research_adhoc(app: tauri::AppHandle, query: String) -> Result<String, String> {
+    let query = crate::research::validate_adhoc_query(&query)?;
+    run_research_pipeline(app, Some(query), None).await
+}
+
+/// Outcome of a quick research run.
+#[derive(Debug, Clone, Serialize)]
+pub struct QuickResearchResult {
+    /// The question researched (the copied text, shortened if needed)
+    pub query: String,
+    /// "research" or "ask" (quick_research_mode)
+    pub mode: String,
+    /// Summary of the ad-hoc research run, in "research" mode
+    pub message: Option<String>,
+    /// The answer from the briefing archive, in "ask" mode
+    pub answer: Option<claudius::archive::ArchiveAnswer>,
+}
+
+/// Research copied `text` as an ad-hoc question, or answer it from the
+/// briefing archive (quick_research_mode). Progress and the result are sent
+/// to the popover as quick_research:* events.
+#[tauri::command]
+pub async fn quick_research(
+    app: tauri::AppHandle,
+    text: String,
+) -> Result<QuickResearchResult, String> {
+    let query = claudius::research::adhoc_query_from_text(&text).inspect_err(|e| {
+        let _ = app.emit(
+            "quick_research:failed",
+            serde_json::json!({ "query": "", "error": e }),
+        );
+    })?;
+    let mode = read_settings()?.quick_research_mode;
+    let _ = app.emit(
+        "quick_research:started",
+        serde_json::json!({ "query": query, "mode": mode }),
+    );
+
+    let result = if mode == "ask" {
+        send_archive_chat_message(query.clone(), None)
+            .await
+            .map(|answer| QuickResearchResult {
+                query: query.clone(),
+                mode: mode.clone(),
+                message: None,
+                answer: Some(answer),
+            })
+    } else {
+        run_research_pipeline(app.clone(), Some(query.clone()), None)
+            .await
+            .map(|message| QuickResearchResult {
+                query: query.clone(),
+                mode: mode.clone(),
+                message: Some(message),
+                answer: None,
+            })
+    };
+    match &result {
+        Ok(done) => {
+            let _ = app.emit("quick_research:completed", done);
+        }
+        Err(e) => {
+            let _ = app.emit(
+                "quick_research:failed",
+                serde_json::json!({ "query": query, "error": e }),
+            );
+        }
+    }
+    result
+}
+
+/// The quick research shortcut: show the popover and research the
+/// clipboard's text there.
+pub fn quick_research_from_clipboard(app: &tauri::AppHandle) {
+    use tauri_plugin_clipboard_manager::ClipboardExt;
+
+    crate::tray::show_popover(app);
+    let text = match app.clipboard().read_text() {
+        Ok(text) => text,
+        Err(e) => {
+            tracing::warn!("Quick research: failed to read the clipboard: {}", e);
+            let error = "The clipboard has no text to research";
+            let _ = app.emit(
+                "quick_research:failed",
+                serde_json::json!({ "query": "", "error": error }),
+            );
+            return;
+        }
+    };
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = quick_research(app, text).await {
+            tracing::warn!("Quick research failed: {}", e);
+        }
+    });
+}
+
+/// Research the topics that failed in a briefing's run again. The result is
+/// saved as a new briefing.
+#[tauri::command]
+pub async fn retry_failed_topics(
+    app: tauri::AppHandle,
+    briefing_id: i64,
+) -> Result<String, String> {
+    let failed = {
+        let conn =
+            db::get_connection().map_err(|e| format!("Database connection failed: {}", e))?;
+        db::get_briefing_failed_topics(&conn, briefing_id)?
+    };
+    if failed.is_empty() {
+        return Err(format!("Briefing {} has no failed topics", briefing_id));
+    }
+    let topics = failed.into_iter().map(|f| f.topic).collect();
+    run_research_pipeline(app, None, Some(topics)).await
+}
+
+/// Research the due topics, or `query` alone, or just the `retry` topics
+/// when given, then save, export and notify.
+async fn run_research_pipeline(
+    app: tauri::AppHandle,
+    query: Option<String>,
+    retry: Option<Vec<String>>,
+) -> Result<String, String> {
+    use crate::notifications::{
+        notify_budget_warning, notify_research_complete, notify_research_error,
+        notify_topics_failed,
+    };
     use crate::research::ResearchAgent;
 
     tracing::info!("Starting research via Rust agent");
@@ -422,32 +371,16 @@ pub async fn trigger_research(app: tauri::AppHandle) -> Result<String, String> {
     let _guard = StateGuard;
 
     // Get settings
-    let settings = read_settings().unwrap_or_else(|_| ResearchSettings {
-        schedule_cron: "0 6 * * *".to_string(),
-        model: "claude-haiku-4-5-20251001".to_string(),
-        research_depth: "medium".to_string(),
-        max_sources_per_topic: 10,
-        enable_notifications: true,
-        notification_sound: true,
-        enable_web_search: false,
-        retention_days: None,
-        condense_briefings: false,
-        dedup_days: default_dedup_days(),
-        dedup_threshold: default_dedup_threshold(),
-        enable_image_generation: true,
-        research_mode: default_research_mode(),
-        rate_limit_firecrawl_agent: default_rate_limit_firecrawl_agent(),
-    });
+    let settings = read_settings().unwrap_or_default();
 
-    // Get API key from file-based storage
+    // Get the active profile's API key from the credential store
+    let profile = claudius::config::active_profile();
     let api_key = match get_api_key_for_research() {
         Some(key) => key,
         None => {
             let err = "No API key configured. Please set your Anthropic API key in Settings.";
             log_agent_error("RESEARCH", err);
-            if settings.enable_notifications {
-                let _ = notify_research_error(&app, err);
-            }
+            let _ = notify_research_error(&app, &settings, err);
             return Err(err.to_string());
         }
     };
@@ -457,34 +390,57 @@ pub async fn trigger_research(app: tauri::AppHandle) -> Result<String, String> {
         Ok(c) => c,
         Err(e) => {
             let err = format!("Database connection failed: {}", e);
-            if settings.enable_notifications {
-                let _ = notify_research_error(&app, &err);
-            }
+            let _ = notify_research_error(&app, &settings, &err);
             return Err(err);
         }
     };
 
-    let all_topics = match db::get_all_topics(&conn) {
-        Ok(t) => t,
-        Err(e) => {
-            if settings.enable_notifications {
-                let _ = notify_research_error(&app, &e);
-            }
+    let all_topics = match (&query, db::get_all_topics(&conn)) {
+        (Some(_), _) => Vec::new(),
+        (None, Ok(t)) => t,
+        (None, Err(e)) => {
+            let _ = notify_research_error(&app, &settings, &e);
             return Err(e);
         }
     };
 
-    let topics: Vec<String> = all_topics
+    // Snoozed topics and topics set to skip this run sit it out
+    let now = Utc::now();
+    let topics: Vec<String> = match (&query, &retry) {
+        (Some(query), _) => vec![query.clone()],
+        (None, Some(retry)) => all_topics
+            .iter()
+            .filter(|t| retry.iter().any(|r| r.eq_ignore_ascii_case(&t.name)))
+            .map(|t| t.name.clone())
+            .collect(),
+        (None, None) => all_topics
+            .iter()
+            .filter(|t| t.is_due(now))
+            .map(|t| t.name.clone())
+            .collect(),
+    };
+
+    let preferred_sources: HashMap<String, Vec<String>> = all_topics
         .iter()
-        .filter(|t| t.enabled)
-        .map(|t| t.name.clone())
+        .filter(|t| topics.contains(&t.name) && !t.preferred_sources.is_empty())
+        .map(|t| (t.name.clone(), t.preferred_sources.clone()))
         .collect();
 
-    if topics.is_empty() {
-        let err = "No topics configured. Please add topics in Settings.";
-        if settings.enable_notifications {
-            let _ = notify_research_error(&app, err);
+    if query.is_none() && retry.is_none() {
+        if let Err(e) = db::clear_skip_next_run(&conn) {
+            tracing::warn!("Failed to clear skip_next_run: {}", e);
         }
+    }
+
+    if topics.is_empty() {
+        let err = if retry.is_some() {
+            "The failed topics no longer exist."
+        } else if all_topics.iter().any(|t| t.enabled) {
+            "All enabled topics are snoozed or skipping this run."
+        } else {
+            "No topics configured. Please add topics in Settings."
+        };
+        let _ = notify_research_error(&app, &settings, err);
         return Err(err.to_string());
     }
 
@@ -493,11 +449,22 @@ pub async fn trigger_research(app: tauri::AppHandle) -> Result<String, String> {
     // Update phase
     research_state::set_phase("researching");
 
-    // Load past card fingerprints for deduplication
-    let (past_cards_context, past_fingerprints) = if settings.dedup_days > 0 {
-        match db::get_recent_card_fingerprints(&conn, settings.dedup_days) {
+    // Load past card fingerprints for deduplication (an ad-hoc question wants
+    // its answer even if earlier briefings covered it)
+    let dedup_policy = crate::dedup::DedupPolicy::for_topics(
+        settings.dedup_days,
+        settings.dedup_threshold,
+        &all_topics,
+    );
+    let (past_cards_context, past_fingerprints) = if dedup_policy.max_days() > 0 && query.is_none()
+    {
+        match db::get_recent_card_fingerprints_for(&conn, &dedup_policy) {
             Ok(fingerprints) => {
-                let context = crate::dedup::format_past_cards_for_prompt(&fingerprints);
+                let context = crate::dedup::format_past_cards_for_prompt(
+                    &fingerprints,
+                    &topics,
+                    settings.dedup_context_tokens as usize,
+                );
                 tracing::info!(
                     "Loaded {} past card fingerprints for deduplication",
                     fingerprints.len()
@@ -525,10 +492,39 @@ pub async fn trigger_research(app: tauri::AppHandle) -> Result<String, String> {
         settings.rate_limit_firecrawl_agent,
     );
     agent.set_cancellation_token(cancellation_token);
-
+    agent.set_archive_sources(settings.archive_sources);
+    agent.set_preferred_sources(preferred_sources);
+    agent.set_mcp_routing(crate::mcp_routing::McpRouting::from_topics(&all_topics));
+    agent.set_topic_timeout(std::time::Duration::from_secs(
+        u64::from(settings.topic_timeout_minutes) * 60,
+    ));
+    agent.set_tool_result_limits(
+        settings.mcp_result_max_chars,
+        settings.mcp_tool_result_limits.clone(),
+    );
+    agent.set_briefing_format(
+        settings.briefing_format.clone(),
+        settings.topic_formats.clone(),
+    );
+    agent.set_recency_window(
+        settings.recency_window.clone(),
+        settings.topic_recency_windows.clone(),
+    );
+    agent.set_about_me(settings.about_me.clone());
+    agent.set_source_requirements(settings.source_recency_days, settings.repair_weak_cards);
+    agent.set_tool_filter(crate::tools::ToolFilter::new(&settings.disabled_tools));
+    agent.set_firecrawl_budget(crate::firecrawl_budget::FirecrawlBudget::load(
+        settings.firecrawl_run_budget,
+        settings.firecrawl_monthly_budget,
+    ));
+    agent.set_thinking_budget(settings.enable_thinking.then(|| {
+        crate::research::thinking_budget(&settings.research_depth, &settings.thinking_budgets)
+    }));
+
+    let log_since = crate::research_log::log_timestamp();
     let mut result = match agent
         .run_research(
-            topics,
+            topics.clone(),
             Some(app.clone()),
             settings.condense_briefings,
             past_cards_context,
@@ -540,20 +536,24 @@ pub async fn trigger_research(app: tauri::AppHandle) -> Result<String, String> {
             // Check if this was a cancellation
             if e.contains("cancelled") {
                 tracing::info!("Research was cancelled by user");
-            } else if settings.enable_notifications {
-                let _ = notify_research_error(&app, &e);
             }
+            let _ = notify_research_error(&app, &settings, &e);
             return Err(e);
         }
     };
 
+    // Warn about Firecrawl budgets crossed during the run
+    for warning in agent.budget_warnings() {
+        let _ = notify_budget_warning(&app, &settings, &warning.message());
+    }
+
     // Apply post-synthesis deduplication filter (safety net)
-    if !past_fingerprints.is_empty() && settings.dedup_threshold > 0.0 {
+    if !past_fingerprints.is_empty() {
         let original_count = result.cards.len();
-        result.cards = crate::dedup::filter_duplicates(
+        result.cards = crate::dedup::filter_duplicates_by_topic(
             result.cards,
             &past_fingerprints,
-            settings.dedup_threshold,
+            &dedup_policy,
         );
         let filtered_count = original_count - result.cards.len();
         if filtered_count > 0 {
@@ -561,11 +561,32 @@ pub async fn trigger_research(app: tauri::AppHandle) -> Result<String, String> {
         }
     }
 
+    // Drop cards below the minimum relevance setting
+    let min_relevance = if query.is_some() {
+        "low"
+    } else {
+        settings.min_relevance.as_str()
+    };
+    let (kept, discarded) = crate::research::filter_by_relevance(result.cards, min_relevance);
+    result.cards = kept;
+    if !discarded.is_empty() {
+        tracing::info!(
+            "Relevance filter dropped {} cards below {}",
+            discarded.len(),
+            min_relevance
+        );
+    }
+
+    // Most relevant first, adjusted by feedback on each topic
+    crate::research::rank_cards_by_feedback(&mut result.cards);
+    result.index_topic_cards();
+
     // Update phase to saving
     research_state::set_phase("saving");
 
     // Emit research:saving event
-    let _ = app.emit(
+    research_state::emit(
+        &app,
         "research:saving",
         serde_json::json!({
             "timestamp": chrono::Utc::now().to_rfc3339(),
@@ -576,24 +597,91 @@ pub async fn trigger_research(app: tauri::AppHandle) -> Result<String, String> {
     // Save to database first to get briefing_id for images
     let cards_json = serde_json::to_string(&result.cards)
         .map_err(|e| format!("Failed to serialize cards: {}", e))?;
+    let topics_json = serde_json::to_string(&result.topics)
+        .map_err(|e| format!("Failed to serialize topic results: {}", e))?;
+    let failed_topics_json = if result.failed_topics.is_empty() {
+        None
+    } else {
+        Some(
+            serde_json::to_string(&result.failed_topics)
+                .map_err(|e| format!("Failed to serialize failed topics: {}", e))?,
+        )
+    };
 
     let conn = db::get_connection().map_err(|e| format!("Database connection failed: {}", e))?;
 
-    conn.execute(
-        "INSERT INTO briefings (date, title, cards, research_time_ms, model_used, total_tokens)
-         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
-        rusqlite::params![
-            result.date,
-            result.title,
-            cards_json,
-            result.research_time_ms as i64,
-            result.model_used,
-            result.total_tokens as i64,
-        ],
-    )
-    .map_err(|e| format!("Failed to insert briefing: {}", e))?;
+    if let Some(query) = &query {
+        result.title = crate::research::adhoc_title(query);
+    }
+    let saved = db::persist_briefing(
+        &conn,
+        &db::BriefingRecord {
+            briefing: db::NewBriefing {
+                run_id: Some(&result.run_id),
+                date: &result.date,
+                title: &result.title,
+                cards: &cards_json,
+                research_time_ms: result.research_time_ms as i64,
+                model_used: &result.model_used,
+                total_tokens: result.total_tokens as i64,
+                tag: query.as_ref().map(|_| crate::research::ADHOC_TAG),
+                topics: Some(&topics_json),
+                failed_topics: failed_topics_json.as_deref(),
+            },
+            cards: &result.cards,
+            discarded: if settings.keep_discarded_cards {
+                &discarded
+            } else {
+                &[]
+            },
+            profile: &profile,
+            batch_tokens: result.batch_tokens as i64,
+            log_since: &log_since,
+            merge_same_day: settings
+                .merge_same_day_runs
+                .then_some(settings.dedup_threshold),
+        },
+    )?;
+    let (briefing_id, created) = (saved.id, saved.created);
+    // A run merged into the day's briefing continues with all of its cards
+    let merged = saved.merged_cards.is_some();
+    if let Some(cards) = saved.merged_cards {
+        result.cards = cards;
+    }
 
-    let briefing_id = conn.last_insert_rowid();
+    // Ad-hoc questions aren't topics, so they don't count toward topic
+    // activity or trigger topic discovery. Merged runs were already recorded
+    // with the day's first run.
+    if query.is_none() && created && !merged {
+        if let Err(e) = claudius::topic_activity::record_run(&conn, briefing_id, &topics) {
+            tracing::warn!("Failed to record topic activity: {}", e);
+        }
+    }
+
+    // Periodically look for new topics the user seems interested in
+    let last_discovery = db::last_topic_discovery(&conn).unwrap_or(None);
+    if query.is_none()
+        && claudius::topic_discovery::discovery_due(
+            last_discovery.as_deref(),
+            settings.topic_discovery_days,
+        )
+    {
+        if let Some(api_key) = claudius::read_api_key() {
+            let model = settings.model.clone();
+            let profile = profile.clone();
+            let app = app.clone();
+            tauri::async_runtime::spawn(async move {
+                match claudius::topic_discovery::discover_topics(&api_key, &model, &profile).await
+                {
+                    Ok(added) if !added.is_empty() => {
+                        let _ = app.emit("topics:suggestions_updated", added.len());
+                    }
+                    Ok(_) => {}
+                    Err(e) => tracing::warn!("Topic discovery failed: {}", e),
+                }
+            });
+        }
+    }
 
     // Generate images for cards that have image_prompt (if enabled and API key configured)
     if settings.enable_image_generation {
@@ -601,7 +689,8 @@ pub async fn trigger_research(app: tauri::AppHandle) -> Result<String, String> {
             use claudius::image_gen;
 
             research_state::set_phase("Generating header images...");
-            let _ = app.emit(
+            research_state::emit(
+                &app,
                 "research:generating_images",
                 serde_json::json!({
                     "timestamp": chrono::Utc::now().to_rfc3339(),
@@ -609,16 +698,29 @@ pub async fn trigger_research(app: tauri::AppHandle) -> Result<String, String> {
                 }),
             );
 
-            let mut images_generated = 0;
+            let mut generated = Vec::new();
             for (idx, card) in result.cards.iter_mut().enumerate() {
+                // Cards merged from an earlier run already have their image
+                if card.image_path.is_some() {
+                    continue;
+                }
                 if let Some(ref prompt) = card.image_prompt {
                     tracing::info!("Generating image for card {}: prompt='{}'", idx, prompt);
 
-                    match image_gen::generate_image(prompt, briefing_id, idx, &openai_key).await {
+                    match image_gen::generate_image(
+                        prompt,
+                        &settings.image_style,
+                        briefing_id,
+                        idx,
+                        &openai_key,
+                    )
+                    .await
+                    {
                         image_gen::ImageGenResult::Success(path) => {
                             card.image_path = Some(path.to_string_lossy().to_string());
-                            images_generated += 1;
+                            card.image_style = Some(settings.image_style.clone());
                             tracing::info!("Image generated for card {}: {:?}", idx, path);
+                            generated.push(path);
                         }
                         image_gen::ImageGenResult::Disabled => {
                             tracing::debug!("Image generation disabled");
@@ -637,27 +739,81 @@ pub async fn trigger_research(app: tauri::AppHandle) -> Result<String, String> {
             }
 
             // Update briefing with image paths if any were generated
-            if images_generated > 0 {
-                let updated_cards_json = serde_json::to_string(&result.cards)
-                    .map_err(|e| format!("Failed to serialize updated cards: {}", e))?;
-
-                conn.execute(
-                    "UPDATE briefings SET cards = ?1 WHERE id = ?2",
-                    rusqlite::params![updated_cards_json, briefing_id],
-                )
-                .map_err(|e| format!("Failed to update briefing with image paths: {}", e))?;
+            if !generated.is_empty() {
+                db::save_briefing_images(&conn, briefing_id, &result.cards, &generated)?;
 
                 tracing::info!(
                     "Updated briefing {} with {} image paths",
                     briefing_id,
-                    images_generated
+                    generated.len()
                 );
             }
+
+            // One hero image for the whole briefing, shown in briefing lists
+            if settings.enable_cover_image && !merged {
+                // The cards are this crate's, so use its copy of the prompt builder
+                if let Some(prompt) = crate::image_gen::cover_prompt(&result.title, &result.cards) {
+                    research_state::set_phase("Generating cover image...");
+                    match image_gen::generate_cover_image(
+                        &prompt,
+                        &settings.image_style,
+                        briefing_id,
+                        &openai_key,
+                    )
+                    .await
+                    {
+                        image_gen::ImageGenResult::Success(path) => {
+                            match db::set_briefing_cover(&conn, briefing_id, &prompt, &path) {
+                                Ok(()) => tracing::info!("Cover image generated: {:?}", path),
+                                Err(e) => tracing::warn!("Failed to save cover image: {}", e),
+                            }
+                        }
+                        image_gen::ImageGenResult::Failed(err) => {
+                            tracing::warn!("Failed to generate cover image: {}", err);
+                        }
+                        _ => {}
+                    }
+                }
+            }
         } else {
             tracing::debug!("Image generation enabled but no OpenAI API key configured");
         }
     }
 
+    // Export to configured integrations (e.g. Obsidian vault, Notion)
+    // Converted to the library's settings, like in update_settings
+    let schedule = serde_json::to_value(&settings)
+        .and_then(serde_json::from_value::<claudius::config::ResearchSettings>)
+        .map(|settings| claudius::smart_schedule::effective_schedule(&settings))
+        .unwrap_or_else(|_| settings.schedule_cron.clone());
+    let export_targets =
+        crate::integrations::configured_targets(crate::integrations::ExportConfig {
+            obsidian_vault_path: settings.obsidian_vault_path.as_deref(),
+            obsidian_folder: &settings.obsidian_folder,
+            enable_notion_sync: settings.enable_notion_sync,
+            notion_database_id: settings.notion_database_id.as_deref(),
+            notion_token: claudius::read_notion_token(),
+            enable_atom_feed: settings.enable_atom_feed,
+            atom_feed_path: settings.atom_feed_path.as_deref(),
+            enable_schedule_calendar: settings.enable_schedule_calendar,
+            schedule_calendar_path: settings.schedule_calendar_path.as_deref(),
+            schedule_cron: &schedule,
+        });
+    if !export_targets.is_empty() {
+        crate::integrations::export_to_all(
+            &export_targets,
+            &crate::integrations::ExportBriefing {
+                id: briefing_id,
+                date: &result.date,
+                title: &result.title,
+                model_used: Some(&result.model_used),
+                cards: &result.cards,
+                first_new_card: saved.first_new_card,
+            },
+        )
+        .await;
+    }
+
     tracing::info!(
         "Research completed: {} cards saved, {}ms",
         result.cards.len(),
@@ -670,7 +826,8 @@ pub async fn trigger_research(app: tauri::AppHandle) -> Result<String, String> {
     }
 
     // Emit research:completed event after successful save
-    let _ = app.emit(
+    research_state::emit(
+        &app,
         "research:completed",
         serde_json::json!({
             "timestamp": chrono::Utc::now().to_rfc3339(),
@@ -680,8 +837,19 @@ pub async fn trigger_research(app: tauri::AppHandle) -> Result<String, String> {
     );
 
     // Send success notification
-    if settings.enable_notifications {
-        let _ = notify_research_complete(&app, result.cards.len(), settings.notification_sound);
+    if let Some(delivery) = crate::notification_prefs::delivery(
+        &settings,
+        crate::notification_prefs::NotificationEvent::ResearchCompleted,
+    ) {
+        let _ = notify_research_complete(
+            &app,
+            Some(briefing_id),
+            result.cards.len(),
+            delivery.sound.as_deref(),
+        );
+    }
+    if !result.failed_topics.is_empty() {
+        let _ = notify_topics_failed(&app, &settings, &result.failed_topics);
     }
 
     Ok(format!(
@@ -691,6 +859,32 @@ pub async fn trigger_research(app: tauri::AppHandle) -> Result<String, String> {
     ))
 }
 
+/// Summarize the past week's or month's briefings ("weekly" or "monthly")
+/// into a meta-briefing and save it.
+#[tauri::command]
+pub async fn generate_meta_briefing(
+    app: tauri::AppHandle,
+    period: String,
+) -> Result<claudius::meta_briefing::MetaBriefing, String> {
+    let period = claudius::meta_briefing::MetaPeriod::parse(&period)?;
+    let api_key = get_api_key_for_research()
+        .ok_or("No API key configured. Please set your Anthropic API key in Settings.")?;
+    let settings = read_settings()?;
+
+    let meta =
+        claudius::meta_briefing::generate_meta_briefing(&api_key, &settings.model, period).await?;
+    if meta.period == claudius::meta_briefing::MetaPeriod::Weekly {
+        let _ = crate::notifications::notify_event(
+            &app,
+            &settings,
+            crate::notification_prefs::NotificationEvent::WeeklyDigestReady,
+            "Weekly Digest Ready",
+            &meta.title,
+        );
+    }
+    Ok(meta)
+}
+
 // ============================================================================
 // Topics commands (SQLite-backed)
 // ============================================================================
@@ -702,7 +896,11 @@ pub fn get_topics() -> Result<Vec<Topic>, String> {
 }
 
 #[tauri::command]
-pub fn add_topic(name: String, description: Option<String>) -> Result<Topic, String> {
+pub fn add_topic(
+    name: String,
+    description: Option<String>,
+    preferred_sources: Option<Vec<String>>,
+) -> Result<Topic, String> {
     let conn = db::get_connection().map_err(|e| format!("Database connection failed: {}", e))?;
 
     // Check if topic already exists
@@ -715,7 +913,13 @@ pub fn add_topic(name: String, description: Option<String>) -> Result<Topic, Str
         id: Uuid::new_v4().to_string(),
         name,
         description,
+        preferred_sources: preferred_sources.unwrap_or_default(),
         enabled: true,
+        snoozed_until: None,
+        skip_next_run: false,
+        dedup_days: None,
+        dedup_threshold: None,
+        mcp_servers: Vec::new(),
         created_at: now.clone(),
         updated_at: now,
     };
@@ -726,12 +930,96 @@ pub fn add_topic(name: String, description: Option<String>) -> Result<Topic, Str
     Ok(topic)
 }
 
+/// Get topic suggestions waiting for the user to accept or dismiss them
+#[tauri::command]
+pub fn get_topic_suggestions() -> Result<Vec<db::TopicSuggestion>, String> {
+    let conn = db::get_connection().map_err(|e| format!("Database connection failed: {}", e))?;
+    db::get_topic_suggestions(&conn, "pending")
+}
+
+/// How much each topic's findings changed over its last `runs` research runs
+#[tauri::command]
+pub fn get_topic_activity(
+    runs: Option<usize>,
+) -> Result<Vec<claudius::topic_activity::TopicActivity>, String> {
+    let conn = db::get_connection().map_err(|e| format!("Database connection failed: {}", e))?;
+    claudius::topic_activity::get_topic_activity(&conn, runs.unwrap_or(5).max(1))
+}
+
+/// Every card saved for a topic, oldest first. With `narrate`, Claude also
+/// writes a narrative timeline of the topic's developments.
+#[tauri::command]
+pub async fn get_topic_timeline(
+    topic: String,
+    narrate: Option<bool>,
+    days: Option<u32>,
+) -> Result<claudius::topic_timeline::TopicTimeline, String> {
+    let mut timeline = {
+        let conn =
+            db::get_connection().map_err(|e| format!("Database connection failed: {}", e))?;
+        claudius::topic_timeline::load_timeline(&conn, &topic, days)?
+    };
+    if narrate.unwrap_or(false) && !timeline.entries.is_empty() {
+        let api_key = get_api_key_for_research()
+            .ok_or("No API key configured. Please set your Anthropic API key in Settings.")?;
+        let settings = read_settings()?;
+        claudius::topic_timeline::narrate_timeline(&api_key, &settings.model, &mut timeline)
+            .await?;
+    }
+    Ok(timeline)
+}
+
+/// Analyze recent bookmarks, feedback and chats for new topic suggestions now
+#[tauri::command]
+pub async fn suggest_topics() -> Result<Vec<claudius::db::TopicSuggestion>, String> {
+    let api_key = claudius::read_api_key().ok_or("No API key configured")?;
+    let settings = read_settings()?;
+    let profile = claudius::config::active_profile();
+    claudius::topic_discovery::discover_topics(&api_key, &settings.model, &profile).await
+}
+
+/// Estimate the tools and cost of researching a topic before adding it
+#[tauri::command]
+pub async fn preview_topic(
+    name: String,
+    description: Option<String>,
+    preferred_sources: Option<Vec<String>>,
+) -> Result<claudius::topic_preview::TopicPreview, String> {
+    let api_key = get_api_key_for_research()
+        .ok_or("No API key configured. Please set your Anthropic API key in Settings.")?;
+    let settings = read_settings()?;
+    claudius::topic_preview::preview_topic(
+        &api_key,
+        &settings.model,
+        &name,
+        description.as_deref(),
+        &preferred_sources.unwrap_or_default(),
+    )
+    .await
+}
+
+/// Accept a suggestion, adding it as an enabled topic
+#[tauri::command]
+pub fn accept_topic_suggestion(id: i64) -> Result<Topic, String> {
+    let conn = db::get_connection().map_err(|e| format!("Database connection failed: {}", e))?;
+    db::accept_topic_suggestion(&conn, id)?.ok_or_else(|| format!("Suggestion {} not found", id))
+}
+
+/// Dismiss a suggestion so it isn't suggested again
+#[tauri::command]
+pub fn dismiss_topic_suggestion(id: i64) -> Result<bool, String> {
+    let conn = db::get_connection().map_err(|e| format!("Database connection failed: {}", e))?;
+    db::dismiss_topic_suggestion(&conn, id)
+}
+
 #[tauri::command]
 pub fn update_topic(
     id: String,
     name: Option<String>,
     description: Option<String>,
     enabled: Option<bool>,
+    preferred_sources: Option<Vec<String>>,
+    mcp_servers: Option<Vec<String>>,
 ) -> Result<Topic, String> {
     let conn = db::get_connection().map_err(|e| format!("Database connection failed: {}", e))?;
 
@@ -749,6 +1037,61 @@ pub fn update_topic(
     if let Some(new_enabled) = enabled {
         topic.enabled = new_enabled;
     }
+    if let Some(new_sources) = preferred_sources {
+        topic.preferred_sources = new_sources;
+    }
+    if let Some(new_servers) = mcp_servers {
+        topic.mcp_servers = new_servers;
+    }
+    topic.updated_at = Utc::now().to_rfc3339();
+
+    db::update_topic(&conn, &topic)?;
+
+    Ok(topic)
+}
+
+/// Snooze a topic for `days` days, or set it to skip the next run.
+/// `days` of 0 clears the snooze.
+#[tauri::command]
+pub fn snooze_topic(
+    id: String,
+    days: Option<u32>,
+    skip_next_run: Option<bool>,
+) -> Result<Topic, String> {
+    let conn = db::get_connection().map_err(|e| format!("Database connection failed: {}", e))?;
+    let mut topic = db::get_topic_by_id(&conn, &id)?
+        .ok_or_else(|| format!("Topic with id '{}' not found", id))?;
+
+    let now = Utc::now();
+    if let Some(days) = days {
+        topic.snooze(days, now);
+    }
+    if let Some(skip) = skip_next_run {
+        topic.skip_next_run = skip;
+    }
+    topic.updated_at = now.to_rfc3339();
+
+    db::update_topic(&conn, &topic)?;
+
+    Ok(topic)
+}
+
+/// Override the dedup window and threshold for one topic. `None` for either
+/// falls back to the global setting.
+#[tauri::command]
+pub fn set_topic_dedup(
+    id: String,
+    days: Option<i32>,
+    threshold: Option<f64>,
+) -> Result<Topic, String> {
+    claudius::settings_schema::validate_topic_dedup(days, threshold)?;
+
+    let conn = db::get_connection().map_err(|e| format!("Database connection failed: {}", e))?;
+    let mut topic = db::get_topic_by_id(&conn, &id)?
+        .ok_or_else(|| format!("Topic with id '{}' not found", id))?;
+
+    topic.dedup_days = days;
+    topic.dedup_threshold = threshold;
     topic.updated_at = Utc::now().to_rfc3339();
 
     db::update_topic(&conn, &topic)?;
@@ -778,6 +1121,45 @@ pub fn get_mcp_servers() -> Result<Vec<MCPServer>, String> {
     Ok(config.servers)
 }
 
+/// A built-in tool and whether the `disabled_tools` setting leaves it on.
+#[derive(Debug, Clone, Serialize)]
+pub struct BuiltinToolInfo {
+    pub name: String,
+    pub description: String,
+    pub enabled: bool,
+}
+
+#[tauri::command]
+pub fn get_builtin_tools() -> Vec<BuiltinToolInfo> {
+    let filter = crate::tools::ToolFilter::current();
+    crate::tools::BuiltinTools::all_definitions()
+        .into_iter()
+        .map(|tool| BuiltinToolInfo {
+            enabled: filter.allows(None, &tool.name),
+            name: tool.name,
+            description: tool.description,
+        })
+        .collect()
+}
+
+/// A research mode offered in settings.
+#[derive(Debug, Clone, Serialize)]
+pub struct ResearchModeInfo {
+    pub name: String,
+    pub description: String,
+}
+
+#[tauri::command]
+pub fn get_research_modes() -> Vec<ResearchModeInfo> {
+    crate::research_mode::MODES
+        .iter()
+        .map(|mode| ResearchModeInfo {
+            name: mode.name().to_string(),
+            description: mode.description().to_string(),
+        })
+        .collect()
+}
+
 #[tauri::command]
 pub fn toggle_mcp_server(id: String, enabled: bool) -> Result<MCPServer, String> {
     let mut config = read_mcp_servers()?;
@@ -827,6 +1209,51 @@ pub fn add_mcp_server(name: String, config_data: serde_json::Value) -> Result<MC
     Ok(server)
 }
 
+/// Get the MCP server catalog for the install picker
+#[tauri::command]
+pub async fn get_mcp_catalog(
+    refresh: Option<bool>,
+) -> Result<claudius::mcp_catalog::McpCatalog, String> {
+    Ok(claudius::mcp_catalog::load_catalog(refresh.unwrap_or(false)).await)
+}
+
+/// Values the user has to fill in to install a catalog preset
+#[tauri::command]
+pub async fn get_mcp_preset_fields(
+    id: String,
+) -> Result<Vec<claudius::mcp_catalog::RequiredField>, String> {
+    let catalog = claudius::mcp_catalog::load_catalog(false).await;
+    let entry = catalog
+        .find(&id)
+        .ok_or_else(|| format!("'{}' is not in the MCP catalog", id))?;
+    Ok(entry.required_fields())
+}
+
+/// Install a server from the MCP catalog with the given API key, after
+/// checking that it starts
+#[tauri::command]
+pub async fn install_mcp_preset(
+    id: String,
+    key: Option<String>,
+    env: Option<HashMap<String, String>>,
+) -> Result<MCPServer, String> {
+    let catalog = claudius::mcp_catalog::load_catalog(false).await;
+    let entry = catalog
+        .find(&id)
+        .ok_or_else(|| format!("'{}' is not in the MCP catalog", id))?;
+    let extra_env: Vec<(String, String)> = env.unwrap_or_default().into_iter().collect();
+    let missing = entry.missing_fields(key.as_deref(), &extra_env);
+    if !missing.is_empty() {
+        let labels: Vec<&str> = missing.iter().map(|f| f.label.as_str()).collect();
+        return Err(format!("{} needs {}", entry.name, labels.join(", ")));
+    }
+    let server = claudius::mcp_catalog::install(entry, key.as_deref(), &extra_env, true).await?;
+    // The library's MCPServer, as written to mcp-servers.json
+    let value = serde_json::to_value(&server)
+        .map_err(|e| format!("Failed to serialize MCP server: {}", e))?;
+    serde_json::from_value(value).map_err(|e| format!("Failed to convert MCP server: {}", e))
+}
+
 #[tauri::command]
 pub fn remove_mcp_server(id: String) -> Result<(), String> {
     let mut config = read_mcp_servers()?;
@@ -881,11 +1308,71 @@ pub fn get_settings() -> Result<ResearchSettings, String> {
 }
 
 #[tauri::command]
-pub fn update_settings(settings: ResearchSettings) -> Result<ResearchSettings, String> {
+pub fn update_settings(
+    app: tauri::AppHandle,
+    settings: ResearchSettings,
+) -> Result<ResearchSettings, String> {
+    let known = claudius::models::cached_models().unwrap_or_default();
+    let check = claudius::models::check_model(&settings.model, &known);
+    if let Some(msg) = check.message(&settings.model) {
+        // Deprecated models still work; unknown IDs are rejected by the schema below
+        tracing::warn!("{}", msg);
+    }
+    let value = serde_json::to_value(&settings)
+        .map_err(|e| format!("Failed to serialize settings: {}", e))?;
+    let schema_settings: claudius::config::ResearchSettings =
+        serde_json::from_value(value).map_err(|e| format!("Failed to convert settings: {}", e))?;
+    claudius::settings_schema::validate_settings(&schema_settings)?;
+    write_settings(&settings)?;
+    crate::log_sink::set_debug_logging(settings.debug_logging);
+    if let Err(e) = crate::global_shortcut::apply(
+        &app,
+        &settings.global_shortcut,
+        &settings.quick_research_shortcut,
+    ) {
+        tracing::warn!("{}", e);
+    }
+    Ok(settings)
+}
+
+/// Change the global shortcut that shows/hides the main window ("off" to
+/// turn it off). The new shortcut is registered before it is saved, so a
+/// shortcut taken by another app is rejected.
+#[tauri::command]
+pub fn update_global_shortcut(
+    app: tauri::AppHandle,
+    shortcut: String,
+) -> Result<ResearchSettings, String> {
+    let shortcut = shortcut.trim().to_string();
+    crate::global_shortcut::parse_shortcut(&shortcut)?;
+    let mut settings = read_settings()?;
+    let quick = &settings.quick_research_shortcut;
+    if let Err(e) = crate::global_shortcut::apply(&app, &shortcut, quick) {
+        // Put the previous shortcut back
+        let _ = crate::global_shortcut::apply(&app, &settings.global_shortcut, quick);
+        return Err(e);
+    }
+    settings.global_shortcut = shortcut;
     write_settings(&settings)?;
     Ok(settings)
 }
 
+/// List the Anthropic models available to the configured API key (cached for a day)
+#[tauri::command]
+pub async fn list_models(
+    refresh: Option<bool>,
+) -> Result<Vec<claudius::models::ModelInfo>, String> {
+    let api_key = claudius::read_api_key().ok_or("No API key configured")?;
+    claudius::models::list_models(&api_key, refresh.unwrap_or(false)).await
+}
+
+/// Check a model ID against the cached model list, returning a warning if any
+#[tauri::command]
+pub fn check_model(model: String) -> Result<Option<String>, String> {
+    let known = claudius::models::cached_models().unwrap_or_default();
+    Ok(claudius::models::check_model(&model, &known).message(&model))
+}
+
 // ============================================================================
 // Housekeeping / Cleanup commands
 // ============================================================================
@@ -896,12 +1383,23 @@ pub struct HousekeepingResult {
     pub remaining_count: usize,
 }
 
-/// Delete a specific briefing by ID
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeleteBriefingResult {
+    pub deleted: bool,
+    pub briefing: db::BriefingDeletion,
+}
+
+/// Delete a specific briefing by ID, with its images, chat history and
+/// bookmarks. Without `confirm` nothing is deleted: the result says what
+/// would be, for the confirmation dialog.
 #[tauri::command]
-pub fn delete_briefing(id: i64) -> Result<bool, String> {
+pub fn delete_briefing(id: i64, confirm: Option<bool>) -> Result<DeleteBriefingResult, String> {
     let conn =
         db::get_connection().map_err(|e| format!("Failed to get database connection: {}", e))?;
-    db::delete_briefing(&conn, id)
+    let briefing = db::briefing_deletion(&conn, id)?
+        .ok_or_else(|| format!("Briefing with id '{}' not found", id))?;
+    let deleted = confirm.unwrap_or(false) && db::delete_briefing(&conn, id)?;
+    Ok(DeleteBriefingResult { deleted, briefing })
 }
 
 /// Check if a briefing has any bookmarked cards
@@ -912,6 +1410,27 @@ pub fn briefing_has_bookmarks(briefing_id: i64) -> Result<bool, String> {
     db::briefing_has_bookmarks(&conn, briefing_id)
 }
 
+/// Render a briefing, or only its card `card_index`, as a PNG share image.
+/// Saves to `path` or under the config dir's `share/` folder and returns
+/// the file path, which the UI copies to the clipboard.
+#[tauri::command]
+pub async fn render_card_share_image(
+    briefing_id: i64,
+    card_index: Option<usize>,
+    path: Option<String>,
+) -> Result<String, String> {
+    tokio::task::spawn_blocking(move || {
+        claudius::share_image::render_share_image(
+            briefing_id,
+            card_index,
+            path.as_deref().map(std::path::Path::new),
+        )
+    })
+    .await
+    .map_err(|e| format!("Rendering failed: {}", e))?
+    .map(|p| p.to_string_lossy().to_string())
+}
+
 /// Run housekeeping cleanup based on retention_days setting
 #[tauri::command]
 pub fn run_housekeeping() -> Result<HousekeepingResult, String> {
@@ -919,6 +1438,11 @@ pub fn run_housekeeping() -> Result<HousekeepingResult, String> {
     let conn =
         db::get_connection().map_err(|e| format!("Failed to get database connection: {}", e))?;
 
+    crate::housekeeping::prune_research_logs(settings.log_retention_days);
+    crate::housekeeping::prune_content_cache(&conn);
+    crate::web_cache::prune(&conn);
+    crate::housekeeping::dedupe_briefings(&conn);
+
     let deleted_count = if let Some(days) = settings.retention_days {
         db::cleanup_old_briefings(&conn, days)?
     } else {
@@ -961,138 +1485,30 @@ pub fn get_card_count() -> Result<usize, String> {
 // Notification commands
 // ============================================================================
 
-/// Request notification permission from the OS
-#[tauri::command]
-pub async fn request_notification_permission(app: tauri::AppHandle) -> Result<bool, String> {
-    use crate::notifications::check_notification_permission;
-    tracing::info!("Requesting notification permission...");
-    let result = check_notification_permission(&app).await;
-    tracing::info!("Notification permission result: {}", result);
-    Ok(result)
-}
-
-// ============================================================================
-// API Key commands - Using file-based storage in ~/.claudius/.env
-// ============================================================================
-
-fn get_env_file_path() -> PathBuf {
-    get_config_dir().join(".env")
-}
-
-/// Read API key from ~/.claudius/.env file
-fn read_api_key_from_file() -> Option<String> {
-    let env_path = get_env_file_path();
-
-    if !env_path.exists() {
-        tracing::debug!("No .env file found at {:?}", env_path);
-        return None;
-    }
-
-    match std::fs::read_to_string(&env_path) {
-        Ok(content) => {
-            for line in content.lines() {
-                let line = line.trim();
-                if line.starts_with("ANTHROPIC_API_KEY=") {
-                    let key = line.trim_start_matches("ANTHROPIC_API_KEY=").trim();
-                    // Remove quotes if present
-                    let key = key.trim_matches('"').trim_matches('\'');
-                    if !key.is_empty() {
-                        tracing::info!("Successfully read API key from .env file");
-                        return Some(key.to_string());
-                    }
-                }
-            }
-            tracing::debug!("No ANTHROPIC_API_KEY found in .env file");
-            None
-        }
-        Err(e) => {
-            tracing::warn!("Failed to read .env file: {}", e);
-            None
-        }
-    }
-}
-
-/// Write API key to ~/.claudius/.env file
-fn write_api_key_to_file(api_key: &str) -> Result<(), String> {
-    ensure_config_dir()?;
-    let env_path = get_env_file_path();
-
-    tracing::info!("Saving API key to {:?}", env_path);
-
-    // Read existing content to preserve other variables
-    let mut lines: Vec<String> = Vec::new();
-    let mut key_updated = false;
-
-    if env_path.exists() {
-        if let Ok(content) = std::fs::read_to_string(&env_path) {
-            for line in content.lines() {
-                if line.trim().starts_with("ANTHROPIC_API_KEY=") {
-                    lines.push(format!("ANTHROPIC_API_KEY={}", api_key));
-                    key_updated = true;
-                } else {
-                    lines.push(line.to_string());
-                }
-            }
-        }
-    }
-
-    if !key_updated {
-        lines.push(format!("ANTHROPIC_API_KEY={}", api_key));
-    }
-
-    let content = lines.join("\n") + "\n";
-
-    std::fs::write(&env_path, content).map_err(|e| format!("Failed to write .env file: {}", e))?;
-
-    // Set restrictive permissions (owner read/write only)
-    #[cfg(unix)]
-    {
-        use std::os::unix::fs::PermissionsExt;
-        let permissions = std::fs::Permissions::from_mode(0o600);
-        let _ = std::fs::set_permissions(&env_path, permissions);
-    }
-
-    tracing::info!("Successfully saved API key to .env file");
-    Ok(())
-}
-
-/// Delete API key from ~/.claudius/.env file
-fn delete_api_key_from_file() -> Result<(), String> {
-    let env_path = get_env_file_path();
-
-    if !env_path.exists() {
-        return Ok(());
-    }
-
-    // Read and filter out the API key line
-    if let Ok(content) = std::fs::read_to_string(&env_path) {
-        let lines: Vec<&str> = content
-            .lines()
-            .filter(|line| !line.trim().starts_with("ANTHROPIC_API_KEY="))
-            .collect();
-
-        if lines.is_empty() {
-            // If no other content, delete the file
-            let _ = std::fs::remove_file(&env_path);
-        } else {
-            let content = lines.join("\n") + "\n";
-            std::fs::write(&env_path, content)
-                .map_err(|e| format!("Failed to update .env file: {}", e))?;
-        }
-    }
-
-    Ok(())
+/// Request notification permission from the OS
+#[tauri::command]
+pub async fn request_notification_permission(app: tauri::AppHandle) -> Result<bool, String> {
+    use crate::notifications::check_notification_permission;
+    tracing::info!("Requesting notification permission...");
+    let result = check_notification_permission(&app).await;
+    tracing::info!("Notification permission result: {}", result);
+    Ok(result)
 }
 
+// ============================================================================
+// API Key commands - Stored in the OS credential store (falls back to ~/.claudius/.env)
+// Uses functions from claudius::config (lib.rs)
+// ============================================================================
+
 /// Get the API key for use in research (returns full key, not masked)
 pub fn get_api_key_for_research() -> Option<String> {
-    read_api_key_from_file()
+    claudius::read_api_key()
 }
 
 #[tauri::command]
 pub fn get_api_key() -> Result<Option<String>, String> {
     // Return masked version with dots for security - typical password field style
-    if let Some(key) = read_api_key_from_file() {
+    if let Some(key) = claudius::read_api_key() {
         // Show dots representing the key length (capped at 20 for display)
         let dot_count = std::cmp::min(key.len(), 20);
         let masked = "•".repeat(dot_count);
@@ -1104,25 +1520,93 @@ pub fn get_api_key() -> Result<Option<String>, String> {
 
 #[tauri::command]
 pub fn set_api_key(api_key: String) -> Result<(), String> {
-    if api_key.is_empty() {
-        return Err("API key cannot be empty".to_string());
-    }
-
-    if !api_key.starts_with("sk-ant-") {
-        return Err("Invalid API key format. Anthropic API keys start with 'sk-ant-'".to_string());
-    }
-
-    write_api_key_to_file(&api_key)
+    claudius::validate_api_key(&api_key)?;
+    claudius::write_api_key(&api_key)
 }
 
 #[tauri::command]
 pub fn has_api_key() -> Result<bool, String> {
-    Ok(read_api_key_from_file().is_some())
+    Ok(claudius::has_api_key())
 }
 
 #[tauri::command]
 pub fn clear_api_key() -> Result<(), String> {
-    delete_api_key_from_file()
+    claudius::delete_api_key()
+}
+
+// ============================================================================
+// Credential profile commands - Named Anthropic API keys
+// ============================================================================
+
+/// A credential profile as shown in the profile selector.
+#[derive(Debug, Serialize)]
+pub struct ProfileInfo {
+    pub name: String,
+    pub active: bool,
+    pub has_key: bool,
+}
+
+#[tauri::command]
+pub fn list_profiles() -> Result<Vec<ProfileInfo>, String> {
+    let active = claudius::config::active_profile();
+    let secrets = claudius::secret_store::secrets();
+    Ok(claudius::config::list_profiles()
+        .into_iter()
+        .map(|name| ProfileInfo {
+            active: name == active,
+            has_key: secrets
+                .get(&claudius::config::profile_secret_name(&name))
+                .is_some(),
+            name,
+        })
+        .collect())
+}
+
+#[tauri::command]
+pub fn add_profile(name: String, api_key: String) -> Result<(), String> {
+    claudius::config::add_profile(&name, &api_key)
+}
+
+#[tauri::command]
+pub fn remove_profile(name: String) -> Result<(), String> {
+    claudius::config::remove_profile(&name)
+}
+
+#[tauri::command]
+pub fn set_active_profile(name: String) -> Result<(), String> {
+    claudius::config::set_active_profile(&name)
+}
+
+/// Token usage per profile for the last `days` days (default 30).
+#[tauri::command]
+pub fn get_profile_usage(days: Option<i64>) -> Result<Vec<db::ProfileUsage>, String> {
+    let conn = db::get_connection().map_err(|e| format!("Database connection failed: {}", e))?;
+    db::get_profile_usage(&conn, days.unwrap_or(30))
+}
+
+// ============================================================================
+// Workspace commands - Separate topics, briefings and settings
+// ============================================================================
+
+#[tauri::command]
+pub fn list_workspaces() -> Vec<claudius::config::Workspace> {
+    claudius::config::list_workspaces()
+}
+
+#[tauri::command]
+pub fn create_workspace(name: String) -> Result<(), String> {
+    claudius::config::create_workspace(&name).map(|_| ())
+}
+
+/// Switch the app and CLI to workspace `name` and restart the app on it.
+#[tauri::command]
+pub fn switch_workspace(app: tauri::AppHandle, name: String) -> Result<(), String> {
+    if research_state::is_running() {
+        return Err("Research is running; switch workspaces once it finishes".to_string());
+    }
+    claudius::config::save_workspace(&name)?;
+    tracing::info!("Switching to workspace {}, restarting", name);
+    app.restart();
 }
 
 // ============================================================================
@@ -1163,6 +1647,38 @@ pub fn clear_openai_api_key() -> Result<(), String> {
     claudius::delete_openai_api_key()
 }
 
+// ============================================================================
+// Notion token commands - For Notion export
+// ============================================================================
+
+#[tauri::command]
+pub fn get_notion_token() -> Result<Option<String>, String> {
+    // Return masked version with dots for security
+    if let Some(token) = claudius::read_notion_token() {
+        let dot_count = std::cmp::min(token.len(), 20);
+        let masked = "•".repeat(dot_count);
+        Ok(Some(masked))
+    } else {
+        Ok(None)
+    }
+}
+
+#[tauri::command]
+pub fn set_notion_token(token: String) -> Result<(), String> {
+    claudius::validate_notion_token(&token)?;
+    claudius::write_notion_token(&token)
+}
+
+#[tauri::command]
+pub fn has_notion_token() -> Result<bool, String> {
+    Ok(claudius::has_notion_token())
+}
+
+#[tauri::command]
+pub fn clear_notion_token() -> Result<(), String> {
+    claudius::delete_notion_token()
+}
+
 // ============================================================================
 // Additional briefing commands
 // ============================================================================
@@ -1177,7 +1693,8 @@ pub fn get_todays_briefings() -> Result<Vec<Briefing>, String> {
     // Return ALL briefings for today (not just the most recent)
     let mut stmt = conn
         .prepare(
-            "SELECT id, date, title, cards, research_time_ms, model_used, total_tokens
+            "SELECT id, date, title, cards, research_time_ms, model_used, total_tokens, tag,
+         cover_image_path
          FROM briefings
          WHERE date LIKE ?1
          ORDER BY id DESC",
@@ -1194,6 +1711,8 @@ pub fn get_todays_briefings() -> Result<Vec<Briefing>, String> {
                 research_time_ms: row.get(4)?,
                 model_used: row.get(5)?,
                 total_tokens: row.get(6)?,
+                tag: row.get(7)?,
+                cover_image_path: row.get(8)?,
             })
         })
         .map_err(|e| format!("Query failed: {}", e))?
@@ -1285,6 +1804,27 @@ pub async fn send_chat_message(
     Ok(response_message)
 }
 
+/// Ask a question about the whole briefing archive. The frontend keeps the
+/// conversation and passes the earlier turns as `history`.
+#[tauri::command]
+pub async fn send_archive_chat_message(
+    message: String,
+    history: Option<Vec<claudius::archive::ArchiveTurn>>,
+) -> Result<claudius::archive::ArchiveAnswer, String> {
+    let api_key = get_api_key_for_research()
+        .ok_or("No API key configured. Please set your Anthropic API key in Settings.")?;
+    let settings = read_settings()?;
+
+    claudius::archive::ask_archive(
+        &api_key,
+        &settings.model,
+        &message,
+        &history.unwrap_or_default(),
+        claudius::archive::DEFAULT_TOP_K,
+    )
+    .await
+}
+
 /// Get chat history for a specific card in a briefing.
 #[tauri::command]
 pub fn get_chat_history(briefing_id: i64, card_index: i32) -> Result<Vec<ChatMessage>, String> {
@@ -1297,10 +1837,16 @@ pub fn clear_chat_history(briefing_id: i64, card_index: i32) -> Result<usize, St
     chat::clear_chat_history(briefing_id, card_index)
 }
 
+/// Export the chat about a card as markdown or JSON.
+#[tauri::command]
+pub fn export_chat(briefing_id: i64, card_index: i32, format: String) -> Result<String, String> {
+    chat::export_chat(briefing_id, card_index, &format)
+}
+
 /// Get all cards (briefing_id, card_index) that have chat messages.
 #[tauri::command]
 pub fn get_cards_with_chats() -> Result<Vec<claudius::db::CardWithChat>, String> {
-    let db_path = claudius::config::get_config_dir().join("claudius.db");
+    let db_path = claudius::config::get_data_dir().join("claudius.db");
     let conn = rusqlite::Connection::open(&db_path)
         .map_err(|e| format!("Failed to open database: {}", e))?;
     claudius::db::get_cards_with_chats(&conn)
@@ -1315,7 +1861,7 @@ use claudius::db::Bookmark;
 /// Toggle bookmark status for a card. Returns true if bookmarked, false if unbookmarked.
 #[tauri::command]
 pub fn toggle_bookmark(briefing_id: i64, card_index: i32) -> Result<bool, String> {
-    let db_path = claudius::config::get_config_dir().join("claudius.db");
+    let db_path = claudius::config::get_data_dir().join("claudius.db");
     let conn = rusqlite::Connection::open(&db_path)
         .map_err(|e| format!("Failed to open database: {}", e))?;
     claudius::db::toggle_bookmark(&conn, briefing_id, card_index)
@@ -1324,7 +1870,7 @@ pub fn toggle_bookmark(briefing_id: i64, card_index: i32) -> Result<bool, String
 /// Check if a card is bookmarked.
 #[tauri::command]
 pub fn is_card_bookmarked(briefing_id: i64, card_index: i32) -> Result<bool, String> {
-    let db_path = claudius::config::get_config_dir().join("claudius.db");
+    let db_path = claudius::config::get_data_dir().join("claudius.db");
     let conn = rusqlite::Connection::open(&db_path)
         .map_err(|e| format!("Failed to open database: {}", e))?;
     claudius::db::is_bookmarked(&conn, briefing_id, card_index)
@@ -1333,12 +1879,118 @@ pub fn is_card_bookmarked(briefing_id: i64, card_index: i32) -> Result<bool, Str
 /// Get all bookmarks.
 #[tauri::command]
 pub fn get_bookmarks() -> Result<Vec<Bookmark>, String> {
-    let db_path = claudius::config::get_config_dir().join("claudius.db");
+    let db_path = claudius::config::get_data_dir().join("claudius.db");
     let conn = rusqlite::Connection::open(&db_path)
         .map_err(|e| format!("Failed to open database: {}", e))?;
     claudius::db::get_all_bookmarks(&conn)
 }
 
+// ============================================================================
+// Read-later queue commands
+// ============================================================================
+
+use claudius::db::ReadLaterItem;
+
+fn open_claudius_db() -> Result<rusqlite::Connection, String> {
+    let db_path = claudius::config::get_data_dir().join("claudius.db");
+    rusqlite::Connection::open(&db_path).map_err(|e| format!("Failed to open database: {}", e))
+}
+
+/// Save a card to the read-later queue, forwarding it to the configured
+/// read-later service (Pocket, Readwise or Instapaper) if one is set.
+#[tauri::command]
+pub async fn save_card_for_later(
+    briefing_id: i64,
+    card_index: i32,
+) -> Result<ReadLaterItem, String> {
+    let (mut item, card) = {
+        let conn = open_claudius_db()?;
+        let item = claudius::db::save_for_later(&conn, briefing_id, card_index)?;
+        let cards: Vec<claudius::BriefingCard> =
+            serde_json::from_str(&get_briefing(briefing_id)?.cards)
+                .map_err(|e| format!("Failed to parse cards: {}", e))?;
+        (item, cards.into_iter().nth(card_index as usize))
+    };
+
+    let settings = read_settings()?;
+    let service = settings
+        .read_later_service
+        .as_deref()
+        .and_then(claudius::read_later::ReadLaterService::from_setting);
+
+    if let (Some(service), Some(card)) = (service, card) {
+        if item.forwarded_to.as_deref() != Some(service.as_str()) {
+            match claudius::read_later::forward_card(service, &card).await {
+                Ok(()) => {
+                    let conn = open_claudius_db()?;
+                    claudius::db::set_read_later_forwarded(&conn, item.id, service.as_str())?;
+                    item.forwarded_to = Some(service.as_str().to_string());
+                }
+                // The card is still queued locally, so forwarding failures are not fatal
+                Err(e) => tracing::warn!("Failed to forward card to {}: {}", service.as_str(), e),
+            }
+        }
+    }
+
+    Ok(item)
+}
+
+/// Get the read-later queue in order.
+#[tauri::command]
+pub fn get_read_later_queue(include_completed: Option<bool>) -> Result<Vec<ReadLaterItem>, String> {
+    let conn = open_claudius_db()?;
+    claudius::db::get_read_later_queue(&conn, include_completed.unwrap_or(false))
+}
+
+/// Mark a read-later item as done.
+#[tauri::command]
+pub fn complete_read_later_item(id: i64) -> Result<bool, String> {
+    let conn = open_claudius_db()?;
+    claudius::db::complete_read_later(&conn, id)
+}
+
+/// Remove an item from the read-later queue.
+#[tauri::command]
+pub fn remove_read_later_item(id: i64) -> Result<bool, String> {
+    let conn = open_claudius_db()?;
+    claudius::db::remove_read_later(&conn, id)
+}
+
+/// Reorder the read-later queue.
+#[tauri::command]
+pub fn reorder_read_later(ids: Vec<i64>) -> Result<(), String> {
+    let conn = open_claudius_db()?;
+    claudius::db::reorder_read_later(&conn, &ids)
+}
+
+// ============================================================================
+// Discarded card commands
+// ============================================================================
+
+use claudius::db::DiscardedCard;
+
+/// Get cards that were dropped for falling below the min_relevance setting.
+#[tauri::command]
+pub fn get_discarded_cards(limit: Option<i64>) -> Result<Vec<DiscardedCard>, String> {
+    let conn = open_claudius_db()?;
+    claudius::db::get_discarded_cards(&conn, limit.unwrap_or(50))
+}
+
+/// Add a discarded card back to its briefing. Returns the briefing ID.
+#[tauri::command]
+pub fn restore_discarded_card(id: i64) -> Result<i64, String> {
+    let conn = open_claudius_db()?;
+    claudius::db::restore_discarded_card(&conn, id)?
+        .ok_or_else(|| format!("Discarded card {} not found", id))
+}
+
+/// Permanently delete a discarded card.
+#[tauri::command]
+pub fn delete_discarded_card(id: i64) -> Result<bool, String> {
+    let conn = open_claudius_db()?;
+    claudius::db::delete_discarded_card(&conn, id)
+}
+
 // ============================================================================
 // Window control commands (for popover)
 // ============================================================================
@@ -1362,7 +2014,7 @@ pub fn hide_popover(app: tauri::AppHandle) {
 // Research log commands
 // ============================================================================
 
-use crate::research_log::{ResearchLogRecord, ResearchLogger};
+use crate::research_log::{ResearchLogRecord, ResearchLogger, RunReport, ToolAnalytics};
 
 /// Get recent research logs, optionally filtered by briefing ID.
 #[tauri::command]
@@ -1381,6 +2033,47 @@ pub fn get_actionable_errors(limit: Option<i64>) -> Result<Vec<ResearchLogRecord
     ResearchLogger::get_actionable_errors(limit)
 }
 
+/// Get per-tool and per-server usage analytics for the last `days` days (default 30).
+#[tauri::command]
+pub fn get_tool_analytics(days: Option<i64>) -> Result<ToolAnalytics, String> {
+    ResearchLogger::get_tool_analytics(days.unwrap_or(30))
+}
+
+/// Dashboard statistics: research streaks, briefings per week, cards per
+/// topic, token spend and tool usage over the last `days` days (default 90).
+#[tauri::command]
+pub fn get_statistics(days: Option<i64>) -> Result<claudius::stats::Statistics, String> {
+    let conn = db::get_connection().map_err(|e| format!("Database connection failed: {}", e))?;
+    claudius::stats::statistics(
+        &conn,
+        days.unwrap_or(claudius::stats::DEFAULT_DAYS),
+        Local::now().date_naive(),
+    )
+}
+
+/// Get the report of a research run: topics, tool call timeline, errors, tokens and cost.
+#[tauri::command]
+pub fn get_research_report(briefing_id: i64) -> Result<RunReport, String> {
+    ResearchLogger::get_run_report(briefing_id)
+}
+
+/// Get what each topic of a research run took (tokens, tool calls, duration)
+/// and which of the briefing's cards it produced.
+#[tauri::command]
+pub fn get_briefing_topics(briefing_id: i64) -> Result<Vec<crate::research::TopicResult>, String> {
+    let conn = db::get_connection().map_err(|e| format!("Database connection failed: {}", e))?;
+    db::get_briefing_topics(&conn, briefing_id)
+}
+
+/// Get the topics that failed in a briefing's research run, and why.
+#[tauri::command]
+pub fn get_briefing_failed_topics(
+    briefing_id: i64,
+) -> Result<Vec<crate::research::FailedTopic>, String> {
+    let conn = db::get_connection().map_err(|e| format!("Database connection failed: {}", e))?;
+    db::get_briefing_failed_topics(&conn, briefing_id)
+}
+
 // ============================================================================
 // Research state control commands (cancellation, reset, status)
 // ============================================================================
@@ -1402,7 +2095,8 @@ pub fn cancel_research(app: tauri::AppHandle) -> Result<(), String> {
     research_state::cancel()?;
 
     // Emit the cancelled event
-    let _ = app.emit(
+    research_state::emit(
+        &app,
         "research:cancelled",
         CancelledEvent {
             timestamp: chrono::Utc::now().to_rfc3339(),
@@ -1427,7 +2121,8 @@ pub fn reset_research_state(app: tauri::AppHandle) -> Result<(), String> {
     research_state::reset();
 
     // Emit reset event so frontend can update
-    let _ = app.emit(
+    research_state::emit(
+        &app,
         "research:reset",
         serde_json::json!({
             "timestamp": chrono::Utc::now().to_rfc3339(),
@@ -1457,6 +2152,20 @@ pub fn get_research_status() -> Result<serde_json::Value, String> {
     }))
 }
 
+/// The current run's state plus every progress event emitted so far, so a
+/// window opened mid-run (like the menu bar popover) can catch up.
+#[tauri::command]
+pub fn get_research_progress_snapshot() -> research_state::ProgressSnapshot {
+    research_state::progress_snapshot()
+}
+
+/// Recent research events newer than `since` (a `seq` from an earlier call),
+/// so a window can rebuild progress it missed while it wasn't listening.
+#[tauri::command]
+pub fn get_event_backlog(since: Option<u64>) -> Vec<research_state::RecordedEvent> {
+    research_state::event_backlog(since)
+}
+
 // ============================================================================
 // CLI Installation commands
 // ============================================================================
@@ -1737,7 +2446,7 @@ pub async fn uninstall_cli() -> Result<CliInstallResult, String> {
 // Auto-Update commands
 // ============================================================================
 
-use tauri_plugin_updater::UpdaterExt;
+use crate::releases::{self, Release};
 
 /// Information about an available update
 #[derive(Debug, Serialize)]
@@ -1747,34 +2456,59 @@ pub struct UpdateInfo {
     pub date: Option<String>,
 }
 
-/// Check if an update is available (manual check from Settings)
-#[tauri::command]
-pub async fn check_for_update(app: tauri::AppHandle) -> Result<Option<UpdateInfo>, String> {
-    tracing::info!("Manually checking for updates...");
+/// Result of a manual update check
+#[derive(Debug, Serialize)]
+pub struct UpdateCheck {
+    pub current_version: String,
+    pub channel: String,
+    pub update: Option<UpdateInfo>,
+    /// Releases newer than the running version, newest first
+    pub changelog: Vec<Release>,
+}
 
-    let updater = app.updater().map_err(|e| {
-        tracing::warn!("Failed to get updater: {}", e);
-        e.to_string()
-    })?;
+/// Check the configured release channel for an update (manual check from
+/// Settings), with the release notes of every version since this one.
+#[tauri::command]
+pub async fn check_for_updates(app: tauri::AppHandle) -> Result<UpdateCheck, String> {
+    let channel = crate::updater::release_channel();
+    tracing::info!("Manually checking for updates ({} channel)...", channel);
 
-    match updater.check().await {
+    let updater = crate::updater::channel_updater(&app).await?;
+    let update = match updater.check().await {
         Ok(Some(update)) => {
             tracing::info!("Update available: v{}", update.version);
-            Ok(Some(UpdateInfo {
+            Some(UpdateInfo {
                 version: update.version,
                 notes: update.body,
                 date: update.date.map(|d| d.to_string()),
-            }))
+            })
         }
         Ok(None) => {
             tracing::info!("No updates available");
-            Ok(None)
+            None
         }
         Err(e) => {
             tracing::warn!("Update check failed: {}", e);
-            Err(e.to_string())
+            return Err(e.to_string());
         }
-    }
+    };
+
+    // The changelog is extra; an update is still reported without it
+    let current_version = env!("CARGO_PKG_VERSION").to_string();
+    let changelog = match releases::fetch_releases(&channel).await {
+        Ok(all) => releases::changelog_since(&all, &current_version),
+        Err(e) => {
+            tracing::warn!("{}", e);
+            Vec::new()
+        }
+    };
+
+    Ok(UpdateCheck {
+        current_version,
+        channel,
+        update,
+        changelog,
+    })
 }
 
 /// Install pending update and restart the app
@@ -1793,10 +2527,7 @@ pub async fn install_update_and_restart(app: tauri::AppHandle) -> Result<(), Str
     // Fallback: check for update and download if needed (e.g., if app was restarted between download and install)
     tracing::info!("No pre-installed update found, checking for updates...");
 
-    let updater = app.updater().map_err(|e| {
-        tracing::warn!("Failed to get updater: {}", e);
-        e.to_string()
-    })?;
+    let updater = crate::updater::channel_updater(&app).await?;
 
     match updater.check().await {
         Ok(Some(update)) => {
@@ -1827,6 +2558,104 @@ pub async fn install_update_and_restart(app: tauri::AppHandle) -> Result<(), Str
     }
 }
 
+// ============================================================================
+// Card image commands
+// ============================================================================
+
+/// Regenerate one card's header image, with an edited prompt and style or
+/// the card's current ones, and return the image path. The prompt, style and
+/// path are saved to the briefing's cards.
+/// The image style presets, for the settings picker.
+#[tauri::command]
+pub fn get_image_styles() -> Vec<crate::image_gen::ImageStyle> {
+    crate::image_gen::IMAGE_STYLES.to_vec()
+}
+
+#[tauri::command]
+pub async fn regenerate_card_image(
+    briefing_id: i64,
+    card_index: usize,
+    prompt: Option<String>,
+    style: Option<String>,
+) -> Result<String, String> {
+    let api_key = get_openai_api_key_for_image_gen()
+        .ok_or("No OpenAI API key configured. Add one in Settings to generate images.")?;
+    let path = crate::image_gen::regenerate_card_image(
+        briefing_id,
+        card_index,
+        prompt.as_deref(),
+        style.as_deref(),
+        &api_key,
+    )
+    .await?;
+    Ok(path.to_string_lossy().to_string())
+}
+
+// ============================================================================
+// Narration commands
+// ============================================================================
+
+/// Generate spoken audio for a briefing (or a single card) and return the file path.
+#[tauri::command]
+pub async fn generate_briefing_audio(
+    briefing_id: i64,
+    card_index: Option<usize>,
+) -> Result<String, String> {
+    use claudius::tts;
+
+    let briefing = get_briefing(briefing_id)?;
+    let cards: Vec<claudius::BriefingCard> = serde_json::from_str(&briefing.cards)
+        .map_err(|e| format!("Failed to parse cards: {}", e))?;
+    let settings = read_settings()?;
+
+    let path = tts::narrate_briefing(
+        briefing_id,
+        &briefing.title,
+        &cards,
+        card_index,
+        tts::TtsEngine::from_setting(&settings.tts_engine),
+        &settings.tts_voice,
+        claudius::read_openai_api_key().as_deref(),
+    )
+    .await?;
+
+    Ok(path.to_string_lossy().to_string())
+}
+
+// ============================================================================
+// Integration commands
+// ============================================================================
+
+/// Sync a briefing to the configured Notion database, one page per card.
+/// Returns the database URL.
+#[tauri::command]
+pub async fn sync_briefing_to_notion(briefing_id: i64) -> Result<String, String> {
+    use crate::integrations::{ExportBriefing, ExportTarget, NotionDatabase};
+
+    let settings = read_settings()?;
+    let database_id = settings
+        .notion_database_id
+        .filter(|id| !id.trim().is_empty())
+        .ok_or("No Notion database configured. Set one in Settings.")?;
+    let token =
+        claudius::read_notion_token().ok_or("No Notion token configured. Add one in Settings.")?;
+
+    let briefing = get_briefing(briefing_id)?;
+    let cards: Vec<crate::research::BriefingCard> = serde_json::from_str(&briefing.cards)
+        .map_err(|e| format!("Failed to parse cards: {}", e))?;
+
+    NotionDatabase::new(&token, &database_id)
+        .export(&ExportBriefing {
+            id: briefing.id,
+            date: &briefing.date,
+            title: &briefing.title,
+            model_used: briefing.model_used.as_deref(),
+            cards: &cards,
+            first_new_card: 0,
+        })
+        .await
+}
+
 // ============================================================================
 // Export commands
 // ============================================================================
@@ -1850,6 +2679,7 @@ pub async fn export_card(
     let (filter_name, extensions) = match file_type.as_str() {
         "markdown" => ("Markdown", vec!["md"]),
         "html" => ("HTML", vec!["html"]),
+        "json" => ("JSON", vec!["json"]),
         _ => ("Text", vec!["txt"]),
     };
 
@@ -1882,6 +2712,80 @@ pub async fn export_card(
     }
 }
 
+// ============================================================================
+// Card action commands
+// ============================================================================
+
+/// Run one of a card's structured actions (reminder, link, GitHub issue, calendar event)
+#[tauri::command]
+pub async fn run_card_action(
+    briefing_id: i64,
+    card_index: usize,
+    action_index: usize,
+) -> Result<crate::card_actions::ActionOutcome, String> {
+    let (card_title, action) = {
+        let conn = db::get_connection()
+            .map_err(|e| format!("Failed to get database connection: {}", e))?;
+        crate::card_actions::load_card_action(&conn, briefing_id, card_index, action_index)?
+    };
+    crate::card_actions::run_action(briefing_id, card_index, &card_title, &action, true).await
+}
+
+/// Get reminders that haven't fired yet
+#[tauri::command]
+pub fn get_reminders() -> Result<Vec<db::Reminder>, String> {
+    let conn =
+        db::get_connection().map_err(|e| format!("Failed to get database connection: {}", e))?;
+    db::get_pending_reminders(&conn)
+}
+
+/// Cancel a pending reminder
+#[tauri::command]
+pub fn delete_reminder(id: i64) -> Result<bool, String> {
+    let conn =
+        db::get_connection().map_err(|e| format!("Failed to get database connection: {}", e))?;
+    db::delete_reminder(&conn, id)
+}
+
+// ============================================================================
+// Deep link and read state commands
+// ============================================================================
+
+/// Mark a briefing as read (also done by the notification's "Mark read" button)
+#[tauri::command]
+pub fn mark_briefing_read(id: i64) -> Result<bool, String> {
+    let conn =
+        db::get_connection().map_err(|e| format!("Failed to get database connection: {}", e))?;
+    db::mark_briefing_read(&conn, id)
+}
+
+/// Collect a `claudius://` link that arrived before the frontend was listening
+#[tauri::command]
+pub fn take_pending_deep_link() -> Option<crate::deep_link::DeepLink> {
+    crate::deep_link::take_pending()
+}
+
+/// Get the saved window geometry and the view the user was last on.
+#[tauri::command]
+pub fn get_ui_state() -> crate::ui_state::UiState {
+    crate::ui_state::read_ui_state()
+}
+
+/// Remember the view (route) and the briefing/card the user is on, so the
+/// app reopens there.
+#[tauri::command]
+pub fn save_ui_state(
+    route: Option<String>,
+    briefing_id: Option<i64>,
+    card_index: Option<usize>,
+) -> Result<(), String> {
+    let mut state = crate::ui_state::read_ui_state();
+    state.route = route;
+    state.briefing_id = briefing_id;
+    state.card_index = card_index;
+    crate::ui_state::write_ui_state(&state)
+}
+
 // ============================================================================
 // Print commands
 // ============================================================================
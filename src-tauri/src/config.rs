@@ -6,8 +6,13 @@
 // Note: Many functions are used by CLI but not by Tauri app, so we allow dead_code.
 #![allow(dead_code)]
 
+use crate::notification_prefs::{EventPreference, NotificationEvent};
+use crate::secret_store::secrets;
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{OnceLock, RwLock};
+use tracing::warn;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MCPServer {
@@ -33,21 +38,253 @@ pub struct ResearchSettings {
     #[serde(default = "default_notification_sound")]
     pub notification_sound: bool,
     #[serde(default)]
+    pub notification_events: HashMap<NotificationEvent, EventPreference>, // Per-event notify/silent/off and sound; missing events use their defaults
+    #[serde(default)]
     pub enable_web_search: bool,
     #[serde(default)]
+    pub disabled_tools: Vec<String>, // Tools left out of research and chat: "fetch_webpage", "web_search" or "server/tool" for MCP
+    #[serde(default)]
     pub retention_days: Option<i32>, // None = never delete
     #[serde(default)]
     pub condense_briefings: bool, // Combine all topics into one comprehensive card
+    #[serde(default)]
+    pub merge_same_day_runs: bool, // Append later runs of the day to the day's briefing instead of saving a new one
     #[serde(default = "default_dedup_days")]
     pub dedup_days: i32, // Days to look back for duplicates
     #[serde(default = "default_dedup_threshold")]
     pub dedup_threshold: f64, // Similarity threshold (0.0-1.0)
+    #[serde(default = "default_dedup_context_tokens")]
+    pub dedup_context_tokens: u32, // Token budget for recent cards listed in the synthesis prompt (0 = none)
     #[serde(default)]
     pub enable_image_generation: bool, // Generate header images using DALL-E
+    #[serde(default)]
+    pub enable_cover_image: bool, // Also generate one hero image per briefing from its title and themes
+    #[serde(default = "default_image_style")]
+    pub image_style: String, // Name of an image_gen::IMAGE_STYLES preset combined with each card's image prompt
     #[serde(default = "default_research_mode")]
-    pub research_mode: String, // "standard" | "firecrawl" - determines which tools are used
+    pub research_mode: String, // Name of a research_mode::MODES entry - determines which tools are used
     #[serde(default = "default_rate_limit_firecrawl_agent")]
     pub rate_limit_firecrawl_agent: bool, // Limit firecrawl_agent to 5 calls/day (free tier)
+    #[serde(default)]
+    pub firecrawl_run_budget: u32, // Max Firecrawl tool calls per research run (0 = no limit)
+    #[serde(default)]
+    pub firecrawl_monthly_budget: u32, // Max Firecrawl tool calls per calendar month (0 = no limit)
+    #[serde(default)]
+    pub archive_sources: bool, // Save a text snapshot of each source in the sources table
+    #[serde(default = "default_source_recency_days")]
+    pub source_recency_days: u32, // A card's newest dated source must be this recent (0 = any age)
+    #[serde(default = "default_repair_weak_cards")]
+    pub repair_weak_cards: bool, // Send cards with no or stale sources back to Claude once before saving
+    #[serde(default = "default_respect_robots_txt")]
+    pub respect_robots_txt: bool, // Skip pages a site's robots.txt disallows for fetch_webpage
+    #[serde(default = "default_crawl_delay_ms")]
+    pub crawl_delay_ms: u64, // Minimum gap between fetch_webpage requests to one domain
+    #[serde(default)]
+    pub fetch_contact: Option<String>, // Email or URL added to the User-Agent so site owners can reach you
+    #[serde(default = "default_tts_engine")]
+    pub tts_engine: String, // Narration engine: "openai" or "local"
+    #[serde(default = "default_tts_voice")]
+    pub tts_voice: String, // OpenAI TTS voice for narration
+    #[serde(default)]
+    pub obsidian_vault_path: Option<String>, // Obsidian vault to export briefings into after each run
+    #[serde(default = "default_obsidian_folder")]
+    pub obsidian_folder: String, // Folder inside the vault for briefing notes
+    #[serde(default)]
+    pub enable_notion_sync: bool, // Sync each new briefing to Notion after research
+    #[serde(default)]
+    pub notion_database_id: Option<String>, // Notion database that receives one page per card
+    #[serde(default)]
+    pub enable_atom_feed: bool, // Rewrite an Atom feed of recent briefings after each run
+    #[serde(default)]
+    pub atom_feed_path: Option<String>, // Where the feed is written (default ~/.claudius/atom.xml)
+    #[serde(default)]
+    pub enable_schedule_calendar: bool, // Rewrite an .ics of upcoming runs and follow-up events
+    #[serde(default)]
+    pub schedule_calendar_path: Option<String>, // Where the calendar is written (default ~/.claudius/claudius.ics)
+    #[serde(default)]
+    pub read_later_service: Option<String>, // Forward saved cards to "pocket", "readwise" or "instapaper"
+    #[serde(default = "default_log_retention_days")]
+    pub log_retention_days: u32, // Days to keep research JSONL logs
+    #[serde(default)]
+    pub debug_logging: bool, // Capture debug-level detail in the research log
+    #[serde(default = "default_topic_timeout_minutes")]
+    pub topic_timeout_minutes: u32, // Wall-clock limit per topic before it is skipped
+    #[serde(default = "default_mcp_result_max_chars")]
+    pub mcp_result_max_chars: usize, // Cap on MCP tool result size passed to Claude
+    #[serde(default)]
+    pub mcp_tool_result_limits: HashMap<String, usize>, // Per-tool overrides of mcp_result_max_chars
+    #[serde(default = "default_min_relevance")]
+    pub min_relevance: String, // Lowest card relevance saved to a briefing (low, medium, high)
+    #[serde(default = "default_keep_discarded_cards")]
+    pub keep_discarded_cards: bool, // Keep cards below min_relevance in discarded_cards for review
+    #[serde(default = "default_active_profile")]
+    pub active_profile: String, // Credential profile whose Anthropic key is used
+    #[serde(default)]
+    pub api_profiles: Vec<String>, // Named credential profiles besides "default"
+    #[serde(default = "default_briefing_format")]
+    pub briefing_format: String, // standard, newsletter, digest or executive
+    #[serde(default)]
+    pub topic_formats: HashMap<String, String>, // Per-topic briefing format overrides (keyed by topic name)
+    #[serde(default = "default_recency_window")]
+    pub recency_window: String, // How far back research looks for news: 24h, 72h or 1w
+    #[serde(default)]
+    pub topic_recency_windows: HashMap<String, String>, // Per-topic research window overrides (keyed by topic name)
+    #[serde(default = "default_topic_discovery_days")]
+    pub topic_discovery_days: u32, // Days between automatic topic suggestion runs (0 = off)
+    #[serde(default)]
+    pub about_me: String, // Freeform description of the user (role, expertise, preferences) for prompts
+    #[serde(default = "default_schedule_cron")]
+    pub schedule_cron: String, // Research schedule for `claudius daemon` (5-field cron, local time)
+    #[serde(default = "default_schedule_mode")]
+    pub schedule_mode: String, // "cron" follows schedule_cron; "adaptive" finishes research before the user's usual start of day
+    #[serde(default = "default_catch_up_missed_runs")]
+    pub catch_up_missed_runs: bool, // `claudius daemon` makes up a run missed while the machine was asleep or off
+    #[serde(default = "default_meta_briefings")]
+    pub meta_briefings: String, // "off" | "weekly" | "monthly" | "both" - meta-briefings generated by `claudius daemon`
+    #[serde(default = "default_notification_snooze_hours")]
+    pub notification_snooze_hours: u32, // Delay before a snoozed notification is shown again
+    #[serde(default)]
+    pub quiet_hours_start: Option<String>, // "HH:MM" local; notifications are held until quiet_hours_end
+    #[serde(default)]
+    pub quiet_hours_end: Option<String>, // "HH:MM" local; may be earlier than the start (overnight)
+    #[serde(default)]
+    pub quiet_weekends: bool, // Hold notifications on Saturday and Sunday
+    #[serde(default)]
+    pub respect_system_dnd: bool, // Hold notifications while the OS is in Do Not Disturb / Focus
+    #[serde(default)]
+    pub enable_thinking: bool, // Let Claude reason (extended thinking) before each research step
+    #[serde(default)]
+    pub thinking_budgets: HashMap<String, u32>, // Per-depth thinking token budgets (keyed by research_depth)
+    #[serde(default)]
+    pub batch_scheduled_research: bool, // Run `claudius daemon` research through the Batch API (half price, slower)
+    #[serde(default = "default_batch_deadline_minutes")]
+    pub batch_deadline_minutes: u32, // Research topics interactively if the batch hasn't finished them by then
+    #[serde(default)]
+    pub proxy_url: Option<String>, // HTTP(S) or SOCKS5 proxy for all requests, e.g. "http://proxy.corp:8080"
+    #[serde(default)]
+    pub proxy_username: Option<String>, // Proxy user; the password is the PROXY_PASSWORD secret
+    #[serde(default)]
+    pub ca_cert_path: Option<String>, // PEM file with extra root certificates (TLS-intercepting proxies)
+    #[serde(default)]
+    pub api_base_url: Option<String>, // Anthropic API base URL override (gateway or compatible provider)
+    #[serde(default)]
+    pub api_headers: HashMap<String, String>, // Extra headers sent with every Anthropic API request
+    #[serde(default)]
+    pub api_user_id: Option<String>, // Opaque ID sent as metadata.user_id with every Messages request
+    #[serde(default = "default_global_shortcut")]
+    pub global_shortcut: String, // Shows/hides the main window, e.g. "CmdOrCtrl+Shift+B", or "off"
+    #[serde(default = "default_quick_research_shortcut")]
+    pub quick_research_shortcut: String, // Researches the clipboard from anywhere, or "off"
+    #[serde(default = "default_quick_research_mode")]
+    pub quick_research_mode: String, // "research" (ad-hoc briefing) or "ask" (answer from the briefing archive)
+    #[serde(default = "default_release_channel")]
+    pub release_channel: String, // Updates to follow: "stable" or "beta" (includes pre-releases)
+    #[serde(default)]
+    pub config_version: u32, // Layout version of the config files (0 = written before versioning)
+}
+
+fn default_global_shortcut() -> String {
+    crate::global_shortcut::DEFAULT_SHORTCUT.to_string()
+}
+
+fn default_quick_research_shortcut() -> String {
+    crate::global_shortcut::DEFAULT_QUICK_RESEARCH_SHORTCUT.to_string()
+}
+
+fn default_quick_research_mode() -> String {
+    "research".to_string()
+}
+
+fn default_release_channel() -> String {
+    crate::releases::DEFAULT_CHANNEL.to_string()
+}
+
+fn default_respect_robots_txt() -> bool {
+    true
+}
+
+fn default_crawl_delay_ms() -> u64 {
+    1000
+}
+
+fn default_source_recency_days() -> u32 {
+    30
+}
+
+fn default_repair_weak_cards() -> bool {
+    true
+}
+
+fn default_batch_deadline_minutes() -> u32 {
+    120
+}
+
+fn default_notification_snooze_hours() -> u32 {
+    2
+}
+
+fn default_schedule_cron() -> String {
+    "0 6 * * *".to_string()
+}
+
+fn default_schedule_mode() -> String {
+    "cron".to_string()
+}
+
+fn default_catch_up_missed_runs() -> bool {
+    true
+}
+
+fn default_meta_briefings() -> String {
+    "off".to_string()
+}
+
+fn default_topic_discovery_days() -> u32 {
+    7
+}
+
+fn default_briefing_format() -> String {
+    "standard".to_string()
+}
+
+fn default_recency_window() -> String {
+    "24h".to_string()
+}
+
+fn default_active_profile() -> String {
+    "default".to_string()
+}
+
+fn default_keep_discarded_cards() -> bool {
+    true
+}
+
+fn default_min_relevance() -> String {
+    "low".to_string()
+}
+
+fn default_mcp_result_max_chars() -> usize {
+    20_000
+}
+
+fn default_topic_timeout_minutes() -> u32 {
+    10
+}
+
+fn default_log_retention_days() -> u32 {
+    14
+}
+
+fn default_obsidian_folder() -> String {
+    "Claudius".to_string()
+}
+
+fn default_tts_engine() -> String {
+    "openai".to_string()
+}
+
+fn default_tts_voice() -> String {
+    "alloy".to_string()
 }
 
 fn default_rate_limit_firecrawl_agent() -> bool {
@@ -66,6 +303,14 @@ fn default_dedup_threshold() -> f64 {
     0.75
 }
 
+fn default_dedup_context_tokens() -> u32 {
+    crate::dedup::DEFAULT_CONTEXT_TOKENS
+}
+
+fn default_image_style() -> String {
+    crate::image_gen::DEFAULT_IMAGE_STYLE.to_string()
+}
+
 fn default_research_mode() -> String {
     "standard".to_string()
 }
@@ -78,14 +323,78 @@ impl Default for ResearchSettings {
             max_sources_per_topic: 10,
             enable_notifications: true,
             notification_sound: true,
+            notification_events: HashMap::new(),
             enable_web_search: false,
+            disabled_tools: Vec::new(),
             retention_days: None,
             condense_briefings: false,
+            merge_same_day_runs: false,
             dedup_days: default_dedup_days(),
             dedup_threshold: default_dedup_threshold(),
+            dedup_context_tokens: default_dedup_context_tokens(),
             enable_image_generation: true,
+            image_style: default_image_style(),
+            enable_cover_image: false,
             research_mode: default_research_mode(),
             rate_limit_firecrawl_agent: default_rate_limit_firecrawl_agent(),
+            firecrawl_run_budget: 0,
+            firecrawl_monthly_budget: 0,
+            archive_sources: false,
+            source_recency_days: default_source_recency_days(),
+            repair_weak_cards: default_repair_weak_cards(),
+            respect_robots_txt: default_respect_robots_txt(),
+            crawl_delay_ms: default_crawl_delay_ms(),
+            fetch_contact: None,
+            tts_engine: default_tts_engine(),
+            tts_voice: default_tts_voice(),
+            obsidian_vault_path: None,
+            obsidian_folder: default_obsidian_folder(),
+            enable_notion_sync: false,
+            notion_database_id: None,
+            enable_atom_feed: false,
+            atom_feed_path: None,
+            enable_schedule_calendar: false,
+            schedule_calendar_path: None,
+            read_later_service: None,
+            log_retention_days: default_log_retention_days(),
+            debug_logging: false,
+            topic_timeout_minutes: default_topic_timeout_minutes(),
+            mcp_result_max_chars: default_mcp_result_max_chars(),
+            mcp_tool_result_limits: HashMap::new(),
+            min_relevance: default_min_relevance(),
+            keep_discarded_cards: default_keep_discarded_cards(),
+            active_profile: default_active_profile(),
+            api_profiles: Vec::new(),
+            briefing_format: default_briefing_format(),
+            topic_formats: HashMap::new(),
+            recency_window: default_recency_window(),
+            topic_recency_windows: HashMap::new(),
+            topic_discovery_days: default_topic_discovery_days(),
+            about_me: String::new(),
+            schedule_cron: default_schedule_cron(),
+            schedule_mode: default_schedule_mode(),
+            catch_up_missed_runs: default_catch_up_missed_runs(),
+            meta_briefings: default_meta_briefings(),
+            notification_snooze_hours: default_notification_snooze_hours(),
+            quiet_hours_start: None,
+            quiet_hours_end: None,
+            quiet_weekends: false,
+            respect_system_dnd: false,
+            enable_thinking: false,
+            thinking_budgets: HashMap::new(),
+            batch_scheduled_research: false,
+            batch_deadline_minutes: default_batch_deadline_minutes(),
+            proxy_url: None,
+            proxy_username: None,
+            ca_cert_path: None,
+            api_base_url: None,
+            api_headers: HashMap::new(),
+            api_user_id: None,
+            global_shortcut: default_global_shortcut(),
+            quick_research_shortcut: default_quick_research_shortcut(),
+            quick_research_mode: default_quick_research_mode(),
+            release_channel: default_release_channel(),
+            config_version: CONFIG_VERSION,
         }
     }
 }
@@ -99,13 +408,448 @@ pub struct Briefing {
     pub research_time_ms: Option<i64>,
     pub model_used: Option<String>,
     pub total_tokens: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tag: Option<String>, // "ad-hoc" for one-off query briefings, None for topic research
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cover_image_path: Option<String>, // Hero image for the whole briefing (enable_cover_image)
 }
 
-pub fn get_config_dir() -> PathBuf {
+// ============================================================================
+// Directories
+// ============================================================================
+//
+// macOS and Windows keep everything in `~/.claudius`. Linux follows the XDG
+// base directory spec: settings and credentials in `$XDG_CONFIG_HOME/claudius`
+// (~/.config/claudius), the database, images and logs in
+// `$XDG_DATA_HOME/claudius` (~/.local/share/claudius), and disposable caches
+// in `$XDG_CACHE_HOME/claudius` (~/.cache/claudius). An existing
+// `~/.claudius` keeps being used until `migrate_legacy_dir` has moved it.
+//
+// `CLAUDIUS_HOME` (or the CLI's `--config-dir`) replaces all of these with
+// one directory laid out like `~/.claudius`, for tests, CI and separate
+// setups side by side.
+//
+// A named workspace keeps its own settings, database and logs in
+// `workspaces/<name>` of the data directory, also laid out like
+// `~/.claudius`. Caches and `.env` stay shared between workspaces.
+
+/// Environment variable naming a directory to use instead of the defaults.
+pub const HOME_ENV: &str = "CLAUDIUS_HOME";
+
+/// Directory selected for this process (the CLI `--config-dir` flag).
+static HOME_OVERRIDE: RwLock<Option<PathBuf>> = RwLock::new(None);
+
+/// Use `dir` instead of `CLAUDIUS_HOME` or the default directories for this
+/// process. Call it before anything reads settings or opens the database.
+pub fn set_home_override(dir: Option<PathBuf>) {
+    if let Ok(mut current) = HOME_OVERRIDE.write() {
+        *current = dir.map(|dir| absolute_path(&dir));
+    }
+}
+
+/// The directory replacing the defaults: the `--config-dir` override, else
+/// `CLAUDIUS_HOME` if it is set and not empty.
+pub fn home_override() -> Option<PathBuf> {
+    if let Some(dir) = HOME_OVERRIDE.read().ok().and_then(|dir| dir.clone()) {
+        return Some(dir);
+    }
+    std::env::var_os(HOME_ENV)
+        .filter(|dir| !dir.is_empty())
+        .map(|dir| absolute_path(Path::new(&dir)))
+}
+
+/// `--config-dir <dir>` and `--workspace <name>` for a `claudius` child
+/// process, so it uses the same directory and workspace as this one. Empty
+/// without overrides.
+pub fn dir_args() -> Vec<std::ffi::OsString> {
+    let mut args = Vec::new();
+    if let Some(dir) = home_override() {
+        args.extend(["--config-dir".into(), dir.into_os_string()]);
+    }
+    if let Some(name) = WORKSPACE_OVERRIDE.read().ok().and_then(|n| n.clone()) {
+        args.extend(["--workspace".into(), name.into()]);
+    }
+    args
+}
+
+/// `path` relative to the current directory, so it means the same thing
+/// to processes started elsewhere (such as the daemon).
+fn absolute_path(path: &Path) -> PathBuf {
+    if path.is_absolute() {
+        return path.to_path_buf();
+    }
+    std::env::current_dir()
+        .map(|cwd| cwd.join(path))
+        .unwrap_or_else(|_| path.to_path_buf())
+}
+
+/// Entries of the legacy directory that belong in the data directory.
+const DATA_ENTRIES: &[&str] = &[
+    WORKSPACES_ENTRY,
+    "claudius.db",
+    "claudius.db-wal",
+    "claudius.db-shm",
+    "images",
+    "logs",
+    "share",
+    "calendar",
+    "atom.xml",
+    "claudius.ics",
+    "daemon.json",
+    "research.lock",
+];
+
+/// Entry of the legacy directory that becomes the cache directory.
+const CACHE_ENTRY: &str = "cache";
+
+/// The single directory used everywhere but Linux (and on Linux before
+/// migration).
+pub fn get_legacy_dir() -> PathBuf {
     let home = dirs::home_dir().expect("Could not find home directory");
     home.join(".claudius")
 }
 
+/// The one directory used when the XDG directories aren't: the override,
+/// else `~/.claudius`.
+fn get_single_dir() -> PathBuf {
+    home_override().unwrap_or_else(get_legacy_dir)
+}
+
+/// XDG config, data and cache directories, on Linux only.
+#[derive(Debug, Clone, PartialEq)]
+pub struct XdgDirs {
+    pub config: PathBuf,
+    pub data: PathBuf,
+    pub cache: PathBuf,
+}
+
+impl XdgDirs {
+    /// The XDG directories (from `$XDG_*_HOME` or their defaults) on Linux.
+    pub fn current() -> Option<Self> {
+        if !cfg!(target_os = "linux") {
+            return None;
+        }
+        Some(Self {
+            config: dirs::config_dir()?.join("claudius"),
+            data: dirs::data_dir()?.join("claudius"),
+            cache: dirs::cache_dir()?.join("claudius"),
+        })
+    }
+
+    /// Whether to use these rather than the legacy directory: they are in
+    /// use already, or there is nothing to migrate.
+    fn in_use(&self, legacy: &Path) -> bool {
+        self.config.exists() || !legacy.exists()
+    }
+}
+
+/// The XDG directories when they are in use (never with an override).
+fn xdg_dirs() -> Option<XdgDirs> {
+    if home_override().is_some() {
+        return None;
+    }
+    XdgDirs::current().filter(|dirs| dirs.in_use(&get_legacy_dir()))
+}
+
+/// The config directory of the default workspace.
+fn get_base_config_dir() -> PathBuf {
+    xdg_dirs()
+        .map(|dirs| dirs.config)
+        .unwrap_or_else(get_single_dir)
+}
+
+/// The data directory of the default workspace.
+fn get_base_data_dir() -> PathBuf {
+    xdg_dirs()
+        .map(|dirs| dirs.data)
+        .unwrap_or_else(get_single_dir)
+}
+
+/// Settings, MCP servers and UI state of the active workspace.
+pub fn get_config_dir() -> PathBuf {
+    get_workspace_dir().unwrap_or_else(get_base_config_dir)
+}
+
+/// The database, images, logs and generated files of the active workspace.
+pub fn get_data_dir() -> PathBuf {
+    get_workspace_dir().unwrap_or_else(get_base_data_dir)
+}
+
+/// Caches that can be deleted at any time.
+pub fn get_cache_dir() -> PathBuf {
+    xdg_dirs()
+        .map(|dirs| dirs.cache)
+        .unwrap_or_else(|| get_single_dir().join(CACHE_ENTRY))
+}
+
+// ============================================================================
+// Workspaces
+// ============================================================================
+
+/// Workspace using the directories above directly.
+pub const DEFAULT_WORKSPACE: &str = "default";
+
+/// Entry of the data directory holding the named workspaces.
+const WORKSPACES_ENTRY: &str = "workspaces";
+
+/// Workspace selected for this process (the CLI `--workspace` flag).
+static WORKSPACE_OVERRIDE: RwLock<Option<String>> = RwLock::new(None);
+
+/// The saved workspace, read once so that switching never moves a running
+/// process to another database.
+static SAVED_WORKSPACE: OnceLock<String> = OnceLock::new();
+
+/// A workspace as listed by `claudius workspace list` and the app.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Workspace {
+    pub name: String,
+    pub path: PathBuf,
+    pub active: bool,
+}
+
+/// Use workspace `name` instead of the saved one for this process. Call it
+/// before anything reads settings or opens the database.
+pub fn set_workspace_override(name: Option<String>) {
+    if let Ok(mut current) = WORKSPACE_OVERRIDE.write() {
+        *current = name;
+    }
+}
+
+/// Directory holding the named workspaces.
+pub fn get_workspaces_dir() -> PathBuf {
+    get_base_data_dir().join(WORKSPACES_ENTRY)
+}
+
+/// File naming the workspace the app and CLI use (absent for the default).
+fn get_saved_workspace_path() -> PathBuf {
+    get_base_config_dir().join("workspace")
+}
+
+/// The workspace saved by `claudius workspace use` or the app's switcher.
+/// One that has since been deleted counts as the default.
+fn read_saved_workspace() -> String {
+    std::fs::read_to_string(get_saved_workspace_path())
+        .ok()
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty() && get_workspaces_dir().join(name).is_dir())
+        .unwrap_or_else(|| DEFAULT_WORKSPACE.to_string())
+}
+
+/// The workspace in use: the override, else the saved one.
+pub fn active_workspace() -> String {
+    if let Some(name) = WORKSPACE_OVERRIDE.read().ok().and_then(|n| n.clone()) {
+        return name;
+    }
+    SAVED_WORKSPACE.get_or_init(read_saved_workspace).clone()
+}
+
+/// The directory of the active workspace, or None for the default one.
+fn get_workspace_dir() -> Option<PathBuf> {
+    let name = active_workspace();
+    (name != DEFAULT_WORKSPACE).then(|| get_workspaces_dir().join(name))
+}
+
+pub fn validate_workspace_name(name: &str) -> Result<(), String> {
+    if name.is_empty() || name.len() > 40 {
+        return Err("Workspace names must be 1 to 40 characters".to_string());
+    }
+    if !name
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err("Workspace names may only contain letters, digits, '-' and '_'".to_string());
+    }
+    Ok(())
+}
+
+/// The default workspace, then the named ones in alphabetical order.
+pub fn list_workspaces() -> Vec<Workspace> {
+    let active = active_workspace();
+    let mut names: Vec<String> = std::fs::read_dir(get_workspaces_dir())
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok())
+                .filter(|entry| entry.path().is_dir())
+                .filter_map(|entry| entry.file_name().into_string().ok())
+                .filter(|name| validate_workspace_name(name).is_ok() && name != DEFAULT_WORKSPACE)
+                .collect()
+        })
+        .unwrap_or_default();
+    names.sort();
+
+    std::iter::once(DEFAULT_WORKSPACE.to_string())
+        .chain(names)
+        .map(|name| Workspace {
+            path: if name == DEFAULT_WORKSPACE {
+                get_base_data_dir()
+            } else {
+                get_workspaces_dir().join(&name)
+            },
+            active: name == active,
+            name,
+        })
+        .collect()
+}
+
+/// Check that workspace `name` exists.
+pub fn check_workspace(name: &str) -> Result<(), String> {
+    if list_workspaces().iter().any(|w| w.name == name) {
+        Ok(())
+    } else {
+        Err(format!(
+            "Unknown workspace: {} (see: claudius workspace list)",
+            name
+        ))
+    }
+}
+
+/// Create an empty workspace with an initialized database. Returns its
+/// directory.
+pub fn create_workspace(name: &str) -> Result<PathBuf, String> {
+    validate_workspace_name(name)?;
+    if name == DEFAULT_WORKSPACE || check_workspace(name).is_ok() {
+        return Err(format!("Workspace {} already exists", name));
+    }
+    let dir = get_workspaces_dir().join(name);
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    crate::db::open_database(&dir.join(crate::db::DB_FILE))
+        .map_err(|e| format!("Failed to create the workspace database: {}", e))?;
+    Ok(dir)
+}
+
+/// Delete a workspace with its database, settings and logs. The default
+/// workspace and the one in use can't be deleted.
+pub fn delete_workspace(name: &str) -> Result<(), String> {
+    check_workspace(name)?;
+    if name == DEFAULT_WORKSPACE {
+        return Err("The default workspace can't be deleted".to_string());
+    }
+    if name == active_workspace() || name == read_saved_workspace() {
+        return Err(format!(
+            "Workspace {} is in use; switch to another one first",
+            name
+        ));
+    }
+    let dir = get_workspaces_dir().join(name);
+    std::fs::remove_dir_all(&dir).map_err(|e| format!("Failed to delete {}: {}", dir.display(), e))
+}
+
+/// Make `name` the workspace the app and CLI use from their next start.
+pub fn save_workspace(name: &str) -> Result<(), String> {
+    check_workspace(name)?;
+    let path = get_saved_workspace_path();
+    if name == DEFAULT_WORKSPACE {
+        return match std::fs::remove_file(&path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                Err(format!("Failed to save workspace: {}", e))
+            }
+            _ => Ok(()),
+        };
+    }
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)
+            .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    }
+    std::fs::write(&path, format!("{}\n", name))
+        .map_err(|e| format!("Failed to save workspace: {}", e))
+}
+
+/// Move a legacy `~/.claudius` into the XDG directories (Linux only).
+/// Returns the directories moved into, or None if there was nothing to do.
+///
+/// Nothing is overwritten: an entry that already exists in its new place
+/// stays behind. If a move fails, the entries moved so far are moved back,
+/// so the legacy directory stays complete and in use. The config entries
+/// go last, since the XDG config directory existing is what switches to
+/// the new layout. Safe to call on every start; call it first thing,
+/// before anything opens the database or the log files.
+pub fn migrate_legacy_dir() -> Result<Option<XdgDirs>, String> {
+    // An override is used as it is, and leaves ~/.claudius alone
+    if home_override().is_some() {
+        return Ok(None);
+    }
+    let Some(xdg) = XdgDirs::current() else {
+        return Ok(None);
+    };
+    let legacy = get_legacy_dir();
+    if !legacy.is_dir() {
+        return Ok(None);
+    }
+    migrate_legacy_dir_to(&legacy, &xdg)?;
+    Ok(Some(xdg))
+}
+
+fn migrate_legacy_dir_to(legacy: &Path, xdg: &XdgDirs) -> Result<(), String> {
+    let entries = std::fs::read_dir(legacy)
+        .map_err(|e| format!("Failed to read {}: {}", legacy.display(), e))?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.file_name())
+        .collect::<Vec<_>>();
+
+    let mut plan = Vec::new();
+    for name in &entries {
+        let name_str = name.to_string_lossy();
+        if name_str == CACHE_ENTRY {
+            plan.push((0, legacy.join(name), xdg.cache.clone()));
+        } else if DATA_ENTRIES.contains(&name_str.as_ref()) {
+            plan.push((0, legacy.join(name), xdg.data.join(name)));
+        } else {
+            plan.push((1, legacy.join(name), xdg.config.join(name)));
+        }
+    }
+    plan.sort_by_key(|(order, _, _)| *order);
+
+    let mut moved: Vec<(PathBuf, PathBuf)> = Vec::new();
+    for (_, from, to) in plan {
+        if to.exists() {
+            warn!(
+                "Not moving {}: {} already exists",
+                from.display(),
+                to.display()
+            );
+            continue;
+        }
+        let result = to
+            .parent()
+            .map_or(Ok(()), std::fs::create_dir_all)
+            .and_then(|_| std::fs::rename(&from, &to));
+        if let Err(e) = result {
+            for (from, to) in moved.iter().rev() {
+                let _ = std::fs::rename(to, from);
+            }
+            return Err(format!(
+                "Failed to move {} to {}: {}",
+                from.display(),
+                to.display(),
+                e
+            ));
+        }
+        moved.push((from, to));
+    }
+
+    // Create the config directory even if there were no config files, so
+    // the new layout is used from now on
+    std::fs::create_dir_all(&xdg.config)
+        .map_err(|e| format!("Failed to create {}: {}", xdg.config.display(), e))?;
+    if std::fs::remove_dir(legacy).is_err() {
+        // Files that already existed in the new place; kept for the user
+        // to sort out, out of the way of the next start
+        let mut kept = legacy.as_os_str().to_owned();
+        kept.push(".migrated");
+        if let Err(e) = std::fs::rename(legacy, &kept) {
+            warn!("Failed to rename {}: {}", legacy.display(), e);
+        } else {
+            warn!(
+                "Files left over from {} are in {}",
+                legacy.display(),
+                Path::new(&kept).display()
+            );
+        }
+    }
+    Ok(())
+}
+
 pub fn ensure_config_dir() -> Result<PathBuf, String> {
     let config_dir = get_config_dir();
     std::fs::create_dir_all(&config_dir)
@@ -121,12 +865,13 @@ pub fn get_preferences_path() -> PathBuf {
     get_config_dir().join("preferences.json")
 }
 
+/// `.env` is shared by all workspaces.
 pub fn get_env_file_path() -> PathBuf {
-    get_config_dir().join(".env")
+    get_base_config_dir().join(".env")
 }
 
 pub fn get_logs_dir() -> PathBuf {
-    get_config_dir().join("logs")
+    get_data_dir().join("logs")
 }
 
 // ============================================================================
@@ -194,7 +939,8 @@ pub fn read_settings() -> Result<ResearchSettings, String> {
     }
     let content =
         std::fs::read_to_string(&path).map_err(|e| format!("Failed to read settings: {}", e))?;
-    serde_json::from_str(&content).map_err(|e| format!("Failed to parse settings: {}", e))
+    serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse settings in {}: {}", path.display(), e))
 }
 
 pub fn write_settings(settings: &ResearchSettings) -> Result<(), String> {
@@ -206,100 +952,271 @@ pub fn write_settings(settings: &ResearchSettings) -> Result<(), String> {
 }
 
 // ============================================================================
-// API Key
+// Config versioning and legacy migration
 // ============================================================================
 
-pub fn read_api_key() -> Option<String> {
-    let env_path = get_env_file_path();
+/// Current layout of the config files. Settings live in `preferences.json`,
+/// MCP servers in `mcp-servers.json` and credentials in the OS credential
+/// store (`.env` is migrated lazily by `secret_store`).
+///
+/// Version 1 retired `config.json`, whose interests are now topics and whose
+/// preferences are now settings.
+pub const CONFIG_VERSION: u32 = 1;
 
-    if !env_path.exists() {
-        return None;
+pub fn get_legacy_config_path() -> PathBuf {
+    get_config_dir().join("config.json")
+}
+
+/// What `migrate_config` changed.
+#[derive(Debug, Default, PartialEq)]
+pub struct ConfigMigration {
+    pub from_version: u32,
+    pub interests_imported: usize,
+    pub legacy_config_backed_up: bool,
+}
+
+/// Legacy `briefingLength` preference as a research depth.
+pub fn depth_from_briefing_length(length: &str) -> Option<&'static str> {
+    match length {
+        "short" | "shallow" => Some("shallow"),
+        "medium" => Some("medium"),
+        "long" | "deep" => Some("deep"),
+        _ => None,
     }
+}
 
-    match std::fs::read_to_string(&env_path) {
-        Ok(content) => {
-            for line in content.lines() {
-                let line = line.trim();
-                if line.starts_with("ANTHROPIC_API_KEY=") {
-                    let key = line.trim_start_matches("ANTHROPIC_API_KEY=").trim();
-                    // Remove quotes if present
-                    let key = key.trim_matches('"').trim_matches('\'');
-                    if !key.is_empty() {
-                        return Some(key.to_string());
-                    }
-                }
-            }
-            None
+/// Bring the config files in `~/.claudius` up to `CONFIG_VERSION`.
+///
+/// `import_interests` receives the interests from a legacy `config.json`
+/// and should add them as topics; the file is only retired (renamed to
+/// `config.json.migrated`) once that succeeds. Safe to call on every start.
+pub fn migrate_config(
+    import_interests: impl FnOnce(&[String]) -> Result<usize, String>,
+) -> Result<ConfigMigration, String> {
+    migrate_config_in(&get_config_dir(), import_interests)
+}
+
+fn migrate_config_in(
+    dir: &std::path::Path,
+    import_interests: impl FnOnce(&[String]) -> Result<usize, String>,
+) -> Result<ConfigMigration, String> {
+    let prefs_path = dir.join("preferences.json");
+    let legacy_path = dir.join("config.json");
+
+    let mut settings: serde_json::Value = if prefs_path.exists() {
+        let content = std::fs::read_to_string(&prefs_path)
+            .map_err(|e| format!("Failed to read settings: {}", e))?;
+        serde_json::from_str(&content).map_err(|e| {
+            format!(
+                "Failed to parse settings in {}: {}",
+                prefs_path.display(),
+                e
+            )
+        })?
+    } else {
+        serde_json::json!({})
+    };
+    let from_version = settings
+        .get("config_version")
+        .and_then(|v| v.as_u64())
+        .unwrap_or(0) as u32;
+    let mut migration = ConfigMigration {
+        from_version,
+        ..Default::default()
+    };
+    if from_version >= CONFIG_VERSION || (!prefs_path.exists() && !legacy_path.exists()) {
+        return Ok(migration);
+    }
+
+    if legacy_path.exists() {
+        let content = std::fs::read_to_string(&legacy_path)
+            .map_err(|e| format!("Failed to read {}: {}", legacy_path.display(), e))?;
+        let legacy: serde_json::Value = serde_json::from_str(&content)
+            .map_err(|e| format!("Failed to parse {}: {}", legacy_path.display(), e))?;
+
+        // Legacy preferences only fill in settings the user never set
+        let prefs = &legacy["preferences"];
+        let obj = settings
+            .as_object_mut()
+            .ok_or_else(|| format!("{} is not a JSON object", prefs_path.display()))?;
+        if let Some(schedule) = prefs.get("schedule").and_then(|v| v.as_str()) {
+            obj.entry("schedule_cron").or_insert(schedule.into());
+        }
+        if let Some(depth) = prefs
+            .get("briefingLength")
+            .and_then(|v| v.as_str())
+            .and_then(depth_from_briefing_length)
+        {
+            obj.entry("research_depth").or_insert(depth.into());
+        }
+        if let Some(enabled) = prefs.get("notificationsEnabled").and_then(|v| v.as_bool()) {
+            obj.entry("enable_notifications").or_insert(enabled.into());
         }
-        Err(_) => None,
+
+        let interests: Vec<String> = legacy["interests"]
+            .as_array()
+            .map(|items| {
+                items
+                    .iter()
+                    .filter_map(|i| i.as_str())
+                    .map(|i| i.trim().to_string())
+                    .filter(|i| !i.is_empty())
+                    .collect()
+            })
+            .unwrap_or_default();
+        if !interests.is_empty() {
+            migration.interests_imported = import_interests(&interests)?;
+        }
+
+        std::fs::rename(&legacy_path, dir.join("config.json.migrated"))
+            .map_err(|e| format!("Failed to back up {}: {}", legacy_path.display(), e))?;
+        migration.legacy_config_backed_up = true;
     }
+
+    // Missing fields take their defaults, then the file is stamped as current
+    let mut upgraded: ResearchSettings = serde_json::from_value(merge_defaults(settings)?)
+        .map_err(|e| format!("Failed to migrate settings: {}", e))?;
+    upgraded.config_version = CONFIG_VERSION;
+    let content = serde_json::to_string_pretty(&upgraded)
+        .map_err(|e| format!("Failed to serialize settings: {}", e))?;
+    std::fs::create_dir_all(dir)
+        .map_err(|e| format!("Failed to create config directory: {}", e))?;
+    std::fs::write(&prefs_path, content).map_err(|e| format!("Failed to write settings: {}", e))?;
+
+    Ok(migration)
 }
 
-pub fn write_api_key(api_key: &str) -> Result<(), String> {
-    ensure_config_dir()?;
-    let env_path = get_env_file_path();
-
-    // Read existing content to preserve other variables
-    let mut lines: Vec<String> = Vec::new();
-    let mut key_updated = false;
-
-    if env_path.exists() {
-        if let Ok(content) = std::fs::read_to_string(&env_path) {
-            for line in content.lines() {
-                if line.trim().starts_with("ANTHROPIC_API_KEY=") {
-                    lines.push(format!("ANTHROPIC_API_KEY={}", api_key));
-                    key_updated = true;
-                } else {
-                    lines.push(line.to_string());
-                }
-            }
+/// Fill the fields `ResearchSettings` requires from its defaults.
+fn merge_defaults(mut settings: serde_json::Value) -> Result<serde_json::Value, String> {
+    let defaults = serde_json::to_value(ResearchSettings::default())
+        .map_err(|e| format!("Failed to serialize settings: {}", e))?;
+    if let (Some(obj), serde_json::Value::Object(defaults)) = (settings.as_object_mut(), defaults) {
+        for (key, value) in defaults {
+            obj.entry(key).or_insert(value);
         }
     }
+    Ok(settings)
+}
+
+// ============================================================================
+// Credential profiles
+// ============================================================================
+
+/// Profile that uses the plain `ANTHROPIC_API_KEY` secret.
+pub const DEFAULT_PROFILE: &str = "default";
 
-    if !key_updated {
-        lines.push(format!("ANTHROPIC_API_KEY={}", api_key));
+/// Profile selected for this process (the CLI `--profile` flag).
+static PROFILE_OVERRIDE: RwLock<Option<String>> = RwLock::new(None);
+
+/// Use `profile` instead of the `active_profile` setting for this process.
+pub fn set_profile_override(profile: Option<String>) {
+    if let Ok(mut current) = PROFILE_OVERRIDE.write() {
+        *current = profile;
     }
+}
 
-    let content = lines.join("\n") + "\n";
+/// The profile whose API key is used: the override, else the `active_profile` setting.
+pub fn active_profile() -> String {
+    if let Some(profile) = PROFILE_OVERRIDE.read().ok().and_then(|p| p.clone()) {
+        return profile;
+    }
+    read_settings()
+        .map(|s| s.active_profile)
+        .unwrap_or_else(|_| DEFAULT_PROFILE.to_string())
+}
 
-    std::fs::write(&env_path, content).map_err(|e| format!("Failed to write .env file: {}", e))?;
+/// All profiles, starting with "default".
+pub fn list_profiles() -> Vec<String> {
+    let mut profiles = vec![DEFAULT_PROFILE.to_string()];
+    if let Ok(settings) = read_settings() {
+        profiles.extend(settings.api_profiles);
+    }
+    profiles
+}
 
-    // Set restrictive permissions (owner read/write only)
-    #[cfg(unix)]
+pub fn validate_profile_name(name: &str) -> Result<(), String> {
+    if name.is_empty() {
+        return Err("Profile name cannot be empty".to_string());
+    }
+    if !name
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
     {
-        use std::os::unix::fs::PermissionsExt;
-        let permissions = std::fs::Permissions::from_mode(0o600);
-        let _ = std::fs::set_permissions(&env_path, permissions);
+        return Err("Profile names may only contain letters, digits, '-' and '_'".to_string());
     }
+    Ok(())
+}
 
+/// Secret name holding a profile's Anthropic API key.
+pub fn profile_secret_name(profile: &str) -> String {
+    if profile == DEFAULT_PROFILE {
+        "ANTHROPIC_API_KEY".to_string()
+    } else {
+        format!(
+            "ANTHROPIC_API_KEY_{}",
+            profile.to_uppercase().replace('-', "_")
+        )
+    }
+}
+
+/// Create (or update the key of) a named profile.
+pub fn add_profile(name: &str, api_key: &str) -> Result<(), String> {
+    validate_profile_name(name)?;
+    validate_api_key(api_key)?;
+    secrets().set(&profile_secret_name(name), api_key)?;
+
+    let mut settings = read_settings()?;
+    if name != DEFAULT_PROFILE && !settings.api_profiles.iter().any(|p| p == name) {
+        settings.api_profiles.push(name.to_string());
+        write_settings(&settings)?;
+    }
     Ok(())
 }
 
-pub fn delete_api_key() -> Result<(), String> {
-    let env_path = get_env_file_path();
+/// Delete a named profile and its key. Falls back to "default" if it was active.
+pub fn remove_profile(name: &str) -> Result<(), String> {
+    if name == DEFAULT_PROFILE {
+        return Err("The default profile cannot be removed".to_string());
+    }
 
-    if !env_path.exists() {
-        return Ok(());
+    let mut settings = read_settings()?;
+    if !settings.api_profiles.iter().any(|p| p == name) {
+        return Err(format!("Unknown profile: {}", name));
     }
+    secrets().delete(&profile_secret_name(name))?;
 
-    // Read and filter out the API key line
-    if let Ok(content) = std::fs::read_to_string(&env_path) {
-        let lines: Vec<&str> = content
-            .lines()
-            .filter(|line| !line.trim().starts_with("ANTHROPIC_API_KEY="))
-            .collect();
+    settings.api_profiles.retain(|p| p != name);
+    if settings.active_profile == name {
+        settings.active_profile = DEFAULT_PROFILE.to_string();
+    }
+    write_settings(&settings)
+}
 
-        if lines.is_empty() {
-            // If no other content, delete the file
-            let _ = std::fs::remove_file(&env_path);
-        } else {
-            let content = lines.join("\n") + "\n";
-            std::fs::write(&env_path, content)
-                .map_err(|e| format!("Failed to update .env file: {}", e))?;
-        }
+/// Make `name` the profile used by the app and CLI.
+pub fn set_active_profile(name: &str) -> Result<(), String> {
+    if !list_profiles().iter().any(|p| p == name) {
+        return Err(format!("Unknown profile: {}", name));
     }
+    let mut settings = read_settings()?;
+    settings.active_profile = name.to_string();
+    write_settings(&settings)
+}
 
-    Ok(())
+// ============================================================================
+// API Key
+// ============================================================================
+
+/// Read the Anthropic API key of the active profile.
+pub fn read_api_key() -> Option<String> {
+    secrets().get(&profile_secret_name(&active_profile()))
+}
+
+pub fn write_api_key(api_key: &str) -> Result<(), String> {
+    secrets().set(&profile_secret_name(&active_profile()), api_key)
+}
+
+pub fn delete_api_key() -> Result<(), String> {
+    secrets().delete(&profile_secret_name(&active_profile()))
 }
 
 pub fn has_api_key() -> bool {
@@ -311,7 +1228,11 @@ pub fn validate_api_key(api_key: &str) -> Result<(), String> {
         return Err("API key cannot be empty".to_string());
     }
 
-    if !api_key.starts_with("sk-ant-") {
+    // Gateways and compatible providers (api_base_url) issue their own key formats
+    let official_api = read_settings()
+        .map(|s| s.api_base_url.is_none_or(|url| url.trim().is_empty()))
+        .unwrap_or(true);
+    if official_api && !api_key.starts_with("sk-ant-") {
         return Err("Invalid API key format. Anthropic API keys start with 'sk-ant-'".to_string());
     }
 
@@ -323,110 +1244,205 @@ pub fn validate_api_key(api_key: &str) -> Result<(), String> {
 // ============================================================================
 
 pub fn read_openai_api_key() -> Option<String> {
-    let env_path = get_env_file_path();
+    secrets().get("OPENAI_API_KEY")
+}
 
-    if !env_path.exists() {
-        return None;
-    }
+pub fn write_openai_api_key(api_key: &str) -> Result<(), String> {
+    secrets().set("OPENAI_API_KEY", api_key)
+}
 
-    match std::fs::read_to_string(&env_path) {
-        Ok(content) => {
-            for line in content.lines() {
-                let line = line.trim();
-                if line.starts_with("OPENAI_API_KEY=") {
-                    let key = line.trim_start_matches("OPENAI_API_KEY=").trim();
-                    // Remove quotes if present
-                    let key = key.trim_matches('"').trim_matches('\'');
-                    if !key.is_empty() {
-                        return Some(key.to_string());
-                    }
-                }
-            }
-            None
-        }
-        Err(_) => None,
-    }
+pub fn delete_openai_api_key() -> Result<(), String> {
+    secrets().delete("OPENAI_API_KEY")
 }
 
-pub fn write_openai_api_key(api_key: &str) -> Result<(), String> {
-    ensure_config_dir()?;
-    let env_path = get_env_file_path();
-
-    // Read existing content to preserve other variables
-    let mut lines: Vec<String> = Vec::new();
-    let mut key_updated = false;
-
-    if env_path.exists() {
-        if let Ok(content) = std::fs::read_to_string(&env_path) {
-            for line in content.lines() {
-                if line.trim().starts_with("OPENAI_API_KEY=") {
-                    lines.push(format!("OPENAI_API_KEY={}", api_key));
-                    key_updated = true;
-                } else {
-                    lines.push(line.to_string());
-                }
-            }
-        }
+pub fn has_openai_api_key() -> bool {
+    read_openai_api_key().is_some()
+}
+
+pub fn validate_openai_api_key(api_key: &str) -> Result<(), String> {
+    if api_key.is_empty() {
+        return Err("API key cannot be empty".to_string());
     }
 
-    if !key_updated {
-        lines.push(format!("OPENAI_API_KEY={}", api_key));
+    if !api_key.starts_with("sk-") {
+        return Err("Invalid API key format. OpenAI API keys start with 'sk-'".to_string());
     }
 
-    let content = lines.join("\n") + "\n";
+    Ok(())
+}
 
-    std::fs::write(&env_path, content).map_err(|e| format!("Failed to write .env file: {}", e))?;
+// ============================================================================
+// Notion Integration Token (for Notion export)
+// ============================================================================
 
-    // Set restrictive permissions (owner read/write only)
-    #[cfg(unix)]
-    {
-        use std::os::unix::fs::PermissionsExt;
-        let permissions = std::fs::Permissions::from_mode(0o600);
-        let _ = std::fs::set_permissions(&env_path, permissions);
-    }
+pub fn read_notion_token() -> Option<String> {
+    secrets().get("NOTION_API_KEY")
+}
 
-    Ok(())
+pub fn write_notion_token(token: &str) -> Result<(), String> {
+    secrets().set("NOTION_API_KEY", token)
 }
 
-pub fn delete_openai_api_key() -> Result<(), String> {
-    let env_path = get_env_file_path();
+pub fn delete_notion_token() -> Result<(), String> {
+    secrets().delete("NOTION_API_KEY")
+}
 
-    if !env_path.exists() {
-        return Ok(());
-    }
+pub fn has_notion_token() -> bool {
+    read_notion_token().is_some()
+}
 
-    // Read and filter out the API key line
-    if let Ok(content) = std::fs::read_to_string(&env_path) {
-        let lines: Vec<&str> = content
-            .lines()
-            .filter(|line| !line.trim().starts_with("OPENAI_API_KEY="))
-            .collect();
+pub fn validate_notion_token(token: &str) -> Result<(), String> {
+    if token.is_empty() {
+        return Err("Notion token cannot be empty".to_string());
+    }
 
-        if lines.is_empty() {
-            // If no other content, delete the file
-            let _ = std::fs::remove_file(&env_path);
-        } else {
-            let content = lines.join("\n") + "\n";
-            std::fs::write(&env_path, content)
-                .map_err(|e| format!("Failed to update .env file: {}", e))?;
-        }
+    if !token.starts_with("secret_") && !token.starts_with("ntn_") {
+        return Err(
+            "Invalid token format. Notion integration tokens start with 'secret_' or 'ntn_'"
+                .to_string(),
+        );
     }
 
     Ok(())
 }
 
-pub fn has_openai_api_key() -> bool {
-    read_openai_api_key().is_some()
+// ============================================================================
+// Other credentials (read-later services)
+// ============================================================================
+
+/// Read an arbitrary credential, from the credential store or the .env file.
+pub fn read_env_value(name: &str) -> Option<String> {
+    secrets().get(name)
 }
 
-pub fn validate_openai_api_key(api_key: &str) -> Result<(), String> {
-    if api_key.is_empty() {
-        return Err("API key cannot be empty".to_string());
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("claudius-config-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
     }
 
-    if !api_key.starts_with("sk-") {
-        return Err("Invalid API key format. OpenAI API keys start with 'sk-'".to_string());
+    #[test]
+    fn test_absolute_path() {
+        let dir = Path::new("/srv/claudius");
+        assert_eq!(absolute_path(dir), dir);
+        let relative = absolute_path(Path::new("test-home"));
+        assert!(relative.is_absolute());
+        assert!(relative.ends_with("test-home"));
     }
 
-    Ok(())
+    #[test]
+    fn test_migrate_legacy_dir_to_xdg() {
+        let root = temp_dir("xdg");
+        let legacy = root.join(".claudius");
+        std::fs::create_dir_all(legacy.join("cache")).unwrap();
+        std::fs::create_dir_all(legacy.join("images")).unwrap();
+        std::fs::write(legacy.join("preferences.json"), "{}").unwrap();
+        std::fs::write(legacy.join("claudius.db"), "db").unwrap();
+        std::fs::write(legacy.join("cache").join("models.json"), "[]").unwrap();
+        let xdg = XdgDirs {
+            config: root.join("config").join("claudius"),
+            data: root.join("share").join("claudius"),
+            cache: root.join("cache").join("claudius"),
+        };
+        assert!(!xdg.in_use(&legacy));
+
+        migrate_legacy_dir_to(&legacy, &xdg).unwrap();
+        assert!(xdg.config.join("preferences.json").exists());
+        assert!(xdg.data.join("claudius.db").exists());
+        assert!(xdg.data.join("images").is_dir());
+        assert!(xdg.cache.join("models.json").exists());
+        assert!(!legacy.exists());
+        assert!(xdg.in_use(&legacy));
+
+        // Files already in the new place win; the old copies are set aside
+        std::fs::create_dir_all(&legacy).unwrap();
+        std::fs::write(legacy.join("preferences.json"), "old").unwrap();
+        migrate_legacy_dir_to(&legacy, &xdg).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(xdg.config.join("preferences.json")).unwrap(),
+            "{}"
+        );
+        assert!(root
+            .join(".claudius.migrated")
+            .join("preferences.json")
+            .exists());
+        assert!(!legacy.exists());
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_migrate_legacy_config_json() {
+        let dir = temp_dir("legacy");
+        std::fs::write(
+            dir.join("config.json"),
+            r#"{"interests":["Rust"," ","AI safety"],
+                "preferences":{"schedule":"0 7 * * *","briefingLength":"long","notificationsEnabled":false}}"#,
+        )
+        .unwrap();
+        std::fs::write(dir.join("preferences.json"), r#"{"model":"claude-opus-4-5-20251101","research_depth":"shallow","max_sources_per_topic":5,"enable_notifications":true}"#).unwrap();
+
+        let mut imported = Vec::new();
+        let migration = migrate_config_in(&dir, |names| {
+            imported = names.to_vec();
+            Ok(names.len())
+        })
+        .unwrap();
+
+        assert_eq!(
+            migration,
+            ConfigMigration {
+                from_version: 0,
+                interests_imported: 2,
+                legacy_config_backed_up: true,
+            }
+        );
+        assert_eq!(imported, vec!["Rust", "AI safety"]);
+        assert!(!dir.join("config.json").exists());
+        assert!(dir.join("config.json.migrated").exists());
+
+        let content = std::fs::read_to_string(dir.join("preferences.json")).unwrap();
+        let settings: ResearchSettings = serde_json::from_str(&content).unwrap();
+        assert_eq!(settings.config_version, CONFIG_VERSION);
+        // Existing settings win over legacy preferences
+        assert_eq!(settings.model, "claude-opus-4-5-20251101");
+        assert_eq!(settings.research_depth, "shallow");
+        assert!(settings.enable_notifications);
+        assert_eq!(settings.schedule_cron, "0 7 * * *");
+
+        // Already current: nothing to do
+        let again = migrate_config_in(&dir, |_| panic!("no interests to import")).unwrap();
+        assert_eq!(again.from_version, CONFIG_VERSION);
+        assert!(!again.legacy_config_backed_up);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_failed_interest_import_keeps_legacy_file() {
+        let dir = temp_dir("import-failed");
+        std::fs::write(dir.join("config.json"), r#"{"interests":["Rust"]}"#).unwrap();
+
+        let result = migrate_config_in(&dir, |_| Err("database locked".to_string()));
+        assert_eq!(result, Err("database locked".to_string()));
+        assert!(dir.join("config.json").exists());
+        assert!(!dir.join("preferences.json").exists());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_fresh_install_writes_nothing() {
+        let dir = temp_dir("fresh");
+        let migration = migrate_config_in(&dir, |_| Ok(0)).unwrap();
+        assert_eq!(migration, ConfigMigration::default());
+        assert!(!dir.join("preferences.json").exists());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
 }
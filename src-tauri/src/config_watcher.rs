@@ -0,0 +1,126 @@
+//! Detects hand edits to the config files while the desktop app is running.
+//!
+//! `preferences.json` and `mcp-servers.json` can be changed by the CLI or an
+//! editor behind the app's back. The app polls them with a [`ConfigWatcher`]
+//! and emits `config:changed` so open windows reload their settings and MCP
+//! server lists. Polling (rather than OS file notifications) keeps this
+//! dependency-free and copes with editors that save by replacing the file.
+
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+use crate::config::get_config_dir;
+
+/// How often the desktop app checks the config files.
+pub const POLL_INTERVAL_SECS: u64 = 2;
+
+/// A watched config file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConfigFile {
+    Settings,
+    McpServers,
+}
+
+impl ConfigFile {
+    pub const ALL: [ConfigFile; 2] = [ConfigFile::Settings, ConfigFile::McpServers];
+
+    pub fn file_name(&self) -> &'static str {
+        match self {
+            ConfigFile::Settings => "preferences.json",
+            ConfigFile::McpServers => "mcp-servers.json",
+        }
+    }
+}
+
+/// Payload of the `config:changed` event.
+#[derive(Debug, Clone, Serialize)]
+pub struct ConfigChangedEvent {
+    pub files: Vec<ConfigFile>,
+}
+
+/// Remembers the last seen contents of each config file.
+pub struct ConfigWatcher {
+    dir: PathBuf,
+    last: Vec<(ConfigFile, Option<String>)>,
+}
+
+impl ConfigWatcher {
+    /// Watch the files in `~/.claudius`, starting from their current contents.
+    pub fn new() -> Self {
+        Self::in_dir(&get_config_dir())
+    }
+
+    /// Watch the files in `dir`.
+    pub fn in_dir(dir: &Path) -> Self {
+        let last = ConfigFile::ALL
+            .iter()
+            .map(|file| (*file, read(&dir.join(file.file_name()))))
+            .collect();
+        Self {
+            dir: dir.to_path_buf(),
+            last,
+        }
+    }
+
+    /// Files whose contents changed (or that appeared or were deleted) since
+    /// the previous call. Comparing contents rather than modification times
+    /// ignores saves that didn't change anything.
+    pub fn poll(&mut self) -> Vec<ConfigFile> {
+        let mut changed = Vec::new();
+        for (file, last) in self.last.iter_mut() {
+            let current = read(&self.dir.join(file.file_name()));
+            if current != *last {
+                *last = current;
+                changed.push(*file);
+            }
+        }
+        changed
+    }
+}
+
+impl Default for ConfigWatcher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn read(path: &Path) -> Option<String> {
+    std::fs::read_to_string(path).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_poll_reports_changed_files() {
+        let dir =
+            std::env::temp_dir().join(format!("claudius-config-watch-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let prefs = dir.join("preferences.json");
+        let mcp = dir.join("mcp-servers.json");
+        std::fs::write(&prefs, r#"{"model":"a"}"#).unwrap();
+        let _ = std::fs::remove_file(&mcp);
+
+        let mut watcher = ConfigWatcher::in_dir(&dir);
+        assert!(watcher.poll().is_empty());
+
+        // Rewriting identical contents is not a change
+        std::fs::write(&prefs, r#"{"model":"a"}"#).unwrap();
+        assert!(watcher.poll().is_empty());
+
+        std::fs::write(&prefs, r#"{"model":"b"}"#).unwrap();
+        std::fs::write(&mcp, r#"{"servers":[]}"#).unwrap();
+        assert_eq!(
+            watcher.poll(),
+            vec![ConfigFile::Settings, ConfigFile::McpServers]
+        );
+        assert!(watcher.poll().is_empty());
+
+        std::fs::remove_file(&mcp).unwrap();
+        assert_eq!(watcher.poll(), vec![ConfigFile::McpServers]);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,721 @@
+//! Headless research scheduler for `claudius daemon`.
+//!
+//! The daemon starts a research run whenever the `schedule_cron` setting
+//! comes due, so scheduled briefings no longer need the desktop app open.
+//! Each run is a `claudius research now` child process (the same approach as
+//! `claudius serve`). The schedule is re-read every minute, so changes apply
+//! without a restart. With `schedule_mode` set to "adaptive", the time is
+//! learned from activity instead (see `smart_schedule`); the daemon logs the
+//! machine waking from sleep towards it.
+//!
+//! A run missed while the machine was asleep or off is made up on wake or
+//! when the daemon starts, unless research already ran since or
+//! `catch_up_missed_runs` is off. The catch-up is announced through the
+//! notification queue, which the desktop app delivers. With
+//! `enable_schedule_calendar` on, the calendar of upcoming runs is refreshed
+//! on start, after each run and whenever the schedule changes.
+//!
+//! `install` registers the daemon as a launchd agent on macOS or a systemd
+//! user unit on Linux, so it starts at login and is restarted if it exits.
+
+use chrono::{DateTime, Local, Utc};
+use croner::Cron;
+use serde::{Deserialize, Serialize};
+use std::ffi::{OsStr, OsString};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::Duration;
+use tracing::{error, info, warn};
+
+use crate::config::{self, get_logs_dir};
+use crate::meta_briefing::{meta_briefing_due, MetaPeriod};
+use crate::notification_prefs::NotificationEvent;
+use crate::smart_schedule::{self, ACTIVITY_WAKE};
+
+/// launchd label and systemd unit name.
+pub const SERVICE_LABEL: &str = "com.claudius.daemon";
+const SYSTEMD_UNIT: &str = "claudius-daemon.service";
+
+/// Longest the scheduler sleeps before re-reading the schedule.
+const MAX_SLEEP_SECS: u64 = 60;
+
+/// How far the clock may run past a sleep before the machine is taken to
+/// have been asleep.
+const WAKE_GAP_SECS: i64 = 5 * 60;
+
+/// A run starting this long after its time was missed rather than late.
+const MISSED_AFTER_SECS: i64 = 5 * 60;
+
+/// Runs missed longer ago than this aren't made up.
+const CATCH_UP_HOURS: i64 = 18;
+
+/// The most recent scheduled run.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DaemonRun {
+    pub started_at: String,
+    pub finished_at: String,
+    pub success: bool,
+    #[serde(default)]
+    pub error: Option<String>,
+    /// When the missed run this one made up was scheduled
+    #[serde(default)]
+    pub catch_up_for: Option<String>,
+}
+
+/// What a running daemon reports about itself in `~/.claudius/daemon.json`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DaemonState {
+    pub pid: u32,
+    pub started_at: String,
+    pub schedule: String,
+    pub next_run: Option<String>,
+    #[serde(default)]
+    pub last_run: Option<DaemonRun>,
+}
+
+fn get_state_path() -> PathBuf {
+    config::get_data_dir().join("daemon.json")
+}
+
+/// Read the state file written by the daemon, if any.
+pub fn read_daemon_state() -> Option<DaemonState> {
+    let content = std::fs::read_to_string(get_state_path()).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+fn write_daemon_state(state: &DaemonState) {
+    match serde_json::to_string_pretty(state) {
+        Ok(content) => {
+            if let Err(e) = std::fs::write(get_state_path(), content) {
+                warn!("Failed to write daemon state: {}", e);
+            }
+        }
+        Err(e) => warn!("Failed to serialize daemon state: {}", e),
+    }
+}
+
+// ============================================================================
+// Schedule
+// ============================================================================
+
+/// Parse a 5-field cron expression ("minute hour day month weekday").
+pub fn parse_schedule(expr: &str) -> Result<Cron, String> {
+    Cron::new(expr.trim())
+        .parse()
+        .map_err(|e| format!("Invalid schedule '{}': {}", expr, e))
+}
+
+/// The first time after `after` that `expr` matches, in local time.
+pub fn next_run(expr: &str, after: &DateTime<Local>) -> Result<DateTime<Local>, String> {
+    parse_schedule(expr)?
+        .find_next_occurrence(after, false)
+        .map_err(|e| format!("No upcoming run for schedule '{}': {}", expr, e))
+}
+
+/// The latest run of `expr` in the [`CATCH_UP_HOURS`] up to `now` that was
+/// missed: research last ran before it (`last_research`), or never.
+pub fn missed_run(
+    expr: &str,
+    last_research: Option<DateTime<Local>>,
+    now: &DateTime<Local>,
+) -> Result<Option<DateTime<Local>>, String> {
+    let cron = parse_schedule(expr)?;
+    let mut missed = None;
+    let mut after = *now - chrono::Duration::hours(CATCH_UP_HOURS);
+    while let Ok(run) = cron.find_next_occurrence(&after, false) {
+        if run > *now {
+            break;
+        }
+        missed = Some(run);
+        after = run;
+    }
+    Ok(missed.filter(|run| last_research.is_none_or(|last| last < *run)))
+}
+
+/// When research last ran: the daemon's last run or the newest briefing,
+/// whichever is later (research may have been run from the app or CLI).
+fn last_research(last_run: Option<&DaemonRun>) -> Option<DateTime<Local>> {
+    let newest_briefing = crate::db::get_connection()
+        .map_err(|e| e.to_string())
+        .and_then(|conn| crate::db::last_research_date(&conn))
+        .unwrap_or_else(|e| {
+            warn!("Failed to check the last briefing: {}", e);
+            None
+        });
+    last_run
+        .map(|run| run.started_at.clone())
+        .into_iter()
+        .chain(newest_briefing)
+        .filter_map(|time| DateTime::parse_from_rfc3339(&time).ok())
+        .map(|time| time.with_timezone(&Local))
+        .max()
+}
+
+/// Queue a notification about a catch-up run for the desktop app to show,
+/// as the research completed or failed preferences say.
+fn notify_catch_up(run: &DaemonRun, missed: &DateTime<Local>) {
+    let settings = config::read_settings().unwrap_or_default();
+    let event = if run.success {
+        NotificationEvent::ResearchCompleted
+    } else {
+        NotificationEvent::ResearchFailed
+    };
+    if crate::notification_prefs::delivery(&settings, event).is_none() {
+        return;
+    }
+    let missed = missed.format("%-I:%M %p");
+    let (title, body) = match &run.error {
+        None => (
+            "Caught up on a missed briefing".to_string(),
+            format!(
+                "Research scheduled for {} was missed while your computer was asleep or off, so it ran now.",
+                missed
+            ),
+        ),
+        Some(e) => (
+            "Catch-up research failed".to_string(),
+            format!("Making up the {} run failed: {}", missed, e),
+        ),
+    };
+    let deliver_at = Utc::now().format("%Y-%m-%d %H:%M:%S").to_string();
+    let result = crate::db::get_connection()
+        .map_err(|e| e.to_string())
+        .and_then(|conn| crate::db::queue_notification(&conn, None, &title, &body, &deliver_at));
+    if let Err(e) = result {
+        warn!("Failed to queue catch-up notification: {}", e);
+    }
+}
+
+/// Run `claudius research now` and wait for it to finish. With
+/// `batch_scheduled_research` on, the run goes through the Batch API.
+async fn run_scheduled_research(cli_path: &Path) -> DaemonRun {
+    let mut args = vec!["research", "now"];
+    if config::read_settings()
+        .unwrap_or_default()
+        .batch_scheduled_research
+    {
+        args.push("--batch");
+    }
+    run_cli(cli_path, &args).await
+}
+
+/// Generate the meta-briefings asked for by the `meta_briefings` setting
+/// whose last one is at least a period old.
+async fn run_due_meta_briefings(cli_path: &Path) {
+    let setting = config::read_settings().unwrap_or_default().meta_briefings;
+    for period in MetaPeriod::scheduled(&setting) {
+        let last = match crate::db::get_connection() {
+            Ok(conn) => crate::db::last_briefing_date_with_tag(&conn, period.tag()),
+            Err(e) => Err(e.to_string()),
+        };
+        let last = match last {
+            Ok(last) => last,
+            Err(e) => {
+                warn!("Failed to check for due meta-briefings: {}", e);
+                return;
+            }
+        };
+        if !meta_briefing_due(period, last.as_deref(), Utc::now()) {
+            continue;
+        }
+
+        info!("Generating {} meta-briefing", period.tag());
+        let run = run_cli(cli_path, &["briefings", period.tag()]).await;
+        match run.error {
+            None => info!("Generated {} meta-briefing", period.tag()),
+            Some(e) => warn!("Failed to generate {} meta-briefing: {}", period.tag(), e),
+        }
+    }
+}
+
+/// Rewrite the schedule calendar if `enable_schedule_calendar` is on.
+fn refresh_schedule_calendar() {
+    let settings = config::read_settings().unwrap_or_default();
+    if !settings.enable_schedule_calendar {
+        return;
+    }
+    let path = crate::schedule_calendar::calendar_path(settings.schedule_calendar_path.as_deref());
+    let result = crate::db::get_connection()
+        .map_err(|e| e.to_string())
+        .and_then(|conn| {
+            let schedule = smart_schedule::effective_schedule(&settings);
+            crate::schedule_calendar::write_calendar(&conn, &schedule, &path)
+        });
+    if let Err(e) = result {
+        warn!("Failed to refresh schedule calendar: {}", e);
+    }
+}
+
+/// Run `claudius <args>` for the active profile and wait for it to finish.
+async fn run_cli(cli_path: &Path, args: &[&str]) -> DaemonRun {
+    let started_at = Local::now().to_rfc3339();
+    let profile = config::active_profile();
+    let output = tokio::process::Command::new(cli_path)
+        .args(["--json", "--profile", profile.as_str()])
+        .args(config::dir_args())
+        .args(args)
+        .env("NO_COLOR", "1")
+        .stdin(std::process::Stdio::null())
+        .output()
+        .await;
+
+    let error = match output {
+        Ok(output) if output.status.success() => None,
+        Ok(output) => Some(
+            String::from_utf8_lossy(&output.stderr)
+                .lines()
+                .rev()
+                .find(|l| !l.trim().is_empty())
+                .map(|l| l.trim().trim_start_matches("Error:").trim().to_string())
+                .unwrap_or_else(|| format!("{} exited with {}", args.join(" "), output.status)),
+        ),
+        Err(e) => Some(format!("Failed to start {}: {}", args.join(" "), e)),
+    };
+
+    DaemonRun {
+        started_at,
+        finished_at: Local::now().to_rfc3339(),
+        success: error.is_none(),
+        error,
+        catch_up_for: None,
+    }
+}
+
+/// Run the scheduler until the process is stopped.
+///
+/// `cli_path` is the `claudius` binary used for research runs.
+pub async fn run(cli_path: PathBuf) -> Result<(), String> {
+    // Create or migrate the schema before anything reads it, so neither the
+    // daemon nor its `research now` runs meet missing tables or columns
+    crate::db::get_connection().map_err(|e| format!("Failed to open database: {}", e))?;
+    let settings = config::read_settings().unwrap_or_default();
+    let mut mode = settings.schedule_mode.clone();
+    let mut schedule = smart_schedule::effective_schedule(&settings);
+    let mut next = next_run(&schedule, &Local::now())?;
+    let mut state = DaemonState {
+        pid: std::process::id(),
+        started_at: Local::now().to_rfc3339(),
+        schedule: schedule.clone(),
+        next_run: Some(next.to_rfc3339()),
+        // Kept across restarts, for missed-run checks and `daemon status`
+        last_run: read_daemon_state().and_then(|state| state.last_run),
+    };
+    write_daemon_state(&state);
+    refresh_schedule_calendar();
+    info!("Daemon started, next research run at {}", next);
+
+    // The scheduled time of a missed run being made up
+    let mut catch_up = None;
+    if settings.catch_up_missed_runs {
+        let last = last_research(state.last_run.as_ref());
+        if let Some(missed) = missed_run(&schedule, last, &Local::now())? {
+            info!("Research run at {} was missed, catching up now", missed);
+            catch_up = Some(missed);
+            next = Local::now();
+        }
+    }
+
+    loop {
+        let wait = match state.next_run {
+            Some(_) => (next - Local::now())
+                .to_std()
+                .unwrap_or_default()
+                .min(Duration::from_secs(MAX_SLEEP_SECS)),
+            // Paused on an invalid schedule, just wait for it to be fixed
+            None => Duration::from_secs(MAX_SLEEP_SECS),
+        };
+        let slept_at = Local::now();
+        tokio::time::sleep(wait).await;
+        // The sleep timer stops while the machine sleeps, the clock doesn't
+        let overslept = (Local::now() - slept_at).num_seconds() - wait.as_secs() as i64;
+        if overslept > WAKE_GAP_SECS {
+            info!("Machine woke from sleep");
+            smart_schedule::record_activity(ACTIVITY_WAKE);
+        }
+
+        // Pick up schedule changes made in the app or with `config set`. The
+        // adaptive schedule is only re-learned after a run (or when switching
+        // to it), so new activity can't move today's run around.
+        let settings = config::read_settings().unwrap_or_default();
+        let current = if settings.schedule_mode == "adaptive" && mode == "adaptive" {
+            schedule.clone()
+        } else {
+            smart_schedule::effective_schedule(&settings)
+        };
+        mode = settings.schedule_mode;
+        if current != schedule {
+            match next_run(&current, &Local::now()) {
+                Ok(updated) => {
+                    info!("Schedule changed to '{}', next run at {}", current, updated);
+                    next = updated;
+                    state.next_run = Some(next.to_rfc3339());
+                }
+                Err(e) => {
+                    error!("{}; scheduled runs are paused until it is fixed", e);
+                    state.next_run = None;
+                }
+            }
+            schedule = current;
+            state.schedule = schedule.clone();
+            write_daemon_state(&state);
+            if state.next_run.is_some() {
+                refresh_schedule_calendar();
+            }
+            continue;
+        }
+
+        if state.next_run.is_none() || Local::now() < next {
+            continue;
+        }
+
+        // Long past its time: the machine slept through the run
+        if catch_up.is_none() && (Local::now() - next).num_seconds() > MISSED_AFTER_SECS {
+            let researched = last_research(state.last_run.as_ref()).is_some_and(|t| t >= next);
+            if settings.catch_up_missed_runs && !researched {
+                info!("Research run at {} was missed, catching up now", next);
+                catch_up = Some(next);
+            } else {
+                info!("Skipping the missed research run at {}", next);
+                next = next_run(&schedule, &Local::now())?;
+                state.next_run = Some(next.to_rfc3339());
+                write_daemon_state(&state);
+                continue;
+            }
+        }
+
+        info!("Starting scheduled research");
+        let mut run = run_scheduled_research(&cli_path).await;
+        match &run.error {
+            None => info!("Scheduled research completed"),
+            Some(e) => warn!("Scheduled research failed: {}", e),
+        }
+        if let Some(missed) = catch_up.take() {
+            run.catch_up_for = Some(missed.to_rfc3339());
+            notify_catch_up(&run, &missed);
+        }
+        let started = run_day(&run);
+        state.last_run = Some(run);
+        run_due_meta_briefings(&cli_path).await;
+
+        schedule = smart_schedule::effective_schedule(&config::read_settings().unwrap_or_default());
+        state.schedule = schedule.clone();
+        next = next_run(&schedule, &Local::now())?;
+        // A re-learned adaptive time may fall later the same day; one run a day
+        if mode == "adaptive" && Some(next.date_naive()) == started {
+            next = next_run(&schedule, &next)?;
+        }
+        state.next_run = Some(next.to_rfc3339());
+        write_daemon_state(&state);
+        refresh_schedule_calendar();
+        info!("Next research run at {}", next);
+    }
+}
+
+/// Local day a run started on.
+fn run_day(run: &DaemonRun) -> Option<chrono::NaiveDate> {
+    DateTime::parse_from_rfc3339(&run.started_at)
+        .ok()
+        .map(|t| t.with_timezone(&Local).date_naive())
+}
+
+// ============================================================================
+// Service installation
+// ============================================================================
+
+/// Escape text for an XML plist.
+fn xml_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+/// launchd agent definition running `claudius <options> daemon`.
+pub fn launchd_plist(exe: &Path, log_path: &Path, options: &[OsString]) -> String {
+    let exe = xml_escape(&exe.to_string_lossy());
+    let log = xml_escape(&log_path.to_string_lossy());
+    let options: String = options
+        .iter()
+        .map(|option| {
+            format!(
+                "\n        <string>{}</string>",
+                xml_escape(&option.to_string_lossy())
+            )
+        })
+        .collect();
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+    <key>Label</key>
+    <string>{SERVICE_LABEL}</string>
+    <key>ProgramArguments</key>
+    <array>
+        <string>{exe}</string>{options}
+        <string>daemon</string>
+    </array>
+    <key>RunAtLoad</key>
+    <true/>
+    <key>KeepAlive</key>
+    <true/>
+    <key>StandardOutPath</key>
+    <string>{log}</string>
+    <key>StandardErrorPath</key>
+    <string>{log}</string>
+</dict>
+</plist>
+"#
+    )
+}
+
+/// systemd user unit running `claudius <options> daemon`.
+pub fn systemd_unit(exe: &Path, options: &[OsString]) -> String {
+    let quote = |arg: &OsStr| format!("\"{}\"", arg.to_string_lossy().replace('"', "\\\""));
+    let options: String = options
+        .iter()
+        .map(|option| format!(" {}", quote(option.as_os_str())))
+        .collect();
+    format!(
+        "[Unit]
+Description=Claudius research scheduler
+After=network-online.target
+
+[Service]
+ExecStart={}{} daemon
+Restart=on-failure
+RestartSec=30
+
+[Install]
+WantedBy=default.target
+",
+        quote(exe.as_os_str()),
+        options
+    )
+}
+
+/// Where the service definition is installed on this platform.
+pub fn unit_path() -> Result<PathBuf, String> {
+    let home = dirs::home_dir().ok_or("Could not find home directory")?;
+    if cfg!(target_os = "macos") {
+        Ok(home
+            .join("Library/LaunchAgents")
+            .join(format!("{}.plist", SERVICE_LABEL)))
+    } else if cfg!(target_os = "linux") {
+        Ok(dirs::config_dir()
+            .unwrap_or_else(|| home.join(".config"))
+            .join("systemd/user")
+            .join(SYSTEMD_UNIT))
+    } else {
+        Err("Daemon installation is only supported on macOS (launchd) and Linux (systemd)".into())
+    }
+}
+
+fn run_command(program: &str, args: &[&str]) -> Result<(), String> {
+    let output = Command::new(program)
+        .args(args)
+        .output()
+        .map_err(|e| format!("Failed to run {}: {}", program, e))?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(format!(
+            "{} {} failed: {}",
+            program,
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ))
+    }
+}
+
+/// Write the service definition for `exe` and start it. Returns the path written.
+pub fn install(exe: &Path) -> Result<PathBuf, String> {
+    let path = unit_path()?;
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)
+            .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    }
+    let path_str = path.to_string_lossy().to_string();
+    // Keep using the directory and workspace this was installed with
+    let options = config::dir_args();
+
+    if cfg!(target_os = "macos") {
+        let logs_dir = get_logs_dir();
+        std::fs::create_dir_all(&logs_dir)
+            .map_err(|e| format!("Failed to create logs directory: {}", e))?;
+        // Reinstalling: unload the old definition first, ignoring errors
+        if path.exists() {
+            let _ = run_command("launchctl", &["unload", &path_str]);
+        }
+        std::fs::write(
+            &path,
+            launchd_plist(exe, &logs_dir.join("daemon.log"), &options),
+        )
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+        run_command("launchctl", &["load", "-w", &path_str])?;
+    } else {
+        std::fs::write(&path, systemd_unit(exe, &options))
+            .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+        run_command("systemctl", &["--user", "daemon-reload"])?;
+        run_command("systemctl", &["--user", "enable", "--now", SYSTEMD_UNIT])?;
+        // Pick up a new binary path when reinstalling
+        run_command("systemctl", &["--user", "restart", SYSTEMD_UNIT])?;
+    }
+
+    info!("Installed daemon service at {}", path.display());
+    Ok(path)
+}
+
+/// Stop the service and remove its definition. Returns false if it wasn't installed.
+pub fn uninstall() -> Result<bool, String> {
+    let path = unit_path()?;
+    if !path.exists() {
+        return Ok(false);
+    }
+    let path_str = path.to_string_lossy().to_string();
+
+    if cfg!(target_os = "macos") {
+        if let Err(e) = run_command("launchctl", &["unload", "-w", &path_str]) {
+            warn!("{}", e);
+        }
+    } else if let Err(e) = run_command("systemctl", &["--user", "disable", "--now", SYSTEMD_UNIT]) {
+        warn!("{}", e);
+    }
+
+    std::fs::remove_file(&path).map_err(|e| format!("Failed to remove {}: {}", path_str, e))?;
+    if cfg!(target_os = "linux") {
+        let _ = run_command("systemctl", &["--user", "daemon-reload"]);
+    }
+    let _ = std::fs::remove_file(get_state_path());
+    Ok(true)
+}
+
+/// Whether the daemon service is installed and running.
+#[derive(Debug, Clone, Serialize)]
+pub struct DaemonStatus {
+    pub installed: bool,
+    pub running: bool,
+    pub unit_path: Option<String>,
+    pub state: Option<DaemonState>,
+    /// Whether `schedule_mode` is "adaptive"
+    pub adaptive: bool,
+    /// The learned schedule, once there is enough activity
+    pub learned: Option<smart_schedule::AdaptiveSchedule>,
+}
+
+pub fn status() -> DaemonStatus {
+    let path = unit_path().ok();
+    let installed = path.as_ref().is_some_and(|p| p.exists());
+    let running = installed
+        && if cfg!(target_os = "macos") {
+            run_command("launchctl", &["list", SERVICE_LABEL]).is_ok()
+        } else {
+            run_command(
+                "systemctl",
+                &["--user", "is-active", "--quiet", SYSTEMD_UNIT],
+            )
+            .is_ok()
+        };
+    let adaptive = config::read_settings().unwrap_or_default().schedule_mode == "adaptive";
+    let learned = if adaptive {
+        crate::db::get_connection()
+            .map_err(|e| e.to_string())
+            .and_then(|conn| smart_schedule::adaptive_schedule(&conn, Local::now().naive_local()))
+            .unwrap_or_else(|e| {
+                warn!("Failed to learn the adaptive schedule: {}", e);
+                None
+            })
+    } else {
+        None
+    };
+
+    DaemonStatus {
+        installed,
+        running,
+        unit_path: path.map(|p| p.to_string_lossy().to_string()),
+        state: read_daemon_state(),
+        adaptive,
+        learned,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_next_run() {
+        let now = Local.with_ymd_and_hms(2026, 3, 2, 5, 30, 0).unwrap();
+        let next = next_run("0 6 * * *", &now).unwrap();
+        assert_eq!(next, Local.with_ymd_and_hms(2026, 3, 2, 6, 0, 0).unwrap());
+
+        // Exactly on the scheduled minute, the next run is the following day
+        let next = next_run("0 6 * * *", &next).unwrap();
+        assert_eq!(next, Local.with_ymd_and_hms(2026, 3, 3, 6, 0, 0).unwrap());
+
+        assert!(parse_schedule("30 6,18 * * 1-5").is_ok());
+        assert!(parse_schedule("not a schedule").is_err());
+    }
+
+    #[test]
+    fn test_missed_run() {
+        let at = |day, h, m| Local.with_ymd_and_hms(2026, 3, day, h, m, 0).unwrap();
+        let now = at(2, 9, 15);
+
+        // Asleep at 6:00, last ran yesterday
+        assert_eq!(
+            missed_run("0 6 * * *", Some(at(1, 6, 0)), &now).unwrap(),
+            Some(at(2, 6, 0))
+        );
+        assert_eq!(
+            missed_run("0 6 * * *", None, &now).unwrap(),
+            Some(at(2, 6, 0))
+        );
+        // Already ran (or researched from the app) since
+        assert_eq!(
+            missed_run("0 6 * * *", Some(at(2, 6, 1)), &now).unwrap(),
+            None
+        );
+        // The latest of several runs today
+        assert_eq!(
+            missed_run("0 6,9 * * *", Some(at(1, 18, 0)), &now).unwrap(),
+            Some(at(2, 9, 0))
+        );
+        // Too long ago to make up
+        assert_eq!(
+            missed_run("0 6 * * 1", Some(at(1, 6, 0)), &at(3, 9, 15)).unwrap(),
+            None
+        );
+        assert!(missed_run("not a schedule", None, &now).is_err());
+    }
+
+    #[test]
+    fn test_service_definitions() {
+        let exe = Path::new("/usr/local/bin/claudius");
+        let plist = launchd_plist(exe, Path::new("/Users/me/.claudius/logs/daemon.log"), &[]);
+        assert!(plist.contains("<string>/usr/local/bin/claudius</string>"));
+        assert!(plist.contains("<string>daemon</string>"));
+        assert!(plist.contains(SERVICE_LABEL));
+        assert!(!plist.contains("--config-dir"));
+
+        let options: Vec<OsString> = vec![
+            "--config-dir".into(),
+            "/tmp/a&b".into(),
+            "--workspace".into(),
+            "work".into(),
+        ];
+        let plist = launchd_plist(exe, Path::new("/tmp/daemon.log"), &options);
+        assert!(plist.contains(
+            "<string>/tmp/a&amp;b</string>\n        <string>--workspace</string>\n        <string>work</string>\n        <string>daemon</string>"
+        ));
+
+        let unit = systemd_unit(Path::new("/home/me/My Apps/claudius"), &[]);
+        assert!(unit.contains("ExecStart=\"/home/me/My Apps/claudius\" daemon"));
+        assert!(unit.contains("WantedBy=default.target"));
+
+        let unit = systemd_unit(exe, &options[..2]);
+        assert!(unit.contains(
+            "ExecStart=\"/usr/local/bin/claudius\" \"--config-dir\" \"/tmp/a&b\" daemon"
+        ));
+    }
+}
@@ -1,12 +1,15 @@
 // Note: Many functions are used by CLI but not by Tauri app, so we allow dead_code.
 #![allow(dead_code)]
 
-use rusqlite::{params, Connection, Result};
+use rusqlite::{params, Connection, OptionalExtension, Result};
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use tauri::AppHandle;
 use tracing::{debug, info, warn};
 
+use crate::config::{get_config_dir, Briefing};
+
 /// Topic struct for database operations
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Topic {
@@ -14,11 +17,48 @@ pub struct Topic {
     pub name: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
+    #[serde(default)]
+    pub preferred_sources: Vec<String>, // Sites to check first when researching this topic
     pub enabled: bool,
+    #[serde(default)]
+    pub snoozed_until: Option<String>, // RFC 3339; skipped by research runs until then
+    #[serde(default)]
+    pub skip_next_run: bool, // Skipped by the next research run only
+    #[serde(default)]
+    pub dedup_days: Option<i32>, // Overrides the global dedup_days setting
+    #[serde(default)]
+    pub dedup_threshold: Option<f64>, // Overrides the global dedup_threshold setting
+    #[serde(default)]
+    pub mcp_servers: Vec<String>, // MCP servers ("server" or "server/tool") bound to this topic
     pub created_at: String,
     pub updated_at: String,
 }
 
+impl Topic {
+    /// Whether the topic is snoozed at `now`
+    pub fn is_snoozed(&self, now: chrono::DateTime<chrono::Utc>) -> bool {
+        self.snoozed_until
+            .as_deref()
+            .and_then(|until| chrono::DateTime::parse_from_rfc3339(until).ok())
+            .map(|until| until > now)
+            .unwrap_or(false)
+    }
+
+    /// Whether a research run over all topics should include this topic
+    pub fn is_due(&self, now: chrono::DateTime<chrono::Utc>) -> bool {
+        self.enabled && !self.skip_next_run && !self.is_snoozed(now)
+    }
+
+    /// Snooze for `days` days from `now` (0 clears the snooze)
+    pub fn snooze(&mut self, days: u32, now: chrono::DateTime<chrono::Utc>) {
+        self.snoozed_until = if days == 0 {
+            None
+        } else {
+            Some((now + chrono::Duration::days(days as i64)).to_rfc3339())
+        };
+    }
+}
+
 /// Result of migrating topics from JSON to SQLite
 #[derive(Debug)]
 pub struct MigrationResult {
@@ -38,6 +78,9 @@ pub struct ChatMessage {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub tokens_used: Option<i32>,
     pub created_at: String,
+    /// Tools Claude called while writing this reply
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tools_used: Vec<String>,
 }
 
 /// Represents a card that has chat messages (briefing_id + card_index)
@@ -47,55 +90,176 @@ pub struct CardWithChat {
     pub card_index: i32,
 }
 
-pub fn get_db_path() -> PathBuf {
-    let home = dirs::home_dir().expect("Could not find home directory");
-    home.join(".claudius").join("claudius.db")
-}
+/// File name of the database in the data (or workspace) directory.
+pub const DB_FILE: &str = "claudius.db";
 
-fn get_config_dir() -> PathBuf {
-    let home = dirs::home_dir().expect("Could not find home directory");
-    home.join(".claudius")
+pub fn get_db_path() -> PathBuf {
+    crate::config::get_data_dir().join(DB_FILE)
 }
 
 pub fn init_database(_app: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
-    let db_path = get_db_path();
+    let conn = get_connection()?;
 
-    // Ensure directory exists
-    if let Some(parent) = db_path.parent() {
-        std::fs::create_dir_all(parent)?;
+    // Run topic migration from JSON (idempotent)
+    if let Err(e) = migrate_topics_from_json(&conn) {
+        warn!("Topics migration encountered an issue: {}", e);
     }
 
-    let conn = Connection::open(&db_path)?;
+    Ok(())
+}
 
+/// Create the tables and run the migrations (idempotent). `get_connection`
+/// does this the first time it opens each database, so the CLI, daemon and
+/// server work on a database the desktop app has never opened.
+pub fn ensure_schema(conn: &Connection) -> Result<()> {
     // Create tables
     conn.execute_batch(include_str!("schema.sql"))?;
 
     // Run migrations
-    if let Err(e) = migrate_chat_messages_add_card_index(&conn) {
+    if let Err(e) = migrate_chat_messages_add_card_index(conn) {
         warn!("Chat messages migration encountered an issue: {}", e);
     }
 
-    // Run topic migration from JSON (idempotent)
-    if let Err(e) = migrate_topics_from_json(&conn) {
-        warn!("Topics migration encountered an issue: {}", e);
+    if let Err(e) = migrate_chat_messages_add_tools_used(conn) {
+        warn!(
+            "Chat messages tools_used migration encountered an issue: {}",
+            e
+        );
+    }
+
+    if let Err(e) = migrate_topics_add_preferred_sources(conn) {
+        warn!(
+            "Topics preferred_sources migration encountered an issue: {}",
+            e
+        );
+    }
+
+    if let Err(e) = migrate_topics_add_snooze(conn) {
+        warn!("Topics snooze migration encountered an issue: {}", e);
+    }
+
+    if let Err(e) = migrate_topics_add_dedup_overrides(conn) {
+        warn!(
+            "Topics dedup overrides migration encountered an issue: {}",
+            e
+        );
+    }
+
+    if let Err(e) = migrate_topics_add_mcp_servers(conn) {
+        warn!("Topics mcp_servers migration encountered an issue: {}", e);
+    }
+
+    if let Err(e) = migrate_briefings_add_read_at(conn) {
+        warn!("Briefings read_at migration encountered an issue: {}", e);
+    }
+
+    if let Err(e) = migrate_briefings_add_report(conn) {
+        warn!("Briefings report migration encountered an issue: {}", e);
+    }
+
+    if let Err(e) = migrate_briefings_add_tag(conn) {
+        warn!("Briefings tag migration encountered an issue: {}", e);
+    }
+
+    if let Err(e) = migrate_briefings_add_run_id(conn) {
+        warn!("Briefings run_id migration encountered an issue: {}", e);
+    }
+
+    if let Err(e) = migrate_briefings_add_topics(conn) {
+        warn!("Briefings topics migration encountered an issue: {}", e);
+    }
+
+    if let Err(e) = migrate_briefings_add_failed_topics(conn) {
+        warn!(
+            "Briefings failed_topics migration encountered an issue: {}",
+            e
+        );
+    }
+
+    if let Err(e) = migrate_briefings_add_cover_image(conn) {
+        warn!(
+            "Briefings cover image migration encountered an issue: {}",
+            e
+        );
+    }
+
+    if let Err(e) = migrate_card_fingerprints_add_relevance(conn) {
+        warn!(
+            "Card fingerprints relevance migration encountered an issue: {}",
+            e
+        );
+    }
+
+    if let Err(e) = backfill_card_fingerprints(conn) {
+        warn!("Card fingerprints backfill encountered an issue: {}", e);
+    }
+
+    if let Err(e) = migrate_api_usage_add_batch_tokens(conn) {
+        warn!(
+            "API usage batch_tokens migration encountered an issue: {}",
+            e
+        );
+    }
+
+    if let Err(e) = migrate_research_logs_add_request_id(conn) {
+        warn!(
+            "Research logs request_id migration encountered an issue: {}",
+            e
+        );
     }
 
     Ok(())
 }
 
+/// Databases whose schema is known to be current in this process.
+static SCHEMA_READY: std::sync::Mutex<Vec<PathBuf>> = std::sync::Mutex::new(Vec::new());
+
 pub fn get_connection() -> Result<Connection> {
-    Connection::open(get_db_path())
+    open_database(&get_db_path())
+}
+
+/// Open the database at `db_path`, creating it and bringing its schema up to
+/// date the first time this process opens it.
+pub fn open_database(db_path: &Path) -> Result<Connection> {
+    // On Linux the data directory isn't created along with the config one
+    if let Some(parent) = db_path.parent() {
+        let _ = std::fs::create_dir_all(parent);
+    }
+    let conn = Connection::open(db_path)?;
+
+    let mut ready = SCHEMA_READY.lock().unwrap_or_else(|e| e.into_inner());
+    if !ready.iter().any(|path| path == db_path) {
+        ensure_schema(&conn)?;
+        ready.push(db_path.to_path_buf());
+    }
+    Ok(conn)
 }
 
 // ============================================================================
 // Topic CRUD operations
 // ============================================================================
 
+/// Parse a list column such as preferred_sources (JSON array, NULL for none)
+fn parse_string_list(raw: Option<String>) -> Vec<String> {
+    raw.and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default()
+}
+
+/// Serialize a list column for storage (NULL when empty)
+fn serialize_string_list(items: &[String]) -> Option<String> {
+    if items.is_empty() {
+        None
+    } else {
+        serde_json::to_string(items).ok()
+    }
+}
+
 /// Get all topics ordered by sort_order
 pub fn get_all_topics(conn: &Connection) -> std::result::Result<Vec<Topic>, String> {
     let mut stmt = conn
         .prepare(
-            "SELECT id, name, description, enabled, created_at, updated_at
+            "SELECT id, name, description, enabled, created_at, updated_at, preferred_sources,
+                snoozed_until, skip_next_run, dedup_days, dedup_threshold, mcp_servers
          FROM topics
          ORDER BY sort_order ASC, created_at ASC",
         )
@@ -107,7 +271,13 @@ pub fn get_all_topics(conn: &Connection) -> std::result::Result<Vec<Topic>, Stri
                 id: row.get(0)?,
                 name: row.get(1)?,
                 description: row.get(2)?,
+                preferred_sources: parse_string_list(row.get(6)?),
                 enabled: row.get::<_, i32>(3)? != 0,
+                snoozed_until: row.get(7)?,
+                skip_next_run: row.get::<_, i32>(8)? != 0,
+                dedup_days: row.get(9)?,
+                dedup_threshold: row.get(10)?,
+                mcp_servers: parse_string_list(row.get(11)?),
                 created_at: row.get(4)?,
                 updated_at: row.get(5)?,
             })
@@ -123,7 +293,8 @@ pub fn get_all_topics(conn: &Connection) -> std::result::Result<Vec<Topic>, Stri
 pub fn get_topic_by_id(conn: &Connection, id: &str) -> std::result::Result<Option<Topic>, String> {
     let mut stmt = conn
         .prepare(
-            "SELECT id, name, description, enabled, created_at, updated_at
+            "SELECT id, name, description, enabled, created_at, updated_at, preferred_sources,
+                snoozed_until, skip_next_run, dedup_days, dedup_threshold, mcp_servers
          FROM topics
          WHERE id = ?1",
         )
@@ -134,7 +305,13 @@ pub fn get_topic_by_id(conn: &Connection, id: &str) -> std::result::Result<Optio
             id: row.get(0)?,
             name: row.get(1)?,
             description: row.get(2)?,
+            preferred_sources: parse_string_list(row.get(6)?),
             enabled: row.get::<_, i32>(3)? != 0,
+            snoozed_until: row.get(7)?,
+            skip_next_run: row.get::<_, i32>(8)? != 0,
+            dedup_days: row.get(9)?,
+            dedup_threshold: row.get(10)?,
+            mcp_servers: parse_string_list(row.get(11)?),
             created_at: row.get(4)?,
             updated_at: row.get(5)?,
         })
@@ -154,8 +331,9 @@ pub fn insert_topic(
     sort_order: i32,
 ) -> std::result::Result<(), String> {
     conn.execute(
-        "INSERT INTO topics (id, name, description, enabled, sort_order, created_at, updated_at)
-         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        "INSERT INTO topics (id, name, description, enabled, sort_order, created_at, updated_at, preferred_sources,
+                             snoozed_until, skip_next_run, dedup_days, dedup_threshold, mcp_servers)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)",
         params![
             topic.id,
             topic.name,
@@ -164,6 +342,12 @@ pub fn insert_topic(
             sort_order,
             topic.created_at,
             topic.updated_at,
+            serialize_string_list(&topic.preferred_sources),
+            topic.snoozed_until,
+            if topic.skip_next_run { 1 } else { 0 },
+            topic.dedup_days,
+            topic.dedup_threshold,
+            serialize_string_list(&topic.mcp_servers),
         ],
     )
     .map_err(|e| format!("Failed to insert topic: {}", e))?;
@@ -176,13 +360,21 @@ pub fn update_topic(conn: &Connection, topic: &Topic) -> std::result::Result<(),
     let rows_affected = conn
         .execute(
             "UPDATE topics
-         SET name = ?1, description = ?2, enabled = ?3, updated_at = ?4
-         WHERE id = ?5",
+         SET name = ?1, description = ?2, enabled = ?3, updated_at = ?4, preferred_sources = ?5,
+             snoozed_until = ?6, skip_next_run = ?7, dedup_days = ?8, dedup_threshold = ?9,
+             mcp_servers = ?10
+         WHERE id = ?11",
             params![
                 topic.name,
                 topic.description,
                 if topic.enabled { 1 } else { 0 },
                 topic.updated_at,
+                serialize_string_list(&topic.preferred_sources),
+                topic.snoozed_until,
+                if topic.skip_next_run { 1 } else { 0 },
+                topic.dedup_days,
+                topic.dedup_threshold,
+                serialize_string_list(&topic.mcp_servers),
                 topic.id,
             ],
         )
@@ -233,6 +425,15 @@ pub fn get_next_sort_order(conn: &Connection) -> std::result::Result<i32, String
     }
 }
 
+/// Clear skip_next_run on every topic once a run over all topics has started
+pub fn clear_skip_next_run(conn: &Connection) -> std::result::Result<usize, String> {
+    conn.execute(
+        "UPDATE topics SET skip_next_run = 0 WHERE skip_next_run = 1",
+        [],
+    )
+    .map_err(|e| format!("Failed to clear skip_next_run: {}", e))
+}
+
 /// Check if a topic with the given name already exists (case-insensitive)
 pub fn topic_name_exists(conn: &Connection, name: &str) -> std::result::Result<bool, String> {
     let count: i32 = conn
@@ -258,7 +459,7 @@ pub fn get_chat_messages(
 ) -> std::result::Result<Vec<ChatMessage>, String> {
     let mut stmt = conn
         .prepare(
-            "SELECT id, briefing_id, card_index, role, content, tokens_used, created_at
+            "SELECT id, briefing_id, card_index, role, content, tokens_used, created_at, tools_used
          FROM chat_messages
          WHERE briefing_id = ?1 AND card_index = ?2
          ORDER BY created_at ASC",
@@ -275,6 +476,7 @@ pub fn get_chat_messages(
                 content: row.get(4)?,
                 tokens_used: row.get(5)?,
                 created_at: row.get(6)?,
+                tools_used: parse_tools_used(row.get(7)?),
             })
         })
         .map_err(|e| format!("Query failed: {}", e))?
@@ -293,10 +495,36 @@ pub fn insert_chat_message(
     content: &str,
     tokens_used: Option<i32>,
 ) -> std::result::Result<i64, String> {
+    insert_chat_message_with_tools(
+        conn,
+        briefing_id,
+        card_index,
+        role,
+        content,
+        tokens_used,
+        &[],
+    )
+}
+
+/// Insert a chat message, recording the tools used to write it
+pub fn insert_chat_message_with_tools(
+    conn: &Connection,
+    briefing_id: i64,
+    card_index: i32,
+    role: &str,
+    content: &str,
+    tokens_used: Option<i32>,
+    tools_used: &[String],
+) -> std::result::Result<i64, String> {
+    let tools_json = if tools_used.is_empty() {
+        None
+    } else {
+        Some(serde_json::to_string(tools_used).map_err(|e| e.to_string())?)
+    };
     conn.execute(
-        "INSERT INTO chat_messages (briefing_id, card_index, role, content, tokens_used)
-         VALUES (?1, ?2, ?3, ?4, ?5)",
-        params![briefing_id, card_index, role, content, tokens_used],
+        "INSERT INTO chat_messages (briefing_id, card_index, role, content, tokens_used, tools_used)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        params![briefing_id, card_index, role, content, tokens_used, tools_json],
     )
     .map_err(|e| format!("Failed to insert chat message: {}", e))?;
 
@@ -311,7 +539,7 @@ pub fn get_chat_message_by_id(
 ) -> std::result::Result<Option<ChatMessage>, String> {
     let mut stmt = conn
         .prepare(
-            "SELECT id, briefing_id, card_index, role, content, tokens_used, created_at
+            "SELECT id, briefing_id, card_index, role, content, tokens_used, created_at, tools_used
          FROM chat_messages
          WHERE id = ?1",
         )
@@ -326,6 +554,7 @@ pub fn get_chat_message_by_id(
             content: row.get(4)?,
             tokens_used: row.get(5)?,
             created_at: row.get(6)?,
+            tools_used: parse_tools_used(row.get(7)?),
         })
     });
 
@@ -336,6 +565,12 @@ pub fn get_chat_message_by_id(
     }
 }
 
+/// Tool names stored as a JSON array (NULL for none)
+fn parse_tools_used(json: Option<String>) -> Vec<String> {
+    json.and_then(|j| serde_json::from_str(&j).ok())
+        .unwrap_or_default()
+}
+
 /// Delete all chat messages for a specific card
 pub fn delete_chat_messages(
     conn: &Connection,
@@ -472,226 +707,2797 @@ pub fn toggle_bookmark(
 }
 
 // ============================================================================
-// Housekeeping / Cleanup functions
+// Read-later queue operations
 // ============================================================================
 
-/// Delete briefings older than `days`, excluding any briefings that have bookmarked cards.
-/// Returns the count of deleted briefings.
-pub fn cleanup_old_briefings(conn: &Connection, days: i32) -> std::result::Result<usize, String> {
-    // First get the IDs of briefings that will be deleted (for image cleanup)
+/// A card saved to the read-later queue
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReadLaterItem {
+    pub id: i64,
+    pub briefing_id: i64,
+    pub card_index: i32,
+    pub position: i32,
+    pub completed: bool,
+    pub completed_at: Option<String>,
+    pub forwarded_to: Option<String>, // External service the card was sent to (e.g. "pocket")
+    pub created_at: String,
+}
+
+fn read_later_from_row(row: &rusqlite::Row) -> rusqlite::Result<ReadLaterItem> {
+    Ok(ReadLaterItem {
+        id: row.get(0)?,
+        briefing_id: row.get(1)?,
+        card_index: row.get(2)?,
+        position: row.get(3)?,
+        completed: row.get::<_, i32>(4)? != 0,
+        completed_at: row.get(5)?,
+        forwarded_to: row.get(6)?,
+        created_at: row.get(7)?,
+    })
+}
+
+const READ_LATER_COLUMNS: &str =
+    "id, briefing_id, card_index, position, completed, completed_at, forwarded_to, created_at";
+
+/// Save a card to the end of the read-later queue.
+/// Saving a card that is already queued returns the existing item (re-opened if completed).
+pub fn save_for_later(
+    conn: &Connection,
+    briefing_id: i64,
+    card_index: i32,
+) -> std::result::Result<ReadLaterItem, String> {
+    conn.execute(
+        "INSERT INTO read_later (briefing_id, card_index, position)
+         VALUES (?1, ?2, (SELECT COALESCE(MAX(position), -1) + 1 FROM read_later))
+         ON CONFLICT(briefing_id, card_index)
+         DO UPDATE SET completed = 0, completed_at = NULL",
+        params![briefing_id, card_index],
+    )
+    .map_err(|e| format!("Failed to save card for later: {}", e))?;
+
+    conn.query_row(
+        &format!(
+            "SELECT {} FROM read_later WHERE briefing_id = ?1 AND card_index = ?2",
+            READ_LATER_COLUMNS
+        ),
+        params![briefing_id, card_index],
+        read_later_from_row,
+    )
+    .map_err(|e| format!("Failed to get read-later item: {}", e))
+}
+
+/// Get the read-later queue in order. Completed items are listed last when included.
+pub fn get_read_later_queue(
+    conn: &Connection,
+    include_completed: bool,
+) -> std::result::Result<Vec<ReadLaterItem>, String> {
     let mut stmt = conn
-        .prepare(
-            "SELECT id FROM briefings
-         WHERE date < date('now', '-' || ?1 || ' days')
-           AND id NOT IN (SELECT DISTINCT briefing_id FROM bookmarks)",
-        )
+        .prepare(&format!(
+            "SELECT {} FROM read_later
+         WHERE ?1 OR completed = 0
+         ORDER BY completed ASC, position ASC",
+            READ_LATER_COLUMNS
+        ))
         .map_err(|e| format!("Failed to prepare statement: {}", e))?;
 
-    let ids: Vec<i64> = stmt
-        .query_map([days], |row| row.get(0))
-        .map_err(|e| format!("Failed to query briefing IDs: {}", e))?
-        .filter_map(|r| r.ok())
-        .collect();
+    let items = stmt
+        .query_map([include_completed], read_later_from_row)
+        .map_err(|e| format!("Query failed: {}", e))?
+        .collect::<std::result::Result<Vec<_>, _>>()
+        .map_err(|e| format!("Failed to collect results: {}", e))?;
 
-    // Delete images for each briefing
-    for id in &ids {
-        if let Err(e) = crate::image_gen::delete_briefing_images(*id) {
-            tracing::warn!("Failed to delete images for briefing {}: {}", id, e);
-        }
-    }
+    Ok(items)
+}
 
-    let deleted = conn
+/// Mark a read-later item as done. Returns false if the item does not exist.
+pub fn complete_read_later(conn: &Connection, id: i64) -> std::result::Result<bool, String> {
+    let rows_affected = conn
         .execute(
-            "DELETE FROM briefings
-         WHERE date < date('now', '-' || ?1 || ' days')
-           AND id NOT IN (SELECT DISTINCT briefing_id FROM bookmarks)",
-            [days],
+            "UPDATE read_later SET completed = 1, completed_at = CURRENT_TIMESTAMP WHERE id = ?1",
+            [id],
         )
-        .map_err(|e| format!("Failed to cleanup old briefings: {}", e))?;
+        .map_err(|e| format!("Failed to complete read-later item: {}", e))?;
+    Ok(rows_affected > 0)
+}
 
-    Ok(deleted)
+/// Remove an item from the read-later queue
+pub fn remove_read_later(conn: &Connection, id: i64) -> std::result::Result<bool, String> {
+    let rows_affected = conn
+        .execute("DELETE FROM read_later WHERE id = ?1", [id])
+        .map_err(|e| format!("Failed to remove read-later item: {}", e))?;
+    Ok(rows_affected > 0)
 }
 
-/// Delete a specific briefing by ID.
-/// Returns true if a briefing was deleted, false if not found.
-pub fn delete_briefing(conn: &Connection, id: i64) -> std::result::Result<bool, String> {
-    // Delete associated images first
-    if let Err(e) = crate::image_gen::delete_briefing_images(id) {
-        tracing::warn!("Failed to delete images for briefing {}: {}", id, e);
+/// Reorder the read-later queue based on the provided item IDs
+pub fn reorder_read_later(conn: &Connection, ids: &[i64]) -> std::result::Result<(), String> {
+    for (index, id) in ids.iter().enumerate() {
+        conn.execute(
+            "UPDATE read_later SET position = ?1 WHERE id = ?2",
+            params![index as i32, id],
+        )
+        .map_err(|e| format!("Failed to update queue position: {}", e))?;
     }
 
-    let deleted = conn
-        .execute("DELETE FROM briefings WHERE id = ?1", [id])
-        .map_err(|e| format!("Failed to delete briefing: {}", e))?;
-
-    Ok(deleted > 0)
+    Ok(())
 }
 
-/// Get count of briefings that would be deleted by cleanup (for UI preview).
-/// Excludes briefings with bookmarked cards.
-pub fn count_cleanup_candidates(
+/// Record which external service a read-later item was forwarded to
+pub fn set_read_later_forwarded(
     conn: &Connection,
-    days: i32,
-) -> std::result::Result<usize, String> {
-    let count: i64 = conn
-        .query_row(
-            "SELECT COUNT(*) FROM briefings
-         WHERE date < date('now', '-' || ?1 || ' days')
-           AND id NOT IN (SELECT DISTINCT briefing_id FROM bookmarks)",
-            [days],
-            |row| row.get(0),
-        )
-        .map_err(|e| format!("Failed to count cleanup candidates: {}", e))?;
-
-    Ok(count as usize)
+    id: i64,
+    service: &str,
+) -> std::result::Result<(), String> {
+    conn.execute(
+        "UPDATE read_later SET forwarded_to = ?1 WHERE id = ?2",
+        params![service, id],
+    )
+    .map_err(|e| format!("Failed to update read-later item: {}", e))?;
+    Ok(())
 }
 
-/// Get total count of briefings in database.
-pub fn count_briefings(conn: &Connection) -> std::result::Result<usize, String> {
-    let count: i64 = conn
-        .query_row("SELECT COUNT(*) FROM briefings", [], |row| row.get(0))
-        .map_err(|e| format!("Failed to count briefings: {}", e))?;
+// ============================================================================
+// Source citation operations
+// ============================================================================
 
-    Ok(count as usize)
+/// A validated source citation stored in the sources table
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SourceRecord {
+    pub id: i64,
+    pub briefing_id: i64,
+    pub card_index: i32,
+    pub url: String,
+    pub final_url: Option<String>,
+    pub status_code: Option<i32>,
+    pub valid: bool,
+    pub error: Option<String>,
+    pub archived_text: Option<String>,
+    pub checked_at: String,
 }
 
-/// Count total cards across all briefings.
-pub fn count_cards(conn: &Connection) -> std::result::Result<usize, String> {
+/// Store the source validation results for every card in a briefing.
+/// Returns the number of rows inserted.
+pub fn insert_source_records(
+    conn: &Connection,
+    briefing_id: i64,
+    cards: &[crate::research::BriefingCard],
+) -> std::result::Result<usize, String> {
+    insert_source_records_from(conn, briefing_id, 0, cards)
+}
+
+/// Like [`insert_source_records`], for cards appended to a briefing from
+/// `first_index` on.
+fn insert_source_records_from(
+    conn: &Connection,
+    briefing_id: i64,
+    first_index: usize,
+    cards: &[crate::research::BriefingCard],
+) -> std::result::Result<usize, String> {
     let mut stmt = conn
-        .prepare("SELECT cards FROM briefings")
+        .prepare(
+            "INSERT INTO sources (briefing_id, card_index, url, final_url, status_code, valid, error, archived_text)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+        )
         .map_err(|e| format!("Failed to prepare statement: {}", e))?;
 
-    let mut total_cards = 0usize;
-    let rows = stmt
-        .query_map([], |row| {
-            let cards_json: String = row.get(0)?;
-            Ok(cards_json)
-        })
-        .map_err(|e| format!("Failed to query briefings: {}", e))?;
-
-    for cards_json in rows.flatten() {
-        // Parse JSON array and count elements
-        if let Ok(cards) = serde_json::from_str::<Vec<serde_json::Value>>(&cards_json) {
-            total_cards += cards.len();
+    let mut inserted = 0;
+    for (card_index, card) in cards.iter().enumerate() {
+        for check in &card.source_checks {
+            stmt.execute(params![
+                briefing_id,
+                (first_index + card_index) as i32,
+                check.url,
+                check.final_url,
+                check.status_code.map(|c| c as i32),
+                check.valid,
+                check.error,
+                check.snapshot,
+            ])
+            .map_err(|e| format!("Failed to insert source: {}", e))?;
+            inserted += 1;
         }
     }
 
-    Ok(total_cards)
+    Ok(inserted)
 }
 
-/// Check if a briefing has any bookmarked cards.
-pub fn briefing_has_bookmarks(
+/// Get all stored sources for a briefing, ordered by card
+pub fn get_sources_for_briefing(
     conn: &Connection,
     briefing_id: i64,
-) -> std::result::Result<bool, String> {
-    let count: i64 = conn
-        .query_row(
-            "SELECT COUNT(*) FROM bookmarks WHERE briefing_id = ?1",
-            [briefing_id],
-            |row| row.get(0),
+) -> std::result::Result<Vec<SourceRecord>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, briefing_id, card_index, url, final_url, status_code, valid, error, archived_text, checked_at
+         FROM sources
+         WHERE briefing_id = ?1
+         ORDER BY card_index ASC, id ASC",
         )
-        .map_err(|e| format!("Failed to check briefing bookmarks: {}", e))?;
+        .map_err(|e| format!("Failed to prepare statement: {}", e))?;
 
-    Ok(count > 0)
-}
+    let sources = stmt
+        .query_map([briefing_id], |row| {
+            Ok(SourceRecord {
+                id: row.get(0)?,
+                briefing_id: row.get(1)?,
+                card_index: row.get(2)?,
+                url: row.get(3)?,
+                final_url: row.get(4)?,
+                status_code: row.get(5)?,
+                valid: row.get(6)?,
+                error: row.get(7)?,
+                archived_text: row.get(8)?,
+                checked_at: row.get(9)?,
+            })
+        })
+        .map_err(|e| format!("Query failed: {}", e))?
+        .collect::<std::result::Result<Vec<_>, _>>()
+        .map_err(|e| format!("Failed to collect results: {}", e))?;
 
-/// Get recent card fingerprints for deduplication.
-/// Returns (title, topic, summary) for all cards from the last N days.
-pub fn get_recent_card_fingerprints(
-    conn: &Connection,
-    days: i32,
-) -> std::result::Result<Vec<crate::dedup::CardFingerprint>, String> {
-    let query = format!(
-        "SELECT cards FROM briefings WHERE date > datetime('now', '-{} days') ORDER BY date DESC",
-        days
-    );
+    Ok(sources)
+}
 
-    let mut stmt = conn
-        .prepare(&query)
-        .map_err(|e| format!("Failed to prepare query: {}", e))?;
+// ============================================================================
+// Discarded card operations
+// ============================================================================
 
-    let rows = stmt
+/// A card dropped at save time for falling below the min_relevance setting
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiscardedCard {
+    pub id: i64,
+    pub briefing_id: i64,
+    pub title: String,
+    pub topic: Option<String>,
+    pub relevance: String,
+    pub card: String, // JSON of the full BriefingCard
+    pub created_at: String,
+}
+
+/// Store cards that were filtered out of a briefing.
+/// Returns the number of rows inserted.
+pub fn insert_discarded_cards(
+    conn: &Connection,
+    briefing_id: i64,
+    cards: &[crate::research::BriefingCard],
+) -> std::result::Result<usize, String> {
+    let mut stmt = conn
+        .prepare(
+            "INSERT INTO discarded_cards (briefing_id, title, topic, relevance, card)
+         VALUES (?1, ?2, ?3, ?4, ?5)",
+        )
+        .map_err(|e| format!("Failed to prepare statement: {}", e))?;
+
+    for card in cards {
+        let card_json =
+            serde_json::to_string(card).map_err(|e| format!("Failed to serialize card: {}", e))?;
+        stmt.execute(params![
+            briefing_id,
+            card.title,
+            card.topic,
+            card.relevance,
+            card_json
+        ])
+        .map_err(|e| format!("Failed to insert discarded card: {}", e))?;
+    }
+
+    Ok(cards.len())
+}
+
+/// Get the most recently discarded cards
+pub fn get_discarded_cards(
+    conn: &Connection,
+    limit: i64,
+) -> std::result::Result<Vec<DiscardedCard>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, briefing_id, title, topic, relevance, card, created_at
+         FROM discarded_cards
+         ORDER BY id DESC
+         LIMIT ?1",
+        )
+        .map_err(|e| format!("Failed to prepare statement: {}", e))?;
+
+    let cards = stmt
+        .query_map([limit], |row| {
+            Ok(DiscardedCard {
+                id: row.get(0)?,
+                briefing_id: row.get(1)?,
+                title: row.get(2)?,
+                topic: row.get(3)?,
+                relevance: row.get(4)?,
+                card: row.get(5)?,
+                created_at: row.get(6)?,
+            })
+        })
+        .map_err(|e| format!("Query failed: {}", e))?
+        .collect::<std::result::Result<Vec<_>, _>>()
+        .map_err(|e| format!("Failed to collect results: {}", e))?;
+
+    Ok(cards)
+}
+
+/// Move a discarded card back into its briefing (appended as the last card).
+/// Returns the briefing ID, or None if the discarded card does not exist.
+pub fn restore_discarded_card(
+    conn: &Connection,
+    id: i64,
+) -> std::result::Result<Option<i64>, String> {
+    let result = conn.query_row(
+        "SELECT briefing_id, card FROM discarded_cards WHERE id = ?1",
+        [id],
+        |row| Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?)),
+    );
+    let (briefing_id, card_json) = match result {
+        Ok(row) => row,
+        Err(rusqlite::Error::QueryReturnedNoRows) => return Ok(None),
+        Err(e) => return Err(format!("Failed to get discarded card: {}", e)),
+    };
+
+    let cards_json: String = conn
+        .query_row(
+            "SELECT cards FROM briefings WHERE id = ?1",
+            [briefing_id],
+            |row| row.get(0),
+        )
+        .map_err(|e| format!("Failed to get briefing: {}", e))?;
+    let mut cards: Vec<serde_json::Value> =
+        serde_json::from_str(&cards_json).map_err(|e| format!("Failed to parse cards: {}", e))?;
+    let mut card: serde_json::Value = serde_json::from_str(&card_json)
+        .map_err(|e| format!("Failed to parse discarded card: {}", e))?;
+    // Shown last in a ranked briefing
+    if cards.iter().any(|c| c.get("rank").is_some()) {
+        card["rank"] = serde_json::json!(cards.len());
+    }
+    cards.push(card);
+    let cards_json =
+        serde_json::to_string(&cards).map_err(|e| format!("Failed to serialize cards: {}", e))?;
+
+    conn.execute(
+        "UPDATE briefings SET cards = ?1 WHERE id = ?2",
+        params![cards_json, briefing_id],
+    )
+    .map_err(|e| format!("Failed to update briefing: {}", e))?;
+    conn.execute("DELETE FROM discarded_cards WHERE id = ?1", [id])
+        .map_err(|e| format!("Failed to delete discarded card: {}", e))?;
+    if let Err(e) = index_card_fingerprints(conn, briefing_id) {
+        warn!("{}", e);
+    }
+
+    Ok(Some(briefing_id))
+}
+
+/// Set the display order of a briefing's cards. `order` lists every card
+/// index once, first card first; the cards themselves stay in place.
+pub fn reorder_cards(
+    conn: &Connection,
+    briefing_id: i64,
+    order: &[usize],
+) -> std::result::Result<(), String> {
+    let cards_json: String = conn
+        .query_row(
+            "SELECT cards FROM briefings WHERE id = ?1",
+            [briefing_id],
+            |row| row.get(0),
+        )
+        .map_err(|e| format!("Failed to get briefing: {}", e))?;
+    let mut cards: Vec<serde_json::Value> =
+        serde_json::from_str(&cards_json).map_err(|e| format!("Failed to parse cards: {}", e))?;
+
+    let mut sorted = order.to_vec();
+    sorted.sort_unstable();
+    if sorted != (0..cards.len()).collect::<Vec<_>>() {
+        return Err(format!(
+            "Card order must list each of the briefing's {} cards exactly once",
+            cards.len()
+        ));
+    }
+    for (rank, &index) in order.iter().enumerate() {
+        cards[index]["rank"] = serde_json::json!(rank);
+    }
+
+    let cards_json =
+        serde_json::to_string(&cards).map_err(|e| format!("Failed to serialize cards: {}", e))?;
+    conn.execute(
+        "UPDATE briefings SET cards = ?1 WHERE id = ?2",
+        params![cards_json, briefing_id],
+    )
+    .map_err(|e| format!("Failed to update briefing: {}", e))?;
+    Ok(())
+}
+
+/// Permanently delete a discarded card
+pub fn delete_discarded_card(conn: &Connection, id: i64) -> std::result::Result<bool, String> {
+    let rows_affected = conn
+        .execute("DELETE FROM discarded_cards WHERE id = ?1", [id])
+        .map_err(|e| format!("Failed to delete discarded card: {}", e))?;
+    Ok(rows_affected > 0)
+}
+
+// ============================================================================
+// API usage per credential profile
+// ============================================================================
+
+/// Token usage of one credential profile over a time window
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProfileUsage {
+    pub profile: String,
+    pub research_runs: i64,
+    pub research_tokens: i64,
+    pub batch_tokens: i64, // Research tokens billed at the Batch API discount
+    pub chat_messages: i64,
+    pub chat_tokens: i64,
+    pub total_tokens: i64,
+}
+
+/// Record tokens spent by a research run or chat message under a profile
+pub fn record_api_usage(
+    conn: &Connection,
+    profile: &str,
+    kind: &str,
+    briefing_id: Option<i64>,
+    tokens: i64,
+) -> std::result::Result<(), String> {
+    conn.execute(
+        "INSERT INTO api_usage (profile, kind, briefing_id, tokens) VALUES (?1, ?2, ?3, ?4)",
+        params![profile, kind, briefing_id, tokens],
+    )
+    .map_err(|e| format!("Failed to record API usage: {}", e))?;
+    Ok(())
+}
+
+/// Record tokens spent by a research run, `batch_tokens` of which were
+/// billed at the Batch API discount
+pub fn record_research_usage(
+    conn: &Connection,
+    profile: &str,
+    briefing_id: Option<i64>,
+    tokens: i64,
+    batch_tokens: i64,
+) -> std::result::Result<(), String> {
+    conn.execute(
+        "INSERT INTO api_usage (profile, kind, briefing_id, tokens, batch_tokens)
+         VALUES (?1, 'research', ?2, ?3, ?4)",
+        params![profile, briefing_id, tokens, batch_tokens],
+    )
+    .map_err(|e| format!("Failed to record API usage: {}", e))?;
+    Ok(())
+}
+
+/// Get token usage per profile for the last `days` days, highest first
+pub fn get_profile_usage(
+    conn: &Connection,
+    days: i64,
+) -> std::result::Result<Vec<ProfileUsage>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT profile,
+                SUM(kind = 'research'),
+                COALESCE(SUM(CASE WHEN kind = 'research' THEN tokens END), 0),
+                SUM(batch_tokens),
+                SUM(kind = 'chat'),
+                COALESCE(SUM(CASE WHEN kind = 'chat' THEN tokens END), 0),
+                SUM(tokens)
+         FROM api_usage
+         WHERE created_at >= datetime('now', '-' || ?1 || ' days')
+         GROUP BY profile
+         ORDER BY SUM(tokens) DESC",
+        )
+        .map_err(|e| format!("Failed to prepare statement: {}", e))?;
+
+    let usage = stmt
+        .query_map([days], |row| {
+            Ok(ProfileUsage {
+                profile: row.get(0)?,
+                research_runs: row.get(1)?,
+                research_tokens: row.get(2)?,
+                batch_tokens: row.get(3)?,
+                chat_messages: row.get(4)?,
+                chat_tokens: row.get(5)?,
+                total_tokens: row.get(6)?,
+            })
+        })
+        .map_err(|e| format!("Query failed: {}", e))?
+        .collect::<std::result::Result<Vec<_>, _>>()
+        .map_err(|e| format!("Failed to collect results: {}", e))?;
+
+    Ok(usage)
+}
+
+// ============================================================================
+// Topic suggestions (discovery)
+// ============================================================================
+
+/// A topic suggested from briefing history, chats and feedback
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TopicSuggestion {
+    pub id: i64,
+    pub name: String,
+    pub reason: Option<String>,
+    pub status: String, // "pending", "accepted" or "dismissed"
+    pub created_at: String,
+}
+
+/// Recent user activity that hints at interests beyond the current topics
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DiscoverySignals {
+    pub bookmarked_cards: Vec<String>, // "Topic: Title" of bookmarked cards
+    pub liked_cards: Vec<String>,      // "Topic: Title" of cards rated 4 or higher
+    pub chat_questions: Vec<String>,   // Questions the user asked about cards
+}
+
+/// Look up "Topic: Title" for a card in a briefing's cards JSON
+fn card_label(conn: &Connection, briefing_id: i64, card_index: i64) -> Option<String> {
+    let cards_json: String = conn
+        .query_row(
+            "SELECT cards FROM briefings WHERE id = ?1",
+            [briefing_id],
+            |row| row.get(0),
+        )
+        .ok()?;
+    let cards: Vec<serde_json::Value> = serde_json::from_str(&cards_json).ok()?;
+    let card = cards.get(usize::try_from(card_index).ok()?)?;
+    let title = card.get("title")?.as_str()?;
+    match card.get("topic").and_then(|t| t.as_str()) {
+        Some(topic) => Some(format!("{}: {}", topic, title)),
+        None => Some(title.to_string()),
+    }
+}
+
+/// Feedback ratings from the last `days` days grouped by the rated card's
+/// topic (lowercased): topic -> (sum of ratings, number of ratings)
+pub fn get_topic_feedback(
+    conn: &Connection,
+    days: i64,
+) -> std::result::Result<HashMap<String, (i64, i64)>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT f.card_index, f.rating, b.cards
+         FROM feedback f JOIN briefings b ON b.id = f.briefing_id
+         WHERE f.created_at >= datetime('now', '-' || ?1 || ' days')",
+        )
+        .map_err(|e| format!("Failed to prepare statement: {}", e))?;
+    let rows = stmt
+        .query_map([days], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, i64>(1)?,
+                row.get::<_, String>(2)?,
+            ))
+        })
+        .map_err(|e| format!("Query failed: {}", e))?
+        .collect::<std::result::Result<Vec<_>, _>>()
+        .map_err(|e| format!("Failed to collect results: {}", e))?;
+
+    let mut feedback: HashMap<String, (i64, i64)> = HashMap::new();
+    for (card_index, rating, cards_json) in rows {
+        let Ok(cards) = serde_json::from_str::<Vec<serde_json::Value>>(&cards_json) else {
+            continue;
+        };
+        let topic = usize::try_from(card_index)
+            .ok()
+            .and_then(|i| cards.get(i))
+            .and_then(|card| card["topic"].as_str());
+        if let Some(topic) = topic {
+            let entry = feedback.entry(topic.trim().to_lowercase()).or_default();
+            entry.0 += rating;
+            entry.1 += 1;
+        }
+    }
+    Ok(feedback)
+}
+
+/// Collect discovery signals from the last `days` days (at most `limit` of each kind)
+pub fn get_discovery_signals(
+    conn: &Connection,
+    days: i64,
+    limit: i64,
+) -> std::result::Result<DiscoverySignals, String> {
+    let card_refs = |sql: &str| -> std::result::Result<Vec<String>, String> {
+        let mut stmt = conn
+            .prepare(sql)
+            .map_err(|e| format!("Failed to prepare statement: {}", e))?;
+        let refs = stmt
+            .query_map(params![days, limit], |row| {
+                Ok((row.get::<_, i64>(0)?, row.get::<_, i64>(1)?))
+            })
+            .map_err(|e| format!("Query failed: {}", e))?
+            .collect::<std::result::Result<Vec<_>, _>>()
+            .map_err(|e| format!("Failed to collect results: {}", e))?;
+        Ok(refs
+            .into_iter()
+            .filter_map(|(briefing_id, card_index)| card_label(conn, briefing_id, card_index))
+            .collect())
+    };
+
+    let bookmarked_cards = card_refs(
+        "SELECT briefing_id, card_index FROM bookmarks
+         WHERE created_at >= datetime('now', '-' || ?1 || ' days')
+         ORDER BY created_at DESC LIMIT ?2",
+    )?;
+    let liked_cards = card_refs(
+        "SELECT briefing_id, card_index FROM feedback
+         WHERE rating >= 4 AND created_at >= datetime('now', '-' || ?1 || ' days')
+         ORDER BY created_at DESC LIMIT ?2",
+    )?;
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT content FROM chat_messages
+         WHERE role = 'user' AND created_at >= datetime('now', '-' || ?1 || ' days')
+         ORDER BY created_at DESC LIMIT ?2",
+        )
+        .map_err(|e| format!("Failed to prepare statement: {}", e))?;
+    let chat_questions = stmt
+        .query_map(params![days, limit], |row| row.get::<_, String>(0))
+        .map_err(|e| format!("Query failed: {}", e))?
+        .collect::<std::result::Result<Vec<_>, _>>()
+        .map_err(|e| format!("Failed to collect results: {}", e))?;
+
+    Ok(DiscoverySignals {
+        bookmarked_cards,
+        liked_cards,
+        chat_questions,
+    })
+}
+
+/// Store a suggestion unless one with the same name (any status) exists.
+/// Returns true if it was inserted.
+pub fn insert_topic_suggestion(
+    conn: &Connection,
+    name: &str,
+    reason: Option<&str>,
+) -> std::result::Result<bool, String> {
+    let rows_affected = conn
+        .execute(
+            "INSERT OR IGNORE INTO topic_suggestions (name, reason) VALUES (?1, ?2)",
+            params![name, reason],
+        )
+        .map_err(|e| format!("Failed to insert topic suggestion: {}", e))?;
+    Ok(rows_affected > 0)
+}
+
+/// Get suggestions with the given status, newest first
+pub fn get_topic_suggestions(
+    conn: &Connection,
+    status: &str,
+) -> std::result::Result<Vec<TopicSuggestion>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, name, reason, status, created_at
+         FROM topic_suggestions
+         WHERE status = ?1
+         ORDER BY id DESC",
+        )
+        .map_err(|e| format!("Failed to prepare statement: {}", e))?;
+
+    let suggestions = stmt
+        .query_map([status], |row| {
+            Ok(TopicSuggestion {
+                id: row.get(0)?,
+                name: row.get(1)?,
+                reason: row.get(2)?,
+                status: row.get(3)?,
+                created_at: row.get(4)?,
+            })
+        })
+        .map_err(|e| format!("Query failed: {}", e))?
+        .collect::<std::result::Result<Vec<_>, _>>()
+        .map_err(|e| format!("Failed to collect results: {}", e))?;
+
+    Ok(suggestions)
+}
+
+/// Names of every suggestion ever made, so discovery doesn't repeat itself
+pub fn get_topic_suggestion_names(conn: &Connection) -> std::result::Result<Vec<String>, String> {
+    let mut stmt = conn
+        .prepare("SELECT name FROM topic_suggestions ORDER BY id")
+        .map_err(|e| format!("Failed to prepare statement: {}", e))?;
+    let names = stmt
+        .query_map([], |row| row.get::<_, String>(0))
+        .map_err(|e| format!("Query failed: {}", e))?
+        .collect::<std::result::Result<Vec<_>, _>>()
+        .map_err(|e| format!("Failed to collect results: {}", e))?;
+    Ok(names)
+}
+
+/// Turn a pending suggestion into an enabled topic.
+/// Returns the new topic, or None if no pending suggestion has this ID.
+pub fn accept_topic_suggestion(
+    conn: &Connection,
+    id: i64,
+) -> std::result::Result<Option<Topic>, String> {
+    let result = conn.query_row(
+        "SELECT name FROM topic_suggestions WHERE id = ?1 AND status = 'pending'",
+        [id],
+        |row| row.get::<_, String>(0),
+    );
+    let name = match result {
+        Ok(name) => name,
+        Err(rusqlite::Error::QueryReturnedNoRows) => return Ok(None),
+        Err(e) => return Err(format!("Failed to get topic suggestion: {}", e)),
+    };
+
+    if topic_name_exists(conn, &name)? {
+        return Err(format!("Topic '{}' already exists", name));
+    }
+
+    let now = chrono::Utc::now().to_rfc3339();
+    let topic = Topic {
+        id: uuid::Uuid::new_v4().to_string(),
+        name,
+        description: None,
+        preferred_sources: Vec::new(),
+        enabled: true,
+        snoozed_until: None,
+        skip_next_run: false,
+        dedup_days: None,
+        dedup_threshold: None,
+        mcp_servers: Vec::new(),
+        created_at: now.clone(),
+        updated_at: now,
+    };
+    let sort_order = get_next_sort_order(conn)?;
+    insert_topic(conn, &topic, sort_order)?;
+
+    conn.execute(
+        "UPDATE topic_suggestions SET status = 'accepted' WHERE id = ?1",
+        [id],
+    )
+    .map_err(|e| format!("Failed to update topic suggestion: {}", e))?;
+
+    Ok(Some(topic))
+}
+
+/// Dismiss a pending suggestion. Dismissed names are never suggested again.
+pub fn dismiss_topic_suggestion(conn: &Connection, id: i64) -> std::result::Result<bool, String> {
+    let rows_affected = conn
+        .execute(
+            "UPDATE topic_suggestions SET status = 'dismissed' WHERE id = ?1 AND status = 'pending'",
+            [id],
+        )
+        .map_err(|e| format!("Failed to dismiss topic suggestion: {}", e))?;
+    Ok(rows_affected > 0)
+}
+
+/// When discovery last ran (the time its API usage was recorded), if ever
+pub fn last_topic_discovery(conn: &Connection) -> std::result::Result<Option<String>, String> {
+    conn.query_row(
+        "SELECT MAX(created_at) FROM api_usage WHERE kind = 'discovery'",
+        [],
+        |row| row.get(0),
+    )
+    .map_err(|e| format!("Failed to get last discovery run: {}", e))
+}
+
+// ============================================================================
+// Reminders (card actions)
+// ============================================================================
+
+/// A reminder scheduled by a card's remind_me action
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Reminder {
+    pub id: i64,
+    pub briefing_id: i64,
+    pub card_index: i64,
+    pub card_title: String,
+    pub message: String,
+    pub due_at: String, // UTC, "YYYY-MM-DD HH:MM:SS"
+    pub fired: bool,
+}
+
+fn reminder_from_row(row: &rusqlite::Row) -> rusqlite::Result<Reminder> {
+    Ok(Reminder {
+        id: row.get(0)?,
+        briefing_id: row.get(1)?,
+        card_index: row.get(2)?,
+        card_title: row.get(3)?,
+        message: row.get(4)?,
+        due_at: row.get(5)?,
+        fired: row.get::<_, i32>(6)? != 0,
+    })
+}
+
+/// Schedule a reminder. Returns its ID.
+pub fn insert_reminder(
+    conn: &Connection,
+    briefing_id: i64,
+    card_index: i64,
+    card_title: &str,
+    message: &str,
+    due_at: &str,
+) -> std::result::Result<i64, String> {
+    conn.execute(
+        "INSERT INTO reminders (briefing_id, card_index, card_title, message, due_at)
+         VALUES (?1, ?2, ?3, ?4, ?5)",
+        params![briefing_id, card_index, card_title, message, due_at],
+    )
+    .map_err(|e| format!("Failed to insert reminder: {}", e))?;
+    Ok(conn.last_insert_rowid())
+}
+
+/// Get reminders that haven't fired yet, soonest first
+pub fn get_pending_reminders(conn: &Connection) -> std::result::Result<Vec<Reminder>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, briefing_id, card_index, card_title, message, due_at, fired
+         FROM reminders
+         WHERE fired = 0
+         ORDER BY due_at",
+        )
+        .map_err(|e| format!("Failed to prepare statement: {}", e))?;
+
+    let reminders = stmt
+        .query_map([], reminder_from_row)
+        .map_err(|e| format!("Query failed: {}", e))?
+        .collect::<std::result::Result<Vec<_>, _>>()
+        .map_err(|e| format!("Failed to collect results: {}", e))?;
+
+    Ok(reminders)
+}
+
+/// Get unfired reminders whose due time has passed
+pub fn get_due_reminders(conn: &Connection) -> std::result::Result<Vec<Reminder>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, briefing_id, card_index, card_title, message, due_at, fired
+         FROM reminders
+         WHERE fired = 0 AND due_at <= datetime('now')
+         ORDER BY due_at",
+        )
+        .map_err(|e| format!("Failed to prepare statement: {}", e))?;
+
+    let reminders = stmt
+        .query_map([], reminder_from_row)
+        .map_err(|e| format!("Query failed: {}", e))?
+        .collect::<std::result::Result<Vec<_>, _>>()
+        .map_err(|e| format!("Failed to collect results: {}", e))?;
+
+    Ok(reminders)
+}
+
+/// Mark a reminder as delivered
+pub fn mark_reminder_fired(conn: &Connection, id: i64) -> std::result::Result<(), String> {
+    conn.execute("UPDATE reminders SET fired = 1 WHERE id = ?1", [id])
+        .map_err(|e| format!("Failed to update reminder: {}", e))?;
+    Ok(())
+}
+
+/// Cancel a reminder
+pub fn delete_reminder(conn: &Connection, id: i64) -> std::result::Result<bool, String> {
+    let rows_affected = conn
+        .execute("DELETE FROM reminders WHERE id = ?1", [id])
+        .map_err(|e| format!("Failed to delete reminder: {}", e))?;
+    Ok(rows_affected > 0)
+}
+
+// ============================================================================
+// Read state and notification queue
+// ============================================================================
+
+/// Mark a briefing as read. Returns false if the briefing doesn't exist.
+pub fn mark_briefing_read(
+    conn: &Connection,
+    briefing_id: i64,
+) -> std::result::Result<bool, String> {
+    let rows_affected = conn
+        .execute(
+            "UPDATE briefings SET read_at = COALESCE(read_at, CURRENT_TIMESTAMP) WHERE id = ?1",
+            [briefing_id],
+        )
+        .map_err(|e| format!("Failed to mark briefing read: {}", e))?;
+    Ok(rows_affected > 0)
+}
+
+/// Whether a briefing has been marked read
+pub fn is_briefing_read(conn: &Connection, briefing_id: i64) -> std::result::Result<bool, String> {
+    conn.query_row(
+        "SELECT read_at IS NOT NULL FROM briefings WHERE id = ?1",
+        [briefing_id],
+        |row| row.get(0),
+    )
+    .optional()
+    .map(|read| read.unwrap_or(false))
+    .map_err(|e| format!("Failed to check read state: {}", e))
+}
+
+/// A notification waiting in the queue
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueuedNotification {
+    pub id: i64,
+    pub briefing_id: Option<i64>,
+    pub title: String,
+    pub body: String,
+    pub deliver_at: String, // UTC, "YYYY-MM-DD HH:MM:SS"
+}
+
+/// Queue a notification for delivery at `deliver_at`. Returns its ID.
+pub fn queue_notification(
+    conn: &Connection,
+    briefing_id: Option<i64>,
+    title: &str,
+    body: &str,
+    deliver_at: &str,
+) -> std::result::Result<i64, String> {
+    conn.execute(
+        "INSERT INTO notification_queue (briefing_id, title, body, deliver_at)
+         VALUES (?1, ?2, ?3, ?4)",
+        params![briefing_id, title, body, deliver_at],
+    )
+    .map_err(|e| format!("Failed to queue notification: {}", e))?;
+    Ok(conn.last_insert_rowid())
+}
+
+/// Get undelivered notifications whose delivery time has passed
+pub fn get_due_notifications(
+    conn: &Connection,
+) -> std::result::Result<Vec<QueuedNotification>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, briefing_id, title, body, deliver_at
+         FROM notification_queue
+         WHERE delivered = 0 AND deliver_at <= datetime('now')
+         ORDER BY deliver_at",
+        )
+        .map_err(|e| format!("Failed to prepare statement: {}", e))?;
+
+    let notifications = stmt
+        .query_map([], |row| {
+            Ok(QueuedNotification {
+                id: row.get(0)?,
+                briefing_id: row.get(1)?,
+                title: row.get(2)?,
+                body: row.get(3)?,
+                deliver_at: row.get(4)?,
+            })
+        })
+        .map_err(|e| format!("Query failed: {}", e))?
+        .collect::<std::result::Result<Vec<_>, _>>()
+        .map_err(|e| format!("Failed to collect results: {}", e))?;
+
+    Ok(notifications)
+}
+
+/// Move a queued notification to a new delivery time
+pub fn reschedule_notification(
+    conn: &Connection,
+    id: i64,
+    deliver_at: &str,
+) -> std::result::Result<(), String> {
+    conn.execute(
+        "UPDATE notification_queue SET deliver_at = ?1 WHERE id = ?2",
+        params![deliver_at, id],
+    )
+    .map_err(|e| format!("Failed to reschedule notification: {}", e))?;
+    Ok(())
+}
+
+/// Mark a queued notification as delivered
+pub fn mark_notification_delivered(conn: &Connection, id: i64) -> std::result::Result<(), String> {
+    conn.execute(
+        "UPDATE notification_queue SET delivered = 1 WHERE id = ?1",
+        [id],
+    )
+    .map_err(|e| format!("Failed to update notification: {}", e))?;
+    Ok(())
+}
+
+// ============================================================================
+// Activity log (adaptive schedule)
+// ============================================================================
+
+/// Log that the user was around at `at` (local time, "YYYY-MM-DD HH:MM:SS").
+/// Activity of the same kind within half an hour of the last is skipped,
+/// and entries older than 60 days are dropped. Returns whether it was logged.
+pub fn record_activity(
+    conn: &Connection,
+    kind: &str,
+    at: &str,
+) -> std::result::Result<bool, String> {
+    let recent: bool = conn
+        .query_row(
+            "SELECT EXISTS(SELECT 1 FROM activity_log
+             WHERE kind = ?1 AND occurred_at > datetime(?2, '-30 minutes'))",
+            params![kind, at],
+            |row| row.get(0),
+        )
+        .map_err(|e| format!("Failed to check activity log: {}", e))?;
+    if recent {
+        return Ok(false);
+    }
+    conn.execute(
+        "INSERT INTO activity_log (kind, occurred_at) VALUES (?1, ?2)",
+        params![kind, at],
+    )
+    .map_err(|e| format!("Failed to log activity: {}", e))?;
+    conn.execute(
+        "DELETE FROM activity_log WHERE occurred_at < datetime(?1, '-60 days')",
+        [at],
+    )
+    .map_err(|e| format!("Failed to prune activity log: {}", e))?;
+    Ok(true)
+}
+
+/// Times of the activity logged since `since` (local time), oldest first.
+pub fn get_activity_since(
+    conn: &Connection,
+    since: &str,
+) -> std::result::Result<Vec<String>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT occurred_at FROM activity_log WHERE occurred_at >= ?1 ORDER BY occurred_at",
+        )
+        .map_err(|e| format!("Failed to prepare statement: {}", e))?;
+    let times = stmt
+        .query_map([since], |row| row.get(0))
+        .map_err(|e| format!("Failed to query activity log: {}", e))?
+        .collect::<Result<Vec<String>>>()
+        .map_err(|e| format!("Failed to read activity log: {}", e))?;
+    Ok(times)
+}
+
+/// How long the latest `limit` topic research runs took, newest first.
+pub fn get_recent_research_times_ms(
+    conn: &Connection,
+    limit: usize,
+) -> std::result::Result<Vec<i64>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT research_time_ms FROM briefings
+             WHERE tag IS NULL AND research_time_ms > 0
+             ORDER BY date DESC LIMIT ?1",
+        )
+        .map_err(|e| format!("Failed to prepare statement: {}", e))?;
+    let times = stmt
+        .query_map([limit as i64], |row| row.get(0))
+        .map_err(|e| format!("Failed to query research times: {}", e))?
+        .collect::<Result<Vec<i64>>>()
+        .map_err(|e| format!("Failed to read research times: {}", e))?;
+    Ok(times)
+}
+
+// ============================================================================
+// Content cache operations (offline research)
+// ============================================================================
+
+/// Content fetched by a research tool, kept so offline runs can reuse it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CachedContent {
+    pub kind: String,
+    pub cache_key: String,
+    pub topic: Option<String>,
+    pub content: String,
+    pub fetched_at: String,
+}
+
+fn cached_content_from_row(row: &rusqlite::Row) -> rusqlite::Result<CachedContent> {
+    Ok(CachedContent {
+        kind: row.get(0)?,
+        cache_key: row.get(1)?,
+        topic: row.get(2)?,
+        content: row.get(3)?,
+        fetched_at: row.get(4)?,
+    })
+}
+
+/// Store fetched content, replacing any earlier copy of the same key.
+pub fn upsert_cached_content(
+    conn: &Connection,
+    kind: &str,
+    cache_key: &str,
+    topic: Option<&str>,
+    content: &str,
+) -> std::result::Result<(), String> {
+    conn.execute(
+        "INSERT INTO content_cache (kind, cache_key, topic, content, fetched_at)
+         VALUES (?1, ?2, ?3, ?4, CURRENT_TIMESTAMP)
+         ON CONFLICT(kind, cache_key) DO UPDATE SET
+             topic = COALESCE(excluded.topic, content_cache.topic),
+             content = excluded.content,
+             fetched_at = excluded.fetched_at",
+        params![kind, cache_key, topic, content],
+    )
+    .map_err(|e| format!("Failed to cache content: {}", e))?;
+    Ok(())
+}
+
+/// Get cached content for a key if it was fetched within `max_age_hours`.
+pub fn get_cached_content(
+    conn: &Connection,
+    kind: &str,
+    cache_key: &str,
+    max_age_hours: i64,
+) -> std::result::Result<Option<CachedContent>, String> {
+    let result = conn.query_row(
+        "SELECT kind, cache_key, topic, content, fetched_at FROM content_cache
+         WHERE kind = ?1 AND cache_key = ?2
+           AND fetched_at >= datetime('now', '-' || ?3 || ' hours')",
+        params![kind, cache_key, max_age_hours],
+        cached_content_from_row,
+    );
+
+    match result {
+        Ok(content) => Ok(Some(content)),
+        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+        Err(e) => Err(format!("Failed to get cached content: {}", e)),
+    }
+}
+
+/// Get the most recent cached content fetched for a topic within `max_age_hours`.
+pub fn get_cached_content_for_topic(
+    conn: &Connection,
+    topic: &str,
+    max_age_hours: i64,
+    limit: usize,
+) -> std::result::Result<Vec<CachedContent>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT kind, cache_key, topic, content, fetched_at FROM content_cache
+         WHERE topic = ?1 AND fetched_at >= datetime('now', '-' || ?2 || ' hours')
+         ORDER BY fetched_at DESC
+         LIMIT ?3",
+        )
+        .map_err(|e| format!("Failed to prepare statement: {}", e))?;
+
+    let items = stmt
+        .query_map(
+            params![topic, max_age_hours, limit as i64],
+            cached_content_from_row,
+        )
+        .map_err(|e| format!("Failed to query cached content: {}", e))?
+        .collect::<Result<Vec<_>>>()
+        .map_err(|e| format!("Failed to read cached content: {}", e))?;
+
+    Ok(items)
+}
+
+/// Delete cached content older than `days`. Returns the number of rows removed.
+pub fn cleanup_content_cache(conn: &Connection, days: i64) -> std::result::Result<usize, String> {
+    conn.execute(
+        "DELETE FROM content_cache WHERE fetched_at < datetime('now', '-' || ?1 || ' days')",
+        [days],
+    )
+    .map_err(|e| format!("Failed to clean up content cache: {}", e))
+}
+
+// ============================================================================
+// Web cache operations (fetch_webpage)
+// ============================================================================
+
+/// A cached `fetch_webpage` result with its HTTP validators
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebCacheEntry {
+    pub url: String,
+    pub content: String,
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+    pub age_secs: i64, // Seconds since the page was fetched or revalidated
+}
+
+/// Get the cached copy of a URL, whatever its age, marking it as used.
+pub fn get_web_cache(
+    conn: &Connection,
+    url: &str,
+) -> std::result::Result<Option<WebCacheEntry>, String> {
+    let entry = conn
+        .query_row(
+            "SELECT url, content, etag, last_modified,
+                    CAST((julianday('now') - julianday(fetched_at)) * 86400 AS INTEGER)
+             FROM web_cache WHERE url = ?1",
+            [url],
+            |row| {
+                Ok(WebCacheEntry {
+                    url: row.get(0)?,
+                    content: row.get(1)?,
+                    etag: row.get(2)?,
+                    last_modified: row.get(3)?,
+                    age_secs: row.get(4)?,
+                })
+            },
+        )
+        .optional()
+        .map_err(|e| format!("Failed to get web cache entry: {}", e))?;
+
+    if entry.is_some() {
+        conn.execute(
+            "UPDATE web_cache SET accessed_at = CURRENT_TIMESTAMP WHERE url = ?1",
+            [url],
+        )
+        .map_err(|e| format!("Failed to update web cache entry: {}", e))?;
+    }
+
+    Ok(entry)
+}
+
+/// Store a fetched page, replacing any earlier copy of the URL.
+pub fn upsert_web_cache(
+    conn: &Connection,
+    url: &str,
+    content: &str,
+    etag: Option<&str>,
+    last_modified: Option<&str>,
+) -> std::result::Result<(), String> {
+    conn.execute(
+        "INSERT INTO web_cache (url, content, etag, last_modified, size, fetched_at, accessed_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, CURRENT_TIMESTAMP, CURRENT_TIMESTAMP)
+         ON CONFLICT(url) DO UPDATE SET
+             content = excluded.content,
+             etag = excluded.etag,
+             last_modified = excluded.last_modified,
+             size = excluded.size,
+             fetched_at = excluded.fetched_at,
+             accessed_at = excluded.accessed_at",
+        params![url, content, etag, last_modified, content.len() as i64],
+    )
+    .map_err(|e| format!("Failed to cache web page: {}", e))?;
+    Ok(())
+}
+
+/// Restart a cached page's TTL after the server confirmed it is unchanged.
+pub fn touch_web_cache(conn: &Connection, url: &str) -> std::result::Result<(), String> {
+    conn.execute(
+        "UPDATE web_cache SET fetched_at = CURRENT_TIMESTAMP, accessed_at = CURRENT_TIMESTAMP
+         WHERE url = ?1",
+        [url],
+    )
+    .map_err(|e| format!("Failed to revalidate web cache entry: {}", e))?;
+    Ok(())
+}
+
+/// Number of cached pages and their total size in bytes.
+pub fn web_cache_stats(conn: &Connection) -> std::result::Result<(usize, i64), String> {
+    conn.query_row(
+        "SELECT COUNT(*), COALESCE(SUM(size), 0) FROM web_cache",
+        [],
+        |row| Ok((row.get::<_, i64>(0)? as usize, row.get(1)?)),
+    )
+    .map_err(|e| format!("Failed to get web cache stats: {}", e))
+}
+
+/// Delete pages not fetched within `days`, then the least recently used
+/// pages until the cache fits in `max_bytes`. Returns the number removed.
+pub fn cleanup_web_cache(
+    conn: &Connection,
+    days: i64,
+    max_bytes: i64,
+) -> std::result::Result<usize, String> {
+    let expired = conn
+        .execute(
+            "DELETE FROM web_cache WHERE fetched_at < datetime('now', '-' || ?1 || ' days')",
+            [days],
+        )
+        .map_err(|e| format!("Failed to clean up web cache: {}", e))?;
+
+    // Keep the most recently used pages whose running total fits the cap
+    let evicted = conn
+        .execute(
+            "DELETE FROM web_cache WHERE url IN (
+                 SELECT url FROM (
+                     SELECT url, SUM(size) OVER (ORDER BY accessed_at DESC, url) AS running
+                     FROM web_cache
+                 ) WHERE running > ?1
+             )",
+            [max_bytes],
+        )
+        .map_err(|e| format!("Failed to trim web cache: {}", e))?;
+
+    Ok(expired + evicted)
+}
+
+// ============================================================================
+// Briefings
+// ============================================================================
+
+/// A briefing about to be saved.
+#[derive(Debug, Clone, Default)]
+pub struct NewBriefing<'a> {
+    /// Research run that produced it; None for briefings not tied to a run
+    pub run_id: Option<&'a str>,
+    pub date: &'a str,
+    pub title: &'a str,
+    /// JSON array of cards
+    pub cards: &'a str,
+    pub research_time_ms: i64,
+    pub model_used: &'a str,
+    pub total_tokens: i64,
+    pub tag: Option<&'a str>,
+    /// JSON array of the run's per-topic breakdown
+    pub topics: Option<&'a str>,
+    /// JSON array of the topics that failed; None if all succeeded
+    pub failed_topics: Option<&'a str>,
+}
+
+/// Save a briefing, returning its ID and whether it was created. Saving a
+/// run that is already saved (a retried save) returns the existing briefing
+/// instead of inserting a duplicate.
+pub fn insert_briefing(
+    conn: &Connection,
+    briefing: &NewBriefing,
+) -> std::result::Result<(i64, bool), String> {
+    let run_id = briefing.run_id.filter(|id| !id.is_empty());
+    if let Some(run_id) = run_id {
+        let existing = saved_run(conn, run_id)?;
+        if let Some(id) = existing {
+            info!(
+                "Research run {} is already saved as briefing {}",
+                run_id, id
+            );
+            return Ok((id, false));
+        }
+    }
+
+    conn.execute(
+        "INSERT INTO briefings (date, title, cards, research_time_ms, model_used, total_tokens, tag, run_id, topics, failed_topics)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+        params![
+            briefing.date,
+            briefing.title,
+            briefing.cards,
+            briefing.research_time_ms,
+            briefing.model_used,
+            briefing.total_tokens,
+            briefing.tag,
+            run_id,
+            briefing.topics,
+            briefing.failed_topics,
+        ],
+    )
+    .map_err(|e| format!("Failed to insert briefing: {}", e))?;
+    Ok((conn.last_insert_rowid(), true))
+}
+
+/// The per-topic breakdown of the run that produced a briefing. Empty for
+/// briefings saved before breakdowns were recorded.
+pub fn get_briefing_topics(
+    conn: &Connection,
+    briefing_id: i64,
+) -> std::result::Result<Vec<crate::research::TopicResult>, String> {
+    let topics: Option<String> = conn
+        .query_row(
+            "SELECT topics FROM briefings WHERE id = ?1",
+            [briefing_id],
+            |row| row.get(0),
+        )
+        .map_err(|e| match e {
+            rusqlite::Error::QueryReturnedNoRows => format!("Briefing {} not found", briefing_id),
+            e => format!("Failed to load briefing topics: {}", e),
+        })?;
+    match topics {
+        Some(json) => serde_json::from_str(&json)
+            .map_err(|e| format!("Failed to parse briefing topics: {}", e)),
+        None => Ok(Vec::new()),
+    }
+}
+
+/// The topics that failed in the run that produced a briefing. Empty if
+/// every topic was researched.
+pub fn get_briefing_failed_topics(
+    conn: &Connection,
+    briefing_id: i64,
+) -> std::result::Result<Vec<crate::research::FailedTopic>, String> {
+    let failed: Option<String> = conn
+        .query_row(
+            "SELECT failed_topics FROM briefings WHERE id = ?1",
+            [briefing_id],
+            |row| row.get(0),
+        )
+        .map_err(|e| match e {
+            rusqlite::Error::QueryReturnedNoRows => format!("Briefing {} not found", briefing_id),
+            e => format!("Failed to load failed topics: {}", e),
+        })?;
+    match failed {
+        Some(json) => {
+            serde_json::from_str(&json).map_err(|e| format!("Failed to parse failed topics: {}", e))
+        }
+        None => Ok(Vec::new()),
+    }
+}
+
+/// The briefing a research run was saved as, either on its own or merged
+/// into an earlier briefing of the same day.
+fn saved_run(conn: &Connection, run_id: &str) -> std::result::Result<Option<i64>, String> {
+    conn.query_row(
+        "SELECT id FROM briefings WHERE run_id = ?1
+         UNION ALL
+         SELECT briefing_id FROM briefing_runs WHERE run_id = ?1
+         LIMIT 1",
+        [run_id],
+        |row| row.get::<_, i64>(0),
+    )
+    .optional()
+    .map_err(|e| format!("Failed to look up briefing: {}", e))
+}
+
+/// The latest topic-research briefing saved on the same day as `date`, if
+/// any. Ad-hoc and meta-briefings are never merged into.
+fn same_day_briefing(conn: &Connection, date: &str) -> std::result::Result<Option<i64>, String> {
+    conn.query_row(
+        "SELECT id FROM briefings
+         WHERE substr(date, 1, 10) = substr(?1, 1, 10) AND tag IS NULL
+         ORDER BY id DESC LIMIT 1",
+        [date],
+        |row| row.get::<_, i64>(0),
+    )
+    .optional()
+    .map_err(|e| format!("Failed to look up today's briefing: {}", e))
+}
+
+/// Everything saved when a research run finishes.
+#[derive(Debug, Clone)]
+pub struct BriefingRecord<'a> {
+    pub briefing: NewBriefing<'a>,
+    /// The saved cards, for their source checks
+    pub cards: &'a [crate::research::BriefingCard],
+    /// Cards dropped by the relevance filter; empty unless they're kept
+    pub discarded: &'a [crate::research::BriefingCard],
+    /// Credential profile billed for the run
+    pub profile: &'a str,
+    pub batch_tokens: i64,
+    /// When the run started; research logs written since then belong to it
+    pub log_since: &'a str,
+    /// Merge into the day's earlier briefing (`merge_same_day_runs`),
+    /// dropping new cards at least this similar to its cards
+    pub merge_same_day: Option<f64>,
+}
+
+/// What [`persist_briefing`] saved.
+#[derive(Debug, Clone)]
+pub struct SavedBriefing {
+    pub id: i64,
+    /// Whether the run was saved now, as a new briefing or merged into an
+    /// earlier one; false for a retried save of a run that is already saved
+    pub created: bool,
+    /// Every card of the briefing, when the run was merged into an earlier
+    /// briefing of the same day
+    pub merged_cards: Option<Vec<crate::research::BriefingCard>>,
+    /// Index of the first card this run added (past the earlier runs' cards
+    /// when merged)
+    pub first_new_card: usize,
+}
+
+/// Save a finished run in one transaction: the briefing, its source checks,
+/// discarded cards and API usage, its research logs and the run report.
+/// Either all of it is saved or none of it is. A retried save of the same
+/// run only relinks its logs and rebuilds the report (see
+/// [`insert_briefing`]). With `merge_same_day`, a topic-research run is
+/// appended to the day's earlier briefing if there is one.
+pub fn persist_briefing(
+    conn: &Connection,
+    record: &BriefingRecord,
+) -> std::result::Result<SavedBriefing, String> {
+    let tx = conn
+        .unchecked_transaction()
+        .map_err(|e| format!("Failed to start transaction: {}", e))?;
+
+    // A retried save of a run goes through insert_briefing, merged or not
+    let already_saved = match record.briefing.run_id.filter(|id| !id.is_empty()) {
+        Some(run_id) => saved_run(&tx, run_id)?.is_some(),
+        None => false,
+    };
+    let merge_into = match record.merge_same_day {
+        Some(_) if record.briefing.tag.is_none() && !already_saved => {
+            same_day_briefing(&tx, record.briefing.date)?
+        }
+        _ => None,
+    };
+    if let (Some(briefing_id), Some(threshold)) = (merge_into, record.merge_same_day) {
+        let (cards, first_new_card) = merge_into_briefing(&tx, briefing_id, record, threshold)?;
+        crate::research_log::attach_logs_to_briefing(&tx, briefing_id, record.log_since)?;
+        crate::research_log::save_run_report(&tx, briefing_id)?;
+        tx.commit()
+            .map_err(|e| format!("Failed to save briefing: {}", e))?;
+        return Ok(SavedBriefing {
+            id: briefing_id,
+            created: true,
+            merged_cards: Some(cards),
+            first_new_card,
+        });
+    }
+
+    let (briefing_id, created) = insert_briefing(&tx, &record.briefing)?;
+    if created {
+        insert_source_records(&tx, briefing_id, record.cards)?;
+        // Only costs deduplication if it fails (e.g. a database the app
+        // hasn't migrated yet); the briefing is still saved
+        if let Err(e) = index_card_fingerprints(&tx, briefing_id) {
+            warn!("{}", e);
+        }
+        if !record.discarded.is_empty() {
+            insert_discarded_cards(&tx, briefing_id, record.discarded)?;
+        }
+        record_research_usage(
+            &tx,
+            record.profile,
+            Some(briefing_id),
+            record.briefing.total_tokens,
+            record.batch_tokens,
+        )?;
+    }
+    crate::research_log::attach_logs_to_briefing(&tx, briefing_id, record.log_since)?;
+    crate::research_log::save_run_report(&tx, briefing_id)?;
+
+    tx.commit()
+        .map_err(|e| format!("Failed to save briefing: {}", e))?;
+    Ok(SavedBriefing {
+        id: briefing_id,
+        created,
+        merged_cards: None,
+        first_new_card: 0,
+    })
+}
+
+/// Append a run's cards to an earlier briefing, leaving out cards at least
+/// `threshold` similar to the briefing's own. Appended cards keep their
+/// order below the existing ones, so card indices (and with them feedback,
+/// bookmarks and chats) stay put. Returns all of the briefing's cards and
+/// the index of the first appended one.
+fn merge_into_briefing(
+    conn: &Connection,
+    briefing_id: i64,
+    record: &BriefingRecord,
+    threshold: f64,
+) -> std::result::Result<(Vec<crate::research::BriefingCard>, usize), String> {
+    use crate::research::{BriefingCard, FailedTopic, TopicResult};
+
+    let (cards_json, topics_json, failed_json): (String, Option<String>, Option<String>) = conn
+        .query_row(
+            "SELECT cards, topics, failed_topics FROM briefings WHERE id = ?1",
+            [briefing_id],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        )
+        .map_err(|e| format!("Failed to load briefing {}: {}", briefing_id, e))?;
+    let mut cards: Vec<BriefingCard> = serde_json::from_str(&cards_json)
+        .map_err(|e| format!("Failed to parse briefing cards: {}", e))?;
+    fn parse_list<T: serde::de::DeserializeOwned>(json: Option<&str>) -> Vec<T> {
+        json.and_then(|json| serde_json::from_str(json).ok())
+            .unwrap_or_default()
+    }
+    let mut topics: Vec<TopicResult> = parse_list(topics_json.as_deref());
+    let failed: Vec<FailedTopic> = parse_list(failed_json.as_deref());
+    let run_topics: Vec<TopicResult> = parse_list(record.briefing.topics);
+    let run_failed: Vec<FailedTopic> = parse_list(record.briefing.failed_topics);
+
+    let existing: Vec<crate::dedup::CardFingerprint> = cards
+        .iter()
+        .map(crate::dedup::CardFingerprint::from_card)
+        .collect();
+    let first_index = cards.len();
+    let mut added = crate::dedup::filter_duplicates(record.cards.to_vec(), &existing, threshold);
+    for (i, card) in added.iter_mut().enumerate() {
+        card.rank = Some((first_index + card.rank.map_or(i, |rank| rank as usize)) as u32);
+    }
+    info!(
+        "Merging {} of {} new cards into briefing {}",
+        added.len(),
+        record.cards.len(),
+        briefing_id
+    );
+    cards.extend(added.iter().cloned());
+
+    // Topics researched again are no longer failed unless they failed again
+    let mut failed: Vec<FailedTopic> = failed
+        .into_iter()
+        .filter(|f| !run_topics.iter().any(|t| t.topic == f.topic))
+        .collect();
+    failed.extend(run_failed);
+    for mut topic in run_topics {
+        topic.card_indices = (first_index..cards.len())
+            .filter(|&i| {
+                cards[i]
+                    .topic
+                    .trim()
+                    .eq_ignore_ascii_case(topic.topic.trim())
+            })
+            .collect();
+        topics.push(topic);
+    }
+
+    conn.execute(
+        "UPDATE briefings
+         SET cards = ?1, topics = ?2, failed_topics = ?3,
+             research_time_ms = COALESCE(research_time_ms, 0) + ?4,
+             total_tokens = COALESCE(total_tokens, 0) + ?5
+         WHERE id = ?6",
+        params![
+            serde_json::to_string(&cards)
+                .map_err(|e| format!("Failed to serialize cards: {}", e))?,
+            serde_json::to_string(&topics)
+                .map_err(|e| format!("Failed to serialize topic results: {}", e))?,
+            if failed.is_empty() {
+                None
+            } else {
+                Some(
+                    serde_json::to_string(&failed)
+                        .map_err(|e| format!("Failed to serialize failed topics: {}", e))?,
+                )
+            },
+            record.briefing.research_time_ms,
+            record.briefing.total_tokens,
+            briefing_id,
+        ],
+    )
+    .map_err(|e| format!("Failed to merge into briefing {}: {}", briefing_id, e))?;
+    if let Some(run_id) = record.briefing.run_id.filter(|id| !id.is_empty()) {
+        conn.execute(
+            "INSERT INTO briefing_runs (run_id, briefing_id) VALUES (?1, ?2)",
+            params![run_id, briefing_id],
+        )
+        .map_err(|e| format!("Failed to record merged run: {}", e))?;
+    }
+
+    insert_source_records_from(conn, briefing_id, first_index, &added)?;
+    if let Err(e) = index_card_fingerprints(conn, briefing_id) {
+        warn!("{}", e);
+    }
+    if !record.discarded.is_empty() {
+        insert_discarded_cards(conn, briefing_id, record.discarded)?;
+    }
+    record_research_usage(
+        conn,
+        record.profile,
+        Some(briefing_id),
+        record.briefing.total_tokens,
+        record.batch_tokens,
+    )?;
+    Ok((cards, first_index))
+}
+
+/// Store the cards of a saved briefing again once header images are
+/// generated. If that fails the `generated` image files are deleted, so no
+/// image files are left that no card points to. Images of earlier runs
+/// merged into the same briefing are left alone.
+pub fn save_briefing_images(
+    conn: &Connection,
+    briefing_id: i64,
+    cards: &[crate::research::BriefingCard],
+    generated: &[PathBuf],
+) -> std::result::Result<(), String> {
+    let saved = update_briefing_cards(conn, briefing_id, cards);
+    if saved.is_err() {
+        for path in generated {
+            if let Err(e) = std::fs::remove_file(path) {
+                warn!("Failed to delete unsaved image {:?}: {}", path, e);
+            }
+        }
+    }
+    saved
+}
+
+/// Replace the cards of a briefing, in a transaction.
+fn update_briefing_cards(
+    conn: &Connection,
+    briefing_id: i64,
+    cards: &[crate::research::BriefingCard],
+) -> std::result::Result<(), String> {
+    let cards_json = serde_json::to_string(cards)
+        .map_err(|e| format!("Failed to serialize updated cards: {}", e))?;
+    let tx = conn
+        .unchecked_transaction()
+        .map_err(|e| format!("Failed to start transaction: {}", e))?;
+    let updated = tx
+        .execute(
+            "UPDATE briefings SET cards = ?1 WHERE id = ?2",
+            params![cards_json, briefing_id],
+        )
+        .map_err(|e| format!("Failed to update briefing with image paths: {}", e))?;
+    if updated != 1 {
+        return Err(format!("Briefing {} not found", briefing_id));
+    }
+    tx.commit()
+        .map_err(|e| format!("Failed to update briefing with image paths: {}", e))
+}
+
+/// Store a saved briefing's cover image prompt and path. If that fails the
+/// cover image is deleted, like card images in [`save_briefing_images`].
+pub fn set_briefing_cover(
+    conn: &Connection,
+    briefing_id: i64,
+    prompt: &str,
+    path: &std::path::Path,
+) -> std::result::Result<(), String> {
+    let saved = conn
+        .execute(
+            "UPDATE briefings SET cover_image_prompt = ?1, cover_image_path = ?2 WHERE id = ?3",
+            params![prompt, path.to_string_lossy(), briefing_id],
+        )
+        .map_err(|e| format!("Failed to update briefing with cover image: {}", e));
+    match saved {
+        Ok(1) => Ok(()),
+        result => {
+            if let Err(delete_error) = std::fs::remove_file(path) {
+                warn!("Failed to delete unsaved cover image: {}", delete_error);
+            }
+            result.and(Err(format!("Briefing {} not found", briefing_id)))
+        }
+    }
+}
+
+// ============================================================================
+// Housekeeping / Cleanup functions
+// ============================================================================
+
+/// Most recent briefings first.
+pub fn get_briefings(conn: &Connection, limit: i32) -> std::result::Result<Vec<Briefing>, String> {
+    get_briefings_filtered(conn, &BriefingFilter::default(), limit)
+}
+
+/// Narrows a briefing list. Every field is optional; set fields must all
+/// match.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BriefingFilter {
+    /// First day included (YYYY-MM-DD)
+    pub from: Option<String>,
+    /// Last day included (YYYY-MM-DD)
+    pub to: Option<String>,
+    /// Only briefings with a card on this topic (case-insensitive)
+    pub topic: Option<String>,
+    /// Only briefings with a card at least this relevant (low, medium, high)
+    pub min_relevance: Option<String>,
+    /// Only briefings written by this model
+    pub model: Option<String>,
+}
+
+/// A `YYYY-MM-DD` date filter value.
+fn filter_date<'a>(name: &str, value: &'a str) -> std::result::Result<&'a str, String> {
+    let value = value.trim();
+    chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .map(|_| value)
+        .map_err(|_| format!("Invalid {} date '{}' (expected YYYY-MM-DD)", name, value))
+}
+
+/// Most recent briefings first, narrowed by `filter`. Topic and relevance
+/// are matched against the card fingerprints, so a card must have both;
+/// weekly and monthly meta-briefings have none and never match them.
+pub fn get_briefings_filtered(
+    conn: &Connection,
+    filter: &BriefingFilter,
+    limit: i32,
+) -> std::result::Result<Vec<Briefing>, String> {
+    let mut conditions: Vec<String> = Vec::new();
+    let mut values: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
+    if let Some(from) = &filter.from {
+        conditions.push("date >= ?".to_string());
+        values.push(Box::new(filter_date("from", from)?.to_string()));
+    }
+    if let Some(to) = &filter.to {
+        // Dates carry a time, so everything before the next day
+        conditions.push("date < date(?, '+1 day')".to_string());
+        values.push(Box::new(filter_date("to", to)?.to_string()));
+    }
+    if let Some(model) = &filter.model {
+        conditions.push("model_used = ?".to_string());
+        values.push(Box::new(model.trim().to_string()));
+    }
+
+    let mut card_conditions = Vec::new();
+    if let Some(topic) = &filter.topic {
+        card_conditions.push("f.topic = ? COLLATE NOCASE");
+        values.push(Box::new(topic.trim().to_string()));
+    }
+    if let Some(level) = &filter.min_relevance {
+        let level = level.trim().to_lowercase();
+        if !crate::research::RELEVANCE_LEVELS.contains(&level.as_str()) {
+            return Err(format!(
+                "Invalid relevance '{}' (expected {})",
+                level,
+                crate::research::RELEVANCE_LEVELS.join(", ")
+            ));
+        }
+        card_conditions.push("f.relevance >= ?");
+        values.push(Box::new(crate::research::relevance_rank(&level) as i64));
+    }
+    if !card_conditions.is_empty() {
+        conditions.push(format!(
+            "EXISTS (SELECT 1 FROM card_fingerprints f WHERE f.briefing_id = briefings.id AND {})",
+            card_conditions.join(" AND ")
+        ));
+    }
+
+    let mut query = String::from(
+        "SELECT id, date, title, cards, research_time_ms, model_used, total_tokens, tag,
+         cover_image_path
+         FROM briefings",
+    );
+    if !conditions.is_empty() {
+        query.push_str(" WHERE ");
+        query.push_str(&conditions.join(" AND "));
+    }
+    query.push_str(" ORDER BY date DESC LIMIT ?");
+    values.push(Box::new(limit));
+
+    let mut stmt = conn
+        .prepare(&query)
+        .map_err(|e| format!("Failed to prepare statement: {}", e))?;
+
+    let briefings = stmt
+        .query_map(
+            rusqlite::params_from_iter(values.iter().map(|v| v.as_ref())),
+            |row| {
+                Ok(Briefing {
+                    id: row.get(0)?,
+                    date: row.get(1)?,
+                    title: row.get(2)?,
+                    cards: row.get(3)?,
+                    research_time_ms: row.get(4)?,
+                    model_used: row.get(5)?,
+                    total_tokens: row.get(6)?,
+                    tag: row.get(7)?,
+                    cover_image_path: row.get(8)?,
+                })
+            },
+        )
+        .map_err(|e| format!("Query failed: {}", e))?
+        .collect::<Result<Vec<_>>>()
+        .map_err(|e| format!("Failed to collect results: {}", e))?;
+
+    Ok(briefings)
+}
+
+pub fn get_briefing(conn: &Connection, id: i64) -> std::result::Result<Briefing, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, date, title, cards, research_time_ms, model_used, total_tokens, tag,
+         cover_image_path
+         FROM briefings
+         WHERE id = ?1",
+        )
+        .map_err(|e| format!("Failed to prepare statement: {}", e))?;
+
+    stmt.query_row([id], |row| {
+        Ok(Briefing {
+            id: row.get(0)?,
+            date: row.get(1)?,
+            title: row.get(2)?,
+            cards: row.get(3)?,
+            research_time_ms: row.get(4)?,
+            model_used: row.get(5)?,
+            total_tokens: row.get(6)?,
+            tag: row.get(7)?,
+            cover_image_path: row.get(8)?,
+        })
+    })
+    .map_err(|e| format!("Briefing not found: {}", e))
+}
+
+/// Date of the most recent briefing with `tag` (e.g. "weekly"), if any.
+pub fn last_briefing_date_with_tag(
+    conn: &Connection,
+    tag: &str,
+) -> std::result::Result<Option<String>, String> {
+    conn.query_row(
+        "SELECT MAX(date) FROM briefings WHERE tag = ?1",
+        [tag],
+        |row| row.get(0),
+    )
+    .map_err(|e| format!("Failed to get last {} briefing: {}", tag, e))
+}
+
+/// Date of the newest topic research briefing (not ad-hoc or meta), RFC 3339.
+pub fn last_research_date(conn: &Connection) -> std::result::Result<Option<String>, String> {
+    conn.query_row(
+        "SELECT MAX(date) FROM briefings WHERE tag IS NULL",
+        [],
+        |row| row.get(0),
+    )
+    .map_err(|e| format!("Failed to get last research briefing: {}", e))
+}
+
+/// Delete briefings older than `days`, excluding any briefings that have bookmarked cards.
+/// Returns the count of deleted briefings.
+pub fn cleanup_old_briefings(conn: &Connection, days: i32) -> std::result::Result<usize, String> {
+    // First get the IDs of briefings that will be deleted (for image cleanup)
+    let mut stmt = conn
+        .prepare(
+            "SELECT id FROM briefings
+         WHERE date < date('now', '-' || ?1 || ' days')
+           AND id NOT IN (SELECT DISTINCT briefing_id FROM bookmarks)",
+        )
+        .map_err(|e| format!("Failed to prepare statement: {}", e))?;
+
+    let ids: Vec<i64> = stmt
+        .query_map([days], |row| row.get(0))
+        .map_err(|e| format!("Failed to query briefing IDs: {}", e))?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    let mut deleted = 0;
+    for id in ids {
+        if delete_briefing(conn, id)? {
+            deleted += 1;
+        }
+    }
+    Ok(deleted)
+}
+
+/// Tables holding rows for a briefing, removed with it (foreign key
+/// cascades are not enabled). Reminders are kept: they outlive the briefing.
+const BRIEFING_CHILD_TABLES: [(&str, &str); 9] = [
+    ("sources", "sources"),
+    ("card_fingerprints", "card fingerprints"),
+    ("read_later", "read-later items"),
+    ("discarded_cards", "discarded cards"),
+    ("chat_messages", "chat history"),
+    ("feedback", "feedback"),
+    ("bookmarks", "bookmarks"),
+    ("topic_activity", "topic activity"),
+    ("briefing_runs", "merged runs"),
+];
+
+/// Delete a specific briefing by ID, with its images, narration audio, chat
+/// history, bookmarks and other rows. Research logs are kept but unlinked.
+/// Returns true if a briefing was deleted, false if not found.
+pub fn delete_briefing(conn: &Connection, id: i64) -> std::result::Result<bool, String> {
+    // Delete associated images and narration audio first
+    if let Err(e) = crate::image_gen::delete_briefing_images(id) {
+        tracing::warn!("Failed to delete images for briefing {}: {}", id, e);
+    }
+    if let Err(e) = crate::tts::delete_briefing_audio(id) {
+        tracing::warn!("Failed to delete audio for briefing {}: {}", id, e);
+    }
+
+    for (table, label) in BRIEFING_CHILD_TABLES {
+        conn.execute(
+            &format!("DELETE FROM {} WHERE briefing_id = ?1", table),
+            [id],
+        )
+        .map_err(|e| format!("Failed to delete {}: {}", label, e))?;
+    }
+    conn.execute(
+        "UPDATE research_logs SET briefing_id = NULL WHERE briefing_id = ?1",
+        [id],
+    )
+    .map_err(|e| format!("Failed to unlink research logs: {}", e))?;
+    conn.execute(
+        "DELETE FROM notification_queue WHERE briefing_id = ?1 AND delivered = 0",
+        [id],
+    )
+    .map_err(|e| format!("Failed to delete queued notifications: {}", e))?;
+
+    let deleted = conn
+        .execute("DELETE FROM briefings WHERE id = ?1", [id])
+        .map_err(|e| format!("Failed to delete briefing: {}", e))?;
+
+    Ok(deleted > 0)
+}
+
+/// Tables whose rows move to the kept briefing when duplicates are merged.
+/// The other child tables only hold data derived from the (identical) cards,
+/// so the duplicate's copies are dropped.
+const MERGED_CHILD_TABLES: [&str; 9] = [
+    "feedback",
+    "chat_messages",
+    "bookmarks",
+    "read_later",
+    "reminders",
+    "research_logs",
+    "api_usage",
+    "notification_queue",
+    "briefing_runs",
+];
+
+/// Merge exact duplicate briefings: same title, tag, cards and day. The
+/// oldest of each group is kept and gets the others' feedback, chats,
+/// bookmarks, read-later items, reminders, logs and usage; the rest are
+/// deleted. Returns how many briefings were removed.
+pub fn merge_duplicate_briefings(conn: &Connection) -> std::result::Result<usize, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, title, COALESCE(tag, ''), cards, substr(date, 1, 10)
+             FROM briefings ORDER BY id ASC",
+        )
+        .map_err(|e| format!("Failed to prepare statement: {}", e))?;
+    let rows = stmt
+        .query_map([], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                (
+                    row.get::<_, String>(1)?,
+                    row.get::<_, String>(2)?,
+                    row.get::<_, String>(3)?,
+                    row.get::<_, String>(4)?,
+                ),
+            ))
+        })
+        .map_err(|e| format!("Failed to query briefings: {}", e))?
+        .collect::<Result<Vec<_>>>()
+        .map_err(|e| format!("Failed to read briefing: {}", e))?;
+
+    let mut kept: HashMap<(String, String, String, String), i64> = HashMap::new();
+    let mut duplicates: Vec<(i64, i64)> = Vec::new();
+    for (id, key) in rows {
+        match kept.get(&key) {
+            Some(&keep) => duplicates.push((id, keep)),
+            None => {
+                kept.insert(key, id);
+            }
+        }
+    }
+    if duplicates.is_empty() {
+        return Ok(0);
+    }
+
+    let tx = conn
+        .unchecked_transaction()
+        .map_err(|e| format!("Failed to start transaction: {}", e))?;
+    for &(duplicate, keep) in &duplicates {
+        for table in MERGED_CHILD_TABLES {
+            // Rows the kept briefing already has (same bookmarked card) stay
+            // behind and are deleted with the duplicate
+            tx.execute(
+                &format!(
+                    "UPDATE OR IGNORE {} SET briefing_id = ?1 WHERE briefing_id = ?2",
+                    table
+                ),
+                [keep, duplicate],
+            )
+            .map_err(|e| format!("Failed to merge {}: {}", table, e))?;
+            tx.execute(
+                &format!("DELETE FROM {} WHERE briefing_id = ?1", table),
+                [duplicate],
+            )
+            .map_err(|e| format!("Failed to merge {}: {}", table, e))?;
+        }
+        for (table, label) in BRIEFING_CHILD_TABLES {
+            tx.execute(
+                &format!("DELETE FROM {} WHERE briefing_id = ?1", table),
+                [duplicate],
+            )
+            .map_err(|e| format!("Failed to delete {}: {}", label, e))?;
+        }
+        tx.execute("DELETE FROM briefings WHERE id = ?1", [duplicate])
+            .map_err(|e| format!("Failed to delete briefing: {}", e))?;
+    }
+    tx.commit()
+        .map_err(|e| format!("Failed to commit merge: {}", e))?;
+
+    for &(duplicate, keep) in &duplicates {
+        info!("Merged duplicate briefing {} into {}", duplicate, keep);
+        // Identical cards point at the kept briefing's files, if any
+        if let Err(e) = crate::image_gen::delete_briefing_images(duplicate) {
+            tracing::warn!("Failed to delete images for briefing {}: {}", duplicate, e);
+        }
+        if let Err(e) = crate::tts::delete_briefing_audio(duplicate) {
+            tracing::warn!("Failed to delete audio for briefing {}: {}", duplicate, e);
+        }
+    }
+    Ok(duplicates.len())
+}
+
+/// What deleting a briefing would remove, for confirmation prompts.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BriefingDeletion {
+    pub id: i64,
+    pub date: String,
+    pub title: String,
+    pub cards: usize,
+    pub bookmarks: usize,
+    pub chat_messages: usize,
+}
+
+/// Preview deleting briefing `id`, or None if it doesn't exist.
+pub fn briefing_deletion(
+    conn: &Connection,
+    id: i64,
+) -> std::result::Result<Option<BriefingDeletion>, String> {
+    let Some((date, title, cards)) = conn
+        .query_row(
+            "SELECT date, title, cards FROM briefings WHERE id = ?1",
+            [id],
+            |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, String>(2)?,
+                ))
+            },
+        )
+        .optional()
+        .map_err(|e| format!("Failed to get briefing: {}", e))?
+    else {
+        return Ok(None);
+    };
+
+    let count = |table: &str| -> std::result::Result<usize, String> {
+        conn.query_row(
+            &format!("SELECT COUNT(*) FROM {} WHERE briefing_id = ?1", table),
+            [id],
+            |row| row.get::<_, i64>(0),
+        )
+        .map(|n| n as usize)
+        .map_err(|e| format!("Failed to count {}: {}", table, e))
+    };
+
+    Ok(Some(BriefingDeletion {
+        id,
+        date,
+        title,
+        cards: serde_json::from_str::<Vec<serde_json::Value>>(&cards)
+            .map(|c| c.len())
+            .unwrap_or(0),
+        bookmarks: count("bookmarks")?,
+        chat_messages: count("chat_messages")?,
+    }))
+}
+
+/// Briefings dated before `before` (YYYY-MM-DD), oldest first. Briefings
+/// with bookmarked cards are left out unless `include_bookmarked` is set.
+pub fn briefings_before(
+    conn: &Connection,
+    before: &str,
+    include_bookmarked: bool,
+) -> std::result::Result<Vec<BriefingDeletion>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT id FROM briefings
+         WHERE date < ?1
+           AND (?2 OR id NOT IN (SELECT DISTINCT briefing_id FROM bookmarks))
+         ORDER BY date ASC, id ASC",
+        )
+        .map_err(|e| format!("Failed to prepare statement: {}", e))?;
+
+    let ids: Vec<i64> = stmt
+        .query_map(params![before, include_bookmarked], |row| row.get(0))
+        .map_err(|e| format!("Failed to query briefing IDs: {}", e))?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    let mut briefings = Vec::with_capacity(ids.len());
+    for id in ids {
+        if let Some(briefing) = briefing_deletion(conn, id)? {
+            briefings.push(briefing);
+        }
+    }
+    Ok(briefings)
+}
+
+/// Get count of briefings that would be deleted by cleanup (for UI preview).
+/// Excludes briefings with bookmarked cards.
+pub fn count_cleanup_candidates(
+    conn: &Connection,
+    days: i32,
+) -> std::result::Result<usize, String> {
+    let count: i64 = conn
+        .query_row(
+            "SELECT COUNT(*) FROM briefings
+         WHERE date < date('now', '-' || ?1 || ' days')
+           AND id NOT IN (SELECT DISTINCT briefing_id FROM bookmarks)",
+            [days],
+            |row| row.get(0),
+        )
+        .map_err(|e| format!("Failed to count cleanup candidates: {}", e))?;
+
+    Ok(count as usize)
+}
+
+/// Get total count of briefings in database.
+pub fn count_briefings(conn: &Connection) -> std::result::Result<usize, String> {
+    let count: i64 = conn
+        .query_row("SELECT COUNT(*) FROM briefings", [], |row| row.get(0))
+        .map_err(|e| format!("Failed to count briefings: {}", e))?;
+
+    Ok(count as usize)
+}
+
+/// Count total cards across all briefings.
+pub fn count_cards(conn: &Connection) -> std::result::Result<usize, String> {
+    let mut stmt = conn
+        .prepare("SELECT cards FROM briefings")
+        .map_err(|e| format!("Failed to prepare statement: {}", e))?;
+
+    let mut total_cards = 0usize;
+    let rows = stmt
+        .query_map([], |row| {
+            let cards_json: String = row.get(0)?;
+            Ok(cards_json)
+        })
+        .map_err(|e| format!("Failed to query briefings: {}", e))?;
+
+    for cards_json in rows.flatten() {
+        // Parse JSON array and count elements
+        if let Ok(cards) = serde_json::from_str::<Vec<serde_json::Value>>(&cards_json) {
+            total_cards += cards.len();
+        }
+    }
+
+    Ok(total_cards)
+}
+
+/// Check if a briefing has any bookmarked cards.
+pub fn briefing_has_bookmarks(
+    conn: &Connection,
+    briefing_id: i64,
+) -> std::result::Result<bool, String> {
+    let count: i64 = conn
+        .query_row(
+            "SELECT COUNT(*) FROM bookmarks WHERE briefing_id = ?1",
+            [briefing_id],
+            |row| row.get(0),
+        )
+        .map_err(|e| format!("Failed to check briefing bookmarks: {}", e))?;
+
+    Ok(count > 0)
+}
+
+/// Get recent card fingerprints for deduplication.
+/// Returns (title, topic, summary) for all cards from the last N days.
+pub fn get_recent_card_fingerprints(
+    conn: &Connection,
+    days: i32,
+) -> std::result::Result<Vec<crate::dedup::CardFingerprint>, String> {
+    get_recent_card_fingerprints_for(conn, &crate::dedup::DedupPolicy::new(days, 0.0))
+}
+
+/// Title, topic and summary of a stored card (a JSON object), or None if it
+/// has no title.
+fn fingerprint_of(card: &serde_json::Value) -> Option<crate::dedup::CardFingerprint> {
+    let field = |name: &str| {
+        card.get(name)
+            .and_then(|v| v.as_str())
+            .unwrap_or("")
+            .to_string()
+    };
+    let title = field("title");
+    if title.is_empty() {
+        return None;
+    }
+    Some(crate::dedup::CardFingerprint {
+        title,
+        topic: field("topic"),
+        summary: field("summary"),
+    })
+}
+
+/// Write the fingerprints of a saved briefing's cards, replacing any it
+/// had. Weekly and monthly meta-briefings restate daily cards, so they get
+/// none. Returns how many were written.
+pub fn index_card_fingerprints(
+    conn: &Connection,
+    briefing_id: i64,
+) -> std::result::Result<usize, String> {
+    let (date, tag, cards_json): (String, Option<String>, String) = conn
+        .query_row(
+            "SELECT date, tag, cards FROM briefings WHERE id = ?1",
+            [briefing_id],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        )
+        .map_err(|e| format!("Failed to get briefing {}: {}", briefing_id, e))?;
+
+    conn.execute(
+        "DELETE FROM card_fingerprints WHERE briefing_id = ?1",
+        [briefing_id],
+    )
+    .map_err(|e| format!("Failed to index card fingerprints: {}", e))?;
+    if matches!(tag.as_deref(), Some("weekly" | "monthly")) {
+        return Ok(0);
+    }
+
+    let cards: Vec<serde_json::Value> = serde_json::from_str(&cards_json).unwrap_or_default();
+    let mut stmt = conn
+        .prepare(
+            "INSERT INTO card_fingerprints
+             (briefing_id, card_index, title, title_hash, topic, summary, relevance, date)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+        )
+        .map_err(|e| format!("Failed to index card fingerprints: {}", e))?;
+    let mut written = 0;
+    for (index, card) in cards.iter().enumerate() {
+        let Some(fingerprint) = fingerprint_of(card) else {
+            continue;
+        };
+        stmt.execute(params![
+            briefing_id,
+            index as i64,
+            fingerprint.title,
+            crate::dedup::title_hash(&fingerprint.title),
+            fingerprint.topic,
+            fingerprint.summary,
+            crate::research::relevance_rank(
+                card.get("relevance").and_then(|v| v.as_str()).unwrap_or("")
+            ) as i64,
+            date,
+        ])
+        .map_err(|e| format!("Failed to index card fingerprints: {}", e))?;
+        written += 1;
+    }
+    Ok(written)
+}
+
+/// Migration: fingerprint the cards of briefings saved before
+/// card_fingerprints existed (or by a CLI whose database wasn't migrated
+/// yet). Briefings without titled cards are checked again on each start,
+/// which costs next to nothing.
+fn backfill_card_fingerprints(conn: &Connection) -> std::result::Result<usize, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT id FROM briefings
+             WHERE (tag IS NULL OR tag NOT IN ('weekly', 'monthly'))
+               AND id NOT IN (SELECT briefing_id FROM card_fingerprints)",
+        )
+        .map_err(|e| format!("Failed to prepare statement: {}", e))?;
+    let ids: Vec<i64> = stmt
+        .query_map([], |row| row.get(0))
+        .map_err(|e| format!("Failed to query briefings: {}", e))?
+        .collect::<Result<Vec<_>>>()
+        .map_err(|e| format!("Failed to read briefing: {}", e))?;
+
+    let tx = conn
+        .unchecked_transaction()
+        .map_err(|e| format!("Failed to start transaction: {}", e))?;
+    let mut written = 0;
+    for id in &ids {
+        written += index_card_fingerprints(&tx, *id)?;
+    }
+    tx.commit()
+        .map_err(|e| format!("Failed to save card fingerprints: {}", e))?;
+    if written > 0 {
+        info!(
+            "Indexed {} card fingerprints from {} briefings",
+            written,
+            ids.len()
+        );
+    }
+    Ok(written)
+}
+
+/// Get recent card fingerprints for deduplication, newest first, keeping
+/// each card only if its briefing falls inside its topic's dedup window.
+pub fn get_recent_card_fingerprints_for(
+    conn: &Connection,
+    policy: &crate::dedup::DedupPolicy,
+) -> std::result::Result<Vec<crate::dedup::CardFingerprint>, String> {
+    let query = format!(
+        "SELECT title, topic, summary, julianday('now') - julianday(date) FROM card_fingerprints
+         WHERE date > datetime('now', '-{} days')
+         ORDER BY date DESC, briefing_id DESC, card_index ASC",
+        policy.max_days()
+    );
+    let mut stmt = match conn.prepare(&query) {
+        Ok(stmt) => stmt,
+        Err(e) => {
+            // A database the app hasn't migrated yet has no fingerprints table
+            warn!(
+                "Card fingerprints unavailable ({}), reading briefings instead",
+                e
+            );
+            return get_recent_card_fingerprints_from_briefings(conn, policy);
+        }
+    };
+    let rows = stmt
+        .query_map([], |row| {
+            Ok((
+                crate::dedup::CardFingerprint {
+                    title: row.get(0)?,
+                    topic: row.get(1)?,
+                    summary: row.get(2)?,
+                },
+                row.get::<_, Option<f64>>(3)?.unwrap_or(0.0),
+            ))
+        })
+        .map_err(|e| format!("Failed to query card fingerprints: {}", e))?
+        .collect::<Result<Vec<_>>>()
+        .map_err(|e| format!("Failed to read card fingerprint: {}", e))?;
+
+    Ok(rows
+        .into_iter()
+        .filter(|(fingerprint, age_days)| {
+            *age_days < f64::from(policy.days_for(&fingerprint.topic))
+        })
+        .map(|(fingerprint, _)| fingerprint)
+        .collect())
+}
+
+/// [`get_recent_card_fingerprints_for`] by parsing each recent briefing's
+/// cards.
+fn get_recent_card_fingerprints_from_briefings(
+    conn: &Connection,
+    policy: &crate::dedup::DedupPolicy,
+) -> std::result::Result<Vec<crate::dedup::CardFingerprint>, String> {
+    // Weekly and monthly meta-briefings restate daily cards, so they are skipped
+    let query = format!(
+        "SELECT cards, julianday('now') - julianday(date) FROM briefings
+         WHERE date > datetime('now', '-{} days')
+           AND (tag IS NULL OR tag NOT IN ('weekly', 'monthly'))
+         ORDER BY date DESC",
+        policy.max_days()
+    );
+
+    let mut stmt = conn
+        .prepare(&query)
+        .map_err(|e| format!("Failed to prepare query: {}", e))?;
+
+    let rows = stmt
         .query_map([], |row| {
             let cards_json: String = row.get(0)?;
-            Ok(cards_json)
+            let age_days: Option<f64> = row.get(1)?;
+            Ok((cards_json, age_days.unwrap_or(0.0)))
         })
         .map_err(|e| format!("Failed to query briefings: {}", e))?;
 
     let mut fingerprints = Vec::new();
-
     for row in rows {
-        let cards_json = row.map_err(|e| format!("Failed to read row: {}", e))?;
+        let (cards_json, age_days) = row.map_err(|e| format!("Failed to read row: {}", e))?;
+        if let Ok(cards) = serde_json::from_str::<Vec<serde_json::Value>>(&cards_json) {
+            fingerprints.extend(
+                cards
+                    .iter()
+                    .filter_map(fingerprint_of)
+                    .filter(|fingerprint| {
+                        age_days < f64::from(policy.days_for(&fingerprint.topic))
+                    }),
+            );
+        }
+    }
+
+    Ok(fingerprints)
+}
+
+// ============================================================================
+// Chat messages migration (add card_index column)
+// ============================================================================
+
+/// Migrate chat_messages table to add card_index column if it doesn't exist.
+/// Also ensures the composite index exists (for both migrated and new databases).
+/// This is idempotent.
+fn migrate_chat_messages_add_card_index(conn: &Connection) -> std::result::Result<(), String> {
+    // Check if card_index column exists
+    let mut stmt = conn
+        .prepare("PRAGMA table_info(chat_messages)")
+        .map_err(|e| format!("Failed to get table info: {}", e))?;
+
+    let has_card_index = stmt
+        .query_map([], |row| {
+            row.get::<_, String>(1) // column name is at index 1
+        })
+        .map_err(|e| format!("Failed to query table info: {}", e))?
+        .any(|name| name.map(|n| n == "card_index").unwrap_or(false));
+
+    if !has_card_index {
+        info!("Migrating chat_messages table: adding card_index column");
+        conn.execute(
+            "ALTER TABLE chat_messages ADD COLUMN card_index INTEGER NOT NULL DEFAULT 0",
+            [],
+        )
+        .map_err(|e| format!("Failed to add card_index column: {}", e))?;
+        info!("Chat messages column migration complete");
+    }
+
+    // Always ensure the composite index exists (works for both migrated and new databases)
+    // Drop old single-column index if it exists
+    let _ = conn.execute("DROP INDEX IF EXISTS idx_chat_messages_briefing", []);
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_chat_messages_briefing_card ON chat_messages(briefing_id, card_index)",
+        [],
+    ).map_err(|e| format!("Failed to create index: {}", e))?;
+
+    Ok(())
+}
+
+/// Migration: Add tools_used column to chat_messages table if it doesn't exist
+fn migrate_chat_messages_add_tools_used(conn: &Connection) -> std::result::Result<(), String> {
+    let mut stmt = conn
+        .prepare("PRAGMA table_info(chat_messages)")
+        .map_err(|e| format!("Failed to get table info: {}", e))?;
+
+    let has_column = stmt
+        .query_map([], |row| row.get::<_, String>(1))
+        .map_err(|e| format!("Failed to query table info: {}", e))?
+        .any(|name| name.map(|n| n == "tools_used").unwrap_or(false));
+
+    if !has_column {
+        info!("Migrating chat_messages table: adding tools_used column");
+        conn.execute("ALTER TABLE chat_messages ADD COLUMN tools_used TEXT", [])
+            .map_err(|e| format!("Failed to add tools_used column: {}", e))?;
+    }
+
+    Ok(())
+}
+
+/// Migration: Add preferred_sources column to topics table if it doesn't exist
+fn migrate_topics_add_preferred_sources(conn: &Connection) -> std::result::Result<(), String> {
+    let mut stmt = conn
+        .prepare("PRAGMA table_info(topics)")
+        .map_err(|e| format!("Failed to get table info: {}", e))?;
+
+    let has_column = stmt
+        .query_map([], |row| row.get::<_, String>(1))
+        .map_err(|e| format!("Failed to query table info: {}", e))?
+        .any(|name| name.map(|n| n == "preferred_sources").unwrap_or(false));
+
+    if !has_column {
+        info!("Migrating topics table: adding preferred_sources column");
+        conn.execute("ALTER TABLE topics ADD COLUMN preferred_sources TEXT", [])
+            .map_err(|e| format!("Failed to add preferred_sources column: {}", e))?;
+    }
+
+    Ok(())
+}
+
+/// Migration: Add snoozed_until and skip_next_run columns to topics table if they don't exist
+fn migrate_topics_add_snooze(conn: &Connection) -> std::result::Result<(), String> {
+    let mut stmt = conn
+        .prepare("PRAGMA table_info(topics)")
+        .map_err(|e| format!("Failed to get table info: {}", e))?;
+
+    let has_column = stmt
+        .query_map([], |row| row.get::<_, String>(1))
+        .map_err(|e| format!("Failed to query table info: {}", e))?
+        .any(|name| name.map(|n| n == "snoozed_until").unwrap_or(false));
+
+    if !has_column {
+        info!("Migrating topics table: adding snoozed_until and skip_next_run columns");
+        conn.execute("ALTER TABLE topics ADD COLUMN snoozed_until TEXT", [])
+            .map_err(|e| format!("Failed to add snoozed_until column: {}", e))?;
+        conn.execute(
+            "ALTER TABLE topics ADD COLUMN skip_next_run INTEGER NOT NULL DEFAULT 0",
+            [],
+        )
+        .map_err(|e| format!("Failed to add skip_next_run column: {}", e))?;
+    }
+
+    Ok(())
+}
+
+/// Migration: Add dedup_days and dedup_threshold columns to topics table if they don't exist
+fn migrate_topics_add_dedup_overrides(conn: &Connection) -> std::result::Result<(), String> {
+    let mut stmt = conn
+        .prepare("PRAGMA table_info(topics)")
+        .map_err(|e| format!("Failed to get table info: {}", e))?;
+
+    let has_column = stmt
+        .query_map([], |row| row.get::<_, String>(1))
+        .map_err(|e| format!("Failed to query table info: {}", e))?
+        .any(|name| name.map(|n| n == "dedup_days").unwrap_or(false));
+
+    if !has_column {
+        info!("Migrating topics table: adding dedup_days and dedup_threshold columns");
+        conn.execute("ALTER TABLE topics ADD COLUMN dedup_days INTEGER", [])
+            .map_err(|e| format!("Failed to add dedup_days column: {}", e))?;
+        conn.execute("ALTER TABLE topics ADD COLUMN dedup_threshold REAL", [])
+            .map_err(|e| format!("Failed to add dedup_threshold column: {}", e))?;
+    }
+
+    Ok(())
+}
+
+/// Migration: Add mcp_servers column to topics table if it doesn't exist
+fn migrate_topics_add_mcp_servers(conn: &Connection) -> std::result::Result<(), String> {
+    let mut stmt = conn
+        .prepare("PRAGMA table_info(topics)")
+        .map_err(|e| format!("Failed to get table info: {}", e))?;
+
+    let has_column = stmt
+        .query_map([], |row| row.get::<_, String>(1))
+        .map_err(|e| format!("Failed to query table info: {}", e))?
+        .any(|name| name.map(|n| n == "mcp_servers").unwrap_or(false));
+
+    if !has_column {
+        info!("Migrating topics table: adding mcp_servers column");
+        conn.execute("ALTER TABLE topics ADD COLUMN mcp_servers TEXT", [])
+            .map_err(|e| format!("Failed to add mcp_servers column: {}", e))?;
+    }
+
+    Ok(())
+}
+
+/// Migration: Add read_at column to briefings table if it doesn't exist
+fn migrate_briefings_add_read_at(conn: &Connection) -> std::result::Result<(), String> {
+    let mut stmt = conn
+        .prepare("PRAGMA table_info(briefings)")
+        .map_err(|e| format!("Failed to get table info: {}", e))?;
+
+    let has_column = stmt
+        .query_map([], |row| row.get::<_, String>(1))
+        .map_err(|e| format!("Failed to query table info: {}", e))?
+        .any(|name| name.map(|n| n == "read_at").unwrap_or(false));
+
+    if !has_column {
+        info!("Migrating briefings table: adding read_at column");
+        conn.execute("ALTER TABLE briefings ADD COLUMN read_at TIMESTAMP", [])
+            .map_err(|e| format!("Failed to add read_at column: {}", e))?;
+    }
+
+    Ok(())
+}
+
+/// Migration: Add report column to briefings table if it doesn't exist
+fn migrate_briefings_add_report(conn: &Connection) -> std::result::Result<(), String> {
+    let mut stmt = conn
+        .prepare("PRAGMA table_info(briefings)")
+        .map_err(|e| format!("Failed to get table info: {}", e))?;
+
+    let has_column = stmt
+        .query_map([], |row| row.get::<_, String>(1))
+        .map_err(|e| format!("Failed to query table info: {}", e))?
+        .any(|name| name.map(|n| n == "report").unwrap_or(false));
+
+    if !has_column {
+        info!("Migrating briefings table: adding report column");
+        conn.execute("ALTER TABLE briefings ADD COLUMN report TEXT", [])
+            .map_err(|e| format!("Failed to add report column: {}", e))?;
+    }
+
+    Ok(())
+}
+
+/// Migration: Add tag column to briefings table if it doesn't exist
+fn migrate_briefings_add_tag(conn: &Connection) -> std::result::Result<(), String> {
+    let mut stmt = conn
+        .prepare("PRAGMA table_info(briefings)")
+        .map_err(|e| format!("Failed to get table info: {}", e))?;
+
+    let has_column = stmt
+        .query_map([], |row| row.get::<_, String>(1))
+        .map_err(|e| format!("Failed to query table info: {}", e))?
+        .any(|name| name.map(|n| n == "tag").unwrap_or(false));
+
+    if !has_column {
+        info!("Migrating briefings table: adding tag column");
+        conn.execute("ALTER TABLE briefings ADD COLUMN tag TEXT", [])
+            .map_err(|e| format!("Failed to add tag column: {}", e))?;
+    }
+
+    Ok(())
+}
+
+/// Migration: Add run_id column to briefings table if it doesn't exist, and
+/// the unique index that keeps a run from being saved twice. The index lives
+/// here rather than in schema.sql, which runs before the column exists.
+fn migrate_briefings_add_run_id(conn: &Connection) -> std::result::Result<(), String> {
+    let mut stmt = conn
+        .prepare("PRAGMA table_info(briefings)")
+        .map_err(|e| format!("Failed to get table info: {}", e))?;
+
+    let has_column = stmt
+        .query_map([], |row| row.get::<_, String>(1))
+        .map_err(|e| format!("Failed to query table info: {}", e))?
+        .any(|name| name.map(|n| n == "run_id").unwrap_or(false));
+
+    if !has_column {
+        info!("Migrating briefings table: adding run_id column");
+        conn.execute("ALTER TABLE briefings ADD COLUMN run_id TEXT", [])
+            .map_err(|e| format!("Failed to add run_id column: {}", e))?;
+    }
+
+    conn.execute(
+        "CREATE UNIQUE INDEX IF NOT EXISTS idx_briefings_run_id ON briefings(run_id)",
+        [],
+    )
+    .map_err(|e| format!("Failed to create run_id index: {}", e))?;
+
+    Ok(())
+}
+
+/// Migration: Add topics column to briefings table if it doesn't exist
+fn migrate_briefings_add_topics(conn: &Connection) -> std::result::Result<(), String> {
+    let mut stmt = conn
+        .prepare("PRAGMA table_info(briefings)")
+        .map_err(|e| format!("Failed to get table info: {}", e))?;
+
+    let has_column = stmt
+        .query_map([], |row| row.get::<_, String>(1))
+        .map_err(|e| format!("Failed to query table info: {}", e))?
+        .any(|name| name.map(|n| n == "topics").unwrap_or(false));
+
+    if !has_column {
+        info!("Migrating briefings table: adding topics column");
+        conn.execute("ALTER TABLE briefings ADD COLUMN topics TEXT", [])
+            .map_err(|e| format!("Failed to add topics column: {}", e))?;
+    }
+
+    Ok(())
+}
+
+/// Migration: Add failed_topics column to briefings table if it doesn't exist
+fn migrate_briefings_add_failed_topics(conn: &Connection) -> std::result::Result<(), String> {
+    let mut stmt = conn
+        .prepare("PRAGMA table_info(briefings)")
+        .map_err(|e| format!("Failed to get table info: {}", e))?;
+
+    let has_column = stmt
+        .query_map([], |row| row.get::<_, String>(1))
+        .map_err(|e| format!("Failed to query table info: {}", e))?
+        .any(|name| name.map(|n| n == "failed_topics").unwrap_or(false));
+
+    if !has_column {
+        info!("Migrating briefings table: adding failed_topics column");
+        conn.execute("ALTER TABLE briefings ADD COLUMN failed_topics TEXT", [])
+            .map_err(|e| format!("Failed to add failed_topics column: {}", e))?;
+    }
+
+    Ok(())
+}
+
+/// Migration: Add cover_image_prompt and cover_image_path columns to
+/// briefings table if they don't exist
+fn migrate_briefings_add_cover_image(conn: &Connection) -> std::result::Result<(), String> {
+    let mut stmt = conn
+        .prepare("PRAGMA table_info(briefings)")
+        .map_err(|e| format!("Failed to get table info: {}", e))?;
+
+    let columns: Vec<String> = stmt
+        .query_map([], |row| row.get::<_, String>(1))
+        .map_err(|e| format!("Failed to query table info: {}", e))?
+        .filter_map(|name| name.ok())
+        .collect();
 
-        // Parse JSON array of cards
-        if let Ok(cards) = serde_json::from_str::<Vec<serde_json::Value>>(&cards_json) {
-            for card in cards {
-                let title = card
-                    .get("title")
-                    .and_then(|v| v.as_str())
-                    .unwrap_or("")
-                    .to_string();
-                let topic = card
-                    .get("topic")
-                    .and_then(|v| v.as_str())
-                    .unwrap_or("")
-                    .to_string();
-                let summary = card
-                    .get("summary")
-                    .and_then(|v| v.as_str())
-                    .unwrap_or("")
-                    .to_string();
-
-                if !title.is_empty() {
-                    fingerprints.push(crate::dedup::CardFingerprint {
-                        title,
-                        topic,
-                        summary,
-                    });
-                }
-            }
+    for column in ["cover_image_prompt", "cover_image_path"] {
+        if !columns.iter().any(|c| c == column) {
+            info!("Migrating briefings table: adding {} column", column);
+            conn.execute(
+                &format!("ALTER TABLE briefings ADD COLUMN {} TEXT", column),
+                [],
+            )
+            .map_err(|e| format!("Failed to add {} column: {}", column, e))?;
         }
     }
 
-    Ok(fingerprints)
+    Ok(())
 }
 
-// ============================================================================
-// Chat messages migration (add card_index column)
-// ============================================================================
+/// Migration: Add relevance column to card_fingerprints (for briefing list
+/// filters). Existing fingerprints are dropped so the backfill that runs
+/// next indexes them again with their relevance.
+fn migrate_card_fingerprints_add_relevance(conn: &Connection) -> std::result::Result<(), String> {
+    let mut stmt = conn
+        .prepare("PRAGMA table_info(card_fingerprints)")
+        .map_err(|e| format!("Failed to get table info: {}", e))?;
 
-/// Migrate chat_messages table to add card_index column if it doesn't exist.
-/// Also ensures the composite index exists (for both migrated and new databases).
-/// This is idempotent.
-fn migrate_chat_messages_add_card_index(conn: &Connection) -> std::result::Result<(), String> {
-    // Check if card_index column exists
+    let has_relevance = stmt
+        .query_map([], |row| row.get::<_, String>(1))
+        .map_err(|e| format!("Failed to query table info: {}", e))?
+        .filter_map(|name| name.ok())
+        .any(|name| name == "relevance");
+
+    if !has_relevance {
+        info!("Migrating card_fingerprints table: adding relevance column");
+        conn.execute_batch(
+            "ALTER TABLE card_fingerprints ADD COLUMN relevance INTEGER NOT NULL DEFAULT 1;
+             DELETE FROM card_fingerprints;",
+        )
+        .map_err(|e| format!("Failed to add relevance column: {}", e))?;
+    }
+
+    Ok(())
+}
+
+/// Migration: Add batch_tokens column to api_usage table if it doesn't exist
+fn migrate_api_usage_add_batch_tokens(conn: &Connection) -> std::result::Result<(), String> {
     let mut stmt = conn
-        .prepare("PRAGMA table_info(chat_messages)")
+        .prepare("PRAGMA table_info(api_usage)")
         .map_err(|e| format!("Failed to get table info: {}", e))?;
 
-    let has_card_index = stmt
-        .query_map([], |row| {
-            row.get::<_, String>(1) // column name is at index 1
-        })
+    let has_column = stmt
+        .query_map([], |row| row.get::<_, String>(1))
         .map_err(|e| format!("Failed to query table info: {}", e))?
-        .any(|name| name.map(|n| n == "card_index").unwrap_or(false));
+        .any(|name| name.map(|n| n == "batch_tokens").unwrap_or(false));
 
-    if !has_card_index {
-        info!("Migrating chat_messages table: adding card_index column");
+    if !has_column {
+        info!("Migrating api_usage table: adding batch_tokens column");
         conn.execute(
-            "ALTER TABLE chat_messages ADD COLUMN card_index INTEGER NOT NULL DEFAULT 0",
+            "ALTER TABLE api_usage ADD COLUMN batch_tokens INTEGER NOT NULL DEFAULT 0",
             [],
         )
-        .map_err(|e| format!("Failed to add card_index column: {}", e))?;
-        info!("Chat messages column migration complete");
+        .map_err(|e| format!("Failed to add batch_tokens column: {}", e))?;
     }
 
-    // Always ensure the composite index exists (works for both migrated and new databases)
-    // Drop old single-column index if it exists
-    let _ = conn.execute("DROP INDEX IF EXISTS idx_chat_messages_briefing", []);
-    conn.execute(
-        "CREATE INDEX IF NOT EXISTS idx_chat_messages_briefing_card ON chat_messages(briefing_id, card_index)",
-        [],
-    ).map_err(|e| format!("Failed to create index: {}", e))?;
+    Ok(())
+}
+
+/// Migration: Add request_id column to research_logs table if it doesn't exist
+fn migrate_research_logs_add_request_id(conn: &Connection) -> std::result::Result<(), String> {
+    let mut stmt = conn
+        .prepare("PRAGMA table_info(research_logs)")
+        .map_err(|e| format!("Failed to get table info: {}", e))?;
+
+    let has_column = stmt
+        .query_map([], |row| row.get::<_, String>(1))
+        .map_err(|e| format!("Failed to query table info: {}", e))?
+        .any(|name| name.map(|n| n == "request_id").unwrap_or(false));
+
+    if !has_column {
+        info!("Migrating research_logs table: adding request_id column");
+        conn.execute("ALTER TABLE research_logs ADD COLUMN request_id TEXT", [])
+            .map_err(|e| format!("Failed to add request_id column: {}", e))?;
+    }
 
     Ok(())
 }
@@ -797,6 +3603,41 @@ pub fn migrate_topics_from_json(conn: &Connection) -> std::result::Result<Migrat
     Ok(result)
 }
 
+/// Add the interests from a legacy `config.json` as topics, skipping names
+/// that already exist. Returns how many topics were added.
+pub fn import_legacy_interests(
+    conn: &Connection,
+    interests: &[String],
+) -> std::result::Result<usize, String> {
+    let mut imported = 0;
+    for name in interests {
+        if topic_name_exists(conn, name)? {
+            continue;
+        }
+        let now = chrono::Utc::now().to_rfc3339();
+        let topic = Topic {
+            id: uuid::Uuid::new_v4().to_string(),
+            name: name.clone(),
+            description: None,
+            preferred_sources: Vec::new(),
+            enabled: true,
+            snoozed_until: None,
+            skip_next_run: false,
+            dedup_days: None,
+            dedup_threshold: None,
+            mcp_servers: Vec::new(),
+            created_at: now.clone(),
+            updated_at: now,
+        };
+        insert_topic(conn, &topic, get_next_sort_order(conn)?)?;
+        imported += 1;
+    }
+    if imported > 0 {
+        info!("Imported {} interests from config.json as topics", imported);
+    }
+    Ok(imported)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -808,6 +3649,20 @@ mod tests {
         conn
     }
 
+    #[test]
+    fn test_ensure_schema() {
+        let conn = Connection::open_in_memory().unwrap();
+        ensure_schema(&conn).unwrap();
+        ensure_schema(&conn).unwrap();
+
+        assert!(conn.prepare("SELECT preferred_sources FROM topics").is_ok());
+        assert!(conn.prepare("SELECT card_index FROM chat_messages").is_ok());
+        assert!(conn
+            .prepare("SELECT snoozed_until, mcp_servers FROM topics")
+            .is_ok());
+        assert!(conn.prepare("SELECT run_id FROM briefings").is_ok());
+    }
+
     fn create_test_briefing(conn: &Connection) -> i64 {
         conn.execute(
             "INSERT INTO briefings (date, title, cards) VALUES (?1, ?2, ?3)",
@@ -869,6 +3724,30 @@ mod tests {
         assert_eq!(messages[1].tokens_used, Some(100));
     }
 
+    #[test]
+    fn test_chat_message_tools_used() {
+        let conn = setup_test_db();
+        let briefing_id = create_test_briefing(&conn);
+
+        let tools = vec!["fetch_webpage".to_string(), "web_search".to_string()];
+        let id = insert_chat_message_with_tools(
+            &conn,
+            briefing_id,
+            0,
+            "assistant",
+            "Here is what I found",
+            Some(50),
+            &tools,
+        )
+        .unwrap();
+        insert_chat_message(&conn, briefing_id, 0, "user", "Thanks", None).unwrap();
+
+        let saved = get_chat_message_by_id(&conn, id).unwrap().unwrap();
+        assert_eq!(saved.tools_used, tools);
+        let messages = get_chat_messages(&conn, briefing_id, 0).unwrap();
+        assert!(messages[1].tools_used.is_empty());
+    }
+
     #[test]
     fn test_chat_messages_by_card_index() {
         let conn = setup_test_db();
@@ -1006,139 +3885,742 @@ mod tests {
         let conn = setup_test_db();
         let briefing_id = create_test_briefing(&conn);
 
-        // Add bookmark
-        add_bookmark(&conn, briefing_id, 0).unwrap();
+        // Add bookmark
+        add_bookmark(&conn, briefing_id, 0).unwrap();
+
+        // Verify it exists
+        assert!(is_bookmarked(&conn, briefing_id, 0).unwrap());
+    }
+
+    #[test]
+    fn test_add_bookmark_idempotent() {
+        let conn = setup_test_db();
+        let briefing_id = create_test_briefing(&conn);
+
+        // Add bookmark twice (should not error)
+        add_bookmark(&conn, briefing_id, 0).unwrap();
+        add_bookmark(&conn, briefing_id, 0).unwrap();
+
+        // Should still only have one bookmark
+        let bookmarks = get_all_bookmarks(&conn).unwrap();
+        assert_eq!(bookmarks.len(), 1);
+    }
+
+    #[test]
+    fn test_remove_bookmark() {
+        let conn = setup_test_db();
+        let briefing_id = create_test_briefing(&conn);
+
+        // Add then remove bookmark
+        add_bookmark(&conn, briefing_id, 0).unwrap();
+        let removed = remove_bookmark(&conn, briefing_id, 0).unwrap();
+
+        assert!(removed);
+        assert!(!is_bookmarked(&conn, briefing_id, 0).unwrap());
+    }
+
+    #[test]
+    fn test_remove_nonexistent_bookmark() {
+        let conn = setup_test_db();
+        let briefing_id = create_test_briefing(&conn);
+
+        // Remove bookmark that doesn't exist
+        let removed = remove_bookmark(&conn, briefing_id, 0).unwrap();
+        assert!(!removed);
+    }
+
+    #[test]
+    fn test_is_bookmarked() {
+        let conn = setup_test_db();
+        let briefing_id = create_test_briefing(&conn);
+
+        // Not bookmarked initially
+        assert!(!is_bookmarked(&conn, briefing_id, 0).unwrap());
+
+        // Add bookmark
+        add_bookmark(&conn, briefing_id, 0).unwrap();
+        assert!(is_bookmarked(&conn, briefing_id, 0).unwrap());
+
+        // Different card index should not be bookmarked
+        assert!(!is_bookmarked(&conn, briefing_id, 1).unwrap());
+    }
+
+    #[test]
+    fn test_get_all_bookmarks() {
+        let conn = setup_test_db();
+        let briefing_id = create_test_briefing(&conn);
+
+        // No bookmarks initially
+        let bookmarks = get_all_bookmarks(&conn).unwrap();
+        assert!(bookmarks.is_empty());
+
+        // Add some bookmarks
+        add_bookmark(&conn, briefing_id, 0).unwrap();
+        add_bookmark(&conn, briefing_id, 2).unwrap();
+
+        let bookmarks = get_all_bookmarks(&conn).unwrap();
+        assert_eq!(bookmarks.len(), 2);
+    }
+
+    #[test]
+    fn test_toggle_bookmark() {
+        let conn = setup_test_db();
+        let briefing_id = create_test_briefing(&conn);
+
+        // Toggle on (add)
+        let added = toggle_bookmark(&conn, briefing_id, 0).unwrap();
+        assert!(added);
+        assert!(is_bookmarked(&conn, briefing_id, 0).unwrap());
+
+        // Toggle off (remove)
+        let added = toggle_bookmark(&conn, briefing_id, 0).unwrap();
+        assert!(!added);
+        assert!(!is_bookmarked(&conn, briefing_id, 0).unwrap());
+    }
+
+    #[test]
+    fn test_bookmarks_cascade_delete() {
+        let conn = setup_test_db();
+        let briefing_id = create_test_briefing(&conn);
+
+        // Add bookmark
+        add_bookmark(&conn, briefing_id, 0).unwrap();
+        assert!(is_bookmarked(&conn, briefing_id, 0).unwrap());
+
+        // Delete the briefing (should cascade to bookmarks)
+        conn.execute("DELETE FROM briefings WHERE id = ?1", [briefing_id])
+            .unwrap();
+
+        // Bookmark should be gone
+        let bookmarks = get_all_bookmarks(&conn).unwrap();
+        assert!(bookmarks.is_empty());
+    }
+
+    // ========================================================================
+    // Read-later queue tests
+    // ========================================================================
+
+    #[test]
+    fn test_save_for_later_appends_and_is_idempotent() {
+        let conn = setup_test_db();
+        let briefing_id = create_test_briefing(&conn);
+
+        let first = save_for_later(&conn, briefing_id, 0).unwrap();
+        let second = save_for_later(&conn, briefing_id, 2).unwrap();
+        assert_eq!(first.position, 0);
+        assert_eq!(second.position, 1);
+
+        // Saving again returns the same item and re-opens it
+        assert!(complete_read_later(&conn, first.id).unwrap());
+        let again = save_for_later(&conn, briefing_id, 0).unwrap();
+        assert_eq!(again.id, first.id);
+        assert!(!again.completed);
+        assert_eq!(get_read_later_queue(&conn, true).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_read_later_complete_reorder_remove() {
+        let conn = setup_test_db();
+        let briefing_id = create_test_briefing(&conn);
+
+        let a = save_for_later(&conn, briefing_id, 0).unwrap();
+        let b = save_for_later(&conn, briefing_id, 1).unwrap();
+        let c = save_for_later(&conn, briefing_id, 2).unwrap();
+
+        reorder_read_later(&conn, &[c.id, a.id, b.id]).unwrap();
+        let ids: Vec<i64> = get_read_later_queue(&conn, false)
+            .unwrap()
+            .iter()
+            .map(|i| i.id)
+            .collect();
+        assert_eq!(ids, vec![c.id, a.id, b.id]);
+
+        complete_read_later(&conn, c.id).unwrap();
+        let pending = get_read_later_queue(&conn, false).unwrap();
+        assert_eq!(pending.len(), 2);
+        let all = get_read_later_queue(&conn, true).unwrap();
+        assert_eq!(all.last().unwrap().id, c.id);
+        assert!(all.last().unwrap().completed_at.is_some());
+
+        set_read_later_forwarded(&conn, a.id, "pocket").unwrap();
+        let queue = get_read_later_queue(&conn, false).unwrap();
+        assert_eq!(queue[0].forwarded_to.as_deref(), Some("pocket"));
+
+        assert!(remove_read_later(&conn, b.id).unwrap());
+        assert!(!remove_read_later(&conn, b.id).unwrap());
+
+        delete_briefing(&conn, briefing_id).unwrap();
+        assert!(get_read_later_queue(&conn, true).unwrap().is_empty());
+    }
+
+    // ========================================================================
+    // Content cache tests
+    // ========================================================================
+
+    #[test]
+    fn test_content_cache_upsert_and_lookup() {
+        let conn = setup_test_db();
+
+        upsert_cached_content(&conn, "web", "https://example.com", Some("Rust"), "v1").unwrap();
+        upsert_cached_content(&conn, "web", "https://example.com", None, "v2").unwrap();
+
+        let cached = get_cached_content(&conn, "web", "https://example.com", 24)
+            .unwrap()
+            .unwrap();
+        assert_eq!(cached.content, "v2");
+        assert_eq!(cached.topic.as_deref(), Some("Rust"));
+        assert!(
+            get_cached_content(&conn, "github", "https://example.com", 24)
+                .unwrap()
+                .is_none()
+        );
+
+        let for_topic = get_cached_content_for_topic(&conn, "Rust", 24, 10).unwrap();
+        assert_eq!(for_topic.len(), 1);
+    }
+
+    #[test]
+    fn test_content_cache_expiry() {
+        let conn = setup_test_db();
+        upsert_cached_content(&conn, "web", "https://old.example.com", Some("Rust"), "old")
+            .unwrap();
+        conn.execute(
+            "UPDATE content_cache SET fetched_at = datetime('now', '-10 days')",
+            [],
+        )
+        .unwrap();
+
+        assert!(
+            get_cached_content(&conn, "web", "https://old.example.com", 24)
+                .unwrap()
+                .is_none()
+        );
+        assert!(get_cached_content_for_topic(&conn, "Rust", 24, 10)
+            .unwrap()
+            .is_empty());
+        assert_eq!(cleanup_content_cache(&conn, 7).unwrap(), 1);
+    }
+
+    #[test]
+    fn test_web_cache_lookup_and_cleanup() {
+        let conn = setup_test_db();
+        upsert_web_cache(&conn, "https://a.example.com", "aaaa", Some("\"v1\""), None).unwrap();
+        upsert_web_cache(&conn, "https://b.example.com", "bbbbbb", None, None).unwrap();
+        upsert_web_cache(&conn, "https://c.example.com", "cc", None, None).unwrap();
+        assert_eq!(web_cache_stats(&conn).unwrap(), (3, 12));
+
+        let entry = get_web_cache(&conn, "https://a.example.com")
+            .unwrap()
+            .unwrap();
+        assert_eq!(entry.content, "aaaa");
+        assert_eq!(entry.etag.as_deref(), Some("\"v1\""));
+        assert!(entry.age_secs < 60);
+        assert!(get_web_cache(&conn, "https://missing.example.com")
+            .unwrap()
+            .is_none());
+
+        // c expired; b was used least recently, so it goes to fit the cap
+        conn.execute(
+            "UPDATE web_cache SET fetched_at = datetime('now', '-30 days')
+             WHERE url = 'https://c.example.com'",
+            [],
+        )
+        .unwrap();
+        conn.execute(
+            "UPDATE web_cache SET accessed_at = datetime('now', '-1 hour')
+             WHERE url = 'https://b.example.com'",
+            [],
+        )
+        .unwrap();
+        assert_eq!(cleanup_web_cache(&conn, 14, 5).unwrap(), 2);
+        assert_eq!(web_cache_stats(&conn).unwrap(), (1, 4));
+
+        touch_web_cache(&conn, "https://a.example.com").unwrap();
+        assert!(get_web_cache(&conn, "https://a.example.com")
+            .unwrap()
+            .is_some());
+    }
+
+    // ========================================================================
+    // Housekeeping / Cleanup tests
+    // ========================================================================
+
+    #[test]
+    fn test_delete_briefing() {
+        let conn = setup_test_db();
+        let briefing_id = create_test_briefing(&conn);
+
+        // Briefing exists
+        assert_eq!(count_briefings(&conn).unwrap(), 1);
+
+        // Delete it
+        let deleted = delete_briefing(&conn, briefing_id).unwrap();
+        assert!(deleted);
+
+        // Gone
+        assert_eq!(count_briefings(&conn).unwrap(), 0);
 
-        // Verify it exists
-        assert!(is_bookmarked(&conn, briefing_id, 0).unwrap());
+        // Deleting again returns false
+        let deleted = delete_briefing(&conn, briefing_id).unwrap();
+        assert!(!deleted);
     }
 
     #[test]
-    fn test_add_bookmark_idempotent() {
+    fn test_set_briefing_cover() {
         let conn = setup_test_db();
         let briefing_id = create_test_briefing(&conn);
+        assert_eq!(
+            get_briefing(&conn, briefing_id).unwrap().cover_image_path,
+            None
+        );
 
-        // Add bookmark twice (should not error)
-        add_bookmark(&conn, briefing_id, 0).unwrap();
-        add_bookmark(&conn, briefing_id, 0).unwrap();
+        let path = std::env::temp_dir().join(format!("claudius-cover-{}.png", std::process::id()));
+        std::fs::write(&path, b"png").unwrap();
+        set_briefing_cover(&conn, briefing_id, "A harbor at dawn", &path).unwrap();
+        let briefing = get_briefing(&conn, briefing_id).unwrap();
+        assert_eq!(
+            briefing.cover_image_path.as_deref(),
+            Some(path.to_string_lossy().as_ref())
+        );
+        assert!(path.exists());
 
-        // Should still only have one bookmark
-        let bookmarks = get_all_bookmarks(&conn).unwrap();
-        assert_eq!(bookmarks.len(), 1);
+        // An unknown briefing leaves no orphaned image behind
+        assert!(set_briefing_cover(&conn, briefing_id + 1, "A harbor", &path).is_err());
+        assert!(!path.exists());
     }
 
     #[test]
-    fn test_remove_bookmark() {
+    fn test_delete_briefing_removes_chat_and_bookmarks() {
         let conn = setup_test_db();
         let briefing_id = create_test_briefing(&conn);
-
-        // Add then remove bookmark
+        insert_chat_message(&conn, briefing_id, 0, "user", "Why?", None).unwrap();
         add_bookmark(&conn, briefing_id, 0).unwrap();
-        let removed = remove_bookmark(&conn, briefing_id, 0).unwrap();
 
-        assert!(removed);
-        assert!(!is_bookmarked(&conn, briefing_id, 0).unwrap());
+        let preview = briefing_deletion(&conn, briefing_id).unwrap().unwrap();
+        assert_eq!(preview.title, "Test Briefing");
+        assert_eq!(preview.chat_messages, 1);
+        assert_eq!(preview.bookmarks, 1);
+
+        assert!(delete_briefing(&conn, briefing_id).unwrap());
+        assert!(briefing_deletion(&conn, briefing_id).unwrap().is_none());
+        for table in ["chat_messages", "bookmarks"] {
+            let count: i64 = conn
+                .query_row(&format!("SELECT COUNT(*) FROM {}", table), [], |row| {
+                    row.get(0)
+                })
+                .unwrap();
+            assert_eq!(count, 0, "{} not cleaned up", table);
+        }
     }
 
     #[test]
-    fn test_remove_nonexistent_bookmark() {
+    fn test_insert_briefing_is_idempotent_per_run() {
         let conn = setup_test_db();
-        let briefing_id = create_test_briefing(&conn);
-
-        // Remove bookmark that doesn't exist
-        let removed = remove_bookmark(&conn, briefing_id, 0).unwrap();
-        assert!(!removed);
+        migrate_briefings_add_run_id(&conn).unwrap();
+        // Running the migration again is a no-op
+        migrate_briefings_add_run_id(&conn).unwrap();
+
+        let briefing = NewBriefing {
+            run_id: Some("run-1"),
+            date: "2025-01-01T07:00:00",
+            title: "Daily Briefing",
+            cards: "[]",
+            ..Default::default()
+        };
+        let (id, created) = insert_briefing(&conn, &briefing).unwrap();
+        assert!(created);
+        assert_eq!(insert_briefing(&conn, &briefing).unwrap(), (id, false));
+
+        // Briefings without a run are never matched
+        let unlinked = NewBriefing {
+            run_id: None,
+            ..briefing.clone()
+        };
+        assert!(insert_briefing(&conn, &unlinked).unwrap().1);
+        assert!(insert_briefing(&conn, &unlinked).unwrap().1);
+        assert_eq!(count_briefings(&conn).unwrap(), 3);
+
+        // The index rejects a second row for the same run
+        assert!(conn
+            .execute(
+                "INSERT INTO briefings (date, title, cards, run_id) VALUES ('2025-01-01', 'B', '[]', 'run-1')",
+                [],
+            )
+            .is_err());
     }
 
     #[test]
-    fn test_is_bookmarked() {
+    fn test_briefing_failed_topics() {
         let conn = setup_test_db();
-        let briefing_id = create_test_briefing(&conn);
-
-        // Not bookmarked initially
-        assert!(!is_bookmarked(&conn, briefing_id, 0).unwrap());
-
-        // Add bookmark
-        add_bookmark(&conn, briefing_id, 0).unwrap();
-        assert!(is_bookmarked(&conn, briefing_id, 0).unwrap());
-
-        // Different card index should not be bookmarked
-        assert!(!is_bookmarked(&conn, briefing_id, 1).unwrap());
+        let (id, _) = insert_briefing(
+            &conn,
+            &NewBriefing {
+                date: "2025-01-01T07:00:00",
+                title: "Daily Briefing",
+                cards: "[]",
+                failed_topics: Some(r#"[{"topic":"Rust","error":"Overloaded (api_overloaded)"}]"#),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        let failed = get_briefing_failed_topics(&conn, id).unwrap();
+        assert_eq!(failed.len(), 1);
+        assert_eq!(failed[0].topic, "Rust");
+        assert!(get_briefing_failed_topics(&conn, id + 1).is_err());
     }
 
     #[test]
-    fn test_get_all_bookmarks() {
+    fn test_get_briefings_filtered() {
         let conn = setup_test_db();
-        let briefing_id = create_test_briefing(&conn);
-
-        // No bookmarks initially
-        let bookmarks = get_all_bookmarks(&conn).unwrap();
-        assert!(bookmarks.is_empty());
-
-        // Add some bookmarks
-        add_bookmark(&conn, briefing_id, 0).unwrap();
-        add_bookmark(&conn, briefing_id, 2).unwrap();
+        let insert = |date: &str, model: &str, cards: &str| -> i64 {
+            conn.execute(
+                "INSERT INTO briefings (date, title, cards, model_used) VALUES (?1, 'Daily', ?2, ?3)",
+                [date, cards, model],
+            )
+            .unwrap();
+            let id = conn.last_insert_rowid();
+            index_card_fingerprints(&conn, id).unwrap();
+            id
+        };
+        let march = insert(
+            "2025-03-01T07:00:00",
+            "claude-sonnet-4-5",
+            r#"[{"title":"A","topic":"Rust","relevance":"low"}]"#,
+        );
+        let april = insert(
+            "2025-04-30T23:00:00",
+            "claude-opus-4-1",
+            r#"[{"title":"B","topic":"rust","relevance":"high"},{"title":"C","topic":"AI","relevance":"low"}]"#,
+        );
+        let ids = |filter: BriefingFilter| -> Vec<i64> {
+            get_briefings_filtered(&conn, &filter, 10)
+                .unwrap()
+                .iter()
+                .map(|b| b.id)
+                .collect()
+        };
+
+        assert_eq!(ids(BriefingFilter::default()), vec![april, march]);
+        assert_eq!(
+            ids(BriefingFilter {
+                from: Some("2025-03-02".to_string()),
+                ..Default::default()
+            }),
+            vec![april]
+        );
+        // The last day is included whatever the time
+        assert_eq!(
+            ids(BriefingFilter {
+                to: Some("2025-04-30".to_string()),
+                ..Default::default()
+            }),
+            vec![april, march]
+        );
+        assert_eq!(
+            ids(BriefingFilter {
+                topic: Some("RUST".to_string()),
+                min_relevance: Some("high".to_string()),
+                ..Default::default()
+            }),
+            vec![april]
+        );
+        // Topic and relevance must match the same card
+        assert!(ids(BriefingFilter {
+            topic: Some("AI".to_string()),
+            min_relevance: Some("medium".to_string()),
+            ..Default::default()
+        })
+        .is_empty());
+        assert_eq!(
+            ids(BriefingFilter {
+                model: Some("claude-sonnet-4-5".to_string()),
+                ..Default::default()
+            }),
+            vec![march]
+        );
 
-        let bookmarks = get_all_bookmarks(&conn).unwrap();
-        assert_eq!(bookmarks.len(), 2);
+        let invalid = |filter: BriefingFilter| get_briefings_filtered(&conn, &filter, 10).is_err();
+        assert!(invalid(BriefingFilter {
+            from: Some("March 1".to_string()),
+            ..Default::default()
+        }));
+        assert!(invalid(BriefingFilter {
+            min_relevance: Some("urgent".to_string()),
+            ..Default::default()
+        }));
     }
 
     #[test]
-    fn test_toggle_bookmark() {
+    fn test_persist_briefing() {
         let conn = setup_test_db();
-        let briefing_id = create_test_briefing(&conn);
+        let card = card_with_checks(vec![crate::sources::SourceCheck {
+            url: "https://example.com/a".to_string(),
+            valid: true,
+            status_code: Some(200),
+            final_url: None,
+            error: None,
+            published: None,
+            snapshot: None,
+        }]);
+        let discarded = vec![card_with_checks(vec![])];
+        conn.execute(
+            "INSERT INTO research_logs (log_type, topic, created_at) VALUES ('tool_call', 'AI', '2025-01-01 07:00:01')",
+            [],
+        )
+        .unwrap();
 
-        // Toggle on (add)
-        let added = toggle_bookmark(&conn, briefing_id, 0).unwrap();
-        assert!(added);
-        assert!(is_bookmarked(&conn, briefing_id, 0).unwrap());
+        let cards = vec![card];
+        let record = BriefingRecord {
+            briefing: NewBriefing {
+                run_id: Some("run-1"),
+                date: "2025-01-01T07:00:00",
+                title: "Daily Briefing",
+                cards: "[]",
+                total_tokens: 1200,
+                topics: Some(
+                    r#"[{"topic":"AI","tokens":900,"tool_calls":1,"duration_ms":4000,"card_indices":[0]}]"#,
+                ),
+                ..Default::default()
+            },
+            cards: &cards,
+            discarded: &discarded,
+            profile: "default",
+            batch_tokens: 0,
+            log_since: "2025-01-01 07:00:00",
+            merge_same_day: None,
+        };
+        let saved = persist_briefing(&conn, &record).unwrap();
+        let id = saved.id;
+        assert!(saved.created);
+        // A retried save adds nothing
+        let retried = persist_briefing(&conn, &record).unwrap();
+        assert_eq!((retried.id, retried.created), (id, false));
+
+        let topics = get_briefing_topics(&conn, id).unwrap();
+        assert_eq!(topics.len(), 1);
+        assert_eq!(topics[0].card_indices, vec![0]);
+        assert!(get_briefing_failed_topics(&conn, id).unwrap().is_empty());
+
+        let count = |sql: &str| -> i64 { conn.query_row(sql, [id], |row| row.get(0)).unwrap() };
+        assert_eq!(
+            count("SELECT COUNT(*) FROM sources WHERE briefing_id = ?1"),
+            1
+        );
+        assert_eq!(
+            count("SELECT COUNT(*) FROM discarded_cards WHERE briefing_id = ?1"),
+            1
+        );
+        assert_eq!(
+            count("SELECT COUNT(*) FROM api_usage WHERE briefing_id = ?1"),
+            1
+        );
+        assert_eq!(
+            count("SELECT COUNT(*) FROM research_logs WHERE briefing_id = ?1"),
+            1
+        );
+        assert_eq!(
+            count("SELECT COUNT(*) FROM briefings WHERE id = ?1 AND report IS NOT NULL"),
+            1
+        );
 
-        // Toggle off (remove)
-        let added = toggle_bookmark(&conn, briefing_id, 0).unwrap();
-        assert!(!added);
-        assert!(!is_bookmarked(&conn, briefing_id, 0).unwrap());
+        // A failed write rolls back the whole save
+        conn.execute_batch("DROP TABLE api_usage").unwrap();
+        let failed = BriefingRecord {
+            briefing: NewBriefing {
+                run_id: Some("run-2"),
+                ..record.briefing.clone()
+            },
+            ..record.clone()
+        };
+        assert!(persist_briefing(&conn, &failed).is_err());
+        assert_eq!(count_briefings(&conn).unwrap(), 1);
+        assert_eq!(
+            count("SELECT COUNT(*) FROM sources WHERE briefing_id != ?1"),
+            0
+        );
     }
 
     #[test]
-    fn test_bookmarks_cascade_delete() {
+    fn test_save_briefing_images() {
         let conn = setup_test_db();
-        let briefing_id = create_test_briefing(&conn);
-
-        // Add bookmark
-        add_bookmark(&conn, briefing_id, 0).unwrap();
-        assert!(is_bookmarked(&conn, briefing_id, 0).unwrap());
-
-        // Delete the briefing (should cascade to bookmarks)
-        conn.execute("DELETE FROM briefings WHERE id = ?1", [briefing_id])
+        let id = insert_briefing(
+            &conn,
+            &NewBriefing {
+                date: "2025-01-01",
+                title: "Daily Briefing",
+                cards: "[]",
+                ..Default::default()
+            },
+        )
+        .unwrap()
+        .0;
+        let dir = std::env::temp_dir().join(format!("claudius-images-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let image = dir.join(format!("{}_0.png", id));
+        let earlier = dir.join(format!("{}_1.png", id));
+        std::fs::write(&image, b"png").unwrap();
+        std::fs::write(&earlier, b"png").unwrap();
+
+        let cards = vec![card_with_checks(vec![])];
+        save_briefing_images(&conn, id, &cards, std::slice::from_ref(&image)).unwrap();
+        let stored: String = conn
+            .query_row("SELECT cards FROM briefings WHERE id = ?1", [id], |row| {
+                row.get(0)
+            })
             .unwrap();
-
-        // Bookmark should be gone
-        let bookmarks = get_all_bookmarks(&conn).unwrap();
-        assert!(bookmarks.is_empty());
+        assert_eq!(stored, serde_json::to_string(&cards).unwrap());
+        assert!(image.exists());
+
+        // A failed update deletes only the images generated for it
+        assert!(save_briefing_images(&conn, id + 1, &cards, std::slice::from_ref(&image)).is_err());
+        assert!(!image.exists());
+        assert!(earlier.exists());
+        assert!(conn.is_autocommit());
+        let _ = std::fs::remove_dir_all(&dir);
     }
 
-    // ========================================================================
-    // Housekeeping / Cleanup tests
-    // ========================================================================
-
     #[test]
-    fn test_delete_briefing() {
+    fn test_persist_briefing_merge_same_day() {
         let conn = setup_test_db();
-        let briefing_id = create_test_briefing(&conn);
+        let card = |title: &str, summary: &str| crate::research::BriefingCard {
+            title: title.to_string(),
+            summary: summary.to_string(),
+            topic: "Rust".to_string(),
+            ..card_with_checks(vec![])
+        };
+        fn record<'a>(run_id: &'a str, date: &'a str, cards: &'a str) -> BriefingRecord<'a> {
+            BriefingRecord {
+                briefing: NewBriefing {
+                    run_id: Some(run_id),
+                    date,
+                    title: "Daily Briefing",
+                    cards,
+                    total_tokens: 1000,
+                    topics: Some(
+                        r#"[{"topic":"Rust","tokens":1000,"tool_calls":1,"duration_ms":1000,"card_indices":[0]}]"#,
+                    ),
+                    ..Default::default()
+                },
+                cards: &[],
+                discarded: &[],
+                profile: "default",
+                batch_tokens: 0,
+                log_since: "2025-01-01 07:00:00",
+                merge_same_day: Some(0.75),
+            }
+        }
 
-        // Briefing exists
+        let morning = vec![card("Rust 2.0 released", "The new edition ships today")];
+        let morning_json = serde_json::to_string(&morning).unwrap();
+        let first = persist_briefing(
+            &conn,
+            &BriefingRecord {
+                cards: &morning,
+                ..record("run-1", "2025-01-01T07:00:00", &morning_json)
+            },
+        )
+        .unwrap();
+        assert!(first.created);
+        assert!(first.merged_cards.is_none());
+
+        // The afternoon run repeats the morning's card and adds a new one
+        let afternoon = vec![
+            card("Rust 2.0 released", "The new edition ships today"),
+            card(
+                "Tokio adds io_uring",
+                "Async file IO moves to the kernel ring",
+            ),
+        ];
+        let second_record = BriefingRecord {
+            cards: &afternoon,
+            ..record("run-2", "2025-01-01T15:00:00", "[]")
+        };
+        let second = persist_briefing(&conn, &second_record).unwrap();
+        assert_eq!(second.id, first.id);
+        assert!(second.created);
+        let merged = second.merged_cards.unwrap();
+        assert_eq!(merged.len(), 2);
+        assert_eq!(merged[1].title, "Tokio adds io_uring");
+        assert_eq!(merged[1].rank, Some(1));
+        assert_eq!(second.first_new_card, 1);
         assert_eq!(count_briefings(&conn).unwrap(), 1);
 
-        // Delete it
-        let deleted = delete_briefing(&conn, briefing_id).unwrap();
-        assert!(deleted);
+        let topics = get_briefing_topics(&conn, first.id).unwrap();
+        assert_eq!(topics.len(), 2);
+        assert_eq!(topics[1].card_indices, vec![1]);
+        let tokens: i64 = conn
+            .query_row(
+                "SELECT total_tokens FROM briefings WHERE id = ?1",
+                [first.id],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(tokens, 2000);
 
-        // Gone
-        assert_eq!(count_briefings(&conn).unwrap(), 0);
+        // A retried save of the merged run adds nothing
+        let retried = persist_briefing(&conn, &second_record).unwrap();
+        assert_eq!((retried.id, retried.created), (first.id, false));
 
-        // Deleting again returns false
-        let deleted = delete_briefing(&conn, briefing_id).unwrap();
-        assert!(!deleted);
+        // The next day starts a new briefing
+        let next_day =
+            persist_briefing(&conn, &record("run-3", "2025-01-02T07:00:00", "[]")).unwrap();
+        assert_ne!(next_day.id, first.id);
+        assert!(next_day.created);
+    }
+
+    #[test]
+    fn test_merge_duplicate_briefings() {
+        let conn = setup_test_db();
+        let insert = |date: &str, title: &str| -> i64 {
+            conn.execute(
+                "INSERT INTO briefings (date, title, cards) VALUES (?1, ?2, '[{\"title\":\"A\"}]')",
+                [date, title],
+            )
+            .unwrap();
+            conn.last_insert_rowid()
+        };
+        let kept = insert("2025-01-01T07:00:00", "Daily");
+        let duplicate = insert("2025-01-01T07:00:05", "Daily");
+        let other_day = insert("2025-01-02T07:00:00", "Daily");
+        let other_title = insert("2025-01-01T07:00:00", "Other");
+
+        add_bookmark(&conn, kept, 0).unwrap();
+        add_bookmark(&conn, duplicate, 0).unwrap();
+        insert_chat_message(&conn, duplicate, 0, "user", "Why?", None).unwrap();
+
+        assert_eq!(merge_duplicate_briefings(&conn).unwrap(), 1);
+        assert_eq!(count_briefings(&conn).unwrap(), 3);
+        assert!(briefing_deletion(&conn, duplicate).unwrap().is_none());
+        let merged = briefing_deletion(&conn, kept).unwrap().unwrap();
+        assert_eq!(merged.bookmarks, 1);
+        assert_eq!(merged.chat_messages, 1);
+        assert!(briefing_deletion(&conn, other_day).unwrap().is_some());
+        assert!(briefing_deletion(&conn, other_title).unwrap().is_some());
+
+        assert_eq!(merge_duplicate_briefings(&conn).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_briefings_before() {
+        let conn = setup_test_db();
+        let old = create_test_briefing(&conn); // 2025-01-01
+        conn.execute(
+            "INSERT INTO briefings (date, title, cards) VALUES ('2025-03-01T07:00:00', 'Newer', '[]')",
+            [],
+        )
+        .unwrap();
+        let bookmarked = create_test_briefing(&conn);
+        add_bookmark(&conn, bookmarked, 0).unwrap();
+
+        let ids = |include_bookmarked| -> Vec<i64> {
+            briefings_before(&conn, "2025-02-01", include_bookmarked)
+                .unwrap()
+                .iter()
+                .map(|b| b.id)
+                .collect()
+        };
+        assert_eq!(ids(false), vec![old]);
+        assert_eq!(ids(true), vec![old, bookmarked]);
     }
 
     #[test]
@@ -1282,4 +4764,479 @@ mod tests {
         add_bookmark(&conn, old_id, 0).unwrap();
         assert_eq!(count_cleanup_candidates(&conn, 30).unwrap(), 1);
     }
+
+    fn card_with_checks(checks: Vec<crate::sources::SourceCheck>) -> crate::research::BriefingCard {
+        crate::research::BriefingCard {
+            title: "Card".to_string(),
+            summary: String::new(),
+            detailed_content: String::new(),
+            sources: checks.iter().map(|c| c.url.clone()).collect(),
+            suggested_next: None,
+            relevance: "high".to_string(),
+            topic: "AI".to_string(),
+            image_prompt: None,
+            image_style: None,
+            image_path: None,
+            source_checks: checks,
+            action_items: vec![],
+            actions: vec![],
+            source_warning: None,
+            rank: None,
+        }
+    }
+
+    #[test]
+    fn test_insert_and_get_source_records() {
+        let conn = setup_test_db();
+        let briefing_id = create_test_briefing(&conn);
+
+        let cards = vec![
+            card_with_checks(vec![crate::sources::SourceCheck {
+                url: "https://example.com/a".to_string(),
+                valid: true,
+                status_code: Some(200),
+                final_url: None,
+                error: None,
+                published: None,
+                snapshot: Some("Archived article".to_string()),
+            }]),
+            card_with_checks(vec![crate::sources::SourceCheck {
+                url: "https://example.com/missing".to_string(),
+                valid: false,
+                status_code: Some(404),
+                final_url: None,
+                error: Some("HTTP 404 Not Found".to_string()),
+                published: None,
+                snapshot: None,
+            }]),
+        ];
+
+        let inserted = insert_source_records(&conn, briefing_id, &cards).unwrap();
+        assert_eq!(inserted, 2);
+
+        let sources = get_sources_for_briefing(&conn, briefing_id).unwrap();
+        assert_eq!(sources.len(), 2);
+        assert_eq!(sources[0].card_index, 0);
+        assert!(sources[0].valid);
+        assert_eq!(
+            sources[0].archived_text.as_deref(),
+            Some("Archived article")
+        );
+        assert_eq!(sources[1].card_index, 1);
+        assert!(!sources[1].valid);
+        assert_eq!(sources[1].status_code, Some(404));
+    }
+
+    #[test]
+    fn test_delete_briefing_removes_sources() {
+        let conn = setup_test_db();
+        let briefing_id = create_test_briefing(&conn);
+
+        let cards = vec![card_with_checks(vec![crate::sources::SourceCheck {
+            url: "https://example.com/a".to_string(),
+            valid: true,
+            status_code: Some(200),
+            final_url: None,
+            error: None,
+            published: None,
+            snapshot: None,
+        }])];
+        insert_source_records(&conn, briefing_id, &cards).unwrap();
+
+        delete_briefing(&conn, briefing_id).unwrap();
+        assert!(get_sources_for_briefing(&conn, briefing_id)
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_discarded_cards_restore_and_delete() {
+        let conn = setup_test_db();
+        let briefing_id = create_test_briefing(&conn);
+
+        let mut low = card_with_checks(vec![]);
+        low.title = "Minor update".to_string();
+        low.relevance = "low".to_string();
+        let inserted = insert_discarded_cards(&conn, briefing_id, &[low.clone(), low]).unwrap();
+        assert_eq!(inserted, 2);
+
+        let discarded = get_discarded_cards(&conn, 10).unwrap();
+        assert_eq!(discarded.len(), 2);
+        assert_eq!(discarded[0].relevance, "low");
+        assert_eq!(discarded[0].topic.as_deref(), Some("AI"));
+
+        let restored = restore_discarded_card(&conn, discarded[0].id).unwrap();
+        assert_eq!(restored, Some(briefing_id));
+        let cards: String = conn
+            .query_row(
+                "SELECT cards FROM briefings WHERE id = ?1",
+                [briefing_id],
+                |row| row.get(0),
+            )
+            .unwrap();
+        let cards: Vec<crate::research::BriefingCard> = serde_json::from_str(&cards).unwrap();
+        assert_eq!(cards.len(), 1);
+        assert_eq!(cards[0].title, "Minor update");
+
+        assert_eq!(
+            restore_discarded_card(&conn, discarded[0].id).unwrap(),
+            None
+        );
+        assert!(delete_discarded_card(&conn, discarded[1].id).unwrap());
+        assert!(get_discarded_cards(&conn, 10).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_topic_feedback_and_reorder_cards() {
+        let conn = setup_test_db();
+        conn.execute(
+            "INSERT INTO briefings (date, title, cards) VALUES ('2025-01-01', 'B', ?1)",
+            [r#"[{"title": "a", "topic": "Rust"}, {"title": "b", "topic": "AI"}, {"title": "c", "topic": "rust "}]"#],
+        )
+        .unwrap();
+        let briefing_id = conn.last_insert_rowid();
+        for (card_index, rating) in [(0, 5), (2, 4), (1, 1), (7, 5)] {
+            conn.execute(
+                "INSERT INTO feedback (briefing_id, card_index, rating) VALUES (?1, ?2, ?3)",
+                params![briefing_id, card_index, rating],
+            )
+            .unwrap();
+        }
+
+        let feedback = get_topic_feedback(&conn, 30).unwrap();
+        assert_eq!(feedback.len(), 2);
+        assert_eq!(feedback["rust"], (9, 2));
+        assert_eq!(feedback["ai"], (1, 1));
+
+        reorder_cards(&conn, briefing_id, &[2, 0, 1]).unwrap();
+        let cards: String = conn
+            .query_row(
+                "SELECT cards FROM briefings WHERE id = ?1",
+                [briefing_id],
+                |row| row.get(0),
+            )
+            .unwrap();
+        let cards: Vec<serde_json::Value> = serde_json::from_str(&cards).unwrap();
+        let ranks: Vec<i64> = cards.iter().map(|c| c["rank"].as_i64().unwrap()).collect();
+        assert_eq!(ranks, vec![1, 2, 0]);
+        assert_eq!(cards[0]["title"], "a");
+
+        assert!(reorder_cards(&conn, briefing_id, &[0, 1]).is_err());
+        assert!(reorder_cards(&conn, briefing_id, &[0, 0, 1]).is_err());
+    }
+
+    #[test]
+    fn test_profile_usage() {
+        let conn = setup_test_db();
+        record_api_usage(&conn, "work", "research", Some(1), 5000).unwrap();
+        record_api_usage(&conn, "work", "chat", Some(1), 800).unwrap();
+        record_api_usage(&conn, "default", "research", Some(2), 3000).unwrap();
+        record_research_usage(&conn, "work", Some(3), 1000, 1000).unwrap();
+
+        let usage = get_profile_usage(&conn, 30).unwrap();
+        assert_eq!(usage.len(), 2);
+        assert_eq!(usage[0].profile, "work");
+        assert_eq!(usage[0].research_runs, 2);
+        assert_eq!(usage[0].research_tokens, 6000);
+        assert_eq!(usage[0].batch_tokens, 1000);
+        assert_eq!(usage[0].chat_messages, 1);
+        assert_eq!(usage[0].chat_tokens, 800);
+        assert_eq!(usage[0].total_tokens, 6800);
+        assert_eq!(usage[1].profile, "default");
+        assert_eq!(usage[1].chat_messages, 0);
+    }
+
+    #[test]
+    fn test_topic_suggestions() {
+        let conn = setup_test_db();
+        let briefing_id = create_test_briefing(&conn);
+        let cards = serde_json::to_string(&vec![card_with_checks(vec![])]).unwrap();
+        conn.execute(
+            "UPDATE briefings SET cards = ?1 WHERE id = ?2",
+            params![cards, briefing_id],
+        )
+        .unwrap();
+        add_bookmark(&conn, briefing_id, 0).unwrap();
+        insert_chat_message(&conn, briefing_id, 0, "user", "What about Rust?", None).unwrap();
+
+        let signals = get_discovery_signals(&conn, 30, 10).unwrap();
+        assert_eq!(signals.bookmarked_cards.len(), 1);
+        assert!(signals.bookmarked_cards[0].starts_with("AI: "));
+        assert_eq!(signals.chat_questions, vec!["What about Rust?"]);
+
+        assert!(insert_topic_suggestion(&conn, "Rust tooling", Some("Asked about Rust")).unwrap());
+        assert!(!insert_topic_suggestion(&conn, "rust TOOLING", None).unwrap());
+        assert!(insert_topic_suggestion(&conn, "Crypto", None).unwrap());
+        let pending = get_topic_suggestions(&conn, "pending").unwrap();
+        assert_eq!(pending.len(), 2);
+
+        let rust = pending.iter().find(|s| s.name == "Rust tooling").unwrap();
+        let topic = accept_topic_suggestion(&conn, rust.id).unwrap().unwrap();
+        assert_eq!(topic.name, "Rust tooling");
+        assert!(topic_name_exists(&conn, "Rust tooling").unwrap());
+        assert!(accept_topic_suggestion(&conn, rust.id).unwrap().is_none());
+
+        let crypto = pending.iter().find(|s| s.name == "Crypto").unwrap();
+        assert!(dismiss_topic_suggestion(&conn, crypto.id).unwrap());
+        assert!(get_topic_suggestions(&conn, "pending").unwrap().is_empty());
+        assert_eq!(get_topic_suggestion_names(&conn).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_reminders() {
+        let conn = setup_test_db();
+        let briefing_id = create_test_briefing(&conn);
+
+        let due = insert_reminder(
+            &conn,
+            briefing_id,
+            0,
+            "AI",
+            "Check release",
+            "2000-01-01 00:00:00",
+        )
+        .unwrap();
+        let later = insert_reminder(
+            &conn,
+            briefing_id,
+            1,
+            "AI",
+            "Follow up",
+            "2999-01-01 00:00:00",
+        )
+        .unwrap();
+        assert_eq!(get_pending_reminders(&conn).unwrap().len(), 2);
+
+        let due_now = get_due_reminders(&conn).unwrap();
+        assert_eq!(due_now.len(), 1);
+        assert_eq!(due_now[0].id, due);
+        assert_eq!(due_now[0].message, "Check release");
+
+        mark_reminder_fired(&conn, due).unwrap();
+        assert!(get_due_reminders(&conn).unwrap().is_empty());
+        assert!(delete_reminder(&conn, later).unwrap());
+        assert!(!delete_reminder(&conn, later).unwrap());
+        assert!(get_pending_reminders(&conn).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_read_state_and_notification_queue() {
+        let conn = setup_test_db();
+        let briefing_id = create_test_briefing(&conn);
+
+        assert!(!is_briefing_read(&conn, briefing_id).unwrap());
+        assert!(mark_briefing_read(&conn, briefing_id).unwrap());
+        assert!(is_briefing_read(&conn, briefing_id).unwrap());
+        assert!(!mark_briefing_read(&conn, briefing_id + 1).unwrap());
+        assert!(!is_briefing_read(&conn, briefing_id + 1).unwrap());
+
+        let due = queue_notification(
+            &conn,
+            Some(briefing_id),
+            "Research Complete",
+            "3 new briefings ready!",
+            "2000-01-01 00:00:00",
+        )
+        .unwrap();
+        queue_notification(&conn, None, "Later", "Not yet", "2999-01-01 00:00:00").unwrap();
+
+        let due_now = get_due_notifications(&conn).unwrap();
+        assert_eq!(due_now.len(), 1);
+        assert_eq!(due_now[0].id, due);
+        assert_eq!(due_now[0].briefing_id, Some(briefing_id));
+
+        reschedule_notification(&conn, due, "2999-01-01 00:00:00").unwrap();
+        assert!(get_due_notifications(&conn).unwrap().is_empty());
+        reschedule_notification(&conn, due, "2000-01-02 00:00:00").unwrap();
+        mark_notification_delivered(&conn, due).unwrap();
+        assert!(get_due_notifications(&conn).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_topic_preferred_sources_roundtrip() {
+        let conn = setup_test_db();
+        let mut topic = Topic {
+            id: "topic-1".to_string(),
+            name: "Rust".to_string(),
+            description: None,
+            preferred_sources: vec!["Rust blog".to_string(), "lobste.rs".to_string()],
+            enabled: true,
+            snoozed_until: None,
+            skip_next_run: false,
+            dedup_days: None,
+            dedup_threshold: None,
+            mcp_servers: Vec::new(),
+            created_at: "2025-01-01T00:00:00Z".to_string(),
+            updated_at: "2025-01-01T00:00:00Z".to_string(),
+        };
+        insert_topic(&conn, &topic, 0).unwrap();
+
+        let loaded = get_topic_by_id(&conn, "topic-1").unwrap().unwrap();
+        assert_eq!(loaded.preferred_sources, topic.preferred_sources);
+
+        topic.preferred_sources.clear();
+        update_topic(&conn, &topic).unwrap();
+        let all = get_all_topics(&conn).unwrap();
+        assert!(all[0].preferred_sources.is_empty());
+    }
+
+    #[test]
+    fn test_migrate_topics_add_preferred_sources() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE topics (
+                id TEXT PRIMARY KEY,
+                name TEXT NOT NULL,
+                description TEXT,
+                enabled INTEGER NOT NULL DEFAULT 1,
+                sort_order INTEGER NOT NULL DEFAULT 0,
+                created_at TEXT NOT NULL,
+                updated_at TEXT NOT NULL
+            );
+            INSERT INTO topics (id, name, created_at, updated_at)
+            VALUES ('t1', 'Old topic', '2025-01-01', '2025-01-01');",
+        )
+        .unwrap();
+
+        migrate_topics_add_preferred_sources(&conn).unwrap();
+        // Idempotent
+        migrate_topics_add_preferred_sources(&conn).unwrap();
+        migrate_topics_add_snooze(&conn).unwrap();
+        migrate_topics_add_snooze(&conn).unwrap();
+        migrate_topics_add_dedup_overrides(&conn).unwrap();
+        migrate_topics_add_dedup_overrides(&conn).unwrap();
+        migrate_topics_add_mcp_servers(&conn).unwrap();
+        migrate_topics_add_mcp_servers(&conn).unwrap();
+
+        let topics = get_all_topics(&conn).unwrap();
+        assert_eq!(topics.len(), 1);
+        assert!(topics[0].preferred_sources.is_empty());
+        assert!(topics[0].snoozed_until.is_none());
+        assert!(!topics[0].skip_next_run);
+        assert!(topics[0].dedup_days.is_none());
+        assert!(topics[0].mcp_servers.is_empty());
+    }
+
+    #[test]
+    fn test_topic_snooze_and_skip_next_run() {
+        let conn = setup_test_db();
+        let now = chrono::Utc::now();
+        let mut topic = Topic {
+            id: "topic-1".to_string(),
+            name: "Rust".to_string(),
+            description: None,
+            preferred_sources: vec![],
+            enabled: true,
+            snoozed_until: None,
+            skip_next_run: false,
+            dedup_days: None,
+            dedup_threshold: None,
+            mcp_servers: Vec::new(),
+            created_at: "2025-01-01T00:00:00Z".to_string(),
+            updated_at: "2025-01-01T00:00:00Z".to_string(),
+        };
+        assert!(topic.is_due(now));
+
+        topic.snooze(7, now);
+        assert!(topic.is_snoozed(now));
+        assert!(!topic.is_due(now));
+        assert!(topic.is_due(now + chrono::Duration::days(8)));
+        topic.snooze(0, now);
+        assert!(topic.is_due(now));
+
+        topic.skip_next_run = true;
+        insert_topic(&conn, &topic, 0).unwrap();
+        assert!(!get_all_topics(&conn).unwrap()[0].is_due(now));
+        assert_eq!(clear_skip_next_run(&conn).unwrap(), 1);
+        assert!(get_all_topics(&conn).unwrap()[0].is_due(now));
+    }
+
+    #[test]
+    fn test_recent_card_fingerprints_use_topic_windows() {
+        let conn = setup_test_db();
+        for (age, title) in [(1, "Yesterday"), (5, "Last week")] {
+            let cards = serde_json::json!([
+                { "title": format!("AI {}", title), "topic": "AI News", "summary": "" },
+                { "title": format!("Standards {}", title), "topic": "Standards", "summary": "" },
+                { "title": format!("Rust {}", title), "topic": "Rust", "summary": "" },
+            ]);
+            conn.execute(
+                "INSERT INTO briefings (date, title, cards) VALUES (datetime('now', ?1), 'Test', ?2)",
+                params![format!("-{} days", age), cards.to_string()],
+            )
+            .unwrap();
+        }
+        // Briefings saved before fingerprints were stored
+        assert_eq!(backfill_card_fingerprints(&conn).unwrap(), 6);
+        assert_eq!(backfill_card_fingerprints(&conn).unwrap(), 0);
+
+        let mut ai = Topic {
+            id: "topic-ai".to_string(),
+            name: "AI News".to_string(),
+            description: None,
+            preferred_sources: vec![],
+            enabled: true,
+            snoozed_until: None,
+            skip_next_run: false,
+            dedup_days: Some(2),
+            dedup_threshold: None,
+            mcp_servers: Vec::new(),
+            created_at: "2025-01-01T00:00:00Z".to_string(),
+            updated_at: "2025-01-01T00:00:00Z".to_string(),
+        };
+        insert_topic(&conn, &ai, 0).unwrap();
+        ai.id = "topic-standards".to_string();
+        ai.name = "Standards".to_string();
+        ai.dedup_days = Some(30);
+        ai.dedup_threshold = Some(0.9);
+        insert_topic(&conn, &ai, 1).unwrap();
+
+        let topics = get_all_topics(&conn).unwrap();
+        assert_eq!(topics[1].dedup_days, Some(30));
+        assert_eq!(topics[1].dedup_threshold, Some(0.9));
+
+        let policy = crate::dedup::DedupPolicy::for_topics(3, 0.75, &topics);
+        let mut titles: Vec<String> = get_recent_card_fingerprints_for(&conn, &policy)
+            .unwrap()
+            .into_iter()
+            .map(|f| f.title)
+            .collect();
+        titles.sort();
+        assert_eq!(
+            titles,
+            vec![
+                "AI Yesterday",
+                "Rust Yesterday",
+                "Standards Last week",
+                "Standards Yesterday"
+            ]
+        );
+
+        // Without the table the briefings' cards are read instead
+        conn.execute_batch("DROP TABLE card_fingerprints").unwrap();
+        let mut fallback: Vec<String> = get_recent_card_fingerprints_for(&conn, &policy)
+            .unwrap()
+            .into_iter()
+            .map(|f| f.title)
+            .collect();
+        fallback.sort();
+        assert_eq!(fallback, titles);
+    }
+
+    #[test]
+    fn test_import_legacy_interests_skips_existing_topics() {
+        let conn = setup_test_db();
+        let interests = vec!["Rust".to_string(), "AI safety".to_string()];
+        assert_eq!(import_legacy_interests(&conn, &interests).unwrap(), 2);
+
+        let again = vec!["rust".to_string(), "WebAssembly".to_string()];
+        assert_eq!(import_legacy_interests(&conn, &again).unwrap(), 1);
+
+        let names: Vec<String> = get_all_topics(&conn)
+            .unwrap()
+            .into_iter()
+            .map(|t| t.name)
+            .collect();
+        assert_eq!(names, vec!["Rust", "AI safety", "WebAssembly"]);
+    }
 }
@@ -5,9 +5,11 @@
 #![allow(dead_code)]
 
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use strsim::normalized_levenshtein;
 use tracing::info;
 
+use crate::db::Topic;
 use crate::research::BriefingCard;
 
 /// Fingerprint of a past card for comparison
@@ -36,12 +38,25 @@ fn normalize(s: &str) -> String {
         .join(" ")
 }
 
+/// Stable hash of a normalized card title (64-bit FNV-1a, as hex), so the
+/// same title always hashes the same across runs and versions
+pub fn title_hash(title: &str) -> String {
+    let hash = normalize(title)
+        .bytes()
+        .fold(0xcbf29ce484222325_u64, |hash, byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(0x100000001b3)
+        });
+    format!("{:016x}", hash)
+}
+
 /// Calculate similarity ratio between two strings (0.0 - 1.0)
 /// Uses normalized Levenshtein distance
 pub fn similarity(a: &str, b: &str) -> f64 {
-    let a = normalize(a);
-    let b = normalize(b);
+    normalized_similarity(&normalize(a), &normalize(b))
+}
 
+/// [`similarity`] of two already normalized strings
+fn normalized_similarity(a: &str, b: &str) -> f64 {
     if a.is_empty() && b.is_empty() {
         return 1.0;
     }
@@ -49,40 +64,117 @@ pub fn similarity(a: &str, b: &str) -> f64 {
         return 0.0;
     }
 
-    normalized_levenshtein(&a, &b)
+    normalized_levenshtein(a, b)
 }
 
 /// Check if a card is a duplicate of any past card
 /// Only compares cards with the same topic
 pub fn is_duplicate(card: &BriefingCard, past: &[CardFingerprint], threshold: f64) -> bool {
-    for past_card in past {
-        // Only compare cards from the same topic
-        if normalize(&card.topic) != normalize(&past_card.topic) {
-            continue;
+    PastCards::new(past).is_duplicate(card, threshold)
+}
+
+/// A normalized title or summary of a past card.
+#[derive(Debug)]
+struct PastText<'a> {
+    text: String,
+    chars: usize,
+    /// Title of the card it belongs to, for logging
+    title: &'a str,
+}
+
+/// Titles and summaries of one topic's past cards, each sorted by length.
+#[derive(Debug, Default)]
+struct TopicTexts<'a> {
+    titles: Vec<PastText<'a>>,
+    summaries: Vec<PastText<'a>>,
+}
+
+/// Past cards normalized once and grouped by topic, so checking a new card
+/// only compares it with same-topic cards of a length that could reach the
+/// threshold (two strings are at most as similar as the shorter one's share
+/// of the longer one's length).
+#[derive(Debug)]
+pub struct PastCards<'a> {
+    by_topic: HashMap<String, TopicTexts<'a>>,
+}
+
+impl<'a> PastCards<'a> {
+    pub fn new(past: &'a [CardFingerprint]) -> Self {
+        let mut by_topic: HashMap<String, TopicTexts<'a>> = HashMap::new();
+        for card in past {
+            let texts = by_topic.entry(normalize(&card.topic)).or_default();
+            for (field, list) in [
+                (&card.title, &mut texts.titles),
+                (&card.summary, &mut texts.summaries),
+            ] {
+                let text = normalize(field);
+                list.push(PastText {
+                    chars: text.chars().count(),
+                    text,
+                    title: &card.title,
+                });
+            }
         }
+        for texts in by_topic.values_mut() {
+            texts.titles.sort_by_key(|t| t.chars);
+            texts.summaries.sort_by_key(|t| t.chars);
+        }
+        Self { by_topic }
+    }
+
+    /// The first text in `sorted` at least `threshold` similar to `text`
+    fn find_similar<'s>(
+        sorted: &'s [PastText<'a>],
+        text: &str,
+        threshold: f64,
+    ) -> Option<(&'s PastText<'a>, f64)> {
+        let chars = text.chars().count();
+        // Lengths outside [chars * threshold, chars / threshold] can't match;
+        // rounded outwards so float error never skips a match
+        let min_chars = (chars as f64 * threshold).floor().max(0.0) as usize;
+        let max_chars = if threshold > 0.0 {
+            (chars as f64 / threshold).ceil() as usize
+        } else {
+            usize::MAX
+        };
+        let start = sorted.partition_point(|t| t.chars < min_chars);
+        sorted[start..]
+            .iter()
+            .take_while(|t| t.chars <= max_chars)
+            .map(|t| (t, normalized_similarity(text, &t.text)))
+            .find(|(_, sim)| *sim >= threshold)
+    }
+
+    /// Check if a card is a duplicate of any past card of the same topic
+    pub fn is_duplicate(&self, card: &BriefingCard, threshold: f64) -> bool {
+        let Some(texts) = self.by_topic.get(&normalize(&card.topic)) else {
+            return false;
+        };
 
         // Check title similarity
-        let title_sim = similarity(&card.title, &past_card.title);
-        if title_sim >= threshold {
+        if let Some((past, title_sim)) =
+            Self::find_similar(&texts.titles, &normalize(&card.title), threshold)
+        {
             info!(
                 "Duplicate detected: '{}' similar to '{}' (similarity: {:.2})",
-                card.title, past_card.title, title_sim
+                card.title, past.title, title_sim
             );
             return true;
         }
 
         // Also check summary similarity for same-topic cards
-        let summary_sim = similarity(&card.summary, &past_card.summary);
-        if summary_sim >= threshold {
+        if let Some((_, summary_sim)) =
+            Self::find_similar(&texts.summaries, &normalize(&card.summary), threshold)
+        {
             info!(
                 "Duplicate detected via summary: '{}' (similarity: {:.2})",
                 card.title, summary_sim
             );
             return true;
         }
-    }
 
-    false
+        false
+    }
 }
 
 /// Filter out duplicate cards from new cards
@@ -96,10 +188,11 @@ pub fn filter_duplicates(
         return new_cards;
     }
 
+    let past = PastCards::new(past);
     let original_count = new_cards.len();
     let filtered: Vec<BriefingCard> = new_cards
         .into_iter()
-        .filter(|card| !is_duplicate(card, past, threshold))
+        .filter(|card| !past.is_duplicate(card, threshold))
         .collect();
 
     let removed = original_count - filtered.len();
@@ -113,22 +206,156 @@ pub fn filter_duplicates(
     filtered
 }
 
-/// Format past cards for inclusion in synthesis prompt
-pub fn format_past_cards_for_prompt(past: &[CardFingerprint]) -> String {
+/// Dedup window and threshold for each topic: the global settings unless the
+/// topic overrides them. A window of 0 days or a threshold of 0 turns
+/// deduplication off for that topic.
+#[derive(Debug, Clone)]
+pub struct DedupPolicy {
+    days: i32,
+    threshold: f64,
+    overrides: HashMap<String, (Option<i32>, Option<f64>)>, // Keyed by normalized topic name
+}
+
+impl DedupPolicy {
+    pub fn new(days: i32, threshold: f64) -> Self {
+        Self {
+            days,
+            threshold,
+            overrides: HashMap::new(),
+        }
+    }
+
+    /// Policy from the global settings plus each topic's overrides
+    pub fn for_topics(days: i32, threshold: f64, topics: &[Topic]) -> Self {
+        let mut policy = Self::new(days, threshold);
+        for topic in topics {
+            if topic.dedup_days.is_some() || topic.dedup_threshold.is_some() {
+                policy.overrides.insert(
+                    normalize(&topic.name),
+                    (topic.dedup_days, topic.dedup_threshold),
+                );
+            }
+        }
+        policy
+    }
+
+    /// How many days back to look for duplicates of `topic`'s cards
+    pub fn days_for(&self, topic: &str) -> i32 {
+        self.overrides
+            .get(&normalize(topic))
+            .and_then(|(days, _)| *days)
+            .unwrap_or(self.days)
+    }
+
+    /// Similarity at which a `topic` card counts as a duplicate
+    pub fn threshold_for(&self, topic: &str) -> f64 {
+        self.overrides
+            .get(&normalize(topic))
+            .and_then(|(_, threshold)| *threshold)
+            .unwrap_or(self.threshold)
+    }
+
+    /// The longest window of any topic, i.e. how far back past cards are needed
+    pub fn max_days(&self) -> i32 {
+        self.overrides
+            .values()
+            .filter_map(|(days, _)| *days)
+            .fold(self.days, i32::max)
+    }
+}
+
+/// Filter out duplicate cards, using each card's topic threshold from `policy`
+pub fn filter_duplicates_by_topic(
+    new_cards: Vec<BriefingCard>,
+    past: &[CardFingerprint],
+    policy: &DedupPolicy,
+) -> Vec<BriefingCard> {
     if past.is_empty() {
+        return new_cards;
+    }
+
+    let past = PastCards::new(past);
+    let original_count = new_cards.len();
+    let filtered: Vec<BriefingCard> = new_cards
+        .into_iter()
+        .filter(|card| {
+            let threshold = policy.threshold_for(&card.topic);
+            threshold <= 0.0 || !past.is_duplicate(card, threshold)
+        })
+        .collect();
+
+    let removed = original_count - filtered.len();
+    if removed > 0 {
+        info!(
+            "Deduplication: removed {} duplicate cards (per-topic thresholds)",
+            removed
+        );
+    }
+
+    filtered
+}
+
+/// Default token budget for the recent cards listed in the synthesis prompt
+/// (around 60 card titles).
+pub const DEFAULT_CONTEXT_TOKENS: u32 = 1_000;
+
+const PAST_CARDS_HEADER: &str =
+    "RECENTLY COVERED TOPICS (avoid duplicating unless there's significant NEW information):";
+
+/// Rough token count of prompt text (about 4 characters per token)
+fn estimate_tokens(text: &str) -> usize {
+    text.len().div_ceil(4)
+}
+
+/// Format past cards for inclusion in synthesis prompt, within `max_tokens`.
+///
+/// `past` is newest first. Cards of the topics being researched (`topics`)
+/// go in before other topics' cards, newest first within each, until the
+/// budget is spent; repeated titles are listed once. A budget of 0 leaves
+/// past cards out of the prompt (they are still filtered afterwards).
+pub fn format_past_cards_for_prompt(
+    past: &[CardFingerprint],
+    topics: &[String],
+    max_tokens: usize,
+) -> String {
+    let mut budget = max_tokens.saturating_sub(estimate_tokens(PAST_CARDS_HEADER));
+    if past.is_empty() || budget == 0 {
         return String::new();
     }
 
-    let formatted: Vec<String> = past
+    let researched: Vec<String> = topics.iter().map(|t| normalize(t)).collect();
+    let (same_topic, other_topic): (Vec<&CardFingerprint>, Vec<&CardFingerprint>) = past
         .iter()
-        .take(30) // Limit to avoid huge prompts
-        .map(|c| format!("- [{}]: \"{}\"", c.topic, c.title))
-        .collect();
+        .partition(|c| researched.contains(&normalize(&c.topic)));
 
-    format!(
-        "RECENTLY COVERED TOPICS (avoid duplicating unless there's significant NEW information):\n{}",
-        formatted.join("\n")
-    )
+    let mut seen = std::collections::HashSet::new();
+    let mut lines = Vec::new();
+    for card in same_topic.into_iter().chain(other_topic) {
+        if !seen.insert((normalize(&card.topic), normalize(&card.title))) {
+            continue;
+        }
+        let line = format!("- [{}]: \"{}\"", card.topic, card.title);
+        // +1 for the newline
+        let cost = estimate_tokens(&line) + 1;
+        if cost > budget {
+            break;
+        }
+        budget -= cost;
+        lines.push(line);
+    }
+
+    if lines.is_empty() {
+        return String::new();
+    }
+    if lines.len() < past.len() {
+        info!(
+            "Past cards context: {} of {} cards within {} tokens",
+            lines.len(),
+            past.len(),
+            max_tokens
+        );
+    }
+    format!("{}\n{}", PAST_CARDS_HEADER, lines.join("\n"))
 }
 
 #[cfg(test)]
@@ -145,6 +372,13 @@ mod tests {
         assert!((similarity("Hello World", "hello world") - 1.0).abs() < 0.01);
     }
 
+    #[test]
+    fn test_title_hash() {
+        assert_eq!(title_hash(""), "cbf29ce484222325");
+        assert_eq!(title_hash("Rust 2.0"), title_hash("  rust   2.0 "));
+        assert_ne!(title_hash("Rust 2.0"), title_hash("Rust 2.1"));
+    }
+
     #[test]
     fn test_similarity_different_strings() {
         let sim = similarity("OpenAI releases GPT-5", "Anthropic announces Claude 4");
@@ -173,6 +407,11 @@ mod tests {
             image_prompt: None,
             image_style: None,
             image_path: None,
+            source_checks: vec![],
+            action_items: vec![],
+            actions: vec![],
+            source_warning: None,
+            rank: None,
         };
 
         let past = vec![CardFingerprint {
@@ -197,6 +436,11 @@ mod tests {
             image_prompt: None,
             image_style: None,
             image_path: None,
+            source_checks: vec![],
+            action_items: vec![],
+            actions: vec![],
+            source_warning: None,
+            rank: None,
         };
 
         let past = vec![CardFingerprint {
@@ -223,6 +467,11 @@ mod tests {
                 image_prompt: None,
                 image_style: None,
                 image_path: None,
+                source_checks: vec![],
+                action_items: vec![],
+                actions: vec![],
+                source_warning: None,
+                rank: None,
             },
             BriefingCard {
                 title: "OpenAI releases GPT-5".to_string(),
@@ -235,6 +484,11 @@ mod tests {
                 image_prompt: None,
                 image_style: None,
                 image_path: None,
+                source_checks: vec![],
+                action_items: vec![],
+                actions: vec![],
+                source_warning: None,
+                rank: None,
             },
         ];
 
@@ -248,4 +502,166 @@ mod tests {
         assert_eq!(filtered.len(), 1);
         assert_eq!(filtered[0].title, "New development in AI");
     }
+
+    fn topic(name: &str, dedup_days: Option<i32>, dedup_threshold: Option<f64>) -> Topic {
+        Topic {
+            id: name.to_string(),
+            name: name.to_string(),
+            description: None,
+            preferred_sources: vec![],
+            enabled: true,
+            snoozed_until: None,
+            skip_next_run: false,
+            dedup_days,
+            dedup_threshold,
+            mcp_servers: Vec::new(),
+            created_at: String::new(),
+            updated_at: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_dedup_policy_overrides() {
+        let topics = vec![
+            topic("AI News", Some(2), None),
+            topic("Standards", Some(30), Some(0.9)),
+            topic("Rust", None, None),
+        ];
+        let policy = DedupPolicy::for_topics(14, 0.75, &topics);
+
+        assert_eq!(policy.days_for("AI News"), 2);
+        assert_eq!(policy.days_for("ai  news"), 2);
+        assert_eq!(policy.days_for("Standards"), 30);
+        assert_eq!(policy.days_for("Rust"), 14);
+        assert_eq!(policy.days_for("Unknown"), 14);
+        assert!((policy.threshold_for("AI News") - 0.75).abs() < f64::EPSILON);
+        assert!((policy.threshold_for("Standards") - 0.9).abs() < f64::EPSILON);
+        assert_eq!(policy.max_days(), 30);
+    }
+
+    #[test]
+    fn test_filter_duplicates_by_topic() {
+        let card = |title: &str, topic: &str| BriefingCard {
+            title: title.to_string(),
+            summary: String::new(),
+            detailed_content: String::new(),
+            sources: vec![],
+            suggested_next: None,
+            relevance: "high".to_string(),
+            topic: topic.to_string(),
+            image_prompt: None,
+            image_style: None,
+            image_path: None,
+            source_checks: vec![],
+            action_items: vec![],
+            actions: vec![],
+            source_warning: None,
+            rank: None,
+        };
+        let past = vec![
+            CardFingerprint {
+                title: "OpenAI releases GPT-5 today".to_string(),
+                topic: "AI".to_string(),
+                summary: String::new(),
+            },
+            CardFingerprint {
+                title: "OpenAI releases GPT-5 today".to_string(),
+                topic: "Standards".to_string(),
+                summary: String::new(),
+            },
+        ];
+        // Dedup is off for Standards
+        let policy = DedupPolicy::for_topics(14, 0.75, &[topic("Standards", None, Some(0.0))]);
+
+        let filtered = filter_duplicates_by_topic(
+            vec![
+                card("OpenAI releases GPT-5", "AI"),
+                card("OpenAI releases GPT-5", "Standards"),
+            ],
+            &past,
+            &policy,
+        );
+        assert_eq!(filtered.len(), 1);
+        assert_eq!(filtered[0].topic, "Standards");
+    }
+
+    #[test]
+    fn test_format_past_cards_within_budget() {
+        let fingerprint = |title: &str, topic: &str| CardFingerprint {
+            title: title.to_string(),
+            topic: topic.to_string(),
+            summary: String::new(),
+        };
+        // Newest first
+        let past = vec![
+            fingerprint("Kubernetes 1.31 released", "Cloud"),
+            fingerprint("Rust 1.80 released", "Rust"),
+            fingerprint("Rust 1.80 released", "Rust"),
+            fingerprint("Cargo gets a new resolver", "Rust"),
+            fingerprint("AWS raises prices", "Cloud"),
+        ];
+        let topics = vec!["rust".to_string()];
+
+        let context = format_past_cards_for_prompt(&past, &topics, 10_000);
+        let lines: Vec<&str> = context.lines().skip(1).collect();
+        assert_eq!(
+            lines,
+            vec![
+                "- [Rust]: \"Rust 1.80 released\"",
+                "- [Rust]: \"Cargo gets a new resolver\"",
+                "- [Cloud]: \"Kubernetes 1.31 released\"",
+                "- [Cloud]: \"AWS raises prices\"",
+            ]
+        );
+
+        // A small budget keeps the researched topic's newest cards
+        let header = estimate_tokens(PAST_CARDS_HEADER);
+        let context = format_past_cards_for_prompt(&past, &topics, header + 20);
+        assert_eq!(context.lines().count(), 3);
+        assert!(!context.contains("Cloud"));
+        assert!(estimate_tokens(&context) <= header + 20);
+
+        assert_eq!(format_past_cards_for_prompt(&past, &topics, 0), "");
+        assert_eq!(format_past_cards_for_prompt(&[], &topics, 10_000), "");
+    }
+
+    proptest::proptest! {
+        #[test]
+        fn prop_past_cards_match_pairwise_comparison(
+            past in proptest::collection::vec(("[ab]{1,2}", "[xy ]{0,12}", "[xy ]{0,12}"), 0..20),
+            card in ("[ab]{1,2}", "[xy ]{0,12}", "[xy ]{0,12}"),
+            threshold in 0.0..1.0f64,
+        ) {
+            let past: Vec<CardFingerprint> = past
+                .into_iter()
+                .map(|(topic, title, summary)| CardFingerprint { title, topic, summary })
+                .collect();
+            let (topic, title, summary) = card;
+            let card = BriefingCard {
+                title,
+                summary,
+                detailed_content: String::new(),
+                sources: vec![],
+                suggested_next: None,
+                relevance: "high".to_string(),
+                topic,
+                image_prompt: None,
+                image_style: None,
+                image_path: None,
+                source_checks: vec![],
+                action_items: vec![],
+                actions: vec![],
+                source_warning: None,
+                rank: None,
+            };
+
+            // Skipping cards by length never skips a duplicate
+            let pairwise = past.iter().any(|p| {
+                normalize(&p.topic) == normalize(&card.topic)
+                    && (similarity(&card.title, &p.title) >= threshold
+                        || similarity(&card.summary, &p.summary) >= threshold)
+            });
+            proptest::prop_assert_eq!(is_duplicate(&card, &past, threshold), pairwise);
+        }
+    }
 }
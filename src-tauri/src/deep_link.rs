@@ -0,0 +1,162 @@
+//! `claudius://` deep links.
+//!
+//! The desktop app registers the `claudius` URL scheme so notifications,
+//! exported markdown and API events can link straight to a briefing:
+//!
+//! - `claudius://briefing/123` opens briefing 123
+//! - `claudius://briefing/123/card/2` opens briefing 123 scrolled to its
+//!   second card (card numbers are 1-based, as in the CLI)
+
+use lazy_static::lazy_static;
+use serde::Serialize;
+use std::sync::Mutex;
+
+/// URL scheme registered by the desktop app.
+pub const SCHEME: &str = "claudius";
+
+/// A parsed deep link, emitted to the frontend as the `deeplink:open` event.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct DeepLink {
+    pub briefing_id: i64,
+    /// 0-based card index, if the link points at a single card
+    pub card_index: Option<usize>,
+}
+
+impl DeepLink {
+    pub fn briefing(briefing_id: i64) -> Self {
+        Self {
+            briefing_id,
+            card_index: None,
+        }
+    }
+
+    pub fn card(briefing_id: i64, card_index: usize) -> Self {
+        Self {
+            briefing_id,
+            card_index: Some(card_index),
+        }
+    }
+
+    /// Parse a `claudius://` URL. Returns `None` for other schemes or paths.
+    pub fn parse(url: &str) -> Option<Self> {
+        let rest = url.trim().strip_prefix(SCHEME)?.strip_prefix("://")?;
+        // Ignore any query string or fragment, and a trailing slash
+        let path = rest.split(['?', '#']).next()?.trim_end_matches('/');
+        let mut parts = path.split('/');
+
+        if parts.next()? != "briefing" {
+            return None;
+        }
+        let briefing_id = parts.next()?.parse().ok()?;
+        let link = match (parts.next(), parts.next()) {
+            (None, _) => Self::briefing(briefing_id),
+            (Some("card"), Some(number)) => {
+                let number: usize = number.parse().ok()?;
+                Self::card(briefing_id, number.checked_sub(1)?)
+            }
+            _ => return None,
+        };
+        parts.next().is_none().then_some(link)
+    }
+
+    pub fn url(&self) -> String {
+        match self.card_index {
+            Some(index) => format!(
+                "{}://briefing/{}/card/{}",
+                SCHEME,
+                self.briefing_id,
+                index + 1
+            ),
+            None => format!("{}://briefing/{}", SCHEME, self.briefing_id),
+        }
+    }
+}
+
+lazy_static! {
+    /// A link that arrived before the frontend was listening (e.g. the one
+    /// that launched the app).
+    static ref PENDING: Mutex<Option<DeepLink>> = Mutex::new(None);
+}
+
+/// Remember a link until the frontend asks for it with `take_pending`.
+pub fn set_pending(link: DeepLink) {
+    *PENDING.lock().unwrap_or_else(|e| e.into_inner()) = Some(link);
+}
+
+/// Take the link waiting for the frontend, if any.
+pub fn take_pending() -> Option<DeepLink> {
+    PENDING.lock().unwrap_or_else(|e| e.into_inner()).take()
+}
+
+/// Link that opens a briefing in the desktop app.
+pub fn briefing_url(briefing_id: i64) -> String {
+    DeepLink::briefing(briefing_id).url()
+}
+
+/// Hand a URL to the OS so the registered app opens it.
+#[allow(dead_code)]
+pub fn open_url(url: &str) -> Result<(), String> {
+    #[cfg(target_os = "macos")]
+    let mut command = std::process::Command::new("open");
+    #[cfg(target_os = "windows")]
+    let mut command = {
+        let mut command = std::process::Command::new("cmd");
+        command.args(["/C", "start", ""]);
+        command
+    };
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    let mut command = std::process::Command::new("xdg-open");
+
+    let status = command
+        .arg(url)
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .status()
+        .map_err(|e| format!("Failed to open {}: {}", url, e))?;
+    if !status.success() {
+        return Err(format!(
+            "Failed to open {} (is the Claudius app installed?)",
+            url
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_links() {
+        assert_eq!(
+            DeepLink::parse("claudius://briefing/123"),
+            Some(DeepLink::briefing(123))
+        );
+        assert_eq!(
+            DeepLink::parse("claudius://briefing/123/"),
+            Some(DeepLink::briefing(123))
+        );
+        assert_eq!(
+            DeepLink::parse("claudius://briefing/123/card/2?from=notification"),
+            Some(DeepLink::card(123, 1))
+        );
+        assert_eq!(DeepLink::parse("claudius://briefing/abc"), None);
+        assert_eq!(DeepLink::parse("claudius://briefing/123/card/0"), None);
+        assert_eq!(DeepLink::parse("claudius://briefing/123/card/2/x"), None);
+        assert_eq!(DeepLink::parse("claudius://settings"), None);
+        assert_eq!(DeepLink::parse("https://briefing/123"), None);
+    }
+
+    #[test]
+    fn test_url_round_trip() {
+        for link in [
+            DeepLink::briefing(7),
+            DeepLink::card(7, 0),
+            DeepLink::card(7, 4),
+        ] {
+            assert_eq!(DeepLink::parse(&link.url()), Some(link));
+        }
+        assert_eq!(briefing_url(42), "claudius://briefing/42");
+        assert_eq!(DeepLink::card(42, 0).url(), "claudius://briefing/42/card/1");
+    }
+}
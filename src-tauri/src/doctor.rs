@@ -0,0 +1,371 @@
+//! Health checks for `claudius doctor`.
+//!
+//! Each check reports pass, warn or fail with a short detail line. Checks
+//! never stop at the first problem, so one run shows everything that needs
+//! fixing. Network checks (API keys, MCP servers) make the cheapest request
+//! that proves the credential or server works.
+
+use crate::config::{self, get_config_dir};
+use crate::db;
+use crate::mcp_client::{load_mcp_servers, McpClient};
+use serde::Serialize;
+use std::path::Path;
+use std::time::Duration;
+
+/// Free space below which the disk check fails.
+const MIN_FREE_MB: u64 = 100;
+
+/// Free space below which the disk check warns.
+const LOW_FREE_MB: u64 = 1024;
+
+/// Longest an MCP server may take to start and list its tools.
+const MCP_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Where the desktop app installs the CLI symlink.
+pub const CLI_SYMLINK_PATH: &str = "/usr/local/bin/claudius";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckStatus {
+    Pass,
+    Warn,
+    Fail,
+}
+
+/// Outcome of one check.
+#[derive(Debug, Clone, Serialize)]
+pub struct Check {
+    pub name: String,
+    pub status: CheckStatus,
+    pub detail: String,
+}
+
+impl Check {
+    fn new(name: &str, status: CheckStatus, detail: impl Into<String>) -> Self {
+        Self {
+            name: name.to_string(),
+            status,
+            detail: detail.into(),
+        }
+    }
+
+    fn pass(name: &str, detail: impl Into<String>) -> Self {
+        Self::new(name, CheckStatus::Pass, detail)
+    }
+
+    fn warn(name: &str, detail: impl Into<String>) -> Self {
+        Self::new(name, CheckStatus::Warn, detail)
+    }
+
+    fn fail(name: &str, detail: impl Into<String>) -> Self {
+        Self::new(name, CheckStatus::Fail, detail)
+    }
+}
+
+/// Full doctor report.
+#[derive(Debug, Clone, Serialize)]
+pub struct Report {
+    pub checks: Vec<Check>,
+    pub passed: usize,
+    pub warnings: usize,
+    pub failures: usize,
+}
+
+impl Report {
+    fn new(checks: Vec<Check>) -> Self {
+        let count = |status| checks.iter().filter(|c| c.status == status).count();
+        Self {
+            passed: count(CheckStatus::Pass),
+            warnings: count(CheckStatus::Warn),
+            failures: count(CheckStatus::Fail),
+            checks,
+        }
+    }
+}
+
+/// Config directory exists, is writable and isn't writable by other users.
+pub fn check_config_dir(dir: &Path) -> Check {
+    const NAME: &str = "Config directory";
+    if !dir.is_dir() {
+        return Check::fail(NAME, format!("{} does not exist", dir.display()));
+    }
+
+    let probe = dir.join(".doctor-write-test");
+    if let Err(e) = std::fs::write(&probe, b"ok") {
+        return Check::fail(NAME, format!("{} is not writable: {}", dir.display(), e));
+    }
+    let _ = std::fs::remove_file(&probe);
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        if let Ok(meta) = std::fs::metadata(dir) {
+            let mode = meta.permissions().mode() & 0o777;
+            if mode & 0o022 != 0 {
+                return Check::warn(
+                    NAME,
+                    format!(
+                        "{} is writable by other users (mode {:o}); run: chmod 700 {}",
+                        dir.display(),
+                        mode,
+                        dir.display()
+                    ),
+                );
+            }
+        }
+    }
+
+    Check::pass(NAME, format!("{} is writable", dir.display()))
+}
+
+/// SQLite integrity check of the briefing database.
+pub fn check_database(path: &Path) -> Check {
+    const NAME: &str = "Database";
+    if !path.exists() {
+        return Check::warn(
+            NAME,
+            format!("{} not created yet (open the app once)", path.display()),
+        );
+    }
+    let conn = match rusqlite::Connection::open(path) {
+        Ok(conn) => conn,
+        Err(e) => return Check::fail(NAME, format!("Cannot open {}: {}", path.display(), e)),
+    };
+    match conn.query_row("PRAGMA integrity_check", [], |row| row.get::<_, String>(0)) {
+        Ok(result) if result == "ok" => Check::pass(NAME, "Integrity check passed"),
+        Ok(result) => Check::fail(NAME, format!("Integrity check failed: {}", result)),
+        Err(e) => Check::fail(NAME, format!("Integrity check could not run: {}", e)),
+    }
+}
+
+/// Anthropic API key of the active profile: present, well formed and accepted.
+pub async fn check_anthropic_key() -> Check {
+    const NAME: &str = "Anthropic API key";
+    let profile = config::active_profile();
+    let Some(key) = config::read_api_key() else {
+        return Check::fail(
+            NAME,
+            format!(
+                "No key for profile '{}'; set one with: claudius config api-key set <key>",
+                profile
+            ),
+        );
+    };
+    if let Err(e) = config::validate_api_key(&key) {
+        return Check::fail(NAME, e);
+    }
+    match crate::models::fetch_models(&key).await {
+        Ok(models) => Check::pass(
+            NAME,
+            format!(
+                "Accepted for profile '{}' ({} models available)",
+                profile,
+                models.len()
+            ),
+        ),
+        Err(e) => Check::fail(NAME, format!("Rejected or unreachable: {}", e)),
+    }
+}
+
+/// OpenAI key, only needed for image generation and narration.
+pub async fn check_openai_key() -> Check {
+    const NAME: &str = "OpenAI API key";
+    let Some(key) = config::read_openai_api_key() else {
+        return Check::pass(
+            NAME,
+            "Not configured (only needed for images and narration)",
+        );
+    };
+    if let Err(e) = config::validate_openai_api_key(&key) {
+        return Check::fail(NAME, e);
+    }
+    let response = crate::http_client::client()
+        .get("https://api.openai.com/v1/models")
+        .header("Authorization", format!("Bearer {}", key))
+        .timeout(Duration::from_secs(15))
+        .send()
+        .await;
+    match response {
+        Ok(resp) if resp.status().is_success() => Check::pass(NAME, "Accepted"),
+        Ok(resp) => Check::fail(NAME, format!("Rejected (HTTP {})", resp.status().as_u16())),
+        Err(e) => Check::warn(NAME, format!("Could not reach OpenAI: {}", e)),
+    }
+}
+
+/// Start each enabled MCP server and list its tools.
+pub async fn check_mcp_servers() -> Vec<Check> {
+    let servers = match load_mcp_servers() {
+        Ok(servers) => servers,
+        Err(e) => return vec![Check::fail("MCP servers", e)],
+    };
+    let enabled: Vec<_> = servers.into_iter().filter(|s| s.enabled).collect();
+    if enabled.is_empty() {
+        return vec![Check::warn(
+            "MCP servers",
+            "None enabled; research is limited to built-in tools",
+        )];
+    }
+
+    let mut checks = Vec::new();
+    for server in enabled {
+        let name = format!("MCP server '{}'", server.name);
+        let check = match tokio::time::timeout(MCP_TIMEOUT, McpClient::check_server(&server)).await
+        {
+            Ok(Ok(tools)) if tools.is_empty() => Check::warn(&name, "Connected but lists no tools"),
+            Ok(Ok(tools)) => Check::pass(
+                &name,
+                format!("{} tools: {}", tools.len(), tools.join(", ")),
+            ),
+            Ok(Err(e)) => Check::fail(&name, e),
+            Err(_) => Check::fail(
+                &name,
+                format!("No answer within {}s", MCP_TIMEOUT.as_secs()),
+            ),
+        };
+        checks.push(check);
+    }
+    checks
+}
+
+/// Parse the available kilobytes from `df -Pk` output.
+fn parse_df_available_kb(output: &str) -> Option<u64> {
+    output
+        .lines()
+        .nth(1)?
+        .split_whitespace()
+        .nth(3)?
+        .parse()
+        .ok()
+}
+
+/// Free disk space where the database and images live.
+pub fn check_disk_space(dir: &Path) -> Check {
+    const NAME: &str = "Disk space";
+    let output = std::process::Command::new("df")
+        .arg("-Pk")
+        .arg(dir)
+        .output();
+    let available_kb = match output {
+        Ok(out) if out.status.success() => {
+            parse_df_available_kb(&String::from_utf8_lossy(&out.stdout))
+        }
+        _ => None,
+    };
+    let Some(kb) = available_kb else {
+        return Check::warn(NAME, "Could not determine free space");
+    };
+
+    let mb = kb / 1024;
+    let detail = format!("{} MB free", mb);
+    if mb < MIN_FREE_MB {
+        Check::fail(NAME, detail)
+    } else if mb < LOW_FREE_MB {
+        Check::warn(NAME, detail)
+    } else {
+        Check::pass(NAME, detail)
+    }
+}
+
+/// The `claudius` symlink installed by the desktop app points at a CLI binary
+/// that still exists.
+pub fn check_cli_symlink(path: &Path) -> Check {
+    const NAME: &str = "CLI symlink";
+    let target = match std::fs::read_link(path) {
+        Ok(target) => target,
+        Err(_) if path.exists() => {
+            return Check::pass(NAME, format!("{} is a regular file", path.display()))
+        }
+        Err(_) => {
+            return Check::warn(
+                NAME,
+                format!(
+                    "{} not installed (install it from Settings in the app)",
+                    path.display()
+                ),
+            )
+        }
+    };
+    if !path.exists() {
+        return Check::fail(
+            NAME,
+            format!(
+                "{} points to missing {}; reinstall it from Settings",
+                path.display(),
+                target.display()
+            ),
+        );
+    }
+    if !target.to_string_lossy().to_lowercase().contains("claudius") {
+        return Check::warn(
+            NAME,
+            format!("{} points to {}", path.display(), target.display()),
+        );
+    }
+    Check::pass(NAME, format!("{} -> {}", path.display(), target.display()))
+}
+
+/// Run every check.
+pub async fn run() -> Report {
+    let config_dir = get_config_dir();
+    let mut checks = vec![
+        check_config_dir(&config_dir),
+        check_database(&db::get_db_path()),
+        check_anthropic_key().await,
+        check_openai_key().await,
+    ];
+    checks.extend(check_mcp_servers().await);
+    checks.push(check_disk_space(&config::get_data_dir()));
+    checks.push(check_cli_symlink(Path::new(CLI_SYMLINK_PATH)));
+    Report::new(checks)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_df_available_kb() {
+        let output = "Filesystem 1024-blocks      Used Available Capacity Mounted on\n\
+                      /dev/disk3s5 971350180 512341234 430000000    55% /System/Volumes/Data\n";
+        assert_eq!(parse_df_available_kb(output), Some(430000000));
+        assert_eq!(parse_df_available_kb("Filesystem\n"), None);
+        assert_eq!(parse_df_available_kb(""), None);
+    }
+
+    #[test]
+    fn test_check_config_dir_and_database() {
+        let dir = std::env::temp_dir().join(format!("claudius-doctor-{}", std::process::id()));
+        assert_eq!(check_config_dir(&dir).status, CheckStatus::Fail);
+        std::fs::create_dir_all(&dir).unwrap();
+        assert_ne!(check_config_dir(&dir).status, CheckStatus::Fail);
+
+        let db_path = dir.join("claudius.db");
+        assert_eq!(check_database(&db_path).status, CheckStatus::Warn);
+        rusqlite::Connection::open(&db_path)
+            .unwrap()
+            .execute_batch(include_str!("schema.sql"))
+            .unwrap();
+        assert_eq!(check_database(&db_path).status, CheckStatus::Pass);
+
+        let link = dir.join("claudius-link");
+        assert_eq!(check_cli_symlink(&link).status, CheckStatus::Warn);
+        #[cfg(unix)]
+        {
+            std::os::unix::fs::symlink(dir.join("missing-claudius"), &link).unwrap();
+            assert_eq!(check_cli_symlink(&link).status, CheckStatus::Fail);
+        }
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_report_counts() {
+        let report = Report::new(vec![
+            Check::pass("a", ""),
+            Check::warn("b", ""),
+            Check::fail("c", ""),
+            Check::pass("d", ""),
+        ]);
+        assert_eq!((report.passed, report.warnings, report.failures), (2, 1, 1));
+    }
+}
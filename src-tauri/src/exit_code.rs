@@ -0,0 +1,132 @@
+//! CLI exit codes.
+//!
+//! CLI commands fail with a message string; [`ExitCode::classify`] sorts
+//! those messages into a few failure kinds so scripts can branch on the
+//! exit status (or on `kind` and `code` in `--json` error output) instead
+//! of parsing text. Code 2 is left to clap for invalid arguments.
+
+use crate::research_log::ErrorCode;
+use serde::Serialize;
+
+/// Exit status of a failed CLI command.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExitCode {
+    /// Any failure not listed below
+    Failure = 1,
+    /// No Anthropic API key is configured
+    NoApiKey = 3,
+    /// There are no topics to research
+    NoTopics = 4,
+    /// Research was cancelled
+    Cancelled = 5,
+    /// The Anthropic API returned an error or couldn't be reached
+    ApiError = 6,
+    /// The database couldn't be opened, read or written
+    DbError = 7,
+    /// An MCP server couldn't be started or failed a call
+    McpError = 8,
+}
+
+impl ExitCode {
+    /// The process exit status.
+    pub fn code(self) -> i32 {
+        self as i32
+    }
+
+    /// Classify an error message. `mcp_command` is set for `claudius mcp`
+    /// subcommands, whose failures are MCP errors unless the message says
+    /// otherwise.
+    pub fn classify(message: &str, mcp_command: bool) -> (ExitCode, Option<ErrorCode>) {
+        let lower = message.to_lowercase();
+        let code = tagged_error_code(message);
+
+        let kind = if lower.contains("no api key configured") {
+            ExitCode::NoApiKey
+        } else if lower.contains("no topics") || lower.contains("all enabled topics are snoozed") {
+            ExitCode::NoTopics
+        } else if lower.contains("cancelled") {
+            ExitCode::Cancelled
+        } else if let Some(code) = &code {
+            match code {
+                ErrorCode::McpConnectionFailed | ErrorCode::McpToolFailed => ExitCode::McpError,
+                ErrorCode::ToolExecutionFailed | ErrorCode::InternalError | ErrorCode::Unknown => {
+                    ExitCode::Failure
+                }
+                _ => ExitCode::ApiError,
+            }
+        } else if lower.contains("database") {
+            ExitCode::DbError
+        } else if mcp_command || lower.contains("mcp server") {
+            ExitCode::McpError
+        } else {
+            ExitCode::Failure
+        };
+
+        let code = code.or(match kind {
+            ExitCode::NoApiKey => Some(ErrorCode::InvalidApiKey),
+            ExitCode::McpError => Some(ErrorCode::McpConnectionFailed),
+            _ => None,
+        });
+        (kind, code)
+    }
+}
+
+/// The error code a research error was tagged with, e.g. "(rate_limited)"
+/// (see `ResearchError::tagged`).
+fn tagged_error_code(message: &str) -> Option<ErrorCode> {
+    ErrorCode::ALL
+        .into_iter()
+        .find(|code| message.contains(&format!("({})", code.as_str())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::research_log::ResearchError;
+
+    #[test]
+    fn test_classify_messages() {
+        assert_eq!(
+            ExitCode::classify("Error: No API key configured.\n\nSet it", false).0,
+            ExitCode::NoApiKey
+        );
+        assert_eq!(
+            ExitCode::classify("No topics to research. Add topics with: ...", false).0,
+            ExitCode::NoTopics
+        );
+        assert_eq!(
+            ExitCode::classify("Research cancelled by user", false).0,
+            ExitCode::Cancelled
+        );
+        assert_eq!(
+            ExitCode::classify("Database connection failed: locked", false),
+            (ExitCode::DbError, None)
+        );
+        assert_eq!(
+            ExitCode::classify("Server 'x' not found", true),
+            (ExitCode::McpError, Some(ErrorCode::McpConnectionFailed))
+        );
+        assert_eq!(
+            ExitCode::classify("Topic 'x' not found", false),
+            (ExitCode::Failure, None)
+        );
+    }
+
+    #[test]
+    fn test_classify_tagged_research_errors() {
+        let err = ResearchError::new(ErrorCode::RateLimited, "Too many requests");
+        let message = format!("Synthesis failed: {}", err.tagged());
+        assert_eq!(
+            ExitCode::classify(&message, false),
+            (ExitCode::ApiError, Some(ErrorCode::RateLimited))
+        );
+
+        let err = ResearchError::new(ErrorCode::McpToolFailed, "boom");
+        assert_eq!(
+            ExitCode::classify(&err.tagged(), false).0,
+            ExitCode::McpError
+        );
+        assert_eq!(ExitCode::ApiError.code(), 6);
+    }
+}
@@ -0,0 +1,246 @@
+//! Atom feed of recent briefings.
+//!
+//! With `enable_atom_feed` set, `atom.xml` (in `~/.claudius`, or at
+//! `atom_feed_path`) is rewritten after every research run so briefings can
+//! be followed in any feed reader. Each card of the last [`FEED_BRIEFINGS`]
+//! briefings is one entry, linking to its first source. `claudius serve`
+//! serves the same feed at `/feed.xml`.
+
+use crate::config::Briefing;
+use crate::research::BriefingCard;
+use chrono::{DateTime, FixedOffset, Local, NaiveDateTime, Utc};
+use std::path::{Path, PathBuf};
+
+/// Briefings included in the feed.
+pub const FEED_BRIEFINGS: i32 = 20;
+
+/// Where the feed is written when `atom_feed_path` isn't set.
+pub fn default_feed_path() -> PathBuf {
+    crate::config::get_data_dir().join("atom.xml")
+}
+
+/// The feed path from settings, expanding a leading `~/`.
+pub fn feed_path(configured: Option<&str>) -> PathBuf {
+    configured
+        .map(str::trim)
+        .filter(|p| !p.is_empty())
+        .map(crate::integrations::expand_home)
+        .unwrap_or_else(default_feed_path)
+}
+
+/// Escape text for XML element content and attribute values.
+fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            // Control characters aren't allowed in XML 1.0
+            c if c.is_control() && !matches!(c, '\n' | '\r' | '\t') => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// A briefing date as RFC 3339. Briefings store local time without an
+/// offset ("2025-01-15T07:00:00").
+fn feed_timestamp(date: &str) -> String {
+    if let Ok(date) = DateTime::<FixedOffset>::parse_from_rfc3339(date) {
+        return date.to_rfc3339();
+    }
+    NaiveDateTime::parse_from_str(date, "%Y-%m-%dT%H:%M:%S")
+        .ok()
+        .and_then(|naive| naive.and_local_timezone(Local).earliest())
+        .map(|local| local.to_rfc3339())
+        .unwrap_or_else(|| Utc::now().to_rfc3339())
+}
+
+/// HTML body of a card's entry: summary, details and sources.
+fn card_html(card: &BriefingCard) -> String {
+    let mut html = format!("<p>{}</p>", escape_xml(&card.summary));
+    for paragraph in card
+        .detailed_content
+        .split("\n\n")
+        .map(str::trim)
+        .filter(|p| !p.is_empty())
+    {
+        html.push_str(&format!("<p>{}</p>", escape_xml(paragraph)));
+    }
+    if !card.sources.is_empty() {
+        html.push_str("<h4>Sources</h4><ul>");
+        for source in &card.sources {
+            match crate::sources::extract_url(source) {
+                Some(url) => html.push_str(&format!(
+                    "<li><a href=\"{}\">{}</a></li>",
+                    escape_xml(&url),
+                    escape_xml(source)
+                )),
+                None => html.push_str(&format!("<li>{}</li>", escape_xml(source))),
+            }
+        }
+        html.push_str("</ul>");
+    }
+    html
+}
+
+/// Render briefings (newest first) as an Atom feed, one entry per card in
+/// display order.
+pub fn render_feed(briefings: &[Briefing]) -> String {
+    let updated = briefings
+        .first()
+        .map(|b| feed_timestamp(&b.date))
+        .unwrap_or_else(|| Utc::now().to_rfc3339());
+
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n");
+    xml.push_str("<feed xmlns=\"http://www.w3.org/2005/Atom\">\n");
+    xml.push_str("  <title>Claudius Briefings</title>\n");
+    xml.push_str("  <id>urn:claudius:briefings</id>\n");
+    xml.push_str(&format!("  <updated>{}</updated>\n", updated));
+    xml.push_str("  <author><name>Claudius</name></author>\n");
+    xml.push_str(&format!(
+        "  <generator version=\"{}\">Claudius</generator>\n",
+        env!("CARGO_PKG_VERSION")
+    ));
+
+    for briefing in briefings {
+        let cards: Vec<BriefingCard> = serde_json::from_str(&briefing.cards).unwrap_or_default();
+        let updated = feed_timestamp(&briefing.date);
+        let mut order: Vec<usize> = (0..cards.len()).collect();
+        order.sort_by_key(|&i| cards[i].rank.map_or(i, |r| r as usize));
+
+        for i in order {
+            let card = &cards[i];
+            let app_link = crate::deep_link::DeepLink::card(briefing.id, i).url();
+            let link = card
+                .sources
+                .iter()
+                .find_map(|s| crate::sources::extract_url(s))
+                .unwrap_or_else(|| app_link.clone());
+
+            xml.push_str("  <entry>\n");
+            xml.push_str(&format!("    <title>{}</title>\n", escape_xml(&card.title)));
+            xml.push_str(&format!(
+                "    <id>urn:claudius:briefing:{}:card:{}</id>\n",
+                briefing.id,
+                i + 1
+            ));
+            xml.push_str(&format!("    <updated>{}</updated>\n", updated));
+            xml.push_str(&format!(
+                "    <link rel=\"alternate\" href=\"{}\"/>\n",
+                escape_xml(&link)
+            ));
+            xml.push_str(&format!(
+                "    <link rel=\"related\" href=\"{}\"/>\n",
+                escape_xml(&app_link)
+            ));
+            if !card.topic.is_empty() {
+                xml.push_str(&format!(
+                    "    <category term=\"{}\"/>\n",
+                    escape_xml(&card.topic)
+                ));
+            }
+            xml.push_str(&format!(
+                "    <summary>{}</summary>\n",
+                escape_xml(&card.summary)
+            ));
+            xml.push_str(&format!(
+                "    <content type=\"html\">{}</content>\n",
+                escape_xml(&card_html(card))
+            ));
+            xml.push_str("  </entry>\n");
+        }
+    }
+    xml.push_str("</feed>\n");
+    xml
+}
+
+/// The feed of the most recent briefings.
+pub fn build_feed(conn: &rusqlite::Connection) -> Result<String, String> {
+    let briefings = crate::db::get_briefings(conn, FEED_BRIEFINGS)?;
+    Ok(render_feed(&briefings))
+}
+
+/// Rewrite the feed file at `path`, replacing it atomically so feed readers
+/// never see a partial file.
+pub fn write_feed(conn: &rusqlite::Connection, path: &Path) -> Result<(), String> {
+    let xml = build_feed(conn)?;
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+    let tmp = path.with_extension("xml.tmp");
+    std::fs::write(&tmp, xml).map_err(|e| format!("Failed to write feed: {}", e))?;
+    std::fs::rename(&tmp, path).map_err(|e| format!("Failed to replace feed: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn briefing(id: i64, cards: serde_json::Value) -> Briefing {
+        Briefing {
+            id,
+            date: "2025-01-15T07:00:00".to_string(),
+            title: "Morning Briefing".to_string(),
+            cards: cards.to_string(),
+            research_time_ms: None,
+            model_used: None,
+            total_tokens: None,
+            tag: None,
+            cover_image_path: None,
+        }
+    }
+
+    #[test]
+    fn test_render_feed() {
+        let cards = serde_json::json!([
+            {
+                "title": "Second <card>",
+                "summary": "Tom & Jerry",
+                "detailed_content": "",
+                "sources": [],
+                "suggested_next": null,
+                "relevance": "low",
+                "topic": "",
+                "rank": 1,
+            },
+            {
+                "title": "First card",
+                "summary": "Summary",
+                "detailed_content": "One.\n\nTwo.",
+                "sources": ["Rust Blog - https://blog.rust-lang.org/post"],
+                "suggested_next": null,
+                "relevance": "high",
+                "topic": "Rust",
+                "rank": 0,
+            },
+        ]);
+        let xml = render_feed(&[briefing(7, cards)]);
+
+        assert!(xml.starts_with("<?xml"));
+        assert_eq!(xml.matches("<entry>").count(), 2);
+        // Display order: the rank 0 card comes first
+        assert!(xml.find("First card").unwrap() < xml.find("Second &lt;card&gt;").unwrap());
+        assert!(xml.contains("<id>urn:claudius:briefing:7:card:2</id>"));
+        assert!(xml.contains("<link rel=\"alternate\" href=\"https://blog.rust-lang.org/post\"/>"));
+        // No sources: the entry links to the card in the app
+        assert!(xml.contains("<link rel=\"alternate\" href=\"claudius://briefing/7/card/1\"/>"));
+        assert!(xml.contains("<category term=\"Rust\"/>"));
+        assert!(xml.contains("<summary>Tom &amp; Jerry</summary>"));
+        assert!(xml.contains("&lt;p&gt;Two.&lt;/p&gt;"));
+    }
+
+    #[test]
+    fn test_feed_timestamp() {
+        assert_eq!(
+            feed_timestamp("2025-01-15T07:00:00+02:00"),
+            "2025-01-15T07:00:00+02:00"
+        );
+        assert!(feed_timestamp("2025-01-15T07:00:00").starts_with("2025-01-15T07:00:00"));
+        assert_eq!(escape_xml("a\u{1}b \"c\""), "ab &quot;c&quot;");
+    }
+}
@@ -0,0 +1,364 @@
+//! Politeness rules for fetching web pages.
+//!
+//! Research without Firecrawl fetches pages directly, often several from the
+//! same site in a row. Before each `fetch_webpage` request, [`prepare`]
+//! checks the site's robots.txt (cached per origin for a day), waits out the
+//! per-domain crawl delay (the larger of the configured delay and the site's
+//! own `Crawl-delay`), and returns the User-Agent to send, which names
+//! Claudius and the contact configured in `fetch_contact`.
+#![allow(dead_code)]
+
+use lazy_static::lazy_static;
+use reqwest::{Client, StatusCode, Url};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tracing::{debug, info};
+
+use crate::config::ResearchSettings;
+
+/// Product token matched against robots.txt `User-agent` lines.
+pub const ROBOTS_AGENT: &str = "claudius";
+
+/// How long a fetched robots.txt is trusted.
+const ROBOTS_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Timeout for fetching robots.txt.
+const ROBOTS_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Longest `Crawl-delay` honored from a robots.txt, so one site can't stall a run.
+const MAX_ROBOTS_CRAWL_DELAY: Duration = Duration::from_secs(30);
+
+/// robots.txt files larger than this are ignored (treated as allowing everything).
+const MAX_ROBOTS_BYTES: usize = 500 * 1024;
+
+lazy_static! {
+    static ref ROBOTS_CACHE: Mutex<HashMap<String, (Instant, Robots)>> = Mutex::new(HashMap::new());
+    static ref NEXT_SLOT: Mutex<HashMap<String, Instant>> = Mutex::new(HashMap::new());
+}
+
+/// Fetch politeness settings.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FetchPolicy {
+    pub respect_robots_txt: bool,
+    pub crawl_delay: Duration,
+    pub contact: Option<String>,
+}
+
+impl FetchPolicy {
+    pub fn from_settings(settings: &ResearchSettings) -> Self {
+        Self {
+            respect_robots_txt: settings.respect_robots_txt,
+            crawl_delay: Duration::from_millis(settings.crawl_delay_ms),
+            contact: settings
+                .fetch_contact
+                .as_deref()
+                .map(str::trim)
+                .filter(|c| !c.is_empty())
+                .map(str::to_string),
+        }
+    }
+
+    /// Policy from the current settings file (defaults if it can't be read).
+    pub fn current() -> Self {
+        Self::from_settings(&crate::config::read_settings().unwrap_or_default())
+    }
+
+    /// User-Agent naming Claudius, where it comes from, and who runs it.
+    pub fn user_agent(&self) -> String {
+        let mut info = format!("+{}", env!("CARGO_PKG_REPOSITORY"));
+        if let Some(ref contact) = self.contact {
+            info.push_str("; ");
+            info.push_str(contact);
+        }
+        format!("Claudius/{} ({})", env!("CARGO_PKG_VERSION"), info)
+    }
+}
+
+/// The rules of a robots.txt that apply to Claudius.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Robots {
+    rules: Vec<(bool, String)>, // (allow, path pattern)
+    crawl_delay: Option<Duration>,
+}
+
+impl Robots {
+    /// Parse a robots.txt, keeping the group for `agent` if there is one,
+    /// else the `*` group.
+    pub fn parse(text: &str, agent: &str) -> Self {
+        let agent = agent.to_lowercase();
+        let mut specific = Robots::default();
+        let mut wildcard = Robots::default();
+        let mut has_specific = false;
+
+        // Consecutive User-agent lines share the rules that follow them
+        let mut group_agents: Vec<String> = Vec::new();
+        let mut in_rules = false;
+        for line in text.lines() {
+            let line = line.split('#').next().unwrap_or("").trim();
+            let Some((key, value)) = line.split_once(':') else {
+                continue;
+            };
+            let key = key.trim().to_lowercase();
+            let value = value.trim();
+
+            if key == "user-agent" {
+                if in_rules {
+                    group_agents.clear();
+                    in_rules = false;
+                }
+                group_agents.push(value.to_lowercase());
+                continue;
+            }
+            in_rules = true;
+
+            let matches_agent = group_agents
+                .iter()
+                .any(|a| a.split('/').next() == Some(agent.as_str()));
+            let matches_wildcard = group_agents.iter().any(|a| a == "*");
+            has_specific |= matches_agent;
+            for (applies, target) in [
+                (matches_agent, &mut specific),
+                (matches_wildcard, &mut wildcard),
+            ] {
+                if !applies {
+                    continue;
+                }
+                match key.as_str() {
+                    // An empty Disallow allows everything
+                    "disallow" if !value.is_empty() => {
+                        target.rules.push((false, value.to_string()))
+                    }
+                    "allow" if !value.is_empty() => target.rules.push((true, value.to_string())),
+                    "crawl-delay" => {
+                        target.crawl_delay = value
+                            .parse::<f64>()
+                            .ok()
+                            .filter(|secs| secs.is_finite() && *secs >= 0.0)
+                            .map(Duration::from_secs_f64);
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        if has_specific {
+            specific
+        } else {
+            wildcard
+        }
+    }
+
+    /// Whether `path` (including any query string) may be fetched. The most
+    /// specific (longest) matching rule wins, and Allow wins a tie.
+    pub fn is_allowed(&self, path: &str) -> bool {
+        self.rules
+            .iter()
+            .filter(|(_, pattern)| pattern_matches(pattern, path))
+            .max_by_key(|(allow, pattern)| (pattern.len(), *allow))
+            .is_none_or(|(allow, _)| *allow)
+    }
+
+    pub fn crawl_delay(&self) -> Option<Duration> {
+        self.crawl_delay
+    }
+}
+
+/// Match a robots.txt path pattern, supporting `*` wildcards and a trailing `$`.
+fn pattern_matches(pattern: &str, path: &str) -> bool {
+    let (pattern, anchored) = match pattern.strip_suffix('$') {
+        Some(p) => (p, true),
+        None => (pattern, false),
+    };
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or("");
+    let Some(mut rest) = path.strip_prefix(first) else {
+        return false;
+    };
+    let parts: Vec<&str> = parts.collect();
+    for (i, part) in parts.iter().enumerate() {
+        // The last piece of an anchored pattern must end the path
+        if anchored && i == parts.len() - 1 {
+            return rest.ends_with(part);
+        }
+        match rest.find(part) {
+            Some(pos) => rest = &rest[pos + part.len()..],
+            None => return false,
+        }
+    }
+    !anchored || rest.is_empty()
+}
+
+/// Reserve the next request slot for `host`, returning how long to wait
+/// before sending. Requests to one host are spaced at least `delay` apart.
+fn reserve_slot(
+    slots: &mut HashMap<String, Instant>,
+    host: &str,
+    delay: Duration,
+    now: Instant,
+) -> Duration {
+    let start = slots.get(host).map_or(now, |next| (*next).max(now));
+    slots.insert(host.to_string(), start + delay);
+    start - now
+}
+
+async fn robots_for(client: &Client, url: &Url, user_agent: &str) -> Robots {
+    let origin = url.origin().ascii_serialization();
+    if let Some((fetched, robots)) = ROBOTS_CACHE
+        .lock()
+        .ok()
+        .and_then(|c| c.get(&origin).cloned())
+    {
+        if fetched.elapsed() < ROBOTS_TTL {
+            return robots;
+        }
+    }
+
+    let robots_url = format!("{}/robots.txt", origin);
+    let robots = match client
+        .get(&robots_url)
+        .header("User-Agent", user_agent)
+        .timeout(ROBOTS_TIMEOUT)
+        .send()
+        .await
+    {
+        Ok(response) if response.status() == StatusCode::OK => match response.text().await {
+            Ok(text) if text.len() <= MAX_ROBOTS_BYTES => Robots::parse(&text, ROBOTS_AGENT),
+            _ => Robots::default(),
+        },
+        // No robots.txt (or an unreadable one) means no restrictions
+        Ok(response) => {
+            debug!(
+                "No usable robots.txt at {} (HTTP {})",
+                robots_url,
+                response.status()
+            );
+            Robots::default()
+        }
+        Err(e) => {
+            debug!("Failed to fetch {}: {}", robots_url, e);
+            Robots::default()
+        }
+    };
+
+    if let Ok(mut cache) = ROBOTS_CACHE.lock() {
+        cache.insert(origin, (Instant::now(), robots.clone()));
+    }
+    robots
+}
+
+/// Apply the fetch policy to a request for `url`: refuse it if robots.txt
+/// disallows it, otherwise wait for the host's next request slot. Returns
+/// the User-Agent to send.
+pub async fn prepare(client: &Client, url: &str, policy: &FetchPolicy) -> Result<String, String> {
+    let user_agent = policy.user_agent();
+    let parsed = Url::parse(url).map_err(|e| format!("Invalid URL: {}", e))?;
+    let host = parsed.host_str().unwrap_or_default().to_lowercase();
+
+    let mut delay = policy.crawl_delay;
+    if policy.respect_robots_txt {
+        let robots = robots_for(client, &parsed, &user_agent).await;
+        let path = match parsed.query() {
+            Some(query) => format!("{}?{}", parsed.path(), query),
+            None => parsed.path().to_string(),
+        };
+        if !robots.is_allowed(&path) {
+            info!("robots.txt disallows {}", url);
+            return Err(format!("Blocked by the site's robots.txt: {}", url));
+        }
+        if let Some(site_delay) = robots.crawl_delay() {
+            delay = delay.max(site_delay.min(MAX_ROBOTS_CRAWL_DELAY));
+        }
+    }
+
+    let wait = match NEXT_SLOT.lock() {
+        Ok(mut slots) => reserve_slot(&mut slots, &host, delay, Instant::now()),
+        Err(_) => Duration::ZERO,
+    };
+    if !wait.is_zero() {
+        debug!(
+            "Waiting {}ms before fetching from {}",
+            wait.as_millis(),
+            host
+        );
+        tokio::time::sleep(wait).await;
+    }
+
+    Ok(user_agent)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ROBOTS: &str = "
+# Example
+User-agent: *
+Disallow: /private/
+Allow: /private/public-page
+Crawl-delay: 5
+
+User-agent: BadBot
+User-agent: Claudius
+Disallow: /search
+Disallow: /*.pdf$
+";
+
+    #[test]
+    fn test_robots_uses_most_specific_group() {
+        let robots = Robots::parse(ROBOTS, ROBOTS_AGENT);
+        assert!(!robots.is_allowed("/search?q=rust"));
+        assert!(!robots.is_allowed("/files/report.pdf"));
+        assert!(robots.is_allowed("/files/report.pdf.html"));
+        // The * group doesn't apply once Claudius has its own
+        assert!(robots.is_allowed("/private/notes"));
+        assert_eq!(robots.crawl_delay(), None);
+
+        let other = Robots::parse(ROBOTS, "otherbot");
+        assert!(!other.is_allowed("/private/notes"));
+        assert!(other.is_allowed("/private/public-page"));
+        assert!(other.is_allowed("/search"));
+        assert_eq!(other.crawl_delay(), Some(Duration::from_secs(5)));
+    }
+
+    #[test]
+    fn test_robots_empty_or_missing_allows_everything() {
+        assert!(Robots::default().is_allowed("/anything"));
+        let robots = Robots::parse("User-agent: *\nDisallow:\n", ROBOTS_AGENT);
+        assert!(robots.is_allowed("/anything"));
+    }
+
+    #[test]
+    fn test_reserve_slot_spaces_requests_per_host() {
+        let mut slots = HashMap::new();
+        let now = Instant::now();
+        let delay = Duration::from_secs(2);
+        assert_eq!(
+            reserve_slot(&mut slots, "a.com", delay, now),
+            Duration::ZERO
+        );
+        assert_eq!(reserve_slot(&mut slots, "a.com", delay, now), delay);
+        assert_eq!(reserve_slot(&mut slots, "a.com", delay, now), delay * 2);
+        assert_eq!(
+            reserve_slot(&mut slots, "b.com", delay, now),
+            Duration::ZERO
+        );
+        // A slot in the past doesn't make later requests wait
+        assert_eq!(
+            reserve_slot(&mut slots, "b.com", delay, now + Duration::from_secs(10)),
+            Duration::ZERO
+        );
+    }
+
+    #[test]
+    fn test_user_agent_includes_contact() {
+        let mut policy = FetchPolicy::from_settings(&ResearchSettings::default());
+        assert!(policy.user_agent().starts_with("Claudius/"));
+        assert!(policy
+            .user_agent()
+            .contains("github.com/chrisvanbuskirk/claudius"));
+
+        policy.contact = Some("ops@example.com".to_string());
+        assert!(policy.user_agent().ends_with("; ops@example.com)"));
+    }
+}
@@ -0,0 +1,233 @@
+//! Firecrawl call budgets.
+//!
+//! Deep research can use up Firecrawl credits quickly. The
+//! `firecrawl_run_budget` setting caps Firecrawl tool calls in one research
+//! run and `firecrawl_monthly_budget` caps them per calendar month, counted
+//! from the successful calls in the research log (0 = no limit). Research
+//! warns once a budget is [`WARN_PERCENT`] used and stops offering Firecrawl
+//! tools to Claude once one is used up.
+
+use serde::Serialize;
+use tracing::{info, warn};
+
+/// Share of a budget (in percent) at which research warns.
+pub const WARN_PERCENT: u32 = 80;
+
+/// Whether `tool_name` is a Firecrawl tool.
+pub fn is_firecrawl_tool(tool_name: &str) -> bool {
+    tool_name.to_lowercase().contains("firecrawl")
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BudgetScope {
+    Run,
+    Month,
+}
+
+impl BudgetScope {
+    fn label(self) -> &'static str {
+        match self {
+            BudgetScope::Run => "per-run",
+            BudgetScope::Month => "monthly",
+        }
+    }
+
+    fn period(self) -> &'static str {
+        match self {
+            BudgetScope::Run => "this run",
+            BudgetScope::Month => "this month",
+        }
+    }
+}
+
+/// A budget that crossed the warning threshold or was used up.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BudgetWarning {
+    pub scope: BudgetScope,
+    pub used: u32,
+    pub limit: u32,
+    pub exhausted: bool,
+}
+
+impl BudgetWarning {
+    pub fn message(&self) -> String {
+        if self.exhausted {
+            format!(
+                "Firecrawl {} budget used up ({}/{} calls); Firecrawl tools are off for the rest of {}",
+                self.scope.label(),
+                self.used,
+                self.limit,
+                self.scope.period()
+            )
+        } else {
+            format!(
+                "Firecrawl {} budget {}% used ({}/{} calls)",
+                self.scope.label(),
+                self.used * 100 / self.limit,
+                self.used,
+                self.limit
+            )
+        }
+    }
+}
+
+/// What crossing from `before` to `after` calls means for a budget of `limit`.
+fn crossing(scope: BudgetScope, before: u32, after: u32, limit: u32) -> Option<BudgetWarning> {
+    if limit == 0 {
+        return None;
+    }
+    let exhausted = before < limit && after >= limit;
+    let warned = before * 100 < limit * WARN_PERCENT && after * 100 >= limit * WARN_PERCENT;
+    (exhausted || warned).then_some(BudgetWarning {
+        scope,
+        used: after,
+        limit,
+        exhausted,
+    })
+}
+
+/// Firecrawl calls made so far in this run and this month, against the budgets.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FirecrawlBudget {
+    run_limit: u32,
+    monthly_limit: u32,
+    run_calls: u32,
+    month_calls: u32,
+}
+
+impl FirecrawlBudget {
+    pub fn new(run_limit: u32, monthly_limit: u32, month_calls: u32) -> Self {
+        Self {
+            run_limit,
+            monthly_limit,
+            run_calls: 0,
+            month_calls,
+        }
+    }
+
+    /// Budgets with this month's calls read from the research log. If the log
+    /// can't be read, the monthly budget counts as used up so no credits are
+    /// spent unexpectedly.
+    pub fn load(run_limit: u32, monthly_limit: u32) -> Self {
+        if monthly_limit == 0 {
+            return Self::new(run_limit, 0, 0);
+        }
+        let month_calls = crate::db::get_connection()
+            .map_err(|e| e.to_string())
+            .and_then(|conn| monthly_calls(&conn))
+            .unwrap_or_else(|e| {
+                warn!("Failed to count this month's Firecrawl calls: {}", e);
+                monthly_limit
+            });
+        info!(
+            "Firecrawl budget: {} of {} monthly calls used",
+            month_calls, monthly_limit
+        );
+        Self::new(run_limit, monthly_limit, month_calls)
+    }
+
+    /// The budget that is used up, if any.
+    pub fn exhausted(&self) -> Option<BudgetScope> {
+        if self.run_limit > 0 && self.run_calls >= self.run_limit {
+            Some(BudgetScope::Run)
+        } else if self.monthly_limit > 0 && self.month_calls >= self.monthly_limit {
+            Some(BudgetScope::Month)
+        } else {
+            None
+        }
+    }
+
+    /// Warning for a monthly budget already past the threshold when the run starts.
+    pub fn start_warning(&self) -> Option<BudgetWarning> {
+        crossing(BudgetScope::Month, 0, self.month_calls, self.monthly_limit)
+    }
+
+    /// Count one Firecrawl call, returning the warnings it triggers.
+    pub fn record_call(&mut self) -> Vec<BudgetWarning> {
+        self.run_calls += 1;
+        self.month_calls += 1;
+        [
+            crossing(
+                BudgetScope::Run,
+                self.run_calls - 1,
+                self.run_calls,
+                self.run_limit,
+            ),
+            crossing(
+                BudgetScope::Month,
+                self.month_calls - 1,
+                self.month_calls,
+                self.monthly_limit,
+            ),
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
+    }
+
+    /// Note for Claude when Firecrawl tools are no longer offered.
+    pub fn prompt_note(&self) -> Option<String> {
+        self.exhausted().map(|scope| {
+            format!(
+                "NOTE: The {} Firecrawl budget is used up, so Firecrawl tools are unavailable for {}. Research with the other tools listed above.",
+                scope.label(),
+                scope.period()
+            )
+        })
+    }
+}
+
+/// Successful Firecrawl calls logged since the start of the month (local time).
+pub fn monthly_calls(conn: &rusqlite::Connection) -> Result<u32, String> {
+    conn.query_row(
+        "SELECT COUNT(*) FROM research_logs
+         WHERE tool_name LIKE '%firecrawl%' AND success = 1
+           AND created_at >= DATE('now', 'localtime', 'start of month')",
+        [],
+        |row| row.get::<_, u32>(0),
+    )
+    .map_err(|e| format!("Failed to count Firecrawl calls: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_budget_warns_then_exhausts() {
+        let mut budget = FirecrawlBudget::new(5, 0, 0);
+        assert!(budget.record_call().is_empty());
+        assert!(budget.record_call().is_empty());
+        assert!(budget.record_call().is_empty());
+
+        let warnings = budget.record_call();
+        assert_eq!(warnings.len(), 1);
+        assert!(!warnings[0].exhausted);
+        assert_eq!(
+            warnings[0].message(),
+            "Firecrawl per-run budget 80% used (4/5 calls)"
+        );
+        assert!(budget.exhausted().is_none());
+
+        let warnings = budget.record_call();
+        assert!(warnings[0].exhausted);
+        assert_eq!(budget.exhausted(), Some(BudgetScope::Run));
+        assert!(budget.prompt_note().unwrap().contains("per-run"));
+    }
+
+    #[test]
+    fn test_monthly_budget_counts_earlier_calls() {
+        let budget = FirecrawlBudget::new(0, 100, 100);
+        assert_eq!(budget.exhausted(), Some(BudgetScope::Month));
+        assert!(budget.start_warning().unwrap().exhausted);
+
+        let mut budget = FirecrawlBudget::new(0, 100, 10);
+        assert!(budget.start_warning().is_none());
+        assert!(budget.record_call().is_empty());
+
+        let unlimited = FirecrawlBudget::default();
+        assert!(unlimited.exhausted().is_none());
+        assert!(unlimited.prompt_note().is_none());
+    }
+}
@@ -0,0 +1,468 @@
+//! GitHub API cache and rate limiter for Claudius.
+//!
+//! The GitHub research tools can be called many times per research run, and the
+//! GitHub API only allows 60 unauthenticated requests per hour. This module
+//! keeps an in-memory + on-disk cache of responses (revalidated with
+//! `If-None-Match`/ETag, which does not count against the quota) and a
+//! request budget shared by the research agent and chat.
+#![allow(dead_code)]
+
+use crate::json_cache::unix_now;
+use lazy_static::lazy_static;
+use reqwest::{Client, StatusCode};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tracing::{debug, info, warn};
+
+/// Responses younger than this are served without contacting GitHub at all.
+const FRESH_TTL_SECS: u64 = 10 * 60;
+
+/// Maximum GitHub requests per research run (or per hour outside of runs).
+const MAX_REQUESTS_PER_RUN: u32 = 30;
+
+/// How long a budget window lasts if no new run resets it.
+const BUDGET_WINDOW: Duration = Duration::from_secs(60 * 60);
+
+// ============================================================================
+// Cache Types
+// ============================================================================
+
+/// A cached GitHub API response.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CacheEntry {
+    url: String,
+    etag: Option<String>,
+    data: serde_json::Value,
+    fetched_at: u64, // Unix seconds
+}
+
+impl CacheEntry {
+    fn is_fresh(&self, now: u64) -> bool {
+        now.saturating_sub(self.fetched_at) < FRESH_TTL_SECS
+    }
+}
+
+/// Result of a GitHub fetch, noting whether it was served from cache.
+#[derive(Debug, Clone)]
+pub struct GithubFetch {
+    pub data: serde_json::Value,
+    pub from_cache: bool,
+}
+
+// ============================================================================
+// Rate Limiter
+// ============================================================================
+
+/// Request budget shared by all GitHub tool calls.
+#[derive(Debug)]
+struct RateLimiter {
+    window_start: Instant,
+    used: u32,
+    /// Set when GitHub reports an exhausted quota (X-RateLimit-Remaining: 0).
+    blocked_until: Option<u64>,
+}
+
+impl RateLimiter {
+    fn new() -> Self {
+        Self {
+            window_start: Instant::now(),
+            used: 0,
+            blocked_until: None,
+        }
+    }
+
+    /// Try to take one request from the budget.
+    fn try_acquire(&mut self, now: Instant, unix_now: u64) -> bool {
+        if let Some(until) = self.blocked_until {
+            if unix_now < until {
+                return false;
+            }
+            self.blocked_until = None;
+        }
+
+        if now.duration_since(self.window_start) >= BUDGET_WINDOW {
+            self.window_start = now;
+            self.used = 0;
+        }
+
+        if self.used >= MAX_REQUESTS_PER_RUN {
+            return false;
+        }
+
+        self.used += 1;
+        true
+    }
+
+    fn reset(&mut self) {
+        self.window_start = Instant::now();
+        self.used = 0;
+    }
+}
+
+lazy_static! {
+    static ref MEMORY_CACHE: Mutex<HashMap<String, CacheEntry>> = Mutex::new(HashMap::new());
+    static ref RATE_LIMITER: Mutex<RateLimiter> = Mutex::new(RateLimiter::new());
+}
+
+/// Reset the request budget. Called at the start of each research run.
+pub fn reset_run_budget() {
+    if let Ok(mut limiter) = RATE_LIMITER.lock() {
+        limiter.reset();
+    }
+}
+
+// ============================================================================
+// Disk Cache
+// ============================================================================
+
+fn get_cache_dir() -> PathBuf {
+    crate::config::get_cache_dir().join("github")
+}
+
+/// Build a filesystem-safe cache file name from a URL.
+fn cache_file_name(url: &str) -> String {
+    let stripped = url.trim_start_matches("https://api.github.com/");
+    let safe: String = stripped
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    format!("{}.json", safe)
+}
+
+fn read_disk_entry(url: &str) -> Option<CacheEntry> {
+    let path = get_cache_dir().join(cache_file_name(url));
+    let content = std::fs::read_to_string(path).ok()?;
+    serde_json::from_str::<CacheEntry>(&content)
+        .ok()
+        .filter(|e| e.url == url)
+}
+
+fn write_disk_entry(entry: &CacheEntry) {
+    let dir = get_cache_dir();
+    if let Err(e) = std::fs::create_dir_all(&dir) {
+        warn!("Failed to create GitHub cache directory: {}", e);
+        return;
+    }
+    match serde_json::to_string(entry) {
+        Ok(content) => {
+            if let Err(e) = std::fs::write(dir.join(cache_file_name(&entry.url)), content) {
+                warn!("Failed to write GitHub cache entry: {}", e);
+            }
+        }
+        Err(e) => warn!("Failed to serialize GitHub cache entry: {}", e),
+    }
+}
+
+/// Look up a cached entry, checking memory first and then disk.
+fn get_cached(url: &str) -> Option<CacheEntry> {
+    if let Some(entry) = MEMORY_CACHE.lock().ok()?.get(url).cloned() {
+        return Some(entry);
+    }
+    let entry = read_disk_entry(url)?;
+    if let Ok(mut cache) = MEMORY_CACHE.lock() {
+        cache.insert(url.to_string(), entry.clone());
+    }
+    Some(entry)
+}
+
+fn store_cached(entry: CacheEntry) {
+    write_disk_entry(&entry);
+    if let Ok(mut cache) = MEMORY_CACHE.lock() {
+        cache.insert(entry.url.clone(), entry);
+    }
+}
+
+// ============================================================================
+// Fetching
+// ============================================================================
+
+/// Fetch a GitHub API URL through the cache and rate limiter.
+///
+/// Fresh cache entries are returned directly. Stale entries are revalidated
+/// with `If-None-Match`; when the request budget is exhausted or GitHub
+/// rate-limits us, a stale entry is served instead of failing.
+pub async fn fetch_json(
+    client: &Client,
+    url: &str,
+    user_agent: &str,
+    github_token: Option<&str>,
+) -> Result<GithubFetch, String> {
+    let cached = get_cached(url);
+    let now = unix_now();
+
+    if let Some(ref entry) = cached {
+        if entry.is_fresh(now) {
+            debug!("GitHub cache hit (fresh): {}", url);
+            return Ok(GithubFetch {
+                data: entry.data.clone(),
+                from_cache: true,
+            });
+        }
+    }
+
+    let allowed = RATE_LIMITER
+        .lock()
+        .map(|mut l| l.try_acquire(Instant::now(), now))
+        .unwrap_or(false);
+
+    if !allowed {
+        return match cached {
+            Some(entry) => {
+                info!("GitHub request budget exhausted, serving stale cache: {}", url);
+                Ok(GithubFetch {
+                    data: entry.data,
+                    from_cache: true,
+                })
+            }
+            None => Err(format!(
+                "GitHub rate limit reached ({} requests per run). Set GITHUB_TOKEN for a higher limit.",
+                MAX_REQUESTS_PER_RUN
+            )),
+        };
+    }
+
+    let mut request = client
+        .get(url)
+        .header("User-Agent", user_agent)
+        .header("Accept", "application/vnd.github.v3+json");
+
+    if let Some(token) = github_token {
+        request = request.header("Authorization", format!("Bearer {}", token));
+    }
+
+    if let Some(etag) = cached.as_ref().and_then(|e| e.etag.as_deref()) {
+        request = request.header("If-None-Match", etag);
+    }
+
+    let response = request
+        .send()
+        .await
+        .map_err(|e| format!("GitHub API request failed: {}", e))?;
+
+    record_quota(&response);
+
+    let status = response.status();
+
+    if status == StatusCode::NOT_MODIFIED {
+        if let Some(mut entry) = cached {
+            debug!("GitHub cache hit (304 Not Modified): {}", url);
+            entry.fetched_at = now;
+            let data = entry.data.clone();
+            store_cached(entry);
+            return Ok(GithubFetch {
+                data,
+                from_cache: true,
+            });
+        }
+    }
+
+    if !status.is_success() {
+        if status == StatusCode::FORBIDDEN || status == StatusCode::TOO_MANY_REQUESTS {
+            if let Some(entry) = cached {
+                warn!(
+                    "GitHub API returned {}, serving stale cache: {}",
+                    status, url
+                );
+                return Ok(GithubFetch {
+                    data: entry.data,
+                    from_cache: true,
+                });
+            }
+        }
+        let body = response.text().await.unwrap_or_default();
+        return Err(format!("GitHub API error {}: {}", status, body));
+    }
+
+    let etag = response
+        .headers()
+        .get("etag")
+        .and_then(|v| v.to_str().ok())
+        .map(|s| s.to_string());
+
+    let data: serde_json::Value = response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse GitHub response: {}", e))?;
+
+    store_cached(CacheEntry {
+        url: url.to_string(),
+        etag,
+        data: data.clone(),
+        fetched_at: now,
+    });
+
+    Ok(GithubFetch {
+        data,
+        from_cache: false,
+    })
+}
+
+/// Run a GitHub GraphQL query through the cache and rate limiter.
+///
+/// Used for data the REST API doesn't expose (e.g. Discussions). GraphQL
+/// requires a token and has no ETags, so responses are cached under
+/// `cache_key` and only the fresh TTL applies; a stale entry is served when
+/// the budget runs out or the request fails.
+pub async fn fetch_graphql(
+    client: &Client,
+    cache_key: &str,
+    query: &str,
+    variables: serde_json::Value,
+    user_agent: &str,
+    github_token: Option<&str>,
+) -> Result<GithubFetch, String> {
+    let token = github_token
+        .ok_or("The GitHub GraphQL API requires a token. Set GITHUB_TOKEN to use this tool.")?;
+    let cached = get_cached(cache_key);
+    let now = unix_now();
+
+    if let Some(ref entry) = cached {
+        if entry.is_fresh(now) {
+            debug!("GitHub cache hit (fresh): {}", cache_key);
+            return Ok(GithubFetch {
+                data: entry.data.clone(),
+                from_cache: true,
+            });
+        }
+    }
+
+    let stale_or = |cached: Option<CacheEntry>, err: String| match cached {
+        Some(entry) => {
+            warn!("{}, serving stale cache: {}", err, cache_key);
+            Ok(GithubFetch {
+                data: entry.data,
+                from_cache: true,
+            })
+        }
+        None => Err(err),
+    };
+
+    let allowed = RATE_LIMITER
+        .lock()
+        .map(|mut l| l.try_acquire(Instant::now(), now))
+        .unwrap_or(false);
+    if !allowed {
+        return stale_or(
+            cached,
+            format!(
+                "GitHub rate limit reached ({} requests per run)",
+                MAX_REQUESTS_PER_RUN
+            ),
+        );
+    }
+
+    let response = client
+        .post("https://api.github.com/graphql")
+        .header("User-Agent", user_agent)
+        .header("Authorization", format!("Bearer {}", token))
+        .json(&serde_json::json!({ "query": query, "variables": variables }))
+        .send()
+        .await
+        .map_err(|e| format!("GitHub API request failed: {}", e))?;
+
+    record_quota(&response);
+
+    let status = response.status();
+    if !status.is_success() {
+        let body = response.text().await.unwrap_or_default();
+        return stale_or(cached, format!("GitHub API error {}: {}", status, body));
+    }
+
+    let body: serde_json::Value = response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse GitHub response: {}", e))?;
+
+    // GraphQL reports errors (unknown repo, disabled discussions) with a 200
+    if let Some(message) = body["errors"]
+        .as_array()
+        .and_then(|errors| errors.first())
+        .and_then(|e| e["message"].as_str())
+    {
+        return Err(format!("GitHub GraphQL error: {}", message));
+    }
+
+    let data = body["data"].clone();
+    store_cached(CacheEntry {
+        url: cache_key.to_string(),
+        etag: None,
+        data: data.clone(),
+        fetched_at: now,
+    });
+
+    Ok(GithubFetch {
+        data,
+        from_cache: false,
+    })
+}
+
+/// Block further requests until the reset time if GitHub reports no quota left.
+fn record_quota(response: &reqwest::Response) {
+    let header = |name: &str| {
+        response
+            .headers()
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|s| s.parse::<u64>().ok())
+    };
+
+    if let (Some(0), Some(reset)) = (header("x-ratelimit-remaining"), header("x-ratelimit-reset")) {
+        warn!("GitHub API quota exhausted until {}", reset);
+        if let Ok(mut limiter) = RATE_LIMITER.lock() {
+            limiter.blocked_until = Some(reset);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cache_file_name_is_filesystem_safe() {
+        let name = cache_file_name(
+            "https://api.github.com/repos/rust-lang/rust/pulls?state=all&per_page=10",
+        );
+        assert_eq!(
+            name,
+            "repos_rust_lang_rust_pulls_state_all_per_page_10.json"
+        );
+    }
+
+    #[test]
+    fn test_cache_entry_freshness() {
+        let entry = CacheEntry {
+            url: "https://api.github.com/repos/a/b/commits".to_string(),
+            etag: None,
+            data: serde_json::json!([]),
+            fetched_at: 1_000,
+        };
+        assert!(entry.is_fresh(1_000 + FRESH_TTL_SECS - 1));
+        assert!(!entry.is_fresh(1_000 + FRESH_TTL_SECS));
+    }
+
+    #[test]
+    fn test_rate_limiter_budget() {
+        let mut limiter = RateLimiter::new();
+        let now = Instant::now();
+        for _ in 0..MAX_REQUESTS_PER_RUN {
+            assert!(limiter.try_acquire(now, 0));
+        }
+        assert!(!limiter.try_acquire(now, 0));
+
+        limiter.reset();
+        assert!(limiter.try_acquire(Instant::now(), 0));
+    }
+
+    #[test]
+    fn test_rate_limiter_blocked_until_reset() {
+        let mut limiter = RateLimiter::new();
+        limiter.blocked_until = Some(2_000);
+        assert!(!limiter.try_acquire(Instant::now(), 1_999));
+        assert!(limiter.try_acquire(Instant::now(), 2_000));
+        assert!(limiter.blocked_until.is_none());
+    }
+}
@@ -0,0 +1,161 @@
+//! The global shortcuts: one shows and hides the main window, the other
+//! starts a quick research run on the clipboard.
+//!
+//! `global_shortcut` and `quick_research_shortcut` in settings are
+//! accelerators such as "CmdOrCtrl+Shift+B" (Cmd on macOS, Ctrl elsewhere),
+//! or "off" to register none. Changing them re-registers the shortcuts
+//! without a restart.
+
+use std::sync::{Mutex, OnceLock};
+use tauri::{AppHandle, Manager};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutState};
+use tracing::{error, info};
+
+pub const DEFAULT_SHORTCUT: &str = "CmdOrCtrl+Shift+B";
+
+pub const DEFAULT_QUICK_RESEARCH_SHORTCUT: &str = "CmdOrCtrl+Alt+Shift+R";
+
+/// Setting value that turns the shortcut off.
+pub const SHORTCUT_OFF: &str = "off";
+
+/// The settings currently registered (show/hide, quick research), so
+/// unchanged settings aren't registered again.
+static REGISTERED: Mutex<Option<(String, String)>> = Mutex::new(None);
+
+/// What the quick research shortcut does, set once by the app.
+static QUICK_RESEARCH: OnceLock<fn(&AppHandle)> = OnceLock::new();
+
+/// Set the action of the quick research shortcut. Until it is set, the
+/// shortcut is registered but does nothing.
+pub fn set_quick_research_action(action: fn(&AppHandle)) {
+    let _ = QUICK_RESEARCH.set(action);
+}
+
+/// Parse a `global_shortcut` setting. None when it is off (or empty).
+pub fn parse_shortcut(value: &str) -> Result<Option<Shortcut>, String> {
+    let value = value.trim();
+    if value.is_empty() || value.eq_ignore_ascii_case(SHORTCUT_OFF) {
+        return Ok(None);
+    }
+    let shortcut: Shortcut = value.parse().map_err(|e| {
+        format!(
+            "Invalid shortcut '{}': {} (use e.g. {} or {})",
+            value, e, DEFAULT_SHORTCUT, SHORTCUT_OFF
+        )
+    })?;
+    // A bare key would be taken from every other app
+    if shortcut.mods.is_empty() {
+        return Err(format!(
+            "Shortcut '{}' needs a modifier (Cmd, Ctrl, Alt or Shift)",
+            value
+        ));
+    }
+    Ok(Some(shortcut))
+}
+
+/// Show the main window if it's hidden, hide it if it's visible.
+fn toggle_main_window(app: &AppHandle) {
+    let Some(window) = app.get_webview_window("main") else {
+        return;
+    };
+    match window.is_visible() {
+        Ok(true) => {
+            info!("Main window visible, hiding");
+            let _ = window.hide();
+        }
+        Ok(false) => {
+            info!("Main window hidden, showing");
+            let _ = window.show();
+            let _ = window.set_focus();
+        }
+        Err(e) => error!("Failed to check window visibility: {}", e),
+    }
+}
+
+/// Register the show/hide shortcut named by `toggle` and the quick research
+/// shortcut named by `quick` in place of the current ones. Does nothing if
+/// they are already registered.
+pub fn apply(app: &AppHandle, toggle: &str, quick: &str) -> Result<(), String> {
+    let mut registered = REGISTERED.lock().unwrap_or_else(|e| e.into_inner());
+    let settings = (toggle.to_string(), quick.to_string());
+    if registered.as_ref() == Some(&settings) {
+        return Ok(());
+    }
+    let toggle_shortcut = parse_shortcut(toggle)?;
+    let quick_shortcut = parse_shortcut(quick)?;
+    if toggle_shortcut.is_some() && toggle_shortcut == quick_shortcut {
+        return Err(format!(
+            "The quick research shortcut '{}' is the same as the global shortcut",
+            quick
+        ));
+    }
+
+    let shortcuts = app.global_shortcut();
+    shortcuts
+        .unregister_all()
+        .map_err(|e| format!("Failed to unregister global shortcuts: {}", e))?;
+    *registered = Some(settings);
+
+    let failed = |setting: &str, e: tauri_plugin_global_shortcut::Error| {
+        format!(
+            "Failed to register global shortcut '{}': {} (it may be taken by another app)",
+            setting, e
+        )
+    };
+    match toggle_shortcut {
+        Some(shortcut) => {
+            let result = shortcuts.on_shortcut(shortcut, |app, _shortcut, event| {
+                // Only respond to key press, not release (prevents double-trigger)
+                if event.state == ShortcutState::Pressed {
+                    info!("Global shortcut triggered (key pressed)");
+                    toggle_main_window(app);
+                }
+            });
+            if let Err(e) = result {
+                *registered = None;
+                return Err(failed(toggle, e));
+            }
+            info!("Global shortcut registered: {}", toggle);
+        }
+        None => info!("Global shortcut turned off"),
+    }
+    match quick_shortcut {
+        Some(shortcut) => {
+            let result = shortcuts.on_shortcut(shortcut, |app, _shortcut, event| {
+                if event.state == ShortcutState::Pressed {
+                    info!("Quick research shortcut triggered");
+                    match QUICK_RESEARCH.get() {
+                        Some(action) => action(app),
+                        None => error!("No quick research action set"),
+                    }
+                }
+            });
+            if let Err(e) = result {
+                *registered = None;
+                return Err(failed(quick, e));
+            }
+            info!("Quick research shortcut registered: {}", quick);
+        }
+        None => info!("Quick research shortcut turned off"),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_shortcut() {
+        assert!(parse_shortcut(DEFAULT_SHORTCUT).unwrap().is_some());
+        assert!(parse_shortcut(DEFAULT_QUICK_RESEARCH_SHORTCUT)
+            .unwrap()
+            .is_some());
+        assert!(parse_shortcut("Alt+Shift+F9").unwrap().is_some());
+        assert!(parse_shortcut("off").unwrap().is_none());
+        assert!(parse_shortcut(" ").unwrap().is_none());
+
+        assert!(parse_shortcut("B").unwrap_err().contains("modifier"));
+        assert!(parse_shortcut("Ctrl+Shift+Nope").is_err());
+    }
+}
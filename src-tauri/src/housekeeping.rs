@@ -2,9 +2,16 @@
 //!
 //! This module provides functions for cleaning up old briefings based on
 //! user-configured retention settings. Bookmarked briefings are always preserved.
+//! Research log files are pruned separately using `log_retention_days`,
+//! cached tool content once it is too old for offline research, and the
+//! `fetch_webpage` cache by age and total size. Exact duplicate briefings
+//! (saved twice by a retried save) are merged.
 
 use crate::config::read_settings;
 use crate::db;
+use crate::log_sink;
+use crate::research::CONTENT_CACHE_MAX_AGE_DAYS;
+use crate::web_cache;
 use rusqlite::Connection;
 use tracing::{info, warn};
 
@@ -68,9 +75,68 @@ pub fn run_cleanup() -> Result<CleanupResult, String> {
     let conn =
         db::get_connection().map_err(|e| format!("Failed to get database connection: {}", e))?;
 
+    prune_research_logs(settings.log_retention_days);
+    prune_content_cache(&conn);
+    web_cache::prune(&conn);
+    dedupe_briefings(&conn);
     run_cleanup_with_conn(&conn, settings.retention_days)
 }
 
+/// Merge exact duplicate briefings into the oldest copy.
+pub fn dedupe_briefings(conn: &Connection) -> usize {
+    match db::merge_duplicate_briefings(conn) {
+        Ok(merged) => {
+            if merged > 0 {
+                info!("Housekeeping: merged {} duplicate briefing(s)", merged);
+            }
+            merged
+        }
+        Err(e) => {
+            warn!("Housekeeping: failed to merge duplicate briefings: {}", e);
+            0
+        }
+    }
+}
+
+/// Delete cached tool content that is too old to be reused by offline research.
+pub fn prune_content_cache(conn: &Connection) -> usize {
+    match db::cleanup_content_cache(conn, CONTENT_CACHE_MAX_AGE_DAYS) {
+        Ok(deleted) => {
+            if deleted > 0 {
+                info!(
+                    "Housekeeping: deleted {} cached content item(s) older than {} days",
+                    deleted, CONTENT_CACHE_MAX_AGE_DAYS
+                );
+            }
+            deleted
+        }
+        Err(e) => {
+            warn!("Housekeeping: failed to prune content cache: {}", e);
+            0
+        }
+    }
+}
+
+/// Delete research JSONL logs older than `log_retention_days`.
+/// Log pruning is independent of briefing retention and never fails the run.
+pub fn prune_research_logs(retention_days: u32) -> usize {
+    match log_sink::cleanup_old_logs(retention_days) {
+        Ok(deleted) => {
+            if deleted > 0 {
+                info!(
+                    "Housekeeping: deleted {} research log file(s) older than {} days",
+                    deleted, retention_days
+                );
+            }
+            deleted
+        }
+        Err(e) => {
+            warn!("Housekeeping: failed to prune research logs: {}", e);
+            0
+        }
+    }
+}
+
 /// Run cleanup on app startup (non-blocking, logs errors but doesn't fail)
 pub fn run_startup_cleanup() {
     match run_cleanup() {
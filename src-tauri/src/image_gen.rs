@@ -6,8 +6,9 @@
 #![allow(dead_code)]
 
 use base64::{engine::general_purpose::STANDARD, Engine};
+use rusqlite::Connection;
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use tracing::{debug, error, info, warn};
 
 /// Result of an image generation attempt
@@ -23,6 +24,71 @@ pub enum ImageGenResult {
     Failed(String),
 }
 
+/// A style preset combined with a card's short image prompt, so header
+/// images get a consistent look instead of DALL-E's default.
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct ImageStyle {
+    pub name: &'static str,
+    pub label: &'static str,
+    /// Style description added to the prompt; empty sends the prompt as is
+    pub prompt: &'static str,
+}
+
+/// Style presets for the `image_style` setting.
+pub const IMAGE_STYLES: &[ImageStyle] = &[
+    ImageStyle {
+        name: "editorial",
+        label: "Editorial illustration",
+        prompt: "editorial magazine illustration, bold flat shapes, limited color palette, strong central composition",
+    },
+    ImageStyle {
+        name: "isometric",
+        label: "Isometric 3D",
+        prompt: "isometric 3D illustration, clean geometric forms, soft studio lighting, muted pastel colors",
+    },
+    ImageStyle {
+        name: "watercolor",
+        label: "Watercolor",
+        prompt: "loose watercolor painting, soft washes of color on textured paper, gentle natural light",
+    },
+    ImageStyle {
+        name: "photo",
+        label: "Photograph",
+        prompt: "photorealistic editorial photograph, natural light, shallow depth of field, 35mm lens",
+    },
+    ImageStyle {
+        name: "none",
+        label: "None (prompt only)",
+        prompt: "",
+    },
+];
+
+/// Preset used when the setting is missing.
+pub const DEFAULT_IMAGE_STYLE: &str = "editorial";
+
+/// Names of the style presets, for validation and help text.
+pub fn image_style_names() -> Vec<&'static str> {
+    IMAGE_STYLES.iter().map(|s| s.name).collect()
+}
+
+/// Look up a style preset by name.
+pub fn find_image_style(name: &str) -> Option<&'static ImageStyle> {
+    IMAGE_STYLES.iter().find(|s| s.name == name)
+}
+
+/// Combine a card's image prompt with a style preset. Unknown styles and
+/// `none` leave the prompt unchanged.
+pub fn styled_prompt(prompt: &str, style: &str) -> String {
+    let prompt = prompt.trim().trim_end_matches('.');
+    match find_image_style(style) {
+        Some(style) if !style.prompt.is_empty() => format!(
+            "{}. Style: {}. Wide landscape composition with no text, letters or logos.",
+            prompt, style.prompt
+        ),
+        _ => prompt.to_string(),
+    }
+}
+
 /// DALL-E API request
 #[derive(Serialize)]
 struct DalleRequest {
@@ -45,10 +111,9 @@ struct DalleImage {
     b64_json: String,
 }
 
-/// Get the images directory path (~/.claudius/images/)
+/// Get the images directory path (`images` in the data directory)
 pub fn get_images_dir() -> Result<PathBuf, String> {
-    let home = dirs::home_dir().ok_or_else(|| "Could not find home directory".to_string())?;
-    Ok(home.join(".claudius").join("images"))
+    Ok(crate::config::get_data_dir().join("images"))
 }
 
 /// Ensure the images directory exists
@@ -64,6 +129,12 @@ pub fn get_image_path(briefing_id: i64, card_index: usize) -> Result<PathBuf, St
     Ok(get_images_dir()?.join(format!("{}_{}.png", briefing_id, card_index)))
 }
 
+/// Cover image path for a briefing. Shares the `{briefing_id}_` prefix with
+/// card images, so [`delete_briefing_images`] removes it too.
+pub fn get_cover_image_path(briefing_id: i64) -> Result<PathBuf, String> {
+    Ok(get_images_dir()?.join(format!("{}_cover.png", briefing_id)))
+}
+
 /// Check if an image exists for a card
 pub fn image_exists(briefing_id: i64, card_index: usize) -> bool {
     get_image_path(briefing_id, card_index)
@@ -114,23 +185,21 @@ pub fn delete_briefing_images(briefing_id: i64) -> Result<usize, String> {
 }
 
 /// Save a base64-encoded image to disk
-fn save_base64_image(b64: &str, briefing_id: i64, card_index: usize) -> Result<PathBuf, String> {
+fn save_base64_image(b64: &str, path: &Path) -> Result<(), String> {
     let bytes = STANDARD
         .decode(b64)
         .map_err(|e| format!("Base64 decode failed: {}", e))?;
 
-    let path = get_image_path(briefing_id, card_index)?;
     ensure_images_dir()?;
 
-    std::fs::write(&path, bytes).map_err(|e| format!("Failed to write image: {}", e))?;
-
-    Ok(path)
+    std::fs::write(path, bytes).map_err(|e| format!("Failed to write image: {}", e))
 }
 
 /// Generate an image using OpenAI DALL-E API.
 ///
 /// # Arguments
 /// * `prompt` - Text description for image generation
+/// * `style` - Name of an [`IMAGE_STYLES`] preset combined with the prompt
 /// * `briefing_id` - ID of the briefing (for file naming)
 /// * `card_index` - Index of the card within the briefing
 /// * `api_key` - OpenAI API key
@@ -139,24 +208,56 @@ fn save_base64_image(b64: &str, briefing_id: i64, card_index: usize) -> Result<P
 /// `ImageGenResult` indicating success, failure, or configuration issues.
 pub async fn generate_image(
     prompt: &str,
+    style: &str,
     briefing_id: i64,
     card_index: usize,
     api_key: &str,
+) -> ImageGenResult {
+    debug!(
+        "Generating image for briefing {}, card {}",
+        briefing_id, card_index
+    );
+    match get_image_path(briefing_id, card_index) {
+        Ok(path) => generate_image_at(prompt, style, path, api_key).await,
+        Err(e) => ImageGenResult::Failed(e),
+    }
+}
+
+/// Generate a briefing's cover image from a [`cover_prompt`], saved next to
+/// its card images.
+pub async fn generate_cover_image(
+    prompt: &str,
+    style: &str,
+    briefing_id: i64,
+    api_key: &str,
+) -> ImageGenResult {
+    debug!("Generating cover image for briefing {}", briefing_id);
+    match get_cover_image_path(briefing_id) {
+        Ok(path) => generate_image_at(prompt, style, path, api_key).await,
+        Err(e) => ImageGenResult::Failed(e),
+    }
+}
+
+async fn generate_image_at(
+    prompt: &str,
+    style: &str,
+    path: PathBuf,
+    api_key: &str,
 ) -> ImageGenResult {
     // Ensure images directory exists
     if let Err(e) = ensure_images_dir() {
         return ImageGenResult::Failed(e);
     }
 
+    let prompt = styled_prompt(prompt, style);
     debug!("Generating image with DALL-E");
     debug!("  Prompt: {}", prompt);
-    debug!("  Briefing: {}, Card: {}", briefing_id, card_index);
 
-    let client = reqwest::Client::new();
+    let client = crate::http_client::client();
 
     let request = DalleRequest {
         model: "dall-e-3".to_string(),
-        prompt: prompt.to_string(),
+        prompt,
         n: 1,
         size: "1792x1024".to_string(), // Landscape format, ideal for header images
         response_format: "b64_json".to_string(),
@@ -182,8 +283,8 @@ pub async fn generate_image(
             match resp.json::<DalleResponse>().await {
                 Ok(dalle_resp) => {
                     if let Some(image) = dalle_resp.data.first() {
-                        match save_base64_image(&image.b64_json, briefing_id, card_index) {
-                            Ok(path) => {
+                        match save_base64_image(&image.b64_json, &path) {
+                            Ok(()) => {
                                 info!("Image generated: {:?}", path);
                                 ImageGenResult::Success(path)
                             }
@@ -210,10 +311,331 @@ pub async fn generate_image(
     }
 }
 
+// ============================================================================
+// Briefing cover image
+// ============================================================================
+
+/// Most card themes combined into one cover prompt.
+const COVER_THEMES: usize = 3;
+
+/// A single hero image prompt for a whole briefing: its title plus the visual
+/// themes of its most relevant cards (their image prompts, or topics when
+/// they have none). `None` for a briefing without cards.
+pub fn cover_prompt(title: &str, cards: &[crate::research::BriefingCard]) -> Option<String> {
+    let rank = |relevance: &str| match relevance {
+        "high" => 0,
+        "medium" => 1,
+        _ => 2,
+    };
+    let mut ranked: Vec<&crate::research::BriefingCard> = cards.iter().collect();
+    ranked.sort_by_key(|card| rank(&card.relevance));
+
+    let mut themes: Vec<String> = Vec::new();
+    for card in ranked {
+        let theme = card
+            .image_prompt
+            .as_deref()
+            .map(str::trim)
+            .filter(|p| !p.is_empty())
+            .unwrap_or(card.topic.trim())
+            .trim_end_matches('.')
+            .to_string();
+        if !theme.is_empty() && !themes.iter().any(|t| t.eq_ignore_ascii_case(&theme)) {
+            themes.push(theme);
+        }
+        if themes.len() == COVER_THEMES {
+            break;
+        }
+    }
+    if themes.is_empty() {
+        return None;
+    }
+
+    let themes = match themes.split_last() {
+        Some((last, rest)) if !rest.is_empty() => format!("{} and {}", rest.join(", "), last),
+        _ => themes.join(""),
+    };
+    Some(format!(
+        "A single cohesive cover scene for a news briefing titled \"{}\", bringing together {}",
+        title.trim(),
+        themes
+    ))
+}
+
+// ============================================================================
+// Regenerating a stored card's image
+// ============================================================================
+
+fn load_cards(conn: &Connection, briefing_id: i64) -> Result<Vec<serde_json::Value>, String> {
+    let cards_json: String = conn
+        .query_row(
+            "SELECT cards FROM briefings WHERE id = ?1",
+            [briefing_id],
+            |row| row.get(0),
+        )
+        .map_err(|e| match e {
+            rusqlite::Error::QueryReturnedNoRows => format!("Briefing {} not found", briefing_id),
+            e => format!("Failed to get briefing: {}", e),
+        })?;
+    serde_json::from_str(&cards_json).map_err(|e| format!("Failed to parse cards: {}", e))
+}
+
+/// The prompt to regenerate a card's image with: `prompt` if given, else
+/// the card's stored `image_prompt`.
+pub fn card_image_prompt(
+    conn: &Connection,
+    briefing_id: i64,
+    card_index: usize,
+    prompt: Option<&str>,
+) -> Result<String, String> {
+    let cards = load_cards(conn, briefing_id)?;
+    let card = cards.get(card_index).ok_or_else(|| {
+        format!(
+            "Briefing {} has no card {} ({} cards)",
+            briefing_id,
+            card_index + 1,
+            cards.len()
+        )
+    })?;
+    prompt
+        .map(str::trim)
+        .filter(|p| !p.is_empty())
+        .or_else(|| card["image_prompt"].as_str())
+        .map(str::to_string)
+        .ok_or_else(|| "This card has no image prompt; provide one".to_string())
+}
+
+/// The style to regenerate a card's image with: `style` if given, else the
+/// preset the card's image was made with, else `default` (the setting).
+pub fn card_image_style(
+    conn: &Connection,
+    briefing_id: i64,
+    card_index: usize,
+    style: Option<&str>,
+    default: &str,
+) -> Result<String, String> {
+    if let Some(style) = style.map(str::trim).filter(|s| !s.is_empty()) {
+        return find_image_style(style)
+            .map(|s| s.name.to_string())
+            .ok_or_else(|| {
+                format!(
+                    "Unknown image style '{}'. Use one of: {}",
+                    style,
+                    image_style_names().join(", ")
+                )
+            });
+    }
+    let cards = load_cards(conn, briefing_id)?;
+    // Cards saved before presets may carry an unrelated legacy value
+    Ok(cards
+        .get(card_index)
+        .and_then(|card| card["image_style"].as_str())
+        .and_then(find_image_style)
+        .map(|s| s.name)
+        .unwrap_or(default)
+        .to_string())
+}
+
+/// Store a card's image prompt, style preset and image path in its
+/// briefing's cards JSON.
+pub fn update_card_image(
+    conn: &Connection,
+    briefing_id: i64,
+    card_index: usize,
+    prompt: &str,
+    style: &str,
+    path: &Path,
+) -> Result<(), String> {
+    let mut cards = load_cards(conn, briefing_id)?;
+    let card = cards
+        .get_mut(card_index)
+        .and_then(|card| card.as_object_mut())
+        .ok_or_else(|| format!("Briefing {} has no card {}", briefing_id, card_index + 1))?;
+    card.insert("image_prompt".to_string(), prompt.into());
+    card.insert("image_style".to_string(), style.into());
+    card.insert(
+        "image_path".to_string(),
+        path.to_string_lossy().to_string().into(),
+    );
+
+    let cards_json =
+        serde_json::to_string(&cards).map_err(|e| format!("Failed to serialize cards: {}", e))?;
+    conn.execute(
+        "UPDATE briefings SET cards = ?1 WHERE id = ?2",
+        rusqlite::params![cards_json, briefing_id],
+    )
+    .map_err(|e| format!("Failed to update briefing: {}", e))?;
+    Ok(())
+}
+
+/// Regenerate the header image of one saved card, with a new prompt and
+/// style or its stored ones, replacing the old image and saving the prompt
+/// and style with the card. Runs regardless of `enable_image_generation`,
+/// since the user asked for it.
+pub async fn regenerate_card_image(
+    briefing_id: i64,
+    card_index: usize,
+    prompt: Option<&str>,
+    style: Option<&str>,
+    api_key: &str,
+) -> Result<PathBuf, String> {
+    let default_style = crate::config::read_settings()
+        .map(|s| s.image_style)
+        .unwrap_or_else(|_| DEFAULT_IMAGE_STYLE.to_string());
+    let (prompt, style) = {
+        let conn = crate::db::get_connection()
+            .map_err(|e| format!("Database connection failed: {}", e))?;
+        (
+            card_image_prompt(&conn, briefing_id, card_index, prompt)?,
+            card_image_style(&conn, briefing_id, card_index, style, &default_style)?,
+        )
+    };
+
+    let path = match generate_image(&prompt, &style, briefing_id, card_index, api_key).await {
+        ImageGenResult::Success(path) => path,
+        ImageGenResult::Failed(e) => return Err(e),
+        ImageGenResult::Disabled => return Err("Image generation is disabled".to_string()),
+        ImageGenResult::NoApiKey => return Err("No OpenAI API key configured".to_string()),
+    };
+
+    let conn =
+        crate::db::get_connection().map_err(|e| format!("Database connection failed: {}", e))?;
+    update_card_image(&conn, briefing_id, card_index, &prompt, &style, &path)?;
+    info!(
+        "Regenerated image for briefing {} card {}",
+        briefing_id, card_index
+    );
+    Ok(path)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_card_image_prompt_and_update() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(include_str!("schema.sql")).unwrap();
+        conn.execute(
+            "INSERT INTO briefings (date, title, cards) VALUES ('2025-01-01', 'B', ?1)",
+            [r#"[{"title":"A","summary":"s","image_prompt":"A lighthouse"},{"title":"B","summary":"s"}]"#],
+        )
+        .unwrap();
+        let id = conn.last_insert_rowid();
+
+        assert_eq!(
+            card_image_prompt(&conn, id, 0, None).unwrap(),
+            "A lighthouse"
+        );
+        assert_eq!(
+            card_image_prompt(&conn, id, 0, Some(" A harbor ")).unwrap(),
+            "A harbor"
+        );
+        assert!(card_image_prompt(&conn, id, 1, Some("  ")).is_err());
+        assert!(card_image_prompt(&conn, id, 2, Some("x")).is_err());
+        assert!(card_image_prompt(&conn, id + 1, 0, None).is_err());
+
+        update_card_image(
+            &conn,
+            id,
+            1,
+            "A bridge",
+            "watercolor",
+            Path::new("/tmp/1_1.png"),
+        )
+        .unwrap();
+        let cards = load_cards(&conn, id).unwrap();
+        assert_eq!(cards[1]["image_prompt"], "A bridge");
+        assert_eq!(cards[1]["image_style"], "watercolor");
+        assert_eq!(cards[1]["image_path"], "/tmp/1_1.png");
+        assert_eq!(cards[1]["title"], "B");
+        assert_eq!(cards[0]["image_prompt"], "A lighthouse");
+    }
+
+    #[test]
+    fn test_card_image_style() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(include_str!("schema.sql")).unwrap();
+        conn.execute(
+            "INSERT INTO briefings (date, title, cards) VALUES ('2025-01-01', 'B', ?1)",
+            [r#"[{"title":"A","image_style":"photo"},{"title":"B","image_style":"illustration"},{"title":"C"}]"#],
+        )
+        .unwrap();
+        let id = conn.last_insert_rowid();
+
+        // The preset the image was made with wins over the setting
+        assert_eq!(
+            card_image_style(&conn, id, 0, None, "editorial").unwrap(),
+            "photo"
+        );
+        // Legacy values and missing styles fall back to the setting
+        assert_eq!(
+            card_image_style(&conn, id, 1, None, "editorial").unwrap(),
+            "editorial"
+        );
+        assert_eq!(
+            card_image_style(&conn, id, 2, None, "isometric").unwrap(),
+            "isometric"
+        );
+        assert_eq!(
+            card_image_style(&conn, id, 0, Some(" watercolor "), "editorial").unwrap(),
+            "watercolor"
+        );
+        assert!(card_image_style(&conn, id, 0, Some("oil"), "editorial").is_err());
+    }
+
+    #[test]
+    fn test_cover_prompt() {
+        let card = |topic: &str, relevance: &str, image_prompt: Option<&str>| {
+            serde_json::from_value::<crate::research::BriefingCard>(serde_json::json!({
+                "title": "T",
+                "summary": "S",
+                "detailed_content": "D",
+                "sources": [],
+                "suggested_next": null,
+                "relevance": relevance,
+                "topic": topic,
+                "image_prompt": image_prompt,
+            }))
+            .unwrap()
+        };
+
+        assert_eq!(cover_prompt("Morning", &[]), None);
+
+        let cards = [
+            card("Space", "low", Some("a rocket on the pad")),
+            card("Rust", "high", Some("a crab building gears.")),
+            card("AI", "medium", None),
+            card("Rust", "high", Some("A crab building gears")),
+            card("Climate", "medium", Some("wind turbines at dawn")),
+        ];
+        let prompt = cover_prompt(" Morning Briefing ", &cards).unwrap();
+        assert_eq!(
+            prompt,
+            "A single cohesive cover scene for a news briefing titled \"Morning Briefing\", \
+             bringing together a crab building gears, AI and wind turbines at dawn"
+        );
+
+        let prompt = cover_prompt("Solo", &cards[..1]).unwrap();
+        assert!(prompt.ends_with("bringing together a rocket on the pad"));
+    }
+
+    #[test]
+    fn test_get_cover_image_path() {
+        let path = get_cover_image_path(7).unwrap();
+        assert!(path.to_string_lossy().ends_with("7_cover.png"));
+    }
+
+    #[test]
+    fn test_styled_prompt() {
+        let styled = styled_prompt(" A lighthouse at dusk. ", "watercolor");
+        assert!(styled.starts_with("A lighthouse at dusk. Style: loose watercolor"));
+        assert!(styled.ends_with("no text, letters or logos."));
+        assert_eq!(styled_prompt("A lighthouse", "none"), "A lighthouse");
+        assert_eq!(styled_prompt("A lighthouse", "unknown"), "A lighthouse");
+        assert!(find_image_style(DEFAULT_IMAGE_STYLE).is_some());
+    }
+
     #[test]
     fn test_get_image_path() {
         let path = get_image_path(123, 0).expect("Should get image path");
@@ -232,7 +654,6 @@ mod tests {
     #[test]
     fn test_get_images_dir() {
         let dir = get_images_dir().expect("Should get images dir");
-        assert!(dir.to_string_lossy().contains(".claudius"));
-        assert!(dir.to_string_lossy().contains("images"));
+        assert_eq!(dir, crate::config::get_data_dir().join("images"));
     }
 }
@@ -0,0 +1,699 @@
+//! Export integrations for sending briefings to other apps.
+//!
+//! Each destination implements [`ExportTarget`]. Targets enabled in settings
+//! run automatically after every completed research run, and any target can
+//! be used one-off from the CLI (`claudius briefings export-to vault <id>`).
+//!
+//! Currently supported:
+//! - Obsidian vault: one markdown file per briefing with YAML frontmatter,
+//!   a tag per topic, and header images copied into an attachments folder.
+//! - Notion database: one page per card with topic, relevance, date and
+//!   sources properties.
+//! - Atom feed: rewrites a feed of recent briefings (see [`crate::feed`]).
+//! - Schedule calendar: rewrites an .ics of upcoming runs and follow-up
+//!   events (see [`crate::schedule_calendar`]).
+
+use crate::research::BriefingCard;
+use async_trait::async_trait;
+use serde_json::json;
+use std::path::{Path, PathBuf};
+use tracing::{debug, info, warn};
+
+/// Briefing data handed to export targets.
+#[derive(Debug, Clone)]
+pub struct ExportBriefing<'a> {
+    pub id: i64,
+    pub date: &'a str,
+    pub title: &'a str,
+    pub model_used: Option<&'a str>,
+    pub cards: &'a [BriefingCard],
+    /// Index of the first card not exported before. Cards ahead of it came
+    /// from an earlier run merged into the same briefing, and targets that
+    /// add a record per card (Notion) skip them.
+    pub first_new_card: usize,
+}
+
+/// A destination briefings can be exported to.
+#[async_trait]
+pub trait ExportTarget: Send + Sync {
+    /// Human-readable name used in logs and CLI output.
+    fn name(&self) -> &'static str;
+
+    /// Write the briefing, returning where it ended up (file path or URL).
+    async fn export(&self, briefing: &ExportBriefing<'_>) -> Result<String, String>;
+}
+
+/// Export settings, shared by the app and CLI settings structs.
+#[derive(Debug, Clone, Default)]
+pub struct ExportConfig<'a> {
+    pub obsidian_vault_path: Option<&'a str>,
+    pub obsidian_folder: &'a str,
+    pub enable_notion_sync: bool,
+    pub notion_database_id: Option<&'a str>,
+    pub notion_token: Option<String>,
+    pub enable_atom_feed: bool,
+    pub atom_feed_path: Option<&'a str>,
+    pub enable_schedule_calendar: bool,
+    pub schedule_calendar_path: Option<&'a str>,
+    pub schedule_cron: &'a str,
+}
+
+/// Build the export targets enabled in settings.
+pub fn configured_targets(config: ExportConfig) -> Vec<Box<dyn ExportTarget>> {
+    let mut targets: Vec<Box<dyn ExportTarget>> = Vec::new();
+    if let Some(vault) = config.obsidian_vault_path.filter(|p| !p.trim().is_empty()) {
+        targets.push(Box::new(ObsidianVault::new(vault, config.obsidian_folder)));
+    }
+    if config.enable_notion_sync {
+        match (
+            config.notion_database_id.filter(|id| !id.trim().is_empty()),
+            config.notion_token,
+        ) {
+            (Some(database_id), Some(token)) => {
+                targets.push(Box::new(NotionDatabase::new(&token, database_id)));
+            }
+            _ => warn!("Notion sync enabled but database ID or token is missing"),
+        }
+    }
+    if config.enable_atom_feed {
+        targets.push(Box::new(AtomFeed::new(config.atom_feed_path)));
+    }
+    if config.enable_schedule_calendar {
+        targets.push(Box::new(ScheduleCalendar::new(
+            config.schedule_calendar_path,
+            config.schedule_cron,
+        )));
+    }
+    targets
+}
+
+/// Run every target, logging failures instead of aborting.
+///
+/// Returns the locations that were written successfully.
+pub async fn export_to_all(
+    targets: &[Box<dyn ExportTarget>],
+    briefing: &ExportBriefing<'_>,
+) -> Vec<String> {
+    let mut written = Vec::new();
+    for target in targets {
+        match target.export(briefing).await {
+            Ok(location) => {
+                info!(
+                    "Exported briefing {} to {}: {}",
+                    briefing.id,
+                    target.name(),
+                    location
+                );
+                written.push(location);
+            }
+            Err(e) => warn!(
+                "Failed to export briefing {} to {}: {}",
+                briefing.id,
+                target.name(),
+                e
+            ),
+        }
+    }
+    written
+}
+
+// ============================================================================
+// Obsidian Vault
+// ============================================================================
+
+/// Writes briefings as markdown notes into an Obsidian vault.
+pub struct ObsidianVault {
+    vault_path: PathBuf,
+    folder: String,
+}
+
+impl ObsidianVault {
+    pub fn new(vault_path: &str, folder: &str) -> Self {
+        Self {
+            vault_path: expand_home(vault_path),
+            folder: folder.trim().trim_matches('/').to_string(),
+        }
+    }
+
+    fn notes_dir(&self) -> PathBuf {
+        if self.folder.is_empty() {
+            self.vault_path.clone()
+        } else {
+            self.vault_path.join(&self.folder)
+        }
+    }
+
+    /// Copy a card's header image into the vault and return its vault-relative link.
+    fn copy_image(&self, image_path: &str) -> Option<String> {
+        let source = Path::new(image_path);
+        let file_name = source.file_name()?.to_str()?.to_string();
+        let attachments = self.notes_dir().join("attachments");
+
+        if let Err(e) = std::fs::create_dir_all(&attachments) {
+            warn!("Failed to create attachments folder: {}", e);
+            return None;
+        }
+        if let Err(e) = std::fs::copy(source, attachments.join(&file_name)) {
+            warn!("Failed to copy image {:?} into vault: {}", source, e);
+            return None;
+        }
+        Some(format!("attachments/{}", file_name))
+    }
+}
+
+#[async_trait]
+impl ExportTarget for ObsidianVault {
+    fn name(&self) -> &'static str {
+        "Obsidian"
+    }
+
+    async fn export(&self, briefing: &ExportBriefing<'_>) -> Result<String, String> {
+        if !self.vault_path.is_dir() {
+            return Err(format!(
+                "Obsidian vault not found: {}",
+                self.vault_path.display()
+            ));
+        }
+
+        let dir = self.notes_dir();
+        std::fs::create_dir_all(&dir)
+            .map_err(|e| format!("Failed to create vault folder: {}", e))?;
+
+        let images: Vec<Option<String>> = briefing
+            .cards
+            .iter()
+            .map(|c| c.image_path.as_deref().and_then(|p| self.copy_image(p)))
+            .collect();
+
+        let path = dir.join(note_file_name(briefing));
+        std::fs::write(&path, render_markdown(briefing, &images))
+            .map_err(|e| format!("Failed to write note: {}", e))?;
+
+        Ok(path.display().to_string())
+    }
+}
+
+// ============================================================================
+// Atom feed
+// ============================================================================
+
+/// Keeps an Atom feed of recent briefings up to date. The briefing being
+/// exported must already be saved; the feed is rebuilt from the database.
+pub struct AtomFeed {
+    path: PathBuf,
+}
+
+impl AtomFeed {
+    pub fn new(path: Option<&str>) -> Self {
+        Self {
+            path: crate::feed::feed_path(path),
+        }
+    }
+}
+
+#[async_trait]
+impl ExportTarget for AtomFeed {
+    fn name(&self) -> &'static str {
+        "Atom feed"
+    }
+
+    async fn export(&self, _briefing: &ExportBriefing<'_>) -> Result<String, String> {
+        let conn = crate::db::get_connection().map_err(|e| format!("Database error: {}", e))?;
+        crate::feed::write_feed(&conn, &self.path)?;
+        Ok(self.path.display().to_string())
+    }
+}
+
+// ============================================================================
+// Schedule calendar
+// ============================================================================
+
+/// Keeps the .ics of upcoming research runs and follow-up events up to date.
+/// Like [`AtomFeed`], it is rebuilt from the database.
+pub struct ScheduleCalendar {
+    path: PathBuf,
+    schedule: String,
+}
+
+impl ScheduleCalendar {
+    pub fn new(path: Option<&str>, schedule: &str) -> Self {
+        Self {
+            path: crate::schedule_calendar::calendar_path(path),
+            schedule: schedule.to_string(),
+        }
+    }
+}
+
+#[async_trait]
+impl ExportTarget for ScheduleCalendar {
+    fn name(&self) -> &'static str {
+        "Schedule calendar"
+    }
+
+    async fn export(&self, _briefing: &ExportBriefing<'_>) -> Result<String, String> {
+        let conn = crate::db::get_connection().map_err(|e| format!("Database error: {}", e))?;
+        crate::schedule_calendar::write_calendar(&conn, &self.schedule, &self.path)?;
+        Ok(self.path.display().to_string())
+    }
+}
+
+/// Expand a leading `~/` to the home directory.
+pub(crate) fn expand_home(path: &str) -> PathBuf {
+    match (path.strip_prefix("~/"), dirs::home_dir()) {
+        (Some(rest), Some(home)) => home.join(rest),
+        _ => PathBuf::from(path),
+    }
+}
+
+/// Note file name, e.g. `2025-01-15 Morning Briefing (42).md`.
+fn note_file_name(briefing: &ExportBriefing) -> String {
+    let date: String = briefing.date.chars().take(10).collect();
+    let title: String = briefing
+        .title
+        .chars()
+        .filter(|c| {
+            !matches!(
+                c,
+                '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' | '#' | '^' | '[' | ']'
+            )
+        })
+        .collect();
+    format!("{} {} ({}).md", date, title.trim(), briefing.id)
+}
+
+/// Convert a topic name into an Obsidian tag (no spaces, lowercase).
+fn topic_tag(topic: &str) -> Option<String> {
+    let tag = topic
+        .to_lowercase()
+        .split(|c: char| !(c.is_alphanumeric() || c == '_' || c == '/'))
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-");
+    if tag.is_empty() {
+        None
+    } else {
+        Some(format!("topic/{}", tag))
+    }
+}
+
+/// Escape a value for a double-quoted YAML string.
+fn yaml_string(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Render a briefing as an Obsidian note. `images` holds the vault link for
+/// each card's header image, if one was copied.
+fn render_markdown(briefing: &ExportBriefing, images: &[Option<String>]) -> String {
+    let mut tags = vec!["claudius".to_string()];
+    for card in briefing.cards {
+        if let Some(tag) = topic_tag(&card.topic) {
+            if !tags.contains(&tag) {
+                tags.push(tag);
+            }
+        }
+    }
+
+    let mut out = String::new();
+    out.push_str("---\n");
+    out.push_str(&format!("title: {}\n", yaml_string(briefing.title)));
+    out.push_str(&format!(
+        "date: {}\n",
+        briefing.date.chars().take(10).collect::<String>()
+    ));
+    out.push_str(&format!("briefing_id: {}\n", briefing.id));
+    out.push_str(&format!(
+        "claudius_url: {}\n",
+        crate::deep_link::briefing_url(briefing.id)
+    ));
+    if let Some(model) = briefing.model_used {
+        out.push_str(&format!("model: {}\n", yaml_string(model)));
+    }
+    out.push_str("tags:\n");
+    for tag in &tags {
+        out.push_str(&format!("  - {}\n", tag));
+    }
+    out.push_str("---\n\n");
+
+    out.push_str(&format!("# {}\n\n", briefing.title));
+
+    for (idx, card) in briefing.cards.iter().enumerate() {
+        out.push_str(&format!("## {}\n\n", card.title));
+        out.push_str(&format!(
+            "[Open in Claudius]({})\n\n",
+            crate::deep_link::DeepLink::card(briefing.id, idx).url()
+        ));
+        if let Some(Some(link)) = images.get(idx) {
+            out.push_str(&format!("![[{}]]\n\n", link));
+        }
+        if let Some(tag) = topic_tag(&card.topic) {
+            out.push_str(&format!("#{}\n\n", tag));
+        }
+        out.push_str(&format!("{}\n\n", card.summary.trim()));
+        if !card.detailed_content.trim().is_empty() {
+            out.push_str(&format!("{}\n\n", card.detailed_content.trim()));
+        }
+        if !card.sources.is_empty() {
+            out.push_str("### Sources\n\n");
+            for source in &card.sources {
+                out.push_str(&format!("- {}\n", source));
+            }
+            out.push('\n');
+        }
+        if let Some(ref next) = card.suggested_next {
+            out.push_str(&format!("> **Next:** {}\n\n", next));
+        }
+    }
+
+    out.trim_end().to_string() + "\n"
+}
+
+// ============================================================================
+// Notion Database
+// ============================================================================
+
+const NOTION_API_URL: &str = "https://api.notion.com/v1";
+const NOTION_VERSION: &str = "2022-06-28";
+
+/// Notion caps each rich text object at 2000 characters.
+const NOTION_MAX_TEXT_CHARS: usize = 2000;
+
+/// Notion caps a page creation request at 100 child blocks.
+const NOTION_MAX_BLOCKS: usize = 100;
+
+/// Creates one page per card in a Notion database.
+///
+/// The database is expected to have these properties: `Name` (title),
+/// `Topic` (select), `Relevance` (select), `Date` (date) and `Sources` (text).
+pub struct NotionDatabase {
+    client: reqwest::Client,
+    token: String,
+    database_id: String,
+}
+
+impl NotionDatabase {
+    pub fn new(token: &str, database_id: &str) -> Self {
+        Self {
+            client: crate::http_client::client(),
+            token: token.to_string(),
+            database_id: normalize_notion_id(database_id)
+                .unwrap_or_else(|| database_id.trim().to_string()),
+        }
+    }
+
+    async fn create_page(&self, body: &serde_json::Value) -> Result<(), String> {
+        let response = self
+            .client
+            .post(format!("{}/pages", NOTION_API_URL))
+            .header("Authorization", format!("Bearer {}", self.token))
+            .header("Notion-Version", NOTION_VERSION)
+            .json(body)
+            .send()
+            .await
+            .map_err(|e| format!("Notion request failed: {}", e))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let text = response.text().await.unwrap_or_default();
+            return Err(format!("Notion API error {}: {}", status, text));
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl ExportTarget for NotionDatabase {
+    fn name(&self) -> &'static str {
+        "Notion"
+    }
+
+    async fn export(&self, briefing: &ExportBriefing<'_>) -> Result<String, String> {
+        let date: String = briefing.date.chars().take(10).collect();
+        for (idx, card) in briefing
+            .cards
+            .iter()
+            .enumerate()
+            .skip(briefing.first_new_card)
+        {
+            debug!("Creating Notion page for card {}: {}", idx, card.title);
+            self.create_page(&notion_page_body(&self.database_id, card, &date))
+                .await
+                .map_err(|e| format!("Card {} ({}): {}", idx + 1, card.title, e))?;
+        }
+        Ok(format!("https://www.notion.so/{}", self.database_id))
+    }
+}
+
+/// Extract the 32-character database ID from an ID or a Notion URL.
+fn normalize_notion_id(input: &str) -> Option<String> {
+    let path = input.trim().split(['?', '#']).next()?;
+    let segment = path.trim_end_matches('/').rsplit('/').next()?;
+    let compact: String = segment.chars().filter(|c| *c != '-').collect();
+    if compact.len() < 32 {
+        return None;
+    }
+    let id = &compact[compact.len() - 32..];
+    id.chars()
+        .all(|c| c.is_ascii_hexdigit())
+        .then(|| id.to_lowercase())
+}
+
+/// Split text into Notion rich text objects of at most 2000 characters.
+fn rich_text(text: &str) -> Vec<serde_json::Value> {
+    let chars: Vec<char> = text.chars().collect();
+    chars
+        .chunks(NOTION_MAX_TEXT_CHARS)
+        .map(|chunk| json!({ "type": "text", "text": { "content": chunk.iter().collect::<String>() } }))
+        .collect()
+}
+
+/// Build the page creation request for a card.
+fn notion_page_body(database_id: &str, card: &BriefingCard, date: &str) -> serde_json::Value {
+    let sources: Vec<serde_json::Value> = card
+        .sources
+        .iter()
+        .enumerate()
+        .map(|(i, source)| {
+            let content = if i + 1 < card.sources.len() {
+                format!("{}\n", source)
+            } else {
+                source.clone()
+            };
+            match crate::sources::extract_url(source) {
+                Some(url) => json!({ "type": "text", "text": { "content": content, "link": { "url": url } } }),
+                None => json!({ "type": "text", "text": { "content": content } }),
+            }
+        })
+        .take(NOTION_MAX_BLOCKS)
+        .collect();
+
+    let mut properties = json!({
+        "Name": { "title": rich_text(&card.title) },
+        "Relevance": { "select": { "name": card.relevance } },
+        "Sources": { "rich_text": sources },
+    });
+    // Select options cannot contain commas
+    if !card.topic.trim().is_empty() {
+        properties["Topic"] = json!({ "select": { "name": card.topic.replace(',', " ") } });
+    }
+    if !date.is_empty() {
+        properties["Date"] = json!({ "date": { "start": date } });
+    }
+
+    let paragraphs = std::iter::once(card.summary.as_str())
+        .chain(card.detailed_content.split("\n\n"))
+        .chain(card.suggested_next.as_deref())
+        .map(str::trim)
+        .filter(|p| !p.is_empty());
+    let children: Vec<serde_json::Value> = paragraphs
+        .map(|p| json!({ "object": "block", "type": "paragraph", "paragraph": { "rich_text": rich_text(p) } }))
+        .take(NOTION_MAX_BLOCKS)
+        .collect();
+
+    json!({
+        "parent": { "database_id": database_id },
+        "properties": properties,
+        "children": children,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn card(title: &str, topic: &str, image_path: Option<&str>) -> BriefingCard {
+        BriefingCard {
+            title: title.to_string(),
+            summary: "Summary text.".to_string(),
+            detailed_content: "Details.".to_string(),
+            sources: vec!["https://example.com/a".to_string()],
+            suggested_next: None,
+            relevance: "high".to_string(),
+            topic: topic.to_string(),
+            image_prompt: None,
+            image_style: None,
+            image_path: image_path.map(|p| p.to_string()),
+            source_checks: vec![],
+            action_items: vec![],
+            actions: vec![],
+            source_warning: None,
+            rank: None,
+        }
+    }
+
+    #[test]
+    fn test_topic_tag() {
+        assert_eq!(
+            topic_tag("Rust Programming"),
+            Some("topic/rust-programming".to_string())
+        );
+        assert_eq!(topic_tag("AI & ML"), Some("topic/ai-ml".to_string()));
+        assert_eq!(topic_tag("  "), None);
+    }
+
+    #[test]
+    fn test_note_file_name_strips_invalid_chars() {
+        let cards = vec![];
+        let briefing = ExportBriefing {
+            id: 7,
+            date: "2025-01-15T08:00:00Z",
+            title: "Rust: 2025/01 [Update]",
+            model_used: None,
+            cards: &cards,
+            first_new_card: 0,
+        };
+        assert_eq!(
+            note_file_name(&briefing),
+            "2025-01-15 Rust 202501 Update (7).md"
+        );
+    }
+
+    #[test]
+    fn test_render_markdown_frontmatter_and_images() {
+        let cards = vec![card("First", "Rust", None), card("Second", "Rust", None)];
+        let briefing = ExportBriefing {
+            id: 42,
+            date: "2025-01-15T08:00:00Z",
+            title: "Morning \"Briefing\"",
+            model_used: Some("claude-sonnet-4"),
+            cards: &cards,
+            first_new_card: 0,
+        };
+        let md = render_markdown(&briefing, &[Some("attachments/42_0.png".to_string()), None]);
+
+        assert!(md.starts_with("---\ntitle: \"Morning \\\"Briefing\\\"\"\ndate: 2025-01-15\n"));
+        assert!(md.contains("briefing_id: 42\nclaudius_url: claudius://briefing/42\n"));
+        assert!(md.contains("[Open in Claudius](claudius://briefing/42/card/2)"));
+        assert!(md.contains("tags:\n  - claudius\n  - topic/rust\n---"));
+        assert_eq!(md.matches("topic/rust\n").count(), 3); // frontmatter + one per card
+        assert!(md.contains("claudius://briefing/42/card/1)\n\n![[attachments/42_0.png]]"));
+        assert!(!md.contains("![[attachments/42_1.png]]"));
+        assert!(md.contains("- https://example.com/a"));
+    }
+
+    #[tokio::test]
+    async fn test_obsidian_export_writes_note_and_copies_image() {
+        let vault = std::env::temp_dir().join(format!("claudius-vault-{}", std::process::id()));
+        std::fs::create_dir_all(&vault).unwrap();
+        let image = vault.join("source_image.png");
+        std::fs::write(&image, b"png").unwrap();
+
+        let cards = vec![card("First", "Rust", Some(image.to_str().unwrap()))];
+        let briefing = ExportBriefing {
+            id: 1,
+            date: "2025-01-15",
+            title: "Briefing",
+            model_used: None,
+            cards: &cards,
+            first_new_card: 0,
+        };
+
+        let target = ObsidianVault::new(vault.to_str().unwrap(), "Claudius");
+        let path = PathBuf::from(
+            target
+                .export(&briefing)
+                .await
+                .expect("export should succeed"),
+        );
+
+        assert!(path.starts_with(vault.join("Claudius")));
+        assert!(vault.join("Claudius/attachments/source_image.png").exists());
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert!(content.contains("![[attachments/source_image.png]]"));
+
+        std::fs::remove_dir_all(&vault).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_obsidian_export_missing_vault() {
+        let cards = vec![];
+        let briefing = ExportBriefing {
+            id: 1,
+            date: "2025-01-15",
+            title: "Briefing",
+            model_used: None,
+            cards: &cards,
+            first_new_card: 0,
+        };
+        let target = ObsidianVault::new("/nonexistent/claudius/vault", "Claudius");
+        assert!(target.export(&briefing).await.is_err());
+    }
+
+    #[test]
+    fn test_normalize_notion_id() {
+        let id = "0123456789abcdef0123456789abcdef";
+        assert_eq!(normalize_notion_id(id), Some(id.to_string()));
+        assert_eq!(
+            normalize_notion_id("01234567-89ab-cdef-0123-456789abcdef"),
+            Some(id.to_string())
+        );
+        assert_eq!(
+            normalize_notion_id(
+                "https://www.notion.so/team/Briefings-0123456789ABCDEF0123456789abcdef?v=1234"
+            ),
+            Some(id.to_string())
+        );
+        assert_eq!(normalize_notion_id("not-an-id"), None);
+    }
+
+    #[test]
+    fn test_notion_page_body() {
+        let mut c = card("First", "AI, ML", None);
+        c.sources.push("Reuters".to_string());
+        let body = notion_page_body("db123", &c, "2025-01-15");
+
+        assert_eq!(body["parent"]["database_id"], "db123");
+        let props = &body["properties"];
+        assert_eq!(props["Name"]["title"][0]["text"]["content"], "First");
+        assert_eq!(props["Topic"]["select"]["name"], "AI  ML");
+        assert_eq!(props["Relevance"]["select"]["name"], "high");
+        assert_eq!(props["Date"]["date"]["start"], "2025-01-15");
+        assert_eq!(
+            props["Sources"]["rich_text"][0]["text"]["link"]["url"],
+            "https://example.com/a"
+        );
+        assert!(props["Sources"]["rich_text"][1]["text"]["link"].is_null());
+        assert_eq!(body["children"].as_array().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_rich_text_splits_long_content() {
+        let text = "x".repeat(NOTION_MAX_TEXT_CHARS + 10);
+        let parts = rich_text(&text);
+        assert_eq!(parts.len(), 2);
+        assert_eq!(parts[1]["text"]["content"], "x".repeat(10));
+    }
+
+    #[test]
+    fn test_configured_targets() {
+        let targets = configured_targets(ExportConfig {
+            obsidian_vault_path: Some("~/vault"),
+            obsidian_folder: "Claudius",
+            enable_notion_sync: true,
+            notion_database_id: Some("0123456789abcdef0123456789abcdef"),
+            notion_token: None,
+            ..Default::default()
+        });
+        let names: Vec<&str> = targets.iter().map(|t| t.name()).collect();
+        assert_eq!(names, vec!["Obsidian"]);
+    }
+}
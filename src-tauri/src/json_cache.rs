@@ -0,0 +1,54 @@
+//! JSON files in the cache directory.
+//!
+//! Lists fetched from the network (available models, the MCP catalog) are
+//! kept as one JSON file each, stamped with [`unix_now`] when fetched so
+//! callers can tell whether to refresh them. A missing or unreadable file is
+//! just a cache miss, and failing to write one only logs a warning.
+//
+// Note: Only `unix_now` is used by the Tauri app, so we allow dead_code.
+#![allow(dead_code)]
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tracing::warn;
+
+/// Current time in Unix seconds.
+pub fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Path of the cache file `name`.
+pub fn get_cache_path(name: &str) -> PathBuf {
+    crate::config::get_cache_dir().join(name)
+}
+
+/// Read the cache file `name`; None if it is missing or can't be parsed.
+pub fn read_cache<T: DeserializeOwned>(name: &str) -> Option<T> {
+    let content = std::fs::read_to_string(get_cache_path(name)).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+/// Write `value` to the cache file `name`. `what` names the cache in
+/// warnings.
+pub fn write_cache<T: Serialize>(name: &str, what: &str, value: &T) {
+    let path = get_cache_path(name);
+    if let Some(dir) = path.parent() {
+        if let Err(e) = std::fs::create_dir_all(dir) {
+            warn!("Failed to create {} cache directory: {}", what, e);
+            return;
+        }
+    }
+    match serde_json::to_string_pretty(value) {
+        Ok(content) => {
+            if let Err(e) = std::fs::write(path, content) {
+                warn!("Failed to write {} cache: {}", what, e);
+            }
+        }
+        Err(e) => warn!("Failed to serialize {} cache: {}", what, e),
+    }
+}
@@ -4,23 +4,68 @@
 // without Tauri dependencies.
 
 // Core modules (pure Rust, no Tauri dependencies)
+pub mod agent_loop;
+pub mod archive;
+pub mod article;
+pub mod batch;
+pub mod card_actions;
 pub mod chat;
 pub mod config;
+pub mod config_watcher;
+pub mod daemon;
 pub mod db;
 pub mod dedup;
+pub mod deep_link;
+pub mod doctor;
+pub mod exit_code;
+pub mod feed;
+pub mod fetch_policy;
+pub mod firecrawl_budget;
+pub mod github_cache;
+pub mod global_shortcut;
 pub mod housekeeping;
+pub mod http_client;
 pub mod image_gen;
+pub mod integrations;
+pub mod json_cache;
+pub mod log_sink;
+pub mod mcp_catalog;
 pub mod mcp_client;
+pub mod mcp_routing;
+pub mod meta_briefing;
+pub mod models;
+pub mod notification_prefs;
+pub mod quiet_hours;
+pub mod read_later;
+pub mod releases;
 pub mod research;
 pub mod research_log;
+pub mod research_mode;
 pub mod research_state;
+pub mod schedule_calendar;
+pub mod secret_store;
+pub mod server;
+pub mod settings_schema;
+pub mod share_image;
+pub mod smart_schedule;
+pub mod sources;
+pub mod stats;
+pub mod term_markdown;
+pub mod tools;
+pub mod topic_activity;
+pub mod topic_discovery;
+pub mod topic_preview;
+pub mod topic_timeline;
+pub mod tts;
+pub mod web_cache;
 
 // Re-export key types for convenience
 pub use chat::{clear_chat_history, get_chat_history, send_chat_message};
 pub use config::{
-    delete_api_key, delete_openai_api_key, ensure_config_dir, get_config_dir, has_api_key,
-    has_openai_api_key, read_api_key, read_mcp_servers, read_openai_api_key, read_settings,
-    validate_api_key, validate_openai_api_key, write_api_key, write_mcp_servers,
+    delete_api_key, delete_notion_token, delete_openai_api_key, ensure_config_dir, get_config_dir,
+    has_api_key, has_notion_token, has_openai_api_key, read_api_key, read_mcp_servers,
+    read_notion_token, read_openai_api_key, read_settings, validate_api_key, validate_notion_token,
+    validate_openai_api_key, write_api_key, write_mcp_servers, write_notion_token,
     write_openai_api_key, write_settings, Briefing, MCPServer, MCPServersConfig, ResearchSettings,
 };
 pub use db::{ChatMessage, Topic};
@@ -0,0 +1,507 @@
+//! Structured JSONL log sink for research runs.
+//!
+//! Research progress used to be appended to `~/.claudius/research-debug.log`,
+//! which grew without bound and was truncated at the start of every run.
+//! Records now go to `~/.claudius/logs/research-YYYY-MM-DD.jsonl`, one JSON
+//! object per line. A new file is started each day, and the active file is
+//! rotated to `research-YYYY-MM-DD.N.jsonl` once it exceeds the size limit.
+//! Old files are removed by housekeeping according to `log_retention_days`.
+//!
+//! [`research_layer`] feeds `tracing` output from the research, MCP client and
+//! chat modules into the same file. Each research run is wrapped in a
+//! `research_run` span whose `run_id` is attached to every record. Debug-level
+//! events are only captured while the `debug_logging` setting is on.
+
+use crate::config::get_logs_dir;
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime};
+use tracing::field::{Field, Visit};
+use tracing::{span, Event, Level, Metadata, Subscriber};
+use tracing_subscriber::filter::filter_fn;
+use tracing_subscriber::layer::Context;
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::Layer;
+
+/// Maximum size of the active log file before it is rotated (5 MB).
+pub const DEFAULT_MAX_BYTES: u64 = 5 * 1024 * 1024;
+
+/// File name prefix for research logs.
+pub const RESEARCH_LOG_PREFIX: &str = "research";
+
+/// Modules whose `tracing` output is captured in the research log.
+const CAPTURED_MODULES: &[&str] = &["research", "mcp_client", "chat"];
+
+static DEBUG_LOGGING: AtomicBool = AtomicBool::new(false);
+
+/// A single structured log record.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct LogRecord {
+    pub timestamp: String,
+    pub level: String,
+    pub event: String,
+    pub message: String,
+    #[serde(default, skip_serializing_if = "serde_json::Value::is_null")]
+    pub fields: serde_json::Value,
+}
+
+impl LogRecord {
+    pub fn new(level: &str, event: &str, message: impl Into<String>) -> Self {
+        Self {
+            timestamp: chrono::Local::now().to_rfc3339(),
+            level: level.to_string(),
+            event: event.to_string(),
+            message: message.into(),
+            fields: serde_json::Value::Null,
+        }
+    }
+
+    pub fn with_fields(mut self, fields: serde_json::Value) -> Self {
+        self.fields = fields;
+        self
+    }
+}
+
+/// Destination for structured log records.
+pub trait LogSink: Send + Sync {
+    fn write(&self, record: &LogRecord) -> Result<(), String>;
+}
+
+/// Appends records as JSON lines to a daily file with size-based rotation.
+pub struct JsonlFileSink {
+    dir: PathBuf,
+    prefix: String,
+    max_bytes: u64,
+    lock: Mutex<()>,
+}
+
+impl JsonlFileSink {
+    pub fn new(dir: impl Into<PathBuf>, prefix: &str, max_bytes: u64) -> Self {
+        Self {
+            dir: dir.into(),
+            prefix: prefix.to_string(),
+            max_bytes,
+            lock: Mutex::new(()),
+        }
+    }
+
+    /// Path of the file currently being written to.
+    pub fn active_path(&self) -> PathBuf {
+        active_path_in(&self.dir, &self.prefix)
+    }
+
+    /// Move the active file aside to the next free `.N.jsonl` slot.
+    fn rotate(&self, active: &Path) -> Result<(), String> {
+        let stem = active
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or(&self.prefix)
+            .to_string();
+        let mut n = 1;
+        let rotated = loop {
+            let candidate = self.dir.join(format!("{}.{}.jsonl", stem, n));
+            if !candidate.exists() {
+                break candidate;
+            }
+            n += 1;
+        };
+        fs::rename(active, &rotated).map_err(|e| format!("Failed to rotate log file: {}", e))
+    }
+}
+
+impl LogSink for JsonlFileSink {
+    fn write(&self, record: &LogRecord) -> Result<(), String> {
+        let mut line = serde_json::to_string(record)
+            .map_err(|e| format!("Failed to serialize log record: {}", e))?;
+        line.push('\n');
+
+        let _guard = self.lock.lock().unwrap_or_else(|e| e.into_inner());
+
+        fs::create_dir_all(&self.dir)
+            .map_err(|e| format!("Failed to create logs directory: {}", e))?;
+
+        let active = self.active_path();
+        if let Ok(meta) = fs::metadata(&active) {
+            if meta.len() > 0 && meta.len() + line.len() as u64 > self.max_bytes {
+                self.rotate(&active)?;
+            }
+        }
+
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&active)
+            .map_err(|e| format!("Failed to open log file: {}", e))?;
+        file.write_all(line.as_bytes())
+            .map_err(|e| format!("Failed to write log file: {}", e))
+    }
+}
+
+fn active_path_in(dir: &Path, prefix: &str) -> PathBuf {
+    dir.join(format!(
+        "{}-{}.jsonl",
+        prefix,
+        chrono::Local::now().format("%Y-%m-%d")
+    ))
+}
+
+lazy_static! {
+    static ref RESEARCH_SINK: JsonlFileSink =
+        JsonlFileSink::new(get_logs_dir(), RESEARCH_LOG_PREFIX, DEFAULT_MAX_BYTES);
+}
+
+/// Path of today's research log file.
+#[allow(dead_code)]
+pub fn active_log_path() -> PathBuf {
+    RESEARCH_SINK.active_path()
+}
+
+/// Write a record to the research log. Failures are reported via tracing only,
+/// logging must never interrupt a research run.
+pub fn record(record: LogRecord) {
+    if let Err(e) = RESEARCH_SINK.write(&record) {
+        tracing::debug!("Research log sink write failed: {}", e);
+    }
+}
+
+/// Turn capture of debug-level events on or off. Takes effect immediately.
+pub fn set_debug_logging(enabled: bool) {
+    DEBUG_LOGGING.store(enabled, Ordering::Relaxed);
+}
+
+fn is_captured(metadata: &Metadata<'_>) -> bool {
+    // Targets are module paths such as `claudius::research`
+    let module = metadata.target().split("::").nth(1).unwrap_or_default();
+    if !CAPTURED_MODULES.contains(&module) {
+        return false;
+    }
+    let max_level = if DEBUG_LOGGING.load(Ordering::Relaxed) {
+        Level::DEBUG
+    } else {
+        Level::INFO
+    };
+    *metadata.level() <= max_level
+}
+
+/// `tracing` layer that writes captured events to the research log.
+pub fn research_layer<S>() -> impl Layer<S>
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    ResearchLogLayer.with_filter(filter_fn(is_captured))
+}
+
+struct ResearchLogLayer;
+
+/// `run_id` of a `research_run` span, stored in the span's extensions.
+struct RunId(String);
+
+#[derive(Default)]
+struct FieldVisitor {
+    message: Option<String>,
+    fields: serde_json::Map<String, serde_json::Value>,
+}
+
+impl FieldVisitor {
+    fn insert(&mut self, field: &Field, value: serde_json::Value) {
+        self.fields.insert(field.name().to_string(), value);
+    }
+}
+
+impl Visit for FieldVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.message = Some(value.to_string());
+        } else {
+            self.insert(field, value.into());
+        }
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.insert(field, value.into());
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.insert(field, value.into());
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.insert(field, value.into());
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        self.record_str(field, &format!("{:?}", value));
+    }
+}
+
+impl<S> Layer<S> for ResearchLogLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &span::Attributes<'_>, id: &span::Id, ctx: Context<'_, S>) {
+        let mut visitor = FieldVisitor::default();
+        attrs.record(&mut visitor);
+        if let (Some(run_id), Some(span)) = (visitor.fields.remove("run_id"), ctx.span(id)) {
+            let run_id = run_id
+                .as_str()
+                .map(String::from)
+                .unwrap_or(run_id.to_string());
+            span.extensions_mut().insert(RunId(run_id));
+        }
+    }
+
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        let metadata = event.metadata();
+        let mut visitor = FieldVisitor::default();
+        event.record(&mut visitor);
+
+        let run_id = ctx.event_scope(event).and_then(|scope| {
+            scope
+                .from_root()
+                .find_map(|span| span.extensions().get::<RunId>().map(|r| r.0.clone()))
+        });
+
+        let mut fields = visitor.fields;
+        let event_name = match fields.remove("event") {
+            Some(serde_json::Value::String(name)) => name,
+            _ => metadata
+                .target()
+                .rsplit("::")
+                .next()
+                .unwrap_or_default()
+                .to_string(),
+        };
+        fields.insert("target".to_string(), metadata.target().into());
+        if let Some(run_id) = run_id {
+            fields.insert("run_id".to_string(), run_id.into());
+        }
+
+        record(
+            LogRecord::new(
+                &metadata.level().as_str().to_lowercase(),
+                &event_name,
+                visitor.message.unwrap_or_default(),
+            )
+            .with_fields(serde_json::Value::Object(fields)),
+        );
+    }
+}
+
+/// Follows a daily JSONL log, returning the lines appended since the last
+/// poll. Handles size rotation and the switch to a new file at midnight.
+#[allow(dead_code)]
+pub struct LogTail {
+    dir: PathBuf,
+    prefix: String,
+    path: PathBuf,
+    offset: u64,
+}
+
+#[allow(dead_code)]
+impl LogTail {
+    /// Follow the active file in `dir`, starting `offset` bytes in.
+    pub fn new(dir: impl Into<PathBuf>, prefix: &str, offset: u64) -> Self {
+        let dir = dir.into();
+        Self {
+            path: active_path_in(&dir, prefix),
+            dir,
+            prefix: prefix.to_string(),
+            offset,
+        }
+    }
+
+    /// Follow the research log, starting `offset` bytes into today's file.
+    pub fn research(offset: u64) -> Self {
+        Self::new(get_logs_dir(), RESEARCH_LOG_PREFIX, offset)
+    }
+
+    /// Follow the research log from its current end.
+    pub fn research_from_end() -> Self {
+        let offset = fs::metadata(active_log_path())
+            .map(|m| m.len())
+            .unwrap_or(0);
+        Self::research(offset)
+    }
+
+    /// Return complete lines written since the last call. A partially written
+    /// record is left for the next poll.
+    pub fn poll(&mut self) -> Result<Vec<String>, String> {
+        use std::io::{BufRead, BufReader, Seek, SeekFrom};
+
+        // A new day starts a new file
+        let active = active_path_in(&self.dir, &self.prefix);
+        if active != self.path {
+            self.path = active;
+            self.offset = 0;
+        }
+
+        let len = match fs::metadata(&self.path) {
+            Ok(meta) => meta.len(),
+            Err(_) => return Ok(Vec::new()),
+        };
+        // The file was rotated and a fresh one started
+        if len < self.offset {
+            self.offset = 0;
+        }
+        if len == self.offset {
+            return Ok(Vec::new());
+        }
+
+        let mut file =
+            fs::File::open(&self.path).map_err(|e| format!("Failed to open log file: {}", e))?;
+        file.seek(SeekFrom::Start(self.offset))
+            .map_err(|e| format!("Failed to read log file: {}", e))?;
+        let mut reader = BufReader::new(file);
+        let mut lines = Vec::new();
+        let mut line = String::new();
+        while reader
+            .read_line(&mut line)
+            .map_err(|e| format!("Failed to read log file: {}", e))?
+            > 0
+        {
+            if !line.ends_with('\n') {
+                break;
+            }
+            self.offset += line.len() as u64;
+            lines.push(line.trim_end().to_string());
+            line.clear();
+        }
+        Ok(lines)
+    }
+}
+
+/// Delete `.jsonl` log files in `dir` not modified within `retention_days`.
+/// Returns the number of files removed.
+pub fn cleanup_old_logs_in(dir: &Path, retention_days: u32) -> Result<usize, String> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(0),
+        Err(e) => return Err(format!("Failed to read logs directory: {}", e)),
+    };
+
+    let cutoff = SystemTime::now()
+        .checked_sub(Duration::from_secs(
+            u64::from(retention_days) * 24 * 60 * 60,
+        ))
+        .unwrap_or(SystemTime::UNIX_EPOCH);
+
+    let mut deleted = 0;
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.extension().and_then(|e| e.to_str()) != Some("jsonl") {
+            continue;
+        }
+        let modified = entry.metadata().and_then(|m| m.modified());
+        if matches!(modified, Ok(t) if t < cutoff) && fs::remove_file(&path).is_ok() {
+            deleted += 1;
+        }
+    }
+    Ok(deleted)
+}
+
+/// Delete research log files older than `retention_days` from `~/.claudius/logs`.
+pub fn cleanup_old_logs(retention_days: u32) -> Result<usize, String> {
+    cleanup_old_logs_in(&get_logs_dir(), retention_days)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("claudius-log-sink-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn test_writes_jsonl_records() {
+        let dir = temp_dir("write");
+        let sink = JsonlFileSink::new(&dir, "research", DEFAULT_MAX_BYTES);
+        sink.write(&LogRecord::new("info", "research_started", "Started"))
+            .unwrap();
+        sink.write(
+            &LogRecord::new("warn", "mcp_init", "Failed")
+                .with_fields(serde_json::json!({ "error": "timeout" })),
+        )
+        .unwrap();
+
+        let content = fs::read_to_string(sink.active_path()).unwrap();
+        let records: Vec<LogRecord> = content
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].event, "research_started");
+        assert!(records[0].fields.is_null());
+        assert_eq!(records[1].fields["error"], "timeout");
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_rotates_when_size_exceeded() {
+        let dir = temp_dir("rotate");
+        let sink = JsonlFileSink::new(&dir, "research", 200);
+        for i in 0..10 {
+            sink.write(&LogRecord::new("info", "step", format!("message {}", i)))
+                .unwrap();
+        }
+
+        let files: Vec<_> = fs::read_dir(&dir).unwrap().flatten().collect();
+        assert!(files.len() > 1);
+        for file in &files {
+            assert!(file.metadata().unwrap().len() <= 200);
+        }
+        assert!(sink.active_path().exists());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_log_tail_returns_new_lines() {
+        let dir = temp_dir("tail");
+        let sink = JsonlFileSink::new(&dir, "research", DEFAULT_MAX_BYTES);
+        sink.write(&LogRecord::new("info", "step", "before"))
+            .unwrap();
+
+        let start = fs::metadata(sink.active_path()).unwrap().len();
+        let mut tail = LogTail::new(&dir, "research", start);
+        assert!(tail.poll().unwrap().is_empty());
+
+        sink.write(&LogRecord::new("info", "step", "after"))
+            .unwrap();
+        let lines = tail.poll().unwrap();
+        assert_eq!(lines.len(), 1);
+        assert!(lines[0].contains("\"after\""));
+        assert!(tail.poll().unwrap().is_empty());
+
+        // A rotated file is read from the start
+        fs::write(sink.active_path(), "{}\n").unwrap();
+        assert_eq!(tail.poll().unwrap(), vec!["{}"]);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_cleanup_keeps_recent_logs() {
+        let dir = temp_dir("cleanup");
+        let sink = JsonlFileSink::new(&dir, "research", DEFAULT_MAX_BYTES);
+        sink.write(&LogRecord::new("info", "step", "recent"))
+            .unwrap();
+        fs::write(dir.join("notes.txt"), "not a log").unwrap();
+
+        assert_eq!(cleanup_old_logs_in(&dir, 7).unwrap(), 0);
+        assert_eq!(cleanup_old_logs_in(&dir, 0).unwrap(), 1);
+        assert!(dir.join("notes.txt").exists());
+        assert_eq!(cleanup_old_logs_in(&dir.join("missing"), 7).unwrap(), 0);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -1,25 +1,103 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
+mod agent_loop;
+mod article;
+mod batch;
+mod card_actions;
 mod commands;
 mod config;
 mod db;
 mod dedup;
+mod deep_link;
+mod feed;
+mod fetch_policy;
+mod firecrawl_budget;
+mod github_cache;
+mod global_shortcut;
 mod housekeeping;
+mod http_client;
 mod image_gen;
+mod integrations;
+mod json_cache;
+mod log_sink;
 mod mcp_client;
+mod mcp_routing;
+mod notification_prefs;
 mod notifications;
+mod quiet_hours;
+mod releases;
 mod research;
 mod research_log;
+mod research_mode;
 mod research_state;
+mod schedule_calendar;
+mod secret_store;
+mod sources;
+mod tools;
 mod tray;
+mod tts;
+mod ui_state;
 mod updater;
+mod web_cache;
 
 use tauri::{Emitter, Manager};
-use tauri_plugin_global_shortcut::{Code, GlobalShortcutExt, Modifiers, Shortcut, ShortcutState};
+use tauri_plugin_deep_link::DeepLinkExt;
+use tracing_subscriber::filter::LevelFilter;
+use tracing_subscriber::prelude::*;
+
+/// Open a `claudius://` URL received from the OS.
+fn open_deep_link(app: &tauri::AppHandle, url: &str) {
+    match deep_link::DeepLink::parse(url) {
+        Some(link) => {
+            tracing::info!("Opening deep link: {}", url);
+            show_deep_link(app, link);
+        }
+        None => tracing::warn!("Ignoring unrecognized deep link: {}", url),
+    }
+}
+
+/// Show the main window and ask the frontend to navigate to `link`.
+/// Also used by notification actions.
+fn show_deep_link(app: &tauri::AppHandle, link: deep_link::DeepLink) {
+    // Kept until the frontend collects it, in case it isn't listening yet
+    deep_link::set_pending(link);
+    let _ = app.emit("deeplink:open", link);
+
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.show();
+        let _ = window.set_focus();
+    }
+}
 
 fn main() {
-    // Initialize tracing for logging
-    tracing_subscriber::fmt::init();
+    // Move ~/.claudius to the XDG directories on Linux, before anything
+    // opens the database or log files (reported once logging is up)
+    let dir_migration = config::migrate_legacy_dir();
+
+    // Initialize tracing for logging: INFO to stdout, plus the research log file
+    let debug_logging = config::read_settings()
+        .map(|s| s.debug_logging)
+        .unwrap_or(false);
+    log_sink::set_debug_logging(debug_logging);
+    tracing_subscriber::registry()
+        .with(tracing_subscriber::fmt::layer().with_filter(LevelFilter::INFO))
+        .with(log_sink::research_layer())
+        .init();
+
+    match dir_migration {
+        Ok(Some(dirs)) => tracing::info!(
+            "Moved ~/.claudius to {}, {} and {}",
+            dirs.config.display(),
+            dirs.data.display(),
+            dirs.cache.display()
+        ),
+        Ok(None) => {}
+        Err(e) => tracing::warn!("Moving ~/.claudius failed, still using it: {}", e),
+    }
+    let workspace = config::active_workspace();
+    if workspace != config::DEFAULT_WORKSPACE {
+        tracing::info!("Using workspace {}", workspace);
+    }
 
     tauri::Builder::default()
         .plugin(tauri_plugin_shell::init())
@@ -27,7 +105,9 @@ fn main() {
         .plugin(tauri_plugin_positioner::init())
         .plugin(tauri_plugin_fs::init())
         .plugin(tauri_plugin_dialog::init())
+        .plugin(tauri_plugin_deep_link::init())
         .plugin(tauri_plugin_global_shortcut::Builder::new().build())
+        .plugin(tauri_plugin_clipboard_manager::init())
         .plugin(tauri_plugin_single_instance::init(|app, args, _cwd| {
             // Check if this is a refresh signal from CLI
             let is_refresh = args
@@ -62,45 +142,90 @@ fn main() {
             // Topic commands
             commands::get_topics,
             commands::add_topic,
+            commands::get_topic_suggestions,
+            commands::suggest_topics,
+            commands::preview_topic,
+            commands::accept_topic_suggestion,
+            commands::dismiss_topic_suggestion,
+            commands::get_topic_activity,
+            commands::get_topic_timeline,
             commands::update_topic,
+            commands::snooze_topic,
+            commands::set_topic_dedup,
             commands::delete_topic,
             commands::reorder_topics,
             // MCP server commands
             commands::get_mcp_servers,
+            commands::get_builtin_tools,
+            commands::get_research_modes,
             commands::toggle_mcp_server,
             commands::add_mcp_server,
             commands::update_mcp_server,
             commands::remove_mcp_server,
+            commands::get_mcp_catalog,
+            commands::get_mcp_preset_fields,
+            commands::install_mcp_preset,
             // Settings commands
             commands::get_settings,
             commands::update_settings,
+            commands::list_models,
+            commands::check_model,
             // Notification commands
             commands::request_notification_permission,
-            // API Key commands (stored in ~/.claudius/.env)
+            // API Key commands (stored in the OS credential store)
             commands::get_api_key,
             commands::set_api_key,
             commands::has_api_key,
             commands::clear_api_key,
+            commands::list_profiles,
+            commands::add_profile,
+            commands::remove_profile,
+            commands::set_active_profile,
+            commands::get_profile_usage,
+            // Workspaces
+            commands::list_workspaces,
+            commands::create_workspace,
+            commands::switch_workspace,
             // OpenAI API Key commands (for DALL-E image generation)
             commands::get_openai_api_key,
             commands::set_openai_api_key,
             commands::has_openai_api_key,
             commands::clear_openai_api_key,
+            commands::get_notion_token,
+            commands::set_notion_token,
+            commands::has_notion_token,
+            commands::clear_notion_token,
             // Research commands
             commands::trigger_research,
             commands::run_research_now,
+            commands::research_adhoc,
+            commands::quick_research,
+            commands::retry_failed_topics,
+            commands::generate_meta_briefing,
             // Chat commands
             commands::send_chat_message,
+            commands::send_archive_chat_message,
             commands::get_chat_history,
             commands::clear_chat_history,
+            commands::export_chat,
+            commands::reorder_cards,
             commands::get_cards_with_chats,
             // Bookmark commands
             commands::toggle_bookmark,
             commands::is_card_bookmarked,
             commands::get_bookmarks,
+            commands::save_card_for_later,
+            commands::get_read_later_queue,
+            commands::complete_read_later_item,
+            commands::remove_read_later_item,
+            commands::reorder_read_later,
+            commands::get_discarded_cards,
+            commands::restore_discarded_card,
+            commands::delete_discarded_card,
             // Housekeeping commands
             commands::delete_briefing,
             commands::briefing_has_bookmarks,
+            commands::render_card_share_image,
             commands::run_housekeeping,
             commands::get_cleanup_preview,
             commands::get_briefing_count,
@@ -118,20 +243,41 @@ fn main() {
             // Research log commands
             commands::get_research_logs,
             commands::get_actionable_errors,
+            commands::get_tool_analytics,
+            commands::get_statistics,
+            commands::get_research_report,
+            commands::get_briefing_topics,
+            commands::get_briefing_failed_topics,
             // Research state control commands
             commands::cancel_research,
             commands::reset_research_state,
             commands::get_research_status,
+            commands::get_research_progress_snapshot,
+            commands::get_event_backlog,
             // CLI installation commands
             commands::get_cli_status,
             commands::install_cli,
             commands::uninstall_cli,
             // Auto-update commands
-            commands::check_for_update,
+            commands::check_for_updates,
             commands::install_update_and_restart,
             // Export & Print commands
+            commands::generate_briefing_audio,
+            commands::regenerate_card_image,
+            commands::get_image_styles,
+            commands::sync_briefing_to_notion,
             commands::export_card,
             commands::print_card,
+            // Card action commands
+            commands::run_card_action,
+            commands::get_reminders,
+            commands::delete_reminder,
+            // Deep link and read state commands
+            commands::take_pending_deep_link,
+            commands::get_ui_state,
+            commands::update_global_shortcut,
+            commands::save_ui_state,
+            commands::mark_briefing_read,
         ])
         .setup(|app| {
             let app_handle = app.handle().clone();
@@ -139,9 +285,26 @@ fn main() {
             // Initialize database
             db::init_database(&app_handle)?;
 
+            // Bring legacy config files (config.json) up to the current layout
+            if let Err(e) = config::migrate_config(|interests| {
+                let conn = db::get_connection()
+                    .map_err(|e| format!("Database connection failed: {}", e))?;
+                db::import_legacy_interests(&conn, interests)
+            }) {
+                tracing::warn!("Config migration failed: {}", e);
+            }
+
+            // Reopen the main window where it was last left
+            if let Some(window) = app.get_webview_window("main") {
+                ui_state::restore_window_geometry(&window);
+            }
+
             // Run housekeeping cleanup (non-blocking, logs errors)
             housekeeping::run_startup_cleanup();
 
+            // Launching the app counts as activity for the adaptive schedule
+            claudius::smart_schedule::record_activity(claudius::smart_schedule::ACTIVITY_APP_OPEN);
+
             // Initialize system tray (kept for Windows/Linux where it works better)
             if let Err(e) = tray::init_tray(&app_handle) {
                 tracing::error!("Failed to initialize tray: {}", e);
@@ -155,42 +318,80 @@ fn main() {
                 }
             });
 
-            // Register global shortcut: Cmd+Shift+B (macOS) or Ctrl+Shift+B (Windows/Linux)
-            #[cfg(target_os = "macos")]
-            let shortcut = Shortcut::new(Some(Modifiers::SUPER | Modifiers::SHIFT), Code::KeyB);
-            #[cfg(not(target_os = "macos"))]
-            let shortcut = Shortcut::new(Some(Modifiers::CONTROL | Modifiers::SHIFT), Code::KeyB);
-
-            let app_handle_for_shortcut = app_handle.clone();
-            if let Err(e) =
-                app.global_shortcut()
-                    .on_shortcut(shortcut, move |_app, _shortcut, event| {
-                        // Only respond to key press, not release (prevents double-trigger)
-                        if event.state != ShortcutState::Pressed {
-                            return;
-                        }
-                        tracing::info!("Global shortcut triggered (key pressed)");
-                        if let Some(window) = app_handle_for_shortcut.get_webview_window("main") {
-                            match window.is_visible() {
-                                Ok(true) => {
-                                    tracing::info!("Main window visible, hiding");
-                                    let _ = window.hide();
-                                }
-                                Ok(false) => {
-                                    tracing::info!("Main window hidden, showing");
-                                    let _ = window.show();
-                                    let _ = window.set_focus();
-                                }
-                                Err(e) => {
-                                    tracing::error!("Failed to check window visibility: {}", e);
+            // Deliver card-action reminders and snoozed notifications as they come due
+            let reminder_handle = app_handle.clone();
+            tauri::async_runtime::spawn(async move {
+                let mut interval = tokio::time::interval(std::time::Duration::from_secs(60));
+                loop {
+                    interval.tick().await;
+                    notifications::deliver_due_reminders(&reminder_handle);
+                    notifications::deliver_queued_notifications(&reminder_handle);
+                }
+            });
+
+            // Pick up edits to preferences.json / mcp-servers.json made outside the app
+            let config_handle = app_handle.clone();
+            tauri::async_runtime::spawn(async move {
+                let mut watcher = claudius::config_watcher::ConfigWatcher::new();
+                let mut interval = tokio::time::interval(std::time::Duration::from_secs(
+                    claudius::config_watcher::POLL_INTERVAL_SECS,
+                ));
+                loop {
+                    interval.tick().await;
+                    let files = watcher.poll();
+                    if files.is_empty() {
+                        continue;
+                    }
+                    tracing::info!("Config files changed: {:?}", files);
+                    if files.contains(&claudius::config_watcher::ConfigFile::Settings) {
+                        match config::read_settings() {
+                            Ok(settings) => {
+                                log_sink::set_debug_logging(settings.debug_logging);
+                                if let Err(e) = global_shortcut::apply(
+                                    &config_handle,
+                                    &settings.global_shortcut,
+                                    &settings.quick_research_shortcut,
+                                ) {
+                                    tracing::warn!("{}", e);
                                 }
                             }
+                            Err(e) => tracing::warn!("Ignoring changed settings: {}", e),
                         }
-                    })
-            {
-                tracing::error!("Failed to register global shortcut: {}", e);
-            } else {
-                tracing::info!("Global shortcut registered: Cmd/Ctrl+Shift+B");
+                    }
+                    let _ = config_handle.emit(
+                        "config:changed",
+                        claudius::config_watcher::ConfigChangedEvent { files },
+                    );
+                }
+            });
+
+            // Handle claudius:// links (e.g. claudius://briefing/123)
+            #[cfg(any(target_os = "linux", target_os = "windows"))]
+            if let Err(e) = app.deep_link().register_all() {
+                tracing::warn!("Failed to register claudius:// URL scheme: {}", e);
+            }
+            if let Ok(Some(urls)) = app.deep_link().get_current() {
+                for url in urls {
+                    open_deep_link(&app_handle, url.as_str());
+                }
+            }
+            let deep_link_handle = app_handle.clone();
+            app.deep_link().on_open_url(move |event| {
+                for url in event.urls() {
+                    open_deep_link(&deep_link_handle, url.as_str());
+                }
+            });
+
+            // Show/hide the main window from anywhere (Cmd/Ctrl+Shift+B unless configured),
+            // and research the clipboard (Cmd/Ctrl+Alt+Shift+R unless configured)
+            global_shortcut::set_quick_research_action(commands::quick_research_from_clipboard);
+            let settings = config::read_settings().unwrap_or_default();
+            if let Err(e) = global_shortcut::apply(
+                &app_handle,
+                &settings.global_shortcut,
+                &settings.quick_research_shortcut,
+            ) {
+                tracing::error!("{}", e);
             }
 
             Ok(())
@@ -202,6 +403,9 @@ fn main() {
                 tauri::WindowEvent::CloseRequested { api, .. } => {
                     if window.label() == "main" {
                         tracing::info!("Main window close requested, hiding instead");
+                        if let Some(main) = window.app_handle().get_webview_window("main") {
+                            ui_state::save_window_geometry(&main);
+                        }
                         let _ = window.hide();
                         api.prevent_close();
                     }
@@ -213,6 +417,14 @@ fn main() {
                         tracing::info!("Popover lost focus, hiding");
                         let _ = window.hide();
                     }
+                    // Times the adaptive schedule (schedule_mode) learns from
+                    if *focused {
+                        tauri::async_runtime::spawn_blocking(|| {
+                            claudius::smart_schedule::record_activity(
+                                claudius::smart_schedule::ACTIVITY_APP_OPEN,
+                            )
+                        });
+                    }
                 }
                 _ => {}
             }
@@ -0,0 +1,516 @@
+//! Catalog of popular MCP servers with install presets.
+//!
+//! A curated list of servers (Brave Search, Perplexity, Firecrawl, GitHub,
+//! ...) ships with the app and is refreshed from the repository once a day,
+//! so new presets and changed package names reach users without a release.
+//! Each preset is a command/args/env template in which `{key}` stands for the
+//! user's API key; installing a preset fills it in and writes the server to
+//! `mcp-servers.json`.
+
+use crate::config::{read_mcp_servers, write_mcp_servers, MCPServer};
+use crate::json_cache::{self, unix_now};
+use crate::mcp_client::{McpClient, McpServerConfig};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::time::Duration;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+/// Catalog shipped with the app.
+const BUNDLED_CATALOG: &str = include_str!("mcp-catalog.json");
+
+/// Latest catalog, published from the main branch.
+const CATALOG_URL: &str =
+    "https://raw.githubusercontent.com/chrisvanbuskirk/claudius/main/src-tauri/src/mcp-catalog.json";
+
+/// Cache file of the remote catalog.
+const CACHE_FILE: &str = "mcp-catalog.json";
+
+/// Cached catalogs younger than this are used without fetching.
+const CACHE_TTL_SECS: u64 = 24 * 60 * 60;
+
+/// Give up on the remote catalog after this long and use the local one.
+const FETCH_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Placeholder for the user's API key in preset args and env values.
+const KEY_PLACEHOLDER: &str = "{key}";
+
+/// An MCP server preset.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CatalogEntry {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    #[serde(default)]
+    pub pricing: String,
+    #[serde(default)]
+    pub recommended: bool,
+    pub command: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+    #[serde(default)]
+    pub env: BTreeMap<String, String>,
+    /// What the key is called on the provider's site ("API Key", "Personal Access Token").
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key_label: Option<String>,
+    /// Where to get a key.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key_url: Option<String>,
+}
+
+impl CatalogEntry {
+    /// The environment variable the API key goes in, if the preset needs one.
+    pub fn key_env(&self) -> Option<&str> {
+        self.env
+            .iter()
+            .find(|(_, value)| value.contains(KEY_PLACEHOLDER))
+            .map(|(name, _)| name.as_str())
+    }
+
+    /// Whether installing this preset needs an API key.
+    pub fn requires_key(&self) -> bool {
+        self.key_env().is_some() || self.args.iter().any(|a| a.contains(KEY_PLACEHOLDER))
+    }
+
+    /// Values the user has to provide to install this preset.
+    pub fn required_fields(&self) -> Vec<RequiredField> {
+        if !self.requires_key() {
+            return Vec::new();
+        }
+        vec![RequiredField {
+            env_var: self.key_env().unwrap_or("KEY").to_string(),
+            label: self
+                .key_label
+                .clone()
+                .unwrap_or_else(|| "API Key".to_string()),
+            url: self.key_url.clone(),
+            secret: true,
+        }]
+    }
+
+    /// The API key: `key`, or the key's environment variable in `extra_env`.
+    fn resolve_key<'a>(
+        &self,
+        key: Option<&'a str>,
+        extra_env: &'a [(String, String)],
+    ) -> Option<&'a str> {
+        key.or_else(|| {
+            let key_env = self.key_env()?;
+            extra_env
+                .iter()
+                .find(|(name, _)| name == key_env)
+                .map(|(_, value)| value.as_str())
+        })
+        .map(str::trim)
+        .filter(|k| !k.is_empty())
+    }
+
+    /// Required fields that neither `key` nor `extra_env` provides.
+    pub fn missing_fields(
+        &self,
+        key: Option<&str>,
+        extra_env: &[(String, String)],
+    ) -> Vec<RequiredField> {
+        if self.resolve_key(key, extra_env).is_some() {
+            Vec::new()
+        } else {
+            self.required_fields()
+        }
+    }
+
+    /// Server config (`command`/`args`/`env`) with the key filled in. Entries
+    /// in `extra_env` are added to, or override, the preset's environment.
+    pub fn server_config(
+        &self,
+        key: Option<&str>,
+        extra_env: &[(String, String)],
+    ) -> Result<Value, String> {
+        let key = self.resolve_key(key, extra_env);
+        if self.requires_key() && key.is_none() {
+            return Err(format!(
+                "{} needs {} ({})",
+                self.name,
+                self.key_label.as_deref().unwrap_or("an API key"),
+                self.key_env().unwrap_or("--key")
+            ));
+        }
+        let fill = |template: &str| template.replace(KEY_PLACEHOLDER, key.unwrap_or_default());
+
+        let mut env: BTreeMap<String, String> = self
+            .env
+            .iter()
+            .map(|(name, value)| (name.clone(), fill(value)))
+            .collect();
+        env.extend(extra_env.iter().cloned());
+
+        let mut config = json!({
+            "command": self.command,
+            "args": self.args.iter().map(|a| fill(a)).collect::<Vec<_>>(),
+        });
+        if !env.is_empty() {
+            config["env"] = json!(env);
+        }
+        Ok(config)
+    }
+}
+
+/// A value the user has to provide to install a preset.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct RequiredField {
+    /// Environment variable the value goes in.
+    pub env_var: String,
+    pub label: String,
+    /// Where to get the value.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    pub secret: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct McpCatalog {
+    pub version: u32,
+    pub servers: Vec<CatalogEntry>,
+}
+
+impl McpCatalog {
+    /// Find a preset by id or (case-insensitive) name.
+    pub fn find(&self, id_or_name: &str) -> Option<&CatalogEntry> {
+        self.servers
+            .iter()
+            .find(|s| s.id == id_or_name || s.name.eq_ignore_ascii_case(id_or_name))
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CatalogCache {
+    fetched_at: u64, // Unix seconds
+    catalog: McpCatalog,
+}
+
+/// The catalog shipped with this build.
+pub fn bundled_catalog() -> McpCatalog {
+    serde_json::from_str(BUNDLED_CATALOG).unwrap_or_else(|e| {
+        warn!("Bundled MCP catalog is invalid: {}", e);
+        McpCatalog::default()
+    })
+}
+
+async fn fetch_catalog() -> Result<McpCatalog, String> {
+    let response = crate::http_client::client()
+        .get(CATALOG_URL)
+        .timeout(FETCH_TIMEOUT)
+        .send()
+        .await
+        .map_err(|e| format!("HTTP request failed: {}", e))?;
+
+    if !response.status().is_success() {
+        return Err(format!(
+            "Catalog request failed: {}",
+            response.status().as_u16()
+        ));
+    }
+
+    response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse MCP catalog: {}", e))
+}
+
+/// The newer of two catalogs; the first wins a tie.
+fn newer(a: McpCatalog, b: McpCatalog) -> McpCatalog {
+    if b.version > a.version {
+        b
+    } else {
+        a
+    }
+}
+
+/// The MCP server catalog: the bundled one, or a newer one from the
+/// repository. The remote copy is cached for a day unless `refresh` is set;
+/// if it can't be fetched, the cache or the bundled catalog is used.
+pub async fn load_catalog(refresh: bool) -> McpCatalog {
+    let bundled = bundled_catalog();
+    let cached: Option<CatalogCache> = json_cache::read_cache(CACHE_FILE);
+    if !refresh {
+        if let Some(cache) = &cached {
+            if unix_now().saturating_sub(cache.fetched_at) < CACHE_TTL_SECS {
+                return newer(bundled, cache.catalog.clone());
+            }
+        }
+    }
+
+    match fetch_catalog().await {
+        Ok(catalog) => {
+            info!(
+                "Fetched MCP catalog v{} ({} servers)",
+                catalog.version,
+                catalog.servers.len()
+            );
+            json_cache::write_cache(
+                CACHE_FILE,
+                "MCP catalog",
+                &CatalogCache {
+                    fetched_at: unix_now(),
+                    catalog: catalog.clone(),
+                },
+            );
+            newer(bundled, catalog)
+        }
+        Err(e) => {
+            warn!("Failed to refresh MCP catalog: {}", e);
+            match cached {
+                Some(cache) => newer(bundled, cache.catalog),
+                None => bundled,
+            }
+        }
+    }
+}
+
+/// Environment variables in a server config that were left empty or still
+/// hold a placeholder such as `YOUR_BRAVE_API_KEY`.
+pub fn unset_env_vars(server_config: &Value) -> Vec<String> {
+    server_config
+        .get("env")
+        .and_then(|env| env.as_object())
+        .map(|env| {
+            env.iter()
+                .filter(|(_, value)| {
+                    let value = value.as_str().unwrap_or_default().trim();
+                    value.is_empty()
+                        || value.starts_with("YOUR_")
+                        || value.contains(KEY_PLACEHOLDER)
+                })
+                .map(|(name, _)| name.clone())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// What most likely went wrong when a server failed to start, in terms the
+/// user can act on, followed by the underlying error.
+pub fn explain_connect_error(command: &str, key_label: Option<&str>, error: &str) -> String {
+    let lower = error.to_lowercase();
+    let mentions = |patterns: &[&str]| patterns.iter().any(|p| lower.contains(p));
+
+    let reason = if lower.contains("failed to spawn")
+        && mentions(&["os error 2", "not found", "no such file"])
+    {
+        if matches!(command, "npx" | "npm" | "node") {
+            "Node.js isn't installed, so npx can't be found. Install it from https://nodejs.org (it includes npm and npx) and try again.".to_string()
+        } else {
+            format!(
+                "'{}' was not found. Check that it is installed and on your PATH.",
+                command
+            )
+        }
+    } else if lower.contains("failed to spawn") {
+        format!("'{}' could not be started.", command)
+    } else if mentions(&["e404", "404 not found", "is not in this registry"]) {
+        "npm couldn't find the server's package. The preset may be out of date; try 'claudius mcp catalog --refresh'.".to_string()
+    } else if mentions(&[
+        "401",
+        "403",
+        "unauthorized",
+        "forbidden",
+        "invalid api key",
+        "invalid key",
+        "invalid token",
+        "authentication",
+    ]) {
+        format!(
+            "The server rejected the {}. Check that it was copied correctly and is still active.",
+            key_label.unwrap_or("API key")
+        )
+    } else if mentions(&[
+        "enotfound",
+        "eai_again",
+        "econnrefused",
+        "etimedout",
+        "network",
+    ]) {
+        "The server's package couldn't be downloaded. Check your internet connection and proxy settings.".to_string()
+    } else if lower.contains("timed out") {
+        "The server didn't answer in time. The first start can be slow while npx downloads the package; try again.".to_string()
+    } else {
+        "The server failed to start.".to_string()
+    };
+
+    format!("{}\n\nDetails: {}", reason, error)
+}
+
+/// Start the server described by `server_config` and list its tools, then
+/// shut it down. Failures are explained with [`explain_connect_error`].
+pub async fn verify(
+    name: &str,
+    server_config: &Value,
+    key_label: Option<&str>,
+) -> Result<Vec<String>, String> {
+    let server = McpServerConfig {
+        id: String::new(),
+        name: name.to_string(),
+        enabled: true,
+        config: server_config.clone(),
+        last_used: None,
+    };
+    let command = server_config
+        .get("command")
+        .and_then(|c| c.as_str())
+        .unwrap_or_default();
+    McpClient::check_server(&server)
+        .await
+        .map_err(|e| explain_connect_error(command, key_label, &e))
+}
+
+/// Add `entry` to the MCP servers config with the key filled in, enabled.
+/// A server already configured under the preset's name is updated in place.
+/// With `check` set, the server must start and list its tools before it is
+/// saved.
+pub async fn install(
+    entry: &CatalogEntry,
+    key: Option<&str>,
+    extra_env: &[(String, String)],
+    check: bool,
+) -> Result<MCPServer, String> {
+    let server_config = entry.server_config(key, extra_env)?;
+    if check {
+        let tools = verify(&entry.name, &server_config, entry.key_label.as_deref()).await?;
+        info!(
+            "MCP server '{}' started with {} tools",
+            entry.name,
+            tools.len()
+        );
+    }
+    let mut config = read_mcp_servers()?;
+    let server = match config
+        .servers
+        .iter_mut()
+        .find(|s| s.name.eq_ignore_ascii_case(&entry.name))
+    {
+        Some(existing) => {
+            existing.config = server_config;
+            existing.enabled = true;
+            existing.clone()
+        }
+        None => {
+            let server = MCPServer {
+                id: Uuid::new_v4().to_string(),
+                name: entry.name.clone(),
+                enabled: true,
+                config: server_config,
+                last_used: None,
+            };
+            config.servers.push(server.clone());
+            server
+        }
+    };
+
+    write_mcp_servers(&config)?;
+    info!("Installed MCP server '{}' from catalog", server.name);
+    Ok(server)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bundled_catalog_parses() {
+        let catalog: McpCatalog = serde_json::from_str(BUNDLED_CATALOG).unwrap();
+        assert!(catalog.version >= 1);
+        for id in ["brave-search", "perplexity", "firecrawl", "github"] {
+            let entry = catalog.find(id).unwrap();
+            assert!(entry.requires_key(), "{} should need a key", id);
+        }
+        assert_eq!(catalog.find("brave search").unwrap().id, "brave-search");
+        assert!(!catalog.find("memory").unwrap().requires_key());
+    }
+
+    #[test]
+    fn test_server_config_fills_key() {
+        let catalog = bundled_catalog();
+        let brave = catalog.find("brave-search").unwrap();
+        assert_eq!(brave.key_env(), Some("BRAVE_API_KEY"));
+        assert!(brave.server_config(None, &[]).is_err());
+        assert!(brave.server_config(Some("  "), &[]).is_err());
+
+        let config = brave
+            .server_config(Some("abc123"), &[("EXTRA".to_string(), "1".to_string())])
+            .unwrap();
+        assert_eq!(config["command"], "npx");
+        assert_eq!(config["env"]["BRAVE_API_KEY"], "abc123");
+        assert_eq!(config["env"]["EXTRA"], "1");
+
+        let memory = catalog.find("memory").unwrap();
+        let config = memory.server_config(None, &[]).unwrap();
+        assert!(config.get("env").is_none());
+    }
+
+    #[test]
+    fn test_missing_fields() {
+        let catalog = bundled_catalog();
+        let github = catalog.find("github").unwrap();
+
+        let missing = github.missing_fields(None, &[]);
+        assert_eq!(missing.len(), 1);
+        assert_eq!(missing[0].env_var, "GITHUB_PERSONAL_ACCESS_TOKEN");
+        assert_eq!(missing[0].label, "Personal Access Token");
+
+        assert!(github.missing_fields(Some("ghp_123"), &[]).is_empty());
+        let env = [(
+            "GITHUB_PERSONAL_ACCESS_TOKEN".to_string(),
+            "ghp_123".to_string(),
+        )];
+        assert!(github.missing_fields(None, &env).is_empty());
+        let config = github.server_config(None, &env).unwrap();
+        assert_eq!(config["env"]["GITHUB_PERSONAL_ACCESS_TOKEN"], "ghp_123");
+
+        assert!(catalog
+            .find("fetch")
+            .unwrap()
+            .missing_fields(None, &[])
+            .is_empty());
+    }
+
+    #[test]
+    fn test_unset_env_vars() {
+        let config = json!({
+            "command": "npx",
+            "env": {
+                "BRAVE_API_KEY": "YOUR_BRAVE_API_KEY",
+                "TOKEN": "",
+                "TIMEOUT": "600000"
+            }
+        });
+        assert_eq!(unset_env_vars(&config), vec!["BRAVE_API_KEY", "TOKEN"]);
+        assert!(unset_env_vars(&json!({ "command": "npx" })).is_empty());
+    }
+
+    #[test]
+    fn test_explain_connect_error() {
+        let missing_npx = explain_connect_error(
+            "npx",
+            None,
+            "Failed to spawn MCP server 'Brave': No such file or directory (os error 2)",
+        );
+        assert!(missing_npx.starts_with("Node.js isn't installed"));
+        assert!(missing_npx.contains("Details: Failed to spawn"));
+
+        let bad_key = explain_connect_error(
+            "npx",
+            Some("Personal Access Token"),
+            "MCP server exited before responding\nServer output:\nError: 401 Unauthorized",
+        );
+        assert!(bad_key.contains("rejected the Personal Access Token"));
+
+        let no_package = explain_connect_error(
+            "npx",
+            None,
+            "npm ERR! code E404\nnpm ERR! 404 Not Found - GET https://registry.npmjs.org/nope",
+        );
+        assert!(no_package.starts_with("npm couldn't find"));
+
+        assert!(explain_connect_error("uvx", None, "something else")
+            .starts_with("The server failed to start."));
+    }
+}
@@ -6,16 +6,35 @@
 
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
-use std::collections::HashMap;
-use std::io::{BufRead, BufReader, Write};
-use std::process::{Child, Command, Stdio};
+use std::collections::{HashMap, VecDeque};
+use std::process::Stdio;
 use std::sync::atomic::{AtomicU64, Ordering};
-use std::time::Duration;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::process::{Child, ChildStdin, ChildStdout, Command};
+use tokio::sync::{oneshot, Mutex};
+use tokio::task::JoinHandle;
 use tracing::{debug, info, warn};
 
 /// Counter for generating unique JSON-RPC request IDs.
 static REQUEST_ID: AtomicU64 = AtomicU64::new(1);
 
+/// How long a server gets to answer each request while starting up.
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// How long a tool call may take before it is abandoned.
+pub const REQUEST_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// How many of a server's last stderr lines are kept for error messages.
+const STDERR_TAIL_LINES: usize = 20;
+
+/// How long a failed start waits for the server's remaining stderr output.
+const STDERR_WAIT: Duration = Duration::from_secs(1);
+
+/// Tool calls slower than this are logged as slow.
+const SLOW_CALL: Duration = Duration::from_secs(30);
+
 /// MCP server configuration as stored in the config file.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct McpServerConfig {
@@ -36,112 +55,72 @@ pub struct McpTool {
     pub input_schema: Value,
 }
 
-/// A running MCP server connection.
-pub struct McpConnection {
-    pub server_name: String,
-    pub server_id: String,
-    child: Child,
-    tools: Vec<McpTool>,
-    /// Original config for restarting the server if it crashes
-    config: McpServerConfig,
+/// Requests waiting for a response, by JSON-RPC id. `None` once the server has
+/// exited, which drops the senders and wakes every waiting request.
+type PendingMap = Arc<std::sync::Mutex<Option<HashMap<u64, oneshot::Sender<Value>>>>>;
+
+fn lock_pending(
+    pending: &PendingMap,
+) -> std::sync::MutexGuard<'_, Option<HashMap<u64, oneshot::Sender<Value>>>> {
+    pending.lock().unwrap_or_else(|e| e.into_inner())
 }
 
-impl McpConnection {
-    /// Get the available tools from this server.
-    #[allow(dead_code)]
-    pub fn tools(&self) -> &[McpTool] {
-        &self.tools
-    }
+/// JSON-RPC over a server process's stdio. Writes are serialized; a background
+/// task reads stdout and hands each response to the request with its id, so
+/// several requests can be in flight at once.
+struct Transport {
+    // Killed when the transport is dropped
+    _child: Child,
+    stdin: Mutex<ChildStdin>,
+    pending: PendingMap,
+    reader: JoinHandle<()>,
+    /// Last lines the server wrote to stderr, to explain a failed start
+    stderr_tail: Arc<std::sync::Mutex<VecDeque<String>>>,
+    stderr_reader: Option<JoinHandle<()>>,
 }
 
-impl Drop for McpConnection {
+impl Drop for Transport {
     fn drop(&mut self) {
-        // Clean up the child process - kill and wait to avoid zombie processes
-        let _ = self.child.kill();
-        let _ = self.child.wait();
+        self.reader.abort();
+        if let Some(stderr_reader) = &self.stderr_reader {
+            stderr_reader.abort();
+        }
     }
 }
 
-/// MCP Client that manages connections to multiple MCP servers.
-pub struct McpClient {
-    connections: Vec<McpConnection>,
-    /// Maps tool names to server index for routing tool calls.
-    tool_routes: HashMap<String, usize>,
+/// A request that was sent and is waiting for its response. Dropping it (for
+/// example when a research topic times out) forgets the request.
+struct PendingRequest {
+    id: u64,
+    rx: oneshot::Receiver<Value>,
+    pending: PendingMap,
 }
 
-impl McpClient {
-    /// Create a new MCP client by connecting to all enabled MCP servers.
-    pub async fn connect(servers: Vec<McpServerConfig>) -> Result<Self, String> {
-        let mut connections = Vec::new();
-        let mut tool_routes = HashMap::new();
-
-        for server in servers.into_iter().filter(|s| s.enabled) {
-            match Self::connect_to_server(&server).await {
-                Ok(conn) => {
-                    let server_idx = connections.len();
-                    // Register all tools from this server
-                    for tool in &conn.tools {
-                        // Prefix tool name with server name to avoid conflicts
-                        let prefixed_name = format!(
-                            "{}_{}",
-                            conn.server_name.replace(' ', "_").to_lowercase(),
-                            tool.name
-                        );
-                        tool_routes.insert(prefixed_name, server_idx);
-                        // Also register without prefix for direct calls
-                        tool_routes.insert(tool.name.clone(), server_idx);
-                    }
-                    info!(
-                        "Connected to MCP server '{}' with {} tools",
-                        conn.server_name,
-                        conn.tools.len()
-                    );
-                    connections.push(conn);
-                }
-                Err(e) => {
-                    warn!("Failed to connect to MCP server '{}': {}", server.name, e);
-                    // Continue with other servers
-                }
-            }
+impl PendingRequest {
+    /// Wait up to `timeout` for the response.
+    async fn response(mut self, timeout: Duration) -> Result<Value, String> {
+        match tokio::time::timeout(timeout, &mut self.rx).await {
+            Ok(Ok(response)) => Ok(response),
+            Ok(Err(_)) => Err("MCP server exited before responding".to_string()),
+            Err(_) => Err(format!(
+                "MCP request timed out after {} seconds",
+                timeout.as_secs()
+            )),
         }
-
-        Ok(Self {
-            connections,
-            tool_routes,
-        })
     }
+}
 
-    /// Connect to a single MCP server with timeout.
-    /// Uses a separate thread with real timeout since the connection involves blocking I/O.
-    async fn connect_to_server(server: &McpServerConfig) -> Result<McpConnection, String> {
-        let server_clone = server.clone();
-        let server_name = server.name.clone();
-
-        // Use oneshot channel to get result from thread
-        let (tx, rx) = tokio::sync::oneshot::channel();
-
-        // Spawn connection in a separate thread since it uses blocking I/O
-        std::thread::spawn(move || {
-            let result = Self::connect_to_server_blocking(&server_clone);
-            let _ = tx.send(result);
-        });
-
-        // Wait with timeout
-        match tokio::time::timeout(Duration::from_secs(30), rx).await {
-            Ok(Ok(result)) => result,
-            Ok(Err(_)) => Err(format!(
-                "MCP server '{}' connection channel closed",
-                server_name
-            )),
-            Err(_) => Err(format!(
-                "MCP server '{}' connection timed out after 30 seconds",
-                server_name
-            )),
+impl Drop for PendingRequest {
+    fn drop(&mut self) {
+        if let Some(map) = lock_pending(&self.pending).as_mut() {
+            map.remove(&self.id);
         }
     }
+}
 
-    /// Blocking connection logic - runs in a separate thread.
-    fn connect_to_server_blocking(server: &McpServerConfig) -> Result<McpConnection, String> {
+impl Transport {
+    /// Spawn the server process described by `server`.
+    fn spawn(server: &McpServerConfig) -> Result<Self, String> {
         // Extract command and args from config
         let command = server
             .config
@@ -185,7 +164,8 @@ impl McpClient {
         cmd.args(&args)
             .stdin(Stdio::piped())
             .stdout(Stdio::piped())
-            .stderr(Stdio::piped());
+            .stderr(Stdio::piped())
+            .kill_on_drop(true);
 
         // Add environment variables from config
         for (key, value) in &env {
@@ -221,136 +201,385 @@ impl McpClient {
             .spawn()
             .map_err(|e| format!("Failed to spawn MCP server '{}': {}", server.name, e))?;
 
-        // Initialize the connection
         let stdin = child
             .stdin
-            .as_mut()
+            .take()
             .ok_or_else(|| "Failed to get stdin".to_string())?;
         let stdout = child
             .stdout
             .take()
             .ok_or_else(|| "Failed to get stdout".to_string())?;
 
-        // Send initialize request
-        let init_request = json!({
-            "jsonrpc": "2.0",
-            "id": REQUEST_ID.fetch_add(1, Ordering::SeqCst),
-            "method": "initialize",
-            "params": {
-                "protocolVersion": "2024-11-05",
-                "capabilities": {
-                    "roots": { "listChanged": true }
-                },
-                "clientInfo": {
-                    "name": "claudius",
-                    "version": env!("CARGO_PKG_VERSION")
+        // Drain stderr so a chatty server can't fill the pipe and stall,
+        // keeping the last lines for error messages
+        let stderr_tail = Arc::new(std::sync::Mutex::new(VecDeque::new()));
+        let stderr_reader = child.stderr.take().map(|stderr| {
+            let server_name = server.name.clone();
+            let tail = stderr_tail.clone();
+            tokio::spawn(async move {
+                let mut lines = BufReader::new(stderr).lines();
+                while let Ok(Some(line)) = lines.next_line().await {
+                    debug!("MCP server '{}' stderr: {}", server_name, line);
+                    let mut tail = tail.lock().unwrap_or_else(|e| e.into_inner());
+                    if tail.len() == STDERR_TAIL_LINES {
+                        tail.pop_front();
+                    }
+                    tail.push_back(line);
                 }
-            }
-        });
-
-        Self::send_request(stdin, &init_request)?;
-
-        // Read initialize response
-        let mut reader = BufReader::new(stdout);
-        let _init_response = Self::read_response(&mut reader)?;
-
-        // Send initialized notification
-        let initialized = json!({
-            "jsonrpc": "2.0",
-            "method": "notifications/initialized"
-        });
-        let stdin = child.stdin.as_mut().unwrap();
-        Self::send_request(stdin, &initialized)?;
-
-        // Request tools list
-        let tools_request = json!({
-            "jsonrpc": "2.0",
-            "id": REQUEST_ID.fetch_add(1, Ordering::SeqCst),
-            "method": "tools/list",
-            "params": {}
+            })
         });
-        Self::send_request(stdin, &tools_request)?;
 
-        let tools_response = Self::read_response(&mut reader)?;
+        let pending: PendingMap = Arc::new(std::sync::Mutex::new(Some(HashMap::new())));
+        let reader = tokio::spawn(read_messages(
+            server.name.clone(),
+            BufReader::new(stdout),
+            pending.clone(),
+        ));
 
-        // Parse tools from response
-        let tools: Vec<McpTool> = tools_response
-            .get("result")
-            .and_then(|r| r.get("tools"))
-            .and_then(|t| serde_json::from_value(t.clone()).ok())
-            .unwrap_or_default();
+        Ok(Self {
+            _child: child,
+            stdin: Mutex::new(stdin),
+            pending,
+            reader,
+            stderr_tail,
+            stderr_reader,
+        })
+    }
 
-        debug!(
-            "MCP server '{}' provides tools: {:?}",
-            server.name,
-            tools.iter().map(|t| &t.name).collect::<Vec<_>>()
-        );
+    /// The server's last stderr lines. Waits briefly for the rest of the
+    /// output of a server that has just exited.
+    async fn stderr_output(&mut self) -> Vec<String> {
+        if let Some(stderr_reader) = self.stderr_reader.take() {
+            let _ = tokio::time::timeout(STDERR_WAIT, stderr_reader).await;
+        }
+        self.stderr_tail
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .cloned()
+            .collect()
+    }
 
-        // Reattach stdout for future reads
-        child.stdout = Some(reader.into_inner());
+    /// Whether the server has exited (or stopped answering) and needs a restart.
+    fn is_closed(&self) -> bool {
+        lock_pending(&self.pending).is_none()
+    }
 
-        Ok(McpConnection {
-            server_name: server.name.clone(),
-            server_id: server.id.clone(),
-            child,
-            tools,
-            config: server.clone(),
-        })
+    /// Mark the server as gone, failing any requests still waiting.
+    fn close(&self) {
+        *lock_pending(&self.pending) = None;
     }
 
-    /// Send a JSON-RPC request to the server.
-    fn send_request(stdin: &mut impl Write, request: &Value) -> Result<(), String> {
-        let request_str = serde_json::to_string(request)
+    /// Write one JSON-RPC message as a line on the server's stdin.
+    async fn write_message(&self, message: &Value) -> Result<(), String> {
+        let mut line = serde_json::to_string(message)
             .map_err(|e| format!("Failed to serialize request: {}", e))?;
 
-        debug!("MCP request: {}", request_str);
+        debug!("MCP request: {}", line);
+        line.push('\n');
 
-        writeln!(stdin, "{}", request_str)
+        let mut stdin = self.stdin.lock().await;
+        stdin
+            .write_all(line.as_bytes())
+            .await
             .map_err(|e| format!("Failed to write to MCP server: {}", e))?;
-
         stdin
             .flush()
-            .map_err(|e| format!("Failed to flush to MCP server: {}", e))?;
+            .await
+            .map_err(|e| format!("Failed to flush to MCP server: {}", e))
+    }
+
+    /// Send a request; its response arrives through the returned [`PendingRequest`].
+    async fn send_request(&self, method: &str, params: &Value) -> Result<PendingRequest, String> {
+        let id = REQUEST_ID.fetch_add(1, Ordering::SeqCst);
+        let (tx, rx) = oneshot::channel();
+        match lock_pending(&self.pending).as_mut() {
+            Some(map) => map.insert(id, tx),
+            None => return Err("MCP server has exited".to_string()),
+        };
+        let request = PendingRequest {
+            id,
+            rx,
+            pending: self.pending.clone(),
+        };
+
+        self.write_message(&json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "method": method,
+            "params": params
+        }))
+        .await?;
 
-        Ok(())
+        Ok(request)
     }
 
-    /// Read a JSON-RPC response from the server.
-    /// This function skips over notifications (messages without an "id" field)
-    /// and keeps reading until it gets an actual response.
-    fn read_response(reader: &mut BufReader<impl std::io::Read>) -> Result<Value, String> {
-        loop {
-            let mut line = String::new();
-            reader
-                .read_line(&mut line)
-                .map_err(|e| format!("Failed to read from MCP server: {}", e))?;
+    /// Send a request and wait up to `timeout` for its response.
+    async fn request(
+        &self,
+        method: &str,
+        params: &Value,
+        timeout: Duration,
+    ) -> Result<Value, String> {
+        self.send_request(method, params)
+            .await?
+            .response(timeout)
+            .await
+    }
+}
 
-            if line.trim().is_empty() {
-                continue;
+/// Read messages from a server's stdout until it closes, handing each response
+/// to the request waiting for it.
+async fn read_messages(
+    server_name: String,
+    mut stdout: BufReader<ChildStdout>,
+    pending: PendingMap,
+) {
+    let mut line = String::new();
+    loop {
+        line.clear();
+        match stdout.read_line(&mut line).await {
+            Ok(0) => {
+                debug!("MCP server '{}' closed its output", server_name);
+                break;
+            }
+            Ok(_) => {}
+            Err(e) => {
+                warn!("Failed to read from MCP server '{}': {}", server_name, e);
+                break;
             }
+        }
+
+        let message = line.trim();
+        if message.is_empty() {
+            continue;
+        }
+
+        debug!("MCP message: {}", message);
 
-            debug!("MCP message: {}", line.trim());
-
-            let value: Value = serde_json::from_str(&line)
-                .map_err(|e| format!("Failed to parse MCP response: {}", e))?;
-
-            // Skip notifications (they have a "method" field but no "id" field)
-            // These are progress updates, logs, etc. that we don't need to process
-            if value.get("method").is_some() && value.get("id").is_none() {
-                debug!(
-                    "Skipping MCP notification: {}",
-                    value
-                        .get("method")
-                        .and_then(|m| m.as_str())
-                        .unwrap_or("unknown")
-                );
+        let value: Value = match serde_json::from_str(message) {
+            Ok(value) => value,
+            Err(e) => {
+                warn!("Failed to parse MCP message from '{}': {}", server_name, e);
                 continue;
             }
+        };
+
+        // Skip notifications and requests from the server (they have a
+        // "method" field). These are progress updates, logs, etc. that we
+        // don't need to process
+        if let Some(method) = value.get("method") {
+            debug!(
+                "Skipping MCP notification: {}",
+                method.as_str().unwrap_or("unknown")
+            );
+            continue;
+        }
 
-            return Ok(value);
+        let Some(id) = value.get("id").and_then(|id| id.as_u64()) else {
+            debug!("Skipping MCP message without an id");
+            continue;
+        };
+        let waiting = lock_pending(&pending)
+            .as_mut()
+            .and_then(|map| map.remove(&id));
+        match waiting {
+            Some(tx) => {
+                let _ = tx.send(value);
+            }
+            // The request already timed out or was abandoned
+            None => debug!("Dropping MCP response {} with no waiting request", id),
         }
     }
 
+    *lock_pending(&pending) = None;
+}
+
+/// A running MCP server connection.
+pub struct McpConnection {
+    pub server_name: String,
+    pub server_id: String,
+    transport: Mutex<Arc<Transport>>,
+    tools: Vec<McpTool>,
+    /// Original config for restarting the server if it crashes
+    config: McpServerConfig,
+}
+
+impl McpConnection {
+    /// Get the available tools from this server.
+    #[allow(dead_code)]
+    pub fn tools(&self) -> &[McpTool] {
+        &self.tools
+    }
+
+    /// The server's transport, restarting the server first if it has exited.
+    async fn live_transport(&self) -> Result<Arc<Transport>, String> {
+        let mut transport = self.transport.lock().await;
+        if transport.is_closed() {
+            warn!(
+                "MCP server '{}' has crashed, attempting restart...",
+                self.server_name
+            );
+            let (restarted, _) = start_server(&self.config).await.map_err(|e| {
+                warn!("Failed to restart MCP server '{}': {}", self.server_name, e);
+                format!("MCP server crashed and failed to restart: {}", e)
+            })?;
+            info!("Successfully restarted MCP server '{}'", self.server_name);
+            *transport = Arc::new(restarted);
+        }
+        Ok(transport.clone())
+    }
+}
+
+/// Start a server and run the MCP handshake, returning its transport and tools.
+/// If the handshake fails, the error ends with what the server wrote to
+/// stderr, which usually says why (a missing package, a rejected API key).
+async fn start_server(server: &McpServerConfig) -> Result<(Transport, Vec<McpTool>), String> {
+    let mut transport = Transport::spawn(server)?;
+    match handshake(&transport, server).await {
+        Ok(tools) => Ok((transport, tools)),
+        Err(e) => {
+            let output = transport.stderr_output().await;
+            if output.is_empty() {
+                Err(e)
+            } else {
+                Err(format!("{}\nServer output:\n{}", e, output.join("\n")))
+            }
+        }
+    }
+}
+
+/// Initialize the connection and list the server's tools.
+async fn handshake(
+    transport: &Transport,
+    server: &McpServerConfig,
+) -> Result<Vec<McpTool>, String> {
+    // Send initialize request
+    let init_params = json!({
+        "protocolVersion": "2024-11-05",
+        "capabilities": {
+            "roots": { "listChanged": true }
+        },
+        "clientInfo": {
+            "name": "claudius",
+            "version": env!("CARGO_PKG_VERSION")
+        }
+    });
+    transport
+        .request("initialize", &init_params, CONNECT_TIMEOUT)
+        .await
+        .map_err(|e| format!("MCP server '{}' failed to initialize: {}", server.name, e))?;
+
+    // Send initialized notification
+    transport
+        .write_message(&json!({
+            "jsonrpc": "2.0",
+            "method": "notifications/initialized"
+        }))
+        .await?;
+
+    // Request tools list
+    let tools_response = transport
+        .request("tools/list", &json!({}), CONNECT_TIMEOUT)
+        .await
+        .map_err(|e| format!("MCP server '{}' failed to list tools: {}", server.name, e))?;
+
+    // Parse tools from response
+    let tools: Vec<McpTool> = tools_response
+        .get("result")
+        .and_then(|r| r.get("tools"))
+        .and_then(|t| serde_json::from_value(t.clone()).ok())
+        .unwrap_or_default();
+
+    debug!(
+        "MCP server '{}' provides tools: {:?}",
+        server.name,
+        tools.iter().map(|t| &t.name).collect::<Vec<_>>()
+    );
+
+    Ok(tools)
+}
+
+/// MCP Client that manages connections to multiple MCP servers.
+pub struct McpClient {
+    connections: Vec<McpConnection>,
+    /// Maps tool names to server index for routing tool calls.
+    tool_routes: HashMap<String, usize>,
+}
+
+impl McpClient {
+    /// Create a new MCP client by connecting to all enabled MCP servers.
+    /// Servers start concurrently.
+    pub async fn connect(servers: Vec<McpServerConfig>) -> Result<Self, String> {
+        let mut connections: Vec<McpConnection> = Vec::new();
+        let mut tool_routes = HashMap::new();
+
+        let enabled: Vec<McpServerConfig> = servers.into_iter().filter(|s| s.enabled).collect();
+        let results =
+            futures_util::future::join_all(enabled.iter().map(Self::connect_to_server)).await;
+
+        for (server, result) in enabled.iter().zip(results) {
+            match result {
+                Ok(conn) => {
+                    let server_idx = connections.len();
+                    // Register all tools from this server under their namespaced names
+                    for tool in &conn.tools {
+                        tool_routes.insert(
+                            namespaced_tool_name(&conn.server_name, &tool.name),
+                            server_idx,
+                        );
+                        // Also register the bare name for direct calls; the first
+                        // server to expose a name keeps it
+                        if let Some(other) = tool_routes
+                            .get(&tool.name)
+                            .and_then(|&idx| connections.get(idx))
+                        {
+                            warn!(
+                                "MCP tool '{}' is exposed by both '{}' and '{}'; calls use namespaced names",
+                                tool.name, other.server_name, conn.server_name
+                            );
+                        } else {
+                            tool_routes.insert(tool.name.clone(), server_idx);
+                        }
+                    }
+                    info!(
+                        "Connected to MCP server '{}' with {} tools",
+                        conn.server_name,
+                        conn.tools.len()
+                    );
+                    connections.push(conn);
+                }
+                Err(e) => {
+                    warn!("Failed to connect to MCP server '{}': {}", server.name, e);
+                    // Continue with other servers
+                }
+            }
+        }
+
+        Ok(Self {
+            connections,
+            tool_routes,
+        })
+    }
+
+    /// Connect to a single server and return its tool names, then shut it down.
+    #[allow(dead_code)]
+    pub async fn check_server(server: &McpServerConfig) -> Result<Vec<String>, String> {
+        let conn = Self::connect_to_server(server).await?;
+        Ok(conn.tools.iter().map(|t| t.name.clone()).collect())
+    }
+
+    async fn connect_to_server(server: &McpServerConfig) -> Result<McpConnection, String> {
+        let (transport, tools) = start_server(server).await?;
+        Ok(McpConnection {
+            server_name: server.name.clone(),
+            server_id: server.id.clone(),
+            transport: Mutex::new(Arc::new(transport)),
+            tools,
+            config: server.clone(),
+        })
+    }
+
     /// Get all available tools from all connected servers.
     pub fn get_all_tools(&self) -> Vec<McpToolWithServer> {
         let mut tools = Vec::new();
@@ -368,24 +597,27 @@ impl McpClient {
         tools
     }
 
+    /// The server name and bare tool name behind a namespaced (or bare) tool name.
+    pub fn resolve_tool(&self, tool_name: &str) -> Option<(String, String)> {
+        let conn = self.connections.get(*self.tool_routes.get(tool_name)?)?;
+        conn.tools
+            .iter()
+            .find(|t| {
+                t.name == tool_name || namespaced_tool_name(&conn.server_name, &t.name) == tool_name
+            })
+            .map(|t| (conn.server_name.clone(), t.name.clone()))
+    }
+
     /// Check if a tool is available.
     #[allow(dead_code)]
     pub fn has_tool(&self, tool_name: &str) -> bool {
         self.tool_routes.contains_key(tool_name)
     }
 
-    /// Call a tool on the appropriate MCP server.
-    pub fn call_tool(&mut self, tool_name: &str, arguments: Value) -> Result<Value, String> {
-        self.call_tool_with_retry(tool_name, arguments, true)
-    }
-
-    /// Internal implementation of call_tool with optional retry on broken pipe.
-    fn call_tool_with_retry(
-        &mut self,
-        tool_name: &str,
-        arguments: Value,
-        allow_retry: bool,
-    ) -> Result<Value, String> {
+    /// Call a tool on the appropriate MCP server. Calls don't block each
+    /// other, so several can run at once, and each gives up after
+    /// [`REQUEST_TIMEOUT`]. A server that has crashed is restarted once.
+    pub async fn call_tool(&self, tool_name: &str, arguments: Value) -> Result<Value, String> {
         // Find which server has this tool
         let server_idx = *self
             .tool_routes
@@ -394,20 +626,15 @@ impl McpClient {
 
         let conn = self
             .connections
-            .get_mut(server_idx)
+            .get(server_idx)
             .ok_or_else(|| "Server connection not found".to_string())?;
 
-        // Find the actual tool name (might be prefixed)
+        // Find the actual tool name (might be namespaced)
         let actual_tool_name = conn
             .tools
             .iter()
             .find(|t| {
-                t.name == tool_name
-                    || format!(
-                        "{}_{}",
-                        conn.server_name.replace(' ', "_").to_lowercase(),
-                        t.name
-                    ) == tool_name
+                t.name == tool_name || namespaced_tool_name(&conn.server_name, &t.name) == tool_name
             })
             .map(|t| t.name.clone())
             .ok_or_else(|| format!("Tool '{}' not found on server", tool_name))?;
@@ -417,90 +644,38 @@ impl McpClient {
             actual_tool_name, conn.server_name
         );
 
-        // Send tools/call request
-        let request = json!({
-            "jsonrpc": "2.0",
-            "id": REQUEST_ID.fetch_add(1, Ordering::SeqCst),
-            "method": "tools/call",
-            "params": {
-                "name": actual_tool_name,
-                "arguments": arguments
-            }
+        let params = json!({
+            "name": actual_tool_name,
+            "arguments": arguments
         });
 
-        let call_start = std::time::Instant::now();
-
-        // Try to send request - detect broken pipe
-        let send_result = {
-            let stdin = conn
-                .child
-                .stdin
-                .as_mut()
-                .ok_or_else(|| "Server stdin not available".to_string())?;
-            Self::send_request(stdin, &request)
-        };
-
-        // If we got a broken pipe error, try to restart the server and retry
-        if let Err(ref e) = send_result {
-            if e.contains("Broken pipe") || e.contains("os error 32") {
-                if allow_retry {
-                    warn!(
-                        "MCP server '{}' has crashed (broken pipe), attempting restart...",
-                        conn.server_name
-                    );
-
-                    // Get the config before dropping the connection
-                    let config = conn.config.clone();
-                    let server_name = conn.server_name.clone();
-
-                    // Try to restart the server
-                    // Use tokio's block_in_place to run async code from sync context
-                    let restart_result = tokio::task::block_in_place(|| {
-                        tokio::runtime::Handle::current().block_on(Self::connect_to_server(&config))
-                    });
-                    match restart_result {
-                        Ok(new_conn) => {
-                            info!("Successfully restarted MCP server '{}'", server_name);
-                            // Replace the old connection
-                            self.connections[server_idx] = new_conn;
-                            // Retry the tool call (without allowing another retry)
-                            return self.call_tool_with_retry(tool_name, arguments, false);
-                        }
-                        Err(restart_err) => {
-                            warn!(
-                                "Failed to restart MCP server '{}': {}",
-                                server_name, restart_err
-                            );
-                            return Err(format!(
-                                "MCP server crashed and failed to restart: {}",
-                                restart_err
-                            ));
-                        }
-                    }
-                } else {
-                    return Err(format!("MCP server crashed after restart attempt: {}", e));
-                }
+        let call_start = Instant::now();
+
+        // If the request can't be sent the server has crashed: restart it and
+        // retry once
+        let transport = conn.live_transport().await?;
+        let pending = match transport.send_request("tools/call", &params).await {
+            Ok(pending) => pending,
+            Err(e) => {
+                warn!("MCP server '{}' is unreachable: {}", conn.server_name, e);
+                transport.close();
+                conn.live_transport()
+                    .await?
+                    .send_request("tools/call", &params)
+                    .await
+                    .map_err(|e| format!("MCP server crashed after restart attempt: {}", e))?
             }
-        }
-        send_result?;
-
-        let conn = self
-            .connections
-            .get_mut(server_idx)
-            .ok_or_else(|| "Server connection not found after send".to_string())?;
-
-        let stdout = conn
-            .child
-            .stdout
-            .take()
-            .ok_or_else(|| "Server stdout not available".to_string())?;
-        let mut reader = BufReader::new(stdout);
+        };
 
-        let response = Self::read_response(&mut reader)?;
-        conn.child.stdout = Some(reader.into_inner());
+        let response = pending.response(REQUEST_TIMEOUT).await.map_err(|e| {
+            format!(
+                "MCP tool '{}' on server '{}' failed: {}",
+                actual_tool_name, conn.server_name, e
+            )
+        })?;
 
         let call_duration = call_start.elapsed();
-        if call_duration > Duration::from_secs(30) {
+        if call_duration > SLOW_CALL {
             warn!(
                 "MCP tool '{}' on server '{}' took {:.1}s (slow)",
                 actual_tool_name,
@@ -569,11 +744,16 @@ pub struct McpToolWithServer {
 }
 
 impl McpToolWithServer {
+    /// Name Claude sees for this tool, unique across servers.
+    pub fn namespaced_name(&self) -> String {
+        namespaced_tool_name(&self.server_name, &self.tool.name)
+    }
+
     /// Convert to Anthropic API tool format.
     #[allow(dead_code)]
     pub fn to_anthropic_tool(&self) -> Value {
         json!({
-            "name": self.tool.name,
+            "name": self.namespaced_name(),
             "description": self.tool.description.clone().unwrap_or_else(||
                 format!("Tool from {} MCP server", self.server_name)
             ),
@@ -582,11 +762,93 @@ impl McpToolWithServer {
     }
 }
 
+/// Separator between the server and tool parts of a namespaced tool name.
+const NAMESPACE_SEPARATOR: &str = "__";
+
+/// Longest tool name the Anthropic API accepts.
+const MAX_TOOL_NAME_LEN: usize = 64;
+
+/// Name an MCP tool is presented to Claude under: `servername__toolname`, with
+/// the server name lowercased and anything outside `[a-z0-9_-]` replaced by
+/// `_`. Two servers exposing the same tool name get distinct names. The server
+/// part is shortened if the whole name would exceed the API's 64 characters.
+pub fn namespaced_tool_name(server_name: &str, tool_name: &str) -> String {
+    let server: String = server_name
+        .trim()
+        .to_lowercase()
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    let server = server.trim_matches('_');
+    let room = MAX_TOOL_NAME_LEN
+        .saturating_sub(tool_name.len() + NAMESPACE_SEPARATOR.len())
+        .max(1);
+    let server: String = server.chars().take(room).collect();
+    format!("{}{}{}", server, NAMESPACE_SEPARATOR, tool_name)
+}
+
+/// Default maximum size (in characters) of an MCP tool result passed to Claude.
+pub const DEFAULT_RESULT_MAX_CHARS: usize = 20_000;
+
+/// An oversized tool result split into the parts that are kept and the part that is dropped.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TruncatedResult {
+    pub head: String,
+    pub omitted: String,
+    pub tail: String,
+    pub original_chars: usize,
+}
+
+/// Split a tool result that exceeds `max_chars` into head (two thirds of the budget),
+/// tail (one third) and the omitted middle. Cuts are moved back to a line break when
+/// one is close by. Returns `None` if the result already fits.
+pub fn truncate_result(text: &str, max_chars: usize) -> Option<TruncatedResult> {
+    let original_chars = text.chars().count();
+    if original_chars <= max_chars {
+        return None;
+    }
+
+    let head_chars = max_chars * 2 / 3;
+    let tail_chars = max_chars - head_chars;
+
+    let byte_at = |chars: usize| {
+        text.char_indices()
+            .nth(chars)
+            .map(|(i, _)| i)
+            .unwrap_or(text.len())
+    };
+    let mut head_end = byte_at(head_chars);
+    let mut tail_start = byte_at(original_chars - tail_chars);
+
+    // Prefer cutting at a line break within the last/first 10% of each part
+    if let Some(nl) = text[..head_end].rfind('\n') {
+        if head_end - nl < head_end / 10 + 1 {
+            head_end = nl + 1;
+        }
+    }
+    if let Some(nl) = text[tail_start..].find('\n') {
+        if nl < (text.len() - tail_start) / 10 + 1 {
+            tail_start += nl + 1;
+        }
+    }
+
+    Some(TruncatedResult {
+        head: text[..head_end].to_string(),
+        omitted: text[head_end..tail_start].to_string(),
+        tail: text[tail_start..].to_string(),
+        original_chars,
+    })
+}
+
 /// Read MCP server configurations from the config file.
 pub fn load_mcp_servers() -> Result<Vec<McpServerConfig>, String> {
-    let home = dirs::home_dir().ok_or_else(|| "Could not find home directory".to_string())?;
-
-    let config_path = home.join(".claudius").join("mcp-servers.json");
+    let config_path = crate::config::get_mcp_servers_path();
 
     if !config_path.exists() {
         debug!("No MCP servers config file found");
@@ -630,11 +892,92 @@ mod tests {
         };
 
         let anthropic = tool.to_anthropic_tool();
-        assert_eq!(anthropic["name"], "search_repositories");
+        assert_eq!(anthropic["name"], "github__search_repositories");
         assert_eq!(anthropic["description"], "Search GitHub repositories");
         assert!(anthropic["input_schema"]["properties"]["query"].is_object());
     }
 
+    #[test]
+    fn test_namespaced_tool_name() {
+        assert_eq!(
+            namespaced_tool_name("Brave Search", "web_search"),
+            "brave_search__web_search"
+        );
+        assert_eq!(namespaced_tool_name("my.notes!", "find"), "my_notes__find");
+        assert_ne!(
+            namespaced_tool_name("brave", "search"),
+            namespaced_tool_name("perplexity", "search")
+        );
+
+        let long = namespaced_tool_name(&"x".repeat(100), "list_everything");
+        assert_eq!(long.len(), 64);
+        assert!(long.ends_with("__list_everything"));
+    }
+
+    fn pending_request(pending: &PendingMap, id: u64) -> PendingRequest {
+        let (tx, rx) = oneshot::channel();
+        lock_pending(pending).as_mut().unwrap().insert(id, tx);
+        PendingRequest {
+            id,
+            rx,
+            pending: pending.clone(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_pending_request_timeout_and_close() {
+        let pending: PendingMap = Arc::new(std::sync::Mutex::new(Some(HashMap::new())));
+
+        // A request that times out is forgotten, so a late response is dropped
+        let request = pending_request(&pending, 1);
+        let err = request
+            .response(Duration::from_millis(10))
+            .await
+            .unwrap_err();
+        assert!(err.contains("timed out"));
+        assert!(lock_pending(&pending).as_ref().unwrap().is_empty());
+
+        // Responses are matched to requests by id
+        let first = pending_request(&pending, 2);
+        let second = pending_request(&pending, 3);
+        let tx = lock_pending(&pending).as_mut().unwrap().remove(&3).unwrap();
+        tx.send(json!({"id": 3})).unwrap();
+        let response = second.response(REQUEST_TIMEOUT).await.unwrap();
+        assert_eq!(response["id"], 3);
+
+        // The server exiting fails the requests still waiting
+        *lock_pending(&pending) = None;
+        let err = first.response(REQUEST_TIMEOUT).await.unwrap_err();
+        assert!(err.contains("exited"));
+    }
+
+    #[test]
+    fn test_truncate_result_keeps_head_and_tail() {
+        assert_eq!(truncate_result("short", 100), None);
+
+        let lines: Vec<String> = (0..200).map(|i| format!("line {:03}", i)).collect();
+        let text = lines.join("\n");
+        let truncated = truncate_result(&text, 300).unwrap();
+
+        assert_eq!(truncated.original_chars, text.chars().count());
+        assert!(truncated.head.starts_with("line 000"));
+        assert!(truncated.tail.ends_with("line 199"));
+        assert!(truncated.head.ends_with('\n'));
+        assert!(truncated.head.chars().count() + truncated.tail.chars().count() <= 300);
+        assert_eq!(
+            format!("{}{}{}", truncated.head, truncated.omitted, truncated.tail),
+            text
+        );
+    }
+
+    #[test]
+    fn test_truncate_result_multibyte() {
+        let text = "é".repeat(1000);
+        let truncated = truncate_result(&text, 90).unwrap();
+        assert_eq!(truncated.head.chars().count(), 60);
+        assert_eq!(truncated.tail.chars().count(), 30);
+    }
+
     #[test]
     fn test_load_mcp_servers_missing_file() {
         // This test just verifies the function doesn't panic on missing file
@@ -0,0 +1,130 @@
+//! Topic-to-MCP-server routing rules.
+//!
+//! A topic can be bound to MCP servers, or to single tools as `server/tool`
+//! (the format `disabled_tools` uses). A server bound to any topic is only
+//! offered while researching the topics bound to it, so unrelated topics
+//! don't spend iterations on tools that can't help them. Servers no topic
+//! mentions stay available everywhere.
+
+use crate::db::Topic;
+use std::collections::HashMap;
+
+/// MCP servers and tools bound to each topic.
+#[derive(Debug, Clone, Default)]
+pub struct McpRouting {
+    /// Lowercased topic name -> lowercased `server` or `server/tool` entries
+    bindings: HashMap<String, Vec<String>>,
+}
+
+/// Normalize a binding to lowercase `server` or `server/tool`.
+fn normalize(binding: &str) -> String {
+    match binding.split_once('/') {
+        Some((server, tool)) => format!("{}/{}", server.trim(), tool.trim()),
+        None => binding.trim().to_string(),
+    }
+    .to_lowercase()
+}
+
+/// Whether `binding` is `server` itself or one of its tools.
+fn binds_server(binding: &str, server: &str) -> bool {
+    binding == server
+        || binding
+            .strip_prefix(server)
+            .is_some_and(|rest| rest.starts_with('/'))
+}
+
+impl McpRouting {
+    /// Routing from topic name -> bindings.
+    pub fn new(bindings: HashMap<String, Vec<String>>) -> Self {
+        Self {
+            bindings: bindings
+                .into_iter()
+                .filter(|(_, entries)| !entries.is_empty())
+                .map(|(topic, entries)| {
+                    (
+                        topic.trim().to_lowercase(),
+                        entries.iter().map(|e| normalize(e)).collect(),
+                    )
+                })
+                .collect(),
+        }
+    }
+
+    /// Routing from every topic's `mcp_servers`, enabled or not.
+    pub fn from_topics(topics: &[Topic]) -> Self {
+        Self::new(
+            topics
+                .iter()
+                .map(|t| (t.name.clone(), t.mcp_servers.clone()))
+                .collect(),
+        )
+    }
+
+    /// Whether `server`'s tool `tool` may be offered while researching
+    /// `topic`. With no topic (e.g. listing tools outside a topic) every
+    /// tool is allowed.
+    pub fn allows(&self, topic: Option<&str>, server: &str, tool: &str) -> bool {
+        let server = server.trim().to_lowercase();
+        let bound = self
+            .bindings
+            .values()
+            .flatten()
+            .any(|binding| binds_server(binding, &server));
+        let Some(topic) = topic.filter(|_| bound) else {
+            return true;
+        };
+
+        let topic_bindings: Vec<&String> = self
+            .bindings
+            .get(&topic.trim().to_lowercase())
+            .into_iter()
+            .flatten()
+            .filter(|binding| binds_server(binding, &server))
+            .collect();
+        let qualified = format!("{}/{}", server, tool.to_lowercase());
+        topic_bindings
+            .iter()
+            .any(|binding| **binding == server || **binding == qualified)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn routing() -> McpRouting {
+        McpRouting::new(HashMap::from([
+            ("Kubernetes".to_string(), vec!["k8s".to_string()]),
+            (
+                "Rust".to_string(),
+                vec!["GitHub / search_repositories".to_string()],
+            ),
+            ("Empty".to_string(), vec![]),
+        ]))
+    }
+
+    #[test]
+    fn test_bound_server_only_for_its_topics() {
+        let routing = routing();
+        assert!(routing.allows(Some("kubernetes"), "k8s", "get_pods"));
+        assert!(!routing.allows(Some("Rust"), "k8s", "get_pods"));
+        assert!(!routing.allows(Some("AI"), "k8s", "get_pods"));
+
+        // Servers no topic mentions are offered everywhere
+        assert!(routing.allows(Some("AI"), "Brave Search", "brave_web_search"));
+        assert!(routing.allows(Some("Kubernetes"), "Brave Search", "brave_web_search"));
+
+        // Outside a topic everything is allowed
+        assert!(routing.allows(None, "k8s", "get_pods"));
+    }
+
+    #[test]
+    fn test_tool_bindings() {
+        let routing = routing();
+        assert!(routing.allows(Some("Rust"), "github", "search_repositories"));
+        assert!(!routing.allows(Some("Rust"), "github", "create_issue"));
+        assert!(!routing.allows(Some("Kubernetes"), "github", "search_repositories"));
+        // A server whose name only starts with a bound name is not bound
+        assert!(routing.allows(Some("AI"), "k8s-extra", "get_pods"));
+    }
+}
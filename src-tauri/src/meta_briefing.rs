@@ -0,0 +1,498 @@
+//! Weekly and monthly meta-briefings.
+//!
+//! A meta-briefing reads the cards of the past week's (or month's) briefings
+//! and asks Claude for the bigger picture: the trends running through them,
+//! what resolved and what is still developing. Claude cites the daily cards
+//! it draws on with the archive's citation tags (`[briefing 42, card 3]`),
+//! and each cited card is added to the meta card's sources as a
+//! `claudius://` deep link back to it.
+//!
+//! Meta-briefings are saved as briefings tagged "weekly" or "monthly". They
+//! restate daily cards, so later meta-briefings and deduplication skip them.
+//! The daemon generates them after a scheduled run when the `meta_briefings`
+//! setting asks for them and the last one is a period old.
+
+use chrono::{DateTime, Duration as ChronoDuration, Local, Utc};
+use serde::Serialize;
+use std::time::{Duration, Instant};
+use tracing::info;
+
+use crate::agent_loop::{ApiEndpoint, Message, MessagesRequest, MessagesResponse};
+use crate::archive::{self, ArchiveCard};
+use crate::db;
+use crate::deep_link::DeepLink;
+use crate::research::{self, BriefingCard};
+
+/// Values of the `meta_briefings` setting.
+pub const META_BRIEFING_SCHEDULES: [&str; 4] = ["off", "weekly", "monthly", "both"];
+
+/// Sections of a meta-briefing, used as its cards' topics.
+pub const SECTIONS: [&str; 3] = ["Trend", "Resolved", "Still developing"];
+
+/// Most daily cards given to Claude; beyond this the least relevant are left out.
+const MAX_CONTEXT_CARDS: usize = 300;
+
+/// Characters of each daily card's detailed content included in the context.
+const CONTEXT_CHARS_PER_CARD: usize = 400;
+
+/// A scheduled run starting this much early still counts as a full period.
+const DUE_SLACK_HOURS: i64 = 12;
+
+const MAX_TOKENS: u32 = 8192;
+
+/// The span a meta-briefing covers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MetaPeriod {
+    Weekly,
+    Monthly,
+}
+
+impl MetaPeriod {
+    pub fn parse(value: &str) -> Result<Self, String> {
+        match value.trim().to_lowercase().as_str() {
+            "weekly" | "week" => Ok(Self::Weekly),
+            "monthly" | "month" => Ok(Self::Monthly),
+            other => Err(format!(
+                "Unknown period '{}' (expected weekly or monthly)",
+                other
+            )),
+        }
+    }
+
+    pub fn days(self) -> i64 {
+        match self {
+            Self::Weekly => 7,
+            Self::Monthly => 30,
+        }
+    }
+
+    /// Tag stored on the saved briefing.
+    pub fn tag(self) -> &'static str {
+        match self {
+            Self::Weekly => "weekly",
+            Self::Monthly => "monthly",
+        }
+    }
+
+    fn noun(self) -> &'static str {
+        match self {
+            Self::Weekly => "week",
+            Self::Monthly => "month",
+        }
+    }
+
+    /// Periods enabled by a `meta_briefings` setting value.
+    pub fn scheduled(setting: &str) -> Vec<Self> {
+        match setting.trim() {
+            "weekly" => vec![Self::Weekly],
+            "monthly" => vec![Self::Monthly],
+            "both" => vec![Self::Weekly, Self::Monthly],
+            _ => Vec::new(),
+        }
+    }
+
+    /// Briefing title for the period ending at `end`.
+    pub fn title(self, end: DateTime<Local>) -> String {
+        let start = end - ChronoDuration::days(self.days() - 1);
+        let label = match self {
+            Self::Weekly => "Weekly",
+            Self::Monthly => "Monthly",
+        };
+        format!(
+            "{} Briefing: {} – {}",
+            label,
+            start.format("%b %-d"),
+            end.format("%b %-d, %Y")
+        )
+    }
+}
+
+/// Whether a scheduled meta-briefing is due, given when the last one for the
+/// period was saved (an RFC 3339 briefing date).
+pub fn meta_briefing_due(period: MetaPeriod, last: Option<&str>, now: DateTime<Utc>) -> bool {
+    let Some(last) = last else {
+        return true;
+    };
+    match DateTime::parse_from_rfc3339(last) {
+        Ok(last) => {
+            now - last.with_timezone(&Utc)
+                >= ChronoDuration::days(period.days()) - ChronoDuration::hours(DUE_SLACK_HOURS)
+        }
+        Err(_) => true,
+    }
+}
+
+/// A saved meta-briefing.
+#[derive(Debug, Clone, Serialize)]
+pub struct MetaBriefing {
+    pub briefing_id: i64,
+    pub title: String,
+    pub period: MetaPeriod,
+    /// Daily briefings the meta-briefing was built from
+    pub source_briefings: usize,
+    pub cards: Vec<BriefingCard>,
+    pub tokens_used: u32,
+}
+
+/// Cards of the daily briefings saved since `since` (RFC 3339), oldest first.
+/// Returns the number of briefings along with the cards.
+fn load_period_cards(
+    conn: &rusqlite::Connection,
+    since: &str,
+) -> Result<(usize, Vec<ArchiveCard>), String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, date, cards FROM briefings
+             WHERE date >= ?1 AND (tag IS NULL OR tag NOT IN ('weekly', 'monthly'))
+             ORDER BY date ASC",
+        )
+        .map_err(|e| format!("Failed to prepare statement: {}", e))?;
+    let rows = stmt
+        .query_map([since], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+            ))
+        })
+        .map_err(|e| format!("Query failed: {}", e))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("Failed to collect results: {}", e))?;
+
+    let briefings = rows.len();
+    let mut cards = Vec::new();
+    for (briefing_id, date, cards_json) in rows {
+        let Ok(parsed) = serde_json::from_str::<Vec<BriefingCard>>(&cards_json) else {
+            continue;
+        };
+        cards.extend(
+            parsed
+                .into_iter()
+                .enumerate()
+                .map(|(index, card)| ArchiveCard {
+                    briefing_id,
+                    briefing_date: date.clone(),
+                    card_index: index as i32,
+                    card,
+                }),
+        );
+    }
+    Ok((briefings, cards))
+}
+
+/// Keep at most `max` cards, dropping the least relevant (newest first among
+/// equals), and return them in their original order.
+fn select_cards(mut cards: Vec<ArchiveCard>, max: usize) -> Vec<ArchiveCard> {
+    if cards.len() <= max {
+        return cards;
+    }
+    let mut order: Vec<usize> = (0..cards.len()).collect();
+    order.sort_by_key(|&i| {
+        (
+            std::cmp::Reverse(research::relevance_rank(&cards[i].card.relevance)),
+            std::cmp::Reverse(i),
+        )
+    });
+    let mut keep = vec![false; cards.len()];
+    for &i in order.iter().take(max) {
+        keep[i] = true;
+    }
+    let mut keep = keep.into_iter();
+    cards.retain(|_| keep.next().unwrap_or(false));
+    cards
+}
+
+/// Context block listing the daily cards with their citation tags.
+fn build_context(cards: &[ArchiveCard]) -> String {
+    cards
+        .iter()
+        .map(|entry| {
+            let card = &entry.card;
+            let details: String = card
+                .detailed_content
+                .chars()
+                .take(CONTEXT_CHARS_PER_CARD)
+                .collect();
+            let mut text = format!(
+                "{} {} · Topic: {} · Relevance: {}\nTitle: {}\nSummary: {}",
+                entry.citation_tag(),
+                entry
+                    .briefing_date
+                    .get(..10)
+                    .unwrap_or(entry.briefing_date.as_str()),
+                card.topic,
+                card.relevance,
+                card.title,
+                card.summary
+            );
+            if !details.is_empty() {
+                text.push_str(&format!("\nDetails: {}", details));
+            }
+            text
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+fn build_prompt(period: MetaPeriod, briefings: usize, context: &str) -> String {
+    format!(
+        r#"Below are the cards of the user's {briefings} research briefing(s) from the past {noun}, oldest first. Each starts with a citation tag.
+
+Write a {label} meta-briefing that steps back from the daily news: the trends that ran through the {noun}, stories that resolved, and stories that are still developing. Don't repeat the daily cards one by one; connect them. Cite every daily card you draw on with its tag exactly as written (for example [briefing 42, card 3]).
+
+Return ONLY valid JSON in this form:
+{{"cards": [{{"title": "...", "summary": "1-2 sentences", "detailed_content": "2-3 paragraphs with citation tags", "sources": [], "suggested_next": "what to watch next, or null", "relevance": "high|medium|low", "topic": "{sections}"}}]}}
+
+Write 3 to 8 cards. Each card's topic must be one of: {sections}.
+
+{context}"#,
+        briefings = briefings,
+        noun = period.noun(),
+        label = period.tag(),
+        sections = SECTIONS.join(" | "),
+        context = context
+    )
+}
+
+/// Point each card's sources at the daily cards it cites, and keep its topic
+/// to one of the [`SECTIONS`].
+fn link_cited_cards(cards: &mut [BriefingCard], daily: &[ArchiveCard]) {
+    let daily: Vec<&ArchiveCard> = daily.iter().collect();
+    for card in cards.iter_mut() {
+        let text = format!("{}\n{}", card.summary, card.detailed_content);
+        let mut sources: Vec<String> = archive::cited_cards(&text, &daily)
+            .into_iter()
+            .map(|c| DeepLink::card(c.briefing_id, c.card_index as usize).url())
+            .collect();
+        // Keep any web sources Claude added after the links back
+        for source in card.sources.drain(..) {
+            if !sources.contains(&source) {
+                sources.push(source);
+            }
+        }
+        card.sources = sources;
+
+        if !SECTIONS
+            .iter()
+            .any(|s| s.eq_ignore_ascii_case(card.topic.trim()))
+        {
+            card.topic = SECTIONS[0].to_string();
+        }
+    }
+}
+
+/// Build and save the meta-briefing for the period ending now.
+pub async fn generate_meta_briefing(
+    api_key: &str,
+    model: &str,
+    period: MetaPeriod,
+) -> Result<MetaBriefing, String> {
+    let started = Instant::now();
+    let since = (Utc::now() - ChronoDuration::days(period.days())).to_rfc3339();
+    let (briefings, daily) = {
+        let conn = db::get_connection().map_err(|e| format!("Database error: {}", e))?;
+        load_period_cards(&conn, &since)?
+    };
+    if daily.is_empty() {
+        return Err(format!(
+            "No briefings from the past {} to summarize",
+            period.noun()
+        ));
+    }
+    let daily = select_cards(daily, MAX_CONTEXT_CARDS);
+    info!(
+        "Generating {} meta-briefing from {} cards in {} briefing(s)",
+        period.tag(),
+        daily.len(),
+        briefings
+    );
+
+    let endpoint = ApiEndpoint::current();
+    let request = MessagesRequest {
+        model: model.to_string(),
+        max_tokens: MAX_TOKENS,
+        messages: vec![Message::user(build_prompt(
+            period,
+            briefings,
+            &build_context(&daily),
+        ))],
+        tools: None,
+        system: None,
+        thinking: None,
+        metadata: endpoint.metadata(),
+    };
+
+    let client = crate::http_client::client_builder()
+        .timeout(Duration::from_secs(300))
+        .connect_timeout(Duration::from_secs(10))
+        .build()
+        .map_err(|e| format!("Failed to build HTTP client: {}", e))?;
+    let response = endpoint
+        .authorize(client.post(endpoint.messages_url()), api_key)
+        .header("content-type", "application/json")
+        .json(&request)
+        .send()
+        .await
+        .map_err(|e| format!("HTTP request failed: {}", e))?;
+
+    if !response.status().is_success() {
+        let status = response.status().as_u16();
+        let body = response.text().await.unwrap_or_default();
+        return Err(format!("API error {}: {}", status, body));
+    }
+
+    let response: MessagesResponse = response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse response: {}", e))?;
+    let tokens_used = response.total_tokens();
+    let mut cards = research::parse_briefing_response(&response.text())?;
+    if cards.is_empty() {
+        return Err("Claude returned no cards for the meta-briefing".to_string());
+    }
+    link_cited_cards(&mut cards, &daily);
+
+    let title = period.title(Local::now());
+    let cards_json =
+        serde_json::to_string(&cards).map_err(|e| format!("Failed to serialize cards: {}", e))?;
+    let date = Utc::now().to_rfc3339();
+    let run_id = uuid::Uuid::new_v4().to_string();
+    // It writes no research logs of its own, so claim none of a run going on
+    // at the same time
+    let log_since = crate::research_log::log_timestamp();
+    let conn = db::get_connection().map_err(|e| format!("Database error: {}", e))?;
+    let saved = db::persist_briefing(
+        &conn,
+        &db::BriefingRecord {
+            briefing: db::NewBriefing {
+                run_id: Some(&run_id),
+                date: &date,
+                title: &title,
+                cards: &cards_json,
+                research_time_ms: started.elapsed().as_millis() as i64,
+                model_used: model,
+                total_tokens: tokens_used as i64,
+                tag: Some(period.tag()),
+                topics: None,
+                failed_topics: None,
+            },
+            cards: &cards,
+            discarded: &[],
+            profile: &crate::config::active_profile(),
+            batch_tokens: 0,
+            log_since: &log_since,
+            merge_same_day: None,
+        },
+    )
+    .map_err(|e| format!("Failed to save meta-briefing: {}", e))?;
+    let briefing_id = saved.id;
+
+    Ok(MetaBriefing {
+        briefing_id,
+        title,
+        period,
+        source_briefings: briefings,
+        cards,
+        tokens_used,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn daily(briefing_id: i64, card_index: i32, relevance: &str) -> ArchiveCard {
+        ArchiveCard {
+            briefing_id,
+            briefing_date: "2025-01-06T08:00:00+00:00".to_string(),
+            card_index,
+            card: serde_json::from_value(serde_json::json!({
+                "title": format!("Card {}-{}", briefing_id, card_index),
+                "summary": "",
+                "detailed_content": "",
+                "sources": [],
+                "suggested_next": null,
+                "relevance": relevance,
+                "topic": "Tech",
+            }))
+            .unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_meta_briefing_due() {
+        let now = Utc::now();
+        let ago = |days: i64| (now - ChronoDuration::days(days)).to_rfc3339();
+        assert!(meta_briefing_due(MetaPeriod::Weekly, None, now));
+        assert!(!meta_briefing_due(
+            MetaPeriod::Weekly,
+            Some(ago(3).as_str()),
+            now
+        ));
+        assert!(meta_briefing_due(
+            MetaPeriod::Weekly,
+            Some(ago(7).as_str()),
+            now
+        ));
+        // A run a few hours early still counts
+        let early = (now - ChronoDuration::days(7) + ChronoDuration::hours(2)).to_rfc3339();
+        assert!(meta_briefing_due(
+            MetaPeriod::Weekly,
+            Some(early.as_str()),
+            now
+        ));
+        assert!(!meta_briefing_due(
+            MetaPeriod::Monthly,
+            Some(ago(7).as_str()),
+            now
+        ));
+
+        assert_eq!(
+            MetaPeriod::scheduled("both"),
+            vec![MetaPeriod::Weekly, MetaPeriod::Monthly]
+        );
+        assert!(MetaPeriod::scheduled("off").is_empty());
+    }
+
+    #[test]
+    fn test_select_cards_keeps_most_relevant_in_order() {
+        let cards = vec![
+            daily(1, 0, "low"),
+            daily(1, 1, "high"),
+            daily(2, 0, "medium"),
+            daily(2, 1, "high"),
+        ];
+        let kept: Vec<(i64, i32)> = select_cards(cards, 3)
+            .iter()
+            .map(|c| (c.briefing_id, c.card_index))
+            .collect();
+        assert_eq!(kept, vec![(1, 1), (2, 0), (2, 1)]);
+    }
+
+    #[test]
+    fn test_link_cited_cards() {
+        let daily_cards = vec![daily(4, 0, "high"), daily(5, 2, "medium")];
+        let mut cards: Vec<BriefingCard> = serde_json::from_value(serde_json::json!([{
+            "title": "Agents everywhere",
+            "summary": "Agent launches dominated [briefing 5, card 3].",
+            "detailed_content": "Starting with [briefing 4, card 1], then [briefing 9, card 1].",
+            "sources": ["https://example.com"],
+            "suggested_next": null,
+            "relevance": "high",
+            "topic": "Megatrend",
+        }]))
+        .unwrap();
+
+        link_cited_cards(&mut cards, &daily_cards);
+        assert_eq!(
+            cards[0].sources,
+            vec![
+                "claudius://briefing/5/card/3",
+                "claudius://briefing/4/card/1",
+                "https://example.com",
+            ]
+        );
+        assert_eq!(cards[0].topic, "Trend");
+    }
+}
@@ -0,0 +1,238 @@
+//! Anthropic model listing and validation for Claudius.
+//!
+//! Model IDs are easy to mistype, and a bad ID only surfaces as an API error
+//! in the middle of a research run. This module fetches the available models
+//! from the Anthropic models endpoint, caches the list on disk for a day, and
+//! checks configured model IDs against it (plus a table of deprecated IDs).
+
+use crate::agent_loop::ApiEndpoint;
+use crate::json_cache::{self, unix_now};
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+/// Models API path, relative to the API base URL.
+const MODELS_PATH: &str = "/v1/models?limit=1000";
+
+/// Cache file of the model list.
+const CACHE_FILE: &str = "models.json";
+
+/// Cached model lists younger than this are used without contacting the API.
+const CACHE_TTL_SECS: u64 = 24 * 60 * 60;
+
+/// Model IDs Anthropic has deprecated or retired, with a suggested replacement.
+pub const DEPRECATED_MODELS: &[(&str, &str)] = &[
+    ("claude-instant-1.2", "claude-haiku-4-5-20251001"),
+    ("claude-2.0", "claude-sonnet-4-5-20250929"),
+    ("claude-2.1", "claude-sonnet-4-5-20250929"),
+    ("claude-3-sonnet-20240229", "claude-sonnet-4-5-20250929"),
+    ("claude-3-opus-20240229", "claude-opus-4-5-20251101"),
+    ("claude-3-haiku-20240307", "claude-haiku-4-5-20251001"),
+    ("claude-3-5-sonnet-20240620", "claude-sonnet-4-5-20250929"),
+    ("claude-3-5-sonnet-20241022", "claude-sonnet-4-5-20250929"),
+    ("claude-3-5-haiku-20241022", "claude-haiku-4-5-20251001"),
+    ("claude-3-7-sonnet-20250219", "claude-sonnet-4-5-20250929"),
+];
+
+/// A model available to the configured API key.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ModelInfo {
+    pub id: String,
+    pub display_name: String,
+    pub created_at: Option<String>,
+    pub deprecated: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ModelsCache {
+    fetched_at: u64, // Unix seconds
+    models: Vec<ModelInfo>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ModelsResponse {
+    data: Vec<ApiModel>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiModel {
+    id: String,
+    display_name: Option<String>,
+    created_at: Option<String>,
+}
+
+/// Return the deprecated-model replacement for an ID, if it is deprecated.
+pub fn deprecated_replacement(model: &str) -> Option<&'static str> {
+    DEPRECATED_MODELS
+        .iter()
+        .find(|(id, _)| *id == model)
+        .map(|(_, replacement)| *replacement)
+}
+
+/// Return the cached model list without contacting the API, even if stale.
+pub fn cached_models() -> Option<Vec<ModelInfo>> {
+    json_cache::read_cache::<ModelsCache>(CACHE_FILE)
+        .map(|c| c.models)
+        .filter(|m| !m.is_empty())
+}
+
+pub(crate) async fn fetch_models(api_key: &str) -> Result<Vec<ModelInfo>, String> {
+    let endpoint = ApiEndpoint::current();
+    let response = endpoint
+        .authorize(
+            crate::http_client::client().get(endpoint.url(MODELS_PATH)),
+            api_key,
+        )
+        .send()
+        .await
+        .map_err(|e| format!("HTTP request failed: {}", e))?;
+
+    if !response.status().is_success() {
+        let status = response.status().as_u16();
+        let body = response.text().await.unwrap_or_default();
+        return Err(format!("API error {}: {}", status, body));
+    }
+
+    let parsed: ModelsResponse = response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse models response: {}", e))?;
+
+    Ok(parsed
+        .data
+        .into_iter()
+        .map(|m| ModelInfo {
+            deprecated: deprecated_replacement(&m.id).is_some(),
+            display_name: m.display_name.unwrap_or_else(|| m.id.clone()),
+            id: m.id,
+            created_at: m.created_at,
+        })
+        .collect())
+}
+
+/// List the models available to `api_key`.
+///
+/// A cached list younger than a day is returned directly unless `refresh`
+/// is set. If the API cannot be reached, a stale cached list is returned
+/// instead of failing.
+pub async fn list_models(api_key: &str, refresh: bool) -> Result<Vec<ModelInfo>, String> {
+    let cached: Option<ModelsCache> = json_cache::read_cache(CACHE_FILE);
+    if !refresh {
+        if let Some(cache) = &cached {
+            if unix_now().saturating_sub(cache.fetched_at) < CACHE_TTL_SECS
+                && !cache.models.is_empty()
+            {
+                return Ok(cache.models.clone());
+            }
+        }
+    }
+
+    match fetch_models(api_key).await {
+        Ok(models) => {
+            info!("Fetched {} models from Anthropic API", models.len());
+            json_cache::write_cache(
+                CACHE_FILE,
+                "models",
+                &ModelsCache {
+                    fetched_at: unix_now(),
+                    models: models.clone(),
+                },
+            );
+            Ok(models)
+        }
+        Err(e) => match cached {
+            Some(cache) if !cache.models.is_empty() => {
+                warn!("Failed to refresh model list, using cache: {}", e);
+                Ok(cache.models)
+            }
+            _ => Err(e),
+        },
+    }
+}
+
+/// Outcome of checking a model ID against the known model list.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ModelCheck {
+    /// The model is known and current, or no model list is available.
+    Ok,
+    /// The model is deprecated; carries the suggested replacement.
+    Deprecated(String),
+    /// The model is not in the list; carries the closest known ID, if any.
+    Unknown(Option<String>),
+}
+
+impl ModelCheck {
+    /// Human-readable message for this result, or `None` when the model is fine.
+    pub fn message(&self, model: &str) -> Option<String> {
+        match self {
+            ModelCheck::Ok => None,
+            ModelCheck::Deprecated(replacement) => Some(format!(
+                "Model '{}' is deprecated; consider '{}' instead",
+                model, replacement
+            )),
+            ModelCheck::Unknown(Some(suggestion)) => Some(format!(
+                "Unknown model '{}'. Did you mean '{}'?",
+                model, suggestion
+            )),
+            ModelCheck::Unknown(None) => Some(format!("Unknown model '{}'", model)),
+        }
+    }
+}
+
+/// Check a model ID against `known` models.
+///
+/// Deprecated IDs are reported even when they still appear in the list. An
+/// empty `known` list (nothing fetched yet) accepts any non-deprecated ID.
+pub fn check_model(model: &str, known: &[ModelInfo]) -> ModelCheck {
+    if let Some(replacement) = deprecated_replacement(model) {
+        return ModelCheck::Deprecated(replacement.to_string());
+    }
+    if known.is_empty() || known.iter().any(|m| m.id == model) {
+        return ModelCheck::Ok;
+    }
+
+    let suggestion = known
+        .iter()
+        .map(|m| (strsim::normalized_levenshtein(model, &m.id), &m.id))
+        .filter(|(score, _)| *score >= 0.6)
+        .max_by(|a, b| a.0.total_cmp(&b.0))
+        .map(|(_, id)| id.clone());
+    ModelCheck::Unknown(suggestion)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn model(id: &str) -> ModelInfo {
+        ModelInfo {
+            id: id.to_string(),
+            display_name: id.to_string(),
+            created_at: None,
+            deprecated: false,
+        }
+    }
+
+    #[test]
+    fn test_check_model() {
+        let known = vec![
+            model("claude-haiku-4-5-20251001"),
+            model("claude-sonnet-4-5-20250929"),
+        ];
+
+        assert_eq!(
+            check_model("claude-sonnet-4-5-20250929", &known),
+            ModelCheck::Ok
+        );
+        assert_eq!(
+            check_model("claude-sonet-4-5-20250929", &known),
+            ModelCheck::Unknown(Some("claude-sonnet-4-5-20250929".to_string()))
+        );
+        assert_eq!(check_model("gpt-4", &known), ModelCheck::Unknown(None));
+        assert_eq!(
+            check_model("claude-3-opus-20240229", &known),
+            ModelCheck::Deprecated("claude-opus-4-5-20251101".to_string())
+        );
+        // Without a fetched list, only deprecations are reported
+        assert_eq!(check_model("anything", &[]), ModelCheck::Ok);
+    }
+}
@@ -0,0 +1,308 @@
+//! Per-event notification preferences.
+//!
+//! Each kind of notification (research completed, failed, cancelled, an
+//! error the user has to fix, a watchlist hit, a weekly digest) can be shown
+//! with a sound, shown silently, or turned off, and can name its own sound.
+//! Events missing from `notification_events` use their defaults. The old
+//! `enable_notifications` switch still silences everything, and
+//! `notification_sound` still decides whether events without their own sound
+//! play the default one.
+
+use crate::config::ResearchSettings;
+use serde::{Deserialize, Serialize};
+
+/// Sound name meaning the platform's usual notification sound.
+pub const DEFAULT_SOUND: &str = "default";
+
+/// Something Claudius can notify about.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationEvent {
+    /// A research run saved a briefing
+    ResearchCompleted,
+    /// A research run failed (API errors, timeouts)
+    ResearchFailed,
+    /// A research run was cancelled
+    ResearchCancelled,
+    /// Research can't run until the user fixes something (no API key, no
+    /// topics, an exhausted Firecrawl budget)
+    ActionableError,
+    /// A watched term turned up in a briefing
+    WatchlistHit,
+    /// A weekly meta-briefing was generated
+    WeeklyDigestReady,
+}
+
+impl NotificationEvent {
+    pub const ALL: [NotificationEvent; 6] = [
+        NotificationEvent::ResearchCompleted,
+        NotificationEvent::ResearchFailed,
+        NotificationEvent::ResearchCancelled,
+        NotificationEvent::ActionableError,
+        NotificationEvent::WatchlistHit,
+        NotificationEvent::WeeklyDigestReady,
+    ];
+
+    /// Key in `notification_events` and `claudius config set notify.<key>`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            NotificationEvent::ResearchCompleted => "research_completed",
+            NotificationEvent::ResearchFailed => "research_failed",
+            NotificationEvent::ResearchCancelled => "research_cancelled",
+            NotificationEvent::ActionableError => "actionable_error",
+            NotificationEvent::WatchlistHit => "watchlist_hit",
+            NotificationEvent::WeeklyDigestReady => "weekly_digest_ready",
+        }
+    }
+
+    /// Parse a `notification_events` key.
+    #[allow(dead_code)]
+    pub fn parse(value: &str) -> Result<Self, String> {
+        Self::ALL
+            .into_iter()
+            .find(|e| e.as_str() == value.trim())
+            .ok_or_else(|| {
+                format!(
+                    "Unknown notification event '{}'. Use one of: {}",
+                    value,
+                    Self::ALL.map(|e| e.as_str()).join(", ")
+                )
+            })
+    }
+
+    /// The event for a research error: errors the user can fix in settings
+    /// are actionable, anything else is a failed run.
+    pub fn for_research_error(message: &str) -> Self {
+        let lower = message.to_lowercase();
+        let actionable = [
+            "no api key",
+            "invalid_api_key",
+            "no topics",
+            "snoozed",
+            "budget",
+        ];
+        if actionable.iter().any(|s| lower.contains(s)) {
+            NotificationEvent::ActionableError
+        } else if lower.contains("cancelled") {
+            NotificationEvent::ResearchCancelled
+        } else {
+            NotificationEvent::ResearchFailed
+        }
+    }
+
+    /// Preference used when the event isn't configured. Cancellations are
+    /// user-initiated, so they stay quiet.
+    pub fn default_preference(&self) -> EventPreference {
+        let mode = match self {
+            NotificationEvent::ResearchCancelled => NotifyMode::Off,
+            _ => NotifyMode::Notify,
+        };
+        EventPreference { mode, sound: None }
+    }
+}
+
+/// How an event is shown.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NotifyMode {
+    /// Show it with a sound
+    #[default]
+    Notify,
+    /// Show it without a sound
+    Silent,
+    /// Don't show it
+    Off,
+}
+
+impl NotifyMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            NotifyMode::Notify => "notify",
+            NotifyMode::Silent => "silent",
+            NotifyMode::Off => "off",
+        }
+    }
+}
+
+/// One event's notification preference.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EventPreference {
+    #[serde(default)]
+    pub mode: NotifyMode,
+    /// Sound to play in `notify` mode: a system sound name (e.g. "Glass" on
+    /// macOS) or "default"; None follows `notification_sound`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sound: Option<String>,
+}
+
+impl EventPreference {
+    /// Parse a `claudius config set notify.<event>` value: "notify",
+    /// "silent", "off", or "notify:<sound>".
+    #[allow(dead_code)]
+    pub fn parse(value: &str) -> Result<Self, String> {
+        let (mode, sound) = match value.trim().split_once(':') {
+            Some((mode, sound)) => (mode.trim(), Some(sound.trim())),
+            None => (value.trim(), None),
+        };
+        let mode = match mode {
+            "notify" => NotifyMode::Notify,
+            "silent" => NotifyMode::Silent,
+            "off" => NotifyMode::Off,
+            _ => {
+                return Err(format!(
+                    "Invalid notification mode '{}'. Use notify, silent, off or notify:<sound>",
+                    mode
+                ))
+            }
+        };
+        let sound = match sound {
+            Some("") => return Err("Sound name cannot be empty".to_string()),
+            Some(_) if mode != NotifyMode::Notify => {
+                return Err("Only notify mode plays a sound".to_string())
+            }
+            sound => sound.map(str::to_string),
+        };
+        Ok(Self { mode, sound })
+    }
+}
+
+impl std::fmt::Display for EventPreference {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.sound {
+            Some(sound) => write!(f, "{}:{}", self.mode.as_str(), sound),
+            None => write!(f, "{}", self.mode.as_str()),
+        }
+    }
+}
+
+/// How to show an event that should be shown.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Delivery {
+    /// Sound to play ("default" or a system sound name), None for silent
+    pub sound: Option<String>,
+}
+
+/// The preference in effect for `event`.
+pub fn preference(settings: &ResearchSettings, event: NotificationEvent) -> EventPreference {
+    settings
+        .notification_events
+        .get(&event)
+        .cloned()
+        .unwrap_or_else(|| event.default_preference())
+}
+
+/// How to show `event`, or None if it shouldn't be shown.
+pub fn delivery(settings: &ResearchSettings, event: NotificationEvent) -> Option<Delivery> {
+    if !settings.enable_notifications {
+        return None;
+    }
+    let preference = preference(settings, event);
+    let sound = match preference.mode {
+        NotifyMode::Off => return None,
+        NotifyMode::Silent => None,
+        NotifyMode::Notify => preference.sound.or_else(|| {
+            settings
+                .notification_sound
+                .then(|| DEFAULT_SOUND.to_string())
+        }),
+    };
+    Some(Delivery { sound })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_delivery() {
+        let mut settings = ResearchSettings::default();
+        assert_eq!(
+            delivery(&settings, NotificationEvent::ResearchCompleted),
+            Some(Delivery {
+                sound: Some(DEFAULT_SOUND.to_string())
+            })
+        );
+        assert_eq!(
+            delivery(&settings, NotificationEvent::ResearchCancelled),
+            None
+        );
+
+        settings.notification_sound = false;
+        settings.notification_events.insert(
+            NotificationEvent::ResearchFailed,
+            EventPreference::parse("notify:Basso").unwrap(),
+        );
+        settings.notification_events.insert(
+            NotificationEvent::ResearchCompleted,
+            EventPreference::parse("off").unwrap(),
+        );
+        assert_eq!(
+            delivery(&settings, NotificationEvent::ResearchFailed),
+            Some(Delivery {
+                sound: Some("Basso".to_string())
+            })
+        );
+        assert_eq!(
+            delivery(&settings, NotificationEvent::ActionableError),
+            Some(Delivery { sound: None })
+        );
+        assert_eq!(
+            delivery(&settings, NotificationEvent::ResearchCompleted),
+            None
+        );
+
+        // The master switch still silences everything
+        settings.enable_notifications = false;
+        assert_eq!(delivery(&settings, NotificationEvent::ResearchFailed), None);
+    }
+
+    #[test]
+    fn test_parse_and_serde() {
+        assert_eq!(
+            EventPreference::parse("silent").unwrap().mode,
+            NotifyMode::Silent
+        );
+        assert_eq!(
+            EventPreference::parse("notify:Ping").unwrap().to_string(),
+            "notify:Ping"
+        );
+        assert!(EventPreference::parse("loud").is_err());
+        assert!(EventPreference::parse("off:Ping").is_err());
+        assert_eq!(
+            NotificationEvent::parse("weekly_digest_ready").unwrap(),
+            NotificationEvent::WeeklyDigestReady
+        );
+        assert!(NotificationEvent::parse("digest").is_err());
+
+        let json = serde_json::json!({
+            "research_failed": { "mode": "notify", "sound": "Basso" },
+            "research_completed": { "mode": "silent" },
+        });
+        let events: std::collections::HashMap<NotificationEvent, EventPreference> =
+            serde_json::from_value(json).unwrap();
+        assert_eq!(
+            events[&NotificationEvent::ResearchCompleted].mode,
+            NotifyMode::Silent
+        );
+        assert_eq!(
+            serde_json::to_value(&events).unwrap()["research_failed"]["sound"],
+            "Basso"
+        );
+    }
+
+    #[test]
+    fn test_event_for_research_error() {
+        assert_eq!(
+            NotificationEvent::for_research_error("No API key configured."),
+            NotificationEvent::ActionableError
+        );
+        assert_eq!(
+            NotificationEvent::for_research_error("Synthesis failed: overloaded (overloaded)"),
+            NotificationEvent::ResearchFailed
+        );
+        assert_eq!(
+            NotificationEvent::for_research_error("Research cancelled by user"),
+            NotificationEvent::ResearchCancelled
+        );
+    }
+}
@@ -1,5 +1,7 @@
+use crate::config::ResearchSettings;
+use crate::notification_prefs::NotificationEvent;
 use std::process::Command;
-use tauri::{AppHandle, Manager};
+use tauri::{AppHandle, Emitter, Manager};
 use tauri_plugin_notification::NotificationExt;
 use tracing::{error, info, warn};
 
@@ -12,15 +14,75 @@ fn escape_applescript(s: &str) -> String {
         .replace('\r', "\\r")
 }
 
-/// Send a notification that research is complete.
+/// The platform sound to play: `platform_default` for "default", otherwise
+/// the configured sound name.
+#[cfg(any(target_os = "macos", target_os = "linux"))]
+fn sound_name<'a>(sound: &'a str, platform_default: &'a str) -> &'a str {
+    if sound == crate::notification_prefs::DEFAULT_SOUND {
+        platform_default
+    } else {
+        sound
+    }
+}
+
+/// Buttons on a notification that announces a briefing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NotificationAction {
+    Open,
+    Snooze,
+    MarkRead,
+}
+
+impl NotificationAction {
+    pub const ALL: [NotificationAction; 3] = [
+        NotificationAction::Open,
+        NotificationAction::Snooze,
+        NotificationAction::MarkRead,
+    ];
+
+    /// Identifier passed to the platform notification API.
+    pub fn id(&self) -> &'static str {
+        match self {
+            NotificationAction::Open => "open",
+            NotificationAction::Snooze => "snooze",
+            NotificationAction::MarkRead => "mark_read",
+        }
+    }
+
+    /// Button text.
+    pub fn label(&self) -> &'static str {
+        match self {
+            NotificationAction::Open => "Open briefing",
+            NotificationAction::Snooze => "Snooze",
+            NotificationAction::MarkRead => "Mark read",
+        }
+    }
+
+    /// Map a platform response back to an action. Accepts identifiers and
+    /// labels (macOS reports the label); clicking the notification body
+    /// ("default") opens the briefing.
+    pub fn from_response(response: &str) -> Option<Self> {
+        if response == "default" || response == "__default" {
+            return Some(NotificationAction::Open);
+        }
+        Self::ALL
+            .into_iter()
+            .find(|a| a.id() == response || a.label() == response)
+    }
+}
+
+/// Send a notification that research is complete, playing `sound` (None
+/// for silent). When `briefing_id` is known, the notification gets
+/// Open/Snooze/Mark read buttons on platforms that support them.
 pub fn notify_research_complete(
     app: &AppHandle,
+    briefing_id: Option<i64>,
     count: usize,
-    enable_sound: bool,
+    sound: Option<&str>,
 ) -> Result<(), String> {
     info!(
-        "Sending research complete notification (count: {}, sound: {})",
-        count, enable_sound
+        "Sending research complete notification (count: {}, sound: {:?})",
+        count, sound
     );
 
     let title = "Research Complete";
@@ -30,6 +92,16 @@ pub fn notify_research_complete(
         format!("{} new briefings ready!", count)
     };
 
+    if defer_if_quiet(briefing_id, title, &body) {
+        return Ok(());
+    }
+
+    if let Some(briefing_id) = briefing_id {
+        if show_actionable(app, briefing_id, title, &body, sound) {
+            return Ok(());
+        }
+    }
+
     // Check permission state first
     match app.notification().permission_state() {
         Ok(state) => {
@@ -45,8 +117,8 @@ pub fn notify_research_complete(
     // In dev mode, notifications may show a generic icon
     let mut builder = app.notification().builder().title(title).body(&body);
 
-    if enable_sound {
-        builder = builder.sound("default");
+    if let Some(sound) = sound {
+        builder = builder.sound(sound);
     }
 
     // Try to set icon path (helps in dev mode on some platforms)
@@ -76,11 +148,14 @@ pub fn notify_research_complete(
     // Note: AppleScript notifications show Script Editor icon, not app icon
     #[cfg(target_os = "macos")]
     if !tauri_success {
-        let sound_option = if enable_sound {
-            "sound name \"Glass\""
-        } else {
-            ""
-        };
+        let sound_option = sound
+            .map(|s| {
+                format!(
+                    "sound name \"{}\"",
+                    escape_applescript(sound_name(s, "Glass"))
+                )
+            })
+            .unwrap_or_default();
         let script = format!(
             r#"display notification "{}" with title "{}" {}"#,
             escape_applescript(&body),
@@ -106,20 +181,430 @@ pub fn notify_research_complete(
     Ok(())
 }
 
-/// Send a notification for research errors.
-pub fn notify_research_error(app: &AppHandle, error_message: &str) -> Result<(), String> {
-    warn!("Sending research error notification: {}", error_message);
+/// Show a notification with action buttons, handling the chosen action on a
+/// background thread. Returns false where buttons aren't supported, so the
+/// caller can fall back to a plain notification.
+#[cfg(target_os = "macos")]
+fn show_actionable(
+    app: &AppHandle,
+    briefing_id: i64,
+    title: &str,
+    body: &str,
+    sound: Option<&str>,
+) -> bool {
+    use mac_notification_sys::{MainButton, Notification, NotificationResponse};
+
+    // Only a bundled app can post through the notification center
+    if mac_notification_sys::set_application(&app.config().identifier).is_err() {
+        return false;
+    }
+
+    let app = app.clone();
+    let (title, body) = (title.to_string(), body.to_string());
+    let sound = sound.map(|s| sound_name(s, "Glass").to_string());
+    std::thread::spawn(move || {
+        let labels = [
+            NotificationAction::Snooze.label(),
+            NotificationAction::MarkRead.label(),
+        ];
+        let mut options = Notification::new();
+        options
+            .main_button(MainButton::DropdownActions(
+                NotificationAction::Open.label(),
+                &labels,
+            ))
+            .close_button("Dismiss");
+        if let Some(sound) = &sound {
+            options.sound(sound);
+        }
+
+        let action =
+            match mac_notification_sys::send_notification(&title, None, &body, Some(&options)) {
+                Ok(NotificationResponse::ActionButton(label)) => {
+                    NotificationAction::from_response(&label)
+                }
+                Ok(NotificationResponse::Click) => Some(NotificationAction::Open),
+                Ok(_) => None,
+                Err(e) => {
+                    warn!("Actionable notification failed: {}", e);
+                    None
+                }
+            };
+        if let Some(action) = action {
+            run_action(&app, briefing_id, action);
+        }
+    });
+    true
+}
+
+#[cfg(target_os = "linux")]
+fn show_actionable(
+    app: &AppHandle,
+    briefing_id: i64,
+    title: &str,
+    body: &str,
+    sound: Option<&str>,
+) -> bool {
+    let mut notification = notify_rust::Notification::new();
+    notification.summary(title).body(body).appname("Claudius");
+    // "default" is the notification body itself
+    notification.action("default", NotificationAction::Open.label());
+    for action in NotificationAction::ALL {
+        notification.action(action.id(), action.label());
+    }
+    if let Some(sound) = sound {
+        notification.sound_name(sound_name(sound, "message-new-instant"));
+    }
+
+    // Fails when the notification server doesn't support actions
+    let handle = match notification.show() {
+        Ok(handle) => handle,
+        Err(e) => {
+            warn!("Actionable notification failed: {}", e);
+            return false;
+        }
+    };
+
+    let app = app.clone();
+    std::thread::spawn(move || {
+        handle.wait_for_action(|response| {
+            if let Some(action) = NotificationAction::from_response(response) {
+                run_action(&app, briefing_id, action);
+            }
+        });
+    });
+    true
+}
+
+#[cfg(target_os = "windows")]
+fn show_actionable(
+    app: &AppHandle,
+    briefing_id: i64,
+    title: &str,
+    body: &str,
+    sound: Option<&str>,
+) -> bool {
+    use tauri_winrt_notification::{Sound, Toast};
 
-    app.notification()
-        .builder()
-        .title("Research Failed")
-        .body(error_message)
-        .show()
-        .map_err(|e| e.to_string())?;
+    let mut toast = Toast::new(&app.config().identifier)
+        .title(title)
+        .text1(body);
+    for action in NotificationAction::ALL {
+        toast = toast.add_button(action.label(), action.id());
+    }
+    // Toasts only play the system's sounds, so any sound uses the default
+    if sound.is_none() {
+        toast = toast.sound(None::<Sound>);
+    }
+
+    let app = app.clone();
+    let result = toast
+        .on_activated(move |response| {
+            // No argument means the toast itself was clicked
+            let action = match response {
+                Some(id) => NotificationAction::from_response(&id),
+                None => Some(NotificationAction::Open),
+            };
+            if let Some(action) = action {
+                run_action(&app, briefing_id, action);
+            }
+            Ok(())
+        })
+        .show();
+    match result {
+        Ok(()) => true,
+        Err(e) => {
+            warn!("Actionable notification failed: {}", e);
+            false
+        }
+    }
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "linux", target_os = "windows")))]
+fn show_actionable(_: &AppHandle, _: i64, _: &str, _: &str, _: Option<&str>) -> bool {
+    false
+}
 
+/// Format a time the way the queue's `deliver_at` column stores it.
+fn db_time(time: chrono::DateTime<chrono::Utc>) -> String {
+    time.format("%Y-%m-%d %H:%M:%S").to_string()
+}
+
+/// When notifications may be shown again, if quiet hours or the OS's Do Not
+/// Disturb are holding them back.
+fn quiet_until(settings: &crate::config::ResearchSettings) -> Option<String> {
+    let now = chrono::Local::now();
+    let until = crate::quiet_hours::defer_until(settings, now.naive_local())?;
+    let until = until
+        .and_local_timezone(chrono::Local)
+        .earliest()
+        .map(|t| t.with_timezone(&chrono::Utc))
+        // The end falls in a DST gap
+        .unwrap_or_else(|| chrono::Utc::now() + chrono::Duration::hours(1));
+    Some(db_time(until))
+}
+
+/// Queue a notification for the end of quiet hours instead of showing it
+/// now. Returns false if it should be shown right away.
+fn defer_if_quiet(briefing_id: Option<i64>, title: &str, body: &str) -> bool {
+    let Ok(settings) = crate::config::read_settings() else {
+        return false;
+    };
+    let Some(until) = quiet_until(&settings) else {
+        return false;
+    };
+
+    let queued = crate::db::get_connection()
+        .map_err(|e| e.to_string())
+        .and_then(|conn| crate::db::queue_notification(&conn, briefing_id, title, body, &until));
+    match queued {
+        Ok(_) => {
+            info!(
+                "Quiet hours: holding \"{}\" notification until {} UTC",
+                title, until
+            );
+            true
+        }
+        Err(e) => {
+            warn!("Failed to queue notification, showing it now: {}", e);
+            false
+        }
+    }
+}
+
+fn run_action(app: &AppHandle, briefing_id: i64, action: NotificationAction) {
+    info!(
+        "Notification action {:?} for briefing {}",
+        action, briefing_id
+    );
+    if let Err(e) = handle_notification_action(app, briefing_id, action) {
+        error!("Notification action {:?} failed: {}", action, e);
+    }
+}
+
+/// Carry out a notification button's action for the briefing it announced.
+pub fn handle_notification_action(
+    app: &AppHandle,
+    briefing_id: i64,
+    action: NotificationAction,
+) -> Result<(), String> {
+    let conn = crate::db::get_connection()
+        .map_err(|e| format!("Failed to get database connection: {}", e))?;
+
+    match action {
+        NotificationAction::Open => {
+            crate::show_deep_link(app, crate::deep_link::DeepLink::briefing(briefing_id));
+            crate::db::mark_briefing_read(&conn, briefing_id)?;
+        }
+        NotificationAction::Snooze => {
+            let hours = crate::config::read_settings()
+                .map(|s| s.notification_snooze_hours)
+                .unwrap_or(2)
+                .max(1);
+            let deliver_at =
+                db_time(chrono::Utc::now() + chrono::Duration::hours(i64::from(hours)));
+            let title = crate::db::get_briefing(&conn, briefing_id)
+                .map(|b| b.title)
+                .unwrap_or_else(|_| "Research Complete".to_string());
+            crate::db::queue_notification(
+                &conn,
+                Some(briefing_id),
+                &title,
+                "Snoozed briefing is ready to read",
+                &deliver_at,
+            )?;
+        }
+        NotificationAction::MarkRead => {
+            crate::db::mark_briefing_read(&conn, briefing_id)?;
+            let _ = app.emit("briefings:refresh", ());
+        }
+    }
     Ok(())
 }
 
+/// Show queued notifications that have come due (e.g. snoozed ones), skipping
+/// any whose briefing has since been read. Called once a minute.
+pub fn deliver_queued_notifications(app: &AppHandle) {
+    let conn = match crate::db::get_connection() {
+        Ok(conn) => conn,
+        Err(e) => {
+            warn!("Failed to open database for queued notifications: {}", e);
+            return;
+        }
+    };
+    let due = match crate::db::get_due_notifications(&conn) {
+        Ok(due) => due,
+        Err(e) => {
+            warn!("Failed to load queued notifications: {}", e);
+            return;
+        }
+    };
+    if due.is_empty() {
+        return;
+    }
+
+    let settings = crate::config::read_settings().unwrap_or_default();
+    let sound =
+        crate::notification_prefs::delivery(&settings, NotificationEvent::ResearchCompleted)
+            .and_then(|d| d.sound);
+    // Still quiet (e.g. a snooze ran into quiet hours): push everything back
+    if let Some(until) = quiet_until(&settings) {
+        for queued in &due {
+            if let Err(e) = crate::db::reschedule_notification(&conn, queued.id, &until) {
+                error!("Failed to reschedule notification {}: {}", queued.id, e);
+            }
+        }
+        return;
+    }
+
+    for queued in due {
+        let already_read = queued
+            .briefing_id
+            .is_some_and(|id| crate::db::is_briefing_read(&conn, id).unwrap_or(false));
+        if !already_read {
+            info!("Delivering queued notification {}", queued.id);
+            let shown = queued.briefing_id.is_some_and(|id| {
+                show_actionable(app, id, &queued.title, &queued.body, sound.as_deref())
+            });
+            if !shown {
+                if let Err(e) = app
+                    .notification()
+                    .builder()
+                    .title(&queued.title)
+                    .body(&queued.body)
+                    .show()
+                {
+                    warn!("Queued notification failed: {}", e);
+                }
+            }
+        }
+        if let Err(e) = crate::db::mark_notification_delivered(&conn, queued.id) {
+            error!("Failed to mark notification {} delivered: {}", queued.id, e);
+        }
+    }
+}
+
+/// Show a notification for `event` as its preference says: with its sound,
+/// silently, or not at all. Held for later during quiet hours.
+pub fn notify_event(
+    app: &AppHandle,
+    settings: &ResearchSettings,
+    event: NotificationEvent,
+    title: &str,
+    body: &str,
+) -> Result<(), String> {
+    let Some(delivery) = crate::notification_prefs::delivery(settings, event) else {
+        info!("Notification for {} is turned off", event.as_str());
+        return Ok(());
+    };
+
+    if defer_if_quiet(None, title, body) {
+        return Ok(());
+    }
+
+    let mut builder = app.notification().builder().title(title).body(body);
+    if let Some(sound) = &delivery.sound {
+        builder = builder.sound(sound);
+    }
+    builder.show().map_err(|e| e.to_string())
+}
+
+/// Send a notification for a research error. Errors the user has to fix,
+/// failed runs and cancellations each follow their own preference.
+pub fn notify_research_error(
+    app: &AppHandle,
+    settings: &ResearchSettings,
+    error_message: &str,
+) -> Result<(), String> {
+    let event = NotificationEvent::for_research_error(error_message);
+    warn!("Research error ({}): {}", event.as_str(), error_message);
+
+    let title = match event {
+        NotificationEvent::ResearchCancelled => "Research Cancelled",
+        _ => "Research Failed",
+    };
+    notify_event(app, settings, event, title, error_message)
+}
+
+/// Send a notification naming the topics a research run left out because
+/// their research failed, with the command that retries them.
+pub fn notify_topics_failed(
+    app: &AppHandle,
+    settings: &ResearchSettings,
+    failed: &[crate::research::FailedTopic],
+) -> Result<(), String> {
+    let mut body = failed
+        .iter()
+        .map(|f| format!("{}: {}", f.topic, f.error))
+        .collect::<Vec<_>>()
+        .join("\n");
+    if let Some(command) = crate::research::retry_command(failed) {
+        body.push_str(&format!("\nRetry with: {}", command));
+    }
+    let title = if failed.len() == 1 {
+        "1 Topic Failed".to_string()
+    } else {
+        format!("{} Topics Failed", failed.len())
+    };
+    notify_event(
+        app,
+        settings,
+        NotificationEvent::ResearchFailed,
+        &title,
+        &body,
+    )
+}
+
+/// Send a notification when a Firecrawl budget nears its limit or runs out.
+pub fn notify_budget_warning(
+    app: &AppHandle,
+    settings: &ResearchSettings,
+    message: &str,
+) -> Result<(), String> {
+    notify_event(
+        app,
+        settings,
+        NotificationEvent::ActionableError,
+        "Firecrawl Budget",
+        message,
+    )
+}
+
+/// Notify for every card-action reminder that has come due, then mark it fired.
+/// Called once a minute from a background task.
+pub fn deliver_due_reminders(app: &AppHandle) {
+    let conn = match crate::db::get_connection() {
+        Ok(conn) => conn,
+        Err(e) => {
+            warn!("Failed to open database for reminders: {}", e);
+            return;
+        }
+    };
+    let due = match crate::db::get_due_reminders(&conn) {
+        Ok(due) => due,
+        Err(e) => {
+            warn!("Failed to load due reminders: {}", e);
+            return;
+        }
+    };
+
+    for reminder in due {
+        info!("Delivering reminder {}: {}", reminder.id, reminder.message);
+        if let Err(e) = app
+            .notification()
+            .builder()
+            .title(format!("Reminder: {}", reminder.card_title))
+            .body(&reminder.message)
+            .show()
+        {
+            warn!("Reminder notification failed: {}", e);
+        }
+        if let Err(e) = crate::db::mark_reminder_fired(&conn, reminder.id) {
+            error!("Failed to mark reminder {} as fired: {}", reminder.id, e);
+        }
+    }
+}
+
 /// Check if notifications are allowed.
 pub async fn check_notification_permission(app: &AppHandle) -> bool {
     match app.notification().permission_state() {
@@ -155,3 +640,24 @@ pub async fn check_notification_permission(app: &AppHandle) -> bool {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_action_from_response() {
+        for action in NotificationAction::ALL {
+            assert_eq!(NotificationAction::from_response(action.id()), Some(action));
+            assert_eq!(
+                NotificationAction::from_response(action.label()),
+                Some(action)
+            );
+        }
+        assert_eq!(
+            NotificationAction::from_response("default"),
+            Some(NotificationAction::Open)
+        );
+        assert_eq!(NotificationAction::from_response("__closed"), None);
+    }
+}
@@ -0,0 +1,240 @@
+//! Quiet hours for notifications.
+//!
+//! Notifications that would be shown during quiet hours are queued in
+//! `notification_queue` and delivered when the quiet period ends. Quiet
+//! hours are a daily window (`quiet_hours_start`..`quiet_hours_end`, local
+//! time, may wrap past midnight), optionally all of Saturday and Sunday
+//! (`quiet_weekends`), and optionally whenever the OS reports Do Not Disturb
+//! or Focus mode (`respect_system_dnd`).
+
+use crate::config::ResearchSettings;
+use chrono::{Datelike, Duration, NaiveDateTime, NaiveTime, Weekday};
+
+/// How long to hold a notification while the OS is in Do Not Disturb; it is
+/// checked again when it comes due.
+pub const DND_RETRY_MINUTES: i64 = 15;
+
+/// Quiet-hours rules from settings.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct QuietHours {
+    /// Daily window as (start, end); `start > end` wraps past midnight
+    pub window: Option<(NaiveTime, NaiveTime)>,
+    pub weekends: bool,
+}
+
+/// Parse an "HH:MM" time.
+pub fn parse_time(value: &str) -> Result<NaiveTime, String> {
+    NaiveTime::parse_from_str(value.trim(), "%H:%M")
+        .map_err(|_| format!("Invalid time '{}', use HH:MM (e.g. 22:00)", value))
+}
+
+/// Parse a "HH:MM-HH:MM" range as used by `claudius config set quiet_hours`.
+#[allow(dead_code)]
+pub fn parse_range(value: &str) -> Result<(NaiveTime, NaiveTime), String> {
+    let (start, end) = value
+        .split_once('-')
+        .ok_or_else(|| format!("Invalid range '{}', use HH:MM-HH:MM", value))?;
+    Ok((parse_time(start)?, parse_time(end)?))
+}
+
+impl QuietHours {
+    pub fn from_settings(settings: &ResearchSettings) -> Result<Self, String> {
+        let window = match (&settings.quiet_hours_start, &settings.quiet_hours_end) {
+            (Some(start), Some(end)) => {
+                let (start, end) = (parse_time(start)?, parse_time(end)?);
+                (start != end).then_some((start, end))
+            }
+            _ => None,
+        };
+        Ok(Self {
+            window,
+            weekends: settings.quiet_weekends,
+        })
+    }
+
+    fn in_window(&self, time: NaiveTime) -> bool {
+        match self.window {
+            Some((start, end)) if start < end => time >= start && time < end,
+            Some((start, end)) => time >= start || time < end,
+            None => false,
+        }
+    }
+
+    fn is_weekend(at: NaiveDateTime) -> bool {
+        matches!(at.weekday(), Weekday::Sat | Weekday::Sun)
+    }
+
+    pub fn is_quiet(&self, at: NaiveDateTime) -> bool {
+        (self.weekends && Self::is_weekend(at)) || self.in_window(at.time())
+    }
+
+    /// When the quiet period containing `at` ends, or `None` if `at` isn't
+    /// in quiet hours.
+    pub fn ends_at(&self, at: NaiveDateTime) -> Option<NaiveDateTime> {
+        if !self.is_quiet(at) {
+            return None;
+        }
+
+        // A weekend and a nightly window can run into each other, so keep
+        // jumping to the end of whichever rule applies until neither does.
+        let mut t = at;
+        while self.is_quiet(t) {
+            t = if self.weekends && Self::is_weekend(t) {
+                let days_to_monday = 7 - t.weekday().num_days_from_monday();
+                (t.date() + Duration::days(i64::from(days_to_monday))).and_time(NaiveTime::MIN)
+            } else {
+                let (start, end) = self.window?;
+                if start > end && t.time() >= start {
+                    (t.date() + Duration::days(1)).and_time(end)
+                } else {
+                    t.date().and_time(end)
+                }
+            };
+        }
+        Some(t)
+    }
+}
+
+/// Whether the OS is currently in Do Not Disturb / Focus mode. Returns
+/// false where this can't be detected.
+pub fn system_dnd_active() -> bool {
+    #[cfg(target_os = "macos")]
+    {
+        // Focus modes record their active assertions here (macOS 12+)
+        let Some(home) = dirs::home_dir() else {
+            return false;
+        };
+        let path = home.join("Library/DoNotDisturb/DB/Assertions.json");
+        std::fs::read_to_string(path)
+            .ok()
+            .and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok())
+            .and_then(|json| {
+                json["data"].as_array().map(|data| {
+                    data.iter().any(|entry| {
+                        entry["storeAssertionRecords"]
+                            .as_array()
+                            .is_some_and(|records| !records.is_empty())
+                    })
+                })
+            })
+            .unwrap_or(false)
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        // GNOME turns banners off while Do Not Disturb is on
+        std::process::Command::new("gsettings")
+            .args(["get", "org.gnome.desktop.notifications", "show-banners"])
+            .output()
+            .ok()
+            .filter(|output| output.status.success())
+            .is_some_and(|output| String::from_utf8_lossy(&output.stdout).trim() == "false")
+    }
+
+    #[cfg(not(any(target_os = "macos", target_os = "linux")))]
+    {
+        false
+    }
+}
+
+/// When a notification shown at `now` should be delivered instead, or
+/// `None` to show it right away.
+pub fn defer_until(settings: &ResearchSettings, now: NaiveDateTime) -> Option<NaiveDateTime> {
+    let quiet = QuietHours::from_settings(settings).unwrap_or_default();
+    quiet.ends_at(now).or_else(|| {
+        (settings.respect_system_dnd && system_dnd_active())
+            .then(|| now + Duration::minutes(DND_RETRY_MINUTES))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn at(date: (i32, u32, u32), time: &str) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(date.0, date.1, date.2)
+            .unwrap()
+            .and_time(parse_time(time).unwrap())
+    }
+
+    fn nightly() -> QuietHours {
+        QuietHours {
+            window: Some(parse_range("22:00-08:00").unwrap()),
+            weekends: false,
+        }
+    }
+
+    #[test]
+    fn test_overnight_window() {
+        let quiet = nightly();
+        // 2025-01-15 is a Wednesday
+        assert!(!quiet.is_quiet(at((2025, 1, 15), "21:59")));
+        assert_eq!(
+            quiet.ends_at(at((2025, 1, 15), "23:30")),
+            Some(at((2025, 1, 16), "08:00"))
+        );
+        assert_eq!(
+            quiet.ends_at(at((2025, 1, 16), "03:00")),
+            Some(at((2025, 1, 16), "08:00"))
+        );
+        assert_eq!(quiet.ends_at(at((2025, 1, 16), "08:00")), None);
+    }
+
+    #[test]
+    fn test_daytime_window() {
+        let quiet = QuietHours {
+            window: Some(parse_range("12:00-13:30").unwrap()),
+            weekends: false,
+        };
+        assert_eq!(
+            quiet.ends_at(at((2025, 1, 15), "12:10")),
+            Some(at((2025, 1, 15), "13:30"))
+        );
+        assert_eq!(quiet.ends_at(at((2025, 1, 15), "11:00")), None);
+    }
+
+    #[test]
+    fn test_weekends_run_into_nightly_window() {
+        let quiet = QuietHours {
+            weekends: true,
+            ..nightly()
+        };
+        // Friday night waits for the weekend and the Monday morning window
+        assert_eq!(
+            quiet.ends_at(at((2025, 1, 17), "23:00")),
+            Some(at((2025, 1, 20), "08:00"))
+        );
+        assert_eq!(
+            quiet.ends_at(at((2025, 1, 18), "14:00")),
+            Some(at((2025, 1, 20), "08:00"))
+        );
+
+        let weekends_only = QuietHours {
+            window: None,
+            weekends: true,
+        };
+        assert_eq!(
+            weekends_only.ends_at(at((2025, 1, 19), "10:00")),
+            Some(at((2025, 1, 20), "00:00"))
+        );
+        assert_eq!(weekends_only.ends_at(at((2025, 1, 17), "23:00")), None);
+    }
+
+    #[test]
+    fn test_from_settings() {
+        let mut settings = ResearchSettings::default();
+        assert_eq!(
+            QuietHours::from_settings(&settings).unwrap(),
+            QuietHours::default()
+        );
+
+        settings.quiet_hours_start = Some("22:00".to_string());
+        settings.quiet_hours_end = Some("08:00".to_string());
+        assert_eq!(QuietHours::from_settings(&settings).unwrap(), nightly());
+
+        settings.quiet_hours_end = Some("8pm".to_string());
+        assert!(QuietHours::from_settings(&settings).is_err());
+        assert!(parse_range("22:00").is_err());
+    }
+}
@@ -0,0 +1,165 @@
+//! Forwarding saved cards to read-later services.
+//!
+//! Cards saved with "save for later" are always stored in the local
+//! `read_later` table. When `read_later_service` is set, the card's first
+//! source URL is also sent to Pocket, Readwise Reader or Instapaper.
+//! Credentials are read from the OS credential store or `~/.claudius/.env`:
+//!
+//! - Pocket: `POCKET_CONSUMER_KEY`, `POCKET_ACCESS_TOKEN`
+//! - Readwise: `READWISE_TOKEN`
+//! - Instapaper: `INSTAPAPER_USERNAME`, `INSTAPAPER_PASSWORD`
+
+use crate::config::read_env_value;
+use crate::research::BriefingCard;
+use serde_json::json;
+use std::time::Duration;
+use tracing::info;
+
+/// Supported read-later services.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReadLaterService {
+    Pocket,
+    Readwise,
+    Instapaper,
+}
+
+impl ReadLaterService {
+    /// Parse the `read_later_service` setting.
+    pub fn from_setting(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "pocket" => Some(ReadLaterService::Pocket),
+            "readwise" | "reader" => Some(ReadLaterService::Readwise),
+            "instapaper" => Some(ReadLaterService::Instapaper),
+            _ => None,
+        }
+    }
+
+    /// Name stored in `read_later.forwarded_to`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReadLaterService::Pocket => "pocket",
+            ReadLaterService::Readwise => "readwise",
+            ReadLaterService::Instapaper => "instapaper",
+        }
+    }
+}
+
+/// The URL to forward for a card: its first source that contains a link.
+pub fn card_url(card: &BriefingCard) -> Option<String> {
+    card.sources
+        .iter()
+        .find_map(|s| crate::sources::extract_url(s))
+}
+
+fn credential(name: &str) -> Result<String, String> {
+    read_env_value(name).ok_or_else(|| format!("{} is not set in ~/.claudius/.env", name))
+}
+
+/// Send a card to the given read-later service.
+pub async fn forward_card(service: ReadLaterService, card: &BriefingCard) -> Result<(), String> {
+    let url = card_url(card).ok_or("Card has no source URL to save")?;
+    let client = crate::http_client::client_builder()
+        .timeout(Duration::from_secs(15))
+        .build()
+        .map_err(|e| format!("Failed to build HTTP client: {}", e))?;
+
+    let request = match service {
+        ReadLaterService::Pocket => client.post("https://getpocket.com/v3/add").json(&json!({
+            "url": url,
+            "title": card.title,
+            "tags": card.topic,
+            "consumer_key": credential("POCKET_CONSUMER_KEY")?,
+            "access_token": credential("POCKET_ACCESS_TOKEN")?,
+        })),
+        ReadLaterService::Readwise => client
+            .post("https://readwise.io/api/v3/save/")
+            .header(
+                "Authorization",
+                format!("Token {}", credential("READWISE_TOKEN")?),
+            )
+            .json(&json!({
+                "url": url,
+                "title": card.title,
+                "summary": card.summary,
+                "tags": [card.topic],
+                "saved_using": "Claudius",
+            })),
+        ReadLaterService::Instapaper => client
+            .post("https://www.instapaper.com/api/add")
+            .basic_auth(
+                credential("INSTAPAPER_USERNAME")?,
+                Some(credential("INSTAPAPER_PASSWORD")?),
+            )
+            .form(&[
+                ("url", url.as_str()),
+                ("title", card.title.as_str()),
+                ("selection", card.summary.as_str()),
+            ]),
+    };
+
+    let response = request
+        .send()
+        .await
+        .map_err(|e| format!("{} request failed: {}", service.as_str(), e))?;
+
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        return Err(format!(
+            "{} API error {}: {}",
+            service.as_str(),
+            status,
+            body
+        ));
+    }
+
+    info!("Forwarded '{}' to {}", card.title, service.as_str());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_service_from_setting() {
+        assert_eq!(
+            ReadLaterService::from_setting("Pocket"),
+            Some(ReadLaterService::Pocket)
+        );
+        assert_eq!(
+            ReadLaterService::from_setting("reader"),
+            Some(ReadLaterService::Readwise)
+        );
+        assert_eq!(ReadLaterService::from_setting(""), None);
+    }
+
+    #[test]
+    fn test_card_url_uses_first_linked_source() {
+        let card = BriefingCard {
+            title: "Title".to_string(),
+            summary: "Summary".to_string(),
+            detailed_content: String::new(),
+            sources: vec![
+                "Reuters".to_string(),
+                "Blog - https://example.com/post".to_string(),
+                "https://example.com/other".to_string(),
+            ],
+            suggested_next: None,
+            relevance: "high".to_string(),
+            topic: "Rust".to_string(),
+            image_prompt: None,
+            image_style: None,
+            image_path: None,
+            source_checks: vec![],
+            action_items: vec![],
+            actions: vec![],
+            source_warning: None,
+            rank: None,
+        };
+        assert_eq!(
+            card_url(&card),
+            Some("https://example.com/post".to_string())
+        );
+    }
+}
@@ -0,0 +1,371 @@
+//! Release channels and changelogs from the project's GitHub releases.
+//!
+//! `release_channel` in settings is "stable" (the default), which follows
+//! full releases only, or "beta", which also takes pre-releases (tags such
+//! as "v0.6.0-beta.1"). The app updater and `claudius update` both pick
+//! their release here.
+
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+pub const RELEASE_CHANNELS: &[&str] = &["stable", "beta"];
+
+pub const DEFAULT_CHANNEL: &str = "stable";
+
+const RELEASES_API_URL: &str = "https://api.github.com/repos/chrisvanbuskirk/claudius/releases";
+
+/// Updater manifest of the latest full release.
+const STABLE_UPDATER_URL: &str =
+    "https://github.com/chrisvanbuskirk/claudius/releases/latest/download/latest.json";
+
+/// Name of the updater manifest attached to each release.
+const UPDATER_MANIFEST: &str = "latest.json";
+
+/// A downloadable file attached to a release.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReleaseAsset {
+    pub name: String,
+    #[serde(rename(deserialize = "browser_download_url"))]
+    pub url: String,
+    #[serde(default)]
+    pub size: u64,
+}
+
+/// A release as returned by the GitHub API.
+#[derive(Debug, Deserialize)]
+struct GithubRelease {
+    tag_name: String,
+    #[serde(default)]
+    body: Option<String>,
+    #[serde(default)]
+    draft: bool,
+    #[serde(default)]
+    prerelease: bool,
+    #[serde(default)]
+    published_at: Option<String>,
+    html_url: String,
+    #[serde(default)]
+    assets: Vec<ReleaseAsset>,
+}
+
+/// A published release, with its notes.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Release {
+    /// Version without the leading "v" (e.g. "0.6.0-beta.1")
+    pub version: String,
+    pub tag: String,
+    pub notes: Option<String>,
+    pub published_at: Option<String>,
+    /// Release page on GitHub
+    pub url: String,
+    pub prerelease: bool,
+    pub assets: Vec<ReleaseAsset>,
+}
+
+impl Release {
+    pub fn asset(&self, name: &str) -> Option<&ReleaseAsset> {
+        self.assets.iter().find(|a| a.name == name)
+    }
+}
+
+pub fn validate_channel(channel: &str) -> Result<(), String> {
+    if RELEASE_CHANNELS.contains(&channel) {
+        Ok(())
+    } else {
+        Err(format!(
+            "Unknown release channel '{}' (use {})",
+            channel,
+            RELEASE_CHANNELS.join(" or ")
+        ))
+    }
+}
+
+/// Split "v1.2.3-beta.1" into its numbers and its pre-release identifiers.
+fn parse_version(version: &str) -> (Vec<u64>, Vec<&str>) {
+    let version = version.trim().trim_start_matches('v');
+    // Build metadata ("+abc") doesn't affect precedence
+    let version = version.split('+').next().unwrap_or_default();
+    let (core, pre) = match version.split_once('-') {
+        Some((core, pre)) => (core, pre.split('.').collect()),
+        None => (version, Vec::new()),
+    };
+    let numbers = core
+        .split('.')
+        .map(|n| n.parse().unwrap_or(0))
+        .collect::<Vec<u64>>();
+    (numbers, pre)
+}
+
+/// Compare two versions the semver way: numbers first, then a pre-release
+/// sorts before the release it leads up to ("1.0.0-beta.2" < "1.0.0").
+pub fn compare_versions(a: &str, b: &str) -> Ordering {
+    let (a_core, a_pre) = parse_version(a);
+    let (b_core, b_pre) = parse_version(b);
+    let len = a_core.len().max(b_core.len());
+    for i in 0..len {
+        let ordering = a_core.get(i).unwrap_or(&0).cmp(b_core.get(i).unwrap_or(&0));
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+
+    match (a_pre.is_empty(), b_pre.is_empty()) {
+        (true, true) => return Ordering::Equal,
+        (true, false) => return Ordering::Greater,
+        (false, true) => return Ordering::Less,
+        (false, false) => {}
+    }
+    for (a_id, b_id) in a_pre.iter().zip(b_pre.iter()) {
+        let ordering = match (a_id.parse::<u64>(), b_id.parse::<u64>()) {
+            (Ok(a_num), Ok(b_num)) => a_num.cmp(&b_num),
+            (Ok(_), Err(_)) => Ordering::Less,
+            (Err(_), Ok(_)) => Ordering::Greater,
+            (Err(_), Err(_)) => a_id.cmp(b_id),
+        };
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+    a_pre.len().cmp(&b_pre.len())
+}
+
+/// The releases of a channel, newest first. Drafts are never included.
+fn select_releases(releases: Vec<GithubRelease>, channel: &str) -> Vec<Release> {
+    let mut selected: Vec<Release> = releases
+        .into_iter()
+        .filter(|r| !r.draft && (channel == "beta" || !r.prerelease))
+        .map(|r| Release {
+            version: r.tag_name.trim_start_matches('v').to_string(),
+            tag: r.tag_name,
+            notes: r.body.filter(|b| !b.trim().is_empty()),
+            published_at: r.published_at,
+            url: r.html_url,
+            prerelease: r.prerelease,
+            assets: r.assets,
+        })
+        .collect();
+    selected.sort_by(|a, b| compare_versions(&b.version, &a.version));
+    selected
+}
+
+/// Recent releases of `channel`, newest first.
+pub async fn fetch_releases(channel: &str) -> Result<Vec<Release>, String> {
+    validate_channel(channel)?;
+    let client = crate::http_client::client_builder()
+        .timeout(Duration::from_secs(30))
+        .build()
+        .map_err(|e| format!("Failed to build HTTP client: {}", e))?;
+    let response = client
+        .get(RELEASES_API_URL)
+        .query(&[("per_page", "30")])
+        // GitHub rejects API requests without a user agent
+        .header(
+            "User-Agent",
+            concat!("claudius/", env!("CARGO_PKG_VERSION")),
+        )
+        .header("Accept", "application/vnd.github+json")
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch releases: {}", e))?;
+
+    if !response.status().is_success() {
+        return Err(format!(
+            "Failed to fetch releases: GitHub returned {}",
+            response.status().as_u16()
+        ));
+    }
+    let releases: Vec<GithubRelease> = response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse releases: {}", e))?;
+    Ok(select_releases(releases, channel))
+}
+
+/// Releases newer than `current`, newest first: the changelog of an update.
+pub fn changelog_since(releases: &[Release], current: &str) -> Vec<Release> {
+    releases
+        .iter()
+        .filter(|r| compare_versions(&r.version, current) == Ordering::Greater)
+        .cloned()
+        .collect()
+}
+
+/// Updater manifest to check for `channel`, given its releases (newest
+/// first). Beta uses the manifest of the newest release, pre-release or
+/// not; stable always uses the latest full release.
+pub fn updater_endpoint(channel: &str, releases: &[Release]) -> String {
+    if channel == "beta" {
+        if let Some(manifest) = releases.first().and_then(|r| r.asset(UPDATER_MANIFEST)) {
+            return manifest.url.clone();
+        }
+    }
+    STABLE_UPDATER_URL.to_string()
+}
+
+/// Name of the standalone CLI binary attached to releases for a platform
+/// (`std::env::consts::OS` and `ARCH`), e.g. "claudius-cli-macos-aarch64".
+#[allow(dead_code)]
+pub fn cli_asset_name(os: &str, arch: &str) -> String {
+    format!("claudius-cli-{}-{}", os, arch)
+}
+
+fn write_error(dir: &Path, e: std::io::Error) -> String {
+    if e.kind() == std::io::ErrorKind::PermissionDenied {
+        format!(
+            "Cannot write to {}: {} (run the update with sudo)",
+            dir.display(),
+            e
+        )
+    } else {
+        format!("Failed to write to {}: {}", dir.display(), e)
+    }
+}
+
+/// Write a new binary for `target` beside it (same directory, so it can be
+/// renamed over it) and make it executable. Returns the staged path.
+#[allow(dead_code)]
+pub fn stage_binary(target: &Path, contents: &[u8]) -> Result<PathBuf, String> {
+    let dir = target
+        .parent()
+        .ok_or_else(|| format!("{} has no parent directory", target.display()))?;
+    let name = target
+        .file_name()
+        .ok_or_else(|| format!("{} is not a file", target.display()))?
+        .to_string_lossy();
+    let staged = dir.join(format!(".{}.new", name));
+    std::fs::write(&staged, contents).map_err(|e| write_error(dir, e))?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        if let Err(e) = std::fs::set_permissions(&staged, std::fs::Permissions::from_mode(0o755)) {
+            let _ = std::fs::remove_file(&staged);
+            return Err(write_error(dir, e));
+        }
+    }
+    Ok(staged)
+}
+
+/// Put a staged binary in place of `target`. The old binary is copied to
+/// `<target>.old` first, then the staged one is renamed over it, so
+/// `target` (and any symlink to it) never points at a half-written file.
+/// Returns the backup path.
+#[allow(dead_code)]
+pub fn swap_binary(target: &Path, staged: &Path) -> Result<PathBuf, String> {
+    let dir = target.parent().unwrap_or(Path::new("."));
+    let mut backup = target.as_os_str().to_owned();
+    backup.push(".old");
+    let backup = PathBuf::from(backup);
+
+    let result = std::fs::copy(target, &backup)
+        .and_then(|_| std::fs::rename(staged, target))
+        .map_err(|e| write_error(dir, e));
+    if result.is_err() {
+        let _ = std::fs::remove_file(staged);
+    }
+    result.map(|_| backup)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn github_release(tag: &str, prerelease: bool, draft: bool) -> GithubRelease {
+        GithubRelease {
+            tag_name: tag.to_string(),
+            body: Some(format!("Notes for {}", tag)),
+            draft,
+            prerelease,
+            published_at: None,
+            html_url: format!("https://github.com/example/releases/tag/{}", tag),
+            assets: vec![ReleaseAsset {
+                name: UPDATER_MANIFEST.to_string(),
+                url: format!("https://example.com/{}/latest.json", tag),
+                size: 1,
+            }],
+        }
+    }
+
+    #[test]
+    fn test_compare_versions() {
+        assert_eq!(compare_versions("0.5.3", "v0.5.3"), Ordering::Equal);
+        assert_eq!(compare_versions("0.5.10", "0.5.9"), Ordering::Greater);
+        assert_eq!(compare_versions("0.6", "0.5.9"), Ordering::Greater);
+        assert_eq!(compare_versions("1.0.0-beta.2", "1.0.0"), Ordering::Less);
+        assert_eq!(
+            compare_versions("1.0.0-beta.10", "1.0.0-beta.2"),
+            Ordering::Greater
+        );
+        assert_eq!(
+            compare_versions("1.0.0-beta", "1.0.0-alpha.5"),
+            Ordering::Greater
+        );
+        assert_eq!(compare_versions("1.0.0-rc.1", "0.9.9"), Ordering::Greater);
+    }
+
+    #[test]
+    fn test_select_releases_by_channel() {
+        let releases = || {
+            vec![
+                github_release("v0.5.3", false, false),
+                github_release("v0.6.0-beta.1", true, false),
+                github_release("v0.5.4", false, false),
+                github_release("v0.7.0", false, true),
+            ]
+        };
+
+        let stable = select_releases(releases(), "stable");
+        let versions: Vec<&str> = stable.iter().map(|r| r.version.as_str()).collect();
+        assert_eq!(versions, vec!["0.5.4", "0.5.3"]);
+
+        let beta = select_releases(releases(), "beta");
+        let versions: Vec<&str> = beta.iter().map(|r| r.version.as_str()).collect();
+        assert_eq!(versions, vec!["0.6.0-beta.1", "0.5.4", "0.5.3"]);
+
+        let changelog = changelog_since(&beta, "0.5.3");
+        assert_eq!(changelog.len(), 2);
+        assert_eq!(
+            changelog[0].notes.as_deref(),
+            Some("Notes for v0.6.0-beta.1")
+        );
+
+        assert_eq!(
+            updater_endpoint("beta", &beta),
+            "https://example.com/v0.6.0-beta.1/latest.json"
+        );
+        assert_eq!(updater_endpoint("stable", &stable), STABLE_UPDATER_URL);
+        assert_eq!(updater_endpoint("beta", &[]), STABLE_UPDATER_URL);
+    }
+
+    #[test]
+    fn test_stage_and_swap_binary() {
+        let dir = std::env::temp_dir().join(format!("claudius-swap-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let target = dir.join("claudius");
+        std::fs::write(&target, b"old").unwrap();
+
+        let staged = stage_binary(&target, b"new").unwrap();
+        assert_eq!(std::fs::read(&target).unwrap(), b"old");
+        let backup = swap_binary(&target, &staged).unwrap();
+
+        assert_eq!(std::fs::read(&target).unwrap(), b"new");
+        assert_eq!(std::fs::read(&backup).unwrap(), b"old");
+        assert!(!staged.exists());
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&target).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o755);
+        }
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_validate_channel() {
+        assert!(validate_channel("stable").is_ok());
+        assert!(validate_channel("beta").is_ok());
+        assert!(validate_channel("nightly").is_err());
+    }
+}
@@ -4,24 +4,37 @@
 //! Supports tool calling for external data sources via MCP servers and built-in tools.
 #![allow(dead_code)]
 
-use crate::mcp_client::{load_mcp_servers, McpClient};
+use crate::agent_loop::{
+    mcp_result_text, ApiEndpoint, ContentBlock, Message, MessageContent, MessagesRequest,
+    MessagesResponse, ThinkingConfig,
+};
+use crate::batch::{BatchClient, BatchResult};
+use crate::firecrawl_budget::{self, BudgetWarning, FirecrawlBudget};
+use crate::mcp_client::{load_mcp_servers, truncate_result, McpClient, DEFAULT_RESULT_MAX_CHARS};
+use crate::mcp_routing::McpRouting;
 use crate::research_log::{parse_api_error, ErrorCode, ResearchError, ResearchLogger};
+use crate::research_mode::{self, ResearchMode};
 use crate::research_state;
+use crate::sources::{self, SourceCheck};
+use crate::tools::{self, BuiltinTools, ToolDefinition, ToolFilter, ToolRegistry};
 use chrono::Datelike;
-use regex::Regex;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
-use serde_json::json;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
-use tauri::Emitter;
-use tracing::{debug, error, info, warn};
+use tracing::{debug, error, info, warn, Instrument};
 
 /// Maximum number of tool use iterations to prevent infinite loops.
 const MAX_TOOL_ITERATIONS: usize = 10;
 
+/// Output tokens per research step, on top of any thinking budget.
+const RESEARCH_MAX_TOKENS: u32 = 2048;
+
+/// How often a pending research batch is checked.
+const BATCH_POLL_INTERVAL: Duration = Duration::from_secs(30);
+
 /// Claude's built-in web search tool type identifier.
 /// This version string may change with API updates.
 const WEB_SEARCH_TOOL_TYPE: &str = "web_search_20250305";
@@ -29,6 +42,40 @@ const WEB_SEARCH_TOOL_TYPE: &str = "web_search_20250305";
 /// Maximum number of web searches per topic to control costs (~$0.01/search).
 const WEB_SEARCH_MAX_USES: u32 = 10;
 
+/// Maximum number of preferred sources pre-fetched per topic when web search is off.
+const MAX_PREFETCH_SOURCES: usize = 3;
+
+/// Maximum characters kept from each pre-fetched preferred source.
+const PREFETCH_MAX_CHARS: usize = 4000;
+
+/// Default wall-clock limit for researching a single topic.
+pub const DEFAULT_TOPIC_TIMEOUT: Duration = Duration::from_secs(10 * 60);
+
+/// Maximum characters kept from each tool result for a timed-out topic's partial findings.
+const PARTIAL_FINDING_MAX_CHARS: usize = 2000;
+
+/// Fetched content is kept in the content cache (and reused offline) for this many days.
+pub const CONTENT_CACHE_MAX_AGE_DAYS: i64 = 7;
+
+/// Maximum cached items added to a topic's prompt in offline mode.
+const OFFLINE_MAX_CACHED_ITEMS: usize = 5;
+
+/// Maximum characters kept from each cached item in offline mode.
+const OFFLINE_CACHED_ITEM_MAX_CHARS: usize = 4000;
+
+/// Model used to summarize the omitted middle of oversized MCP tool results.
+const TRUNCATION_SUMMARY_MODEL: &str = "claude-haiku-4-5-20251001";
+
+/// Maximum characters of an omitted section sent for summarization.
+const TRUNCATION_SUMMARY_INPUT_CHARS: usize = 50_000;
+
+/// Most cards sent back in the single source repair round; the rest are
+/// saved with a warning so a bad synthesis can't run up the bill.
+const MAX_REPAIR_CARDS: usize = 5;
+
+/// Output token limit for the source repair request.
+const REPAIR_MAX_TOKENS: u32 = 8192;
+
 /// A single briefing card containing research on a topic.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BriefingCard {
@@ -43,9 +90,29 @@ pub struct BriefingCard {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub image_prompt: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub image_style: Option<String>, // Legacy field, not used with DALL-E
+    pub image_style: Option<String>, // image_gen::IMAGE_STYLES preset the header image was made with
     #[serde(skip_serializing_if = "Option::is_none")]
     pub image_path: Option<String>,
+    // Source validation results (populated after synthesis)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub source_checks: Vec<SourceCheck>,
+    // Follow-up actions (executive summary format)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub action_items: Vec<String>,
+    // Structured actions the user can run (reminders, links, issues, events)
+    #[serde(
+        default,
+        deserialize_with = "crate::card_actions::deserialize_actions",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub actions: Vec<crate::card_actions::CardAction>,
+    // Why the card failed source checks, if it still did after the repair round
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_warning: Option<String>,
+    // Display position in the briefing (0 = first). Cards keep their index
+    // for feedback, chat and bookmarks; only the display order changes.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rank: Option<u32>,
 }
 
 /// Result of a research operation.
@@ -57,6 +124,323 @@ pub struct ResearchResult {
     pub research_time_ms: u64,
     pub model_used: String,
     pub total_tokens: u32,
+    /// Part of `total_tokens` billed at the Batch API discount
+    #[serde(default)]
+    pub batch_tokens: u32,
+    /// ID of the research run. Saved with the briefing so saving the same
+    /// result again doesn't create a second briefing.
+    #[serde(default)]
+    pub run_id: String,
+    /// What each topic took and produced, in research order
+    #[serde(default)]
+    pub topics: Vec<TopicResult>,
+    /// Topics that produced no research and were left out of synthesis
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub failed_topics: Vec<FailedTopic>,
+}
+
+/// A topic whose research failed, and why.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct FailedTopic {
+    pub topic: String,
+    pub error: String,
+}
+
+/// CLI command that researches `failed` topics again, e.g.
+/// `claudius research now --topic "AI News" --topic Rust`.
+pub fn retry_command(failed: &[FailedTopic]) -> Option<String> {
+    if failed.is_empty() {
+        return None;
+    }
+    let args: Vec<String> = failed
+        .iter()
+        .map(|f| {
+            let plain = f
+                .topic
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || "-_.".contains(c));
+            if plain {
+                return format!("--topic {}", f.topic);
+            }
+            let mut quoted = String::from("--topic \"");
+            for c in f.topic.chars() {
+                if "\\\"$`".contains(c) {
+                    quoted.push('\\');
+                }
+                quoted.push(c);
+            }
+            quoted.push('"');
+            quoted
+        })
+        .collect();
+    Some(format!("claudius research now {}", args.join(" ")))
+}
+
+/// What researching one topic took and produced.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TopicResult {
+    pub topic: String,
+    /// Tokens of the topic's research. Synthesis is shared by all topics
+    /// and not included; a topic that timed out reports 0.
+    pub tokens: u32,
+    /// Tool calls, including web searches
+    pub tool_calls: u32,
+    pub duration_ms: u64,
+    /// Positions in `cards` of the cards about this topic
+    #[serde(default)]
+    pub card_indices: Vec<usize>,
+}
+
+impl ResearchResult {
+    /// Point each topic at its cards. Call again after cards are filtered or
+    /// reordered.
+    pub fn index_topic_cards(&mut self) {
+        for topic in &mut self.topics {
+            topic.card_indices = self
+                .cards
+                .iter()
+                .enumerate()
+                .filter(|(_, card)| card.topic.trim().eq_ignore_ascii_case(topic.topic.trim()))
+                .map(|(i, _)| i)
+                .collect();
+        }
+    }
+}
+
+/// Relevance levels a card can have, lowest first.
+pub const RELEVANCE_LEVELS: [&str; 3] = ["low", "medium", "high"];
+
+/// Rank of a relevance label. Unrecognized labels rank as "medium".
+pub fn relevance_rank(relevance: &str) -> usize {
+    let relevance = relevance.trim().to_lowercase();
+    RELEVANCE_LEVELS
+        .iter()
+        .position(|level| *level == relevance)
+        .unwrap_or(1)
+}
+
+/// Split cards into those at or above `min_relevance` and those below it.
+pub fn filter_by_relevance(
+    cards: Vec<BriefingCard>,
+    min_relevance: &str,
+) -> (Vec<BriefingCard>, Vec<BriefingCard>) {
+    let min = relevance_rank(min_relevance);
+    cards
+        .into_iter()
+        .partition(|card| relevance_rank(&card.relevance) >= min)
+}
+
+/// How far back feedback counts toward card ranking.
+pub const RANK_FEEDBACK_DAYS: i64 = 180;
+
+/// Ratings a topic needs before its average counts fully; fewer ratings are
+/// pulled toward neutral.
+const RANK_FEEDBACK_PRIOR: f64 = 2.0;
+
+/// How far a topic's feedback can move its cards, in relevance levels.
+const RANK_FEEDBACK_WEIGHT: f64 = 1.5;
+
+/// A topic's weight from feedback: -1 (always rated 1) to 1 (always rated 5).
+pub fn topic_affinity(rating_sum: i64, count: i64) -> f64 {
+    let deviation = (rating_sum - 3 * count) as f64 / 2.0;
+    deviation / (count as f64 + RANK_FEEDBACK_PRIOR)
+}
+
+/// Set each card's display `rank`: higher relevance first, adjusted by the
+/// feedback on each card's topic (see [`topic_affinity`]). Ties keep the
+/// order Claude wrote the cards in.
+pub fn rank_cards(cards: &mut [BriefingCard], topic_feedback: &HashMap<String, (i64, i64)>) {
+    let score = |card: &BriefingCard| {
+        let affinity = topic_feedback
+            .get(&card.topic.trim().to_lowercase())
+            .map_or(0.0, |&(sum, count)| topic_affinity(sum, count));
+        relevance_rank(&card.relevance) as f64 + RANK_FEEDBACK_WEIGHT * affinity
+    };
+    let mut order: Vec<usize> = (0..cards.len()).collect();
+    order.sort_by(|&a, &b| score(&cards[b]).total_cmp(&score(&cards[a])));
+    for (rank, index) in order.into_iter().enumerate() {
+        cards[index].rank = Some(rank as u32);
+    }
+}
+
+/// Rank cards before saving, using the stored feedback. Without feedback
+/// (or a database), cards are ranked by relevance alone.
+pub fn rank_cards_by_feedback(cards: &mut [BriefingCard]) {
+    let feedback = crate::db::get_connection()
+        .map_err(|e| e.to_string())
+        .and_then(|conn| crate::db::get_topic_feedback(&conn, RANK_FEEDBACK_DAYS))
+        .unwrap_or_else(|e| {
+            warn!("Failed to load feedback for card ranking: {}", e);
+            HashMap::new()
+        });
+    rank_cards(cards, &feedback);
+}
+
+/// Briefing formats, each a distinct synthesis prompt and card layout.
+///
+/// - `standard`: one card per development (or one combined card when condensed)
+/// - `newsletter`: narrative cards that read like a newsletter issue
+/// - `digest`: terse bullet-point cards for skimming
+/// - `executive`: a short summary card per area with explicit action items
+pub const BRIEFING_FORMATS: [&str; 4] = ["standard", "newsletter", "digest", "executive"];
+
+/// Normalize a briefing format name, rejecting unknown formats.
+pub fn parse_briefing_format(format: &str) -> Result<String, String> {
+    let format = format.trim().to_lowercase();
+    if BRIEFING_FORMATS.contains(&format.as_str()) {
+        Ok(format)
+    } else {
+        Err(format!(
+            "Unknown briefing format '{}'. Use one of: {}",
+            format,
+            BRIEFING_FORMATS.join(", ")
+        ))
+    }
+}
+
+/// Research windows: how far back research looks for news. Users who
+/// research weekly rather than daily widen it to `1w`.
+pub const RECENCY_WINDOWS: [&str; 3] = ["24h", "72h", "1w"];
+
+/// Normalize a research window, rejecting unknown windows.
+pub fn parse_recency_window(window: &str) -> Result<String, String> {
+    let window = window.trim().to_lowercase();
+    if RECENCY_WINDOWS.contains(&window.as_str()) {
+        Ok(window)
+    } else {
+        Err(format!(
+            "Unknown research window '{}'. Use one of: {}",
+            window,
+            RECENCY_WINDOWS.join(", ")
+        ))
+    }
+}
+
+/// Days a research window covers. Unknown windows count as `24h`.
+pub fn recency_window_days(window: &str) -> u32 {
+    match window {
+        "72h" => 3,
+        "1w" => 7,
+        _ => 1,
+    }
+}
+
+/// Days within which a card's newest source must fall: `recency_days`,
+/// widened to cover the research window so the check never rejects what the
+/// prompt asked for. A `recency_days` of 0 keeps the check off.
+pub fn effective_recency_days(recency_days: u32, window: &str) -> u32 {
+    if recency_days == 0 {
+        0
+    } else {
+        recency_days.max(recency_window_days(window))
+    }
+}
+
+/// How the research prompt asks for developments within `window`.
+fn recency_window_prompt(window: &str, today: chrono::NaiveDate) -> String {
+    let since = today - chrono::Duration::days(i64::from(recency_window_days(window)));
+    match window {
+        "72h" => format!(
+            "ideally within the last 72 hours, since {}",
+            since.format("%B %d")
+        ),
+        "1w" => format!(
+            "ideally within the past week, since {}",
+            since.format("%B %d")
+        ),
+        _ => "ideally within the last 24-48 hours".to_string(),
+    }
+}
+
+/// Research depths, shallowest first.
+pub const RESEARCH_DEPTHS: [&str; 3] = ["shallow", "medium", "deep"];
+
+/// Extended thinking budget (tokens) for a research depth. `overrides` is
+/// keyed by depth; unknown depths use the "medium" budget.
+pub fn thinking_budget(depth: &str, overrides: &HashMap<String, u32>) -> u32 {
+    let depth = depth.trim().to_lowercase();
+    let budget = overrides
+        .get(&depth)
+        .copied()
+        .unwrap_or(match depth.as_str() {
+            "shallow" => 2048,
+            "deep" => 8192,
+            _ => 4096,
+        });
+    budget.max(ThinkingConfig::MIN_BUDGET)
+}
+
+/// Group topics by the briefing format they are synthesized with, keeping
+/// first-seen order. Topics without an entry in `topic_formats` use `default`.
+pub fn group_topics_by_format(
+    topics: &[String],
+    default: &str,
+    topic_formats: &HashMap<String, String>,
+) -> Vec<(String, Vec<String>)> {
+    let mut groups: Vec<(String, Vec<String>)> = Vec::new();
+    for topic in topics {
+        let format = topic_formats
+            .get(topic)
+            .map(String::as_str)
+            .unwrap_or(default)
+            .to_string();
+        match groups.iter_mut().find(|(f, _)| *f == format) {
+            Some((_, members)) => members.push(topic.clone()),
+            None => groups.push((format, vec![topic.clone()])),
+        }
+    }
+    groups
+}
+
+/// Tag stored on briefings researched from a one-off question.
+pub const ADHOC_TAG: &str = "ad-hoc";
+
+/// Longest ad-hoc question accepted (it is researched as a topic name).
+pub const ADHOC_QUERY_MAX_CHARS: usize = 500;
+
+/// Trim an ad-hoc question, rejecting empty or overly long ones.
+pub fn validate_adhoc_query(query: &str) -> Result<String, String> {
+    let query = query.split_whitespace().collect::<Vec<_>>().join(" ");
+    if query.is_empty() {
+        return Err("The question cannot be empty".to_string());
+    }
+    if query.chars().count() > ADHOC_QUERY_MAX_CHARS {
+        return Err(format!(
+            "The question is too long ({} characters, max {})",
+            query.chars().count(),
+            ADHOC_QUERY_MAX_CHARS
+        ));
+    }
+    Ok(query)
+}
+
+/// An ad-hoc question from copied text (the quick research shortcut).
+/// Whitespace is collapsed and text longer than an ad-hoc question is cut
+/// at the last word that fits.
+pub fn adhoc_query_from_text(text: &str) -> Result<String, String> {
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if text.is_empty() {
+        return Err("The clipboard has no text to research".to_string());
+    }
+    if text.chars().count() <= ADHOC_QUERY_MAX_CHARS {
+        return Ok(text);
+    }
+    let cut: String = text.chars().take(ADHOC_QUERY_MAX_CHARS + 1).collect();
+    let query = match cut.rfind(' ') {
+        Some(end) if end > 0 => &cut[..end],
+        _ => &cut[..cut.char_indices().last().map_or(0, |(i, _)| i)],
+    };
+    Ok(query.to_string())
+}
+
+/// Briefing title for an ad-hoc question, shortened to fit list views.
+pub fn adhoc_title(query: &str) -> String {
+    const MAX_CHARS: usize = 80;
+    if query.chars().count() <= MAX_CHARS {
+        return format!("Ad-hoc: {}", query);
+    }
+    let short: String = query.chars().take(MAX_CHARS - 1).collect();
+    format!("Ad-hoc: {}…", short.trim_end())
 }
 
 // ============================================================================
@@ -101,16 +485,24 @@ pub struct TopicStartedEvent {
 
 /// Event emitted when Claude is thinking/reasoning
 #[derive(Serialize, Clone)]
-#[allow(dead_code)]
 pub struct ThinkingEvent {
     timestamp: String,
     topic_name: String,
-    phase: String, // "initial_research" | "tool_calling" | "synthesis"
+    phase: String,             // "initial_research" | "tool_calling" | "synthesis"
+    reasoning: Option<String>, // Thinking summary when extended thinking is enabled
+}
+
+/// Event emitted when a tool starts executing
+#[derive(Serialize, Clone)]
+pub struct ToolStartedEvent {
+    timestamp: String,
+    topic_name: String,
+    tool_name: String,
+    tool_type: String, // "mcp" | "builtin"
 }
 
 /// Event emitted after tool execution
 #[derive(Serialize, Clone)]
-#[allow(dead_code)]
 pub struct ToolExecutedEvent {
     timestamp: String,
     topic_name: String,
@@ -163,6 +555,14 @@ pub struct SynthesisCompletedEvent {
     duration_ms: u128,
 }
 
+/// Event emitted when source URL validation completes
+#[derive(Serialize, Clone)]
+pub struct SourcesValidatedEvent {
+    timestamp: String,
+    sources_checked: usize,
+    sources_broken: usize,
+}
+
 /// Event emitted when research is cancelled
 #[derive(Serialize, Clone)]
 pub struct CancelledEvent {
@@ -182,6 +582,25 @@ pub struct HeartbeatEvent {
     pub message: String,
 }
 
+/// Event emitted when a Firecrawl budget crosses its warning threshold or is used up
+#[derive(Serialize, Clone)]
+pub struct FirecrawlBudgetEvent {
+    pub timestamp: String,
+    #[serde(flatten)]
+    pub warning: BudgetWarning,
+    pub message: String,
+}
+
+/// Event emitted when a topic is aborted by the per-topic timeout
+#[derive(Serialize, Clone)]
+pub struct TopicTimeoutEvent {
+    pub timestamp: String,
+    pub topic_name: String,
+    pub topic_index: usize,
+    pub timeout_secs: u64,
+    pub has_partial_findings: bool,
+}
+
 /// Event emitted when Claude uses built-in web search
 #[derive(Serialize, Clone)]
 pub struct WebSearchEvent {
@@ -196,96 +615,6 @@ fn get_timestamp() -> String {
     chrono::Utc::now().to_rfc3339()
 }
 
-// ============================================================================
-// Anthropic API Types with Tool Support
-// ============================================================================
-
-/// Tool definition for Anthropic API.
-#[derive(Debug, Clone, Serialize)]
-struct Tool {
-    name: String,
-    description: String,
-    input_schema: serde_json::Value,
-}
-
-/// Anthropic API message request with tools.
-/// Note: `tools` uses serde_json::Value to support both regular tools and server tools (like web_search)
-#[derive(Debug, Serialize)]
-struct AnthropicRequest {
-    model: String,
-    max_tokens: u32,
-    messages: Vec<Message>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    tools: Option<Vec<serde_json::Value>>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    system: Option<String>,
-}
-
-/// A message in the conversation.
-#[derive(Debug, Clone, Serialize, Deserialize)]
-struct Message {
-    role: String,
-    content: MessageContent,
-}
-
-/// Message content can be a string or array of content blocks.
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(untagged)]
-enum MessageContent {
-    Text(String),
-    Blocks(Vec<ContentBlock>),
-}
-
-/// A content block in a message.
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(tag = "type")]
-enum ContentBlock {
-    #[serde(rename = "text")]
-    Text { text: String },
-    #[serde(rename = "tool_use")]
-    ToolUse {
-        id: String,
-        name: String,
-        input: serde_json::Value,
-    },
-    #[serde(rename = "tool_result")]
-    ToolResult {
-        tool_use_id: String,
-        content: String,
-        #[serde(skip_serializing_if = "Option::is_none")]
-        is_error: Option<bool>,
-    },
-}
-
-/// Anthropic API response.
-#[derive(Debug, Deserialize)]
-struct AnthropicResponse {
-    content: Vec<ResponseContentBlock>,
-    usage: Usage,
-    stop_reason: Option<String>,
-}
-
-/// Content block in API response (slightly different structure for deserialization).
-#[derive(Debug, Deserialize)]
-struct ResponseContentBlock {
-    #[serde(rename = "type")]
-    content_type: String,
-    #[serde(default)]
-    text: Option<String>,
-    #[serde(default)]
-    id: Option<String>,
-    #[serde(default)]
-    name: Option<String>,
-    #[serde(default)]
-    input: Option<serde_json::Value>,
-}
-
-#[derive(Debug, Deserialize)]
-struct Usage {
-    input_tokens: u32,
-    output_tokens: u32,
-}
-
 /// Response from Claude for briefing cards.
 #[derive(Debug, Deserialize)]
 struct BriefingResponse {
@@ -293,326 +622,204 @@ struct BriefingResponse {
 }
 
 // ============================================================================
-// Tool Definitions
-// ============================================================================
-
-fn get_research_tools() -> Vec<Tool> {
-    vec![
-        Tool {
-            name: "get_github_activity".to_string(),
-            description: "Get recent activity from a GitHub repository including recent commits, PRs, and issues. Use this when researching topics related to open source projects or specific GitHub repositories.".to_string(),
-            input_schema: json!({
-                "type": "object",
-                "properties": {
-                    "owner": {
-                        "type": "string",
-                        "description": "The GitHub repository owner (username or organization)"
-                    },
-                    "repo": {
-                        "type": "string",
-                        "description": "The GitHub repository name"
-                    },
-                    "activity_type": {
-                        "type": "string",
-                        "enum": ["commits", "pulls", "issues", "releases"],
-                        "description": "Type of activity to fetch"
-                    }
-                },
-                "required": ["owner", "repo", "activity_type"]
-            }),
-        },
-        Tool {
-            name: "fetch_webpage".to_string(),
-            description: "Fetch and extract text content from a webpage URL. Use this to get current information from news sites, documentation, or other web sources.".to_string(),
-            input_schema: json!({
-                "type": "object",
-                "properties": {
-                    "url": {
-                        "type": "string",
-                        "description": "The URL of the webpage to fetch"
-                    }
-                },
-                "required": ["url"]
-            }),
-        },
-    ]
-}
-
-// ============================================================================
-// Tool Execution
+// Offline Content Cache
 // ============================================================================
 
-/// Execute a tool and return the result.
-async fn execute_tool(
-    client: &Client,
-    tool_name: &str,
-    input: &serde_json::Value,
-    github_token: Option<&str>,
-) -> Result<String, String> {
-    match tool_name {
-        "get_github_activity" => {
-            let owner = input
-                .get("owner")
-                .and_then(|v| v.as_str())
-                .ok_or("Missing owner")?;
-            let repo = input
-                .get("repo")
-                .and_then(|v| v.as_str())
-                .ok_or("Missing repo")?;
-            let activity_type = input
-                .get("activity_type")
-                .and_then(|v| v.as_str())
-                .ok_or("Missing activity_type")?;
-
-            execute_github_activity(client, owner, repo, activity_type, github_token).await
-        }
-        "fetch_webpage" => {
-            let url = input
-                .get("url")
-                .and_then(|v| v.as_str())
-                .ok_or("Missing url")?;
-            execute_fetch_webpage(client, url).await
-        }
-        _ => Err(format!("Unknown tool: {}", tool_name)),
-    }
-}
-
-/// Fetch GitHub activity (commits, PRs, issues, or releases).
-async fn execute_github_activity(
-    client: &Client,
-    owner: &str,
-    repo: &str,
-    activity_type: &str,
-    github_token: Option<&str>,
-) -> Result<String, String> {
-    let endpoint = match activity_type {
-        "commits" => format!(
-            "https://api.github.com/repos/{}/{}/commits?per_page=10",
-            owner, repo
-        ),
-        "pulls" => format!(
-            "https://api.github.com/repos/{}/{}/pulls?state=all&per_page=10",
-            owner, repo
-        ),
-        "issues" => format!(
-            "https://api.github.com/repos/{}/{}/issues?state=all&per_page=10",
-            owner, repo
-        ),
-        "releases" => format!(
-            "https://api.github.com/repos/{}/{}/releases?per_page=5",
-            owner, repo
-        ),
-        _ => return Err(format!("Unknown activity type: {}", activity_type)),
-    };
-
-    let mut request = client
-        .get(&endpoint)
-        .header("User-Agent", "Claudius-Research-Agent")
-        .header("Accept", "application/vnd.github.v3+json");
-
-    if let Some(token) = github_token {
-        request = request.header("Authorization", format!("Bearer {}", token));
-    }
-
-    let response = request
-        .send()
-        .await
-        .map_err(|e| format!("GitHub API request failed: {}", e))?;
-
-    if !response.status().is_success() {
-        let status = response.status();
-        let body = response.text().await.unwrap_or_default();
-        return Err(format!("GitHub API error {}: {}", status, body));
+/// Save fetched content for offline runs. Failures are logged and ignored.
+fn store_cached_content(kind: &str, key: &str, topic: &str, content: &str) {
+    let result = crate::db::get_connection()
+        .map_err(|e| e.to_string())
+        .and_then(|conn| crate::db::upsert_cached_content(&conn, kind, key, Some(topic), content));
+    if let Err(e) = result {
+        debug!("Failed to cache content for {}: {}", key, e);
     }
-
-    let data: serde_json::Value = response
-        .json()
-        .await
-        .map_err(|e| format!("Failed to parse GitHub response: {}", e))?;
-
-    // Format the response based on activity type
-    let formatted = match activity_type {
-        "commits" => format_github_commits(&data),
-        "pulls" => format_github_pulls(&data),
-        "issues" => format_github_issues(&data),
-        "releases" => format_github_releases(&data),
-        _ => data.to_string(),
-    };
-
-    Ok(formatted)
 }
 
-fn format_github_commits(data: &serde_json::Value) -> String {
-    let commits = data.as_array().map(|arr| {
-        arr.iter()
-            .take(10)
-            .filter_map(|c| {
-                let sha = c.get("sha")?.as_str()?.get(..7)?;
-                let message = c.get("commit")?.get("message")?.as_str()?;
-                let author = c.get("commit")?.get("author")?.get("name")?.as_str()?;
-                let date = c.get("commit")?.get("author")?.get("date")?.as_str()?;
-                Some(format!(
-                    "- {} by {} ({}): {}",
-                    sha,
-                    author,
-                    &date[..10],
-                    message.lines().next().unwrap_or("")
-                ))
-            })
-            .collect::<Vec<_>>()
-            .join("\n")
-    });
-    commits.unwrap_or_else(|| "No commits found".to_string())
-}
-
-fn format_github_pulls(data: &serde_json::Value) -> String {
-    let pulls = data.as_array().map(|arr| {
-        arr.iter()
-            .take(10)
-            .filter_map(|p| {
-                let number = p.get("number")?.as_i64()?;
-                let title = p.get("title")?.as_str()?;
-                let state = p.get("state")?.as_str()?;
-                let user = p.get("user")?.get("login")?.as_str()?;
-                Some(format!("- #{} [{}] by {}: {}", number, state, user, title))
-            })
-            .collect::<Vec<_>>()
-            .join("\n")
-    });
-    pulls.unwrap_or_else(|| "No pull requests found".to_string())
+/// Serve a built-in tool call from the content cache (offline mode).
+fn execute_tool_offline(tool_name: &str, input: &serde_json::Value) -> Result<String, String> {
+    let (kind, key) = tools::cache_key(tool_name, input)
+        .ok_or_else(|| format!("Tool '{}' is not available in offline mode", tool_name))?;
+    let conn =
+        crate::db::get_connection().map_err(|e| format!("Failed to open database: {}", e))?;
+    crate::db::get_cached_content(&conn, kind, &key, CONTENT_CACHE_MAX_AGE_DAYS * 24)?
+        .map(|cached| cached.content)
+        .ok_or_else(|| {
+            format!(
+                "Offline mode: no cached content for {}. Work from the cached content in the prompt instead.",
+                key
+            )
+        })
 }
 
-fn format_github_issues(data: &serde_json::Value) -> String {
-    let issues = data.as_array().map(|arr| {
-        arr.iter()
-            .take(10)
-            .filter_map(|i| {
-                let number = i.get("number")?.as_i64()?;
-                let title = i.get("title")?.as_str()?;
-                let state = i.get("state")?.as_str()?;
-                let user = i.get("user")?.get("login")?.as_str()?;
-                Some(format!("- #{} [{}] by {}: {}", number, state, user, title))
-            })
-            .collect::<Vec<_>>()
-            .join("\n")
-    });
-    issues.unwrap_or_else(|| "No issues found".to_string())
-}
+/// Prompt section listing recently cached content for a topic (offline mode).
+fn offline_context_prompt(topic: &str) -> String {
+    let cached = crate::db::get_connection()
+        .map_err(|e| e.to_string())
+        .and_then(|conn| {
+            crate::db::get_cached_content_for_topic(
+                &conn,
+                topic,
+                CONTENT_CACHE_MAX_AGE_DAYS * 24,
+                OFFLINE_MAX_CACHED_ITEMS,
+            )
+        })
+        .unwrap_or_else(|e| {
+            warn!("Failed to load cached content for '{}': {}", topic, e);
+            Vec::new()
+        });
 
-fn format_github_releases(data: &serde_json::Value) -> String {
-    let releases = data.as_array().map(|arr| {
-        arr.iter()
-            .take(5)
-            .filter_map(|r| {
-                let tag = r.get("tag_name")?.as_str()?;
-                let name = r.get("name")?.as_str().unwrap_or(tag);
-                let date = r.get("published_at")?.as_str()?;
-                let prerelease = r.get("prerelease")?.as_bool().unwrap_or(false);
-                let suffix = if prerelease { " (prerelease)" } else { "" };
-                Some(format!("- {} - {}{} ({})", tag, name, suffix, &date[..10]))
-            })
-            .collect::<Vec<_>>()
-            .join("\n")
-    });
-    releases.unwrap_or_else(|| "No releases found".to_string())
+    format_offline_context(&cached)
 }
 
-/// Fetch and extract text content from a webpage.
-async fn execute_fetch_webpage(client: &Client, url: &str) -> Result<String, String> {
-    // Validate URL
-    if !url.starts_with("http://") && !url.starts_with("https://") {
-        return Err("URL must start with http:// or https://".to_string());
+fn format_offline_context(cached: &[crate::db::CachedContent]) -> String {
+    if cached.is_empty() {
+        return "\n\nOFFLINE MODE: No cached content is available for this topic and live tools cannot be used. \
+                Work from what you know and state clearly that current sources could not be checked."
+            .to_string();
     }
 
-    let response = client
-        .get(url)
-        .header("User-Agent", "Claudius-Research-Agent")
-        .send()
-        .await
-        .map_err(|e| format!("Failed to fetch URL: {}", e))?;
-
-    if !response.status().is_success() {
-        return Err(format!("HTTP error: {}", response.status()));
-    }
+    let sections: Vec<String> = cached
+        .iter()
+        .map(|item| {
+            let excerpt: String = item
+                .content
+                .chars()
+                .take(OFFLINE_CACHED_ITEM_MAX_CHARS)
+                .collect();
+            format!(
+                "### {} (fetched {})\n{}",
+                item.cache_key, item.fetched_at, excerpt
+            )
+        })
+        .collect();
 
-    let html = response
-        .text()
-        .await
-        .map_err(|e| format!("Failed to read response: {}", e))?;
+    format!(
+        "\n\nCACHED CONTENT (offline mode - fetched during earlier runs; live tools cannot be used, so work from this):\n{}",
+        sections.join("\n\n")
+    )
+}
 
-    // Extract text content from HTML (simple extraction)
-    let text = extract_text_from_html(&html);
+// ============================================================================
+// Preferred Sources
+// ============================================================================
 
-    // Truncate if too long (use character count, not byte index to avoid UTF-8 panic)
-    let max_chars = 8000;
-    let char_count = text.chars().count();
-    if char_count > max_chars {
-        let truncated: String = text.chars().take(max_chars).collect();
-        Ok(format!(
-            "{}...\n\n[Content truncated, {} total characters]",
-            truncated, char_count
-        ))
+/// Turn a preferred source into a fetchable URL.
+/// Accepts full URLs and bare domains ("lobste.rs"); plain names ("Rust blog") return None.
+fn preferred_source_url(source: &str) -> Option<String> {
+    let source = source.trim();
+    if source.starts_with("http://") || source.starts_with("https://") {
+        return Some(source.to_string());
+    }
+    let looks_like_domain = source.contains('.')
+        && !source.contains(char::is_whitespace)
+        && !source.starts_with('.')
+        && !source.ends_with('.');
+    if looks_like_domain {
+        Some(format!("https://{}", source))
     } else {
-        Ok(text)
+        None
     }
 }
 
-/// Simple HTML text extraction (removes tags, scripts, styles).
-fn extract_text_from_html(html: &str) -> String {
-    // Remove script and style tags with content
-    let without_scripts = Regex::new(r"(?is)<script[^>]*>.*?</script>")
-        .ok()
-        .map(|re| re.replace_all(html, "").to_string())
-        .unwrap_or_else(|| html.to_string());
-
-    let without_styles = Regex::new(r"(?is)<style[^>]*>.*?</style>")
-        .ok()
-        .map(|re| re.replace_all(&without_scripts, "").to_string())
-        .unwrap_or_else(|| without_scripts.clone());
-
-    // Remove HTML tags
-    let without_tags = Regex::new(r"<[^>]+>")
-        .ok()
-        .map(|re| re.replace_all(&without_styles, " ").to_string())
-        .unwrap_or_else(|| without_styles.clone());
-
-    // Decode common HTML entities
-    let decoded = without_tags
-        .replace("&nbsp;", " ")
-        .replace("&amp;", "&")
-        .replace("&lt;", "<")
-        .replace("&gt;", ">")
-        .replace("&quot;", "\"")
-        .replace("&#39;", "'");
-
-    // Clean up whitespace
-    Regex::new(r"\s+")
-        .ok()
-        .map(|re| re.replace_all(&decoded, " ").trim().to_string())
-        .unwrap_or_else(|| decoded.trim().to_string())
+/// Format a topic's preferred sources for the research system prompt.
+fn format_preferred_sources_prompt(sources: &[String]) -> String {
+    let list: Vec<String> = sources.iter().map(|s| format!("- {}", s)).collect();
+    format!(
+        "PREFERRED SOURCES (the user trusts these for this topic - check them first and cite them when relevant):\n{}",
+        list.join("\n")
+    )
 }
 
 // ============================================================================
 // Research Agent
 // ============================================================================
 
+/// A topic's research conversation with Claude. Carried across turns, so a
+/// topic started in a batch can be finished interactively.
+struct TopicConversation {
+    /// Topic being researched, for per-topic tool routing
+    topic: String,
+    system_prompt: String,
+    messages: Vec<Message>,
+    total_tokens: u32,
+    /// Tool calls so far, including web searches
+    tool_calls: u32,
+    /// Turns taken so far (capped at `MAX_TOOL_ITERATIONS`)
+    iterations: usize,
+}
+
+impl TopicConversation {
+    fn new(topic: String, system_prompt: String, user_prompt: String) -> Self {
+        Self {
+            topic,
+            system_prompt,
+            messages: vec![Message::user(user_prompt)],
+            total_tokens: 0,
+            tool_calls: 0,
+            iterations: 0,
+        }
+    }
+}
+
 /// Research agent that calls Anthropic API with tool support.
 pub struct ResearchAgent {
     client: Client,
     api_key: String,
+    /// Anthropic API base URL and extra headers
+    endpoint: ApiEndpoint,
     model: String,
-    github_token: Option<String>,
+    /// Built-in tools (web, GitHub, news) shared with chat
+    tools: BuiltinTools,
     mcp_client: Option<McpClient>,
     /// Names of built-in tools (to differentiate from MCP tools)
     builtin_tools: HashSet<String>,
+    /// Tools turned off in the `disabled_tools` setting
+    tool_filter: ToolFilter,
     /// Cancellation token for aborting research
     cancellation_token: Option<Arc<AtomicBool>>,
     /// Enable Claude's built-in web search ($0.01/search)
     enable_web_search: bool,
-    /// Research mode: "standard" or "firecrawl"
-    research_mode: String,
+    /// Research mode deciding tools, tool instructions and card length
+    mode: &'static dyn ResearchMode,
     /// Limit firecrawl_agent to 5 calls/day (free tier)
     rate_limit_firecrawl_agent: bool,
+    /// Per-run and monthly Firecrawl call budgets
+    firecrawl_budget: FirecrawlBudget,
+    /// MCP servers and tools bound to specific topics
+    mcp_routing: McpRouting,
+    /// Budget warnings raised during the run, for notifications and the CLI
+    budget_warnings: Vec<BudgetWarning>,
+    archive_sources: bool,
+    preferred_sources: HashMap<String, Vec<String>>, // Topic name -> preferred sites
+    /// Wall-clock limit per topic before it is skipped
+    topic_timeout: Duration,
+    /// Tool results and notes gathered for the current topic, kept if it times out
+    partial_findings: Vec<String>,
+    /// Research from the content cache only (no MCP, web search or fetches)
+    offline: bool,
+    /// Maximum characters of an MCP tool result passed to Claude
+    result_max_chars: usize,
+    /// Per-tool overrides of `result_max_chars` (keyed by tool name)
+    tool_result_limits: HashMap<String, usize>,
+    /// Briefing format used for synthesis (see `BRIEFING_FORMATS`)
+    briefing_format: String,
+    /// Per-topic format overrides (keyed by topic name)
+    topic_formats: HashMap<String, String>,
+    /// Freeform description of the user, added to research and synthesis prompts
+    about_me: String,
+    /// Extended thinking budget per research step (None = thinking off)
+    thinking_budget: Option<u32>,
+    /// Research through the Batch API, falling back to interactive requests
+    /// for topics not finished within this long (None = interactive only)
+    batch_deadline: Option<Duration>,
+    /// Days within which a card's newest source must fall (0 = no recency check)
+    source_recency_days: u32,
+    /// How far back research looks for news (see `RECENCY_WINDOWS`)
+    recency_window: String,
+    /// Per-topic research window overrides (keyed by topic name)
+    topic_recency_windows: HashMap<String, String>,
+    /// Send cards failing source checks back to Claude once before saving
+    repair_weak_cards: bool,
 }
 
 impl ResearchAgent {
@@ -624,23 +831,12 @@ impl ResearchAgent {
         research_mode: String,
         rate_limit_firecrawl_agent: bool,
     ) -> Self {
-        // Try to read GitHub token from environment or config
-        let github_token = std::env::var("GITHUB_TOKEN").ok().or_else(|| {
-            // Try to read from ~/.claudius/.env
-            let home = dirs::home_dir()?;
-            let env_path = home.join(".claudius").join(".env");
-            let content = std::fs::read_to_string(env_path).ok()?;
-            content
-                .lines()
-                .find(|line| line.starts_with("GITHUB_TOKEN="))
-                .map(|line| line.trim_start_matches("GITHUB_TOKEN=").trim().to_string())
-        });
+        // GitHub token comes from the environment or config
+        let tools = BuiltinTools::from_env();
 
         // Track built-in tool names
-        let builtin_tools: HashSet<String> = get_research_tools()
-            .iter()
-            .map(|t| t.name.clone())
-            .collect();
+        let builtin_tools: HashSet<String> =
+            tools.definitions().into_iter().map(|t| t.name).collect();
 
         if enable_web_search {
             tracing::info!(
@@ -649,21 +845,42 @@ impl ResearchAgent {
         }
 
         Self {
-            client: Client::builder()
+            client: crate::http_client::client_builder()
                 .timeout(Duration::from_secs(180)) // 3 min total request timeout
                 .connect_timeout(Duration::from_secs(30)) // 30s to establish connection
                 .pool_idle_timeout(Duration::from_secs(60)) // Close idle connections after 60s
                 .build()
                 .expect("Failed to build HTTP client"),
             api_key,
+            endpoint: ApiEndpoint::current(),
             model: model.unwrap_or_else(|| "claude-haiku-4-5-20251001".to_string()),
-            github_token,
+            tools,
             mcp_client: None,
             builtin_tools,
+            tool_filter: ToolFilter::default(),
             cancellation_token: None,
             enable_web_search,
-            research_mode,
+            mode: research_mode::find_or_standard(&research_mode),
             rate_limit_firecrawl_agent,
+            firecrawl_budget: FirecrawlBudget::default(),
+            mcp_routing: McpRouting::default(),
+            budget_warnings: Vec::new(),
+            archive_sources: false,
+            preferred_sources: HashMap::new(),
+            topic_timeout: DEFAULT_TOPIC_TIMEOUT,
+            partial_findings: Vec::new(),
+            offline: false,
+            result_max_chars: DEFAULT_RESULT_MAX_CHARS,
+            tool_result_limits: HashMap::new(),
+            briefing_format: "standard".to_string(),
+            topic_formats: HashMap::new(),
+            about_me: String::new(),
+            thinking_budget: None,
+            batch_deadline: None,
+            source_recency_days: 0,
+            repair_weak_cards: false,
+            recency_window: "24h".to_string(),
+            topic_recency_windows: HashMap::new(),
         }
     }
 
@@ -672,49 +889,176 @@ impl ResearchAgent {
         self.cancellation_token = Some(token);
     }
 
-    /// Check if cancellation has been requested
-    fn check_cancellation(&self) -> Result<(), String> {
-        if let Some(ref token) = self.cancellation_token {
-            if token.load(Ordering::Relaxed) {
-                return Err("Research cancelled by user".to_string());
-            }
-        }
-        Ok(())
+    /// Save a text snapshot of each source during validation
+    pub fn set_archive_sources(&mut self, archive: bool) {
+        self.archive_sources = archive;
     }
 
-    /// Check cancellation and emit cancelled event if cancelled
-    fn check_cancellation_with_event(
-        &self,
-        app_handle: Option<&tauri::AppHandle>,
-        phase: &str,
-        topics_completed: usize,
-        total_topics: usize,
-    ) -> Result<(), String> {
-        if let Some(ref token) = self.cancellation_token {
-            if token.load(Ordering::Relaxed) {
-                // Emit cancelled event
-                if let Some(app) = app_handle {
-                    let _ = app.emit(
-                        "research:cancelled",
-                        CancelledEvent {
-                            timestamp: get_timestamp(),
-                            reason: "User cancelled research".to_string(),
-                            phase: phase.to_string(),
-                            topics_completed,
-                            total_topics,
-                        },
-                    );
-                }
-                return Err("Research cancelled by user".to_string());
-            }
+    /// Set preferred sources per topic (keyed by topic name)
+    pub fn set_preferred_sources(&mut self, sources: HashMap<String, Vec<String>>) {
+        self.preferred_sources = sources;
+    }
+
+    /// Set the wall-clock limit for researching a single topic
+    pub fn set_topic_timeout(&mut self, timeout: Duration) {
+        self.topic_timeout = timeout;
+    }
+
+    /// Research from previously cached content only. MCP servers, web search,
+    /// page fetches and GitHub requests are skipped; the Anthropic API is still used.
+    pub fn set_offline_mode(&mut self, offline: bool) {
+        self.offline = offline;
+        if offline {
+            self.enable_web_search = false;
         }
-        Ok(())
     }
 
-    /// Initialize MCP connections to configured servers.
-    pub async fn init_mcp(&mut self) -> Result<(), String> {
-        match load_mcp_servers() {
-            Ok(servers) => {
+    /// Set the size limit for MCP tool results, with optional per-tool overrides
+    pub fn set_tool_result_limits(&mut self, max_chars: usize, per_tool: HashMap<String, usize>) {
+        self.result_max_chars = max_chars;
+        self.tool_result_limits = per_tool;
+    }
+
+    /// Set the briefing format, with optional per-topic overrides. Topics
+    /// sharing a format are synthesized together.
+    pub fn set_briefing_format(&mut self, format: String, per_topic: HashMap<String, String>) {
+        self.briefing_format = format;
+        self.topic_formats = per_topic;
+    }
+
+    /// Describe the user (role, expertise, preferences) so relevance and framing fit them
+    pub fn set_about_me(&mut self, about_me: String) {
+        self.about_me = about_me;
+    }
+
+    /// Let Claude think before each research step, up to `budget` tokens (None = off)
+    pub fn set_thinking_budget(&mut self, budget: Option<u32>) {
+        self.thinking_budget = budget;
+    }
+
+    /// Research through the Message Batches API (half price, slower). Topics
+    /// the batches haven't finished within `deadline` are researched
+    /// interactively. Ignored in offline mode.
+    pub fn set_batch_deadline(&mut self, deadline: Option<Duration>) {
+        self.batch_deadline = deadline;
+    }
+
+    /// Require every card to cite sources, the newest within `recency_days`
+    /// (0 = any age). With `repair` set, failing cards get one repair round
+    /// before they are saved with a `source_warning`.
+    pub fn set_source_requirements(&mut self, recency_days: u32, repair: bool) {
+        self.source_recency_days = recency_days;
+        self.repair_weak_cards = repair;
+    }
+
+    /// Set how far back research looks for news, with optional per-topic
+    /// overrides. The source recency check widens to match.
+    pub fn set_recency_window(&mut self, window: String, per_topic: HashMap<String, String>) {
+        self.recency_window = window;
+        self.topic_recency_windows = per_topic;
+    }
+
+    /// The research window for `topic`.
+    fn recency_window_for(&self, topic: &str) -> &str {
+        self.topic_recency_windows
+            .get(topic)
+            .unwrap_or(&self.recency_window)
+    }
+
+    /// Days within which the newest source of a card on `topic` must fall.
+    fn card_recency_days(&self, topic: &str) -> u32 {
+        effective_recency_days(self.source_recency_days, self.recency_window_for(topic))
+    }
+
+    /// Leave the tools `filter` disables out of research.
+    pub fn set_tool_filter(&mut self, filter: ToolFilter) {
+        self.tools = self.tools.clone().with_filter(filter.clone());
+        self.builtin_tools = self
+            .tools
+            .definitions()
+            .into_iter()
+            .map(|t| t.name)
+            .collect();
+        self.tool_filter = filter;
+    }
+
+    /// Limit Firecrawl tool calls; Firecrawl tools are withdrawn once a budget is used up.
+    pub fn set_firecrawl_budget(&mut self, budget: FirecrawlBudget) {
+        self.firecrawl_budget = budget;
+    }
+
+    /// Offer MCP servers bound to topics only while researching those topics.
+    pub fn set_mcp_routing(&mut self, routing: McpRouting) {
+        self.mcp_routing = routing;
+    }
+
+    /// Firecrawl budget warnings raised by the last run.
+    pub fn budget_warnings(&self) -> &[BudgetWarning] {
+        &self.budget_warnings
+    }
+
+    /// Record a budget warning and tell the app about it.
+    fn raise_budget_warning(
+        &mut self,
+        warning: BudgetWarning,
+        app_handle: Option<&tauri::AppHandle>,
+    ) {
+        let message = warning.message();
+        warn!("{}", message);
+        research_state::publish(
+            app_handle,
+            "research:firecrawl_budget",
+            FirecrawlBudgetEvent {
+                timestamp: get_timestamp(),
+                warning: warning.clone(),
+                message,
+            },
+        );
+        self.budget_warnings.push(warning);
+    }
+
+    /// Check if cancellation has been requested
+    fn check_cancellation(&self) -> Result<(), String> {
+        if let Some(ref token) = self.cancellation_token {
+            if token.load(Ordering::Relaxed) {
+                return Err("Research cancelled by user".to_string());
+            }
+        }
+        Ok(())
+    }
+
+    /// Check cancellation and emit cancelled event if cancelled
+    fn check_cancellation_with_event(
+        &self,
+        app_handle: Option<&tauri::AppHandle>,
+        phase: &str,
+        topics_completed: usize,
+        total_topics: usize,
+    ) -> Result<(), String> {
+        if let Some(ref token) = self.cancellation_token {
+            if token.load(Ordering::Relaxed) {
+                // Emit cancelled event
+                research_state::publish(
+                    app_handle,
+                    "research:cancelled",
+                    CancelledEvent {
+                        timestamp: get_timestamp(),
+                        reason: "User cancelled research".to_string(),
+                        phase: phase.to_string(),
+                        topics_completed,
+                        total_topics,
+                    },
+                );
+                return Err("Research cancelled by user".to_string());
+            }
+        }
+        Ok(())
+    }
+
+    /// Initialize MCP connections to configured servers.
+    pub async fn init_mcp(&mut self) -> Result<(), String> {
+        match load_mcp_servers() {
+            Ok(servers) => {
                 let enabled_count = servers.iter().filter(|s| s.enabled).count();
                 if enabled_count == 0 {
                     info!("No enabled MCP servers configured");
@@ -747,35 +1091,25 @@ impl ResearchAgent {
         }
     }
 
-    /// Get all available tools (built-in + MCP), filtered by research_mode.
-    fn get_all_tools(&self) -> Vec<Tool> {
+    /// Get all available tools (built-in + MCP), filtered by the research mode
+    /// and, for a topic, by the topic's MCP routing.
+    fn get_all_tools(&self, topic: Option<&str>) -> Vec<ToolDefinition> {
         let mut tools = Vec::new();
 
-        // Firecrawl tool names to filter
-        let firecrawl_tools = [
-            "firecrawl_search",
-            "firecrawl_scrape",
-            "firecrawl_extract",
-            "firecrawl_map",
-            "firecrawl_crawl",
-        ];
-        // Standard search tools to exclude in firecrawl mode
-        let standard_search_tools = [
-            "brave_search",
-            "brave_web_search",
-            "perplexity_ask",
-            "fetch_webpage",
-        ];
         // Expensive tools to always exclude (firecrawl_agent uses 100s of credits per call)
         let expensive_tools = ["firecrawl_agent"];
 
+        // Firecrawl tools are withdrawn once a Firecrawl budget is used up
+        let firecrawl_exhausted = self.firecrawl_budget.exhausted().is_some();
+
         // Add built-in tools (filtered by mode)
-        for tool in get_research_tools() {
-            // In firecrawl mode, exclude the built-in fetch_webpage
-            if self.research_mode == "firecrawl"
-                && standard_search_tools.contains(&tool.name.as_str())
-            {
-                tracing::debug!("Excluding built-in tool '{}' in firecrawl mode", tool.name);
+        for tool in self.tools.definitions() {
+            if !self.mode.allows_tool(None, &tool.name) {
+                tracing::debug!(
+                    "Excluding built-in tool '{}' in {} mode",
+                    tool.name,
+                    self.mode.name()
+                );
                 continue;
             }
             tools.push(tool);
@@ -786,6 +1120,35 @@ impl ResearchAgent {
             for mcp_tool in mcp_client.get_all_tools() {
                 let tool_name = &mcp_tool.tool.name;
 
+                if !self
+                    .tool_filter
+                    .allows(Some(&mcp_tool.server_name), tool_name)
+                {
+                    tracing::debug!(
+                        "Excluding tool '{}' from {} (disabled in settings)",
+                        tool_name,
+                        mcp_tool.server_name
+                    );
+                    continue;
+                }
+
+                if !self
+                    .mcp_routing
+                    .allows(topic, &mcp_tool.server_name, tool_name)
+                {
+                    tracing::debug!(
+                        "Excluding tool '{}' from {} (not routed to this topic)",
+                        tool_name,
+                        mcp_tool.server_name
+                    );
+                    continue;
+                }
+
+                if firecrawl_exhausted && firecrawl_budget::is_firecrawl_tool(tool_name) {
+                    tracing::debug!("Excluding tool '{}' (Firecrawl budget used up)", tool_name);
+                    continue;
+                }
+
                 // Always exclude expensive tools
                 if expensive_tools.iter().any(|et| tool_name.contains(et)) {
                     tracing::debug!(
@@ -795,29 +1158,20 @@ impl ResearchAgent {
                     continue;
                 }
 
-                // Filter based on research mode
-                let dominated_by_firecrawl =
-                    firecrawl_tools.iter().any(|ft| tool_name.contains(ft));
-                let is_standard_search = standard_search_tools
-                    .iter()
-                    .any(|st| tool_name.contains(st));
-
-                if self.research_mode == "firecrawl" {
-                    // In firecrawl mode, exclude standard search tools
-                    if is_standard_search {
-                        tracing::debug!("Excluding tool '{}' in firecrawl mode", tool_name);
-                        continue;
-                    }
-                } else {
-                    // In standard mode, exclude firecrawl tools
-                    if dominated_by_firecrawl {
-                        tracing::debug!("Excluding tool '{}' in standard mode", tool_name);
-                        continue;
-                    }
+                if !self
+                    .mode
+                    .allows_tool(Some(&mcp_tool.server_name), tool_name)
+                {
+                    tracing::debug!(
+                        "Excluding tool '{}' in {} mode",
+                        tool_name,
+                        self.mode.name()
+                    );
+                    continue;
                 }
 
-                tools.push(Tool {
-                    name: mcp_tool.tool.name.clone(),
+                tools.push(ToolDefinition {
+                    name: mcp_tool.namespaced_name(),
                     description: mcp_tool.tool.description.clone().unwrap_or_else(|| {
                         format!("Tool from {} MCP server", mcp_tool.server_name)
                     }),
@@ -828,28 +1182,23 @@ impl ResearchAgent {
 
         tracing::info!(
             "Research mode '{}': {} tools available",
-            self.research_mode,
+            self.mode.name(),
             tools.len()
         );
         tools
     }
 
     /// Get all tools as JSON values for API request, including web_search if enabled.
-    fn get_tools_json(&self) -> Vec<serde_json::Value> {
-        let tools = self.get_all_tools();
-        let mut tools_json: Vec<serde_json::Value> = tools
-            .iter()
-            .map(|t| {
-                serde_json::json!({
-                    "name": t.name,
-                    "description": t.description,
-                    "input_schema": t.input_schema
-                })
-            })
-            .collect();
+    fn get_tools_json(&self, topic: Option<&str>) -> Vec<serde_json::Value> {
+        let tools = self.get_all_tools(topic);
+        let mut tools_json: Vec<serde_json::Value> =
+            tools.iter().map(ToolDefinition::to_json).collect();
 
         // Add Claude's built-in web search tool if enabled
-        if self.enable_web_search {
+        if self.enable_web_search
+            && self.tool_filter.allows(None, "web_search")
+            && self.mode.allows_tool(None, "web_search")
+        {
             tools_json.push(serde_json::json!({
                 "type": WEB_SEARCH_TOOL_TYPE,
                 "name": "web_search",
@@ -867,12 +1216,33 @@ impl ResearchAgent {
     }
 
     /// Run research on the given topics and generate a briefing.
+    ///
+    /// The run executes inside a `research_run` span so every record it writes
+    /// to the research log carries the same `run_id`, which is also returned
+    /// in the result.
     pub async fn run_research(
         &mut self,
         topics: Vec<String>,
         app_handle: Option<tauri::AppHandle>,
         condense_briefings: bool,
         past_cards_context: Option<String>,
+    ) -> Result<ResearchResult, String> {
+        let run_id = uuid::Uuid::new_v4();
+        let span = tracing::info_span!("research_run", run_id = %run_id);
+        let mut result = self
+            .run_research_inner(topics, app_handle, condense_briefings, past_cards_context)
+            .instrument(span)
+            .await?;
+        result.run_id = run_id.to_string();
+        Ok(result)
+    }
+
+    async fn run_research_inner(
+        &mut self,
+        topics: Vec<String>,
+        app_handle: Option<tauri::AppHandle>,
+        condense_briefings: bool,
+        past_cards_context: Option<String>,
     ) -> Result<ResearchResult, String> {
         let start_time = Instant::now();
         info!("Starting research on {} topics", topics.len());
@@ -884,287 +1254,80 @@ impl ResearchAgent {
         // Emit research:started event and update phase
         research_state::set_phase("Starting research...");
 
-        // Debug logging to file
-        let log_path = dirs::home_dir()
-            .unwrap()
-            .join(".claudius")
-            .join("research-debug.log");
-        let _ = std::fs::write(
-            &log_path,
-            format!("{}: RESEARCH STARTED\n", chrono::Local::now()),
+        // Each run gets a fresh GitHub request budget
+        crate::github_cache::reset_run_budget();
+
+        debug!("Emitting research:started event");
+        research_state::publish(
+            app_handle.as_ref(),
+            "research:started",
+            ResearchStartedEvent {
+                timestamp: get_timestamp(),
+                total_topics: topics.len(),
+                topics: topics.clone(),
+            },
         );
 
-        if let Some(app) = &app_handle {
-            let _ = std::fs::OpenOptions::new()
-                .append(true)
-                .open(&log_path)
-                .and_then(|mut f| {
-                    std::io::Write::write_all(
-                        &mut f,
-                        format!(
-                            "{}: Emitting research:started event\n",
-                            chrono::Local::now()
-                        )
-                        .as_bytes(),
-                    )
-                });
-            let _ = app.emit(
-                "research:started",
-                ResearchStartedEvent {
-                    timestamp: get_timestamp(),
-                    total_topics: topics.len(),
-                    topics: topics.clone(),
-                },
-            );
+        self.budget_warnings.clear();
+        if let Some(warning) = self.firecrawl_budget.start_warning() {
+            self.raise_budget_warning(warning, app_handle.as_ref());
         }
 
-        // Initialize MCP connections in a separate thread to avoid blocking Tauri's async runtime.
-        // The MCP client uses blocking I/O (std::io::BufReader::read_line) which would block
-        // the entire async runtime if run directly. Using std::thread::spawn ensures the blocking
-        // I/O runs on a completely separate OS thread.
-        let _ = std::fs::OpenOptions::new()
-            .append(true)
-            .open(&log_path)
-            .and_then(|mut f| {
-                std::io::Write::write_all(
-                    &mut f,
-                    format!(
-                        "{}: STARTING MCP INIT (std::thread)\n",
-                        chrono::Local::now()
-                    )
-                    .as_bytes(),
-                )
-            });
-
-        // Use a oneshot channel to get the result from the thread
-        let (tx, rx) = tokio::sync::oneshot::channel();
-        let log_path_clone = log_path.clone();
-
-        std::thread::spawn(move || {
-            let _ = std::fs::OpenOptions::new()
-                .append(true)
-                .open(&log_path_clone)
-                .and_then(|mut f| {
-                    std::io::Write::write_all(
-                        &mut f,
-                        format!("{}: MCP thread started\n", chrono::Local::now()).as_bytes(),
-                    )
-                });
-
-            // Create a new tokio runtime for this thread
-            let rt = match tokio::runtime::Builder::new_current_thread()
-                .enable_all()
-                .build()
-            {
-                Ok(rt) => rt,
-                Err(e) => {
-                    let _ = tx.send(Err(format!("Failed to create runtime: {}", e)));
-                    return;
-                }
-            };
-
-            let result = rt.block_on(async {
-                let _ = std::fs::OpenOptions::new()
-                    .append(true)
-                    .open(&log_path_clone)
-                    .and_then(|mut f| {
-                        std::io::Write::write_all(
-                            &mut f,
-                            format!("{}: Loading MCP servers config\n", chrono::Local::now())
-                                .as_bytes(),
-                        )
-                    });
-
-                match load_mcp_servers() {
-                    Ok(servers) => {
-                        let enabled_count = servers.iter().filter(|s| s.enabled).count();
-                        let _ = std::fs::OpenOptions::new()
-                            .append(true)
-                            .open(&log_path_clone)
-                            .and_then(|mut f| {
-                                std::io::Write::write_all(
-                                    &mut f,
-                                    format!(
-                                        "{}: Found {} enabled MCP servers\n",
-                                        chrono::Local::now(),
-                                        enabled_count
-                                    )
-                                    .as_bytes(),
-                                )
-                            });
-
-                        if enabled_count == 0 {
-                            return Ok(None);
-                        }
-
-                        let _ = std::fs::OpenOptions::new()
-                            .append(true)
-                            .open(&log_path_clone)
-                            .and_then(|mut f| {
-                                std::io::Write::write_all(
-                                    &mut f,
-                                    format!(
-                                        "{}: Connecting to MCP servers...\n",
-                                        chrono::Local::now()
-                                    )
-                                    .as_bytes(),
-                                )
-                            });
-
-                        match McpClient::connect(servers).await {
-                            Ok(client) => {
-                                let _ = std::fs::OpenOptions::new()
-                                    .append(true)
-                                    .open(&log_path_clone)
-                                    .and_then(|mut f| {
-                                        std::io::Write::write_all(
-                                            &mut f,
-                                            format!(
-                                                "{}: MCP connect returned {} tools\n",
-                                                chrono::Local::now(),
-                                                client.tool_count()
-                                            )
-                                            .as_bytes(),
-                                        )
-                                    });
-                                Ok(Some(client))
-                            }
-                            Err(e) => {
-                                let _ = std::fs::OpenOptions::new()
-                                    .append(true)
-                                    .open(&log_path_clone)
-                                    .and_then(|mut f| {
-                                        std::io::Write::write_all(
-                                            &mut f,
-                                            format!(
-                                                "{}: MCP connect error: {}\n",
-                                                chrono::Local::now(),
-                                                e
-                                            )
-                                            .as_bytes(),
-                                        )
-                                    });
-                                Err(e)
-                            }
-                        }
-                    }
-                    Err(e) => {
-                        let _ = std::fs::OpenOptions::new()
-                            .append(true)
-                            .open(&log_path_clone)
-                            .and_then(|mut f| {
-                                std::io::Write::write_all(
-                                    &mut f,
-                                    format!(
-                                        "{}: Failed to load MCP config: {}\n",
-                                        chrono::Local::now(),
-                                        e
-                                    )
-                                    .as_bytes(),
-                                )
-                            });
-                        Err(e)
-                    }
-                }
-            });
-
-            let _ = tx.send(result);
-        });
-
-        // Wait for the MCP init thread with a timeout
-        let mcp_result = match tokio::time::timeout(Duration::from_secs(120), rx).await {
-            Ok(Ok(result)) => result,
-            Ok(Err(_)) => Err("MCP init channel closed unexpectedly".to_string()),
-            Err(_) => Err("MCP initialization timed out after 120 seconds".to_string()),
-        };
-
-        match mcp_result {
-            Ok(Some(client)) => {
-                info!(
-                    "MCP connected: {} servers, {} tools",
-                    client.server_count(),
-                    client.tool_count()
-                );
-                let _ = std::fs::OpenOptions::new()
-                    .append(true)
-                    .open(&log_path)
-                    .and_then(|mut f| {
-                        std::io::Write::write_all(
-                            &mut f,
-                            format!(
-                                "{}: MCP INIT SUCCESS - {} tools\n",
-                                chrono::Local::now(),
-                                client.tool_count()
-                            )
-                            .as_bytes(),
-                        )
-                    });
-                self.mcp_client = Some(client);
-            }
-            Ok(None) => {
-                info!("No MCP servers enabled");
-                let _ = std::fs::OpenOptions::new()
-                    .append(true)
-                    .open(&log_path)
-                    .and_then(|mut f| {
-                        std::io::Write::write_all(
-                            &mut f,
-                            format!("{}: NO MCP SERVERS ENABLED\n", chrono::Local::now())
-                                .as_bytes(),
-                        )
-                    });
-            }
-            Err(e) => {
-                warn!("MCP initialization failed: {}", e);
-                let _ = std::fs::OpenOptions::new()
-                    .append(true)
-                    .open(&log_path)
-                    .and_then(|mut f| {
-                        std::io::Write::write_all(
-                            &mut f,
-                            format!("{}: MCP INIT FAILED: {}\n", chrono::Local::now(), e)
-                                .as_bytes(),
-                        )
-                    });
-            }
+        // Connect to MCP servers. Connecting and tool calls are async with their
+        // own timeouts, so a stuck server can't block the runtime.
+        if self.offline {
+            debug!(event = "mcp_init", "Offline mode - skipping MCP servers");
+        } else {
+            debug!(event = "mcp_init", "Starting MCP init");
+            self.init_mcp().await?;
         }
 
-        let _ = std::fs::OpenOptions::new()
-            .append(true)
-            .open(&log_path)
-            .and_then(|mut f| {
-                std::io::Write::write_all(
-                    &mut f,
-                    format!("{}: MCP INIT COMPLETE\n", chrono::Local::now()).as_bytes(),
-                )
-            });
+        debug!(event = "mcp_init", "MCP init complete");
 
-        // Validate Firecrawl mode - fail early if Firecrawl MCP is not configured
-        if self.research_mode == "firecrawl" {
-            let has_firecrawl = self
+        // Fail early if the mode's required MCP server (Firecrawl for Deep
+        // Research) is not configured
+        if let Some(required) = self.mode.required_mcp_tool().filter(|_| !self.offline) {
+            let has_required = self
                 .mcp_client
                 .as_ref()
                 .map(|client| {
                     client
                         .get_all_tools()
                         .iter()
-                        .any(|t| t.tool.name.contains("firecrawl"))
+                        .any(|t| t.tool.name.contains(required))
                 })
                 .unwrap_or(false);
 
-            if !has_firecrawl {
-                return Err(
-                    "Deep Research mode requires Firecrawl MCP server to be configured. \
-                     Please add Firecrawl in Settings → MCP Servers, or switch to Standard mode."
-                        .to_string(),
-                );
+            if !has_required {
+                return Err(format!(
+                    "The {} research mode requires a {} MCP server to be configured. \
+                     Please add it in Settings → MCP Servers, or switch to Standard mode.",
+                    self.mode.name(),
+                    required
+                ));
             }
         }
 
-        // Step 1: Research each topic with tool support
+        // Step 1: Research each topic with tool support, starting with batched
+        // rounds when batch mode is on
+        let mut batch_tokens: u32 = 0;
+        let mut batched: Vec<Option<(TopicConversation, Option<String>)>> =
+            match self.batch_deadline {
+                Some(deadline) if !self.offline => {
+                    let (outcomes, tokens) = self
+                        .research_topics_batched(&topics, deadline, app_handle.as_ref())
+                        .await?;
+                    batch_tokens = tokens;
+                    outcomes.into_iter().map(Some).collect()
+                }
+                _ => Vec::new(),
+            };
+
         let mut research_content = String::new();
         let mut total_tokens: u32 = 0;
-        let mut topic_stats: Vec<(String, usize)> = Vec::new(); // Track (topic_name, cards_generated)
+        let mut topic_results: Vec<TopicResult> = Vec::new();
+        let mut failed_topics: Vec<FailedTopic> = Vec::new();
+        let mut topic_sections: HashMap<String, String> = HashMap::new(); // Topic -> its research content
 
         let mut topics_completed_count = 0;
         for (i, topic) in topics.iter().enumerate() {
@@ -1185,23 +1348,46 @@ impl ResearchAgent {
                 topics.len(),
                 topic
             ));
-            if let Some(app) = &app_handle {
-                let _ = app.emit(
-                    "research:topic_started",
-                    TopicStartedEvent {
-                        timestamp: get_timestamp(),
-                        topic_name: topic.clone(),
-                        topic_index: i,
-                        total_topics: topics.len(),
-                    },
-                );
-            }
+            research_state::publish(
+                app_handle.as_ref(),
+                "research:topic_started",
+                TopicStartedEvent {
+                    timestamp: get_timestamp(),
+                    topic_name: topic.clone(),
+                    topic_index: i,
+                    total_topics: topics.len(),
+                },
+            );
 
-            match self
-                .research_topic_with_tools(topic, app_handle.as_ref(), i)
-                .await
-            {
-                Ok((content, tokens)) => {
+            self.partial_findings.clear();
+            let topic_start = Instant::now();
+            let outcome = match batched.get_mut(i).and_then(Option::take) {
+                Some((conversation, Some(content))) => Ok(Ok((
+                    content,
+                    conversation.total_tokens,
+                    conversation.tool_calls,
+                ))),
+                started => {
+                    tokio::time::timeout(
+                        self.topic_timeout,
+                        self.research_topic_with_tools(
+                            topic,
+                            app_handle.as_ref(),
+                            i,
+                            started.map(|(conversation, _)| conversation),
+                        ),
+                    )
+                    .await
+                }
+            };
+
+            let section_start = research_content.len();
+            let (topic_tokens, topic_tool_calls) = match &outcome {
+                Ok(Ok((_, tokens, tool_calls))) => (*tokens, *tool_calls),
+                _ => (0, 0),
+            };
+            match outcome {
+                Ok(Ok((content, tokens, _))) => {
                     research_content.push_str(&format!(
                         "\n## Topic {}: {}\n{}\n",
                         i + 1,
@@ -1209,31 +1395,89 @@ impl ResearchAgent {
                         content
                     ));
                     total_tokens += tokens;
-                    topic_stats.push((topic.clone(), 0)); // Will be updated after synthesis
                 }
-                Err(e) => {
+                Ok(Err(e)) => {
                     error!("Error researching topic '{}': {}", topic, e);
-                    research_content.push_str(&format!(
-                        "\n## Topic {}: {}\nError: Could not research this topic.\n",
-                        i + 1,
-                        topic
-                    ));
-                    topic_stats.push((topic.clone(), 0));
+                    failed_topics.push(FailedTopic {
+                        topic: topic.clone(),
+                        error: e,
+                    });
+                }
+                Err(_) => {
+                    // A cancellation that arrived while the topic was stuck takes precedence
+                    self.check_cancellation_with_event(
+                        app_handle.as_ref(),
+                        "researching",
+                        topics_completed_count,
+                        topics.len(),
+                    )?;
+
+                    let timeout_secs = self.topic_timeout.as_secs();
+                    warn!(
+                        "Topic '{}' timed out after {}s, continuing with remaining topics",
+                        topic, timeout_secs
+                    );
+                    let err = ResearchError::new(
+                        ErrorCode::Timeout,
+                        format!("Topic research timed out after {}s", timeout_secs),
+                    );
+                    let _ = ResearchLogger::log_topic_timeout(
+                        topic,
+                        &err,
+                        topic_start.elapsed().as_millis() as i64,
+                    );
+
+                    let partial = self.partial_findings.join("\n\n");
+                    if partial.is_empty() {
+                        failed_topics.push(FailedTopic {
+                            topic: topic.clone(),
+                            error: format!(
+                                "Timed out after {}s before any findings were gathered",
+                                timeout_secs
+                            ),
+                        });
+                    } else {
+                        research_content.push_str(&format!(
+                            "\n## Topic {}: {}\nNote: Research on this topic timed out; the findings below are partial.\n{}\n",
+                            i + 1,
+                            topic,
+                            partial
+                        ));
+                    }
+
+                    research_state::publish(
+                        app_handle.as_ref(),
+                        "research:topic_timeout",
+                        TopicTimeoutEvent {
+                            timestamp: get_timestamp(),
+                            topic_name: topic.clone(),
+                            topic_index: i,
+                            timeout_secs,
+                            has_partial_findings: !partial.is_empty(),
+                        },
+                    );
                 }
             }
+            topic_sections.insert(topic.clone(), research_content[section_start..].to_string());
+            topic_results.push(TopicResult {
+                topic: topic.clone(),
+                tokens: topic_tokens,
+                tool_calls: topic_tool_calls,
+                duration_ms: topic_start.elapsed().as_millis() as u64,
+                card_indices: Vec::new(),
+            });
 
             // Emit research:topic_completed event
-            if let Some(app) = &app_handle {
-                let _ = app.emit(
-                    "research:topic_completed",
-                    TopicCompletedEvent {
-                        timestamp: get_timestamp(),
-                        topic_name: topic.clone(),
-                        topic_index: i,
-                        cards_generated: 0, // Will be known after synthesis
-                    },
-                );
-            }
+            research_state::publish(
+                app_handle.as_ref(),
+                "research:topic_completed",
+                TopicCompletedEvent {
+                    timestamp: get_timestamp(),
+                    topic_name: topic.clone(),
+                    topic_index: i,
+                    cards_generated: 0, // Will be known after synthesis
+                },
+            );
 
             topics_completed_count += 1;
         }
@@ -1246,28 +1490,97 @@ impl ResearchAgent {
             topics.len(),
         )?;
 
-        // Step 2: Synthesize into briefing cards
-        info!(
-            "Synthesizing research into briefing cards (condensed: {})",
-            condense_briefings
-        );
-        let (cards, synthesis_tokens) = self
-            .synthesize_briefing(
-                &research_content,
+        // Failed topics are left out of synthesis; with nothing left there is
+        // no briefing to write
+        if failed_topics.len() == topics.len() {
+            return Err(match failed_topics.as_slice() {
+                [only] => only.error.clone(),
+                [first, ..] => format!(
+                    "All {} topics failed to research: {}",
+                    failed_topics.len(),
+                    first.error
+                ),
+                [] => "No topics to research".to_string(),
+            });
+        }
+        if !failed_topics.is_empty() {
+            warn!(
+                "{} of {} topics failed and are left out of the briefing: {}",
+                failed_topics.len(),
+                topics.len(),
+                failed_topics
+                    .iter()
+                    .map(|f| f.topic.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            );
+        }
+
+        // Step 2: Synthesize into briefing cards, once per format group
+        let groups = group_topics_by_format(&topics, &self.briefing_format, &self.topic_formats);
+        let mut cards: Vec<BriefingCard> = Vec::new();
+        for (format, group_topics) in &groups {
+            info!(
+                "Synthesizing {} topics into briefing cards (format: {}, condensed: {})",
+                group_topics.len(),
+                format,
+                condense_briefings
+            );
+            let group_content = if groups.len() == 1 {
+                research_content.clone()
+            } else {
+                group_topics
+                    .iter()
+                    .filter_map(|t| topic_sections.get(t))
+                    .cloned()
+                    .collect::<String>()
+            };
+            if group_content.trim().is_empty() {
+                info!("Skipping {} synthesis: all of its topics failed", format);
+                continue;
+            }
+            let (group_cards, synthesis_tokens) = self
+                .synthesize_briefing(
+                    &group_content,
+                    app_handle.as_ref(),
+                    condense_briefings,
+                    format,
+                    past_cards_context.as_deref(),
+                )
+                .await
+                .map_err(|e| {
+                    let _ = ResearchLogger::log_api_error("synthesis", &e);
+                    e.tagged()
+                })?;
+            cards.extend(group_cards);
+            total_tokens += synthesis_tokens;
+        }
+
+        // Step 3: Validate source URLs (and archive snapshots if enabled)
+        if self.offline {
+            info!("Offline mode - skipping source validation");
+        } else {
+            research_state::set_phase("Validating sources...");
+            let validation = sources::validate_cards(&mut cards, self.archive_sources).await;
+            research_state::publish(
                 app_handle.as_ref(),
-                condense_briefings,
-                past_cards_context.as_deref(),
-            )
-            .await
-            .map_err(|e| {
-                let _ = ResearchLogger::log_api_error("synthesis", &e);
-                e.message
-            })?;
-        total_tokens += synthesis_tokens;
+                "research:sources_validated",
+                SourcesValidatedEvent {
+                    timestamp: get_timestamp(),
+                    sources_checked: validation.checked,
+                    sources_broken: validation.broken,
+                },
+            );
+        }
+
+        // Step 4: Send cards citing no sources (or only stale ones) back once
+        total_tokens += self
+            .enforce_source_requirements(&mut cards, &research_content)
+            .await;
 
         let research_time_ms = start_time.elapsed().as_millis() as u64;
 
-        let result = ResearchResult {
+        let mut result = ResearchResult {
             date: chrono::Local::now().format("%Y-%m-%dT%H:%M:%S").to_string(),
             title: format!(
                 "Daily Briefing - {}",
@@ -1277,7 +1590,12 @@ impl ResearchAgent {
             research_time_ms,
             model_used: self.model.clone(),
             total_tokens,
+            batch_tokens,
+            run_id: String::new(), // Set by run_research
+            topics: topic_results,
+            failed_topics,
         };
+        result.index_topic_cards();
 
         info!(
             "Research complete: {} cards, {}ms, {} tokens",
@@ -1298,22 +1616,254 @@ impl ResearchAgent {
         Ok(result)
     }
 
-    /// Research a single topic using Claude with tool support.
-    async fn research_topic_with_tools(
+    /// Research topics through the Message Batches API, one batch per round of
+    /// turns: the next request of every unfinished topic is submitted
+    /// together, and once the batch ends the requested tools run locally
+    /// before the next round.
+    ///
+    /// Returns each topic's conversation with its research text if it
+    /// finished. Topics that didn't (deadline passed, a request failed, or
+    /// the Batch API was unavailable) continue interactively from where they
+    /// stopped. Also returns the tokens billed at the batch rate.
+    async fn research_topics_batched(
         &mut self,
-        topic: &str,
+        topics: &[String],
+        deadline: Duration,
         app_handle: Option<&tauri::AppHandle>,
-        topic_index: usize,
-    ) -> Result<(String, u32), String> {
-        // Build dynamic system prompt based on available tools
-        let tools = self.get_all_tools();
-        let tool_descriptions: Vec<String> = tools
-            .iter()
-            .map(|t| format!("- {}: {}", t.name, t.description))
-            .collect();
+    ) -> Result<(Vec<(TopicConversation, Option<String>)>, u32), String> {
+        let started = Instant::now();
+        research_state::set_phase("Preparing batch research...");
 
-        // Get current date components for research context
-        let now = chrono::Local::now();
+        let mut conversations = Vec::new();
+        for topic in topics {
+            conversations.push(self.start_topic(topic).await);
+        }
+        let mut finished: Vec<Option<String>> = vec![None; topics.len()];
+        // Topics whose batch request failed are left to the interactive path
+        let mut dropped: HashSet<usize> = HashSet::new();
+        let mut batch_tokens: u32 = 0;
+
+        'rounds: loop {
+            let requests: Vec<(String, MessagesRequest)> = conversations
+                .iter()
+                .enumerate()
+                .filter(|(i, conversation)| {
+                    finished[*i].is_none()
+                        && !dropped.contains(i)
+                        && conversation.iterations < MAX_TOOL_ITERATIONS
+                })
+                .map(|(i, conversation)| (format!("topic-{}", i), self.topic_request(conversation)))
+                .collect();
+            if requests.is_empty() || started.elapsed() >= deadline {
+                break;
+            }
+
+            info!("Submitting research batch of {} request(s)", requests.len());
+            let round_start = Instant::now();
+            let mut batch = match BatchClient::new(&self.client, &self.api_key, &self.endpoint)
+                .create(&requests)
+                .await
+            {
+                Ok(batch) => batch,
+                Err(e) => {
+                    warn!("Batch submission failed, continuing interactively: {}", e);
+                    break;
+                }
+            };
+
+            // Poll until the batch ends, checking for cancellation every second
+            let mut next_poll = Instant::now() + BATCH_POLL_INTERVAL;
+            while !batch.is_ended() {
+                let topics_done = finished.iter().filter(|f| f.is_some()).count();
+                if self.check_cancellation().is_err() {
+                    let _ = BatchClient::new(&self.client, &self.api_key, &self.endpoint)
+                        .cancel(&batch.id)
+                        .await;
+                    self.check_cancellation_with_event(
+                        app_handle,
+                        "researching",
+                        topics_done,
+                        topics.len(),
+                    )?;
+                }
+                if started.elapsed() >= deadline {
+                    warn!(
+                        "Batch {} missed the {}-minute deadline, researching the remaining topics interactively",
+                        batch.id,
+                        deadline.as_secs() / 60
+                    );
+                    if let Err(e) = BatchClient::new(&self.client, &self.api_key, &self.endpoint)
+                        .cancel(&batch.id)
+                        .await
+                    {
+                        warn!("Failed to cancel batch {}: {}", batch.id, e);
+                    }
+                    break 'rounds;
+                }
+                if Instant::now() < next_poll {
+                    tokio::time::sleep(Duration::from_secs(1)).await;
+                    continue;
+                }
+
+                next_poll = Instant::now() + BATCH_POLL_INTERVAL;
+                match BatchClient::new(&self.client, &self.api_key, &self.endpoint)
+                    .get(&batch.id)
+                    .await
+                {
+                    Ok(updated) => batch = updated,
+                    Err(e) => warn!("Failed to check batch {}: {}", batch.id, e),
+                }
+                let message = format!(
+                    "Waiting for research batch ({} of {} requests done, {} of {} topics finished)",
+                    batch.request_counts.succeeded + batch.request_counts.errored,
+                    requests.len(),
+                    topics_done,
+                    topics.len()
+                );
+                research_state::set_phase(&format!("{}...", message));
+                research_state::publish(
+                    app_handle,
+                    "research:heartbeat",
+                    HeartbeatEvent {
+                        timestamp: get_timestamp(),
+                        phase: "batch".to_string(),
+                        topic_index: None,
+                        message,
+                    },
+                );
+            }
+
+            let mut results = match BatchClient::new(&self.client, &self.api_key, &self.endpoint)
+                .results(&batch)
+                .await
+            {
+                Ok(results) => results,
+                Err(e) => {
+                    warn!("Failed to read batch {} results: {}", batch.id, e);
+                    break;
+                }
+            };
+            let round_duration = round_start.elapsed().as_millis() as i64;
+            info!(
+                "Research batch {} ended after {}s",
+                batch.id,
+                round_duration / 1000
+            );
+
+            for (i, topic) in topics.iter().enumerate() {
+                let response = match results.remove(&format!("topic-{}", i)) {
+                    Some(BatchResult::Succeeded(response)) => response,
+                    Some(BatchResult::Failed(e)) => {
+                        warn!("Batch request for '{}' failed: {}", topic, e);
+                        let err = ResearchError::new(ErrorCode::InvalidResponse, e);
+                        let _ = ResearchLogger::log_api_error(topic, &err);
+                        dropped.insert(i);
+                        continue;
+                    }
+                    None => continue,
+                };
+
+                let conversation = &mut conversations[i];
+                conversation.iterations += 1;
+                let tokens = response.total_tokens();
+                conversation.total_tokens += tokens;
+                batch_tokens += tokens;
+
+                // Tool calls run here, so bound them like an interactive topic
+                self.partial_findings.clear();
+                match tokio::time::timeout(
+                    self.topic_timeout,
+                    self.handle_topic_response(
+                        topic,
+                        conversation,
+                        &response,
+                        round_duration,
+                        app_handle,
+                    ),
+                )
+                .await
+                {
+                    Ok(Some(content)) => finished[i] = Some(content),
+                    Ok(None) => {}
+                    Err(_) => {
+                        warn!(
+                            "Tools for '{}' timed out after a batch round, continuing interactively",
+                            topic
+                        );
+                        dropped.insert(i);
+                    }
+                }
+            }
+        }
+
+        let done = finished.iter().filter(|f| f.is_some()).count();
+        info!(
+            "Batch research finished {} of {} topics ({} batch tokens)",
+            done,
+            topics.len(),
+            batch_tokens
+        );
+        Ok((
+            conversations.into_iter().zip(finished).collect(),
+            batch_tokens,
+        ))
+    }
+
+    /// Fetch the user's preferred sources for a topic before the agentic loop.
+    /// Returns the formatted content, or an empty string if nothing could be fetched.
+    async fn prefetch_preferred_sources(&self, topic: &str, sources: &[String]) -> String {
+        let mut sections = Vec::new();
+
+        for url in sources
+            .iter()
+            .filter_map(|s| preferred_source_url(s))
+            .take(MAX_PREFETCH_SOURCES)
+        {
+            let fetch_start = Instant::now();
+            match tools::web::fetch_webpage(&self.client, &url).await {
+                Ok(output) => {
+                    let content = output.text;
+                    let duration = fetch_start.elapsed().as_millis() as i64;
+                    info!("Pre-fetched preferred source {} in {}ms", url, duration);
+                    store_cached_content("web", &url, topic, &content);
+                    let _ = ResearchLogger::log_tool_call(
+                        topic,
+                        "fetch_webpage",
+                        &url,
+                        &content,
+                        duration,
+                    );
+                    let excerpt: String = content.chars().take(PREFETCH_MAX_CHARS).collect();
+                    sections.push(format!("### {}\n{}", url, excerpt));
+                }
+                Err(e) => {
+                    warn!("Failed to pre-fetch preferred source {}: {}", url, e);
+                    let err = ResearchError::new(ErrorCode::ToolExecutionFailed, &e);
+                    let _ = ResearchLogger::log_tool_error(
+                        topic,
+                        "fetch_webpage",
+                        &url,
+                        &err,
+                        fetch_start.elapsed().as_millis() as i64,
+                    );
+                }
+            }
+        }
+
+        sections.join("\n\n")
+    }
+
+    /// Build the research prompts for a topic and start its conversation.
+    async fn start_topic(&self, topic: &str) -> TopicConversation {
+        // Build dynamic system prompt based on available tools
+        let tools = self.get_all_tools(Some(topic));
+        let tool_descriptions: Vec<String> = tools
+            .iter()
+            .map(|t| format!("- {}: {}", t.name, t.description))
+            .collect();
+
+        // Get current date components for research context
+        let now = chrono::Local::now();
         let current_date = now.format("%B %d, %Y").to_string();
         let _current_month = now.format("%B").to_string();
         let current_year = now.format("%Y").to_string();
@@ -1321,47 +1871,9 @@ impl ResearchAgent {
         let month_year = now.format("%B %Y").to_string();
 
         // Build mode-specific tool usage instructions
-        let tool_usage_instructions = if self.research_mode == "firecrawl" {
-            format!(
-                r#"CRITICAL SEARCH TOOL USAGE (Firecrawl Deep Research Mode):
-- Use firecrawl_search to find {} articles - it searches AND extracts content in one call
-- Use specific search queries like "[topic] {}" or "[topic] {} latest news"
-- firecrawl_search returns full page content, not just URLs - analyze the results directly
-- Use firecrawl_scrape to get full content from specific URLs you want to analyze deeply
-- Use firecrawl_extract for structured data extraction with custom prompts (great for extracting specific facts)
-- Use firecrawl_map to discover related pages on a website
-- Use get_github_activity for open source projects to see recent commits, PRs, and releases from {}
-
-Firecrawl tools handle JavaScript-heavy sites and provide clean markdown content. Use them aggressively for comprehensive research."#,
-                month_year,
-                month_year,
-                current_year,
-                month_year
-            )
-        } else {
-            format!(
-                r#"CRITICAL SEARCH TOOL USAGE:
-- If you have access to brave_search or perplexity search tools, USE THEM FIRST to find {} articles and information
-- Use specific search queries like "[topic] {}" or "[topic] {} latest news"
-- Search tools will give you current URLs and content - these are your primary source for {} information
-- After getting search results, use fetch_webpage to read the most promising URLs in full
-- Use get_github_activity for open source projects to see recent commits, PRs, and releases from {}
-
-When using fetch_webpage directly (without search):
-- Target URLs likely to have {} content: TechCrunch, The Verge, Hacker News, company blogs, official documentation
-- Prioritize URLs with "/{}" or "{}" in the path"#,
-                month_year,
-                month_year,
-                current_year,
-                month_year,
-                month_year,
-                month_year,
-                current_year.to_lowercase(),
-                month_year.to_lowercase().replace(" ", "-")
-            )
-        };
+        let tool_usage_instructions = self.mode.tool_instructions(&month_year, &current_year);
 
-        let system_prompt = format!(
+        let mut system_prompt = format!(
             r#"You are a research assistant gathering information on topics of interest.
 
 IMPORTANT: Today's date is {}. You must focus on finding information from {} and late {}. Any information from {} or earlier is outdated and should be avoided unless it provides essential background context.
@@ -1381,9 +1893,28 @@ After gathering current information, provide a comprehensive research summary ba
             month_year
         );
 
-        let user_prompt = format!(
+        // Point Claude at the user's preferred sources for this topic
+        let preferred_sources = self
+            .preferred_sources
+            .get(topic)
+            .cloned()
+            .unwrap_or_default();
+        if !preferred_sources.is_empty() {
+            system_prompt.push_str("\n\n");
+            system_prompt.push_str(&format_preferred_sources_prompt(&preferred_sources));
+        }
+        if let Some(about) = format_about_me_prompt(&self.about_me) {
+            system_prompt.push_str("\n\n");
+            system_prompt.push_str(&about);
+        }
+        if let Some(note) = self.firecrawl_budget.prompt_note() {
+            system_prompt.push_str("\n\n");
+            system_prompt.push_str(&note);
+        }
+
+        let mut user_prompt = format!(
             r#"Research the following topic and provide:
-1. Key recent developments from {} (ideally within the last 24-48 hours, or at minimum from late {})
+1. Key recent developments from {} ({}, or at minimum from late {})
 2. Why this might be relevant to someone interested in this topic
 3. Actionable insights or next steps
 4. Credible sources with dates (MUST be from {}, preferably {})
@@ -1394,6 +1925,7 @@ CRITICAL: Use the available tools aggressively to fetch current {} information.
 
 Provide a concise but informative research summary (2-3 paragraphs) based on current {} data."#,
             month_year,
+            recency_window_prompt(self.recency_window_for(topic), now.date_naive()),
             current_year,
             current_year,
             month_year,
@@ -1402,13 +1934,39 @@ Provide a concise but informative research summary (2-3 paragraphs) based on cur
             month_year,
             month_year
         );
-        let mut messages = vec![Message {
-            role: "user".to_string(),
-            content: MessageContent::Text(user_prompt),
-        }];
 
-        let mut total_tokens: u32 = 0;
-        let mut iterations = 0;
+        // Without web search, ground the research by pre-fetching preferred sources
+        if self.offline {
+            user_prompt.push_str(&offline_context_prompt(topic));
+        } else if !self.enable_web_search && !preferred_sources.is_empty() {
+            let prefetched = self
+                .prefetch_preferred_sources(topic, &preferred_sources)
+                .await;
+            if !prefetched.is_empty() {
+                user_prompt.push_str(&format!(
+                    "\n\nCONTENT FROM PREFERRED SOURCES (fetched just now - start from here):\n{}",
+                    prefetched
+                ));
+            }
+        }
+
+        TopicConversation::new(topic.to_string(), system_prompt, user_prompt)
+    }
+
+    /// Research a single topic using Claude with tool support. Continues
+    /// `conversation` when one was already started (by a batch run).
+    async fn research_topic_with_tools(
+        &mut self,
+        topic: &str,
+        app_handle: Option<&tauri::AppHandle>,
+        topic_index: usize,
+        conversation: Option<TopicConversation>,
+    ) -> Result<(String, u32, u32), String> {
+        let mut conversation = match conversation {
+            Some(conversation) => conversation,
+            None => self.start_topic(topic).await,
+        };
+
         let mut last_heartbeat = Instant::now();
         const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(30);
 
@@ -1419,25 +1977,24 @@ Provide a concise but informative research summary (2-3 paragraphs) based on cur
 
             // Emit heartbeat if enough time has passed
             if last_heartbeat.elapsed() >= HEARTBEAT_INTERVAL {
-                if let Some(app) = app_handle {
-                    let _ = app.emit(
-                        "research:heartbeat",
-                        HeartbeatEvent {
-                            timestamp: get_timestamp(),
-                            phase: "researching".to_string(),
-                            topic_index: Some(topic_index),
-                            message: format!(
-                                "Still researching '{}' (iteration {})",
-                                topic, iterations
-                            ),
-                        },
-                    );
-                }
+                research_state::publish(
+                    app_handle,
+                    "research:heartbeat",
+                    HeartbeatEvent {
+                        timestamp: get_timestamp(),
+                        phase: "researching".to_string(),
+                        topic_index: Some(topic_index),
+                        message: format!(
+                            "Still researching '{}' (iteration {})",
+                            topic, conversation.iterations
+                        ),
+                    },
+                );
                 last_heartbeat = Instant::now();
             }
 
-            iterations += 1;
-            if iterations > MAX_TOOL_ITERATIONS {
+            conversation.iterations += 1;
+            if conversation.iterations > MAX_TOOL_ITERATIONS {
                 warn!(
                     "Reached max tool iterations ({}), stopping",
                     MAX_TOOL_ITERATIONS
@@ -1445,17 +2002,11 @@ Provide a concise but informative research summary (2-3 paragraphs) based on cur
                 break;
             }
 
-            let request = AnthropicRequest {
-                model: self.model.clone(),
-                max_tokens: 2048,
-                messages: messages.clone(),
-                tools: Some(self.get_tools_json()),
-                system: Some(system_prompt.to_string()),
-            };
+            let request = self.topic_request(&conversation);
 
             info!(
                 "Calling Claude API (iteration {}/{}) for topic: {}",
-                iterations, MAX_TOOL_ITERATIONS, topic
+                conversation.iterations, MAX_TOOL_ITERATIONS, topic
             );
             let api_start = Instant::now();
             let response = match self.send_request(&request).await {
@@ -1463,190 +2014,234 @@ Provide a concise but informative research summary (2-3 paragraphs) based on cur
                 Err(e) => {
                     // Log the API error
                     let _ = ResearchLogger::log_api_error(topic, &e);
-                    return Err(e.message);
+                    return Err(e.tagged());
                 }
             };
             let api_duration = api_start.elapsed().as_millis() as i64;
-            let tokens = response.usage.input_tokens + response.usage.output_tokens;
-            total_tokens += tokens;
+            let tokens = response.total_tokens();
+            conversation.total_tokens += tokens;
 
             info!(
                 "Claude API responded in {}ms ({} tokens, stop_reason: {:?})",
                 api_duration, tokens, response.stop_reason
             );
 
-            // Log successful API request
-            let _ = ResearchLogger::log_api_request(topic, tokens as i64, api_duration);
+            if let Some(text) = self
+                .handle_topic_response(
+                    topic,
+                    &mut conversation,
+                    &response,
+                    api_duration,
+                    app_handle,
+                )
+                .await
+            {
+                return Ok((text, conversation.total_tokens, conversation.tool_calls));
+            }
+        }
 
-            // Check for web_search usage in response (server_tool_use blocks)
-            // Claude's built-in web_search returns server_tool_use and web_search_tool_result blocks
-            let web_search_uses: Vec<_> = response
-                .content
-                .iter()
-                .filter(|c| {
-                    c.content_type == "server_tool_use"
-                        || c.content_type == "web_search_tool_result"
-                })
-                .collect();
+        // If we exit the loop due to max iterations, extract any text we have
+        Ok((
+            "Research completed (max iterations reached)".to_string(),
+            conversation.total_tokens,
+            conversation.tool_calls,
+        ))
+    }
 
-            if !web_search_uses.is_empty() {
-                for block in &web_search_uses {
-                    if block.content_type == "server_tool_use" {
-                        // Extract search query from input if available
-                        let search_query = block
-                            .input
-                            .as_ref()
-                            .and_then(|i| i.get("query"))
-                            .and_then(|q| q.as_str())
-                            .map(|s| s.to_string());
-
-                        if let Some(name) = &block.name {
-                            info!(
-                                "🔍 Web search initiated: tool={}, query={:?}",
-                                name, search_query
-                            );
-                        }
+    /// The next Messages API request for a topic's conversation.
+    fn topic_request(&self, conversation: &TopicConversation) -> MessagesRequest {
+        MessagesRequest {
+            model: self.model.clone(),
+            max_tokens: RESEARCH_MAX_TOKENS + self.thinking_budget.unwrap_or(0),
+            messages: conversation.messages.clone(),
+            tools: Some(self.get_tools_json(Some(&conversation.topic))),
+            system: Some(conversation.system_prompt.clone()),
+            thinking: self.thinking_budget.map(ThinkingConfig::enabled),
+            metadata: self.endpoint.metadata(),
+        }
+    }
 
-                        // Emit web search started event
-                        if let Some(app) = app_handle {
-                            let _ = app.emit(
-                                "research:web_search",
-                                WebSearchEvent {
-                                    timestamp: get_timestamp(),
-                                    topic_name: topic.to_string(),
-                                    search_query: search_query.clone(),
-                                    status: "started".to_string(),
-                                },
-                            );
-                        }
-                    } else if block.content_type == "web_search_tool_result" {
-                        info!("🔍 Web search completed for topic: {}", topic);
-
-                        // Emit web search completed event
-                        if let Some(app) = app_handle {
-                            let _ = app.emit(
-                                "research:web_search",
-                                WebSearchEvent {
-                                    timestamp: get_timestamp(),
-                                    topic_name: topic.to_string(),
-                                    search_query: None,
-                                    status: "completed".to_string(),
-                                },
-                            );
-                        }
+    /// Handle one of Claude's turns for a topic. Records its reasoning and web
+    /// searches, then returns the research text if Claude is done, or runs
+    /// the requested tools and adds the turn and their results to the
+    /// conversation.
+    async fn handle_topic_response(
+        &mut self,
+        topic: &str,
+        conversation: &mut TopicConversation,
+        response: &MessagesResponse,
+        api_duration: i64,
+        app_handle: Option<&tauri::AppHandle>,
+    ) -> Option<String> {
+        // Log successful API request
+        let _ = ResearchLogger::log_api_request(
+            topic,
+            response.total_tokens() as i64,
+            api_duration,
+            response.request_id.clone(),
+        );
 
-                        // Log the web search tool result
-                        let _ = ResearchLogger::log_tool_call(
-                            topic,
-                            "web_search",
-                            "built-in web search",
-                            "Web search completed (result in response)",
-                            api_duration,
+        // Record Claude's reasoning so users can see how it reached its conclusions
+        if let Some(reasoning) = response.thinking() {
+            debug!("Claude's reasoning for '{}': {}", topic, reasoning);
+            let _ = ResearchLogger::log_thinking(topic, &reasoning);
+            research_state::publish(
+                app_handle,
+                "research:thinking",
+                ThinkingEvent {
+                    timestamp: get_timestamp(),
+                    topic_name: topic.to_string(),
+                    phase: if conversation.iterations == 1 {
+                        "initial_research"
+                    } else {
+                        "tool_calling"
+                    }
+                    .to_string(),
+                    reasoning: Some(reasoning),
+                },
+            );
+        }
+
+        // Check for web_search usage in response (server_tool_use blocks)
+        // Claude's built-in web_search returns server_tool_use and web_search_tool_result blocks
+        let web_search_uses: Vec<_> = response
+            .content
+            .iter()
+            .filter(|c| {
+                c.content_type == "server_tool_use" || c.content_type == "web_search_tool_result"
+            })
+            .collect();
+
+        if !web_search_uses.is_empty() {
+            for block in &web_search_uses {
+                if block.content_type == "server_tool_use" {
+                    conversation.tool_calls += 1;
+
+                    // Extract search query from input if available
+                    let search_query = block
+                        .input
+                        .as_ref()
+                        .and_then(|i| i.get("query"))
+                        .and_then(|q| q.as_str())
+                        .map(|s| s.to_string());
+
+                    if let Some(name) = &block.name {
+                        info!(
+                            "🔍 Web search initiated: tool={}, query={:?}",
+                            name, search_query
                         );
                     }
+
+                    // Emit web search started event
+                    research_state::publish(
+                        app_handle,
+                        "research:web_search",
+                        WebSearchEvent {
+                            timestamp: get_timestamp(),
+                            topic_name: topic.to_string(),
+                            search_query: search_query.clone(),
+                            status: "started".to_string(),
+                        },
+                    );
+                } else if block.content_type == "web_search_tool_result" {
+                    info!("🔍 Web search completed for topic: {}", topic);
+
+                    // Emit web search completed event
+                    research_state::publish(
+                        app_handle,
+                        "research:web_search",
+                        WebSearchEvent {
+                            timestamp: get_timestamp(),
+                            topic_name: topic.to_string(),
+                            search_query: None,
+                            status: "completed".to_string(),
+                        },
+                    );
+
+                    // Log the web search tool result
+                    let _ = ResearchLogger::log_tool_call(
+                        topic,
+                        "web_search",
+                        "built-in web search",
+                        "Web search completed (result in response)",
+                        api_duration,
+                    );
                 }
             }
+        }
 
-            // Check if Claude wants to use tools
-            let tool_uses: Vec<_> = response
-                .content
-                .iter()
-                .filter(|c| c.content_type == "tool_use")
-                .collect();
-
-            if tool_uses.is_empty() || response.stop_reason.as_deref() == Some("end_turn") {
-                info!(
-                    "No more tool calls requested - research complete for topic: {}",
-                    topic
-                );
-                // No more tool calls, extract the text response
-                let text_content: String = response
-                    .content
-                    .iter()
-                    .filter_map(|c| {
-                        if c.content_type == "text" {
-                            c.text.clone()
-                        } else {
-                            None
-                        }
-                    })
-                    .collect::<Vec<_>>()
-                    .join("\n");
+        if response.is_final() {
+            info!(
+                "No more tool calls requested - research complete for topic: {}",
+                topic
+            );
+            // No more tool calls, extract the text response
+            return Some(response.text());
+        }
 
-                return Ok((text_content, total_tokens));
+        // Keep interim notes in case the topic times out before finishing
+        let assistant_message = response.assistant_message();
+        if let MessageContent::Blocks(blocks) = &assistant_message.content {
+            for block in blocks {
+                if let ContentBlock::Text { text } = block {
+                    self.partial_findings.push(text.clone());
+                }
             }
+        }
 
-            // Build assistant message with tool uses
-            // Filter out empty text blocks - Claude API rejects "text content blocks must be non-empty"
-            let assistant_blocks: Vec<ContentBlock> = response
-                .content
-                .iter()
-                .filter_map(|c| {
-                    if c.content_type == "text" {
-                        let text = c.text.clone().unwrap_or_default();
-                        // Only include non-empty text blocks
-                        if !text.is_empty() {
-                            Some(ContentBlock::Text { text })
-                        } else {
-                            None
-                        }
-                    } else if c.content_type == "tool_use" {
-                        Some(ContentBlock::ToolUse {
-                            id: c.id.clone().unwrap_or_default(),
-                            name: c.name.clone().unwrap_or_default(),
-                            input: c.input.clone().unwrap_or(json!({})),
-                        })
-                    } else {
-                        // Skip unknown content types instead of creating empty text blocks
-                        None
-                    }
-                })
-                .collect();
-
-            messages.push(Message {
-                role: "assistant".to_string(),
-                content: MessageContent::Blocks(assistant_blocks),
-            });
+        // Execute tools and build results
+        let tool_calls = response.tool_calls();
+        info!("Claude requested {} tool call(s)", tool_calls.len());
+        conversation.tool_calls += tool_calls.len() as u32;
+        let mut tool_results: Vec<ContentBlock> = Vec::new();
+        for tool_call in &tool_calls {
+            let tool_name = tool_call.name.as_str();
+            let tool_id = tool_call.id.as_str();
+            let tool_input = &tool_call.input;
+            let input_str = serde_json::to_string(tool_input).unwrap_or_default();
+
+            info!("Executing tool: {}", tool_name);
+            debug!("Tool input: {}", tool_input);
+
+            let tool_start = Instant::now();
+
+            // Route to built-in tools or MCP client
+            let is_mcp_tool = !self.is_builtin_tool(tool_name);
+            // Find which server a (namespaced) MCP tool belongs to and its own name
+            let mcp_tool: Option<(String, String)> = if is_mcp_tool {
+                self.mcp_client
+                    .as_ref()
+                    .and_then(|client| client.resolve_tool(tool_name))
+            } else {
+                None
+            };
+            let mcp_server_name = mcp_tool.as_ref().map(|(server, _)| server.clone());
+            // Logs and per-tool limits use the tool's name on its server
+            let logged_tool_name = mcp_tool
+                .as_ref()
+                .map_or(tool_name, |(_, name)| name.as_str());
+            let tool_type = if is_mcp_tool { "mcp" } else { "builtin" };
 
-            // Execute tools and build results
-            info!("Claude requested {} tool call(s)", tool_uses.len());
-            let mut tool_results: Vec<ContentBlock> = Vec::new();
-            let empty_input = json!({});
-            for tool_use in tool_uses {
-                let tool_name = tool_use.name.as_deref().unwrap_or("");
-                let tool_id = tool_use.id.as_deref().unwrap_or("");
-                let tool_input = tool_use.input.as_ref().unwrap_or(&empty_input);
-                let input_str = serde_json::to_string(tool_input).unwrap_or_default();
-
-                info!("Executing tool: {}", tool_name);
-                debug!("Tool input: {}", tool_input);
-
-                let tool_start = Instant::now();
-
-                // Route to built-in tools or MCP client
-                let is_mcp_tool = !self.is_builtin_tool(tool_name);
-                let mcp_server_name: Option<String> = if is_mcp_tool {
-                    // Find which server this tool belongs to
-                    self.mcp_client.as_ref().and_then(|client| {
-                        client
-                            .get_all_tools()
-                            .into_iter()
-                            .find(|t| t.tool.name == tool_name)
-                            .map(|t| t.server_name)
-                    })
-                } else {
-                    None
-                };
+            research_state::publish(
+                app_handle,
+                "research:tool_started",
+                ToolStartedEvent {
+                    timestamp: get_timestamp(),
+                    topic_name: topic.to_string(),
+                    tool_name: tool_name.to_string(),
+                    tool_type: tool_type.to_string(),
+                },
+            );
 
-                // Rate-limit expensive tools (firecrawl_agent: 5 free/day, then 200-600 credits)
-                const FIRECRAWL_AGENT_DAILY_LIMIT: i64 = 5;
-                let is_firecrawl_agent = tool_name.contains("firecrawl_agent");
-                let rate_limited = if is_firecrawl_agent && self.rate_limit_firecrawl_agent {
-                    // Check how many firecrawl_agent calls we've made today
-                    // Use SQLite date range for reliable comparison across timezones
-                    let daily_count = match crate::db::get_connection() {
-                        Ok(conn) => {
-                            match conn.query_row(
+            // Rate-limit expensive tools (firecrawl_agent: 5 free/day, then 200-600 credits)
+            const FIRECRAWL_AGENT_DAILY_LIMIT: i64 = 5;
+            let is_firecrawl_agent = tool_name.contains("firecrawl_agent");
+            let rate_limited = if is_firecrawl_agent && self.rate_limit_firecrawl_agent {
+                // Check how many firecrawl_agent calls we've made today
+                // Use SQLite date range for reliable comparison across timezones
+                let daily_count = match crate::db::get_connection() {
+                    Ok(conn) => {
+                        match conn.query_row(
                                 "SELECT COUNT(*) FROM research_logs
                                  WHERE tool_name LIKE '%firecrawl_agent%'
                                  AND created_at >= DATE('now', 'localtime', 'start of day')
@@ -1661,149 +2256,302 @@ Provide a concise but informative research summary (2-3 paragraphs) based on cur
                                     FIRECRAWL_AGENT_DAILY_LIMIT + 1
                                 }
                             }
-                        }
-                        Err(e) => {
-                            error!("Database connection failed during rate limit check: {}", e);
-                            // Fail closed: assume limit exceeded to prevent unexpected charges
-                            FIRECRAWL_AGENT_DAILY_LIMIT + 1
-                        }
-                    };
-
-                    if daily_count >= FIRECRAWL_AGENT_DAILY_LIMIT {
-                        warn!(
-                            "firecrawl_agent rate limited: {} calls today (limit: {})",
-                            daily_count, FIRECRAWL_AGENT_DAILY_LIMIT
-                        );
-                        true
-                    } else {
-                        info!(
-                            "firecrawl_agent allowed: {} of {} daily calls used",
-                            daily_count, FIRECRAWL_AGENT_DAILY_LIMIT
-                        );
-                        false
                     }
+                    Err(e) => {
+                        error!("Database connection failed during rate limit check: {}", e);
+                        // Fail closed: assume limit exceeded to prevent unexpected charges
+                        FIRECRAWL_AGENT_DAILY_LIMIT + 1
+                    }
+                };
+
+                if daily_count >= FIRECRAWL_AGENT_DAILY_LIMIT {
+                    warn!(
+                        "firecrawl_agent rate limited: {} calls today (limit: {})",
+                        daily_count, FIRECRAWL_AGENT_DAILY_LIMIT
+                    );
+                    true
                 } else {
+                    info!(
+                        "firecrawl_agent allowed: {} of {} daily calls used",
+                        daily_count, FIRECRAWL_AGENT_DAILY_LIMIT
+                    );
                     false
-                };
+                }
+            } else {
+                false
+            };
+
+            // Firecrawl tools are refused once a Firecrawl budget is used up
+            let is_firecrawl = is_mcp_tool && firecrawl_budget::is_firecrawl_tool(tool_name);
+            let budget_note = if is_firecrawl {
+                self.firecrawl_budget.prompt_note()
+            } else {
+                None
+            };
 
-                let result = if rate_limited {
-                    // Return error for rate-limited tools
-                    Err(format!(
+            // MCP tools bound to other topics are refused even if Claude asks
+            let unrouted = mcp_tool
+                .as_ref()
+                .is_some_and(|(server, name)| !self.mcp_routing.allows(Some(topic), server, name));
+
+            // Set when a built-in tool was served from the GitHub cache
+            let mut cache_hit = false;
+
+            let result = if rate_limited {
+                // Return error for rate-limited tools
+                Err(format!(
                         "Tool '{}' has reached its daily limit ({} calls). Please use firecrawl_search, firecrawl_scrape, or firecrawl_extract instead.",
                         tool_name, FIRECRAWL_AGENT_DAILY_LIMIT
                     ))
-                } else if self.is_builtin_tool(tool_name) {
-                    // Execute built-in tool
-                    execute_tool(
-                        &self.client,
-                        tool_name,
-                        tool_input,
-                        self.github_token.as_deref(),
-                    )
+            } else if unrouted {
+                Err(format!(
+                    "Tool '{}' is not available for the topic '{}'",
+                    tool_name, topic
+                ))
+            } else if let Some(note) = budget_note {
+                Err(note)
+            } else if self.is_builtin_tool(tool_name) && self.offline {
+                // Offline mode: serve built-in tools from the content cache
+                execute_tool_offline(tool_name, tool_input).inspect(|_| cache_hit = true)
+            } else if self.is_builtin_tool(tool_name) {
+                // Execute built-in tool
+                self.tools
+                    .execute(&self.client, tool_name, tool_input)
                     .await
-                } else if let Some(ref mut mcp_client) = self.mcp_client {
-                    // Execute MCP tool
-                    mcp_client
-                        .call_tool(tool_name, tool_input.clone())
-                        .map(|v| {
-                            if let Some(s) = v.as_str() {
-                                s.to_string()
-                            } else {
-                                serde_json::to_string_pretty(&v).unwrap_or_default()
-                            }
-                        })
-                } else {
-                    Err(format!("Unknown tool: {}", tool_name))
-                };
+                    .map(|output| {
+                        cache_hit = output.from_cache;
+                        if let Some((kind, key)) = tools::cache_key(tool_name, tool_input) {
+                            store_cached_content(kind, &key, topic, &output.text);
+                        }
+                        output.text
+                    })
+            } else if let Some(ref mcp_client) = self.mcp_client {
+                // Execute MCP tool; the call has its own timeout and is
+                // abandoned cleanly if the per-topic timeout fires first
+                mcp_client
+                    .call_tool(tool_name, tool_input.clone())
+                    .await
+                    .map(|v| mcp_result_text(&v))
+            } else {
+                Err(format!("Unknown tool: {}", tool_name))
+            };
 
-                let tool_duration = tool_start.elapsed().as_millis() as i64;
+            let tool_duration = tool_start.elapsed().as_millis() as i64;
+
+            // Cap oversized MCP results before they reach the conversation
+            let mut truncation_note: Option<String> = None;
+            let result = match result {
+                Ok(output) if is_mcp_tool => {
+                    let (limited, note, summary_tokens) =
+                        self.limit_tool_result(logged_tool_name, output).await;
+                    conversation.total_tokens += summary_tokens;
+                    truncation_note = note;
+                    Ok(limited)
+                }
+                other => other,
+            };
 
-                let (content, is_error) = match result {
-                    Ok(output) => {
-                        info!(
-                            "Tool {} completed in {}ms (output: {} chars)",
+            let result = match result {
+                Ok(output) => {
+                    info!(
+                        "Tool {} completed in {}ms (output: {} chars{})",
+                        tool_name,
+                        tool_duration,
+                        output.len(),
+                        if cache_hit { ", cached" } else { "" }
+                    );
+                    // Log successful tool call - use MCP logging if it's an MCP tool
+                    if is_mcp_tool {
+                        let server_name = mcp_server_name.as_deref().unwrap_or("unknown");
+                        let logged_output = match &truncation_note {
+                            Some(note) => format!("[{}] {}", note, output),
+                            None => output.clone(),
+                        };
+                        let _ = ResearchLogger::log_mcp_call(
+                            topic,
+                            server_name,
+                            logged_tool_name,
+                            &input_str,
+                            &logged_output,
+                            tool_duration,
+                        );
+                    } else if cache_hit {
+                        let _ = ResearchLogger::log_tool_call(
+                            topic,
                             tool_name,
+                            &input_str,
+                            &format!("[cache hit] {}", output),
                             tool_duration,
-                            output.len()
                         );
-                        // Log successful tool call - use MCP logging if it's an MCP tool
-                        if is_mcp_tool {
-                            let server_name = mcp_server_name.as_deref().unwrap_or("unknown");
-                            let _ = ResearchLogger::log_mcp_call(
-                                topic,
-                                server_name,
-                                tool_name,
-                                &input_str,
-                                &output,
-                                tool_duration,
-                            );
-                        } else {
-                            let _ = ResearchLogger::log_tool_call(
-                                topic,
-                                tool_name,
-                                &input_str,
-                                &output,
-                                tool_duration,
-                            );
+                    } else {
+                        let _ = ResearchLogger::log_tool_call(
+                            topic,
+                            tool_name,
+                            &input_str,
+                            &output,
+                            tool_duration,
+                        );
+                    }
+                    if is_firecrawl {
+                        for warning in self.firecrawl_budget.record_call() {
+                            self.raise_budget_warning(warning, app_handle);
                         }
-                        (output, None)
                     }
-                    Err(e) => {
-                        error!("Tool {} failed: {}", tool_name, e);
-                        // Log failed tool call - use appropriate error code for MCP tools
-                        let err = if is_mcp_tool {
-                            ResearchError::new(ErrorCode::McpToolFailed, &e)
-                        } else {
-                            ResearchError::new(ErrorCode::ToolExecutionFailed, &e)
-                        };
+                    self.partial_findings.push(format!(
+                        "### {} result\n{}",
+                        tool_name,
+                        output
+                            .chars()
+                            .take(PARTIAL_FINDING_MAX_CHARS)
+                            .collect::<String>()
+                    ));
+                    Ok(output)
+                }
+                Err(e) => {
+                    error!("Tool {} failed: {}", tool_name, e);
+                    // Log failed tool call - use appropriate error code for MCP tools
+                    let err = if is_mcp_tool {
+                        ResearchError::new(ErrorCode::McpToolFailed, &e)
+                    } else {
+                        ResearchError::new(ErrorCode::ToolExecutionFailed, &e)
+                    };
 
-                        if is_mcp_tool {
-                            let server_name = mcp_server_name.as_deref().unwrap_or("unknown");
-                            let _ = ResearchLogger::log_mcp_error(topic, server_name, &err);
-                        } else {
-                            let _ = ResearchLogger::log_tool_error(
-                                topic,
-                                tool_name,
-                                &input_str,
-                                &err,
-                                tool_duration,
-                            );
-                        }
-                        (format!("Error: {}", e), Some(true))
+                    if is_mcp_tool {
+                        let server_name = mcp_server_name.as_deref().unwrap_or("unknown");
+                        let _ = ResearchLogger::log_mcp_error(
+                            topic,
+                            server_name,
+                            logged_tool_name,
+                            &err,
+                            tool_duration,
+                        );
+                    } else {
+                        let _ = ResearchLogger::log_tool_error(
+                            topic,
+                            tool_name,
+                            &input_str,
+                            &err,
+                            tool_duration,
+                        );
                     }
-                };
+                    Err(e)
+                }
+            };
 
-                tool_results.push(ContentBlock::ToolResult {
-                    tool_use_id: tool_id.to_string(),
-                    content,
-                    is_error,
-                });
-            }
+            research_state::publish(
+                app_handle,
+                "research:tool_executed",
+                ToolExecutedEvent {
+                    timestamp: get_timestamp(),
+                    topic_name: topic.to_string(),
+                    tool_name: tool_name.to_string(),
+                    tool_type: tool_type.to_string(),
+                    status: if result.is_ok() { "success" } else { "error" }.to_string(),
+                    error: result.as_ref().err().cloned(),
+                },
+            );
 
-            // Add tool results as user message
-            messages.push(Message {
-                role: "user".to_string(),
-                content: MessageContent::Blocks(tool_results),
-            });
+            tool_results.push(ContentBlock::tool_result(tool_id, result));
         }
 
-        // If we exit the loop due to max iterations, extract any text we have
-        Ok((
-            "Research completed (max iterations reached)".to_string(),
-            total_tokens,
-        ))
+        // Add the turn and its tool results only once every tool has run, so a
+        // conversation interrupted mid-turn can be resent as it was
+        conversation.messages.push(assistant_message);
+        conversation
+            .messages
+            .push(Message::tool_results(tool_results));
+        None
+    }
+
+    /// Cap an MCP tool result at its configured size. The head and tail are kept and
+    /// the omitted middle is replaced by a short summary from a cheap model, or by a
+    /// plain marker if that call fails. Returns the result, a note for the tool log
+    /// when it was truncated, and the tokens spent on the summary.
+    async fn limit_tool_result(
+        &self,
+        tool_name: &str,
+        output: String,
+    ) -> (String, Option<String>, u32) {
+        let max_chars = self
+            .tool_result_limits
+            .get(tool_name)
+            .copied()
+            .unwrap_or(self.result_max_chars);
+        let truncated = match truncate_result(&output, max_chars) {
+            Some(truncated) => truncated,
+            None => return (output, None, 0),
+        };
+
+        let omitted_chars = truncated.omitted.chars().count();
+        let note = format!(
+            "truncated {} -> {} chars",
+            truncated.original_chars,
+            truncated.original_chars - omitted_chars
+        );
+        info!("MCP tool {} result {}", tool_name, note);
+
+        let (marker, tokens) = match self.summarize_omitted(tool_name, &truncated.omitted).await {
+            Some((summary, tokens)) => (
+                format!(
+                    "[... {} chars omitted. Summary of the omitted section:\n{}\n...]",
+                    omitted_chars, summary
+                ),
+                tokens,
+            ),
+            None => (format!("[... {} chars omitted ...]", omitted_chars), 0),
+        };
+
+        (
+            format!("{}\n{}\n{}", truncated.head, marker, truncated.tail),
+            Some(note),
+            tokens,
+        )
+    }
+
+    /// Summarize the omitted middle of a truncated tool result with a cheap model.
+    async fn summarize_omitted(&self, tool_name: &str, omitted: &str) -> Option<(String, u32)> {
+        let excerpt: String = omitted
+            .chars()
+            .take(TRUNCATION_SUMMARY_INPUT_CHARS)
+            .collect();
+        let request = MessagesRequest {
+            model: TRUNCATION_SUMMARY_MODEL.to_string(),
+            max_tokens: 512,
+            messages: vec![Message::user(format!(
+                "The following section was cut from a large result of the '{}' tool to save space. \
+                 Summarize the facts, names, dates and URLs it contains in at most 8 short bullet points. \
+                 Reply with the bullet points only.\n\n{}",
+                tool_name, excerpt
+            ))],
+            tools: None,
+            system: None,
+            thinking: None,
+            metadata: self.endpoint.metadata(),
+        };
+
+        match self.send_request(&request).await {
+            Ok(response) => {
+                let summary = response.text();
+                (!summary.trim().is_empty()).then_some((summary, response.total_tokens()))
+            }
+            Err(e) => {
+                warn!("Failed to summarize truncated {} result: {}", tool_name, e);
+                None
+            }
+        }
     }
 
     /// Send a request to the Anthropic API.
     async fn send_request(
         &self,
-        request: &AnthropicRequest,
-    ) -> Result<AnthropicResponse, ResearchError> {
+        request: &MessagesRequest,
+    ) -> Result<MessagesResponse, ResearchError> {
         let response = self
-            .client
-            .post("https://api.anthropic.com/v1/messages")
-            .header("x-api-key", &self.api_key)
-            .header("anthropic-version", "2023-06-01")
+            .endpoint
+            .authorize(
+                self.client.post(self.endpoint.messages_url()),
+                &self.api_key,
+            )
             .header("content-type", "application/json")
             .json(request)
             .send()
@@ -1817,29 +2565,183 @@ Provide a concise but informative research summary (2-3 paragraphs) based on cur
                 err
             })?;
 
-        if !response.status().is_success() {
-            let status = response.status().as_u16();
-            let body = response.text().await.unwrap_or_default();
-            let err = parse_api_error(status, &body);
+        let request_id = crate::agent_loop::request_id(&response);
+        if !response.status().is_success() {
+            let status = response.status().as_u16();
+            let body = response.text().await.unwrap_or_default();
+            let err = parse_api_error(status, &body).with_request_id(request_id);
+
+            // Log the error
+            error!(
+                "API error {}: {} (code: {:?}, request: {})",
+                status,
+                err.message,
+                err.code,
+                err.request_id.as_deref().unwrap_or("unknown")
+            );
+            if err.requires_user_action {
+                error!("USER ACTION REQUIRED: {}", err.user_message);
+            }
+
+            return Err(err);
+        }
+
+        let mut response: MessagesResponse = response.json().await.map_err(|e| {
+            ResearchError::new(
+                ErrorCode::ParseError,
+                format!("Failed to parse response: {}", e),
+            )
+            .with_request_id(request_id.clone())
+        })?;
+        response.request_id = request_id;
+        Ok(response)
+    }
+
+    /// Check every card's sources, give up to `MAX_REPAIR_CARDS` failing
+    /// cards one repair round, and mark the cards that still fail with a
+    /// `source_warning`. Returns the tokens the repair request used.
+    async fn enforce_source_requirements(
+        &self,
+        cards: &mut [BriefingCard],
+        research_content: &str,
+    ) -> u32 {
+        let today = chrono::Local::now().date_naive();
+        let issues: Vec<(usize, String)> = cards
+            .iter()
+            .enumerate()
+            .filter_map(|(i, card)| {
+                let recency_days = self.card_recency_days(&card.topic);
+                sources::source_issue(card, recency_days, today).map(|issue| (i, issue))
+            })
+            .collect();
+        if issues.is_empty() {
+            return 0;
+        }
+
+        let mut tokens = 0;
+        if self.repair_weak_cards && self.check_cancellation().is_ok() {
+            let batch: Vec<(usize, String)> =
+                issues.iter().take(MAX_REPAIR_CARDS).cloned().collect();
+            research_state::set_phase(&format!("Repairing sources for {} card(s)...", batch.len()));
+            match self.repair_cards(cards, &batch, research_content).await {
+                Ok((mut repaired, repair_tokens)) => {
+                    tokens = repair_tokens;
+                    if !self.offline {
+                        sources::validate_cards(&mut repaired, self.archive_sources).await;
+                    }
+                    for ((i, _), card) in batch.iter().zip(repaired) {
+                        cards[*i] = card;
+                    }
+                }
+                Err(e) => warn!("Source repair failed, keeping cards as-is: {}", e.message),
+            }
+        }
+
+        // Whatever still fails is saved, but flagged
+        for (i, _) in &issues {
+            let card = &mut cards[*i];
+            let recency_days = self.card_recency_days(&card.topic);
+            card.source_warning = sources::source_issue(card, recency_days, today);
+            if let Some(ref warning) = card.source_warning {
+                warn!("Card '{}' failed source checks: {}", card.title, warning);
+            }
+        }
+
+        tokens
+    }
+
+    /// Ask Claude to add sources (or newer ones) to the cards in `batch`,
+    /// drawing only on the research content. Returns one card per entry in
+    /// `batch`, falling back to the original where the reply didn't match up.
+    async fn repair_cards(
+        &self,
+        cards: &[BriefingCard],
+        batch: &[(usize, String)],
+        research_content: &str,
+    ) -> Result<(Vec<BriefingCard>, u32), ResearchError> {
+        let listed: Vec<String> = batch
+            .iter()
+            .enumerate()
+            .map(|(n, (i, issue))| {
+                format!(
+                    "{}. Problem: {}\n{}",
+                    n + 1,
+                    issue,
+                    serde_json::to_string(&cards[*i]).unwrap_or_default()
+                )
+            })
+            .collect();
+        let recency_days = batch
+            .iter()
+            .map(|(i, _)| self.card_recency_days(&cards[*i].topic))
+            .max()
+            .unwrap_or(0);
+        let recency = if recency_days > 0 {
+            format!(
+                " At least one source should be from the last {} days.",
+                recency_days
+            )
+        } else {
+            String::new()
+        };
+        let prompt = format!(
+            r#"These briefing cards failed source checks. Revise each one so it cites sources for its claims.{}
+
+Use ONLY source URLs that appear in the RESEARCH CONTENT below, preferring the most recent. Do not invent URLs. Keep each card's title, topic and content unless a newer source changes the facts. If the research content has no suitable source for a card, return it unchanged.
 
-            // Log the error
-            error!(
-                "API error {}: {} (code: {:?})",
-                status, err.message, err.code
-            );
-            if err.requires_user_action {
-                error!("USER ACTION REQUIRED: {}", err.user_message);
-            }
+CARDS:
+{}
 
-            return Err(err);
-        }
+RESEARCH CONTENT:
+{}
 
-        response.json().await.map_err(|e| {
-            ResearchError::new(
-                ErrorCode::ParseError,
-                format!("Failed to parse response: {}", e),
-            )
-        })
+Return ONLY valid JSON in the form {{"cards": [...]}} with exactly {} revised cards, in the same order and with the same fields as above."#,
+            recency,
+            listed.join("\n\n"),
+            research_content,
+            batch.len()
+        );
+
+        let request = MessagesRequest {
+            model: self.model.clone(),
+            max_tokens: REPAIR_MAX_TOKENS,
+            messages: vec![Message::user(prompt)],
+            tools: None,
+            system: None,
+            thinking: None,
+            metadata: self.endpoint.metadata(),
+        };
+
+        info!("Asking Claude to repair sources for {} cards", batch.len());
+        let response = self.send_request(&request).await?;
+        let tokens = response.total_tokens();
+        let revised = parse_briefing_response(&response.text())
+            .map_err(|e| ResearchError::new(ErrorCode::ParseError, e))?;
+
+        let repaired = batch
+            .iter()
+            .enumerate()
+            .map(|(n, (i, _))| {
+                let original = &cards[*i];
+                let replacement = if revised.len() == batch.len() {
+                    revised.get(n)
+                } else {
+                    revised.iter().find(|c| c.title == original.title)
+                };
+                match replacement {
+                    Some(card) => BriefingCard {
+                        source_checks: Vec::new(),
+                        source_warning: None,
+                        rank: None,
+                        image_path: original.image_path.clone(),
+                        ..card.clone()
+                    },
+                    None => original.clone(),
+                }
+            })
+            .collect();
+
+        Ok((repaired, tokens))
     }
 
     /// Synthesize research results into briefing cards.
@@ -1848,6 +2750,7 @@ Provide a concise but informative research summary (2-3 paragraphs) based on cur
         research_content: &str,
         app_handle: Option<&tauri::AppHandle>,
         condense_briefings: bool,
+        format: &str,
         past_cards_context: Option<&str>,
     ) -> Result<(Vec<BriefingCard>, u32), ResearchError> {
         // Build the deduplication context if available
@@ -1873,24 +2776,21 @@ When generating cards, avoid creating cards that duplicate these previously cove
         };
 
         // Adjust content requirements based on research mode
-        let is_deep_research = self.research_mode == "firecrawl";
-        let (min_words_condensed, min_paragraphs_condensed) = if is_deep_research {
-            (800, "8-12")  // Deep research: more comprehensive
-        } else {
-            (400, "5-7")   // Standard: normal length
-        };
-        let (min_words_standard, min_paragraphs_standard) = if is_deep_research {
-            (350, "4-6")   // Deep research: more detailed per card
-        } else {
-            (150, "2-3")   // Standard: normal length
-        };
-        let depth_instruction = if is_deep_research {
-            "\n**DEEP RESEARCH MODE**: You have access to comprehensive web extraction. Provide EXTRA detail, analysis, and insights. Include more sources, deeper technical analysis, and thorough coverage. Users are paying premium credits for this depth - deliver exceptional value."
-        } else {
-            ""
-        };
-
-        let prompt = if condense_briefings {
+        let length = self.mode.card_length();
+        let (min_words_condensed, min_paragraphs_condensed) =
+            (length.condensed_words, length.condensed_paragraphs);
+        let (min_words_standard, min_paragraphs_standard) =
+            (length.card_words, length.card_paragraphs);
+        let depth_instruction = self.mode.depth_instruction();
+
+        let prompt = if let Some(prompt) = build_format_prompt(
+            format,
+            depth_instruction,
+            &dedup_instruction,
+            research_content,
+        ) {
+            prompt
+        } else if condense_briefings {
             // Condensed mode: one comprehensive card combining all topics
             format!(
                 r#"You are a research assistant creating a personalized daily briefing.
@@ -1920,11 +2820,19 @@ For the single card, provide:
   - This is the user's "daily read" - make it engaging and insightful
 - **Sources**: Combined list of all source URLs
 - **Suggested Next**: Key action or focus area based on the briefing
+- **Actions**: Structured follow-ups (see CARD ACTIONS below), or an empty array
 - **Relevance**: "high" (single briefing is always high priority)
 - **Topic**: "Daily Briefing"
 - **Image Prompt**: SHORT visual description (max 8 words, plain text only, no quotes or special characters).
   Examples: "futuristic city skyline at sunset", "abstract flowing data streams"
 
+CARD ACTIONS (optional, at most 2 per card, only when clearly useful):
+- {{"type": "remind_me", "message": "Check whether the release candidate shipped", "in_hours": 72}}
+- {{"type": "open_url", "url": "https://example.com/release-notes", "label": "Release notes"}}
+- {{"type": "create_github_issue", "repo": "owner/name", "title": "Issue title", "body": "Issue body"}}
+- {{"type": "add_calendar_event", "title": "Event title", "date": "YYYY-MM-DD", "time": "HH:MM", "duration_minutes": 60}}
+Only use add_calendar_event for a concrete dated event in the research (launch, conference, deadline), and create_github_issue for a specific repository it mentions.
+
 Return ONLY valid JSON in this exact format:
 {{
   "cards": [
@@ -1934,6 +2842,7 @@ Return ONLY valid JSON in this exact format:
       "detailed_content": "**Key Themes**\\n\\nOpening paragraph introduces key themes and sets the stage for the briefing.\\n\\n**Topic Area One**\\n\\nThis section covers the first major topic area with **key findings** highlighted.\\n\\n- Important point one\\n- Important point two\\n\\n**Topic Area Two**\\n\\nSubsequent sections cover each major topic area, weaving them together into a coherent narrative.\\n\\n**Implications**\\n\\nAnalysis explores implications, connections between topics, and deeper insights.\\n\\n**Key Takeaways**\\n\\nConcluding section summarizes key takeaways and what to watch for going forward.",
       "sources": ["https://example.com/source1", "https://example.com/source2"],
       "suggested_next": "Key action or focus area",
+      "actions": [],
       "relevance": "high",
       "topic": "Daily Briefing",
       "image_prompt": "abstract network of connected glowing nodes"
@@ -1979,6 +2888,7 @@ For each card, provide:
   - Think of this as the "full story" while summary is the "headline"
 - **Sources**: List of source URLs (if available, otherwise empty array)
 - **Suggested Next**: Optional next action or follow-up
+- **Actions**: Structured follow-ups (see CARD ACTIONS below), or an empty array
 - **Relevance**: "high", "medium", or "low"
 - **Topic**: The original topic this relates to
 - **Image Prompt**: SHORT visual description (max 8 words, plain text only, no quotes or special characters).
@@ -1987,6 +2897,13 @@ For each card, provide:
 IMPORTANT: The detailed_content must be significantly more comprehensive than the summary.
 The summary is what users see at a glance. The detailed_content is what they read when they want the full analysis.
 
+CARD ACTIONS (optional, at most 2 per card, only when clearly useful):
+- {{"type": "remind_me", "message": "Check whether the release candidate shipped", "in_hours": 72}}
+- {{"type": "open_url", "url": "https://example.com/release-notes", "label": "Release notes"}}
+- {{"type": "create_github_issue", "repo": "owner/name", "title": "Issue title", "body": "Issue body"}}
+- {{"type": "add_calendar_event", "title": "Event title", "date": "YYYY-MM-DD", "time": "HH:MM", "duration_minutes": 60}}
+Only use add_calendar_event for a concrete dated event in the research (launch, conference, deadline), and create_github_issue for a specific repository it mentions.
+
 Return ONLY valid JSON in this exact format:
 {{
   "cards": [
@@ -1996,6 +2913,7 @@ Return ONLY valid JSON in this exact format:
       "detailed_content": "**Context and Background**\\n\\nFirst paragraph provides context and background information about the topic, explaining the current situation and recent developments.\\n\\n**Key Findings**\\n\\nSecond paragraph dives into the technical details, implications, and analysis of what this means:\\n\\n- Important finding or data point\\n- Another key insight from the research\\n- Relevant quote or statistic\\n\\n**Looking Ahead**\\n\\nThird paragraph discusses future implications, what to watch for, and how this connects to broader trends or related topics.",
       "sources": ["https://example.com/source1"],
       "suggested_next": "Optional next action",
+      "actions": [{{"type": "remind_me", "message": "Follow up on this development", "in_hours": 48}}],
       "relevance": "high",
       "topic": "Original topic name",
       "image_prompt": "futuristic circuit board with glowing pathways"
@@ -2008,28 +2926,26 @@ Return the JSON response now:"#,
             )
         };
 
-        let request = AnthropicRequest {
+        let request = MessagesRequest {
             model: self.model.clone(),
             max_tokens: 16384, // Large enough for many cards with detailed_content + image fields
-            messages: vec![Message {
-                role: "user".to_string(),
-                content: MessageContent::Text(prompt),
-            }],
+            messages: vec![Message::user(prompt)],
             tools: None,
-            system: None,
+            system: format_about_me_prompt(&self.about_me),
+            thinking: None,
+            metadata: self.endpoint.metadata(),
         };
 
         // Update phase and emit synthesis:started event
         research_state::set_phase("Synthesizing briefing cards...");
-        if let Some(app) = app_handle {
-            let _ = app.emit(
-                "research:synthesis_started",
-                SynthesisStartedEvent {
-                    timestamp: get_timestamp(),
-                    research_content_length: research_content.len(),
-                },
-            );
-        }
+        research_state::publish(
+            app_handle,
+            "research:synthesis_started",
+            SynthesisStartedEvent {
+                timestamp: get_timestamp(),
+                research_content_length: research_content.len(),
+            },
+        );
 
         info!(
             "Calling Claude API for synthesis (research content: {} chars)",
@@ -2039,14 +2955,8 @@ Return the JSON response now:"#,
         let response = self.send_request(&request).await?;
         let synthesis_duration = synthesis_start.elapsed().as_millis();
 
-        let content = response
-            .content
-            .iter()
-            .filter_map(|c| c.text.clone())
-            .collect::<Vec<_>>()
-            .join("\n");
-
-        let tokens = response.usage.input_tokens + response.usage.output_tokens;
+        let content = response.text();
+        let tokens = response.total_tokens();
 
         info!(
             "Synthesis API responded in {}ms ({} tokens)",
@@ -2064,75 +2974,293 @@ Return the JSON response now:"#,
 
         // Update phase and emit synthesis:completed event
         research_state::set_phase(&format!("Synthesis complete: {} cards", cards.len()));
-        if let Some(app) = app_handle {
-            let _ = app.emit(
-                "research:synthesis_completed",
-                SynthesisCompletedEvent {
-                    timestamp: get_timestamp(),
-                    cards_generated: cards.len(),
-                    duration_ms: synthesis_duration,
-                },
-            );
-        }
+        research_state::publish(
+            app_handle,
+            "research:synthesis_completed",
+            SynthesisCompletedEvent {
+                timestamp: get_timestamp(),
+                cards_generated: cards.len(),
+                duration_ms: synthesis_duration,
+            },
+        );
 
         Ok((cards, tokens))
     }
 }
 
-/// Parse Claude's response into BriefingCard objects.
-fn parse_briefing_response(response: &str) -> Result<Vec<BriefingCard>, String> {
-    // Try to extract JSON from response (Claude might wrap it in markdown)
-    // Use (?s) flag for DOTALL mode to match across newlines
-    let json_str = if let Some(captures) = Regex::new(r"(?s)```(?:json)?\s*(\{.*\})\s*```")
-        .ok()
-        .and_then(|re| re.captures(response))
-    {
-        captures.get(1).map(|m| m.as_str()).unwrap_or(response)
-    } else if let Some(captures) = Regex::new(r"(?s)(\{.*\})")
-        .ok()
-        .and_then(|re| re.captures(response))
-    {
-        captures.get(1).map(|m| m.as_str()).unwrap_or(response)
-    } else {
-        response
+/// Maximum characters of the user's "about me" text included in prompts.
+const ABOUT_ME_MAX_CHARS: usize = 2000;
+
+/// Build the prompt section describing the user, or None if they haven't written one.
+fn format_about_me_prompt(about_me: &str) -> Option<String> {
+    let about_me = about_me.trim();
+    if about_me.is_empty() {
+        return None;
+    }
+    let about_me: String = about_me.chars().take(ABOUT_ME_MAX_CHARS).collect();
+    Some(format!(
+        r#"ABOUT THE USER:
+{}
+
+Judge relevance for this specific person: rate developments that affect their work, stack or stated interests higher, and pitch explanations at their level of expertise (skip basics an expert already knows; explain jargon for a newcomer). Do not mention this profile in your output."#,
+        about_me
+    ))
+}
+
+/// Build the synthesis prompt for a non-standard briefing format.
+///
+/// Returns `None` for "standard", which uses the regular (or condensed) prompt.
+fn build_format_prompt(
+    format: &str,
+    depth_instruction: &str,
+    dedup_instruction: &str,
+    research_content: &str,
+) -> Option<String> {
+    let (style, card_guidelines, example) = match format {
+        "newsletter" => (
+            "Write the briefing as a newsletter issue: a few narrative cards that read like feature sections, with a conversational but informed voice.",
+            r#"- **Title**: A feature-style headline (max 70 chars)
+- **Summary**: A hook paragraph that makes the reader want to continue (2-3 sentences)
+- **Detailed Content**: Flowing MARKDOWN prose (minimum 250 words) that tells the story of the development, with context, why it matters, and what comes next. Use **bold** sparingly for key terms; avoid bullet lists except for short recaps.
+- Maximum 6 cards; group related topics into one section where it reads naturally"#,
+            r#"{
+      "title": "The Week Open Models Caught Up",
+      "summary": "A hook paragraph introducing the story and why the reader should care.",
+      "detailed_content": "Opening paragraph that sets the scene.\n\nMiddle paragraphs that explain what happened and why it matters.\n\n**What's next**: a closing paragraph on what to watch.",
+      "sources": ["https://example.com/source1"],
+      "suggested_next": "Optional next action",
+      "relevance": "high",
+      "topic": "Original topic name",
+      "image_prompt": "newspaper pages fanned across a desk"
+    }"#,
+        ),
+        "digest" => (
+            "Write the briefing as a terse bullet digest for skimming. No narrative, no filler.",
+            r#"- **Title**: Short, specific title (max 50 chars)
+- **Summary**: ONE sentence stating the single most important fact
+- **Detailed Content**: 3-6 MARKDOWN bullet points, each a single line of at most 25 words, leading with the concrete fact (numbers, names, dates)
+- Maximum 12 cards; one card per distinct development"#,
+            r#"{
+      "title": "Card title",
+      "summary": "One sentence with the key fact.",
+      "detailed_content": "- First key fact with a concrete detail\n- Second key fact\n- Third key fact",
+      "sources": ["https://example.com/source1"],
+      "suggested_next": null,
+      "relevance": "medium",
+      "topic": "Original topic name",
+      "image_prompt": "bulleted list on a clipboard"
+    }"#,
+        ),
+        "executive" => (
+            "Write the briefing as an executive summary for a busy decision-maker: conclusions first, then the evidence, then what to do about it.",
+            r#"- **Title**: The conclusion, stated plainly (max 70 chars)
+- **Summary**: Bottom line up front - what happened and what it means (2 sentences)
+- **Detailed Content**: MARKDOWN with **Situation**, **Implications** and **Risks** sections (100-200 words total)
+- **Action Items**: 1-4 concrete, imperative follow-up actions (e.g. "Evaluate X for the Q3 roadmap")
+- Maximum 5 cards; one per area that needs attention"#,
+            r#"{
+      "title": "Card title stating the conclusion",
+      "summary": "Bottom line up front.",
+      "detailed_content": "**Situation**\n\nWhat happened.\n\n**Implications**\n\nWhat it means.\n\n**Risks**\n\nWhat could go wrong.",
+      "action_items": ["First concrete action", "Second concrete action"],
+      "sources": ["https://example.com/source1"],
+      "suggested_next": "The single most important action",
+      "relevance": "high",
+      "topic": "Original topic name",
+      "image_prompt": "chess pieces on a boardroom table"
+    }"#,
+        ),
+        _ => return None,
     };
 
-    // Parse JSON - if it fails, try to provide helpful error message
-    match serde_json::from_str::<BriefingResponse>(json_str) {
-        Ok(briefing_response) => Ok(briefing_response.cards),
-        Err(e) => {
-            // Check if response looks truncated (EOF errors)
-            let error_msg = e.to_string();
-            if error_msg.contains("EOF") {
-                // Try to fix truncated JSON by closing the array and object
-                let fixed_attempt = format!("{}\n]\n}}", json_str.trim_end_matches(','));
-                if let Ok(briefing_response) =
-                    serde_json::from_str::<BriefingResponse>(&fixed_attempt)
-                {
-                    warn!(
-                        "Recovered {} cards from truncated response",
-                        briefing_response.cards.len()
-                    );
-                    return Ok(briefing_response.cards);
+    Some(format!(
+        r#"You are a research assistant creating a personalized daily briefing.
+{}
+{}
+CRITICAL: ONLY create cards for topics that appear in the RESEARCH CONTENT below.
+Do NOT create cards for topics mentioned in the deduplication list - that list is ONLY to help you avoid repeating old content.
+{}
+{}
+
+For each card, provide:
+{}
+- **Sources**: List of source URLs (if available, otherwise empty array)
+- **Suggested Next**: Optional next action or follow-up
+- **Actions**: Structured follow-ups (see CARD ACTIONS below), or an empty array
+- **Relevance**: "high", "medium", or "low"
+- **Topic**: The original topic this relates to
+- **Image Prompt**: SHORT visual description (max 8 words, plain text only, no quotes or special characters)
+
+CARD ACTIONS (optional, at most 2 per card, only when clearly useful):
+- {{"type": "remind_me", "message": "Check whether the release candidate shipped", "in_hours": 72}}
+- {{"type": "open_url", "url": "https://example.com/release-notes", "label": "Release notes"}}
+- {{"type": "create_github_issue", "repo": "owner/name", "title": "Issue title", "body": "Issue body"}}
+- {{"type": "add_calendar_event", "title": "Event title", "date": "YYYY-MM-DD", "time": "HH:MM", "duration_minutes": 60}}
+Only use add_calendar_event for a concrete dated event in the research (launch, conference, deadline), and create_github_issue for a specific repository it mentions.
+
+Return ONLY valid JSON in this exact format:
+{{
+  "cards": [
+    {}
+  ]
+}}
+
+Return the JSON response now:"#,
+        style, depth_instruction, dedup_instruction, research_content, card_guidelines, example
+    ))
+}
+
+/// Most `{` positions tried as the start of the briefing JSON, so a
+/// response full of stray braces can't make parsing quadratic.
+const MAX_JSON_CANDIDATES: usize = 16;
+
+/// How far the JSON object starting at some `{` goes.
+#[derive(Debug, PartialEq)]
+enum ScannedObject {
+    /// Balanced; ends (exclusive) at this byte offset
+    Complete(usize),
+    /// The response ends inside it. Holds the end of its last complete
+    /// card, if any, so the cards before the cut can be kept.
+    Truncated(Option<usize>),
+}
+
+/// Find the end of the JSON object starting at `start` by counting
+/// brackets outside of strings, so braces and code fences inside card text
+/// don't end it early (or late, as a greedy regex would).
+fn scan_object(text: &str, start: usize) -> ScannedObject {
+    let mut open: Vec<u8> = Vec::new();
+    let mut in_string = false;
+    let mut escaped = false;
+    let mut last_card = None;
+    // JSON's delimiters are ASCII, which never occurs inside a multi-byte
+    // UTF-8 character, so every offset after one is a char boundary
+    for (at, &byte) in text.as_bytes().iter().enumerate().skip(start) {
+        if in_string {
+            if escaped {
+                escaped = false;
+            } else if byte == b'\\' {
+                escaped = true;
+            } else if byte == b'"' {
+                in_string = false;
+            }
+            continue;
+        }
+        match byte {
+            b'"' => in_string = true,
+            b'{' | b'[' => open.push(byte),
+            b'}' | b']' => {
+                open.pop();
+                if open.is_empty() {
+                    return ScannedObject::Complete(at + 1);
+                }
+                // A card: an object directly inside the top-level array
+                if byte == b'}' && open == [b'{', b'['] {
+                    last_card = Some(at + 1);
                 }
+            }
+            _ => {}
+        }
+    }
+    ScannedObject::Truncated(last_card)
+}
 
-                Err(format!(
-                    "Response was truncated (likely hit max_tokens limit). Increase max_tokens in synthesis call. \
-                    Error: {}. Response length: {} chars. Last 200 chars: ...{}",
-                    error_msg,
-                    json_str.len(),
-                    &json_str[json_str.len().saturating_sub(200)..]
-                ))
-            } else {
-                Err(format!(
-                    "Failed to parse briefing JSON: {}. Response length: {} chars. First 500 chars: {}...",
-                    error_msg,
-                    json_str.len(),
-                    &json_str[..json_str.len().min(500)]
-                ))
+/// The first `max_chars` characters of `text`.
+fn head_chars(text: &str, max_chars: usize) -> &str {
+    text.char_indices()
+        .nth(max_chars)
+        .map_or(text, |(end, _)| &text[..end])
+}
+
+/// The last `max_chars` characters of `text`.
+fn tail_chars(text: &str, max_chars: usize) -> &str {
+    match max_chars.checked_sub(1) {
+        Some(skip) => text
+            .char_indices()
+            .rev()
+            .nth(skip)
+            .map_or(text, |(start, _)| &text[start..]),
+        None => "",
+    }
+}
+
+/// Parse Claude's response into BriefingCard objects.
+///
+/// The cards are the first JSON object in the response that parses, so
+/// prose or markdown fences around it are ignored. If the response was cut
+/// off (max_tokens), the cards completed before the cut are kept.
+pub(crate) fn parse_briefing_response(response: &str) -> Result<Vec<BriefingCard>, String> {
+    let mut failed: Option<(&str, serde_json::Error)> = None;
+    let mut truncated: Option<(&str, serde_json::Error)> = None;
+    let mut next = 0;
+    let mut tried = 0;
+    for (start, _) in response.match_indices('{') {
+        if start < next {
+            continue;
+        }
+        if tried == MAX_JSON_CANDIDATES {
+            break;
+        }
+        tried += 1;
+        match scan_object(response, start) {
+            ScannedObject::Complete(end) => {
+                let json_str = &response[start..end];
+                match serde_json::from_str::<BriefingResponse>(json_str) {
+                    Ok(briefing_response) => return Ok(briefing_response.cards),
+                    Err(e) => {
+                        if failed.is_none() {
+                            failed = Some((json_str, e));
+                        }
+                    }
+                }
+                // Objects nested in this one are cards, not the response
+                next = end;
+            }
+            ScannedObject::Truncated(last_card) => {
+                let json_str = &response[start..];
+                if let Some(end) = last_card {
+                    let fixed_attempt = format!("{}\n]\n}}", &response[start..end]);
+                    if let Ok(briefing_response) =
+                        serde_json::from_str::<BriefingResponse>(&fixed_attempt)
+                    {
+                        warn!(
+                            "Recovered {} cards from truncated response",
+                            briefing_response.cards.len()
+                        );
+                        return Ok(briefing_response.cards);
+                    }
+                }
+                if truncated.is_none() {
+                    if let Err(e) = serde_json::from_str::<BriefingResponse>(json_str) {
+                        truncated = Some((json_str, e));
+                    }
+                }
             }
         }
     }
+
+    // Prefer explaining a cut-off response: it is the one with a fix
+    if let Some((json_str, e)) = truncated.as_ref().filter(|(_, e)| e.is_eof()) {
+        return Err(format!(
+            "Response was truncated (likely hit max_tokens limit). Increase max_tokens in synthesis call. \
+            Error: {}. Response length: {} chars. Last 200 chars: ...{}",
+            e,
+            json_str.len(),
+            tail_chars(json_str, 200)
+        ));
+    }
+    match failed.or(truncated) {
+        Some((json_str, e)) => Err(format!(
+            "Failed to parse briefing JSON: {}. Response length: {} chars. First 500 chars: {}...",
+            e,
+            json_str.len(),
+            head_chars(json_str, 500)
+        )),
+        None => Err(format!(
+            "Failed to parse briefing JSON: no JSON object found. Response length: {} chars. First 500 chars: {}...",
+            response.len(),
+            head_chars(response, 500)
+        )),
+    }
 }
 
 #[cfg(test)]
@@ -2225,6 +3353,127 @@ That's the summary!"#;
         assert!(cards[0].detailed_content.contains("**Key Takeaways**"));
     }
 
+    fn card_json(title: &str, detailed_content: &str) -> serde_json::Value {
+        serde_json::json!({
+            "title": title,
+            "summary": "Summary",
+            "detailed_content": detailed_content,
+            "sources": [],
+            "suggested_next": null,
+            "relevance": "high",
+            "topic": "Topic"
+        })
+    }
+
+    #[test]
+    fn test_parse_briefing_response_nested_code_fences() {
+        // Card text with its own fences and braces, in a fenced response
+        let content = "Example:\n```json\n{\"key\": [1, 2]}\n```\nand a stray } brace";
+        let cards = serde_json::json!({ "cards": [card_json("Fenced", content)] });
+        let response = format!(
+            "Here you go {{see below}}:\n```json\n{}\n```\nMore ``` after.",
+            cards
+        );
+        let cards = parse_briefing_response(&response).unwrap();
+        assert_eq!(cards.len(), 1);
+        assert_eq!(cards[0].detailed_content, content);
+    }
+
+    #[test]
+    fn test_parse_briefing_response_escaped_unicode() {
+        let response = r#"{"cards": [{"title": "Caf\u00e9 \ud83d\ude00 \"quoted\" \\ {x}", "summary": "S", "detailed_content": "D", "sources": [], "suggested_next": null, "relevance": "high", "topic": "T"}]}"#;
+        let cards = parse_briefing_response(response).unwrap();
+        assert_eq!(cards[0].title, "Café 😀 \"quoted\" \\ {x}");
+    }
+
+    #[test]
+    fn test_parse_briefing_response_truncated() {
+        let response = serde_json::json!({
+            "cards": [card_json("One", "First"), card_json("Two", "Second")]
+        })
+        .to_string();
+        let cut = response.find("Second").unwrap();
+
+        let cards = parse_briefing_response(&response[..cut]).unwrap();
+        assert_eq!(cards.len(), 1);
+        assert_eq!(cards[0].title, "One");
+
+        let error = parse_briefing_response(&response[..20]).unwrap_err();
+        assert!(error.starts_with("Response was truncated"));
+    }
+
+    #[test]
+    fn test_parse_briefing_response_huge_input() {
+        // Thousands of stray braces before the JSON are skipped cheaply
+        let cards = serde_json::json!({ "cards": [card_json("Big", &"é".repeat(200_000))] });
+        let response = format!("{}{}", "{ ".repeat(50_000), cards);
+        assert!(parse_briefing_response(&response).is_err());
+        let response = format!("{}{}", "} ".repeat(50_000), cards);
+        assert_eq!(parse_briefing_response(&response).unwrap()[0].title, "Big");
+
+        // Error messages cut multi-byte text on character boundaries
+        let error = parse_briefing_response(&"é".repeat(1_000)).unwrap_err();
+        assert!(error.contains("no JSON object found"));
+        let error =
+            parse_briefing_response(&format!("{{\"cards\": [\"{}", "é".repeat(300))).unwrap_err();
+        assert!(error.ends_with(&"é".repeat(200)));
+    }
+
+    #[test]
+    fn test_head_and_tail_chars() {
+        assert_eq!(head_chars("héllo", 2), "hé");
+        assert_eq!(head_chars("hé", 5), "hé");
+        assert_eq!(tail_chars("héllo", 4), "éllo");
+        assert_eq!(tail_chars("hé", 5), "hé");
+        assert_eq!(tail_chars("hé", 0), "");
+    }
+
+    proptest::proptest! {
+        #[test]
+        fn prop_parse_briefing_response_never_panics(response in "\\PC*") {
+            let _ = parse_briefing_response(&response);
+        }
+
+        #[test]
+        fn prop_parse_briefing_response_finds_wrapped_cards(
+            titles in proptest::collection::vec("\\PC{1,40}", 0..5),
+            content in "\\PC*",
+            prefix in "[^{]*",
+            suffix in "\\PC*",
+            fenced in proptest::bool::ANY,
+        ) {
+            let cards: Vec<_> = titles.iter().map(|title| card_json(title, &content)).collect();
+            let json = serde_json::json!({ "cards": cards }).to_string();
+            let response = if fenced {
+                format!("{}\n```json\n{}\n```\n{}", prefix, json, suffix)
+            } else {
+                format!("{}{}{}", prefix, json, suffix)
+            };
+            let parsed = parse_briefing_response(&response).unwrap();
+            let parsed: Vec<_> = parsed.into_iter().map(|card| card.title).collect();
+            proptest::prop_assert_eq!(parsed, titles);
+        }
+
+        #[test]
+        fn prop_parse_briefing_response_keeps_cards_before_a_cut(
+            titles in proptest::collection::vec("\\PC{1,40}", 1..5),
+            content in "\\PC*",
+            cut in 0.0..1.0f64,
+        ) {
+            let cards: Vec<_> = titles.iter().map(|title| card_json(title, &content)).collect();
+            let json = serde_json::json!({ "cards": cards }).to_string();
+            let mut end = (json.len() as f64 * cut) as usize;
+            while !json.is_char_boundary(end) {
+                end -= 1;
+            }
+            // Whatever survives is the leading cards, unchanged
+            if let Ok(parsed) = parse_briefing_response(&json[..end]) {
+                let parsed: Vec<_> = parsed.into_iter().map(|card| card.title).collect();
+                proptest::prop_assert!(titles.starts_with(&parsed));
+            }
+        }
+    }
+
     #[test]
     fn test_briefing_card_serialization() {
         let card = BriefingCard {
@@ -2238,6 +3487,11 @@ That's the summary!"#;
             image_prompt: Some("futuristic technology concept".to_string()),
             image_style: Some("illustration".to_string()),
             image_path: None,
+            source_checks: vec![],
+            action_items: vec![],
+            actions: vec![],
+            source_warning: None,
+            rank: None,
         };
 
         let json = serde_json::to_string(&card).unwrap();
@@ -2255,7 +3509,7 @@ That's the summary!"#;
 
     #[test]
     fn test_research_result_serialization() {
-        let result = ResearchResult {
+        let mut result = ResearchResult {
             date: "2025-01-15".to_string(),
             title: "Daily Briefing - January 15, 2025".to_string(),
             cards: vec![BriefingCard {
@@ -2269,11 +3523,32 @@ That's the summary!"#;
                 image_prompt: None,
                 image_style: None,
                 image_path: None,
+                source_checks: vec![],
+                action_items: vec![],
+                actions: vec![],
+                source_warning: None,
+                rank: None,
             }],
             research_time_ms: 1500,
             model_used: "claude-haiku-4-5-20251001".to_string(),
             total_tokens: 2500,
+            batch_tokens: 0,
+            run_id: "3f2b8c1e".to_string(),
+            topics: ["topic 1", "Topic 2"]
+                .iter()
+                .map(|topic| TopicResult {
+                    topic: topic.to_string(),
+                    tokens: 1000,
+                    tool_calls: 3,
+                    duration_ms: 700,
+                    card_indices: vec![],
+                })
+                .collect(),
+            failed_topics: vec![],
         };
+        result.index_topic_cards();
+        assert_eq!(result.topics[0].card_indices, vec![0]);
+        assert!(result.topics[1].card_indices.is_empty());
 
         let json = serde_json::to_string(&result).unwrap();
         assert!(json.contains("2025-01-15"));
@@ -2285,6 +3560,26 @@ That's the summary!"#;
         assert_eq!(parsed.date, result.date);
         assert_eq!(parsed.cards.len(), 1);
         assert_eq!(parsed.research_time_ms, 1500);
+        assert_eq!(parsed.topics, result.topics);
+        assert!(!json.contains("failed_topics"));
+        assert!(parsed.failed_topics.is_empty());
+    }
+
+    #[test]
+    fn test_retry_command() {
+        assert_eq!(retry_command(&[]), None);
+        let failed = |topic: &str| FailedTopic {
+            topic: topic.to_string(),
+            error: "Overloaded (api_overloaded)".to_string(),
+        };
+        assert_eq!(
+            retry_command(&[failed("Rust"), failed("AI News")]).as_deref(),
+            Some("claudius research now --topic Rust --topic \"AI News\"")
+        );
+        assert_eq!(
+            retry_command(&[failed("Say \"hi\" $HOME")]).as_deref(),
+            Some("claudius research now --topic \"Say \\\"hi\\\" \\$HOME\"")
+        );
     }
 
     #[test]
@@ -2298,7 +3593,8 @@ That's the summary!"#;
         );
         assert_eq!(agent.model, "claude-haiku-4-5-20251001");
         assert!(!agent.enable_web_search);
-        assert_eq!(agent.research_mode, "standard");
+        assert_eq!(agent.mode.name(), "standard");
+        assert_eq!(agent.topic_timeout, DEFAULT_TOPIC_TIMEOUT);
 
         let agent_custom = ResearchAgent::new(
             "test-api-key".to_string(),
@@ -2308,7 +3604,7 @@ That's the summary!"#;
             true,
         );
         assert_eq!(agent_custom.model, "claude-opus-4-5-20251101");
-        assert_eq!(agent_custom.research_mode, "firecrawl");
+        assert_eq!(agent_custom.mode.name(), "firecrawl");
     }
 
     #[test]
@@ -2323,7 +3619,7 @@ That's the summary!"#;
         assert!(agent.enable_web_search);
 
         // Test that get_tools_json includes web_search when enabled
-        let tools = agent.get_tools_json();
+        let tools = agent.get_tools_json(None);
         let has_web_search = tools
             .iter()
             .any(|t| t.get("type").and_then(|v| v.as_str()) == Some(WEB_SEARCH_TOOL_TYPE));
@@ -2348,64 +3644,233 @@ That's the summary!"#;
     }
 
     #[test]
-    fn test_extract_text_from_html() {
-        let html =
-            r#"<html><head><title>Test</title></head><body><p>Hello World</p></body></html>"#;
-        let text = extract_text_from_html(html);
-        assert!(text.contains("Hello World"));
-        assert!(!text.contains("<p>"));
+    fn test_preferred_source_url() {
+        assert_eq!(
+            preferred_source_url("https://blog.rust-lang.org/"),
+            Some("https://blog.rust-lang.org/".to_string())
+        );
+        assert_eq!(
+            preferred_source_url("lobste.rs"),
+            Some("https://lobste.rs".to_string())
+        );
+        assert_eq!(preferred_source_url("Rust blog"), None);
+        assert_eq!(preferred_source_url("Hacker News"), None);
     }
 
     #[test]
-    fn test_extract_text_from_html_with_scripts() {
-        let html = r#"<html><script>alert('test');</script><body><p>Content</p></body></html>"#;
-        let text = extract_text_from_html(html);
-        assert!(text.contains("Content"));
-        assert!(!text.contains("alert"));
+    fn test_format_preferred_sources_prompt() {
+        let prompt =
+            format_preferred_sources_prompt(&["Rust blog".to_string(), "lobste.rs".to_string()]);
+        assert!(prompt.starts_with("PREFERRED SOURCES"));
+        assert!(prompt.contains("- Rust blog\n- lobste.rs"));
     }
 
     #[test]
-    fn test_format_github_commits() {
-        let data = json!([
-            {
-                "sha": "abc123def456789",
-                "commit": {
-                    "message": "Fix bug in parser",
-                    "author": {
-                        "name": "John Doe",
-                        "date": "2025-01-15T10:00:00Z"
-                    }
-                }
-            }
+    fn test_filter_by_relevance() {
+        let card = |title: &str, relevance: &str| BriefingCard {
+            title: title.to_string(),
+            summary: String::new(),
+            detailed_content: String::new(),
+            sources: vec![],
+            suggested_next: None,
+            relevance: relevance.to_string(),
+            topic: "Rust".to_string(),
+            image_prompt: None,
+            image_style: None,
+            image_path: None,
+            source_checks: vec![],
+            action_items: vec![],
+            actions: vec![],
+            source_warning: None,
+            rank: None,
+        };
+        let cards = vec![
+            card("a", "high"),
+            card("b", "Low"),
+            card("c", "medium"),
+            card("d", "unknown"),
+        ];
+
+        let (kept, discarded) = filter_by_relevance(cards.clone(), "low");
+        assert_eq!(kept.len(), 4);
+        assert!(discarded.is_empty());
+
+        let (kept, discarded) = filter_by_relevance(cards.clone(), "medium");
+        assert_eq!(
+            kept.iter().map(|c| c.title.as_str()).collect::<Vec<_>>(),
+            vec!["a", "c", "d"]
+        );
+        assert_eq!(discarded[0].title, "b");
+
+        let (kept, discarded) = filter_by_relevance(cards, "high");
+        assert_eq!(kept.len(), 1);
+        assert_eq!(discarded.len(), 3);
+    }
+
+    #[test]
+    fn test_rank_cards_uses_relevance_and_feedback() {
+        let card = |topic: &str, relevance: &str| BriefingCard {
+            title: topic.to_string(),
+            summary: String::new(),
+            detailed_content: String::new(),
+            sources: vec![],
+            suggested_next: None,
+            relevance: relevance.to_string(),
+            topic: topic.to_string(),
+            image_prompt: None,
+            image_style: None,
+            image_path: None,
+            source_checks: vec![],
+            action_items: vec![],
+            actions: vec![],
+            source_warning: None,
+            rank: None,
+        };
+        let mut cards = vec![
+            card("Rust", "medium"),
+            card("Python", "high"),
+            card("Go", "medium"),
+            card("AI", "low"),
+        ];
+
+        rank_cards(&mut cards, &HashMap::new());
+        let ranks: Vec<Option<u32>> = cards.iter().map(|c| c.rank).collect();
+        assert_eq!(ranks, vec![Some(1), Some(0), Some(2), Some(3)]);
+
+        // Rust is consistently rated 5, Python 1
+        let feedback = HashMap::from([
+            ("rust".to_string(), (40, 8)),
+            ("python".to_string(), (8, 8)),
         ]);
-        let formatted = format_github_commits(&data);
-        assert!(formatted.contains("abc123d"));
-        assert!(formatted.contains("John Doe"));
-        assert!(formatted.contains("Fix bug"));
+        rank_cards(&mut cards, &feedback);
+        let ranks: Vec<Option<u32>> = cards.iter().map(|c| c.rank).collect();
+        assert_eq!(ranks, vec![Some(0), Some(2), Some(1), Some(3)]);
+
+        assert_eq!(topic_affinity(0, 0), 0.0);
+        assert!(topic_affinity(5, 1) < topic_affinity(50, 10));
     }
 
     #[test]
-    fn test_format_github_pulls() {
-        let data = json!([
-            {
-                "number": 123,
-                "title": "Add new feature",
-                "state": "open",
-                "user": { "login": "contributor" }
-            }
+    fn test_adhoc_query_and_title() {
+        assert_eq!(
+            validate_adhoc_query("  EU AI Act\n enforcement   this week ").unwrap(),
+            "EU AI Act enforcement this week"
+        );
+        assert!(validate_adhoc_query("   ").is_err());
+        assert!(validate_adhoc_query(&"x".repeat(ADHOC_QUERY_MAX_CHARS + 1)).is_err());
+
+        assert_eq!(
+            adhoc_query_from_text("\n  Copied   text\t").unwrap(),
+            "Copied text"
+        );
+        assert!(adhoc_query_from_text(" \n ").is_err());
+        let long = adhoc_query_from_text(&"word ".repeat(200)).unwrap();
+        assert!(long.chars().count() <= ADHOC_QUERY_MAX_CHARS);
+        assert!(long.ends_with("word"));
+        assert_eq!(validate_adhoc_query(&long).unwrap(), long);
+        let unbroken = adhoc_query_from_text(&"é".repeat(ADHOC_QUERY_MAX_CHARS * 2)).unwrap();
+        assert_eq!(unbroken.chars().count(), ADHOC_QUERY_MAX_CHARS);
+
+        assert_eq!(
+            adhoc_title("EU AI Act enforcement this week"),
+            "Ad-hoc: EU AI Act enforcement this week"
+        );
+        let long_title = adhoc_title(&"word ".repeat(40));
+        assert!(long_title.ends_with('…'));
+        assert_eq!(long_title.chars().count(), "Ad-hoc: ".len() + 80);
+    }
+
+    #[test]
+    fn test_recency_window() {
+        assert_eq!(parse_recency_window(" 1W ").unwrap(), "1w");
+        assert!(parse_recency_window("48h").is_err());
+
+        assert_eq!(effective_recency_days(30, "1w"), 30);
+        assert_eq!(effective_recency_days(2, "1w"), 7);
+        assert_eq!(effective_recency_days(2, "24h"), 2);
+        assert_eq!(effective_recency_days(0, "1w"), 0);
+
+        let today = chrono::NaiveDate::from_ymd_opt(2025, 3, 10).unwrap();
+        assert_eq!(
+            recency_window_prompt("24h", today),
+            "ideally within the last 24-48 hours"
+        );
+        assert_eq!(
+            recency_window_prompt("1w", today),
+            "ideally within the past week, since March 03"
+        );
+    }
+
+    #[test]
+    fn test_group_topics_by_format() {
+        let topics: Vec<String> = ["AI", "Rust", "Markets", "Security"]
+            .iter()
+            .map(|t| t.to_string())
+            .collect();
+        let overrides = HashMap::from([
+            ("Markets".to_string(), "executive".to_string()),
+            ("Security".to_string(), "digest".to_string()),
         ]);
-        let formatted = format_github_pulls(&data);
-        assert!(formatted.contains("#123"));
-        assert!(formatted.contains("open"));
-        assert!(formatted.contains("Add new feature"));
+
+        let groups = group_topics_by_format(&topics, "standard", &overrides);
+        assert_eq!(
+            groups,
+            vec![
+                (
+                    "standard".to_string(),
+                    vec!["AI".to_string(), "Rust".to_string()]
+                ),
+                ("executive".to_string(), vec!["Markets".to_string()]),
+                ("digest".to_string(), vec!["Security".to_string()]),
+            ]
+        );
+
+        assert_eq!(parse_briefing_format(" Digest ").unwrap(), "digest");
+        assert!(parse_briefing_format("tabloid").is_err());
+        assert!(build_format_prompt("standard", "", "", "content").is_none());
+        assert!(build_format_prompt("executive", "", "", "content")
+            .unwrap()
+            .contains("action_items"));
     }
 
     #[test]
-    fn test_get_research_tools() {
-        let tools = get_research_tools();
-        assert_eq!(tools.len(), 2);
-        assert!(tools.iter().any(|t| t.name == "get_github_activity"));
-        assert!(tools.iter().any(|t| t.name == "fetch_webpage"));
+    fn test_thinking_budget() {
+        let none = HashMap::new();
+        assert_eq!(thinking_budget("shallow", &none), 2048);
+        assert_eq!(thinking_budget("Deep", &none), 8192);
+        assert_eq!(thinking_budget("unknown", &none), 4096);
+
+        let overrides = HashMap::from([("deep".to_string(), 16000), ("shallow".to_string(), 500)]);
+        assert_eq!(thinking_budget("deep", &overrides), 16000);
+        // Budgets below the API minimum are raised to it
+        assert_eq!(thinking_budget("shallow", &overrides), 1024);
+    }
+
+    #[test]
+    fn test_format_about_me_prompt() {
+        assert!(format_about_me_prompt("  ").is_none());
+        let prompt = format_about_me_prompt("Senior Rust engineer at a fintech").unwrap();
+        assert!(prompt.starts_with("ABOUT THE USER:\nSenior Rust engineer at a fintech"));
+
+        let long = "x".repeat(ABOUT_ME_MAX_CHARS + 100);
+        let prompt = format_about_me_prompt(&long).unwrap();
+        assert!(!prompt.contains(&long));
+    }
+
+    #[test]
+    fn test_format_offline_context() {
+        assert!(format_offline_context(&[]).contains("No cached content"));
+
+        let prompt = format_offline_context(&[crate::db::CachedContent {
+            kind: "web".to_string(),
+            cache_key: "https://example.com".to_string(),
+            topic: Some("Rust".to_string()),
+            content: "Rust 2.0 released".to_string(),
+            fetched_at: "2026-01-01 08:00:00".to_string(),
+        }]);
+        assert!(prompt.contains("CACHED CONTENT"));
+        assert!(prompt.contains("### https://example.com (fetched 2026-01-01 08:00:00)"));
+        assert!(prompt.contains("Rust 2.0 released"));
     }
 
     #[test]
@@ -2420,8 +3885,8 @@ That's the summary!"#;
         );
 
         // Without MCP client, should only have built-in tools
-        let tools = agent.get_all_tools();
-        assert_eq!(tools.len(), 2); // get_github_activity and fetch_webpage
+        let tools = agent.get_all_tools(None);
+        assert_eq!(tools.len(), 10); // GitHub, arXiv/HN/Reddit tools and fetch_webpage
         assert!(tools.iter().any(|t| t.name == "fetch_webpage"));
         assert!(tools.iter().any(|t| t.name == "get_github_activity"));
     }
@@ -2438,8 +3903,8 @@ That's the summary!"#;
         );
 
         // Without MCP client, fetch_webpage should be excluded
-        let tools = agent.get_all_tools();
-        assert_eq!(tools.len(), 1); // Only get_github_activity
+        let tools = agent.get_all_tools(None);
+        assert_eq!(tools.len(), 9); // Everything except fetch_webpage
         assert!(tools.iter().any(|t| t.name == "get_github_activity"));
         assert!(
             !tools.iter().any(|t| t.name == "fetch_webpage"),
@@ -2447,15 +3912,32 @@ That's the summary!"#;
         );
     }
 
+    #[test]
+    fn test_tool_filtering_github_only_mode() {
+        // GitHub-only mode keeps just the GitHub tools, even with web search on
+        let agent = ResearchAgent::new(
+            "test-api-key".to_string(),
+            None,
+            true,
+            "github-only".to_string(),
+            true,
+        );
+
+        let tools = agent.get_all_tools(None);
+        assert_eq!(tools.len(), 6);
+        assert!(tools.iter().all(|t| t.name.contains("github")));
+        assert_eq!(agent.get_tools_json(None).len(), 6);
+    }
+
     #[test]
     fn test_research_mode_stored_correctly() {
         let agent_standard =
             ResearchAgent::new("key".to_string(), None, false, "standard".to_string(), true);
-        assert_eq!(agent_standard.research_mode, "standard");
+        assert_eq!(agent_standard.mode.name(), "standard");
 
         let agent_firecrawl =
             ResearchAgent::new("key".to_string(), None, false, "firecrawl".to_string(), true);
-        assert_eq!(agent_firecrawl.research_mode, "firecrawl");
+        assert_eq!(agent_firecrawl.mode.name(), "firecrawl");
     }
 
     #[test]
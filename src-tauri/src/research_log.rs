@@ -4,6 +4,8 @@
 //! for research operations.
 
 use crate::db::get_connection;
+use crate::log_sink::{self, LogRecord};
+use rusqlite::Connection;
 use serde::{Deserialize, Serialize};
 use tracing::{debug, info};
 
@@ -40,6 +42,24 @@ pub enum ErrorCode {
 }
 
 impl ErrorCode {
+    /// Every error code.
+    #[allow(dead_code)]
+    pub const ALL: [ErrorCode; 13] = [
+        ErrorCode::InvalidApiKey,
+        ErrorCode::BudgetExceeded,
+        ErrorCode::RateLimited,
+        ErrorCode::ApiOverloaded,
+        ErrorCode::ToolExecutionFailed,
+        ErrorCode::McpConnectionFailed,
+        ErrorCode::McpToolFailed,
+        ErrorCode::NetworkError,
+        ErrorCode::Timeout,
+        ErrorCode::ParseError,
+        ErrorCode::InvalidResponse,
+        ErrorCode::InternalError,
+        ErrorCode::Unknown,
+    ];
+
     /// Returns true if this error requires user action to resolve.
     pub fn requires_user_action(&self) -> bool {
         matches!(self, ErrorCode::InvalidApiKey | ErrorCode::BudgetExceeded)
@@ -118,6 +138,8 @@ pub struct ResearchError {
     pub user_message: String,
     pub requires_user_action: bool,
     pub details: Option<String>,
+    /// Anthropic's `request-id` for the failed API call, if it got a response
+    pub request_id: Option<String>,
 }
 
 impl ResearchError {
@@ -130,6 +152,7 @@ impl ResearchError {
             user_message,
             requires_user_action,
             details: None,
+            request_id: None,
         }
     }
 
@@ -137,6 +160,18 @@ impl ResearchError {
         self.details = Some(details.into());
         self
     }
+
+    pub fn with_request_id(mut self, request_id: Option<String>) -> Self {
+        self.request_id = request_id;
+        self
+    }
+
+    /// The message with its code appended, e.g. "Overloaded (api_overloaded)",
+    /// for errors passed on as strings. The CLI reads the code back to pick
+    /// its exit status.
+    pub fn tagged(&self) -> String {
+        format!("{} ({})", self.message, self.code.as_str())
+    }
 }
 
 impl std::fmt::Display for ResearchError {
@@ -236,7 +271,7 @@ pub enum LogType {
     ToolCall,
     ApiRequest,
     McpCall,
-    #[allow(dead_code)]
+    Thinking,
     Error,
 }
 
@@ -246,6 +281,7 @@ impl LogType {
             LogType::ToolCall => "tool_call",
             LogType::ApiRequest => "api_request",
             LogType::McpCall => "mcp_call",
+            LogType::Thinking => "thinking",
             LogType::Error => "error",
         }
     }
@@ -265,6 +301,7 @@ pub struct ResearchLogEntry {
     pub success: bool,
     pub error_code: Option<ErrorCode>,
     pub error_message: Option<String>,
+    pub request_id: Option<String>,
 }
 
 impl ResearchLogEntry {
@@ -282,6 +319,7 @@ impl ResearchLogEntry {
             success: true,
             error_code: None,
             error_message: None,
+            request_id: None,
         }
     }
 
@@ -299,6 +337,7 @@ impl ResearchLogEntry {
             success: false,
             error_code: Some(error.code.clone()),
             error_message: Some(error.message.clone()),
+            request_id: error.request_id.clone(),
         }
     }
 
@@ -341,6 +380,16 @@ impl ResearchLogEntry {
         self.tokens_used = Some(tokens);
         self
     }
+
+    pub fn with_request_id(mut self, request_id: Option<String>) -> Self {
+        self.request_id = request_id;
+        self
+    }
+}
+
+/// Rough token estimate for text fed back into the model (~4 chars per token).
+fn estimate_tokens(text: &str) -> i64 {
+    (text.len() / 4) as i64
 }
 
 /// Truncate a string to a maximum length, adding "..." if truncated.
@@ -381,8 +430,9 @@ impl ResearchLogger {
         conn.execute(
             r#"INSERT INTO research_logs
                (briefing_id, log_type, topic, tool_name, input_summary, output_summary,
-                duration_ms, tokens_used, success, error_code, error_message, user_action_required)
-               VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)"#,
+                duration_ms, tokens_used, success, error_code, error_message, user_action_required,
+                request_id)
+               VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)"#,
             rusqlite::params![
                 entry.briefing_id,
                 entry.log_type.as_str(),
@@ -396,12 +446,36 @@ impl ResearchLogger {
                 entry.error_code.as_ref().map(|c| c.as_str()),
                 entry.error_message,
                 if user_action_required { 1 } else { 0 },
+                entry.request_id,
             ],
         )
         .map_err(|e| format!("Failed to insert log: {}", e))?;
 
         let id = conn.last_insert_rowid();
 
+        log_sink::record(
+            LogRecord::new(
+                if entry.success { "info" } else { "error" },
+                entry.log_type.as_str(),
+                entry
+                    .error_message
+                    .as_deref()
+                    .or(entry.output_summary.as_deref())
+                    .unwrap_or_default(),
+            )
+            .with_fields(serde_json::json!({
+                "log_id": id,
+                "briefing_id": entry.briefing_id,
+                "topic": entry.topic,
+                "tool_name": entry.tool_name,
+                "duration_ms": entry.duration_ms,
+                "tokens_used": entry.tokens_used,
+                "success": entry.success,
+                "error_code": entry.error_code.as_ref().map(|c| c.as_str()),
+                "request_id": entry.request_id,
+            })),
+        );
+
         if entry.success {
             debug!(
                 "Logged {} for {:?}: {}",
@@ -435,7 +509,8 @@ impl ResearchLogger {
                 .with_tool(tool_name)
                 .with_input(input)
                 .with_output(output)
-                .with_duration_ms(duration_ms),
+                .with_duration_ms(duration_ms)
+                .with_tokens(estimate_tokens(output)),
         )
     }
 
@@ -456,13 +531,19 @@ impl ResearchLogger {
         Self::log(&entry)
     }
 
-    /// Log an API request.
-    pub fn log_api_request(topic: &str, tokens: i64, duration_ms: i64) -> Result<i64, String> {
+    /// Log an API request, with Anthropic's ID for it when known.
+    pub fn log_api_request(
+        topic: &str,
+        tokens: i64,
+        duration_ms: i64,
+        request_id: Option<String>,
+    ) -> Result<i64, String> {
         Self::log(
             &ResearchLogEntry::success(LogType::ApiRequest)
                 .with_topic(topic)
                 .with_tokens(tokens)
-                .with_duration_ms(duration_ms),
+                .with_duration_ms(duration_ms)
+                .with_request_id(request_id),
         )
     }
 
@@ -471,6 +552,15 @@ impl ResearchLogger {
         Self::log(&ResearchLogEntry::failure(LogType::ApiRequest, error).with_topic(topic))
     }
 
+    /// Log Claude's thinking summary for a research step.
+    pub fn log_thinking(topic: &str, summary: &str) -> Result<i64, String> {
+        Self::log(
+            &ResearchLogEntry::success(LogType::Thinking)
+                .with_topic(topic)
+                .with_output(summary),
+        )
+    }
+
     /// Log an MCP tool call.
     pub fn log_mcp_call(
         topic: &str,
@@ -486,7 +576,8 @@ impl ResearchLogger {
                 .with_tool(format!("{}:{}", server_name, tool_name))
                 .with_input(input)
                 .with_output(output)
-                .with_duration_ms(duration_ms),
+                .with_duration_ms(duration_ms)
+                .with_tokens(estimate_tokens(output)),
         )
     }
 
@@ -494,12 +585,28 @@ impl ResearchLogger {
     pub fn log_mcp_error(
         topic: &str,
         server_name: &str,
+        tool_name: &str,
         error: &ResearchError,
+        duration_ms: i64,
     ) -> Result<i64, String> {
         Self::log(
             &ResearchLogEntry::failure(LogType::McpCall, error)
                 .with_topic(topic)
-                .with_tool(server_name),
+                .with_tool(format!("{}:{}", server_name, tool_name))
+                .with_duration_ms(duration_ms),
+        )
+    }
+
+    /// Log a topic that was aborted by the per-topic timeout.
+    pub fn log_topic_timeout(
+        topic: &str,
+        error: &ResearchError,
+        duration_ms: i64,
+    ) -> Result<i64, String> {
+        Self::log(
+            &ResearchLogEntry::failure(LogType::Error, error)
+                .with_topic(topic)
+                .with_duration_ms(duration_ms),
         )
     }
 
@@ -513,7 +620,7 @@ impl ResearchLogger {
         let query = if briefing_id.is_some() {
             r#"SELECT id, briefing_id, log_type, topic, tool_name, input_summary, output_summary,
                       duration_ms, tokens_used, success, error_code, error_message,
-                      user_action_required, created_at
+                      user_action_required, created_at, request_id
                FROM research_logs
                WHERE briefing_id = ?1
                ORDER BY created_at DESC
@@ -521,7 +628,7 @@ impl ResearchLogger {
         } else {
             r#"SELECT id, briefing_id, log_type, topic, tool_name, input_summary, output_summary,
                       duration_ms, tokens_used, success, error_code, error_message,
-                      user_action_required, created_at
+                      user_action_required, created_at, request_id
                FROM research_logs
                ORDER BY created_at DESC
                LIMIT ?2"#
@@ -540,24 +647,7 @@ impl ResearchLogger {
         let rows = stmt
             .query_map(
                 rusqlite::params_from_iter(params.iter().map(|p| p.as_ref())),
-                |row| {
-                    Ok(ResearchLogRecord {
-                        id: row.get(0)?,
-                        briefing_id: row.get(1)?,
-                        log_type: row.get(2)?,
-                        topic: row.get(3)?,
-                        tool_name: row.get(4)?,
-                        input_summary: row.get(5)?,
-                        output_summary: row.get(6)?,
-                        duration_ms: row.get(7)?,
-                        tokens_used: row.get(8)?,
-                        success: row.get::<_, i32>(9)? == 1,
-                        error_code: row.get(10)?,
-                        error_message: row.get(11)?,
-                        user_action_required: row.get::<_, i32>(12)? == 1,
-                        created_at: row.get(13)?,
-                    })
-                },
+                log_record_from_row,
             )
             .map_err(|e| format!("Failed to query logs: {}", e))?;
 
@@ -577,7 +667,7 @@ impl ResearchLogger {
             .prepare(
                 r#"SELECT id, briefing_id, log_type, topic, tool_name, input_summary, output_summary,
                           duration_ms, tokens_used, success, error_code, error_message,
-                          user_action_required, created_at
+                          user_action_required, created_at, request_id
                    FROM research_logs
                    WHERE user_action_required = 1
                    ORDER BY created_at DESC
@@ -586,24 +676,7 @@ impl ResearchLogger {
             .map_err(|e| format!("Failed to prepare query: {}", e))?;
 
         let rows = stmt
-            .query_map([limit], |row| {
-                Ok(ResearchLogRecord {
-                    id: row.get(0)?,
-                    briefing_id: row.get(1)?,
-                    log_type: row.get(2)?,
-                    topic: row.get(3)?,
-                    tool_name: row.get(4)?,
-                    input_summary: row.get(5)?,
-                    output_summary: row.get(6)?,
-                    duration_ms: row.get(7)?,
-                    tokens_used: row.get(8)?,
-                    success: row.get::<_, i32>(9)? == 1,
-                    error_code: row.get(10)?,
-                    error_message: row.get(11)?,
-                    user_action_required: row.get::<_, i32>(12)? == 1,
-                    created_at: row.get(13)?,
-                })
-            })
+            .query_map([limit], log_record_from_row)
             .map_err(|e| format!("Failed to query logs: {}", e))?;
 
         let mut logs = Vec::new();
@@ -613,6 +686,471 @@ impl ResearchLogger {
 
         Ok(logs)
     }
+
+    /// Get tool usage analytics for the last `days` days.
+    pub fn get_tool_analytics(days: i64) -> Result<ToolAnalytics, String> {
+        let conn = get_connection().map_err(|e| format!("Failed to open database: {}", e))?;
+        tool_analytics(&conn, days)
+    }
+
+    /// Build the report for a finished run and store it with its briefing.
+    #[allow(dead_code)]
+    pub fn save_run_report(briefing_id: i64) -> Result<RunReport, String> {
+        let conn = get_connection().map_err(|e| format!("Failed to open database: {}", e))?;
+        save_run_report(&conn, briefing_id)
+    }
+
+    /// Get the report of a run (by briefing ID).
+    pub fn get_run_report(briefing_id: i64) -> Result<RunReport, String> {
+        let conn = get_connection().map_err(|e| format!("Failed to open database: {}", e))?;
+        run_report(&conn, briefing_id)
+    }
+}
+
+fn log_record_from_row(row: &rusqlite::Row) -> rusqlite::Result<ResearchLogRecord> {
+    Ok(ResearchLogRecord {
+        id: row.get(0)?,
+        briefing_id: row.get(1)?,
+        log_type: row.get(2)?,
+        topic: row.get(3)?,
+        tool_name: row.get(4)?,
+        input_summary: row.get(5)?,
+        output_summary: row.get(6)?,
+        duration_ms: row.get(7)?,
+        tokens_used: row.get(8)?,
+        success: row.get::<_, i32>(9)? == 1,
+        error_code: row.get(10)?,
+        error_message: row.get(11)?,
+        user_action_required: row.get::<_, i32>(12)? == 1,
+        created_at: row.get(13)?,
+        request_id: row.get(14)?,
+    })
+}
+
+/// Current time in the format SQLite uses for `CURRENT_TIMESTAMP`.
+pub fn log_timestamp() -> String {
+    chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string()
+}
+
+/// Link logs written since `since` (UTC, `YYYY-MM-DD HH:MM:SS`) to a saved briefing.
+pub(crate) fn attach_logs_to_briefing(
+    conn: &Connection,
+    briefing_id: i64,
+    since: &str,
+) -> Result<usize, String> {
+    conn.execute(
+        "UPDATE research_logs SET briefing_id = ?1 WHERE briefing_id IS NULL AND created_at >= ?2",
+        rusqlite::params![briefing_id, since],
+    )
+    .map_err(|e| format!("Failed to attach logs to briefing: {}", e))
+}
+
+// ============================================================================
+// Tool Analytics
+// ============================================================================
+
+/// Usage of a single tool (built-in or `server:tool` for MCP).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ToolStats {
+    pub tool_name: String,
+    pub server: Option<String>,
+    pub log_type: String,
+    pub calls: i64,
+    pub failures: i64,
+    pub failure_rate: f64,
+    pub avg_duration_ms: Option<f64>,
+    /// Estimated tokens the tool's output added to the context.
+    pub est_tokens: i64,
+}
+
+/// Usage of all tools on one MCP server.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServerStats {
+    pub server: String,
+    pub tools: i64,
+    pub calls: i64,
+    pub failures: i64,
+    pub failure_rate: f64,
+    pub avg_duration_ms: Option<f64>,
+    pub est_tokens: i64,
+}
+
+/// Tool usage for one day.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DailyToolStats {
+    pub date: String,
+    pub tool_calls: i64,
+    pub failures: i64,
+    pub avg_duration_ms: Option<f64>,
+    pub est_tool_tokens: i64,
+    pub api_tokens: i64,
+}
+
+/// Tool usage for one briefing.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BriefingToolStats {
+    pub briefing_id: i64,
+    pub date: String,
+    pub tool_calls: i64,
+    pub failures: i64,
+    pub est_tool_tokens: i64,
+    pub api_tokens: i64,
+}
+
+/// Aggregated tool usage over a time window.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ToolAnalytics {
+    pub days: i64,
+    pub tools: Vec<ToolStats>,
+    pub servers: Vec<ServerStats>,
+    pub daily: Vec<DailyToolStats>,
+    pub briefings: Vec<BriefingToolStats>,
+}
+
+fn failure_rate(calls: i64, failures: i64) -> f64 {
+    if calls == 0 {
+        0.0
+    } else {
+        failures as f64 / calls as f64
+    }
+}
+
+/// Aggregate tool calls from `research_logs` over the last `days` days.
+pub fn tool_analytics(conn: &Connection, days: i64) -> Result<ToolAnalytics, String> {
+    let window = format!("-{} days", days.max(1));
+
+    let mut stmt = conn
+        .prepare(
+            r#"SELECT tool_name, log_type, COUNT(*), SUM(success = 0), AVG(duration_ms),
+                      COALESCE(SUM(tokens_used), 0)
+               FROM research_logs
+               WHERE log_type IN ('tool_call', 'mcp_call') AND tool_name IS NOT NULL
+                 AND created_at >= datetime('now', ?1)
+               GROUP BY tool_name, log_type
+               ORDER BY COUNT(*) DESC, tool_name"#,
+        )
+        .map_err(|e| format!("Failed to prepare query: {}", e))?;
+    let tools = stmt
+        .query_map([&window], |row| {
+            let tool_name: String = row.get(0)?;
+            let log_type: String = row.get(1)?;
+            let calls: i64 = row.get(2)?;
+            let failures: i64 = row.get(3)?;
+            let server = if log_type == LogType::McpCall.as_str() {
+                tool_name.split(':').next().map(str::to_string)
+            } else {
+                None
+            };
+            Ok(ToolStats {
+                tool_name,
+                server,
+                log_type,
+                calls,
+                failures,
+                failure_rate: failure_rate(calls, failures),
+                avg_duration_ms: row.get(4)?,
+                est_tokens: row.get(5)?,
+            })
+        })
+        .map_err(|e| format!("Failed to query tool stats: {}", e))?
+        .collect::<rusqlite::Result<Vec<_>>>()
+        .map_err(|e| format!("Failed to read tool stats: {}", e))?;
+
+    let mut stmt = conn
+        .prepare(
+            r#"SELECT CASE WHEN instr(tool_name, ':') > 0
+                           THEN substr(tool_name, 1, instr(tool_name, ':') - 1)
+                           ELSE tool_name END AS server,
+                      COUNT(DISTINCT tool_name), COUNT(*), SUM(success = 0), AVG(duration_ms),
+                      COALESCE(SUM(tokens_used), 0)
+               FROM research_logs
+               WHERE log_type = 'mcp_call' AND tool_name IS NOT NULL
+                 AND created_at >= datetime('now', ?1)
+               GROUP BY server
+               ORDER BY COUNT(*) DESC, server"#,
+        )
+        .map_err(|e| format!("Failed to prepare query: {}", e))?;
+    let servers = stmt
+        .query_map([&window], |row| {
+            let calls: i64 = row.get(2)?;
+            let failures: i64 = row.get(3)?;
+            Ok(ServerStats {
+                server: row.get(0)?,
+                tools: row.get(1)?,
+                calls,
+                failures,
+                failure_rate: failure_rate(calls, failures),
+                avg_duration_ms: row.get(4)?,
+                est_tokens: row.get(5)?,
+            })
+        })
+        .map_err(|e| format!("Failed to query server stats: {}", e))?
+        .collect::<rusqlite::Result<Vec<_>>>()
+        .map_err(|e| format!("Failed to read server stats: {}", e))?;
+
+    let mut stmt = conn
+        .prepare(
+            r#"SELECT date(created_at) AS day,
+                      SUM(log_type IN ('tool_call', 'mcp_call')),
+                      SUM(log_type IN ('tool_call', 'mcp_call') AND success = 0),
+                      AVG(CASE WHEN log_type IN ('tool_call', 'mcp_call') THEN duration_ms END),
+                      COALESCE(SUM(CASE WHEN log_type IN ('tool_call', 'mcp_call')
+                                        THEN tokens_used END), 0),
+                      COALESCE(SUM(CASE WHEN log_type = 'api_request' THEN tokens_used END), 0)
+               FROM research_logs
+               WHERE created_at >= datetime('now', ?1)
+               GROUP BY day
+               ORDER BY day"#,
+        )
+        .map_err(|e| format!("Failed to prepare query: {}", e))?;
+    let daily = stmt
+        .query_map([&window], |row| {
+            Ok(DailyToolStats {
+                date: row.get(0)?,
+                tool_calls: row.get(1)?,
+                failures: row.get(2)?,
+                avg_duration_ms: row.get(3)?,
+                est_tool_tokens: row.get(4)?,
+                api_tokens: row.get(5)?,
+            })
+        })
+        .map_err(|e| format!("Failed to query daily stats: {}", e))?
+        .collect::<rusqlite::Result<Vec<_>>>()
+        .map_err(|e| format!("Failed to read daily stats: {}", e))?;
+
+    let mut stmt = conn
+        .prepare(
+            r#"SELECT b.id, b.date,
+                      SUM(l.log_type IN ('tool_call', 'mcp_call')),
+                      SUM(l.log_type IN ('tool_call', 'mcp_call') AND l.success = 0),
+                      COALESCE(SUM(CASE WHEN l.log_type IN ('tool_call', 'mcp_call')
+                                        THEN l.tokens_used END), 0),
+                      COALESCE(b.total_tokens, 0)
+               FROM briefings b
+               JOIN research_logs l ON l.briefing_id = b.id
+               WHERE l.created_at >= datetime('now', ?1)
+               GROUP BY b.id
+               ORDER BY b.id DESC"#,
+        )
+        .map_err(|e| format!("Failed to prepare query: {}", e))?;
+    let briefings = stmt
+        .query_map([&window], |row| {
+            Ok(BriefingToolStats {
+                briefing_id: row.get(0)?,
+                date: row.get(1)?,
+                tool_calls: row.get(2)?,
+                failures: row.get(3)?,
+                est_tool_tokens: row.get(4)?,
+                api_tokens: row.get(5)?,
+            })
+        })
+        .map_err(|e| format!("Failed to query briefing stats: {}", e))?
+        .collect::<rusqlite::Result<Vec<_>>>()
+        .map_err(|e| format!("Failed to read briefing stats: {}", e))?;
+
+    Ok(ToolAnalytics {
+        days,
+        tools,
+        servers,
+        daily,
+        briefings,
+    })
+}
+
+// ============================================================================
+// Run Reports
+// ============================================================================
+
+/// Blended USD price per million tokens by model family. Only total tokens
+/// are recorded, so this assumes research's usual mix of about four input
+/// tokens per output token.
+const BLENDED_PRICE_PER_MTOK: &[(&str, f64)] = &[("opus", 9.0), ("sonnet", 5.4), ("haiku", 1.8)];
+
+/// Rough cost of a run in USD; `batch_tokens` are billed at half price.
+pub fn estimate_cost_usd(model: &str, tokens: i64, batch_tokens: i64) -> f64 {
+    let price = BLENDED_PRICE_PER_MTOK
+        .iter()
+        .find(|(family, _)| model.contains(family))
+        .map(|(_, price)| *price)
+        .unwrap_or(5.4);
+    let batch_tokens = batch_tokens.clamp(0, tokens.max(0));
+    ((tokens - batch_tokens) as f64 + batch_tokens as f64 / 2.0) * price / 1_000_000.0
+}
+
+/// Activity for one topic of a run.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TopicReport {
+    pub topic: String,
+    pub api_requests: i64,
+    pub tool_calls: i64,
+    pub failures: i64,
+    /// Tokens reported by API requests
+    pub tokens: i64,
+    /// Time spent in API requests and tool calls
+    pub duration_ms: i64,
+}
+
+/// Structured report of a research run, assembled from its research logs.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunReport {
+    pub briefing_id: i64,
+    pub date: String,
+    pub title: String,
+    pub model: Option<String>,
+    pub research_time_ms: Option<i64>,
+    pub total_tokens: i64,
+    pub batch_tokens: i64,
+    pub estimated_cost_usd: f64,
+    pub topics: Vec<TopicReport>,
+    /// Every logged step in the order it happened
+    pub timeline: Vec<ResearchLogRecord>,
+    pub failures: i64,
+}
+
+impl RunReport {
+    /// Failed steps of the run.
+    #[allow(dead_code)]
+    pub fn errors(&self) -> impl Iterator<Item = &ResearchLogRecord> {
+        self.timeline.iter().filter(|entry| !entry.success)
+    }
+}
+
+/// Assemble the report of a run from its briefing, research logs and API usage.
+pub fn build_run_report(conn: &Connection, briefing_id: i64) -> Result<RunReport, String> {
+    let (date, title, model, research_time_ms, total_tokens) = conn
+        .query_row(
+            "SELECT date, title, model_used, research_time_ms, COALESCE(total_tokens, 0)
+             FROM briefings WHERE id = ?1",
+            [briefing_id],
+            |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, Option<String>>(2)?,
+                    row.get::<_, Option<i64>>(3)?,
+                    row.get::<_, i64>(4)?,
+                ))
+            },
+        )
+        .map_err(|e| match e {
+            rusqlite::Error::QueryReturnedNoRows => format!("Run {} not found", briefing_id),
+            e => format!("Failed to load briefing: {}", e),
+        })?;
+
+    let batch_tokens: i64 = conn
+        .query_row(
+            "SELECT COALESCE(SUM(batch_tokens), 0) FROM api_usage
+             WHERE briefing_id = ?1 AND kind = 'research'",
+            [briefing_id],
+            |row| row.get(0),
+        )
+        .map_err(|e| format!("Failed to load API usage: {}", e))?;
+
+    let mut stmt = conn
+        .prepare(
+            r#"SELECT id, briefing_id, log_type, topic, tool_name, input_summary, output_summary,
+                      duration_ms, tokens_used, success, error_code, error_message,
+                      user_action_required, created_at, request_id
+               FROM research_logs
+               WHERE briefing_id = ?1
+               ORDER BY id"#,
+        )
+        .map_err(|e| format!("Failed to prepare query: {}", e))?;
+    let timeline = stmt
+        .query_map([briefing_id], log_record_from_row)
+        .map_err(|e| format!("Failed to query logs: {}", e))?
+        .collect::<rusqlite::Result<Vec<_>>>()
+        .map_err(|e| format!("Failed to read log row: {}", e))?;
+
+    let mut topics: Vec<TopicReport> = Vec::new();
+    for entry in &timeline {
+        let Some(name) = entry.topic.as_deref().filter(|t| !t.is_empty()) else {
+            continue;
+        };
+        let index = match topics.iter().position(|t| t.topic == name) {
+            Some(index) => index,
+            None => {
+                topics.push(TopicReport {
+                    topic: name.to_string(),
+                    api_requests: 0,
+                    tool_calls: 0,
+                    failures: 0,
+                    tokens: 0,
+                    duration_ms: 0,
+                });
+                topics.len() - 1
+            }
+        };
+        let topic = &mut topics[index];
+        match entry.log_type.as_str() {
+            "api_request" => {
+                topic.api_requests += 1;
+                topic.tokens += entry.tokens_used.unwrap_or(0);
+                topic.duration_ms += entry.duration_ms.unwrap_or(0);
+            }
+            "tool_call" | "mcp_call" => {
+                topic.tool_calls += 1;
+                topic.duration_ms += entry.duration_ms.unwrap_or(0);
+            }
+            _ => {}
+        }
+        if !entry.success {
+            topic.failures += 1;
+        }
+    }
+
+    let failures = timeline.iter().filter(|entry| !entry.success).count() as i64;
+    let estimated_cost_usd = estimate_cost_usd(
+        model.as_deref().unwrap_or_default(),
+        total_tokens,
+        batch_tokens,
+    );
+
+    Ok(RunReport {
+        briefing_id,
+        date,
+        title,
+        model,
+        research_time_ms,
+        total_tokens,
+        batch_tokens,
+        estimated_cost_usd,
+        topics,
+        timeline,
+        failures,
+    })
+}
+
+/// Build the report of a run and store it as JSON on its briefing.
+pub fn save_run_report(conn: &Connection, briefing_id: i64) -> Result<RunReport, String> {
+    let report = build_run_report(conn, briefing_id)?;
+    let json =
+        serde_json::to_string(&report).map_err(|e| format!("Failed to serialize report: {}", e))?;
+    conn.execute(
+        "UPDATE briefings SET report = ?1 WHERE id = ?2",
+        rusqlite::params![json, briefing_id],
+    )
+    .map_err(|e| format!("Failed to save report: {}", e))?;
+    Ok(report)
+}
+
+/// The stored report of a run, or one built from its logs for runs saved
+/// before reports existed.
+pub fn run_report(conn: &Connection, briefing_id: i64) -> Result<RunReport, String> {
+    let stored: Option<String> = conn
+        .query_row(
+            "SELECT report FROM briefings WHERE id = ?1",
+            [briefing_id],
+            |row| row.get(0),
+        )
+        .map_err(|e| match e {
+            rusqlite::Error::QueryReturnedNoRows => format!("Run {} not found", briefing_id),
+            e => format!("Failed to load report: {}", e),
+        })?;
+    match stored.and_then(|json| serde_json::from_str(&json).ok()) {
+        Some(report) => Ok(report),
+        None => build_run_report(conn, briefing_id),
+    }
 }
 
 /// A log record from the database.
@@ -632,6 +1170,8 @@ pub struct ResearchLogRecord {
     pub error_message: Option<String>,
     pub user_action_required: bool,
     pub created_at: String,
+    #[serde(default)]
+    pub request_id: Option<String>,
 }
 
 #[cfg(test)]
@@ -654,6 +1194,17 @@ mod tests {
         assert!(error.requires_user_action);
     }
 
+    #[test]
+    fn test_api_error_request_id() {
+        let body = r#"{"type":"error","error":{"type":"overloaded_error","message":"Overloaded"}}"#;
+        let error = parse_api_error(529, body).with_request_id(Some("req_011C".to_string()));
+        let entry = ResearchLogEntry::failure(LogType::ApiRequest, &error);
+        assert_eq!(entry.request_id.as_deref(), Some("req_011C"));
+        assert!(ResearchLogEntry::success(LogType::ApiRequest)
+            .request_id
+            .is_none());
+    }
+
     #[test]
     fn test_parse_api_error_rate_limit() {
         let body = r#"{"type":"error","error":{"type":"rate_limit_error","message":"Rate limit exceeded"}}"#;
@@ -675,6 +1226,155 @@ mod tests {
         assert_eq!(truncate_string("this is a long string", 10), "this is...");
     }
 
+    fn insert_log(
+        conn: &Connection,
+        log_type: &str,
+        tool: &str,
+        success: bool,
+        ms: i64,
+        tokens: i64,
+    ) {
+        conn.execute(
+            "INSERT INTO research_logs (log_type, tool_name, duration_ms, tokens_used, success)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            rusqlite::params![log_type, tool, ms, tokens, success as i32],
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_tool_analytics_aggregates_per_tool_and_server() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(include_str!("schema.sql")).unwrap();
+        insert_log(&conn, "mcp_call", "github:search", true, 100, 40);
+        insert_log(&conn, "mcp_call", "github:search", false, 300, 0);
+        insert_log(&conn, "mcp_call", "github:issues", true, 200, 60);
+        insert_log(&conn, "tool_call", "fetch_webpage", true, 50, 10);
+        insert_log(&conn, "api_request", "", true, 900, 1000);
+
+        let since = "2000-01-01 00:00:00";
+        conn.execute(
+            "INSERT INTO briefings (date, title, cards, total_tokens) VALUES ('2025-01-01', 'B', '[]', 5000)",
+            [],
+        )
+        .unwrap();
+        let briefing_id = conn.last_insert_rowid();
+        assert_eq!(
+            attach_logs_to_briefing(&conn, briefing_id, since).unwrap(),
+            5
+        );
+
+        let analytics = tool_analytics(&conn, 30).unwrap();
+
+        let search = analytics
+            .tools
+            .iter()
+            .find(|t| t.tool_name == "github:search")
+            .unwrap();
+        assert_eq!(search.calls, 2);
+        assert_eq!(search.failures, 1);
+        assert_eq!(search.failure_rate, 0.5);
+        assert_eq!(search.avg_duration_ms, Some(200.0));
+        assert_eq!(search.server.as_deref(), Some("github"));
+        assert_eq!(analytics.tools.len(), 3);
+
+        assert_eq!(analytics.servers.len(), 1);
+        assert_eq!(analytics.servers[0].server, "github");
+        assert_eq!(analytics.servers[0].tools, 2);
+        assert_eq!(analytics.servers[0].calls, 3);
+        assert_eq!(analytics.servers[0].est_tokens, 100);
+
+        assert_eq!(analytics.daily.len(), 1);
+        assert_eq!(analytics.daily[0].tool_calls, 4);
+        assert_eq!(analytics.daily[0].est_tool_tokens, 110);
+        assert_eq!(analytics.daily[0].api_tokens, 1000);
+
+        assert_eq!(analytics.briefings.len(), 1);
+        assert_eq!(analytics.briefings[0].tool_calls, 4);
+        assert_eq!(analytics.briefings[0].api_tokens, 5000);
+    }
+
+    #[test]
+    fn test_run_report() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(include_str!("schema.sql")).unwrap();
+        conn.execute(
+            "INSERT INTO briefings (date, title, cards, research_time_ms, model_used, total_tokens)
+             VALUES ('2025-01-01', 'Daily', '[]', 42000, 'claude-haiku-4-5-20251001', 1000000)",
+            [],
+        )
+        .unwrap();
+        let briefing_id = conn.last_insert_rowid();
+        conn.execute(
+            "INSERT INTO api_usage (profile, kind, briefing_id, tokens, batch_tokens)
+             VALUES ('default', 'research', ?1, 1000000, 500000)",
+            [briefing_id],
+        )
+        .unwrap();
+        for (log_type, topic, tool, success, ms, tokens) in [
+            ("api_request", "Rust", "", true, 900, 600),
+            ("tool_call", "Rust", "fetch_webpage", true, 100, 10),
+            ("mcp_call", "Rust", "github:search", false, 300, 0),
+            ("api_request", "AI", "", true, 700, 400),
+        ] {
+            conn.execute(
+                "INSERT INTO research_logs
+                 (briefing_id, log_type, topic, tool_name, duration_ms, tokens_used, success,
+                  error_message)
+                 VALUES (?1, ?2, ?3, NULLIF(?4, ''), ?5, ?6, ?7, ?8)",
+                rusqlite::params![
+                    briefing_id,
+                    log_type,
+                    topic,
+                    tool,
+                    ms,
+                    tokens,
+                    success as i32,
+                    (!success).then_some("Server unavailable"),
+                ],
+            )
+            .unwrap();
+        }
+
+        let report = save_run_report(&conn, briefing_id).unwrap();
+        assert_eq!(report.timeline.len(), 4);
+        assert_eq!(report.failures, 1);
+        assert_eq!(report.batch_tokens, 500000);
+        assert_eq!(
+            report.errors().next().unwrap().tool_name.as_deref(),
+            Some("github:search")
+        );
+        assert_eq!(
+            report.topics[0],
+            TopicReport {
+                topic: "Rust".to_string(),
+                api_requests: 1,
+                tool_calls: 2,
+                failures: 1,
+                tokens: 600,
+                duration_ms: 1300,
+            }
+        );
+        assert_eq!(report.topics[1].topic, "AI");
+        assert!((report.estimated_cost_usd - 1.35).abs() < 1e-9);
+
+        // Later logs don't change a stored report
+        conn.execute("DELETE FROM research_logs", []).unwrap();
+        assert_eq!(run_report(&conn, briefing_id).unwrap().timeline.len(), 4);
+        assert!(run_report(&conn, briefing_id + 1).is_err());
+    }
+
+    #[test]
+    fn test_estimate_cost_usd() {
+        assert_eq!(
+            estimate_cost_usd("claude-opus-4-5-20251101", 1_000_000, 0),
+            9.0
+        );
+        // Batch tokens cost half
+        assert!((estimate_cost_usd("claude-sonnet-4-5", 1_000_000, 1_000_000) - 2.7).abs() < 1e-9);
+        assert_eq!(estimate_cost_usd("unknown", 0, 0), 0.0);
+    }
+
     #[test]
     fn test_research_error_display() {
         let error = ResearchError::new(ErrorCode::RateLimited, "Too many requests");
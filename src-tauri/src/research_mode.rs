@@ -0,0 +1,374 @@
+//! Research modes.
+//!
+//! A mode decides which tools research may offer Claude, what Claude is told
+//! about using them and how long the briefing cards should be. The
+//! `research_mode` setting (or `claudius research now --mode`) picks one by
+//! name. To add a mode, implement [`ResearchMode`] and list it in [`MODES`].
+
+/// MCP tools from the Firecrawl server, used only in Firecrawl mode.
+const FIRECRAWL_TOOLS: [&str; 5] = [
+    "firecrawl_search",
+    "firecrawl_scrape",
+    "firecrawl_extract",
+    "firecrawl_map",
+    "firecrawl_crawl",
+];
+
+/// Search and fetch tools Firecrawl replaces.
+const STANDARD_SEARCH_TOOLS: [&str; 4] = [
+    "brave_search",
+    "brave_web_search",
+    "perplexity_ask",
+    "fetch_webpage",
+];
+
+/// Built-in tools for community discussion rather than primary sources.
+const COMMUNITY_TOOLS: [&str; 2] = ["get_hackernews_top", "search_reddit"];
+
+/// Tools that reach the public web, left out of local-only research.
+const WEB_TOOLS: [&str; 6] = [
+    "brave",
+    "perplexity",
+    "firecrawl",
+    "fetch",
+    "web_search",
+    "tavily",
+];
+
+/// How long the synthesized cards should be.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CardLength {
+    /// Minimum words of a condensed (all topics in one) card
+    pub condensed_words: u32,
+    /// Paragraphs of a condensed card, e.g. "5-7"
+    pub condensed_paragraphs: &'static str,
+    /// Minimum words of each topic card
+    pub card_words: u32,
+    /// Paragraphs of each topic card
+    pub card_paragraphs: &'static str,
+}
+
+impl CardLength {
+    pub const STANDARD: CardLength = CardLength {
+        condensed_words: 400,
+        condensed_paragraphs: "5-7",
+        card_words: 150,
+        card_paragraphs: "2-3",
+    };
+
+    pub const DEEP: CardLength = CardLength {
+        condensed_words: 800,
+        condensed_paragraphs: "8-12",
+        card_words: 350,
+        card_paragraphs: "4-6",
+    };
+}
+
+/// A way of researching topics.
+pub trait ResearchMode: Send + Sync {
+    /// Name used in settings and on the command line.
+    fn name(&self) -> &'static str;
+
+    /// One-line description for settings and `config show`.
+    fn description(&self) -> &'static str;
+
+    /// Whether the tool `name` may be offered to Claude. `server` is the MCP
+    /// server providing it, or None for built-in tools and `web_search`.
+    fn allows_tool(&self, server: Option<&str>, name: &str) -> bool;
+
+    /// Part of an MCP tool name this mode can't run without. Research fails
+    /// early when no configured server provides such a tool.
+    fn required_mcp_tool(&self) -> Option<&'static str> {
+        None
+    }
+
+    /// Tool usage instructions added to each topic's system prompt.
+    fn tool_instructions(&self, month_year: &str, current_year: &str) -> String;
+
+    fn card_length(&self) -> CardLength {
+        CardLength::STANDARD
+    }
+
+    /// Extra instruction for the synthesis prompt ("" for none).
+    fn depth_instruction(&self) -> &'static str {
+        ""
+    }
+}
+
+fn contains_any(name: &str, fragments: &[&str]) -> bool {
+    let name = name.to_lowercase();
+    fragments.iter().any(|fragment| name.contains(fragment))
+}
+
+const GITHUB_INSTRUCTIONS: &str = "- Use get_github_activity for open source projects to see recent commits, PRs, and releases from {month_year}
+- Dig deeper into GitHub with get_github_issue (an issue or PR with its comments), get_github_commit_diff (what a commit changed), get_github_discussions, search_github_repos, and get_github_trending";
+
+const NEWS_INSTRUCTIONS: &str = "- Use search_arxiv for new papers, get_hackernews_top for what the tech community is discussing, and search_reddit for community reactions";
+
+/// Search MCP servers (Brave, Perplexity) plus the built-in fetch, GitHub and news tools.
+pub struct StandardMode;
+
+impl ResearchMode for StandardMode {
+    fn name(&self) -> &'static str {
+        "standard"
+    }
+
+    fn description(&self) -> &'static str {
+        "Brave/Perplexity search and built-in page fetching; fast and cost-effective"
+    }
+
+    fn allows_tool(&self, _server: Option<&str>, name: &str) -> bool {
+        !contains_any(name, &FIRECRAWL_TOOLS)
+    }
+
+    fn tool_instructions(&self, month_year: &str, current_year: &str) -> String {
+        format!(
+            r#"CRITICAL SEARCH TOOL USAGE:
+- If you have access to brave_search or perplexity search tools, USE THEM FIRST to find {month_year} articles and information
+- Use specific search queries like "[topic] {month_year}" or "[topic] {current_year} latest news"
+- Search tools will give you current URLs and content - these are your primary source for {month_year} information
+- After getting search results, use fetch_webpage to read the most promising URLs in full
+{github}
+{news}
+
+When using fetch_webpage directly (without search):
+- Target URLs likely to have {month_year} content: TechCrunch, The Verge, Hacker News, company blogs, official documentation
+- Prioritize URLs with "/{year_path}" or "{month_path}" in the path"#,
+            github = GITHUB_INSTRUCTIONS.replace("{month_year}", month_year),
+            news = NEWS_INSTRUCTIONS,
+            year_path = current_year.to_lowercase(),
+            month_path = month_year.to_lowercase().replace(' ', "-"),
+        )
+    }
+}
+
+/// Firecrawl's search and extraction instead of the standard search tools.
+pub struct FirecrawlMode;
+
+impl ResearchMode for FirecrawlMode {
+    fn name(&self) -> &'static str {
+        "firecrawl"
+    }
+
+    fn description(&self) -> &'static str {
+        "Deep research with Firecrawl web extraction (needs the Firecrawl MCP server)"
+    }
+
+    fn allows_tool(&self, _server: Option<&str>, name: &str) -> bool {
+        !contains_any(name, &STANDARD_SEARCH_TOOLS)
+    }
+
+    fn required_mcp_tool(&self) -> Option<&'static str> {
+        Some("firecrawl")
+    }
+
+    fn tool_instructions(&self, month_year: &str, current_year: &str) -> String {
+        format!(
+            r#"CRITICAL SEARCH TOOL USAGE (Firecrawl Deep Research Mode):
+- Use firecrawl_search to find {month_year} articles - it searches AND extracts content in one call
+- Use specific search queries like "[topic] {month_year}" or "[topic] {current_year} latest news"
+- firecrawl_search returns full page content, not just URLs - analyze the results directly
+- Use firecrawl_scrape to get full content from specific URLs you want to analyze deeply
+- Use firecrawl_extract for structured data extraction with custom prompts (great for extracting specific facts)
+- Use firecrawl_map to discover related pages on a website
+{github}
+{news}
+
+Firecrawl tools handle JavaScript-heavy sites and provide clean markdown content. Use them aggressively for comprehensive research."#,
+            github = GITHUB_INSTRUCTIONS.replace("{month_year}", month_year),
+            news = NEWS_INSTRUCTIONS,
+        )
+    }
+
+    fn card_length(&self) -> CardLength {
+        CardLength::DEEP
+    }
+
+    fn depth_instruction(&self) -> &'static str {
+        "\n**DEEP RESEARCH MODE**: You have access to comprehensive web extraction. Provide EXTRA detail, analysis, and insights. Include more sources, deeper technical analysis, and thorough coverage. Users are paying premium credits for this depth - deliver exceptional value."
+    }
+}
+
+/// Papers and primary sources; community discussion tools are left out.
+pub struct AcademicMode;
+
+impl ResearchMode for AcademicMode {
+    fn name(&self) -> &'static str {
+        "academic"
+    }
+
+    fn description(&self) -> &'static str {
+        "Papers, preprints and primary sources; skips Hacker News and Reddit"
+    }
+
+    fn allows_tool(&self, _server: Option<&str>, name: &str) -> bool {
+        !contains_any(name, &FIRECRAWL_TOOLS) && !contains_any(name, &COMMUNITY_TOOLS)
+    }
+
+    fn tool_instructions(&self, month_year: &str, current_year: &str) -> String {
+        format!(
+            r#"CRITICAL SEARCH TOOL USAGE (Academic Mode):
+- Use search_arxiv FIRST to find papers and preprints from {month_year}
+- If you have brave_search or perplexity search tools, use them to find conference proceedings, journal articles, lab and university announcements from {current_year}
+- Use fetch_webpage to read abstracts, paper pages and official announcements in full
+- Use get_github_activity and search_github_repos to find code released alongside papers
+- Prefer primary sources (papers, datasets, official documentation) over news coverage, and never rely on forum or social media posts
+
+Name the authors and venue of every paper you cite, and say whether it is peer-reviewed or a preprint."#
+        )
+    }
+
+    fn card_length(&self) -> CardLength {
+        CardLength {
+            card_words: 250,
+            card_paragraphs: "3-4",
+            ..CardLength::STANDARD
+        }
+    }
+
+    fn depth_instruction(&self) -> &'static str {
+        "\n**ACADEMIC MODE**: Focus on methods, results and limitations of the work you cover. Cite papers by title and link, and separate peer-reviewed findings from preprints."
+    }
+}
+
+/// Only the GitHub tools (built-in or from an MCP server).
+pub struct GitHubOnlyMode;
+
+impl ResearchMode for GitHubOnlyMode {
+    fn name(&self) -> &'static str {
+        "github-only"
+    }
+
+    fn description(&self) -> &'static str {
+        "Only GitHub activity: commits, releases, issues, PRs and discussions"
+    }
+
+    fn allows_tool(&self, server: Option<&str>, name: &str) -> bool {
+        contains_any(name, &["github"]) || server.is_some_and(|s| contains_any(s, &["github"]))
+    }
+
+    fn tool_instructions(&self, month_year: &str, _current_year: &str) -> String {
+        format!(
+            r#"CRITICAL TOOL USAGE (GitHub-only Mode):
+- Only GitHub tools are available; do not rely on news articles or general web pages
+{github}
+- Find the repositories behind each topic with search_github_repos and get_github_trending when you don't already know them
+
+Report what changed in the code, releases and discussions, and link the commits, PRs, issues and releases you cite."#,
+            github = GITHUB_INSTRUCTIONS.replace("{month_year}", month_year),
+        )
+    }
+}
+
+/// Only MCP servers that don't reach the public web (notes, files, databases).
+pub struct LocalOnlyMode;
+
+impl ResearchMode for LocalOnlyMode {
+    fn name(&self) -> &'static str {
+        "local-only"
+    }
+
+    fn description(&self) -> &'static str {
+        "Only local MCP servers (files, notes, databases); nothing from the web"
+    }
+
+    fn allows_tool(&self, server: Option<&str>, name: &str) -> bool {
+        server.is_some_and(|s| !contains_any(s, &WEB_TOOLS)) && !contains_any(name, &WEB_TOOLS)
+    }
+
+    fn tool_instructions(&self, _month_year: &str, _current_year: &str) -> String {
+        r#"CRITICAL TOOL USAGE (Local-only Mode):
+- Only local tools (files, notes, databases from the user's MCP servers) are available; there is no web access
+- Search the local sources thoroughly for everything related to the topic
+- Base every finding on what the tools return and say when the local sources have nothing new; do not fill gaps from memory"#
+            .to_string()
+    }
+}
+
+/// Every research mode, in the order settings list them.
+pub static MODES: [&dyn ResearchMode; 5] = [
+    &StandardMode,
+    &FirecrawlMode,
+    &AcademicMode,
+    &GitHubOnlyMode,
+    &LocalOnlyMode,
+];
+
+/// Names of all research modes.
+pub fn names() -> Vec<&'static str> {
+    MODES.iter().map(|mode| mode.name()).collect()
+}
+
+/// The mode called `name` (case-insensitive).
+pub fn find(name: &str) -> Option<&'static dyn ResearchMode> {
+    let name = name.trim().to_lowercase();
+    MODES.iter().copied().find(|mode| mode.name() == name)
+}
+
+/// The mode called `name`, or standard mode for an unknown name.
+pub fn find_or_standard(name: &str) -> &'static dyn ResearchMode {
+    find(name).unwrap_or_else(|| {
+        tracing::warn!("Unknown research mode '{}', using standard", name);
+        &StandardMode
+    })
+}
+
+/// Validate a research mode name, returning it normalized.
+#[allow(dead_code)]
+pub fn parse_research_mode(name: &str) -> Result<String, String> {
+    find(name)
+        .map(|mode| mode.name().to_string())
+        .ok_or_else(|| {
+            format!(
+                "Unknown research mode '{}'. Use one of: {}",
+                name.trim(),
+                names().join(", ")
+            )
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_modes() {
+        assert_eq!(find(" Firecrawl ").unwrap().name(), "firecrawl");
+        assert!(find("tabloid").is_none());
+        assert_eq!(find_or_standard("tabloid").name(), "standard");
+        assert_eq!(parse_research_mode("GitHub-Only").unwrap(), "github-only");
+        assert!(parse_research_mode("tabloid").is_err());
+        assert_eq!(names().len(), MODES.len());
+    }
+
+    #[test]
+    fn test_mode_tool_filters() {
+        let standard = find("standard").unwrap();
+        assert!(standard.allows_tool(None, "fetch_webpage"));
+        assert!(standard.allows_tool(Some("brave"), "brave_web_search"));
+        assert!(!standard.allows_tool(Some("firecrawl"), "firecrawl_scrape"));
+
+        let firecrawl = find("firecrawl").unwrap();
+        assert!(!firecrawl.allows_tool(None, "fetch_webpage"));
+        assert!(!firecrawl.allows_tool(Some("brave"), "brave_web_search"));
+        assert!(firecrawl.allows_tool(Some("firecrawl"), "firecrawl_scrape"));
+        assert!(firecrawl.allows_tool(None, "web_search"));
+
+        let academic = find("academic").unwrap();
+        assert!(academic.allows_tool(None, "search_arxiv"));
+        assert!(!academic.allows_tool(None, "search_reddit"));
+
+        let github = find("github-only").unwrap();
+        assert!(github.allows_tool(None, "get_github_issue"));
+        assert!(github.allows_tool(Some("github"), "list_commits"));
+        assert!(!github.allows_tool(None, "fetch_webpage"));
+        assert!(!github.allows_tool(None, "web_search"));
+
+        let local = find("local-only").unwrap();
+        assert!(local.allows_tool(Some("obsidian"), "search_notes"));
+        assert!(!local.allows_tool(Some("brave"), "brave_web_search"));
+        assert!(!local.allows_tool(Some("tools"), "fetch"));
+        assert!(!local.allows_tool(None, "get_github_activity"));
+        assert!(!local.allows_tool(None, "web_search"));
+    }
+}
@@ -1,7 +1,20 @@
 use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::PathBuf;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
-use std::time::SystemTime;
+use std::time::{Duration, SystemTime};
+use tauri::Emitter;
+use tracing::{debug, info, warn};
+
+/// How often the lock holder refreshes the lock file's heartbeat.
+const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(15);
+
+/// A lock whose heartbeat is older than this is treated as abandoned.
+const STALE_AFTER_SECS: i64 = 90;
 
 /// Global research state for coordinating cancellation and preventing concurrent operations
 #[derive(Debug, Clone)]
@@ -26,6 +39,275 @@ impl Default for ResearchState {
 lazy_static! {
     static ref GLOBAL_STATE: Arc<Mutex<ResearchState>> =
         Arc::new(Mutex::new(ResearchState::default()));
+    /// Stop flag for the heartbeat thread of the lock file this process holds.
+    static ref HELD_LOCK: Mutex<Option<Arc<AtomicBool>>> = Mutex::new(None);
+    /// Most recent progress events, for windows opened mid-run.
+    static ref EVENT_BACKLOG: Mutex<EventBacklog> = Mutex::new(EventBacklog::default());
+}
+
+// ============================================================================
+// Progress events
+// ============================================================================
+
+/// Progress events kept in memory. Older events are dropped beyond this.
+const EVENT_BACKLOG_CAPACITY: usize = 500;
+
+/// A `research:*` event as it was emitted to the frontend.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordedEvent {
+    pub seq: u64, // Increases by one per event, for resuming with `event_backlog`
+    pub event: String,
+    pub payload: serde_json::Value,
+}
+
+/// Ring buffer of recent progress events, across runs.
+#[derive(Debug, Default)]
+struct EventBacklog {
+    events: VecDeque<RecordedEvent>,
+    next_seq: u64,
+}
+
+/// Everything a window needs to show a run it didn't see start.
+#[derive(Debug, Clone, Serialize)]
+pub struct ProgressSnapshot {
+    pub is_running: bool,
+    pub current_phase: String,
+    pub started_at: Option<String>,
+    pub elapsed_ms: Option<u64>,
+    pub events: Vec<RecordedEvent>,
+}
+
+/// Remember an event so windows opened later can replay it.
+pub fn record_event<S: Serialize>(event: &str, payload: &S) {
+    let payload = serde_json::to_value(payload).unwrap_or(serde_json::Value::Null);
+    let mut backlog = EVENT_BACKLOG.lock().unwrap_or_else(|e| e.into_inner());
+    let seq = backlog.next_seq;
+    backlog.next_seq += 1;
+    if backlog.events.len() == EVENT_BACKLOG_CAPACITY {
+        backlog.events.pop_front();
+    }
+    backlog.events.push_back(RecordedEvent {
+        seq,
+        event: event.to_string(),
+        payload,
+    });
+}
+
+/// Recorded events newer than `since` (all of them when None), oldest first.
+pub fn event_backlog(since: Option<u64>) -> Vec<RecordedEvent> {
+    EVENT_BACKLOG
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .events
+        .iter()
+        .filter(|e| since.is_none_or(|s| e.seq > s))
+        .cloned()
+        .collect()
+}
+
+/// Record a progress event and emit it to every window.
+pub fn emit<S: Serialize + Clone>(app: &tauri::AppHandle, event: &str, payload: S) {
+    publish(Some(app), event, payload);
+}
+
+/// Record a progress event, emitting it to every window when there is an
+/// app. Without one (the CLI) the event is only recorded, for
+/// `research now --json --stream` to print.
+pub fn publish<S: Serialize + Clone>(app: Option<&tauri::AppHandle>, event: &str, payload: S) {
+    record_event(event, &payload);
+    if let Some(app) = app {
+        let _ = app.emit(event, payload);
+    }
+}
+
+/// Events since the most recent `research:started` still in the backlog.
+fn current_run_events() -> Vec<RecordedEvent> {
+    let mut events = event_backlog(None);
+    if let Some(start) = events.iter().rposition(|e| e.event == "research:started") {
+        events.drain(..start);
+    }
+    events
+}
+
+/// The current run's state and every progress event emitted so far.
+pub fn progress_snapshot() -> ProgressSnapshot {
+    let state = get_state();
+    ProgressSnapshot {
+        is_running: state.is_running,
+        current_phase: state.current_phase,
+        started_at: state
+            .started_at
+            .map(|t| chrono::DateTime::<chrono::Utc>::from(t).to_rfc3339()),
+        elapsed_ms: state
+            .started_at
+            .and_then(|t| t.elapsed().ok())
+            .map(|d| d.as_millis() as u64),
+        events: current_run_events(),
+    }
+}
+
+// ============================================================================
+// Cross-process lock
+// ============================================================================
+
+/// Contents of `~/.claudius/research.lock`, held by whichever process (app,
+/// CLI or daemon) is running research.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct LockInfo {
+    pub pid: u32,
+    pub process: String,
+    pub started_at: String,
+    pub heartbeat: i64, // Unix seconds, refreshed while research runs
+}
+
+impl LockInfo {
+    fn current() -> Self {
+        let process = std::env::current_exe()
+            .ok()
+            .and_then(|p| p.file_name().map(|n| n.to_string_lossy().to_string()))
+            .unwrap_or_else(|| "claudius".to_string());
+        Self {
+            pid: std::process::id(),
+            process,
+            started_at: chrono::Local::now().to_rfc3339(),
+            heartbeat: chrono::Utc::now().timestamp(),
+        }
+    }
+
+    /// Whether the holder stopped refreshing the lock or no longer exists.
+    pub fn is_stale(&self) -> bool {
+        chrono::Utc::now().timestamp() - self.heartbeat > STALE_AFTER_SECS
+            || !process_alive(self.pid)
+    }
+}
+
+#[cfg(unix)]
+fn process_alive(pid: u32) -> bool {
+    std::process::Command::new("kill")
+        .args(["-0", &pid.to_string()])
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .status()
+        .map(|s| s.success())
+        .unwrap_or(true)
+}
+
+#[cfg(not(unix))]
+fn process_alive(_pid: u32) -> bool {
+    // Rely on the heartbeat alone
+    true
+}
+
+fn lock_path() -> PathBuf {
+    // Keep tests away from a real research run's lock
+    if cfg!(test) {
+        return std::env::temp_dir().join(format!("claudius-research-{}.lock", std::process::id()));
+    }
+    crate::config::get_data_dir().join("research.lock")
+}
+
+/// Read the research lock, if one exists.
+pub fn lock_info() -> Option<LockInfo> {
+    let content = std::fs::read_to_string(lock_path()).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+fn write_lock(file: &mut std::fs::File, info: &LockInfo) -> Result<(), String> {
+    let content =
+        serde_json::to_string(info).map_err(|e| format!("Failed to serialize lock: {}", e))?;
+    file.write_all(content.as_bytes())
+        .map_err(|e| format!("Failed to write research lock: {}", e))
+}
+
+/// Create the lock file, failing if another live process holds it.
+fn acquire_lock() -> Result<(), String> {
+    let path = lock_path();
+    if let Some(dir) = path.parent() {
+        let _ = std::fs::create_dir_all(dir);
+    }
+
+    for _ in 0..2 {
+        match OpenOptions::new().write(true).create_new(true).open(&path) {
+            Ok(mut file) => {
+                let info = LockInfo::current();
+                write_lock(&mut file, &info)?;
+                start_heartbeat(info);
+                return Ok(());
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => match lock_info() {
+                Some(holder) if holder.pid == std::process::id() => {
+                    // Left over from this process (e.g. after a reset)
+                    let _ = std::fs::remove_file(&path);
+                }
+                Some(holder) if !holder.is_stale() => {
+                    return Err(format!(
+                        "Research is already running in {} (pid {}, started {})",
+                        holder.process, holder.pid, holder.started_at
+                    ));
+                }
+                holder => {
+                    warn!("Removing stale research lock: {:?}", holder);
+                    let _ = std::fs::remove_file(&path);
+                }
+            },
+            Err(e) => return Err(format!("Failed to create research lock: {}", e)),
+        }
+    }
+    Err("Failed to acquire research lock".to_string())
+}
+
+/// Refresh the lock's heartbeat until `release_lock` is called.
+fn start_heartbeat(mut info: LockInfo) {
+    let stop = Arc::new(AtomicBool::new(false));
+    *HELD_LOCK.lock().unwrap_or_else(|e| e.into_inner()) = Some(stop.clone());
+
+    std::thread::spawn(move || loop {
+        std::thread::sleep(HEARTBEAT_INTERVAL);
+        // Holding HELD_LOCK keeps release_lock from deleting the file mid-write
+        let _held = HELD_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        if stop.load(Ordering::Relaxed) {
+            break;
+        }
+        info.heartbeat = chrono::Utc::now().timestamp();
+        let result = std::fs::File::create(lock_path())
+            .map_err(|e| format!("Failed to open research lock: {}", e))
+            .and_then(|mut file| write_lock(&mut file, &info));
+        if let Err(e) = result {
+            debug!("Research lock heartbeat failed: {}", e);
+        }
+    });
+}
+
+/// Remove the lock file if this process holds it.
+fn release_lock() {
+    let mut held = HELD_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(stop) = held.take() {
+        stop.store(true, Ordering::Relaxed);
+        if lock_info().is_some_and(|l| l.pid == std::process::id()) {
+            let _ = std::fs::remove_file(lock_path());
+        }
+    }
+}
+
+/// Remove the research lock left by another process. Unless `force` is set,
+/// only a stale lock is removed. Returns the removed lock, if any.
+#[allow(dead_code)]
+pub fn unlock(force: bool) -> Result<Option<LockInfo>, String> {
+    let Some(holder) = lock_info() else {
+        // An unreadable lock file is removed too
+        let _ = std::fs::remove_file(lock_path());
+        return Ok(None);
+    };
+    if !force && !holder.is_stale() {
+        return Err(format!(
+            "Research is running in {} (pid {}); use --force to remove the lock anyway",
+            holder.process, holder.pid
+        ));
+    }
+    std::fs::remove_file(lock_path())
+        .map_err(|e| format!("Failed to remove research lock: {}", e))?;
+    info!("Removed research lock held by pid {}", holder.pid);
+    Ok(Some(holder))
 }
 
 /// Get a clone of the current global research state
@@ -52,6 +334,9 @@ pub fn set_running(phase: &str) -> Result<Arc<AtomicBool>, String> {
         return Err("Research is already running".to_string());
     }
 
+    // Another process (app, CLI or daemon) may be running research
+    acquire_lock()?;
+
     // Create new cancellation token
     state.cancellation_token = Arc::new(AtomicBool::new(false));
     state.is_running = true;
@@ -70,6 +355,7 @@ pub fn set_stopped() -> Result<(), String> {
     state.is_running = false;
     state.current_phase = String::new();
     state.started_at = None;
+    release_lock();
     Ok(())
 }
 
@@ -104,6 +390,7 @@ pub fn reset() {
     if let Ok(mut state) = GLOBAL_STATE.lock() {
         *state = ResearchState::default();
     }
+    release_lock();
 }
 
 #[cfg(test)]
@@ -163,6 +450,83 @@ mod tests {
         assert!(!is_running());
     }
 
+    #[test]
+    fn test_lock_file() {
+        let _lock = TEST_MUTEX.lock().unwrap();
+        reset();
+        let _ = set_running("starting").unwrap();
+        let holder = lock_info().unwrap();
+        assert_eq!(holder.pid, std::process::id());
+        assert!(!holder.is_stale());
+        assert!(unlock(false).is_err());
+
+        set_stopped().unwrap();
+        assert!(lock_info().is_none());
+
+        // A lock whose holder stopped sending heartbeats is taken over
+        let abandoned = LockInfo {
+            pid: 1,
+            process: "claudius".to_string(),
+            started_at: "2026-01-01T06:00:00+00:00".to_string(),
+            heartbeat: 0,
+        };
+        std::fs::write(lock_path(), serde_json::to_string(&abandoned).unwrap()).unwrap();
+        assert!(abandoned.is_stale());
+        let _ = set_running("starting").unwrap();
+        assert_eq!(lock_info().unwrap().pid, std::process::id());
+
+        set_stopped().unwrap();
+        std::fs::write(lock_path(), serde_json::to_string(&abandoned).unwrap()).unwrap();
+        assert_eq!(unlock(false).unwrap(), Some(abandoned));
+        assert!(lock_info().is_none());
+    }
+
+    #[test]
+    fn test_progress_snapshot_events() {
+        let _lock = TEST_MUTEX.lock().unwrap();
+        reset();
+        record_event(
+            "research:topic_started",
+            &serde_json::json!({ "topic_name": "Old" }),
+        );
+        record_event(
+            "research:started",
+            &serde_json::json!({ "total_topics": 2 }),
+        );
+        record_event(
+            "research:tool_started",
+            &serde_json::json!({ "tool_name": "fetch_webpage" }),
+        );
+
+        let snapshot = progress_snapshot();
+        assert!(!snapshot.is_running);
+        assert_eq!(snapshot.events.len(), 2);
+        assert_eq!(snapshot.events[0].event, "research:started");
+        assert_eq!(snapshot.events[1].payload["tool_name"], "fetch_webpage");
+
+        let _ = set_running("starting").unwrap();
+        assert!(progress_snapshot().elapsed_ms.is_some());
+    }
+
+    #[test]
+    fn test_event_backlog_is_bounded() {
+        let _lock = TEST_MUTEX.lock().unwrap();
+        let first = event_backlog(None).last().map(|e| e.seq + 1).unwrap_or(0);
+        for i in 0..EVENT_BACKLOG_CAPACITY + 10 {
+            record_event("research:heartbeat", &serde_json::json!({ "i": i }));
+        }
+
+        let all = event_backlog(None);
+        assert_eq!(all.len(), EVENT_BACKLOG_CAPACITY);
+        assert_eq!(all[0].payload["i"], 10);
+        assert!(all.windows(2).all(|w| w[1].seq == w[0].seq + 1));
+
+        let last_seq = first + EVENT_BACKLOG_CAPACITY as u64 + 9;
+        let newer = event_backlog(Some(last_seq - 2));
+        assert_eq!(newer.len(), 2);
+        assert_eq!(newer[1].seq, last_seq);
+    }
+
     #[test]
     fn test_phase_updates() {
         let _lock = TEST_MUTEX.lock().unwrap();
@@ -0,0 +1,267 @@
+//! iCalendar feed of the research schedule and suggested follow-ups.
+//!
+//! With `enable_schedule_calendar` set, `claudius.ics` (in `~/.claudius`, or
+//! at `schedule_calendar_path`) lists the scheduled research runs of the next
+//! [`SCHEDULE_DAYS`] days and every `add_calendar_event` action on the cards
+//! of recent briefings. It is rewritten after each research run and by
+//! `claudius daemon` whenever the schedule changes, so calendar apps that
+//! subscribe to the file (or to `/calendar.ics` from `claudius serve`) stay
+//! current.
+
+use crate::card_actions::{ics_event_lines, CardAction, DEFAULT_EVENT_MINUTES};
+use crate::config::Briefing;
+use crate::research::BriefingCard;
+use chrono::{DateTime, Duration, Local, Utc};
+use croner::Cron;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+/// Days of upcoming research runs included in the calendar.
+pub const SCHEDULE_DAYS: i64 = 14;
+
+/// Cap on research run events, for schedules that run every few minutes.
+const MAX_SCHEDULED_RUNS: usize = 100;
+
+/// Briefings whose follow-up events are included.
+pub const CALENDAR_BRIEFINGS: i32 = 30;
+
+/// Length of a research run event.
+const RUN_EVENT_MINUTES: i64 = 15;
+
+/// Where the calendar is written when `schedule_calendar_path` isn't set.
+pub fn default_calendar_path() -> PathBuf {
+    crate::config::get_data_dir().join("claudius.ics")
+}
+
+/// The calendar path from settings, expanding a leading `~/`.
+pub fn calendar_path(configured: Option<&str>) -> PathBuf {
+    configured
+        .map(str::trim)
+        .filter(|p| !p.is_empty())
+        .map(crate::integrations::expand_home)
+        .unwrap_or_else(default_calendar_path)
+}
+
+/// Scheduled run times of `schedule` in the [`SCHEDULE_DAYS`] after `after`.
+pub fn upcoming_runs(
+    schedule: &str,
+    after: DateTime<Local>,
+) -> Result<Vec<DateTime<Local>>, String> {
+    let cron = Cron::new(schedule.trim())
+        .parse()
+        .map_err(|e| format!("Invalid schedule '{}': {}", schedule, e))?;
+    let until = after + Duration::days(SCHEDULE_DAYS);
+
+    let mut runs = Vec::new();
+    let mut current = after;
+    while runs.len() < MAX_SCHEDULED_RUNS {
+        match cron.find_next_occurrence(&current, false) {
+            Ok(next) if next <= until => {
+                runs.push(next);
+                current = next;
+            }
+            _ => break,
+        }
+    }
+    Ok(runs)
+}
+
+/// `VEVENT` lines for a scheduled research run. Run times are exact
+/// instants, so they're written in UTC.
+fn run_event_lines(run: &DateTime<Local>, schedule: &str) -> Vec<String> {
+    let start = run.with_timezone(&Utc);
+    let end = start + Duration::minutes(RUN_EVENT_MINUTES);
+    vec![
+        "BEGIN:VEVENT".to_string(),
+        format!("UID:run-{}@claudius", start.format("%Y%m%dT%H%M%SZ")),
+        format!("DTSTAMP:{}", Utc::now().format("%Y%m%dT%H%M%SZ")),
+        format!("DTSTART:{}", start.format("%Y%m%dT%H%M%SZ")),
+        format!("DTEND:{}", end.format("%Y%m%dT%H%M%SZ")),
+        "SUMMARY:Claudius research run".to_string(),
+        format!(
+            "DESCRIPTION:{}",
+            crate::card_actions::escape_ics(&format!("Scheduled by \"{}\"", schedule))
+        ),
+        "TRANSP:TRANSPARENT".to_string(),
+        "END:VEVENT".to_string(),
+    ]
+}
+
+/// Render the calendar: research runs for `schedule` after `now`, then the
+/// calendar event actions of `briefings` (newest first). An event suggested
+/// again by a later briefing is only listed once, and actions with invalid
+/// dates are skipped.
+pub fn render_calendar(
+    schedule: &str,
+    now: DateTime<Local>,
+    briefings: &[Briefing],
+) -> Result<String, String> {
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//Claudius//Research Schedule//EN".to_string(),
+        "CALSCALE:GREGORIAN".to_string(),
+        "X-WR-CALNAME:Claudius".to_string(),
+        "REFRESH-INTERVAL;VALUE=DURATION:PT1H".to_string(),
+        "X-PUBLISHED-TTL:PT1H".to_string(),
+    ];
+
+    for run in upcoming_runs(schedule, now)? {
+        lines.extend(run_event_lines(&run, schedule));
+    }
+
+    let mut seen = HashSet::new();
+    for briefing in briefings {
+        let cards: Vec<BriefingCard> = serde_json::from_str(&briefing.cards).unwrap_or_default();
+        for (card_index, card) in cards.iter().enumerate() {
+            for (action_index, action) in card.actions.iter().enumerate() {
+                let CardAction::AddCalendarEvent {
+                    title,
+                    date,
+                    time,
+                    duration_minutes,
+                    description,
+                } = action
+                else {
+                    continue;
+                };
+                if !seen.insert((title.clone(), date.clone(), time.clone())) {
+                    continue;
+                }
+                let uid = format!(
+                    "briefing-{}-card-{}-action-{}@claudius",
+                    briefing.id,
+                    card_index + 1,
+                    action_index + 1
+                );
+                let description = description.clone().unwrap_or_else(|| card.title.clone());
+                match ics_event_lines(
+                    &uid,
+                    title,
+                    date,
+                    time.as_deref(),
+                    duration_minutes.unwrap_or(DEFAULT_EVENT_MINUTES),
+                    Some(&description),
+                ) {
+                    Ok(event) => lines.extend(event),
+                    Err(e) => tracing::debug!("Skipping calendar event '{}': {}", title, e),
+                }
+            }
+        }
+    }
+
+    lines.push("END:VCALENDAR".to_string());
+    Ok(lines.join("\r\n") + "\r\n")
+}
+
+/// The calendar for the current schedule and recent briefings.
+pub fn build_calendar(conn: &rusqlite::Connection, schedule: &str) -> Result<String, String> {
+    let briefings = crate::db::get_briefings(conn, CALENDAR_BRIEFINGS)?;
+    render_calendar(schedule, Local::now(), &briefings)
+}
+
+/// Rewrite the calendar file at `path`, replacing it atomically so calendar
+/// apps never see a partial file.
+pub fn write_calendar(
+    conn: &rusqlite::Connection,
+    schedule: &str,
+    path: &Path,
+) -> Result<(), String> {
+    let ics = build_calendar(conn, schedule)?;
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+    let tmp = path.with_extension("ics.tmp");
+    std::fs::write(&tmp, ics).map_err(|e| format!("Failed to write calendar: {}", e))?;
+    std::fs::rename(&tmp, path).map_err(|e| format!("Failed to replace calendar: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn briefing(id: i64, cards: serde_json::Value) -> Briefing {
+        Briefing {
+            id,
+            date: "2026-03-01T07:00:00".to_string(),
+            title: "Morning Briefing".to_string(),
+            cards: cards.to_string(),
+            research_time_ms: None,
+            model_used: None,
+            total_tokens: None,
+            tag: None,
+            cover_image_path: None,
+        }
+    }
+
+    fn card(actions: serde_json::Value) -> serde_json::Value {
+        serde_json::json!({
+            "title": "Rust 2.0",
+            "summary": "Summary",
+            "detailed_content": "",
+            "sources": [],
+            "suggested_next": null,
+            "relevance": "high",
+            "topic": "Rust",
+            "actions": actions,
+        })
+    }
+
+    #[test]
+    fn test_upcoming_runs() {
+        let now = Local.with_ymd_and_hms(2026, 3, 1, 8, 0, 0).unwrap();
+        let runs = upcoming_runs("0 7 * * *", now).unwrap();
+        assert_eq!(runs.len(), SCHEDULE_DAYS as usize);
+        assert_eq!(
+            runs[0],
+            Local.with_ymd_and_hms(2026, 3, 2, 7, 0, 0).unwrap()
+        );
+
+        assert_eq!(
+            upcoming_runs("* * * * *", now).unwrap().len(),
+            MAX_SCHEDULED_RUNS
+        );
+        assert!(upcoming_runs("every morning", now).is_err());
+    }
+
+    #[test]
+    fn test_render_calendar() {
+        let event = serde_json::json!({
+            "type": "add_calendar_event",
+            "title": "Rust 2.0 launch",
+            "date": "2026-03-05",
+            "time": "14:30",
+        });
+        let briefings = [
+            briefing(
+                8,
+                serde_json::json!([card(serde_json::json!([
+                    { "type": "open_url", "url": "https://rust-lang.org" },
+                    event,
+                ]))]),
+            ),
+            // The same event suggested by an older briefing, plus a bad date
+            briefing(
+                7,
+                serde_json::json!([card(serde_json::json!([
+                    event,
+                    { "type": "add_calendar_event", "title": "Soon", "date": "soon" },
+                ]))]),
+            ),
+        ];
+        let now = Local.with_ymd_and_hms(2026, 3, 1, 8, 0, 0).unwrap();
+        let ics = render_calendar("0 7 * * 1", now, &briefings).unwrap();
+
+        assert!(ics.starts_with("BEGIN:VCALENDAR\r\n"));
+        assert!(ics.ends_with("END:VCALENDAR\r\n"));
+        // Two Mondays in the next 14 days, plus one follow-up
+        assert_eq!(ics.matches("BEGIN:VEVENT").count(), 3);
+        assert_eq!(ics.matches("SUMMARY:Claudius research run").count(), 2);
+        assert!(ics.contains("UID:briefing-8-card-1-action-2@claudius\r\n"));
+        assert!(ics.contains("DTSTART:20260305T143000\r\n"));
+        assert!(ics.contains("DESCRIPTION:Rust 2.0\r\n"));
+        assert!(!ics.contains("SUMMARY:Soon"));
+    }
+}
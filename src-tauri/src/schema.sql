@@ -8,9 +8,25 @@ CREATE TABLE IF NOT EXISTS briefings (
     research_time_ms INTEGER,
     model_used TEXT,
     total_tokens INTEGER,
+    read_at TIMESTAMP,                -- Set by "Mark read" (notification action or app)
+    report TEXT,                      -- JSON run report (topics, tool call timeline, errors, cost)
+    tag TEXT,                         -- "ad-hoc" for one-off query briefings, NULL for topic research
+    run_id TEXT,                      -- Research run that saved it (unique index added by migration)
+    topics TEXT,                      -- JSON per-topic breakdown (tokens, tool calls, duration, cards)
+    failed_topics TEXT,               -- JSON array of topics that failed and why (left out of the cards)
+    cover_image_prompt TEXT,          -- Hero image prompt built from the whole briefing
+    cover_image_path TEXT,            -- Hero image shown in briefing lists (enable_cover_image)
     created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
 );
 
+-- Research runs merged into an earlier briefing of the same day (merge_same_day_runs)
+CREATE TABLE IF NOT EXISTS briefing_runs (
+    run_id TEXT PRIMARY KEY,
+    briefing_id INTEGER NOT NULL,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (briefing_id) REFERENCES briefings(id) ON DELETE CASCADE
+);
+
 CREATE TABLE IF NOT EXISTS feedback (
     id INTEGER PRIMARY KEY AUTOINCREMENT,
     briefing_id INTEGER NOT NULL,
@@ -30,6 +46,7 @@ CREATE TABLE IF NOT EXISTS chat_messages (
     content TEXT NOT NULL,
     tokens_used INTEGER,
     created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    tools_used TEXT, -- JSON array of tool names used for an assistant reply
     FOREIGN KEY (briefing_id) REFERENCES briefings(id) ON DELETE CASCADE
 );
 
@@ -37,7 +54,7 @@ CREATE TABLE IF NOT EXISTS chat_messages (
 CREATE TABLE IF NOT EXISTS research_logs (
     id INTEGER PRIMARY KEY AUTOINCREMENT,
     briefing_id INTEGER,              -- NULL if research failed before creating briefing
-    log_type TEXT NOT NULL,           -- 'tool_call', 'api_request', 'mcp_call', 'error'
+    log_type TEXT NOT NULL,           -- 'tool_call', 'api_request', 'mcp_call', 'thinking', 'error'
     topic TEXT,                       -- The topic being researched
     tool_name TEXT,                   -- Name of tool/MCP server called
     input_summary TEXT,               -- Brief summary of input (truncated)
@@ -49,6 +66,7 @@ CREATE TABLE IF NOT EXISTS research_logs (
     error_message TEXT,               -- Human-readable error message
     user_action_required INTEGER DEFAULT 0, -- 1 if user needs to take action
     created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    request_id TEXT,                  -- Anthropic request-id response header (API calls)
     FOREIGN KEY (briefing_id) REFERENCES briefings(id) ON DELETE SET NULL
 );
 
@@ -60,7 +78,13 @@ CREATE TABLE IF NOT EXISTS topics (
     enabled INTEGER NOT NULL DEFAULT 1,
     sort_order INTEGER NOT NULL DEFAULT 0,
     created_at TEXT NOT NULL,
-    updated_at TEXT NOT NULL
+    updated_at TEXT NOT NULL,
+    preferred_sources TEXT,           -- JSON array of preferred sites (NULL = none)
+    snoozed_until TEXT,               -- RFC 3339; research skips the topic until then
+    skip_next_run INTEGER NOT NULL DEFAULT 0,
+    dedup_days INTEGER,               -- Overrides the global dedup window (NULL = use setting)
+    dedup_threshold REAL,             -- Overrides the global dedup threshold (NULL = use setting)
+    mcp_servers TEXT                  -- JSON array of MCP servers/tools bound to the topic (NULL = none)
 );
 
 -- Bookmarks for saving cards
@@ -73,8 +97,155 @@ CREATE TABLE IF NOT EXISTS bookmarks (
     UNIQUE(briefing_id, card_index)
 );
 
+-- Read-later queue of saved cards
+CREATE TABLE IF NOT EXISTS read_later (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    briefing_id INTEGER NOT NULL,
+    card_index INTEGER NOT NULL,
+    position INTEGER NOT NULL DEFAULT 0,
+    completed INTEGER NOT NULL DEFAULT 0,
+    completed_at TIMESTAMP,
+    forwarded_to TEXT,                -- External service the card was sent to
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (briefing_id) REFERENCES briefings(id) ON DELETE CASCADE,
+    UNIQUE(briefing_id, card_index)
+);
+
+-- Source citations validated after synthesis (with optional archived text)
+CREATE TABLE IF NOT EXISTS sources (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    briefing_id INTEGER NOT NULL,
+    card_index INTEGER NOT NULL,
+    url TEXT NOT NULL,
+    final_url TEXT,                   -- Set when the URL redirected elsewhere
+    status_code INTEGER,              -- NULL if the request failed outright
+    valid INTEGER NOT NULL DEFAULT 1, -- 0 = broken link
+    error TEXT,
+    archived_text TEXT,               -- Article text snapshot (if archiving enabled)
+    checked_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (briefing_id) REFERENCES briefings(id) ON DELETE CASCADE
+);
+
+-- Content fetched by research tools, reused by offline research runs
+CREATE TABLE IF NOT EXISTS content_cache (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    kind TEXT NOT NULL,               -- 'web' or 'github'
+    cache_key TEXT NOT NULL,          -- URL, or owner/repo/activity_type for GitHub
+    topic TEXT,                       -- Topic the content was fetched for
+    content TEXT NOT NULL,
+    fetched_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE(kind, cache_key)
+);
+
+-- HTTP cache for fetch_webpage, shared by research and chat across runs
+CREATE TABLE IF NOT EXISTS web_cache (
+    url TEXT PRIMARY KEY,
+    content TEXT NOT NULL,            -- Extracted page text as returned by the tool
+    etag TEXT,                        -- Validators for conditional revalidation
+    last_modified TEXT,
+    size INTEGER NOT NULL,            -- Bytes of content, for the size cap
+    fetched_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,   -- Last fetched or revalidated
+    accessed_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP   -- Last served; oldest go first over the cap
+);
+
+-- Cards dropped at save time for falling below the min_relevance setting
+CREATE TABLE IF NOT EXISTS discarded_cards (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    briefing_id INTEGER NOT NULL,     -- Briefing the card was synthesized for
+    title TEXT NOT NULL,
+    topic TEXT,
+    relevance TEXT NOT NULL,
+    card TEXT NOT NULL,               -- JSON of the full card
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (briefing_id) REFERENCES briefings(id) ON DELETE CASCADE
+);
+
+-- Anthropic token usage, attributed to the credential profile that paid for it
+CREATE TABLE IF NOT EXISTS api_usage (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    profile TEXT NOT NULL,            -- Credential profile name ('default' or a named profile)
+    kind TEXT NOT NULL,               -- 'research', 'chat' or 'discovery'
+    briefing_id INTEGER,
+    tokens INTEGER NOT NULL DEFAULT 0,
+    batch_tokens INTEGER NOT NULL DEFAULT 0, -- Part of tokens billed at the Batch API discount
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Topics suggested by discovery from bookmarks, feedback and chat questions
+CREATE TABLE IF NOT EXISTS topic_suggestions (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL UNIQUE COLLATE NOCASE,
+    reason TEXT,                      -- Why the topic was suggested
+    status TEXT NOT NULL DEFAULT 'pending' CHECK (status IN ('pending', 'accepted', 'dismissed')),
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Reminders scheduled from card actions; the app notifies when they come due
+CREATE TABLE IF NOT EXISTS reminders (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    briefing_id INTEGER NOT NULL,
+    card_index INTEGER NOT NULL,
+    card_title TEXT NOT NULL,
+    message TEXT NOT NULL,
+    due_at TEXT NOT NULL,             -- UTC, 'YYYY-MM-DD HH:MM:SS'
+    fired INTEGER NOT NULL DEFAULT 0,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+    -- No foreign key: a reminder outlives cleanup of the briefing it came from
+);
+
+-- Notifications held back to be delivered later (e.g. snoozed from a notification button)
+CREATE TABLE IF NOT EXISTS notification_queue (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    briefing_id INTEGER,              -- Briefing the notification announces, if any
+    title TEXT NOT NULL,
+    body TEXT NOT NULL,
+    deliver_at TEXT NOT NULL,         -- UTC, 'YYYY-MM-DD HH:MM:SS'
+    delivered INTEGER NOT NULL DEFAULT 0,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Novelty of each topic's cards per run, compared with its recent cards (see topic_activity.rs)
+CREATE TABLE IF NOT EXISTS topic_activity (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    briefing_id INTEGER NOT NULL,
+    topic TEXT NOT NULL,
+    card_count INTEGER NOT NULL DEFAULT 0,
+    novelty REAL NOT NULL,            -- 1.0 = all new, 0.0 = repeats or no cards
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (briefing_id) REFERENCES briefings(id) ON DELETE CASCADE
+);
+
+-- Title, topic and summary of each saved card, written when the briefing is
+-- saved so deduplication doesn't re-parse every briefing's cards
+CREATE TABLE IF NOT EXISTS card_fingerprints (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    briefing_id INTEGER NOT NULL,
+    card_index INTEGER NOT NULL,
+    title TEXT NOT NULL,
+    title_hash TEXT NOT NULL,         -- Hash of the normalized title (see dedup::title_hash)
+    topic TEXT NOT NULL,
+    summary TEXT NOT NULL DEFAULT '',
+    relevance INTEGER NOT NULL DEFAULT 1, -- research::relevance_rank of the card (0 = low, 2 = high)
+    embedding BLOB,                   -- Optional vector for semantic matching (NULL until computed)
+    date TEXT NOT NULL,               -- The briefing's date
+    FOREIGN KEY (briefing_id) REFERENCES briefings(id) ON DELETE CASCADE
+);
+
+-- When the user was around: the app opened or focused, or the machine woke
+-- from sleep. Used to time the adaptive research schedule (see smart_schedule.rs)
+CREATE TABLE IF NOT EXISTS activity_log (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    kind TEXT NOT NULL,               -- "app_open" or "wake"
+    occurred_at TEXT NOT NULL         -- Local time, 'YYYY-MM-DD HH:MM:SS'
+);
+
 CREATE INDEX IF NOT EXISTS idx_briefings_date ON briefings(date DESC);
+CREATE INDEX IF NOT EXISTS idx_briefings_model ON briefings(model_used, date DESC);
+CREATE INDEX IF NOT EXISTS idx_card_fingerprints_date_topic ON card_fingerprints(date, topic);
+CREATE INDEX IF NOT EXISTS idx_card_fingerprints_briefing ON card_fingerprints(briefing_id);
+CREATE INDEX IF NOT EXISTS idx_card_fingerprints_topic ON card_fingerprints(topic COLLATE NOCASE, briefing_id);
 CREATE INDEX IF NOT EXISTS idx_feedback_briefing ON feedback(briefing_id);
+CREATE INDEX IF NOT EXISTS idx_activity_log_time ON activity_log(occurred_at);
 -- Note: idx_chat_messages_briefing_card index is created in migration after card_index column is added
 CREATE INDEX IF NOT EXISTS idx_research_logs_briefing ON research_logs(briefing_id);
 CREATE INDEX IF NOT EXISTS idx_research_logs_type ON research_logs(log_type);
@@ -82,3 +253,10 @@ CREATE INDEX IF NOT EXISTS idx_research_logs_error ON research_logs(error_code)
 CREATE INDEX IF NOT EXISTS idx_topics_enabled ON topics(enabled);
 CREATE INDEX IF NOT EXISTS idx_topics_sort_order ON topics(sort_order);
 CREATE INDEX IF NOT EXISTS idx_bookmarks_briefing ON bookmarks(briefing_id);
+CREATE INDEX IF NOT EXISTS idx_sources_briefing ON sources(briefing_id);
+CREATE INDEX IF NOT EXISTS idx_read_later_position ON read_later(completed, position);
+CREATE INDEX IF NOT EXISTS idx_content_cache_topic ON content_cache(topic, fetched_at DESC);
+CREATE INDEX IF NOT EXISTS idx_discarded_cards_briefing ON discarded_cards(briefing_id);
+CREATE INDEX IF NOT EXISTS idx_api_usage_profile ON api_usage(profile, created_at);
+CREATE INDEX IF NOT EXISTS idx_reminders_due ON reminders(fired, due_at);
+CREATE INDEX IF NOT EXISTS idx_topic_activity_briefing ON topic_activity(briefing_id DESC);
@@ -0,0 +1,444 @@
+//! Secure storage for API keys and service credentials.
+//!
+//! Secrets are kept in the OS credential store (macOS Keychain, Windows
+//! Credential Manager, or the Secret Service / libsecret on Linux) under the
+//! service name `claudius`. `.env` in the config directory is still read as
+//! a fallback: a secret found there is moved into the credential store the
+//! first time it is read, and the file is used for writes when no credential
+//! store is available (such as a Linux machine without a Secret Service).
+//! Set `CLAUDIUS_SECRET_STORE=file` to keep everything in `.env`.
+//
+// Note: Some functions are used by CLI but not by Tauri app, so we allow dead_code.
+#![allow(dead_code)]
+
+use crate::config::get_env_file_path;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+use tracing::{debug, info, warn};
+
+/// Service name used for entries in the OS credential store.
+const KEYRING_SERVICE: &str = "claudius";
+
+/// Credentials that `migrate_env_file` moves out of `.env`.
+pub const SECRET_NAMES: &[&str] = &[
+    "ANTHROPIC_API_KEY",
+    "OPENAI_API_KEY",
+    "NOTION_API_KEY",
+    "GITHUB_TOKEN",
+    "POCKET_CONSUMER_KEY",
+    "POCKET_ACCESS_TOKEN",
+    "READWISE_TOKEN",
+    "INSTAPAPER_USERNAME",
+    "INSTAPAPER_PASSWORD",
+    "CLAUDIUS_SERVER_TOKEN",
+    "PROXY_PASSWORD",
+];
+
+/// A place secrets can be stored, keyed by their `.env` variable name.
+pub trait SecretStore: Send + Sync {
+    /// Short name shown to the user (e.g. "keychain").
+    fn name(&self) -> &'static str;
+    fn get(&self, key: &str) -> Result<Option<String>, String>;
+    fn set(&self, key: &str, value: &str) -> Result<(), String>;
+    /// Remove a secret. Removing a missing secret is not an error.
+    fn delete(&self, key: &str) -> Result<(), String>;
+}
+
+// ============================================================================
+// OS credential store
+// ============================================================================
+
+/// Secrets in the platform credential store, via the `keyring` crate.
+pub struct KeyringStore;
+
+impl KeyringStore {
+    fn entry(key: &str) -> Result<keyring::Entry, String> {
+        keyring::Entry::new(KEYRING_SERVICE, key)
+            .map_err(|e| format!("Credential store unavailable: {}", e))
+    }
+
+    /// Whether the credential store can be reached. Always on macOS and
+    /// Windows; on Linux it takes a Secret Service provider (GNOME Keyring,
+    /// KWallet) on the session bus, which headless machines often lack.
+    /// Checked once per process.
+    pub fn available() -> bool {
+        if !cfg!(target_os = "linux") {
+            return true;
+        }
+        static AVAILABLE: OnceLock<bool> = OnceLock::new();
+        *AVAILABLE.get_or_init(|| {
+            let probe =
+                Self::entry("ANTHROPIC_API_KEY").and_then(|entry| match entry.get_password() {
+                    Ok(_) | Err(keyring::Error::NoEntry) => Ok(()),
+                    Err(e) => Err(e.to_string()),
+                });
+            match probe {
+                Ok(()) => true,
+                Err(e) => {
+                    info!(
+                        "No Secret Service available, keeping secrets in .env: {}",
+                        e
+                    );
+                    false
+                }
+            }
+        })
+    }
+}
+
+impl SecretStore for KeyringStore {
+    fn name(&self) -> &'static str {
+        if cfg!(target_os = "macos") {
+            "keychain"
+        } else if cfg!(target_os = "windows") {
+            "credential-manager"
+        } else {
+            "secret-service"
+        }
+    }
+
+    fn get(&self, key: &str) -> Result<Option<String>, String> {
+        match Self::entry(key)?.get_password() {
+            Ok(value) => Ok(Some(value)),
+            Err(keyring::Error::NoEntry) => Ok(None),
+            Err(e) => Err(format!(
+                "Failed to read {} from {}: {}",
+                key,
+                self.name(),
+                e
+            )),
+        }
+    }
+
+    fn set(&self, key: &str, value: &str) -> Result<(), String> {
+        Self::entry(key)?
+            .set_password(value)
+            .map_err(|e| format!("Failed to save {} to {}: {}", key, self.name(), e))
+    }
+
+    fn delete(&self, key: &str) -> Result<(), String> {
+        match Self::entry(key)?.delete_credential() {
+            Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+            Err(e) => Err(format!(
+                "Failed to delete {} from {}: {}",
+                key,
+                self.name(),
+                e
+            )),
+        }
+    }
+}
+
+// ============================================================================
+// .env file
+// ============================================================================
+
+/// Secrets as `NAME=value` lines in a plaintext env file (owner-only permissions).
+pub struct EnvFileStore {
+    path: PathBuf,
+}
+
+impl EnvFileStore {
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    fn write_lines(&self, lines: &[String]) -> Result<(), String> {
+        if lines.is_empty() {
+            // If no other content, delete the file
+            let _ = std::fs::remove_file(&self.path);
+            return Ok(());
+        }
+
+        let content = lines.join("\n") + "\n";
+        std::fs::write(&self.path, content)
+            .map_err(|e| format!("Failed to write .env file: {}", e))?;
+
+        // Set restrictive permissions (owner read/write only)
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let permissions = std::fs::Permissions::from_mode(0o600);
+            let _ = std::fs::set_permissions(&self.path, permissions);
+        }
+
+        Ok(())
+    }
+
+    fn read_lines(&self) -> Vec<String> {
+        std::fs::read_to_string(&self.path)
+            .map(|content| content.lines().map(str::to_string).collect())
+            .unwrap_or_default()
+    }
+}
+
+impl SecretStore for EnvFileStore {
+    fn name(&self) -> &'static str {
+        "env-file"
+    }
+
+    fn get(&self, key: &str) -> Result<Option<String>, String> {
+        let prefix = format!("{}=", key);
+        Ok(self.read_lines().iter().find_map(|line| {
+            let value = line.trim().strip_prefix(&prefix)?.trim();
+            // Remove quotes if present
+            let value = value.trim_matches('"').trim_matches('\'');
+            (!value.is_empty()).then(|| value.to_string())
+        }))
+    }
+
+    fn set(&self, key: &str, value: &str) -> Result<(), String> {
+        let prefix = format!("{}=", key);
+        let mut lines = self.read_lines();
+        let entry = format!("{}{}", prefix, value);
+
+        match lines
+            .iter_mut()
+            .find(|line| line.trim().starts_with(&prefix))
+        {
+            Some(line) => *line = entry,
+            None => lines.push(entry),
+        }
+
+        self.write_lines(&lines)
+    }
+
+    fn delete(&self, key: &str) -> Result<(), String> {
+        if !self.path.exists() {
+            return Ok(());
+        }
+
+        let prefix = format!("{}=", key);
+        let lines = self.read_lines();
+        let kept: Vec<String> = lines
+            .iter()
+            .filter(|line| !line.trim().starts_with(&prefix))
+            .cloned()
+            .collect();
+
+        if kept.len() == lines.len() {
+            return Ok(());
+        }
+        self.write_lines(&kept)
+    }
+}
+
+// ============================================================================
+// Combined store with .env fallback
+// ============================================================================
+
+/// The credential store backed by `.env` for reads of not-yet-migrated
+/// secrets and for writes when the credential store fails.
+pub struct Secrets {
+    primary: Option<Box<dyn SecretStore>>,
+    env_file: EnvFileStore,
+}
+
+impl Secrets {
+    pub fn new(primary: Option<Box<dyn SecretStore>>, env_file: EnvFileStore) -> Self {
+        Self { primary, env_file }
+    }
+
+    /// Name of the store new secrets are written to.
+    pub fn backend_name(&self) -> &'static str {
+        self.primary
+            .as_ref()
+            .map(|store| store.name())
+            .unwrap_or(self.env_file.name())
+    }
+
+    /// Read a secret, moving it out of `.env` if it is only found there.
+    pub fn get(&self, key: &str) -> Option<String> {
+        if let Some(primary) = &self.primary {
+            match primary.get(key) {
+                Ok(Some(value)) => return Some(value),
+                Ok(None) => {}
+                Err(e) => debug!("{}", e),
+            }
+        }
+
+        let value = self.env_file.get(key).ok().flatten()?;
+
+        if let Some(primary) = &self.primary {
+            match primary.set(key, &value) {
+                Ok(()) => {
+                    info!("Moved {} from .env to {}", key, primary.name());
+                    if let Err(e) = self.env_file.delete(key) {
+                        warn!("Failed to remove {} from .env: {}", key, e);
+                    }
+                }
+                Err(e) => debug!("Keeping {} in .env: {}", key, e),
+            }
+        }
+
+        Some(value)
+    }
+
+    /// Save a secret, falling back to `.env` if the credential store fails.
+    pub fn set(&self, key: &str, value: &str) -> Result<(), String> {
+        if let Some(primary) = &self.primary {
+            match primary.set(key, value) {
+                Ok(()) => return self.env_file.delete(key),
+                Err(e) => warn!("{}; saving to .env instead", e),
+            }
+        }
+
+        if let Some(dir) = self.env_file.path.parent() {
+            std::fs::create_dir_all(dir)
+                .map_err(|e| format!("Failed to create config directory: {}", e))?;
+        }
+        self.env_file.set(key, value)
+    }
+
+    /// Remove a secret from both the credential store and `.env`.
+    pub fn delete(&self, key: &str) -> Result<(), String> {
+        if let Some(primary) = &self.primary {
+            primary.delete(key)?;
+        }
+        self.env_file.delete(key)
+    }
+
+    /// Move every known secret from `.env` into the credential store.
+    /// Returns the names that were moved.
+    pub fn migrate_env_file(&self) -> Result<Vec<String>, String> {
+        let primary = self
+            .primary
+            .as_ref()
+            .ok_or("No credential store is available (CLAUDIUS_SECRET_STORE=file, or no Secret Service running)")?;
+
+        let mut moved = Vec::new();
+        for key in SECRET_NAMES {
+            if let Some(value) = self.env_file.get(key)? {
+                primary.set(key, &value)?;
+                self.env_file.delete(key)?;
+                moved.push(key.to_string());
+            }
+        }
+
+        Ok(moved)
+    }
+}
+
+/// The secret store for the config directory, using the OS credential
+/// store unless `CLAUDIUS_SECRET_STORE=file` is set or (on Linux) no Secret
+/// Service is running.
+pub fn secrets() -> Secrets {
+    let use_file = std::env::var("CLAUDIUS_SECRET_STORE")
+        .map(|v| v.eq_ignore_ascii_case("file"))
+        .unwrap_or(false);
+    let primary: Option<Box<dyn SecretStore>> = if use_file || !KeyringStore::available() {
+        None
+    } else {
+        Some(Box::new(KeyringStore))
+    };
+
+    Secrets::new(primary, EnvFileStore::new(get_env_file_path()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+
+    /// In-memory stand-in for the OS credential store.
+    #[derive(Default)]
+    struct MemoryStore(Mutex<HashMap<String, String>>);
+
+    impl SecretStore for MemoryStore {
+        fn name(&self) -> &'static str {
+            "memory"
+        }
+        fn get(&self, key: &str) -> Result<Option<String>, String> {
+            Ok(self.0.lock().unwrap().get(key).cloned())
+        }
+        fn set(&self, key: &str, value: &str) -> Result<(), String> {
+            self.0
+                .lock()
+                .unwrap()
+                .insert(key.to_string(), value.to_string());
+            Ok(())
+        }
+        fn delete(&self, key: &str) -> Result<(), String> {
+            self.0.lock().unwrap().remove(key);
+            Ok(())
+        }
+    }
+
+    fn temp_env_file(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("claudius-secrets-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir.join(".env")
+    }
+
+    #[test]
+    fn test_env_file_store_roundtrip() {
+        let path = temp_env_file("roundtrip");
+        std::fs::write(&path, "OTHER=1\nOPENAI_API_KEY=\"sk-old\"\n").unwrap();
+        let store = EnvFileStore::new(path.clone());
+
+        assert_eq!(
+            store.get("OPENAI_API_KEY").unwrap(),
+            Some("sk-old".to_string())
+        );
+        store.set("OPENAI_API_KEY", "sk-new").unwrap();
+        store.set("ANTHROPIC_API_KEY", "sk-ant-1").unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "OTHER=1\nOPENAI_API_KEY=sk-new\nANTHROPIC_API_KEY=sk-ant-1\n"
+        );
+
+        store.delete("OPENAI_API_KEY").unwrap();
+        store.delete("OTHER").unwrap();
+        store.delete("ANTHROPIC_API_KEY").unwrap();
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_read_moves_secret_out_of_env_file() {
+        let path = temp_env_file("transparent");
+        std::fs::write(&path, "ANTHROPIC_API_KEY=sk-ant-1\nOTHER=1\n").unwrap();
+        let secrets = Secrets::new(
+            Some(Box::new(MemoryStore::default())),
+            EnvFileStore::new(path.clone()),
+        );
+
+        assert_eq!(
+            secrets.get("ANTHROPIC_API_KEY").as_deref(),
+            Some("sk-ant-1")
+        );
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "OTHER=1\n");
+        assert_eq!(
+            secrets.get("ANTHROPIC_API_KEY").as_deref(),
+            Some("sk-ant-1")
+        );
+
+        secrets.delete("ANTHROPIC_API_KEY").unwrap();
+        assert_eq!(secrets.get("ANTHROPIC_API_KEY"), None);
+    }
+
+    #[test]
+    fn test_migrate_env_file() {
+        let path = temp_env_file("migrate");
+        std::fs::write(
+            &path,
+            "ANTHROPIC_API_KEY=sk-ant-1\nREADWISE_TOKEN=rw\nCUSTOM=keep\n",
+        )
+        .unwrap();
+        let secrets = Secrets::new(
+            Some(Box::new(MemoryStore::default())),
+            EnvFileStore::new(path.clone()),
+        );
+
+        let moved = secrets.migrate_env_file().unwrap();
+        assert_eq!(moved, vec!["ANTHROPIC_API_KEY", "READWISE_TOKEN"]);
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "CUSTOM=keep\n");
+        assert_eq!(secrets.get("READWISE_TOKEN").as_deref(), Some("rw"));
+
+        let file_only = Secrets::new(None, EnvFileStore::new(path));
+        assert!(file_only.migrate_env_file().is_err());
+        assert_eq!(file_only.backend_name(), "env-file");
+    }
+}
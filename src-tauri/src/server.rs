@@ -0,0 +1,666 @@
+//! HTTP API for `claudius serve`.
+//!
+//! Exposes topics, briefings and research runs as a small JSON API so home
+//! automation setups and other apps can integrate without the desktop app.
+//! Every `/api` request except `/api/health` needs an
+//! `Authorization: Bearer <token>` header.
+//!
+//! Research runs are started as a `claudius research now --json` child
+//! process, so they go through exactly the same pipeline as the CLI. Progress
+//! is streamed from the research log as server-sent events on
+//! `/api/research/events`, together with `research` lifecycle events.
+//!
+//! `/feed.xml` serves the Atom feed of recent briefings and `/calendar.ics`
+//! the schedule calendar. Feed readers and calendar apps can't always set
+//! headers, so both also accept the token as `?token=`.
+
+use axum::extract::{Path, Query, Request, State};
+use axum::http::{header, HeaderMap, StatusCode};
+use axum::middleware::{self, Next};
+use axum::response::sse::{Event, KeepAlive, Sse};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use chrono::Utc;
+use futures_util::stream::{self, Stream, StreamExt};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::convert::Infallible;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use tokio::sync::broadcast;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::db::{self, Topic};
+use crate::deep_link;
+use crate::log_sink::LogTail;
+use crate::research::BriefingCard;
+use crate::secret_store;
+
+/// Secret name the API token is stored under.
+pub const TOKEN_SECRET: &str = "CLAUDIUS_SERVER_TOKEN";
+
+/// Default port for `claudius serve`.
+pub const DEFAULT_PORT: u16 = 7878;
+
+/// How often the research log is checked for new records to stream.
+const LOG_POLL_MS: u64 = 500;
+
+/// Options for [`serve`].
+pub struct ServeOptions {
+    pub host: String,
+    pub port: u16,
+    pub token: String,
+    /// Binary used to run research, normally the running `claudius` CLI.
+    pub cli_path: PathBuf,
+    /// Profile passed to research runs with `--profile`.
+    pub profile: String,
+}
+
+/// Outcome of the most recent research run started by the server.
+#[derive(Debug, Clone, Serialize)]
+struct LastRun {
+    finished_at: String,
+    success: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    result: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+struct RunStatus {
+    running: bool,
+    topic: Option<String>,
+    started_at: Option<String>,
+    last_run: Option<LastRun>,
+}
+
+/// A server-sent event: `event` is the SSE event name, `data` is JSON.
+#[derive(Debug, Clone)]
+struct ServerEvent {
+    event: &'static str,
+    data: String,
+}
+
+#[derive(Clone)]
+struct AppState {
+    token: Arc<String>,
+    cli_path: Arc<PathBuf>,
+    profile: Arc<String>,
+    run: Arc<Mutex<RunStatus>>,
+    events: broadcast::Sender<ServerEvent>,
+}
+
+impl AppState {
+    fn status(&self) -> RunStatus {
+        self.run.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    fn emit(&self, event: &'static str, data: Value) {
+        // No subscribers is not an error
+        let _ = self.events.send(ServerEvent {
+            event,
+            data: data.to_string(),
+        });
+    }
+}
+
+/// Error returned from API handlers as `{"error": "..."}`.
+struct ApiError(StatusCode, String);
+
+impl ApiError {
+    fn not_found(message: impl Into<String>) -> Self {
+        Self(StatusCode::NOT_FOUND, message.into())
+    }
+
+    fn bad_request(message: impl Into<String>) -> Self {
+        Self(StatusCode::BAD_REQUEST, message.into())
+    }
+}
+
+impl From<String> for ApiError {
+    fn from(message: String) -> Self {
+        Self(StatusCode::INTERNAL_SERVER_ERROR, message)
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (self.0, Json(json!({ "error": self.1 }))).into_response()
+    }
+}
+
+type ApiResult<T> = Result<T, ApiError>;
+
+fn connection() -> ApiResult<rusqlite::Connection> {
+    db::get_connection().map_err(|e| ApiError::from(format!("Database error: {}", e)))
+}
+
+// ============================================================================
+// Authentication
+// ============================================================================
+
+/// Return the API token: the explicit one if given, otherwise the stored
+/// token, generating and storing a new one on first use. The flag is true when
+/// a new token was generated.
+pub fn resolve_token(explicit: Option<String>) -> Result<(String, bool), String> {
+    if let Some(token) = explicit.filter(|t| !t.trim().is_empty()) {
+        return Ok((token.trim().to_string(), false));
+    }
+
+    let secrets = secret_store::secrets();
+    if let Some(token) = secrets.get(TOKEN_SECRET) {
+        return Ok((token, false));
+    }
+
+    let token = Uuid::new_v4().simple().to_string();
+    secrets.set(TOKEN_SECRET, &token)?;
+    Ok((token, true))
+}
+
+/// Extract the token from an `Authorization: Bearer <token>` header value.
+fn bearer_token(header_value: &str) -> Option<&str> {
+    let (scheme, token) = header_value.trim().split_once(' ')?;
+    scheme
+        .eq_ignore_ascii_case("bearer")
+        .then(|| token.trim())
+        .filter(|t| !t.is_empty())
+}
+
+/// Compare tokens without exiting early on the first differing byte.
+fn token_matches(given: &str, expected: &str) -> bool {
+    given.len() == expected.len()
+        && given
+            .bytes()
+            .zip(expected.bytes())
+            .fold(0u8, |acc, (a, b)| acc | (a ^ b))
+            == 0
+}
+
+async fn require_token(State(state): State<AppState>, request: Request, next: Next) -> Response {
+    let authorized = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(bearer_token)
+        .is_some_and(|token| token_matches(token, &state.token));
+
+    if !authorized {
+        return ApiError(
+            StatusCode::UNAUTHORIZED,
+            "Missing or invalid bearer token".to_string(),
+        )
+        .into_response();
+    }
+    next.run(request).await
+}
+
+// ============================================================================
+// Topics
+// ============================================================================
+
+#[derive(Debug, Deserialize)]
+struct NewTopic {
+    name: String,
+    description: Option<String>,
+    #[serde(default)]
+    preferred_sources: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TopicUpdate {
+    name: Option<String>,
+    description: Option<String>,
+    enabled: Option<bool>,
+    preferred_sources: Option<Vec<String>>,
+}
+
+async fn list_topics() -> ApiResult<Json<Vec<Topic>>> {
+    let conn = connection()?;
+    Ok(Json(db::get_all_topics(&conn)?))
+}
+
+async fn get_topic(Path(id): Path<String>) -> ApiResult<Json<Topic>> {
+    let conn = connection()?;
+    db::get_topic_by_id(&conn, &id)?
+        .map(Json)
+        .ok_or_else(|| ApiError::not_found(format!("Topic {} not found", id)))
+}
+
+async fn create_topic(Json(new): Json<NewTopic>) -> ApiResult<(StatusCode, Json<Topic>)> {
+    let name = new.name.trim();
+    if name.is_empty() {
+        return Err(ApiError::bad_request("Topic name is required"));
+    }
+
+    let conn = connection()?;
+    if db::topic_name_exists(&conn, name)? {
+        return Err(ApiError(
+            StatusCode::CONFLICT,
+            format!("Topic '{}' already exists", name),
+        ));
+    }
+
+    let now = Utc::now().to_rfc3339();
+    let topic = Topic {
+        id: Uuid::new_v4().to_string(),
+        name: name.to_string(),
+        description: new.description,
+        preferred_sources: new.preferred_sources,
+        enabled: true,
+        snoozed_until: None,
+        skip_next_run: false,
+        dedup_days: None,
+        dedup_threshold: None,
+        mcp_servers: Vec::new(),
+        created_at: now.clone(),
+        updated_at: now,
+    };
+    let sort_order = db::get_next_sort_order(&conn)?;
+    db::insert_topic(&conn, &topic, sort_order)?;
+
+    Ok((StatusCode::CREATED, Json(topic)))
+}
+
+async fn update_topic(
+    Path(id): Path<String>,
+    Json(update): Json<TopicUpdate>,
+) -> ApiResult<Json<Topic>> {
+    let conn = connection()?;
+    let mut topic = db::get_topic_by_id(&conn, &id)?
+        .ok_or_else(|| ApiError::not_found(format!("Topic {} not found", id)))?;
+
+    if let Some(name) = update.name {
+        let name = name.trim().to_string();
+        if name.is_empty() {
+            return Err(ApiError::bad_request("Topic name is required"));
+        }
+        if !name.eq_ignore_ascii_case(&topic.name) && db::topic_name_exists(&conn, &name)? {
+            return Err(ApiError(
+                StatusCode::CONFLICT,
+                format!("Topic '{}' already exists", name),
+            ));
+        }
+        topic.name = name;
+    }
+    if let Some(description) = update.description {
+        topic.description = Some(description).filter(|d| !d.is_empty());
+    }
+    if let Some(enabled) = update.enabled {
+        topic.enabled = enabled;
+    }
+    if let Some(sources) = update.preferred_sources {
+        topic.preferred_sources = sources;
+    }
+    topic.updated_at = Utc::now().to_rfc3339();
+    db::update_topic(&conn, &topic)?;
+
+    Ok(Json(topic))
+}
+
+async fn delete_topic(Path(id): Path<String>) -> ApiResult<StatusCode> {
+    let conn = connection()?;
+    if db::get_topic_by_id(&conn, &id)?.is_none() {
+        return Err(ApiError::not_found(format!("Topic {} not found", id)));
+    }
+    db::delete_topic(&conn, &id)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// ============================================================================
+// Briefings
+// ============================================================================
+
+#[derive(Debug, Deserialize)]
+struct ListQuery {
+    limit: Option<i32>,
+}
+
+async fn list_briefings(Query(query): Query<ListQuery>) -> ApiResult<Json<Vec<Value>>> {
+    let conn = connection()?;
+    let limit = query.limit.unwrap_or(10).clamp(1, 100);
+    let briefings = db::get_briefings(&conn, limit)?
+        .into_iter()
+        .map(|b| {
+            let cards: Vec<BriefingCard> = serde_json::from_str(&b.cards).unwrap_or_default();
+            json!({
+                "id": b.id,
+                "url": deep_link::briefing_url(b.id),
+                "date": b.date,
+                "title": b.title,
+                "card_count": cards.len(),
+                "model_used": b.model_used,
+                "research_time_ms": b.research_time_ms,
+                "total_tokens": b.total_tokens,
+            })
+        })
+        .collect();
+    Ok(Json(briefings))
+}
+
+async fn get_briefing(Path(id): Path<i64>) -> ApiResult<Json<Value>> {
+    let conn = connection()?;
+    let briefing = db::get_briefing(&conn, id)
+        .map_err(|_| ApiError::not_found(format!("Briefing {} not found", id)))?;
+    let cards: Vec<BriefingCard> = serde_json::from_str(&briefing.cards)
+        .map_err(|e| format!("Failed to parse cards: {}", e))?;
+
+    Ok(Json(json!({
+        "id": briefing.id,
+        "url": deep_link::briefing_url(briefing.id),
+        "date": briefing.date,
+        "title": briefing.title,
+        "cards": cards,
+        "model_used": briefing.model_used,
+        "research_time_ms": briefing.research_time_ms,
+        "total_tokens": briefing.total_tokens,
+    })))
+}
+
+// ============================================================================
+// Research
+// ============================================================================
+
+#[derive(Debug, Default, Deserialize)]
+struct ResearchRequest {
+    topic: Option<String>,
+}
+
+/// Pull the final JSON object out of `research now --json` output, which may
+/// be preceded by log lines.
+fn parse_run_output(stdout: &str) -> Option<Value> {
+    let starts: Vec<usize> = stdout
+        .match_indices('\n')
+        .map(|(i, _)| i + 1)
+        .chain(std::iter::once(0))
+        .filter(|&i| stdout[i..].starts_with('{'))
+        .collect();
+    starts
+        .into_iter()
+        .filter_map(|i| serde_json::from_str::<Value>(stdout[i..].trim()).ok())
+        .next()
+}
+
+async fn research_status(State(state): State<AppState>) -> Json<Value> {
+    Json(json!(state.status()))
+}
+
+async fn start_research(
+    State(state): State<AppState>,
+    body: Option<Json<ResearchRequest>>,
+) -> ApiResult<(StatusCode, Json<Value>)> {
+    let topic = body
+        .and_then(|Json(b)| b.topic)
+        .filter(|t| !t.trim().is_empty());
+
+    {
+        let mut run = state.run.lock().unwrap_or_else(|e| e.into_inner());
+        if run.running {
+            return Err(ApiError(
+                StatusCode::CONFLICT,
+                "Research is already running".to_string(),
+            ));
+        }
+        run.running = true;
+        run.topic = topic.clone();
+        run.started_at = Some(Utc::now().to_rfc3339());
+    }
+
+    info!("Starting research run via API (topic: {:?})", topic);
+    state.emit("research", json!({ "status": "started", "topic": topic }));
+    tokio::spawn(run_research(state.clone(), topic.clone()));
+
+    Ok((
+        StatusCode::ACCEPTED,
+        Json(json!({ "status": "started", "topic": topic })),
+    ))
+}
+
+async fn run_research(state: AppState, topic: Option<String>) {
+    let mut command = tokio::process::Command::new(state.cli_path.as_path());
+    command
+        .args(["--json", "--profile", state.profile.as_str()])
+        .args(crate::config::dir_args())
+        .args(["research", "now"])
+        .env("NO_COLOR", "1")
+        .stdin(std::process::Stdio::null());
+    if let Some(topic) = &topic {
+        command.args(["--topic", topic]);
+    }
+
+    let last_run = match command.output().await {
+        Ok(output) if output.status.success() => {
+            let stdout = String::from_utf8_lossy(&output.stdout);
+            LastRun {
+                finished_at: Utc::now().to_rfc3339(),
+                success: true,
+                result: parse_run_output(&stdout),
+                error: None,
+            }
+        }
+        Ok(output) => {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            let error = stderr
+                .lines()
+                .rev()
+                .find(|l| !l.trim().is_empty())
+                .map(|l| l.trim().trim_start_matches("Error:").trim().to_string())
+                .unwrap_or_else(|| format!("Research exited with {}", output.status));
+            LastRun {
+                finished_at: Utc::now().to_rfc3339(),
+                success: false,
+                result: None,
+                error: Some(error),
+            }
+        }
+        Err(e) => LastRun {
+            finished_at: Utc::now().to_rfc3339(),
+            success: false,
+            result: None,
+            error: Some(format!("Failed to start research: {}", e)),
+        },
+    };
+
+    if let Some(error) = &last_run.error {
+        warn!("API research run failed: {}", error);
+    }
+    state.emit(
+        "research",
+        json!({
+            "status": if last_run.success { "completed" } else { "failed" },
+            "topic": topic,
+            "result": last_run.result,
+            "error": last_run.error,
+        }),
+    );
+
+    let mut run = state.run.lock().unwrap_or_else(|e| e.into_inner());
+    run.running = false;
+    run.topic = None;
+    run.started_at = None;
+    run.last_run = Some(last_run);
+}
+
+async fn research_events(
+    State(state): State<AppState>,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    // Start with the current status so clients don't need a separate request
+    let status = Event::default()
+        .event("status")
+        .data(json!(state.status()).to_string());
+
+    let updates = stream::unfold(state.events.subscribe(), |mut rx| async move {
+        loop {
+            match rx.recv().await {
+                Ok(event) => {
+                    let sse = Event::default().event(event.event).data(event.data);
+                    return Some((sse, rx));
+                }
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    warn!("SSE client fell behind, skipped {} events", skipped);
+                }
+                Err(broadcast::error::RecvError::Closed) => return None,
+            }
+        }
+    });
+
+    Sse::new(stream::once(async { status }).chain(updates).map(Ok)).keep_alive(KeepAlive::default())
+}
+
+/// Forward new research log records to SSE subscribers as `log` events.
+async fn stream_research_log(state: AppState) {
+    let mut tail = LogTail::research_from_end();
+    loop {
+        tokio::time::sleep(std::time::Duration::from_millis(LOG_POLL_MS)).await;
+        match tail.poll() {
+            Ok(lines) => {
+                for line in lines {
+                    let _ = state.events.send(ServerEvent {
+                        event: "log",
+                        data: line,
+                    });
+                }
+            }
+            Err(e) => warn!("Failed to read research log: {}", e),
+        }
+    }
+}
+
+// ============================================================================
+// Server
+// ============================================================================
+
+#[derive(Debug, Deserialize)]
+struct FeedQuery {
+    token: Option<String>,
+}
+
+/// Check the token of a feed request, from `?token=` or the header.
+fn authorize_feed(state: &AppState, query: &FeedQuery, headers: &HeaderMap) -> ApiResult<()> {
+    let authorized = query
+        .token
+        .as_deref()
+        .or_else(|| {
+            headers
+                .get(header::AUTHORIZATION)
+                .and_then(|v| v.to_str().ok())
+                .and_then(bearer_token)
+        })
+        .is_some_and(|token| token_matches(token, &state.token));
+    if !authorized {
+        return Err(ApiError(
+            StatusCode::UNAUTHORIZED,
+            "Missing or invalid token".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+async fn atom_feed(
+    State(state): State<AppState>,
+    Query(query): Query<FeedQuery>,
+    headers: HeaderMap,
+) -> ApiResult<Response> {
+    authorize_feed(&state, &query, &headers)?;
+    let xml = crate::feed::build_feed(&connection()?)?;
+    Ok((
+        [(header::CONTENT_TYPE, "application/atom+xml; charset=utf-8")],
+        xml,
+    )
+        .into_response())
+}
+
+async fn schedule_calendar(
+    State(state): State<AppState>,
+    Query(query): Query<FeedQuery>,
+    headers: HeaderMap,
+) -> ApiResult<Response> {
+    authorize_feed(&state, &query, &headers)?;
+    let schedule = crate::smart_schedule::effective_schedule(
+        &crate::config::read_settings().unwrap_or_default(),
+    );
+    let ics = crate::schedule_calendar::build_calendar(&connection()?, &schedule)?;
+    Ok((
+        [(header::CONTENT_TYPE, "text/calendar; charset=utf-8")],
+        ics,
+    )
+        .into_response())
+}
+
+async fn health() -> Json<Value> {
+    Json(json!({ "status": "ok", "version": env!("CARGO_PKG_VERSION") }))
+}
+
+fn router(state: AppState) -> Router {
+    let api = Router::new()
+        .route("/topics", get(list_topics).post(create_topic))
+        .route(
+            "/topics/:id",
+            get(get_topic).patch(update_topic).delete(delete_topic),
+        )
+        .route("/briefings", get(list_briefings))
+        .route("/briefings/:id", get(get_briefing))
+        .route("/research", post(start_research))
+        .route("/research/status", get(research_status))
+        .route("/research/events", get(research_events))
+        .route_layer(middleware::from_fn_with_state(state.clone(), require_token));
+
+    Router::new()
+        .route("/api/health", get(health))
+        .route("/feed.xml", get(atom_feed))
+        .route("/calendar.ics", get(schedule_calendar))
+        .nest("/api", api)
+        .with_state(state)
+}
+
+/// Run the API server until the process is stopped.
+pub async fn serve(options: ServeOptions) -> Result<(), String> {
+    let (events, _) = broadcast::channel(256);
+    let state = AppState {
+        token: Arc::new(options.token),
+        cli_path: Arc::new(options.cli_path),
+        profile: Arc::new(options.profile),
+        run: Arc::new(Mutex::new(RunStatus::default())),
+        events,
+    };
+
+    let address = format!("{}:{}", options.host, options.port);
+    let listener = tokio::net::TcpListener::bind(&address)
+        .await
+        .map_err(|e| format!("Failed to listen on {}: {}", address, e))?;
+    info!("Claudius API listening on http://{}", address);
+
+    tokio::spawn(stream_research_log(state.clone()));
+    axum::serve(listener, router(state))
+        .await
+        .map_err(|e| format!("Server error: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bearer_token_auth() {
+        assert_eq!(bearer_token("Bearer abc123"), Some("abc123"));
+        assert_eq!(bearer_token("bearer  abc123 "), Some("abc123"));
+        assert_eq!(bearer_token("Basic abc123"), None);
+        assert_eq!(bearer_token("Bearer "), None);
+
+        assert!(token_matches("abc123", "abc123"));
+        assert!(!token_matches("abc124", "abc123"));
+        assert!(!token_matches("abc", "abc123"));
+    }
+
+    #[test]
+    fn test_parse_run_output() {
+        let stdout =
+            "2026-01-01 INFO Researching AI\n{\n  \"status\": \"completed\",\n  \"cards\": 3\n}\n";
+        let result = parse_run_output(stdout).unwrap();
+        assert_eq!(result["status"], "completed");
+        assert_eq!(result["cards"], 3);
+        assert!(parse_run_output("no json here").is_none());
+    }
+}
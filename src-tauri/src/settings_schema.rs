@@ -0,0 +1,465 @@
+//! Validation of `preferences.json` settings.
+//!
+//! Serde only checks that each setting has the right JSON type. This module
+//! checks the values: known model IDs, valid cron schedules, enum-like
+//! strings and numeric ranges. `claudius config set` and the app's
+//! `update_settings` command both validate the whole settings object before
+//! writing it, so a bad value is rejected with a message naming the key
+//! instead of surfacing later as a failed research run.
+
+use crate::agent_loop::{validate_header, ThinkingConfig};
+use crate::config::{ResearchSettings, DEFAULT_PROFILE};
+use crate::meta_briefing::META_BRIEFING_SCHEDULES;
+use crate::models::{self, ModelCheck, ModelInfo};
+use crate::read_later::ReadLaterService;
+use crate::research::{
+    parse_briefing_format, parse_recency_window, BRIEFING_FORMATS, RECENCY_WINDOWS,
+    RELEVANCE_LEVELS, RESEARCH_DEPTHS,
+};
+use crate::research_mode;
+
+/// Narration engines.
+pub const TTS_ENGINES: [&str; 2] = ["openai", "local"];
+
+/// What the quick research shortcut does with the clipboard.
+pub const QUICK_RESEARCH_MODES: [&str; 2] = ["research", "ask"];
+
+/// Inclusive ranges of numeric settings.
+pub const MAX_SOURCES_RANGE: (i32, i32) = (1, 50);
+pub const DEDUP_DAYS_RANGE: (i32, i32) = (0, 365);
+pub const DEDUP_CONTEXT_TOKENS_RANGE: (u32, u32) = (0, 50_000);
+pub const RETENTION_DAYS_RANGE: (i32, i32) = (1, 3650);
+pub const LOG_RETENTION_DAYS_RANGE: (u32, u32) = (1, 365);
+pub const TOPIC_TIMEOUT_MINUTES_RANGE: (u32, u32) = (1, 120);
+pub const MCP_RESULT_MAX_CHARS_RANGE: (usize, usize) = (1_000, 500_000);
+pub const TOPIC_DISCOVERY_DAYS_RANGE: (u32, u32) = (0, 365);
+pub const NOTIFICATION_SNOOZE_HOURS_RANGE: (u32, u32) = (1, 168);
+pub const BATCH_DEADLINE_MINUTES_RANGE: (u32, u32) = (1, 24 * 60);
+pub const SOURCE_RECENCY_DAYS_RANGE: (u32, u32) = (0, 3650);
+pub const CRAWL_DELAY_MS_RANGE: (u64, u64) = (0, 60_000);
+
+fn check_range<T: PartialOrd + std::fmt::Display>(
+    errors: &mut Vec<String>,
+    key: &str,
+    value: T,
+    (min, max): (T, T),
+) {
+    if value < min || value > max {
+        errors.push(format!(
+            "{} must be between {} and {} (got {})",
+            key, min, max, value
+        ));
+    }
+}
+
+fn check_choice(errors: &mut Vec<String>, key: &str, value: &str, allowed: &[&str]) {
+    if !allowed.contains(&value) {
+        errors.push(format!(
+            "{} must be one of {} (got '{}')",
+            key,
+            allowed.join(", "),
+            value
+        ));
+    }
+}
+
+/// Check every setting against the schema, using `known_models` for the
+/// model ID. Returns all problems found, one message per setting.
+///
+/// As with [`models::check_model`], an empty `known_models` (no list fetched
+/// yet) accepts any model ID, and deprecated models are allowed.
+pub fn validate_settings_with(
+    settings: &ResearchSettings,
+    known_models: &[ModelInfo],
+) -> Result<(), Vec<String>> {
+    let mut errors = Vec::new();
+
+    // Gateways and compatible providers name their own models
+    let official_api = settings
+        .api_base_url
+        .as_deref()
+        .is_none_or(|url| url.trim().is_empty());
+    if settings.model.trim().is_empty() {
+        errors.push("model cannot be empty".to_string());
+    } else if official_api {
+        let check = models::check_model(&settings.model, known_models);
+        if let (ModelCheck::Unknown(_), Some(msg)) = (&check, check.message(&settings.model)) {
+            errors.push(format!(
+                "model: {} (refresh the list with: claudius config models --refresh)",
+                msg
+            ));
+        }
+    }
+
+    check_choice(
+        &mut errors,
+        "research_depth",
+        &settings.research_depth,
+        &RESEARCH_DEPTHS,
+    );
+    check_choice(
+        &mut errors,
+        "research_mode",
+        &settings.research_mode,
+        &research_mode::names(),
+    );
+    check_choice(
+        &mut errors,
+        "min_relevance",
+        &settings.min_relevance,
+        &RELEVANCE_LEVELS,
+    );
+    check_choice(
+        &mut errors,
+        "briefing_format",
+        &settings.briefing_format,
+        &BRIEFING_FORMATS,
+    );
+    check_choice(
+        &mut errors,
+        "recency_window",
+        &settings.recency_window,
+        &RECENCY_WINDOWS,
+    );
+    check_choice(
+        &mut errors,
+        "meta_briefings",
+        &settings.meta_briefings,
+        &META_BRIEFING_SCHEDULES,
+    );
+    check_choice(
+        &mut errors,
+        "image_style",
+        &settings.image_style,
+        &crate::image_gen::image_style_names(),
+    );
+    check_choice(
+        &mut errors,
+        "tts_engine",
+        &settings.tts_engine,
+        &TTS_ENGINES,
+    );
+    check_choice(
+        &mut errors,
+        "tts_voice",
+        &settings.tts_voice,
+        crate::tts::OPENAI_VOICES,
+    );
+    if let Some(service) = &settings.read_later_service {
+        if ReadLaterService::from_setting(service).is_none() {
+            errors.push(format!(
+                "read_later_service must be one of pocket, readwise, instapaper (got '{}')",
+                service
+            ));
+        }
+    }
+    for (topic, format) in &settings.topic_formats {
+        if let Err(e) = parse_briefing_format(format) {
+            errors.push(format!("topic_format for '{}': {}", topic, e));
+        }
+    }
+    for (topic, window) in &settings.topic_recency_windows {
+        if let Err(e) = parse_recency_window(window) {
+            errors.push(format!("topic_recency_window for '{}': {}", topic, e));
+        }
+    }
+
+    check_range(
+        &mut errors,
+        "max_sources_per_topic",
+        settings.max_sources_per_topic,
+        MAX_SOURCES_RANGE,
+    );
+    check_range(
+        &mut errors,
+        "dedup_days",
+        settings.dedup_days,
+        DEDUP_DAYS_RANGE,
+    );
+    check_range(
+        &mut errors,
+        "dedup_threshold",
+        settings.dedup_threshold,
+        (0.0, 1.0),
+    );
+    check_range(
+        &mut errors,
+        "dedup_context_tokens",
+        settings.dedup_context_tokens,
+        DEDUP_CONTEXT_TOKENS_RANGE,
+    );
+    if let Some(days) = settings.retention_days {
+        check_range(&mut errors, "retention_days", days, RETENTION_DAYS_RANGE);
+    }
+    check_range(
+        &mut errors,
+        "source_recency_days",
+        settings.source_recency_days,
+        SOURCE_RECENCY_DAYS_RANGE,
+    );
+    check_range(
+        &mut errors,
+        "crawl_delay_ms",
+        settings.crawl_delay_ms,
+        CRAWL_DELAY_MS_RANGE,
+    );
+    for tool in &settings.disabled_tools {
+        let valid = match tool.trim().split_once('/') {
+            Some((server, name)) => {
+                !server.trim().is_empty() && !name.trim().is_empty() && !name.contains('/')
+            }
+            None => !tool.trim().is_empty(),
+        };
+        if !valid {
+            errors.push(format!(
+                "disabled_tools entries must be a tool name or server/tool (got '{}')",
+                tool
+            ));
+        }
+    }
+    if let Some(contact) = &settings.fetch_contact {
+        // It is sent in the User-Agent header
+        if !contact.chars().all(|c| c.is_ascii_graphic() || c == ' ') {
+            errors.push(format!(
+                "fetch_contact must be plain ASCII without line breaks (got '{}')",
+                contact
+            ));
+        }
+    }
+    check_range(
+        &mut errors,
+        "log_retention_days",
+        settings.log_retention_days,
+        LOG_RETENTION_DAYS_RANGE,
+    );
+    check_range(
+        &mut errors,
+        "topic_timeout_minutes",
+        settings.topic_timeout_minutes,
+        TOPIC_TIMEOUT_MINUTES_RANGE,
+    );
+    check_range(
+        &mut errors,
+        "mcp_result_max_chars",
+        settings.mcp_result_max_chars,
+        MCP_RESULT_MAX_CHARS_RANGE,
+    );
+    for (tool, chars) in &settings.mcp_tool_result_limits {
+        check_range(
+            &mut errors,
+            &format!("mcp_tool_result_limit for '{}'", tool),
+            *chars,
+            MCP_RESULT_MAX_CHARS_RANGE,
+        );
+    }
+    check_range(
+        &mut errors,
+        "topic_discovery_days",
+        settings.topic_discovery_days,
+        TOPIC_DISCOVERY_DAYS_RANGE,
+    );
+    check_range(
+        &mut errors,
+        "notification_snooze_hours",
+        settings.notification_snooze_hours,
+        NOTIFICATION_SNOOZE_HOURS_RANGE,
+    );
+    check_range(
+        &mut errors,
+        "batch_deadline_minutes",
+        settings.batch_deadline_minutes,
+        BATCH_DEADLINE_MINUTES_RANGE,
+    );
+    for (depth, tokens) in &settings.thinking_budgets {
+        if !RESEARCH_DEPTHS.contains(&depth.as_str()) {
+            errors.push(format!(
+                "thinking_budget depth must be one of {} (got '{}')",
+                RESEARCH_DEPTHS.join(", "),
+                depth
+            ));
+        } else if *tokens < ThinkingConfig::MIN_BUDGET {
+            errors.push(format!(
+                "thinking_budget for '{}' must be at least {} tokens (got {})",
+                depth,
+                ThinkingConfig::MIN_BUDGET,
+                tokens
+            ));
+        }
+    }
+
+    if let Err(e) = crate::daemon::parse_schedule(&settings.schedule_cron) {
+        errors.push(format!("schedule_cron: {}", e));
+    }
+    check_choice(
+        &mut errors,
+        "schedule_mode",
+        &settings.schedule_mode,
+        &crate::smart_schedule::SCHEDULE_MODES,
+    );
+    // Each end is set separately from the CLI, so only the format is checked
+    for (key, value) in [
+        ("quiet_hours_start", &settings.quiet_hours_start),
+        ("quiet_hours_end", &settings.quiet_hours_end),
+    ] {
+        if let Some(Err(e)) = value.as_deref().map(crate::quiet_hours::parse_time) {
+            errors.push(format!("{}: {}", key, e));
+        }
+    }
+
+    if let Err(e) = crate::global_shortcut::parse_shortcut(&settings.global_shortcut) {
+        errors.push(format!("global_shortcut: {}", e));
+    }
+    match crate::global_shortcut::parse_shortcut(&settings.quick_research_shortcut) {
+        Err(e) => errors.push(format!("quick_research_shortcut: {}", e)),
+        Ok(Some(quick)) => {
+            if crate::global_shortcut::parse_shortcut(&settings.global_shortcut) == Ok(Some(quick))
+            {
+                errors.push("quick_research_shortcut: same as global_shortcut".to_string());
+            }
+        }
+        Ok(None) => {}
+    }
+    check_choice(
+        &mut errors,
+        "quick_research_mode",
+        &settings.quick_research_mode,
+        &QUICK_RESEARCH_MODES,
+    );
+    check_choice(
+        &mut errors,
+        "release_channel",
+        &settings.release_channel,
+        crate::releases::RELEASE_CHANNELS,
+    );
+
+    if let Some(url) = &settings.proxy_url {
+        if let Err(e) = crate::http_client::parse_proxy_url(url) {
+            errors.push(format!("proxy_url: {}", e));
+        }
+    }
+    for (name, value) in &settings.api_headers {
+        if let Err(e) = validate_header(name, value) {
+            errors.push(format!("api_header: {}", e));
+        }
+    }
+    if let Some(user_id) = &settings.api_user_id {
+        if let Err(e) = crate::agent_loop::validate_user_id(user_id) {
+            errors.push(format!("api_user_id: {}", e));
+        }
+    }
+    if settings.active_profile != DEFAULT_PROFILE
+        && !settings.api_profiles.contains(&settings.active_profile)
+    {
+        errors.push(format!(
+            "active_profile '{}' is not a configured profile (see: claudius config profile list)",
+            settings.active_profile
+        ));
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+/// Check every setting against the schema and the cached model list.
+/// The error lists every problem, one per line.
+pub fn validate_settings(settings: &ResearchSettings) -> Result<(), String> {
+    let known = models::cached_models().unwrap_or_default();
+    validate_settings_with(settings, &known)
+        .map_err(|errors| format!("Invalid settings:\n  - {}", errors.join("\n  - ")))
+}
+
+/// Check a topic's dedup overrides against the same ranges as the global settings.
+pub fn validate_topic_dedup(days: Option<i32>, threshold: Option<f64>) -> Result<(), String> {
+    let mut errors = Vec::new();
+    if let Some(days) = days {
+        check_range(&mut errors, "dedup_days", days, DEDUP_DAYS_RANGE);
+    }
+    if let Some(threshold) = threshold {
+        check_range(&mut errors, "dedup_threshold", threshold, (0.0, 1.0));
+    }
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors.join("; "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn known_models() -> Vec<ModelInfo> {
+        [
+            "claude-haiku-4-5-20251001",
+            "claude-sonnet-4-5-20250929",
+            "claude-opus-4-5-20251101",
+        ]
+        .iter()
+        .map(|id| ModelInfo {
+            id: id.to_string(),
+            display_name: id.to_string(),
+            created_at: None,
+            deprecated: false,
+        })
+        .collect()
+    }
+
+    #[test]
+    fn test_default_settings_are_valid() {
+        assert_eq!(
+            validate_settings_with(&ResearchSettings::default(), &known_models()),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn test_invalid_values_are_reported_by_key() {
+        let mut settings = ResearchSettings {
+            model: "claude-sonet-4-5-20250929".to_string(),
+            research_depth: "bottomless".to_string(),
+            max_sources_per_topic: 0,
+            dedup_threshold: 1.5,
+            schedule_cron: "every morning".to_string(),
+            quiet_hours_start: Some("10pm".to_string()),
+            global_shortcut: "B".to_string(),
+            release_channel: "nightly".to_string(),
+            ..Default::default()
+        };
+        settings.thinking_budgets.insert("deep".to_string(), 10);
+
+        let errors = validate_settings_with(&settings, &known_models()).unwrap_err();
+        let keys: Vec<&str> = errors
+            .iter()
+            .map(|e| e.split([':', ' ']).next().unwrap())
+            .collect();
+        assert_eq!(
+            keys,
+            vec![
+                "model",
+                "research_depth",
+                "max_sources_per_topic",
+                "dedup_threshold",
+                "thinking_budget",
+                "schedule_cron",
+                "quiet_hours_start",
+                "global_shortcut",
+                "release_channel",
+            ]
+        );
+        assert!(errors[0].contains("Did you mean 'claude-sonnet-4-5-20250929'"));
+    }
+
+    #[test]
+    fn test_gateway_models_are_not_checked() {
+        let mut settings = ResearchSettings {
+            model: "anthropic/claude-haiku".to_string(),
+            ..Default::default()
+        };
+        assert!(validate_settings_with(&settings, &known_models()).is_err());
+        settings.api_base_url = Some("https://litellm.corp/anthropic".to_string());
+        assert_eq!(validate_settings_with(&settings, &known_models()), Ok(()));
+    }
+}
@@ -0,0 +1,501 @@
+//! Share images for cards and briefings.
+//!
+//! Renders a card (topic, title, summary, sources) or a whole briefing (its
+//! cards' titles and summaries) into a PNG sized for pasting into Slack or a
+//! social post. No font is bundled, so text is drawn with one found on the
+//! system (Arial, Helvetica, DejaVu Sans or Liberation Sans).
+
+use crate::research::BriefingCard;
+use ab_glyph::{Font, FontVec, PxScale, ScaleFont};
+use image::{ImageFormat, Rgba, RgbaImage};
+use reqwest::Url;
+use std::path::{Path, PathBuf};
+
+/// Image width; 1200x630 is the usual link-preview size.
+pub const WIDTH: u32 = 1200;
+const MIN_HEIGHT: u32 = 630;
+const MARGIN: f32 = 72.0;
+
+const MAX_TITLE_LINES: usize = 3;
+const MAX_SUMMARY_LINES: usize = 8;
+const MAX_SOURCES: usize = 3;
+/// Cards listed on a briefing image before "+N more cards"
+const MAX_BRIEFING_CARDS: usize = 6;
+
+type Color = [u8; 3];
+const BACKGROUND_TOP: Color = [30, 27, 75]; // indigo-950
+const BACKGROUND_BOTTOM: Color = [46, 16, 101]; // purple-950
+const ACCENT: Color = [167, 139, 250]; // violet-400
+const TEXT: Color = [243, 244, 246]; // gray-100
+const BODY: Color = [209, 213, 219]; // gray-300
+const MUTED: Color = [156, 163, 175]; // gray-400
+
+/// A font file and the index of the font in it (for .ttc collections).
+type FontFile = (&'static str, u32);
+
+/// Regular and bold font files to try, in order.
+const FONT_CANDIDATES: &[(FontFile, FontFile)] = &[
+    (
+        ("/System/Library/Fonts/Supplemental/Arial.ttf", 0),
+        ("/System/Library/Fonts/Supplemental/Arial Bold.ttf", 0),
+    ),
+    (
+        ("/System/Library/Fonts/Helvetica.ttc", 0),
+        ("/System/Library/Fonts/Helvetica.ttc", 1),
+    ),
+    (
+        ("/usr/share/fonts/truetype/dejavu/DejaVuSans.ttf", 0),
+        ("/usr/share/fonts/truetype/dejavu/DejaVuSans-Bold.ttf", 0),
+    ),
+    (
+        ("/usr/share/fonts/TTF/DejaVuSans.ttf", 0),
+        ("/usr/share/fonts/TTF/DejaVuSans-Bold.ttf", 0),
+    ),
+    (
+        ("/usr/share/fonts/dejavu-sans-fonts/DejaVuSans.ttf", 0),
+        ("/usr/share/fonts/dejavu-sans-fonts/DejaVuSans-Bold.ttf", 0),
+    ),
+    (
+        (
+            "/usr/share/fonts/truetype/liberation/LiberationSans-Regular.ttf",
+            0,
+        ),
+        (
+            "/usr/share/fonts/truetype/liberation/LiberationSans-Bold.ttf",
+            0,
+        ),
+    ),
+    (
+        ("C:\\Windows\\Fonts\\arial.ttf", 0),
+        ("C:\\Windows\\Fonts\\arialbd.ttf", 0),
+    ),
+];
+
+struct Fonts {
+    regular: FontVec,
+    bold: FontVec,
+}
+
+fn load_font(path: &str, index: u32) -> Option<FontVec> {
+    let data = std::fs::read(path).ok()?;
+    FontVec::try_from_vec_and_index(data, index).ok()
+}
+
+impl Fonts {
+    /// The first system font found; bold falls back to regular.
+    fn load() -> Result<Self, String> {
+        FONT_CANDIDATES
+            .iter()
+            .find_map(|&((regular, ri), (bold, bi))| {
+                let regular_font = load_font(regular, ri)?;
+                let bold_font = load_font(bold, bi).or_else(|| load_font(regular, ri))?;
+                Some(Fonts {
+                    regular: regular_font,
+                    bold: bold_font,
+                })
+            })
+            .ok_or_else(|| {
+                "No font found for share images. Install DejaVu Sans or Liberation Sans (e.g. the fonts-dejavu package).".to_string()
+            })
+    }
+
+    fn get(&self, bold: bool) -> &FontVec {
+        if bold {
+            &self.bold
+        } else {
+            &self.regular
+        }
+    }
+
+    /// Width of `text` in pixels.
+    fn measure(&self, text: &str, bold: bool, size: f32) -> f32 {
+        let font = self.get(bold).as_scaled(PxScale::from(size));
+        let mut width = 0.0;
+        let mut previous = None;
+        for ch in text.chars() {
+            let id = font.glyph_id(ch);
+            if let Some(previous) = previous {
+                width += font.kern(previous, id);
+            }
+            width += font.h_advance(id);
+            previous = Some(id);
+        }
+        width
+    }
+}
+
+/// Break `text` into lines no wider than `max_width`, at most `max_lines`
+/// of them; text that doesn't fit ends with an ellipsis.
+fn wrap_text(
+    text: &str,
+    max_width: f32,
+    max_lines: usize,
+    measure: impl Fn(&str) -> f32,
+) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    let mut current = String::new();
+    let mut truncated = false;
+
+    for word in text.split_whitespace() {
+        let candidate = if current.is_empty() {
+            word.to_string()
+        } else {
+            format!("{} {}", current, word)
+        };
+        if measure(&candidate) <= max_width {
+            current = candidate;
+            continue;
+        }
+        if !current.is_empty() {
+            lines.push(std::mem::take(&mut current));
+        }
+        // A word wider than a line is broken wherever it overflows
+        for ch in word.chars() {
+            current.push(ch);
+            if measure(&current) > max_width && current.chars().count() > 1 {
+                current.pop();
+                lines.push(std::mem::replace(&mut current, ch.to_string()));
+            }
+        }
+        if lines.len() >= max_lines {
+            truncated = true;
+            break;
+        }
+    }
+    if !current.is_empty() {
+        lines.push(current);
+    }
+
+    if lines.len() > max_lines || truncated {
+        lines.truncate(max_lines);
+        if let Some(last) = lines.last_mut() {
+            while !last.is_empty() && measure(&format!("{}…", last)) > max_width {
+                last.pop();
+            }
+            *last = format!("{}…", last.trim_end());
+        }
+    }
+    lines
+}
+
+/// A line of text placed on the image.
+struct Line {
+    text: String,
+    bold: bool,
+    size: f32,
+    color: Color,
+    x: f32,
+    /// Top of the line box
+    y: f32,
+}
+
+/// Lines laid out top to bottom, before the image size is known.
+struct Layout<'a> {
+    fonts: &'a Fonts,
+    lines: Vec<Line>,
+    y: f32,
+}
+
+impl<'a> Layout<'a> {
+    fn new(fonts: &'a Fonts) -> Self {
+        Self {
+            fonts,
+            lines: Vec::new(),
+            y: MARGIN,
+        }
+    }
+
+    fn line_height(size: f32) -> f32 {
+        size * 1.3
+    }
+
+    fn gap(&mut self, pixels: f32) {
+        self.y += pixels;
+    }
+
+    /// Add wrapped text at the left margin.
+    fn paragraph(&mut self, text: &str, bold: bool, size: f32, color: Color, max_lines: usize) {
+        let max_width = WIDTH as f32 - 2.0 * MARGIN;
+        for text in wrap_text(text, max_width, max_lines, |t| {
+            self.fonts.measure(t, bold, size)
+        }) {
+            self.lines.push(Line {
+                text,
+                bold,
+                size,
+                color,
+                x: MARGIN,
+                y: self.y,
+            });
+            self.y += Self::line_height(size);
+        }
+    }
+
+    /// Add the branding footer below everything else and return the image height.
+    fn footer(&mut self, date: &str) -> u32 {
+        let size = 22.0;
+        let height = (self.y + 2.0 * MARGIN).max(MIN_HEIGHT as f32);
+        let y = height - MARGIN;
+        self.lines.push(Line {
+            text: "Claudius".to_string(),
+            bold: true,
+            size,
+            color: ACCENT,
+            x: MARGIN,
+            y,
+        });
+        self.lines.push(Line {
+            text: date.to_string(),
+            bold: false,
+            size,
+            color: MUTED,
+            x: WIDTH as f32 - MARGIN - self.fonts.measure(date, false, size),
+            y,
+        });
+        height.ceil() as u32
+    }
+
+    fn render(&self, height: u32) -> RgbaImage {
+        let mut image = RgbaImage::from_fn(WIDTH, height, |_, y| {
+            let t = y as f32 / height as f32;
+            let mix = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * t).round() as u8;
+            Rgba([
+                mix(BACKGROUND_TOP[0], BACKGROUND_BOTTOM[0]),
+                mix(BACKGROUND_TOP[1], BACKGROUND_BOTTOM[1]),
+                mix(BACKGROUND_TOP[2], BACKGROUND_BOTTOM[2]),
+                255,
+            ])
+        });
+
+        // Accent stripe along the top edge
+        for y in 0..8 {
+            for x in 0..WIDTH {
+                image.put_pixel(x, y, Rgba([ACCENT[0], ACCENT[1], ACCENT[2], 255]));
+            }
+        }
+
+        for line in &self.lines {
+            draw_text(&mut image, self.fonts.get(line.bold), line);
+        }
+        image
+    }
+}
+
+fn draw_text(image: &mut RgbaImage, font: &FontVec, line: &Line) {
+    let scale = PxScale::from(line.size);
+    let scaled = font.as_scaled(scale);
+    let baseline = line.y + scaled.ascent();
+    let mut x = line.x;
+    let mut previous = None;
+
+    for ch in line.text.chars() {
+        let id = scaled.glyph_id(ch);
+        if let Some(previous) = previous {
+            x += scaled.kern(previous, id);
+        }
+        let glyph = id.with_scale_and_position(scale, ab_glyph::point(x, baseline));
+        x += scaled.h_advance(id);
+        previous = Some(id);
+
+        let Some(outlined) = font.outline_glyph(glyph) else {
+            continue;
+        };
+        let bounds = outlined.px_bounds();
+        outlined.draw(|gx, gy, coverage| {
+            let px = bounds.min.x as i32 + gx as i32;
+            let py = bounds.min.y as i32 + gy as i32;
+            if px < 0 || py < 0 || px >= image.width() as i32 || py >= image.height() as i32 {
+                return;
+            }
+            let pixel = image.get_pixel_mut(px as u32, py as u32);
+            let alpha = coverage.clamp(0.0, 1.0);
+            for channel in 0..3 {
+                let under = pixel[channel] as f32;
+                pixel[channel] =
+                    (under + (line.color[channel] as f32 - under) * alpha).round() as u8;
+            }
+        });
+    }
+}
+
+/// A source URL as shown on the image: its host without "www.".
+fn source_label(url: &str) -> String {
+    Url::parse(url)
+        .ok()
+        .and_then(|u| {
+            u.host_str()
+                .map(|h| h.trim_start_matches("www.").to_string())
+        })
+        .unwrap_or_else(|| url.to_string())
+}
+
+/// The day part of a briefing date ("2025-01-15T07:00:00" -> "2025-01-15").
+fn display_date(date: &str) -> &str {
+    date.get(..10).unwrap_or(date)
+}
+
+/// Render one card as a share image.
+pub fn render_card(card: &BriefingCard, date: &str) -> Result<RgbaImage, String> {
+    let fonts = Fonts::load()?;
+    let mut layout = Layout::new(&fonts);
+
+    if !card.topic.is_empty() {
+        layout.paragraph(&card.topic.to_uppercase(), true, 22.0, ACCENT, 1);
+        layout.gap(12.0);
+    }
+    layout.paragraph(&card.title, true, 52.0, TEXT, MAX_TITLE_LINES);
+    layout.gap(20.0);
+    layout.paragraph(&card.summary, false, 28.0, BODY, MAX_SUMMARY_LINES);
+
+    if !card.sources.is_empty() {
+        layout.gap(28.0);
+        layout.paragraph("Sources", true, 20.0, MUTED, 1);
+        for source in card.sources.iter().take(MAX_SOURCES) {
+            layout.paragraph(&source_label(source), false, 22.0, ACCENT, 1);
+        }
+        if card.sources.len() > MAX_SOURCES {
+            let more = format!("+{} more", card.sources.len() - MAX_SOURCES);
+            layout.paragraph(&more, false, 20.0, MUTED, 1);
+        }
+    }
+
+    let height = layout.footer(display_date(date));
+    Ok(layout.render(height))
+}
+
+/// Render a briefing as a share image listing its cards in display order.
+pub fn render_briefing(
+    title: &str,
+    date: &str,
+    cards: &[BriefingCard],
+) -> Result<RgbaImage, String> {
+    let fonts = Fonts::load()?;
+    let mut layout = Layout::new(&fonts);
+
+    layout.paragraph("DAILY BRIEFING", true, 22.0, ACCENT, 1);
+    layout.gap(12.0);
+    layout.paragraph(title, true, 48.0, TEXT, 2);
+
+    let mut order: Vec<usize> = (0..cards.len()).collect();
+    order.sort_by_key(|&i| cards[i].rank.map_or(i, |r| r as usize));
+    for &i in order.iter().take(MAX_BRIEFING_CARDS) {
+        layout.gap(28.0);
+        layout.paragraph(&cards[i].title, true, 30.0, TEXT, 2);
+        layout.gap(4.0);
+        layout.paragraph(&cards[i].summary, false, 22.0, BODY, 2);
+    }
+    if cards.len() > MAX_BRIEFING_CARDS {
+        layout.gap(28.0);
+        let more = format!("+{} more cards", cards.len() - MAX_BRIEFING_CARDS);
+        layout.paragraph(&more, false, 22.0, MUTED, 1);
+    }
+
+    let height = layout.footer(display_date(date));
+    Ok(layout.render(height))
+}
+
+/// Where share images are written when no path is given.
+pub fn default_share_path(briefing_id: i64, card_index: Option<usize>) -> PathBuf {
+    let name = match card_index {
+        Some(index) => format!("briefing-{}-card-{}.png", briefing_id, index + 1),
+        None => format!("briefing-{}.png", briefing_id),
+    };
+    crate::config::get_data_dir().join("share").join(name)
+}
+
+/// Render briefing `briefing_id`, or only its card `card_index` (0-based),
+/// to a PNG at `out` (see `default_share_path` when None). Returns the path.
+pub fn render_share_image(
+    briefing_id: i64,
+    card_index: Option<usize>,
+    out: Option<&Path>,
+) -> Result<PathBuf, String> {
+    let conn = crate::db::get_connection().map_err(|e| format!("Database error: {}", e))?;
+    let briefing = crate::db::get_briefing(&conn, briefing_id)?;
+    let cards: Vec<BriefingCard> = serde_json::from_str(&briefing.cards)
+        .map_err(|e| format!("Failed to parse cards: {}", e))?;
+
+    let image = match card_index {
+        Some(index) => {
+            let card = cards.get(index).ok_or_else(|| {
+                format!(
+                    "Briefing {} has no card {} (it has {})",
+                    briefing_id,
+                    index + 1,
+                    cards.len()
+                )
+            })?;
+            render_card(card, &briefing.date)?
+        }
+        None => render_briefing(&briefing.title, &briefing.date, &cards)?,
+    };
+
+    let path = out
+        .map(Path::to_path_buf)
+        .unwrap_or_else(|| default_share_path(briefing_id, card_index));
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+    image
+        .save_with_format(&path, ImageFormat::Png)
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Every character is 10px wide
+    fn measure(text: &str) -> f32 {
+        text.chars().count() as f32 * 10.0
+    }
+
+    #[test]
+    fn test_wrap_text() {
+        let lines = wrap_text("the quick brown fox jumps", 100.0, 5, measure);
+        assert_eq!(lines, vec!["the quick", "brown fox", "jumps"]);
+
+        // Too long: the last line ends with an ellipsis and still fits
+        let lines = wrap_text("the quick brown fox jumps", 100.0, 2, measure);
+        assert_eq!(lines.len(), 2);
+        assert!(lines[1].ends_with('…'));
+        assert!(measure(&lines[1]) <= 100.0);
+
+        // A word wider than a line is split
+        let lines = wrap_text("abcdefghijklmnop", 100.0, 5, measure);
+        assert_eq!(lines, vec!["abcdefghij", "klmnop"]);
+    }
+
+    #[test]
+    fn test_labels() {
+        assert_eq!(
+            source_label("https://www.example.com/a/b?c=1"),
+            "example.com"
+        );
+        assert_eq!(source_label("not a url"), "not a url");
+        assert_eq!(display_date("2025-01-15T07:00:00"), "2025-01-15");
+    }
+
+    #[test]
+    fn test_render_card() {
+        // Rendering needs a system font; skip where none is installed
+        if Fonts::load().is_err() {
+            return;
+        }
+        let card: BriefingCard = serde_json::from_value(serde_json::json!({
+            "title": "Rust 2024 edition ships",
+            "summary": "The new edition stabilizes async closures. ".repeat(20),
+            "detailed_content": "",
+            "sources": vec!["https://blog.rust-lang.org/"; 5],
+            "suggested_next": null,
+            "relevance": "high",
+            "topic": "Rust",
+        }))
+        .unwrap();
+        let image = render_card(&card, "2025-01-15T07:00:00").unwrap();
+        assert_eq!(image.width(), WIDTH);
+        assert!(image.height() >= MIN_HEIGHT);
+    }
+}
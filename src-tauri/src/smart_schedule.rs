@@ -0,0 +1,204 @@
+//! Adaptive research schedule (`schedule_mode = "adaptive"`).
+//!
+//! Instead of following `schedule_cron`, research is timed to finish shortly
+//! before the user usually starts their day. Opening or focusing the app and
+//! waking the machine from sleep (noticed by `claudius daemon`) are logged in
+//! `activity_log`. The median time of each day's first activity over the last
+//! [`LEARNING_DAYS`] days, less [`LEAD_MINUTES`] and the usual length of a
+//! run, becomes a daily cron expression, so the daemon and the schedule
+//! calendar treat it like any other schedule. Until [`MIN_DAYS`] days of
+//! activity are logged, `schedule_cron` is used.
+
+use crate::config::ResearchSettings;
+use chrono::{Duration, Local, NaiveDate, NaiveDateTime, Timelike};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use tracing::warn;
+
+/// Values of the `schedule_mode` setting.
+pub const SCHEDULE_MODES: [&str; 2] = ["cron", "adaptive"];
+
+/// Activity kinds logged in `activity_log`.
+pub const ACTIVITY_APP_OPEN: &str = "app_open";
+pub const ACTIVITY_WAKE: &str = "wake";
+
+/// Days of activity the schedule is learned from.
+pub const LEARNING_DAYS: i64 = 21;
+
+/// Days with activity needed before the adaptive schedule is used.
+pub const MIN_DAYS: usize = 3;
+
+/// How long before the usual first activity research should be finished.
+pub const LEAD_MINUTES: u32 = 15;
+
+/// Assumed run length until runs have been timed.
+const DEFAULT_RUN_MINUTES: u32 = 10;
+
+/// Runs counted towards the usual run length.
+const RUN_SAMPLES: usize = 10;
+
+/// Activity before this hour belongs to the night before (e.g. working past
+/// midnight), not to the start of the day.
+const DAY_START_HOUR: u32 = 4;
+
+const TIME_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+/// The learned schedule.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AdaptiveSchedule {
+    /// Daily cron expression starting research
+    pub cron: String,
+    /// Usual first activity of the day ("HH:MM")
+    pub first_activity: String,
+    /// Usual length of a research run
+    pub run_minutes: u32,
+    /// Days with activity it was learned from
+    pub days: usize,
+}
+
+/// Median minute of the day (0-1439) of each day's first activity, or None
+/// with fewer than [`MIN_DAYS`] days of activity.
+pub fn typical_first_activity(times: &[NaiveDateTime]) -> Option<(u32, usize)> {
+    let mut firsts: BTreeMap<NaiveDate, u32> = BTreeMap::new();
+    for time in times.iter().filter(|t| t.hour() >= DAY_START_HOUR) {
+        let minute = time.hour() * 60 + time.minute();
+        firsts
+            .entry(time.date())
+            .and_modify(|first| *first = (*first).min(minute))
+            .or_insert(minute);
+    }
+    if firsts.len() < MIN_DAYS {
+        return None;
+    }
+    let mut minutes: Vec<u32> = firsts.into_values().collect();
+    minutes.sort_unstable();
+    Some((minutes[(minutes.len() - 1) / 2], minutes.len()))
+}
+
+/// Usual length of a research run in whole minutes, from recent run times.
+pub fn typical_run_minutes(durations_ms: &[i64]) -> u32 {
+    let mut minutes: Vec<u32> = durations_ms
+        .iter()
+        .filter(|ms| **ms > 0)
+        .map(|ms| (*ms as f64 / 60_000.0).ceil() as u32)
+        .collect();
+    if minutes.is_empty() {
+        return DEFAULT_RUN_MINUTES;
+    }
+    minutes.sort_unstable();
+    minutes[minutes.len() / 2].clamp(1, 60)
+}
+
+/// Learn the schedule from `times` of activity and recent run lengths.
+pub fn learn(times: &[NaiveDateTime], durations_ms: &[i64]) -> Option<AdaptiveSchedule> {
+    let (first, days) = typical_first_activity(times)?;
+    let run_minutes = typical_run_minutes(durations_ms);
+    let start = (first + 24 * 60 - LEAD_MINUTES - run_minutes) % (24 * 60);
+    Some(AdaptiveSchedule {
+        cron: format!("{} {} * * *", start % 60, start / 60),
+        first_activity: format!("{:02}:{:02}", first / 60, first % 60),
+        run_minutes,
+        days,
+    })
+}
+
+/// Learn the schedule from the activity logged up to `now`.
+pub fn adaptive_schedule(
+    conn: &rusqlite::Connection,
+    now: NaiveDateTime,
+) -> Result<Option<AdaptiveSchedule>, String> {
+    let since = (now - Duration::days(LEARNING_DAYS)).format(TIME_FORMAT);
+    let times: Vec<NaiveDateTime> = crate::db::get_activity_since(conn, &since.to_string())?
+        .iter()
+        .filter_map(|t| NaiveDateTime::parse_from_str(t, TIME_FORMAT).ok())
+        .collect();
+    let durations = crate::db::get_recent_research_times_ms(conn, RUN_SAMPLES)?;
+    Ok(learn(&times, &durations))
+}
+
+/// The cron schedule research follows: the learned one in adaptive mode
+/// (once there is enough activity), `schedule_cron` otherwise.
+pub fn effective_schedule(settings: &ResearchSettings) -> String {
+    if settings.schedule_mode != "adaptive" {
+        return settings.schedule_cron.clone();
+    }
+    let learned = crate::db::get_connection()
+        .map_err(|e| e.to_string())
+        .and_then(|conn| adaptive_schedule(&conn, Local::now().naive_local()));
+    match learned {
+        Ok(Some(schedule)) => schedule.cron,
+        Ok(None) => settings.schedule_cron.clone(),
+        Err(e) => {
+            warn!("Failed to learn the adaptive schedule: {}", e);
+            settings.schedule_cron.clone()
+        }
+    }
+}
+
+/// Log activity of `kind` now, warning on failure.
+pub fn record_activity(kind: &str) {
+    let now = Local::now().format(TIME_FORMAT).to_string();
+    let result = crate::db::get_connection()
+        .map_err(|e| e.to_string())
+        .and_then(|conn| crate::db::record_activity(&conn, kind, &now));
+    if let Err(e) = result {
+        warn!("Failed to log activity: {}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(day: u32, time: &str) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2025, 3, day)
+            .unwrap()
+            .and_time(chrono::NaiveTime::parse_from_str(time, "%H:%M").unwrap())
+    }
+
+    #[test]
+    fn test_learn() {
+        let mut times = vec![at(3, "07:40"), at(3, "12:00"), at(4, "07:10")];
+        assert!(learn(&times, &[]).is_none());
+
+        // Past midnight counts towards the night before
+        times.extend([at(5, "01:30"), at(5, "07:55"), at(6, "07:30")]);
+        assert_eq!(typical_first_activity(&times), Some((7 * 60 + 30, 4)));
+
+        let schedule = learn(&times, &[300_000, 610_000, 1_200_000]).unwrap();
+        assert_eq!(schedule.first_activity, "07:30");
+        assert_eq!(schedule.run_minutes, 11);
+        assert_eq!(schedule.cron, "4 7 * * *");
+        assert!(crate::daemon::parse_schedule(&schedule.cron).is_ok());
+
+        // Activity from DAY_START_HOUR on starts the day
+        let early = [at(3, "04:05"), at(4, "04:10"), at(5, "04:00")];
+        assert_eq!(learn(&early, &[]).unwrap().cron, "40 3 * * *");
+
+        assert_eq!(typical_run_minutes(&[]), DEFAULT_RUN_MINUTES);
+        assert_eq!(typical_run_minutes(&[7_200_000]), 60);
+    }
+
+    #[test]
+    fn test_activity_log() {
+        let conn = rusqlite::Connection::open_in_memory().unwrap();
+        conn.execute_batch(include_str!("schema.sql")).unwrap();
+
+        let log = |kind, at| crate::db::record_activity(&conn, kind, at).unwrap();
+        assert!(log(ACTIVITY_APP_OPEN, "2025-03-03 07:40:00"));
+        assert!(!log(ACTIVITY_APP_OPEN, "2025-03-03 07:55:00"));
+        assert!(log(ACTIVITY_WAKE, "2025-03-03 07:55:00"));
+        assert!(log(ACTIVITY_APP_OPEN, "2025-03-04 07:10:00"));
+        assert!(log(ACTIVITY_APP_OPEN, "2025-03-05 07:30:00"));
+
+        let schedule = adaptive_schedule(&conn, at(5, "09:00")).unwrap().unwrap();
+        assert_eq!(schedule.days, 3);
+        assert_eq!(schedule.first_activity, "07:30");
+        assert_eq!(schedule.cron, "5 7 * * *");
+
+        // Older activity is pruned and ignored
+        assert!(log(ACTIVITY_APP_OPEN, "2025-05-20 07:00:00"));
+        let since = crate::db::get_activity_since(&conn, "2025-01-01 00:00:00").unwrap();
+        assert_eq!(since, vec!["2025-05-20 07:00:00".to_string()]);
+    }
+}
@@ -0,0 +1,435 @@
+//! Source citation validation for briefing cards.
+//!
+//! After synthesis, every source URL on every card is checked with a HEAD
+//! request (falling back to GET when HEAD is refused). The result — status
+//! code, final URL after redirects, and whether the link looks broken — is
+//! attached to the card. When archiving is enabled, the article text of each
+//! reachable source is captured so it can be stored in the `sources` table.
+//!
+//! [`source_issue`] then flags cards that cite no sources or only stale ones,
+//! so research can ask Claude to repair them before the briefing is saved.
+
+use crate::article;
+use crate::research::BriefingCard;
+use chrono::NaiveDate;
+use lazy_static::lazy_static;
+use regex::Regex;
+use reqwest::{Client, StatusCode};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Semaphore;
+use tracing::{debug, info, warn};
+
+/// Per-request timeout for source checks.
+const CHECK_TIMEOUT_SECS: u64 = 15;
+
+/// Maximum number of source checks in flight at once.
+const MAX_CONCURRENT_CHECKS: usize = 8;
+
+/// Maximum characters of archived article text stored per source.
+const MAX_SNAPSHOT_CHARS: usize = 50_000;
+
+lazy_static! {
+    static ref URL_RE: Regex = Regex::new(r#"https?://[^\s<>"'\]]+"#).unwrap();
+    // Dates in URL paths, e.g. /2025/01/15/ or /2025-01-15-title
+    static ref URL_DATE_RE: Regex =
+        Regex::new(r"/((?:19|20)\d{2})[/-](\d{1,2})[/-](\d{1,2})(?:[/-]|\.html?|$)").unwrap();
+}
+
+/// Validation result for a single source URL.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SourceCheck {
+    pub url: String,
+    pub valid: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status_code: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub final_url: Option<String>, // Only set when the URL redirected elsewhere
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub published: Option<String>, // Publication date from the page metadata, when the body was read
+    #[serde(skip)]
+    pub snapshot: Option<String>, // Archived article text (stored in DB, not in card JSON)
+}
+
+/// Summary of a validation pass.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ValidationSummary {
+    pub checked: usize,
+    pub broken: usize,
+}
+
+/// Pull the URL out of a source string.
+///
+/// Sources are usually bare URLs but Claude sometimes writes
+/// "Title - https://..." or wraps them in markdown links.
+pub fn extract_url(source: &str) -> Option<String> {
+    URL_RE.find(source).map(|m| {
+        m.as_str()
+            .trim_end_matches(['.', ',', ';', ':', ')', '!', '?'])
+            .to_string()
+    })
+}
+
+/// Whether a status code means the page exists.
+///
+/// 401/403/429 usually mean a paywall or bot protection rather than a dead
+/// link, so they are not reported as broken.
+fn is_reachable(status: StatusCode) -> bool {
+    status.is_success()
+        || status.is_redirection()
+        || matches!(
+            status,
+            StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN | StatusCode::TOO_MANY_REQUESTS
+        )
+}
+
+/// Check a single URL, optionally capturing an article snapshot.
+pub async fn check_url(client: &Client, url: &str, archive: bool) -> SourceCheck {
+    let mut check = SourceCheck {
+        url: url.to_string(),
+        valid: false,
+        status_code: None,
+        final_url: None,
+        error: None,
+        published: None,
+        snapshot: None,
+    };
+
+    // HEAD is cheap, but some servers refuse it; archiving always needs the body
+    let mut response = if archive {
+        None
+    } else {
+        match client.head(url).send().await {
+            Ok(r)
+                if r.status() != StatusCode::METHOD_NOT_ALLOWED
+                    && r.status() != StatusCode::NOT_IMPLEMENTED
+                    && r.status() != StatusCode::FORBIDDEN =>
+            {
+                Some(r)
+            }
+            _ => None,
+        }
+    };
+
+    if response.is_none() {
+        match client.get(url).send().await {
+            Ok(r) => response = Some(r),
+            Err(e) => {
+                check.error = Some(if e.is_timeout() {
+                    "Request timed out".to_string()
+                } else {
+                    format!("Request failed: {}", e)
+                });
+                return check;
+            }
+        }
+    }
+
+    let Some(response) = response else {
+        return check;
+    };
+
+    let status = response.status();
+    check.status_code = Some(status.as_u16());
+    check.valid = is_reachable(status);
+
+    let final_url = response.url().as_str();
+    if final_url.trim_end_matches('/') != url.trim_end_matches('/') {
+        check.final_url = Some(final_url.to_string());
+    }
+
+    if !check.valid {
+        check.error = Some(format!("HTTP {}", status));
+    }
+
+    if archive && status.is_success() {
+        match response.text().await {
+            Ok(html) => {
+                let article = article::extract_article(&html);
+                check.published = article.published;
+                let text: String = article.text.chars().take(MAX_SNAPSHOT_CHARS).collect();
+                if !text.is_empty() {
+                    check.snapshot = Some(text);
+                }
+            }
+            Err(e) => debug!("Failed to read body for archiving {}: {}", url, e),
+        }
+    }
+
+    check
+}
+
+/// Validate every source on every card, attaching results to `source_checks`.
+pub async fn validate_cards(cards: &mut [BriefingCard], archive: bool) -> ValidationSummary {
+    let mut urls: Vec<String> = cards
+        .iter()
+        .flat_map(|c| c.sources.iter().filter_map(|s| extract_url(s)))
+        .collect();
+    urls.sort();
+    urls.dedup();
+
+    if urls.is_empty() {
+        return ValidationSummary::default();
+    }
+
+    let client = match crate::http_client::client_builder()
+        .timeout(Duration::from_secs(CHECK_TIMEOUT_SECS))
+        .user_agent(crate::fetch_policy::FetchPolicy::current().user_agent())
+        .build()
+    {
+        Ok(c) => c,
+        Err(e) => {
+            warn!("Failed to build HTTP client for source validation: {}", e);
+            return ValidationSummary::default();
+        }
+    };
+
+    info!(
+        "Validating {} source URLs (archive: {})",
+        urls.len(),
+        archive
+    );
+
+    let semaphore = Arc::new(Semaphore::new(MAX_CONCURRENT_CHECKS));
+    let mut tasks = tokio::task::JoinSet::new();
+    for url in urls {
+        let client = client.clone();
+        let semaphore = semaphore.clone();
+        tasks.spawn(async move {
+            let _permit = semaphore.acquire().await;
+            check_url(&client, &url, archive).await
+        });
+    }
+
+    let mut results: HashMap<String, SourceCheck> = HashMap::new();
+    while let Some(joined) = tasks.join_next().await {
+        match joined {
+            Ok(check) => {
+                results.insert(check.url.clone(), check);
+            }
+            Err(e) => warn!("Source validation task failed: {}", e),
+        }
+    }
+
+    let summary = ValidationSummary {
+        checked: results.len(),
+        broken: results.values().filter(|c| !c.valid).count(),
+    };
+
+    for card in cards.iter_mut() {
+        card.source_checks = card
+            .sources
+            .iter()
+            .filter_map(|s| extract_url(s))
+            .filter_map(|url| results.get(&url).cloned())
+            .collect();
+    }
+
+    if summary.broken > 0 {
+        warn!(
+            "Source validation: {} of {} URLs look broken",
+            summary.broken, summary.checked
+        );
+    } else {
+        info!("Source validation: all {} URLs reachable", summary.checked);
+    }
+
+    summary
+}
+
+/// Parse the date part of a publication timestamp ("2025-01-15" or RFC 3339).
+fn parse_published(published: &str) -> Option<NaiveDate> {
+    let date = published.trim().get(..10)?;
+    NaiveDate::parse_from_str(date, "%Y-%m-%d").ok()
+}
+
+/// Best guess at when a source was published: the page's own metadata when
+/// its body was read during validation, else a date in the URL path.
+pub fn source_date(source: &str, check: Option<&SourceCheck>) -> Option<NaiveDate> {
+    if let Some(date) = check
+        .and_then(|c| c.published.as_deref())
+        .and_then(parse_published)
+    {
+        return Some(date);
+    }
+    let url = extract_url(source)?;
+    let caps = URL_DATE_RE.captures(&url)?;
+    NaiveDate::from_ymd_opt(
+        caps[1].parse().ok()?,
+        caps[2].parse().ok()?,
+        caps[3].parse().ok()?,
+    )
+}
+
+/// Why a card's sourcing is too weak to save as-is, or None if it is fine.
+///
+/// A card needs at least one source, and when any of its sources can be
+/// dated, the newest must fall within `recency_days` of `today`. Undated
+/// sources are given the benefit of the doubt. A `recency_days` of 0 turns
+/// the recency check off.
+pub fn source_issue(card: &BriefingCard, recency_days: u32, today: NaiveDate) -> Option<String> {
+    if card.sources.iter().all(|s| s.trim().is_empty()) {
+        return Some("No sources cited".to_string());
+    }
+    if recency_days == 0 {
+        return None;
+    }
+
+    let newest = card
+        .sources
+        .iter()
+        .filter_map(|source| {
+            let check = extract_url(source)
+                .and_then(|url| card.source_checks.iter().find(|c| c.url == url));
+            source_date(source, check)
+        })
+        .max()?;
+    let cutoff = today - chrono::Duration::days(i64::from(recency_days));
+    if newest < cutoff {
+        Some(format!(
+            "Newest source is from {}, older than {} days",
+            newest, recency_days
+        ))
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_url_bare() {
+        assert_eq!(
+            extract_url("https://example.com/article"),
+            Some("https://example.com/article".to_string())
+        );
+    }
+
+    #[test]
+    fn test_extract_url_with_title_and_punctuation() {
+        assert_eq!(
+            extract_url("Rust Blog - https://blog.rust-lang.org/2025/01/01/post.html."),
+            Some("https://blog.rust-lang.org/2025/01/01/post.html".to_string())
+        );
+        assert_eq!(
+            extract_url("[Announcement](https://example.com/news)"),
+            Some("https://example.com/news".to_string())
+        );
+    }
+
+    #[test]
+    fn test_extract_url_none() {
+        assert_eq!(extract_url("Reuters"), None);
+        assert_eq!(extract_url(""), None);
+    }
+
+    #[test]
+    fn test_is_reachable() {
+        assert!(is_reachable(StatusCode::OK));
+        assert!(is_reachable(StatusCode::MOVED_PERMANENTLY));
+        assert!(is_reachable(StatusCode::FORBIDDEN));
+        assert!(!is_reachable(StatusCode::NOT_FOUND));
+        assert!(!is_reachable(StatusCode::GONE));
+        assert!(!is_reachable(StatusCode::INTERNAL_SERVER_ERROR));
+    }
+
+    #[test]
+    fn test_snapshot_not_serialized() {
+        let check = SourceCheck {
+            url: "https://example.com".to_string(),
+            valid: true,
+            status_code: Some(200),
+            final_url: None,
+            error: None,
+            published: None,
+            snapshot: Some("archived text".to_string()),
+        };
+        let json = serde_json::to_string(&check).unwrap();
+        assert!(!json.contains("archived text"));
+        assert!(!json.contains("final_url"));
+        assert!(json.contains("\"status_code\":200"));
+    }
+
+    fn card_with_sources(sources: &[&str]) -> BriefingCard {
+        BriefingCard {
+            title: "Card".to_string(),
+            summary: String::new(),
+            detailed_content: String::new(),
+            sources: sources.iter().map(|s| s.to_string()).collect(),
+            suggested_next: None,
+            relevance: "high".to_string(),
+            topic: "AI".to_string(),
+            image_prompt: None,
+            image_style: None,
+            image_path: None,
+            source_checks: vec![],
+            action_items: vec![],
+            actions: vec![],
+            source_warning: None,
+            rank: None,
+        }
+    }
+
+    #[test]
+    fn test_source_date_from_url_and_metadata() {
+        assert_eq!(
+            source_date("https://blog.example.com/2025/01/15/launch.html", None),
+            NaiveDate::from_ymd_opt(2025, 1, 15)
+        );
+        assert_eq!(
+            source_date("News - https://example.com/posts/2024-11-02-recap", None),
+            NaiveDate::from_ymd_opt(2024, 11, 2)
+        );
+        assert_eq!(source_date("https://example.com/about", None), None);
+
+        let check = SourceCheck {
+            url: "https://example.com/about".to_string(),
+            valid: true,
+            status_code: Some(200),
+            final_url: None,
+            error: None,
+            published: Some("2025-03-01T09:00:00Z".to_string()),
+            snapshot: None,
+        };
+        assert_eq!(
+            source_date("https://example.com/about", Some(&check)),
+            NaiveDate::from_ymd_opt(2025, 3, 1)
+        );
+    }
+
+    #[test]
+    fn test_source_issue() {
+        let today = NaiveDate::from_ymd_opt(2025, 6, 30).unwrap();
+
+        assert_eq!(
+            source_issue(&card_with_sources(&[]), 30, today).as_deref(),
+            Some("No sources cited")
+        );
+
+        let stale = card_with_sources(&[
+            "https://example.com/2025/01/15/a",
+            "https://example.com/2025/03/01/b",
+        ]);
+        assert_eq!(
+            source_issue(&stale, 30, today).as_deref(),
+            Some("Newest source is from 2025-03-01, older than 30 days")
+        );
+        assert!(source_issue(&stale, 0, today).is_none());
+        assert!(source_issue(&stale, 180, today).is_none());
+
+        // One recent source is enough, and undated sources aren't held against a card
+        let fresh = card_with_sources(&[
+            "https://example.com/2025/01/15/a",
+            "https://example.com/2025/06/20/b",
+        ]);
+        assert!(source_issue(&fresh, 30, today).is_none());
+        let undated = card_with_sources(&["https://example.com/about"]);
+        assert!(source_issue(&undated, 30, today).is_none());
+    }
+}
@@ -0,0 +1,340 @@
+//! Statistics for the dashboard and `claudius stats`.
+//!
+//! Everything is aggregated in SQL over `briefings`, `card_fingerprints`,
+//! `api_usage` and `research_logs`. Streaks count days with at least one
+//! research run (weekly and monthly meta-briefings don't count) over the
+//! whole history; the other figures cover the last `days` days.
+
+use crate::research_log::estimate_cost_usd;
+use chrono::NaiveDate;
+use rusqlite::Connection;
+use serde::Serialize;
+
+/// Default window of `get_statistics` and `claudius stats`.
+pub const DEFAULT_DAYS: i64 = 90;
+
+/// Briefings of one week (Monday to Sunday).
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct WeeklyBriefings {
+    /// Monday of the week (YYYY-MM-DD)
+    pub week: String,
+    pub briefings: i64,
+    pub cards: i64,
+    pub tokens: i64,
+}
+
+/// Cards saved for one topic.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct TopicCards {
+    pub topic: String,
+    pub cards: i64,
+    /// Briefings with at least one card on the topic
+    pub briefings: i64,
+}
+
+/// Token spend of research runs.
+#[derive(Debug, Clone, Default, Serialize, PartialEq)]
+pub struct RunSpend {
+    pub runs: i64,
+    pub total_tokens: i64,
+    pub avg_tokens: f64,
+    /// Estimated with [`estimate_cost_usd`] per model
+    pub total_cost_usd: f64,
+    pub avg_cost_usd: f64,
+    pub avg_duration_ms: Option<f64>,
+}
+
+/// Calls of one tool and their share of all tool calls.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct ToolShare {
+    pub tool_name: String,
+    pub calls: i64,
+    /// 0.0-1.0
+    pub share: f64,
+}
+
+/// Dashboard statistics.
+#[derive(Debug, Clone, Serialize)]
+pub struct Statistics {
+    pub days: i64,
+    /// Consecutive days with research up to today (or yesterday, if there
+    /// was none yet today)
+    pub current_streak: i64,
+    pub longest_streak: i64,
+    /// Last day with research (YYYY-MM-DD)
+    pub last_research_day: Option<String>,
+    pub briefings: i64,
+    pub cards: i64,
+    /// Oldest week first
+    pub weekly: Vec<WeeklyBriefings>,
+    /// Most cards first
+    pub topics: Vec<TopicCards>,
+    pub spend: RunSpend,
+    /// Most calls first
+    pub tools: Vec<ToolShare>,
+}
+
+/// Briefings that count as research days.
+const RESEARCH_BRIEFINGS: &str = "(tag IS NULL OR tag NOT IN ('weekly', 'monthly'))";
+
+/// Current and longest streak of consecutive research days, and the last
+/// research day.
+fn streaks(conn: &Connection, today: NaiveDate) -> Result<(i64, i64, Option<String>), String> {
+    // Consecutive days share day number minus row number
+    let query = format!(
+        "WITH days AS (
+             SELECT DISTINCT substr(date, 1, 10) AS day FROM briefings WHERE {}
+         ),
+         runs AS (
+             SELECT day, julianday(day) - ROW_NUMBER() OVER (ORDER BY day) AS run FROM days
+         )
+         SELECT MAX(day), COUNT(*) FROM runs GROUP BY run ORDER BY MAX(day) DESC",
+        RESEARCH_BRIEFINGS
+    );
+    let mut stmt = conn
+        .prepare(&query)
+        .map_err(|e| format!("Failed to prepare streak query: {}", e))?;
+    let runs: Vec<(String, i64)> = stmt
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+        .map_err(|e| format!("Failed to query streaks: {}", e))?
+        .collect::<rusqlite::Result<Vec<_>>>()
+        .map_err(|e| format!("Failed to read streaks: {}", e))?;
+
+    let longest = runs.iter().map(|(_, days)| *days).max().unwrap_or(0);
+    let last_day = runs.first().map(|(day, _)| day.clone());
+    let current = match runs.first() {
+        Some((day, days)) => {
+            let ended = NaiveDate::parse_from_str(day, "%Y-%m-%d").ok();
+            match ended.map(|ended| (today - ended).num_days()) {
+                Some(0 | 1) => *days,
+                _ => 0,
+            }
+        }
+        None => 0,
+    };
+    Ok((current, longest, last_day))
+}
+
+/// Statistics over the `days` days up to `today` (the local date).
+pub fn statistics(conn: &Connection, days: i64, today: NaiveDate) -> Result<Statistics, String> {
+    let days = days.max(1);
+    // First day of the window, compared with the local briefing dates
+    let since = (today - chrono::Duration::days(days - 1))
+        .format("%Y-%m-%d")
+        .to_string();
+    let (current_streak, longest_streak, last_research_day) = streaks(conn, today)?;
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT date(substr(date, 1, 10), 'weekday 0', '-6 days') AS week,
+                    COUNT(*),
+                    COALESCE(SUM(json_array_length(cards)), 0),
+                    COALESCE(SUM(total_tokens), 0)
+             FROM briefings
+             WHERE date >= ?1
+             GROUP BY week
+             ORDER BY week",
+        )
+        .map_err(|e| format!("Failed to prepare weekly query: {}", e))?;
+    let weekly = stmt
+        .query_map([&since], |row| {
+            Ok(WeeklyBriefings {
+                week: row.get(0)?,
+                briefings: row.get(1)?,
+                cards: row.get(2)?,
+                tokens: row.get(3)?,
+            })
+        })
+        .map_err(|e| format!("Failed to query briefings per week: {}", e))?
+        .collect::<rusqlite::Result<Vec<_>>>()
+        .map_err(|e| format!("Failed to read briefings per week: {}", e))?;
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT MIN(topic), COUNT(*), COUNT(DISTINCT briefing_id)
+             FROM card_fingerprints
+             WHERE date >= ?1
+             GROUP BY topic COLLATE NOCASE
+             ORDER BY COUNT(*) DESC, MIN(topic)",
+        )
+        .map_err(|e| format!("Failed to prepare topic query: {}", e))?;
+    let topics = stmt
+        .query_map([&since], |row| {
+            Ok(TopicCards {
+                topic: row.get(0)?,
+                cards: row.get(1)?,
+                briefings: row.get(2)?,
+            })
+        })
+        .map_err(|e| format!("Failed to query cards per topic: {}", e))?
+        .collect::<rusqlite::Result<Vec<_>>>()
+        .map_err(|e| format!("Failed to read cards per topic: {}", e))?;
+
+    // Grouped by model, since the cost estimate depends on it
+    let mut stmt = conn
+        .prepare(
+            "SELECT COALESCE(b.model_used, ''), COUNT(*), SUM(u.tokens), SUM(u.batch_tokens)
+             FROM api_usage u LEFT JOIN briefings b ON b.id = u.briefing_id
+             WHERE u.kind = 'research' AND u.created_at >= ?1
+             GROUP BY b.model_used",
+        )
+        .map_err(|e| format!("Failed to prepare spend query: {}", e))?;
+    let mut spend = RunSpend::default();
+    let rows = stmt
+        .query_map([&since], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, i64>(1)?,
+                row.get::<_, i64>(2)?,
+                row.get::<_, i64>(3)?,
+            ))
+        })
+        .map_err(|e| format!("Failed to query token spend: {}", e))?;
+    for row in rows {
+        let (model, runs, tokens, batch_tokens) =
+            row.map_err(|e| format!("Failed to read token spend: {}", e))?;
+        spend.runs += runs;
+        spend.total_tokens += tokens;
+        spend.total_cost_usd += estimate_cost_usd(&model, tokens, batch_tokens);
+    }
+    if spend.runs > 0 {
+        spend.avg_tokens = spend.total_tokens as f64 / spend.runs as f64;
+        spend.avg_cost_usd = spend.total_cost_usd / spend.runs as f64;
+    }
+
+    let (briefings, cards, avg_duration_ms): (i64, i64, Option<f64>) = conn
+        .query_row(
+            &format!(
+                "SELECT COUNT(*), COALESCE(SUM(json_array_length(cards)), 0),
+                        AVG(CASE WHEN {} THEN research_time_ms END)
+                 FROM briefings WHERE date >= ?1",
+                RESEARCH_BRIEFINGS
+            ),
+            [&since],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        )
+        .map_err(|e| format!("Failed to count briefings: {}", e))?;
+    spend.avg_duration_ms = avg_duration_ms;
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT tool_name, COUNT(*), COUNT(*) * 1.0 / SUM(COUNT(*)) OVER ()
+             FROM research_logs
+             WHERE log_type IN ('tool_call', 'mcp_call') AND tool_name IS NOT NULL
+               AND created_at >= ?1
+             GROUP BY tool_name
+             ORDER BY COUNT(*) DESC, tool_name",
+        )
+        .map_err(|e| format!("Failed to prepare tool query: {}", e))?;
+    let tools = stmt
+        .query_map([&since], |row| {
+            Ok(ToolShare {
+                tool_name: row.get(0)?,
+                calls: row.get(1)?,
+                share: row.get(2)?,
+            })
+        })
+        .map_err(|e| format!("Failed to query tool usage: {}", e))?
+        .collect::<rusqlite::Result<Vec<_>>>()
+        .map_err(|e| format!("Failed to read tool usage: {}", e))?;
+
+    Ok(Statistics {
+        days,
+        current_streak,
+        longest_streak,
+        last_research_day,
+        briefings,
+        cards,
+        weekly,
+        topics,
+        spend,
+        tools,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn save_briefing(conn: &Connection, date: &str, tag: Option<&str>, topics: &[&str]) -> i64 {
+        let cards: Vec<serde_json::Value> = topics
+            .iter()
+            .enumerate()
+            .map(|(i, topic)| serde_json::json!({"title": format!("Card {}", i), "topic": topic}))
+            .collect();
+        conn.execute(
+            "INSERT INTO briefings (date, title, cards, model_used, total_tokens, research_time_ms, tag)
+             VALUES (?1, 'B', ?2, 'claude-sonnet-4-5', 1000, 2000, ?3)",
+            rusqlite::params![date, serde_json::to_string(&cards).unwrap(), tag],
+        )
+        .unwrap();
+        let id = conn.last_insert_rowid();
+        crate::db::index_card_fingerprints(conn, id).unwrap();
+        conn.execute(
+            "INSERT INTO api_usage (profile, kind, briefing_id, tokens, created_at)
+             VALUES ('default', 'research', ?1, 1000, ?2)",
+            rusqlite::params![id, date.replace('T', " ")],
+        )
+        .unwrap();
+        id
+    }
+
+    #[test]
+    fn test_statistics() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(include_str!("schema.sql")).unwrap();
+        let today = NaiveDate::from_ymd_opt(2025, 3, 6).unwrap();
+
+        let empty = statistics(&conn, 30, today).unwrap();
+        assert_eq!((empty.current_streak, empty.longest_streak), (0, 0));
+        assert_eq!(empty.spend, RunSpend::default());
+
+        for day in ["2025-03-01", "2025-03-02", "2025-03-03", "2025-03-05"] {
+            save_briefing(&conn, &format!("{}T07:00:00", day), None, &["Rust", "AI"]);
+        }
+        save_briefing(&conn, "2025-03-05T18:00:00", Some("ad-hoc"), &["rust"]);
+        // Meta-briefings don't bridge the gap on the 4th
+        save_briefing(&conn, "2025-03-04T08:00:00", Some("weekly"), &["Rust"]);
+        conn.execute_batch(
+            "INSERT INTO research_logs (log_type, tool_name, created_at) VALUES
+                 ('tool_call', 'web_search', '2025-03-05 07:00:00'),
+                 ('tool_call', 'web_search', '2025-03-05 07:00:01'),
+                 ('tool_call', 'web_search', '2025-03-05 07:00:02'),
+                 ('mcp_call', 'github:search_issues', '2025-03-05 07:00:03'),
+                 ('api_request', NULL, '2025-03-05 07:00:04')",
+        )
+        .unwrap();
+
+        let stats = statistics(&conn, 30, today).unwrap();
+        assert_eq!(stats.current_streak, 1);
+        assert_eq!(stats.longest_streak, 3);
+        assert_eq!(stats.last_research_day.as_deref(), Some("2025-03-05"));
+        assert_eq!(stats.briefings, 6);
+
+        // 2025-03-01 is a Saturday, so the rest fall in the next week
+        let weeks: Vec<(&str, i64)> = stats
+            .weekly
+            .iter()
+            .map(|w| (w.week.as_str(), w.briefings))
+            .collect();
+        assert_eq!(weeks, vec![("2025-02-24", 2), ("2025-03-03", 4)]);
+
+        // Meta-briefings have no fingerprints; topics match case-insensitively
+        assert_eq!(stats.topics[0].cards, 5);
+        assert_eq!(stats.topics[0].topic.to_lowercase(), "rust");
+        assert_eq!(stats.topics[1].cards, 4);
+
+        assert_eq!(stats.spend.runs, 6);
+        assert_eq!(stats.spend.avg_tokens, 1000.0);
+        assert!(stats.spend.avg_cost_usd > 0.0);
+        assert_eq!(stats.spend.avg_duration_ms, Some(2000.0));
+
+        assert_eq!(stats.tools[0].tool_name, "web_search");
+        assert_eq!(stats.tools[0].share, 0.75);
+        assert_eq!(stats.tools.len(), 2);
+
+        // A day without research breaks the current streak
+        let later = statistics(&conn, 30, today + chrono::Duration::days(1)).unwrap();
+        assert_eq!(later.current_streak, 0);
+    }
+}
@@ -0,0 +1,539 @@
+//! Markdown rendering for the terminal.
+//!
+//! Card text is Markdown written for the app, so printing it raw leaves
+//! literal `**` and `-` markers in `claudius briefings show`. This renders
+//! the subset Claude writes (headings, paragraphs, bullet and numbered
+//! lists, block quotes, code blocks, rules, bold, italic, inline code and
+//! links) with colors, wraps it to the terminal width, and turns links into
+//! clickable OSC 8 hyperlinks when stdout is a terminal.
+
+use colored::Colorize;
+use std::io::IsTerminal;
+
+/// Widest the text is wrapped to, even on a very wide terminal.
+pub const MAX_WIDTH: usize = 100;
+
+/// Narrowest the text is wrapped to, indent included.
+const MIN_WIDTH: usize = 40;
+
+/// Width used when stdout is not a terminal.
+const DEFAULT_WIDTH: usize = 80;
+
+/// How to render Markdown.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RenderOptions {
+    /// Total line width, indent included
+    pub width: usize,
+    /// Spaces before every line
+    pub indent: usize,
+    /// Emit OSC 8 hyperlinks; otherwise link URLs are printed after the text
+    pub hyperlinks: bool,
+}
+
+impl RenderOptions {
+    /// Options for printing to stdout: its width (capped at [`MAX_WIDTH`])
+    /// and hyperlinks only when it is a terminal with colors on.
+    pub fn stdout(indent: usize) -> Self {
+        let terminal = std::io::stdout().is_terminal();
+        let width = terminal_size::terminal_size()
+            .filter(|_| terminal)
+            .map(|(terminal_size::Width(w), _)| w as usize)
+            .unwrap_or(DEFAULT_WIDTH);
+        Self {
+            width: width.clamp(MIN_WIDTH, MAX_WIDTH),
+            indent,
+            hyperlinks: terminal && colored::control::SHOULD_COLORIZE.should_colorize(),
+        }
+    }
+}
+
+/// Wrap `text` in an OSC 8 hyperlink to `url`. Terminals without support
+/// show the text alone.
+pub fn hyperlink(text: &str, url: &str) -> String {
+    format!("\x1b]8;;{}\x1b\\{}\x1b]8;;\x1b\\", url, text)
+}
+
+/// Render Markdown to styled, wrapped lines joined with newlines.
+pub fn render(markdown: &str, options: &RenderOptions) -> String {
+    let mut out: Vec<String> = Vec::new();
+    let mut paragraph: Vec<&str> = Vec::new();
+    let mut in_code = false;
+
+    for line in markdown.lines() {
+        let trimmed = line.trim();
+
+        if trimmed.starts_with("```") {
+            flush_paragraph(&mut paragraph, &mut out, options);
+            in_code = !in_code;
+            continue;
+        }
+        if in_code {
+            out.push(format!(
+                "{}{}",
+                " ".repeat(options.indent + 2),
+                line.dimmed()
+            ));
+            continue;
+        }
+
+        if trimmed.is_empty() {
+            flush_paragraph(&mut paragraph, &mut out, options);
+            if out.last().is_some_and(|l| !l.is_empty()) {
+                out.push(String::new());
+            }
+            continue;
+        }
+
+        if let Some((level, text)) = heading(trimmed) {
+            flush_paragraph(&mut paragraph, &mut out, options);
+            let text = text.trim_end_matches('#').trim();
+            let styled = match level {
+                1 => text.cyan().bold().underline(),
+                2 => text.cyan().bold(),
+                _ => text.yellow().bold(),
+            };
+            out.push(format!("{}{}", " ".repeat(options.indent), styled));
+            continue;
+        }
+
+        if is_rule(trimmed) {
+            flush_paragraph(&mut paragraph, &mut out, options);
+            out.push(format!(
+                "{}{}",
+                " ".repeat(options.indent),
+                "─"
+                    .repeat(options.width.saturating_sub(options.indent))
+                    .dimmed()
+            ));
+            continue;
+        }
+
+        if let Some(text) = trimmed.strip_prefix('>') {
+            flush_paragraph(&mut paragraph, &mut out, options);
+            let bar = "│ ".dimmed().to_string();
+            out.extend(wrap(&parse_inline(text.trim()), &bar, &bar, options));
+            continue;
+        }
+
+        if let Some((marker, text)) = list_item(line) {
+            flush_paragraph(&mut paragraph, &mut out, options);
+            let nesting = " ".repeat((line.len() - line.trim_start().len()) / 2 * 2);
+            let first = format!("{}{} ", nesting, marker);
+            let rest = " ".repeat(first.chars().count());
+            out.extend(wrap(&parse_inline(text), &first, &rest, options));
+            continue;
+        }
+
+        paragraph.push(trimmed);
+    }
+    flush_paragraph(&mut paragraph, &mut out, options);
+
+    while out.last().is_some_and(|l| l.is_empty()) {
+        out.pop();
+    }
+    out.join("\n")
+}
+
+/// Wrap the lines of a paragraph collected so far as one block of text.
+fn flush_paragraph(paragraph: &mut Vec<&str>, out: &mut Vec<String>, options: &RenderOptions) {
+    if !paragraph.is_empty() {
+        let text = paragraph.join(" ");
+        out.extend(wrap(&parse_inline(&text), "", "", options));
+        paragraph.clear();
+    }
+}
+
+/// `# Title` to (1, "Title"); up to six levels.
+fn heading(line: &str) -> Option<(usize, &str)> {
+    let level = line.chars().take_while(|&c| c == '#').count();
+    if !(1..=6).contains(&level) {
+        return None;
+    }
+    line[level..]
+        .strip_prefix(' ')
+        .map(|text| (level, text.trim()))
+}
+
+/// `---`, `***` or `___` on their own.
+fn is_rule(line: &str) -> bool {
+    let compact: String = line.chars().filter(|c| !c.is_whitespace()).collect();
+    compact.len() >= 3
+        && ['-', '*', '_']
+            .iter()
+            .any(|&m| compact.chars().all(|c| c == m))
+}
+
+/// A bullet (`-`, `*`, `+`) or numbered (`1.`, `1)`) list item: the marker
+/// to print and the item text.
+fn list_item(line: &str) -> Option<(String, &str)> {
+    let trimmed = line.trim_start();
+    for bullet in ["- ", "* ", "+ "] {
+        if let Some(text) = trimmed.strip_prefix(bullet) {
+            return Some(("•".to_string(), text.trim()));
+        }
+    }
+    let digits = trimmed.chars().take_while(char::is_ascii_digit).count();
+    if digits > 0 && digits <= 3 {
+        let rest = &trimmed[digits..];
+        if let Some(text) = rest.strip_prefix(". ").or_else(|| rest.strip_prefix(") ")) {
+            return Some((format!("{}.", &trimmed[..digits]), text.trim()));
+        }
+    }
+    None
+}
+
+// ============================================================================
+// Inline formatting
+// ============================================================================
+
+/// Formatting of a run of inline text.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct Style {
+    bold: bool,
+    italic: bool,
+    code: bool,
+    link: Option<String>,
+}
+
+/// A run of text with one style.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Span {
+    text: String,
+    style: Style,
+}
+
+fn push_text(spans: &mut Vec<Span>, text: &str, style: &Style) {
+    if text.is_empty() {
+        return;
+    }
+    match spans.last_mut() {
+        Some(last) if last.style == *style => last.text.push_str(text),
+        _ => spans.push(Span {
+            text: text.to_string(),
+            style: style.clone(),
+        }),
+    }
+}
+
+/// `[text](url)` at the start of `s`: (text, url, bytes consumed).
+fn parse_link(s: &str) -> Option<(&str, &str, usize)> {
+    let close = s.find("](")?;
+    let text = &s[1..close];
+    if text.contains('[') {
+        return None;
+    }
+    let end = s[close + 2..].find(')')? + close + 2;
+    let url = s[close + 2..end].trim();
+    if url.is_empty() || url.contains(char::is_whitespace) {
+        return None;
+    }
+    Some((text, url, end + 1))
+}
+
+/// A bare `http(s)://` URL at the start of `s`, without trailing punctuation.
+fn bare_url(s: &str) -> Option<&str> {
+    if !s.starts_with("https://") && !s.starts_with("http://") {
+        return None;
+    }
+    let end = s
+        .find(|c: char| c.is_whitespace() || c == '<' || c == '>')
+        .unwrap_or(s.len());
+    Some(s[..end].trim_end_matches(['.', ',', ';', ':', '!', '?', ')']))
+}
+
+/// Split inline Markdown into styled spans. Markers without a closing
+/// partner are kept as literal text, as are `_` inside words (snake_case).
+fn parse_inline(text: &str) -> Vec<Span> {
+    let mut spans = Vec::new();
+    let mut style = Style::default();
+    let mut i = 0;
+
+    while i < text.len() {
+        let rest = &text[i..];
+        let c = rest.chars().next().unwrap_or_default();
+        let prev = text[..i].chars().next_back();
+
+        if c == '\\' {
+            if let Some(next) = rest[1..]
+                .chars()
+                .next()
+                .filter(|n| n.is_ascii_punctuation())
+            {
+                push_text(&mut spans, &next.to_string(), &style);
+                i += 1 + next.len_utf8();
+                continue;
+            }
+        }
+
+        if c == '`' {
+            if let Some(end) = rest[1..].find('`') {
+                let code = Style {
+                    code: true,
+                    ..style.clone()
+                };
+                push_text(&mut spans, &rest[1..end + 1], &code);
+                i += end + 2;
+                continue;
+            }
+        }
+
+        if c == '[' && style.link.is_none() {
+            if let Some((label, url, consumed)) = parse_link(rest) {
+                let linked = Style {
+                    link: Some(url.to_string()),
+                    ..style.clone()
+                };
+                for span in parse_inline(label) {
+                    let nested = Style {
+                        link: linked.link.clone(),
+                        bold: span.style.bold || linked.bold,
+                        italic: span.style.italic || linked.italic,
+                        code: span.style.code,
+                    };
+                    push_text(&mut spans, &span.text, &nested);
+                }
+                i += consumed;
+                continue;
+            }
+        }
+
+        if style.link.is_none() && prev.is_none_or(|p| !p.is_alphanumeric()) {
+            if let Some(url) = bare_url(rest) {
+                let linked = Style {
+                    link: Some(url.to_string()),
+                    ..style.clone()
+                };
+                push_text(&mut spans, url, &linked);
+                i += url.len();
+                continue;
+            }
+        }
+
+        let double = rest.starts_with("**") || rest.starts_with("__");
+        if double {
+            let marker = &rest[..2];
+            let opens = !style.bold && rest[2..].contains(marker);
+            if style.bold || opens {
+                style.bold = !style.bold;
+                i += 2;
+                continue;
+            }
+        } else if c == '*' || c == '_' {
+            let next = rest[1..].chars().next();
+            let opens = !style.italic
+                && next.is_some_and(|n| !n.is_whitespace())
+                && rest[1..].contains(c)
+                && (c == '*' || prev.is_none_or(|p| !p.is_alphanumeric()));
+            let closes = style.italic
+                && prev.is_some_and(|p| !p.is_whitespace())
+                && (c == '*' || next.is_none_or(|n| !n.is_alphanumeric()));
+            if opens || closes {
+                style.italic = !style.italic;
+                i += 1;
+                continue;
+            }
+        }
+
+        push_text(&mut spans, &c.to_string(), &style);
+        i += c.len_utf8();
+    }
+    spans
+}
+
+fn paint(text: &str, style: &Style, options: &RenderOptions) -> String {
+    let mut styled = text.normal();
+    if style.code {
+        styled = styled.yellow();
+    }
+    if style.bold {
+        styled = styled.bold();
+    }
+    if style.italic {
+        styled = styled.italic();
+    }
+    if style.link.is_some() {
+        styled = styled.blue().underline();
+    }
+    let styled = styled.to_string();
+    match &style.link {
+        Some(url) if options.hyperlinks => hyperlink(&styled, url),
+        _ => styled,
+    }
+}
+
+/// Word-wrap styled spans. `first` and `rest` prefix the first and the
+/// following lines (after the indent) and count toward the width. Without
+/// hyperlinks, a link's URL is printed after its text unless they match.
+fn wrap(spans: &[Span], first: &str, rest: &str, options: &RenderOptions) -> Vec<String> {
+    // Words as lists of (text, style) pieces, so styles can change mid-word
+    let plain = Style::default();
+    let mut words: Vec<Vec<(String, &Style)>> = vec![Vec::new()];
+    for (n, span) in spans.iter().enumerate() {
+        let mut pieces = span.text.split(' ').peekable();
+        while let Some(piece) = pieces.next() {
+            if let Some(word) = words.last_mut().filter(|_| !piece.is_empty()) {
+                word.push((piece.to_string(), &span.style));
+            }
+            if pieces.peek().is_some() && words.last().is_some_and(|w| !w.is_empty()) {
+                words.push(Vec::new());
+            }
+        }
+        if let Some(url) = &span.style.link {
+            let ends_link = spans
+                .get(n + 1)
+                .is_none_or(|next| next.style.link != span.style.link);
+            if ends_link && !options.hyperlinks && span.text.trim() != url {
+                if let Some(word) = words.last_mut() {
+                    word.push((format!(" ({})", url), &plain));
+                }
+            }
+        }
+    }
+
+    let rendered = words.iter().filter(|word| !word.is_empty()).map(|word| {
+        let width: usize = word.iter().map(|(t, _)| t.chars().count()).sum();
+        let text: String = word.iter().map(|(t, s)| paint(t, s, options)).collect();
+        (width, text)
+    });
+
+    let indent = " ".repeat(options.indent);
+    let prefix_width = |prefix: &str| strip_ansi(prefix).chars().count();
+    let mut lines = Vec::new();
+    let mut line = format!("{}{}", indent, first);
+    let mut used = options.indent + prefix_width(first);
+    let mut empty = true;
+    for (width, text) in rendered {
+        if !empty && used + 1 + width > options.width {
+            lines.push(line);
+            line = format!("{}{}", indent, rest);
+            used = options.indent + prefix_width(rest);
+            empty = true;
+        }
+        if !empty {
+            line.push(' ');
+            used += 1;
+        }
+        line.push_str(&text);
+        used += width;
+        empty = false;
+    }
+    lines.push(line);
+    lines
+}
+
+/// `text` without ANSI color codes, for measuring prefixes.
+fn strip_ansi(text: &str) -> String {
+    let mut out = String::new();
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            for c in chars.by_ref() {
+                if c.is_ascii_alphabetic() {
+                    break;
+                }
+            }
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn plain(width: usize) -> RenderOptions {
+        colored::control::set_override(false);
+        RenderOptions {
+            width,
+            indent: 0,
+            hyperlinks: false,
+        }
+    }
+
+    #[test]
+    fn test_inline_markers() {
+        let spans = parse_inline("A **bold** and *italic* `code_x` word, snake_case_name");
+        let bold: Vec<&str> = spans
+            .iter()
+            .filter(|s| s.style.bold)
+            .map(|s| s.text.as_str())
+            .collect();
+        assert_eq!(bold, vec!["bold"]);
+        assert!(spans.iter().any(|s| s.style.italic && s.text == "italic"));
+        assert!(spans.iter().any(|s| s.style.code && s.text == "code_x"));
+        assert!(spans.iter().any(|s| s.text.contains("snake_case_name")));
+
+        // Unclosed markers stay literal
+        let spans = parse_inline("2 * 3 = 6 and **not bold");
+        assert_eq!(spans.len(), 1);
+        assert_eq!(spans[0].text, "2 * 3 = 6 and **not bold");
+        assert_eq!(parse_inline(r"\*literal\*")[0].text, "*literal*");
+    }
+
+    #[test]
+    fn test_links() {
+        let spans = parse_inline("See [the post](https://example.com/a) or https://example.com/b.");
+        let links: Vec<(&str, &str)> = spans
+            .iter()
+            .filter_map(|s| s.style.link.as_deref().map(|l| (s.text.as_str(), l)))
+            .collect();
+        assert_eq!(
+            links,
+            vec![
+                ("the post", "https://example.com/a"),
+                ("https://example.com/b", "https://example.com/b"),
+            ]
+        );
+
+        let text = render("See [the post](https://example.com/a).", &plain(80));
+        assert_eq!(text, "See the post (https://example.com/a).");
+
+        let options = RenderOptions {
+            hyperlinks: true,
+            ..plain(80)
+        };
+        let text = render("[post](https://example.com/a)", &options);
+        assert_eq!(
+            text,
+            "\x1b]8;;https://example.com/a\x1b\\post\x1b]8;;\x1b\\"
+        );
+    }
+
+    #[test]
+    fn test_blocks() {
+        let markdown = "## Key points\n\n- **First** point\n- Second\n  - Nested\n1. One\n\n> Quoted\n\n---\n\n```\nlet x = 1;\n```\nDone.";
+        let text = render(markdown, &plain(40));
+        assert_eq!(
+            text,
+            "Key points\n\n• First point\n• Second\n  • Nested\n1. One\n\n│ Quoted\n\n────────────────────────────────────────\n\n  let x = 1;\nDone."
+        );
+    }
+
+    #[test]
+    fn test_wrapping() {
+        let options = RenderOptions {
+            indent: 3,
+            ..plain(20)
+        };
+        let text = render("- one two three four five six seven", &options);
+        for line in text.lines() {
+            assert!(line.chars().count() <= 20, "too wide: {:?}", line);
+        }
+        assert_eq!(text, "   • one two three\n     four five six\n     seven");
+
+        // Paragraph lines are joined before wrapping
+        assert_eq!(render("a\nb\n\nc", &plain(40)), "a b\n\nc");
+        // A word longer than the width gets a line of its own
+        assert_eq!(
+            render("x https://example.com/a/very/long/path y", &plain(20)),
+            "x\nhttps://example.com/a/very/long/path\ny"
+        );
+    }
+
+    #[test]
+    fn test_strip_ansi() {
+        assert_eq!(strip_ansi("\x1b[2m│ \x1b[0m"), "│ ");
+    }
+}
@@ -0,0 +1,930 @@
+//! Built-in GitHub tools.
+//!
+//! All requests go through `github_cache`, which caches responses and keeps
+//! calls within the GitHub rate limit.
+
+use super::{ToolDefinition, ToolOutput, USER_AGENT};
+use crate::github_cache;
+use reqwest::Client;
+use serde_json::json;
+
+pub fn definitions() -> Vec<ToolDefinition> {
+    vec![
+        ToolDefinition {
+            name: "get_github_activity".to_string(),
+            description: "Get recent activity from a GitHub repository including recent commits, PRs, and issues. Use this when researching topics related to open source projects or specific GitHub repositories.".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "owner": {
+                        "type": "string",
+                        "description": "The GitHub repository owner (username or organization)"
+                    },
+                    "repo": {
+                        "type": "string",
+                        "description": "The GitHub repository name"
+                    },
+                    "activity_type": {
+                        "type": "string",
+                        "enum": ["commits", "pulls", "issues", "releases"],
+                        "description": "Type of activity to fetch"
+                    }
+                },
+                "required": ["owner", "repo", "activity_type"]
+            }),
+        },
+        ToolDefinition {
+            name: "search_github_repos".to_string(),
+            description: "Search GitHub repositories by keyword. Use this to find the projects behind a topic when you don't know the exact repository.".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "query": {
+                        "type": "string",
+                        "description": "Search keywords, optionally with GitHub qualifiers (e.g. \"vector database language:rust\")"
+                    },
+                    "sort": {
+                        "type": "string",
+                        "enum": ["stars", "updated"],
+                        "description": "Sort by stars (default) or most recently updated"
+                    }
+                },
+                "required": ["query"]
+            }),
+        },
+        ToolDefinition {
+            name: "get_github_issue".to_string(),
+            description: "Get a specific GitHub issue or pull request with its description and recent comments. Use this to understand the discussion behind an important change or bug.".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "owner": {
+                        "type": "string",
+                        "description": "The GitHub repository owner (username or organization)"
+                    },
+                    "repo": {
+                        "type": "string",
+                        "description": "The GitHub repository name"
+                    },
+                    "number": {
+                        "type": "integer",
+                        "description": "The issue or pull request number"
+                    }
+                },
+                "required": ["owner", "repo", "number"]
+            }),
+        },
+        ToolDefinition {
+            name: "get_github_discussions".to_string(),
+            description: "Get recent GitHub Discussions (announcements, RFCs, Q&A) for a repository. Requires a GitHub token.".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "owner": {
+                        "type": "string",
+                        "description": "The GitHub repository owner (username or organization)"
+                    },
+                    "repo": {
+                        "type": "string",
+                        "description": "The GitHub repository name"
+                    }
+                },
+                "required": ["owner", "repo"]
+            }),
+        },
+        ToolDefinition {
+            name: "get_github_trending".to_string(),
+            description: "Get trending GitHub repositories: the most-starred repositories created recently, optionally for one language.".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "language": {
+                        "type": "string",
+                        "description": "Programming language to filter by (e.g. \"rust\", \"python\")"
+                    },
+                    "since": {
+                        "type": "string",
+                        "enum": ["daily", "weekly", "monthly"],
+                        "description": "How far back to look for new repositories (default weekly)"
+                    }
+                }
+            }),
+        },
+        ToolDefinition {
+            name: "get_github_commit_diff".to_string(),
+            description: "Summarize the diff of a specific commit: message, changed files with line counts, and the start of each patch. Use this to see what a notable commit actually changed.".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "owner": {
+                        "type": "string",
+                        "description": "The GitHub repository owner (username or organization)"
+                    },
+                    "repo": {
+                        "type": "string",
+                        "description": "The GitHub repository name"
+                    },
+                    "sha": {
+                        "type": "string",
+                        "description": "The commit SHA (full or abbreviated)"
+                    }
+                },
+                "required": ["owner", "repo", "sha"]
+            }),
+        },
+    ]
+}
+
+/// Content-cache key for a GitHub tool call.
+pub fn cache_key(tool_name: &str, input: &serde_json::Value) -> Option<String> {
+    let field = |name: &str| input.get(name).and_then(|v| v.as_str());
+    match tool_name {
+        "get_github_activity" => Some(format!(
+            "{}/{}/{}",
+            field("owner")?,
+            field("repo")?,
+            field("activity_type")?
+        )),
+        "search_github_repos" => Some(format!(
+            "search/{}/{}",
+            field("query")?,
+            field("sort").unwrap_or("stars")
+        )),
+        "get_github_issue" => Some(format!(
+            "{}/{}/issues/{}",
+            field("owner")?,
+            field("repo")?,
+            input.get("number")?.as_u64()?
+        )),
+        "get_github_discussions" => Some(format!(
+            "{}/{}/discussions",
+            field("owner")?,
+            field("repo")?
+        )),
+        "get_github_trending" => Some(format!(
+            "trending/{}/{}",
+            field("language").unwrap_or("all"),
+            field("since").unwrap_or("weekly")
+        )),
+        "get_github_commit_diff" => Some(format!(
+            "{}/{}/commit/{}",
+            field("owner")?,
+            field("repo")?,
+            field("sha")?
+        )),
+        _ => None,
+    }
+}
+
+/// Execute a GitHub tool.
+pub async fn execute(
+    client: &Client,
+    tool_name: &str,
+    input: &serde_json::Value,
+    github_token: Option<&str>,
+) -> Result<ToolOutput, String> {
+    let (text, from_cache) = match tool_name {
+        "get_github_activity" => {
+            let owner = input
+                .get("owner")
+                .and_then(|v| v.as_str())
+                .ok_or("Missing owner")?;
+            let repo = input
+                .get("repo")
+                .and_then(|v| v.as_str())
+                .ok_or("Missing repo")?;
+            let activity_type = input
+                .get("activity_type")
+                .and_then(|v| v.as_str())
+                .ok_or("Missing activity_type")?;
+
+            execute_github_activity(client, owner, repo, activity_type, github_token).await
+        }
+        "search_github_repos" => {
+            let query = input
+                .get("query")
+                .and_then(|v| v.as_str())
+                .ok_or("Missing query")?;
+            let sort = input
+                .get("sort")
+                .and_then(|v| v.as_str())
+                .unwrap_or("stars");
+            execute_github_search(client, query, sort, github_token).await
+        }
+        "get_github_issue" => {
+            let owner = input
+                .get("owner")
+                .and_then(|v| v.as_str())
+                .ok_or("Missing owner")?;
+            let repo = input
+                .get("repo")
+                .and_then(|v| v.as_str())
+                .ok_or("Missing repo")?;
+            let number = input
+                .get("number")
+                .and_then(|v| v.as_u64())
+                .ok_or("Missing number")?;
+            execute_github_issue(client, owner, repo, number, github_token).await
+        }
+        "get_github_discussions" => {
+            let owner = input
+                .get("owner")
+                .and_then(|v| v.as_str())
+                .ok_or("Missing owner")?;
+            let repo = input
+                .get("repo")
+                .and_then(|v| v.as_str())
+                .ok_or("Missing repo")?;
+            execute_github_discussions(client, owner, repo, github_token).await
+        }
+        "get_github_trending" => {
+            let language = input.get("language").and_then(|v| v.as_str());
+            let since = input
+                .get("since")
+                .and_then(|v| v.as_str())
+                .unwrap_or("weekly");
+            execute_github_trending(client, language, since, github_token).await
+        }
+        "get_github_commit_diff" => {
+            let owner = input
+                .get("owner")
+                .and_then(|v| v.as_str())
+                .ok_or("Missing owner")?;
+            let repo = input
+                .get("repo")
+                .and_then(|v| v.as_str())
+                .ok_or("Missing repo")?;
+            let sha = input
+                .get("sha")
+                .and_then(|v| v.as_str())
+                .ok_or("Missing sha")?;
+            execute_github_commit_diff(client, owner, repo, sha, github_token).await
+        }
+        _ => return Err(format!("Unknown tool: {}", tool_name)),
+    }?;
+    Ok(ToolOutput { text, from_cache })
+}
+
+/// Fetch GitHub activity (commits, PRs, issues, or releases).
+///
+/// Returns the formatted activity and whether it was served from cache.
+async fn execute_github_activity(
+    client: &Client,
+    owner: &str,
+    repo: &str,
+    activity_type: &str,
+    github_token: Option<&str>,
+) -> Result<(String, bool), String> {
+    let endpoint = match activity_type {
+        "commits" => format!(
+            "https://api.github.com/repos/{}/{}/commits?per_page=10",
+            owner, repo
+        ),
+        "pulls" => format!(
+            "https://api.github.com/repos/{}/{}/pulls?state=all&per_page=10",
+            owner, repo
+        ),
+        "issues" => format!(
+            "https://api.github.com/repos/{}/{}/issues?state=all&per_page=10",
+            owner, repo
+        ),
+        "releases" => format!(
+            "https://api.github.com/repos/{}/{}/releases?per_page=5",
+            owner, repo
+        ),
+        _ => return Err(format!("Unknown activity type: {}", activity_type)),
+    };
+
+    let fetched = github_cache::fetch_json(client, &endpoint, USER_AGENT, github_token).await?;
+    let data = fetched.data;
+
+    // Format the response based on activity type
+    let formatted = match activity_type {
+        "commits" => format_github_commits(&data),
+        "pulls" => format_github_pulls(&data),
+        "issues" => format_github_issues(&data),
+        "releases" => format_github_releases(&data),
+        _ => data.to_string(),
+    };
+
+    Ok((formatted, fetched.from_cache))
+}
+
+fn format_github_commits(data: &serde_json::Value) -> String {
+    let commits = data.as_array().map(|arr| {
+        arr.iter()
+            .take(10)
+            .filter_map(|c| {
+                let sha = c.get("sha")?.as_str()?.get(..7)?;
+                let message = c.get("commit")?.get("message")?.as_str()?;
+                let author = c.get("commit")?.get("author")?.get("name")?.as_str()?;
+                let date = c.get("commit")?.get("author")?.get("date")?.as_str()?;
+                Some(format!(
+                    "- {} by {} ({}): {}",
+                    sha,
+                    author,
+                    &date[..10],
+                    message.lines().next().unwrap_or("")
+                ))
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    });
+    commits.unwrap_or_else(|| "No commits found".to_string())
+}
+
+fn format_github_pulls(data: &serde_json::Value) -> String {
+    let pulls = data.as_array().map(|arr| {
+        arr.iter()
+            .take(10)
+            .filter_map(|p| {
+                let number = p.get("number")?.as_i64()?;
+                let title = p.get("title")?.as_str()?;
+                let state = p.get("state")?.as_str()?;
+                let user = p.get("user")?.get("login")?.as_str()?;
+                Some(format!("- #{} [{}] by {}: {}", number, state, user, title))
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    });
+    pulls.unwrap_or_else(|| "No pull requests found".to_string())
+}
+
+fn format_github_issues(data: &serde_json::Value) -> String {
+    let issues = data.as_array().map(|arr| {
+        arr.iter()
+            .take(10)
+            .filter_map(|i| {
+                let number = i.get("number")?.as_i64()?;
+                let title = i.get("title")?.as_str()?;
+                let state = i.get("state")?.as_str()?;
+                let user = i.get("user")?.get("login")?.as_str()?;
+                Some(format!("- #{} [{}] by {}: {}", number, state, user, title))
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    });
+    issues.unwrap_or_else(|| "No issues found".to_string())
+}
+
+fn format_github_releases(data: &serde_json::Value) -> String {
+    let releases = data.as_array().map(|arr| {
+        arr.iter()
+            .take(5)
+            .filter_map(|r| {
+                let tag = r.get("tag_name")?.as_str()?;
+                let name = r.get("name")?.as_str().unwrap_or(tag);
+                let date = r.get("published_at")?.as_str()?;
+                let prerelease = r.get("prerelease")?.as_bool().unwrap_or(false);
+                let suffix = if prerelease { " (prerelease)" } else { "" };
+                Some(format!("- {} - {}{} ({})", tag, name, suffix, &date[..10]))
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    });
+    releases.unwrap_or_else(|| "No releases found".to_string())
+}
+
+/// GitHub API URL with query parameters encoded.
+fn github_api_url(path: &str, params: &[(&str, &str)]) -> Result<String, String> {
+    reqwest::Url::parse_with_params(&format!("https://api.github.com/{}", path), params)
+        .map(|url| url.to_string())
+        .map_err(|e| format!("Invalid GitHub request: {}", e))
+}
+
+/// Search repositories by keyword.
+async fn execute_github_search(
+    client: &Client,
+    query: &str,
+    sort: &str,
+    github_token: Option<&str>,
+) -> Result<(String, bool), String> {
+    if !matches!(sort, "stars" | "updated") {
+        return Err(format!("Unknown sort: {}", sort));
+    }
+    let url = github_api_url(
+        "search/repositories",
+        &[("q", query), ("sort", sort), ("per_page", "10")],
+    )?;
+    let fetched = github_cache::fetch_json(client, &url, USER_AGENT, github_token).await?;
+    Ok((format_github_repos(&fetched.data), fetched.from_cache))
+}
+
+/// Search query for repositories created since the trending window started.
+fn trending_query(
+    language: Option<&str>,
+    since: &str,
+    today: chrono::NaiveDate,
+) -> Result<String, String> {
+    let days = match since {
+        "daily" => 1,
+        "weekly" => 7,
+        "monthly" => 30,
+        _ => return Err(format!("Unknown trending period: {}", since)),
+    };
+    let mut query = format!(
+        "created:>={}",
+        (today - chrono::Duration::days(days)).format("%Y-%m-%d")
+    );
+    if let Some(language) = language.map(str::trim).filter(|l| !l.is_empty()) {
+        query.push_str(&format!(" language:{}", language));
+    }
+    Ok(query)
+}
+
+/// Approximate GitHub's trending page with the search API: the most-starred
+/// repositories created in the last day, week, or month.
+async fn execute_github_trending(
+    client: &Client,
+    language: Option<&str>,
+    since: &str,
+    github_token: Option<&str>,
+) -> Result<(String, bool), String> {
+    let query = trending_query(language, since, chrono::Utc::now().date_naive())?;
+    let url = github_api_url(
+        "search/repositories",
+        &[
+            ("q", query.as_str()),
+            ("sort", "stars"),
+            ("order", "desc"),
+            ("per_page", "10"),
+        ],
+    )?;
+    let fetched = github_cache::fetch_json(client, &url, USER_AGENT, github_token).await?;
+    Ok((format_github_repos(&fetched.data), fetched.from_cache))
+}
+
+/// Fetch an issue or pull request along with its most recent comments.
+async fn execute_github_issue(
+    client: &Client,
+    owner: &str,
+    repo: &str,
+    number: u64,
+    github_token: Option<&str>,
+) -> Result<(String, bool), String> {
+    // The issues endpoint also serves pull requests
+    let issue_url = format!(
+        "https://api.github.com/repos/{}/{}/issues/{}",
+        owner, repo, number
+    );
+    let issue = github_cache::fetch_json(client, &issue_url, USER_AGENT, github_token).await?;
+
+    let comment_count = issue.data["comments"].as_u64().unwrap_or(0);
+    let comments = if comment_count > 0 {
+        // Comments are oldest first; jump to the last page of 10
+        let page = comment_count.div_ceil(10).to_string();
+        let url = github_api_url(
+            &format!("repos/{}/{}/issues/{}/comments", owner, repo, number),
+            &[("per_page", "10"), ("page", page.as_str())],
+        )?;
+        Some(github_cache::fetch_json(client, &url, USER_AGENT, github_token).await?)
+    } else {
+        None
+    };
+
+    let from_cache = issue.from_cache && comments.as_ref().is_none_or(|c| c.from_cache);
+    let comments = comments.map(|c| c.data).unwrap_or(serde_json::Value::Null);
+    Ok((format_github_issue(&issue.data, &comments), from_cache))
+}
+
+/// GraphQL query for a repository's most recently updated discussions.
+const DISCUSSIONS_QUERY: &str = r#"query($owner: String!, $repo: String!) {
+  repository(owner: $owner, name: $repo) {
+    discussions(first: 10, orderBy: {field: UPDATED_AT, direction: DESC}) {
+      nodes {
+        number
+        title
+        url
+        updatedAt
+        author { login }
+        category { name }
+        answer { id }
+        upvoteCount
+        comments { totalCount }
+        bodyText
+      }
+    }
+  }
+}"#;
+
+/// Fetch recent GitHub Discussions (GraphQL only, so a token is required).
+async fn execute_github_discussions(
+    client: &Client,
+    owner: &str,
+    repo: &str,
+    github_token: Option<&str>,
+) -> Result<(String, bool), String> {
+    let fetched = github_cache::fetch_graphql(
+        client,
+        &format!(
+            "https://api.github.com/graphql/discussions/{}/{}",
+            owner, repo
+        ),
+        DISCUSSIONS_QUERY,
+        json!({ "owner": owner, "repo": repo }),
+        USER_AGENT,
+        github_token,
+    )
+    .await?;
+    Ok((format_github_discussions(&fetched.data), fetched.from_cache))
+}
+
+/// Fetch a single commit and summarize its diff.
+async fn execute_github_commit_diff(
+    client: &Client,
+    owner: &str,
+    repo: &str,
+    sha: &str,
+    github_token: Option<&str>,
+) -> Result<(String, bool), String> {
+    let url = format!(
+        "https://api.github.com/repos/{}/{}/commits/{}",
+        owner, repo, sha
+    );
+    let fetched = github_cache::fetch_json(client, &url, USER_AGENT, github_token).await?;
+    Ok((format_github_commit_diff(&fetched.data), fetched.from_cache))
+}
+
+fn format_github_repos(data: &serde_json::Value) -> String {
+    let repos = data["items"].as_array().map(|arr| {
+        arr.iter()
+            .take(10)
+            .filter_map(|r| {
+                let name = r.get("full_name")?.as_str()?;
+                let stars = r.get("stargazers_count")?.as_u64()?;
+                let language = r["language"].as_str().unwrap_or("unknown");
+                let description = r["description"].as_str().unwrap_or("");
+                let updated = r["pushed_at"].as_str().and_then(|d| d.get(..10));
+                Some(format!(
+                    "- {} (★{}, {}{}): {}",
+                    name,
+                    stars,
+                    language,
+                    updated
+                        .map(|d| format!(", updated {}", d))
+                        .unwrap_or_default(),
+                    description
+                ))
+            })
+            .collect::<Vec<_>>()
+    });
+    match repos {
+        Some(repos) if !repos.is_empty() => repos.join("\n"),
+        _ => "No repositories found".to_string(),
+    }
+}
+
+/// Maximum characters kept from an issue body and from each comment.
+const GITHUB_ISSUE_BODY_MAX_CHARS: usize = 1500;
+const GITHUB_COMMENT_MAX_CHARS: usize = 500;
+
+fn truncate_chars(text: &str, max_chars: usize) -> String {
+    let text = text.trim();
+    if text.chars().count() <= max_chars {
+        return text.to_string();
+    }
+    let truncated: String = text.chars().take(max_chars).collect();
+    format!("{}…", truncated.trim_end())
+}
+
+fn format_github_issue(issue: &serde_json::Value, comments: &serde_json::Value) -> String {
+    let kind = if issue.get("pull_request").is_some() {
+        "Pull request"
+    } else {
+        "Issue"
+    };
+    let state = if issue["pull_request"]["merged_at"].is_string() {
+        "merged"
+    } else {
+        issue["state"].as_str().unwrap_or("unknown")
+    };
+    let labels: Vec<&str> = issue["labels"]
+        .as_array()
+        .map(|labels| labels.iter().filter_map(|l| l["name"].as_str()).collect())
+        .unwrap_or_default();
+
+    let mut lines = vec![format!(
+        "{} #{} [{}] by {} ({}): {}",
+        kind,
+        issue["number"].as_u64().unwrap_or(0),
+        state,
+        issue["user"]["login"].as_str().unwrap_or("unknown"),
+        issue["created_at"]
+            .as_str()
+            .and_then(|d| d.get(..10))
+            .unwrap_or("unknown date"),
+        issue["title"].as_str().unwrap_or("(untitled)")
+    )];
+    if !labels.is_empty() {
+        lines.push(format!("Labels: {}", labels.join(", ")));
+    }
+    if let Some(url) = issue["html_url"].as_str() {
+        lines.push(format!("URL: {}", url));
+    }
+    if let Some(body) = issue["body"].as_str().filter(|b| !b.trim().is_empty()) {
+        lines.push(String::new());
+        lines.push(truncate_chars(body, GITHUB_ISSUE_BODY_MAX_CHARS));
+    }
+
+    let comments: Vec<String> = comments
+        .as_array()
+        .map(|arr| {
+            arr.iter()
+                .filter_map(|c| {
+                    let user = c.get("user")?.get("login")?.as_str()?;
+                    let date = c.get("created_at")?.as_str()?.get(..10)?;
+                    let body = c.get("body")?.as_str()?;
+                    Some(format!(
+                        "- {} ({}): {}",
+                        user,
+                        date,
+                        truncate_chars(body, GITHUB_COMMENT_MAX_CHARS)
+                    ))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    if !comments.is_empty() {
+        lines.push(String::new());
+        lines.push(format!(
+            "Recent comments ({} total):",
+            issue["comments"].as_u64().unwrap_or(comments.len() as u64)
+        ));
+        lines.extend(comments);
+    }
+
+    lines.join("\n")
+}
+
+fn format_github_discussions(data: &serde_json::Value) -> String {
+    let discussions = data["repository"]["discussions"]["nodes"]
+        .as_array()
+        .map(|arr| {
+            arr.iter()
+                .filter_map(|d| {
+                    let number = d.get("number")?.as_u64()?;
+                    let title = d.get("title")?.as_str()?;
+                    let category = d["category"]["name"].as_str().unwrap_or("General");
+                    let author = d["author"]["login"].as_str().unwrap_or("ghost");
+                    let updated = d["updatedAt"]
+                        .as_str()
+                        .and_then(|u| u.get(..10))
+                        .unwrap_or("");
+                    let answered = if d["answer"].is_object() {
+                        ", answered"
+                    } else {
+                        ""
+                    };
+                    let mut line = format!(
+                        "- #{} [{}] by {} ({}, {} comments, {} upvotes{}): {}",
+                        number,
+                        category,
+                        author,
+                        updated,
+                        d["comments"]["totalCount"].as_u64().unwrap_or(0),
+                        d["upvoteCount"].as_u64().unwrap_or(0),
+                        answered,
+                        title
+                    );
+                    if let Some(body) = d["bodyText"].as_str().filter(|b| !b.trim().is_empty()) {
+                        line.push_str(&format!(
+                            "\n  {}",
+                            truncate_chars(&body.replace('\n', " "), 200)
+                        ));
+                    }
+                    Some(line)
+                })
+                .collect::<Vec<_>>()
+        });
+    match discussions {
+        Some(discussions) if !discussions.is_empty() => discussions.join("\n"),
+        _ => "No discussions found (the repository may not have Discussions enabled)".to_string(),
+    }
+}
+
+/// Maximum files listed and patch lines shown per file in a commit diff summary.
+const COMMIT_DIFF_MAX_FILES: usize = 20;
+const COMMIT_DIFF_PATCH_LINES: usize = 12;
+
+fn format_github_commit_diff(data: &serde_json::Value) -> String {
+    let sha = data["sha"]
+        .as_str()
+        .and_then(|s| s.get(..7))
+        .unwrap_or("unknown");
+    let message = data["commit"]["message"].as_str().unwrap_or("");
+    let author = data["commit"]["author"]["name"]
+        .as_str()
+        .unwrap_or("unknown");
+    let date = data["commit"]["author"]["date"]
+        .as_str()
+        .and_then(|d| d.get(..10))
+        .unwrap_or("unknown date");
+
+    let mut lines = vec![
+        format!("Commit {} by {} ({})", sha, author, date),
+        message.trim().to_string(),
+        String::new(),
+        format!(
+            "{} files changed, +{} -{}",
+            data["files"].as_array().map_or(0, |f| f.len()),
+            data["stats"]["additions"].as_u64().unwrap_or(0),
+            data["stats"]["deletions"].as_u64().unwrap_or(0)
+        ),
+    ];
+
+    let files = data["files"]
+        .as_array()
+        .map(Vec::as_slice)
+        .unwrap_or_default();
+    for file in files.iter().take(COMMIT_DIFF_MAX_FILES) {
+        lines.push(format!(
+            "\n{} ({}, +{} -{})",
+            file["filename"].as_str().unwrap_or("?"),
+            file["status"].as_str().unwrap_or("modified"),
+            file["additions"].as_u64().unwrap_or(0),
+            file["deletions"].as_u64().unwrap_or(0)
+        ));
+        if let Some(patch) = file["patch"].as_str() {
+            let patch_lines: Vec<&str> = patch.lines().collect();
+            lines.extend(
+                patch_lines
+                    .iter()
+                    .take(COMMIT_DIFF_PATCH_LINES)
+                    .map(|l| format!("  {}", l)),
+            );
+            if patch_lines.len() > COMMIT_DIFF_PATCH_LINES {
+                lines.push(format!(
+                    "  ... {} more lines",
+                    patch_lines.len() - COMMIT_DIFF_PATCH_LINES
+                ));
+            }
+        }
+    }
+    if files.len() > COMMIT_DIFF_MAX_FILES {
+        lines.push(format!(
+            "\n... and {} more files",
+            files.len() - COMMIT_DIFF_MAX_FILES
+        ));
+    }
+
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_github_commits() {
+        let data = json!([
+            {
+                "sha": "abc123def456789",
+                "commit": {
+                    "message": "Fix bug in parser",
+                    "author": {
+                        "name": "John Doe",
+                        "date": "2025-01-15T10:00:00Z"
+                    }
+                }
+            }
+        ]);
+        let formatted = format_github_commits(&data);
+        assert!(formatted.contains("abc123d"));
+        assert!(formatted.contains("John Doe"));
+        assert!(formatted.contains("Fix bug"));
+    }
+
+    #[test]
+    fn test_format_github_pulls() {
+        let data = json!([
+            {
+                "number": 123,
+                "title": "Add new feature",
+                "state": "open",
+                "user": { "login": "contributor" }
+            }
+        ]);
+        let formatted = format_github_pulls(&data);
+        assert!(formatted.contains("#123"));
+        assert!(formatted.contains("open"));
+        assert!(formatted.contains("Add new feature"));
+    }
+
+    #[test]
+    fn test_format_github_repos() {
+        let data = json!({
+            "items": [
+                {
+                    "full_name": "tokio-rs/tokio",
+                    "stargazers_count": 27000,
+                    "language": "Rust",
+                    "description": "A runtime for writing reliable asynchronous applications",
+                    "pushed_at": "2025-01-15T10:00:00Z"
+                }
+            ]
+        });
+        let formatted = format_github_repos(&data);
+        assert!(formatted.contains("tokio-rs/tokio (★27000, Rust, updated 2025-01-15)"));
+        assert_eq!(
+            format_github_repos(&json!({ "items": [] })),
+            "No repositories found"
+        );
+    }
+
+    #[test]
+    fn test_trending_query() {
+        let today = chrono::NaiveDate::from_ymd_opt(2025, 1, 15).unwrap();
+        assert_eq!(
+            trending_query(Some("rust"), "weekly", today).unwrap(),
+            "created:>=2025-01-08 language:rust"
+        );
+        assert_eq!(
+            trending_query(None, "monthly", today).unwrap(),
+            "created:>=2024-12-16"
+        );
+        assert!(trending_query(None, "yearly", today).is_err());
+    }
+
+    #[test]
+    fn test_format_github_issue_with_comments() {
+        let issue = json!({
+            "number": 42,
+            "title": "Stabilize async closures",
+            "state": "closed",
+            "user": { "login": "alice" },
+            "created_at": "2025-01-10T10:00:00Z",
+            "labels": [{ "name": "T-lang" }],
+            "html_url": "https://github.com/rust-lang/rust/pull/42",
+            "body": "This PR stabilizes async closures.",
+            "comments": 12,
+            "pull_request": { "merged_at": "2025-01-14T10:00:00Z" }
+        });
+        let comments = json!([
+            { "user": { "login": "bob" }, "created_at": "2025-01-12T10:00:00Z", "body": "r=me" }
+        ]);
+        let formatted = format_github_issue(&issue, &comments);
+        assert!(formatted.starts_with("Pull request #42 [merged] by alice (2025-01-10)"));
+        assert!(formatted.contains("Labels: T-lang"));
+        assert!(formatted.contains("Recent comments (12 total):"));
+        assert!(formatted.contains("- bob (2025-01-12): r=me"));
+
+        let plain = format_github_issue(&json!({ "number": 7, "state": "open" }), &json!(null));
+        assert!(plain.starts_with("Issue #7 [open]"));
+        assert!(!plain.contains("Recent comments"));
+    }
+
+    #[test]
+    fn test_format_github_discussions() {
+        let data = json!({
+            "repository": { "discussions": { "nodes": [
+                {
+                    "number": 5,
+                    "title": "RFC: new plugin API",
+                    "updatedAt": "2025-01-15T10:00:00Z",
+                    "author": { "login": "maintainer" },
+                    "category": { "name": "Ideas" },
+                    "answer": null,
+                    "upvoteCount": 9,
+                    "comments": { "totalCount": 3 },
+                    "bodyText": "Proposal for\nplugins"
+                }
+            ] } }
+        });
+        let formatted = format_github_discussions(&data);
+        assert!(formatted.contains(
+            "#5 [Ideas] by maintainer (2025-01-15, 3 comments, 9 upvotes): RFC: new plugin API"
+        ));
+        assert!(formatted.contains("Proposal for plugins"));
+        assert!(format_github_discussions(&json!({ "repository": null }))
+            .starts_with("No discussions found"));
+    }
+
+    #[test]
+    fn test_format_github_commit_diff() {
+        let patch: Vec<String> = (0..20).map(|i| format!("+line {}", i)).collect();
+        let data = json!({
+            "sha": "abc123def456789",
+            "commit": {
+                "message": "Add parser",
+                "author": { "name": "John Doe", "date": "2025-01-15T10:00:00Z" }
+            },
+            "stats": { "additions": 20, "deletions": 0 },
+            "files": [
+                {
+                    "filename": "src/parser.rs",
+                    "status": "added",
+                    "additions": 20,
+                    "deletions": 0,
+                    "patch": patch.join("\n")
+                }
+            ]
+        });
+        let formatted = format_github_commit_diff(&data);
+        assert!(formatted.starts_with("Commit abc123d by John Doe (2025-01-15)"));
+        assert!(formatted.contains("1 files changed, +20 -0"));
+        assert!(formatted.contains("src/parser.rs (added, +20 -0)"));
+        assert!(formatted.contains("+line 11"));
+        assert!(!formatted.contains("+line 12"));
+        assert!(formatted.contains("... 8 more lines"));
+    }
+}
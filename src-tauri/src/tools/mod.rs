@@ -0,0 +1,281 @@
+//! Built-in tools shared by the research agent and chat.
+//!
+//! Tools are grouped by source (`web`, `github`, `news`) and exposed through
+//! the [`ToolRegistry`] trait, so both callers list and run the same
+//! implementation. A new built-in tool only needs adding to one of the
+//! submodules. MCP tools are handled separately by `mcp_client`.
+//!
+//! The `disabled_tools` setting turns individual tools off for research and
+//! chat (see [`ToolFilter`]).
+
+pub mod github;
+pub mod news;
+pub mod web;
+
+use async_trait::async_trait;
+use reqwest::Client;
+use serde::Serialize;
+use serde_json::json;
+use std::collections::HashSet;
+
+/// User-Agent sent by every built-in tool.
+pub(crate) const USER_AGENT: &str = concat!("Claudius/", env!("CARGO_PKG_VERSION"));
+
+/// Tool definition for the Anthropic API.
+#[derive(Debug, Clone, Serialize)]
+pub struct ToolDefinition {
+    pub name: String,
+    pub description: String,
+    pub input_schema: serde_json::Value,
+}
+
+impl ToolDefinition {
+    /// The definition as sent in a Messages API request.
+    pub fn to_json(&self) -> serde_json::Value {
+        json!({
+            "name": self.name,
+            "description": self.description,
+            "input_schema": self.input_schema
+        })
+    }
+}
+
+/// Result of running a tool.
+#[derive(Debug, Clone)]
+pub struct ToolOutput {
+    pub text: String,
+    /// Served from the GitHub cache rather than fetched
+    pub from_cache: bool,
+}
+
+/// Tools turned off in the `disabled_tools` setting. An entry is a tool name
+/// (`fetch_webpage`, `web_search`, or an MCP tool name), which disables the
+/// tool wherever it comes from, or `server/tool` for one MCP server's tool.
+/// Names are matched case-insensitively.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ToolFilter {
+    disabled: HashSet<String>,
+}
+
+impl ToolFilter {
+    pub fn new(disabled: &[String]) -> Self {
+        Self {
+            disabled: disabled
+                .iter()
+                .map(|entry| entry.trim().to_lowercase())
+                .filter(|entry| !entry.is_empty())
+                .collect(),
+        }
+    }
+
+    /// Filter from the current settings file (nothing disabled if it can't be read).
+    pub fn current() -> Self {
+        Self::new(
+            &crate::config::read_settings()
+                .unwrap_or_default()
+                .disabled_tools,
+        )
+    }
+
+    /// Whether the tool `name` may be offered to Claude. `server` is the MCP
+    /// server providing it, or None for built-in tools.
+    pub fn allows(&self, server: Option<&str>, name: &str) -> bool {
+        let name = name.to_lowercase();
+        if self.disabled.contains(&name) {
+            return false;
+        }
+        server.is_none_or(|server| {
+            !self
+                .disabled
+                .contains(&format!("{}/{}", server.trim().to_lowercase(), name))
+        })
+    }
+}
+
+/// A set of tools Claude can call.
+#[async_trait]
+pub trait ToolRegistry: Send + Sync {
+    fn definitions(&self) -> Vec<ToolDefinition>;
+
+    #[allow(dead_code)]
+    fn has_tool(&self, name: &str) -> bool {
+        self.definitions().iter().any(|t| t.name == name)
+    }
+
+    async fn execute(
+        &self,
+        client: &Client,
+        name: &str,
+        input: &serde_json::Value,
+    ) -> Result<ToolOutput, String>;
+}
+
+/// The built-in tools: `fetch_webpage`, the GitHub tools and the news tools.
+#[derive(Debug, Clone, Default)]
+pub struct BuiltinTools {
+    github_token: Option<String>,
+    filter: ToolFilter,
+}
+
+impl BuiltinTools {
+    pub fn new(github_token: Option<String>) -> Self {
+        Self {
+            github_token,
+            filter: ToolFilter::default(),
+        }
+    }
+
+    /// Leave out the tools `filter` disables.
+    pub fn with_filter(mut self, filter: ToolFilter) -> Self {
+        self.filter = filter;
+        self
+    }
+
+    /// Every built-in tool, including disabled ones.
+    pub fn all_definitions() -> Vec<ToolDefinition> {
+        let mut definitions = github::definitions();
+        definitions.extend(web::definitions());
+        definitions.extend(news::definitions());
+        definitions
+    }
+
+    /// Built-in tools using `GITHUB_TOKEN` from the environment or config.
+    pub fn from_env() -> Self {
+        Self::new(
+            std::env::var("GITHUB_TOKEN")
+                .ok()
+                .or_else(|| crate::config::read_env_value("GITHUB_TOKEN")),
+        )
+    }
+}
+
+fn defines(definitions: Vec<ToolDefinition>, name: &str) -> bool {
+    definitions.iter().any(|t| t.name == name)
+}
+
+#[async_trait]
+impl ToolRegistry for BuiltinTools {
+    fn definitions(&self) -> Vec<ToolDefinition> {
+        Self::all_definitions()
+            .into_iter()
+            .filter(|t| self.filter.allows(None, &t.name))
+            .collect()
+    }
+
+    async fn execute(
+        &self,
+        client: &Client,
+        name: &str,
+        input: &serde_json::Value,
+    ) -> Result<ToolOutput, String> {
+        if !self.filter.allows(None, name) {
+            return Err(format!("Tool '{}' is disabled in settings", name));
+        }
+        match name {
+            "fetch_webpage" => {
+                let url = input
+                    .get("url")
+                    .and_then(|v| v.as_str())
+                    .ok_or("Missing url")?;
+                web::fetch_webpage(client, url).await
+            }
+            name if defines(github::definitions(), name) => {
+                github::execute(client, name, input, self.github_token.as_deref()).await
+            }
+            name if defines(news::definitions(), name) => news::execute(client, name, input).await,
+            _ => Err(format!("Unknown tool: {}", name)),
+        }
+    }
+}
+
+/// Content-cache kind and key for a built-in tool call, if its output is cacheable.
+pub fn cache_key(tool_name: &str, input: &serde_json::Value) -> Option<(&'static str, String)> {
+    if tool_name == "fetch_webpage" {
+        let url = input.get("url").and_then(|v| v.as_str())?;
+        return Some(("web", url.to_string()));
+    }
+    github::cache_key(tool_name, input)
+        .map(|key| ("github", key))
+        .or_else(|| news::cache_key(tool_name, input).map(|key| ("news", key)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builtin_definitions() {
+        let definitions = BuiltinTools::default().definitions();
+        assert_eq!(definitions.len(), 10);
+
+        let names: HashSet<&str> = definitions.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names.len(), definitions.len(), "tool names must be unique");
+
+        let tools = BuiltinTools::default();
+        assert!(tools.has_tool("fetch_webpage"));
+        assert!(tools.has_tool("get_github_issue"));
+        assert!(tools.has_tool("search_reddit"));
+        assert!(!tools.has_tool("brave_search"));
+    }
+
+    #[test]
+    fn test_tool_filter() {
+        let filter = ToolFilter::new(&[
+            "Fetch_Webpage".to_string(),
+            "brave/brave_local_search".to_string(),
+            " ".to_string(),
+        ]);
+        assert!(!filter.allows(None, "fetch_webpage"));
+        assert!(filter.allows(None, "get_github_issue"));
+        assert!(!filter.allows(Some("Brave"), "brave_local_search"));
+        assert!(filter.allows(Some("Brave"), "brave_web_search"));
+        assert!(filter.allows(Some("other"), "brave_local_search"));
+        assert!(!filter.allows(Some("web"), "fetch_webpage"));
+
+        let tools = BuiltinTools::default().with_filter(filter);
+        assert_eq!(tools.definitions().len(), 9);
+        assert!(!tools.has_tool("fetch_webpage"));
+    }
+
+    #[test]
+    fn test_tool_definition_json() {
+        let json = web::definitions()[0].to_json();
+        assert_eq!(json["name"], "fetch_webpage");
+        assert_eq!(json["input_schema"]["required"][0], "url");
+    }
+
+    #[test]
+    fn test_cache_key() {
+        assert_eq!(
+            cache_key("fetch_webpage", &json!({ "url": "https://example.com" })),
+            Some(("web", "https://example.com".to_string()))
+        );
+        assert_eq!(
+            cache_key(
+                "get_github_activity",
+                &json!({ "owner": "rust-lang", "repo": "rust", "activity_type": "releases" })
+            ),
+            Some(("github", "rust-lang/rust/releases".to_string()))
+        );
+        assert_eq!(
+            cache_key("get_github_activity", &json!({ "owner": "rust-lang" })),
+            None
+        );
+        assert_eq!(
+            cache_key(
+                "get_github_issue",
+                &json!({ "owner": "rust-lang", "repo": "rust", "number": 42 })
+            ),
+            Some(("github", "rust-lang/rust/issues/42".to_string()))
+        );
+        assert_eq!(
+            cache_key("get_github_trending", &json!({ "language": "rust" })),
+            Some(("github", "trending/rust/weekly".to_string()))
+        );
+        assert_eq!(
+            cache_key("search_arxiv", &json!({ "query": "rlhf" })),
+            Some(("news", "arxiv:rlhf".to_string()))
+        );
+        assert_eq!(cache_key("brave_search", &json!({})), None);
+    }
+}
@@ -0,0 +1,570 @@
+//! Built-in arXiv, Hacker News and Reddit tools.
+//!
+//! These cover current events without a paid search MCP server:
+//!
+//! - `search_arxiv`: recent papers from the arXiv API
+//! - `get_hackernews_top`: Hacker News front page (or stories matching a
+//!   query) via the Algolia HN API
+//! - `search_reddit`: top Reddit posts for a query, optionally in one
+//!   subreddit
+//!
+//! None of these APIs need a key.
+
+use super::{ToolDefinition, ToolOutput, USER_AGENT};
+use lazy_static::lazy_static;
+use regex::Regex;
+use reqwest::Client;
+use serde_json::json;
+
+/// Default and maximum number of results returned by each tool.
+const DEFAULT_RESULTS: u64 = 10;
+const MAX_RESULTS: u64 = 25;
+
+/// Maximum characters kept from a paper abstract or post body.
+const SNIPPET_MAX_CHARS: usize = 400;
+
+pub fn definitions() -> Vec<ToolDefinition> {
+    vec![
+        ToolDefinition {
+            name: "search_arxiv".to_string(),
+            description: "Search arXiv for recent research papers, newest first. Use this for AI, ML, physics, math and computer science topics to find new papers and preprints.".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "query": {
+                        "type": "string",
+                        "description": "Search keywords (e.g. \"diffusion language models\")"
+                    },
+                    "max_results": {
+                        "type": "integer",
+                        "description": "Number of papers to return (default 10, max 25)"
+                    }
+                },
+                "required": ["query"]
+            }),
+        },
+        ToolDefinition {
+            name: "get_hackernews_top".to_string(),
+            description: "Get the current Hacker News front page, or recent Hacker News stories matching a query, with points and comment counts. Use this to see what the tech community is discussing.".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "query": {
+                        "type": "string",
+                        "description": "Optional keywords; without a query the front page is returned"
+                    },
+                    "limit": {
+                        "type": "integer",
+                        "description": "Number of stories to return (default 10, max 25)"
+                    }
+                }
+            }),
+        },
+        ToolDefinition {
+            name: "search_reddit".to_string(),
+            description: "Search Reddit for the top posts about a topic, optionally within one subreddit. Use this for community reactions and discussion.".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "query": {
+                        "type": "string",
+                        "description": "Search keywords"
+                    },
+                    "subreddit": {
+                        "type": "string",
+                        "description": "Optional subreddit to search, without the r/ prefix"
+                    },
+                    "time": {
+                        "type": "string",
+                        "enum": ["day", "week", "month"],
+                        "description": "Time window for top posts (default week)"
+                    },
+                    "limit": {
+                        "type": "integer",
+                        "description": "Number of posts to return (default 10, max 25)"
+                    }
+                },
+                "required": ["query"]
+            }),
+        },
+    ]
+}
+
+/// Content-cache key for a news tool call.
+pub fn cache_key(tool_name: &str, input: &serde_json::Value) -> Option<String> {
+    let field = |name: &str| input.get(name).and_then(|v| v.as_str());
+    match tool_name {
+        "search_arxiv" => Some(format!("arxiv:{}", field("query")?)),
+        "get_hackernews_top" => Some(format!("hackernews:{}", field("query").unwrap_or("top"))),
+        "search_reddit" => Some(format!(
+            "reddit:{}:{}:{}",
+            field("subreddit").unwrap_or("all"),
+            field("query")?,
+            field("time").unwrap_or("week")
+        )),
+        _ => None,
+    }
+}
+
+/// Execute a news tool.
+pub async fn execute(
+    client: &Client,
+    tool_name: &str,
+    input: &serde_json::Value,
+) -> Result<ToolOutput, String> {
+    let field = |name: &str| {
+        input
+            .get(name)
+            .and_then(|v| v.as_str())
+            .map(str::trim)
+            .filter(|v| !v.is_empty())
+    };
+    let count = |name: &str| {
+        input
+            .get(name)
+            .and_then(|v| v.as_u64())
+            .unwrap_or(DEFAULT_RESULTS)
+            .clamp(1, MAX_RESULTS)
+    };
+
+    let text = match tool_name {
+        "search_arxiv" => {
+            let query = field("query").ok_or("Missing query")?;
+            search_arxiv(client, query, count("max_results")).await
+        }
+        "get_hackernews_top" => get_hackernews(client, field("query"), count("limit")).await,
+        "search_reddit" => {
+            let query = field("query").ok_or("Missing query")?;
+            let time = field("time").unwrap_or("week");
+            search_reddit(client, query, field("subreddit"), time, count("limit")).await
+        }
+        _ => return Err(format!("Unknown tool: {}", tool_name)),
+    }?;
+    Ok(ToolOutput {
+        text,
+        from_cache: false,
+    })
+}
+
+/// GET a URL with query parameters and return the body.
+async fn get_text(client: &Client, base: &str, params: &[(&str, &str)]) -> Result<String, String> {
+    let url = reqwest::Url::parse_with_params(base, params)
+        .map_err(|e| format!("Invalid request URL: {}", e))?;
+    let response = client
+        .get(url)
+        .header("User-Agent", USER_AGENT)
+        .send()
+        .await
+        .map_err(|e| format!("Request failed: {}", e))?;
+
+    if !response.status().is_success() {
+        return Err(format!("HTTP error: {}", response.status()));
+    }
+
+    response
+        .text()
+        .await
+        .map_err(|e| format!("Failed to read response: {}", e))
+}
+
+fn parse_json(body: &str) -> Result<serde_json::Value, String> {
+    serde_json::from_str(body).map_err(|e| format!("Failed to parse response: {}", e))
+}
+
+/// Collapse whitespace and cut text to `max_chars`.
+fn snippet(text: &str, max_chars: usize) -> String {
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if text.chars().count() <= max_chars {
+        return text;
+    }
+    let truncated: String = text.chars().take(max_chars).collect();
+    format!("{}…", truncated.trim_end())
+}
+
+// ============================================================================
+// arXiv
+// ============================================================================
+
+async fn search_arxiv(client: &Client, query: &str, max_results: u64) -> Result<String, String> {
+    let search = format!("all:{}", query);
+    let max_results = max_results.to_string();
+    let body = get_text(
+        client,
+        "https://export.arxiv.org/api/query",
+        &[
+            ("search_query", search.as_str()),
+            ("sortBy", "submittedDate"),
+            ("sortOrder", "descending"),
+            ("max_results", max_results.as_str()),
+        ],
+    )
+    .await?;
+    Ok(format_arxiv_feed(&body))
+}
+
+lazy_static! {
+    static ref ATOM_ENTRY: Regex = Regex::new(r"(?s)<entry>(.*?)</entry>").unwrap();
+    static ref ATOM_AUTHOR: Regex = Regex::new(r"(?s)<name>(.*?)</name>").unwrap();
+}
+
+/// Text of the first `<tag>` element in an Atom fragment.
+fn atom_field(entry: &str, tag: &str) -> Option<String> {
+    let start = entry.find(&format!("<{}>", tag))? + tag.len() + 2;
+    let end = start + entry[start..].find(&format!("</{}>", tag))?;
+    Some(decode_xml_entities(entry[start..end].trim()))
+}
+
+fn decode_xml_entities(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+/// Format an arXiv Atom feed as one paper per entry.
+fn format_arxiv_feed(xml: &str) -> String {
+    let papers: Vec<String> = ATOM_ENTRY
+        .captures_iter(xml)
+        .filter_map(|caps| {
+            let entry = caps.get(1)?.as_str();
+            let title = atom_field(entry, "title")?;
+            let url = atom_field(entry, "id")?;
+            let published = atom_field(entry, "published");
+            let authors: Vec<String> = ATOM_AUTHOR
+                .captures_iter(entry)
+                .filter_map(|a| a.get(1).map(|m| decode_xml_entities(m.as_str().trim())))
+                .collect();
+            let authors = match authors.len() {
+                0 => "unknown authors".to_string(),
+                1..=3 => authors.join(", "),
+                _ => format!("{} et al.", authors[..3].join(", ")),
+            };
+            let mut paper = format!(
+                "- {} ({}, {})\n  {}",
+                snippet(&title, 200),
+                authors,
+                published
+                    .as_deref()
+                    .and_then(|d| d.get(..10))
+                    .unwrap_or("unknown date"),
+                url
+            );
+            if let Some(summary) = atom_field(entry, "summary") {
+                paper.push_str(&format!("\n  {}", snippet(&summary, SNIPPET_MAX_CHARS)));
+            }
+            Some(paper)
+        })
+        .collect();
+
+    if papers.is_empty() {
+        "No arXiv papers found".to_string()
+    } else {
+        papers.join("\n")
+    }
+}
+
+// ============================================================================
+// Hacker News
+// ============================================================================
+
+async fn get_hackernews(
+    client: &Client,
+    query: Option<&str>,
+    limit: u64,
+) -> Result<String, String> {
+    let limit = limit.to_string();
+    let body = match query {
+        // Front page ranking
+        None => {
+            get_text(
+                client,
+                "https://hn.algolia.com/api/v1/search",
+                &[("tags", "front_page"), ("hitsPerPage", limit.as_str())],
+            )
+            .await?
+        }
+        // Newest matching stories
+        Some(query) => {
+            get_text(
+                client,
+                "https://hn.algolia.com/api/v1/search_by_date",
+                &[
+                    ("query", query),
+                    ("tags", "story"),
+                    ("hitsPerPage", limit.as_str()),
+                ],
+            )
+            .await?
+        }
+    };
+    Ok(format_hackernews_stories(&parse_json(&body)?))
+}
+
+/// Format an Algolia HN search response.
+fn format_hackernews_stories(data: &serde_json::Value) -> String {
+    let stories: Vec<String> = data["hits"]
+        .as_array()
+        .map(|hits| {
+            hits.iter()
+                .filter_map(|hit| {
+                    let title = hit.get("title")?.as_str()?;
+                    let id = hit.get("objectID")?.as_str()?;
+                    let discussion = format!("https://news.ycombinator.com/item?id={}", id);
+                    let url = hit["url"]
+                        .as_str()
+                        .filter(|u| !u.is_empty())
+                        .unwrap_or(&discussion);
+                    let mut story = format!(
+                        "- {} ({} points, {} comments, by {}, {})\n  {}",
+                        title,
+                        hit["points"].as_u64().unwrap_or(0),
+                        hit["num_comments"].as_u64().unwrap_or(0),
+                        hit["author"].as_str().unwrap_or("unknown"),
+                        hit["created_at"]
+                            .as_str()
+                            .and_then(|d| d.get(..10))
+                            .unwrap_or("unknown date"),
+                        url
+                    );
+                    if url != discussion {
+                        story.push_str(&format!("\n  Discussion: {}", discussion));
+                    }
+                    Some(story)
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    if stories.is_empty() {
+        "No Hacker News stories found".to_string()
+    } else {
+        stories.join("\n")
+    }
+}
+
+// ============================================================================
+// Reddit
+// ============================================================================
+
+async fn search_reddit(
+    client: &Client,
+    query: &str,
+    subreddit: Option<&str>,
+    time: &str,
+    limit: u64,
+) -> Result<String, String> {
+    if !matches!(time, "day" | "week" | "month") {
+        return Err(format!("Unknown time window: {}", time));
+    }
+    let subreddit = subreddit.map(|s| s.trim_start_matches("r/"));
+    if let Some(name) = subreddit {
+        if !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+            return Err(format!("Invalid subreddit: {}", name));
+        }
+    }
+
+    let base = match subreddit {
+        Some(name) => format!("https://www.reddit.com/r/{}/search.json", name),
+        None => "https://www.reddit.com/search.json".to_string(),
+    };
+    let limit = limit.to_string();
+    let restrict = if subreddit.is_some() { "1" } else { "0" };
+    let body = get_text(
+        client,
+        &base,
+        &[
+            ("q", query),
+            ("sort", "top"),
+            ("t", time),
+            ("limit", limit.as_str()),
+            ("restrict_sr", restrict),
+        ],
+    )
+    .await?;
+    Ok(format_reddit_posts(&parse_json(&body)?))
+}
+
+/// Format a Reddit listing response.
+fn format_reddit_posts(data: &serde_json::Value) -> String {
+    let posts: Vec<String> = data["data"]["children"]
+        .as_array()
+        .map(|children| {
+            children
+                .iter()
+                .filter_map(|child| {
+                    let post = child.get("data")?;
+                    let title = post.get("title")?.as_str()?;
+                    let permalink = post.get("permalink")?.as_str()?;
+                    let date = post["created_utc"]
+                        .as_f64()
+                        .and_then(|ts| chrono::DateTime::from_timestamp(ts as i64, 0))
+                        .map(|dt| dt.format("%Y-%m-%d").to_string())
+                        .unwrap_or_else(|| "unknown date".to_string());
+                    let mut line = format!(
+                        "- [r/{}] {} ({} points, {} comments, {})\n  https://www.reddit.com{}",
+                        post["subreddit"].as_str().unwrap_or("?"),
+                        title,
+                        post["score"].as_i64().unwrap_or(0),
+                        post["num_comments"].as_u64().unwrap_or(0),
+                        date,
+                        permalink
+                    );
+                    // Link posts point somewhere else; self posts carry their text
+                    match post["url"].as_str() {
+                        Some(url) if !url.contains("reddit.com") => {
+                            line.push_str(&format!("\n  Link: {}", url));
+                        }
+                        _ => {}
+                    }
+                    if let Some(text) = post["selftext"].as_str().filter(|t| !t.trim().is_empty()) {
+                        line.push_str(&format!("\n  {}", snippet(text, SNIPPET_MAX_CHARS)));
+                    }
+                    Some(line)
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    if posts.is_empty() {
+        "No Reddit posts found".to_string()
+    } else {
+        posts.join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_definitions() {
+        let names: Vec<String> = definitions().into_iter().map(|t| t.name).collect();
+        assert_eq!(
+            names,
+            vec!["search_arxiv", "get_hackernews_top", "search_reddit"]
+        );
+    }
+
+    #[test]
+    fn test_cache_key() {
+        assert_eq!(
+            cache_key("search_arxiv", &json!({ "query": "rlhf" })),
+            Some("arxiv:rlhf".to_string())
+        );
+        assert_eq!(
+            cache_key("get_hackernews_top", &json!({})),
+            Some("hackernews:top".to_string())
+        );
+        assert_eq!(
+            cache_key(
+                "search_reddit",
+                &json!({ "query": "tauri", "subreddit": "rust" })
+            ),
+            Some("reddit:rust:tauri:week".to_string())
+        );
+        assert_eq!(cache_key("search_reddit", &json!({})), None);
+    }
+
+    #[test]
+    fn test_format_arxiv_feed() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<feed xmlns="http://www.w3.org/2005/Atom">
+  <title>arXiv Query</title>
+  <entry>
+    <id>http://arxiv.org/abs/2501.01234v1</id>
+    <published>2025-01-15T18:00:00Z</published>
+    <title>Scaling   Laws for
+      Tool Use &amp; Agents</title>
+    <summary>We study how agents scale.</summary>
+    <author><name>Ada Lovelace</name></author>
+    <author><name>Alan Turing</name></author>
+    <author><name>Grace Hopper</name></author>
+    <author><name>Edsger Dijkstra</name></author>
+  </entry>
+</feed>"#;
+        let formatted = format_arxiv_feed(xml);
+        assert!(formatted.starts_with(
+            "- Scaling Laws for Tool Use & Agents (Ada Lovelace, Alan Turing, Grace Hopper et al., 2025-01-15)"
+        ));
+        assert!(formatted.contains("http://arxiv.org/abs/2501.01234v1"));
+        assert!(formatted.contains("We study how agents scale."));
+        assert!(!formatted.contains("arXiv Query"));
+        assert_eq!(format_arxiv_feed("<feed></feed>"), "No arXiv papers found");
+    }
+
+    #[test]
+    fn test_format_hackernews_stories() {
+        let data = json!({
+            "hits": [
+                {
+                    "title": "Show HN: A tiny database",
+                    "url": "https://example.com/db",
+                    "objectID": "42",
+                    "points": 120,
+                    "num_comments": 37,
+                    "author": "pg",
+                    "created_at": "2025-01-15T10:00:00Z"
+                },
+                {
+                    "title": "Ask HN: What are you working on?",
+                    "url": null,
+                    "objectID": "43",
+                    "points": 10,
+                    "num_comments": 5,
+                    "author": "dang",
+                    "created_at": "2025-01-15T11:00:00Z"
+                }
+            ]
+        });
+        let formatted = format_hackernews_stories(&data);
+        assert!(formatted
+            .contains("- Show HN: A tiny database (120 points, 37 comments, by pg, 2025-01-15)"));
+        assert!(formatted.contains("Discussion: https://news.ycombinator.com/item?id=42"));
+        assert!(formatted.contains("  https://news.ycombinator.com/item?id=43"));
+        assert_eq!(
+            format_hackernews_stories(&json!({ "hits": [] })),
+            "No Hacker News stories found"
+        );
+    }
+
+    #[test]
+    fn test_format_reddit_posts() {
+        let data = json!({
+            "data": { "children": [
+                { "data": {
+                    "title": "Tauri 2.0 released",
+                    "subreddit": "rust",
+                    "score": 950,
+                    "num_comments": 210,
+                    "created_utc": 1736935200.0,
+                    "permalink": "/r/rust/comments/abc/tauri_20_released/",
+                    "url": "https://v2.tauri.app/blog/tauri-20/",
+                    "selftext": ""
+                } },
+                { "data": {
+                    "title": "Thoughts on async traits?",
+                    "subreddit": "rust",
+                    "score": 40,
+                    "num_comments": 12,
+                    "created_utc": 1736935200.0,
+                    "permalink": "/r/rust/comments/def/thoughts/",
+                    "url": "https://www.reddit.com/r/rust/comments/def/thoughts/",
+                    "selftext": "Now that they're stable,\n\nhow are you using them?"
+                } }
+            ] }
+        });
+        let formatted = format_reddit_posts(&data);
+        assert!(formatted
+            .contains("- [r/rust] Tauri 2.0 released (950 points, 210 comments, 2025-01-15)"));
+        assert!(formatted.contains("Link: https://v2.tauri.app/blog/tauri-20/"));
+        assert!(formatted.contains("Now that they're stable, how are you using them?"));
+        assert_eq!(format_reddit_posts(&json!({})), "No Reddit posts found");
+    }
+
+    #[test]
+    fn test_snippet() {
+        assert_eq!(snippet("  a \n b  ", 10), "a b");
+        assert_eq!(snippet("abcdefgh", 3), "abc…");
+    }
+}
@@ -0,0 +1,99 @@
+//! Built-in `fetch_webpage` tool.
+
+use super::{ToolDefinition, ToolOutput};
+use crate::fetch_policy::{self, FetchPolicy};
+use crate::{article, web_cache};
+use reqwest::header::{ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED};
+use reqwest::{Client, StatusCode};
+use serde_json::json;
+
+/// Maximum characters of article text returned by `fetch_webpage`.
+const FETCH_MAX_CHARS: usize = 8000;
+
+pub fn definitions() -> Vec<ToolDefinition> {
+    vec![ToolDefinition {
+        name: "fetch_webpage".to_string(),
+        description: "Fetch and extract text content from a webpage URL. Use this to get current information from news sites, documentation, or other web sources.".to_string(),
+        input_schema: json!({
+            "type": "object",
+            "properties": {
+                "url": {
+                    "type": "string",
+                    "description": "The URL of the webpage to fetch"
+                }
+            },
+            "required": ["url"]
+        }),
+    }]
+}
+
+/// Fetch and extract text content from a webpage, going through the shared
+/// web cache (see `web_cache`) and the politeness rules in `fetch_policy`.
+pub async fn fetch_webpage(client: &Client, url: &str) -> Result<ToolOutput, String> {
+    // Validate URL
+    if !url.starts_with("http://") && !url.starts_with("https://") {
+        return Err("URL must start with http:// or https://".to_string());
+    }
+
+    let cached = web_cache::lookup(url);
+    if let Some(entry) = cached.as_ref().filter(|e| web_cache::is_fresh(e)) {
+        return Ok(ToolOutput {
+            text: entry.content.clone(),
+            from_cache: true,
+        });
+    }
+
+    let user_agent = fetch_policy::prepare(client, url, &FetchPolicy::current()).await?;
+    let mut request = client.get(url).header("User-Agent", user_agent);
+    if let Some(entry) = cached.as_ref().filter(|e| web_cache::can_revalidate(e)) {
+        if let Some(ref etag) = entry.etag {
+            request = request.header(IF_NONE_MATCH, etag);
+        }
+        if let Some(ref last_modified) = entry.last_modified {
+            request = request.header(IF_MODIFIED_SINCE, last_modified);
+        }
+    }
+
+    let response = request
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch URL: {}", e))?;
+
+    if response.status() == StatusCode::NOT_MODIFIED {
+        if let Some(entry) = cached {
+            web_cache::revalidated(url);
+            return Ok(ToolOutput {
+                text: entry.content,
+                from_cache: true,
+            });
+        }
+    }
+
+    if !response.status().is_success() {
+        return Err(format!("HTTP error: {}", response.status()));
+    }
+
+    let header = |name| {
+        response
+            .headers()
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .map(|v| v.to_string())
+    };
+    let etag = header(ETAG);
+    let last_modified = header(LAST_MODIFIED);
+
+    let html = response
+        .text()
+        .await
+        .map_err(|e| format!("Failed to read response: {}", e))?;
+
+    // Extract the main article content and metadata
+    let text = article::extract_article(&html).to_tool_output(FETCH_MAX_CHARS);
+    web_cache::store(url, &text, etag.as_deref(), last_modified.as_deref());
+
+    Ok(ToolOutput {
+        text,
+        from_cache: false,
+    })
+}
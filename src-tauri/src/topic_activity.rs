@@ -0,0 +1,283 @@
+//! Rate of change of each topic's research.
+//!
+//! After every run, each researched topic gets a novelty score: how different
+//! its new cards are from the topic's cards in the previous
+//! [`LOOKBACK_RUNS`] briefings (1.0 = all new, 0.0 = repeats). A topic that
+//! produced no cards scores 0. Scores are stored in `topic_activity` and
+//! averaged over recent runs to flag stagnant topics, which could be
+//! researched less often, and highly active ones.
+
+use crate::dedup::{similarity, CardFingerprint};
+use rusqlite::{params, Connection};
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// Previous briefings a run's cards are compared against.
+pub const LOOKBACK_RUNS: i64 = 5;
+
+/// Runs needed before a topic is flagged either way.
+pub const MIN_RUNS: usize = 3;
+
+/// Average novelty below which a topic is stagnant.
+pub const STAGNANT_BELOW: f64 = 0.25;
+
+/// Average novelty at or above which a topic is highly active.
+pub const ACTIVE_FROM: f64 = 0.75;
+
+/// Recent activity of one topic.
+#[derive(Debug, Clone, Serialize)]
+pub struct TopicActivity {
+    pub topic: String,
+    /// Runs scored (at most the number asked for)
+    pub runs: usize,
+    pub avg_novelty: f64,
+    pub last_novelty: f64,
+    pub avg_cards: f64,
+    /// "new" (too few runs), "stagnant", "active" or "steady"
+    pub status: String,
+    pub suggestion: Option<String>,
+    pub last_run: String,
+}
+
+fn normalize(topic: &str) -> String {
+    topic.trim().to_lowercase()
+}
+
+fn card_fingerprints(cards_json: &str) -> Vec<CardFingerprint> {
+    serde_json::from_str::<Vec<CardFingerprint>>(cards_json).unwrap_or_default()
+}
+
+/// Novelty of a topic's new cards against its past cards: one minus the
+/// average of each card's closest match (title or summary).
+pub fn novelty(cards: &[&CardFingerprint], past: &[&CardFingerprint]) -> f64 {
+    if cards.is_empty() {
+        return 0.0;
+    }
+    if past.is_empty() {
+        return 1.0;
+    }
+    let total: f64 = cards
+        .iter()
+        .map(|card| {
+            past.iter()
+                .map(|p| {
+                    similarity(&card.title, &p.title).max(similarity(&card.summary, &p.summary))
+                })
+                .fold(0.0, f64::max)
+        })
+        .sum();
+    1.0 - total / cards.len() as f64
+}
+
+/// Score each researched topic for a saved briefing. Topics are the names
+/// that were researched; cards for other topic names are scored too.
+pub fn record_run(
+    conn: &Connection,
+    briefing_id: i64,
+    topics: &[String],
+) -> Result<Vec<(String, f64)>, String> {
+    let cards_json: String = conn
+        .query_row(
+            "SELECT cards FROM briefings WHERE id = ?1",
+            [briefing_id],
+            |row| row.get(0),
+        )
+        .map_err(|e| format!("Failed to read briefing {}: {}", briefing_id, e))?;
+    let cards = card_fingerprints(&cards_json);
+
+    let mut stmt = conn
+        .prepare("SELECT cards FROM briefings WHERE id < ?1 ORDER BY id DESC LIMIT ?2")
+        .map_err(|e| format!("Failed to prepare query: {}", e))?;
+    let past: Vec<CardFingerprint> = stmt
+        .query_map(params![briefing_id, LOOKBACK_RUNS], |row| {
+            row.get::<_, String>(0)
+        })
+        .map_err(|e| format!("Failed to query briefings: {}", e))?
+        .filter_map(|row| row.ok())
+        .flat_map(|json| card_fingerprints(&json))
+        .collect();
+
+    let mut names: Vec<String> = topics.to_vec();
+    for card in &cards {
+        if !names.iter().any(|t| normalize(t) == normalize(&card.topic)) {
+            names.push(card.topic.clone());
+        }
+    }
+
+    let mut scores = Vec::new();
+    for topic in names {
+        let key = normalize(&topic);
+        let topic_cards: Vec<&CardFingerprint> = cards
+            .iter()
+            .filter(|c| normalize(&c.topic) == key)
+            .collect();
+        let topic_past: Vec<&CardFingerprint> =
+            past.iter().filter(|c| normalize(&c.topic) == key).collect();
+        let score = novelty(&topic_cards, &topic_past);
+        conn.execute(
+            "INSERT INTO topic_activity (briefing_id, topic, card_count, novelty)
+             VALUES (?1, ?2, ?3, ?4)",
+            params![briefing_id, topic, topic_cards.len() as i64, score],
+        )
+        .map_err(|e| format!("Failed to record topic activity: {}", e))?;
+        scores.push((topic, score));
+    }
+    Ok(scores)
+}
+
+/// Classify a topic from its scores, newest first.
+fn classify(novelties: &[f64], avg_cards: f64) -> (&'static str, Option<String>) {
+    let avg = novelties.iter().sum::<f64>() / novelties.len().max(1) as f64;
+    if novelties.len() < MIN_RUNS {
+        ("new", None)
+    } else if avg < STAGNANT_BELOW {
+        (
+            "stagnant",
+            Some(
+                "Findings rarely change; consider researching it less often or disabling it"
+                    .to_string(),
+            ),
+        )
+    } else if avg >= ACTIVE_FROM && avg_cards >= 1.0 {
+        (
+            "active",
+            Some(
+                "Mostly new findings every run; consider researching it more often or narrowing it"
+                    .to_string(),
+            ),
+        )
+    } else {
+        ("steady", None)
+    }
+}
+
+/// Activity of every scored topic over its last `runs` runs, most stagnant first.
+pub fn get_topic_activity(conn: &Connection, runs: usize) -> Result<Vec<TopicActivity>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT topic, novelty, card_count, created_at FROM topic_activity
+             ORDER BY briefing_id DESC, id",
+        )
+        .map_err(|e| format!("Failed to prepare query: {}", e))?;
+    let rows = stmt
+        .query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, f64>(1)?,
+                row.get::<_, i64>(2)?,
+                row.get::<_, String>(3)?,
+            ))
+        })
+        .map_err(|e| format!("Failed to query topic activity: {}", e))?
+        .collect::<rusqlite::Result<Vec<_>>>()
+        .map_err(|e| format!("Failed to read topic activity: {}", e))?;
+
+    // (display name, novelties, card counts, last run), newest first
+    let mut by_topic: HashMap<String, (String, Vec<f64>, Vec<i64>, String)> = HashMap::new();
+    for (topic, novelty, cards, created_at) in rows {
+        let entry = by_topic
+            .entry(normalize(&topic))
+            .or_insert_with(|| (topic, Vec::new(), Vec::new(), created_at));
+        if entry.1.len() < runs {
+            entry.1.push(novelty);
+            entry.2.push(cards);
+        }
+    }
+
+    let mut activity: Vec<TopicActivity> = by_topic
+        .into_values()
+        .map(|(topic, novelties, cards, last_run)| {
+            let avg_cards = cards.iter().sum::<i64>() as f64 / cards.len().max(1) as f64;
+            let (status, suggestion) = classify(&novelties, avg_cards);
+            TopicActivity {
+                topic,
+                runs: novelties.len(),
+                avg_novelty: novelties.iter().sum::<f64>() / novelties.len().max(1) as f64,
+                last_novelty: novelties.first().copied().unwrap_or(0.0),
+                avg_cards,
+                status: status.to_string(),
+                suggestion,
+                last_run,
+            }
+        })
+        .collect();
+    activity.sort_by(|a, b| {
+        a.avg_novelty
+            .total_cmp(&b.avg_novelty)
+            .then_with(|| a.topic.cmp(&b.topic))
+    });
+    Ok(activity)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn card(topic: &str, title: &str) -> CardFingerprint {
+        CardFingerprint {
+            title: title.to_string(),
+            topic: topic.to_string(),
+            summary: title.to_string(),
+        }
+    }
+
+    fn save_briefing(conn: &Connection, cards: &[CardFingerprint]) -> i64 {
+        conn.execute(
+            "INSERT INTO briefings (date, title, cards) VALUES ('2025-01-01', 'B', ?1)",
+            [serde_json::to_string(cards).unwrap()],
+        )
+        .unwrap();
+        conn.last_insert_rowid()
+    }
+
+    #[test]
+    fn test_novelty() {
+        let old = card("Rust", "Rust 1.80 released with LazyLock");
+        let same = card("Rust", "Rust 1.80 released with LazyLock");
+        let new = card("Rust", "Async closures stabilized in nightly");
+        assert_eq!(novelty(&[], &[&old]), 0.0);
+        assert_eq!(novelty(&[&new], &[]), 1.0);
+        assert!(novelty(&[&same], &[&old]) < 0.01);
+        assert!(novelty(&[&new], &[&old]) > 0.4);
+    }
+
+    #[test]
+    fn test_record_run_and_activity() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(include_str!("schema.sql")).unwrap();
+        let topics = vec!["Rust".to_string(), "AI".to_string()];
+        let titles = [
+            "Rust 2024 edition ships",
+            "Cargo adds workspace lints",
+            "Rustup gets faster installs",
+        ];
+
+        for title in titles {
+            let id = save_briefing(
+                &conn,
+                &[
+                    card("Rust", title),
+                    card("AI", "Claude tops coding benchmark"),
+                ],
+            );
+            record_run(&conn, id, &topics).unwrap();
+        }
+        let id = save_briefing(&conn, &[card("AI", "Claude tops coding benchmark")]);
+        let scores = record_run(&conn, id, &topics).unwrap();
+        assert_eq!(scores[0], ("Rust".to_string(), 0.0));
+
+        let activity = get_topic_activity(&conn, 3).unwrap();
+        assert_eq!(activity.len(), 2);
+        let ai = &activity[0];
+        assert_eq!(ai.topic, "AI");
+        assert_eq!(ai.runs, 3);
+        assert!(ai.avg_novelty < 0.01);
+        assert_eq!(ai.status, "stagnant");
+        assert!(ai.suggestion.is_some());
+
+        let rust = &activity[1];
+        assert_eq!(rust.last_novelty, 0.0);
+        assert!(rust.avg_novelty > STAGNANT_BELOW);
+        assert_eq!(rust.status, "steady");
+    }
+}
@@ -0,0 +1,250 @@
+//! Topic discovery for Claudius.
+//!
+//! Looks at what the user bookmarks, rates highly and asks about in chat, and
+//! asks Claude for new topics they seem interested in but don't follow yet.
+//! Suggestions are stored in the `topic_suggestions` table until the user
+//! accepts (creating a topic) or dismisses them.
+
+use chrono::{NaiveDateTime, Utc};
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde::Deserialize;
+use serde_json::json;
+use std::time::Duration;
+use tracing::{info, warn};
+
+use crate::agent_loop::ApiEndpoint;
+use crate::db::{self, DiscoverySignals, TopicSuggestion};
+
+/// How far back discovery looks for bookmarks, feedback and chat questions.
+const SIGNAL_DAYS: i64 = 30;
+
+/// Maximum signals of each kind included in the prompt.
+const SIGNAL_LIMIT: i64 = 25;
+
+/// Maximum new suggestions per discovery run.
+const MAX_SUGGESTIONS: usize = 5;
+
+lazy_static! {
+    /// Everything from the first `{` to the last `}` of a response
+    static ref JSON_OBJECT_RE: Regex = Regex::new(r"(?s)(\{.*\})").unwrap();
+}
+
+#[derive(Debug, Deserialize)]
+struct SuggestionsResponse {
+    suggestions: Vec<SuggestedTopic>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SuggestedTopic {
+    name: String,
+    reason: Option<String>,
+}
+
+/// Whether discovery should run, given the last run time and the interval.
+/// An interval of 0 disables periodic discovery.
+pub fn discovery_due(last_run: Option<&str>, interval_days: u32) -> bool {
+    if interval_days == 0 {
+        return false;
+    }
+    let Some(last_run) = last_run else {
+        return true;
+    };
+    match NaiveDateTime::parse_from_str(last_run, "%Y-%m-%d %H:%M:%S") {
+        Ok(last) => {
+            Utc::now().naive_utc() - last >= chrono::Duration::days(i64::from(interval_days))
+        }
+        Err(_) => true,
+    }
+}
+
+/// Build the discovery prompt, or None when there is no activity to learn from.
+fn build_discovery_prompt(
+    topics: &[String],
+    previous: &[String],
+    signals: &DiscoverySignals,
+) -> Option<String> {
+    if signals.bookmarked_cards.is_empty()
+        && signals.liked_cards.is_empty()
+        && signals.chat_questions.is_empty()
+    {
+        return None;
+    }
+
+    let list = |items: &[String]| -> String {
+        if items.is_empty() {
+            "(none)".to_string()
+        } else {
+            items
+                .iter()
+                .map(|i| format!("- {}", i.chars().take(200).collect::<String>()))
+                .collect::<Vec<_>>()
+                .join("\n")
+        }
+    };
+
+    Some(format!(
+        r#"You help a user of a daily research briefing app discover new topics to follow.
+
+TOPICS THEY ALREADY FOLLOW:
+{}
+
+TOPICS ALREADY SUGGESTED (do not suggest these again):
+{}
+
+CARDS THEY BOOKMARKED:
+{}
+
+CARDS THEY RATED HIGHLY:
+{}
+
+QUESTIONS THEY ASKED ABOUT CARDS:
+{}
+
+Suggest up to {} NEW topics this user seems interested in but does not follow yet.
+- Base every suggestion on the activity above; never suggest generic topics
+- Each name must be a short research topic (2-6 words), not a sentence
+- Do not suggest topics that overlap an existing or previously suggested topic
+- The reason should cite the activity that led to it (one sentence)
+- Return an empty list if nothing stands out
+
+Return ONLY valid JSON in this exact format:
+{{"suggestions": [{{"name": "Topic name", "reason": "Why the user seems interested"}}]}}"#,
+        list(topics),
+        list(previous),
+        list(&signals.bookmarked_cards),
+        list(&signals.liked_cards),
+        list(&signals.chat_questions),
+        MAX_SUGGESTIONS
+    ))
+}
+
+/// Parse Claude's suggestions, tolerating text around the JSON object.
+fn parse_suggestions(response: &str) -> Result<Vec<SuggestedTopic>, String> {
+    let json_str = JSON_OBJECT_RE
+        .captures(response)
+        .and_then(|c| c.get(1))
+        .map(|m| m.as_str())
+        .unwrap_or(response);
+    serde_json::from_str::<SuggestionsResponse>(json_str)
+        .map(|r| r.suggestions)
+        .map_err(|e| format!("Failed to parse topic suggestions: {}", e))
+}
+
+/// Analyze recent activity and store new topic suggestions.
+///
+/// Returns the suggestions added by this run. Tokens are recorded as
+/// "discovery" usage for `profile`, which also marks when discovery last ran.
+pub async fn discover_topics(
+    api_key: &str,
+    model: &str,
+    profile: &str,
+) -> Result<Vec<TopicSuggestion>, String> {
+    // Gather inputs up front; the connection can't be held across the API call
+    let prompt = {
+        let conn = db::get_connection().map_err(|e| format!("Database error: {}", e))?;
+        let topics: Vec<String> = db::get_all_topics(&conn)?
+            .into_iter()
+            .map(|t| t.name)
+            .collect();
+        let previous = db::get_topic_suggestion_names(&conn)?;
+        let signals = db::get_discovery_signals(&conn, SIGNAL_DAYS, SIGNAL_LIMIT)?;
+        build_discovery_prompt(&topics, &previous, &signals)
+    };
+    let Some(prompt) = prompt else {
+        info!("No recent bookmarks, feedback or chats; skipping topic discovery");
+        return Ok(Vec::new());
+    };
+
+    let client = crate::http_client::client_builder()
+        .timeout(Duration::from_secs(60))
+        .build()
+        .map_err(|e| format!("Failed to build HTTP client: {}", e))?;
+    let endpoint = ApiEndpoint::current();
+    let mut request = json!({
+        "model": model,
+        "max_tokens": 1024,
+        "messages": [{ "role": "user", "content": prompt }],
+    });
+    if let Some(metadata) = endpoint.metadata() {
+        request["metadata"] = json!(metadata);
+    }
+    let response = endpoint
+        .authorize(client.post(endpoint.messages_url()), api_key)
+        .header("content-type", "application/json")
+        .json(&request)
+        .send()
+        .await
+        .map_err(|e| format!("HTTP request failed: {}", e))?;
+
+    if !response.status().is_success() {
+        let status = response.status().as_u16();
+        let body = response.text().await.unwrap_or_default();
+        return Err(format!("API error {}: {}", status, body));
+    }
+
+    let body: serde_json::Value = response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse response: {}", e))?;
+    let text = body["content"]
+        .as_array()
+        .and_then(|blocks| blocks.iter().find_map(|b| b["text"].as_str()))
+        .unwrap_or_default();
+    let tokens = body["usage"]["input_tokens"].as_i64().unwrap_or(0)
+        + body["usage"]["output_tokens"].as_i64().unwrap_or(0);
+
+    let conn = db::get_connection().map_err(|e| format!("Database error: {}", e))?;
+    if let Err(e) = db::record_api_usage(&conn, profile, "discovery", None, tokens) {
+        warn!("Failed to record discovery usage: {}", e);
+    }
+
+    let mut added = 0;
+    for suggestion in parse_suggestions(text)?.into_iter().take(MAX_SUGGESTIONS) {
+        let name = suggestion.name.trim();
+        if name.is_empty() || db::topic_name_exists(&conn, name)? {
+            continue;
+        }
+        if db::insert_topic_suggestion(&conn, name, suggestion.reason.as_deref())? {
+            added += 1;
+        }
+    }
+    info!("Topic discovery added {} suggestions", added);
+
+    let mut pending = db::get_topic_suggestions(&conn, "pending")?;
+    pending.truncate(added);
+    Ok(pending)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_discovery_prompt_and_parsing() {
+        let empty = DiscoverySignals::default();
+        assert!(build_discovery_prompt(&["AI".to_string()], &[], &empty).is_none());
+
+        let signals = DiscoverySignals {
+            bookmarked_cards: vec!["AI: New WebAssembly runtime ships".to_string()],
+            liked_cards: vec![],
+            chat_questions: vec!["How does this compare to Wasmtime?".to_string()],
+        };
+        let prompt = build_discovery_prompt(&["AI".to_string()], &[], &signals).unwrap();
+        assert!(prompt.contains("- AI: New WebAssembly runtime ships"));
+        assert!(prompt.contains("Wasmtime"));
+
+        let parsed = parse_suggestions(
+            "Here you go:\n{\"suggestions\": [{\"name\": \"WebAssembly runtimes\", \"reason\": \"Bookmarked Wasm news\"}]}",
+        )
+        .unwrap();
+        assert_eq!(parsed.len(), 1);
+        assert_eq!(parsed[0].name, "WebAssembly runtimes");
+
+        assert!(discovery_due(None, 7));
+        assert!(!discovery_due(None, 0));
+        assert!(discovery_due(Some("2000-01-01 00:00:00"), 7));
+        let now = Utc::now().format("%Y-%m-%d %H:%M:%S").to_string();
+        assert!(!discovery_due(Some(&now), 7));
+    }
+}
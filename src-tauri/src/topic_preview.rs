@@ -0,0 +1,367 @@
+//! Cost preview for a topic before it joins the daily rotation.
+//!
+//! One cheap Claude call (on [`PREVIEW_MODEL`], without tools) sketches how
+//! the topic would be researched: which of the available tools it would
+//! likely use and how many searches and page fetches a run takes. The token
+//! estimate is that number of steps times what a research step has cost in
+//! recent runs, priced with the configured research model.
+
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tracing::warn;
+
+use crate::agent_loop::{ApiEndpoint, Message, MessagesRequest, MessagesResponse};
+use crate::db;
+use crate::tools::{BuiltinTools, ToolFilter, ToolRegistry};
+
+/// Model used for the preview call, whatever the research model is.
+pub const PREVIEW_MODEL: &str = "claude-haiku-4-5-20251001";
+
+const MAX_TOKENS: u32 = 1024;
+
+/// Days of research logs used to price a research step.
+const HISTORY_DAYS: i64 = 30;
+
+/// Tokens per research step when there are no recent runs to learn from.
+/// Each step re-sends the conversation so far, so steps are expensive.
+const DEFAULT_TOKENS_PER_STEP: i64 = 8_000;
+
+/// Research runs in a month, for the monthly estimate.
+const RUNS_PER_MONTH: f64 = 30.0;
+
+/// What researching a topic is expected to take and cost.
+#[derive(Debug, Clone, Serialize)]
+pub struct TopicPreview {
+    pub topic: String,
+    /// Tools Claude expects to use, most important first
+    pub tools: Vec<String>,
+    pub searches: u32,
+    pub page_fetches: u32,
+    /// Every expected tool call, including searches and fetches
+    pub tool_calls: u32,
+    /// How Claude would approach the topic (one or two sentences)
+    pub plan: String,
+    /// Tokens of one research run of the topic
+    pub estimated_tokens: i64,
+    pub estimated_cost_usd: f64,
+    /// Daily runs for a month
+    pub estimated_monthly_cost_usd: f64,
+    /// Whether the step cost came from recent runs rather than a default
+    pub from_history: bool,
+    pub model: String,
+    /// Tokens spent on the preview itself
+    pub preview_tokens: u32,
+}
+
+/// Tool calls and tokens of topics researched recently.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct StepHistory {
+    /// Topics researched (one per topic per run)
+    pub topic_runs: i64,
+    pub tool_calls: i64,
+    pub tokens: i64,
+}
+
+impl StepHistory {
+    /// Tokens per research step: each tool call, plus the final answer of
+    /// each topic. None without enough history.
+    pub fn tokens_per_step(&self) -> Option<i64> {
+        if self.topic_runs == 0 || self.tokens <= 0 {
+            return None;
+        }
+        Some(self.tokens / (self.tool_calls + self.topic_runs))
+    }
+}
+
+/// Research steps and tokens of the last [`HISTORY_DAYS`] days.
+pub fn step_history(conn: &rusqlite::Connection) -> Result<StepHistory, String> {
+    conn.query_row(
+        r#"SELECT COUNT(DISTINCT briefing_id || '/' || topic),
+                  COALESCE(SUM(log_type IN ('tool_call', 'mcp_call')), 0),
+                  COALESCE(SUM(CASE WHEN log_type = 'api_request' THEN tokens_used END), 0)
+           FROM research_logs
+           WHERE briefing_id IS NOT NULL AND topic IS NOT NULL AND topic != ''
+             AND created_at >= datetime('now', ?1)"#,
+        [format!("-{} days", HISTORY_DAYS)],
+        |row| {
+            Ok(StepHistory {
+                topic_runs: row.get(0)?,
+                tool_calls: row.get(1)?,
+                tokens: row.get(2)?,
+            })
+        },
+    )
+    .map_err(|e| format!("Failed to load research history: {}", e))
+}
+
+/// Claude's plan for the topic.
+#[derive(Debug, Deserialize)]
+struct PlanResponse {
+    #[serde(default)]
+    tools: Vec<String>,
+    #[serde(default)]
+    searches: u32,
+    #[serde(default)]
+    page_fetches: u32,
+    #[serde(default)]
+    other_calls: u32,
+    #[serde(default)]
+    plan: String,
+}
+
+/// Tools research could use, as (name, what it does). Enabled MCP servers
+/// are listed by server, since their tools aren't known until they connect.
+fn available_tools() -> Vec<(String, String)> {
+    let filter = ToolFilter::current();
+    let mut tools = Vec::new();
+    if filter.allows(None, "web_search") {
+        tools.push(("web_search".to_string(), "Search the web".to_string()));
+    }
+    for tool in BuiltinTools::from_env().with_filter(filter).definitions() {
+        let summary = tool.description.split(". ").next().unwrap_or_default();
+        tools.push((tool.name, summary.trim_end_matches('.').to_string()));
+    }
+    match crate::config::read_mcp_servers() {
+        Ok(config) => tools.extend(
+            config
+                .servers
+                .into_iter()
+                .filter(|s| s.enabled)
+                .map(|s| (s.name, "MCP server".to_string())),
+        ),
+        Err(e) => warn!("Failed to read MCP servers for topic preview: {}", e),
+    }
+    tools
+}
+
+fn build_prompt(
+    name: &str,
+    description: Option<&str>,
+    preferred_sources: &[String],
+    depth: &str,
+    tools: &[(String, String)],
+) -> String {
+    let list = |items: &[String]| -> String {
+        if items.is_empty() {
+            "(none)".to_string()
+        } else {
+            items
+                .iter()
+                .map(|i| format!("- {}", i))
+                .collect::<Vec<_>>()
+                .join("\n")
+        }
+    };
+
+    format!(
+        r#"A daily research briefing app researches each of the user's topics once a day with the tools below, then writes briefing cards about what is new.
+
+TOPIC: {}
+DESCRIPTION: {}
+PREFERRED SOURCES:
+{}
+RESEARCH DEPTH: {}
+
+AVAILABLE TOOLS:
+{}
+
+Plan one day's research of this topic. Do not research it now.
+- List the tools (by name, from the list above) you would most likely use, most important first
+- Estimate how many web searches, page fetches and other tool calls one run would take
+- Be realistic: a narrow topic needs fewer calls than a broad one, and deeper research needs more
+
+Return ONLY valid JSON in this exact format:
+{{"tools": ["web_search"], "searches": 3, "page_fetches": 4, "other_calls": 0, "plan": "One or two sentences on how you would research it"}}"#,
+        name,
+        description
+            .filter(|d| !d.trim().is_empty())
+            .unwrap_or("(none)"),
+        list(preferred_sources),
+        depth,
+        list(
+            &tools
+                .iter()
+                .map(|(name, summary)| format!("{}: {}", name, summary))
+                .collect::<Vec<_>>()
+        ),
+    )
+}
+
+/// Parse Claude's plan, tolerating text around the JSON object.
+fn parse_plan(response: &str) -> Result<PlanResponse, String> {
+    let start = response.find('{');
+    let end = response.rfind('}');
+    let json_str = match (start, end) {
+        (Some(start), Some(end)) if start < end => &response[start..=end],
+        _ => response,
+    };
+    serde_json::from_str(json_str).map_err(|e| format!("Failed to parse topic preview: {}", e))
+}
+
+/// Turn Claude's plan into a preview priced for `model`. Tools Claude made
+/// up (not in `available`) are dropped.
+fn estimate(
+    topic: &str,
+    plan: PlanResponse,
+    available: &[(String, String)],
+    history: StepHistory,
+    model: &str,
+) -> TopicPreview {
+    let tools: Vec<String> = plan
+        .tools
+        .into_iter()
+        .filter_map(|tool| {
+            available
+                .iter()
+                .find(|(name, _)| name.eq_ignore_ascii_case(tool.trim()))
+                .map(|(name, _)| name.clone())
+        })
+        .collect();
+
+    let tool_calls = plan.searches + plan.page_fetches + plan.other_calls;
+    let tokens_per_step = history.tokens_per_step();
+    let estimated_tokens =
+        i64::from(tool_calls + 1) * tokens_per_step.unwrap_or(DEFAULT_TOKENS_PER_STEP);
+    let estimated_cost_usd = crate::research_log::estimate_cost_usd(model, estimated_tokens, 0);
+
+    TopicPreview {
+        topic: topic.to_string(),
+        tools,
+        searches: plan.searches,
+        page_fetches: plan.page_fetches,
+        tool_calls,
+        plan: plan.plan.trim().to_string(),
+        estimated_tokens,
+        estimated_cost_usd,
+        estimated_monthly_cost_usd: estimated_cost_usd * RUNS_PER_MONTH,
+        from_history: tokens_per_step.is_some(),
+        model: model.to_string(),
+        preview_tokens: 0,
+    }
+}
+
+/// Preview researching a topic that isn't in the rotation yet. `model` is
+/// the research model the estimate is priced for.
+pub async fn preview_topic(
+    api_key: &str,
+    model: &str,
+    name: &str,
+    description: Option<&str>,
+    preferred_sources: &[String],
+) -> Result<TopicPreview, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("Topic name cannot be empty".to_string());
+    }
+    let depth = crate::config::read_settings()
+        .unwrap_or_default()
+        .research_depth;
+    let tools = available_tools();
+    let history = {
+        let conn = db::get_connection().map_err(|e| format!("Database error: {}", e))?;
+        step_history(&conn)?
+    };
+
+    let endpoint = ApiEndpoint::current();
+    let request = MessagesRequest {
+        model: PREVIEW_MODEL.to_string(),
+        max_tokens: MAX_TOKENS,
+        messages: vec![Message::user(build_prompt(
+            name,
+            description,
+            preferred_sources,
+            &depth,
+            &tools,
+        ))],
+        tools: None,
+        system: None,
+        thinking: None,
+        metadata: endpoint.metadata(),
+    };
+
+    let client = crate::http_client::client_builder()
+        .timeout(Duration::from_secs(60))
+        .build()
+        .map_err(|e| format!("Failed to build HTTP client: {}", e))?;
+    let response = endpoint
+        .authorize(client.post(endpoint.messages_url()), api_key)
+        .header("content-type", "application/json")
+        .json(&request)
+        .send()
+        .await
+        .map_err(|e| format!("HTTP request failed: {}", e))?;
+
+    if !response.status().is_success() {
+        let status = response.status().as_u16();
+        let body = response.text().await.unwrap_or_default();
+        return Err(format!("API error {}: {}", status, body));
+    }
+
+    let response: MessagesResponse = response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse response: {}", e))?;
+
+    let conn = db::get_connection().map_err(|e| format!("Database error: {}", e))?;
+    if let Err(e) = db::record_api_usage(
+        &conn,
+        &crate::config::active_profile(),
+        "preview",
+        None,
+        i64::from(response.total_tokens()),
+    ) {
+        warn!("Failed to record topic preview usage: {}", e);
+    }
+
+    let plan = parse_plan(&response.text())?;
+    let mut preview = estimate(name, plan, &tools, history, model);
+    preview.preview_tokens = response.total_tokens();
+    Ok(preview)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_plan_and_estimate() {
+        let plan = parse_plan(
+            "Sure:\n{\"tools\": [\"web_search\", \"made_up_tool\", \"github\"], \"searches\": 3, \"page_fetches\": 4, \"other_calls\": 1, \"plan\": \" Search news, read the top posts. \"}",
+        )
+        .unwrap();
+        let available = vec![
+            ("web_search".to_string(), "Search the web".to_string()),
+            ("fetch_webpage".to_string(), "Fetch a page".to_string()),
+            ("GitHub".to_string(), "MCP server".to_string()),
+        ];
+
+        let preview = estimate(
+            "Rust",
+            plan,
+            &available,
+            StepHistory::default(),
+            "claude-sonnet-4-5",
+        );
+        assert_eq!(preview.tools, vec!["web_search", "GitHub"]);
+        assert_eq!(preview.tool_calls, 8);
+        assert_eq!(preview.plan, "Search news, read the top posts.");
+        assert!(!preview.from_history);
+        assert_eq!(preview.estimated_tokens, 9 * DEFAULT_TOKENS_PER_STEP);
+        assert!(
+            (preview.estimated_monthly_cost_usd - preview.estimated_cost_usd * 30.0).abs() < 1e-9
+        );
+
+        assert!(parse_plan("no plan").is_err());
+    }
+
+    #[test]
+    fn test_tokens_per_step() {
+        assert_eq!(StepHistory::default().tokens_per_step(), None);
+        let history = StepHistory {
+            topic_runs: 4,
+            tool_calls: 16,
+            tokens: 100_000,
+        };
+        assert_eq!(history.tokens_per_step(), Some(5_000));
+    }
+}
@@ -0,0 +1,288 @@
+//! Longitudinal view of one topic.
+//!
+//! Each briefing is a daily snapshot. A timeline gathers every card ever
+//! saved for a topic, oldest first, so its developments can be read in
+//! order. Optionally Claude turns the cards into a narrative timeline,
+//! citing them with the archive's citation tags (`[briefing 42, card 3]`).
+//! Weekly and monthly meta-briefings are left out, since they restate the
+//! daily cards.
+
+use chrono::Local;
+use serde::Serialize;
+use std::time::Duration;
+use tracing::{info, warn};
+
+use crate::agent_loop::{ApiEndpoint, Message, MessagesRequest, MessagesResponse};
+use crate::db;
+use crate::research::BriefingCard;
+
+/// Most recent cards given to Claude for the narrative.
+const MAX_NARRATIVE_CARDS: usize = 150;
+
+/// Characters of each card's detailed content included in the narrative context.
+const CONTEXT_CHARS_PER_CARD: usize = 600;
+
+const MAX_TOKENS: u32 = 4096;
+
+/// One card in a topic's timeline.
+#[derive(Debug, Clone, Serialize)]
+pub struct TimelineEntry {
+    pub briefing_id: i64,
+    pub card_index: i32,
+    pub date: String,
+    pub title: String,
+    pub summary: String,
+    pub relevance: String,
+    pub sources: Vec<String>,
+    #[serde(skip)]
+    detailed_content: String,
+}
+
+impl TimelineEntry {
+    /// Tag Claude uses to cite this card (card numbers are 1-based).
+    pub fn citation_tag(&self) -> String {
+        format!(
+            "[briefing {}, card {}]",
+            self.briefing_id,
+            self.card_index + 1
+        )
+    }
+}
+
+/// Every card of a topic, oldest first, with an optional narrative.
+#[derive(Debug, Clone, Serialize)]
+pub struct TopicTimeline {
+    pub topic: String,
+    pub entries: Vec<TimelineEntry>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub narrative: Option<String>,
+    pub tokens_used: u32,
+}
+
+/// Cards whose topic matches `topic` (case-insensitive), oldest first.
+/// With `days`, only briefings from the last `days` days are included.
+pub fn load_timeline(
+    conn: &rusqlite::Connection,
+    topic: &str,
+    days: Option<u32>,
+) -> Result<TopicTimeline, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, date, cards FROM briefings
+             WHERE (tag IS NULL OR tag NOT IN ('weekly', 'monthly'))
+               AND (?1 IS NULL OR date > datetime('now', '-' || ?1 || ' days'))
+             ORDER BY date ASC, id ASC",
+        )
+        .map_err(|e| format!("Failed to prepare statement: {}", e))?;
+    let rows = stmt
+        .query_map([days], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+            ))
+        })
+        .map_err(|e| format!("Query failed: {}", e))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("Failed to collect results: {}", e))?;
+
+    let wanted = topic.trim().to_lowercase();
+    let mut entries = Vec::new();
+    for (briefing_id, date, cards_json) in rows {
+        let Ok(cards) = serde_json::from_str::<Vec<BriefingCard>>(&cards_json) else {
+            continue;
+        };
+        for (index, card) in cards.into_iter().enumerate() {
+            if card.topic.trim().to_lowercase() != wanted {
+                continue;
+            }
+            entries.push(TimelineEntry {
+                briefing_id,
+                card_index: index as i32,
+                date: date.clone(),
+                title: card.title,
+                summary: card.summary,
+                relevance: card.relevance,
+                sources: card.sources,
+                detailed_content: card.detailed_content,
+            });
+        }
+    }
+
+    Ok(TopicTimeline {
+        topic: topic.trim().to_string(),
+        entries,
+        narrative: None,
+        tokens_used: 0,
+    })
+}
+
+/// Context block listing the cards with their dates and citation tags.
+fn build_context(entries: &[TimelineEntry]) -> String {
+    entries
+        .iter()
+        .map(|entry| {
+            let details: String = entry
+                .detailed_content
+                .chars()
+                .take(CONTEXT_CHARS_PER_CARD)
+                .collect();
+            let mut text = format!(
+                "{} {}\nTitle: {}\nSummary: {}",
+                entry.citation_tag(),
+                entry.date.get(..10).unwrap_or(entry.date.as_str()),
+                entry.title,
+                entry.summary
+            );
+            if !details.is_empty() {
+                text.push_str(&format!("\nDetails: {}", details));
+            }
+            text
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+fn build_prompt(topic: &str, context: &str) -> String {
+    format!(
+        r#"Below are the user's research briefing cards about "{topic}", oldest first. Each starts with a citation tag and the date it was saved. Today's date is {date}.
+
+Write a narrative timeline of how "{topic}" developed over this period. Use markdown: a short overview paragraph, then a dated list of the key developments in chronological order, then a few sentences on where things stand now and what to watch. Merge cards that report the same development. Cite the cards you use with their tags exactly as written (for example [briefing 42, card 3]).
+
+{context}"#,
+        topic = topic,
+        date = Local::now().format("%B %d, %Y"),
+        context = context
+    )
+}
+
+/// Ask Claude for a narrative of the timeline's most recent cards, filling
+/// in `narrative` and `tokens_used`.
+pub async fn narrate_timeline(
+    api_key: &str,
+    model: &str,
+    timeline: &mut TopicTimeline,
+) -> Result<(), String> {
+    if timeline.entries.is_empty() {
+        return Err(format!("No cards found for topic '{}'", timeline.topic));
+    }
+    let recent = &timeline.entries[timeline.entries.len().saturating_sub(MAX_NARRATIVE_CARDS)..];
+    info!(
+        "Narrating timeline for '{}' from {} of {} cards",
+        timeline.topic,
+        recent.len(),
+        timeline.entries.len()
+    );
+
+    let endpoint = ApiEndpoint::current();
+    let request = MessagesRequest {
+        model: model.to_string(),
+        max_tokens: MAX_TOKENS,
+        messages: vec![Message::user(build_prompt(
+            &timeline.topic,
+            &build_context(recent),
+        ))],
+        tools: None,
+        system: None,
+        thinking: None,
+        metadata: endpoint.metadata(),
+    };
+
+    let client = crate::http_client::client_builder()
+        .timeout(Duration::from_secs(180))
+        .connect_timeout(Duration::from_secs(10))
+        .build()
+        .map_err(|e| format!("Failed to build HTTP client: {}", e))?;
+    let response = endpoint
+        .authorize(client.post(endpoint.messages_url()), api_key)
+        .header("content-type", "application/json")
+        .json(&request)
+        .send()
+        .await
+        .map_err(|e| format!("HTTP request failed: {}", e))?;
+
+    if !response.status().is_success() {
+        let status = response.status().as_u16();
+        let body = response.text().await.unwrap_or_default();
+        return Err(format!("API error {}: {}", status, body));
+    }
+
+    let response: MessagesResponse = response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse response: {}", e))?;
+    timeline.tokens_used = response.total_tokens();
+    timeline.narrative = Some(response.text().trim().to_string());
+
+    let conn = db::get_connection().map_err(|e| format!("Database error: {}", e))?;
+    if let Err(e) = db::record_api_usage(
+        &conn,
+        &crate::config::active_profile(),
+        "chat",
+        None,
+        timeline.tokens_used as i64,
+    ) {
+        warn!("Failed to record timeline usage: {}", e);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rusqlite::Connection;
+
+    fn setup_test_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(include_str!("schema.sql")).unwrap();
+        conn
+    }
+
+    fn insert_briefing(conn: &Connection, date: &str, tag: Option<&str>, topics: &[&str]) {
+        let cards: Vec<serde_json::Value> = topics
+            .iter()
+            .enumerate()
+            .map(|(i, topic)| {
+                serde_json::json!({
+                    "title": format!("{} {} #{}", topic, date, i),
+                    "summary": "",
+                    "detailed_content": "",
+                    "sources": [],
+                    "suggested_next": null,
+                    "relevance": "medium",
+                    "topic": topic,
+                })
+            })
+            .collect();
+        conn.execute(
+            "INSERT INTO briefings (date, title, cards, tag) VALUES (?1, 'Briefing', ?2, ?3)",
+            rusqlite::params![date, serde_json::to_string(&cards).unwrap(), tag],
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_load_timeline_is_chronological_and_skips_meta_briefings() {
+        let conn = setup_test_db();
+        insert_briefing(&conn, "2025-03-02T06:00:00+00:00", None, &["AI", "rust"]);
+        insert_briefing(&conn, "2025-03-01T06:00:00+00:00", None, &["Rust"]);
+        insert_briefing(
+            &conn,
+            "2025-03-03T06:00:00+00:00",
+            Some("weekly"),
+            &["Rust"],
+        );
+
+        let timeline = load_timeline(&conn, " Rust ", None).unwrap();
+        assert_eq!(timeline.topic, "Rust");
+        let cards: Vec<(i64, i32)> = timeline
+            .entries
+            .iter()
+            .map(|e| (e.briefing_id, e.card_index))
+            .collect();
+        assert_eq!(cards, vec![(2, 0), (1, 1)]);
+        assert_eq!(timeline.entries[1].citation_tag(), "[briefing 1, card 2]");
+
+        assert!(load_timeline(&conn, "Go", None).unwrap().entries.is_empty());
+    }
+}
@@ -48,6 +48,9 @@ pub fn init_tray(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
                     show_main_window(app);
                 }
                 "quit" => {
+                    if let Some(window) = app.get_webview_window("main") {
+                        crate::ui_state::save_window_geometry(&window);
+                    }
                     app.exit(0);
                 }
                 _ => {}
@@ -72,17 +75,7 @@ fn toggle_popover(app: &AppHandle) {
             }
             Ok(false) => {
                 info!("Popover is hidden, showing it");
-                // Position the popover in the top-right area near the tray
-                if let Err(e) = window.move_window(Position::TopRight) {
-                    warn!("Failed to position popover: {}", e);
-                }
-                if let Err(e) = window.show() {
-                    warn!("Failed to show popover: {}", e);
-                }
-                if let Err(e) = window.set_focus() {
-                    warn!("Failed to focus popover: {}", e);
-                }
-                info!("Popover shown and focused");
+                show_popover(app);
             }
             Err(e) => {
                 warn!("Failed to check popover visibility: {}", e);
@@ -93,6 +86,24 @@ fn toggle_popover(app: &AppHandle) {
     }
 }
 
+/// Show the popover in the top-right area near the tray and focus it.
+pub fn show_popover(app: &AppHandle) {
+    let Some(window) = app.get_webview_window("popover") else {
+        warn!("Popover window not found");
+        return;
+    };
+    if let Err(e) = window.move_window(Position::TopRight) {
+        warn!("Failed to position popover: {}", e);
+    }
+    if let Err(e) = window.show() {
+        warn!("Failed to show popover: {}", e);
+    }
+    if let Err(e) = window.set_focus() {
+        warn!("Failed to focus popover: {}", e);
+    }
+    info!("Popover shown and focused");
+}
+
 /// Show the main window and hide the popover.
 pub fn show_main_window(app: &AppHandle) {
     // Show main window
@@ -0,0 +1,421 @@
+//! Briefing narration module using text-to-speech.
+//!
+//! Converts a briefing (or a single card) into spoken audio so it can be
+//! listened to instead of read. Uses the OpenAI TTS API by default, producing
+//! MP3 files stored alongside generated images in `~/.claudius/images/`.
+//! A local engine (`say` on macOS, `espeak-ng`/`espeak` elsewhere) is
+//! available for users without an OpenAI key.
+#![allow(dead_code)]
+
+use crate::research::BriefingCard;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use tracing::{debug, info, warn};
+
+/// OpenAI TTS rejects inputs longer than 4096 characters.
+const MAX_CHUNK_CHARS: usize = 4000;
+
+/// Voices accepted by the OpenAI TTS API.
+pub const OPENAI_VOICES: &[&str] = &["alloy", "echo", "fable", "onyx", "nova", "shimmer"];
+
+/// Which text-to-speech engine to use.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TtsEngine {
+    /// OpenAI TTS API (MP3 output, requires an OpenAI API key)
+    OpenAi,
+    /// Platform speech synthesizer (no network, lower quality)
+    Local,
+}
+
+impl TtsEngine {
+    /// Parse the `tts_engine` setting. Unknown values fall back to OpenAI.
+    pub fn from_setting(value: &str) -> Self {
+        match value.trim().to_lowercase().as_str() {
+            "local" | "system" => TtsEngine::Local,
+            _ => TtsEngine::OpenAi,
+        }
+    }
+
+    /// File extension of the audio this engine produces.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            TtsEngine::OpenAi => "mp3",
+            TtsEngine::Local if cfg!(target_os = "macos") => "aiff",
+            TtsEngine::Local => "wav",
+        }
+    }
+}
+
+/// OpenAI TTS API request
+#[derive(Serialize)]
+struct SpeechRequest<'a> {
+    model: &'a str,
+    input: &'a str,
+    voice: &'a str,
+    response_format: &'a str,
+}
+
+/// Get the audio path for a briefing, or for a single card when `card_index` is set.
+///
+/// Audio is stored next to generated images so housekeeping cleans both up.
+pub fn get_audio_path(
+    briefing_id: i64,
+    card_index: Option<usize>,
+    engine: TtsEngine,
+) -> Result<PathBuf, String> {
+    let name = match card_index {
+        Some(idx) => format!("{}_{}_narration.{}", briefing_id, idx, engine.extension()),
+        None => format!("{}_narration.{}", briefing_id, engine.extension()),
+    };
+    Ok(crate::image_gen::get_images_dir()?.join(name))
+}
+
+/// Delete all narration audio for a briefing
+pub fn delete_briefing_audio(briefing_id: i64) -> Result<usize, String> {
+    let dir = crate::image_gen::get_images_dir()?;
+    if !dir.exists() {
+        return Ok(0);
+    }
+
+    let prefix = format!("{}_", briefing_id);
+    let mut deleted = 0;
+
+    let entries =
+        std::fs::read_dir(&dir).map_err(|e| format!("Failed to read images directory: {}", e))?;
+
+    for entry in entries.flatten() {
+        if let Some(filename) = entry.file_name().to_str() {
+            let is_audio = [".mp3", ".aiff", ".wav"]
+                .iter()
+                .any(|ext| filename.ends_with(ext));
+            if filename.starts_with(&prefix) && filename.contains("_narration.") && is_audio {
+                if let Err(e) = std::fs::remove_file(entry.path()) {
+                    warn!("Failed to delete audio {}: {}", filename, e);
+                } else {
+                    deleted += 1;
+                    debug!("Deleted audio: {}", filename);
+                }
+            }
+        }
+    }
+
+    if deleted > 0 {
+        info!(
+            "Deleted {} audio files for briefing {}",
+            deleted, briefing_id
+        );
+    }
+    Ok(deleted)
+}
+
+/// Build the spoken script for a single card.
+pub fn card_script(card: &BriefingCard) -> String {
+    let mut script = format!("{}.", card.title.trim().trim_end_matches('.'));
+    if !card.summary.trim().is_empty() {
+        script.push_str("\n\n");
+        script.push_str(card.summary.trim());
+    }
+    if !card.detailed_content.trim().is_empty() {
+        script.push_str("\n\n");
+        script.push_str(&strip_markdown(&card.detailed_content));
+    }
+    script
+}
+
+/// Build the spoken script for a whole briefing.
+pub fn briefing_script(title: &str, cards: &[BriefingCard]) -> String {
+    let mut parts = vec![format!(
+        "{}. {} {} today.",
+        title.trim().trim_end_matches('.'),
+        cards.len(),
+        if cards.len() == 1 { "story" } else { "stories" }
+    )];
+    for (i, card) in cards.iter().enumerate() {
+        let intro = if i + 1 == cards.len() && cards.len() > 1 {
+            "Finally".to_string()
+        } else {
+            format!("Story {}", i + 1)
+        };
+        parts.push(format!("{}. {}", intro, card_script(card)));
+    }
+    parts.join("\n\n")
+}
+
+/// Remove markdown syntax that would otherwise be read aloud.
+fn strip_markdown(text: &str) -> String {
+    text.lines()
+        .map(|line| {
+            line.trim()
+                .trim_start_matches('#')
+                .trim_start_matches("- ")
+                .trim_start_matches("* ")
+                .replace("**", "")
+                .replace('`', "")
+                .trim()
+                .to_string()
+        })
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Split a script into chunks no longer than `max_chars`, breaking on
+/// paragraph and sentence boundaries where possible.
+fn split_into_chunks(text: &str, max_chars: usize) -> Vec<String> {
+    let mut chunks = Vec::new();
+    let mut current = String::new();
+
+    let sentences = text.split_inclusive(['.', '!', '?', '\n']);
+    for sentence in sentences {
+        if current.chars().count() + sentence.chars().count() > max_chars && !current.is_empty() {
+            chunks.push(current.trim().to_string());
+            current.clear();
+        }
+        // A single sentence longer than the limit is hard-split
+        let mut rest = sentence;
+        while rest.chars().count() > max_chars {
+            let split_at = rest
+                .char_indices()
+                .nth(max_chars)
+                .map(|(i, _)| i)
+                .unwrap_or(rest.len());
+            chunks.push(rest[..split_at].trim().to_string());
+            rest = &rest[split_at..];
+        }
+        current.push_str(rest);
+    }
+
+    if !current.trim().is_empty() {
+        chunks.push(current.trim().to_string());
+    }
+    chunks.retain(|c| !c.is_empty());
+    chunks
+}
+
+/// Synthesize `text` with the OpenAI TTS API and write the MP3 to `path`.
+///
+/// Long scripts are sent in chunks; MP3 frames can be concatenated directly.
+async fn synthesize_openai(
+    text: &str,
+    path: &Path,
+    voice: &str,
+    api_key: &str,
+) -> Result<(), String> {
+    let client = crate::http_client::client();
+    let voice = if OPENAI_VOICES.contains(&voice) {
+        voice
+    } else {
+        warn!("Unknown TTS voice '{}', using 'alloy'", voice);
+        "alloy"
+    };
+
+    let chunks = split_into_chunks(text, MAX_CHUNK_CHARS);
+    debug!(
+        "Synthesizing {} TTS chunks with voice {}",
+        chunks.len(),
+        voice
+    );
+
+    let mut audio = Vec::new();
+    for (i, chunk) in chunks.iter().enumerate() {
+        let request = SpeechRequest {
+            model: "tts-1",
+            input: chunk,
+            voice,
+            response_format: "mp3",
+        };
+
+        let response = client
+            .post("https://api.openai.com/v1/audio/speech")
+            .header("Authorization", format!("Bearer {}", api_key))
+            .header("Content-Type", "application/json")
+            .json(&request)
+            .send()
+            .await
+            .map_err(|e| format!("TTS request failed: {}", e))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            return Err(format!("TTS API error {}: {}", status, body));
+        }
+
+        let bytes = response
+            .bytes()
+            .await
+            .map_err(|e| format!("Failed to read TTS audio (chunk {}): {}", i + 1, e))?;
+        audio.extend_from_slice(&bytes);
+    }
+
+    std::fs::write(path, audio).map_err(|e| format!("Failed to write audio: {}", e))
+}
+
+/// Synthesize `text` with the platform speech synthesizer.
+async fn synthesize_local(text: &str, path: &Path) -> Result<(), String> {
+    let candidates: &[&str] = if cfg!(target_os = "macos") {
+        &["say"]
+    } else {
+        &["espeak-ng", "espeak"]
+    };
+
+    for program in candidates {
+        let mut command = tokio::process::Command::new(program);
+        if *program == "say" {
+            command.arg("-o").arg(path).arg(text);
+        } else {
+            command.arg("-w").arg(path).arg(text);
+        }
+
+        match command.output().await {
+            Ok(output) if output.status.success() => return Ok(()),
+            Ok(output) => {
+                return Err(format!(
+                    "{} failed: {}",
+                    program,
+                    String::from_utf8_lossy(&output.stderr).trim()
+                ))
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+            Err(e) => return Err(format!("Failed to run {}: {}", program, e)),
+        }
+    }
+
+    Err(format!(
+        "No local speech engine found (tried {}). Install one or use the OpenAI engine.",
+        candidates.join(", ")
+    ))
+}
+
+/// Narrate a briefing, or a single card when `card_index` is set.
+///
+/// # Arguments
+/// * `briefing_id` - ID of the briefing (for file naming)
+/// * `title` - Briefing title, read as the introduction
+/// * `cards` - Cards in the briefing
+/// * `card_index` - Narrate only this card instead of the whole briefing
+/// * `engine` - TTS engine to use
+/// * `voice` - OpenAI voice name (ignored by the local engine)
+/// * `api_key` - OpenAI API key (required for the OpenAI engine)
+///
+/// # Returns
+/// Path to the generated audio file.
+pub async fn narrate_briefing(
+    briefing_id: i64,
+    title: &str,
+    cards: &[BriefingCard],
+    card_index: Option<usize>,
+    engine: TtsEngine,
+    voice: &str,
+    api_key: Option<&str>,
+) -> Result<PathBuf, String> {
+    let script = match card_index {
+        Some(idx) => card_script(
+            cards
+                .get(idx)
+                .ok_or_else(|| format!("Card {} not found in briefing {}", idx, briefing_id))?,
+        ),
+        None => {
+            if cards.is_empty() {
+                return Err("Briefing has no cards to narrate".to_string());
+            }
+            briefing_script(title, cards)
+        }
+    };
+
+    let path = get_audio_path(briefing_id, card_index, engine)?;
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)
+            .map_err(|e| format!("Failed to create images directory: {}", e))?;
+    }
+
+    info!(
+        "Narrating briefing {} ({} chars, engine: {:?})",
+        briefing_id,
+        script.len(),
+        engine
+    );
+
+    match engine {
+        TtsEngine::OpenAi => {
+            let key = api_key.ok_or("No OpenAI API key configured. Add one in Settings.")?;
+            synthesize_openai(&script, &path, voice, key).await?;
+        }
+        TtsEngine::Local => synthesize_local(&script, &path).await?,
+    }
+
+    info!("Audio generated: {:?}", path);
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn card(title: &str, summary: &str, details: &str) -> BriefingCard {
+        BriefingCard {
+            title: title.to_string(),
+            summary: summary.to_string(),
+            detailed_content: details.to_string(),
+            sources: vec![],
+            suggested_next: None,
+            relevance: "high".to_string(),
+            topic: "Rust".to_string(),
+            image_prompt: None,
+            image_style: None,
+            image_path: None,
+            source_checks: vec![],
+            action_items: vec![],
+            actions: vec![],
+            source_warning: None,
+            rank: None,
+        }
+    }
+
+    #[test]
+    fn test_get_audio_path() {
+        let path = get_audio_path(42, None, TtsEngine::OpenAi).expect("Should get audio path");
+        assert!(path.to_string_lossy().ends_with("42_narration.mp3"));
+        let path = get_audio_path(42, Some(3), TtsEngine::OpenAi).expect("Should get audio path");
+        assert!(path.to_string_lossy().ends_with("42_3_narration.mp3"));
+    }
+
+    #[test]
+    fn test_engine_from_setting() {
+        assert_eq!(TtsEngine::from_setting("local"), TtsEngine::Local);
+        assert_eq!(TtsEngine::from_setting("OpenAI"), TtsEngine::OpenAi);
+        assert_eq!(TtsEngine::from_setting("bogus"), TtsEngine::OpenAi);
+    }
+
+    #[test]
+    fn test_briefing_script() {
+        let cards = vec![
+            card(
+                "Rust 2.0 announced",
+                "Big news.",
+                "## Details\n- **Faster** builds",
+            ),
+            card("Tokio release", "New runtime.", ""),
+        ];
+        let script = briefing_script("Morning Briefing", &cards);
+        assert!(script.starts_with("Morning Briefing. 2 stories today."));
+        assert!(script.contains("Story 1. Rust 2.0 announced."));
+        assert!(script.contains("Faster builds"));
+        assert!(!script.contains("**"));
+        assert!(script.contains("Finally. Tokio release."));
+    }
+
+    #[test]
+    fn test_split_into_chunks() {
+        let text = "First sentence. Second sentence. Third sentence.";
+        let chunks = split_into_chunks(text, 20);
+        assert_eq!(
+            chunks,
+            vec!["First sentence.", "Second sentence.", "Third sentence."]
+        );
+
+        let long = "a".repeat(45);
+        let chunks = split_into_chunks(&long, 20);
+        assert_eq!(chunks.len(), 3);
+        assert!(chunks.iter().all(|c| c.chars().count() <= 20));
+    }
+}
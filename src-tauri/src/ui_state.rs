@@ -0,0 +1,190 @@
+//! Where the user left the main window, kept in `~/.claudius/ui_state.json`.
+//!
+//! The window's size and position are saved when it is closed (hidden) or
+//! the app quits from the tray, and restored on startup. The frontend saves
+//! the view and the briefing/card it shows with `save_ui_state` and reopens
+//! them from `get_ui_state`.
+
+use crate::config::get_config_dir;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use tauri::{PhysicalPosition, PhysicalSize, WebviewWindow};
+use tracing::{debug, warn};
+
+/// Smallest window restored; anything smaller was saved by mistake.
+const MIN_WINDOW_SIZE: u32 = 200;
+
+/// Size and position of the main window, in physical pixels.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct WindowGeometry {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+    /// Size and position are those from before the window was maximized
+    #[serde(default)]
+    pub maximized: bool,
+}
+
+/// A monitor's area, in physical pixels: (x, y, width, height).
+type MonitorArea = (i32, i32, u32, u32);
+
+impl WindowGeometry {
+    /// Whether the geometry is usable on the current monitors: big enough,
+    /// and with the window's top-left corner on one of them (a monitor that
+    /// was unplugged would leave the window off screen).
+    fn fits(&self, monitors: &[MonitorArea]) -> bool {
+        if self.width < MIN_WINDOW_SIZE || self.height < MIN_WINDOW_SIZE {
+            return false;
+        }
+        monitors.iter().any(|&(x, y, width, height)| {
+            self.x >= x
+                && self.y >= y
+                && i64::from(self.x) < i64::from(x) + i64::from(width)
+                && i64::from(self.y) < i64::from(y) + i64::from(height)
+        })
+    }
+}
+
+/// Saved window geometry and last view.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct UiState {
+    #[serde(default)]
+    pub window: Option<WindowGeometry>,
+    /// Route of the last view (e.g. "/history?briefing=12&card=2")
+    #[serde(default)]
+    pub route: Option<String>,
+    #[serde(default)]
+    pub briefing_id: Option<i64>,
+    #[serde(default)]
+    pub card_index: Option<usize>,
+}
+
+fn get_state_path() -> PathBuf {
+    get_config_dir().join("ui_state.json")
+}
+
+/// The saved state, or the default if there is none or it can't be read.
+pub fn read_ui_state() -> UiState {
+    std::fs::read_to_string(get_state_path())
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+pub fn write_ui_state(state: &UiState) -> Result<(), String> {
+    let content = serde_json::to_string_pretty(state)
+        .map_err(|e| format!("Failed to serialize UI state: {}", e))?;
+    std::fs::write(get_state_path(), content)
+        .map_err(|e| format!("Failed to write UI state: {}", e))
+}
+
+/// Save the main window's current size and position.
+pub fn save_window_geometry(window: &WebviewWindow) {
+    if window.is_minimized().unwrap_or(false) {
+        return;
+    }
+    let mut state = read_ui_state();
+    let maximized = window.is_maximized().unwrap_or(false);
+    let geometry = if maximized {
+        // Keep the size to return to when it's unmaximized
+        state.window.map(|geometry| WindowGeometry {
+            maximized: true,
+            ..geometry
+        })
+    } else {
+        match (window.outer_position(), window.inner_size()) {
+            (Ok(position), Ok(size)) => Some(WindowGeometry {
+                x: position.x,
+                y: position.y,
+                width: size.width,
+                height: size.height,
+                maximized: false,
+            }),
+            _ => None,
+        }
+    };
+
+    let Some(geometry) = geometry else {
+        return;
+    };
+    state.window = Some(geometry);
+    match write_ui_state(&state) {
+        Ok(()) => debug!("Saved window geometry: {:?}", geometry),
+        Err(e) => warn!("{}", e),
+    }
+}
+
+/// Put the main window back where it was saved, unless that is no longer
+/// on screen.
+pub fn restore_window_geometry(window: &WebviewWindow) {
+    let Some(geometry) = read_ui_state().window else {
+        return;
+    };
+    let monitors: Vec<MonitorArea> = window
+        .available_monitors()
+        .unwrap_or_default()
+        .iter()
+        .map(|m| {
+            (
+                m.position().x,
+                m.position().y,
+                m.size().width,
+                m.size().height,
+            )
+        })
+        .collect();
+    if !geometry.fits(&monitors) {
+        debug!("Saved window geometry is off screen, keeping the default");
+        return;
+    }
+
+    let _ = window.set_size(PhysicalSize::new(geometry.width, geometry.height));
+    let _ = window.set_position(PhysicalPosition::new(geometry.x, geometry.y));
+    if geometry.maximized {
+        let _ = window.maximize();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_geometry_fits() {
+        let geometry = WindowGeometry {
+            x: 100,
+            y: 80,
+            width: 1200,
+            height: 800,
+            maximized: false,
+        };
+        let laptop = (0, 0, 2880, 1800);
+        let external = (2880, 0, 3840, 2160);
+        assert!(geometry.fits(&[laptop]));
+
+        // Left on a monitor that has since been unplugged
+        let on_external = WindowGeometry {
+            x: 3000,
+            ..geometry
+        };
+        assert!(on_external.fits(&[laptop, external]));
+        assert!(!on_external.fits(&[laptop]));
+        assert!(!geometry.fits(&[]));
+
+        let tiny = WindowGeometry {
+            width: 50,
+            ..geometry
+        };
+        assert!(!tiny.fits(&[laptop]));
+    }
+
+    #[test]
+    fn test_ui_state_defaults() {
+        // Older or partial files still load
+        let state: UiState = serde_json::from_str(r#"{"route": "/history"}"#).unwrap();
+        assert_eq!(state.route.as_deref(), Some("/history"));
+        assert_eq!(state.window, None);
+        assert_eq!(state.briefing_id, None);
+    }
+}
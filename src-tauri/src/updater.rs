@@ -2,9 +2,11 @@ use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::Arc;
 use tauri::{AppHandle, Emitter};
 use tauri_plugin_notification::NotificationExt;
-use tauri_plugin_updater::UpdaterExt;
+use tauri_plugin_updater::{Updater, UpdaterExt};
 use tracing::{error, info, warn};
 
+use crate::releases::{self, DEFAULT_CHANNEL};
+
 /// Track if an update has been downloaded and is ready to install
 static UPDATE_READY: AtomicBool = AtomicBool::new(false);
 
@@ -29,14 +31,46 @@ pub struct UpdateProgressEvent {
     pub total: Option<u64>,
 }
 
+/// The release channel in settings
+pub fn release_channel() -> String {
+    crate::config::read_settings()
+        .map(|s| s.release_channel)
+        .unwrap_or_else(|_| DEFAULT_CHANNEL.to_string())
+}
+
+/// An updater that checks the configured release channel. The beta
+/// channel's manifest moves with each pre-release, so it is looked up on
+/// GitHub first; stable uses the latest release's manifest.
+pub async fn channel_updater(app: &AppHandle) -> Result<Updater, String> {
+    let channel = release_channel();
+    let releases = if channel == "beta" {
+        releases::fetch_releases(&channel)
+            .await
+            .unwrap_or_else(|e| {
+                warn!("{}, checking stable releases", e);
+                Vec::new()
+            })
+    } else {
+        Vec::new()
+    };
+    let endpoint = releases::updater_endpoint(&channel, &releases);
+    let url = tauri::Url::parse(&endpoint)
+        .map_err(|e| format!("Invalid updater endpoint {}: {}", endpoint, e))?;
+
+    app.updater_builder()
+        .endpoints(vec![url])
+        .and_then(|builder| builder.build())
+        .map_err(|e| {
+            warn!("Failed to get updater: {}", e);
+            e.to_string()
+        })
+}
+
 /// Check for updates on startup
 pub async fn check_for_updates(app: AppHandle) -> Result<(), String> {
-    info!("Checking for updates...");
+    info!("Checking for updates ({} channel)...", release_channel());
 
-    let updater = app.updater().map_err(|e| {
-        warn!("Failed to get updater: {}", e);
-        e.to_string()
-    })?;
+    let updater = channel_updater(&app).await?;
 
     match updater.check().await {
         Ok(Some(update)) => {
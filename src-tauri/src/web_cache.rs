@@ -0,0 +1,99 @@
+//! Shared cache for the `fetch_webpage` tool.
+//!
+//! The same page is often fetched by several topics in one research run, by
+//! chat, and again the next day. Pages are kept in the `web_cache` table:
+//! within [`FRESH_TTL_SECS`] the cached copy is served without a request;
+//! after that it is revalidated with `If-None-Match` / `If-Modified-Since`,
+//! and a `304 Not Modified` reuses it. Housekeeping expires pages after
+//! [`MAX_AGE_DAYS`] and trims the least recently used ones beyond
+//! [`MAX_BYTES`]. Cache failures never fail a fetch.
+
+use crate::db::{self, WebCacheEntry};
+use tracing::{debug, info, warn};
+
+/// Cached pages younger than this are served without contacting the site.
+pub const FRESH_TTL_SECS: i64 = 6 * 60 * 60;
+
+/// Pages not fetched or revalidated for this long are deleted by housekeeping.
+pub const MAX_AGE_DAYS: i64 = 14;
+
+/// Total size of cached page text kept by housekeeping.
+pub const MAX_BYTES: i64 = 50 * 1024 * 1024;
+
+/// Whether a cached page can be served without revalidating it.
+pub fn is_fresh(entry: &WebCacheEntry) -> bool {
+    entry.age_secs < FRESH_TTL_SECS
+}
+
+/// Whether a stale page can be revalidated instead of fetched in full.
+pub fn can_revalidate(entry: &WebCacheEntry) -> bool {
+    entry.etag.is_some() || entry.last_modified.is_some()
+}
+
+/// The cached copy of `url`, if any.
+pub fn lookup(url: &str) -> Option<WebCacheEntry> {
+    let conn = db::get_connection().ok()?;
+    db::get_web_cache(&conn, url).unwrap_or_else(|e| {
+        debug!("Web cache lookup failed for {}: {}", url, e);
+        None
+    })
+}
+
+/// Cache a freshly fetched page.
+pub fn store(url: &str, content: &str, etag: Option<&str>, last_modified: Option<&str>) {
+    let result = db::get_connection()
+        .map_err(|e| e.to_string())
+        .and_then(|conn| db::upsert_web_cache(&conn, url, content, etag, last_modified));
+    if let Err(e) = result {
+        debug!("Failed to cache {}: {}", url, e);
+    }
+}
+
+/// Record that the site confirmed the cached copy of `url` is current.
+pub fn revalidated(url: &str) {
+    let result = db::get_connection()
+        .map_err(|e| e.to_string())
+        .and_then(|conn| db::touch_web_cache(&conn, url));
+    if let Err(e) = result {
+        debug!("Failed to refresh cache entry for {}: {}", url, e);
+    }
+}
+
+/// Expire old pages and trim the cache to its size cap. Returns pages removed.
+pub fn prune(conn: &rusqlite::Connection) -> usize {
+    match db::cleanup_web_cache(conn, MAX_AGE_DAYS, MAX_BYTES) {
+        Ok(deleted) => {
+            if deleted > 0 {
+                info!("Housekeeping: deleted {} cached web page(s)", deleted);
+            }
+            deleted
+        }
+        Err(e) => {
+            warn!("Housekeeping: failed to prune web cache: {}", e);
+            0
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(age_secs: i64, etag: Option<&str>) -> WebCacheEntry {
+        WebCacheEntry {
+            url: "https://example.com".to_string(),
+            content: "text".to_string(),
+            etag: etag.map(|e| e.to_string()),
+            last_modified: None,
+            age_secs,
+        }
+    }
+
+    #[test]
+    fn test_freshness_and_revalidation() {
+        assert!(is_fresh(&entry(0, None)));
+        assert!(!is_fresh(&entry(FRESH_TTL_SECS, None)));
+        assert!(can_revalidate(&entry(FRESH_TTL_SECS, Some("\"abc\""))));
+        assert!(!can_revalidate(&entry(FRESH_TTL_SECS, None)));
+    }
+}
@@ -0,0 +1,62 @@
+//! End-to-end tests of the `claudius` CLI against a temporary data directory.
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+fn temp_home(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("claudius-cli-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// Run the CLI with `CLAUDIUS_HOME` set to `home`, returning its stdout.
+fn claudius(home: &Path, args: &[&str]) -> String {
+    let output = Command::new(env!("CARGO_BIN_EXE_claudius"))
+        .args(args)
+        .env("CLAUDIUS_HOME", home)
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "claudius {} failed: {}",
+        args.join(" "),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8_lossy(&output.stdout).into_owned()
+}
+
+#[test]
+fn test_fresh_home() {
+    let home = temp_home("home");
+    claudius(&home, &["topics", "add", "Rust", "-d", "Language news"]);
+    assert!(home.join("claudius.db").exists());
+
+    let topics = claudius(&home, &["--json", "topics", "list"]);
+    assert!(topics.contains("\"Rust\""));
+
+    // --config-dir takes precedence over CLAUDIUS_HOME
+    let other = temp_home("config-dir");
+    let dir = other.to_str().unwrap();
+    claudius(&home, &["--config-dir", dir, "topics", "add", "Go"]);
+    let topics = claudius(&home, &["--config-dir", dir, "--json", "topics", "list"]);
+    assert!(topics.contains("\"Go\"") && !topics.contains("\"Rust\""));
+
+    let _ = std::fs::remove_dir_all(&home);
+    let _ = std::fs::remove_dir_all(&other);
+}
+
+#[test]
+fn test_workspace_topics() {
+    let home = temp_home("workspace");
+    claudius(&home, &["workspace", "create", "work"]);
+    assert!(home.join("workspaces/work/claudius.db").exists());
+    claudius(&home, &["--workspace", "work", "topics", "add", "Rust"]);
+
+    let topics = claudius(&home, &["--workspace", "work", "--json", "topics", "list"]);
+    assert!(topics.contains("\"Rust\""));
+    let topics = claudius(&home, &["--json", "topics", "list"]);
+    assert!(!topics.contains("\"Rust\""));
+
+    let _ = std::fs::remove_dir_all(&home);
+}